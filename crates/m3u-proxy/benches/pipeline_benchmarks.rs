@@ -0,0 +1,38 @@
+//! Pipeline stage benchmarks
+//!
+//! Runs each pipeline stage against synthetic datasets of several sizes so regressions in
+//! parsing, data mapping, filtering, numbering and EPG merging show up as throughput
+//! changes. `m3u-proxy --bench-pipeline` runs the same stages once per size and also
+//! reports memory.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use m3u_proxy::pipeline::benchmark::{BenchmarkDataset, BenchmarkStage};
+use std::hint::black_box;
+
+/// Dataset sizes in channels; larger sizes are left to `--bench-pipeline`
+const SIZES: &[usize] = &[1_000, 10_000];
+
+fn bench_pipeline_stages(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let datasets: Vec<BenchmarkDataset> = SIZES
+        .iter()
+        .map(|&size| rt.block_on(BenchmarkDataset::generate(size)).unwrap())
+        .collect();
+
+    for stage in BenchmarkStage::ALL {
+        let mut group = c.benchmark_group(format!("pipeline_{}", stage.name()));
+        group.sample_size(10);
+        for dataset in &datasets {
+            group.throughput(Throughput::Elements(dataset.size as u64));
+            group.bench_with_input(
+                BenchmarkId::from_parameter(dataset.size),
+                dataset,
+                |b, dataset| b.iter(|| black_box(rt.block_on(stage.run(dataset)).unwrap())),
+            );
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(pipeline_benchmarks, bench_pipeline_stages);
+criterion_main!(pipeline_benchmarks);
@@ -20,6 +20,8 @@ pub const DEFAULT_UPLOADED_LOGO_PATH: &str = "./data/logos/uploaded";
 pub const DEFAULT_CACHED_LOGO_PATH: &str = "./data/logos/cached";
 pub const DEFAULT_TEMP_PATH: &str = "./data/temp";
 pub const DEFAULT_PIPELINE_PATH: &str = "./data/pipeline";
+pub const DEFAULT_XMLTV_IMPORT_PATH: &str = "./data/import/xmltv";
+pub const DEFAULT_INGEST_ARCHIVE_PATH: &str = "./data/ingest-archive";
 
 // Ingestion defaults
 pub const DEFAULT_PROGRESS_UPDATE_INTERVAL: usize = 1000;
@@ -28,6 +28,39 @@ pub struct Config {
     pub features: Option<FeaturesConfig>,
     pub circuitbreaker: Option<CircuitBreakerConfig>,
     pub job_scheduling: Option<JobSchedulingConfig>,
+    pub xmltv_import: Option<XmltvImportConfig>,
+    pub ingest_archive: Option<IngestArchiveConfig>,
+    pub epg_merge: Option<EpgMergeConfig>,
+    pub epg_failover: Option<EpgFailoverConfig>,
+    pub epg_gap_filler: Option<EpgGapFillerConfig>,
+    pub channel_probe: Option<ChannelProbeConfig>,
+    pub channel_preview: Option<ChannelPreviewConfig>,
+    pub pipeline_inspection: Option<PipelineInspectionConfig>,
+    pub pipeline_stage_cache: Option<PipelineStageCacheConfig>,
+    pub stream_signing: Option<StreamSigningConfig>,
+    pub playlist_cache: Option<PlaylistCacheConfig>,
+    pub kodi_preset: Option<KodiPresetConfig>,
+    pub stream_hints: Option<StreamHintsConfig>,
+    pub attribute_passthrough: Option<AttributePassthroughConfig>,
+    pub access_control: Option<AccessControlConfig>,
+    pub reverse_proxy: Option<ReverseProxyConfig>,
+    pub compliance_blocklist: Option<ComplianceBlocklistConfig>,
+    pub stream_sessions: Option<StreamSessionsConfig>,
+    pub output_publishing: Option<OutputPublishingConfig>,
+    pub observability: Option<ObservabilityConfig>,
+    pub mqtt: Option<MqttConfig>,
+    pub cluster: Option<ClusterConfig>,
+    pub systemd: Option<SystemdConfig>,
+    pub offline_slate: Option<OfflineSlateConfig>,
+    pub deep_health: Option<DeepHealthConfig>,
+    pub logo_prefetch: Option<LogoPrefetchConfig>,
+    pub trash: Option<TrashConfig>,
+    pub regeneration: Option<ProxyRegenerationConfig>,
+    pub channel_count_alerts: Option<ChannelCountAlertConfig>,
+    pub generation_hooks: Option<GenerationHooksConfig>,
+    pub query_cache: Option<QueryCacheConfig>,
+    pub auth: Option<AuthConfig>,
+    pub stream_latency: Option<StreamLatencyConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -160,6 +193,49 @@ pub struct PostgreSqlConfig {
     pub idle_timeout: Option<String>,
     #[serde(default = "default_max_lifetime")]
     pub max_lifetime: Option<String>,
+
+    /// Optional read-only replica for list, search and EPG viewer queries
+    #[serde(default)]
+    pub read_replica: Option<ReadReplicaConfig>,
+}
+
+/// PostgreSQL read replica configuration
+///
+/// Reads go to the replica while it answers health checks within `max_lag` of the primary;
+/// otherwise they fall back to the primary until the replica recovers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadReplicaConfig {
+    pub url: String,
+    pub max_connections: Option<u32>,
+
+    /// How often the replica's availability and replication lag are checked (e.g., "15s")
+    #[serde(default = "default_read_replica_health_check_interval")]
+    pub health_check_interval: String,
+
+    /// Replication lag beyond which reads fall back to the primary (e.g., "30s")
+    #[serde(default = "default_read_replica_max_lag")]
+    pub max_lag: String,
+}
+
+impl ReadReplicaConfig {
+    /// Parsed health check interval (falls back to 15s)
+    pub fn health_check_interval_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.health_check_interval)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(15))
+    }
+
+    /// Parsed maximum replication lag (falls back to 30s)
+    pub fn max_lag_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.max_lag)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(30))
+    }
+}
+
+fn default_read_replica_health_check_interval() -> String {
+    "15s".to_string()
+}
+fn default_read_replica_max_lag() -> String {
+    "30s".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -199,12 +275,105 @@ pub struct WebConfig {
     /// Only applies to establishing the TCP/TLS connection; no total request timeout is enforced for streaming.
     #[serde(default = "default_proxy_upstream_connect_timeout")]
     pub proxy_upstream_connect_timeout: String,
+    /// Native TLS termination; plaintext HTTP is served when absent
+    #[serde(default)]
+    pub tls: Option<WebTlsConfig>,
 }
 
 fn default_proxy_upstream_connect_timeout() -> String {
     "15s".to_string()
 }
 
+/// TLS termination for the web server
+///
+/// Certificates come either from PEM files (`cert_path` / `key_path`) or from an ACME
+/// provider using the TLS-ALPN-01 challenge, which is answered on the HTTPS port itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebTlsConfig {
+    /// PEM certificate chain
+    #[serde(default)]
+    pub cert_path: Option<PathBuf>,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1)
+    #[serde(default)]
+    pub key_path: Option<PathBuf>,
+    /// Obtain and renew certificates automatically instead of loading them from files
+    #[serde(default)]
+    pub acme: Option<AcmeConfig>,
+    /// Offer HTTP/2 via ALPN alongside HTTP/1.1
+    #[serde(default = "default_tls_http2")]
+    pub http2: bool,
+    /// Plaintext port answering every request with a redirect to HTTPS
+    #[serde(default)]
+    pub redirect_http_port: Option<u16>,
+}
+
+fn default_tls_http2() -> bool {
+    true
+}
+
+impl Default for WebTlsConfig {
+    fn default() -> Self {
+        Self {
+            cert_path: None,
+            key_path: None,
+            acme: None,
+            http2: default_tls_http2(),
+            redirect_http_port: None,
+        }
+    }
+}
+
+/// ACME certificate management (e.g. Let's Encrypt)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcmeConfig {
+    /// Domains the certificate is issued for
+    pub domains: Vec<String>,
+    /// Account contact addresses, e.g. `admin@example.com`
+    #[serde(default)]
+    pub contacts: Vec<String>,
+    /// Directory caching the account key and issued certificates across restarts
+    #[serde(default = "default_acme_cache_path")]
+    pub cache_path: PathBuf,
+    /// ACME directory URL; Let's Encrypt is used when absent
+    #[serde(default)]
+    pub directory_url: Option<String>,
+    /// Use the Let's Encrypt production directory instead of staging
+    #[serde(default)]
+    pub production: bool,
+}
+
+fn default_acme_cache_path() -> PathBuf {
+    PathBuf::from("./data/acme")
+}
+
+impl WebTlsConfig {
+    /// Reject TLS settings that cannot produce a certificate
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match (&self.cert_path, &self.key_path, &self.acme) {
+            (Some(_), Some(_), None) => Ok(()),
+            (None, None, Some(acme)) => {
+                if acme.domains.is_empty() {
+                    anyhow::bail!("web.tls.acme requires at least one domain");
+                }
+                Ok(())
+            }
+            (_, _, Some(_)) => {
+                anyhow::bail!("web.tls.acme cannot be combined with cert_path/key_path")
+            }
+            _ => anyhow::bail!("web.tls requires both cert_path and key_path, or an acme section"),
+        }
+    }
+
+    /// ALPN protocols offered to clients, most preferred first
+    pub fn alpn_protocols(&self) -> Vec<Vec<u8>> {
+        if self.http2 {
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        } else {
+            vec![b"http/1.1".to_vec()]
+        }
+    }
+}
+
 impl WebConfig {
     /// Parses the proxy_upstream_connect_timeout string into a Duration.
     /// Falls back to 15s if parsing fails or value is empty.
@@ -245,6 +414,95 @@ pub struct StorageConfig {
     pub pipeline_retention: String,
     #[serde(default = "default_pipeline_cleanup_interval")]
     pub pipeline_cleanup_interval: String,
+
+    /// S3-compatible bucket for the categories listed in it; the rest stay on local disk
+    #[serde(default)]
+    pub object_storage: Option<ObjectStorageSettings>,
+
+    /// Quotas of top-level subdirectories, keyed by storage category then subdirectory
+    /// (e.g. `[storage.quotas.temp.recordings] max_size_mb = 102400`)
+    #[serde(default)]
+    pub quotas:
+        std::collections::HashMap<String, std::collections::HashMap<String, StorageQuotaSettings>>,
+}
+
+/// Byte and file-count limits of one storage subdirectory
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct StorageQuotaSettings {
+    #[serde(default)]
+    pub max_size_mb: Option<u64>,
+    #[serde(default)]
+    pub max_files: Option<u64>,
+}
+
+impl StorageQuotaSettings {
+    pub fn to_directory_quota(self) -> sandboxed_file_manager::DirectoryQuota {
+        sandboxed_file_manager::DirectoryQuota {
+            max_bytes: self.max_size_mb.map(|mb| mb.saturating_mul(1024 * 1024)),
+            max_files: self.max_files,
+        }
+    }
+}
+
+/// Storage categories that can live in object storage
+///
+/// Temp and pipeline files are handed to ffmpeg and the pipeline as local paths, so they
+/// always stay on disk.
+pub const OBJECT_STORAGE_CATEGORIES: &[&str] = &["m3u", "cached_logos"];
+
+/// Object storage bucket and the storage categories kept in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectStorageSettings {
+    /// Categories stored in the bucket, each under its own key prefix
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    #[serde(flatten)]
+    pub bucket: sandboxed_file_manager::ObjectStorageConfig,
+}
+
+impl StorageConfig {
+    /// Object storage for a category, if it is configured to use it
+    pub fn object_storage_for(
+        &self,
+        category: &str,
+    ) -> Option<sandboxed_file_manager::ObjectStorageConfig> {
+        let settings = self.object_storage.as_ref()?;
+        settings
+            .categories
+            .iter()
+            .any(|c| c == category)
+            .then(|| settings.bucket.with_child_prefix(category))
+    }
+
+    /// Apply the quotas configured for a category to its file manager
+    pub fn with_quotas(
+        &self,
+        category: &str,
+        mut builder: sandboxed_file_manager::SandboxedManagerBuilder,
+    ) -> sandboxed_file_manager::SandboxedManagerBuilder {
+        for (directory, quota) in self.quotas.get(category).into_iter().flatten() {
+            builder = builder.directory_quota(directory.clone(), quota.to_directory_quota());
+        }
+        builder
+    }
+
+    /// Reject object storage categories that cannot be stored remotely
+    pub fn validate_object_storage(&self) -> anyhow::Result<()> {
+        let Some(settings) = &self.object_storage else {
+            return Ok(());
+        };
+        for category in &settings.categories {
+            if !OBJECT_STORAGE_CATEGORIES.contains(&category.as_str()) {
+                anyhow::bail!(
+                    "Storage category '{}' cannot use object storage (supported: {})",
+                    category,
+                    OBJECT_STORAGE_CATEGORIES.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for StorageConfig {
@@ -261,6 +519,8 @@ impl Default for StorageConfig {
             pipeline_path: default_pipeline_path(),
             pipeline_retention: default_pipeline_retention(),
             pipeline_cleanup_interval: default_pipeline_cleanup_interval(),
+            object_storage: None,
+            quotas: std::collections::HashMap::new(),
         }
     }
 }
@@ -401,6 +661,10 @@ fn default_use_new_source_handlers() -> bool {
     true // Default to new source handlers
 }
 
+fn default_max_decompressed_size_mb() -> u64 {
+    4096
+}
+
 fn default_database_config() -> DatabaseConfig {
     DatabaseConfig {
         url: DEFAULT_DATABASE_URL.to_string(),
@@ -425,6 +689,19 @@ pub struct IngestionConfig {
     /// Whether to use new source handlers instead of legacy ingestors
     #[serde(default = "default_use_new_source_handlers")]
     pub use_new_source_handlers: bool,
+    /// Largest size a compressed source download may expand to, in MiB (default: 4096)
+    #[serde(default = "default_max_decompressed_size_mb")]
+    pub max_decompressed_size_mb: u64,
+    /// Resource limits applied to each stream source refresh
+    #[serde(default)]
+    pub limits: IngestionLimitsConfig,
+}
+
+impl IngestionConfig {
+    /// Decompressed size limit in bytes
+    pub fn max_decompressed_bytes(&self) -> u64 {
+        self.max_decompressed_size_mb.saturating_mul(1024 * 1024)
+    }
 }
 
 impl Default for IngestionConfig {
@@ -433,10 +710,84 @@ impl Default for IngestionConfig {
             progress_update_interval: default_progress_update_interval(),
             run_missed_immediately: default_run_missed_immediately(),
             use_new_source_handlers: default_use_new_source_handlers(),
+            max_decompressed_size_mb: default_max_decompressed_size_mb(),
+            limits: IngestionLimitsConfig::default(),
+        }
+    }
+}
+
+/// Per-source ingestion limits, so one oversized playlist cannot starve the others
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestionLimitsConfig {
+    /// Largest playlist download, in MiB before decompression (0 = unlimited, default: 512)
+    #[serde(default = "default_max_source_download_size_mb")]
+    pub max_download_size_mb: u64,
+    /// Most channels a source may yield (0 = unlimited, default: 250000)
+    #[serde(default = "default_max_source_channels")]
+    pub max_channels: usize,
+    /// Longest a source's playlist may take to parse (e.g. "2m"; empty = unlimited)
+    #[serde(default = "default_max_source_parse_time")]
+    pub max_parse_time: String,
+    /// Limits of known-large trusted sources, keyed by source name or ID
+    #[serde(default)]
+    pub overrides: std::collections::HashMap<String, IngestionLimitsOverride>,
+}
+
+impl IngestionLimitsConfig {
+    /// Parsed parse-time limit (falls back to 2m; `None` when unlimited)
+    pub fn max_parse_time_duration(&self) -> Option<std::time::Duration> {
+        parse_optional_limit_duration(&self.max_parse_time)
+    }
+}
+
+impl Default for IngestionLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_download_size_mb: default_max_source_download_size_mb(),
+            max_channels: default_max_source_channels(),
+            max_parse_time: default_max_source_parse_time(),
+            overrides: std::collections::HashMap::new(),
         }
     }
 }
 
+/// Limits replacing the defaults for one source; unset fields keep the default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestionLimitsOverride {
+    pub max_download_size_mb: Option<u64>,
+    pub max_channels: Option<usize>,
+    pub max_parse_time: Option<String>,
+}
+
+impl IngestionLimitsOverride {
+    /// Parsed parse-time limit; `Some(None)` when the override lifts the limit
+    pub fn max_parse_time_duration(&self) -> Option<Option<std::time::Duration>> {
+        self.max_parse_time
+            .as_deref()
+            .map(parse_optional_limit_duration)
+    }
+}
+
+fn parse_optional_limit_duration(value: &str) -> Option<std::time::Duration> {
+    let value = value.trim();
+    if value.is_empty() || value == "0" {
+        return None;
+    }
+    Some(humantime::parse_duration(value).unwrap_or_else(|_| std::time::Duration::from_secs(120)))
+}
+
+fn default_max_source_download_size_mb() -> u64 {
+    512
+}
+
+fn default_max_source_channels() -> usize {
+    250_000
+}
+
+fn default_max_source_parse_time() -> String {
+    "2m".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataMappingEngineConfig {
     /// Special characters used for regex precheck filtering
@@ -447,6 +798,50 @@ pub struct DataMappingEngineConfig {
     /// Set to 0 to disable literal string precheck entirely
     /// Default: 2
     pub minimum_literal_length: Option<usize>,
+    /// Measure each rule's precheck during generation and turn it off for rules where it
+    /// rarely skips the regex or costs more than it saves
+    /// Default: false
+    pub precheck_auto_tune: Option<bool>,
+    /// Number of evaluations sampled per rule before the auto-tuner decides
+    /// Default: 1000
+    pub precheck_sample_size: Option<usize>,
+    /// Prechecks skipping fewer than this fraction of evaluations are disabled (0.0-1.0)
+    /// Default: 0.05
+    pub precheck_min_skip_rate: Option<f64>,
+}
+
+impl DataMappingEngineConfig {
+    /// Regex preprocessor settings for the data mapping stage
+    pub fn preprocessor_config(&self) -> crate::utils::regex_preprocessor::RegexPreprocessorConfig {
+        let mut config = crate::utils::regex_preprocessor::RegexPreprocessorConfig::default();
+        if let Some(chars) = &self.precheck_special_chars {
+            config.precheck_special_chars = chars.clone();
+        }
+        if let Some(length) = self.minimum_literal_length {
+            config.minimum_literal_length = length;
+        }
+        config
+    }
+
+    /// Auto-tuning settings, `None` when auto-tuning is off
+    pub fn precheck_tuning(
+        &self,
+    ) -> Option<crate::utils::regex_preprocessor::PrecheckTuningConfig> {
+        if !self.precheck_auto_tune.unwrap_or(false) {
+            return None;
+        }
+        let defaults = crate::utils::regex_preprocessor::PrecheckTuningConfig::default();
+        Some(crate::utils::regex_preprocessor::PrecheckTuningConfig {
+            sample_size: self
+                .precheck_sample_size
+                .unwrap_or(defaults.sample_size)
+                .max(1) as u64,
+            min_skip_rate: self
+                .precheck_min_skip_rate
+                .unwrap_or(defaults.min_skip_rate)
+                .clamp(0.0, 1.0),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -554,8 +949,8 @@ impl DatabaseBatchConfig {
     const EPG_PROGRAM_FIELDS: usize = 12;
 
     /// Number of fields per stream channel record
-    /// (id, source_id, tvg_id, tvg_name, tvg_chno, channel_name, tvg_logo, tvg_shift, group_title, stream_url, created_at, updated_at)
-    const STREAM_CHANNEL_FIELDS: usize = 12;
+    /// (id, source_id, tvg_id, tvg_name, tvg_chno, channel_name, tvg_logo, tvg_shift, group_title, stream_url, created_at, updated_at, extra_attributes)
+    const STREAM_CHANNEL_FIELDS: usize = 13;
 
     /// Validate batch sizes to ensure they don't exceed SQLite limits
     pub fn validate(&self) -> Result<(), String> {
@@ -668,6 +1063,7 @@ impl Default for PostgreSqlConfig {
             statement_timeout: default_statement_timeout(),
             idle_timeout: default_idle_timeout(),
             max_lifetime: default_max_lifetime(),
+            read_replica: None,
         }
     }
 }
@@ -686,6 +1082,9 @@ impl Default for DataMappingEngineConfig {
         Self {
             precheck_special_chars: Some("+-@#$%&*=<>!~`€£{}[].".to_string()),
             minimum_literal_length: Some(2),
+            precheck_auto_tune: Some(false),
+            precheck_sample_size: Some(1000),
+            precheck_min_skip_rate: Some(0.05),
         }
     }
 }
@@ -716,6 +1115,14 @@ pub struct RelayConfig {
     /// Cyclic buffer configuration for in-memory stream buffering
     #[serde(default)]
     pub buffer: BufferConfig,
+
+    /// Admission control for transcoding relays
+    #[serde(default)]
+    pub transcode: TranscodeAdmissionConfig,
+
+    /// Capture of FFmpeg output per relay process
+    #[serde(default)]
+    pub logs: RelayLogConfig,
 }
 
 fn default_ffmpeg_command() -> String {
@@ -734,10 +1141,105 @@ impl Default for RelayConfig {
             analyzeduration: default_analyzeduration(),
             probesize: default_probesize(),
             buffer: BufferConfig::default(),
+            transcode: TranscodeAdmissionConfig::default(),
+            logs: RelayLogConfig::default(),
+        }
+    }
+}
+
+/// Capture of FFmpeg stderr per relay process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayLogConfig {
+    /// Lines of FFmpeg output kept in memory per relay (default: 500)
+    #[serde(default = "default_relay_log_max_lines")]
+    pub max_lines: usize,
+
+    /// Write the tail of the output to the temp sandbox when FFmpeg dies (default: true)
+    #[serde(default = "default_relay_log_persist_on_crash")]
+    pub persist_on_crash: bool,
+
+    /// Bytes of output written on a crash (default: 64KB)
+    #[serde(default = "default_relay_log_crash_log_bytes")]
+    pub crash_log_bytes: usize,
+}
+
+fn default_relay_log_max_lines() -> usize {
+    500
+}
+
+fn default_relay_log_persist_on_crash() -> bool {
+    true
+}
+
+fn default_relay_log_crash_log_bytes() -> usize {
+    64 * 1024
+}
+
+impl Default for RelayLogConfig {
+    fn default() -> Self {
+        Self {
+            max_lines: default_relay_log_max_lines(),
+            persist_on_crash: default_relay_log_persist_on_crash(),
+            crash_log_bytes: default_relay_log_crash_log_bytes(),
+        }
+    }
+}
+
+/// Limits on concurrent transcoding relays per encoding device
+///
+/// Devices are the hardware accelerator a profile encodes with ("vaapi", "nvenc", "qsv",
+/// ...) or "cpu" for software transcodes. Copy-only relays never take a slot, and devices
+/// without a limit admit any number of transcodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscodeAdmissionConfig {
+    /// Maximum concurrent transcodes per device, e.g. `{ nvenc = 4, cpu = 2 }`
+    #[serde(default)]
+    pub max_concurrent: std::collections::HashMap<String, usize>,
+
+    /// What happens to a new transcode when its device is saturated
+    #[serde(default)]
+    pub when_saturated: TranscodeSaturationPolicy,
+
+    /// How long a queued transcode waits for a slot before falling back (e.g. "15s")
+    #[serde(default = "default_transcode_queue_timeout")]
+    pub queue_timeout: String,
+}
+
+impl TranscodeAdmissionConfig {
+    /// Parsed queue timeout (falls back to 15s)
+    pub fn queue_timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.queue_timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(15))
+    }
+}
+
+impl Default for TranscodeAdmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: std::collections::HashMap::new(),
+            when_saturated: TranscodeSaturationPolicy::default(),
+            queue_timeout: default_transcode_queue_timeout(),
         }
     }
 }
 
+fn default_transcode_queue_timeout() -> String {
+    "15s".to_string()
+}
+
+/// Handling of transcodes that find their device saturated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscodeSaturationPolicy {
+    /// Wait up to `queue_timeout` for a slot, then relay without transcoding
+    #[default]
+    Queue,
+    /// Relay without transcoding (codec copy) straight away
+    Passthrough,
+    /// Refuse the relay
+    Reject,
+}
+
 /// Configuration for relay cyclic buffer system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BufferConfig {
@@ -791,6 +1293,18 @@ pub struct JobSchedulingConfig {
     /// Maximum concurrent maintenance jobs (default: 1)
     #[serde(default = "default_maintenance_limit")]
     pub maintenance_limit: usize,
+
+    /// Maximum concurrent jobs of the ingestion class, stream and EPG combined (default: 2)
+    #[serde(default = "default_ingestion_class_limit")]
+    pub ingestion_class_limit: usize,
+
+    /// Maximum concurrent jobs of the regeneration class (default: 1)
+    #[serde(default = "default_regeneration_class_limit")]
+    pub regeneration_class_limit: usize,
+
+    /// Maximum concurrent jobs of the maintenance class (default: 1)
+    #[serde(default = "default_maintenance_class_limit")]
+    pub maintenance_class_limit: usize,
 }
 
 impl Default for JobSchedulingConfig {
@@ -801,6 +1315,9 @@ impl Default for JobSchedulingConfig {
             epg_ingestion_limit: default_epg_ingestion_limit(),
             proxy_regeneration_limit: default_proxy_regeneration_limit(),
             maintenance_limit: default_maintenance_limit(),
+            ingestion_class_limit: default_ingestion_class_limit(),
+            regeneration_class_limit: default_regeneration_class_limit(),
+            maintenance_class_limit: default_maintenance_class_limit(),
         }
     }
 }
@@ -820,55 +1337,1961 @@ fn default_proxy_regeneration_limit() -> usize {
 fn default_maintenance_limit() -> usize {
     1
 }
+fn default_ingestion_class_limit() -> usize {
+    2
+}
+fn default_regeneration_class_limit() -> usize {
+    1
+}
+fn default_maintenance_class_limit() -> usize {
+    1
+}
 
-fn default_max_buffer_size() -> usize {
-    50 * 1024 * 1024
-} // 50MB
-fn default_max_chunks() -> usize {
-    1000
+/// Watch folder configuration for auto-importing local XMLTV files
+///
+/// Files dropped into `import_path` (`.xml` or `.xml.gz`) are picked up once they have
+/// settled, ingested into a file-backed EPG source named after the file, and then archived
+/// or deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XmltvImportConfig {
+    /// Enable the watch folder (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Directory watched for new XMLTV files
+    #[serde(default = "default_xmltv_import_path")]
+    pub import_path: PathBuf,
+
+    /// How often the import directory is scanned (e.g., "30s")
+    #[serde(default = "default_xmltv_import_poll_interval")]
+    pub poll_interval: String,
+
+    /// Minimum age since last modification before a file is considered complete (e.g., "10s")
+    #[serde(default = "default_xmltv_import_settle_time")]
+    pub settle_time: String,
+
+    /// What to do with a file once ingested
+    #[serde(default)]
+    pub after_import: XmltvImportAction,
+
+    /// How long archived files are kept before cleanup (e.g., "7d")
+    #[serde(default = "default_xmltv_import_archive_retention")]
+    pub archive_retention: String,
 }
-fn default_chunk_timeout_seconds() -> u64 {
-    60
+
+/// Action applied to an imported XMLTV file after successful ingestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum XmltvImportAction {
+    /// Move the file into the `archive/` subdirectory (subject to `archive_retention`)
+    #[default]
+    Archive,
+    /// Remove the file
+    Delete,
 }
-fn default_client_timeout_seconds() -> u64 {
-    30
+
+impl XmltvImportConfig {
+    /// Parsed poll interval (falls back to 30s)
+    pub fn poll_interval_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.poll_interval)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(30))
+    }
+
+    /// Parsed settle time (falls back to 10s)
+    pub fn settle_time_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.settle_time)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(10))
+    }
+
+    /// Parsed archive retention (falls back to 7 days)
+    pub fn archive_retention_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.archive_retention)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(7 * 24 * 60 * 60))
+    }
 }
-fn default_cleanup_interval_seconds() -> u64 {
-    5
+
+impl Default for XmltvImportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            import_path: default_xmltv_import_path(),
+            poll_interval: default_xmltv_import_poll_interval(),
+            settle_time: default_xmltv_import_settle_time(),
+            after_import: XmltvImportAction::default(),
+            archive_retention: default_xmltv_import_archive_retention(),
+        }
+    }
 }
-fn default_enable_file_spill() -> bool {
-    false
+
+fn default_xmltv_import_path() -> PathBuf {
+    PathBuf::from(DEFAULT_XMLTV_IMPORT_PATH)
+}
+fn default_xmltv_import_poll_interval() -> String {
+    "30s".to_string()
+}
+fn default_xmltv_import_settle_time() -> String {
+    "10s".to_string()
+}
+fn default_xmltv_import_archive_retention() -> String {
+    "7d".to_string()
 }
-fn default_max_file_spill_size() -> usize {
-    500 * 1024 * 1024
-} // 500MB
 
-impl Default for BufferConfig {
+/// Raw source snapshot archive configuration
+///
+/// When enabled, the playlist or XMLTV document downloaded by each M3U / XMLTV ingestion is
+/// stored (gzip-compressed) so the run can be replayed later, e.g. to reproduce a parsing bug.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestArchiveConfig {
+    /// Archive downloaded sources (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Directory holding the archive (one subdirectory per source)
+    #[serde(default = "default_ingest_archive_path")]
+    pub path: PathBuf,
+
+    /// How long snapshots are kept before cleanup (e.g., "14d")
+    #[serde(default = "default_ingest_archive_retention")]
+    pub retention: String,
+
+    /// Most recent snapshots kept per source; older ones are removed after each archive
+    #[serde(default = "default_ingest_archive_max_snapshots")]
+    pub max_snapshots_per_source: usize,
+}
+
+impl IngestArchiveConfig {
+    /// Parsed retention (falls back to 14 days)
+    pub fn retention_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.retention)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(14 * 24 * 60 * 60))
+    }
+}
+
+impl Default for IngestArchiveConfig {
     fn default() -> Self {
         Self {
-            max_buffer_size: default_max_buffer_size(),
-            max_chunks: default_max_chunks(),
-            chunk_timeout_seconds: default_chunk_timeout_seconds(),
-            client_timeout_seconds: default_client_timeout_seconds(),
-            cleanup_interval_seconds: default_cleanup_interval_seconds(),
-            enable_file_spill: default_enable_file_spill(),
-            max_file_spill_size: default_max_file_spill_size(),
+            enabled: false,
+            path: default_ingest_archive_path(),
+            retention: default_ingest_archive_retention(),
+            max_snapshots_per_source: default_ingest_archive_max_snapshots(),
         }
     }
 }
 
-impl Default for Config {
+fn default_ingest_archive_path() -> PathBuf {
+    PathBuf::from(DEFAULT_INGEST_ARCHIVE_PATH)
+}
+fn default_ingest_archive_retention() -> String {
+    "14d".to_string()
+}
+fn default_ingest_archive_max_snapshots() -> usize {
+    10
+}
+
+/// Pipeline artifact inspection configuration
+///
+/// When enabled, every regeneration writes the first and last `sample_size` records of each
+/// stage's channel/programme output into the pipeline storage (subject to
+/// `storage.pipeline_retention`), so a channel can be traced through the stages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineInspectionConfig {
+    /// Persist per-stage artifact samples (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Records kept from each end of a stage artifact
+    #[serde(default = "default_pipeline_inspection_sample_size")]
+    pub sample_size: usize,
+}
+
+impl Default for PipelineInspectionConfig {
     fn default() -> Self {
         Self {
-            database: DatabaseConfig {
-                url: "sqlite:./data/m3u-proxy.db".to_string(),
-                max_connections: Some(10),
-                batch_sizes: Some(DatabaseBatchConfig::default()),
-                sqlite: SqliteConfig::default(),
-                postgresql: PostgreSqlConfig::default(),
-                mysql: MySqlConfig::default(),
-            },
-            web: WebConfig {
+            enabled: false,
+            sample_size: default_pipeline_inspection_sample_size(),
+        }
+    }
+}
+
+fn default_pipeline_inspection_sample_size() -> usize {
+    20
+}
+
+/// Incremental regeneration via per-stage output caching
+///
+/// Cacheable stages (ingestion guard, data mapping, filtering, virtual channels) fingerprint
+/// the database state they read. When a stage and every stage before it match the previous
+/// run, its cached output is reused instead of re-running it. Cache entries live in the
+/// pipeline storage and expire with `storage.pipeline_retention`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PipelineStageCacheConfig {
+    /// Reuse cached stage output when inputs are unchanged (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// On-demand channel probe diagnostics configuration
+///
+/// Probe reports are cached per stream URL so repeated diagnostics do not hammer providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelProbeConfig {
+    /// How long a probe report is served from cache (e.g., "10m")
+    #[serde(default = "default_channel_probe_cache_ttl")]
+    pub cache_ttl: String,
+
+    /// How long the upstream stream is read to measure its bitrate (e.g., "5s")
+    #[serde(default = "default_channel_probe_sample_window")]
+    pub sample_window: String,
+
+    /// Upper bound on bytes read while sampling (default: 16 MiB)
+    #[serde(default = "default_channel_probe_max_sample_bytes")]
+    pub max_sample_bytes: u64,
+}
+
+impl ChannelProbeConfig {
+    /// Parsed cache TTL (falls back to 10 minutes)
+    pub fn cache_ttl_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.cache_ttl)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(10 * 60))
+    }
+
+    /// Parsed sample window (falls back to 5s)
+    pub fn sample_window_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.sample_window)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(5))
+    }
+}
+
+impl Default for ChannelProbeConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl: default_channel_probe_cache_ttl(),
+            sample_window: default_channel_probe_sample_window(),
+            max_sample_bytes: default_channel_probe_max_sample_bytes(),
+        }
+    }
+}
+
+fn default_channel_probe_cache_ttl() -> String {
+    "10m".to_string()
+}
+fn default_channel_probe_sample_window() -> String {
+    "5s".to_string()
+}
+fn default_channel_probe_max_sample_bytes() -> u64 {
+    16 * 1024 * 1024
+}
+
+/// On-demand channel preview screenshot configuration
+///
+/// Captured frames are cached per channel in the logo sandbox so repeated previews do
+/// not start an FFmpeg process against the provider each time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelPreviewConfig {
+    /// How long a captured preview is served from cache (e.g., "5m")
+    #[serde(default = "default_channel_preview_cache_ttl")]
+    pub cache_ttl: String,
+
+    /// How long FFmpeg may take to capture a frame before giving up (e.g., "20s")
+    #[serde(default = "default_channel_preview_capture_timeout")]
+    pub capture_timeout: String,
+
+    /// Width of the captured image in pixels; height follows the aspect ratio (default: 640)
+    #[serde(default = "default_channel_preview_width")]
+    pub width: u32,
+}
+
+impl ChannelPreviewConfig {
+    /// Parsed cache TTL (falls back to 5 minutes)
+    pub fn cache_ttl_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.cache_ttl)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(5 * 60))
+    }
+
+    /// Parsed capture timeout (falls back to 20s)
+    pub fn capture_timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.capture_timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(20))
+    }
+}
+
+impl Default for ChannelPreviewConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl: default_channel_preview_cache_ttl(),
+            capture_timeout: default_channel_preview_capture_timeout(),
+            width: default_channel_preview_width(),
+        }
+    }
+}
+
+fn default_channel_preview_cache_ttl() -> String {
+    "5m".to_string()
+}
+fn default_channel_preview_capture_timeout() -> String {
+    "20s".to_string()
+}
+fn default_channel_preview_width() -> u32 {
+    640
+}
+
+/// How programmes are combined when several EPG sources cover the same channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EpgMergeStrategy {
+    /// The highest-priority source with programmes for a channel supplies its whole guide
+    #[default]
+    Priority,
+    /// For overlapping programmes keep the one with the most populated metadata fields
+    RichestMetadata,
+    /// Take the highest-priority source and fill uncovered time slots from lower-priority sources
+    FillGaps,
+    /// Fill gaps like `fill_gaps`, then populate each field from the preferred source that has it
+    FieldMerge,
+}
+
+/// EPG merge configuration for proxies whose channels are covered by multiple EPG sources
+///
+/// Source priority comes from the proxy's EPG source ordering; sources not attached to the
+/// proxy rank after all attached ones. Proxies override the strategy with their own EPG
+/// merge setting (`/api/v1/proxies/{id}/epg-merge`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EpgMergeConfig {
+    /// Strategy used for proxies without their own EPG merge setting
+    #[serde(default)]
+    pub default_strategy: EpgMergeStrategy,
+}
+
+/// Per-field EPG source preference used by the `field_merge` strategy
+///
+/// Sources listed for a field are tried first, in order; remaining sources follow in
+/// priority order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct EpgMergeFieldSources {
+    #[serde(default)]
+    pub description: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub icon: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub category: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub subtitles: Vec<uuid::Uuid>,
+    /// Applies to both episode and season numbers
+    #[serde(default)]
+    pub episode: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub language: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub rating: Vec<uuid::Uuid>,
+}
+
+/// Failover for EPG sources whose guide has gone stale
+///
+/// An EPG source is stale when it has not ingested successfully within `staleness_window`.
+/// During generation its programmes rank after fresh sources, so channels also covered by a
+/// fresh source (the proxy's preferred fallback first, see `/api/v1/proxies/{id}/epg-fallbacks`)
+/// take their guide from there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpgFailoverConfig {
+    /// Demote stale EPG sources during generation (default: true)
+    #[serde(default = "default_epg_failover_enabled")]
+    pub enabled: bool,
+
+    /// How long after its last successful ingestion a source becomes stale (e.g., "72h")
+    #[serde(default = "default_epg_staleness_window")]
+    pub staleness_window: String,
+}
+
+impl EpgFailoverConfig {
+    /// Parsed staleness window (falls back to 72 hours)
+    pub fn staleness_window_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.staleness_window)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(72 * 60 * 60))
+    }
+}
+
+impl Default for EpgFailoverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            staleness_window: default_epg_staleness_window(),
+        }
+    }
+}
+
+fn default_epg_failover_enabled() -> bool {
+    true
+}
+fn default_epg_staleness_window() -> String {
+    "72h".to_string()
+}
+
+/// Synthetic programmes for channels or periods without guide data
+///
+/// Clients show channels without programmes as empty guide rows. When enabled, XMLTV
+/// generation fills every gap between `lookback` before and `lookahead` after generation
+/// with blocks of `block_length`, aligned to block boundaries, titled from `title_template`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpgGapFillerConfig {
+    /// Fill gaps for proxies without their own gap filling setting
+    /// (`/api/v1/proxies/{id}/epg-gap-filling`)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Title of filler programmes; `{channel_name}`, `{channel_id}` and `{group}` are replaced
+    #[serde(default = "default_epg_gap_filler_title_template")]
+    pub title_template: String,
+
+    /// Optional description of filler programmes (same placeholders as the title)
+    #[serde(default)]
+    pub description_template: Option<String>,
+
+    /// Length of each filler block (e.g., "1h")
+    #[serde(default = "default_epg_gap_filler_block_length")]
+    pub block_length: String,
+
+    /// How far before generation time gaps are filled
+    #[serde(default = "default_epg_gap_filler_lookback")]
+    pub lookback: String,
+
+    /// How far after generation time gaps are filled
+    #[serde(default = "default_epg_gap_filler_lookahead")]
+    pub lookahead: String,
+
+    /// Gaps shorter than this between real programmes are left empty
+    #[serde(default = "default_epg_gap_filler_min_gap")]
+    pub min_gap: String,
+
+    /// Only fill channels without any programmes, leaving gaps in partial guides alone
+    #[serde(default)]
+    pub empty_channels_only: bool,
+}
+
+impl EpgGapFillerConfig {
+    /// Whether gap filling runs for a proxy with the given gap filling setting
+    ///
+    /// A proxy's own setting wins; proxies without one follow `enabled`.
+    pub fn applies_to(
+        &self,
+        proxy_setting: Option<&crate::models::proxy_settings::EpgGapFilling>,
+    ) -> bool {
+        proxy_setting.map_or(self.enabled, |setting| setting.enabled)
+    }
+
+    /// Parsed block length (falls back to 1 hour; never below 1 minute)
+    pub fn block_length_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.block_length)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(60 * 60))
+            .max(std::time::Duration::from_secs(60))
+    }
+
+    /// Parsed lookback (falls back to 6 hours)
+    pub fn lookback_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.lookback)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(6 * 60 * 60))
+    }
+
+    /// Parsed lookahead (falls back to 48 hours)
+    pub fn lookahead_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.lookahead)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(48 * 60 * 60))
+    }
+
+    /// Parsed minimum gap (falls back to 5 minutes)
+    pub fn min_gap_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.min_gap)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(5 * 60))
+    }
+}
+
+impl Default for EpgGapFillerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            title_template: default_epg_gap_filler_title_template(),
+            description_template: None,
+            block_length: default_epg_gap_filler_block_length(),
+            lookback: default_epg_gap_filler_lookback(),
+            lookahead: default_epg_gap_filler_lookahead(),
+            min_gap: default_epg_gap_filler_min_gap(),
+            empty_channels_only: false,
+        }
+    }
+}
+
+fn default_epg_gap_filler_title_template() -> String {
+    "{channel_name} programming".to_string()
+}
+fn default_epg_gap_filler_block_length() -> String {
+    "1h".to_string()
+}
+fn default_epg_gap_filler_lookback() -> String {
+    "6h".to_string()
+}
+fn default_epg_gap_filler_lookahead() -> String {
+    "48h".to_string()
+}
+fn default_epg_gap_filler_min_gap() -> String {
+    "5m".to_string()
+}
+
+/// Signed stream URL configuration
+///
+/// Proxies with `sign_stream_urls` enabled serve playlists whose stream URLs carry an
+/// HMAC token bound to the proxy, channel and an expiry `url_lifetime` from the playlist
+/// fetch; the stream endpoint rejects URLs with a missing, forged or expired token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamSigningConfig {
+    /// HMAC key; when unset a random key is generated at startup, so signed URLs do not
+    /// survive a restart
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// How long a signed stream URL stays valid after the playlist is fetched (e.g., "24h")
+    #[serde(default = "default_stream_signing_url_lifetime")]
+    pub url_lifetime: String,
+}
+
+impl StreamSigningConfig {
+    /// Parsed URL lifetime (falls back to 24 hours)
+    pub fn url_lifetime_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.url_lifetime)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(24 * 60 * 60))
+    }
+}
+
+impl Default for StreamSigningConfig {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            url_lifetime: default_stream_signing_url_lifetime(),
+        }
+    }
+}
+
+fn default_stream_signing_url_lifetime() -> String {
+    "24h".to_string()
+}
+
+/// Concurrent stream limits per identity
+///
+/// Streams opened through a share link belong to that link; streams of a proxy with Basic
+/// Auth belong to its authenticated user, carried on signed stream URLs or sent as
+/// credentials with the stream request. Starting a stream beyond the limit is rejected
+/// with 429. A limit of 0 means unlimited; share links may set their own `max_streams`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreamSessionsConfig {
+    /// Default concurrent streams per share link
+    #[serde(default)]
+    pub max_streams_per_share_link: u32,
+
+    /// Concurrent streams per authenticated user
+    #[serde(default)]
+    pub max_streams_per_user: u32,
+}
+
+impl StreamSessionsConfig {
+    fn limit(value: u32) -> Option<u32> {
+        (value > 0).then_some(value)
+    }
+
+    /// Default share link stream limit (None = unlimited)
+    pub fn share_link_limit(&self) -> Option<u32> {
+        Self::limit(self.max_streams_per_share_link)
+    }
+
+    /// Per-user stream limit (None = unlimited)
+    pub fn user_limit(&self) -> Option<u32> {
+        Self::limit(self.max_streams_per_user)
+    }
+}
+
+/// Sanity checks for publishing regenerated output
+///
+/// New M3U/XMLTV files are staged next to the live files and only swapped in once they
+/// pass these checks, so a broken regeneration leaves clients on the previous output.
+/// The replaced generation is kept for `POST /proxies/{id}/rollback`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputPublishingConfig {
+    /// Reject playlists with fewer channels than this (0 = no minimum)
+    #[serde(default)]
+    pub min_channels: usize,
+
+    /// Reject playlists that lost more than this percentage of the previous generation's
+    /// channels (0 = no limit)
+    #[serde(default)]
+    pub max_channel_drop_percent: u8,
+
+    /// Keep the replaced generation so it can be restored with a rollback
+    #[serde(default = "default_keep_previous_output")]
+    pub keep_previous: bool,
+
+    /// Playlist generations kept for `/proxy/{id}/m3u8/delta` (0 disables deltas)
+    #[serde(default = "default_delta_history")]
+    pub delta_history: usize,
+}
+
+fn default_keep_previous_output() -> bool {
+    true
+}
+
+fn default_delta_history() -> usize {
+    10
+}
+
+impl Default for OutputPublishingConfig {
+    fn default() -> Self {
+        Self {
+            min_channels: 0,
+            max_channel_drop_percent: 0,
+            keep_previous: true,
+            delta_history: default_delta_history(),
+        }
+    }
+}
+
+/// Observability settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ObservabilityConfig {
+    #[serde(default)]
+    pub metrics_export: MetricsExportConfig,
+}
+
+/// OTLP transport used to push metrics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetricsExportProtocol {
+    #[default]
+    #[serde(rename = "http/protobuf")]
+    HttpProtobuf,
+    #[serde(rename = "grpc")]
+    Grpc,
+}
+
+/// Push-based metrics export for deployments that cannot be scraped
+///
+/// Metrics are pushed over OTLP every `interval` to an OpenTelemetry collector or to
+/// Prometheus' OTLP receiver; failed pushes are retried up to `max_retries` times.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsExportConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default)]
+    pub protocol: MetricsExportProtocol,
+
+    /// Collector endpoint; for http/protobuf the full metrics URL
+    /// (e.g. "http://collector:4318/v1/metrics"). Falls back to OTEL_EXPORTER_OTLP_ENDPOINT.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// How often metrics are pushed (e.g., "60s")
+    #[serde(default = "default_metrics_export_interval")]
+    pub interval: String,
+
+    /// Timeout of each push
+    #[serde(default = "default_metrics_export_timeout")]
+    pub timeout: String,
+
+    /// Extra HTTP headers sent with each push (http/protobuf only), e.g. authorization
+    #[serde(default)]
+    pub headers: std::collections::HashMap<String, String>,
+
+    /// Retries of a failed push before its batch is dropped
+    #[serde(default = "default_metrics_export_max_retries")]
+    pub max_retries: u32,
+}
+
+impl MetricsExportConfig {
+    /// Parsed push interval (falls back to 60 seconds)
+    pub fn interval_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.interval)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(60))
+    }
+
+    /// Parsed push timeout (falls back to 10 seconds)
+    pub fn timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(10))
+    }
+}
+
+impl Default for MetricsExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            protocol: MetricsExportProtocol::default(),
+            endpoint: None,
+            interval: default_metrics_export_interval(),
+            timeout: default_metrics_export_timeout(),
+            headers: std::collections::HashMap::new(),
+            max_retries: default_metrics_export_max_retries(),
+        }
+    }
+}
+
+fn default_metrics_export_interval() -> String {
+    "60s".to_string()
+}
+fn default_metrics_export_timeout() -> String {
+    "10s".to_string()
+}
+fn default_metrics_export_max_retries() -> u32 {
+    3
+}
+
+/// MQTT publishing of viewing and source events, for home automation
+///
+/// Events are published to `topic_template` (or a per-event entry in `topics`) with a JSON
+/// payload unless a per-event template in `payloads` is set. Templates substitute
+/// `{prefix}`, `{event}` and the event's fields (e.g. `{channel_name}`, `{source_name}`).
+/// `{prefix}/status` carries "online"/"offline" (retained, with a last will) for
+/// availability tracking.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default = "default_mqtt_host")]
+    pub host: String,
+
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+
+    #[serde(default)]
+    pub username: Option<String>,
+
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// Substituted for `{prefix}` in topics
+    #[serde(default = "default_mqtt_topic_prefix")]
+    pub topic_prefix: String,
+
+    /// Topic of events without an entry in `topics`
+    #[serde(default = "default_mqtt_topic_template")]
+    pub topic_template: String,
+
+    /// Topic templates by event name (stream_started, stream_stopped, source_refresh_failed)
+    #[serde(default)]
+    pub topics: std::collections::HashMap<String, String>,
+
+    /// Payload templates by event name; events without one are published as JSON
+    #[serde(default)]
+    pub payloads: std::collections::HashMap<String, String>,
+
+    /// Events to publish; empty publishes every event
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// 0 (at most once), 1 (at least once) or 2 (exactly once)
+    #[serde(default)]
+    pub qos: u8,
+
+    #[serde(default)]
+    pub retain: bool,
+
+    #[serde(default = "default_mqtt_keep_alive")]
+    pub keep_alive: String,
+}
+
+impl MqttConfig {
+    /// Parsed keep-alive interval (falls back to 30 seconds)
+    pub fn keep_alive_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.keep_alive)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(30))
+    }
+
+    /// Whether an event is published
+    pub fn publishes(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event)
+    }
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: default_mqtt_host(),
+            port: default_mqtt_port(),
+            client_id: default_mqtt_client_id(),
+            username: None,
+            password: None,
+            topic_prefix: default_mqtt_topic_prefix(),
+            topic_template: default_mqtt_topic_template(),
+            topics: std::collections::HashMap::new(),
+            payloads: std::collections::HashMap::new(),
+            events: Vec::new(),
+            qos: 0,
+            retain: false,
+            keep_alive: default_mqtt_keep_alive(),
+        }
+    }
+}
+
+fn default_mqtt_host() -> String {
+    "localhost".to_string()
+}
+fn default_mqtt_port() -> u16 {
+    1883
+}
+fn default_mqtt_client_id() -> String {
+    "m3u-proxy".to_string()
+}
+fn default_mqtt_topic_prefix() -> String {
+    "m3u-proxy".to_string()
+}
+fn default_mqtt_topic_template() -> String {
+    "{prefix}/events/{event}".to_string()
+}
+fn default_mqtt_keep_alive() -> String {
+    "30s".to_string()
+}
+
+/// Cluster mode: several instances sharing one PostgreSQL database
+///
+/// The node holding a PostgreSQL advisory lock is the leader: only it schedules and runs
+/// ingestion, regeneration and maintenance jobs and imports the XMLTV watch folder. Every
+/// node serves the API, playlists, guides and streams, so the storage paths (`m3u_path`,
+/// logo paths) must be shared by all nodes. Jobs queued through a follower's API wait
+/// until that node becomes leader; the lock is released when the leader stops or loses its
+/// database connection, and another node takes over within `check_interval`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Name of this node in logs (defaults to the hostname)
+    #[serde(default = "default_cluster_node_id")]
+    pub node_id: String,
+
+    /// Advisory lock key; must be the same on every node of a cluster
+    #[serde(default = "default_cluster_lock_key")]
+    pub lock_key: i64,
+
+    /// How often followers try to take the lock and the leader checks it still holds it
+    #[serde(default = "default_cluster_check_interval")]
+    pub check_interval: String,
+}
+
+impl ClusterConfig {
+    /// Parsed check interval (falls back to 10 seconds)
+    pub fn check_interval_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.check_interval)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(10))
+    }
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            node_id: default_cluster_node_id(),
+            lock_key: default_cluster_lock_key(),
+            check_interval: default_cluster_check_interval(),
+        }
+    }
+}
+
+fn default_cluster_node_id() -> String {
+    std::env::var("HOSTNAME").unwrap_or_else(|_| uuid::Uuid::new_v4().simple().to_string())
+}
+fn default_cluster_lock_key() -> i64 {
+    // "m3uproxy" in ASCII
+    0x6d33_7570_726f_7879
+}
+fn default_cluster_check_interval() -> String {
+    "10s".to_string()
+}
+
+/// systemd service integration
+///
+/// Under a `Type=notify` unit the server reports readiness once it is listening and its
+/// background services are running, and reports stopping on shutdown. With `WatchdogSec=`
+/// set, keepalives are only sent while the job runner is making progress and the database
+/// answers, so systemd restarts an instance that has hung. Without `NOTIFY_SOCKET` (not run
+/// by systemd) nothing is sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemdConfig {
+    #[serde(default = "default_systemd_enabled")]
+    pub enabled: bool,
+
+    /// Withhold watchdog keepalives once the job runner has not completed a pass for this long
+    #[serde(default = "default_systemd_job_runner_stall_timeout")]
+    pub job_runner_stall_timeout: String,
+
+    /// Withhold watchdog keepalives when a database round trip takes longer than this
+    #[serde(default = "default_systemd_database_timeout")]
+    pub database_timeout: String,
+}
+
+impl SystemdConfig {
+    /// Parsed job runner stall timeout (falls back to 60 seconds)
+    pub fn job_runner_stall_timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.job_runner_stall_timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(60))
+    }
+
+    /// Parsed database check timeout (falls back to 5 seconds)
+    pub fn database_timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.database_timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(5))
+    }
+}
+
+impl Default for SystemdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            job_runner_stall_timeout: default_systemd_job_runner_stall_timeout(),
+            database_timeout: default_systemd_database_timeout(),
+        }
+    }
+}
+
+fn default_systemd_enabled() -> bool {
+    true
+}
+fn default_systemd_job_runner_stall_timeout() -> String {
+    "60s".to_string()
+}
+fn default_systemd_database_timeout() -> String {
+    "5s".to_string()
+}
+
+/// Offline slate served by proxies whose `offline_slate` mode is not "off"
+///
+/// When a proxy-mode upstream cannot be reached, or drops mid-stream, the client is fed a
+/// looping slate rendered by ffmpeg instead of an error. The upstream is retried every
+/// `retry_interval` and the stream switches back to it as soon as it answers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineSlateConfig {
+    /// Text on the generated slate; `{channel}` is replaced by the channel name
+    #[serde(default = "default_offline_slate_message")]
+    pub message: String,
+
+    /// MPEG-TS compatible media (e.g. an H.264/AAC MP4) looped by proxies in "media" mode;
+    /// they fall back to the generated slate when unset
+    #[serde(default)]
+    pub media_path: Option<PathBuf>,
+
+    /// Generated slate resolution
+    #[serde(default = "default_offline_slate_width")]
+    pub width: u32,
+    #[serde(default = "default_offline_slate_height")]
+    pub height: u32,
+
+    /// How often the upstream is retried while the slate plays
+    #[serde(default = "default_offline_slate_retry_interval")]
+    pub retry_interval: String,
+
+    /// Give up and end the stream after the slate has played this long
+    #[serde(default = "default_offline_slate_max_duration")]
+    pub max_duration: String,
+}
+
+impl OfflineSlateConfig {
+    /// Parsed retry interval (falls back to 10 seconds)
+    pub fn retry_interval_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.retry_interval)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(10))
+    }
+
+    /// Parsed maximum slate duration (falls back to 1 hour)
+    pub fn max_duration_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.max_duration)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(60 * 60))
+    }
+}
+
+impl Default for OfflineSlateConfig {
+    fn default() -> Self {
+        Self {
+            message: default_offline_slate_message(),
+            media_path: None,
+            width: default_offline_slate_width(),
+            height: default_offline_slate_height(),
+            retry_interval: default_offline_slate_retry_interval(),
+            max_duration: default_offline_slate_max_duration(),
+        }
+    }
+}
+
+fn default_offline_slate_message() -> String {
+    "{channel} is currently unavailable".to_string()
+}
+fn default_offline_slate_width() -> u32 {
+    1280
+}
+fn default_offline_slate_height() -> u32 {
+    720
+}
+fn default_offline_slate_retry_interval() -> String {
+    "10s".to_string()
+}
+fn default_offline_slate_max_duration() -> String {
+    "1h".to_string()
+}
+
+/// Component checks of `/health/deep`
+///
+/// Each check reports healthy, degraded or unhealthy with its own latency; `strictness`
+/// decides which outcome turns the response into a 503 for load balancers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepHealthConfig {
+    #[serde(default)]
+    pub strictness: DeepHealthStrictness,
+
+    /// A component check taking longer than this reports the component unhealthy
+    #[serde(default = "default_deep_health_check_timeout")]
+    pub check_timeout: String,
+
+    /// Database round trips slower than this report the database degraded
+    #[serde(default = "default_deep_health_database_latency_warning")]
+    pub database_latency_warning: String,
+
+    /// Pending jobs above this report the job queue degraded
+    #[serde(default = "default_deep_health_job_backlog_warning")]
+    pub job_backlog_warning: usize,
+
+    /// The job runner is unhealthy once it has not completed a pass for this long
+    #[serde(default = "default_deep_health_job_runner_stall_timeout")]
+    pub job_runner_stall_timeout: String,
+
+    /// The scheduler is unhealthy once it has not ticked for this long (it ticks every minute)
+    #[serde(default = "default_deep_health_scheduler_tick_timeout")]
+    pub scheduler_tick_timeout: String,
+
+    /// Report a missing ffmpeg as unhealthy rather than degraded
+    #[serde(default)]
+    pub require_ffmpeg: bool,
+}
+
+/// Which component outcomes make `/health/deep` answer 503
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeepHealthStrictness {
+    /// Only an unhealthy critical component (database, job queue, storage)
+    Critical,
+    /// Any unhealthy component
+    #[default]
+    Standard,
+    /// Any component that is not fully healthy
+    Strict,
+}
+
+impl DeepHealthConfig {
+    /// Parsed component check timeout (falls back to 5 seconds)
+    pub fn check_timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.check_timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(5))
+    }
+
+    /// Parsed database latency warning (falls back to 200 milliseconds)
+    pub fn database_latency_warning_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.database_latency_warning)
+            .unwrap_or_else(|_| std::time::Duration::from_millis(200))
+    }
+
+    /// Parsed job runner stall timeout (falls back to 60 seconds)
+    pub fn job_runner_stall_timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.job_runner_stall_timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(60))
+    }
+
+    /// Parsed scheduler tick timeout (falls back to 3 minutes)
+    pub fn scheduler_tick_timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.scheduler_tick_timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(180))
+    }
+}
+
+impl Default for DeepHealthConfig {
+    fn default() -> Self {
+        Self {
+            strictness: DeepHealthStrictness::default(),
+            check_timeout: default_deep_health_check_timeout(),
+            database_latency_warning: default_deep_health_database_latency_warning(),
+            job_backlog_warning: default_deep_health_job_backlog_warning(),
+            job_runner_stall_timeout: default_deep_health_job_runner_stall_timeout(),
+            scheduler_tick_timeout: default_deep_health_scheduler_tick_timeout(),
+            require_ffmpeg: false,
+        }
+    }
+}
+
+fn default_deep_health_check_timeout() -> String {
+    "5s".to_string()
+}
+fn default_deep_health_database_latency_warning() -> String {
+    "200ms".to_string()
+}
+fn default_deep_health_job_backlog_warning() -> usize {
+    50
+}
+fn default_deep_health_job_runner_stall_timeout() -> String {
+    "60s".to_string()
+}
+fn default_deep_health_scheduler_tick_timeout() -> String {
+    "3m".to_string()
+}
+
+/// Budget for downloading logos during proxy generation
+///
+/// Generation downloads at most `max_new_logos_per_run` logos that are not cached yet; the
+/// rest keep their original URL for that run and are fetched by a background maintenance
+/// job. All logo downloads share one process-wide rate limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogoPrefetchConfig {
+    /// New (uncached) logos a single generation may download; 0 defers every new logo
+    #[serde(default = "default_logo_prefetch_max_new_logos_per_run")]
+    pub max_new_logos_per_run: usize,
+
+    /// Logo downloads running at the same time, per generation or background job
+    #[serde(default = "default_logo_prefetch_max_concurrent_fetches")]
+    pub max_concurrent_fetches: usize,
+
+    /// Logo downloads started per second across the whole process; 0 disables the limit
+    #[serde(default = "default_logo_prefetch_max_fetches_per_second")]
+    pub max_fetches_per_second: u32,
+
+    /// Deferred logos downloaded by one background job run
+    #[serde(default = "default_logo_prefetch_background_batch_size")]
+    pub background_batch_size: usize,
+
+    /// Deferred logos kept waiting for the background job; further logos are dropped until
+    /// a later generation defers them again
+    #[serde(default = "default_logo_prefetch_max_deferred")]
+    pub max_deferred: usize,
+}
+
+impl Default for LogoPrefetchConfig {
+    fn default() -> Self {
+        Self {
+            max_new_logos_per_run: default_logo_prefetch_max_new_logos_per_run(),
+            max_concurrent_fetches: default_logo_prefetch_max_concurrent_fetches(),
+            max_fetches_per_second: default_logo_prefetch_max_fetches_per_second(),
+            background_batch_size: default_logo_prefetch_background_batch_size(),
+            max_deferred: default_logo_prefetch_max_deferred(),
+        }
+    }
+}
+
+fn default_logo_prefetch_max_new_logos_per_run() -> usize {
+    200
+}
+fn default_logo_prefetch_max_concurrent_fetches() -> usize {
+    8
+}
+fn default_logo_prefetch_max_fetches_per_second() -> u32 {
+    20
+}
+fn default_logo_prefetch_background_batch_size() -> usize {
+    500
+}
+fn default_logo_prefetch_max_deferred() -> usize {
+    20000
+}
+
+/// Trash for deleted sources, proxies, filters and data mapping rules
+///
+/// Deleting one of these moves it to the trash, where it can be restored. A maintenance job
+/// checks every `purge_interval` and permanently removes items trashed longer than
+/// `retention` ago.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashConfig {
+    /// How long trashed items can be restored before they are purged (e.g. "30d")
+    #[serde(default = "default_trash_retention")]
+    pub retention: String,
+
+    /// How often the purge job runs (e.g. "1h")
+    #[serde(default = "default_trash_purge_interval")]
+    pub purge_interval: String,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self {
+            retention: default_trash_retention(),
+            purge_interval: default_trash_purge_interval(),
+        }
+    }
+}
+
+impl TrashConfig {
+    /// Parsed retention period (falls back to 30 days)
+    pub fn retention_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.retention)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(30 * 24 * 60 * 60))
+    }
+
+    /// Parsed purge interval (falls back to 1 hour)
+    pub fn purge_interval_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.purge_interval)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(60 * 60))
+    }
+}
+
+fn default_trash_retention() -> String {
+    "30d".to_string()
+}
+fn default_trash_purge_interval() -> String {
+    "1h".to_string()
+}
+
+/// Automatic proxy regeneration after source updates
+///
+/// A source update opens a debounce window on each proxy it feeds. Updates of further
+/// sources arriving inside the window join the same batch, and the proxy regenerates once
+/// when the window closes, recording every trigger it coalesced. Proxies can override the
+/// window with `regeneration_debounce_seconds`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyRegenerationConfig {
+    /// Default debounce window (e.g. "15s", "5m")
+    #[serde(default = "default_regeneration_debounce")]
+    pub debounce: String,
+}
+
+impl Default for ProxyRegenerationConfig {
+    fn default() -> Self {
+        Self {
+            debounce: default_regeneration_debounce(),
+        }
+    }
+}
+
+impl ProxyRegenerationConfig {
+    /// Parsed debounce window (falls back to 15 seconds)
+    pub fn debounce_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.debounce)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(15))
+    }
+}
+
+fn default_regeneration_debounce() -> String {
+    "15s".to_string()
+}
+
+/// Rate-of-change alerts on channel counts
+///
+/// The channel count of each successful stream source ingestion and proxy generation is
+/// compared with the previous one. A source losing more than `source_drop_percent` of its
+/// channels, or a proxy whose playlist grows or shrinks by more than
+/// `proxy_change_percent`, raises an alert: it is published as an MQTT event and flagged on
+/// the source or proxy until a later count is back within the threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelCountAlertConfig {
+    /// Whether channel counts are tracked and compared (default: true)
+    #[serde(default = "default_channel_count_alerts_enabled")]
+    pub enabled: bool,
+
+    /// Percentage drop of a source's channel count that raises an alert; 0 disables
+    #[serde(default = "default_source_drop_percent")]
+    pub source_drop_percent: f64,
+
+    /// Percentage change, up or down, of a proxy's channel count that raises an alert;
+    /// 0 disables
+    #[serde(default = "default_proxy_change_percent")]
+    pub proxy_change_percent: f64,
+
+    /// Previous counts below this are too small to alert on
+    #[serde(default = "default_channel_count_alert_min_channels")]
+    pub min_channels: u64,
+}
+
+impl Default for ChannelCountAlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            source_drop_percent: default_source_drop_percent(),
+            proxy_change_percent: default_proxy_change_percent(),
+            min_channels: default_channel_count_alert_min_channels(),
+        }
+    }
+}
+
+fn default_channel_count_alerts_enabled() -> bool {
+    true
+}
+fn default_source_drop_percent() -> f64 {
+    20.0
+}
+fn default_proxy_change_percent() -> f64 {
+    25.0
+}
+fn default_channel_count_alert_min_channels() -> u64 {
+    10
+}
+
+/// In-memory cache of frequently polled API queries
+///
+/// Source lists with their channel and programme counts, and the dashboard totals, are
+/// kept for up to `ttl`. Creating, updating, deleting or ingesting a source or proxy drops
+/// the cached results built from it straight away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryCacheConfig {
+    /// Whether query results are cached (default: true)
+    #[serde(default = "default_query_cache_enabled")]
+    pub enabled: bool,
+
+    /// Longest a cached result is served (e.g. "30s")
+    #[serde(default = "default_query_cache_ttl")]
+    pub ttl: String,
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_query_cache_enabled(),
+            ttl: default_query_cache_ttl(),
+        }
+    }
+}
+
+impl QueryCacheConfig {
+    /// Parsed TTL (falls back to 30 seconds)
+    pub fn ttl_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.ttl).unwrap_or_else(|_| std::time::Duration::from_secs(30))
+    }
+}
+
+fn default_query_cache_enabled() -> bool {
+    true
+}
+fn default_query_cache_ttl() -> String {
+    "30s".to_string()
+}
+
+/// External commands run before and after proxy generations
+///
+/// Each hook runs one command directly (no shell) with a cleared environment and a timeout,
+/// receiving a JSON summary of the generation on stdin. Post-generation hooks run once the
+/// new output is published, e.g. to push it to a CDN; failing pre-generation hooks marked
+/// `required` abort the generation. There are no WASM hooks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationHooksConfig {
+    #[serde(default)]
+    pub hooks: Vec<GenerationHookConfig>,
+}
+
+impl GenerationHooksConfig {
+    /// Hooks of a stage that apply to a proxy
+    pub fn hooks_for<'a>(
+        &'a self,
+        stage: GenerationHookStage,
+        proxy_id: &uuid::Uuid,
+        proxy_name: &'a str,
+    ) -> impl Iterator<Item = &'a GenerationHookConfig> + 'a {
+        let proxy_id = proxy_id.to_string();
+        self.hooks
+            .iter()
+            .filter(move |hook| hook.stage == stage && hook.applies_to(&proxy_id, proxy_name))
+    }
+
+    /// Reject hooks that cannot run: a missing command or an unparseable timeout
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for hook in &self.hooks {
+            if hook.name.trim().is_empty() {
+                anyhow::bail!("generation_hooks: every hook needs a name");
+            }
+            if hook
+                .command
+                .first()
+                .is_none_or(|program| program.trim().is_empty())
+            {
+                anyhow::bail!("generation hook '{}' has no command", hook.name);
+            }
+            if let Err(e) = humantime::parse_duration(&hook.timeout) {
+                anyhow::bail!(
+                    "generation hook '{}' has an invalid timeout '{}': {}",
+                    hook.name,
+                    hook.timeout,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// When a generation hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GenerationHookStage {
+    /// Before the pipeline starts
+    Pre,
+    /// After the output has been published
+    Post,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationHookConfig {
+    /// Name used in logs
+    pub name: String,
+
+    pub stage: GenerationHookStage,
+
+    /// Program and arguments, e.g. ["/usr/bin/rsync", "-a", "/data/m3u/", "cdn:/m3u/"]
+    pub command: Vec<String>,
+
+    /// Proxy IDs or names the hook runs for; empty runs it for every proxy. Names survive a
+    /// proxy being recreated; entries matching no proxy are reported at startup.
+    #[serde(default)]
+    pub proxies: Vec<String>,
+
+    /// Environment of the command; nothing else is inherited apart from `PATH`
+    #[serde(default)]
+    pub env: std::collections::HashMap<String, String>,
+
+    /// Working directory of the command (defaults to the server's)
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+
+    /// The command is killed once this has passed
+    #[serde(default = "default_generation_hook_timeout")]
+    pub timeout: String,
+
+    /// A failing pre-generation hook aborts the generation (post hooks only log failures)
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl GenerationHookConfig {
+    /// Parsed timeout (falls back to 60 seconds)
+    pub fn timeout_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.timeout)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(60))
+    }
+
+    fn applies_to(&self, proxy_id: &str, proxy_name: &str) -> bool {
+        self.proxies.is_empty()
+            || self.proxies.iter().any(|proxy| {
+                proxy.eq_ignore_ascii_case(proxy_id) || proxy.eq_ignore_ascii_case(proxy_name)
+            })
+    }
+}
+
+fn default_generation_hook_timeout() -> String {
+    "60s".to_string()
+}
+
+/// HTTP caching of the generated playlist and XMLTV endpoints
+///
+/// Responses carry an `ETag` and `Last-Modified` derived from the proxy's last generation,
+/// so clients, CDNs and reverse proxies can revalidate with a `304 Not Modified` instead of
+/// downloading the full file on every poll.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistCacheConfig {
+    /// `Cache-Control` value for the M3U playlist
+    #[serde(default = "default_playlist_cache_control")]
+    pub m3u_cache_control: String,
+
+    /// `Cache-Control` value for the XMLTV guide
+    #[serde(default = "default_playlist_cache_control")]
+    pub xmltv_cache_control: String,
+}
+
+impl Default for PlaylistCacheConfig {
+    fn default() -> Self {
+        Self {
+            m3u_cache_control: default_playlist_cache_control(),
+            xmltv_cache_control: default_playlist_cache_control(),
+        }
+    }
+}
+
+fn default_playlist_cache_control() -> String {
+    "public, max-age=300, must-revalidate".to_string()
+}
+
+/// Kodi (PVR IPTV Simple Client) output profile
+///
+/// Playlists served with the `kodi` profile point Kodi at the proxy's XMLTV endpoint
+/// through an `x-tvg-url` header, advertise catchup on every channel and can rewrite
+/// nested group titles into Kodi's `;`-separated multi-group form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KodiPresetConfig {
+    /// Days of catchup advertised per channel; 0 omits the catchup attributes. Catchup
+    /// requests forward Kodi's `utc`/`lutc` parameters to the upstream stream URL.
+    #[serde(default = "default_kodi_catchup_days")]
+    pub catchup_days: u32,
+
+    /// Separator of nested group titles (e.g. "|" for "Sports|UK") rewritten to `;`
+    #[serde(default)]
+    pub group_separator: Option<String>,
+
+    /// Group given to channels without a group title
+    #[serde(default)]
+    pub default_group: Option<String>,
+}
+
+impl Default for KodiPresetConfig {
+    fn default() -> Self {
+        Self {
+            catchup_days: default_kodi_catchup_days(),
+            group_separator: None,
+            default_group: None,
+        }
+    }
+}
+
+fn default_kodi_catchup_days() -> u32 {
+    7
+}
+
+/// Playback hints in generated playlists
+///
+/// Players pick a demuxer from the stream URL, which the proxy's stream URLs do not
+/// reveal. When enabled, each playlist entry names its stream's container (from the last
+/// probe of its upstream URL, else sniffed from the URL's extension) in `#EXTVLCOPT` and
+/// `#KODIPROP` lines, and can carry request headers in the pipe-delimited URL form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamHintsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Emit `#EXTVLCOPT:demux=` lines (VLC and players following its options)
+    #[serde(default = "default_stream_hints_lines")]
+    pub vlc_options: bool,
+
+    /// Emit `#KODIPROP:mimetype=` lines (Kodi, TiviMate and others)
+    #[serde(default = "default_stream_hints_lines")]
+    pub kodi_properties: bool,
+
+    /// Request headers appended to stream URLs as `url|Name=value&...` (e.g. a User-Agent
+    /// for redirect-mode proxies); none when empty
+    #[serde(default)]
+    pub headers: std::collections::BTreeMap<String, String>,
+}
+
+impl StreamHintsConfig {
+    /// Whether hints are emitted for a proxy with the given stream hints setting
+    ///
+    /// A proxy's own setting wins; proxies without one follow `enabled`.
+    pub fn applies_to(
+        &self,
+        proxy_setting: Option<&crate::models::proxy_settings::StreamHints>,
+    ) -> bool {
+        proxy_setting.map_or(self.enabled, |setting| setting.enabled)
+    }
+}
+
+impl Default for StreamHintsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            vlc_options: default_stream_hints_lines(),
+            kodi_properties: default_stream_hints_lines(),
+            headers: Default::default(),
+        }
+    }
+}
+
+fn default_stream_hints_lines() -> bool {
+    true
+}
+
+/// Pass-through of extra EXTINF attributes in generated playlists
+///
+/// Attributes the proxy does not model (catch-up, timeshift, recording flags, custom `x-`
+/// attributes) are kept on each channel at ingestion and can be read and set in filters and
+/// data mapping as `attr.<name>`. Only allowlisted attributes are written back out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributePassthroughConfig {
+    #[serde(default = "default_attribute_passthrough_enabled")]
+    pub enabled: bool,
+
+    /// Attribute names written to generated playlists (case-insensitive)
+    #[serde(default = "default_attribute_passthrough_attributes")]
+    pub attributes: Vec<String>,
+}
+
+impl AttributePassthroughConfig {
+    /// Whether attributes are passed through for a proxy with the given passthrough setting
+    ///
+    /// A proxy's own setting wins; proxies without one follow `enabled`. Nothing is passed
+    /// through without allowlisted attributes.
+    pub fn applies_to(
+        &self,
+        proxy_setting: Option<&crate::models::proxy_settings::AttributePassthrough>,
+    ) -> bool {
+        !self.attributes.is_empty() && proxy_setting.map_or(self.enabled, |setting| setting.enabled)
+    }
+}
+
+impl Default for AttributePassthroughConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_attribute_passthrough_enabled(),
+            attributes: default_attribute_passthrough_attributes(),
+        }
+    }
+}
+
+fn default_attribute_passthrough_enabled() -> bool {
+    true
+}
+
+fn default_attribute_passthrough_attributes() -> Vec<String> {
+    [
+        "catchup",
+        "catchup-days",
+        "catchup-source",
+        "catchup-correction",
+        "timeshift",
+        "tvg-rec",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// Client IP and country restrictions for proxy playlist, XMLTV and stream endpoints
+///
+/// Rules are set per proxy through the API; proxies without rules are open to everyone.
+/// Deny rules win over allow rules, and once a proxy has any allow rule only matching
+/// clients are let in. Country rules need a MaxMind database and a build with the `geoip`
+/// feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessControlConfig {
+    /// Path to a MaxMind GeoIP2/GeoLite2 Country (or City) database
+    #[serde(default)]
+    pub geoip_database: Option<PathBuf>,
+
+    /// Take the client address from `X-Real-IP` / `X-Forwarded-For` when the request comes
+    /// from a trusted proxy (see [`ReverseProxyConfig`]); false ignores forwarded client
+    /// addresses entirely
+    #[serde(default = "default_trust_forwarded_headers")]
+    pub trust_forwarded_headers: bool,
+}
+
+impl Default for AccessControlConfig {
+    fn default() -> Self {
+        Self {
+            geoip_database: None,
+            trust_forwarded_headers: default_trust_forwarded_headers(),
+        }
+    }
+}
+
+fn default_trust_forwarded_headers() -> bool {
+    true
+}
+
+/// Reverse proxy (nginx, Traefik, Caddy, ...) in front of the server
+///
+/// `X-Forwarded-*`, `X-Real-IP` and `Remote-User` headers are only honoured on connections
+/// from a trusted proxy address, so clients cannot spoof their address, their identity or
+/// the URLs handed out to others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReverseProxyConfig {
+    /// Proxy addresses allowed to set forwarding headers, as CIDR ranges or single addresses
+    /// (default: loopback and private networks)
+    #[serde(default = "default_trusted_proxies")]
+    pub trusted_proxies: Vec<String>,
+
+    /// Build playlist, guide and stream URLs from `X-Forwarded-Proto` / `X-Forwarded-Host` /
+    /// `X-Forwarded-Port` / `X-Forwarded-Prefix` instead of `web.base_url`
+    #[serde(default = "default_forwarded_base_url")]
+    pub forwarded_base_url: bool,
+}
+
+impl Default for ReverseProxyConfig {
+    fn default() -> Self {
+        Self {
+            trusted_proxies: default_trusted_proxies(),
+            forwarded_base_url: default_forwarded_base_url(),
+        }
+    }
+}
+
+fn default_trusted_proxies() -> Vec<String> {
+    [
+        "127.0.0.0/8",
+        "::1/128",
+        "10.0.0.0/8",
+        "172.16.0.0/12",
+        "192.168.0.0/16",
+        "fc00::/7",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_forwarded_base_url() -> bool {
+    true
+}
+
+/// Stream start latency measurement
+///
+/// Every proxied or relayed stream session records how long its first byte took, split
+/// into setup, upstream connect, relay spawn and buffer fill. Sessions slower than `budget`
+/// are logged with their breakdown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamLatencyConfig {
+    /// Time to first byte above which a session is logged as slow (e.g. "3s")
+    #[serde(default = "default_stream_latency_budget")]
+    pub budget: String,
+
+    /// Recent sessions per proxy kept for the percentiles reported by the API
+    #[serde(default = "default_stream_latency_sample_size")]
+    pub sample_size: usize,
+}
+
+impl Default for StreamLatencyConfig {
+    fn default() -> Self {
+        Self {
+            budget: default_stream_latency_budget(),
+            sample_size: default_stream_latency_sample_size(),
+        }
+    }
+}
+
+impl StreamLatencyConfig {
+    /// Parsed latency budget (falls back to 3 seconds)
+    pub fn budget_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.budget)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(3))
+    }
+}
+
+fn default_stream_latency_budget() -> String {
+    "3s".to_string()
+}
+
+fn default_stream_latency_sample_size() -> usize {
+    500
+}
+
+/// Authentication of the admin API and web UI
+///
+/// Off by default, leaving the API open as before. When enabled, every `/api/v1` request
+/// needs a configured API token, a session from an OpenID Connect login, or an access
+/// token from the identity provider. Playlists, guides, streams and health checks are not
+/// affected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Require authentication on the admin API
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Static bearer tokens for scripts and integrations
+    #[serde(default)]
+    pub api_tokens: Vec<ApiTokenConfig>,
+
+    /// HMAC key of login session cookies; when unset a random key is generated at startup,
+    /// so sessions end on restart and are not shared between instances
+    #[serde(default)]
+    pub session_secret: Option<String>,
+
+    /// How long a login session lasts (e.g. "12h")
+    #[serde(default = "default_auth_session_ttl")]
+    pub session_ttl: String,
+
+    /// Single sign-on through an OpenID Connect identity provider
+    #[serde(default)]
+    pub oidc: Option<OidcConfig>,
+}
+
+impl AuthConfig {
+    /// Parsed session lifetime (falls back to 12 hours)
+    pub fn session_ttl_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.session_ttl)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(12 * 60 * 60))
+    }
+}
+
+fn default_auth_session_ttl() -> String {
+    "12h".to_string()
+}
+
+/// What an authenticated user or token may do
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthRole {
+    /// Read-only access: `GET` and `HEAD` requests
+    Viewer,
+    /// Full access
+    Admin,
+}
+
+/// A static API token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenConfig {
+    /// Name used in logs and change history
+    pub name: String,
+
+    /// Sent as `Authorization: Bearer <token>`
+    pub token: String,
+
+    #[serde(default = "default_api_token_role")]
+    pub role: AuthRole,
+}
+
+fn default_api_token_role() -> AuthRole {
+    AuthRole::Admin
+}
+
+/// OpenID Connect provider used for logins (authorization code flow with PKCE)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcConfig {
+    /// Issuer URL; the provider's configuration is read from
+    /// `<issuer_url>/.well-known/openid-configuration`
+    pub issuer_url: String,
+
+    pub client_id: String,
+
+    #[serde(default)]
+    pub client_secret: Option<String>,
+
+    /// Callback URL registered with the provider (default:
+    /// `<base URL>/api/v1/auth/oidc/callback`)
+    #[serde(default)]
+    pub redirect_url: Option<String>,
+
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+
+    /// Accepted `aud` of bearer access tokens (default: the client id)
+    #[serde(default)]
+    pub audiences: Vec<String>,
+
+    /// Claim holding the user's groups or roles; dots descend into nested objects
+    /// (e.g. "realm_access.roles")
+    #[serde(default = "default_oidc_role_claim")]
+    pub role_claim: String,
+
+    /// Claim values granting the admin role
+    #[serde(default)]
+    pub admin_values: Vec<String>,
+
+    /// Claim values granting the viewer role
+    #[serde(default)]
+    pub viewer_values: Vec<String>,
+
+    /// Role of users matching neither list; unset refuses them
+    #[serde(default)]
+    pub default_role: Option<AuthRole>,
+
+    /// Claim shown as the user's name (falls back to `sub`)
+    #[serde(default = "default_oidc_username_claim")]
+    pub username_claim: String,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    ["openid", "profile", "email"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_oidc_role_claim() -> String {
+    "groups".to_string()
+}
+
+fn default_oidc_username_claim() -> String {
+    "preferred_username".to_string()
+}
+
+/// External blocklist of stream hostnames and tvg_ids that must never be published
+///
+/// The list is loaded from `source` (a local file path or an http(s) URL) at startup and
+/// every `refresh_interval`. Every proxy generation removes matching channels in a final
+/// compliance stage, logging each removal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceBlocklistConfig {
+    /// File path or URL of the blocklist; unset disables the compliance stage
+    #[serde(default)]
+    pub source: Option<String>,
+
+    /// How often the blocklist is reloaded (e.g., "6h")
+    #[serde(default = "default_compliance_refresh_interval")]
+    pub refresh_interval: String,
+
+    /// Fail generation while no blocklist could be loaded, rather than publishing
+    /// unchecked output (default: true)
+    #[serde(default = "default_compliance_fail_closed")]
+    pub fail_closed: bool,
+}
+
+impl ComplianceBlocklistConfig {
+    /// Parsed refresh interval (falls back to 6 hours)
+    pub fn refresh_interval_duration(&self) -> std::time::Duration {
+        humantime::parse_duration(&self.refresh_interval)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(6 * 60 * 60))
+    }
+}
+
+impl Default for ComplianceBlocklistConfig {
+    fn default() -> Self {
+        Self {
+            source: None,
+            refresh_interval: default_compliance_refresh_interval(),
+            fail_closed: default_compliance_fail_closed(),
+        }
+    }
+}
+
+fn default_compliance_refresh_interval() -> String {
+    "6h".to_string()
+}
+fn default_compliance_fail_closed() -> bool {
+    true
+}
+
+fn default_max_buffer_size() -> usize {
+    50 * 1024 * 1024
+} // 50MB
+fn default_max_chunks() -> usize {
+    1000
+}
+fn default_chunk_timeout_seconds() -> u64 {
+    60
+}
+fn default_client_timeout_seconds() -> u64 {
+    30
+}
+fn default_cleanup_interval_seconds() -> u64 {
+    5
+}
+fn default_enable_file_spill() -> bool {
+    false
+}
+fn default_max_file_spill_size() -> usize {
+    500 * 1024 * 1024
+} // 500MB
+
+impl Default for BufferConfig {
+    fn default() -> Self {
+        Self {
+            max_buffer_size: default_max_buffer_size(),
+            max_chunks: default_max_chunks(),
+            chunk_timeout_seconds: default_chunk_timeout_seconds(),
+            client_timeout_seconds: default_client_timeout_seconds(),
+            cleanup_interval_seconds: default_cleanup_interval_seconds(),
+            enable_file_spill: default_enable_file_spill(),
+            max_file_spill_size: default_max_file_spill_size(),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            database: DatabaseConfig {
+                url: "sqlite:./data/m3u-proxy.db".to_string(),
+                max_connections: Some(10),
+                batch_sizes: Some(DatabaseBatchConfig::default()),
+                sqlite: SqliteConfig::default(),
+                postgresql: PostgreSqlConfig::default(),
+                mysql: MySqlConfig::default(),
+            },
+            web: WebConfig {
                 host: "0.0.0.0".to_string(),
                 port: 8080,
                 base_url: "http://localhost:8080".to_string(),
@@ -877,6 +3300,7 @@ impl Default for Config {
                 enable_request_logging: default_enable_request_logging(),
                 user_agent: default_user_agent(),
                 proxy_upstream_connect_timeout: default_proxy_upstream_connect_timeout(),
+                tls: None,
             },
             storage: StorageConfig {
                 m3u_path: PathBuf::from("./data/m3u"),
@@ -890,11 +3314,15 @@ impl Default for Config {
                 pipeline_path: PathBuf::from("./data/pipeline"),
                 pipeline_retention: "10m".to_string(),
                 pipeline_cleanup_interval: "2m".to_string(),
+                object_storage: None,
+                quotas: std::collections::HashMap::new(),
             },
             ingestion: IngestionConfig {
                 progress_update_interval: 1000,
                 run_missed_immediately: true,
                 use_new_source_handlers: default_use_new_source_handlers(),
+                max_decompressed_size_mb: default_max_decompressed_size_mb(),
+                limits: IngestionLimitsConfig::default(),
             },
             data_mapping_engine: Some(DataMappingEngineConfig::default()),
             relay: Some(RelayConfig::default()),
@@ -904,6 +3332,39 @@ impl Default for Config {
             features: Some(FeaturesConfig::default()),
             circuitbreaker: Some(CircuitBreakerConfig::default()),
             job_scheduling: Some(JobSchedulingConfig::default()),
+            xmltv_import: Some(XmltvImportConfig::default()),
+            ingest_archive: Some(IngestArchiveConfig::default()),
+            epg_merge: Some(EpgMergeConfig::default()),
+            epg_failover: Some(EpgFailoverConfig::default()),
+            epg_gap_filler: Some(EpgGapFillerConfig::default()),
+            channel_probe: Some(ChannelProbeConfig::default()),
+            channel_preview: Some(ChannelPreviewConfig::default()),
+            pipeline_inspection: Some(PipelineInspectionConfig::default()),
+            pipeline_stage_cache: Some(PipelineStageCacheConfig::default()),
+            stream_signing: Some(StreamSigningConfig::default()),
+            playlist_cache: Some(PlaylistCacheConfig::default()),
+            kodi_preset: Some(KodiPresetConfig::default()),
+            stream_hints: Some(StreamHintsConfig::default()),
+            attribute_passthrough: Some(AttributePassthroughConfig::default()),
+            access_control: Some(AccessControlConfig::default()),
+            reverse_proxy: Some(ReverseProxyConfig::default()),
+            compliance_blocklist: Some(ComplianceBlocklistConfig::default()),
+            stream_sessions: Some(StreamSessionsConfig::default()),
+            output_publishing: Some(OutputPublishingConfig::default()),
+            observability: Some(ObservabilityConfig::default()),
+            mqtt: Some(MqttConfig::default()),
+            cluster: Some(ClusterConfig::default()),
+            systemd: Some(SystemdConfig::default()),
+            offline_slate: Some(OfflineSlateConfig::default()),
+            deep_health: Some(DeepHealthConfig::default()),
+            logo_prefetch: Some(LogoPrefetchConfig::default()),
+            trash: Some(TrashConfig::default()),
+            regeneration: Some(ProxyRegenerationConfig::default()),
+            channel_count_alerts: Some(ChannelCountAlertConfig::default()),
+            generation_hooks: Some(GenerationHooksConfig::default()),
+            query_cache: Some(QueryCacheConfig::default()),
+            auth: Some(AuthConfig::default()),
+            stream_latency: Some(StreamLatencyConfig::default()),
         }
     }
 }
@@ -986,4 +3447,64 @@ mod tests {
         assert_eq!(default_config.epg_programs, None); // Uses backend-specific defaults
         assert_eq!(default_config.stream_channels, Some(1000));
     }
+
+    #[test]
+    fn test_object_storage_categories() {
+        let mut storage = StorageConfig::default();
+        assert!(storage.object_storage_for("m3u").is_none());
+
+        let mut bucket = sandboxed_file_manager::ObjectStorageConfig::new("iptv");
+        bucket.prefix = "prod".to_string();
+        storage.object_storage = Some(ObjectStorageSettings {
+            categories: vec!["m3u".to_string()],
+            bucket,
+        });
+        assert!(storage.validate_object_storage().is_ok());
+        assert_eq!(
+            storage.object_storage_for("m3u").unwrap().prefix,
+            "prod/m3u"
+        );
+        assert!(storage.object_storage_for("cached_logos").is_none());
+
+        storage.object_storage.as_mut().unwrap().categories = vec!["temp".to_string()];
+        assert!(storage.validate_object_storage().is_err());
+    }
+
+    #[test]
+    fn test_web_tls_validation() {
+        let files = WebTlsConfig {
+            cert_path: Some(PathBuf::from("cert.pem")),
+            key_path: Some(PathBuf::from("key.pem")),
+            ..Default::default()
+        };
+        assert!(files.validate().is_ok());
+        assert_eq!(files.alpn_protocols()[0], b"h2".to_vec());
+
+        let missing_key = WebTlsConfig {
+            key_path: None,
+            ..files.clone()
+        };
+        assert!(missing_key.validate().is_err());
+
+        let acme = AcmeConfig {
+            domains: vec!["tv.example.com".to_string()],
+            contacts: vec![],
+            cache_path: default_acme_cache_path(),
+            directory_url: None,
+            production: false,
+        };
+        let acme_only = WebTlsConfig {
+            acme: Some(acme.clone()),
+            http2: false,
+            ..Default::default()
+        };
+        assert!(acme_only.validate().is_ok());
+        assert_eq!(acme_only.alpn_protocols(), vec![b"http/1.1".to_vec()]);
+
+        let both = WebTlsConfig {
+            acme: Some(acme),
+            ..files
+        };
+        assert!(both.validate().is_err());
+    }
 }
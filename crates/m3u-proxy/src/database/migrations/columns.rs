@@ -0,0 +1,34 @@
+//! Column helpers shared by the migrations
+//!
+//! UUIDs and timestamps are native types on PostgreSQL and strings on SQLite and MySQL,
+//! matching the initial schema.
+
+use sea_orm_migration::prelude::*;
+
+/// UUID column (native UUID on PostgreSQL, string elsewhere), not null
+pub fn uuid_column(manager: &SchemaManager, column: impl IntoIden) -> ColumnDef {
+    let mut col = nullable_uuid_column(manager, column);
+    col.not_null();
+    col
+}
+
+/// Nullable UUID column (native UUID on PostgreSQL, string elsewhere)
+pub fn nullable_uuid_column(manager: &SchemaManager, column: impl IntoIden) -> ColumnDef {
+    let mut col = ColumnDef::new(column);
+    match manager.get_database_backend() {
+        sea_orm::DatabaseBackend::Postgres => col.uuid().null(),
+        _ => col.string().null(),
+    };
+    col
+}
+
+/// Timestamp column (TIMESTAMPTZ on PostgreSQL, string elsewhere); nullable unless the
+/// caller adds `not_null()`
+pub fn timestamp_column(manager: &SchemaManager, column: impl IntoIden) -> ColumnDef {
+    let mut col = ColumnDef::new(column);
+    match manager.get_database_backend() {
+        sea_orm::DatabaseBackend::Postgres => col.timestamp_with_time_zone(),
+        _ => col.string(),
+    };
+    col
+}
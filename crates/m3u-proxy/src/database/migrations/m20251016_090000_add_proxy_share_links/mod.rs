@@ -0,0 +1,104 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `proxy_share_links` table backing temporary, revocable playlist share URLs.
+///
+/// Each link belongs to a proxy (cascade on delete), carries a unique random token, an
+/// expiry, an optional maximum number of playlist fetches and an optional revocation time.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProxyShareLinks::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ProxyShareLinks::Id).primary_key())
+                    .col(uuid_column(manager, ProxyShareLinks::ProxyId))
+                    .col(ColumnDef::new(ProxyShareLinks::Token).string().not_null())
+                    .col(ColumnDef::new(ProxyShareLinks::Name).string())
+                    .col(timestamp_column(manager, ProxyShareLinks::ExpiresAt).not_null())
+                    .col(ColumnDef::new(ProxyShareLinks::MaxUses).integer())
+                    .col(
+                        ColumnDef::new(ProxyShareLinks::UseCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(timestamp_column(manager, ProxyShareLinks::LastUsedAt))
+                    .col(timestamp_column(manager, ProxyShareLinks::RevokedAt))
+                    .col(timestamp_column(manager, ProxyShareLinks::CreatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_proxy_share_links_proxy_id")
+                            .from(ProxyShareLinks::Table, ProxyShareLinks::ProxyId)
+                            .to(StreamProxies::Table, StreamProxies::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_proxy_share_links_token_unique")
+                    .table(ProxyShareLinks::Table)
+                    .col(ProxyShareLinks::Token)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_proxy_share_links_proxy_id")
+                    .table(ProxyShareLinks::Table)
+                    .col(ProxyShareLinks::ProxyId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ProxyShareLinks::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProxyShareLinks {
+    Table,
+    Id,
+    ProxyId,
+    Token,
+    Name,
+    ExpiresAt,
+    MaxUses,
+    UseCount,
+    LastUsedAt,
+    RevokedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    Id,
+}
@@ -0,0 +1,161 @@
+use super::columns::{nullable_uuid_column, timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds user-defined virtual channels and their proxy assignments.
+///
+/// `virtual_channels` holds channels that do not come from any stream source (weather cams,
+/// local tuners, "channel offline" loops). `proxy_virtual_channels` links them to the proxies
+/// they are injected into during generation (cascade on delete of either side).
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VirtualChannels::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, VirtualChannels::Id).primary_key())
+                    .col(ColumnDef::new(VirtualChannels::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(VirtualChannels::StreamUrl)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VirtualChannels::TvgId).string())
+                    .col(ColumnDef::new(VirtualChannels::TvgLogo).string())
+                    .col(ColumnDef::new(VirtualChannels::GroupTitle).string())
+                    .col(ColumnDef::new(VirtualChannels::ChannelNumber).integer())
+                    .col(nullable_uuid_column(
+                        manager,
+                        VirtualChannels::RelayProfileId,
+                    ))
+                    .col(
+                        ColumnDef::new(VirtualChannels::IsActive)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(timestamp_column(manager, VirtualChannels::CreatedAt).not_null())
+                    .col(timestamp_column(manager, VirtualChannels::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_virtual_channels_relay_profile_id")
+                            .from(VirtualChannels::Table, VirtualChannels::RelayProfileId)
+                            .to(RelayProfiles::Table, RelayProfiles::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProxyVirtualChannels::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ProxyVirtualChannels::ProxyId))
+                    .col(uuid_column(manager, ProxyVirtualChannels::VirtualChannelId))
+                    .col(timestamp_column(manager, ProxyVirtualChannels::CreatedAt).not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(ProxyVirtualChannels::ProxyId)
+                            .col(ProxyVirtualChannels::VirtualChannelId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_proxy_virtual_channels_proxy_id")
+                            .from(ProxyVirtualChannels::Table, ProxyVirtualChannels::ProxyId)
+                            .to(StreamProxies::Table, StreamProxies::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_proxy_virtual_channels_virtual_channel_id")
+                            .from(
+                                ProxyVirtualChannels::Table,
+                                ProxyVirtualChannels::VirtualChannelId,
+                            )
+                            .to(VirtualChannels::Table, VirtualChannels::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_proxy_virtual_channels_virtual_channel_id")
+                    .table(ProxyVirtualChannels::Table)
+                    .col(ProxyVirtualChannels::VirtualChannelId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ProxyVirtualChannels::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(VirtualChannels::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VirtualChannels {
+    Table,
+    Id,
+    Name,
+    StreamUrl,
+    TvgId,
+    TvgLogo,
+    GroupTitle,
+    ChannelNumber,
+    RelayProfileId,
+    IsActive,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ProxyVirtualChannels {
+    Table,
+    ProxyId,
+    VirtualChannelId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum RelayProfiles {
+    Table,
+    Id,
+}
@@ -0,0 +1,52 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the per-proxy `sign_stream_urls` toggle.
+///
+/// When set, the proxy's playlist is served with HMAC-signed, expiring stream URLs and the
+/// stream endpoint rejects unsigned requests. Existing proxies keep plain URLs (default false).
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager
+            .has_column("stream_proxies", "sign_stream_urls")
+            .await?
+        {
+            return Ok(());
+        }
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .add_column(
+                        ColumnDef::new(StreamProxies::SignStreamUrls)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .drop_column(StreamProxies::SignStreamUrls)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    SignStreamUrls,
+}
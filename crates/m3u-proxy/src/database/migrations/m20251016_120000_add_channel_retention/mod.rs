@@ -0,0 +1,103 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds retention of channels a stream source stops listing.
+///
+/// `channels.missed_ingestions` counts consecutive ingestions a channel was absent from
+/// (0 for channels present in the latest ingestion). The `stream_source_channel_retention`
+/// table holds, per source, how many absences a channel survives before it is purged;
+/// sources without a row keep the previous behaviour of dropping missing channels at once.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_column("channels", "missed_ingestions").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Channels::Table)
+                        .add_column(
+                            ColumnDef::new(Channels::MissedIngestions)
+                                .integer()
+                                .not_null()
+                                .default(0),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(StreamSourceChannelRetention::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, StreamSourceChannelRetention::SourceId).primary_key())
+                    .col(
+                        ColumnDef::new(StreamSourceChannelRetention::MaxMissedIngestions)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        timestamp_column(manager, StreamSourceChannelRetention::UpdatedAt)
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stream_source_channel_retention_source_id")
+                            .from(
+                                StreamSourceChannelRetention::Table,
+                                StreamSourceChannelRetention::SourceId,
+                            )
+                            .to(StreamSources::Table, StreamSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(StreamSourceChannelRetention::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Channels::Table)
+                    .drop_column(Channels::MissedIngestions)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Channels {
+    Table,
+    MissedIngestions,
+}
+
+#[derive(DeriveIden)]
+enum StreamSourceChannelRetention {
+    Table,
+    SourceId,
+    MaxMissedIngestions,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamSources {
+    Table,
+    Id,
+}
@@ -0,0 +1,52 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the per-proxy `output_profile` column.
+///
+/// Selects the client preset the playlist is served with ("standard" or "kodi"). Existing
+/// proxies keep the standard output.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager
+            .has_column("stream_proxies", "output_profile")
+            .await?
+        {
+            return Ok(());
+        }
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .add_column(
+                        ColumnDef::new(StreamProxies::OutputProfile)
+                            .string()
+                            .not_null()
+                            .default("standard"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .drop_column(StreamProxies::OutputProfile)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    OutputProfile,
+}
@@ -0,0 +1,61 @@
+use super::columns::nullable_uuid_column;
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds source and proxy scoping to data mapping rules.
+///
+/// A rule with `scope_source_id` set applies only to channels of that stream source; one
+/// with `scope_proxy_id` set applies only in that proxy's pipeline. Rules with neither stay
+/// global, which is what every existing rule becomes. The columns carry no foreign keys
+/// (SQLite cannot add them to an existing table); a rule scoped to a deleted source or
+/// proxy simply never applies.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, column) in [
+            ("scope_source_id", DataMappingRules::ScopeSourceId),
+            ("scope_proxy_id", DataMappingRules::ScopeProxyId),
+        ] {
+            if manager.has_column("data_mapping_rules", name).await? {
+                continue;
+            }
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(DataMappingRules::Table)
+                        .add_column(nullable_uuid_column(manager, column))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            DataMappingRules::ScopeSourceId,
+            DataMappingRules::ScopeProxyId,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(DataMappingRules::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum DataMappingRules {
+    Table,
+    ScopeSourceId,
+    ScopeProxyId,
+}
@@ -0,0 +1,90 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds audio and subtitle track selection to relay profiles.
+///
+/// `audio_languages` / `subtitle_languages` hold comma-separated language codes in priority
+/// order; `audio_track_index` / `subtitle_track_index` force a track by its position among
+/// the input's tracks of that type. Existing profiles keep mapping the first audio track and
+/// no subtitles.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One column per ALTER TABLE so SQLite can apply them
+        for (name, column) in [
+            (
+                "audio_languages",
+                ColumnDef::new(RelayProfiles::AudioLanguages)
+                    .text()
+                    .null()
+                    .to_owned(),
+            ),
+            (
+                "audio_track_index",
+                ColumnDef::new(RelayProfiles::AudioTrackIndex)
+                    .integer()
+                    .null()
+                    .to_owned(),
+            ),
+            (
+                "subtitle_languages",
+                ColumnDef::new(RelayProfiles::SubtitleLanguages)
+                    .text()
+                    .null()
+                    .to_owned(),
+            ),
+            (
+                "subtitle_track_index",
+                ColumnDef::new(RelayProfiles::SubtitleTrackIndex)
+                    .integer()
+                    .null()
+                    .to_owned(),
+            ),
+        ] {
+            if manager.has_column("relay_profiles", name).await? {
+                continue;
+            }
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(RelayProfiles::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            RelayProfiles::AudioLanguages,
+            RelayProfiles::AudioTrackIndex,
+            RelayProfiles::SubtitleLanguages,
+            RelayProfiles::SubtitleTrackIndex,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(RelayProfiles::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum RelayProfiles {
+    Table,
+    AudioLanguages,
+    AudioTrackIndex,
+    SubtitleLanguages,
+    SubtitleTrackIndex,
+}
@@ -0,0 +1,47 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the per-link `max_streams` column to proxy share links.
+///
+/// Caps how many streams may be open through the link at once; links without a value use
+/// the configured default (`stream_sessions.max_streams_per_share_link`).
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager
+            .has_column("proxy_share_links", "max_streams")
+            .await?
+        {
+            return Ok(());
+        }
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProxyShareLinks::Table)
+                    .add_column(ColumnDef::new(ProxyShareLinks::MaxStreams).integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProxyShareLinks::Table)
+                    .drop_column(ProxyShareLinks::MaxStreams)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProxyShareLinks {
+    Table,
+    MaxStreams,
+}
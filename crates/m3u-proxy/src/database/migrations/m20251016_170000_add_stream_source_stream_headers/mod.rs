@@ -0,0 +1,81 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds per-source header overrides for upstream streaming connections.
+///
+/// The `stream_source_stream_headers` table holds, per source, the User-Agent, Referer and
+/// extra headers sent when the proxy or relay connects to the source's streams. Sources
+/// without a row keep the default upstream User-Agent.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StreamSourceStreamHeaders::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, StreamSourceStreamHeaders::SourceId).primary_key())
+                    .col(
+                        ColumnDef::new(StreamSourceStreamHeaders::UserAgent)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(StreamSourceStreamHeaders::Referer)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(StreamSourceStreamHeaders::Headers)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(timestamp_column(manager, StreamSourceStreamHeaders::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stream_source_stream_headers_source_id")
+                            .from(
+                                StreamSourceStreamHeaders::Table,
+                                StreamSourceStreamHeaders::SourceId,
+                            )
+                            .to(StreamSources::Table, StreamSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(StreamSourceStreamHeaders::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamSourceStreamHeaders {
+    Table,
+    SourceId,
+    UserAgent,
+    Referer,
+    Headers,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamSources {
+    Table,
+    Id,
+}
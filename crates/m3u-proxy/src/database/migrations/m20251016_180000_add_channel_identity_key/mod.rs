@@ -0,0 +1,72 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds a configurable channel identity key per stream source.
+///
+/// The `stream_source_channel_identity` table holds, per source, which channel fields the
+/// channel UUID is derived from on ingestion (e.g. `tvg_id` for providers rotating stream
+/// URLs). Sources without a row keep deriving it from the stream URL and name.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StreamSourceChannelIdentity::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, StreamSourceChannelIdentity::SourceId).primary_key())
+                    .col(
+                        ColumnDef::new(StreamSourceChannelIdentity::IdentityKey)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        timestamp_column(manager, StreamSourceChannelIdentity::UpdatedAt)
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stream_source_channel_identity_source_id")
+                            .from(
+                                StreamSourceChannelIdentity::Table,
+                                StreamSourceChannelIdentity::SourceId,
+                            )
+                            .to(StreamSources::Table, StreamSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(StreamSourceChannelIdentity::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamSourceChannelIdentity {
+    Table,
+    SourceId,
+    IdentityKey,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamSources {
+    Table,
+    Id,
+}
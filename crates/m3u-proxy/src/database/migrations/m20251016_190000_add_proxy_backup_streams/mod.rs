@@ -0,0 +1,52 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the per-proxy `backup_streams` column.
+///
+/// Selects how streams of the same channel from lower-priority sources are emitted ("off",
+/// "attribute" or "group"). Existing proxies keep one entry per channel.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager
+            .has_column("stream_proxies", "backup_streams")
+            .await?
+        {
+            return Ok(());
+        }
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .add_column(
+                        ColumnDef::new(StreamProxies::BackupStreams)
+                            .string()
+                            .not_null()
+                            .default("off"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .drop_column(StreamProxies::BackupStreams)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    BackupStreams,
+}
@@ -0,0 +1,87 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `ingestion_runs` table recording the outcome of every source ingestion.
+///
+/// Rows are keyed by source id without a foreign key, as both stream and EPG sources
+/// record runs here; history of a deleted source is removed by the application.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IngestionRuns::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, IngestionRuns::Id).primary_key())
+                    .col(uuid_column(manager, IngestionRuns::SourceId))
+                    .col(
+                        ColumnDef::new(IngestionRuns::SourceKind)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(IngestionRuns::Status).string().not_null())
+                    .col(timestamp_column(manager, IngestionRuns::StartedAt).not_null())
+                    .col(timestamp_column(manager, IngestionRuns::FinishedAt).not_null())
+                    .col(
+                        ColumnDef::new(IngestionRuns::DurationMs)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(IngestionRuns::BytesDownloaded).big_integer())
+                    .col(ColumnDef::new(IngestionRuns::RecordsAdded).integer())
+                    .col(ColumnDef::new(IngestionRuns::RecordsUpdated).integer())
+                    .col(ColumnDef::new(IngestionRuns::RecordsRemoved).integer())
+                    .col(ColumnDef::new(IngestionRuns::Error).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ingestion_runs_source_started")
+                    .table(IngestionRuns::Table)
+                    .col(IngestionRuns::SourceId)
+                    .col(IngestionRuns::StartedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(IngestionRuns::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IngestionRuns {
+    Table,
+    Id,
+    SourceId,
+    SourceKind,
+    Status,
+    StartedAt,
+    FinishedAt,
+    DurationMs,
+    BytesDownloaded,
+    RecordsAdded,
+    RecordsUpdated,
+    RecordsRemoved,
+    Error,
+}
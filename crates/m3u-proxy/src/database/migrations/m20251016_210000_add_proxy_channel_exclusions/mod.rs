@@ -0,0 +1,89 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `proxy_channel_exclusions` table backing quick per-proxy channel exclusions.
+///
+/// Each row excludes either a single channel (by id) or a whole group (by group title) from
+/// a proxy's generated output; rows are removed with their proxy (cascade on delete).
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut channel_id = ColumnDef::new(ProxyChannelExclusions::ChannelId);
+        match manager.get_database_backend() {
+            sea_orm::DatabaseBackend::Postgres => channel_id.uuid(),
+            _ => channel_id.string(),
+        };
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProxyChannelExclusions::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ProxyChannelExclusions::Id).primary_key())
+                    .col(uuid_column(manager, ProxyChannelExclusions::ProxyId))
+                    .col(channel_id)
+                    .col(ColumnDef::new(ProxyChannelExclusions::GroupTitle).string())
+                    .col(ColumnDef::new(ProxyChannelExclusions::ChannelName).string())
+                    .col(timestamp_column(manager, ProxyChannelExclusions::CreatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_proxy_channel_exclusions_proxy_id")
+                            .from(
+                                ProxyChannelExclusions::Table,
+                                ProxyChannelExclusions::ProxyId,
+                            )
+                            .to(StreamProxies::Table, StreamProxies::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_proxy_channel_exclusions_proxy_id")
+                    .table(ProxyChannelExclusions::Table)
+                    .col(ProxyChannelExclusions::ProxyId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ProxyChannelExclusions::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProxyChannelExclusions {
+    Table,
+    Id,
+    ProxyId,
+    ChannelId,
+    GroupTitle,
+    ChannelName,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    Id,
+}
@@ -0,0 +1,159 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds filter groups and proxy templates.
+///
+/// `filter_groups` and `filter_group_members` hold named, ordered sets of filters; members
+/// are removed with their group or filter (cascade on delete). `proxy_templates` stores
+/// saved proxy settings as a JSON document, so referenced sources and filters are checked
+/// when a proxy is created from the template rather than by foreign keys.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FilterGroups::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, FilterGroups::Id).primary_key())
+                    .col(
+                        ColumnDef::new(FilterGroups::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(FilterGroups::Description).text())
+                    .col(timestamp_column(manager, FilterGroups::CreatedAt).not_null())
+                    .col(timestamp_column(manager, FilterGroups::UpdatedAt).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(FilterGroupMembers::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, FilterGroupMembers::GroupId))
+                    .col(uuid_column(manager, FilterGroupMembers::FilterId))
+                    .col(
+                        ColumnDef::new(FilterGroupMembers::PriorityOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(FilterGroupMembers::GroupId)
+                            .col(FilterGroupMembers::FilterId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_filter_group_members_group_id")
+                            .from(FilterGroupMembers::Table, FilterGroupMembers::GroupId)
+                            .to(FilterGroups::Table, FilterGroups::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_filter_group_members_filter_id")
+                            .from(FilterGroupMembers::Table, FilterGroupMembers::FilterId)
+                            .to(Filters::Table, Filters::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProxyTemplates::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ProxyTemplates::Id).primary_key())
+                    .col(
+                        ColumnDef::new(ProxyTemplates::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ProxyTemplates::Description).text())
+                    .col(ColumnDef::new(ProxyTemplates::Settings).text().not_null())
+                    .col(timestamp_column(manager, ProxyTemplates::CreatedAt).not_null())
+                    .col(timestamp_column(manager, ProxyTemplates::UpdatedAt).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ProxyTemplates::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(FilterGroupMembers::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(FilterGroups::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FilterGroups {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum FilterGroupMembers {
+    Table,
+    GroupId,
+    FilterId,
+    PriorityOrder,
+}
+
+#[derive(DeriveIden)]
+enum ProxyTemplates {
+    Table,
+    Id,
+    Name,
+    Description,
+    Settings,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Filters {
+    Table,
+    Id,
+}
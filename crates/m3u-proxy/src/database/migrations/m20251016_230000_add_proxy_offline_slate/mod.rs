@@ -0,0 +1,52 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the per-proxy `offline_slate` column.
+///
+/// Selects what proxy-mode streams serve while their upstream is down ("off", "generated" or
+/// "media"). Existing proxies keep failing the stream.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager
+            .has_column("stream_proxies", "offline_slate")
+            .await?
+        {
+            return Ok(());
+        }
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .add_column(
+                        ColumnDef::new(StreamProxies::OfflineSlate)
+                            .string()
+                            .not_null()
+                            .default("off"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .drop_column(StreamProxies::OfflineSlate)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    OfflineSlate,
+}
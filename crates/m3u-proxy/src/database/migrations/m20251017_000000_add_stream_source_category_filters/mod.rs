@@ -0,0 +1,90 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds per-source category ingest filters for Xtream sources.
+///
+/// The `stream_source_category_filters` table holds, per source, the provider category ids
+/// and name patterns to include or exclude while ingesting. Rule lists are stored as JSON
+/// arrays. Sources without a row ingest every category.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StreamSourceCategoryFilters::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, StreamSourceCategoryFilters::SourceId).primary_key())
+                    .col(
+                        ColumnDef::new(StreamSourceCategoryFilters::IncludeCategoryIds)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StreamSourceCategoryFilters::IncludePatterns)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StreamSourceCategoryFilters::ExcludeCategoryIds)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StreamSourceCategoryFilters::ExcludePatterns)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        timestamp_column(manager, StreamSourceCategoryFilters::UpdatedAt)
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stream_source_category_filters_source_id")
+                            .from(
+                                StreamSourceCategoryFilters::Table,
+                                StreamSourceCategoryFilters::SourceId,
+                            )
+                            .to(StreamSources::Table, StreamSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(StreamSourceCategoryFilters::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamSourceCategoryFilters {
+    Table,
+    SourceId,
+    IncludeCategoryIds,
+    IncludePatterns,
+    ExcludeCategoryIds,
+    ExcludePatterns,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamSources {
+    Table,
+    Id,
+}
@@ -0,0 +1,69 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds optional HTTP Basic Auth credentials for proxy playlist and guide endpoints.
+///
+/// The `proxy_basic_auth` table holds, per proxy, a username and a salted PBKDF2 hash of
+/// the password. Proxies without a row serve their playlist and guide without credentials.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProxyBasicAuth::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ProxyBasicAuth::ProxyId).primary_key())
+                    .col(ColumnDef::new(ProxyBasicAuth::Username).text().not_null())
+                    .col(
+                        ColumnDef::new(ProxyBasicAuth::PasswordHash)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(timestamp_column(manager, ProxyBasicAuth::CreatedAt).not_null())
+                    .col(timestamp_column(manager, ProxyBasicAuth::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_proxy_basic_auth_proxy_id")
+                            .from(ProxyBasicAuth::Table, ProxyBasicAuth::ProxyId)
+                            .to(StreamProxies::Table, StreamProxies::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ProxyBasicAuth::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProxyBasicAuth {
+    Table,
+    ProxyId,
+    Username,
+    PasswordHash,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    Id,
+}
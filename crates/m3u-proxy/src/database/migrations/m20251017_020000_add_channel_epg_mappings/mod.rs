@@ -0,0 +1,89 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `channel_epg_mappings` table backing manual channel-to-EPG mappings.
+///
+/// Each row points one channel of a proxy at an EPG channel id, overriding the channel's
+/// tvg-id when the proxy's guide is generated. A channel has at most one mapping per proxy;
+/// rows are removed with their proxy (cascade on delete).
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChannelEpgMappings::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ChannelEpgMappings::Id).primary_key())
+                    .col(uuid_column(manager, ChannelEpgMappings::ProxyId))
+                    .col(uuid_column(manager, ChannelEpgMappings::ChannelId))
+                    .col(
+                        ColumnDef::new(ChannelEpgMappings::EpgChannelId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChannelEpgMappings::ChannelName).string())
+                    .col(timestamp_column(manager, ChannelEpgMappings::CreatedAt).not_null())
+                    .col(timestamp_column(manager, ChannelEpgMappings::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_channel_epg_mappings_proxy_id")
+                            .from(ChannelEpgMappings::Table, ChannelEpgMappings::ProxyId)
+                            .to(StreamProxies::Table, StreamProxies::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_channel_epg_mappings_proxy_channel_unique")
+                    .table(ChannelEpgMappings::Table)
+                    .col(ChannelEpgMappings::ProxyId)
+                    .col(ChannelEpgMappings::ChannelId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ChannelEpgMappings::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChannelEpgMappings {
+    Table,
+    Id,
+    ProxyId,
+    ChannelId,
+    EpgChannelId,
+    ChannelName,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    Id,
+}
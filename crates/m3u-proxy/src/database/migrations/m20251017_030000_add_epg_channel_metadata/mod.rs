@@ -0,0 +1,121 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `epg_channel_metadata` table and the per-proxy `epg_languages` column.
+///
+/// XMLTV `<channel>` elements with language-tagged display names or icons are kept per EPG
+/// source (display names and icons as JSON lists of `{value, lang}`) and replaced on each
+/// ingestion; rows are removed with their source (cascade on delete). `epg_languages` is a
+/// proxy's comma-separated language preference (e.g. "de,en"); existing proxies keep their
+/// playlist's channel names and logos.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EpgChannelMetadata::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, EpgChannelMetadata::Id).primary_key())
+                    .col(uuid_column(manager, EpgChannelMetadata::SourceId))
+                    .col(
+                        ColumnDef::new(EpgChannelMetadata::ChannelId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EpgChannelMetadata::DisplayNames)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EpgChannelMetadata::Icons).text().not_null())
+                    .col(timestamp_column(manager, EpgChannelMetadata::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_epg_channel_metadata_source_id")
+                            .from(EpgChannelMetadata::Table, EpgChannelMetadata::SourceId)
+                            .to(EpgSources::Table, EpgSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_epg_channel_metadata_source_channel_unique")
+                    .table(EpgChannelMetadata::Table)
+                    .col(EpgChannelMetadata::SourceId)
+                    .col(EpgChannelMetadata::ChannelId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        if !manager
+            .has_column("stream_proxies", "epg_languages")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(StreamProxies::Table)
+                        .add_column(ColumnDef::new(StreamProxies::EpgLanguages).text().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .drop_column(StreamProxies::EpgLanguages)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(EpgChannelMetadata::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EpgChannelMetadata {
+    Table,
+    Id,
+    SourceId,
+    ChannelId,
+    DisplayNames,
+    Icons,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum EpgSources {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    EpgLanguages,
+}
@@ -0,0 +1,61 @@
+use super::columns::timestamp_column;
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds a nullable `deleted_at` column to sources, proxies, filters and data mapping rules.
+///
+/// Deleting one of these now only sets `deleted_at`, moving the row to the trash; it is
+/// hidden from every listing and from generation until it is restored, and the scheduled
+/// purge job removes it (with its cascades) once the trash retention period has passed.
+/// Existing rows stay live.
+pub struct Migration;
+
+folder_migration_name!();
+
+/// Tables that support soft-delete
+const TABLES: [&str; 5] = [
+    "stream_sources",
+    "epg_sources",
+    "stream_proxies",
+    "filters",
+    "data_mapping_rules",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            if manager.has_column(table, "deleted_at").await? {
+                continue;
+            }
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(timestamp_column(manager, SoftDelete::DeletedAt).null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_column(SoftDelete::DeletedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SoftDelete {
+    DeletedAt,
+}
@@ -0,0 +1,82 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `stream_source_balance_groups` table.
+///
+/// Stream sources sharing a `group_name` are lines of the same provider (e.g. the same
+/// subscription bought several times with different credentials). Streaming a channel of
+/// one line picks the equivalent channel on the line with the fewest active connections
+/// that is under its `max_concurrent_streams`, failing over to the next line when the
+/// upstream cannot be reached. Sources without a row are not balanced.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StreamSourceBalanceGroups::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, StreamSourceBalanceGroups::SourceId).primary_key())
+                    .col(
+                        ColumnDef::new(StreamSourceBalanceGroups::GroupName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(timestamp_column(manager, StreamSourceBalanceGroups::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stream_source_balance_groups_source_id")
+                            .from(
+                                StreamSourceBalanceGroups::Table,
+                                StreamSourceBalanceGroups::SourceId,
+                            )
+                            .to(StreamSources::Table, StreamSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_stream_source_balance_groups_group_name")
+                    .table(StreamSourceBalanceGroups::Table)
+                    .col(StreamSourceBalanceGroups::GroupName)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(StreamSourceBalanceGroups::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamSourceBalanceGroups {
+    Table,
+    SourceId,
+    GroupName,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamSources {
+    Table,
+    Id,
+}
@@ -0,0 +1,51 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the per-proxy `regeneration_debounce_seconds` column.
+///
+/// Source updates arriving within a proxy's debounce window are batched into a single
+/// regeneration. NULL (all existing proxies) uses the global `regeneration.debounce`.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager
+            .has_column("stream_proxies", "regeneration_debounce_seconds")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(StreamProxies::Table)
+                        .add_column(
+                            ColumnDef::new(StreamProxies::RegenerationDebounceSeconds)
+                                .integer()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .drop_column(StreamProxies::RegenerationDebounceSeconds)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    RegenerationDebounceSeconds,
+}
@@ -0,0 +1,67 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds subtitle passthrough control to relay profiles.
+///
+/// `subtitle_mode` is one of `selected`, `preserve`, `strip` or `convert`; `subtitle_codec`
+/// is the conversion target. Existing profiles keep relaying only their selected track.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One column per ALTER TABLE so SQLite can apply them
+        for (name, column) in [
+            (
+                "subtitle_mode",
+                ColumnDef::new(RelayProfiles::SubtitleMode)
+                    .text()
+                    .null()
+                    .to_owned(),
+            ),
+            (
+                "subtitle_codec",
+                ColumnDef::new(RelayProfiles::SubtitleCodec)
+                    .text()
+                    .null()
+                    .to_owned(),
+            ),
+        ] {
+            if manager.has_column("relay_profiles", name).await? {
+                continue;
+            }
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(RelayProfiles::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [RelayProfiles::SubtitleMode, RelayProfiles::SubtitleCodec] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(RelayProfiles::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum RelayProfiles {
+    Table,
+    SubtitleMode,
+    SubtitleCodec,
+}
@@ -0,0 +1,67 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `channel_count_snapshots` table holding the latest channel count of each
+/// stream source and proxy, along with the rate-of-change alert it raised, if any.
+///
+/// Rows are keyed by source or proxy id without a foreign key, as both kinds record
+/// counts here.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChannelCountSnapshots::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ChannelCountSnapshots::SubjectId).primary_key())
+                    .col(
+                        ColumnDef::new(ChannelCountSnapshots::Subject)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChannelCountSnapshots::ChannelCount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(timestamp_column(manager, ChannelCountSnapshots::RecordedAt).not_null())
+                    .col(ColumnDef::new(ChannelCountSnapshots::AlertPreviousCount).big_integer())
+                    .col(ColumnDef::new(ChannelCountSnapshots::AlertChangePercent).double())
+                    .col(timestamp_column(
+                        manager,
+                        ChannelCountSnapshots::AlertTriggeredAt,
+                    ))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ChannelCountSnapshots::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChannelCountSnapshots {
+    Table,
+    SubjectId,
+    Subject,
+    ChannelCount,
+    RecordedAt,
+    AlertPreviousCount,
+    AlertChangePercent,
+    AlertTriggeredAt,
+}
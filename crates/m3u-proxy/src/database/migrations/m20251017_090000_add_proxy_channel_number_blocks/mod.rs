@@ -0,0 +1,52 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the per-proxy `channel_number_blocks` column.
+///
+/// A JSON list of `{group, start}` blocks the numbering stage fills per channel group
+/// ahead of the proxy's `starting_channel_number`. NULL (all existing proxies) keeps
+/// sequential numbering.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager
+            .has_column("stream_proxies", "channel_number_blocks")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(StreamProxies::Table)
+                        .add_column(
+                            ColumnDef::new(StreamProxies::ChannelNumberBlocks)
+                                .text()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .drop_column(StreamProxies::ChannelNumberBlocks)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    ChannelNumberBlocks,
+}
@@ -0,0 +1,44 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the per-proxy `epg_timezone` column.
+///
+/// An IANA timezone name the generated XMLTV's programme times are written in. NULL
+/// (all existing proxies) keeps UTC times.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_column("stream_proxies", "epg_timezone").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(StreamProxies::Table)
+                        .add_column(ColumnDef::new(StreamProxies::EpgTimezone).string().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StreamProxies::Table)
+                    .drop_column(StreamProxies::EpgTimezone)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    EpgTimezone,
+}
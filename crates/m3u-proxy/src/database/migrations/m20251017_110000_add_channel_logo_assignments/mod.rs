@@ -0,0 +1,85 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `channel_logo_assignments` table backing channel logos from uploaded assets.
+///
+/// Each row points one channel at an uploaded logo asset, replacing the channel's tvg-logo
+/// when proxies are generated. A channel has at most one assignment; rows are removed with
+/// their logo asset (cascade on delete).
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChannelLogoAssignments::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ChannelLogoAssignments::Id).primary_key())
+                    .col(uuid_column(manager, ChannelLogoAssignments::ChannelId))
+                    .col(uuid_column(manager, ChannelLogoAssignments::LogoAssetId))
+                    .col(ColumnDef::new(ChannelLogoAssignments::ChannelName).string())
+                    .col(timestamp_column(manager, ChannelLogoAssignments::CreatedAt).not_null())
+                    .col(timestamp_column(manager, ChannelLogoAssignments::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_channel_logo_assignments_logo_asset_id")
+                            .from(
+                                ChannelLogoAssignments::Table,
+                                ChannelLogoAssignments::LogoAssetId,
+                            )
+                            .to(LogoAssets::Table, LogoAssets::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_channel_logo_assignments_channel_unique")
+                    .table(ChannelLogoAssignments::Table)
+                    .col(ChannelLogoAssignments::ChannelId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ChannelLogoAssignments::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChannelLogoAssignments {
+    Table,
+    Id,
+    ChannelId,
+    LogoAssetId,
+    ChannelName,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum LogoAssets {
+    Table,
+    Id,
+}
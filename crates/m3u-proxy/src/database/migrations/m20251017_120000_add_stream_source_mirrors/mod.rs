@@ -0,0 +1,71 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds mirror URLs to stream sources.
+///
+/// The `stream_source_mirrors` table holds, per source, the alternative URLs serving the same
+/// playlist or API, stored as a JSON array in the order they are tried when the source URL
+/// fails, and the URL that served the last successful refresh. Sources without a row are
+/// only fetched from their own URL.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StreamSourceMirrors::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, StreamSourceMirrors::SourceId).primary_key())
+                    .col(
+                        ColumnDef::new(StreamSourceMirrors::MirrorUrls)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StreamSourceMirrors::LastServedUrl).text())
+                    .col(timestamp_column(manager, StreamSourceMirrors::LastServedAt))
+                    .col(timestamp_column(manager, StreamSourceMirrors::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stream_source_mirrors_source_id")
+                            .from(StreamSourceMirrors::Table, StreamSourceMirrors::SourceId)
+                            .to(StreamSources::Table, StreamSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(StreamSourceMirrors::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamSourceMirrors {
+    Table,
+    SourceId,
+    MirrorUrls,
+    LastServedUrl,
+    LastServedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamSources {
+    Table,
+    Id,
+}
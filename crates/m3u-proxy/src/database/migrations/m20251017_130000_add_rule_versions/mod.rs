@@ -0,0 +1,83 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `rule_versions` table holding the change history of filters and data mapping rules.
+///
+/// Each saved state of a rule is a numbered version recording its name and expression, the
+/// expression it replaced, the full definition it can be reverted to, who saved it and when.
+/// Versions are keyed by `rule_type` ("filter" or "data_mapping_rule") and rule id, so rows
+/// outlive their rule and are not tied to either table by a foreign key.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RuleVersions::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, RuleVersions::Id).primary_key())
+                    .col(ColumnDef::new(RuleVersions::RuleType).string().not_null())
+                    .col(uuid_column(manager, RuleVersions::RuleId))
+                    .col(ColumnDef::new(RuleVersions::Version).integer().not_null())
+                    .col(ColumnDef::new(RuleVersions::Name).text().not_null())
+                    .col(ColumnDef::new(RuleVersions::Expression).text())
+                    .col(ColumnDef::new(RuleVersions::PreviousExpression).text())
+                    .col(ColumnDef::new(RuleVersions::Definition).text().not_null())
+                    .col(ColumnDef::new(RuleVersions::Change).string().not_null())
+                    .col(ColumnDef::new(RuleVersions::RestoredVersion).integer())
+                    .col(ColumnDef::new(RuleVersions::Author).string())
+                    .col(timestamp_column(manager, RuleVersions::CreatedAt).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_rule_versions_rule_version_unique")
+                    .table(RuleVersions::Table)
+                    .col(RuleVersions::RuleType)
+                    .col(RuleVersions::RuleId)
+                    .col(RuleVersions::Version)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(RuleVersions::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RuleVersions {
+    Table,
+    Id,
+    RuleType,
+    RuleId,
+    Version,
+    Name,
+    Expression,
+    PreviousExpression,
+    Definition,
+    Change,
+    RestoredVersion,
+    Author,
+    CreatedAt,
+}
@@ -0,0 +1,45 @@
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `extra_attributes` column to channels.
+///
+/// A JSON object of the EXTINF attributes the channel columns do not cover (catchup,
+/// timeshift, tvg-rec, custom x- attributes), kept so generated playlists can pass them
+/// through. NULL for channels without any.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_column("channels", "extra_attributes").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Channels::Table)
+                        .add_column(ColumnDef::new(Channels::ExtraAttributes).text().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Channels::Table)
+                    .drop_column(Channels::ExtraAttributes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Channels {
+    Table,
+    ExtraAttributes,
+}
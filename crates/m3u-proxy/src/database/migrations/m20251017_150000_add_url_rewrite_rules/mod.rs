@@ -0,0 +1,118 @@
+use super::columns::{nullable_uuid_column, timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `url_rewrite_rules` table of regex rewrites applied to upstream stream URLs.
+///
+/// Each rule replaces the first match of `pattern` in a stream URL with `replacement`
+/// (capture groups allowed). A rule can be scoped to one stream source and/or one proxy;
+/// rules without a scope apply everywhere. Rules are applied in ascending `priority` and are
+/// removed with the source or proxy they are scoped to.
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UrlRewriteRules::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, UrlRewriteRules::Id).primary_key())
+                    .col(ColumnDef::new(UrlRewriteRules::Name).string().not_null())
+                    .col(ColumnDef::new(UrlRewriteRules::Pattern).text().not_null())
+                    .col(
+                        ColumnDef::new(UrlRewriteRules::Replacement)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(nullable_uuid_column(manager, UrlRewriteRules::SourceId))
+                    .col(nullable_uuid_column(manager, UrlRewriteRules::ProxyId))
+                    .col(
+                        ColumnDef::new(UrlRewriteRules::Priority)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(UrlRewriteRules::IsActive)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(timestamp_column(manager, UrlRewriteRules::CreatedAt).not_null())
+                    .col(timestamp_column(manager, UrlRewriteRules::UpdatedAt).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_url_rewrite_rules_source_id")
+                            .from(UrlRewriteRules::Table, UrlRewriteRules::SourceId)
+                            .to(StreamSources::Table, StreamSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_url_rewrite_rules_proxy_id")
+                            .from(UrlRewriteRules::Table, UrlRewriteRules::ProxyId)
+                            .to(StreamProxies::Table, StreamProxies::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_url_rewrite_rules_proxy_id")
+                    .table(UrlRewriteRules::Table)
+                    .col(UrlRewriteRules::ProxyId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(UrlRewriteRules::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UrlRewriteRules {
+    Table,
+    Id,
+    Name,
+    Pattern,
+    Replacement,
+    SourceId,
+    ProxyId,
+    Priority,
+    IsActive,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamSources {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    Id,
+}
@@ -0,0 +1,82 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `proxy_settings` table of optional per-proxy feature settings.
+///
+/// Each row stores one setting of a proxy as JSON under a fixed key (e.g.
+/// `relay_keepalive`), so features that only some proxies opt into do not need their own
+/// `stream_proxies` columns. Rows are removed with their proxy (cascade on delete).
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProxySettings::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, ProxySettings::ProxyId))
+                    .col(ColumnDef::new(ProxySettings::Key).string().not_null())
+                    .col(ColumnDef::new(ProxySettings::Value).text().not_null())
+                    .col(timestamp_column(manager, ProxySettings::UpdatedAt).not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(ProxySettings::ProxyId)
+                            .col(ProxySettings::Key),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_proxy_settings_proxy_id")
+                            .from(ProxySettings::Table, ProxySettings::ProxyId)
+                            .to(StreamProxies::Table, StreamProxies::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_proxy_settings_key")
+                    .table(ProxySettings::Table)
+                    .col(ProxySettings::Key)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ProxySettings::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProxySettings {
+    Table,
+    ProxyId,
+    Key,
+    Value,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    Id,
+}
@@ -0,0 +1,76 @@
+use super::columns::{timestamp_column, uuid_column};
+use crate::folder_migration_name;
+use sea_orm_migration::prelude::*;
+
+/// Adds the `relay_channel_views` table of client connections per proxy channel.
+///
+/// Relays count the clients they serve and add them here periodically, so the
+/// "most watched" relay keep-alive selection survives restarts and is shared by all nodes.
+/// Rows are removed with their proxy (cascade on delete).
+pub struct Migration;
+
+folder_migration_name!();
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RelayChannelViews::Table)
+                    .if_not_exists()
+                    .col(uuid_column(manager, RelayChannelViews::ProxyId))
+                    .col(uuid_column(manager, RelayChannelViews::ChannelId))
+                    .col(
+                        ColumnDef::new(RelayChannelViews::ViewCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(timestamp_column(manager, RelayChannelViews::LastViewedAt).not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(RelayChannelViews::ProxyId)
+                            .col(RelayChannelViews::ChannelId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_relay_channel_views_proxy_id")
+                            .from(RelayChannelViews::Table, RelayChannelViews::ProxyId)
+                            .to(StreamProxies::Table, StreamProxies::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(RelayChannelViews::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RelayChannelViews {
+    Table,
+    ProxyId,
+    ChannelId,
+    ViewCount,
+    LastViewedAt,
+}
+
+#[derive(DeriveIden)]
+enum StreamProxies {
+    Table,
+    Id,
+}
@@ -44,10 +44,45 @@ macro_rules! folder_migration_name {
     };
 }
 
+mod columns;
+
 pub mod m20250829_100000_initial_schema;
 pub mod m20250829_100001_insert_defaults;
 pub mod m20250920_150000_pg_trgm_indexes;
 pub mod m20250921_120000_add_codec_metadata;
+pub mod m20251016_090000_add_proxy_share_links;
+pub mod m20251016_100000_add_virtual_channels;
+pub mod m20251016_110000_add_proxy_stream_signing;
+pub mod m20251016_120000_add_channel_retention;
+pub mod m20251016_130000_add_proxy_output_profile;
+pub mod m20251016_140000_add_data_mapping_rule_scopes;
+pub mod m20251016_150000_add_relay_track_selection;
+pub mod m20251016_160000_add_share_link_max_streams;
+pub mod m20251016_170000_add_stream_source_stream_headers;
+pub mod m20251016_180000_add_channel_identity_key;
+pub mod m20251016_190000_add_proxy_backup_streams;
+pub mod m20251016_200000_add_ingestion_runs;
+pub mod m20251016_210000_add_proxy_channel_exclusions;
+pub mod m20251016_220000_add_filter_groups_and_proxy_templates;
+pub mod m20251016_230000_add_proxy_offline_slate;
+pub mod m20251017_000000_add_stream_source_category_filters;
+pub mod m20251017_010000_add_proxy_basic_auth;
+pub mod m20251017_020000_add_channel_epg_mappings;
+pub mod m20251017_030000_add_epg_channel_metadata;
+pub mod m20251017_040000_add_soft_delete;
+pub mod m20251017_050000_add_stream_source_balance_groups;
+pub mod m20251017_060000_add_proxy_regeneration_debounce;
+pub mod m20251017_070000_add_relay_subtitle_mode;
+pub mod m20251017_080000_add_channel_count_snapshots;
+pub mod m20251017_090000_add_proxy_channel_number_blocks;
+pub mod m20251017_100000_add_proxy_epg_timezone;
+pub mod m20251017_110000_add_channel_logo_assignments;
+pub mod m20251017_120000_add_stream_source_mirrors;
+pub mod m20251017_130000_add_rule_versions;
+pub mod m20251017_140000_add_channel_extra_attributes;
+pub mod m20251017_150000_add_url_rewrite_rules;
+pub mod m20251017_160000_add_proxy_settings;
+pub mod m20251017_170000_add_relay_channel_views;
 
 // (Consolidated into m20250920_150000_pg_trgm_indexes migration)
 
@@ -61,6 +96,39 @@ impl MigratorTrait for Migrator {
             Box::new(m20250829_100001_insert_defaults::Migration),
             Box::new(m20250920_150000_pg_trgm_indexes::Migration),
             Box::new(m20250921_120000_add_codec_metadata::Migration),
+            Box::new(m20251016_090000_add_proxy_share_links::Migration),
+            Box::new(m20251016_100000_add_virtual_channels::Migration),
+            Box::new(m20251016_110000_add_proxy_stream_signing::Migration),
+            Box::new(m20251016_120000_add_channel_retention::Migration),
+            Box::new(m20251016_130000_add_proxy_output_profile::Migration),
+            Box::new(m20251016_140000_add_data_mapping_rule_scopes::Migration),
+            Box::new(m20251016_150000_add_relay_track_selection::Migration),
+            Box::new(m20251016_160000_add_share_link_max_streams::Migration),
+            Box::new(m20251016_170000_add_stream_source_stream_headers::Migration),
+            Box::new(m20251016_180000_add_channel_identity_key::Migration),
+            Box::new(m20251016_190000_add_proxy_backup_streams::Migration),
+            Box::new(m20251016_200000_add_ingestion_runs::Migration),
+            Box::new(m20251016_210000_add_proxy_channel_exclusions::Migration),
+            Box::new(m20251016_220000_add_filter_groups_and_proxy_templates::Migration),
+            Box::new(m20251016_230000_add_proxy_offline_slate::Migration),
+            Box::new(m20251017_000000_add_stream_source_category_filters::Migration),
+            Box::new(m20251017_010000_add_proxy_basic_auth::Migration),
+            Box::new(m20251017_020000_add_channel_epg_mappings::Migration),
+            Box::new(m20251017_030000_add_epg_channel_metadata::Migration),
+            Box::new(m20251017_040000_add_soft_delete::Migration),
+            Box::new(m20251017_050000_add_stream_source_balance_groups::Migration),
+            Box::new(m20251017_060000_add_proxy_regeneration_debounce::Migration),
+            Box::new(m20251017_070000_add_relay_subtitle_mode::Migration),
+            Box::new(m20251017_080000_add_channel_count_snapshots::Migration),
+            Box::new(m20251017_090000_add_proxy_channel_number_blocks::Migration),
+            Box::new(m20251017_100000_add_proxy_epg_timezone::Migration),
+            Box::new(m20251017_110000_add_channel_logo_assignments::Migration),
+            Box::new(m20251017_120000_add_stream_source_mirrors::Migration),
+            Box::new(m20251017_130000_add_rule_versions::Migration),
+            Box::new(m20251017_140000_add_channel_extra_attributes::Migration),
+            Box::new(m20251017_150000_add_url_rewrite_rules::Migration),
+            Box::new(m20251017_160000_add_proxy_settings::Migration),
+            Box::new(m20251017_170000_add_relay_channel_views::Migration),
             // Consolidated uniqueness normalization migrations removed (now handled inside m20250920_150000_pg_trgm_indexes)
         ]
     }
@@ -19,8 +19,11 @@ use crate::config::{DatabaseConfig, IngestionConfig};
 // use crate::entities::prelude::*;
 
 pub mod migrations;
+pub mod read_replica;
 pub mod repositories;
 
+pub use read_replica::ReadReplica;
+
 /// Database connection manager with multi-database support
 #[derive(Clone)]
 pub struct Database {
@@ -28,6 +31,8 @@ pub struct Database {
     pub connection: Arc<DatabaseConnection>,
     /// Read-only connection for API queries (for databases that support it)
     pub read_connection: Arc<DatabaseConnection>,
+    /// Optional PostgreSQL read replica, preferred for reads while healthy
+    pub replica: Option<Arc<ReadReplica>>,
     /// Database backend type for optimization selection
     pub backend: DatabaseBackend,
     /// Ingestion configuration
@@ -108,15 +113,36 @@ impl Database {
             }
         };
 
-        // For now, use the same connection for reads and writes
-        // In the future, we could create separate read replicas for PostgreSQL/MySQL
         let connection = Arc::new(connection);
 
         debug!("Database connection established successfully");
 
+        // Reads use the primary unless a healthy replica is configured
+        let replica = match (&config.postgresql.read_replica, database_type) {
+            (Some(replica_config), DatabaseType::PostgreSQL) => {
+                let replica = ReadReplica::connect(replica_config)
+                    .await
+                    .context("Failed to configure read replica")?;
+                replica.check().await;
+                info!(
+                    "Read replica configured (healthy: {})",
+                    replica.is_healthy()
+                );
+                Some(Arc::new(replica))
+            }
+            (Some(_), _) => {
+                tracing::warn!(
+                    "Read replica is only supported for PostgreSQL; ignoring database.postgresql.read_replica"
+                );
+                None
+            }
+            (None, _) => None,
+        };
+
         Ok(Self {
             connection: connection.clone(),
             read_connection: connection,
+            replica,
             backend,
             ingestion_config: ingestion_config.clone(),
             database_type,
@@ -376,14 +402,59 @@ WHERE t.relname = 'filters'
         Ok(())
     }
 
+    /// Migrations applied to the database and compiled into this binary
+    ///
+    /// A database without a migrations table yet has none applied.
+    pub async fn migration_status(&self) -> MigrationStatus {
+        use migrations::Migrator;
+        use sea_orm::Statement;
+        use sea_orm_migration::MigratorTrait;
+
+        let applied = match self
+            .connection
+            .query_all(Statement::from_string(
+                self.backend,
+                "SELECT version FROM seaql_migrations ORDER BY version".to_string(),
+            ))
+            .await
+        {
+            Ok(rows) => rows
+                .iter()
+                .filter_map(|row| row.try_get::<String>("", "version").ok())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        let available = Migrator::migrations()
+            .iter()
+            .map(|m| m.name().to_string())
+            .collect();
+
+        MigrationStatus { applied, available }
+    }
+
     /// Get the main database connection
     pub fn connection(&self) -> Arc<DatabaseConnection> {
         self.connection.clone()
     }
 
-    /// Get the read-only database connection
+    /// Get the connection for read-only queries
+    ///
+    /// This is the read replica while it is healthy, otherwise the primary.
     pub fn read_connection(&self) -> Arc<DatabaseConnection> {
-        self.read_connection.clone()
+        match &self.replica {
+            Some(replica) if replica.is_healthy() => replica.connection(),
+            _ => self.read_connection.clone(),
+        }
+    }
+
+    /// Spawn the replica health check loop (no-op without a replica)
+    pub fn spawn_replica_health_checks(
+        &self,
+        cancellation_token: tokio_util::sync::CancellationToken,
+    ) {
+        if let Some(replica) = self.replica.clone() {
+            tokio::spawn(replica.run_health_checks(cancellation_token));
+        }
     }
 
     /// Get the database backend type
@@ -428,6 +499,35 @@ WHERE t.relname = 'filters'
     }
 }
 
+/// Applied and available migrations of a database
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    /// Migrations recorded in `seaql_migrations`, in version order
+    pub applied: Vec<String>,
+    /// Migrations compiled into this binary, in run order
+    pub available: Vec<String>,
+}
+
+impl MigrationStatus {
+    /// Available migrations that have not been applied (run on next start)
+    pub fn missing(&self) -> Vec<String> {
+        self.available
+            .iter()
+            .filter(|name| !self.applied.contains(name))
+            .cloned()
+            .collect()
+    }
+
+    /// Applied migrations this binary does not know about
+    pub fn extra(&self) -> Vec<String> {
+        self.applied
+            .iter()
+            .filter(|name| !self.available.contains(name))
+            .cloned()
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum DatabaseFeature {
     Transactions,
@@ -0,0 +1,126 @@
+//! PostgreSQL read replica with health checking
+//!
+//! The replica connection is lazy, so an unreachable replica does not block startup. A
+//! background loop marks it healthy only while it answers queries and its replication lag
+//! stays within `max_lag`; `Database::read_connection` falls back to the primary otherwise.
+
+use anyhow::Result;
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database as SeaOrmDatabase, DatabaseBackend,
+    DatabaseConnection, Statement,
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::config::ReadReplicaConfig;
+
+/// Seconds since the last replayed transaction; 0 when the replica has replayed everything
+/// it received (an idle primary produces no new transactions to replay)
+const REPLICATION_LAG_SQL: &str = "SELECT CASE \
+    WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0::float8 \
+    ELSE EXTRACT(EPOCH FROM (now() - pg_last_xact_replay_timestamp()))::float8 \
+    END AS lag_seconds";
+
+/// Read-only replica connection and its current health
+pub struct ReadReplica {
+    connection: Arc<DatabaseConnection>,
+    healthy: AtomicBool,
+    health_check_interval: Duration,
+    max_lag: Duration,
+}
+
+impl ReadReplica {
+    /// Create the (lazy) replica connection; it stays unhealthy until the first check passes
+    pub async fn connect(config: &ReadReplicaConfig) -> Result<Self> {
+        let mut options = ConnectOptions::new(&config.url);
+        options
+            .max_connections(config.max_connections.unwrap_or(10))
+            .min_connections(0)
+            .connect_timeout(Duration::from_secs(5))
+            .acquire_timeout(Duration::from_secs(3))
+            .idle_timeout(Duration::from_secs(600))
+            .max_lifetime(Duration::from_secs(1800))
+            .connect_lazy(true)
+            .sqlx_logging_level(tracing::log::LevelFilter::Debug);
+
+        let connection = SeaOrmDatabase::connect(options).await?;
+        Ok(Self {
+            connection: Arc::new(connection),
+            healthy: AtomicBool::new(false),
+            health_check_interval: config.health_check_interval_duration(),
+            max_lag: config.max_lag_duration(),
+        })
+    }
+
+    pub fn connection(&self) -> Arc<DatabaseConnection> {
+        self.connection.clone()
+    }
+
+    /// Whether reads are currently routed to the replica
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// Query the replica's lag and update its health, returning the new state
+    pub async fn check(&self) -> bool {
+        let lag = self
+            .connection
+            .query_one(Statement::from_string(
+                DatabaseBackend::Postgres,
+                REPLICATION_LAG_SQL,
+            ))
+            .await
+            .map(|row| row.and_then(|r| r.try_get::<Option<f64>>("", "lag_seconds").ok()));
+
+        let healthy = match lag {
+            Ok(lag) => lag_within_limit(lag.flatten(), self.max_lag),
+            Err(e) => {
+                debug!("Read replica health check failed: {}", e);
+                false
+            }
+        };
+
+        let was_healthy = self.healthy.swap(healthy, Ordering::Relaxed);
+        if healthy && !was_healthy {
+            info!("Read replica healthy; routing reads to the replica");
+        } else if !healthy && was_healthy {
+            warn!("Read replica down or lagging; routing reads to the primary");
+        }
+        healthy
+    }
+
+    /// Check the replica periodically until cancelled
+    pub async fn run_health_checks(self: Arc<Self>, cancellation_token: CancellationToken) {
+        let mut interval = tokio::time::interval(self.health_check_interval);
+        loop {
+            tokio::select! {
+                _ = cancellation_token.cancelled() => break,
+                _ = interval.tick() => {
+                    self.check().await;
+                }
+            }
+        }
+    }
+}
+
+/// A replica without a replay timestamp has never replayed anything and is treated as lagging
+fn lag_within_limit(lag_seconds: Option<f64>, max_lag: Duration) -> bool {
+    lag_seconds.is_some_and(|lag| lag <= max_lag.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lag_within_limit() {
+        let max_lag = Duration::from_secs(30);
+        assert!(lag_within_limit(Some(0.0), max_lag));
+        assert!(lag_within_limit(Some(29.5), max_lag));
+        assert!(!lag_within_limit(Some(31.0), max_lag));
+        assert!(!lag_within_limit(None, max_lag));
+    }
+}
@@ -5,13 +5,19 @@
 use anyhow::Result;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
-    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, sea_query::Expr,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::entities::{channels, prelude::Channels};
+use super::traits::contains_any;
+use crate::entities::{
+    channels,
+    prelude::{Channels, StreamSourceChannelIdentity, StreamSourceChannelRetention},
+};
 use crate::models::Channel;
+use crate::models::ingestion_run::RecordChangeSummary;
 
 /// Request for channel creation
 #[derive(Debug, Clone)]
@@ -57,6 +63,8 @@ impl ChannelSeaOrmRepository {
             stream_url: Set(request.stream_url.clone()),
             created_at: Set(now),
             updated_at: Set(now),
+            missed_ingestions: Set(0),
+            extra_attributes: Set(None),
         };
 
         let model = active_model.insert(&*self.connection).await?;
@@ -70,6 +78,7 @@ impl ChannelSeaOrmRepository {
             channel_name: model.channel_name,
             tvg_logo: model.tvg_logo,
             tvg_shift: model.tvg_shift,
+            epg_shift: None,
             group_title: model.group_title,
             stream_url: model.stream_url,
             created_at: model.created_at,
@@ -79,6 +88,7 @@ impl ChannelSeaOrmRepository {
             resolution: None,
             probe_method: None,
             last_probed_at: None,
+            extra_attributes: Channel::parse_extra_attributes(model.extra_attributes.as_deref()),
         })
     }
 
@@ -105,6 +115,20 @@ impl ChannelSeaOrmRepository {
             .collect())
     }
 
+    /// Find channels by ID; unknown IDs are skipped
+    pub async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Channel>> {
+        let mut channels = Vec::with_capacity(ids.len());
+        // Chunked to stay under SQL parameter limits on large playlists
+        for chunk in ids.chunks(500) {
+            let models = Channels::find()
+                .filter(channels::Column::Id.is_in(chunk.iter().copied()))
+                .all(&*self.connection)
+                .await?;
+            channels.extend(models.into_iter().map(|m| self.model_to_domain(m)));
+        }
+        Ok(channels)
+    }
+
     /// Find all channels
     pub async fn find_all(&self) -> Result<Vec<Channel>> {
         let models = Channels::find()
@@ -143,6 +167,35 @@ impl ChannelSeaOrmRepository {
         }
     }
 
+    /// The channel of another source that carries the same stream as `channel`
+    ///
+    /// Lines of one provider list the same channels with different credentials in their
+    /// URLs, so channels are matched on tvg-id, falling back to name and group title.
+    pub async fn find_equivalent_in_source(
+        &self,
+        channel: &Channel,
+        source_id: Uuid,
+    ) -> Result<Option<Channel>> {
+        let mut query = Channels::find().filter(channels::Column::SourceId.eq(source_id));
+        query = match channel.tvg_id.as_deref().map(str::trim) {
+            Some(tvg_id) if !tvg_id.is_empty() => query.filter(channels::Column::TvgId.eq(tvg_id)),
+            _ => {
+                let query = query.filter(channels::Column::ChannelName.eq(&channel.channel_name));
+                match &channel.group_title {
+                    Some(group_title) => {
+                        query.filter(channels::Column::GroupTitle.eq(group_title.as_str()))
+                    }
+                    None => query.filter(channels::Column::GroupTitle.is_null()),
+                }
+            }
+        };
+        let model = query
+            .order_by_asc(channels::Column::Id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model.map(|m| self.model_to_domain(m)))
+    }
+
     /// Get channel name by ID
     pub async fn get_channel_name(&self, channel_id: Uuid) -> Result<Option<String>> {
         let model = Channels::find_by_id(channel_id)
@@ -152,51 +205,149 @@ impl ChannelSeaOrmRepository {
         Ok(model.map(|m| m.channel_name))
     }
 
+    /// Id, name, tvg-name and tvg-id of every channel, for matching channels by name
+    pub async fn list_name_keys(
+        &self,
+    ) -> Result<Vec<(Uuid, String, Option<String>, Option<String>)>> {
+        let rows = Channels::find()
+            .select_only()
+            .column(channels::Column::Id)
+            .column(channels::Column::ChannelName)
+            .column(channels::Column::TvgName)
+            .column(channels::Column::TvgId)
+            .into_tuple()
+            .all(&*self.connection)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Logo of the channels carrying each tvg-id, for showing guide channels
+    ///
+    /// When several channels share a tvg-id, the logo of the first by name is used.
+    pub async fn find_logos_by_tvg_ids(
+        &self,
+        tvg_ids: &[String],
+    ) -> Result<HashMap<String, String>> {
+        let mut logos = HashMap::new();
+        for chunk in tvg_ids.chunks(500) {
+            let rows: Vec<(Option<String>, Option<String>)> = Channels::find()
+                .select_only()
+                .column(channels::Column::TvgId)
+                .column(channels::Column::TvgLogo)
+                .filter(channels::Column::TvgId.is_in(chunk.iter().cloned()))
+                .filter(channels::Column::TvgLogo.is_not_null())
+                .order_by_asc(channels::Column::ChannelName)
+                .into_tuple()
+                .all(&*self.connection)
+                .await?;
+            for (tvg_id, logo) in rows {
+                if let (Some(tvg_id), Some(logo)) = (tvg_id, logo)
+                    && !logo.trim().is_empty()
+                {
+                    logos.entry(tvg_id).or_insert(logo);
+                }
+            }
+        }
+        Ok(logos)
+    }
+
     /// Update all channels for a source (replaces existing channels)
     pub async fn update_source_channels(
         &self,
         source_id: Uuid,
         channels: &[Channel],
-    ) -> Result<()> {
+    ) -> Result<RecordChangeSummary> {
         self.update_source_channels_with_batch_config(source_id, channels, None)
             .await
     }
 
     /// Update all channels for a source with configurable batch size (replaces existing channels)
+    ///
+    /// With a channel retention configured for the source, channels missing from `channels`
+    /// are kept (their `missed_ingestions` incremented) until they have been missing for more
+    /// than the configured number of consecutive ingestions. Channel ids are re-derived from
+    /// the source's channel identity key when one other than the default is configured.
+    /// Returns how the ingested channels differ from those stored before.
     pub async fn update_source_channels_with_batch_config(
         &self,
         source_id: Uuid,
         channels: &[Channel],
         batch_config: Option<&crate::config::DatabaseBatchConfig>,
-    ) -> Result<()> {
+    ) -> Result<RecordChangeSummary> {
         use sea_orm::TransactionTrait;
 
         if channels.is_empty() {
-            return Ok(());
+            return Ok(RecordChangeSummary::default());
         }
 
         // Use a single transaction for both delete and insert operations
         let txn = self.connection.begin().await?;
 
-        // Delete existing channels for this source
-        let delete_result = Channels::delete_many()
+        let identity_key = StreamSourceChannelIdentity::find_by_id(source_id)
+            .one(&txn)
+            .await?
+            .and_then(|identity| identity.identity_key.parse().ok())
+            .unwrap_or_default();
+        let mut channels = channels.to_vec();
+        let fallbacks = crate::models::channel_identity::assign_channel_ids(
+            source_id,
+            identity_key,
+            &mut channels,
+        );
+        if fallbacks > 0 {
+            tracing::debug!(
+                "{} of {} channels for source {} lack a unique {} and keep their URL-and-name id",
+                fallbacks,
+                channels.len(),
+                source_id,
+                identity_key.as_str()
+            );
+        }
+
+        let max_missed_ingestions = StreamSourceChannelRetention::find_by_id(source_id)
+            .one(&txn)
+            .await?
+            .map(|retention| retention.max_missed_ingestions)
+            .unwrap_or(0);
+
+        let existing: HashMap<Uuid, i32> = Channels::find()
+            .select_only()
+            .column(channels::Column::Id)
+            .column(channels::Column::MissedIngestions)
             .filter(channels::Column::SourceId.eq(source_id))
-            .exec(&txn)
-            .await?;
+            .into_tuple::<(Uuid, i32)>()
+            .all(&txn)
+            .await?
+            .into_iter()
+            .collect();
+        let ingested_ids: Vec<Uuid> = channels.iter().map(|c| c.id).collect();
+        let changes =
+            RecordChangeSummary::for_channels(&existing, &ingested_ids, max_missed_ingestions);
 
-        tracing::debug!(
-            "Deleted {} existing channels for source {}",
-            delete_result.rows_affected,
-            source_id
-        );
+        if max_missed_ingestions > 0 {
+            Self::retain_missing_channels_in_transaction(
+                source_id,
+                &channels,
+                max_missed_ingestions,
+                &txn,
+            )
+            .await?;
+        } else {
+            // Delete existing channels for this source
+            let delete_result = Channels::delete_many()
+                .filter(channels::Column::SourceId.eq(source_id))
+                .exec(&txn)
+                .await?;
+
+            tracing::debug!(
+                "Deleted {} existing channels for source {}",
+                delete_result.rows_affected,
+                source_id
+            );
+        }
 
         // Use the batch insert function but pass the transaction instead of the connection
-        match Self::insert_stream_channels_batch_in_transaction(
-            channels.to_vec(),
-            &txn,
-            batch_config,
-        )
-        .await
+        match Self::insert_stream_channels_batch_in_transaction(channels, &txn, batch_config).await
         {
             Ok(inserted_count) => {
                 // Commit the transaction only after both operations succeed
@@ -206,7 +357,7 @@ impl ChannelSeaOrmRepository {
                     inserted_count,
                     source_id
                 );
-                Ok(())
+                Ok(changes)
             }
             Err(e) => {
                 // Transaction will be automatically rolled back when dropped
@@ -216,6 +367,72 @@ impl ChannelSeaOrmRepository {
         }
     }
 
+    /// Age a source's existing channels and remove those that are replaced or expired
+    ///
+    /// Every existing channel counts one more missed ingestion; channels past the grace period
+    /// are purged, and channels present in the new ingestion are removed so they are inserted
+    /// afresh (resetting their count). The rest stay as stale channels.
+    async fn retain_missing_channels_in_transaction(
+        source_id: Uuid,
+        channels: &[Channel],
+        max_missed_ingestions: i32,
+        txn: &sea_orm::DatabaseTransaction,
+    ) -> Result<()> {
+        Channels::update_many()
+            .col_expr(
+                channels::Column::MissedIngestions,
+                Expr::col(channels::Column::MissedIngestions).add(1),
+            )
+            .filter(channels::Column::SourceId.eq(source_id))
+            .exec(txn)
+            .await?;
+
+        let purged = Channels::delete_many()
+            .filter(channels::Column::SourceId.eq(source_id))
+            .filter(channels::Column::MissedIngestions.gt(max_missed_ingestions))
+            .exec(txn)
+            .await?;
+
+        let mut replaced = 0;
+        for chunk in channels.chunks(500) {
+            let result = Channels::delete_many()
+                .filter(channels::Column::SourceId.eq(source_id))
+                .filter(channels::Column::Id.is_in(chunk.iter().map(|c| c.id)))
+                .exec(txn)
+                .await?;
+            replaced += result.rows_affected;
+        }
+
+        let retained = Channels::find()
+            .filter(channels::Column::SourceId.eq(source_id))
+            .count(txn)
+            .await?;
+
+        tracing::info!(
+            "Channel retention for source {}: replaced={} purged={} retained_stale={} (max missed ingestions {})",
+            source_id,
+            replaced,
+            purged.rows_affected,
+            retained,
+            max_missed_ingestions
+        );
+        Ok(())
+    }
+
+    /// Missed ingestion counts of the given channels that are currently stale
+    pub async fn find_stale_channels(&self, channel_ids: &[Uuid]) -> Result<HashMap<Uuid, i32>> {
+        let mut stale = HashMap::new();
+        for chunk in channel_ids.chunks(500) {
+            let models = Channels::find()
+                .filter(channels::Column::Id.is_in(chunk.iter().copied()))
+                .filter(channels::Column::MissedIngestions.gt(0))
+                .all(&*self.connection)
+                .await?;
+            stale.extend(models.into_iter().map(|m| (m.id, m.missed_ingestions)));
+        }
+        Ok(stale)
+    }
+
     /// Insert stream channels in a transaction (helper method for atomic operations)
     async fn insert_stream_channels_batch_in_transaction(
         channels: Vec<Channel>,
@@ -257,13 +474,13 @@ impl ChannelSeaOrmRepository {
             // Build multi-value INSERT statement with conflict resolution
             let mut query = match txn.get_database_backend() {
                 sea_orm::DatabaseBackend::Postgres => String::from(
-                    "INSERT INTO channels (id, source_id, tvg_id, tvg_name, tvg_chno, channel_name, tvg_logo, tvg_shift, group_title, stream_url, created_at, updated_at) VALUES ",
+                    "INSERT INTO channels (id, source_id, tvg_id, tvg_name, tvg_chno, channel_name, tvg_logo, tvg_shift, group_title, stream_url, created_at, updated_at, extra_attributes) VALUES ",
                 ),
                 sea_orm::DatabaseBackend::Sqlite => String::from(
-                    "INSERT INTO channels (id, source_id, tvg_id, tvg_name, tvg_chno, channel_name, tvg_logo, tvg_shift, group_title, stream_url, created_at, updated_at) VALUES ",
+                    "INSERT INTO channels (id, source_id, tvg_id, tvg_name, tvg_chno, channel_name, tvg_logo, tvg_shift, group_title, stream_url, created_at, updated_at, extra_attributes) VALUES ",
                 ),
                 _ => String::from(
-                    "INSERT INTO channels (id, source_id, tvg_id, tvg_name, tvg_chno, channel_name, tvg_logo, tvg_shift, group_title, stream_url, created_at, updated_at) VALUES ",
+                    "INSERT INTO channels (id, source_id, tvg_id, tvg_name, tvg_chno, channel_name, tvg_logo, tvg_shift, group_title, stream_url, created_at, updated_at, extra_attributes) VALUES ",
                 ),
             };
 
@@ -271,11 +488,11 @@ impl ChannelSeaOrmRepository {
             let placeholders: Vec<String> = (0..chunk.len())
                 .enumerate()
                 .map(|(i, _)| {
-                    let base_idx = i * 12; // 12 fields per channel
+                    let base_idx = i * 13; // 13 fields per channel
                     match txn.get_database_backend() {
                         sea_orm::DatabaseBackend::Postgres => {
                             format!(
-                                "(${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${})",
+                                "(${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${})",
                                 base_idx + 1,
                                 base_idx + 2,
                                 base_idx + 3,
@@ -287,10 +504,11 @@ impl ChannelSeaOrmRepository {
                                 base_idx + 9,
                                 base_idx + 10,
                                 base_idx + 11,
-                                base_idx + 12
+                                base_idx + 12,
+                                base_idx + 13
                             )
                         }
-                        _ => "(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)".to_string(),
+                        _ => "(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)".to_string(),
                     }
                 })
                 .collect();
@@ -327,6 +545,7 @@ impl ChannelSeaOrmRepository {
                 values.push(channel.stream_url.clone().into()); // stream_url
                 values.push(channel.created_at.into()); // created_at
                 values.push(channel.updated_at.into()); // updated_at
+                values.push(channel.extra_attributes_json().into()); // extra_attributes
             }
 
             use sea_orm::{ConnectionTrait, Statement};
@@ -371,6 +590,7 @@ impl ChannelSeaOrmRepository {
             channel_name: model.channel_name,
             tvg_logo: model.tvg_logo,
             tvg_shift: model.tvg_shift,
+            epg_shift: None,
             group_title: model.group_title,
             stream_url: model.stream_url,
             created_at: model.created_at,
@@ -380,6 +600,7 @@ impl ChannelSeaOrmRepository {
             resolution: None,
             probe_method: None,
             last_probed_at: None,
+            extra_attributes: Channel::parse_extra_attributes(model.extra_attributes.as_deref()),
         }
     }
 
@@ -415,6 +636,49 @@ impl ChannelSeaOrmRepository {
             .collect();
         Ok((channels, total_count))
     }
+
+    /// Search channels by name, tvg-id, tvg-name or group (case-insensitive substring match)
+    ///
+    /// Returns the requested window of matches ordered by channel name, plus the total match count.
+    pub async fn search(
+        &self,
+        query: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<Channel>, u64)> {
+        let condition = contains_any(
+            query,
+            [
+                channels::Column::ChannelName,
+                channels::Column::TvgName,
+                channels::Column::TvgId,
+                channels::Column::GroupTitle,
+            ],
+        );
+
+        let total_count = Channels::find()
+            .filter(condition.clone())
+            .count(&*self.connection)
+            .await?;
+
+        if limit == 0 {
+            return Ok((Vec::new(), total_count));
+        }
+
+        let models = Channels::find()
+            .filter(condition)
+            .order_by_asc(channels::Column::ChannelName)
+            .limit(limit)
+            .offset(offset)
+            .all(&*self.connection)
+            .await?;
+
+        let channels = models
+            .into_iter()
+            .map(|m| self.model_to_domain(m))
+            .collect();
+        Ok((channels, total_count))
+    }
 }
 
 #[cfg(test)]
@@ -448,7 +712,8 @@ mod tests {
                 group_title TEXT,
                 stream_url TEXT NOT NULL,
                 created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                extra_attributes TEXT
             );
             "
                 .to_string(),
@@ -459,6 +724,7 @@ mod tests {
         let db = crate::database::Database {
             connection: arc_connection.clone(),
             read_connection: arc_connection,
+            replica: None,
             database_type: crate::database::DatabaseType::SQLite,
             backend: DatabaseBackend::Sqlite,
             ingestion_config: IngestionConfig::default(),
@@ -577,6 +843,14 @@ mod tests {
         let entertainment_channels = repo.find_by_group_title("Entertainment").await?;
         assert_eq!(entertainment_channels.len(), 3);
 
+        // Test search across name and group
+        let (matches, total) = repo.search("entertain", 0, 2).await?;
+        assert_eq!(total, 3);
+        assert_eq!(matches.len(), 2);
+        let (matches, total) = repo.search("no such channel", 0, 10).await?;
+        assert_eq!(total, 0);
+        assert!(matches.is_empty());
+
         // Test that all channels have different IDs
         for i in 0..created_ids.len() {
             for j in (i + 1)..created_ids.len() {
@@ -586,4 +860,37 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_search_matches_wildcards_literally() -> Result<()> {
+        let db = create_test_db().await?;
+        let repo = ChannelSeaOrmRepository::new(db.connection().clone());
+
+        let source_id = Uuid::new_v4();
+        for name in ["Hits 100%", "Hits 1000", "news_24", "News 24"] {
+            repo.create(ChannelCreateRequest {
+                source_id,
+                tvg_id: None,
+                tvg_name: None,
+                tvg_chno: None,
+                tvg_logo: None,
+                tvg_shift: None,
+                group_title: None,
+                channel_name: name.to_string(),
+                stream_url: "http://example.com/stream".to_string(),
+            })
+            .await?;
+        }
+
+        let (matches, total) = repo.search("100%", 0, 10).await?;
+        assert_eq!(total, 1);
+        assert_eq!(matches[0].channel_name, "Hits 100%");
+        let (matches, total) = repo.search("NEWS_", 0, 10).await?;
+        assert_eq!(total, 1);
+        assert_eq!(matches[0].channel_name, "news_24");
+        let (_, total) = repo.search("%", 0, 10).await?;
+        assert_eq!(total, 1);
+
+        Ok(())
+    }
 }
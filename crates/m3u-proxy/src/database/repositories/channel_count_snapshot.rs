@@ -0,0 +1,164 @@
+//! SeaORM-based channel count snapshot repository implementation
+//!
+//! Keeps the latest channel count of each stream source and proxy, with the alert it
+//! raised, so the next ingestion or generation has something to compare against.
+
+use anyhow::Result;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{channel_count_snapshots, prelude::ChannelCountSnapshots};
+use crate::models::channel_count_alert::{ChannelCountAlert, ChannelCountSnapshot};
+
+/// SeaORM-based repository for channel count snapshots
+#[derive(Clone)]
+pub struct ChannelCountSnapshotSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ChannelCountSnapshotSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Latest snapshot of a source or proxy
+    pub async fn get(&self, subject_id: &Uuid) -> Result<Option<ChannelCountSnapshot>> {
+        let model = ChannelCountSnapshots::find_by_id(*subject_id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model.map(model_to_domain))
+    }
+
+    /// Latest alert of a source or proxy, if its last count raised one
+    pub async fn active_alert(&self, subject_id: &Uuid) -> Result<Option<ChannelCountAlert>> {
+        Ok(self
+            .get(subject_id)
+            .await?
+            .and_then(|snapshot| snapshot.alert))
+    }
+
+    /// Replace the snapshot of a source or proxy
+    pub async fn save(&self, snapshot: &ChannelCountSnapshot) -> Result<()> {
+        let alert = snapshot.alert.as_ref();
+        let existing = ChannelCountSnapshots::find_by_id(snapshot.subject_id)
+            .one(&*self.connection)
+            .await?;
+        match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.subject = Set(snapshot.subject);
+                active_model.channel_count = Set(snapshot.channel_count);
+                active_model.recorded_at = Set(snapshot.recorded_at);
+                active_model.alert_previous_count = Set(alert.map(|a| a.previous_count));
+                active_model.alert_change_percent = Set(alert.map(|a| a.change_percent));
+                active_model.alert_triggered_at = Set(alert.map(|a| a.triggered_at));
+                active_model.update(&*self.connection).await?;
+            }
+            None => {
+                channel_count_snapshots::ActiveModel {
+                    subject_id: Set(snapshot.subject_id),
+                    subject: Set(snapshot.subject),
+                    channel_count: Set(snapshot.channel_count),
+                    recorded_at: Set(snapshot.recorded_at),
+                    alert_previous_count: Set(alert.map(|a| a.previous_count)),
+                    alert_change_percent: Set(alert.map(|a| a.change_percent)),
+                    alert_triggered_at: Set(alert.map(|a| a.triggered_at)),
+                }
+                .insert(&*self.connection)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn model_to_domain(model: channel_count_snapshots::Model) -> ChannelCountSnapshot {
+    let alert = match (
+        model.alert_previous_count,
+        model.alert_change_percent,
+        model.alert_triggered_at,
+    ) {
+        (Some(previous_count), Some(change_percent), Some(triggered_at)) => {
+            Some(ChannelCountAlert {
+                previous_count,
+                current_count: model.channel_count,
+                change_percent,
+                triggered_at,
+            })
+        }
+        _ => None,
+    };
+    ChannelCountSnapshot {
+        subject_id: model.subject_id,
+        subject: model.subject,
+        channel_count: model.channel_count,
+        recorded_at: model.recorded_at,
+        alert,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::channel_count_alert::ChannelCountSubject;
+    use chrono::Utc;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<ChannelCountSnapshotSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+            CREATE TABLE channel_count_snapshots (
+                subject_id TEXT PRIMARY KEY,
+                subject TEXT NOT NULL,
+                channel_count INTEGER NOT NULL,
+                recorded_at TEXT NOT NULL,
+                alert_previous_count INTEGER,
+                alert_change_percent REAL,
+                alert_triggered_at TEXT
+            );
+            "
+                .to_string(),
+            ))
+            .await?;
+        Ok(ChannelCountSnapshotSeaOrmRepository::new(Arc::new(
+            connection,
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_save_replaces_snapshot_and_alert() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let source_id = Uuid::new_v4();
+        assert!(repo.get(&source_id).await?.is_none());
+
+        let mut snapshot = ChannelCountSnapshot {
+            subject_id: source_id,
+            subject: ChannelCountSubject::StreamSource,
+            channel_count: 40,
+            recorded_at: Utc::now(),
+            alert: Some(ChannelCountAlert {
+                previous_count: 100,
+                current_count: 40,
+                change_percent: -60.0,
+                triggered_at: Utc::now(),
+            }),
+        };
+        repo.save(&snapshot).await?;
+        let alert = repo.active_alert(&source_id).await?.unwrap();
+        assert_eq!(alert.previous_count, 100);
+        assert_eq!(alert.current_count, 40);
+
+        snapshot.channel_count = 42;
+        snapshot.alert = None;
+        repo.save(&snapshot).await?;
+        let stored = repo.get(&source_id).await?.unwrap();
+        assert_eq!(stored.channel_count, 42);
+        assert!(stored.alert.is_none());
+        Ok(())
+    }
+}
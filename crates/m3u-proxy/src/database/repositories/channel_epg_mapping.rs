@@ -0,0 +1,157 @@
+//! SeaORM-based channel-to-EPG mapping repository implementation
+//!
+//! Stores the manual EPG channel each proxy channel takes its programmes from.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{channel_epg_mappings, prelude::ChannelEpgMappings};
+use crate::models::channel_epg_mapping::ChannelEpgMapping;
+
+/// SeaORM-based repository for manual channel-to-EPG mappings
+pub struct ChannelEpgMappingSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ChannelEpgMappingSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// List a proxy's mappings by channel name
+    pub async fn list_for_proxy(&self, proxy_id: &Uuid) -> Result<Vec<ChannelEpgMapping>> {
+        let models = ChannelEpgMappings::find()
+            .filter(channel_epg_mappings::Column::ProxyId.eq(*proxy_id))
+            .order_by_asc(channel_epg_mappings::Column::ChannelName)
+            .all(&*self.connection)
+            .await?;
+        Ok(models.into_iter().map(model_to_domain).collect())
+    }
+
+    /// Map a channel of a proxy to an EPG channel, replacing any existing mapping
+    pub async fn set(
+        &self,
+        proxy_id: Uuid,
+        channel_id: Uuid,
+        epg_channel_id: &str,
+        channel_name: Option<String>,
+    ) -> Result<ChannelEpgMapping> {
+        let existing = ChannelEpgMappings::find()
+            .filter(channel_epg_mappings::Column::ProxyId.eq(proxy_id))
+            .filter(channel_epg_mappings::Column::ChannelId.eq(channel_id))
+            .one(&*self.connection)
+            .await?;
+        let epg_channel_id = epg_channel_id.trim().to_string();
+
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.epg_channel_id = Set(epg_channel_id);
+                if channel_name.is_some() {
+                    active_model.channel_name = Set(channel_name);
+                }
+                active_model.updated_at = Set(Utc::now());
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                let now = Utc::now();
+                channel_epg_mappings::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    proxy_id: Set(proxy_id),
+                    channel_id: Set(channel_id),
+                    epg_channel_id: Set(epg_channel_id),
+                    channel_name: Set(channel_name),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+
+    /// Remove the mapping of a proxy's channel; returns false when it has none
+    pub async fn delete(&self, proxy_id: &Uuid, channel_id: &Uuid) -> Result<bool> {
+        let result = ChannelEpgMappings::delete_many()
+            .filter(channel_epg_mappings::Column::ProxyId.eq(*proxy_id))
+            .filter(channel_epg_mappings::Column::ChannelId.eq(*channel_id))
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+fn model_to_domain(model: channel_epg_mappings::Model) -> ChannelEpgMapping {
+    ChannelEpgMapping {
+        id: model.id,
+        proxy_id: model.proxy_id,
+        channel_id: model.channel_id,
+        epg_channel_id: model.epg_channel_id,
+        channel_name: model.channel_name,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<ChannelEpgMappingSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE channel_epg_mappings (
+                    id TEXT PRIMARY KEY,
+                    proxy_id TEXT NOT NULL,
+                    channel_id TEXT NOT NULL,
+                    epg_channel_id TEXT NOT NULL,
+                    channel_name TEXT,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    UNIQUE (proxy_id, channel_id)
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        Ok(ChannelEpgMappingSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    #[tokio::test]
+    async fn test_set_replace_and_delete() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let proxy_id = Uuid::new_v4();
+        let channel_id = Uuid::new_v4();
+
+        repo.set(
+            proxy_id,
+            channel_id,
+            " bbc1.uk ",
+            Some("BBC One".to_string()),
+        )
+        .await?;
+        let replaced = repo.set(proxy_id, channel_id, "bbc1hd.uk", None).await?;
+        assert_eq!(replaced.epg_channel_id, "bbc1hd.uk");
+        assert_eq!(replaced.channel_name.as_deref(), Some("BBC One"));
+
+        let mappings = repo.list_for_proxy(&proxy_id).await?;
+        assert_eq!(mappings.len(), 1);
+        assert!(repo.list_for_proxy(&Uuid::new_v4()).await?.is_empty());
+
+        assert!(repo.delete(&proxy_id, &channel_id).await?);
+        assert!(!repo.delete(&proxy_id, &channel_id).await?);
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+//! SeaORM-based proxy channel exclusion repository implementation
+//!
+//! Stores the channels and groups excluded from each proxy's output.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::ProxyChannelExclusions, proxy_channel_exclusions};
+use crate::models::channel_exclusion::ProxyChannelExclusion;
+
+/// SeaORM-based repository for proxy channel exclusions
+pub struct ChannelExclusionSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ChannelExclusionSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// List a proxy's exclusions, newest first
+    pub async fn list_for_proxy(&self, proxy_id: &Uuid) -> Result<Vec<ProxyChannelExclusion>> {
+        let models = ProxyChannelExclusions::find()
+            .filter(proxy_channel_exclusions::Column::ProxyId.eq(*proxy_id))
+            .order_by_desc(proxy_channel_exclusions::Column::CreatedAt)
+            .all(&*self.connection)
+            .await?;
+        Ok(models.into_iter().map(model_to_domain).collect())
+    }
+
+    /// Exclude a channel from a proxy; an existing exclusion of the channel is returned as is
+    pub async fn exclude_channel(
+        &self,
+        proxy_id: Uuid,
+        channel_id: Uuid,
+        channel_name: Option<String>,
+    ) -> Result<ProxyChannelExclusion> {
+        if let Some(existing) = ProxyChannelExclusions::find()
+            .filter(proxy_channel_exclusions::Column::ProxyId.eq(proxy_id))
+            .filter(proxy_channel_exclusions::Column::ChannelId.eq(channel_id))
+            .one(&*self.connection)
+            .await?
+        {
+            return Ok(model_to_domain(existing));
+        }
+        self.insert(proxy_id, Some(channel_id), None, channel_name)
+            .await
+    }
+
+    /// Exclude a group from a proxy; an existing exclusion of the group is returned as is
+    pub async fn exclude_group(
+        &self,
+        proxy_id: Uuid,
+        group_title: &str,
+    ) -> Result<ProxyChannelExclusion> {
+        let group_title = group_title.trim();
+        let existing = ProxyChannelExclusions::find()
+            .filter(proxy_channel_exclusions::Column::ProxyId.eq(proxy_id))
+            .filter(proxy_channel_exclusions::Column::GroupTitle.is_not_null())
+            .all(&*self.connection)
+            .await?
+            .into_iter()
+            .find(|model| {
+                model
+                    .group_title
+                    .as_deref()
+                    .is_some_and(|title| title.eq_ignore_ascii_case(group_title))
+            });
+        if let Some(existing) = existing {
+            return Ok(model_to_domain(existing));
+        }
+        self.insert(proxy_id, None, Some(group_title.to_string()), None)
+            .await
+    }
+
+    /// Remove an exclusion of a proxy; returns false when it does not exist
+    pub async fn delete(&self, proxy_id: &Uuid, id: &Uuid) -> Result<bool> {
+        let result = ProxyChannelExclusions::delete_many()
+            .filter(proxy_channel_exclusions::Column::Id.eq(*id))
+            .filter(proxy_channel_exclusions::Column::ProxyId.eq(*proxy_id))
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn insert(
+        &self,
+        proxy_id: Uuid,
+        channel_id: Option<Uuid>,
+        group_title: Option<String>,
+        channel_name: Option<String>,
+    ) -> Result<ProxyChannelExclusion> {
+        let active_model = proxy_channel_exclusions::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            proxy_id: Set(proxy_id),
+            channel_id: Set(channel_id),
+            group_title: Set(group_title),
+            channel_name: Set(channel_name),
+            created_at: Set(Utc::now()),
+        };
+        let model = active_model.insert(&*self.connection).await?;
+        Ok(model_to_domain(model))
+    }
+}
+
+fn model_to_domain(model: proxy_channel_exclusions::Model) -> ProxyChannelExclusion {
+    ProxyChannelExclusion {
+        id: model.id,
+        proxy_id: model.proxy_id,
+        channel_id: model.channel_id,
+        group_title: model.group_title,
+        channel_name: model.channel_name,
+        created_at: model.created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<ChannelExclusionSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+            CREATE TABLE proxy_channel_exclusions (
+                id TEXT PRIMARY KEY,
+                proxy_id TEXT NOT NULL,
+                channel_id TEXT,
+                group_title TEXT,
+                channel_name TEXT,
+                created_at TEXT NOT NULL
+            );
+            "
+                .to_string(),
+            ))
+            .await?;
+        Ok(ChannelExclusionSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    #[tokio::test]
+    async fn test_exclusions_are_idempotent_and_scoped_to_proxy() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let proxy_id = Uuid::new_v4();
+        let channel_id = Uuid::new_v4();
+
+        let first = repo
+            .exclude_channel(proxy_id, channel_id, Some("BBC One".to_string()))
+            .await?;
+        let again = repo.exclude_channel(proxy_id, channel_id, None).await?;
+        assert_eq!(first.id, again.id);
+
+        let group = repo.exclude_group(proxy_id, " Adult ").await?;
+        assert_eq!(group.group_title.as_deref(), Some("Adult"));
+        assert_eq!(repo.exclude_group(proxy_id, "adult").await?.id, group.id);
+        assert_eq!(repo.list_for_proxy(&proxy_id).await?.len(), 2);
+
+        let other_proxy = Uuid::new_v4();
+        assert!(!repo.delete(&other_proxy, &first.id).await?);
+        assert!(repo.delete(&proxy_id, &first.id).await?);
+        assert_eq!(repo.list_for_proxy(&proxy_id).await?.len(), 1);
+        Ok(())
+    }
+}
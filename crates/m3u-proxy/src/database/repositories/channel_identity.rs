@@ -0,0 +1,75 @@
+//! SeaORM-based stream source channel identity repository
+//!
+//! Stores which channel fields a source's channel UUIDs are derived from.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::StreamSourceChannelIdentity, stream_source_channel_identity};
+use crate::models::channel_identity::{ChannelIdentity, ChannelIdentityKey};
+
+/// SeaORM-based repository for stream source channel identity keys
+pub struct ChannelIdentitySeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ChannelIdentitySeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Identity key of a source (the default when none is configured)
+    pub async fn get(&self, source_id: &Uuid) -> Result<ChannelIdentity> {
+        let model = StreamSourceChannelIdentity::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model
+            .map(model_to_domain)
+            .unwrap_or_else(|| ChannelIdentity::default_for(*source_id)))
+    }
+
+    /// Set a source's identity key; the default key removes the setting
+    pub async fn set(&self, source_id: &Uuid, key: ChannelIdentityKey) -> Result<ChannelIdentity> {
+        let existing = StreamSourceChannelIdentity::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+
+        if key == ChannelIdentityKey::default() {
+            if let Some(model) = existing {
+                model.into_active_model().delete(&*self.connection).await?;
+            }
+            return Ok(ChannelIdentity::default_for(*source_id));
+        }
+
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.identity_key = Set(key.as_str().to_string());
+                active_model.updated_at = Set(Utc::now());
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                stream_source_channel_identity::ActiveModel {
+                    source_id: Set(*source_id),
+                    identity_key: Set(key.as_str().to_string()),
+                    updated_at: Set(Utc::now()),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+}
+
+fn model_to_domain(model: stream_source_channel_identity::Model) -> ChannelIdentity {
+    ChannelIdentity {
+        source_id: model.source_id,
+        identity_key: model.identity_key.parse().unwrap_or_default(),
+        updated_at: Some(model.updated_at),
+    }
+}
@@ -0,0 +1,95 @@
+//! SeaORM-based channel logo assignment repository implementation
+//!
+//! Stores the uploaded logo asset each assigned channel shows instead of its source's logo.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{channel_logo_assignments, prelude::ChannelLogoAssignments};
+use crate::models::channel_logo_assignment::ChannelLogoAssignment;
+
+/// SeaORM-based repository for channel logo assignments
+pub struct ChannelLogoAssignmentSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ChannelLogoAssignmentSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// List all assignments by channel name
+    pub async fn list(&self) -> Result<Vec<ChannelLogoAssignment>> {
+        let models = ChannelLogoAssignments::find()
+            .order_by_asc(channel_logo_assignments::Column::ChannelName)
+            .all(&*self.connection)
+            .await?;
+        Ok(models.into_iter().map(model_to_domain).collect())
+    }
+
+    /// Assign a logo asset to a channel, replacing any existing assignment
+    pub async fn set(
+        &self,
+        channel_id: Uuid,
+        logo_asset_id: Uuid,
+        channel_name: Option<String>,
+    ) -> Result<ChannelLogoAssignment> {
+        let existing = ChannelLogoAssignments::find()
+            .filter(channel_logo_assignments::Column::ChannelId.eq(channel_id))
+            .one(&*self.connection)
+            .await?;
+
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.logo_asset_id = Set(logo_asset_id);
+                if channel_name.is_some() {
+                    active_model.channel_name = Set(channel_name);
+                }
+                active_model.updated_at = Set(Utc::now());
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                let now = Utc::now();
+                channel_logo_assignments::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    channel_id: Set(channel_id),
+                    logo_asset_id: Set(logo_asset_id),
+                    channel_name: Set(channel_name),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+
+    /// Remove the assignment of a channel; returns false when it has none
+    pub async fn delete(&self, channel_id: &Uuid) -> Result<bool> {
+        let result = ChannelLogoAssignments::delete_many()
+            .filter(channel_logo_assignments::Column::ChannelId.eq(*channel_id))
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+fn model_to_domain(model: channel_logo_assignments::Model) -> ChannelLogoAssignment {
+    ChannelLogoAssignment {
+        id: model.id,
+        channel_id: model.channel_id,
+        logo_asset_id: model.logo_asset_id,
+        channel_name: model.channel_name,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
@@ -0,0 +1,79 @@
+//! SeaORM-based stream source channel retention repository
+//!
+//! Stores how many consecutive ingestions a source's missing channels are retained for.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::StreamSourceChannelRetention, stream_source_channel_retention};
+use crate::models::channel_retention::ChannelRetention;
+
+/// SeaORM-based repository for stream source channel retention
+pub struct ChannelRetentionSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ChannelRetentionSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Retention of a source (disabled when none is configured)
+    pub async fn get(&self, source_id: &Uuid) -> Result<ChannelRetention> {
+        let model = StreamSourceChannelRetention::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model
+            .map(model_to_domain)
+            .unwrap_or_else(|| ChannelRetention::disabled(*source_id)))
+    }
+
+    /// Set a source's retention; 0 removes the setting
+    pub async fn set(
+        &self,
+        source_id: &Uuid,
+        max_missed_ingestions: i32,
+    ) -> Result<ChannelRetention> {
+        let existing = StreamSourceChannelRetention::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+
+        if max_missed_ingestions <= 0 {
+            if let Some(model) = existing {
+                model.into_active_model().delete(&*self.connection).await?;
+            }
+            return Ok(ChannelRetention::disabled(*source_id));
+        }
+
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.max_missed_ingestions = Set(max_missed_ingestions);
+                active_model.updated_at = Set(Utc::now());
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                stream_source_channel_retention::ActiveModel {
+                    source_id: Set(*source_id),
+                    max_missed_ingestions: Set(max_missed_ingestions),
+                    updated_at: Set(Utc::now()),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+}
+
+fn model_to_domain(model: stream_source_channel_retention::Model) -> ChannelRetention {
+    ChannelRetention {
+        source_id: model.source_id,
+        max_missed_ingestions: model.max_missed_ingestions,
+        updated_at: Some(model.updated_at),
+    }
+}
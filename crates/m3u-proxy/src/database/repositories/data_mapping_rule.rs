@@ -4,13 +4,17 @@
 //! that works across SQLite, PostgreSQL, and MySQL databases.
 
 use anyhow::Result;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryOrder, Set};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::entities::{data_mapping_rules, prelude::*};
 use crate::models::data_mapping::{
-    DataMappingRule, DataMappingRuleCreateRequest, DataMappingRuleUpdateRequest,
+    DataMappingRule, DataMappingRuleCreateRequest, DataMappingRuleScope,
+    DataMappingRuleUpdateRequest,
 };
 
 /// SeaORM-based DataMappingRule repository
@@ -38,8 +42,11 @@ impl DataMappingRuleSeaOrmRepository {
             expression: Set(request.expression.clone()),
             sort_order: Set(0), // Default sort order for new rules
             is_active: Set(true),
+            scope_source_id: Set(request.scope.source_id()),
+            scope_proxy_id: Set(request.scope.proxy_id()),
             created_at: Set(now),
             updated_at: Set(now),
+            deleted_at: Set(None),
         };
 
         let model = active_model.insert(&*self.connection).await?;
@@ -51,6 +58,7 @@ impl DataMappingRuleSeaOrmRepository {
             sort_order: model.sort_order,
             is_active: model.is_active,
             expression: model.expression,
+            scope: DataMappingRuleScope::from_columns(model.scope_source_id, model.scope_proxy_id),
             created_at: model.created_at,
             updated_at: model.updated_at,
         })
@@ -59,6 +67,7 @@ impl DataMappingRuleSeaOrmRepository {
     /// Find data mapping rule by ID
     pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<DataMappingRule>> {
         let model = DataMappingRules::find_by_id(*id)
+            .filter(data_mapping_rules::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?;
         match model {
@@ -70,6 +79,7 @@ impl DataMappingRuleSeaOrmRepository {
                 sort_order: m.sort_order,
                 is_active: m.is_active,
                 expression: m.expression,
+                scope: DataMappingRuleScope::from_columns(m.scope_source_id, m.scope_proxy_id),
                 created_at: m.created_at,
                 updated_at: m.updated_at,
             })),
@@ -80,6 +90,7 @@ impl DataMappingRuleSeaOrmRepository {
     /// List all data mapping rules
     pub async fn list_all(&self) -> Result<Vec<DataMappingRule>> {
         let models = DataMappingRules::find()
+            .filter(data_mapping_rules::Column::DeletedAt.is_null())
             .order_by_asc(data_mapping_rules::Column::SortOrder)
             .all(&*self.connection)
             .await?;
@@ -94,6 +105,7 @@ impl DataMappingRuleSeaOrmRepository {
                 sort_order: m.sort_order,
                 is_active: m.is_active,
                 expression: m.expression,
+                scope: DataMappingRuleScope::from_columns(m.scope_source_id, m.scope_proxy_id),
                 created_at: m.created_at,
                 updated_at: m.updated_at,
             });
@@ -108,6 +120,7 @@ impl DataMappingRuleSeaOrmRepository {
         request: DataMappingRuleUpdateRequest,
     ) -> Result<DataMappingRule> {
         let model = DataMappingRules::find_by_id(*id)
+            .filter(data_mapping_rules::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Data mapping rule not found"))?;
@@ -129,6 +142,10 @@ impl DataMappingRuleSeaOrmRepository {
         if let Some(is_active) = request.is_active {
             active_model.is_active = Set(is_active);
         }
+        if let Some(scope) = request.scope {
+            active_model.scope_source_id = Set(scope.source_id());
+            active_model.scope_proxy_id = Set(scope.proxy_id());
+        }
 
         active_model.updated_at = Set(chrono::Utc::now());
 
@@ -141,14 +158,26 @@ impl DataMappingRuleSeaOrmRepository {
             sort_order: updated_model.sort_order,
             is_active: updated_model.is_active,
             expression: updated_model.expression,
+            scope: DataMappingRuleScope::from_columns(
+                updated_model.scope_source_id,
+                updated_model.scope_proxy_id,
+            ),
             created_at: updated_model.created_at,
             updated_at: updated_model.updated_at,
         })
     }
 
-    /// Delete data mapping rule
+    /// Move data mapping rule to the trash
     pub async fn delete(&self, id: &Uuid) -> Result<()> {
-        let result = DataMappingRules::delete_by_id(*id)
+        let now = chrono::Utc::now();
+        let result = DataMappingRules::update_many()
+            .col_expr(
+                data_mapping_rules::Column::DeletedAt,
+                Expr::value(Some(now)),
+            )
+            .col_expr(data_mapping_rules::Column::UpdatedAt, Expr::value(now))
+            .filter(data_mapping_rules::Column::Id.eq(*id))
+            .filter(data_mapping_rules::Column::DeletedAt.is_null())
             .exec(&*self.connection)
             .await?;
         if result.rows_affected == 0 {
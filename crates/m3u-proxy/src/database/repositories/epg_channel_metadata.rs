@@ -0,0 +1,170 @@
+//! SeaORM-based EPG channel metadata repository implementation
+//!
+//! Stores the language-tagged display names and icons of each EPG source's channels.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{
+    epg_channel_metadata, prelude::EpgChannelMetadata as EpgChannelMetadataEntity,
+};
+use crate::models::epg_channel_metadata::EpgChannelMetadata;
+
+/// Rows inserted per statement when replacing a source's channels
+const INSERT_BATCH_SIZE: usize = 500;
+
+/// SeaORM-based repository for multilingual EPG channel metadata
+pub struct EpgChannelMetadataSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl EpgChannelMetadataSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// List the channel metadata of the given EPG sources
+    pub async fn list_for_sources(&self, source_ids: &[Uuid]) -> Result<Vec<EpgChannelMetadata>> {
+        if source_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let models = EpgChannelMetadataEntity::find()
+            .filter(epg_channel_metadata::Column::SourceId.is_in(source_ids.iter().copied()))
+            .order_by_asc(epg_channel_metadata::Column::ChannelId)
+            .all(&*self.connection)
+            .await?;
+        models.into_iter().map(model_to_domain).collect()
+    }
+
+    /// Replace a source's channel metadata, on a connection or within an ingestion transaction
+    pub async fn replace_for_source<C: ConnectionTrait>(
+        db: &C,
+        source_id: Uuid,
+        channels: &[EpgChannelMetadata],
+    ) -> Result<usize> {
+        EpgChannelMetadataEntity::delete_many()
+            .filter(epg_channel_metadata::Column::SourceId.eq(source_id))
+            .exec(db)
+            .await?;
+
+        let now = Utc::now();
+        for batch in channels.chunks(INSERT_BATCH_SIZE) {
+            let models = batch
+                .iter()
+                .map(|channel| -> Result<epg_channel_metadata::ActiveModel> {
+                    Ok(epg_channel_metadata::ActiveModel {
+                        id: Set(Uuid::new_v4()),
+                        source_id: Set(source_id),
+                        channel_id: Set(channel.channel_id.clone()),
+                        display_names: Set(serde_json::to_string(&channel.display_names)?),
+                        icons: Set(serde_json::to_string(&channel.icons)?),
+                        updated_at: Set(now),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            EpgChannelMetadataEntity::insert_many(models)
+                .exec(db)
+                .await?;
+        }
+        Ok(channels.len())
+    }
+}
+
+fn model_to_domain(model: epg_channel_metadata::Model) -> Result<EpgChannelMetadata> {
+    Ok(EpgChannelMetadata {
+        source_id: model.source_id,
+        channel_id: model.channel_id,
+        display_names: serde_json::from_str(&model.display_names)?,
+        icons: serde_json::from_str(&model.icons)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::epg_channel_metadata::LocalizedValue;
+    use sea_orm::{DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<EpgChannelMetadataSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE epg_channel_metadata (
+                    id TEXT PRIMARY KEY,
+                    source_id TEXT NOT NULL,
+                    channel_id TEXT NOT NULL,
+                    display_names TEXT NOT NULL,
+                    icons TEXT NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    UNIQUE (source_id, channel_id)
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        Ok(EpgChannelMetadataSeaOrmRepository::new(Arc::new(
+            connection,
+        )))
+    }
+
+    fn channel(source_id: Uuid, channel_id: &str, name: &str) -> EpgChannelMetadata {
+        EpgChannelMetadata {
+            source_id,
+            channel_id: channel_id.to_string(),
+            display_names: vec![LocalizedValue {
+                value: name.to_string(),
+                lang: Some("de".to_string()),
+            }],
+            icons: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_and_list() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let source_id = Uuid::new_v4();
+        let other_source_id = Uuid::new_v4();
+
+        EpgChannelMetadataSeaOrmRepository::replace_for_source(
+            &*repo.connection,
+            source_id,
+            &[
+                channel(source_id, "a", "Erste"),
+                channel(source_id, "b", "Zweite"),
+            ],
+        )
+        .await?;
+        EpgChannelMetadataSeaOrmRepository::replace_for_source(
+            &*repo.connection,
+            other_source_id,
+            &[channel(other_source_id, "c", "Dritte")],
+        )
+        .await?;
+        // A re-ingestion replaces the source's previous channels
+        EpgChannelMetadataSeaOrmRepository::replace_for_source(
+            &*repo.connection,
+            source_id,
+            &[channel(source_id, "b", "Zweites")],
+        )
+        .await?;
+
+        let channels = repo.list_for_sources(&[source_id]).await?;
+        assert_eq!(channels, vec![channel(source_id, "b", "Zweites")]);
+        assert_eq!(
+            repo.list_for_sources(&[source_id, other_source_id])
+                .await?
+                .len(),
+            2
+        );
+        assert!(repo.list_for_sources(&[]).await?.is_empty());
+        Ok(())
+    }
+}
@@ -6,13 +6,16 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, sea_query::Expr,
 };
 use std::sync::Arc;
 use uuid::Uuid;
 
+use super::traits::contains_any;
 use crate::entities::{epg_programs, prelude::EpgPrograms};
 use crate::models::EpgProgram;
+use crate::models::channel_epg_mapping::EpgChannelCandidate;
 
 /// SeaORM repository for EPG programs with clean, focused interface
 #[derive(Clone)]
@@ -84,6 +87,97 @@ impl EpgProgramSeaOrmRepository {
         Ok(count)
     }
 
+    /// Search programs that have not yet ended by title or channel name
+    ///
+    /// Case-insensitive substring match, ordered by start time. Returns the requested window
+    /// of matches plus the total match count.
+    pub async fn search(
+        &self,
+        query: &str,
+        now: &DateTime<Utc>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<EpgProgram>, u64)> {
+        let condition = Condition::all()
+            .add(epg_programs::Column::EndTime.gt(*now))
+            .add(contains_any(
+                query,
+                [
+                    epg_programs::Column::ProgramTitle,
+                    epg_programs::Column::ChannelName,
+                ],
+            ));
+
+        let total_count = EpgPrograms::find()
+            .filter(condition.clone())
+            .count(&*self.connection)
+            .await?;
+
+        if limit == 0 {
+            return Ok((Vec::new(), total_count));
+        }
+
+        let models = EpgPrograms::find()
+            .filter(condition)
+            .order_by_asc(epg_programs::Column::StartTime)
+            .limit(limit)
+            .offset(offset)
+            .all(&*self.connection)
+            .await?;
+
+        Ok((self.models_to_domain(models)?, total_count))
+    }
+
+    /// Distinct EPG channels of the given sources, optionally only those whose id or name
+    /// contains `query` (case-insensitive)
+    pub async fn list_channels(
+        &self,
+        source_ids: &[Uuid],
+        query: Option<&str>,
+    ) -> Result<Vec<EpgChannelCandidate>> {
+        if source_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut select = EpgPrograms::find()
+            .select_only()
+            .column(epg_programs::Column::ChannelId)
+            .column(epg_programs::Column::ChannelName)
+            .column(epg_programs::Column::SourceId)
+            .column_as(
+                Expr::col(epg_programs::Column::Id).count(),
+                "programme_count",
+            )
+            .filter(epg_programs::Column::SourceId.is_in(source_ids.iter().copied()));
+        if let Some(query) = query.map(str::trim).filter(|q| !q.is_empty()) {
+            select = select.filter(contains_any(
+                query,
+                [
+                    epg_programs::Column::ChannelId,
+                    epg_programs::Column::ChannelName,
+                ],
+            ));
+        }
+
+        let rows: Vec<(String, String, Uuid, i64)> = select
+            .group_by(epg_programs::Column::ChannelId)
+            .group_by(epg_programs::Column::ChannelName)
+            .group_by(epg_programs::Column::SourceId)
+            .into_tuple()
+            .all(&*self.connection)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(
+                |(epg_channel_id, channel_name, source_id, programme_count)| EpgChannelCandidate {
+                    epg_channel_id,
+                    channel_name,
+                    source_id,
+                    programme_count: programme_count.max(0) as u64,
+                },
+            )
+            .collect())
+    }
+
     /// Convert SeaORM models to domain models (private helper)
     fn models_to_domain(&self, models: Vec<epg_programs::Model>) -> Result<Vec<EpgProgram>> {
         let mut programs = Vec::new();
@@ -165,6 +259,7 @@ mod tests {
         let db = crate::database::Database {
             connection: arc_connection.clone(),
             read_connection: arc_connection,
+            replica: None,
             database_type: crate::database::DatabaseType::SQLite,
             backend: DatabaseBackend::Sqlite,
             ingestion_config: IngestionConfig::default(),
@@ -188,6 +283,11 @@ mod tests {
         let count = repo.count_by_source_id(&source_id).await?;
         assert_eq!(count, 0);
 
+        // Test search (should be empty initially)
+        let (programs, total) = repo.search("news", &Utc::now(), 0, 10).await?;
+        assert!(programs.is_empty());
+        assert_eq!(total, 0);
+
         Ok(())
     }
 }
@@ -3,6 +3,7 @@
 //! This provides a database-agnostic repository for EPG Source operations using SeaORM.
 
 use anyhow::Result;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
     QueryOrder, Set,
@@ -46,6 +47,7 @@ impl EpgSourceSeaOrmRepository {
             updated_at: Set(now),
             last_ingested_at: Set(None),
             is_active: Set(true),
+            deleted_at: Set(None),
         };
 
         let model = active_model.insert(&*self.connection).await?;
@@ -56,7 +58,10 @@ impl EpgSourceSeaOrmRepository {
 
     /// Find an EPG source by ID
     pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<EpgSource>> {
-        let model = EpgSources::find_by_id(*id).one(&*self.connection).await?;
+        let model = EpgSources::find_by_id(*id)
+            .filter(epg_sources::Column::DeletedAt.is_null())
+            .one(&*self.connection)
+            .await?;
 
         match model {
             Some(m) => Ok(Some(self.model_to_domain(m)?)),
@@ -67,6 +72,7 @@ impl EpgSourceSeaOrmRepository {
     /// Find all EPG sources
     pub async fn find_all(&self) -> Result<Vec<EpgSource>> {
         let models = EpgSources::find()
+            .filter(epg_sources::Column::DeletedAt.is_null())
             .order_by_asc(epg_sources::Column::Name)
             .all(&*self.connection)
             .await?;
@@ -82,6 +88,7 @@ impl EpgSourceSeaOrmRepository {
     /// Find EPG sources by type
     pub async fn find_by_type(&self, source_type: &EpgSourceType) -> Result<Vec<EpgSource>> {
         let models = EpgSources::find()
+            .filter(epg_sources::Column::DeletedAt.is_null())
             .filter(epg_sources::Column::SourceType.eq(source_type.to_string()))
             .all(&*self.connection)
             .await?;
@@ -97,6 +104,7 @@ impl EpgSourceSeaOrmRepository {
     /// Find active EPG sources
     pub async fn find_active(&self) -> Result<Vec<EpgSource>> {
         let models = EpgSources::find()
+            .filter(epg_sources::Column::DeletedAt.is_null())
             .filter(epg_sources::Column::IsActive.eq(true))
             .order_by_asc(epg_sources::Column::Name)
             .all(&*self.connection)
@@ -117,6 +125,7 @@ impl EpgSourceSeaOrmRepository {
         source_type: EpgSourceType,
     ) -> Result<Vec<EpgSource>> {
         let models = EpgSources::find()
+            .filter(epg_sources::Column::DeletedAt.is_null())
             .filter(epg_sources::Column::Url.eq(url))
             .filter(epg_sources::Column::SourceType.eq(source_type.to_string()))
             .filter(epg_sources::Column::IsActive.eq(true))
@@ -192,6 +201,7 @@ impl EpgSourceSeaOrmRepository {
 
         // Find the existing source
         let existing = EpgSources::find_by_id(*id)
+            .filter(epg_sources::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?
             .ok_or_else(|| anyhow::anyhow!("EPG source not found"))?;
@@ -218,18 +228,19 @@ impl EpgSourceSeaOrmRepository {
         self.model_to_domain(updated_model)
     }
 
-    /// Delete an EPG source
+    /// Move an EPG source to the trash
     pub async fn delete(&self, id: &Uuid) -> Result<()> {
-        use crate::entities::prelude::EpgSources;
-
-        let result = EpgSources::delete_by_id(*id)
+        let now = chrono::Utc::now();
+        let result = EpgSources::update_many()
+            .col_expr(epg_sources::Column::DeletedAt, Expr::value(Some(now)))
+            .col_expr(epg_sources::Column::UpdatedAt, Expr::value(now))
+            .filter(epg_sources::Column::Id.eq(*id))
+            .filter(epg_sources::Column::DeletedAt.is_null())
             .exec(&*self.connection)
             .await?;
-
         if result.rows_affected == 0 {
             return Err(anyhow::anyhow!("EPG source not found"));
         }
-
         Ok(())
     }
 
@@ -329,7 +340,8 @@ mod tests {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
                 last_ingested_at TEXT,
-                is_active INTEGER NOT NULL DEFAULT 1
+                is_active INTEGER NOT NULL DEFAULT 1,
+                deleted_at TEXT
             );
             CREATE TABLE epg_programs (
                 id TEXT PRIMARY KEY,
@@ -360,6 +372,7 @@ mod tests {
         let db = crate::database::Database {
             connection: arc_connection.clone(),
             read_connection: arc_connection,
+            replica: None,
             database_type: crate::database::DatabaseType::SQLite,
             backend: DatabaseBackend::Sqlite,
             ingestion_config: IngestionConfig::default(),
@@ -8,6 +8,7 @@
 // current hard‑coded implementation and replace them minimally.
 
 use anyhow::Result;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
     QueryFilter, QueryOrder, Set,
@@ -65,6 +66,7 @@ impl FilterSeaOrmRepository {
             expression: Set(request.expression.clone()),
             created_at: Set(now),
             updated_at: Set(now),
+            deleted_at: Set(None),
         };
 
         let insert_result = active_model.insert(&*self.connection).await;
@@ -100,7 +102,10 @@ impl FilterSeaOrmRepository {
 
     /// Find filter by ID
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Filter>> {
-        let model = Filters::find_by_id(id).one(&*self.connection).await?;
+        let model = Filters::find_by_id(id)
+            .filter(filters::Column::DeletedAt.is_null())
+            .one(&*self.connection)
+            .await?;
 
         match model {
             Some(m) => Ok(Some(Filter {
@@ -120,6 +125,7 @@ impl FilterSeaOrmRepository {
     /// List all filters
     pub async fn list_all(&self) -> Result<Vec<Filter>> {
         let models = Filters::find()
+            .filter(filters::Column::DeletedAt.is_null())
             .order_by_asc(filters::Column::Name)
             .all(&*self.connection)
             .await?;
@@ -145,6 +151,7 @@ impl FilterSeaOrmRepository {
     /// Defensive handling for legacy UNIQUE(name) constraint still lingering.
     pub async fn update(&self, id: &Uuid, request: FilterUpdateRequest) -> Result<Filter> {
         let model = Filters::find_by_id(*id)
+            .filter(filters::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Filter not found"))?;
@@ -187,9 +194,16 @@ impl FilterSeaOrmRepository {
         })
     }
 
-    /// Delete filter
+    /// Move filter to the trash
     pub async fn delete(&self, id: &Uuid) -> Result<()> {
-        let result = Filters::delete_by_id(*id).exec(&*self.connection).await?;
+        let now = chrono::Utc::now();
+        let result = Filters::update_many()
+            .col_expr(filters::Column::DeletedAt, Expr::value(Some(now)))
+            .col_expr(filters::Column::UpdatedAt, Expr::value(now))
+            .filter(filters::Column::Id.eq(*id))
+            .filter(filters::Column::DeletedAt.is_null())
+            .exec(&*self.connection)
+            .await?;
         if result.rows_affected == 0 {
             return Err(anyhow::anyhow!("Filter not found"));
         }
@@ -356,6 +370,7 @@ impl FilterSeaOrmRepository {
                 "program_category",
                 "start_time",
                 "end_time",
+                "programme_duration",
                 "language",
                 "rating",
                 "episode_num",
@@ -439,6 +454,7 @@ impl FilterSeaOrmRepository {
                             tvg_chno: model.tvg_chno.clone(),
                             tvg_logo: model.tvg_logo.clone(),
                             tvg_shift: model.tvg_shift.clone(),
+                            epg_shift: None,
                             group_title: model.group_title.clone(),
                             channel_name: model.channel_name.clone(),
                             stream_url: model.stream_url.clone(),
@@ -449,6 +465,9 @@ impl FilterSeaOrmRepository {
                             last_probed_at: None,
                             created_at: model.created_at,
                             updated_at: model.updated_at,
+                            extra_attributes: crate::models::Channel::parse_extra_attributes(
+                                model.extra_attributes.as_deref(),
+                            ),
                         };
                         scanned += 1;
                         match proc.process_record(&channel_dto) {
@@ -639,6 +658,7 @@ mod epg_filter_test_endpoint_tests {
                 updated_at: Set(now),
                 last_ingested_at: Set(None),
                 is_active: Set(true),
+                deleted_at: Set(None),
             };
             let _ = src.insert(&db).await.expect("insert epg source");
         }
@@ -0,0 +1,251 @@
+//! SeaORM-based filter group repository implementation
+//!
+//! Stores named, ordered sets of filters that are attached to proxies as a unit.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{
+    filter_group_members, filter_groups,
+    prelude::{FilterGroupMembers, FilterGroups},
+};
+use crate::models::proxy_template::{FilterGroup, FilterGroupRequest};
+
+/// SeaORM-based repository for filter groups
+pub struct FilterGroupSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl FilterGroupSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// List all filter groups by name
+    pub async fn list_all(&self) -> Result<Vec<FilterGroup>> {
+        let groups = FilterGroups::find()
+            .order_by_asc(filter_groups::Column::Name)
+            .all(&*self.connection)
+            .await?;
+        let mut members = self
+            .members_of(groups.iter().map(|group| group.id).collect())
+            .await?;
+        Ok(groups
+            .into_iter()
+            .map(|group| {
+                let filter_ids = members.remove(&group.id).unwrap_or_default();
+                model_to_domain(group, filter_ids)
+            })
+            .collect())
+    }
+
+    pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<FilterGroup>> {
+        let Some(group) = FilterGroups::find_by_id(*id).one(&*self.connection).await? else {
+            return Ok(None);
+        };
+        let filter_ids = self
+            .members_of(vec![group.id])
+            .await?
+            .remove(&group.id)
+            .unwrap_or_default();
+        Ok(Some(model_to_domain(group, filter_ids)))
+    }
+
+    /// Load several groups, in the order of `ids`; unknown IDs are skipped
+    pub async fn find_many(&self, ids: &[Uuid]) -> Result<Vec<FilterGroup>> {
+        let mut groups = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(group) = self.find_by_id(id).await? {
+                groups.push(group);
+            }
+        }
+        Ok(groups)
+    }
+
+    pub async fn create(&self, request: FilterGroupRequest) -> Result<FilterGroup> {
+        let now = Utc::now();
+        let id = Uuid::new_v4();
+        let txn = self.connection.begin().await?;
+        filter_groups::ActiveModel {
+            id: Set(id),
+            name: Set(request.name.trim().to_string()),
+            description: Set(request.description),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&txn)
+        .await?;
+        replace_members(&txn, id, &request.filter_ids).await?;
+        txn.commit().await?;
+
+        self.find_by_id(&id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Filter group {id} disappeared after creation"))
+    }
+
+    /// Replace a group's name, description and members; `None` when it does not exist
+    pub async fn update(
+        &self,
+        id: &Uuid,
+        request: FilterGroupRequest,
+    ) -> Result<Option<FilterGroup>> {
+        let Some(existing) = FilterGroups::find_by_id(*id).one(&*self.connection).await? else {
+            return Ok(None);
+        };
+        let txn = self.connection.begin().await?;
+        let mut active_model: filter_groups::ActiveModel = existing.into();
+        active_model.name = Set(request.name.trim().to_string());
+        active_model.description = Set(request.description);
+        active_model.updated_at = Set(Utc::now());
+        active_model.update(&txn).await?;
+        replace_members(&txn, *id, &request.filter_ids).await?;
+        txn.commit().await?;
+
+        self.find_by_id(id).await
+    }
+
+    /// Delete a group (proxies keep the filters attached from it); false when it does not exist
+    pub async fn delete(&self, id: &Uuid) -> Result<bool> {
+        let result = FilterGroups::delete_by_id(*id)
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn members_of(&self, group_ids: Vec<Uuid>) -> Result<HashMap<Uuid, Vec<Uuid>>> {
+        let mut members: HashMap<Uuid, Vec<Uuid>> = Default::default();
+        if group_ids.is_empty() {
+            return Ok(members);
+        }
+        let models = FilterGroupMembers::find()
+            .filter(filter_group_members::Column::GroupId.is_in(group_ids))
+            .order_by_asc(filter_group_members::Column::PriorityOrder)
+            .all(&*self.connection)
+            .await?;
+        for model in models {
+            members
+                .entry(model.group_id)
+                .or_default()
+                .push(model.filter_id);
+        }
+        Ok(members)
+    }
+}
+
+async fn replace_members<C: sea_orm::ConnectionTrait>(
+    connection: &C,
+    group_id: Uuid,
+    filter_ids: &[Uuid],
+) -> Result<()> {
+    FilterGroupMembers::delete_many()
+        .filter(filter_group_members::Column::GroupId.eq(group_id))
+        .exec(connection)
+        .await?;
+
+    let mut seen = HashSet::new();
+    for (filter_id, priority_order) in filter_ids
+        .iter()
+        .filter(|filter_id| seen.insert(**filter_id))
+        .zip(1..)
+    {
+        filter_group_members::ActiveModel {
+            group_id: Set(group_id),
+            filter_id: Set(*filter_id),
+            priority_order: Set(priority_order),
+        }
+        .insert(connection)
+        .await?;
+    }
+    Ok(())
+}
+
+fn model_to_domain(model: filter_groups::Model, filter_ids: Vec<Uuid>) -> FilterGroup {
+    FilterGroup {
+        id: model.id,
+        name: model.name,
+        description: model.description,
+        filter_ids,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<FilterGroupSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        for sql in [
+            r"
+            CREATE TABLE filter_groups (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            ",
+            r"
+            CREATE TABLE filter_group_members (
+                group_id TEXT NOT NULL,
+                filter_id TEXT NOT NULL,
+                priority_order INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (group_id, filter_id)
+            );
+            ",
+        ] {
+            connection
+                .execute(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    sql.to_string(),
+                ))
+                .await?;
+        }
+        Ok(FilterGroupSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    #[tokio::test]
+    async fn test_group_members_keep_order() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let group = repo
+            .create(FilterGroupRequest {
+                name: " Sports ".to_string(),
+                description: None,
+                filter_ids: vec![b, a, b],
+            })
+            .await?;
+        assert_eq!(group.name, "Sports");
+        assert_eq!(group.filter_ids, vec![b, a]);
+
+        let updated = repo
+            .update(
+                &group.id,
+                FilterGroupRequest {
+                    name: "Sports".to_string(),
+                    description: Some("UK sport".to_string()),
+                    filter_ids: vec![c, a],
+                },
+            )
+            .await?
+            .unwrap();
+        assert_eq!(updated.filter_ids, vec![c, a]);
+        assert_eq!(repo.list_all().await?.len(), 1);
+
+        assert!(repo.delete(&group.id).await?);
+        assert!(repo.find_by_id(&group.id).await?.is_none());
+        assert!(!repo.delete(&group.id).await?);
+        Ok(())
+    }
+}
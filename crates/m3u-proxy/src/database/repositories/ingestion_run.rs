@@ -0,0 +1,229 @@
+//! SeaORM-based ingestion run repository implementation
+//!
+//! Stores the outcome of each source ingestion, keeping a bounded history per source.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{ingestion_runs, prelude::IngestionRuns};
+use crate::models::ingestion_run::{
+    IngestionRun, IngestionRunOutcome, IngestionRunStatus, IngestionSourceKind,
+};
+
+/// Number of runs kept per source; older runs are pruned when a new one is recorded
+pub const MAX_INGESTION_RUNS_PER_SOURCE: u64 = 500;
+
+/// SeaORM-based repository for ingestion run history
+#[derive(Clone)]
+pub struct IngestionRunSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl IngestionRunSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Record a finished ingestion run and prune the source's oldest runs
+    pub async fn record(
+        &self,
+        source_id: Uuid,
+        source_kind: IngestionSourceKind,
+        started_at: DateTime<Utc>,
+        outcome: IngestionRunOutcome,
+    ) -> Result<IngestionRun> {
+        let finished_at = Utc::now();
+        let status = if outcome.error.is_some() {
+            IngestionRunStatus::Failed
+        } else {
+            IngestionRunStatus::Success
+        };
+        let to_i32 = |count: usize| i32::try_from(count).unwrap_or(i32::MAX);
+
+        let active_model = ingestion_runs::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            source_id: Set(source_id),
+            source_kind: Set(source_kind),
+            status: Set(status),
+            started_at: Set(started_at),
+            finished_at: Set(finished_at),
+            duration_ms: Set((finished_at - started_at).num_milliseconds().max(0)),
+            bytes_downloaded: Set(outcome
+                .bytes_downloaded
+                .map(|bytes| i64::try_from(bytes).unwrap_or(i64::MAX))),
+            records_added: Set(outcome.changes.map(|c| to_i32(c.added))),
+            records_updated: Set(outcome.changes.map(|c| to_i32(c.updated))),
+            records_removed: Set(outcome.changes.map(|c| to_i32(c.removed))),
+            error: Set(outcome.error),
+        };
+        let model = active_model.insert(&*self.connection).await?;
+
+        self.prune(source_id, MAX_INGESTION_RUNS_PER_SOURCE).await?;
+        Ok(model_to_domain(model))
+    }
+
+    /// Page through a source's runs, newest first; returns the page and the total count
+    pub async fn list_for_source(
+        &self,
+        source_id: &Uuid,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<IngestionRun>, u64)> {
+        let query = IngestionRuns::find().filter(ingestion_runs::Column::SourceId.eq(*source_id));
+        let total = query.clone().count(&*self.connection).await?;
+        let models = query
+            .order_by_desc(ingestion_runs::Column::StartedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(&*self.connection)
+            .await?;
+        Ok((models.into_iter().map(model_to_domain).collect(), total))
+    }
+
+    /// Remove all runs of a source
+    pub async fn delete_for_source(&self, source_id: &Uuid) -> Result<u64> {
+        let result = IngestionRuns::delete_many()
+            .filter(ingestion_runs::Column::SourceId.eq(*source_id))
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// Delete a source's runs beyond the newest `keep`
+    async fn prune(&self, source_id: Uuid, keep: u64) -> Result<u64> {
+        let expired: Vec<Uuid> = IngestionRuns::find()
+            .select_only()
+            .column(ingestion_runs::Column::Id)
+            .filter(ingestion_runs::Column::SourceId.eq(source_id))
+            .order_by_desc(ingestion_runs::Column::StartedAt)
+            .offset(keep)
+            .into_tuple()
+            .all(&*self.connection)
+            .await?;
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        let result = IngestionRuns::delete_many()
+            .filter(ingestion_runs::Column::Id.is_in(expired))
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
+
+fn model_to_domain(model: ingestion_runs::Model) -> IngestionRun {
+    IngestionRun {
+        id: model.id,
+        source_id: model.source_id,
+        source_kind: model.source_kind,
+        status: model.status,
+        started_at: model.started_at,
+        finished_at: model.finished_at,
+        duration_ms: model.duration_ms,
+        bytes_downloaded: model.bytes_downloaded,
+        records_added: model.records_added,
+        records_updated: model.records_updated,
+        records_removed: model.records_removed,
+        error: model.error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ingestion_run::RecordChangeSummary;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<IngestionRunSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+            CREATE TABLE ingestion_runs (
+                id TEXT PRIMARY KEY,
+                source_id TEXT NOT NULL,
+                source_kind TEXT NOT NULL,
+                status TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                bytes_downloaded INTEGER,
+                records_added INTEGER,
+                records_updated INTEGER,
+                records_removed INTEGER,
+                error TEXT
+            );
+            "
+                .to_string(),
+            ))
+            .await?;
+        Ok(IngestionRunSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    #[tokio::test]
+    async fn test_record_and_page_history() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let source_id = Uuid::new_v4();
+        let first_start = Utc::now() - chrono::Duration::minutes(10);
+
+        repo.record(
+            source_id,
+            IngestionSourceKind::Stream,
+            first_start,
+            IngestionRunOutcome {
+                bytes_downloaded: Some(2048),
+                changes: Some(RecordChangeSummary {
+                    added: 10,
+                    updated: 0,
+                    removed: 0,
+                }),
+                error: None,
+            },
+        )
+        .await?;
+        let failed = repo
+            .record(
+                source_id,
+                IngestionSourceKind::Stream,
+                Utc::now(),
+                IngestionRunOutcome {
+                    error: Some("connection refused".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        repo.record(
+            Uuid::new_v4(),
+            IngestionSourceKind::Epg,
+            Utc::now(),
+            IngestionRunOutcome::default(),
+        )
+        .await?;
+
+        let (page, total) = repo.list_for_source(&source_id, 0, 1).await?;
+        assert_eq!(total, 2);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, failed.id);
+        assert_eq!(page[0].status, IngestionRunStatus::Failed);
+        assert_eq!(page[0].records_added, None);
+
+        let (page, _) = repo.list_for_source(&source_id, 1, 1).await?;
+        assert_eq!(page[0].status, IngestionRunStatus::Success);
+        assert_eq!(page[0].bytes_downloaded, Some(2048));
+        assert_eq!(page[0].records_added, Some(10));
+        assert!(page[0].duration_ms >= 10 * 60 * 1000);
+
+        assert_eq!(repo.prune(source_id, 1).await?, 1);
+        assert_eq!(repo.delete_for_source(&source_id).await?, 1);
+        Ok(())
+    }
+}
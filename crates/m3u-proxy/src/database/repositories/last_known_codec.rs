@@ -3,13 +3,19 @@
 //! This provides a database-agnostic repository for LastKnownCodec operations using SeaORM.
 
 use anyhow::Result;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, Set,
+};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::entities::{last_known_codecs, prelude::LastKnownCodecs};
 use crate::models::last_known_codec::{CreateLastKnownCodecRequest, LastKnownCodec, ProbeMethod};
 
+/// Stream URLs per container lookup query, keeping well under bind parameter limits
+const CONTAINER_LOOKUP_BATCH_SIZE: usize = 500;
+
 /// SeaORM-based repository for LastKnownCodec operations
 pub struct LastKnownCodecSeaOrmRepository {
     connection: Arc<DatabaseConnection>,
@@ -106,6 +112,31 @@ impl LastKnownCodecSeaOrmRepository {
         }
     }
 
+    /// Probed container formats (ffprobe `format_name`) of the given stream URLs
+    ///
+    /// URLs without a probe, or whose probe found no container, are absent from the map.
+    pub async fn find_container_formats(
+        &self,
+        stream_urls: &[&str],
+    ) -> Result<HashMap<String, String>> {
+        let mut formats = HashMap::new();
+        for batch in stream_urls.chunks(CONTAINER_LOOKUP_BATCH_SIZE) {
+            let rows: Vec<(String, Option<String>)> = LastKnownCodecs::find()
+                .select_only()
+                .column(last_known_codecs::Column::StreamUrl)
+                .column(last_known_codecs::Column::ContainerFormat)
+                .filter(last_known_codecs::Column::StreamUrl.is_in(batch.iter().copied()))
+                .into_tuple()
+                .all(&*self.connection)
+                .await?;
+            formats.extend(
+                rows.into_iter()
+                    .filter_map(|(url, format)| Some((url, format?))),
+            );
+        }
+        Ok(formats)
+    }
+
     /// Get the latest codec info for a stream (alias for find_by_stream_url)
     pub async fn get_latest_codec_info(&self, stream_url: &str) -> Result<Option<LastKnownCodec>> {
         self.find_by_stream_url(stream_url).await
@@ -4,23 +4,67 @@
 //! SQLite, PostgreSQL, and MySQL databases with database-specific optimizations.
 
 pub mod channel;
+pub mod channel_count_snapshot;
+pub mod channel_epg_mapping;
+pub mod channel_exclusion;
+pub mod channel_identity;
+pub mod channel_logo_assignment;
+pub mod channel_retention;
 pub mod data_mapping_rule;
+pub mod epg_channel_metadata;
 pub mod epg_program;
 pub mod epg_source;
 pub mod filter;
+pub mod filter_group;
+pub mod ingestion_run;
 pub mod last_known_codec;
+pub mod proxy_basic_auth;
+pub mod proxy_settings;
+pub mod proxy_template;
 pub mod relay;
+pub mod relay_channel_view;
+pub mod rule_version;
+pub mod share_link;
+pub mod source_balance_group;
+pub mod stream_headers;
 pub mod stream_proxy;
 pub mod stream_source;
+pub mod stream_source_mirror;
 pub mod traits;
+pub mod trash;
+pub mod url_rewrite_rule;
+pub mod virtual_channel;
+pub mod xtream_category_filter;
 
 // Re-export for convenience
 pub use channel::ChannelSeaOrmRepository;
+pub use channel_count_snapshot::ChannelCountSnapshotSeaOrmRepository;
+pub use channel_epg_mapping::ChannelEpgMappingSeaOrmRepository;
+pub use channel_exclusion::ChannelExclusionSeaOrmRepository;
+pub use channel_identity::ChannelIdentitySeaOrmRepository;
+pub use channel_logo_assignment::ChannelLogoAssignmentSeaOrmRepository;
+pub use channel_retention::ChannelRetentionSeaOrmRepository;
 pub use data_mapping_rule::DataMappingRuleSeaOrmRepository;
+pub use epg_channel_metadata::EpgChannelMetadataSeaOrmRepository;
 pub use epg_program::EpgProgramSeaOrmRepository;
 pub use epg_source::EpgSourceSeaOrmRepository;
 pub use filter::FilterSeaOrmRepository;
+pub use filter_group::FilterGroupSeaOrmRepository;
+pub use ingestion_run::IngestionRunSeaOrmRepository;
 pub use last_known_codec::LastKnownCodecSeaOrmRepository;
+pub use proxy_basic_auth::ProxyBasicAuthSeaOrmRepository;
+pub use proxy_settings::ProxySettingsSeaOrmRepository;
+pub use proxy_template::ProxyTemplateSeaOrmRepository;
 pub use relay::RelaySeaOrmRepository;
+pub use relay_channel_view::RelayChannelViewSeaOrmRepository;
+pub use rule_version::RuleVersionSeaOrmRepository;
+pub use share_link::ShareLinkSeaOrmRepository;
+pub use source_balance_group::SourceBalanceGroupSeaOrmRepository;
+pub use stream_headers::StreamHeadersSeaOrmRepository;
 pub use stream_proxy::StreamProxySeaOrmRepository;
 pub use stream_source::StreamSourceSeaOrmRepository;
+pub use stream_source_mirror::StreamSourceMirrorSeaOrmRepository;
+pub use trash::TrashSeaOrmRepository;
+pub use url_rewrite_rule::UrlRewriteRuleSeaOrmRepository;
+pub use virtual_channel::VirtualChannelSeaOrmRepository;
+pub use xtream_category_filter::XtreamCategoryFilterSeaOrmRepository;
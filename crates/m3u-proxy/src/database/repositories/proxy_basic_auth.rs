@@ -0,0 +1,85 @@
+//! SeaORM-based proxy Basic Auth repository
+//!
+//! Stores the hashed Basic Auth credentials guarding a proxy's playlist and guide.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::ProxyBasicAuth as ProxyBasicAuthEntity, proxy_basic_auth};
+use crate::models::proxy_basic_auth::ProxyBasicAuth;
+
+/// SeaORM-based repository for proxy Basic Auth credentials
+#[derive(Clone)]
+pub struct ProxyBasicAuthSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ProxyBasicAuthSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Credentials of a proxy, if it requires Basic Auth
+    pub async fn get(&self, proxy_id: &Uuid) -> Result<Option<ProxyBasicAuth>> {
+        let model = ProxyBasicAuthEntity::find_by_id(*proxy_id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model.map(model_to_domain))
+    }
+
+    /// Set or replace a proxy's credentials
+    pub async fn set(
+        &self,
+        proxy_id: &Uuid,
+        username: &str,
+        password_hash: &str,
+    ) -> Result<ProxyBasicAuth> {
+        let now = Utc::now();
+        let existing = ProxyBasicAuthEntity::find_by_id(*proxy_id)
+            .one(&*self.connection)
+            .await?;
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.username = Set(username.to_string());
+                active_model.password_hash = Set(password_hash.to_string());
+                active_model.updated_at = Set(now);
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                proxy_basic_auth::ActiveModel {
+                    proxy_id: Set(*proxy_id),
+                    username: Set(username.to_string()),
+                    password_hash: Set(password_hash.to_string()),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+
+    /// Remove a proxy's credentials; returns whether any were set
+    pub async fn delete(&self, proxy_id: &Uuid) -> Result<bool> {
+        let result = ProxyBasicAuthEntity::delete_by_id(*proxy_id)
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+fn model_to_domain(model: proxy_basic_auth::Model) -> ProxyBasicAuth {
+    ProxyBasicAuth {
+        proxy_id: model.proxy_id,
+        username: model.username,
+        password_hash: model.password_hash,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
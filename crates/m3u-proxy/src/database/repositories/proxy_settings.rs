@@ -0,0 +1,102 @@
+//! SeaORM-based per-proxy settings repository
+//!
+//! Stores the optional feature settings of proxies as JSON, keyed by
+//! [`ProxySetting::KEY`].
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    Set,
+};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::entities::{prelude::ProxySettings, proxy_settings};
+use crate::models::proxy_settings::ProxySetting;
+
+/// SeaORM-based repository for per-proxy settings
+#[derive(Clone)]
+pub struct ProxySettingsSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ProxySettingsSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// A proxy's setting, if it has one
+    pub async fn get<S: ProxySetting>(&self, proxy_id: &Uuid) -> Result<Option<S>> {
+        let model = ProxySettings::find_by_id((*proxy_id, S::KEY.to_string()))
+            .one(&*self.connection)
+            .await?;
+        model
+            .map(|model| {
+                serde_json::from_str(&model.value)
+                    .with_context(|| format!("invalid {} setting of proxy {proxy_id}", S::KEY))
+            })
+            .transpose()
+    }
+
+    /// Every proxy having the setting; unreadable rows are logged and skipped
+    pub async fn list<S: ProxySetting>(&self) -> Result<Vec<(Uuid, S)>> {
+        let models = ProxySettings::find()
+            .filter(proxy_settings::Column::Key.eq(S::KEY))
+            .all(&*self.connection)
+            .await?;
+        Ok(models
+            .into_iter()
+            .filter_map(|model| match serde_json::from_str(&model.value) {
+                Ok(setting) => Some((model.proxy_id, setting)),
+                Err(e) => {
+                    warn!(
+                        "Ignoring invalid {} setting of proxy {}: {}",
+                        S::KEY,
+                        model.proxy_id,
+                        e
+                    );
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Set or replace a proxy's setting
+    pub async fn set<S: ProxySetting>(&self, proxy_id: &Uuid, setting: &S) -> Result<()> {
+        let value = serde_json::to_string(setting)?;
+        let now = Utc::now();
+        let existing = ProxySettings::find_by_id((*proxy_id, S::KEY.to_string()))
+            .one(&*self.connection)
+            .await?;
+        match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.value = Set(value);
+                active_model.updated_at = Set(now);
+                active_model.update(&*self.connection).await?;
+            }
+            None => {
+                proxy_settings::ActiveModel {
+                    proxy_id: Set(*proxy_id),
+                    key: Set(S::KEY.to_string()),
+                    value: Set(value),
+                    updated_at: Set(now),
+                }
+                .insert(&*self.connection)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove a proxy's setting; returns whether it had one
+    pub async fn delete<S: ProxySetting>(&self, proxy_id: &Uuid) -> Result<bool> {
+        let result = ProxySettings::delete_by_id((*proxy_id, S::KEY.to_string()))
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
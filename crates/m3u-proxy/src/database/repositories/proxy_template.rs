@@ -0,0 +1,100 @@
+//! SeaORM-based proxy template repository implementation
+//!
+//! Stores saved proxy settings, serialized as JSON, that new proxies can be created from.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryOrder, Set};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::ProxyTemplates, proxy_templates};
+use crate::models::proxy_template::{ProxyTemplate, ProxyTemplateRequest};
+
+/// SeaORM-based repository for proxy templates
+pub struct ProxyTemplateSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ProxyTemplateSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// List all templates by name
+    pub async fn list_all(&self) -> Result<Vec<ProxyTemplate>> {
+        ProxyTemplates::find()
+            .order_by_asc(proxy_templates::Column::Name)
+            .all(&*self.connection)
+            .await?
+            .into_iter()
+            .map(model_to_domain)
+            .collect()
+    }
+
+    pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<ProxyTemplate>> {
+        ProxyTemplates::find_by_id(*id)
+            .one(&*self.connection)
+            .await?
+            .map(model_to_domain)
+            .transpose()
+    }
+
+    pub async fn create(&self, request: ProxyTemplateRequest) -> Result<ProxyTemplate> {
+        let now = Utc::now();
+        let model = proxy_templates::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(request.name.trim().to_string()),
+            description: Set(request.description),
+            settings: Set(serde_json::to_string(&request.settings)?),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&*self.connection)
+        .await?;
+        model_to_domain(model)
+    }
+
+    /// Replace a template; `None` when it does not exist
+    pub async fn update(
+        &self,
+        id: &Uuid,
+        request: ProxyTemplateRequest,
+    ) -> Result<Option<ProxyTemplate>> {
+        let Some(existing) = ProxyTemplates::find_by_id(*id)
+            .one(&*self.connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let mut active_model: proxy_templates::ActiveModel = existing.into();
+        active_model.name = Set(request.name.trim().to_string());
+        active_model.description = Set(request.description);
+        active_model.settings = Set(serde_json::to_string(&request.settings)?);
+        active_model.updated_at = Set(Utc::now());
+        let model = active_model.update(&*self.connection).await?;
+        model_to_domain(model).map(Some)
+    }
+
+    /// Delete a template (proxies created from it are unaffected); false when it does not exist
+    pub async fn delete(&self, id: &Uuid) -> Result<bool> {
+        let result = ProxyTemplates::delete_by_id(*id)
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+fn model_to_domain(model: proxy_templates::Model) -> Result<ProxyTemplate> {
+    let settings = serde_json::from_str(&model.settings)
+        .with_context(|| format!("Invalid settings stored for proxy template {}", model.id))?;
+    Ok(ProxyTemplate {
+        id: model.id,
+        name: model.name,
+        description: model.description,
+        settings,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    })
+}
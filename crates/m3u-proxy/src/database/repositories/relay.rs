@@ -49,6 +49,15 @@ impl RelaySeaOrmRepository {
             segment_duration: Set(request.segment_duration),
             max_segments: Set(request.max_segments),
             input_timeout: Set(request.input_timeout.unwrap_or(30)),
+            audio_languages: Set(request.audio_languages.as_deref().and_then(join_languages)),
+            audio_track_index: Set(request.audio_track_index.map(|v| v as i32)),
+            subtitle_languages: Set(request
+                .subtitle_languages
+                .as_deref()
+                .and_then(join_languages)),
+            subtitle_track_index: Set(request.subtitle_track_index.map(|v| v as i32)),
+            subtitle_mode: Set(request.subtitle_mode.map(|mode| mode.to_string())),
+            subtitle_codec: Set(request.subtitle_codec.map(|codec| codec.to_string())),
             is_system_default: Set(false),
             is_active: Set(true),
             created_at: Set(now),
@@ -103,6 +112,24 @@ impl RelaySeaOrmRepository {
         if let Some(audio_codec) = request.audio_codec {
             active_model.audio_codec = Set(audio_codec.to_string());
         }
+        if let Some(audio_languages) = request.audio_languages {
+            active_model.audio_languages = Set(join_languages(&audio_languages));
+        }
+        if let Some(audio_track_index) = request.audio_track_index {
+            active_model.audio_track_index = Set(Some(audio_track_index as i32));
+        }
+        if let Some(subtitle_languages) = request.subtitle_languages {
+            active_model.subtitle_languages = Set(join_languages(&subtitle_languages));
+        }
+        if let Some(subtitle_track_index) = request.subtitle_track_index {
+            active_model.subtitle_track_index = Set(Some(subtitle_track_index as i32));
+        }
+        if let Some(subtitle_mode) = request.subtitle_mode {
+            active_model.subtitle_mode = Set(Some(subtitle_mode.to_string()));
+        }
+        if let Some(subtitle_codec) = request.subtitle_codec {
+            active_model.subtitle_codec = Set(Some(subtitle_codec.to_string()));
+        }
 
         active_model.updated_at = Set(chrono::Utc::now());
 
@@ -123,7 +150,9 @@ impl RelaySeaOrmRepository {
 
     /// Convert SeaORM model to domain model (only enum parsing needed)
     fn model_to_domain(&self, model: relay_profiles::Model) -> RelayProfile {
-        use crate::models::relay::{AudioCodec, RelayOutputFormat, VideoCodec};
+        use crate::models::relay::{
+            AudioCodec, RelayOutputFormat, SubtitleCodec, SubtitleMode, VideoCodec,
+        };
         use std::str::FromStr;
 
         RelayProfile {
@@ -146,6 +175,19 @@ impl RelaySeaOrmRepository {
             segment_duration: model.segment_duration,
             max_segments: model.max_segments,
             input_timeout: model.input_timeout,
+            audio_languages: split_languages(model.audio_languages.as_deref()),
+            audio_track_index: model.audio_track_index.map(|v| v as u32),
+            subtitle_languages: split_languages(model.subtitle_languages.as_deref()),
+            subtitle_track_index: model.subtitle_track_index.map(|v| v as u32),
+            subtitle_mode: model
+                .subtitle_mode
+                .as_deref()
+                .and_then(|mode| SubtitleMode::from_str(mode).ok())
+                .unwrap_or_default(),
+            subtitle_codec: model
+                .subtitle_codec
+                .as_deref()
+                .and_then(|codec| SubtitleCodec::from_str(codec).ok()),
             is_system_default: model.is_system_default,
             is_active: model.is_active,
             created_at: model.created_at,
@@ -153,3 +195,24 @@ impl RelaySeaOrmRepository {
         }
     }
 }
+
+/// Store a language priority list as a comma-separated column (None when empty)
+fn join_languages(languages: &[String]) -> Option<String> {
+    let joined = languages
+        .iter()
+        .map(|language| language.trim())
+        .filter(|language| !language.is_empty())
+        .collect::<Vec<_>>()
+        .join(",");
+    (!joined.is_empty()).then_some(joined)
+}
+
+fn split_languages(languages: Option<&str>) -> Vec<String> {
+    languages
+        .unwrap_or_default()
+        .split(',')
+        .map(|language| language.trim())
+        .filter(|language| !language.is_empty())
+        .map(|language| language.to_string())
+        .collect()
+}
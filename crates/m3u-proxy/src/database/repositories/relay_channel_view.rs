@@ -0,0 +1,124 @@
+//! SeaORM-based relay channel view repository
+//!
+//! Keeps the client connections served per proxy channel, ranking channels for the
+//! "most watched" relay keep-alive.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::RelayChannelViews, relay_channel_views};
+
+/// SeaORM-based repository for relay channel view counts
+#[derive(Clone)]
+pub struct RelayChannelViewSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl RelayChannelViewSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Add client connections to the counts of (proxy, channel) pairs
+    pub async fn add_views(&self, views: &HashMap<(Uuid, Uuid), u64>) -> Result<()> {
+        let now = Utc::now();
+        for (&(proxy_id, channel_id), &count) in views {
+            let count = i64::try_from(count).unwrap_or(i64::MAX);
+            let existing = RelayChannelViews::find_by_id((proxy_id, channel_id))
+                .one(&*self.connection)
+                .await?;
+            match existing {
+                Some(model) => {
+                    let view_count = model.view_count.saturating_add(count);
+                    let mut active_model = model.into_active_model();
+                    active_model.view_count = Set(view_count);
+                    active_model.last_viewed_at = Set(now);
+                    active_model.update(&*self.connection).await?;
+                }
+                None => {
+                    relay_channel_views::ActiveModel {
+                        proxy_id: Set(proxy_id),
+                        channel_id: Set(channel_id),
+                        view_count: Set(count),
+                        last_viewed_at: Set(now),
+                    }
+                    .insert(&*self.connection)
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The `limit` channels of a proxy with the most client connections
+    pub async fn most_watched(&self, proxy_id: &Uuid, limit: usize) -> Result<Vec<Uuid>> {
+        let models = RelayChannelViews::find()
+            .filter(relay_channel_views::Column::ProxyId.eq(*proxy_id))
+            .order_by_desc(relay_channel_views::Column::ViewCount)
+            .order_by_asc(relay_channel_views::Column::ChannelId)
+            .limit(limit as u64)
+            .all(&*self.connection)
+            .await?;
+        Ok(models.into_iter().map(|model| model.channel_id).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<RelayChannelViewSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+            CREATE TABLE relay_channel_views (
+                proxy_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                view_count INTEGER NOT NULL DEFAULT 0,
+                last_viewed_at TEXT NOT NULL,
+                PRIMARY KEY (proxy_id, channel_id)
+            );
+            "
+                .to_string(),
+            ))
+            .await?;
+        Ok(RelayChannelViewSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    #[tokio::test]
+    async fn test_most_watched_accumulates_views() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let proxy_id = Uuid::from_u128(1);
+        let other_proxy_id = Uuid::from_u128(2);
+        let (a, b, c) = (
+            Uuid::from_u128(10),
+            Uuid::from_u128(11),
+            Uuid::from_u128(12),
+        );
+
+        repo.add_views(&HashMap::from([
+            ((proxy_id, a), 3),
+            ((proxy_id, b), 2),
+            ((other_proxy_id, a), 100),
+        ]))
+        .await?;
+        repo.add_views(&HashMap::from([((proxy_id, b), 5), ((proxy_id, c), 3)]))
+            .await?;
+
+        assert_eq!(repo.most_watched(&proxy_id, 2).await?, vec![b, a]);
+        assert_eq!(repo.most_watched(&proxy_id, 10).await?, vec![b, a, c]);
+        assert!(repo.most_watched(&Uuid::from_u128(3), 5).await?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! SeaORM-based rule version repository implementation
+//!
+//! Records each saved state of a filter or data mapping rule as a numbered version.
+
+use anyhow::Result;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::RuleVersions, rule_versions};
+use crate::models::rule_version::{RuleChange, RuleKind, RuleSnapshot, RuleVersion};
+
+/// SeaORM-based repository for rule versions
+pub struct RuleVersionSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl RuleVersionSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Versions of a rule, newest first
+    pub async fn list(&self, kind: RuleKind, rule_id: &Uuid) -> Result<Vec<RuleVersion>> {
+        let models = RuleVersions::find()
+            .filter(rule_versions::Column::RuleType.eq(kind.as_str()))
+            .filter(rule_versions::Column::RuleId.eq(*rule_id))
+            .order_by_desc(rule_versions::Column::Version)
+            .all(&*self.connection)
+            .await?;
+        models.into_iter().map(model_to_domain).collect()
+    }
+
+    /// A version of a rule
+    pub async fn find(
+        &self,
+        kind: RuleKind,
+        rule_id: &Uuid,
+        version: i32,
+    ) -> Result<Option<RuleVersion>> {
+        RuleVersions::find()
+            .filter(rule_versions::Column::RuleType.eq(kind.as_str()))
+            .filter(rule_versions::Column::RuleId.eq(*rule_id))
+            .filter(rule_versions::Column::Version.eq(version))
+            .one(&*self.connection)
+            .await?
+            .map(model_to_domain)
+            .transpose()
+    }
+
+    /// Latest version of a rule
+    pub async fn latest(&self, kind: RuleKind, rule_id: &Uuid) -> Result<Option<RuleVersion>> {
+        RuleVersions::find()
+            .filter(rule_versions::Column::RuleType.eq(kind.as_str()))
+            .filter(rule_versions::Column::RuleId.eq(*rule_id))
+            .order_by_desc(rule_versions::Column::Version)
+            .one(&*self.connection)
+            .await?
+            .map(model_to_domain)
+            .transpose()
+    }
+
+    /// Record the saved state of a rule as its next version
+    ///
+    /// A rule last saved before versioning existed has no versions; `previous`, its state
+    /// before this change, is then recorded first so the change can still be reverted.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record(
+        &self,
+        kind: RuleKind,
+        rule_id: Uuid,
+        previous: Option<&RuleSnapshot>,
+        current: &RuleSnapshot,
+        change: RuleChange,
+        restored_version: Option<i32>,
+        author: Option<String>,
+    ) -> Result<RuleVersion> {
+        let txn = self.connection.begin().await?;
+        let latest = RuleVersions::find()
+            .filter(rule_versions::Column::RuleType.eq(kind.as_str()))
+            .filter(rule_versions::Column::RuleId.eq(rule_id))
+            .order_by_desc(rule_versions::Column::Version)
+            .one(&txn)
+            .await?;
+
+        let (mut version, mut previous_expression) = match latest {
+            Some(latest) => (latest.version, latest.expression),
+            None => (0, None),
+        };
+        if version == 0
+            && let Some(previous) = previous
+        {
+            version = 1;
+            previous_expression = previous.expression.clone();
+            version_model(kind, rule_id, 1, previous, None, RuleChange::Created)
+                .insert(&txn)
+                .await?;
+        }
+
+        let mut model = version_model(
+            kind,
+            rule_id,
+            version + 1,
+            current,
+            previous_expression,
+            change,
+        );
+        model.restored_version = Set(restored_version);
+        model.author = Set(author);
+        let model = model.insert(&txn).await?;
+        txn.commit().await?;
+        model_to_domain(model)
+    }
+}
+
+/// Version of a rule in a saved state, dated when it was saved
+fn version_model(
+    kind: RuleKind,
+    rule_id: Uuid,
+    version: i32,
+    snapshot: &RuleSnapshot,
+    previous_expression: Option<String>,
+    change: RuleChange,
+) -> rule_versions::ActiveModel {
+    rule_versions::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        rule_type: Set(kind.as_str().to_string()),
+        rule_id: Set(rule_id),
+        version: Set(version),
+        name: Set(snapshot.name.clone()),
+        expression: Set(snapshot.expression.clone()),
+        previous_expression: Set(previous_expression),
+        definition: Set(snapshot.definition.to_string()),
+        change: Set(change.as_str().to_string()),
+        restored_version: Set(None),
+        author: Set(None),
+        created_at: Set(snapshot.saved_at),
+    }
+}
+
+fn model_to_domain(model: rule_versions::Model) -> Result<RuleVersion> {
+    Ok(RuleVersion {
+        id: model.id,
+        rule_kind: RuleKind::parse(&model.rule_type)
+            .ok_or_else(|| anyhow::anyhow!("Unknown rule type '{}'", model.rule_type))?,
+        rule_id: model.rule_id,
+        version: model.version,
+        name: model.name,
+        expression: model.expression,
+        previous_expression: model.previous_expression,
+        definition: serde_json::from_str(&model.definition)?,
+        change: RuleChange::parse(&model.change)
+            .ok_or_else(|| anyhow::anyhow!("Unknown rule change '{}'", model.change))?,
+        restored_version: model.restored_version,
+        author: model.author,
+        created_at: model.created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<RuleVersionSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE rule_versions (
+                    id TEXT PRIMARY KEY,
+                    rule_type TEXT NOT NULL,
+                    rule_id TEXT NOT NULL,
+                    version INTEGER NOT NULL,
+                    name TEXT NOT NULL,
+                    expression TEXT,
+                    previous_expression TEXT,
+                    definition TEXT NOT NULL,
+                    change TEXT NOT NULL,
+                    restored_version INTEGER,
+                    author TEXT,
+                    created_at TEXT NOT NULL
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        Ok(RuleVersionSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    fn snapshot(expression: &str) -> RuleSnapshot {
+        RuleSnapshot {
+            name: "UK".to_string(),
+            expression: Some(expression.to_string()),
+            definition: serde_json::json!({ "expression": expression }),
+            saved_at: Utc::now() - Duration::days(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_seeds_unversioned_rules() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let rule_id = Uuid::new_v4();
+
+        // The state before the first recorded change becomes version 1
+        let updated = repo
+            .record(
+                RuleKind::Filter,
+                rule_id,
+                Some(&snapshot("a")),
+                &snapshot("b"),
+                RuleChange::Updated,
+                None,
+                Some("alice".to_string()),
+            )
+            .await?;
+        assert_eq!(updated.version, 2);
+        assert_eq!(updated.previous_expression.as_deref(), Some("a"));
+
+        let reverted = repo
+            .record(
+                RuleKind::Filter,
+                rule_id,
+                Some(&snapshot("b")),
+                &snapshot("a"),
+                RuleChange::Reverted,
+                Some(1),
+                None,
+            )
+            .await?;
+        assert_eq!(reverted.version, 3);
+        assert_eq!(reverted.previous_expression.as_deref(), Some("b"));
+
+        let versions: Vec<_> = repo
+            .list(RuleKind::Filter, &rule_id)
+            .await?
+            .into_iter()
+            .map(|v| (v.version, v.change, v.author))
+            .collect();
+        assert_eq!(
+            versions,
+            [
+                (3, RuleChange::Reverted, None),
+                (2, RuleChange::Updated, Some("alice".to_string())),
+                (1, RuleChange::Created, None),
+            ]
+        );
+        assert!(
+            repo.list(RuleKind::DataMappingRule, &rule_id)
+                .await?
+                .is_empty()
+        );
+        assert_eq!(
+            repo.find(RuleKind::Filter, &rule_id, 1)
+                .await?
+                .and_then(|v| v.expression),
+            Some("a".to_string())
+        );
+        Ok(())
+    }
+}
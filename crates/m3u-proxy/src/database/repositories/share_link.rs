@@ -0,0 +1,219 @@
+//! SeaORM-based proxy share link repository implementation
+//!
+//! Stores temporary share links and enforces their use limits atomically.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::ProxyShareLinks, proxy_share_links};
+use crate::models::share_link::ProxyShareLink;
+
+/// SeaORM-based repository for proxy share links
+pub struct ShareLinkSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ShareLinkSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Create a share link with a fresh random token
+    pub async fn create(
+        &self,
+        proxy_id: Uuid,
+        name: Option<String>,
+        expires_at: DateTime<Utc>,
+        max_uses: Option<i32>,
+        max_streams: Option<i32>,
+    ) -> Result<ProxyShareLink> {
+        let active_model = proxy_share_links::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            proxy_id: Set(proxy_id),
+            token: Set(generate_token()),
+            name: Set(name),
+            expires_at: Set(expires_at),
+            max_uses: Set(max_uses),
+            use_count: Set(0),
+            max_streams: Set(max_streams),
+            last_used_at: Set(None),
+            revoked_at: Set(None),
+            created_at: Set(Utc::now()),
+        };
+
+        let model = active_model.insert(&*self.connection).await?;
+        Ok(model_to_domain(model))
+    }
+
+    /// List all share links of a proxy, newest first
+    pub async fn list_for_proxy(&self, proxy_id: &Uuid) -> Result<Vec<ProxyShareLink>> {
+        let models = ProxyShareLinks::find()
+            .filter(proxy_share_links::Column::ProxyId.eq(*proxy_id))
+            .order_by_desc(proxy_share_links::Column::CreatedAt)
+            .all(&*self.connection)
+            .await?;
+        Ok(models.into_iter().map(model_to_domain).collect())
+    }
+
+    /// Find a share link by ID
+    pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<ProxyShareLink>> {
+        let model = ProxyShareLinks::find_by_id(*id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model.map(model_to_domain))
+    }
+
+    /// Find a share link by its token
+    pub async fn find_by_token(&self, token: &str) -> Result<Option<ProxyShareLink>> {
+        let model = ProxyShareLinks::find()
+            .filter(proxy_share_links::Column::Token.eq(token))
+            .one(&*self.connection)
+            .await?;
+        Ok(model.map(model_to_domain))
+    }
+
+    /// Revoke a share link; returns false when the link does not exist
+    pub async fn revoke(&self, id: &Uuid) -> Result<bool> {
+        let Some(model) = ProxyShareLinks::find_by_id(*id)
+            .one(&*self.connection)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        if model.revoked_at.is_none() {
+            let mut active_model: proxy_share_links::ActiveModel = model.into();
+            active_model.revoked_at = Set(Some(Utc::now()));
+            active_model.update(&*self.connection).await?;
+        }
+        Ok(true)
+    }
+
+    /// Count one use of a link
+    ///
+    /// The increment only applies while the link is under its use limit and not revoked, so
+    /// concurrent fetches cannot exceed `max_uses`. Returns false when no use was recorded.
+    pub async fn record_use(&self, id: &Uuid) -> Result<bool> {
+        let result = ProxyShareLinks::update_many()
+            .col_expr(
+                proxy_share_links::Column::UseCount,
+                Expr::col(proxy_share_links::Column::UseCount).add(1),
+            )
+            .col_expr(
+                proxy_share_links::Column::LastUsedAt,
+                Expr::value(Some(Utc::now())),
+            )
+            .filter(proxy_share_links::Column::Id.eq(*id))
+            .filter(proxy_share_links::Column::RevokedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(proxy_share_links::Column::MaxUses.is_null())
+                    .add(
+                        Expr::col(proxy_share_links::Column::UseCount)
+                            .lt(Expr::col(proxy_share_links::Column::MaxUses)),
+                    ),
+            )
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected == 1)
+    }
+}
+
+/// 256-bit random URL-safe token
+fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn model_to_domain(model: proxy_share_links::Model) -> ProxyShareLink {
+    ProxyShareLink {
+        id: model.id,
+        proxy_id: model.proxy_id,
+        token: model.token,
+        name: model.name,
+        expires_at: model.expires_at,
+        max_uses: model.max_uses,
+        use_count: model.use_count,
+        max_streams: model.max_streams,
+        last_used_at: model.last_used_at,
+        revoked_at: model.revoked_at,
+        created_at: model.created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::share_link::ShareLinkStatus;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<ShareLinkSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+            CREATE TABLE proxy_share_links (
+                id TEXT PRIMARY KEY,
+                proxy_id TEXT NOT NULL,
+                token TEXT NOT NULL UNIQUE,
+                name TEXT,
+                expires_at TEXT NOT NULL,
+                max_uses INTEGER,
+                use_count INTEGER NOT NULL DEFAULT 0,
+                max_streams INTEGER,
+                last_used_at TEXT,
+                revoked_at TEXT,
+                created_at TEXT NOT NULL
+            );
+            "
+                .to_string(),
+            ))
+            .await?;
+        Ok(ShareLinkSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    #[tokio::test]
+    async fn test_share_link_use_limit_and_revoke() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let proxy_id = Uuid::new_v4();
+        let expires_at = Utc::now() + chrono::Duration::hours(72);
+
+        let link = repo
+            .create(
+                proxy_id,
+                Some("guest".to_string()),
+                expires_at,
+                Some(2),
+                Some(1),
+            )
+            .await?;
+        assert_eq!(link.token.len(), 64);
+        assert_eq!(repo.list_for_proxy(&proxy_id).await?.len(), 1);
+
+        assert!(repo.record_use(&link.id).await?);
+        assert!(repo.record_use(&link.id).await?);
+        assert!(!repo.record_use(&link.id).await?);
+
+        let stored = repo.find_by_token(&link.token).await?.unwrap();
+        assert_eq!(stored.use_count, 2);
+        assert_eq!(stored.max_streams, Some(1));
+        assert!(stored.last_used_at.is_some());
+        assert_eq!(stored.status_at(Utc::now()), ShareLinkStatus::Exhausted);
+
+        let unlimited = repo.create(proxy_id, None, expires_at, None, None).await?;
+        assert!(repo.record_use(&unlimited.id).await?);
+        assert!(repo.revoke(&unlimited.id).await?);
+        assert!(!repo.record_use(&unlimited.id).await?);
+        assert!(!repo.revoke(&Uuid::new_v4()).await?);
+
+        Ok(())
+    }
+}
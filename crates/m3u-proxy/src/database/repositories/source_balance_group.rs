@@ -0,0 +1,273 @@
+//! SeaORM-based stream source balance group repository
+//!
+//! Stores which stream sources are lines of the same provider and loads a group's lines
+//! for streaming and status reporting.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{
+    prelude::{StreamSourceBalanceGroups, StreamSources},
+    stream_source_balance_groups, stream_sources,
+};
+use crate::models::source_balancing::{
+    BalanceGroupStatus, BalanceLineStatus, BalancedLine, SourceBalanceGroup,
+};
+
+/// SeaORM-based repository for stream source balance groups
+pub struct SourceBalanceGroupSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl SourceBalanceGroupSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Balance group of a source (ungrouped when none is configured)
+    pub async fn get(&self, source_id: &Uuid) -> Result<SourceBalanceGroup> {
+        let model = StreamSourceBalanceGroups::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model
+            .map(model_to_domain)
+            .unwrap_or_else(|| SourceBalanceGroup::ungrouped(*source_id)))
+    }
+
+    /// Put a source into a group; `None` removes it from its group
+    pub async fn set(
+        &self,
+        source_id: &Uuid,
+        group_name: Option<&str>,
+    ) -> Result<SourceBalanceGroup> {
+        let existing = StreamSourceBalanceGroups::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+
+        let Some(group_name) = group_name else {
+            if let Some(model) = existing {
+                model.into_active_model().delete(&*self.connection).await?;
+            }
+            return Ok(SourceBalanceGroup::ungrouped(*source_id));
+        };
+
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.group_name = Set(group_name.to_string());
+                active_model.updated_at = Set(Utc::now());
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                stream_source_balance_groups::ActiveModel {
+                    source_id: Set(*source_id),
+                    group_name: Set(group_name.to_string()),
+                    updated_at: Set(Utc::now()),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+
+    /// Active lines of the group a source belongs to, including the source itself
+    ///
+    /// Empty when the source is not balanced. Loads are left at zero for the caller to fill
+    /// in from the live sessions.
+    pub async fn lines_for_source(&self, source_id: &Uuid) -> Result<Vec<BalancedLine>> {
+        let Some(membership) = StreamSourceBalanceGroups::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+        let member_ids: Vec<Uuid> = StreamSourceBalanceGroups::find()
+            .filter(stream_source_balance_groups::Column::GroupName.eq(membership.group_name))
+            .all(&*self.connection)
+            .await?
+            .into_iter()
+            .map(|member| member.source_id)
+            .collect();
+        let sources = StreamSources::find()
+            .filter(stream_sources::Column::DeletedAt.is_null())
+            .filter(stream_sources::Column::Id.is_in(member_ids))
+            .filter(stream_sources::Column::IsActive.eq(true))
+            .order_by_asc(stream_sources::Column::Id)
+            .all(&*self.connection)
+            .await?;
+        Ok(sources
+            .into_iter()
+            .map(|source| BalancedLine {
+                source_id: source.id,
+                max_concurrent_streams: source.max_concurrent_streams,
+                active_streams: 0,
+            })
+            .collect())
+    }
+
+    /// All groups with their lines, by group name
+    ///
+    /// Loads are left at zero for the caller to fill in from the live sessions.
+    pub async fn list_groups(&self) -> Result<Vec<BalanceGroupStatus>> {
+        let memberships = StreamSourceBalanceGroups::find()
+            .all(&*self.connection)
+            .await?;
+        let sources: BTreeMap<Uuid, stream_sources::Model> = StreamSources::find()
+            .filter(stream_sources::Column::DeletedAt.is_null())
+            .filter(
+                stream_sources::Column::Id
+                    .is_in(memberships.iter().map(|membership| membership.source_id)),
+            )
+            .all(&*self.connection)
+            .await?
+            .into_iter()
+            .map(|source| (source.id, source))
+            .collect();
+
+        let mut groups: BTreeMap<String, Vec<BalanceLineStatus>> = BTreeMap::new();
+        for membership in memberships {
+            let Some(source) = sources.get(&membership.source_id) else {
+                continue;
+            };
+            groups
+                .entry(membership.group_name)
+                .or_default()
+                .push(BalanceLineStatus {
+                    source_id: source.id,
+                    source_name: source.name.clone(),
+                    is_active: source.is_active,
+                    max_concurrent_streams: source.max_concurrent_streams,
+                    active_streams: 0,
+                });
+        }
+        Ok(groups
+            .into_iter()
+            .map(|(group_name, mut lines)| {
+                lines.sort_by(|a, b| a.source_name.cmp(&b.source_name));
+                BalanceGroupStatus { group_name, lines }
+            })
+            .collect())
+    }
+}
+
+fn model_to_domain(model: stream_source_balance_groups::Model) -> SourceBalanceGroup {
+    SourceBalanceGroup {
+        source_id: model.source_id,
+        group_name: Some(model.group_name),
+        updated_at: Some(model.updated_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<SourceBalanceGroupSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE stream_sources (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    source_type TEXT NOT NULL,
+                    url TEXT NOT NULL,
+                    max_concurrent_streams INTEGER NOT NULL,
+                    update_cron TEXT NOT NULL,
+                    username TEXT,
+                    password TEXT,
+                    field_map TEXT,
+                    ignore_channel_numbers INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    last_ingested_at TEXT,
+                    is_active INTEGER NOT NULL DEFAULT 1,
+                    deleted_at TEXT
+                );
+                CREATE TABLE stream_source_balance_groups (
+                    source_id TEXT PRIMARY KEY,
+                    group_name TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        Ok(SourceBalanceGroupSeaOrmRepository::new(Arc::new(
+            connection,
+        )))
+    }
+
+    async fn insert_source(
+        repo: &SourceBalanceGroupSeaOrmRepository,
+        name: &str,
+        max_concurrent_streams: i32,
+        is_active: bool,
+    ) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        repo.connection
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                "INSERT INTO stream_sources (id, name, source_type, url, max_concurrent_streams, update_cron, created_at, updated_at, is_active) VALUES (?, ?, 'xtream', 'http://provider.example', ?, '0 0 * * * * *', ?, ?, ?)",
+                [
+                    id.into(),
+                    name.into(),
+                    max_concurrent_streams.into(),
+                    now.into(),
+                    now.into(),
+                    is_active.into(),
+                ],
+            ))
+            .await?;
+        Ok(id)
+    }
+
+    #[tokio::test]
+    async fn test_lines_of_a_group() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let first = insert_source(&repo, "Line 1", 1, true).await?;
+        let second = insert_source(&repo, "Line 2", 2, true).await?;
+        let inactive = insert_source(&repo, "Line 3", 2, false).await?;
+        let other = insert_source(&repo, "Other", 1, true).await?;
+
+        assert!(repo.lines_for_source(&first).await?.is_empty());
+        for source in [first, second, inactive] {
+            repo.set(&source, Some("provider")).await?;
+        }
+        repo.set(&other, Some("other")).await?;
+
+        let mut lines: Vec<Uuid> = repo
+            .lines_for_source(&second)
+            .await?
+            .into_iter()
+            .map(|line| line.source_id)
+            .collect();
+        lines.sort();
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(lines, expected);
+
+        let groups = repo.list_groups().await?;
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].group_name, "provider");
+        assert_eq!(groups[1].lines.len(), 3);
+
+        // Leaving the group stops balancing the source
+        assert!(repo.set(&first, None).await?.group_name.is_none());
+        assert!(repo.lines_for_source(&first).await?.is_empty());
+        assert_eq!(repo.lines_for_source(&second).await?.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! SeaORM-based stream source streaming header repository
+//!
+//! Stores the User-Agent, Referer and extra headers sent upstream when streaming a
+//! source's channels.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::StreamSourceStreamHeaders, stream_source_stream_headers};
+use crate::models::stream_headers::{StreamHeaderOverrides, StreamHeaderOverridesRequest};
+
+/// SeaORM-based repository for stream source streaming headers
+pub struct StreamHeadersSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl StreamHeadersSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Header overrides of a source (empty when none are configured)
+    pub async fn get(&self, source_id: &Uuid) -> Result<StreamHeaderOverrides> {
+        let model = StreamSourceStreamHeaders::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model
+            .map(model_to_domain)
+            .unwrap_or_else(|| StreamHeaderOverrides::none(*source_id)))
+    }
+
+    /// Replace a source's header overrides; an empty request removes them
+    pub async fn set(
+        &self,
+        source_id: &Uuid,
+        request: StreamHeaderOverridesRequest,
+    ) -> Result<StreamHeaderOverrides> {
+        let existing = StreamSourceStreamHeaders::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+
+        if request.user_agent.is_none() && request.referer.is_none() && request.headers.is_empty() {
+            if let Some(model) = existing {
+                model.into_active_model().delete(&*self.connection).await?;
+            }
+            return Ok(StreamHeaderOverrides::none(*source_id));
+        }
+
+        let headers = serde_json::to_string(&request.headers)?;
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.user_agent = Set(request.user_agent);
+                active_model.referer = Set(request.referer);
+                active_model.headers = Set(headers);
+                active_model.updated_at = Set(Utc::now());
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                stream_source_stream_headers::ActiveModel {
+                    source_id: Set(*source_id),
+                    user_agent: Set(request.user_agent),
+                    referer: Set(request.referer),
+                    headers: Set(headers),
+                    updated_at: Set(Utc::now()),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+}
+
+fn model_to_domain(model: stream_source_stream_headers::Model) -> StreamHeaderOverrides {
+    StreamHeaderOverrides {
+        source_id: model.source_id,
+        user_agent: model.user_agent,
+        referer: model.referer,
+        headers: serde_json::from_str::<BTreeMap<String, String>>(&model.headers)
+            .unwrap_or_default(),
+        updated_at: Some(model.updated_at),
+    }
+}
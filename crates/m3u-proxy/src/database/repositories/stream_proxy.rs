@@ -4,15 +4,20 @@
 //! that works across SQLite, PostgreSQL, and MySQL databases.
 
 use anyhow::Result;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter,
-    QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
 };
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::entities::{prelude::*, stream_proxies};
-use crate::models::{StreamProxy, StreamProxyCreateRequest, StreamProxyUpdateRequest};
+use crate::models::proxy_order::{
+    ProxyOrderKind, ProxyOrderMove, ProxyReorderOutcome, apply_move, ordering_version,
+};
+use crate::models::{
+    ChannelNumberBlock, StreamProxy, StreamProxyCreateRequest, StreamProxyUpdateRequest,
+};
 
 /// SeaORM-based StreamProxy repository
 #[derive(Clone)]
@@ -48,6 +53,17 @@ impl StreamProxySeaOrmRepository {
             cache_channel_logos: Set(request.cache_channel_logos),
             cache_program_logos: Set(request.cache_program_logos),
             relay_profile_id: Set(request.relay_profile_id),
+            sign_stream_urls: Set(request.sign_stream_urls),
+            output_profile: Set(request.output_profile),
+            backup_streams: Set(request.backup_streams),
+            offline_slate: Set(request.offline_slate),
+            epg_languages: Set(request.epg_languages.clone()),
+            regeneration_debounce_seconds: Set(request.regeneration_debounce_seconds),
+            channel_number_blocks: Set(ChannelNumberBlock::to_column(
+                &request.channel_number_blocks,
+            )),
+            epg_timezone: Set(request.epg_timezone.clone()),
+            deleted_at: Set(None),
         };
 
         let model = active_model.insert(&*self.connection).await?;
@@ -68,12 +84,23 @@ impl StreamProxySeaOrmRepository {
             cache_channel_logos: model.cache_channel_logos,
             cache_program_logos: model.cache_program_logos,
             relay_profile_id: model.relay_profile_id,
+            sign_stream_urls: model.sign_stream_urls,
+            output_profile: model.output_profile,
+            backup_streams: model.backup_streams,
+            offline_slate: model.offline_slate,
+            epg_languages: model.epg_languages,
+            regeneration_debounce_seconds: model.regeneration_debounce_seconds,
+            channel_number_blocks: ChannelNumberBlock::from_column(
+                model.channel_number_blocks.as_deref(),
+            ),
+            epg_timezone: model.epg_timezone,
         })
     }
 
     /// Find stream proxy by ID
     pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<StreamProxy>> {
         let model = StreamProxies::find_by_id(*id)
+            .filter(stream_proxies::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?;
         match model {
@@ -94,6 +121,16 @@ impl StreamProxySeaOrmRepository {
                 cache_channel_logos: m.cache_channel_logos,
                 cache_program_logos: m.cache_program_logos,
                 relay_profile_id: m.relay_profile_id,
+                sign_stream_urls: m.sign_stream_urls,
+                output_profile: m.output_profile,
+                backup_streams: m.backup_streams,
+                offline_slate: m.offline_slate,
+                epg_languages: m.epg_languages,
+                regeneration_debounce_seconds: m.regeneration_debounce_seconds,
+                channel_number_blocks: ChannelNumberBlock::from_column(
+                    m.channel_number_blocks.as_deref(),
+                ),
+                epg_timezone: m.epg_timezone,
             })),
             None => Ok(None),
         }
@@ -102,6 +139,7 @@ impl StreamProxySeaOrmRepository {
     /// List all stream proxies
     pub async fn list_all(&self) -> Result<Vec<StreamProxy>> {
         let models = StreamProxies::find()
+            .filter(stream_proxies::Column::DeletedAt.is_null())
             .order_by_asc(stream_proxies::Column::Name)
             .all(&*self.connection)
             .await?;
@@ -125,6 +163,16 @@ impl StreamProxySeaOrmRepository {
                 cache_channel_logos: m.cache_channel_logos,
                 cache_program_logos: m.cache_program_logos,
                 relay_profile_id: m.relay_profile_id,
+                sign_stream_urls: m.sign_stream_urls,
+                output_profile: m.output_profile,
+                backup_streams: m.backup_streams,
+                offline_slate: m.offline_slate,
+                epg_languages: m.epg_languages,
+                regeneration_debounce_seconds: m.regeneration_debounce_seconds,
+                channel_number_blocks: ChannelNumberBlock::from_column(
+                    m.channel_number_blocks.as_deref(),
+                ),
+                epg_timezone: m.epg_timezone,
             });
         }
         Ok(results)
@@ -137,12 +185,14 @@ impl StreamProxySeaOrmRepository {
         request: StreamProxyUpdateRequest,
     ) -> Result<StreamProxy> {
         let model = StreamProxies::find_by_id(*id)
+            .filter(stream_proxies::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Stream proxy not found"))?;
 
         let mut active_model: stream_proxies::ActiveModel = model.into();
 
+        set_requested_settings(&mut active_model, &request);
         active_model.name = Set(request.name);
         active_model.description = Set(request.description);
         active_model.proxy_mode = Set(request.proxy_mode);
@@ -175,17 +225,32 @@ impl StreamProxySeaOrmRepository {
             cache_channel_logos: updated_model.cache_channel_logos,
             cache_program_logos: updated_model.cache_program_logos,
             relay_profile_id: updated_model.relay_profile_id,
+            sign_stream_urls: updated_model.sign_stream_urls,
+            output_profile: updated_model.output_profile,
+            backup_streams: updated_model.backup_streams,
+            offline_slate: updated_model.offline_slate,
+            epg_languages: updated_model.epg_languages,
+            regeneration_debounce_seconds: updated_model.regeneration_debounce_seconds,
+            channel_number_blocks: ChannelNumberBlock::from_column(
+                updated_model.channel_number_blocks.as_deref(),
+            ),
+            epg_timezone: updated_model.epg_timezone,
         })
     }
 
-    /// Delete stream proxy
+    /// Move stream proxy to the trash
     pub async fn delete(&self, id: &Uuid) -> Result<()> {
-        let model = StreamProxies::find_by_id(*id)
-            .one(&*self.connection)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Stream proxy not found"))?;
-
-        model.delete(&*self.connection).await?;
+        let now = chrono::Utc::now();
+        let result = StreamProxies::update_many()
+            .col_expr(stream_proxies::Column::DeletedAt, Expr::value(Some(now)))
+            .col_expr(stream_proxies::Column::UpdatedAt, Expr::value(now))
+            .filter(stream_proxies::Column::Id.eq(*id))
+            .filter(stream_proxies::Column::DeletedAt.is_null())
+            .exec(&*self.connection)
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(anyhow::anyhow!("Stream proxy not found"));
+        }
         Ok(())
     }
 
@@ -194,6 +259,7 @@ impl StreamProxySeaOrmRepository {
         use sea_orm::{ActiveModelTrait, EntityTrait, Set};
 
         let model = StreamProxies::find_by_id(proxy_id)
+            .filter(stream_proxies::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Stream proxy not found"))?;
@@ -265,6 +331,17 @@ impl StreamProxySeaOrmRepository {
             cache_channel_logos: Set(request.cache_channel_logos),
             cache_program_logos: Set(request.cache_program_logos),
             relay_profile_id: Set(request.relay_profile_id),
+            sign_stream_urls: Set(request.sign_stream_urls),
+            output_profile: Set(request.output_profile),
+            backup_streams: Set(request.backup_streams),
+            offline_slate: Set(request.offline_slate),
+            epg_languages: Set(request.epg_languages.clone()),
+            regeneration_debounce_seconds: Set(request.regeneration_debounce_seconds),
+            channel_number_blocks: Set(ChannelNumberBlock::to_column(
+                &request.channel_number_blocks,
+            )),
+            epg_timezone: Set(request.epg_timezone.clone()),
+            deleted_at: Set(None),
         };
 
         let model = active_model.insert(&txn).await?;
@@ -285,6 +362,16 @@ impl StreamProxySeaOrmRepository {
             cache_channel_logos: model.cache_channel_logos,
             cache_program_logos: model.cache_program_logos,
             relay_profile_id: model.relay_profile_id,
+            sign_stream_urls: model.sign_stream_urls,
+            output_profile: model.output_profile,
+            backup_streams: model.backup_streams,
+            offline_slate: model.offline_slate,
+            epg_languages: model.epg_languages,
+            regeneration_debounce_seconds: model.regeneration_debounce_seconds,
+            channel_number_blocks: ChannelNumberBlock::from_column(
+                model.channel_number_blocks.as_deref(),
+            ),
+            epg_timezone: model.epg_timezone,
         };
 
         // Create proxy_sources relationships
@@ -345,12 +432,14 @@ impl StreamProxySeaOrmRepository {
 
         // First update the proxy itself within the transaction
         let model = StreamProxies::find_by_id(*id)
+            .filter(stream_proxies::Column::DeletedAt.is_null())
             .one(&txn)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Stream proxy not found"))?;
 
         let mut active_model: stream_proxies::ActiveModel = model.into();
 
+        set_requested_settings(&mut active_model, &request);
         active_model.name = Set(request.name);
         active_model.description = Set(request.description);
         active_model.proxy_mode = Set(request.proxy_mode);
@@ -437,9 +526,192 @@ impl StreamProxySeaOrmRepository {
             cache_channel_logos: updated_model.cache_channel_logos,
             cache_program_logos: updated_model.cache_program_logos,
             relay_profile_id: updated_model.relay_profile_id,
+            sign_stream_urls: updated_model.sign_stream_urls,
+            output_profile: updated_model.output_profile,
+            backup_streams: updated_model.backup_streams,
+            offline_slate: updated_model.offline_slate,
+            epg_languages: updated_model.epg_languages,
+            regeneration_debounce_seconds: updated_model.regeneration_debounce_seconds,
+            channel_number_blocks: ChannelNumberBlock::from_column(
+                updated_model.channel_number_blocks.as_deref(),
+            ),
+            epg_timezone: updated_model.epg_timezone,
         })
     }
 
+    /// IDs of one of a proxy's ordered lists, highest priority first
+    pub async fn get_ordering(&self, proxy_id: Uuid, kind: ProxyOrderKind) -> Result<Vec<Uuid>> {
+        Self::load_ordering(&*self.connection, proxy_id, kind).await
+    }
+
+    /// Move one entry of a proxy's ordered list if the order still matches
+    /// `expected_version`, renumbering priorities 1..n
+    pub async fn reorder(
+        &self,
+        proxy_id: Uuid,
+        kind: ProxyOrderKind,
+        item_id: Uuid,
+        movement: ProxyOrderMove,
+        expected_version: &str,
+    ) -> Result<ProxyReorderOutcome> {
+        use crate::entities::{proxy_epg_sources, proxy_filters, proxy_sources};
+        use sea_orm::TransactionTrait;
+
+        let txn = self.connection.begin().await?;
+
+        // Touch the proxy row first so concurrent reorders of this proxy serialise
+        // before either reads the current order
+        StreamProxies::update_many()
+            .col_expr(
+                stream_proxies::Column::UpdatedAt,
+                sea_orm::sea_query::Expr::value(chrono::Utc::now()),
+            )
+            .filter(stream_proxies::Column::Id.eq(proxy_id))
+            .exec(&txn)
+            .await?;
+
+        let mut ids = Self::load_ordering(&txn, proxy_id, kind).await?;
+        if ordering_version(&ids) != expected_version {
+            return Ok(ProxyReorderOutcome::Conflict(ids));
+        }
+        match apply_move(&mut ids, item_id, movement) {
+            Ok(true) => {}
+            Ok(false) => return Ok(ProxyReorderOutcome::UnknownItem),
+            Err(message) => return Ok(ProxyReorderOutcome::Invalid(message)),
+        }
+
+        for (index, id) in ids.iter().enumerate() {
+            let priority = sea_orm::sea_query::Expr::value(index as i32 + 1);
+            match kind {
+                ProxyOrderKind::StreamSources => {
+                    ProxySources::update_many()
+                        .col_expr(proxy_sources::Column::PriorityOrder, priority)
+                        .filter(proxy_sources::Column::ProxyId.eq(proxy_id))
+                        .filter(proxy_sources::Column::SourceId.eq(*id))
+                        .exec(&txn)
+                        .await?;
+                }
+                ProxyOrderKind::EpgSources => {
+                    ProxyEpgSources::update_many()
+                        .col_expr(proxy_epg_sources::Column::PriorityOrder, priority)
+                        .filter(proxy_epg_sources::Column::ProxyId.eq(proxy_id))
+                        .filter(proxy_epg_sources::Column::EpgSourceId.eq(*id))
+                        .exec(&txn)
+                        .await?;
+                }
+                ProxyOrderKind::Filters => {
+                    ProxyFilters::update_many()
+                        .col_expr(proxy_filters::Column::PriorityOrder, priority)
+                        .filter(proxy_filters::Column::ProxyId.eq(proxy_id))
+                        .filter(proxy_filters::Column::FilterId.eq(*id))
+                        .exec(&txn)
+                        .await?;
+                }
+            }
+        }
+
+        txn.commit().await?;
+        Ok(ProxyReorderOutcome::Applied(ids))
+    }
+
+    async fn load_ordering<C: sea_orm::ConnectionTrait>(
+        connection: &C,
+        proxy_id: Uuid,
+        kind: ProxyOrderKind,
+    ) -> Result<Vec<Uuid>> {
+        use crate::entities::{proxy_epg_sources, proxy_filters, proxy_sources};
+
+        // Ties (possible after wholesale updates of filter priorities) break on ID so the
+        // order, and therefore its version, is stable
+        let ids = match kind {
+            ProxyOrderKind::StreamSources => ProxySources::find()
+                .filter(proxy_sources::Column::ProxyId.eq(proxy_id))
+                .order_by_asc(proxy_sources::Column::PriorityOrder)
+                .order_by_asc(proxy_sources::Column::SourceId)
+                .all(connection)
+                .await?
+                .into_iter()
+                .map(|model| model.source_id)
+                .collect(),
+            ProxyOrderKind::EpgSources => ProxyEpgSources::find()
+                .filter(proxy_epg_sources::Column::ProxyId.eq(proxy_id))
+                .order_by_asc(proxy_epg_sources::Column::PriorityOrder)
+                .order_by_asc(proxy_epg_sources::Column::EpgSourceId)
+                .all(connection)
+                .await?
+                .into_iter()
+                .map(|model| model.epg_source_id)
+                .collect(),
+            ProxyOrderKind::Filters => ProxyFilters::find()
+                .filter(proxy_filters::Column::ProxyId.eq(proxy_id))
+                .order_by_asc(proxy_filters::Column::PriorityOrder)
+                .order_by_asc(proxy_filters::Column::FilterId)
+                .all(connection)
+                .await?
+                .into_iter()
+                .map(|model| model.filter_id)
+                .collect(),
+        };
+        Ok(ids)
+    }
+
+    /// Append filters to the end of a proxy's filter list, skipping ones already attached;
+    /// returns the filters that were added
+    pub async fn attach_filters(&self, proxy_id: Uuid, filter_ids: &[Uuid]) -> Result<Vec<Uuid>> {
+        use crate::entities::proxy_filters;
+        use sea_orm::TransactionTrait;
+
+        let txn = self.connection.begin().await?;
+        let existing = ProxyFilters::find()
+            .filter(proxy_filters::Column::ProxyId.eq(proxy_id))
+            .all(&txn)
+            .await?;
+        let mut next_priority = existing
+            .iter()
+            .map(|model| model.priority_order)
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        let mut attached = Vec::new();
+        for filter_id in filter_ids {
+            if existing.iter().any(|model| model.filter_id == *filter_id)
+                || attached.contains(filter_id)
+            {
+                continue;
+            }
+            proxy_filters::ActiveModel {
+                proxy_id: Set(proxy_id),
+                filter_id: Set(*filter_id),
+                priority_order: Set(next_priority),
+                is_active: Set(true),
+                created_at: Set(chrono::Utc::now()),
+            }
+            .insert(&txn)
+            .await?;
+            next_priority += 1;
+            attached.push(*filter_id);
+        }
+
+        txn.commit().await?;
+        Ok(attached)
+    }
+
+    /// Remove filters from a proxy; returns how many were attached
+    pub async fn detach_filters(&self, proxy_id: Uuid, filter_ids: &[Uuid]) -> Result<u64> {
+        use crate::entities::proxy_filters;
+
+        if filter_ids.is_empty() {
+            return Ok(0);
+        }
+        let result = ProxyFilters::delete_many()
+            .filter(proxy_filters::Column::ProxyId.eq(proxy_id))
+            .filter(proxy_filters::Column::FilterId.is_in(filter_ids.to_vec()))
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
     /// Get proxy filters for a stream proxy
     pub async fn get_proxy_filters(
         &self,
@@ -535,13 +807,21 @@ impl StreamProxySeaOrmRepository {
         channel_id: Uuid,
     ) -> Result<Option<crate::models::Channel>> {
         use crate::database::repositories::channel::ChannelSeaOrmRepository;
+        use crate::database::repositories::virtual_channel::VirtualChannelSeaOrmRepository;
         use crate::entities::{prelude::ProxySources, proxy_sources};
 
         // First check if the channel exists and get its source_id
         let channel_repo = ChannelSeaOrmRepository::new(self.connection.clone());
         let channel = match channel_repo.find_by_id(&channel_id).await? {
             Some(channel) => channel,
-            None => return Ok(None),
+            None => {
+                // Not an ingested channel; it may be a virtual channel assigned to the proxy
+                let virtual_repo = VirtualChannelSeaOrmRepository::new(self.connection.clone());
+                return Ok(virtual_repo
+                    .find_active_for_proxy(&proxy_id, &channel_id)
+                    .await?
+                    .map(|virtual_channel| virtual_channel.to_channel()));
+            }
         };
 
         // Then check if this channel's source is linked to the proxy
@@ -557,3 +837,177 @@ impl StreamProxySeaOrmRepository {
         }
     }
 }
+
+/// Set the settings an update request carries, keeping the stored value of those it leaves
+/// unset
+fn set_requested_settings(
+    active_model: &mut stream_proxies::ActiveModel,
+    request: &StreamProxyUpdateRequest,
+) {
+    if let Some(sign) = request.sign_stream_urls {
+        active_model.sign_stream_urls = Set(sign);
+    }
+    if let Some(profile) = request.output_profile {
+        active_model.output_profile = Set(profile);
+    }
+    if let Some(mode) = request.backup_streams {
+        active_model.backup_streams = Set(mode);
+    }
+    if let Some(mode) = request.offline_slate {
+        active_model.offline_slate = Set(mode);
+    }
+    if let Some(languages) = &request.epg_languages {
+        active_model.epg_languages = Set(languages.clone());
+    }
+    if let Some(seconds) = request.regeneration_debounce_seconds {
+        active_model.regeneration_debounce_seconds = Set(seconds);
+    }
+    if let Some(blocks) = &request.channel_number_blocks {
+        active_model.channel_number_blocks = Set(ChannelNumberBlock::to_column(blocks));
+    }
+    if let Some(timezone) = &request.epg_timezone {
+        active_model.epg_timezone = Set(timezone.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BackupStreamMode, OfflineSlateMode, OutputProfile, StreamProxyMode};
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<StreamProxySeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+            CREATE TABLE stream_proxies (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                proxy_mode TEXT NOT NULL,
+                upstream_timeout INTEGER,
+                buffer_size INTEGER,
+                max_concurrent_streams INTEGER,
+                starting_channel_number INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                last_generated_at TEXT,
+                is_active INTEGER NOT NULL,
+                auto_regenerate INTEGER NOT NULL,
+                cache_channel_logos INTEGER NOT NULL,
+                cache_program_logos INTEGER NOT NULL,
+                relay_profile_id TEXT,
+                sign_stream_urls INTEGER NOT NULL,
+                output_profile TEXT NOT NULL,
+                backup_streams TEXT NOT NULL,
+                offline_slate TEXT NOT NULL,
+                epg_languages TEXT,
+                regeneration_debounce_seconds INTEGER,
+                channel_number_blocks TEXT,
+                epg_timezone TEXT,
+                deleted_at TEXT
+            );
+            "
+                .to_string(),
+            ))
+            .await?;
+        Ok(StreamProxySeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    fn create_request() -> StreamProxyCreateRequest {
+        StreamProxyCreateRequest {
+            name: "Test Proxy".to_string(),
+            description: None,
+            proxy_mode: StreamProxyMode::Proxy,
+            upstream_timeout: None,
+            buffer_size: None,
+            max_concurrent_streams: None,
+            starting_channel_number: 1,
+            stream_sources: Vec::new(),
+            epg_sources: Vec::new(),
+            filters: Vec::new(),
+            is_active: true,
+            auto_regenerate: false,
+            cache_channel_logos: true,
+            cache_program_logos: false,
+            relay_profile_id: None,
+            sign_stream_urls: true,
+            output_profile: OutputProfile::Kodi,
+            backup_streams: BackupStreamMode::Group,
+            offline_slate: OfflineSlateMode::Generated,
+            epg_languages: Some("de,en".to_string()),
+            regeneration_debounce_seconds: Some(120),
+            channel_number_blocks: vec![ChannelNumberBlock {
+                group: "News".to_string(),
+                start: 100,
+            }],
+            epg_timezone: Some("Europe/London".to_string()),
+        }
+    }
+
+    /// An update changing only the name, as sent by clients unaware of the newer settings
+    fn rename_request(name: &str) -> StreamProxyUpdateRequest {
+        StreamProxyUpdateRequest {
+            name: name.to_string(),
+            description: None,
+            proxy_mode: StreamProxyMode::Proxy,
+            upstream_timeout: None,
+            buffer_size: None,
+            max_concurrent_streams: None,
+            starting_channel_number: 1,
+            stream_sources: Vec::new(),
+            epg_sources: Vec::new(),
+            filters: Vec::new(),
+            is_active: true,
+            auto_regenerate: false,
+            cache_channel_logos: true,
+            cache_program_logos: false,
+            relay_profile_id: None,
+            sign_stream_urls: None,
+            output_profile: None,
+            backup_streams: None,
+            offline_slate: None,
+            epg_languages: None,
+            regeneration_debounce_seconds: None,
+            channel_number_blocks: None,
+            epg_timezone: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_settings_it_leaves_unset() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let created = repo.create(create_request()).await?;
+
+        let updated = repo.update(&created.id, rename_request("Renamed")).await?;
+        assert_eq!(updated.name, "Renamed");
+        assert!(updated.sign_stream_urls);
+        assert_eq!(updated.output_profile, OutputProfile::Kodi);
+        assert_eq!(updated.backup_streams, BackupStreamMode::Group);
+        assert_eq!(updated.offline_slate, OfflineSlateMode::Generated);
+        assert_eq!(updated.epg_languages.as_deref(), Some("de,en"));
+        assert_eq!(updated.regeneration_debounce_seconds, Some(120));
+        assert_eq!(updated.channel_number_blocks, created.channel_number_blocks);
+        assert_eq!(updated.epg_timezone.as_deref(), Some("Europe/London"));
+
+        let cleared = repo
+            .update(
+                &created.id,
+                StreamProxyUpdateRequest {
+                    epg_languages: Some(None),
+                    regeneration_debounce_seconds: Some(None),
+                    channel_number_blocks: Some(Vec::new()),
+                    epg_timezone: Some(None),
+                    ..rename_request("Renamed")
+                },
+            )
+            .await?;
+        assert_eq!(cleared.epg_languages, None);
+        assert_eq!(cleared.regeneration_debounce_seconds, None);
+        assert!(cleared.channel_number_blocks.is_empty());
+        assert_eq!(cleared.epg_timezone, None);
+        Ok(())
+    }
+}
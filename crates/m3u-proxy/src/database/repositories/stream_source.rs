@@ -3,6 +3,7 @@
 //! This provides a database-agnostic repository for StreamSource operations using SeaORM.
 
 use anyhow::Result;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
 };
@@ -49,6 +50,7 @@ impl StreamSourceSeaOrmRepository {
             updated_at: Set(now),
             last_ingested_at: Set(None),
             is_active: Set(true),
+            deleted_at: Set(None),
         };
 
         // For now, repository methods work normally - circuit breaker available but not required
@@ -75,6 +77,7 @@ impl StreamSourceSeaOrmRepository {
     /// Find a stream source by ID
     pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<StreamSource>> {
         let model = StreamSources::find_by_id(*id)
+            .filter(stream_sources::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?;
 
@@ -102,6 +105,7 @@ impl StreamSourceSeaOrmRepository {
     /// Find all stream sources
     pub async fn find_all(&self) -> Result<Vec<StreamSource>> {
         let models = StreamSources::find()
+            .filter(stream_sources::Column::DeletedAt.is_null())
             .order_by_asc(stream_sources::Column::Name)
             .all(&*self.connection)
             .await?;
@@ -134,6 +138,7 @@ impl StreamSourceSeaOrmRepository {
         source_type: StreamSourceType,
     ) -> Result<Vec<StreamSource>> {
         let models = StreamSources::find()
+            .filter(stream_sources::Column::DeletedAt.is_null())
             .filter(stream_sources::Column::Url.eq(url))
             .filter(stream_sources::Column::SourceType.eq(source_type))
             .filter(stream_sources::Column::IsActive.eq(true))
@@ -173,6 +178,7 @@ impl StreamSourceSeaOrmRepository {
 
         // Find the existing source
         let existing = StreamSources::find_by_id(id.to_owned())
+            .filter(stream_sources::Column::DeletedAt.is_null())
             .one(&*self.connection)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Stream source not found"))?;
@@ -203,6 +209,7 @@ impl StreamSourceSeaOrmRepository {
     /// Find active stream sources only
     pub async fn find_active(&self) -> Result<Vec<StreamSource>> {
         let models = StreamSources::find()
+            .filter(stream_sources::Column::DeletedAt.is_null())
             .filter(stream_sources::Column::IsActive.eq(true))
             .order_by_asc(stream_sources::Column::Name)
             .all(&*self.connection)
@@ -278,9 +285,14 @@ impl StreamSourceSeaOrmRepository {
         })
     }
 
-    /// Delete a stream source
+    /// Move a stream source to the trash
     pub async fn delete(&self, id: &Uuid) -> Result<()> {
-        let result = StreamSources::delete_by_id(*id)
+        let now = chrono::Utc::now();
+        let result = StreamSources::update_many()
+            .col_expr(stream_sources::Column::DeletedAt, Expr::value(Some(now)))
+            .col_expr(stream_sources::Column::UpdatedAt, Expr::value(now))
+            .filter(stream_sources::Column::Id.eq(*id))
+            .filter(stream_sources::Column::DeletedAt.is_null())
             .exec(&*self.connection)
             .await?;
         if result.rows_affected == 0 {
@@ -381,7 +393,8 @@ mod tests {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
                 last_ingested_at TEXT,
-                is_active INTEGER NOT NULL DEFAULT 1
+                is_active INTEGER NOT NULL DEFAULT 1,
+                deleted_at TEXT
             );
             CREATE TABLE channels (
                 id TEXT PRIMARY KEY,
@@ -395,7 +408,8 @@ mod tests {
                 group_title TEXT,
                 stream_url TEXT NOT NULL,
                 created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                extra_attributes TEXT
             );
             "
                 .to_string(),
@@ -406,6 +420,7 @@ mod tests {
         let db = crate::database::Database {
             connection: arc_connection.clone(),
             read_connection: arc_connection,
+            replica: None,
             database_type: crate::database::DatabaseType::SQLite,
             backend: DatabaseBackend::Sqlite,
             ingestion_config: IngestionConfig::default(),
@@ -0,0 +1,168 @@
+//! SeaORM-based stream source mirror repository
+//!
+//! Stores, per stream source, the mirror URLs tried when its own URL fails and the URL that
+//! served its last successful refresh.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    Set, sea_query::Expr,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{
+    prelude::StreamSourceMirrors as StreamSourceMirrorsEntity, stream_source_mirrors,
+};
+use crate::models::stream_source_mirror::{StreamSourceMirrors, StreamSourceMirrorsRequest};
+
+/// SeaORM-based repository for stream source mirrors
+#[derive(Clone)]
+pub struct StreamSourceMirrorSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl StreamSourceMirrorSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Mirrors of a source (empty when none are configured)
+    pub async fn get(&self, source_id: &Uuid) -> Result<StreamSourceMirrors> {
+        let model = StreamSourceMirrorsEntity::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model
+            .map(model_to_domain)
+            .unwrap_or_else(|| StreamSourceMirrors::none(*source_id)))
+    }
+
+    /// Replace a source's mirror URLs; a request without URLs removes them
+    pub async fn set(
+        &self,
+        source_id: &Uuid,
+        request: StreamSourceMirrorsRequest,
+    ) -> Result<StreamSourceMirrors> {
+        let existing = StreamSourceMirrorsEntity::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+
+        if request.mirror_urls.is_empty() {
+            if let Some(model) = existing {
+                model.into_active_model().delete(&*self.connection).await?;
+            }
+            return Ok(StreamSourceMirrors::none(*source_id));
+        }
+
+        let mirror_urls = serde_json::to_string(&request.mirror_urls)?;
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.mirror_urls = Set(mirror_urls);
+                active_model.updated_at = Set(Utc::now());
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                stream_source_mirrors::ActiveModel {
+                    source_id: Set(*source_id),
+                    mirror_urls: Set(mirror_urls),
+                    last_served_url: Set(None),
+                    last_served_at: Set(None),
+                    updated_at: Set(Utc::now()),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+
+    /// Record the URL that served a successful refresh of a source with mirrors
+    pub async fn record_served(&self, source_id: &Uuid, url: &str) -> Result<()> {
+        StreamSourceMirrorsEntity::update_many()
+            .col_expr(
+                stream_source_mirrors::Column::LastServedUrl,
+                Expr::value(url.to_string()),
+            )
+            .col_expr(
+                stream_source_mirrors::Column::LastServedAt,
+                Expr::value(Utc::now()),
+            )
+            .filter(stream_source_mirrors::Column::SourceId.eq(*source_id))
+            .exec(&*self.connection)
+            .await?;
+        Ok(())
+    }
+}
+
+fn model_to_domain(model: stream_source_mirrors::Model) -> StreamSourceMirrors {
+    StreamSourceMirrors {
+        source_id: model.source_id,
+        mirror_urls: serde_json::from_str(&model.mirror_urls).unwrap_or_default(),
+        last_served_url: model.last_served_url,
+        last_served_at: model.last_served_at,
+        updated_at: Some(model.updated_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<StreamSourceMirrorSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE stream_source_mirrors (
+                    source_id TEXT PRIMARY KEY,
+                    mirror_urls TEXT NOT NULL,
+                    last_served_url TEXT,
+                    last_served_at TEXT,
+                    updated_at TEXT NOT NULL
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        Ok(StreamSourceMirrorSeaOrmRepository::new(Arc::new(
+            connection,
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_set_record_and_clear() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let source_id = Uuid::new_v4();
+        assert!(repo.get(&source_id).await?.mirror_urls.is_empty());
+
+        // Nothing is recorded for a source without mirrors
+        repo.record_served(&source_id, "http://a.example/").await?;
+        assert_eq!(repo.get(&source_id).await?.last_served_url, None);
+
+        repo.set(
+            &source_id,
+            StreamSourceMirrorsRequest {
+                mirror_urls: vec!["http://b.example/".to_string()],
+            },
+        )
+        .await?;
+        repo.record_served(&source_id, "http://b.example/").await?;
+        let mirrors = repo.get(&source_id).await?;
+        assert_eq!(mirrors.mirror_urls, ["http://b.example/"]);
+        assert_eq!(
+            mirrors.last_served_url.as_deref(),
+            Some("http://b.example/")
+        );
+        assert!(mirrors.last_served_at.is_some());
+
+        repo.set(&source_id, StreamSourceMirrorsRequest::default())
+            .await?;
+        assert!(repo.get(&source_id).await?.mirror_urls.is_empty());
+        Ok(())
+    }
+}
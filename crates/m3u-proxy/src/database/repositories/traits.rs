@@ -4,7 +4,10 @@
 //! across all SeaORM repository implementations.
 
 use anyhow::Result;
-use sea_orm::DatabaseConnection;
+use sea_orm::{
+    Condition, DatabaseConnection,
+    sea_query::{Expr, Func, IntoColumnRef, LikeExpr},
+};
 use std::sync::Arc;
 
 /// Common conversion utilities for SeaORM entities to domain models
@@ -43,3 +46,32 @@ impl BaseSeaOrmRepository {
         Self { connection }
     }
 }
+
+/// Case-insensitive substring match of `query` against any of `columns`
+///
+/// `%`, `_` and `\` in the query match literally rather than as `LIKE` wildcards.
+pub fn contains_any<C: IntoColumnRef>(
+    query: &str,
+    columns: impl IntoIterator<Item = C>,
+) -> Condition {
+    let pattern = contains_pattern(query);
+    columns
+        .into_iter()
+        .fold(Condition::any(), |condition, column| {
+            condition.add(Expr::expr(Func::lower(Expr::col(column))).like(pattern.clone()))
+        })
+}
+
+/// `LIKE` pattern matching a lowercased value containing `query`
+fn contains_pattern(query: &str) -> LikeExpr {
+    let mut pattern = String::with_capacity(query.len() + 2);
+    pattern.push('%');
+    for c in query.to_lowercase().chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    LikeExpr::new(pattern).escape('\\')
+}
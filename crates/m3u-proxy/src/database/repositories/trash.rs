@@ -0,0 +1,312 @@
+//! SeaORM trash repository
+//!
+//! Lists, restores and purges soft-deleted stream sources, EPG sources, proxies, filters
+//! and data mapping rules. Purging removes the row for good, along with everything that
+//! cascades from it, a source's ingestion history and a rule's version history.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, TransactionTrait,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{
+    ingestion_runs,
+    prelude::{IngestionRuns, RuleVersions},
+    rule_versions,
+};
+use crate::models::rule_version::RuleKind;
+use crate::models::trash::{TrashItem, TrashKind, TrashPurgeSummary};
+
+/// Run `$body` with `$entity` and `$table` bound to the entity and module of a trash kind
+macro_rules! with_table {
+    ($kind:expr, $entity:ident, $table:ident => $body:expr) => {
+        match $kind {
+            TrashKind::StreamSource => {
+                use crate::entities::{
+                    prelude::StreamSources as $entity, stream_sources as $table,
+                };
+                $body
+            }
+            TrashKind::EpgSource => {
+                use crate::entities::{epg_sources as $table, prelude::EpgSources as $entity};
+                $body
+            }
+            TrashKind::Proxy => {
+                use crate::entities::{
+                    prelude::StreamProxies as $entity, stream_proxies as $table,
+                };
+                $body
+            }
+            TrashKind::Filter => {
+                use crate::entities::{filters as $table, prelude::Filters as $entity};
+                $body
+            }
+            TrashKind::DataMappingRule => {
+                use crate::entities::{
+                    data_mapping_rules as $table, prelude::DataMappingRules as $entity,
+                };
+                $body
+            }
+        }
+    };
+}
+
+/// SeaORM-based trash repository
+#[derive(Clone)]
+pub struct TrashSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl TrashSeaOrmRepository {
+    /// Create a new TrashSeaOrmRepository
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// All trashed items, most recently deleted first
+    pub async fn list(&self, retention: Duration) -> Result<Vec<TrashItem>> {
+        let mut items = Vec::new();
+        for kind in TrashKind::ALL {
+            let rows: Vec<(Uuid, String, Option<DateTime<Utc>>)> = with_table!(kind, Entity, table => {
+                Entity::find()
+                    .select_only()
+                    .column(table::Column::Id)
+                    .column(table::Column::Name)
+                    .column(table::Column::DeletedAt)
+                    .filter(table::Column::DeletedAt.is_not_null())
+                    .into_tuple()
+                    .all(&*self.connection)
+                    .await?
+            });
+            items.extend(rows.into_iter().filter_map(|(id, name, deleted_at)| {
+                deleted_at.map(|deleted_at| TrashItem::new(kind, id, name, deleted_at, retention))
+            }));
+        }
+        items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(items)
+    }
+
+    /// Take an item out of the trash; false when it is not trashed
+    pub async fn restore(&self, kind: TrashKind, id: Uuid) -> Result<bool> {
+        let result = with_table!(kind, Entity, table => {
+            Entity::update_many()
+                .col_expr(table::Column::DeletedAt, Expr::value(Option::<DateTime<Utc>>::None))
+                .col_expr(table::Column::UpdatedAt, Expr::value(Utc::now()))
+                .filter(table::Column::Id.eq(id))
+                .filter(table::Column::DeletedAt.is_not_null())
+                .exec(&*self.connection)
+                .await?
+        });
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Permanently remove a trashed item; false when it is not trashed
+    pub async fn purge(&self, kind: TrashKind, id: Uuid) -> Result<bool> {
+        let trashed: Vec<Uuid> = with_table!(kind, Entity, table => {
+            Entity::find()
+                .select_only()
+                .column(table::Column::Id)
+                .filter(table::Column::Id.eq(id))
+                .filter(table::Column::DeletedAt.is_not_null())
+                .into_tuple()
+                .all(&*self.connection)
+                .await?
+        });
+        Ok(self.purge_ids(kind, &trashed).await? > 0)
+    }
+
+    /// Permanently remove everything trashed before `cutoff`
+    pub async fn purge_deleted_before(&self, cutoff: DateTime<Utc>) -> Result<TrashPurgeSummary> {
+        let mut summary = TrashPurgeSummary::default();
+        for kind in TrashKind::ALL {
+            let expired: Vec<Uuid> = with_table!(kind, Entity, table => {
+                Entity::find()
+                    .select_only()
+                    .column(table::Column::Id)
+                    .filter(table::Column::DeletedAt.is_not_null())
+                    .filter(table::Column::DeletedAt.lt(cutoff))
+                    .into_tuple()
+                    .all(&*self.connection)
+                    .await?
+            });
+            summary.record(kind, self.purge_ids(kind, &expired).await?);
+        }
+        Ok(summary)
+    }
+
+    /// Delete rows of a kind, and the ingestion history of sources or version history of rules
+    async fn purge_ids(&self, kind: TrashKind, ids: &[Uuid]) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let txn = self.connection.begin().await?;
+        let result = with_table!(kind, Entity, table => {
+            Entity::delete_many()
+                .filter(table::Column::Id.is_in(ids.iter().copied()))
+                .exec(&txn)
+                .await?
+        });
+        if matches!(kind, TrashKind::StreamSource | TrashKind::EpgSource) {
+            IngestionRuns::delete_many()
+                .filter(ingestion_runs::Column::SourceId.is_in(ids.iter().copied()))
+                .exec(&txn)
+                .await?;
+        }
+        let rule_kind = match kind {
+            TrashKind::Filter => Some(RuleKind::Filter),
+            TrashKind::DataMappingRule => Some(RuleKind::DataMappingRule),
+            _ => None,
+        };
+        if let Some(rule_kind) = rule_kind {
+            RuleVersions::delete_many()
+                .filter(rule_versions::Column::RuleType.eq(rule_kind.as_str()))
+                .filter(rule_versions::Column::RuleId.is_in(ids.iter().copied()))
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+        Ok(result.rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<TrashSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        for table in [
+            "stream_sources",
+            "epg_sources",
+            "stream_proxies",
+            "filters",
+            "data_mapping_rules",
+        ] {
+            connection
+                .execute(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    format!(
+                        "CREATE TABLE {table} (
+                            id TEXT PRIMARY KEY,
+                            name TEXT NOT NULL,
+                            updated_at TEXT NOT NULL,
+                            deleted_at TEXT
+                        );"
+                    ),
+                ))
+                .await?;
+        }
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE ingestion_runs (
+                    id TEXT PRIMARY KEY,
+                    source_id TEXT NOT NULL
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE rule_versions (
+                    id TEXT PRIMARY KEY,
+                    rule_type TEXT NOT NULL,
+                    rule_id TEXT NOT NULL
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        Ok(TrashSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    async fn insert(
+        repo: &TrashSeaOrmRepository,
+        table: &str,
+        name: &str,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+        repo.connection
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                format!(
+                    "INSERT INTO {table} (id, name, updated_at, deleted_at) VALUES (?, ?, ?, ?)"
+                ),
+                [id.into(), name.into(), Utc::now().into(), deleted_at.into()],
+            ))
+            .await?;
+        Ok(id)
+    }
+
+    #[tokio::test]
+    async fn test_list_and_restore() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let deleted_at = Utc::now() - Duration::days(2);
+        insert(&repo, "stream_proxies", "live", None).await?;
+        let proxy = insert(&repo, "stream_proxies", "old proxy", Some(deleted_at)).await?;
+        let filter = insert(&repo, "filters", "old filter", Some(Utc::now())).await?;
+
+        let items = repo.list(Duration::days(30)).await?;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, filter);
+        assert_eq!(items[1].kind, TrashKind::Proxy);
+        assert_eq!(items[1].purge_at, items[1].deleted_at + Duration::days(30));
+
+        assert!(repo.restore(TrashKind::Proxy, proxy).await?);
+        // Restoring twice, or as the wrong kind, finds nothing in the trash
+        assert!(!repo.restore(TrashKind::Proxy, proxy).await?);
+        assert!(!repo.restore(TrashKind::Proxy, filter).await?);
+        assert_eq!(repo.list(Duration::days(30)).await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_purge_honors_cutoff_and_skips_live_rows() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let live = insert(&repo, "stream_sources", "live", None).await?;
+        let expired = insert(
+            &repo,
+            "stream_sources",
+            "expired",
+            Some(Utc::now() - Duration::days(40)),
+        )
+        .await?;
+        insert(&repo, "stream_sources", "recent", Some(Utc::now())).await?;
+        repo.connection
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                "INSERT INTO ingestion_runs (id, source_id) VALUES (?, ?)",
+                [Uuid::new_v4().into(), expired.into()],
+            ))
+            .await?;
+
+        assert!(!repo.purge(TrashKind::StreamSource, live).await?);
+        let summary = repo
+            .purge_deleted_before(Utc::now() - Duration::days(30))
+            .await?;
+        assert_eq!(summary.stream_sources, 1);
+        assert_eq!(summary.total(), 1);
+        assert_eq!(repo.list(Duration::days(30)).await?.len(), 1);
+        let runs = repo
+            .connection
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT COUNT(*) AS count FROM ingestion_runs".to_string(),
+            ))
+            .await?
+            .map(|row| row.try_get::<i64>("", "count"))
+            .transpose()?;
+        assert_eq!(runs, Some(0));
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+//! SeaORM-based URL rewrite rule repository implementation
+//!
+//! Stores the regex rewrites applied to upstream stream URLs.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::UrlRewriteRules, url_rewrite_rules};
+use crate::models::url_rewrite_rule::{UrlRewriteRule, UrlRewriteRuleRequest};
+
+/// SeaORM-based repository for URL rewrite rules
+pub struct UrlRewriteRuleSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl UrlRewriteRuleSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Create a rule
+    pub async fn create(&self, request: UrlRewriteRuleRequest) -> Result<UrlRewriteRule> {
+        let now = Utc::now();
+        let model = url_rewrite_rules::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(request.name.trim().to_string()),
+            pattern: Set(request.pattern),
+            replacement: Set(request.replacement),
+            source_id: Set(request.source_id),
+            proxy_id: Set(request.proxy_id),
+            priority: Set(request.priority),
+            is_active: Set(request.is_active),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&*self.connection)
+        .await?;
+        Ok(model_to_domain(model))
+    }
+
+    /// Replace a rule; returns None when it does not exist
+    pub async fn update(
+        &self,
+        id: &Uuid,
+        request: UrlRewriteRuleRequest,
+    ) -> Result<Option<UrlRewriteRule>> {
+        let Some(existing) = UrlRewriteRules::find_by_id(*id)
+            .one(&*self.connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut active_model: url_rewrite_rules::ActiveModel = existing.into();
+        active_model.name = Set(request.name.trim().to_string());
+        active_model.pattern = Set(request.pattern);
+        active_model.replacement = Set(request.replacement);
+        active_model.source_id = Set(request.source_id);
+        active_model.proxy_id = Set(request.proxy_id);
+        active_model.priority = Set(request.priority);
+        active_model.is_active = Set(request.is_active);
+        active_model.updated_at = Set(Utc::now());
+        let model = active_model.update(&*self.connection).await?;
+        Ok(Some(model_to_domain(model)))
+    }
+
+    /// Delete a rule; returns false when it does not exist
+    pub async fn delete(&self, id: &Uuid) -> Result<bool> {
+        let result = UrlRewriteRules::delete_by_id(*id)
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Find a rule by ID
+    pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<UrlRewriteRule>> {
+        let model = UrlRewriteRules::find_by_id(*id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model.map(model_to_domain))
+    }
+
+    /// List all rules in application order
+    pub async fn list(&self) -> Result<Vec<UrlRewriteRule>> {
+        let models = UrlRewriteRules::find()
+            .order_by_asc(url_rewrite_rules::Column::Priority)
+            .order_by_asc(url_rewrite_rules::Column::Name)
+            .all(&*self.connection)
+            .await?;
+        Ok(models.into_iter().map(model_to_domain).collect())
+    }
+
+    /// Active rules applying to a proxy's streams (its own and unscoped ones), in application
+    /// order; source scopes are left to [`crate::models::url_rewrite_rule::UrlRewriteRuleSet`]
+    pub async fn list_active_for_proxy(&self, proxy_id: &Uuid) -> Result<Vec<UrlRewriteRule>> {
+        let models = UrlRewriteRules::find()
+            .filter(url_rewrite_rules::Column::IsActive.eq(true))
+            .filter(
+                Condition::any()
+                    .add(url_rewrite_rules::Column::ProxyId.is_null())
+                    .add(url_rewrite_rules::Column::ProxyId.eq(*proxy_id)),
+            )
+            .order_by_asc(url_rewrite_rules::Column::Priority)
+            .order_by_asc(url_rewrite_rules::Column::Name)
+            .all(&*self.connection)
+            .await?;
+        Ok(models.into_iter().map(model_to_domain).collect())
+    }
+}
+
+fn model_to_domain(model: url_rewrite_rules::Model) -> UrlRewriteRule {
+    UrlRewriteRule {
+        id: model.id,
+        name: model.name,
+        pattern: model.pattern,
+        replacement: model.replacement,
+        source_id: model.source_id,
+        proxy_id: model.proxy_id,
+        priority: model.priority,
+        is_active: model.is_active,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<UrlRewriteRuleSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE url_rewrite_rules (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    pattern TEXT NOT NULL,
+                    replacement TEXT NOT NULL,
+                    source_id TEXT,
+                    proxy_id TEXT,
+                    priority INTEGER NOT NULL DEFAULT 0,
+                    is_active BOOLEAN NOT NULL DEFAULT 1,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        Ok(UrlRewriteRuleSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    fn request(name: &str, priority: i32, proxy_id: Option<Uuid>) -> UrlRewriteRuleRequest {
+        UrlRewriteRuleRequest {
+            name: name.to_string(),
+            pattern: "^http://".to_string(),
+            replacement: "https://".to_string(),
+            source_id: None,
+            proxy_id,
+            priority,
+            is_active: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_active_for_proxy() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let proxy_a = Uuid::new_v4();
+        let proxy_b = Uuid::new_v4();
+
+        repo.create(request("Port swap", 10, Some(proxy_a))).await?;
+        let https = repo.create(request("HTTPS", 0, None)).await?;
+        repo.create(request("Token", 5, Some(proxy_b))).await?;
+
+        let names: Vec<_> = repo
+            .list_active_for_proxy(&proxy_a)
+            .await?
+            .into_iter()
+            .map(|rule| rule.name)
+            .collect();
+        assert_eq!(names, ["HTTPS", "Port swap"]);
+
+        let mut disabled = request("HTTPS", 0, None);
+        disabled.is_active = false;
+        repo.update(&https.id, disabled).await?.unwrap();
+        assert_eq!(repo.list_active_for_proxy(&proxy_b).await?.len(), 1);
+        assert_eq!(repo.list().await?.len(), 3);
+
+        assert!(repo.delete(&https.id).await?);
+        assert!(!repo.delete(&https.id).await?);
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+//! SeaORM-based virtual channel repository implementation
+//!
+//! Stores user-defined virtual channels together with the proxies they are injected into.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{
+    prelude::{ProxyVirtualChannels, VirtualChannels},
+    proxy_virtual_channels, virtual_channels,
+};
+use crate::models::virtual_channel::{VirtualChannel, VirtualChannelRequest};
+
+/// SeaORM-based repository for virtual channels
+#[derive(Clone)]
+pub struct VirtualChannelSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl VirtualChannelSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Create a virtual channel and assign it to the requested proxies
+    pub async fn create(&self, request: VirtualChannelRequest) -> Result<VirtualChannel> {
+        let now = Utc::now();
+        let txn = self.connection.begin().await?;
+
+        let model = virtual_channels::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(request.name.trim().to_string()),
+            stream_url: Set(request.stream_url.trim().to_string()),
+            tvg_id: Set(request.tvg_id),
+            tvg_logo: Set(request.tvg_logo),
+            group_title: Set(request.group_title),
+            channel_number: Set(request.channel_number),
+            relay_profile_id: Set(request.relay_profile_id),
+            is_active: Set(request.is_active),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&txn)
+        .await?;
+
+        let proxy_ids = replace_assignments(&txn, model.id, &request.proxy_ids).await?;
+        txn.commit().await?;
+
+        Ok(model_to_domain(model, proxy_ids))
+    }
+
+    /// Replace a virtual channel and its proxy assignments; returns None when it does not exist
+    pub async fn update(
+        &self,
+        id: &Uuid,
+        request: VirtualChannelRequest,
+    ) -> Result<Option<VirtualChannel>> {
+        let txn = self.connection.begin().await?;
+        let Some(existing) = VirtualChannels::find_by_id(*id).one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let mut active_model: virtual_channels::ActiveModel = existing.into();
+        active_model.name = Set(request.name.trim().to_string());
+        active_model.stream_url = Set(request.stream_url.trim().to_string());
+        active_model.tvg_id = Set(request.tvg_id);
+        active_model.tvg_logo = Set(request.tvg_logo);
+        active_model.group_title = Set(request.group_title);
+        active_model.channel_number = Set(request.channel_number);
+        active_model.relay_profile_id = Set(request.relay_profile_id);
+        active_model.is_active = Set(request.is_active);
+        active_model.updated_at = Set(Utc::now());
+        let model = active_model.update(&txn).await?;
+
+        let proxy_ids = replace_assignments(&txn, model.id, &request.proxy_ids).await?;
+        txn.commit().await?;
+
+        Ok(Some(model_to_domain(model, proxy_ids)))
+    }
+
+    /// Delete a virtual channel; returns false when it does not exist
+    pub async fn delete(&self, id: &Uuid) -> Result<bool> {
+        let result = VirtualChannels::delete_by_id(*id)
+            .exec(&*self.connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Find a virtual channel by ID
+    pub async fn find_by_id(&self, id: &Uuid) -> Result<Option<VirtualChannel>> {
+        let Some(model) = VirtualChannels::find_by_id(*id)
+            .one(&*self.connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let proxy_ids = ProxyVirtualChannels::find()
+            .filter(proxy_virtual_channels::Column::VirtualChannelId.eq(*id))
+            .all(&*self.connection)
+            .await?
+            .into_iter()
+            .map(|a| a.proxy_id)
+            .collect();
+        Ok(Some(model_to_domain(model, proxy_ids)))
+    }
+
+    /// List all virtual channels ordered by name
+    pub async fn list(&self) -> Result<Vec<VirtualChannel>> {
+        let models = VirtualChannels::find()
+            .order_by_asc(virtual_channels::Column::Name)
+            .all(&*self.connection)
+            .await?;
+
+        let mut assignments: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for assignment in ProxyVirtualChannels::find().all(&*self.connection).await? {
+            assignments
+                .entry(assignment.virtual_channel_id)
+                .or_default()
+                .push(assignment.proxy_id);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|model| {
+                let proxy_ids = assignments.remove(&model.id).unwrap_or_default();
+                model_to_domain(model, proxy_ids)
+            })
+            .collect())
+    }
+
+    /// Active virtual channels assigned to a proxy, in injection order
+    ///
+    /// Channels with a fixed number come first (ascending), then the rest by name.
+    pub async fn list_active_for_proxy(&self, proxy_id: &Uuid) -> Result<Vec<VirtualChannel>> {
+        let channel_ids: Vec<Uuid> = ProxyVirtualChannels::find()
+            .filter(proxy_virtual_channels::Column::ProxyId.eq(*proxy_id))
+            .all(&*self.connection)
+            .await?
+            .into_iter()
+            .map(|a| a.virtual_channel_id)
+            .collect();
+        if channel_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut channels: Vec<VirtualChannel> = VirtualChannels::find()
+            .filter(virtual_channels::Column::Id.is_in(channel_ids))
+            .filter(virtual_channels::Column::IsActive.eq(true))
+            .all(&*self.connection)
+            .await?
+            .into_iter()
+            .map(|model| model_to_domain(model, vec![*proxy_id]))
+            .collect();
+        channels.sort_by(|a, b| {
+            (a.channel_number.is_none(), a.channel_number, &a.name).cmp(&(
+                b.channel_number.is_none(),
+                b.channel_number,
+                &b.name,
+            ))
+        });
+        Ok(channels)
+    }
+
+    /// Find an active virtual channel assigned to a proxy
+    pub async fn find_active_for_proxy(
+        &self,
+        proxy_id: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<VirtualChannel>> {
+        let assigned = ProxyVirtualChannels::find_by_id((*proxy_id, *id))
+            .one(&*self.connection)
+            .await?
+            .is_some();
+        if !assigned {
+            return Ok(None);
+        }
+        let model = VirtualChannels::find_by_id(*id)
+            .filter(virtual_channels::Column::IsActive.eq(true))
+            .one(&*self.connection)
+            .await?;
+        Ok(model.map(|model| model_to_domain(model, vec![*proxy_id])))
+    }
+}
+
+/// Replace the proxy assignments of a virtual channel, returning the deduplicated proxy IDs
+async fn replace_assignments<C: ConnectionTrait>(
+    connection: &C,
+    virtual_channel_id: Uuid,
+    proxy_ids: &[Uuid],
+) -> Result<Vec<Uuid>> {
+    ProxyVirtualChannels::delete_many()
+        .filter(proxy_virtual_channels::Column::VirtualChannelId.eq(virtual_channel_id))
+        .exec(connection)
+        .await?;
+
+    let now = Utc::now();
+    let mut assigned = Vec::new();
+    for proxy_id in proxy_ids {
+        if assigned.contains(proxy_id) {
+            continue;
+        }
+        proxy_virtual_channels::ActiveModel {
+            proxy_id: Set(*proxy_id),
+            virtual_channel_id: Set(virtual_channel_id),
+            created_at: Set(now),
+        }
+        .insert(connection)
+        .await?;
+        assigned.push(*proxy_id);
+    }
+    Ok(assigned)
+}
+
+fn model_to_domain(model: virtual_channels::Model, proxy_ids: Vec<Uuid>) -> VirtualChannel {
+    VirtualChannel {
+        id: model.id,
+        name: model.name,
+        stream_url: model.stream_url,
+        tvg_id: model.tvg_id,
+        tvg_logo: model.tvg_logo,
+        group_title: model.group_title,
+        channel_number: model.channel_number,
+        relay_profile_id: model.relay_profile_id,
+        is_active: model.is_active,
+        proxy_ids,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<VirtualChannelSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        for sql in [
+            r"
+            CREATE TABLE virtual_channels (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                stream_url TEXT NOT NULL,
+                tvg_id TEXT,
+                tvg_logo TEXT,
+                group_title TEXT,
+                channel_number INTEGER,
+                relay_profile_id TEXT,
+                is_active BOOLEAN NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            ",
+            r"
+            CREATE TABLE proxy_virtual_channels (
+                proxy_id TEXT NOT NULL,
+                virtual_channel_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (proxy_id, virtual_channel_id)
+            );
+            ",
+        ] {
+            connection
+                .execute(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    sql.to_string(),
+                ))
+                .await?;
+        }
+        Ok(VirtualChannelSeaOrmRepository::new(Arc::new(connection)))
+    }
+
+    fn request(
+        name: &str,
+        channel_number: Option<i32>,
+        proxy_ids: Vec<Uuid>,
+    ) -> VirtualChannelRequest {
+        VirtualChannelRequest {
+            name: name.to_string(),
+            stream_url: "http://192.168.1.20:5004/auto/v2.1".to_string(),
+            tvg_id: None,
+            tvg_logo: None,
+            group_title: Some("Local".to_string()),
+            channel_number,
+            relay_profile_id: None,
+            is_active: true,
+            proxy_ids,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_virtual_channel_assignments() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let proxy_a = Uuid::new_v4();
+        let proxy_b = Uuid::new_v4();
+
+        let weather = repo
+            .create(request("Weather Cam", None, vec![proxy_a, proxy_a]))
+            .await?;
+        assert_eq!(weather.proxy_ids, vec![proxy_a]);
+        let tuner = repo
+            .create(request("Local Tuner", Some(2), vec![proxy_a, proxy_b]))
+            .await?;
+
+        let for_a = repo.list_active_for_proxy(&proxy_a).await?;
+        assert_eq!(for_a.len(), 2);
+        assert_eq!(for_a[0].id, tuner.id, "numbered channels come first");
+        assert_eq!(repo.list_active_for_proxy(&proxy_b).await?.len(), 1);
+        assert!(
+            repo.find_active_for_proxy(&proxy_b, &weather.id)
+                .await?
+                .is_none()
+        );
+
+        let mut disabled = request("Weather Cam", None, vec![proxy_b]);
+        disabled.is_active = false;
+        repo.update(&weather.id, disabled).await?.unwrap();
+        assert_eq!(repo.list_active_for_proxy(&proxy_a).await?.len(), 1);
+        assert_eq!(repo.list_active_for_proxy(&proxy_b).await?.len(), 1);
+        assert_eq!(
+            repo.find_by_id(&weather.id).await?.unwrap().proxy_ids,
+            vec![proxy_b]
+        );
+
+        assert!(repo.delete(&tuner.id).await?);
+        assert!(!repo.delete(&tuner.id).await?);
+        assert_eq!(repo.list().await?.len(), 1);
+        Ok(())
+    }
+}
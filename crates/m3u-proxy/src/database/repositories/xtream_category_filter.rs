@@ -0,0 +1,163 @@
+//! SeaORM-based Xtream category ingest filter repository
+//!
+//! Stores, per stream source, the provider categories to include or exclude while ingesting.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Set};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::entities::{prelude::StreamSourceCategoryFilters, stream_source_category_filters};
+use crate::models::xtream_category_filter::{XtreamCategoryFilter, XtreamCategoryFilterRequest};
+
+/// SeaORM-based repository for Xtream category ingest filters
+#[derive(Clone)]
+pub struct XtreamCategoryFilterSeaOrmRepository {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl XtreamCategoryFilterSeaOrmRepository {
+    /// Create a new repository instance
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Category filter of a source (empty when none is configured)
+    pub async fn get(&self, source_id: &Uuid) -> Result<XtreamCategoryFilter> {
+        let model = StreamSourceCategoryFilters::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+        Ok(model
+            .map(model_to_domain)
+            .unwrap_or_else(|| XtreamCategoryFilter::none(*source_id)))
+    }
+
+    /// Replace a source's category filter; a request without rules removes it
+    pub async fn set(
+        &self,
+        source_id: &Uuid,
+        request: XtreamCategoryFilterRequest,
+    ) -> Result<XtreamCategoryFilter> {
+        let existing = StreamSourceCategoryFilters::find_by_id(*source_id)
+            .one(&*self.connection)
+            .await?;
+
+        if request.include_category_ids.is_empty()
+            && request.include_patterns.is_empty()
+            && request.exclude_category_ids.is_empty()
+            && request.exclude_patterns.is_empty()
+        {
+            if let Some(model) = existing {
+                model.into_active_model().delete(&*self.connection).await?;
+            }
+            return Ok(XtreamCategoryFilter::none(*source_id));
+        }
+
+        let include_category_ids = serde_json::to_string(&request.include_category_ids)?;
+        let include_patterns = serde_json::to_string(&request.include_patterns)?;
+        let exclude_category_ids = serde_json::to_string(&request.exclude_category_ids)?;
+        let exclude_patterns = serde_json::to_string(&request.exclude_patterns)?;
+        let model = match existing {
+            Some(model) => {
+                let mut active_model = model.into_active_model();
+                active_model.include_category_ids = Set(include_category_ids);
+                active_model.include_patterns = Set(include_patterns);
+                active_model.exclude_category_ids = Set(exclude_category_ids);
+                active_model.exclude_patterns = Set(exclude_patterns);
+                active_model.updated_at = Set(Utc::now());
+                active_model.update(&*self.connection).await?
+            }
+            None => {
+                stream_source_category_filters::ActiveModel {
+                    source_id: Set(*source_id),
+                    include_category_ids: Set(include_category_ids),
+                    include_patterns: Set(include_patterns),
+                    exclude_category_ids: Set(exclude_category_ids),
+                    exclude_patterns: Set(exclude_patterns),
+                    updated_at: Set(Utc::now()),
+                }
+                .insert(&*self.connection)
+                .await?
+            }
+        };
+        Ok(model_to_domain(model))
+    }
+}
+
+fn model_to_domain(model: stream_source_category_filters::Model) -> XtreamCategoryFilter {
+    XtreamCategoryFilter {
+        source_id: model.source_id,
+        include_category_ids: serde_json::from_str(&model.include_category_ids).unwrap_or_default(),
+        include_patterns: serde_json::from_str(&model.include_patterns).unwrap_or_default(),
+        exclude_category_ids: serde_json::from_str(&model.exclude_category_ids).unwrap_or_default(),
+        exclude_patterns: serde_json::from_str(&model.exclude_patterns).unwrap_or_default(),
+        updated_at: Some(model.updated_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    async fn create_test_repo() -> Result<XtreamCategoryFilterSeaOrmRepository> {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await?;
+        connection
+            .execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                r"
+                CREATE TABLE stream_source_category_filters (
+                    source_id TEXT PRIMARY KEY,
+                    include_category_ids TEXT NOT NULL,
+                    include_patterns TEXT NOT NULL,
+                    exclude_category_ids TEXT NOT NULL,
+                    exclude_patterns TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+                "
+                .to_string(),
+            ))
+            .await?;
+        Ok(XtreamCategoryFilterSeaOrmRepository::new(Arc::new(
+            connection,
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_set_replace_and_clear() -> Result<()> {
+        let repo = create_test_repo().await?;
+        let source_id = Uuid::new_v4();
+        assert!(repo.get(&source_id).await?.is_empty());
+
+        repo.set(
+            &source_id,
+            XtreamCategoryFilterRequest {
+                include_category_ids: vec![12, 15],
+                exclude_patterns: vec!["adult".to_string()],
+                ..Default::default()
+            },
+        )
+        .await?;
+        let filter = repo.get(&source_id).await?;
+        assert_eq!(filter.include_category_ids, vec![12, 15]);
+        assert_eq!(filter.exclude_patterns, vec!["adult".to_string()]);
+
+        repo.set(
+            &source_id,
+            XtreamCategoryFilterRequest {
+                include_patterns: vec!["^UK".to_string()],
+                ..Default::default()
+            },
+        )
+        .await?;
+        let filter = repo.get(&source_id).await?;
+        assert!(filter.include_category_ids.is_empty());
+        assert_eq!(filter.include_patterns, vec!["^UK".to_string()]);
+
+        repo.set(&source_id, XtreamCategoryFilterRequest::default())
+            .await?;
+        assert!(repo.get(&source_id).await?.is_empty());
+        Ok(())
+    }
+}
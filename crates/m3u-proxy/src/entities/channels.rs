@@ -29,6 +29,11 @@ pub struct Model {
     pub stream_url: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Consecutive ingestions the channel was missing from (0 = present in the latest)
+    pub missed_ingestions: i32,
+    /// JSON object of EXTINF attributes without a column of their own
+    #[sea_orm(column_type = "Text", nullable)]
+    pub extra_attributes: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -20,8 +20,11 @@ pub struct Model {
     pub expression: Option<String>,
     pub sort_order: i32,
     pub is_active: bool,
+    pub scope_source_id: Option<Uuid>,
+    pub scope_proxy_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -30,6 +30,7 @@ pub struct Model {
     pub updated_at: DateTime<Utc>,
     pub last_ingested_at: Option<DateTime<Utc>>,
     pub is_active: bool,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
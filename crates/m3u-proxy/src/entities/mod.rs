@@ -2,17 +2,40 @@
 
 pub mod prelude;
 
+pub mod channel_count_snapshots;
+pub mod channel_epg_mappings;
+pub mod channel_logo_assignments;
 pub mod channels;
 pub mod data_mapping_rules;
+pub mod epg_channel_metadata;
 pub mod epg_programs;
 pub mod epg_sources;
+pub mod filter_group_members;
+pub mod filter_groups;
 pub mod filters;
+pub mod ingestion_runs;
 pub mod last_known_codecs;
 pub mod logo_assets;
 pub mod migration_notes;
+pub mod proxy_basic_auth;
+pub mod proxy_channel_exclusions;
 pub mod proxy_epg_sources;
 pub mod proxy_filters;
+pub mod proxy_settings;
+pub mod proxy_share_links;
 pub mod proxy_sources;
+pub mod proxy_templates;
+pub mod proxy_virtual_channels;
+pub mod relay_channel_views;
 pub mod relay_profiles;
+pub mod rule_versions;
 pub mod stream_proxies;
+pub mod stream_source_balance_groups;
+pub mod stream_source_category_filters;
+pub mod stream_source_channel_identity;
+pub mod stream_source_channel_retention;
+pub mod stream_source_mirrors;
+pub mod stream_source_stream_headers;
 pub mod stream_sources;
+pub mod url_rewrite_rules;
+pub mod virtual_channels;
@@ -1,16 +1,39 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.14
 
+pub use super::channel_count_snapshots::Entity as ChannelCountSnapshots;
+pub use super::channel_epg_mappings::Entity as ChannelEpgMappings;
+pub use super::channel_logo_assignments::Entity as ChannelLogoAssignments;
 pub use super::channels::Entity as Channels;
 pub use super::data_mapping_rules::Entity as DataMappingRules;
+pub use super::epg_channel_metadata::Entity as EpgChannelMetadata;
 pub use super::epg_programs::Entity as EpgPrograms;
 pub use super::epg_sources::Entity as EpgSources;
+pub use super::filter_group_members::Entity as FilterGroupMembers;
+pub use super::filter_groups::Entity as FilterGroups;
 pub use super::filters::Entity as Filters;
+pub use super::ingestion_runs::Entity as IngestionRuns;
 pub use super::last_known_codecs::Entity as LastKnownCodecs;
 pub use super::logo_assets::Entity as LogoAssets;
 pub use super::migration_notes::Entity as MigrationNotes;
+pub use super::proxy_basic_auth::Entity as ProxyBasicAuth;
+pub use super::proxy_channel_exclusions::Entity as ProxyChannelExclusions;
 pub use super::proxy_epg_sources::Entity as ProxyEpgSources;
 pub use super::proxy_filters::Entity as ProxyFilters;
+pub use super::proxy_settings::Entity as ProxySettings;
+pub use super::proxy_share_links::Entity as ProxyShareLinks;
 pub use super::proxy_sources::Entity as ProxySources;
+pub use super::proxy_templates::Entity as ProxyTemplates;
+pub use super::proxy_virtual_channels::Entity as ProxyVirtualChannels;
+pub use super::relay_channel_views::Entity as RelayChannelViews;
 pub use super::relay_profiles::Entity as RelayProfiles;
+pub use super::rule_versions::Entity as RuleVersions;
 pub use super::stream_proxies::Entity as StreamProxies;
+pub use super::stream_source_balance_groups::Entity as StreamSourceBalanceGroups;
+pub use super::stream_source_category_filters::Entity as StreamSourceCategoryFilters;
+pub use super::stream_source_channel_identity::Entity as StreamSourceChannelIdentity;
+pub use super::stream_source_channel_retention::Entity as StreamSourceChannelRetention;
+pub use super::stream_source_mirrors::Entity as StreamSourceMirrors;
+pub use super::stream_source_stream_headers::Entity as StreamSourceStreamHeaders;
 pub use super::stream_sources::Entity as StreamSources;
+pub use super::url_rewrite_rules::Entity as UrlRewriteRules;
+pub use super::virtual_channels::Entity as VirtualChannels;
@@ -36,6 +36,16 @@ pub struct Model {
     pub segment_duration: Option<i32>,
     pub max_segments: Option<i32>,
     pub input_timeout: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub audio_languages: Option<String>,
+    pub audio_track_index: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub subtitle_languages: Option<String>,
+    pub subtitle_track_index: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub subtitle_mode: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub subtitle_codec: Option<String>,
     pub is_system_default: bool,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
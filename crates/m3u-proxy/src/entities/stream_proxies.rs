@@ -1,6 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.14
 
-use crate::models::StreamProxyMode;
+use crate::models::{BackupStreamMode, OfflineSlateMode, OutputProfile, StreamProxyMode};
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -28,6 +28,17 @@ pub struct Model {
     pub cache_channel_logos: bool,
     pub cache_program_logos: bool,
     pub relay_profile_id: Option<Uuid>,
+    pub sign_stream_urls: bool,
+    pub output_profile: OutputProfile,
+    pub backup_streams: BackupStreamMode,
+    pub offline_slate: OfflineSlateMode,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub epg_languages: Option<String>,
+    pub regeneration_debounce_seconds: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub channel_number_blocks: Option<String>,
+    pub epg_timezone: Option<String>,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
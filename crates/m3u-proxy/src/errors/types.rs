@@ -159,6 +159,10 @@ pub enum SourceError {
     /// HTTP errors from external sources
     #[error("HTTP error: {status} - {message}")]
     Http { status: u16, message: String },
+
+    /// A per-source ingestion limit was exceeded
+    #[error("Ingestion limit exceeded: {limit} - {message}")]
+    LimitExceeded { limit: String, message: String },
 }
 
 /// Web layer specific errors
@@ -250,6 +254,14 @@ impl SourceError {
             message: message.into(),
         }
     }
+
+    /// Create an ingestion limit exceeded error
+    pub fn limit_exceeded<S: Into<String>, M: Into<String>>(limit: S, message: M) -> Self {
+        Self::LimitExceeded {
+            limit: limit.into(),
+            message: message.into(),
+        }
+    }
 }
 
 impl WebError {}
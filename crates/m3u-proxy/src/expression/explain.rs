@@ -0,0 +1,415 @@
+//! Expression explain traces
+//!
+//! Evaluates a parsed expression against a sample record (field name -> value) and records every
+//! step: each condition with the value it saw and whether it matched, regex captures, and the
+//! actions that would fire with their resulting values. Semantics follow the rule processor:
+//! all conditions are evaluated against the original sample while actions apply, in order, to a
+//! working copy of it.
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+use crate::field_registry::FieldRegistry;
+use crate::models::{
+    Action, ActionOperator, ActionValue, ConditionNode, ConditionTree, ExpressionExplainAction,
+    ExpressionExplainGroup, ExpressionExplainNodeKind, ExpressionExplainResult,
+    ExpressionExplainStep, ExtendedExpression, FilterOperator, LogicalOperator,
+};
+
+/// Sample record keyed by canonical field name
+pub type ExplainRecord = BTreeMap<String, Option<String>>;
+
+/// Build a sample record from JSON values, resolving field aliases
+///
+/// Strings are used as-is, `null` means unset and other values use their JSON text.
+pub fn sample_record(
+    sample: &std::collections::HashMap<String, serde_json::Value>,
+) -> ExplainRecord {
+    sample
+        .iter()
+        .map(|(field, value)| {
+            let value = match value {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(s) => Some(s.clone()),
+                other => Some(other.to_string()),
+            };
+            (canonical_field(field), value)
+        })
+        .collect()
+}
+
+/// Evaluate `expression` against `sample`, tracing every condition and action
+pub fn explain_expression(
+    expression: &ExtendedExpression,
+    sample: &ExplainRecord,
+) -> ExpressionExplainResult {
+    let mut record = sample.clone();
+    let mut modified_fields: Vec<String> = Vec::new();
+
+    let groups: Vec<(&ConditionTree, &[Action])> = match expression {
+        ExtendedExpression::ConditionOnly(tree) => vec![(tree, &[])],
+        ExtendedExpression::ConditionWithActions { condition, actions } => {
+            vec![(condition, actions.as_slice())]
+        }
+        ExtendedExpression::ConditionalActionGroups(groups) => groups
+            .iter()
+            .map(|g| (&g.conditions, g.actions.as_slice()))
+            .collect(),
+    };
+
+    let mut explained = Vec::with_capacity(groups.len());
+    for (tree, actions) in groups {
+        let mut steps = Vec::new();
+        let (matched, captures) = explain_node(&tree.root, sample, 0, &mut steps);
+        let captures = captures.unwrap_or_default();
+
+        let actions = actions
+            .iter()
+            .map(|action| {
+                let traced = explain_action(action, matched, &captures, &mut record);
+                if traced.fired && !modified_fields.contains(&traced.field) {
+                    modified_fields.push(traced.field.clone());
+                }
+                traced
+            })
+            .collect();
+
+        explained.push(ExpressionExplainGroup {
+            matched,
+            steps,
+            captures,
+            actions,
+        });
+    }
+
+    ExpressionExplainResult {
+        is_valid: true,
+        error: None,
+        matched: explained.iter().any(|g| g.matched),
+        groups: explained,
+        record,
+        modified_fields,
+    }
+}
+
+fn canonical_field(field: &str) -> String {
+    FieldRegistry::global()
+        .canonical_or_none(field)
+        .map(str::to_string)
+        .unwrap_or_else(|| field.to_string())
+}
+
+/// Evaluate a node, appending its step (and its children's) to `steps`
+///
+/// Returns whether it matched and the first regex captures found beneath it.
+fn explain_node(
+    node: &ConditionNode,
+    sample: &ExplainRecord,
+    depth: usize,
+    steps: &mut Vec<ExpressionExplainStep>,
+) -> (bool, Option<Vec<String>>) {
+    match node {
+        ConditionNode::Condition {
+            field,
+            operator,
+            value,
+            case_sensitive,
+            negate,
+        } => {
+            let actual = sample.get(&canonical_field(field)).cloned().flatten();
+            let (matched, captures) = evaluate_condition(
+                operator,
+                value,
+                actual.as_deref().unwrap_or_default(),
+                *case_sensitive,
+            );
+            let matched = matched != *negate;
+            steps.push(ExpressionExplainStep {
+                depth,
+                kind: ExpressionExplainNodeKind::Condition,
+                matched,
+                logical_operator: None,
+                field: Some(field.clone()),
+                operator: Some(if *negate {
+                    format!("not {operator}")
+                } else {
+                    operator.to_string()
+                }),
+                value: Some(value.clone()),
+                actual_value: actual,
+                captures: captures.clone().unwrap_or_default(),
+            });
+            (matched, captures)
+        }
+        ConditionNode::Group { operator, children } => {
+            // Placeholder keeps the group ahead of its children; the outcome is filled in below
+            let index = steps.len();
+            steps.push(ExpressionExplainStep {
+                depth,
+                kind: ExpressionExplainNodeKind::Group,
+                matched: true,
+                logical_operator: Some(operator.to_string()),
+                field: None,
+                operator: None,
+                value: None,
+                actual_value: None,
+                captures: Vec::new(),
+            });
+
+            let mut results = Vec::with_capacity(children.len());
+            let mut captures = None;
+            for child in children {
+                let (child_matched, child_captures) = explain_node(child, sample, depth + 1, steps);
+                results.push(child_matched);
+                if captures.is_none() {
+                    captures = child_captures;
+                }
+            }
+
+            // Empty groups match, as in the processors
+            let matched = match operator {
+                LogicalOperator::And => results.iter().all(|&r| r),
+                LogicalOperator::Or => results.is_empty() || results.iter().any(|&r| r),
+            };
+            steps[index].matched = matched;
+            (matched, captures)
+        }
+    }
+}
+
+fn evaluate_condition(
+    operator: &FilterOperator,
+    value: &str,
+    actual: &str,
+    case_sensitive: bool,
+) -> (bool, Option<Vec<String>>) {
+    let (left, right) = if case_sensitive {
+        (actual.to_string(), value.to_string())
+    } else {
+        (actual.to_lowercase(), value.to_lowercase())
+    };
+    let equal = if case_sensitive {
+        actual == value
+    } else {
+        actual.eq_ignore_ascii_case(value)
+    };
+
+    match operator {
+        FilterOperator::Equals => (equal, None),
+        FilterOperator::NotEquals => (!equal, None),
+        FilterOperator::Contains => (left.contains(&right), None),
+        FilterOperator::NotContains => (!left.contains(&right), None),
+        FilterOperator::StartsWith => (left.starts_with(&right), None),
+        FilterOperator::NotStartsWith => (!left.starts_with(&right), None),
+        FilterOperator::EndsWith => (left.ends_with(&right), None),
+        FilterOperator::NotEndsWith => (!left.ends_with(&right), None),
+        FilterOperator::Matches => match Regex::new(value) {
+            Ok(regex) => match regex.captures(actual) {
+                Some(caps) => (
+                    true,
+                    Some(
+                        caps.iter()
+                            .map(|m| m.map_or(String::new(), |m| m.as_str().to_string()))
+                            .collect(),
+                    ),
+                ),
+                None => (false, None),
+            },
+            // Invalid patterns fall back to a substring check, as in the rule processor
+            Err(_) => (actual.contains(value), None),
+        },
+        FilterOperator::NotMatches => match Regex::new(value) {
+            Ok(regex) => (!regex.is_match(actual), None),
+            Err(_) => (!actual.contains(value), None),
+        },
+        FilterOperator::GreaterThan => (compare(actual, value, std::cmp::Ordering::Greater), None),
+        FilterOperator::LessThan => (compare(actual, value, std::cmp::Ordering::Less), None),
+        FilterOperator::GreaterThanOrEqual => (
+            equal || compare(actual, value, std::cmp::Ordering::Greater),
+            None,
+        ),
+        FilterOperator::LessThanOrEqual => (
+            equal || compare(actual, value, std::cmp::Ordering::Less),
+            None,
+        ),
+    }
+}
+
+/// Time-aware comparison (timestamps and @time: helpers), falling back to string order
+fn compare(actual: &str, expected: &str, ordering: std::cmp::Ordering) -> bool {
+    use crate::utils::time::{parse_time_string, resolve_time_functions};
+
+    let Ok(expected) = resolve_time_functions(expected) else {
+        return false;
+    };
+    if let (Ok(a), Ok(b)) = (parse_time_string(actual), parse_time_string(&expected)) {
+        return a.cmp(&b) == ordering;
+    }
+    actual.cmp(expected.as_str()) == ordering
+}
+
+/// Replace $1, $2, ... with the corresponding captures
+fn substitute_captures(input: &str, captures: &[String]) -> String {
+    // Highest index first so $1 does not clobber the prefix of $10
+    captures
+        .iter()
+        .enumerate()
+        .skip(1)
+        .rev()
+        .fold(input.to_string(), |acc, (i, capture)| {
+            acc.replace(&format!("${i}"), capture)
+        })
+}
+
+fn explain_action(
+    action: &Action,
+    group_matched: bool,
+    captures: &[String],
+    record: &mut ExplainRecord,
+) -> ExpressionExplainAction {
+    let field = canonical_field(&action.field);
+    let old_value = record.get(&field).cloned().flatten();
+    let operator = match action.operator {
+        ActionOperator::Set => "set",
+        ActionOperator::SetIfEmpty => "set_if_empty",
+        ActionOperator::Append => "append",
+        ActionOperator::Remove => "remove",
+        ActionOperator::Delete => "delete",
+    };
+    let literal = match &action.value {
+        ActionValue::Literal(v) => Some(v.clone()),
+        _ => None,
+    };
+
+    let mut traced = ExpressionExplainAction {
+        field: field.clone(),
+        operator: operator.to_string(),
+        value: literal.clone(),
+        fired: false,
+        reason: None,
+        old_value: old_value.clone(),
+        new_value: old_value.clone(),
+    };
+
+    if !group_matched {
+        traced.reason = Some("conditions did not match".to_string());
+        return traced;
+    }
+
+    // Required fields are blanked rather than unset
+    let cleared = || match field.as_str() {
+        "channel_name" | "stream_url" => Some(String::new()),
+        _ => None,
+    };
+
+    let new_value = match (&action.operator, &action.value) {
+        (ActionOperator::SetIfEmpty, _)
+            if old_value.as_deref().is_some_and(|v| !v.trim().is_empty()) =>
+        {
+            traced.reason = Some("field already has a value".to_string());
+            return traced;
+        }
+        (ActionOperator::Set | ActionOperator::SetIfEmpty, ActionValue::Literal(v)) => {
+            Some(substitute_captures(v, captures))
+        }
+        (ActionOperator::Set | ActionOperator::SetIfEmpty, ActionValue::Null) => cleared(),
+        (ActionOperator::Append, ActionValue::Literal(v)) => Some(format!(
+            "{}{}",
+            old_value.as_deref().unwrap_or_default(),
+            substitute_captures(v, captures)
+        )),
+        (ActionOperator::Remove | ActionOperator::Delete, _) => cleared(),
+        _ => {
+            traced.reason = Some("value type is not supported by the rule engine".to_string());
+            return traced;
+        }
+    };
+
+    record.insert(field, new_value.clone());
+    traced.fired = true;
+    traced.new_value = new_value;
+    traced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(pairs: &[(&str, &str)]) -> ExplainRecord {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), Some(v.to_string())))
+            .collect()
+    }
+
+    fn parse(expression: &str) -> ExtendedExpression {
+        crate::expression::parse_expression_extended(
+            crate::expression::ExpressionDomain::StreamDataMapping,
+            expression,
+        )
+        .unwrap()
+        .unwrap()
+        .extended
+    }
+
+    #[test]
+    fn test_regex_captures_feed_actions() {
+        let expression = parse(r#"channel_name matches "^(.+) HD$" SET tvg_name = "$1""#);
+        let result = explain_expression(&expression, &sample(&[("channel_name", "BBC One HD")]));
+
+        assert!(result.matched);
+        let group = &result.groups[0];
+        assert_eq!(group.steps.len(), 1);
+        assert_eq!(group.steps[0].actual_value.as_deref(), Some("BBC One HD"));
+        assert_eq!(group.captures, vec!["BBC One HD", "BBC One"]);
+
+        assert!(group.actions[0].fired);
+        assert_eq!(group.actions[0].new_value.as_deref(), Some("BBC One"));
+        assert_eq!(result.record["tvg_name"].as_deref(), Some("BBC One"));
+        assert_eq!(result.modified_fields, vec!["tvg_name"]);
+    }
+
+    #[test]
+    fn test_group_trace_and_unfired_actions() {
+        let expression = parse(
+            r#"channel_name contains "sport" AND group_title equals "UK" SET group_title = "Sports""#,
+        );
+        let result = explain_expression(
+            &expression,
+            &sample(&[("channel_name", "Sky Sports"), ("group_title", "US")]),
+        );
+
+        assert!(!result.matched);
+        let steps = &result.groups[0].steps;
+        assert_eq!(steps[0].kind, ExpressionExplainNodeKind::Group);
+        assert!(!steps[0].matched);
+        assert!(steps[1].matched);
+        assert!(!steps[2].matched);
+        assert_eq!(steps[1].depth, 1);
+
+        let action = &result.groups[0].actions[0];
+        assert!(!action.fired);
+        assert_eq!(action.reason.as_deref(), Some("conditions did not match"));
+        assert_eq!(result.record["group_title"].as_deref(), Some("US"));
+        assert!(result.modified_fields.is_empty());
+    }
+
+    #[test]
+    fn test_set_if_empty_skips_populated_field() {
+        let expression = parse(r#"channel_name contains "a" SET tvg_id ?= "fallback""#);
+        let result = explain_expression(
+            &expression,
+            &sample(&[("channel_name", "abc"), ("tvg_id", "existing")]),
+        );
+        let action = &result.groups[0].actions[0];
+        assert!(!action.fired);
+        assert_eq!(action.reason.as_deref(), Some("field already has a value"));
+    }
+
+    #[test]
+    fn test_substitute_captures_multi_digit() {
+        let captures: Vec<String> = (0..=10).map(|i| format!("c{i}")).collect();
+        assert_eq!(substitute_captures("$10-$1", &captures), "c10-c1");
+    }
+}
@@ -0,0 +1,424 @@
+//! Expression linting
+//!
+//! Static checks over a parsed expression that flag valid-but-questionable constructs:
+//! regexes prone to catastrophic backtracking, duplicate or subsumed OR/AND branches and
+//! case-sensitivity pitfalls. Checks that need data (e.g. fields that are always empty for the
+//! selected sources) are done by the caller and reported with [`empty_field_warning`].
+
+use std::collections::BTreeSet;
+
+use crate::models::{
+    ConditionNode, ExpressionLintSeverity, ExpressionLintWarning, ExtendedExpression,
+    FilterOperator, LogicalOperator,
+};
+
+/// Run all static lint checks over a parsed expression
+pub fn lint_expression(expression: &ExtendedExpression) -> Vec<ExpressionLintWarning> {
+    let mut warnings = Vec::new();
+    for tree in condition_trees(expression) {
+        lint_node(&tree.root, &mut warnings);
+    }
+    warnings
+}
+
+/// Canonical field names referenced by any condition of the expression
+pub fn referenced_fields(expression: &ExtendedExpression) -> BTreeSet<String> {
+    fn collect(node: &ConditionNode, out: &mut BTreeSet<String>) {
+        match node {
+            ConditionNode::Condition { field, .. } => {
+                out.insert(field.clone());
+            }
+            ConditionNode::Group { children, .. } => {
+                children.iter().for_each(|c| collect(c, out));
+            }
+        }
+    }
+
+    let mut fields = BTreeSet::new();
+    for tree in condition_trees(expression) {
+        collect(&tree.root, &mut fields);
+    }
+    fields
+}
+
+/// Warning for a field that has no values in any of the selected sources
+pub fn empty_field_warning(field: &str, source_count: usize) -> ExpressionLintWarning {
+    ExpressionLintWarning {
+        severity: ExpressionLintSeverity::Warning,
+        rule: "always_empty_field".to_string(),
+        message: format!(
+            "Field '{field}' is empty for every record in the {source_count} selected source(s)"
+        ),
+        field: Some(field.to_string()),
+        suggestion: Some(
+            "Conditions on this field never match positively; check the source data or use another field"
+                .to_string(),
+        ),
+    }
+}
+
+fn condition_trees(expression: &ExtendedExpression) -> Vec<&crate::models::ConditionTree> {
+    match expression {
+        ExtendedExpression::ConditionOnly(tree) => vec![tree],
+        ExtendedExpression::ConditionWithActions { condition, .. } => vec![condition],
+        ExtendedExpression::ConditionalActionGroups(groups) => {
+            groups.iter().map(|g| &g.conditions).collect()
+        }
+    }
+}
+
+fn lint_node(node: &ConditionNode, warnings: &mut Vec<ExpressionLintWarning>) {
+    match node {
+        ConditionNode::Condition {
+            field,
+            operator,
+            value,
+            case_sensitive,
+            ..
+        } => lint_condition(field, operator, value, *case_sensitive, warnings),
+        ConditionNode::Group { operator, children } => {
+            lint_group(operator, children, warnings);
+            children.iter().for_each(|c| lint_node(c, warnings));
+        }
+    }
+}
+
+fn lint_condition(
+    field: &str,
+    operator: &FilterOperator,
+    value: &str,
+    case_sensitive: bool,
+    warnings: &mut Vec<ExpressionLintWarning>,
+) {
+    let is_regex = matches!(
+        operator,
+        FilterOperator::Matches | FilterOperator::NotMatches
+    );
+
+    if is_regex {
+        if let Some(issue) = regex_backtracking_issue(value) {
+            warnings.push(ExpressionLintWarning {
+                severity: ExpressionLintSeverity::Warning,
+                rule: "catastrophic_regex".to_string(),
+                message: format!("{issue} in regex '{value}'"),
+                field: Some(field.to_string()),
+                suggestion: Some(
+                    "Remove the outer quantifier or make the repeated parts unambiguous"
+                        .to_string(),
+                ),
+            });
+        }
+
+        // Regex conditions are evaluated as written; the case modifier does not apply to them
+        let has_letters = value.chars().any(|c| c.is_ascii_alphabetic());
+        if !case_sensitive && has_letters && !value.contains("(?i") {
+            warnings.push(ExpressionLintWarning {
+                severity: ExpressionLintSeverity::Info,
+                rule: "case_sensitive_regex".to_string(),
+                message: format!(
+                    "Regex '{value}' on '{field}' is case-sensitive, unlike the other text operators"
+                ),
+                field: Some(field.to_string()),
+                suggestion: Some(format!("Prefix the pattern with (?i) to ignore case: (?i){value}")),
+            });
+        }
+        return;
+    }
+
+    let is_text_match = matches!(
+        operator,
+        FilterOperator::Equals
+            | FilterOperator::NotEquals
+            | FilterOperator::Contains
+            | FilterOperator::NotContains
+            | FilterOperator::StartsWith
+            | FilterOperator::NotStartsWith
+            | FilterOperator::EndsWith
+            | FilterOperator::NotEndsWith
+    );
+    let letters: Vec<char> = value.chars().filter(|c| c.is_alphabetic()).collect();
+    if case_sensitive
+        && is_text_match
+        && letters.len() > 1
+        && (letters.iter().all(|c| c.is_lowercase()) || letters.iter().all(|c| c.is_uppercase()))
+    {
+        warnings.push(ExpressionLintWarning {
+            severity: ExpressionLintSeverity::Info,
+            rule: "case_sensitive_single_case".to_string(),
+            message: format!(
+                "Case-sensitive comparison of '{field}' with single-case value '{value}' misses differently capitalised values"
+            ),
+            field: Some(field.to_string()),
+            suggestion: Some("Drop the case_sensitive modifier unless exact casing matters".to_string()),
+        });
+    }
+}
+
+fn lint_group(
+    operator: &LogicalOperator,
+    children: &[ConditionNode],
+    warnings: &mut Vec<ExpressionLintWarning>,
+) {
+    let keys: Vec<Option<serde_json::Value>> = children
+        .iter()
+        .map(|c| serde_json::to_value(c).ok())
+        .collect();
+    let op_name = operator.to_string().to_uppercase();
+
+    for (i, key) in keys.iter().enumerate() {
+        let Some(key) = key else { continue };
+        if keys[..i].iter().flatten().any(|k| k == key) {
+            warnings.push(ExpressionLintWarning {
+                severity: ExpressionLintSeverity::Warning,
+                rule: "duplicate_branch".to_string(),
+                message: format!("Duplicate {op_name} branch: {}", describe(&children[i])),
+                field: condition_field(&children[i]),
+                suggestion: Some("Remove the repeated condition".to_string()),
+            });
+        }
+    }
+
+    // `contains "HD"` OR `contains "FHD"`: the second branch can never add a match
+    if *operator == LogicalOperator::Or {
+        for (i, child) in children.iter().enumerate() {
+            for (j, other) in children.iter().enumerate() {
+                if i != j
+                    && keys[i] != keys[j]
+                    && contains_subsumes(other, child)
+                    && !(contains_subsumes(child, other) && j > i)
+                {
+                    warnings.push(ExpressionLintWarning {
+                        severity: ExpressionLintSeverity::Info,
+                        rule: "redundant_branch".to_string(),
+                        message: format!(
+                            "OR branch {} is already covered by {}",
+                            describe(child),
+                            describe(other)
+                        ),
+                        field: condition_field(child),
+                        suggestion: Some("Remove the narrower condition".to_string()),
+                    });
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Whether `broad` (a `contains` condition) matches everything `narrow` (also `contains`) matches
+fn contains_subsumes(broad: &ConditionNode, narrow: &ConditionNode) -> bool {
+    match (broad, narrow) {
+        (
+            ConditionNode::Condition {
+                field: f1,
+                operator: FilterOperator::Contains,
+                value: v1,
+                case_sensitive: c1,
+                negate: false,
+            },
+            ConditionNode::Condition {
+                field: f2,
+                operator: FilterOperator::Contains,
+                value: v2,
+                case_sensitive: c2,
+                negate: false,
+            },
+        ) if f1 == f2 && !v1.is_empty() => {
+            if !*c1 {
+                v2.to_lowercase().contains(&v1.to_lowercase())
+            } else {
+                *c2 && v2.contains(v1.as_str())
+            }
+        }
+        _ => false,
+    }
+}
+
+fn condition_field(node: &ConditionNode) -> Option<String> {
+    match node {
+        ConditionNode::Condition { field, .. } => Some(field.clone()),
+        ConditionNode::Group { .. } => None,
+    }
+}
+
+fn describe(node: &ConditionNode) -> String {
+    match node {
+        ConditionNode::Condition {
+            field,
+            operator,
+            value,
+            negate,
+            ..
+        } => {
+            let op = serde_json::to_value(operator)
+                .ok()
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or_else(|| format!("{operator:?}"));
+            let not = if *negate { "not " } else { "" };
+            format!("'{field} {not}{op} \"{value}\"'")
+        }
+        ConditionNode::Group { operator, children } => {
+            format!("({} group of {} conditions)", operator, children.len())
+        }
+    }
+}
+
+/// Detect regex shapes prone to catastrophic backtracking
+///
+/// Flags nested unbounded quantifiers such as `(a+)+` or `(.*)*` and adjacent wildcards such
+/// as `.*.*`. Returns a short description of the first issue found.
+pub fn regex_backtracking_issue(pattern: &str) -> Option<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    // Per open group: whether it contains an unbounded quantifier
+    let mut groups: Vec<bool> = Vec::new();
+    let mut in_class = false;
+    let mut prev_wildcard_end: Option<usize> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' {
+            i += 2;
+            continue;
+        }
+        if in_class {
+            if c == ']' {
+                in_class = false;
+            }
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '[' => in_class = true,
+            '(' => groups.push(false),
+            ')' => {
+                let inner_unbounded = groups.pop().unwrap_or(false);
+                let outer_unbounded = unbounded_quantifier_at(&chars, i + 1);
+                if inner_unbounded && outer_unbounded {
+                    return Some("Nested quantifier".to_string());
+                }
+                if (inner_unbounded || outer_unbounded)
+                    && let Some(parent) = groups.last_mut()
+                {
+                    *parent = true;
+                }
+            }
+            '*' | '+' => {
+                if let Some(current) = groups.last_mut() {
+                    *current = true;
+                }
+                if i > 0 && chars[i - 1] == '.' {
+                    if prev_wildcard_end == Some(i - 2) {
+                        return Some("Adjacent wildcards".to_string());
+                    }
+                    prev_wildcard_end = Some(i);
+                }
+            }
+            '{' => {
+                if unbounded_quantifier_at(&chars, i)
+                    && let Some(current) = groups.last_mut()
+                {
+                    *current = true;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Whether an unbounded quantifier (`*`, `+`, `{n,}`) starts at `pos`
+fn unbounded_quantifier_at(chars: &[char], pos: usize) -> bool {
+    match chars.get(pos) {
+        Some('*') | Some('+') => true,
+        Some('{') => {
+            let rest: String = chars[pos + 1..].iter().take_while(|c| **c != '}').collect();
+            rest.ends_with(',') && rest[..rest.len() - 1].chars().all(|c| c.is_ascii_digit())
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConditionTree;
+
+    fn cond(field: &str, operator: FilterOperator, value: &str, cs: bool) -> ConditionNode {
+        ConditionNode::Condition {
+            field: field.to_string(),
+            operator,
+            value: value.to_string(),
+            case_sensitive: cs,
+            negate: false,
+        }
+    }
+
+    fn or(children: Vec<ConditionNode>) -> ExtendedExpression {
+        ExtendedExpression::ConditionOnly(ConditionTree {
+            root: ConditionNode::Group {
+                operator: LogicalOperator::Or,
+                children,
+            },
+        })
+    }
+
+    fn rules(warnings: &[ExpressionLintWarning]) -> Vec<&str> {
+        warnings.iter().map(|w| w.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_regex_backtracking_issue() {
+        assert!(regex_backtracking_issue(r"^(\w+)+$").is_some());
+        assert!(regex_backtracking_issue(r"(.*)*x").is_some());
+        assert!(regex_backtracking_issue(r"(a{2,})+").is_some());
+        assert!(regex_backtracking_issue(r".*.*HD").is_some());
+        assert!(regex_backtracking_issue(r"^(\w+)\s+HD$").is_none());
+        assert!(regex_backtracking_issue(r"[(+)]+").is_none());
+        assert!(regex_backtracking_issue(r"(ab){2}+").is_none());
+    }
+
+    #[test]
+    fn test_duplicate_and_redundant_branches() {
+        let expr = or(vec![
+            cond("channel_name", FilterOperator::Contains, "HD", false),
+            cond("channel_name", FilterOperator::Contains, "HD", false),
+            cond("channel_name", FilterOperator::Contains, "fhd", false),
+            cond("group_title", FilterOperator::Contains, "hd sports", false),
+        ]);
+        let warnings = lint_expression(&expr);
+        assert_eq!(
+            rules(&warnings),
+            vec!["duplicate_branch", "redundant_branch"]
+        );
+        assert!(warnings[1].message.contains("fhd"));
+    }
+
+    #[test]
+    fn test_case_pitfalls() {
+        let expr = or(vec![
+            cond("channel_name", FilterOperator::Matches, "^sport", false),
+            cond("channel_name", FilterOperator::Matches, "(?i)^news", false),
+            cond("group_title", FilterOperator::Equals, "movies", true),
+            cond("group_title", FilterOperator::Equals, "Movies", true),
+        ]);
+        let warnings = lint_expression(&expr);
+        assert_eq!(
+            rules(&warnings),
+            vec!["case_sensitive_regex", "case_sensitive_single_case"]
+        );
+    }
+
+    #[test]
+    fn test_referenced_fields() {
+        let expr = or(vec![
+            cond("channel_name", FilterOperator::Contains, "a", false),
+            cond("tvg_id", FilterOperator::Equals, "b", false),
+        ]);
+        let fields: Vec<String> = referenced_fields(&expr).into_iter().collect();
+        assert_eq!(fields, vec!["channel_name", "tvg_id"]);
+    }
+}
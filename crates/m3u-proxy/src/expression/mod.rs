@@ -1,3 +1,6 @@
+pub mod explain;
+pub mod lint;
+
 use std::time::Instant;
 
 use tracing::trace;
@@ -439,7 +442,10 @@ fn validate_parsed_fields(
         }
         match node {
             ConditionNode::Condition { field, .. } => {
-                if !field_set.contains(field.as_str()) {
+                if !field_set
+                    .iter()
+                    .any(|name| FieldRegistry::field_matches(name, field))
+                {
                     // Find best suggestion
                     let mut best: Option<(&str, u32)> = None;
                     for cand in canonical {
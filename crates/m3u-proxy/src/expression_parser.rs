@@ -2,6 +2,7 @@
 // Supports complex expressions like: (A=B AND (C=D OR E=F) SET field = value)
 // Used by: data mapping rules, filter expressions, numbering rules, generation rules
 
+use crate::field_registry::FieldRegistry;
 use crate::models::{
     Action, ActionOperator, ActionValue, ConditionNode, ConditionTree, ExpressionErrorCategory,
     ExpressionValidateResult, ExpressionValidationError, ExtendedExpression, FilterOperator,
@@ -326,6 +327,7 @@ impl ExpressionParser {
             canonical_expression: None,
             errors,
             expression_tree,
+            warnings: Vec::new(),
         }
     }
 
@@ -950,7 +952,11 @@ impl ExpressionParser {
             return None; // Skip validation if no fields configured
         }
 
-        if !self.valid_fields.iter().any(|f| f == field) {
+        if !self
+            .valid_fields
+            .iter()
+            .any(|f| FieldRegistry::field_matches(f, field))
+        {
             // Find similar field names for suggestions
             let suggestion = self.find_similar_field_name(field);
 
@@ -97,6 +97,13 @@ macro_rules! fd {
 
 // Stage convenience arrays removed; stages are now inlined per descriptor to avoid unused constant warnings.
 
+/// Prefix addressing an extra EXTINF attribute preserved on a channel, e.g.
+/// `attr.catchup-days`.
+pub const EXTRA_ATTRIBUTE_PREFIX: &str = "attr.";
+
+/// Descriptor name standing in for every `attr.<name>` field.
+pub const EXTRA_ATTRIBUTE_FIELD: &str = "attr.*";
+
 /// Static registry of canonical descriptors.
 /// NOTE: Keep alphabetical-ish grouping per domain for clarity.
 static FIELD_DESCRIPTORS: &[FieldDescriptor] = &[
@@ -164,6 +171,26 @@ static FIELD_DESCRIPTORS: &[FieldDescriptor] = &[
         stages: [StageKind::Filtering, StageKind::DataMapping, StageKind::Numbering, StageKind::Generation],
         aliases: []
     },
+    fd! {
+        name: "epg_shift",
+        display: "EPG Time Shift",
+        ty: FieldDataType::String,
+        nullable: true,
+        read_only: false,
+        sources: [SourceKind::Stream],
+        stages: [StageKind::DataMapping, StageKind::Generation],
+        aliases: []
+    },
+    fd! {
+        name: EXTRA_ATTRIBUTE_FIELD,
+        display: "Extra EXTINF Attribute",
+        ty: FieldDataType::String,
+        nullable: true,
+        read_only: false,
+        sources: [SourceKind::Stream],
+        stages: [StageKind::Filtering, StageKind::DataMapping],
+        aliases: []
+    },
     fd! {
         name: "tvg_chno",
         display: "Channel Number",
@@ -351,8 +378,38 @@ static FIELD_DESCRIPTORS: &[FieldDescriptor] = &[
         stages: [StageKind::Filtering, StageKind::DataMapping, StageKind::Generation],
         aliases: ["subtitles", "programme_subtitles"]
     },
-    // (Optional) temporal fields: add when expression engine supports date arithmetic.
-    // fd! { name: "start_time", ... } etc.
+    // Temporal fields are filter-only: times render as RFC 3339 UTC (`2024-01-01T20:00:00Z`),
+    // which compares correctly as text; the duration is in whole minutes.
+    fd! {
+        name: "start_time",
+        display: "Programme Start",
+        ty: FieldDataType::DateTime,
+        nullable: false,
+        read_only: true,
+        sources: [SourceKind::Epg],
+        stages: [StageKind::Filtering],
+        aliases: ["programme_start", "program_start"]
+    },
+    fd! {
+        name: "end_time",
+        display: "Programme End",
+        ty: FieldDataType::DateTime,
+        nullable: false,
+        read_only: true,
+        sources: [SourceKind::Epg],
+        stages: [StageKind::Filtering],
+        aliases: ["programme_end", "program_end"]
+    },
+    fd! {
+        name: "programme_duration",
+        display: "Programme Duration (minutes)",
+        ty: FieldDataType::Duration,
+        nullable: false,
+        read_only: true,
+        sources: [SourceKind::Epg],
+        stages: [StageKind::Filtering],
+        aliases: ["program_duration", "duration"]
+    },
 ];
 
 /// Central registry object (immutable after init).
@@ -454,6 +511,22 @@ impl FieldRegistry {
         self.canonical_set.contains(field)
     }
 
+    /// Attribute name addressed by an `attr.<name>` field, if `field` is one.
+    pub fn extra_attribute_name(field: &str) -> Option<&str> {
+        field
+            .strip_prefix(EXTRA_ATTRIBUTE_PREFIX)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Does `field` match the registered name `name`? A trailing `*` in `name`
+    /// matches any non-empty remainder (used by `attr.*`).
+    pub fn field_matches(name: &str, field: &str) -> bool {
+        match name.strip_suffix('*') {
+            Some(prefix) => field.len() > prefix.len() && field.starts_with(prefix),
+            None => name == field,
+        }
+    }
+
     /// Utility to sanitise a source URL (remove credentials + sensitive query params).
     /// This is intentionally conservative; expand as needed.
     pub fn sanitise_source_url(raw: &str) -> String {
@@ -544,6 +617,28 @@ mod tests {
         assert!(fields.contains(&"tvg_chno"));
     }
 
+    #[test]
+    fn extra_attribute_fields_match_wildcard() {
+        assert_eq!(
+            FieldRegistry::extra_attribute_name("attr.catchup-days"),
+            Some("catchup-days")
+        );
+        assert_eq!(FieldRegistry::extra_attribute_name("attr."), None);
+        assert_eq!(FieldRegistry::extra_attribute_name("channel_name"), None);
+        assert!(FieldRegistry::field_matches(
+            EXTRA_ATTRIBUTE_FIELD,
+            "attr.timeshift"
+        ));
+        assert!(!FieldRegistry::field_matches(
+            EXTRA_ATTRIBUTE_FIELD,
+            "attr."
+        ));
+        assert!(!FieldRegistry::field_matches(
+            "channel_name",
+            "channel_names"
+        ));
+    }
+
     #[test]
     fn epg_aliases_work() {
         let reg = FieldRegistry::global();
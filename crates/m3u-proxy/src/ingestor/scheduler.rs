@@ -15,10 +15,12 @@ use crate::database::repositories::{
     ChannelSeaOrmRepository, EpgSourceSeaOrmRepository, StreamSourceSeaOrmRepository,
 };
 use crate::models::*;
-use crate::services::{EpgSourceService, ProxyRegenerationService, StreamSourceBusinessService};
+use crate::services::{
+    CachedEntity, EpgSourceService, ProxyRegenerationService, StreamSourceBusinessService,
+};
 
-pub type CacheInvalidationSender = broadcast::Sender<()>;
-pub type CacheInvalidationReceiver = broadcast::Receiver<()>;
+pub type CacheInvalidationSender = broadcast::Sender<CachedEntity>;
+pub type CacheInvalidationReceiver = broadcast::Receiver<CachedEntity>;
 
 pub fn create_cache_invalidation_channel() -> (CacheInvalidationSender, CacheInvalidationReceiver) {
     broadcast::channel(100)
@@ -4,6 +4,7 @@ use crate::config::Config;
 use crate::database::Database;
 use crate::database::repositories::StreamProxySeaOrmRepository;
 use crate::ingestor::IngestionStateManager;
+use crate::services::generation_hooks::{GenerationHookService, GenerationHookSummary};
 use crate::services::logo_cache_maintenance::LogoCacheMaintenanceService;
 use crate::services::progress_service::{OperationType, ProgressService};
 use crate::services::{EpgSourceService, ProxyRegenerationService, StreamSourceBusinessService};
@@ -25,6 +26,9 @@ pub struct JobExecutor {
     temp_file_manager: sandboxed_file_manager::SandboxedManager,
     http_client_factory: Arc<crate::utils::HttpClientFactory>,
     progress_service: Arc<ProgressService>,
+    mqtt_publisher: crate::services::MqttPublisher,
+    logo_service: Option<crate::logo_assets::service::LogoAssetService>,
+    channel_count_alerts: Option<crate::services::ChannelCountAlertService>,
 }
 
 impl JobExecutor {
@@ -54,9 +58,36 @@ impl JobExecutor {
             temp_file_manager,
             http_client_factory,
             progress_service,
+            mqtt_publisher: crate::services::MqttPublisher::disabled(),
+            logo_service: None,
+            channel_count_alerts: None,
         }
     }
 
+    /// Publish source refresh failures to MQTT
+    pub fn with_mqtt_publisher(mut self, mqtt_publisher: crate::services::MqttPublisher) -> Self {
+        self.mqtt_publisher = mqtt_publisher;
+        self
+    }
+
+    /// Download logos deferred by generations (the `logo_prefetch` maintenance job)
+    pub fn with_logo_service(
+        mut self,
+        logo_service: crate::logo_assets::service::LogoAssetService,
+    ) -> Self {
+        self.logo_service = Some(logo_service);
+        self
+    }
+
+    /// Raise alerts when a generation's channel count changes sharply
+    pub fn with_channel_count_alerts(
+        mut self,
+        alerts: crate::services::ChannelCountAlertService,
+    ) -> Self {
+        self.channel_count_alerts = Some(alerts);
+        self
+    }
+
     /// Execute a stream source ingestion job
     /// Returns list of affected proxy IDs that need regeneration
     pub async fn execute_stream_job(&self, source_id: Uuid) -> Result<Vec<Uuid>> {
@@ -109,6 +140,14 @@ impl JobExecutor {
             }
             Err(e) => {
                 warn!("Failed to refresh stream source {}: {}", source_id, e);
+                self.mqtt_publisher.publish(
+                    crate::services::AutomationEvent::SourceRefreshFailed {
+                        source_id: source_id.to_string(),
+                        source_name: source.name.clone(),
+                        source_type: "stream".to_string(),
+                        error: e.to_string(),
+                    },
+                );
                 Err(e)
             }
         }
@@ -166,6 +205,14 @@ impl JobExecutor {
             }
             Err(e) => {
                 warn!("Failed to ingest EPG source {}: {}", source_id, e);
+                self.mqtt_publisher.publish(
+                    crate::services::AutomationEvent::SourceRefreshFailed {
+                        source_id: source_id.to_string(),
+                        source_name: source.name.clone(),
+                        source_type: "epg".to_string(),
+                        error: e.to_string(),
+                    },
+                );
                 Err(e)
             }
         }
@@ -213,6 +260,10 @@ impl JobExecutor {
                 .await
                 .map(|_| ()),
             "memory_cleanup" => self.cleanup_memory().await,
+            crate::services::logo_prefetch::LOGO_PREFETCH_JOB => {
+                self.prefetch_deferred_logos().await
+            }
+            crate::services::trash::TRASH_PURGE_JOB => self.purge_expired_trash().await,
             _ => {
                 warn!("Unknown maintenance operation: {}", operation);
                 Err(anyhow::anyhow!(
@@ -223,6 +274,24 @@ impl JobExecutor {
         }
     }
 
+    /// Download a batch of the logos deferred by generations over their download budget
+    async fn prefetch_deferred_logos(&self) -> Result<()> {
+        let Some(logo_service) = &self.logo_service else {
+            warn!("Logo prefetch job skipped: no logo service configured");
+            return Ok(());
+        };
+        let config = self.app_config.logo_prefetch.clone().unwrap_or_default();
+        crate::services::logo_prefetch::fetch_deferred_logos(logo_service, &config).await;
+        Ok(())
+    }
+
+    /// Permanently remove items that have been in the trash past the retention period
+    async fn purge_expired_trash(&self) -> Result<()> {
+        let config = self.app_config.trash.clone().unwrap_or_default();
+        crate::services::trash::purge_expired(self.database.connection().clone(), &config).await?;
+        Ok(())
+    }
+
     /// Find proxies that use a specific stream source
     #[allow(dead_code)] // Placeholder for future implementation
     async fn find_proxies_using_stream_source(&self, _source_id: Uuid) -> Result<Vec<Uuid>> {
@@ -311,6 +380,22 @@ impl JobExecutor {
             }
         };
 
+        let generation_hooks = GenerationHookService::from_config(&self.app_config);
+        if let Some(hooks) = &generation_hooks
+            && let Err(e) = hooks
+                .run(&GenerationHookSummary::pre(proxy_id, proxy_name))
+                .await
+        {
+            if let Some(ref pm) = progress_manager {
+                pm.fail(&e).await;
+            }
+            return Err(anyhow::anyhow!(
+                "Regeneration of proxy '{}' aborted: {}",
+                proxy_name,
+                e
+            ));
+        }
+
         // Create pipeline factory with all required components
         let factory = PipelineOrchestratorFactory::from_components(
             self.database.clone(),
@@ -390,6 +475,24 @@ impl JobExecutor {
                             );
                         }
 
+                        if let (Some(alerts), Some(channel_count)) =
+                            (&self.channel_count_alerts, result.published_channel_count())
+                        {
+                            alerts
+                                .check_proxy(proxy_id, proxy_name, channel_count)
+                                .await;
+                        }
+
+                        if let Some(hooks) = &generation_hooks {
+                            let _ = hooks
+                                .run(&GenerationHookSummary::post(
+                                    proxy_name,
+                                    &result,
+                                    &self.app_config.storage,
+                                ))
+                                .await;
+                        }
+
                         // Complete the progress manager
                         if let Some(ref pm) = progress_manager {
                             pm.complete().await;
@@ -1,6 +1,6 @@
 //! Job queue implementation with deduplication and priority ordering
 
-use super::types::{JobSchedulingError, ScheduledJob};
+use super::types::{JobClass, JobPriority, JobSchedulingError, ScheduledJob};
 use chrono::{DateTime, Utc};
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, HashMap, HashSet};
@@ -86,7 +86,7 @@ impl JobQueue {
         ready_jobs
     }
 
-    /// Get jobs that can be executed considering both time readiness and concurrency limits
+    /// Get jobs that can be executed considering time readiness, type and class concurrency limits
     pub async fn get_executable_jobs(
         &self,
         now: DateTime<Utc>,
@@ -96,11 +96,14 @@ impl JobQueue {
             usize,
         >,
         type_limits: &std::collections::HashMap<super::job_queue_runner::JobTypeCategory, usize>,
+        current_class_counts: &HashMap<JobClass, usize>,
+        class_limits: &HashMap<JobClass, usize>,
     ) -> Vec<ScheduledJob> {
         let mut pending = self.pending.write().await;
         let mut executable_jobs = Vec::new();
         let mut remaining_jobs = BinaryHeap::new();
         let mut local_type_counts = current_type_counts.clone();
+        let mut local_class_counts = current_class_counts.clone();
 
         // Extract jobs from the heap and determine which can be executed
         while let Some(Reverse(job)) = pending.pop() {
@@ -108,13 +111,19 @@ impl JobQueue {
                 let job_category = super::job_queue_runner::JobTypeCategory::from(&job.job_type);
                 let current_count = local_type_counts.get(&job_category).unwrap_or(&0);
                 let type_limit = type_limits.get(&job_category).unwrap_or(&1);
+                let job_class = job.class();
+                // Classes without a configured limit are only bound by the type limits
+                let class_available = class_limits.get(&job_class).is_none_or(|limit| {
+                    local_class_counts.get(&job_class).copied().unwrap_or(0) < *limit
+                });
 
-                if current_count < type_limit {
+                if current_count < type_limit && class_available {
                     // Can execute this job
                     executable_jobs.push(job);
                     *local_type_counts.entry(job_category).or_insert(0) += 1;
+                    *local_class_counts.entry(job_class).or_insert(0) += 1;
                 } else {
-                    // At concurrency limit for this type, put it back
+                    // At concurrency limit for this type or class, put it back
                     remaining_jobs.push(Reverse(job));
                 }
             } else {
@@ -159,6 +168,56 @@ impl JobQueue {
         }
     }
 
+    /// Pending jobs in execution order (priority, then scheduled time)
+    pub async fn list_pending(&self) -> Vec<ScheduledJob> {
+        let pending = self.pending.read().await;
+        let mut jobs: Vec<ScheduledJob> = pending.iter().map(|Reverse(job)| job.clone()).collect();
+        jobs.sort();
+        jobs
+    }
+
+    /// Change the priority of a pending job
+    ///
+    /// Returns the updated job, or None when no pending job has this ID (e.g. it already started).
+    pub async fn reprioritize(&self, job_id: Uuid, priority: JobPriority) -> Option<ScheduledJob> {
+        let mut pending = self.pending.write().await;
+        let mut jobs = std::mem::take(&mut *pending).into_vec();
+        let updated = jobs
+            .iter_mut()
+            .find(|Reverse(job)| job.id == job_id)
+            .map(|Reverse(job)| {
+                job.priority = priority;
+                job.clone()
+            });
+        *pending = BinaryHeap::from(jobs);
+
+        if let Some(job) = &updated {
+            info!("Reprioritized job {} to {:?}", job.job_key(), priority);
+        }
+        updated
+    }
+
+    /// Remove a pending job from the queue
+    ///
+    /// Returns the cancelled job, or None when no pending job has this ID. Running jobs are
+    /// not affected.
+    pub async fn cancel(&self, job_id: Uuid) -> Option<ScheduledJob> {
+        let mut pending = self.pending.write().await;
+        let mut jobs = std::mem::take(&mut *pending).into_vec();
+        let cancelled = jobs
+            .iter()
+            .position(|Reverse(job)| job.id == job_id)
+            .map(|index| jobs.swap_remove(index).0);
+        *pending = BinaryHeap::from(jobs);
+        drop(pending);
+
+        if let Some(job) = &cancelled {
+            self.job_keys.write().await.remove(&job.job_key());
+            info!("Cancelled queued job {}", job.job_key());
+        }
+        cancelled
+    }
+
     /// Get the number of currently running jobs
     pub async fn running_count(&self) -> usize {
         self.running.read().await.len()
@@ -349,6 +408,109 @@ mod tests {
         assert!(!queue.contains_job_key(&job_key).await);
     }
 
+    #[tokio::test]
+    async fn test_job_queue_reprioritize_and_cancel() {
+        let queue = JobQueue::new();
+        let now = Utc::now();
+
+        let epg_job = ScheduledJob::new_scheduled(
+            JobType::EpgIngestion(Uuid::new_v4()),
+            JobPriority::Normal,
+            now,
+        );
+        let proxy_job = ScheduledJob::new_scheduled(
+            JobType::ProxyRegeneration(Uuid::new_v4()),
+            JobPriority::Low,
+            now,
+        );
+        queue.enqueue(epg_job.clone()).await.unwrap();
+        queue.enqueue(proxy_job.clone()).await.unwrap();
+        assert_eq!(queue.list_pending().await[0].id, epg_job.id);
+
+        let updated = queue
+            .reprioritize(proxy_job.id, JobPriority::Critical)
+            .await
+            .unwrap();
+        assert_eq!(updated.priority, JobPriority::Critical);
+        assert_eq!(queue.list_pending().await[0].id, proxy_job.id);
+        assert!(
+            queue
+                .reprioritize(Uuid::new_v4(), JobPriority::High)
+                .await
+                .is_none()
+        );
+
+        let cancelled = queue.cancel(epg_job.id).await.unwrap();
+        assert_eq!(cancelled.id, epg_job.id);
+        assert!(!queue.contains_job_key(&epg_job.job_key()).await);
+        assert_eq!(queue.pending_count().await, 1);
+        assert!(queue.cancel(epg_job.id).await.is_none());
+
+        // A cancelled job can be queued again
+        assert!(queue.enqueue(epg_job).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_job_queue_class_limits() {
+        use crate::job_scheduling::job_queue_runner::JobTypeCategory;
+
+        let queue = JobQueue::new();
+        let now = Utc::now();
+        for _ in 0..3 {
+            queue
+                .enqueue(ScheduledJob::new_scheduled(
+                    JobType::EpgIngestion(Uuid::new_v4()),
+                    JobPriority::Normal,
+                    now,
+                ))
+                .await
+                .unwrap();
+        }
+        queue
+            .enqueue(ScheduledJob::new_scheduled(
+                JobType::StreamIngestion(Uuid::new_v4()),
+                JobPriority::Normal,
+                now,
+            ))
+            .await
+            .unwrap();
+        queue
+            .enqueue(ScheduledJob::new_scheduled(
+                JobType::ProxyRegeneration(Uuid::new_v4()),
+                JobPriority::Low,
+                now,
+            ))
+            .await
+            .unwrap();
+
+        let type_limits = HashMap::from([
+            (JobTypeCategory::StreamIngestion, 2),
+            (JobTypeCategory::EpgIngestion, 2),
+            (JobTypeCategory::ProxyRegeneration, 1),
+        ]);
+        let class_limits = HashMap::from([(JobClass::Ingestion, 2), (JobClass::Regeneration, 1)]);
+        let jobs = queue
+            .get_executable_jobs(
+                now,
+                10,
+                &HashMap::new(),
+                &type_limits,
+                &HashMap::new(),
+                &class_limits,
+            )
+            .await;
+
+        // Ingestion is capped at 2 despite room in the type limits; regeneration still runs
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(
+            jobs.iter()
+                .filter(|j| j.class() == JobClass::Ingestion)
+                .count(),
+            2
+        );
+        assert!(jobs.iter().any(|j| j.class() == JobClass::Regeneration));
+    }
+
     #[tokio::test]
     async fn test_job_queue_limit_ready_jobs() {
         let queue = JobQueue::new();
@@ -3,14 +3,15 @@
 use super::job_executor::JobExecutor;
 use super::job_queue::JobQueue;
 use super::job_scheduler::JobScheduler;
-use super::types::{JobType, ScheduledJob};
+use super::types::{JobClass, JobType, ScheduledJob};
 use crate::config::JobSchedulingConfig;
+use crate::services::LeaderElection;
 use anyhow::Result;
 use chrono::Utc;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use tokio::sync::RwLock as TokioRwLock;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
+use tokio::sync::{Notify, RwLock as TokioRwLock};
 use tokio::time::{Duration, interval};
 use tracing::{debug, error, info, warn};
 
@@ -21,6 +22,14 @@ pub struct JobQueueRunner {
     job_scheduler: Arc<JobScheduler>, // For scheduling follow-up jobs
     max_concurrent: Arc<AtomicUsize>,
     concurrent_limits: Arc<TokioRwLock<HashMap<JobTypeCategory, usize>>>,
+    class_limits: Arc<TokioRwLock<HashMap<JobClass, usize>>>,
+    /// While paused (maintenance mode) jobs stay queued and none are started
+    paused: Arc<AtomicBool>,
+    wake: Arc<Notify>,
+    /// In cluster mode only the leader starts jobs
+    leader_election: Option<Arc<LeaderElection>>,
+    /// Unix time (ms) the run loop last finished a pass over the queue; 0 before it starts
+    heartbeat: Arc<AtomicI64>,
 }
 
 /// Category of job types for concurrency limiting
@@ -43,6 +52,25 @@ impl From<&JobType> for JobTypeCategory {
     }
 }
 
+impl From<JobTypeCategory> for JobClass {
+    fn from(category: JobTypeCategory) -> Self {
+        match category {
+            JobTypeCategory::StreamIngestion | JobTypeCategory::EpgIngestion => JobClass::Ingestion,
+            JobTypeCategory::ProxyRegeneration => JobClass::Regeneration,
+            JobTypeCategory::Maintenance => JobClass::Maintenance,
+        }
+    }
+}
+
+/// Per-class concurrency limits from configuration
+fn class_limits_from_config(config: &JobSchedulingConfig) -> HashMap<JobClass, usize> {
+    HashMap::from([
+        (JobClass::Ingestion, config.ingestion_class_limit),
+        (JobClass::Regeneration, config.regeneration_class_limit),
+        (JobClass::Maintenance, config.maintenance_class_limit),
+    ])
+}
+
 impl JobQueueRunner {
     /// Create a new job queue runner with configuration
     pub fn new(
@@ -71,9 +99,20 @@ impl JobQueueRunner {
             job_scheduler,
             max_concurrent: Arc::new(AtomicUsize::new(config.global_max_jobs)),
             concurrent_limits: Arc::new(TokioRwLock::new(concurrent_limits)),
+            class_limits: Arc::new(TokioRwLock::new(class_limits_from_config(config))),
+            paused: Arc::new(AtomicBool::new(false)),
+            wake: Arc::new(Notify::new()),
+            leader_election: None,
+            heartbeat: Arc::new(AtomicI64::new(0)),
         }
     }
 
+    /// Only start jobs while this instance is the cluster leader; jobs stay queued otherwise
+    pub fn with_leader_election(mut self, leader_election: Arc<LeaderElection>) -> Self {
+        self.leader_election = Some(leader_election);
+        self
+    }
+
     /// Run the job queue runner service
     pub async fn run(&self, cancellation_token: tokio_util::sync::CancellationToken) -> Result<()> {
         info!(
@@ -88,6 +127,13 @@ impl JobQueueRunner {
                     if let Err(e) = self.process_pending_jobs().await {
                         error!("Error processing pending jobs: {}", e);
                     }
+                    self.beat();
+                }
+                _ = self.wake.notified() => {
+                    if let Err(e) = self.process_pending_jobs().await {
+                        error!("Error processing pending jobs: {}", e);
+                    }
+                    self.beat();
                 }
                 _ = cancellation_token.cancelled() => {
                     info!("Job queue runner received cancellation signal");
@@ -101,8 +147,56 @@ impl JobQueueRunner {
         Ok(())
     }
 
+    /// Stop starting jobs; running jobs finish and new jobs keep queueing
+    pub fn pause(&self) {
+        if !self.paused.swap(true, Ordering::Relaxed) {
+            info!("Job queue runner paused");
+        }
+    }
+
+    /// Start queued jobs again, beginning immediately
+    pub fn resume(&self) {
+        if self.paused.swap(false, Ordering::Relaxed) {
+            info!("Job queue runner resumed");
+            self.wake.notify_one();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// When the run loop last finished a pass over the queue (`None` before it has started)
+    ///
+    /// Passes happen at least every 5 seconds while running, also when paused or a follower,
+    /// so a stale heartbeat means the runner is stuck.
+    pub fn last_heartbeat(&self) -> Option<chrono::DateTime<Utc>> {
+        match self.heartbeat.load(Ordering::Relaxed) {
+            0 => None,
+            millis => chrono::DateTime::from_timestamp_millis(millis),
+        }
+    }
+
+    fn beat(&self) {
+        self.heartbeat
+            .store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+
     /// Process jobs that are ready to run
     async fn process_pending_jobs(&self) -> Result<()> {
+        if self.is_paused() {
+            debug!("Job queue runner paused, leaving jobs queued");
+            return Ok(());
+        }
+        if self
+            .leader_election
+            .as_ref()
+            .is_some_and(|l| !l.is_leader())
+        {
+            debug!("Not the cluster leader, leaving jobs queued");
+            return Ok(());
+        }
+
         let now = Utc::now();
         let current_running = self.job_queue.running_count().await;
 
@@ -119,16 +213,26 @@ impl JobQueueRunner {
         // Get running job keys to check per-type limits
         let running_job_keys = self.job_queue.get_running_job_keys().await;
         let type_counts = self.count_running_jobs_by_type(&running_job_keys);
+        let class_counts = Self::count_jobs_by_class(&type_counts);
 
         // Get current limits (read lock)
         let concurrent_limits = self.concurrent_limits.read().await;
+        let class_limits = self.class_limits.read().await;
 
-        // Get jobs that can actually be executed based on type limits
+        // Get jobs that can actually be executed based on type and class limits
         let jobs_to_execute = self
             .job_queue
-            .get_executable_jobs(now, available_slots, &type_counts, &concurrent_limits)
+            .get_executable_jobs(
+                now,
+                available_slots,
+                &type_counts,
+                &concurrent_limits,
+                &class_counts,
+                &class_limits,
+            )
             .await;
 
+        drop(class_limits);
         drop(concurrent_limits); // Release lock early
 
         if jobs_to_execute.is_empty() {
@@ -262,6 +366,17 @@ impl JobQueueRunner {
         counts
     }
 
+    /// Aggregate per-type counts into per-class counts
+    fn count_jobs_by_class(
+        type_counts: &HashMap<JobTypeCategory, usize>,
+    ) -> HashMap<JobClass, usize> {
+        let mut counts = HashMap::new();
+        for (category, count) in type_counts {
+            *counts.entry(JobClass::from(*category)).or_insert(0) += count;
+        }
+        counts
+    }
+
     /// Wait for all running jobs to complete during shutdown
     async fn wait_for_running_jobs_to_complete(&self) {
         info!("Waiting for running jobs to complete...");
@@ -362,6 +477,7 @@ impl JobQueueRunner {
     /// Get current concurrency configuration
     pub async fn get_concurrency_config(&self) -> JobSchedulingConfig {
         let limits = self.concurrent_limits.read().await;
+        let class_limits = self.class_limits.read().await;
 
         JobSchedulingConfig {
             global_max_jobs: self.max_concurrent.load(Ordering::Relaxed),
@@ -371,6 +487,9 @@ impl JobQueueRunner {
                 .get(&JobTypeCategory::ProxyRegeneration)
                 .unwrap_or(&1),
             maintenance_limit: *limits.get(&JobTypeCategory::Maintenance).unwrap_or(&1),
+            ingestion_class_limit: *class_limits.get(&JobClass::Ingestion).unwrap_or(&1),
+            regeneration_class_limit: *class_limits.get(&JobClass::Regeneration).unwrap_or(&1),
+            maintenance_class_limit: *class_limits.get(&JobClass::Maintenance).unwrap_or(&1),
         }
     }
 
@@ -392,6 +511,10 @@ impl JobQueueRunner {
             config.proxy_regeneration_limit,
         );
         let old_maintenance = limits.insert(JobTypeCategory::Maintenance, config.maintenance_limit);
+        drop(limits);
+
+        // Update class limits
+        *self.class_limits.write().await = class_limits_from_config(config);
 
         info!("Updated job scheduling configuration:");
         info!("  Global max: {}", config.global_max_jobs);
@@ -415,6 +538,12 @@ impl JobQueueRunner {
             old_maintenance.unwrap_or(1),
             config.maintenance_limit
         );
+        info!(
+            "  Classes: ingestion={} regeneration={} maintenance={}",
+            config.ingestion_class_limit,
+            config.regeneration_class_limit,
+            config.maintenance_class_limit
+        );
     }
 }
 
@@ -475,6 +604,21 @@ mod tests {
         assert_eq!(counts.get(&JobTypeCategory::Maintenance), None);
     }
 
+    #[test]
+    fn test_count_running_jobs_by_class() {
+        let type_counts = HashMap::from([
+            (JobTypeCategory::StreamIngestion, 2),
+            (JobTypeCategory::EpgIngestion, 1),
+            (JobTypeCategory::ProxyRegeneration, 1),
+        ]);
+
+        let counts = JobQueueRunner::count_jobs_by_class(&type_counts);
+
+        assert_eq!(counts.get(&JobClass::Ingestion), Some(&3));
+        assert_eq!(counts.get(&JobClass::Regeneration), Some(&1));
+        assert_eq!(counts.get(&JobClass::Maintenance), None);
+    }
+
     // Integration tests would require proper mocking of JobExecutor and JobScheduler
     // These would test the full job execution pipeline
 }
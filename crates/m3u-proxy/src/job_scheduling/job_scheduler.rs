@@ -5,11 +5,15 @@ use super::types::{JobPriority, JobType, ScheduledJob};
 use crate::database::Database;
 use crate::database::repositories::{EpgSourceSeaOrmRepository, StreamSourceSeaOrmRepository};
 use crate::models::{EpgSource, StreamSource};
+use crate::services::LeaderElection;
+use crate::services::logo_prefetch::{LOGO_PREFETCH_JOB, LogoPrefetchQueue};
+use crate::services::trash::TRASH_PURGE_JOB;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use cron::Schedule;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
 use tokio::time::{Duration, interval};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
@@ -19,6 +23,10 @@ pub struct JobScheduler {
     job_queue: Arc<JobQueue>,
     stream_source_repo: StreamSourceSeaOrmRepository,
     epg_source_repo: EpgSourceSeaOrmRepository,
+    leader_election: Option<Arc<LeaderElection>>,
+    last_tick: AtomicI64,
+    trash_purge_interval: Option<Duration>,
+    last_trash_purge: AtomicI64,
 }
 
 impl JobScheduler {
@@ -29,9 +37,25 @@ impl JobScheduler {
             job_queue,
             stream_source_repo: StreamSourceSeaOrmRepository::new(connection.clone()),
             epg_source_repo: EpgSourceSeaOrmRepository::new(connection),
+            leader_election: None,
+            last_tick: AtomicI64::new(0),
+            trash_purge_interval: None,
+            last_trash_purge: AtomicI64::new(0),
         }
     }
 
+    /// Only schedule jobs while this instance is the cluster leader
+    pub fn with_leader_election(mut self, leader_election: Arc<LeaderElection>) -> Self {
+        self.leader_election = Some(leader_election);
+        self
+    }
+
+    /// Schedule the trash purge job every `interval`
+    pub fn with_trash_purge_interval(mut self, interval: Duration) -> Self {
+        self.trash_purge_interval = Some(interval);
+        self
+    }
+
     /// Run the job scheduler service
     pub async fn run(&self, cancellation_token: tokio_util::sync::CancellationToken) -> Result<()> {
         info!("Starting job scheduler service");
@@ -39,10 +63,15 @@ impl JobScheduler {
 
         // Skip the first immediate tick to avoid scheduling jobs right at startup
         schedule_check.tick().await;
+        self.record_tick();
 
         loop {
             tokio::select! {
                 _ = schedule_check.tick() => {
+                    self.record_tick();
+                    if self.leader_election.as_ref().is_some_and(|l| !l.is_leader()) {
+                        continue;
+                    }
                     if let Err(e) = self.schedule_due_jobs().await {
                         error!("Error scheduling due jobs: {}", e);
                     }
@@ -58,6 +87,21 @@ impl JobScheduler {
         Ok(())
     }
 
+    /// When the schedule loop last ticked (`None` before it has started)
+    ///
+    /// The loop ticks every minute, also as a follower, so a stale tick means it is stuck.
+    pub fn last_tick(&self) -> Option<DateTime<Utc>> {
+        match self.last_tick.load(Ordering::Relaxed) {
+            0 => None,
+            millis => DateTime::from_timestamp_millis(millis),
+        }
+    }
+
+    fn record_tick(&self) {
+        self.last_tick
+            .store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+
     /// Check all sources and schedule jobs for those that are due
     async fn schedule_due_jobs(&self) -> Result<()> {
         let now = Utc::now();
@@ -90,9 +134,35 @@ impl JobScheduler {
             Err(e) => error!("Failed to fetch EPG sources: {}", e),
         }
 
+        // Logos deferred by generations over their download budget
+        if !LogoPrefetchQueue::global().is_empty() {
+            self.schedule_maintenance(LOGO_PREFETCH_JOB.to_string(), JobPriority::Maintenance)
+                .await?;
+        }
+
+        // Trashed items past their retention period
+        if self.trash_purge_due(now) {
+            self.schedule_maintenance(TRASH_PURGE_JOB.to_string(), JobPriority::Maintenance)
+                .await?;
+        }
+
         Ok(())
     }
 
+    /// Whether the trash purge interval has elapsed since it was last scheduled
+    fn trash_purge_due(&self, now: DateTime<Utc>) -> bool {
+        let Some(interval) = self.trash_purge_interval else {
+            return false;
+        };
+        let last = self.last_trash_purge.load(Ordering::Relaxed);
+        if last != 0 && now.timestamp_millis() - last < interval.as_millis() as i64 {
+            return false;
+        }
+        self.last_trash_purge
+            .store(now.timestamp_millis(), Ordering::Relaxed);
+        true
+    }
+
     /// Check if a stream source needs scheduling and enqueue it
     async fn check_and_schedule_stream_source(
         &self,
@@ -147,6 +217,10 @@ impl JobScheduler {
             return Ok(()); // Skip inactive sources
         }
 
+        if source.is_file_backed() {
+            return Ok(()); // Refreshed by the XMLTV watch folder, not by cron
+        }
+
         if self.should_schedule_source(&source.update_cron, source.last_ingested_at, now)? {
             let job = ScheduledJob::new(JobType::EpgIngestion(source.id), JobPriority::Normal);
 
@@ -6,7 +6,7 @@ use std::cmp::Ordering;
 use uuid::Uuid;
 
 /// Priority levels for job execution
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
 pub enum JobPriority {
     /// System startup, recovery operations
     Critical = 0,
@@ -70,6 +70,31 @@ impl JobType {
     }
 }
 
+/// Concurrency class of a job
+///
+/// Each class has its own concurrency limit, so a backlog in one class (e.g. many EPG
+/// refreshes) cannot occupy the slots another class (e.g. a manual regeneration) needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobClass {
+    /// Stream and EPG source ingestion
+    Ingestion,
+    /// Proxy regeneration
+    Regeneration,
+    /// Background maintenance
+    Maintenance,
+}
+
+impl From<&JobType> for JobClass {
+    fn from(job_type: &JobType) -> Self {
+        match job_type {
+            JobType::StreamIngestion(_) | JobType::EpgIngestion(_) => JobClass::Ingestion,
+            JobType::ProxyRegeneration(_) => JobClass::Regeneration,
+            JobType::Maintenance(_) => JobClass::Maintenance,
+        }
+    }
+}
+
 /// A scheduled job ready for execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledJob {
@@ -117,6 +142,11 @@ impl ScheduledJob {
     pub fn is_ready(&self, now: DateTime<Utc>) -> bool {
         self.scheduled_time <= now
     }
+
+    /// Get the concurrency class of this job
+    pub fn class(&self) -> JobClass {
+        JobClass::from(&self.job_type)
+    }
 }
 
 impl PartialEq for ScheduledJob {
@@ -237,6 +267,26 @@ mod tests {
         assert!(earlier_job < later_job);
     }
 
+    #[test]
+    fn test_job_class_mapping() {
+        assert_eq!(
+            JobClass::from(&JobType::StreamIngestion(Uuid::new_v4())),
+            JobClass::Ingestion
+        );
+        assert_eq!(
+            JobClass::from(&JobType::EpgIngestion(Uuid::new_v4())),
+            JobClass::Ingestion
+        );
+        assert_eq!(
+            JobClass::from(&JobType::ProxyRegeneration(Uuid::new_v4())),
+            JobClass::Regeneration
+        );
+        assert_eq!(
+            JobClass::from(&JobType::Maintenance("cleanup".to_string())),
+            JobClass::Maintenance
+        );
+    }
+
     #[test]
     fn test_job_is_ready() {
         let now = Utc::now();
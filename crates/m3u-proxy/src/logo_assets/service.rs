@@ -726,6 +726,25 @@ impl LogoAssetService {
         Ok(cache_id)
     }
 
+    /// Whether the logo of `logo_url` is already in the logo cache, i.e. caching it again
+    /// will not download anything
+    pub async fn is_logo_cached(&self, logo_url: &str) -> bool {
+        self.cached_logo_id(logo_url).await.is_some()
+    }
+
+    /// Cache id of the logo of `logo_url`, when it is in the logo cache
+    pub async fn cached_logo_id(&self, logo_url: &str) -> Option<String> {
+        let cache_id = Self::generate_cache_id_from_url(logo_url).ok()?;
+        let cached = match &self.logo_file_manager {
+            Some(file_manager) => file_manager
+                .exists(format!("{cache_id}.png"))
+                .await
+                .unwrap_or(false),
+            None => self.get_cached_logo_path(&cache_id).exists(),
+        };
+        cached.then_some(cache_id)
+    }
+
     /// Download and cache a logo from a URL with optional metadata and size tracking
     pub async fn cache_logo_from_url_with_metadata_and_size_tracking(
         &self,
@@ -111,6 +111,14 @@ struct Cli {
     #[arg(short = 'v', long)]
     version: bool,
 
+    /// Benchmark each pipeline stage on synthetic datasets and exit
+    #[arg(long)]
+    bench_pipeline: bool,
+
+    /// Dataset sizes in channels for --bench-pipeline (default 1000,10000,100000)
+    #[arg(long, value_delimiter = ',')]
+    bench_sizes: Vec<usize>,
+
     /// Optional subcommand (if omitted, we run the server)
     #[command(subcommand)]
     command: Option<Command>,
@@ -131,6 +139,55 @@ enum Command {
         #[arg(long)]
         apply_migrations: bool,
     },
+    /// Check configuration, database, FFmpeg, storage and network and print a pass/fail report
+    Doctor {
+        #[arg(short, long, default_value = "config.toml")]
+        config: String,
+        #[arg(short = 'd', long)]
+        database_url: Option<String>,
+        /// Output JSON instead of plain text
+        #[arg(long)]
+        json: bool,
+        /// URL fetched to verify outbound HTTP connectivity
+        #[arg(long, default_value = "https://example.com/")]
+        http_url: String,
+        /// Skip the outbound HTTP check
+        #[arg(long)]
+        skip_http: bool,
+    },
+    /// Write synthetic M3U and XMLTV files for benchmarking and reproducing scale issues
+    GenerateFixtures {
+        /// Directory the fixtures are written to
+        #[arg(short, long, default_value = "fixtures")]
+        output_dir: std::path::PathBuf,
+        /// Number of channels
+        #[arg(long, default_value_t = 1000)]
+        channels: usize,
+        /// Number of channel groups
+        #[arg(long, default_value_t = 20)]
+        groups: usize,
+        /// How channels spread over groups: uniform, or skewed (a few large groups)
+        #[arg(long, default_value = "skewed")]
+        group_distribution: m3u_proxy::utils::fixture_generator::GroupDistribution,
+        /// Percentage of channels with guide data
+        #[arg(long, default_value_t = 90.0)]
+        epg_coverage: f64,
+        /// Days of guide data
+        #[arg(long, default_value_t = 2)]
+        epg_days: u32,
+        /// Average programmes per channel and day
+        #[arg(long, default_value_t = 24)]
+        programmes_per_day: u32,
+        /// Percentage of malformed playlist entries and guide programmes
+        #[arg(long, default_value_t = 0.0)]
+        malformed: f64,
+        /// Random seed; the same seed and options produce the same files
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Output JSON instead of plain text
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 /// ------------------------------
@@ -163,40 +220,9 @@ fn redact_db_url(url: &str) -> String {
 }
 
 async fn gather_schema_status(db: &Database, original_url: &str) -> Result<SchemaStatus> {
-    use m3u_proxy::database::migrations::Migrator;
-    use sea_orm_migration::MigratorTrait;
-
-    let available: Vec<String> = Migrator::migrations()
-        .into_iter()
-        .map(|m| m.name().to_string())
-        .collect();
-
-    let mut applied: Vec<String> = Vec::new();
-    if let Ok(rows) = db
-        .connection
-        .query_all(Statement::from_string(
-            db.backend(),
-            "SELECT version FROM seaql_migrations ORDER BY version".to_string(),
-        ))
-        .await
-    {
-        for r in rows {
-            if let Ok(v) = r.try_get::<String>("", "version") {
-                applied.push(v);
-            }
-        }
-    }
-
-    let missing: Vec<String> = available
-        .iter()
-        .filter(|m| !applied.contains(&(**m)))
-        .map(|s| s.to_string())
-        .collect();
-    let extra: Vec<String> = applied
-        .iter()
-        .filter(|m| !available.contains(&(**m)))
-        .map(|s| s.to_string())
-        .collect();
+    let migrations = db.migration_status().await;
+    let missing = migrations.missing();
+    let extra = migrations.extra();
 
     let mut legacy_single = false;
     let mut composite_index = false;
@@ -260,8 +286,8 @@ LIMIT 1;"#;
     Ok(SchemaStatus {
         database_type: db.database_type.as_str().to_string(),
         database_url_redacted: redact_db_url(original_url),
-        applied_migrations: applied,
-        available_migrations: available,
+        applied_migrations: migrations.applied,
+        available_migrations: migrations.available,
         missing_migrations: missing,
         extra_migrations: extra,
         filters_legacy_unique_name: legacy_single,
@@ -316,6 +342,18 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    if cli.bench_pipeline {
+        use m3u_proxy::pipeline::benchmark::{DEFAULT_BENCHMARK_SIZES, run_pipeline_benchmark};
+
+        let sizes = if cli.bench_sizes.is_empty() {
+            DEFAULT_BENCHMARK_SIZES.to_vec()
+        } else {
+            cli.bench_sizes.clone()
+        };
+        run_pipeline_benchmark(&sizes).await?.print_text();
+        return Ok(());
+    }
+
     match &cli.command {
         Some(Command::SchemaStatus {
             config,
@@ -351,6 +389,70 @@ async fn main() -> Result<()> {
             }
             return Ok(());
         }
+        Some(Command::Doctor {
+            config,
+            database_url,
+            json,
+            http_url,
+            skip_http,
+        }) => {
+            use m3u_proxy::utils::doctor::{DoctorOptions, run_doctor};
+
+            // Only surface warnings so the report stays readable
+            tracing_subscriber::registry()
+                .with(tracing_subscriber::EnvFilter::new("warn"))
+                .with(tracing_subscriber::fmt::layer().with_target(false))
+                .init();
+
+            let report = run_doctor(&DoctorOptions {
+                config_file: config.clone(),
+                database_url: database_url.clone(),
+                http_probe_url: (!*skip_http).then(|| http_url.clone()),
+            })
+            .await;
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                report.print_text();
+            }
+            if report.has_failures() {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Command::GenerateFixtures {
+            output_dir,
+            channels,
+            groups,
+            group_distribution,
+            epg_coverage,
+            epg_days,
+            programmes_per_day,
+            malformed,
+            seed,
+            json,
+        }) => {
+            use m3u_proxy::utils::fixture_generator::{FixtureOptions, generate_fixtures};
+
+            let report = generate_fixtures(&FixtureOptions {
+                output_dir: output_dir.clone(),
+                channels: *channels,
+                groups: *groups,
+                group_distribution: *group_distribution,
+                epg_coverage_percent: *epg_coverage,
+                epg_days: *epg_days,
+                programmes_per_day: *programmes_per_day,
+                malformed_percent: *malformed,
+                seed: *seed,
+                ..Default::default()
+            })?;
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                report.print_text();
+            }
+            return Ok(());
+        }
         None => {
             // Default to Serve
         }
@@ -438,7 +540,8 @@ async fn main() -> Result<()> {
     let http_client_factory = m3u_proxy::utils::HttpClientFactory::new(
         Some(circuit_breaker_manager.clone()),
         Duration::from_secs(5),
-    );
+    )
+    .with_max_decompressed_bytes(config.ingestion.max_decompressed_bytes());
 
     // Database + migrations (auto-repair inside migrate)
     let database = match Database::new(&config.database, &config.ingestion).await {
@@ -457,6 +560,33 @@ async fn main() -> Result<()> {
     }
     info!("Database connected and migrations applied");
 
+    if let Some(hooks) = m3u_proxy::services::GenerationHookService::from_config(&config) {
+        hooks
+            .warn_unknown_proxies(
+                &m3u_proxy::database::repositories::StreamProxySeaOrmRepository::new(
+                    database.connection().clone(),
+                ),
+            )
+            .await;
+    }
+
+    // Cluster leadership (standalone instances always lead)
+    let cluster_config = config.cluster.clone().unwrap_or_default();
+    let leader_election = Arc::new(if cluster_config.enabled {
+        match m3u_proxy::services::LeaderElection::new(
+            &cluster_config,
+            database.connection().clone(),
+        ) {
+            Ok(election) => election,
+            Err(e) => {
+                eprintln!("Failed to start cluster mode: {e}");
+                std::process::exit(1);
+            }
+        }
+    } else {
+        m3u_proxy::services::LeaderElection::standalone()
+    });
+
     // Ingestion state + progress service
     let ingestion_state = Arc::new(IngestionStateManager::new());
     let progress_service = Arc::new(m3u_proxy::services::progress_service::ProgressService::new(
@@ -479,7 +609,7 @@ async fn main() -> Result<()> {
     .await;
 
     // Cache invalidation channel
-    let (cache_invalidation_tx, _cache_invalidation_rx) = create_cache_invalidation_channel();
+    let (cache_invalidation_tx, cache_invalidation_rx) = create_cache_invalidation_channel();
 
     // File managers (sandboxed)
     use sandboxed_file_manager::{CleanupPolicy, SandboxedManager, TimeMatch};
@@ -494,44 +624,76 @@ async fn main() -> Result<()> {
         .temp_path
         .clone()
         .unwrap_or("./data/temp".into());
-    let temp_file_manager = SandboxedManager::builder()
-        .base_directory(&temp_path)
-        .cleanup_policy(
-            CleanupPolicy::new()
-                .remove_after(parse_duration(&config.storage.temp_retention)?)
-                .time_match(TimeMatch::LastAccess),
+    let temp_file_manager = config
+        .storage
+        .with_quotas(
+            "temp",
+            SandboxedManager::builder()
+                .base_directory(&temp_path)
+                .cleanup_policy(
+                    CleanupPolicy::new()
+                        .remove_after(parse_duration(&config.storage.temp_retention)?)
+                        .time_match(TimeMatch::LastAccess),
+                )
+                .cleanup_interval(parse_duration(&config.storage.temp_cleanup_interval)?),
         )
-        .cleanup_interval(parse_duration(&config.storage.temp_cleanup_interval)?)
         .build()
         .await?;
 
+    // Categories listed under [storage.object_storage] live in the bucket instead of on disk
+    config.storage.validate_object_storage()?;
+    if let Some(tls) = &config.web.tls {
+        tls.validate()?;
+    }
+    if let Some(hooks) = &config.generation_hooks {
+        hooks.validate()?;
+    }
+    let with_object_storage = |builder: sandboxed_file_manager::SandboxedManagerBuilder,
+                               category: &str| {
+        let builder = config.storage.with_quotas(category, builder);
+        match config.storage.object_storage_for(category) {
+            Some(object_storage) => builder.object_storage(object_storage),
+            None => builder,
+        }
+    };
+
     // M3U output
-    let m3u_file_manager = SandboxedManager::builder()
-        .base_directory(&config.storage.m3u_path)
-        .cleanup_policy(
-            CleanupPolicy::new()
-                .remove_after(parse_duration(&config.storage.m3u_retention)?)
-                .time_match(TimeMatch::Modified),
-        )
-        .cleanup_interval(parse_duration(&config.storage.m3u_cleanup_interval)?)
-        .build()
-        .await?;
+    let m3u_file_manager = with_object_storage(
+        SandboxedManager::builder()
+            .base_directory(&config.storage.m3u_path)
+            .cleanup_policy(
+                CleanupPolicy::new()
+                    .remove_after(parse_duration(&config.storage.m3u_retention)?)
+                    .time_match(TimeMatch::Modified),
+            )
+            .cleanup_interval(parse_duration(&config.storage.m3u_cleanup_interval)?),
+        "m3u",
+    )
+    .build()
+    .await?;
 
     // Cached logos (no auto cleanup - handled by maintenance)
-    let logos_cached_file_manager = SandboxedManager::builder()
-        .base_directory(&config.storage.cached_logo_path)
-        .build()
-        .await?;
+    let logos_cached_file_manager = with_object_storage(
+        SandboxedManager::builder().base_directory(&config.storage.cached_logo_path),
+        "cached_logos",
+    )
+    .build()
+    .await?;
 
     // Pipeline
-    let pipeline_file_manager = SandboxedManager::builder()
-        .base_directory(&config.storage.pipeline_path)
-        .cleanup_policy(
-            CleanupPolicy::new()
-                .remove_after(parse_duration(&config.storage.pipeline_retention)?)
-                .time_match(TimeMatch::LastAccess),
+    let pipeline_file_manager = config
+        .storage
+        .with_quotas(
+            "pipeline",
+            SandboxedManager::builder()
+                .base_directory(&config.storage.pipeline_path)
+                .cleanup_policy(
+                    CleanupPolicy::new()
+                        .remove_after(parse_duration(&config.storage.pipeline_retention)?)
+                        .time_match(TimeMatch::LastAccess),
+                )
+                .cleanup_interval(parse_duration(&config.storage.pipeline_cleanup_interval)?),
         )
-        .cleanup_interval(parse_duration(&config.storage.pipeline_cleanup_interval)?)
         .build()
         .await?;
 
@@ -551,11 +713,13 @@ async fn main() -> Result<()> {
     );
     info!(
         "  m3u: {} retention / {}, path {:?}",
-        config.storage.m3u_retention, config.storage.m3u_cleanup_interval, config.storage.m3u_path
+        config.storage.m3u_retention,
+        config.storage.m3u_cleanup_interval,
+        m3u_file_manager.base_directory()
     );
     info!(
         "  logos_cached: manual retention, path {:?}",
-        config.storage.cached_logo_path
+        logos_cached_file_manager.base_directory()
     );
 
     // System manager (basic monitoring)
@@ -563,8 +727,23 @@ async fn main() -> Result<()> {
 
     // Observability
     let observability = Arc::new(
-        m3u_proxy::observability::AppObservability::new("m3u-proxy")
-            .context("Failed to initialize observability")?,
+        m3u_proxy::observability::AppObservability::with_config(
+            "m3u-proxy",
+            &config.observability.clone().unwrap_or_default(),
+        )
+        .context("Failed to initialize observability")?,
+    );
+
+    // Home automation events (no-op unless [mqtt] is enabled)
+    let mqtt_publisher = m3u_proxy::services::MqttPublisher::start(config.mqtt.as_ref());
+
+    // Channel count rate-of-change alerts
+    let channel_count_alerts = m3u_proxy::services::ChannelCountAlertService::new(
+        m3u_proxy::database::repositories::ChannelCountSnapshotSeaOrmRepository::new(
+            database.connection().clone(),
+        ),
+        config.channel_count_alerts.clone().unwrap_or_default(),
+        mqtt_publisher.clone(),
     );
 
     // Proxy regeneration
@@ -575,13 +754,59 @@ async fn main() -> Result<()> {
         database.clone(),
         proxy_repository,
         config.clone(),
-        None,
+        Some(
+            m3u_proxy::services::proxy_regeneration::RegenerationConfig {
+                delay_seconds: config
+                    .regeneration
+                    .clone()
+                    .unwrap_or_default()
+                    .debounce_duration()
+                    .as_secs(),
+                ..Default::default()
+            },
+        ),
         pipeline_file_manager.clone(),
         progress_service.clone(),
         ingestion_state.clone(),
         Arc::new(http_client_factory.clone()),
     )
-    .with_observability(observability.clone());
+    .with_observability(observability.clone())
+    .with_leader_election(leader_election.clone())
+    .with_channel_count_alerts(channel_count_alerts.clone());
+
+    // Raw source snapshot archive (optional)
+    let ingest_archive_config = config.ingest_archive.clone().unwrap_or_default();
+    let ingest_archive = if ingest_archive_config.enabled {
+        let archive_file_manager = SandboxedManager::builder()
+            .base_directory(&ingest_archive_config.path)
+            .cleanup_policy(
+                CleanupPolicy::new()
+                    .remove_after(ingest_archive_config.retention_duration())
+                    .time_match(TimeMatch::Modified),
+            )
+            .cleanup_interval(Duration::from_secs(60 * 60))
+            .build()
+            .await?;
+        info!(
+            "Ingest snapshot archive enabled: {} retention, path {:?}",
+            ingest_archive_config.retention, ingest_archive_config.path
+        );
+        Some(Arc::new(m3u_proxy::services::IngestArchiveService::new(
+            ingest_archive_config,
+            archive_file_manager,
+        )))
+    } else {
+        None
+    };
+
+    // API query cache, invalidated through the cache invalidation channel
+    let query_cache = Arc::new(
+        m3u_proxy::services::QueryCache::new(
+            &config.query_cache.clone().unwrap_or_default(),
+            cache_invalidation_rx,
+        )
+        .with_observability(observability.clone()),
+    );
 
     // EPG source service
     let epg_source_service = {
@@ -596,13 +821,25 @@ async fn main() -> Result<()> {
             database.connection().clone(),
         );
         let url_service = UrlLinkingService::new(stream_repo_for_url, epg_repo_for_url);
-        Arc::new(EpgSourceService::new(
+        let service = EpgSourceService::new(
             database.clone(),
             epg_repo,
             url_service,
             cache_invalidation_tx.clone(),
             http_client_factory.clone(),
-        ))
+        );
+        let service = service.with_ingestion_history(
+            m3u_proxy::database::repositories::IngestionRunSeaOrmRepository::new(
+                database.connection().clone(),
+            ),
+        );
+        let service = service
+            .with_query_cache(query_cache.clone())
+            .with_temp_file_manager(temp_file_manager.clone());
+        Arc::new(match &ingest_archive {
+            Some(archive) => service.with_ingest_archive(archive.clone()),
+            None => service,
+        })
     };
 
     // Stream source service
@@ -621,17 +858,38 @@ async fn main() -> Result<()> {
             database.connection().clone(),
         );
         let url_service = UrlLinkingService::new(stream_repo_for_url, epg_repo_for_url.clone());
-        Arc::new(
-            StreamSourceBusinessService::with_http_client_factory(
-                stream_repo,
-                channel_repo,
-                epg_repo_for_url.clone(),
-                url_service,
-                cache_invalidation_tx.clone(),
-                http_client_factory.clone(),
-            )
-            .with_observability(observability.clone()),
+        let service = StreamSourceBusinessService::with_http_client_factory(
+            stream_repo,
+            channel_repo,
+            epg_repo_for_url.clone(),
+            url_service,
+            cache_invalidation_tx.clone(),
+            http_client_factory.clone(),
         )
+        .with_observability(observability.clone())
+        .with_ingestion_limits(config.ingestion.limits.clone());
+        let service = service
+            .with_ingestion_history(
+                m3u_proxy::database::repositories::IngestionRunSeaOrmRepository::new(
+                    database.connection().clone(),
+                ),
+            )
+            .with_category_filters(
+                m3u_proxy::database::repositories::XtreamCategoryFilterSeaOrmRepository::new(
+                    database.connection().clone(),
+                ),
+            )
+            .with_mirrors(
+                m3u_proxy::database::repositories::StreamSourceMirrorSeaOrmRepository::new(
+                    database.connection().clone(),
+                ),
+            )
+            .with_channel_count_alerts(channel_count_alerts.clone())
+            .with_query_cache(query_cache.clone());
+        Arc::new(match &ingest_archive {
+            Some(archive) => service.with_ingest_archive(archive.clone()),
+            None => service,
+        })
     };
 
     // Job scheduling system base queue (create early so services can attach)
@@ -646,25 +904,43 @@ async fn main() -> Result<()> {
     logo_cache_maintenance_service.initialize().await?;
 
     // Job scheduling system
-    let job_scheduler = Arc::new(JobScheduler::new(job_queue.clone(), database.clone()));
-    let job_executor = Arc::new(JobExecutor::new(
-        stream_source_service.clone(),
-        epg_source_service.clone(),
-        Arc::new(proxy_regeneration_service.clone()),
-        logo_cache_maintenance_service.clone(),
-        ingestion_state.clone(),
-        database.clone(),
-        config.clone(),
-        temp_file_manager.clone(),
-        Arc::new(http_client_factory.clone()),
-        progress_service.clone(),
-    ));
-    let job_queue_runner = Arc::new(JobQueueRunner::new(
-        job_queue.clone(),
-        job_executor.clone(),
-        job_scheduler.clone(),
-        &config.job_scheduling.clone().unwrap_or_default(),
-    ));
+    let job_scheduler = Arc::new(
+        JobScheduler::new(job_queue.clone(), database.clone())
+            .with_leader_election(leader_election.clone())
+            .with_trash_purge_interval(
+                config
+                    .trash
+                    .clone()
+                    .unwrap_or_default()
+                    .purge_interval_duration(),
+            ),
+    );
+    let job_executor = Arc::new(
+        JobExecutor::new(
+            stream_source_service.clone(),
+            epg_source_service.clone(),
+            Arc::new(proxy_regeneration_service.clone()),
+            logo_cache_maintenance_service.clone(),
+            ingestion_state.clone(),
+            database.clone(),
+            config.clone(),
+            temp_file_manager.clone(),
+            Arc::new(http_client_factory.clone()),
+            progress_service.clone(),
+        )
+        .with_mqtt_publisher(mqtt_publisher.clone())
+        .with_logo_service(logo_asset_service.clone())
+        .with_channel_count_alerts(channel_count_alerts),
+    );
+    let job_queue_runner = Arc::new(
+        JobQueueRunner::new(
+            job_queue.clone(),
+            job_executor.clone(),
+            job_scheduler.clone(),
+            &config.job_scheduling.clone().unwrap_or_default(),
+        )
+        .with_leader_election(leader_election.clone()),
+    );
     info!("Job scheduling system initialized");
 
     // Relay manager + config resolver
@@ -696,7 +972,7 @@ async fn main() -> Result<()> {
         pipeline_file_manager,
         logos_cached_file_manager: logos_cached_file_manager.clone(),
         proxy_output_file_manager: m3u_file_manager.clone(),
-        relay_manager,
+        relay_manager: relay_manager.clone(),
         relay_config_resolver,
         system: system_manager.get_system(),
         progress_service: progress_service.clone(),
@@ -705,6 +981,7 @@ async fn main() -> Result<()> {
         log_broadcaster,
         runtime_settings_store: runtime_settings_arc.clone(),
         circuit_breaker_manager: Some(circuit_breaker_manager.clone()),
+        http_client_factory: http_client_factory.clone(),
         observability: observability.clone(),
         job_scheduler: job_scheduler.clone(),
         job_queue: job_queue.clone(),
@@ -716,6 +993,8 @@ async fn main() -> Result<()> {
         )),
         logo_cache_service: logo_cache_service.clone(),
         logo_cache_maintenance_service: logo_cache_maintenance_service.clone(),
+        mqtt_publisher,
+        query_cache,
     })
     .await?;
 
@@ -786,6 +1065,15 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Contend for cluster leadership (no-op for standalone instances); the lock is held
+    // until running jobs have finished during shutdown
+    let election_token = tokio_util::sync::CancellationToken::new();
+    let election_handle = {
+        let election_token = election_token.clone();
+        let election = leader_election.clone();
+        tokio::spawn(async move { election.run(election_token).await })
+    };
+
     // Start job scheduler & runner after server binds
     let sched_token = scheduler_cancellation_token.clone();
     let scheduler_handle = tokio::spawn(async move {
@@ -794,17 +1082,95 @@ async fn main() -> Result<()> {
         }
     });
     let runner_token = scheduler_cancellation_token.clone();
+    let watchdog_job_queue_runner = job_queue_runner.clone();
     let queue_runner_handle = tokio::spawn(async move {
         if let Err(e) = job_queue_runner.run(runner_token).await {
             tracing::error!("Job queue runner error: {e}");
         }
     });
 
+    // Relay keep-alive policies on the cluster leader (no-op when none are configured)
+    tokio::spawn(relay_manager.clone().run_keepalive(
+        leader_election.clone(),
+        scheduler_cancellation_token.clone(),
+    ));
+
+    // Read replica health checks (no-op without a replica)
+    database.spawn_replica_health_checks(scheduler_cancellation_token.clone());
+
+    // XMLTV watch folder import (optional)
+    let xmltv_import_config = config.xmltv_import.clone().unwrap_or_default();
+    if xmltv_import_config.enabled {
+        let import_file_manager = SandboxedManager::builder()
+            .base_directory(&xmltv_import_config.import_path)
+            .build()
+            .await?;
+        let archive_file_manager = SandboxedManager::builder()
+            .base_directory(
+                xmltv_import_config
+                    .import_path
+                    .join(m3u_proxy::services::xmltv_import::ARCHIVE_DIR),
+            )
+            .cleanup_policy(
+                CleanupPolicy::new()
+                    .remove_after(xmltv_import_config.archive_retention_duration())
+                    .time_match(TimeMatch::Modified),
+            )
+            .cleanup_interval(Duration::from_secs(60 * 60))
+            .build()
+            .await?;
+        let xmltv_import_service = m3u_proxy::services::XmltvImportService::new(
+            xmltv_import_config,
+            import_file_manager,
+            archive_file_manager,
+            epg_source_service.clone(),
+        )
+        .with_leader_election(leader_election.clone());
+        let import_token = scheduler_cancellation_token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = xmltv_import_service.run(import_token).await {
+                tracing::error!("XMLTV watch folder error: {e}");
+            }
+        });
+    }
+
+    // Compliance blocklist (optional): load before the first generation, then refresh
+    let compliance_config = config.compliance_blocklist.clone().unwrap_or_default();
+    if compliance_config.source.is_some() {
+        let compliance_service = Arc::new(m3u_proxy::services::ComplianceBlocklistService::new(
+            compliance_config,
+            http_client_factory.clone(),
+        ));
+        match compliance_service.refresh().await {
+            Ok(entries) => tracing::info!("Compliance blocklist loaded ({entries} entries)"),
+            Err(e) => tracing::error!("Failed to load compliance blocklist: {e:#}"),
+        }
+        m3u_proxy::services::ComplianceBlocklistService::install_global(compliance_service.clone());
+        let compliance_token = scheduler_cancellation_token.clone();
+        tokio::spawn(async move {
+            compliance_service.run(compliance_token).await;
+        });
+    }
+
     tracing::info!("All background services started");
 
+    // systemd readiness and watchdog (no-op unless started as a Type=notify unit)
+    let systemd_config = config.systemd.clone().unwrap_or_default();
+    let systemd_notifier = Arc::new(m3u_proxy::services::SystemdNotifier::from_env(
+        &systemd_config,
+    ));
+    systemd_notifier.ready();
+    tokio::spawn(systemd_notifier.clone().run_watchdog(
+        systemd_config,
+        watchdog_job_queue_runner,
+        database.clone(),
+        scheduler_cancellation_token.clone(),
+    ));
+
     // Await cancellation
     scheduler_cancellation_token.cancelled().await;
     tracing::info!("Shutdown requested, stopping background services...");
+    systemd_notifier.stopping();
 
     let shutdown_timeout = tokio::time::timeout(Duration::from_secs(300), async move {
         let s = scheduler_handle.await;
@@ -825,6 +1191,12 @@ async fn main() -> Result<()> {
         Err(_) => tracing::warn!("Timeout waiting for job services shutdown"),
     }
 
+    // Release cluster leadership once jobs have stopped
+    election_token.cancel();
+    if let Err(e) = election_handle.await {
+        tracing::warn!("Leader election join error: {e}");
+    }
+
     // Stop web server
     web_server_cancellation_token.cancel();
     let shutdown_start = std::time::Instant::now();
@@ -840,6 +1212,8 @@ async fn main() -> Result<()> {
         }
     }
 
+    observability.shutdown();
+
     tracing::info!("Shutdown complete");
     Ok(())
 }
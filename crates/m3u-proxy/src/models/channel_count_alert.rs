@@ -0,0 +1,58 @@
+//! Channel count rate-of-change alert models
+//!
+//! The channel count of every successful source ingestion and proxy generation is kept, so
+//! a provider suddenly dropping most of its channels, or a filter change emptying a proxy,
+//! raises an alert instead of going unnoticed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What a channel count was recorded for
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    ToSchema,
+    sea_orm::DeriveActiveEnum,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum ChannelCountSubject {
+    /// Channels ingested from a stream source
+    #[sea_orm(string_value = "stream_source")]
+    StreamSource,
+    /// Channels in a proxy's generated playlist
+    #[sea_orm(string_value = "stream_proxy")]
+    StreamProxy,
+}
+
+/// A channel count change beyond the configured threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ChannelCountAlert {
+    /// Channel count before the change
+    pub previous_count: i64,
+    /// Channel count after the change
+    pub current_count: i64,
+    /// Signed change relative to the previous count; negative for drops
+    pub change_percent: f64,
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Latest channel count of a source or proxy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ChannelCountSnapshot {
+    pub subject_id: Uuid,
+    pub subject: ChannelCountSubject,
+    pub channel_count: i64,
+    pub recorded_at: DateTime<Utc>,
+    /// Set while the latest count is an alerting change from the one before it
+    pub alert: Option<ChannelCountAlert>,
+}
@@ -0,0 +1,207 @@
+//! Manual channel-to-EPG mapping models
+//!
+//! A proxy's channels are matched to guide data by tvg-id. A manual mapping points a channel
+//! at an EPG channel id instead, for channels whose tvg-id is missing or does not match any
+//! EPG source. Mappings take precedence over the tvg-id when the proxy is generated.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Channel;
+
+/// A channel of a proxy mapped to an EPG channel
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChannelEpgMapping {
+    pub id: Uuid,
+    pub proxy_id: Uuid,
+    pub channel_id: Uuid,
+    /// EPG channel id (XMLTV `channel` id) the channel takes its programmes from
+    pub epg_channel_id: String,
+    /// Channel name when the mapping was saved, for display
+    pub channel_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One mapping to save
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ChannelEpgMappingInput {
+    pub channel_id: Uuid,
+    pub epg_channel_id: String,
+}
+
+/// Request to save manual mappings of a proxy's channels
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct SetChannelEpgMappingsRequest {
+    /// Mappings to create or replace; channels not listed keep their mappings
+    pub mappings: Vec<ChannelEpgMappingInput>,
+}
+
+impl SetChannelEpgMappingsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.mappings.is_empty() {
+            return Err("Provide at least one mapping".to_string());
+        }
+        if let Some(mapping) = self
+            .mappings
+            .iter()
+            .find(|m| m.epg_channel_id.trim().is_empty())
+        {
+            return Err(format!(
+                "EPG channel id of channel {} must not be empty",
+                mapping.channel_id
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A channel of an EPG source, as offered for manual mapping
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EpgChannelCandidate {
+    pub epg_channel_id: String,
+    pub channel_name: String,
+    pub source_id: Uuid,
+    /// Programmes the EPG source holds for the channel
+    pub programme_count: u64,
+}
+
+/// A channel of a proxy without guide data
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UnmappedChannel {
+    pub channel_id: Uuid,
+    pub channel_name: String,
+    pub tvg_id: Option<String>,
+    pub tvg_name: Option<String>,
+    pub group_title: Option<String>,
+    pub source_id: Uuid,
+    /// EPG channel with the same normalised name, if any
+    pub suggestion: Option<EpgChannelCandidate>,
+}
+
+/// Manual mappings of a proxy, ready to apply to its channels
+#[derive(Debug, Clone, Default)]
+pub struct ChannelEpgMappingSet {
+    epg_channel_ids: HashMap<Uuid, String>,
+}
+
+impl ChannelEpgMappingSet {
+    pub fn new(mappings: &[ChannelEpgMapping]) -> Self {
+        Self {
+            epg_channel_ids: mappings
+                .iter()
+                .map(|m| (m.channel_id, m.epg_channel_id.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.epg_channel_ids.is_empty()
+    }
+
+    /// Point a mapped channel's tvg-id at its EPG channel; returns whether it was mapped
+    pub fn apply(&self, channel: &mut Channel) -> bool {
+        match self.epg_channel_ids.get(&channel.id) {
+            Some(epg_channel_id) => {
+                channel.tvg_id = Some(epg_channel_id.clone());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Channel name reduced for matching: lowercase alphanumerics without quality suffixes
+pub fn normalize_channel_name(name: &str) -> String {
+    const QUALITY_SUFFIXES: [&str; 5] = ["fhd", "uhd", "hd", "sd", "4k"];
+
+    let mut words: Vec<String> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    while words.len() > 1
+        && words
+            .last()
+            .is_some_and(|word| QUALITY_SUFFIXES.contains(&word.as_str()))
+    {
+        words.pop();
+    }
+    words.concat()
+}
+
+/// Order EPG channels by how well they match a search: exact normalised name or id first,
+/// then prefix matches, then the rest; ties go to the channel with more programmes
+pub fn rank_candidates(query: &str, candidates: &mut [EpgChannelCandidate]) {
+    let query = normalize_channel_name(query);
+    let rank = |candidate: &EpgChannelCandidate| {
+        let name = normalize_channel_name(&candidate.channel_name);
+        let id = normalize_channel_name(&candidate.epg_channel_id);
+        if name == query || id == query {
+            0
+        } else if name.starts_with(&query) || id.starts_with(&query) {
+            1
+        } else {
+            2
+        }
+    };
+    candidates.sort_by(|a, b| {
+        rank(a)
+            .cmp(&rank(b))
+            .then(b.programme_count.cmp(&a.programme_count))
+            .then_with(|| a.channel_name.cmp(&b.channel_name))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, name: &str, programme_count: u64) -> EpgChannelCandidate {
+        EpgChannelCandidate {
+            epg_channel_id: id.to_string(),
+            channel_name: name.to_string(),
+            source_id: Uuid::nil(),
+            programme_count,
+        }
+    }
+
+    #[test]
+    fn test_normalize_channel_name() {
+        assert_eq!(normalize_channel_name("BBC One HD"), "bbcone");
+        assert_eq!(normalize_channel_name("bbc-one"), "bbcone");
+        assert_eq!(normalize_channel_name("Sky Sports 4K UHD"), "skysports");
+        // A name that is only a suffix stays as it is
+        assert_eq!(normalize_channel_name("HD"), "hd");
+    }
+
+    #[test]
+    fn test_rank_candidates() {
+        let mut candidates = vec![
+            candidate("bbcnews.uk", "BBC News", 500),
+            candidate("bbc1.uk", "BBC One", 100),
+            candidate("bbc1wales.uk", "BBC One Wales", 300),
+        ];
+        rank_candidates("bbc one hd", &mut candidates);
+        let ids: Vec<_> = candidates
+            .iter()
+            .map(|c| c.epg_channel_id.as_str())
+            .collect();
+        assert_eq!(ids, ["bbc1.uk", "bbc1wales.uk", "bbcnews.uk"]);
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let request = SetChannelEpgMappingsRequest {
+            mappings: vec![ChannelEpgMappingInput {
+                channel_id: Uuid::new_v4(),
+                epg_channel_id: " ".to_string(),
+            }],
+        };
+        assert!(request.validate().is_err());
+        assert!(SetChannelEpgMappingsRequest::default().validate().is_err());
+    }
+}
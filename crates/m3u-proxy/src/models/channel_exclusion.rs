@@ -0,0 +1,133 @@
+//! Per-proxy channel exclusion models
+//!
+//! Exclusions remove a specific channel, or every channel of a group, from a proxy's
+//! subsequent generations without authoring an expression filter. They are applied after
+//! the proxy's filters, against the mapped channel data.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Channel;
+
+/// A channel or group excluded from a proxy's output
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProxyChannelExclusion {
+    pub id: Uuid,
+    pub proxy_id: Uuid,
+    /// Excluded channel (absent for group exclusions)
+    pub channel_id: Option<Uuid>,
+    /// Excluded group title (absent for channel exclusions)
+    pub group_title: Option<String>,
+    /// Channel name at the time of exclusion, for display
+    pub channel_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to exclude channels from a proxy
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct ExcludeChannelsRequest {
+    /// Channels to exclude
+    #[serde(default)]
+    pub channel_ids: Vec<Uuid>,
+    /// Exclude every channel whose group title matches (case-insensitive)
+    pub group_title: Option<String>,
+}
+
+/// Exclusions of a proxy, ready to test channels against
+#[derive(Debug, Clone, Default)]
+pub struct ChannelExclusionSet {
+    channel_ids: HashSet<Uuid>,
+    group_titles: HashSet<String>,
+}
+
+impl ChannelExclusionSet {
+    pub fn new(exclusions: &[ProxyChannelExclusion]) -> Self {
+        let mut set = Self::default();
+        for exclusion in exclusions {
+            if let Some(channel_id) = exclusion.channel_id {
+                set.channel_ids.insert(channel_id);
+            }
+            if let Some(group_title) = exclusion.group_title.as_deref() {
+                set.group_titles.insert(normalize_group(group_title));
+            }
+        }
+        set
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channel_ids.is_empty() && self.group_titles.is_empty()
+    }
+
+    /// Whether a channel is excluded by id or by its group
+    pub fn excludes(&self, channel: &Channel) -> bool {
+        self.channel_ids.contains(&channel.id)
+            || channel
+                .group_title
+                .as_deref()
+                .is_some_and(|group| self.group_titles.contains(&normalize_group(group)))
+    }
+}
+
+fn normalize_group(group_title: &str) -> String {
+    group_title.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(group_title: Option<&str>) -> Channel {
+        Channel {
+            id: Uuid::new_v4(),
+            source_id: Uuid::new_v4(),
+            tvg_id: None,
+            tvg_name: None,
+            tvg_chno: None,
+            tvg_logo: None,
+            tvg_shift: None,
+            epg_shift: None,
+            group_title: group_title.map(str::to_string),
+            channel_name: "Channel".to_string(),
+            stream_url: "http://example.com/stream".to_string(),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            extra_attributes: Default::default(),
+        }
+    }
+
+    fn exclusion(channel_id: Option<Uuid>, group_title: Option<&str>) -> ProxyChannelExclusion {
+        ProxyChannelExclusion {
+            id: Uuid::new_v4(),
+            proxy_id: Uuid::new_v4(),
+            channel_id,
+            group_title: group_title.map(str::to_string),
+            channel_name: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_excludes_by_channel_and_group() {
+        let excluded = channel(Some("News"));
+        let in_group = channel(Some(" adult "));
+        let kept = channel(Some("Sport"));
+        let set = ChannelExclusionSet::new(&[
+            exclusion(Some(excluded.id), None),
+            exclusion(None, Some("Adult")),
+        ]);
+
+        assert!(set.excludes(&excluded));
+        assert!(set.excludes(&in_group));
+        assert!(!set.excludes(&kept));
+        assert!(!set.excludes(&channel(None)));
+        assert!(ChannelExclusionSet::new(&[]).is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+//! Channel identity models
+//!
+//! A channel's UUID is derived from its fields on every ingestion, so it stays stable for
+//! as long as those fields do. Numbering persistence, overrides and EPG mappings all hang
+//! off the UUID. By default it is derived from the stream URL and name; providers that
+//! rotate stream URLs (e.g. per-session tokens) but keep tvg-ids can key on `tvg_id`
+//! instead.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::Channel;
+use crate::utils::deterministic_uuid::{generate_channel_uuid, generate_deterministic_uuid};
+
+/// Channel fields a source's channel UUIDs are derived from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelIdentityKey {
+    /// Stream URL and channel name (the default)
+    #[default]
+    UrlAndName,
+    /// tvg-id
+    TvgId,
+    /// Path of the stream URL, ignoring host, port and query string
+    StreamUrlPath,
+    /// Channel name and group title
+    NameAndGroup,
+}
+
+impl ChannelIdentityKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UrlAndName => "url_and_name",
+            Self::TvgId => "tvg_id",
+            Self::StreamUrlPath => "stream_url_path",
+            Self::NameAndGroup => "name_and_group",
+        }
+    }
+
+    /// Identity value of a channel, or `None` when the channel lacks the keyed field
+    fn value(&self, channel: &Channel) -> Option<String> {
+        let non_empty = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        match self {
+            Self::UrlAndName => None,
+            Self::TvgId => channel.tvg_id.as_deref().and_then(non_empty),
+            Self::StreamUrlPath => url::Url::parse(&channel.stream_url)
+                .ok()
+                .and_then(|url| non_empty(url.path()).filter(|path| path != "/")),
+            Self::NameAndGroup => non_empty(&channel.channel_name).map(|name| {
+                format!(
+                    "{name}\u{1f}{}",
+                    channel.group_title.as_deref().unwrap_or_default().trim()
+                )
+            }),
+        }
+    }
+}
+
+impl FromStr for ChannelIdentityKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "url_and_name" => Ok(Self::UrlAndName),
+            "tvg_id" => Ok(Self::TvgId),
+            "stream_url_path" => Ok(Self::StreamUrlPath),
+            "name_and_group" => Ok(Self::NameAndGroup),
+            other => Err(format!("Unknown channel identity key '{other}'")),
+        }
+    }
+}
+
+/// Channel identity key of a stream source
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChannelIdentity {
+    pub source_id: Uuid,
+    pub identity_key: ChannelIdentityKey,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ChannelIdentity {
+    /// Identity of a source without a configured key
+    pub fn default_for(source_id: Uuid) -> Self {
+        Self {
+            source_id,
+            identity_key: ChannelIdentityKey::default(),
+            updated_at: None,
+        }
+    }
+}
+
+/// Request to set a stream source's channel identity key
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ChannelIdentityRequest {
+    pub identity_key: ChannelIdentityKey,
+}
+
+/// Re-derive channel UUIDs from `key`
+///
+/// Channels lacking the keyed field, and every channel sharing a key value with another
+/// channel of the batch, keep the default URL-and-name UUID so ids stay unique. Returns
+/// how many channels fell back to the default.
+pub fn assign_channel_ids(
+    source_id: Uuid,
+    key: ChannelIdentityKey,
+    channels: &mut [Channel],
+) -> usize {
+    if key == ChannelIdentityKey::UrlAndName {
+        return 0;
+    }
+
+    let values: Vec<Option<String>> = channels.iter().map(|c| key.value(c)).collect();
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    for value in values.iter().flatten() {
+        *occurrences.entry(value.as_str()).or_default() += 1;
+    }
+
+    let mut fallbacks = 0;
+    for (channel, value) in channels.iter_mut().zip(&values) {
+        channel.id = match value {
+            Some(value) if occurrences[value.as_str()] == 1 => {
+                generate_deterministic_uuid(&[&source_id.to_string(), &key.as_str(), value])
+            }
+            _ => {
+                fallbacks += 1;
+                generate_channel_uuid(&source_id, &channel.stream_url, &channel.channel_name)
+            }
+        };
+    }
+    fallbacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(tvg_id: Option<&str>, name: &str, url: &str) -> Channel {
+        let now = Utc::now();
+        Channel {
+            id: Uuid::nil(),
+            source_id: Uuid::nil(),
+            tvg_id: tvg_id.map(str::to_string),
+            tvg_name: None,
+            tvg_chno: None,
+            tvg_logo: None,
+            tvg_shift: None,
+            epg_shift: None,
+            group_title: Some("News".to_string()),
+            channel_name: name.to_string(),
+            stream_url: url.to_string(),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            created_at: now,
+            updated_at: now,
+            extra_attributes: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_tvg_id_survives_url_rotation() {
+        let source = Uuid::from_u128(1);
+        let mut first = vec![channel(Some("bbc1"), "BBC One", "http://a/1?token=x")];
+        let mut second = vec![channel(Some("bbc1"), "BBC One HD", "http://b/9?token=y")];
+        assign_channel_ids(source, ChannelIdentityKey::TvgId, &mut first);
+        assign_channel_ids(source, ChannelIdentityKey::TvgId, &mut second);
+        assert_eq!(first[0].id, second[0].id);
+    }
+
+    #[test]
+    fn test_stream_url_path_ignores_host_and_query() {
+        let source = Uuid::from_u128(1);
+        let mut first = vec![channel(None, "A", "http://cdn1.example/live/42.ts?t=1")];
+        let mut second = vec![channel(
+            None,
+            "A",
+            "http://cdn2.example:8080/live/42.ts?t=2",
+        )];
+        assign_channel_ids(source, ChannelIdentityKey::StreamUrlPath, &mut first);
+        assign_channel_ids(source, ChannelIdentityKey::StreamUrlPath, &mut second);
+        assert_eq!(first[0].id, second[0].id);
+    }
+
+    #[test]
+    fn test_missing_and_duplicate_keys_fall_back() {
+        let source = Uuid::from_u128(1);
+        let mut channels = vec![
+            channel(Some("dup"), "A", "http://a/1"),
+            channel(Some("dup"), "B", "http://a/2"),
+            channel(None, "C", "http://a/3"),
+            channel(Some("unique"), "D", "http://a/4"),
+        ];
+        let fallbacks = assign_channel_ids(source, ChannelIdentityKey::TvgId, &mut channels);
+
+        assert_eq!(fallbacks, 3);
+        assert_eq!(
+            channels[0].id,
+            generate_channel_uuid(&source, "http://a/1", "A")
+        );
+        assert_ne!(channels[0].id, channels[1].id);
+        assert_ne!(
+            channels[3].id,
+            generate_channel_uuid(&source, "http://a/4", "D")
+        );
+    }
+
+    #[test]
+    fn test_key_round_trip() {
+        for key in [
+            ChannelIdentityKey::UrlAndName,
+            ChannelIdentityKey::TvgId,
+            ChannelIdentityKey::StreamUrlPath,
+            ChannelIdentityKey::NameAndGroup,
+        ] {
+            assert_eq!(key.as_str().parse::<ChannelIdentityKey>().unwrap(), key);
+        }
+        assert!("name".parse::<ChannelIdentityKey>().is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Channel logo assignment models
+//!
+//! Uploaded logo assets can be assigned to channels, replacing the logo the channel's
+//! source provides. Assignments are made in bulk from a ZIP of logos whose file names are
+//! matched to channel names, or by hand for logos that did not match.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Channel;
+
+/// A channel showing an uploaded logo asset
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChannelLogoAssignment {
+    pub id: Uuid,
+    pub channel_id: Uuid,
+    pub logo_asset_id: Uuid,
+    /// Channel name when the logo was assigned, for display
+    pub channel_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome of a bulk logo upload
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct LogoBulkUploadReport {
+    /// Staging batch holding the unmatched logos
+    pub batch_id: Uuid,
+    /// Logos matched to channels by file name, saved as assets and assigned
+    pub matched: Vec<MatchedLogo>,
+    /// Logos without a matching channel, staged for manual assignment
+    pub unmatched: Vec<StagedLogo>,
+    /// Archive entries that were not accepted
+    pub rejected: Vec<RejectedLogo>,
+}
+
+/// A logo saved as an asset and assigned to channels
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MatchedLogo {
+    pub file_name: String,
+    pub logo_asset_id: Uuid,
+    pub channels: Vec<LogoChannel>,
+}
+
+/// A channel a logo was assigned to
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LogoChannel {
+    pub channel_id: Uuid,
+    pub channel_name: String,
+}
+
+/// A logo awaiting manual assignment
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StagedLogo {
+    pub file_name: String,
+    pub file_size: u64,
+}
+
+/// An archive entry that was not accepted as a logo
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RejectedLogo {
+    pub file_name: String,
+    pub reason: String,
+}
+
+/// One staged logo to assign
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct StagedLogoAssignment {
+    pub file_name: String,
+    pub channel_ids: Vec<Uuid>,
+}
+
+/// Request to assign staged logos of a bulk upload to channels
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct AssignStagedLogosRequest {
+    pub assignments: Vec<StagedLogoAssignment>,
+}
+
+impl AssignStagedLogosRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.assignments.is_empty() {
+            return Err("Provide at least one assignment".to_string());
+        }
+        if let Some(assignment) = self.assignments.iter().find(|a| a.channel_ids.is_empty()) {
+            return Err(format!(
+                "Logo '{}' must be assigned to at least one channel",
+                assignment.file_name
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Logo assignments ready to apply to a proxy's channels
+#[derive(Debug, Clone, Default)]
+pub struct ChannelLogoAssignmentSet {
+    logo_urls: HashMap<Uuid, String>,
+}
+
+impl ChannelLogoAssignmentSet {
+    pub fn new(assignments: &[ChannelLogoAssignment], base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        Self {
+            logo_urls: assignments
+                .iter()
+                .map(|a| {
+                    (
+                        a.channel_id,
+                        format!("{base_url}/api/v1/logos/{}", a.logo_asset_id),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.logo_urls.is_empty()
+    }
+
+    /// Point an assigned channel's tvg-logo at its logo asset; returns whether it was assigned
+    pub fn apply(&self, channel: &mut Channel) -> bool {
+        match self.logo_urls.get(&channel.id) {
+            Some(logo_url) => {
+                channel.tvg_logo = Some(logo_url.clone());
+                true
+            }
+            None => false,
+        }
+    }
+}
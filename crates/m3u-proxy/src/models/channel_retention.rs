@@ -0,0 +1,53 @@
+//! Channel retention models
+//!
+//! A stream source's retention lets channels survive a few ingestions they are missing from
+//! (e.g. while a provider temporarily drops them) instead of vanishing from proxies at once.
+//! Retained channels are reported as stale until they reappear or are purged.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Upper bound for `max_missed_ingestions`
+pub const MAX_MISSED_INGESTIONS_LIMIT: i32 = 100;
+
+/// Retention of removed channels for a stream source
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChannelRetention {
+    pub source_id: Uuid,
+    /// Consecutive ingestions a missing channel is kept for before it is purged
+    /// (0 removes missing channels immediately)
+    pub max_missed_ingestions: i32,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ChannelRetention {
+    /// Retention of a source without a configured grace period
+    pub fn disabled(source_id: Uuid) -> Self {
+        Self {
+            source_id,
+            max_missed_ingestions: 0,
+            updated_at: None,
+        }
+    }
+}
+
+/// Request to set a stream source's channel retention
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ChannelRetentionRequest {
+    /// Consecutive ingestions a missing channel is kept for (0 disables retention)
+    #[schema(example = 3)]
+    pub max_missed_ingestions: i32,
+}
+
+impl ChannelRetentionRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0..=MAX_MISSED_INGESTIONS_LIMIT).contains(&self.max_missed_ingestions) {
+            return Err(format!(
+                "max_missed_ingestions must be between 0 and {MAX_MISSED_INGESTIONS_LIMIT}"
+            ));
+        }
+        Ok(())
+    }
+}
@@ -15,10 +15,103 @@ pub struct DataMappingRule {
     pub sort_order: i32,
     pub is_active: bool,
     pub expression: Option<String>,
+    /// Sources or proxy the rule is bound to (global when unset)
+    #[serde(default)]
+    pub scope: DataMappingRuleScope,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Fields that decide which guide data a channel gets: the stream's `tvg_id` and the
+/// EPG channel id of guide programmes
+const EPG_IDENTITY_FIELDS: &[&str] = &["tvg_id", "channel_id"];
+
+impl DataMappingRule {
+    /// Whether the rule's expression refers to a channel's EPG identity, so changing the
+    /// rule can change which guide data channels are mapped to
+    pub fn touches_epg_identity(&self) -> bool {
+        self.expression.as_deref().is_some_and(|expression| {
+            expression
+                .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .any(|token| EPG_IDENTITY_FIELDS.contains(&token))
+        })
+    }
+}
+
+/// Where a data mapping rule applies
+///
+/// Rules run in precedence order global → source → proxy (then by `sort_order`), so a
+/// more specific rule sees, and can override, the result of broader ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DataMappingRuleScope {
+    /// Every source of the rule's source type, in every proxy
+    #[default]
+    Global,
+    /// Channels of one stream source
+    Source { source_id: Uuid },
+    /// Everything processed for one proxy
+    Proxy { proxy_id: Uuid },
+}
+
+impl DataMappingRuleScope {
+    /// Scope stored in the `scope_source_id` / `scope_proxy_id` columns
+    pub fn from_columns(source_id: Option<Uuid>, proxy_id: Option<Uuid>) -> Self {
+        match (source_id, proxy_id) {
+            (Some(source_id), _) => Self::Source { source_id },
+            (None, Some(proxy_id)) => Self::Proxy { proxy_id },
+            (None, None) => Self::Global,
+        }
+    }
+
+    pub fn source_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Source { source_id } => Some(*source_id),
+            _ => None,
+        }
+    }
+
+    pub fn proxy_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Proxy { proxy_id } => Some(*proxy_id),
+            _ => None,
+        }
+    }
+
+    /// Position in the global → source → proxy precedence order
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Self::Global => 0,
+            Self::Source { .. } => 1,
+            Self::Proxy { .. } => 2,
+        }
+    }
+
+    /// Whether a rule with this scope applies to records of `source_id` in `proxy_id`'s pipeline
+    pub fn applies_to(&self, source_id: Option<Uuid>, proxy_id: Option<Uuid>) -> bool {
+        match self {
+            Self::Global => true,
+            Self::Source { source_id: id } => source_id == Some(*id),
+            Self::Proxy { proxy_id: id } => proxy_id == Some(*id),
+        }
+    }
+}
+
+/// Rules applying to `source_id` within `proxy_id`'s pipeline, in precedence order
+pub fn scoped_rules(
+    rules: Vec<DataMappingRule>,
+    source_id: Option<Uuid>,
+    proxy_id: Option<Uuid>,
+) -> Vec<DataMappingRule> {
+    let mut rules = rules
+        .into_iter()
+        .filter(|rule| rule.scope.applies_to(source_id, proxy_id))
+        .collect::<Vec<_>>();
+    // Stable sort keeps the database order among rules of equal precedence and sort_order
+    rules.sort_by_key(|rule| (rule.scope.precedence(), rule.sort_order));
+    rules
+}
+
 #[derive(
     Debug,
     Clone,
@@ -188,6 +281,8 @@ pub struct DataMappingRuleCreateRequest {
     pub description: Option<String>,
     pub source_type: DataMappingSourceType,
     pub expression: Option<String>,
+    #[serde(default)]
+    pub scope: DataMappingRuleScope,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -197,6 +292,7 @@ pub struct DataMappingRuleUpdateRequest {
     pub source_type: Option<DataMappingSourceType>,
     pub expression: Option<String>,
     pub is_active: Option<bool>,
+    pub scope: Option<DataMappingRuleScope>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -359,6 +455,7 @@ impl DataMappingRuleCreateRequest {
             description,
             source_type,
             expression: Some(expression),
+            scope: DataMappingRuleScope::Global,
         }
     }
 
@@ -412,3 +509,81 @@ impl DataMappingStats {
         self.rule_performance.iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, sort_order: i32, scope: DataMappingRuleScope) -> DataMappingRule {
+        DataMappingRule {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: None,
+            source_type: DataMappingSourceType::Stream,
+            sort_order,
+            is_active: true,
+            expression: None,
+            scope,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_touches_epg_identity() {
+        let mut rule = rule("rule", 0, DataMappingRuleScope::Global);
+        assert!(!rule.touches_epg_identity());
+        rule.expression = Some(r#"channel_name contains "BBC" SET tvg_id = "bbc1.uk""#.into());
+        assert!(rule.touches_epg_identity());
+        rule.expression = Some(r#"group_title equals "UK" SET channel_name = "x""#.into());
+        assert!(!rule.touches_epg_identity());
+    }
+
+    #[test]
+    fn test_scoped_rules_filter_and_precedence() {
+        let source_id = Uuid::new_v4();
+        let proxy_id = Uuid::new_v4();
+        let rules = vec![
+            rule("proxy", 0, DataMappingRuleScope::Proxy { proxy_id }),
+            rule("source", 0, DataMappingRuleScope::Source { source_id }),
+            rule("global-late", 5, DataMappingRuleScope::Global),
+            rule("global", 1, DataMappingRuleScope::Global),
+            rule(
+                "other-source",
+                0,
+                DataMappingRuleScope::Source {
+                    source_id: Uuid::new_v4(),
+                },
+            ),
+        ];
+
+        let names =
+            |rules: Vec<DataMappingRule>| rules.into_iter().map(|r| r.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(scoped_rules(rules.clone(), Some(source_id), Some(proxy_id))),
+            ["global", "global-late", "source", "proxy"]
+        );
+        assert_eq!(
+            names(scoped_rules(rules, None, None)),
+            ["global", "global-late"]
+        );
+    }
+
+    #[test]
+    fn test_scope_columns_round_trip() {
+        let source_id = Uuid::new_v4();
+        let scope = DataMappingRuleScope::Source { source_id };
+        assert_eq!(
+            DataMappingRuleScope::from_columns(scope.source_id(), scope.proxy_id()),
+            scope
+        );
+        assert_eq!(
+            DataMappingRuleScope::from_columns(None, None),
+            DataMappingRuleScope::Global
+        );
+        assert_eq!(
+            serde_json::to_value(&scope).unwrap(),
+            serde_json::json!({ "type": "source", "source_id": source_id })
+        );
+    }
+}
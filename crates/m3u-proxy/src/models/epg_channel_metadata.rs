@@ -0,0 +1,139 @@
+//! Multilingual XMLTV channel metadata
+//!
+//! Upstream guides can describe a channel with several `<display-name>` (and `<icon>`)
+//! entries tagged with a `lang` attribute. These are kept per EPG source so a proxy with a
+//! language preference (`epg_languages`, e.g. "de,en") can pick the matching name and icon
+//! when its guide is generated, instead of the playlist's channel name.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A display name or icon URL with its optional language
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalizedValue {
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// Display names and icons of one XMLTV channel of an EPG source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpgChannelMetadata {
+    pub source_id: Uuid,
+    /// XMLTV `channel` id
+    pub channel_id: String,
+    pub display_names: Vec<LocalizedValue>,
+    pub icons: Vec<LocalizedValue>,
+}
+
+impl EpgChannelMetadata {
+    /// Whether any display name or icon carries a language (only those channels are stored)
+    pub fn is_multilingual(&self) -> bool {
+        self.display_names
+            .iter()
+            .chain(&self.icons)
+            .any(|value| value.lang.is_some())
+    }
+
+    /// Display name in the first preferred language available
+    pub fn preferred_display_name(&self, languages: &[String]) -> Option<&LocalizedValue> {
+        preferred(&self.display_names, languages)
+    }
+
+    /// Icon in the first preferred language available
+    pub fn preferred_icon(&self, languages: &[String]) -> Option<&LocalizedValue> {
+        preferred(&self.icons, languages)
+    }
+}
+
+/// Parse a comma-separated language preference ("de, en-GB") into lowercase tags
+pub fn parse_language_preference(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(|lang| lang.trim().to_ascii_lowercase())
+        .filter(|lang| !lang.is_empty())
+        .collect()
+}
+
+/// First value matching a preferred language, in preference order
+///
+/// A preference matches its exact tag or, failing that, any regional variant or base
+/// language ("en" matches "en-GB" and "en-GB" matches "en").
+fn preferred<'a>(values: &'a [LocalizedValue], languages: &[String]) -> Option<&'a LocalizedValue> {
+    languages.iter().find_map(|preference| {
+        let tagged = || values.iter().filter_map(|v| Some((v, v.lang.as_deref()?)));
+        tagged()
+            .find(|(_, lang)| lang.eq_ignore_ascii_case(preference))
+            .or_else(|| {
+                tagged().find(|(_, lang)| {
+                    primary_subtag(lang).eq_ignore_ascii_case(primary_subtag(preference))
+                })
+            })
+            .map(|(value, _)| value)
+    })
+}
+
+fn primary_subtag(lang: &str) -> &str {
+    lang.split(['-', '_']).next().unwrap_or(lang)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(value: &str, lang: Option<&str>) -> LocalizedValue {
+        LocalizedValue {
+            value: value.to_string(),
+            lang: lang.map(str::to_string),
+        }
+    }
+
+    fn metadata() -> EpgChannelMetadata {
+        EpgChannelMetadata {
+            source_id: Uuid::new_v4(),
+            channel_id: "arte.fr".to_string(),
+            display_names: vec![
+                value("ARTE", None),
+                value("Arte Deutsch", Some("de-DE")),
+                value("Arte Français", Some("fr")),
+            ],
+            icons: vec![value("http://img/arte-fr.png", Some("fr"))],
+        }
+    }
+
+    #[test]
+    fn test_parse_language_preference() {
+        assert_eq!(
+            parse_language_preference(Some(" de, EN-gb ,,")),
+            vec!["de".to_string(), "en-gb".to_string()]
+        );
+        assert!(parse_language_preference(None).is_empty());
+    }
+
+    #[test]
+    fn test_preferred_follows_preference_order() {
+        let metadata = metadata();
+        assert!(metadata.is_multilingual());
+
+        let languages = parse_language_preference(Some("en,fr,de"));
+        assert_eq!(
+            metadata.preferred_display_name(&languages).unwrap().value,
+            "Arte Français"
+        );
+        assert_eq!(
+            metadata.preferred_icon(&languages).unwrap().value,
+            "http://img/arte-fr.png"
+        );
+
+        // Base language matches a regional variant
+        let languages = parse_language_preference(Some("de"));
+        assert_eq!(
+            metadata.preferred_display_name(&languages).unwrap().value,
+            "Arte Deutsch"
+        );
+        assert!(metadata.preferred_icon(&languages).is_none());
+
+        assert!(metadata.preferred_display_name(&[]).is_none());
+    }
+}
@@ -5,7 +5,21 @@ use crate::utils::time::{detect_timezone_from_xmltv, log_timezone_detection, par
 use anyhow::Result;
 use tracing::warn;
 
+/// URL prefix identifying EPG sources that are fed from the XMLTV watch folder
+/// rather than fetched over HTTP (e.g. `file://tvguide`)
+pub const FILE_BACKED_URL_PREFIX: &str = "file://";
+
 impl EpgSource {
+    /// Whether this source is populated by the XMLTV watch folder import
+    pub fn is_file_backed(&self) -> bool {
+        self.url.starts_with(FILE_BACKED_URL_PREFIX)
+    }
+
+    /// Build the URL used to identify a file-backed source for an import name
+    pub fn file_backed_url(import_name: &str) -> String {
+        format!("{FILE_BACKED_URL_PREFIX}{import_name}")
+    }
+
     /// Detect timezone from EPG content (simplified after migration 004)
     /// Note: Timezone handling was simplified - all times are normalized to UTC
     pub async fn detect_timezone_from_content(&self, epg_content: &str) -> Result<Option<String>> {
@@ -0,0 +1,85 @@
+//! Ingest snapshot models
+//!
+//! A snapshot is the raw playlist or XMLTV document downloaded by one ingestion run, kept
+//! in the ingest archive so the run can be replayed against the current parser.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Timestamp format used for snapshot ids (sortable, filename-safe)
+const SNAPSHOT_ID_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// Kind of source a snapshot was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IngestSnapshotKind {
+    Stream,
+    Epg,
+}
+
+impl IngestSnapshotKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stream => "stream",
+            Self::Epg => "epg",
+        }
+    }
+}
+
+/// An archived raw source download
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct IngestSnapshot {
+    /// Snapshot id (capture time, e.g. `20251016T120000123Z`)
+    #[schema(example = "20251016T120000123Z")]
+    pub id: String,
+    pub source_id: Uuid,
+    pub kind: IngestSnapshotKind,
+    pub captured_at: DateTime<Utc>,
+    /// Size as stored (compressed)
+    pub size_bytes: u64,
+}
+
+impl IngestSnapshot {
+    /// Snapshot id for a capture time
+    pub fn id_for(captured_at: DateTime<Utc>) -> String {
+        captured_at.format(SNAPSHOT_ID_FORMAT).to_string()
+    }
+
+    /// Capture time encoded in a snapshot id; `None` for anything that is not a snapshot id
+    pub fn parse_id(id: &str) -> Option<DateTime<Utc>> {
+        NaiveDateTime::parse_from_str(id, SNAPSHOT_ID_FORMAT)
+            .ok()
+            .map(|t| t.and_utc())
+    }
+}
+
+/// Outcome of replaying a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IngestSnapshotReplayResult {
+    pub snapshot: IngestSnapshot,
+    /// Channels or programmes saved by the replay
+    pub records_saved: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_round_trip() {
+        let captured_at = DateTime::parse_from_rfc3339("2025-10-16T12:00:00.123Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let id = IngestSnapshot::id_for(captured_at);
+        assert_eq!(id, "20251016T120000123Z");
+        assert_eq!(IngestSnapshot::parse_id(&id), Some(captured_at));
+    }
+
+    #[test]
+    fn test_rejects_non_snapshot_ids() {
+        assert_eq!(IngestSnapshot::parse_id("../../etc/passwd"), None);
+        assert_eq!(IngestSnapshot::parse_id("20251016"), None);
+    }
+}
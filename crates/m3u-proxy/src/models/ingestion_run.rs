@@ -0,0 +1,160 @@
+//! Ingestion run history models
+//!
+//! Every ingestion of a stream or EPG source records its outcome, so trends such as a
+//! provider slowly dropping channels or downloads getting slower become visible.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Kind of source an ingestion run belongs to
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    ToSchema,
+    sea_orm::DeriveActiveEnum,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum IngestionSourceKind {
+    #[sea_orm(string_value = "stream")]
+    Stream,
+    #[sea_orm(string_value = "epg")]
+    Epg,
+}
+
+/// Outcome of an ingestion run
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    ToSchema,
+    sea_orm::DeriveActiveEnum,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum IngestionRunStatus {
+    #[sea_orm(string_value = "success")]
+    Success,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+/// A recorded ingestion run
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IngestionRun {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    pub source_kind: IngestionSourceKind,
+    pub status: IngestionRunStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    /// Bytes fetched from the provider (absent when not measurable, e.g. Xtream APIs)
+    pub bytes_downloaded: Option<i64>,
+    /// Channels or programmes that were not present before the run
+    pub records_added: Option<i32>,
+    /// Channels or programmes that were already present and were refreshed
+    pub records_updated: Option<i32>,
+    /// Channels or programmes that were dropped by the run
+    pub records_removed: Option<i32>,
+    pub error: Option<String>,
+}
+
+/// Outcome of an ingestion run to be recorded
+#[derive(Debug, Clone, Default)]
+pub struct IngestionRunOutcome {
+    pub bytes_downloaded: Option<u64>,
+    pub changes: Option<RecordChangeSummary>,
+    pub error: Option<String>,
+}
+
+/// Record counts of an ingestion compared with what was stored before it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecordChangeSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+impl RecordChangeSummary {
+    /// Compare a source's stored channels with a new ingestion
+    ///
+    /// `existing` maps stored channel ids to their missed ingestion count. With a retention
+    /// of `max_missed_ingestions`, a missing channel only counts as removed once this
+    /// ingestion takes it past the grace period.
+    pub fn for_channels(
+        existing: &HashMap<Uuid, i32>,
+        ingested: &[Uuid],
+        max_missed_ingestions: i32,
+    ) -> Self {
+        let ingested: HashSet<Uuid> = ingested.iter().copied().collect();
+        let updated = ingested
+            .iter()
+            .filter(|id| existing.contains_key(id))
+            .count();
+        let removed = existing
+            .iter()
+            .filter(|(id, missed)| {
+                !ingested.contains(id)
+                    && (max_missed_ingestions <= 0 || **missed + 1 > max_missed_ingestions)
+            })
+            .count();
+        Self {
+            added: ingested.len() - updated,
+            updated,
+            removed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_changes_without_retention() {
+        let kept = Uuid::new_v4();
+        let dropped = Uuid::new_v4();
+        let new = Uuid::new_v4();
+        let existing = HashMap::from([(kept, 0), (dropped, 0)]);
+
+        let summary = RecordChangeSummary::for_channels(&existing, &[kept, new, new], 0);
+
+        assert_eq!(
+            summary,
+            RecordChangeSummary {
+                added: 1,
+                updated: 1,
+                removed: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_channel_changes_respect_retention_grace() {
+        let stale = Uuid::new_v4();
+        let expiring = Uuid::new_v4();
+        let existing = HashMap::from([(stale, 0), (expiring, 2)]);
+
+        let summary = RecordChangeSummary::for_channels(&existing, &[], 2);
+
+        assert_eq!(summary.removed, 1);
+        assert_eq!(summary.added, 0);
+    }
+}
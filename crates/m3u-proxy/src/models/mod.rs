@@ -6,15 +6,37 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 pub mod channel;
+pub mod channel_count_alert;
+pub mod channel_epg_mapping;
+pub mod channel_exclusion;
+pub mod channel_identity;
+pub mod channel_logo_assignment;
+pub mod channel_retention;
 pub mod data_mapping;
+pub mod epg_channel_metadata;
 pub mod epg_source;
 pub mod filter;
+pub mod ingest_snapshot;
+pub mod ingestion_run;
 pub mod last_known_codec;
 pub mod linked_xtream;
 pub mod logo_asset;
+pub mod proxy_basic_auth;
+pub mod proxy_order;
+pub mod proxy_settings;
+pub mod proxy_template;
 pub mod relay;
+pub mod rule_version;
+pub mod share_link;
+pub mod source_balancing;
+pub mod stream_headers;
 pub mod stream_proxy;
 pub mod stream_source;
+pub mod stream_source_mirror;
+pub mod trash;
+pub mod url_rewrite_rule;
+pub mod virtual_channel;
+pub mod xtream_category_filter;
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[schema(description = "Stream source configuration for M3U playlists or Xtream Codes APIs")]
@@ -97,6 +119,138 @@ impl FromStr for StreamProxyMode {
     }
 }
 
+/// Client preset a proxy's playlist is served with
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    ToSchema,
+    sea_orm::DeriveActiveEnum,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum OutputProfile {
+    /// Playlist exactly as generated
+    #[default]
+    #[sea_orm(string_value = "standard")]
+    Standard,
+    /// Kodi PVR IPTV Simple Client: XMLTV header hint, catchup attributes, Kodi groups
+    #[sea_orm(string_value = "kodi")]
+    Kodi,
+}
+
+impl FromStr for OutputProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(OutputProfile::Standard),
+            "kodi" => Ok(OutputProfile::Kodi),
+            _ => Err(format!("Invalid output profile: {s}")),
+        }
+    }
+}
+
+/// How secondary streams of the same channel are emitted in a proxy's playlist
+///
+/// A channel carried by several of the proxy's stream sources (matched by tvg-id, or by name
+/// when there is none) is served from the highest-priority source; the other sources' streams
+/// become its backups.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    ToSchema,
+    sea_orm::DeriveActiveEnum,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum BackupStreamMode {
+    /// Every channel is emitted as its own entry
+    #[default]
+    #[sea_orm(string_value = "off")]
+    Off,
+    /// Backups are listed in a `backup-url` attribute on the primary entry
+    #[sea_orm(string_value = "attribute")]
+    Attribute,
+    /// Backups follow the primary entry in a dedicated "Backup Streams" group
+    #[sea_orm(string_value = "group")]
+    Group,
+}
+
+impl FromStr for BackupStreamMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(BackupStreamMode::Off),
+            "attribute" => Ok(BackupStreamMode::Attribute),
+            "group" => Ok(BackupStreamMode::Group),
+            _ => Err(format!("Invalid backup stream mode: {s}")),
+        }
+    }
+}
+
+/// What a proxy-mode stream serves while its upstream is down
+///
+/// With a slate, a failed or dropped upstream is replaced by a looping "channel unavailable"
+/// stream instead of an error, and the upstream is retried until it recovers.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    ToSchema,
+    sea_orm::DeriveActiveEnum,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum OfflineSlateMode {
+    /// Upstream failures end the stream with an error
+    #[default]
+    #[sea_orm(string_value = "off")]
+    Off,
+    /// A still "channel unavailable" card rendered by ffmpeg
+    #[sea_orm(string_value = "generated")]
+    Generated,
+    /// The configured `offline_slate.media_path` file, looped
+    #[sea_orm(string_value = "media")]
+    Media,
+}
+
+impl FromStr for OfflineSlateMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(OfflineSlateMode::Off),
+            "generated" => Ok(OfflineSlateMode::Generated),
+            "media" => Ok(OfflineSlateMode::Media),
+            _ => Err(format!("Invalid offline slate mode: {s}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamProxy {
     pub id: Uuid,
@@ -117,6 +271,96 @@ pub struct StreamProxy {
     #[serde(default = "default_cache_program_logos")]
     pub cache_program_logos: bool,
     pub relay_profile_id: Option<Uuid>,
+    /// Serve playlists with HMAC-signed, expiring stream URLs
+    #[serde(default)]
+    pub sign_stream_urls: bool,
+    /// Client preset the playlist is served with
+    #[serde(default)]
+    pub output_profile: OutputProfile,
+    /// How streams of the same channel from lower-priority sources are emitted
+    #[serde(default)]
+    pub backup_streams: BackupStreamMode,
+    /// What proxy-mode streams serve while the upstream is down
+    #[serde(default)]
+    pub offline_slate: OfflineSlateMode,
+    /// Preferred languages of EPG channel names and icons, in order (e.g. "de,en")
+    #[serde(default)]
+    pub epg_languages: Option<String>,
+    /// Debounce window for source-triggered regenerations, in seconds (unset uses the
+    /// global `regeneration.debounce`)
+    #[serde(default)]
+    pub regeneration_debounce_seconds: Option<i32>,
+    /// Channel number blocks per group, numbered ahead of `starting_channel_number`
+    #[serde(default)]
+    pub channel_number_blocks: Vec<ChannelNumberBlock>,
+    /// IANA timezone the XMLTV programme times are written in (unset writes UTC)
+    #[serde(default)]
+    pub epg_timezone: Option<String>,
+}
+
+/// A block of channel numbers reserved for one channel group of a proxy
+///
+/// The block runs from `start` up to the next block's start (or the proxy's
+/// `starting_channel_number`, when that lies above); channels that do not fit overflow
+/// into the proxy's general numbering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ChannelNumberBlock {
+    /// Channel group (`group-title`), matched case-insensitively
+    pub group: String,
+    /// First channel number of the block
+    pub start: u32,
+}
+
+impl ChannelNumberBlock {
+    /// Blocks stored in a proxy's `channel_number_blocks` JSON column
+    pub fn from_column(value: Option<&str>) -> Vec<Self> {
+        let Some(value) = value.filter(|v| !v.trim().is_empty()) else {
+            return Vec::new();
+        };
+        serde_json::from_str(value).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid channel_number_blocks '{}': {}", value, e);
+            Vec::new()
+        })
+    }
+
+    /// JSON column value for a proxy's blocks (NULL when there are none)
+    pub fn to_column(blocks: &[Self]) -> Option<String> {
+        if blocks.is_empty() {
+            return None;
+        }
+        serde_json::to_string(blocks).ok()
+    }
+
+    /// Check a proxy's blocks for empty groups, zero starts and duplicates
+    pub fn validate(blocks: &[Self]) -> Result<(), String> {
+        let mut groups = std::collections::HashSet::new();
+        let mut starts = std::collections::HashSet::new();
+        for block in blocks {
+            let group = block.group.trim().to_lowercase();
+            if group.is_empty() {
+                return Err("Channel number blocks need a group".to_string());
+            }
+            if block.start == 0 {
+                return Err(format!(
+                    "Channel number block for group '{}' must start above 0",
+                    block.group
+                ));
+            }
+            if !groups.insert(group) {
+                return Err(format!(
+                    "Group '{}' has more than one channel number block",
+                    block.group
+                ));
+            }
+            if !starts.insert(block.start) {
+                return Err(format!(
+                    "More than one channel number block starts at {}",
+                    block.start
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 fn default_cache_channel_logos() -> bool {
@@ -230,8 +474,20 @@ pub struct StreamProxyCreateRequest {
     pub cache_channel_logos: bool,
     pub cache_program_logos: bool,
     pub relay_profile_id: Option<Uuid>,
+    pub sign_stream_urls: bool,
+    pub output_profile: OutputProfile,
+    pub backup_streams: BackupStreamMode,
+    pub offline_slate: OfflineSlateMode,
+    pub epg_languages: Option<String>,
+    pub regeneration_debounce_seconds: Option<i32>,
+    pub channel_number_blocks: Vec<ChannelNumberBlock>,
+    pub epg_timezone: Option<String>,
 }
 
+/// Changes to a stream proxy
+///
+/// Settings typed `Option<T>` over a non-nullable column, or `Option<Option<T>>` over a
+/// nullable one, keep their stored value when `None`.
 #[derive(Debug, Clone)]
 pub struct StreamProxyUpdateRequest {
     pub name: String,
@@ -249,6 +505,14 @@ pub struct StreamProxyUpdateRequest {
     pub cache_channel_logos: bool,
     pub cache_program_logos: bool,
     pub relay_profile_id: Option<Uuid>,
+    pub sign_stream_urls: Option<bool>,
+    pub output_profile: Option<OutputProfile>,
+    pub backup_streams: Option<BackupStreamMode>,
+    pub offline_slate: Option<OfflineSlateMode>,
+    pub epg_languages: Option<Option<String>>,
+    pub regeneration_debounce_seconds: Option<Option<i32>>,
+    pub channel_number_blocks: Option<Vec<ChannelNumberBlock>>,
+    pub epg_timezone: Option<Option<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -279,6 +543,10 @@ pub struct Channel {
     pub tvg_chno: Option<String>, // Channel number from M3U (e.g., "1", "12")
     pub tvg_logo: Option<String>,
     pub tvg_shift: Option<String>, // Timeshift offset for M3U (e.g., "+1", "+24")
+    /// Shift applied to this channel's programmes in the generated XMLTV (e.g. "+1h");
+    /// only set by data mapping, and taking precedence over `tvg_shift`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epg_shift: Option<String>,
     pub group_title: Option<String>,
     pub channel_name: String,
     pub stream_url: String,
@@ -289,6 +557,36 @@ pub struct Channel {
     pub last_probed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// EXTINF attributes without a channel column (e.g. catchup, timeshift, tvg-rec or
+    /// custom x- attributes), keyed by attribute name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_attributes: BTreeMap<String, String>,
+}
+
+impl Channel {
+    /// Extra attributes as stored in the `extra_attributes` column (`None` when empty)
+    pub fn extra_attributes_json(&self) -> Option<String> {
+        if self.extra_attributes.is_empty() {
+            return None;
+        }
+        serde_json::to_string(&self.extra_attributes).ok()
+    }
+
+    /// Extra attributes from the `extra_attributes` column; malformed JSON reads as none
+    pub fn parse_extra_attributes(json: Option<&str>) -> BTreeMap<String, String> {
+        json.and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Shift applied to the channel's programmes: `epg_shift` from data mapping, else the
+    /// source's `tvg_shift` (`None` when neither is set)
+    pub fn effective_epg_shift(&self) -> Option<&str> {
+        [self.epg_shift.as_deref(), self.tvg_shift.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .find(|shift| !shift.is_empty())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -581,6 +879,142 @@ pub struct ExpressionValidateResult {
         "negate": false
     }))]
     pub expression_tree: Option<serde_json::Value>,
+
+    /// Lint findings (only populated when linting is requested)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ExpressionLintWarning>,
+}
+
+/// Severity of an expression lint finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpressionLintSeverity {
+    Info,
+    Warning,
+}
+
+/// Non-fatal issue found by expression linting (performance, redundancy, likely mistakes)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExpressionLintWarning {
+    #[schema(example = "warning")]
+    pub severity: ExpressionLintSeverity,
+
+    #[schema(example = "catastrophic_regex")]
+    pub rule: String,
+
+    #[schema(example = "Nested quantifier in regex '(\\w+)+$'")]
+    pub message: String,
+
+    #[schema(example = "channel_name")]
+    pub field: Option<String>,
+
+    #[schema(example = "Remove the outer quantifier or make the inner pattern unambiguous")]
+    pub suggestion: Option<String>,
+}
+
+/// Expression plus a sample record to evaluate it against
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExpressionExplainRequest {
+    #[schema(example = "channel_name matches \"^(.+) HD$\" SET tvg_name = \"$1\"")]
+    pub expression: String,
+
+    /// stream_filter, epg_filter, stream_mapping (default) or epg_mapping
+    #[schema(example = "stream_mapping")]
+    pub domain: Option<String>,
+
+    /// Sample channel or programme as field name -> value (aliases accepted)
+    #[schema(value_type = Object, example = json!({"channel_name": "BBC One HD", "group_title": "UK"}))]
+    pub sample: std::collections::HashMap<String, serde_json::Value>,
+}
+
+/// Kind of node in an explain trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpressionExplainNodeKind {
+    Condition,
+    Group,
+}
+
+/// One evaluated node of a condition tree, in evaluation (pre-)order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExpressionExplainStep {
+    /// Nesting level (0 = root)
+    pub depth: usize,
+    pub kind: ExpressionExplainNodeKind,
+    pub matched: bool,
+
+    /// AND/OR for groups
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logical_operator: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Value of the field in the sample (absent when the sample does not set it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_value: Option<String>,
+
+    /// Regex captures (index 0 is the whole match)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captures: Vec<String>,
+}
+
+/// An action of a group and what it would do to the sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExpressionExplainAction {
+    pub field: String,
+    #[schema(example = "set")]
+    pub operator: String,
+
+    /// Value as written, before capture substitution
+    pub value: Option<String>,
+
+    pub fired: bool,
+
+    /// Why the action did not fire
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// Condition group (with its actions) of an explained expression
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExpressionExplainGroup {
+    pub matched: bool,
+    pub steps: Vec<ExpressionExplainStep>,
+
+    /// Captures available to the actions as $1, $2, ...
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captures: Vec<String>,
+
+    pub actions: Vec<ExpressionExplainAction>,
+}
+
+/// Step-by-step evaluation of an expression against a sample record
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExpressionExplainResult {
+    pub is_valid: bool,
+
+    /// Parse or validation error when the expression is invalid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// True if at least one condition group matched
+    pub matched: bool,
+
+    pub groups: Vec<ExpressionExplainGroup>,
+
+    /// Sample after all fired actions, keyed by canonical field name
+    pub record: std::collections::BTreeMap<String, Option<String>>,
+
+    /// Fields changed by fired actions
+    pub modified_fields: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -1500,6 +1934,69 @@ pub struct GenerationStats {
     pub memory_pressure_events: usize,
     pub spill_to_disk_events: usize,
     pub temp_files_created: usize,
+
+    /// Stage cache (incremental regeneration): stages reused vs. re-run
+    #[serde(default)]
+    pub stage_cache_hits: Vec<String>,
+    #[serde(default)]
+    pub stage_cache_misses: Vec<String>,
+
+    /// Logo prefetch: new logos over the download budget, left to the background job
+    #[serde(default)]
+    pub logos_deferred: usize,
+
+    /// Data mapping regex precheck effectiveness per rule (when auto-tuning is enabled)
+    #[serde(default)]
+    pub precheck_effectiveness: Vec<PrecheckEffectiveness>,
+
+    /// Channel number blocks that ran out of numbers
+    #[serde(default)]
+    pub number_block_overflows: Vec<NumberBlockOverflow>,
+
+    /// EPG programmes removed or trimmed because sources overlapped
+    #[serde(default)]
+    pub epg_dedup: EpgDedupStats,
+}
+
+/// Programmes deduplicated across EPG sources before merging
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EpgDedupStats {
+    /// Programmes identical to one already kept from the same or a higher-priority source
+    pub duplicates_removed: usize,
+    /// Programmes shortened to the time not covered by higher-priority sources
+    pub overlaps_trimmed: usize,
+    /// Programmes left with less than a minute of uncovered time
+    pub overlaps_dropped: usize,
+}
+
+/// A group's channel number block that could not hold all of the group's channels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct NumberBlockOverflow {
+    pub group: String,
+    pub start: u32,
+    /// Numbers in the block (unbounded blocks never overflow)
+    pub capacity: u32,
+    /// Channels of the group numbered from the proxy's `starting_channel_number` instead
+    pub overflowed: usize,
+}
+
+/// How well the regex precheck of one data mapping rule paid off during a generation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PrecheckEffectiveness {
+    pub rule_id: String,
+    pub rule_name: String,
+    /// Regex evaluations requested by the rule
+    pub evaluations: u64,
+    /// Evaluations the precheck answered without running the regex
+    pub regex_skipped: u64,
+    /// Fraction of sampled evaluations the precheck skipped
+    pub sampled_skip_rate: f64,
+    pub average_precheck_ns: f64,
+    pub average_regex_ns: f64,
+    /// Whether sampling finished and a decision was made
+    pub tuned: bool,
+    /// Whether the precheck is still applied for this rule
+    pub precheck_enabled: bool,
 }
 
 impl GenerationStats {
@@ -1542,6 +2039,12 @@ impl GenerationStats {
             memory_pressure_events: 0,
             spill_to_disk_events: 0,
             temp_files_created: 0,
+            stage_cache_hits: Vec::new(),
+            stage_cache_misses: Vec::new(),
+            logos_deferred: 0,
+            precheck_effectiveness: Vec::new(),
+            number_block_overflows: Vec::new(),
+            epg_dedup: EpgDedupStats::default(),
         }
     }
 
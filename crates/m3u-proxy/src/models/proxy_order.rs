@@ -0,0 +1,192 @@
+//! Proxy ordering models
+//!
+//! A proxy's stream sources, EPG sources and filters are applied in priority order. The
+//! ordering endpoints move one entry at a time and carry a version derived from the
+//! current order, so a move made against a stale view is rejected instead of silently
+//! overwriting a concurrent edit.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Which of a proxy's ordered lists to act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProxyOrderKind {
+    StreamSources,
+    EpgSources,
+    Filters,
+}
+
+/// How to move an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ProxyOrderMove {
+    /// One place earlier (higher priority)
+    Up,
+    /// One place later (lower priority)
+    Down,
+    /// To a 1-based position; later entries shift down
+    ToPosition { position: usize },
+}
+
+/// Request to move one entry of an ordered list
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ProxyReorderRequest {
+    /// Source or filter ID to move
+    pub item_id: Uuid,
+    #[serde(flatten)]
+    pub movement: ProxyOrderMove,
+    /// Version the client last saw; the move is rejected if the order has changed since
+    pub expected_version: String,
+}
+
+/// An entry and its priority (1 = applied first)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProxyOrderItem {
+    pub id: Uuid,
+    pub priority_order: i32,
+}
+
+/// Current order of one of a proxy's lists
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProxyOrdering {
+    pub kind: ProxyOrderKind,
+    /// Opaque token identifying this order; echo it as `expected_version` when moving
+    pub version: String,
+    pub items: Vec<ProxyOrderItem>,
+}
+
+impl ProxyOrdering {
+    pub fn new(kind: ProxyOrderKind, ids: &[Uuid]) -> Self {
+        Self {
+            kind,
+            version: ordering_version(ids),
+            items: ids
+                .iter()
+                .enumerate()
+                .map(|(index, id)| ProxyOrderItem {
+                    id: *id,
+                    priority_order: index as i32 + 1,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Result of a move attempted against the stored order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyReorderOutcome {
+    /// The move was applied; holds the new order
+    Applied(Vec<Uuid>),
+    /// The order changed since `expected_version`; holds the current order
+    Conflict(Vec<Uuid>),
+    /// The entry is not part of this list
+    UnknownItem,
+    Invalid(String),
+}
+
+/// Version token for an order: changes whenever membership or position changes
+pub fn ordering_version(ids: &[Uuid]) -> String {
+    let mut hasher = Sha256::new();
+    for id in ids {
+        hasher.update(id.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+/// Apply a move to an order in place
+///
+/// Returns `Ok(false)` when `item_id` is not in the order. Moving the first entry up or
+/// the last entry down is a no-op.
+pub fn apply_move(
+    ids: &mut Vec<Uuid>,
+    item_id: Uuid,
+    movement: ProxyOrderMove,
+) -> Result<bool, String> {
+    let Some(from) = ids.iter().position(|id| *id == item_id) else {
+        return Ok(false);
+    };
+    let to = match movement {
+        ProxyOrderMove::Up => from.saturating_sub(1),
+        ProxyOrderMove::Down => (from + 1).min(ids.len() - 1),
+        ProxyOrderMove::ToPosition { position } => {
+            if position == 0 || position > ids.len() {
+                return Err(format!(
+                    "position must be between 1 and {}, got {position}",
+                    ids.len()
+                ));
+            }
+            position - 1
+        }
+    };
+    let item = ids.remove(from);
+    ids.insert(to, item);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: u128) -> Vec<Uuid> {
+        (1..=n).map(Uuid::from_u128).collect()
+    }
+
+    #[test]
+    fn test_moves() {
+        let mut order = ids(4);
+        assert!(apply_move(&mut order, Uuid::from_u128(3), ProxyOrderMove::Up).unwrap());
+        assert_eq!(order, [1, 3, 2, 4].map(Uuid::from_u128));
+
+        assert!(apply_move(&mut order, Uuid::from_u128(1), ProxyOrderMove::Down).unwrap());
+        assert_eq!(order, [3, 1, 2, 4].map(Uuid::from_u128));
+
+        apply_move(
+            &mut order,
+            Uuid::from_u128(4),
+            ProxyOrderMove::ToPosition { position: 1 },
+        )
+        .unwrap();
+        assert_eq!(order, [4, 3, 1, 2].map(Uuid::from_u128));
+
+        // Edges are no-ops
+        apply_move(&mut order, Uuid::from_u128(4), ProxyOrderMove::Up).unwrap();
+        apply_move(&mut order, Uuid::from_u128(2), ProxyOrderMove::Down).unwrap();
+        assert_eq!(order, [4, 3, 1, 2].map(Uuid::from_u128));
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let mut order = ids(2);
+        assert!(!apply_move(&mut order, Uuid::from_u128(9), ProxyOrderMove::Up).unwrap());
+        assert!(
+            apply_move(
+                &mut order,
+                Uuid::from_u128(1),
+                ProxyOrderMove::ToPosition { position: 3 }
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_version_tracks_order() {
+        let order = ids(3);
+        let mut swapped = order.clone();
+        swapped.swap(0, 1);
+        assert_eq!(ordering_version(&order), ordering_version(&ids(3)));
+        assert_ne!(ordering_version(&order), ordering_version(&swapped));
+        assert_ne!(ordering_version(&order), ordering_version(&order[..2]));
+    }
+
+    #[test]
+    fn test_request_deserialization() {
+        let request: ProxyReorderRequest = serde_json::from_str(
+            r#"{"item_id":"00000000-0000-0000-0000-000000000001","action":"to_position","position":2,"expected_version":"abc"}"#,
+        )
+        .unwrap();
+        assert_eq!(request.movement, ProxyOrderMove::ToPosition { position: 2 });
+    }
+}
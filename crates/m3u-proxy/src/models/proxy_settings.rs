@@ -0,0 +1,278 @@
+//! Per-proxy feature settings
+//!
+//! Features that only some proxies opt into are stored as JSON rows of `proxy_settings`,
+//! one row per proxy and feature key, and managed under `/api/v1/proxies/{id}/...`. A proxy
+//! without a row for a feature does not use it.
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::str::FromStr;
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::{EpgMergeFieldSources, EpgMergeStrategy};
+
+/// A setting stored per proxy under a fixed key
+pub trait ProxySetting: Serialize + DeserializeOwned + Send + Sync {
+    /// Key of the setting's `proxy_settings` row
+    const KEY: &'static str;
+
+    /// Check the setting before it is stored, returning a message suitable for a 400 response
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Keep-alive policy for the relays of a relay-mode proxy
+///
+/// Without a `schedule` the selected relays are kept running continuously. With a
+/// `schedule` (7-field cron) they are started at each scheduled time and kept warm for
+/// `warm_for`, after which normal idle cleanup applies.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RelayKeepAlivePolicy {
+    /// Channels to keep warm
+    #[serde(default)]
+    pub channel_ids: Vec<Uuid>,
+
+    /// Additionally keep the N most-watched channels of the proxy warm
+    #[serde(default)]
+    pub most_watched: usize,
+
+    /// Optional pre-warm schedule
+    #[serde(default)]
+    #[schema(example = "0 0 18 * * * *")]
+    pub schedule: Option<String>,
+
+    /// How long relays stay warm after a scheduled pre-warm
+    #[serde(default = "default_keepalive_warm_for")]
+    #[schema(example = "2h")]
+    pub warm_for: String,
+}
+
+fn default_keepalive_warm_for() -> String {
+    "30m".to_string()
+}
+
+impl RelayKeepAlivePolicy {
+    /// Parsed warm duration (falls back to 30m)
+    pub fn warm_for_duration(&self) -> Duration {
+        humantime::parse_duration(&self.warm_for).unwrap_or_else(|_| Duration::from_secs(30 * 60))
+    }
+}
+
+impl ProxySetting for RelayKeepAlivePolicy {
+    const KEY: &'static str = "relay_keepalive";
+
+    fn validate(&self) -> Result<(), String> {
+        if self.channel_ids.is_empty() && self.most_watched == 0 {
+            return Err("select channel_ids or a most_watched count to keep warm".to_string());
+        }
+        if let Some(schedule) = &self.schedule {
+            cron::Schedule::from_str(schedule)
+                .map_err(|e| format!("invalid schedule '{schedule}': {e}"))?;
+        }
+        humantime::parse_duration(&self.warm_for)
+            .map_err(|e| format!("invalid warm_for '{}': {e}", self.warm_for))?;
+        Ok(())
+    }
+}
+
+/// How a proxy combines programmes when several EPG sources cover the same channel
+///
+/// Proxies without this setting use the configured `epg_merge.default_strategy`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct EpgMergePolicy {
+    #[serde(default)]
+    pub strategy: EpgMergeStrategy,
+
+    /// Preferred EPG sources per field for `field_merge` (empty lists use source priority)
+    #[serde(default)]
+    pub field_sources: EpgMergeFieldSources,
+}
+
+impl EpgMergePolicy {
+    /// Policy using `strategy` with source priority for every field
+    pub fn with_strategy(strategy: EpgMergeStrategy) -> Self {
+        Self {
+            strategy,
+            field_sources: EpgMergeFieldSources::default(),
+        }
+    }
+}
+
+impl ProxySetting for EpgMergePolicy {
+    const KEY: &'static str = "epg_merge";
+}
+
+/// Alternate EPG source used when `source_id` is stale
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EpgFallback {
+    pub source_id: Uuid,
+    pub fallback_source_id: Uuid,
+}
+
+/// Preferred alternate sources for a proxy's EPG sources when they go stale
+///
+/// A stale source without an entry falls back to the proxy's next fresh EPG source.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct EpgFallbacks {
+    #[serde(default)]
+    pub fallbacks: Vec<EpgFallback>,
+}
+
+impl EpgFallbacks {
+    /// Preferred fallback of a source
+    pub fn fallback_for(&self, source_id: Uuid) -> Option<Uuid> {
+        self.fallbacks
+            .iter()
+            .find(|f| f.source_id == source_id)
+            .map(|f| f.fallback_source_id)
+    }
+}
+
+impl ProxySetting for EpgFallbacks {
+    const KEY: &'static str = "epg_fallbacks";
+
+    fn validate(&self) -> Result<(), String> {
+        for (index, fallback) in self.fallbacks.iter().enumerate() {
+            if fallback.source_id == fallback.fallback_source_id {
+                return Err(format!(
+                    "EPG source {} cannot be its own fallback",
+                    fallback.source_id
+                ));
+            }
+            if self.fallbacks[..index]
+                .iter()
+                .any(|f| f.source_id == fallback.source_id)
+            {
+                return Err(format!(
+                    "EPG source {} has more than one fallback",
+                    fallback.source_id
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether a proxy's guide gets synthetic programmes where it has no guide data
+///
+/// Overrides `epg_gap_filler.enabled` for the proxy; blocks follow the `epg_gap_filler`
+/// configuration either way.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EpgGapFilling {
+    pub enabled: bool,
+}
+
+impl ProxySetting for EpgGapFilling {
+    const KEY: &'static str = "epg_gap_filling";
+}
+
+/// Whether a proxy's playlist carries container and header hints for players
+///
+/// Overrides `stream_hints.enabled` for the proxy; the emitted lines and headers follow the
+/// `stream_hints` configuration either way.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StreamHints {
+    pub enabled: bool,
+}
+
+impl ProxySetting for StreamHints {
+    const KEY: &'static str = "stream_hints";
+}
+
+/// Whether a proxy's playlist carries the allowlisted extra EXTINF attributes
+///
+/// Overrides `attribute_passthrough.enabled` for the proxy; the attributes written follow
+/// the `attribute_passthrough` allowlist either way.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AttributePassthrough {
+    pub enabled: bool,
+}
+
+impl ProxySetting for AttributePassthrough {
+    const KEY: &'static str = "attribute_passthrough";
+}
+
+/// Client IP and country allow/deny rules of a proxy
+///
+/// Deny rules win; once any allow rule is set, only matching clients get in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ProxyAccessRules {
+    /// CIDR ranges or single addresses, e.g. "192.168.0.0/16" or "2001:db8::/32"
+    #[serde(default)]
+    pub allow_cidrs: Vec<String>,
+    #[serde(default)]
+    pub deny_cidrs: Vec<String>,
+    /// ISO 3166-1 alpha-2 country codes, e.g. "GB"
+    #[serde(default)]
+    pub allow_countries: Vec<String>,
+    #[serde(default)]
+    pub deny_countries: Vec<String>,
+}
+
+impl ProxySetting for ProxyAccessRules {
+    const KEY: &'static str = "access_rules";
+
+    fn validate(&self) -> Result<(), String> {
+        crate::utils::access_control::ProxyAccessPolicy::compile(self).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_keepalive_validation() {
+        let policy = RelayKeepAlivePolicy {
+            channel_ids: Vec::new(),
+            most_watched: 5,
+            schedule: Some("0 0 18 * * * *".to_string()),
+            warm_for: "4h".to_string(),
+        };
+        assert!(policy.validate().is_ok());
+        assert_eq!(policy.warm_for_duration(), Duration::from_secs(4 * 3600));
+
+        let nothing_selected = RelayKeepAlivePolicy {
+            most_watched: 0,
+            ..policy.clone()
+        };
+        assert!(nothing_selected.validate().is_err());
+        let bad_schedule = RelayKeepAlivePolicy {
+            schedule: Some("every evening".to_string()),
+            ..policy.clone()
+        };
+        assert!(bad_schedule.validate().is_err());
+        let bad_warm_for = RelayKeepAlivePolicy {
+            warm_for: "a while".to_string(),
+            ..policy
+        };
+        assert!(bad_warm_for.validate().is_err());
+    }
+
+    #[test]
+    fn test_epg_fallbacks_validation() {
+        let (a, b, c) = (Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3));
+        let fallback = |source_id, fallback_source_id| EpgFallback {
+            source_id,
+            fallback_source_id,
+        };
+
+        let fallbacks = EpgFallbacks {
+            fallbacks: vec![fallback(a, b), fallback(b, c)],
+        };
+        assert!(fallbacks.validate().is_ok());
+        assert_eq!(fallbacks.fallback_for(b), Some(c));
+        assert_eq!(fallbacks.fallback_for(c), None);
+
+        let own = EpgFallbacks {
+            fallbacks: vec![fallback(a, a)],
+        };
+        assert!(own.validate().is_err());
+        let duplicate = EpgFallbacks {
+            fallbacks: vec![fallback(a, b), fallback(a, c)],
+        };
+        assert!(duplicate.validate().is_err());
+    }
+}
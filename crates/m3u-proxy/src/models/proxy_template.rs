@@ -0,0 +1,265 @@
+//! Filter group and proxy template models
+//!
+//! Filter groups are named, ordered bundles of filters that are attached to (and detached
+//! from) a proxy as a unit. Proxy templates are saved proxy settings — mode, sources,
+//! EPG sources, filters and filter groups in priority order — new proxies can be created
+//! from, so common setups do not have to be reassembled by hand each time.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{
+    BackupStreamMode, ChannelNumberBlock, OfflineSlateMode, OutputProfile,
+    ProxyEpgSourceCreateRequest, ProxyFilterCreateRequest, ProxySourceCreateRequest,
+    StreamProxyCreateRequest, StreamProxyMode,
+};
+
+/// A named, ordered set of filters
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FilterGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// Member filters in the order they are attached
+    pub filter_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to create or replace a filter group
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct FilterGroupRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Member filters in the order they are attached
+    #[serde(default)]
+    pub filter_ids: Vec<Uuid>,
+}
+
+/// Proxy settings saved in a template
+///
+/// Sources, EPG sources and filters are listed highest priority first. Filter groups are
+/// expanded, in order, after the individual filters when a proxy is created.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProxyTemplateSettings {
+    /// "redirect", "proxy" or "relay"
+    #[serde(default = "default_proxy_mode")]
+    pub proxy_mode: String,
+    pub upstream_timeout: Option<i32>,
+    pub buffer_size: Option<i32>,
+    pub max_concurrent_streams: Option<i32>,
+    #[serde(default = "default_starting_channel_number")]
+    pub starting_channel_number: i32,
+    #[serde(default)]
+    pub stream_source_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub epg_source_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub filter_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub filter_group_ids: Vec<Uuid>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    #[serde(default)]
+    pub auto_regenerate: bool,
+    #[serde(default = "default_true")]
+    pub cache_channel_logos: bool,
+    #[serde(default)]
+    pub cache_program_logos: bool,
+    pub relay_profile_id: Option<Uuid>,
+    #[serde(default)]
+    pub sign_stream_urls: bool,
+    #[serde(default)]
+    pub output_profile: OutputProfile,
+    #[serde(default)]
+    pub backup_streams: BackupStreamMode,
+    #[serde(default)]
+    pub offline_slate: OfflineSlateMode,
+    #[serde(default)]
+    pub epg_languages: Option<String>,
+    #[serde(default)]
+    pub regeneration_debounce_seconds: Option<i32>,
+    #[serde(default)]
+    pub channel_number_blocks: Vec<ChannelNumberBlock>,
+    #[serde(default)]
+    pub epg_timezone: Option<String>,
+}
+
+fn default_proxy_mode() -> String {
+    "redirect".to_string()
+}
+
+fn default_starting_channel_number() -> i32 {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ProxyTemplateSettings {
+    pub fn proxy_mode(&self) -> Result<StreamProxyMode, String> {
+        self.proxy_mode
+            .to_lowercase()
+            .parse()
+            .map_err(|_| format!("Invalid proxy mode: {}", self.proxy_mode))
+    }
+
+    /// Proxy creation request for these settings
+    ///
+    /// `filter_ids` is the template's full filter list, with filter groups already
+    /// expanded (see [`expand_filters`]).
+    pub fn to_create_request(
+        &self,
+        name: String,
+        description: Option<String>,
+        filter_ids: &[Uuid],
+    ) -> Result<StreamProxyCreateRequest, String> {
+        Ok(StreamProxyCreateRequest {
+            name,
+            description,
+            proxy_mode: self.proxy_mode()?,
+            upstream_timeout: self.upstream_timeout,
+            buffer_size: self.buffer_size,
+            max_concurrent_streams: self.max_concurrent_streams,
+            starting_channel_number: self.starting_channel_number,
+            stream_sources: self
+                .stream_source_ids
+                .iter()
+                .zip(1..)
+                .map(|(source_id, priority_order)| ProxySourceCreateRequest {
+                    source_id: *source_id,
+                    priority_order,
+                })
+                .collect(),
+            epg_sources: self
+                .epg_source_ids
+                .iter()
+                .zip(1..)
+                .map(
+                    |(epg_source_id, priority_order)| ProxyEpgSourceCreateRequest {
+                        epg_source_id: *epg_source_id,
+                        priority_order,
+                    },
+                )
+                .collect(),
+            filters: filter_ids
+                .iter()
+                .zip(1..)
+                .map(|(filter_id, priority_order)| ProxyFilterCreateRequest {
+                    filter_id: *filter_id,
+                    priority_order,
+                    is_active: true,
+                })
+                .collect(),
+            is_active: self.is_active,
+            auto_regenerate: self.auto_regenerate,
+            cache_channel_logos: self.cache_channel_logos,
+            cache_program_logos: self.cache_program_logos,
+            relay_profile_id: self.relay_profile_id,
+            sign_stream_urls: self.sign_stream_urls,
+            output_profile: self.output_profile,
+            backup_streams: self.backup_streams,
+            offline_slate: self.offline_slate,
+            epg_languages: self.epg_languages.clone(),
+            regeneration_debounce_seconds: self.regeneration_debounce_seconds,
+            channel_number_blocks: self.channel_number_blocks.clone(),
+            epg_timezone: self.epg_timezone.clone(),
+        })
+    }
+}
+
+/// Filters first, then each group's members in order, keeping the first occurrence of
+/// a filter that appears more than once
+pub fn expand_filters(filter_ids: &[Uuid], groups: &[FilterGroup]) -> Vec<Uuid> {
+    let mut expanded: Vec<Uuid> = Vec::new();
+    for filter_id in filter_ids
+        .iter()
+        .chain(groups.iter().flat_map(|group| &group.filter_ids))
+    {
+        if !expanded.contains(filter_id) {
+            expanded.push(*filter_id);
+        }
+    }
+    expanded
+}
+
+/// A saved bundle of proxy settings
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProxyTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub settings: ProxyTemplateSettings,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to create or replace a proxy template
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ProxyTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub settings: ProxyTemplateSettings,
+}
+
+/// Request to create a proxy from a template
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateProxyFromTemplateRequest {
+    pub name: String,
+    /// Defaults to the template's description
+    pub description: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(filter_ids: Vec<Uuid>) -> FilterGroup {
+        FilterGroup {
+            id: Uuid::new_v4(),
+            name: "Group".to_string(),
+            description: None,
+            filter_ids,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_expand_filters_keeps_order_and_drops_duplicates() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let expanded = expand_filters(&[b], &[group(vec![a, b]), group(vec![c, a])]);
+        assert_eq!(expanded, vec![b, a, c]);
+    }
+
+    #[test]
+    fn test_settings_to_create_request() {
+        let settings: ProxyTemplateSettings = serde_json::from_str(
+            r#"{"proxy_mode": "Relay", "stream_source_ids": ["00000000-0000-0000-0000-000000000001", "00000000-0000-0000-0000-000000000002"]}"#,
+        )
+        .unwrap();
+        assert!(settings.is_active && settings.cache_channel_logos);
+
+        let filter = Uuid::new_v4();
+        let request = settings
+            .to_create_request("Living room".to_string(), None, &[filter])
+            .unwrap();
+        assert_eq!(request.proxy_mode, StreamProxyMode::Relay);
+        assert_eq!(request.starting_channel_number, 1);
+        assert_eq!(request.stream_sources[1].priority_order, 2);
+        assert_eq!(request.filters[0].filter_id, filter);
+
+        let invalid = ProxyTemplateSettings {
+            proxy_mode: "mirror".to_string(),
+            ..settings
+        };
+        assert!(
+            invalid
+                .to_create_request("x".to_string(), None, &[])
+                .is_err()
+        );
+    }
+}
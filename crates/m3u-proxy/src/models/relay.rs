@@ -51,6 +51,24 @@ pub struct RelayProfile {
     #[schema(example = 30)]
     pub input_timeout: i32,
 
+    // Track selection
+    /// Audio languages in priority order, as tagged in the input (e.g. "eng")
+    #[serde(default)]
+    #[schema(example = json!(["eng", "deu"]))]
+    pub audio_languages: Vec<String>,
+    /// Forced audio track, by position among the input's audio tracks (0-based)
+    pub audio_track_index: Option<u32>,
+    /// Subtitle languages in priority order; no subtitles are relayed when empty
+    #[serde(default)]
+    pub subtitle_languages: Vec<String>,
+    /// Forced subtitle track, by position among the input's subtitle tracks (0-based)
+    pub subtitle_track_index: Option<u32>,
+    /// How subtitle and teletext tracks are relayed
+    #[serde(default)]
+    pub subtitle_mode: SubtitleMode,
+    /// Target format when `subtitle_mode` is `Convert`
+    pub subtitle_codec: Option<SubtitleCodec>,
+
     // System flags
     pub is_system_default: bool,
     pub is_active: bool,
@@ -58,6 +76,21 @@ pub struct RelayProfile {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Rules for picking one input track of a type (audio or subtitles)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrackSelection {
+    /// Languages in priority order, compared case-insensitively with the track's language tag
+    pub languages: Vec<String>,
+    /// Track forced by its position among the input's tracks of this type; wins over `languages`
+    pub track_index: Option<u32>,
+}
+
+impl TrackSelection {
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty() && self.track_index.is_none()
+    }
+}
+
 /// Channel-specific relay configuration linking channels to profiles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelRelayConfig {
@@ -79,6 +112,8 @@ pub struct ResolvedRelayConfig {
     pub config: ChannelRelayConfig,
     pub profile: RelayProfile,
     pub effective_args: Vec<String>, // Resolved FFmpeg arguments
+    /// Headers the channel's source requires on upstream connections
+    pub upstream_headers: Option<crate::models::stream_headers::StreamHeaderOverrides>,
 }
 
 /// Relay event for tracking lifecycle and metrics
@@ -180,6 +215,57 @@ pub enum AudioCodec {
     Copy,       // Pass-through
 }
 
+/// How a relay handles the subtitle and teletext tracks of its input
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum SubtitleMode {
+    /// Copy the track picked by the subtitle selection; none when nothing is selected
+    #[default]
+    Selected,
+    /// Copy every subtitle and teletext track the output can carry
+    Preserve,
+    /// Drop all subtitle tracks
+    Strip,
+    /// Re-encode the selected (or first convertible) track to `subtitle_codec`
+    Convert,
+}
+
+/// Subtitle formats a relay can convert to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum SubtitleCodec {
+    /// Bitmap DVB subtitles, carried by MPEG-TS
+    DvbSubtitle,
+    /// WebVTT text cues, for HLS outputs
+    WebVtt,
+}
+
+impl SubtitleCodec {
+    /// FFmpeg encoder name
+    pub fn encoder(&self) -> &'static str {
+        match self {
+            SubtitleCodec::DvbSubtitle => "dvbsub",
+            SubtitleCodec::WebVtt => "webvtt",
+        }
+    }
+
+    /// Whether the format holds text rather than bitmaps
+    pub fn is_text(&self) -> bool {
+        matches!(self, SubtitleCodec::WebVtt)
+    }
+
+    /// Whether a track of `codec_name` can be converted to this format
+    ///
+    /// Teletext decodes to either text or bitmaps; otherwise text only converts to text
+    /// and bitmaps to bitmaps (there is no OCR).
+    pub fn can_convert_from(&self, codec_name: &str) -> bool {
+        match codec_name {
+            "dvb_teletext" => true,
+            "subrip" | "srt" | "webvtt" | "ass" | "ssa" | "mov_text" | "text" => self.is_text(),
+            "dvb_subtitle" | "hdmv_pgs_subtitle" | "dvd_subtitle" | "xsub" => !self.is_text(),
+            _ => false,
+        }
+    }
+}
+
 /// FFmpeg output format types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum RelayOutputFormat {
@@ -257,6 +343,54 @@ impl std::fmt::Display for RelayOutputFormat {
     }
 }
 
+impl std::fmt::Display for SubtitleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SubtitleMode::Selected => "selected",
+            SubtitleMode::Preserve => "preserve",
+            SubtitleMode::Strip => "strip",
+            SubtitleMode::Convert => "convert",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for SubtitleMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "selected" => Ok(SubtitleMode::Selected),
+            "preserve" => Ok(SubtitleMode::Preserve),
+            "strip" => Ok(SubtitleMode::Strip),
+            "convert" => Ok(SubtitleMode::Convert),
+            _ => Err(format!("Unknown subtitle mode: {s}")),
+        }
+    }
+}
+
+impl std::fmt::Display for SubtitleCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SubtitleCodec::DvbSubtitle => "dvb_subtitle",
+            SubtitleCodec::WebVtt => "webvtt",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for SubtitleCodec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dvb_subtitle" => Ok(SubtitleCodec::DvbSubtitle),
+            "webvtt" => Ok(SubtitleCodec::WebVtt),
+            _ => Err(format!("Unknown subtitle codec: {s}")),
+        }
+    }
+}
+
 impl FromStr for RelayOutputFormat {
     type Err = String;
 
@@ -304,6 +438,14 @@ pub struct CreateRelayProfileRequest {
     pub max_segments: Option<i32>,
     pub input_timeout: Option<i32>,
 
+    // Track selection
+    pub audio_languages: Option<Vec<String>>,
+    pub audio_track_index: Option<u32>,
+    pub subtitle_languages: Option<Vec<String>>,
+    pub subtitle_track_index: Option<u32>,
+    pub subtitle_mode: Option<SubtitleMode>,
+    pub subtitle_codec: Option<SubtitleCodec>,
+
     // System default flag (ignored by API handlers)
     pub is_system_default: Option<bool>,
 }
@@ -338,6 +480,14 @@ pub struct UpdateRelayProfileRequest {
     pub input_timeout: Option<i32>,
     pub is_active: Option<bool>,
 
+    // Track selection
+    pub audio_languages: Option<Vec<String>>,
+    pub audio_track_index: Option<u32>,
+    pub subtitle_languages: Option<Vec<String>>,
+    pub subtitle_track_index: Option<u32>,
+    pub subtitle_mode: Option<SubtitleMode>,
+    pub subtitle_codec: Option<SubtitleCodec>,
+
     // System default flag (ignored by API handlers)
     pub is_system_default: Option<bool>,
 }
@@ -429,9 +579,33 @@ pub struct RelayHealth {
     #[schema(example = "0")]
     pub unhealthy_processes: i32,
     pub processes: Vec<RelayProcessHealth>,
+    /// Transcode slot usage per encoding device
+    #[serde(default)]
+    pub transcode_devices: Vec<TranscodeDeviceUtilization>,
     pub last_check: DateTime<Utc>,
 }
 
+/// Transcode slot usage of one encoding device
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TranscodeDeviceUtilization {
+    /// Hardware accelerator ("vaapi", "nvenc", ...) or "cpu" for software transcodes
+    #[schema(example = "nvenc")]
+    pub device: String,
+    /// Configured slot limit; `None` when the device is unlimited
+    #[schema(example = 4)]
+    pub max_concurrent: Option<usize>,
+    /// Running transcodes
+    #[schema(example = 3)]
+    pub active: usize,
+    /// Relays waiting for a slot
+    #[schema(example = 0)]
+    pub queued: usize,
+    /// Relays started without transcoding because the device was saturated
+    pub passthrough_fallbacks: u64,
+    /// Relays refused because the device was saturated
+    pub rejected: u64,
+}
+
 /// Health status for individual relay process
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RelayProcessHealth {
@@ -492,6 +666,24 @@ pub struct ConnectedClient {
     pub last_activity: DateTime<Utc>,
 }
 
+/// Check that subtitle settings can be relayed into `output_format`
+pub fn validate_subtitle_settings(
+    output_format: &RelayOutputFormat,
+    mode: SubtitleMode,
+    codec: Option<SubtitleCodec>,
+) -> Result<(), String> {
+    if mode != SubtitleMode::Convert {
+        return Ok(());
+    }
+    match (output_format, codec) {
+        (_, None) => Err("subtitle_codec is required when subtitle_mode is Convert".to_string()),
+        (RelayOutputFormat::TransportStream, Some(codec)) if codec.is_text() => Err(format!(
+            "{codec} subtitles cannot be carried in transport stream output; use dvb_subtitle"
+        )),
+        _ => Ok(()),
+    }
+}
+
 impl RelayProfile {
     /// Create a new relay profile
     pub fn new(request: CreateRelayProfileRequest) -> Result<Self, String> {
@@ -501,6 +693,11 @@ impl RelayProfile {
         {
             Self::validate_ffmpeg_args(&args_vec)?;
         }
+        validate_subtitle_settings(
+            &request.output_format,
+            request.subtitle_mode.unwrap_or_default(),
+            request.subtitle_codec,
+        )?;
 
         Ok(Self {
             id: Uuid::new_v4(),
@@ -530,6 +727,14 @@ impl RelayProfile {
             max_segments: request.max_segments,
             input_timeout: request.input_timeout.unwrap_or(30),
 
+            // Track selection
+            audio_languages: request.audio_languages.unwrap_or_default(),
+            audio_track_index: request.audio_track_index,
+            subtitle_languages: request.subtitle_languages.unwrap_or_default(),
+            subtitle_track_index: request.subtitle_track_index,
+            subtitle_mode: request.subtitle_mode.unwrap_or_default(),
+            subtitle_codec: request.subtitle_codec,
+
             // System flags
             is_system_default: false,
             is_active: true,
@@ -587,6 +792,22 @@ impl RelayProfile {
         }
     }
 
+    /// Audio track selection rules
+    pub fn audio_selection(&self) -> TrackSelection {
+        TrackSelection {
+            languages: self.audio_languages.clone(),
+            track_index: self.audio_track_index,
+        }
+    }
+
+    /// Subtitle track selection rules
+    pub fn subtitle_selection(&self) -> TrackSelection {
+        TrackSelection {
+            languages: self.subtitle_languages.clone(),
+            track_index: self.subtitle_track_index,
+        }
+    }
+
     /// Get audio encoder name
     pub fn get_audio_encoder(&self) -> String {
         match self.audio_codec {
@@ -659,6 +880,7 @@ impl ResolvedRelayConfig {
             config,
             profile,
             effective_args,
+            upstream_headers: None,
         })
     }
 
@@ -1049,4 +1271,52 @@ pub enum RelayError {
 
     #[error("UUID error: {0}")]
     Uuid(#[from] uuid::Error),
+
+    #[error("No transcode slot available on {0}")]
+    TranscodeCapacity(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtitle_codec_conversion_sources() {
+        assert!(SubtitleCodec::DvbSubtitle.can_convert_from("dvb_teletext"));
+        assert!(SubtitleCodec::WebVtt.can_convert_from("dvb_teletext"));
+        assert!(SubtitleCodec::DvbSubtitle.can_convert_from("hdmv_pgs_subtitle"));
+        assert!(!SubtitleCodec::WebVtt.can_convert_from("dvb_subtitle"));
+        assert!(!SubtitleCodec::DvbSubtitle.can_convert_from("subrip"));
+    }
+
+    #[test]
+    fn test_validate_subtitle_settings() {
+        let ts = RelayOutputFormat::TransportStream;
+        assert!(validate_subtitle_settings(&ts, SubtitleMode::Strip, None).is_ok());
+        assert!(validate_subtitle_settings(&ts, SubtitleMode::Convert, None).is_err());
+        assert!(
+            validate_subtitle_settings(
+                &ts,
+                SubtitleMode::Convert,
+                Some(SubtitleCodec::DvbSubtitle)
+            )
+            .is_ok()
+        );
+        assert!(
+            validate_subtitle_settings(&ts, SubtitleMode::Convert, Some(SubtitleCodec::WebVtt))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_subtitle_mode_round_trip() {
+        for mode in [
+            SubtitleMode::Selected,
+            SubtitleMode::Preserve,
+            SubtitleMode::Strip,
+            SubtitleMode::Convert,
+        ] {
+            assert_eq!(SubtitleMode::from_str(&mode.to_string()), Ok(mode));
+        }
+    }
 }
@@ -0,0 +1,324 @@
+//! Filter and data mapping rule version models
+//!
+//! Every saved state of a filter or data mapping rule is kept as a numbered version, so a
+//! change that breaks a shared rule can be traced to its author, compared with earlier
+//! versions and reverted.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::data_mapping::{DataMappingRule, DataMappingRuleUpdateRequest};
+use crate::models::{Filter, FilterUpdateRequest};
+
+/// Kind of a versioned rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    Filter,
+    DataMappingRule,
+}
+
+impl RuleKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Filter => "filter",
+            Self::DataMappingRule => "data_mapping_rule",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "filter" => Some(Self::Filter),
+            "data_mapping_rule" => Some(Self::DataMappingRule),
+            _ => None,
+        }
+    }
+}
+
+/// How a version came about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleChange {
+    Created,
+    Updated,
+    Reverted,
+}
+
+impl RuleChange {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Reverted => "reverted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "created" => Some(Self::Created),
+            "updated" => Some(Self::Updated),
+            "reverted" => Some(Self::Reverted),
+            _ => None,
+        }
+    }
+}
+
+/// A saved state of a filter or data mapping rule
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RuleVersion {
+    pub id: Uuid,
+    pub rule_kind: RuleKind,
+    pub rule_id: Uuid,
+    /// Version number, counting from 1
+    pub version: i32,
+    pub name: String,
+    pub expression: Option<String>,
+    /// Expression of the version this one replaced
+    pub previous_expression: Option<String>,
+    /// The rule as saved, in the shape of its update request
+    #[schema(value_type = Object)]
+    pub definition: serde_json::Value,
+    pub change: RuleChange,
+    /// Version restored by a revert
+    pub restored_version: Option<i32>,
+    /// User reported by an authenticating reverse proxy, or the client address
+    pub author: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// State of a rule to record as a version
+#[derive(Debug, Clone)]
+pub struct RuleSnapshot {
+    pub name: String,
+    pub expression: Option<String>,
+    pub definition: serde_json::Value,
+    /// When the rule was saved in this state
+    pub saved_at: DateTime<Utc>,
+}
+
+impl From<&Filter> for RuleSnapshot {
+    fn from(filter: &Filter) -> Self {
+        let definition = FilterUpdateRequest {
+            name: filter.name.clone(),
+            source_type: filter.source_type.clone(),
+            is_inverse: filter.is_inverse,
+            expression: filter.expression.clone(),
+        };
+        Self {
+            name: filter.name.clone(),
+            expression: Some(filter.expression.clone()),
+            definition: serde_json::to_value(definition).unwrap_or_default(),
+            saved_at: filter.updated_at,
+        }
+    }
+}
+
+impl From<&DataMappingRule> for RuleSnapshot {
+    fn from(rule: &DataMappingRule) -> Self {
+        let definition = DataMappingRuleUpdateRequest {
+            name: Some(rule.name.clone()),
+            description: rule.description.clone(),
+            source_type: Some(rule.source_type.clone()),
+            expression: rule.expression.clone(),
+            is_active: Some(rule.is_active),
+            scope: Some(rule.scope.clone()),
+        };
+        Self {
+            name: rule.name.clone(),
+            expression: rule.expression.clone(),
+            definition: serde_json::to_value(definition).unwrap_or_default(),
+            saved_at: rule.updated_at,
+        }
+    }
+}
+
+/// A field whose value differs between two versions
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RuleFieldChange {
+    pub field: String,
+    #[schema(value_type = Object)]
+    pub from: serde_json::Value,
+    #[schema(value_type = Object)]
+    pub to: serde_json::Value,
+}
+
+/// Whether a piece of an expression diff is kept, removed or added
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Removed,
+    Added,
+}
+
+/// A run of expression tokens sharing a diff operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ExpressionDiffSegment {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Differences between two versions of a rule
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RuleVersionDiff {
+    pub rule_kind: RuleKind,
+    pub rule_id: Uuid,
+    pub from_version: i32,
+    pub to_version: i32,
+    /// Definition fields that differ, expression included
+    pub changes: Vec<RuleFieldChange>,
+    /// Word-level diff of the expressions
+    pub expression_diff: Vec<ExpressionDiffSegment>,
+}
+
+impl RuleVersionDiff {
+    pub fn between(from: &RuleVersion, to: &RuleVersion) -> Self {
+        let empty = serde_json::Map::new();
+        let from_fields = from.definition.as_object().unwrap_or(&empty);
+        let to_fields = to.definition.as_object().unwrap_or(&empty);
+
+        let mut fields: Vec<&String> = from_fields.keys().chain(to_fields.keys()).collect();
+        fields.sort();
+        fields.dedup();
+        let changes = fields
+            .into_iter()
+            .filter_map(|field| {
+                let from_value = from_fields.get(field).cloned().unwrap_or_default();
+                let to_value = to_fields.get(field).cloned().unwrap_or_default();
+                (from_value != to_value).then(|| RuleFieldChange {
+                    field: field.clone(),
+                    from: from_value,
+                    to: to_value,
+                })
+            })
+            .collect();
+
+        Self {
+            rule_kind: to.rule_kind,
+            rule_id: to.rule_id,
+            from_version: from.version,
+            to_version: to.version,
+            changes,
+            expression_diff: diff_expressions(
+                from.expression.as_deref().unwrap_or_default(),
+                to.expression.as_deref().unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// Word-level diff of two expressions, as runs of equal, removed and added words
+///
+/// Words are compared by their longest common subsequence, so a changed condition shows as
+/// its removed and added words amid the unchanged rest of the expression.
+pub fn diff_expressions(from: &str, to: &str) -> Vec<ExpressionDiffSegment> {
+    let from: Vec<&str> = from.split_whitespace().collect();
+    let to: Vec<&str> = to.split_whitespace().collect();
+
+    // lcs[i][j]: length of the longest common subsequence of from[i..] and to[j..]
+    let mut lcs = vec![vec![0usize; to.len() + 1]; from.len() + 1];
+    for i in (0..from.len()).rev() {
+        for j in (0..to.len()).rev() {
+            lcs[i][j] = if from[i] == to[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut segments: Vec<ExpressionDiffSegment> = Vec::new();
+    let mut push = |op: DiffOp, word: &str| match segments.last_mut() {
+        Some(last) if last.op == op => {
+            last.text.push(' ');
+            last.text.push_str(word);
+        }
+        _ => segments.push(ExpressionDiffSegment {
+            op,
+            text: word.to_string(),
+        }),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < from.len() || j < to.len() {
+        if i < from.len() && j < to.len() && from[i] == to[j] {
+            push(DiffOp::Equal, from[i]);
+            i += 1;
+            j += 1;
+        } else if j < to.len() && (i == from.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            push(DiffOp::Added, to[j]);
+            j += 1;
+        } else {
+            push(DiffOp::Removed, from[i]);
+            i += 1;
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(op: DiffOp, text: &str) -> ExpressionDiffSegment {
+        ExpressionDiffSegment {
+            op,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_expressions() {
+        assert_eq!(
+            diff_expressions(
+                "group_title contains \"UK\" AND channel_name contains \"HD\"",
+                "group_title contains \"UK\" AND channel_name not_contains \"SD\""
+            ),
+            [
+                segment(
+                    DiffOp::Equal,
+                    "group_title contains \"UK\" AND channel_name"
+                ),
+                segment(DiffOp::Added, "not_contains \"SD\""),
+                segment(DiffOp::Removed, "contains \"HD\""),
+            ]
+        );
+        assert_eq!(
+            diff_expressions("", "channel_name equals \"BBC\""),
+            [segment(DiffOp::Added, "channel_name equals \"BBC\"")]
+        );
+        assert!(diff_expressions("", "").is_empty());
+    }
+
+    #[test]
+    fn test_version_diff_lists_changed_fields() {
+        let version = |version: i32, expression: &str, is_inverse: bool| RuleVersion {
+            id: Uuid::new_v4(),
+            rule_kind: RuleKind::Filter,
+            rule_id: Uuid::nil(),
+            version,
+            name: "UK".to_string(),
+            expression: Some(expression.to_string()),
+            previous_expression: None,
+            definition: serde_json::json!({
+                "name": "UK",
+                "source_type": "stream",
+                "is_inverse": is_inverse,
+                "expression": expression,
+            }),
+            change: RuleChange::Updated,
+            restored_version: None,
+            author: None,
+            created_at: Utc::now(),
+        };
+
+        let diff = RuleVersionDiff::between(
+            &version(1, "group_title contains \"UK\"", false),
+            &version(2, "group_title contains \"GB\"", true),
+        );
+        let fields: Vec<_> = diff.changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, ["expression", "is_inverse"]);
+        assert_eq!((diff.from_version, diff.to_version), (1, 2));
+    }
+}
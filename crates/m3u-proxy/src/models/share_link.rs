@@ -0,0 +1,104 @@
+//! Proxy share link models
+//!
+//! Share links grant temporary access to a proxy's playlist, guide and streams through a
+//! random token, without exposing the proxy's own URLs.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A temporary, revocable share link for a proxy
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProxyShareLink {
+    pub id: Uuid,
+    pub proxy_id: Uuid,
+    pub token: String,
+    pub name: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    /// Maximum number of playlist fetches (unlimited when absent)
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    /// Maximum concurrent streams through the link (configured default when absent)
+    pub max_streams: Option<i32>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Current state of a share link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareLinkStatus {
+    Active,
+    Expired,
+    Exhausted,
+    Revoked,
+}
+
+impl ProxyShareLink {
+    /// Status of the link at the given time
+    pub fn status_at(&self, now: DateTime<Utc>) -> ShareLinkStatus {
+        if self.revoked_at.is_some() {
+            ShareLinkStatus::Revoked
+        } else if now >= self.expires_at {
+            ShareLinkStatus::Expired
+        } else if self.max_uses.is_some_and(|max| self.use_count >= max) {
+            ShareLinkStatus::Exhausted
+        } else {
+            ShareLinkStatus::Active
+        }
+    }
+}
+
+/// Request to create a share link
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateShareLinkRequest {
+    /// Optional label (e.g. who the link was given to)
+    pub name: Option<String>,
+    /// How long the link stays valid (e.g. "72h", "7d"); defaults to 72h
+    #[schema(example = "72h")]
+    pub valid_for: Option<String>,
+    /// Maximum number of playlist fetches (unlimited when absent)
+    pub max_uses: Option<i32>,
+    /// Maximum concurrent streams through the link (configured default when absent)
+    pub max_streams: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(expires_in_hours: i64) -> ProxyShareLink {
+        let now = Utc::now();
+        ProxyShareLink {
+            id: Uuid::new_v4(),
+            proxy_id: Uuid::new_v4(),
+            token: "token".to_string(),
+            name: None,
+            expires_at: now + chrono::Duration::hours(expires_in_hours),
+            max_uses: None,
+            use_count: 0,
+            max_streams: None,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: now,
+        }
+    }
+
+    #[test]
+    fn test_share_link_status() {
+        let now = Utc::now();
+        assert_eq!(link(1).status_at(now), ShareLinkStatus::Active);
+        assert_eq!(link(-1).status_at(now), ShareLinkStatus::Expired);
+
+        let mut exhausted = link(1);
+        exhausted.max_uses = Some(2);
+        exhausted.use_count = 2;
+        assert_eq!(exhausted.status_at(now), ShareLinkStatus::Exhausted);
+
+        let mut revoked = link(-1);
+        revoked.revoked_at = Some(now);
+        assert_eq!(revoked.status_at(now), ShareLinkStatus::Revoked);
+    }
+}
@@ -0,0 +1,169 @@
+//! Stream source load balancing models
+//!
+//! A provider subscription bought several times ("lines") is configured as one stream
+//! source per set of credentials. Declaring those sources a balance group lets streaming
+//! spread viewers over the lines: a channel is served from the line with the fewest active
+//! connections that is still under its `max_concurrent_streams`, and the next line is
+//! tried when the chosen upstream cannot be reached.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Maximum length of a balance group name
+pub const MAX_BALANCE_GROUP_NAME_LEN: usize = 64;
+
+/// Balance group membership of a stream source
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceBalanceGroup {
+    pub source_id: Uuid,
+    /// Group the source is a line of (`None` when it is not balanced)
+    pub group_name: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl SourceBalanceGroup {
+    /// Membership of a source that is not part of any group
+    pub fn ungrouped(source_id: Uuid) -> Self {
+        Self {
+            source_id,
+            group_name: None,
+            updated_at: None,
+        }
+    }
+}
+
+/// Request to put a stream source into a balance group
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SourceBalanceGroupRequest {
+    /// Group name shared by all lines of the provider; empty or null removes the source
+    /// from its group
+    #[schema(example = "provider-x")]
+    pub group_name: Option<String>,
+}
+
+impl SourceBalanceGroupRequest {
+    /// Trimmed group name, `None` when the source should leave its group
+    pub fn group_name(&self) -> Option<&str> {
+        self.group_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(name) = self.group_name()
+            && name.chars().count() > MAX_BALANCE_GROUP_NAME_LEN
+        {
+            return Err(format!(
+                "group_name must be at most {MAX_BALANCE_GROUP_NAME_LEN} characters"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A line of a balance group and its current load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalancedLine {
+    pub source_id: Uuid,
+    /// Connection limit of the line (0 or less is unlimited)
+    pub max_concurrent_streams: i32,
+    pub active_streams: usize,
+}
+
+impl BalancedLine {
+    pub fn has_capacity(&self) -> bool {
+        self.max_concurrent_streams <= 0
+            || self.active_streams < self.max_concurrent_streams as usize
+    }
+}
+
+/// Lines with spare capacity, in the order they should be tried
+///
+/// The least loaded line comes first; on a tie the requested line wins, so an idle group
+/// keeps serving channels from the source the playlist listed them under.
+pub fn rank_lines(lines: &[BalancedLine], requested: Uuid) -> Vec<Uuid> {
+    let mut available: Vec<&BalancedLine> =
+        lines.iter().filter(|line| line.has_capacity()).collect();
+    available.sort_by_key(|line| {
+        (
+            line.active_streams,
+            line.source_id != requested,
+            line.source_id,
+        )
+    });
+    available.into_iter().map(|line| line.source_id).collect()
+}
+
+/// A line of a balance group as reported by the API
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BalanceLineStatus {
+    pub source_id: Uuid,
+    pub source_name: String,
+    pub is_active: bool,
+    pub max_concurrent_streams: i32,
+    /// Streams currently served from this line
+    pub active_streams: usize,
+}
+
+/// A balance group and the load on each of its lines
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BalanceGroupStatus {
+    pub group_name: String,
+    pub lines: Vec<BalanceLineStatus>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(id: u128, max: i32, active: usize) -> BalancedLine {
+        BalancedLine {
+            source_id: Uuid::from_u128(id),
+            max_concurrent_streams: max,
+            active_streams: active,
+        }
+    }
+
+    #[test]
+    fn test_least_loaded_line_first() {
+        let lines = [line(1, 2, 1), line(2, 2, 0), line(3, 0, 5)];
+        assert_eq!(
+            rank_lines(&lines, Uuid::from_u128(1)),
+            vec![Uuid::from_u128(2), Uuid::from_u128(1), Uuid::from_u128(3)]
+        );
+    }
+
+    #[test]
+    fn test_full_lines_are_skipped() {
+        let lines = [line(1, 1, 1), line(2, 3, 3)];
+        assert!(rank_lines(&lines, Uuid::from_u128(1)).is_empty());
+        // Unlimited lines never fill up
+        assert_eq!(
+            rank_lines(&[line(1, 1, 1), line(2, 0, 9)], Uuid::from_u128(1)),
+            vec![Uuid::from_u128(2)]
+        );
+    }
+
+    #[test]
+    fn test_requested_line_wins_ties() {
+        let lines = [line(1, 2, 0), line(2, 2, 0)];
+        assert_eq!(
+            rank_lines(&lines, Uuid::from_u128(2)),
+            vec![Uuid::from_u128(2), Uuid::from_u128(1)]
+        );
+    }
+
+    #[test]
+    fn test_group_name_validation() {
+        let request = |name: Option<&str>| SourceBalanceGroupRequest {
+            group_name: name.map(str::to_string),
+        };
+        assert_eq!(request(Some("  lines ")).group_name(), Some("lines"));
+        assert_eq!(request(Some("   ")).group_name(), None);
+        assert!(request(None).validate().is_ok());
+        assert!(request(Some(&"x".repeat(65))).validate().is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Upstream streaming header models
+//!
+//! Some providers reject stream requests that lack a particular User-Agent or Referer,
+//! even when ingestion succeeds. A stream source's header overrides are sent on every
+//! upstream connection made for its channels: proxied passthrough, stream classification,
+//! collapsed HLS sessions and the relay's ffmpeg input.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Most extra headers a source may define
+pub const MAX_EXTRA_HEADERS: usize = 32;
+
+/// Headers managed by the proxy itself that cannot be overridden
+const RESERVED_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "transfer-encoding",
+    "connection",
+    "range",
+    "m3u-proxy-version",
+];
+
+/// Streaming header overrides of a stream source
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StreamHeaderOverrides {
+    pub source_id: Uuid,
+    /// User-Agent sent upstream instead of the proxy's own
+    pub user_agent: Option<String>,
+    /// Referer sent upstream
+    pub referer: Option<String>,
+    /// Additional headers sent upstream, by name
+    pub headers: BTreeMap<String, String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl StreamHeaderOverrides {
+    /// Overrides of a source without configured headers
+    pub fn none(source_id: Uuid) -> Self {
+        Self {
+            source_id,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.user_agent.is_none() && self.referer.is_none() && self.headers.is_empty()
+    }
+
+    /// Referer and extra headers as name/value pairs (the User-Agent is applied separately)
+    pub fn header_pairs(&self) -> Vec<(&str, &str)> {
+        self.referer
+            .as_deref()
+            .map(|referer| ("Referer", referer))
+            .into_iter()
+            .chain(
+                self.headers
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str())),
+            )
+            .collect()
+    }
+}
+
+/// Request to set a stream source's streaming header overrides
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct StreamHeaderOverridesRequest {
+    #[schema(example = "Mozilla/5.0 (SMART-TV; Linux; Tizen 6.0)")]
+    pub user_agent: Option<String>,
+    #[schema(example = "https://provider.example.com/")]
+    pub referer: Option<String>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl StreamHeaderOverridesRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        for (field, value) in [("user_agent", &self.user_agent), ("referer", &self.referer)] {
+            if let Some(value) = value {
+                validate_header_value(field, value)?;
+            }
+        }
+        if self.headers.len() > MAX_EXTRA_HEADERS {
+            return Err(format!(
+                "At most {MAX_EXTRA_HEADERS} extra headers may be configured"
+            ));
+        }
+        for (name, value) in &self.headers {
+            if name.is_empty()
+                || !name
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+            {
+                return Err(format!("Invalid header name '{name}'"));
+            }
+            let lower = name.to_ascii_lowercase();
+            if RESERVED_HEADERS.contains(&lower.as_str()) {
+                return Err(format!("Header '{name}' cannot be overridden"));
+            }
+            if lower == "user-agent" || lower == "referer" {
+                return Err(format!(
+                    "Set '{name}' with the {} field",
+                    lower.replace('-', "_")
+                ));
+            }
+            validate_header_value(name, value)?;
+        }
+        Ok(())
+    }
+
+    /// Blank user agent and referer count as unset
+    pub fn normalized(self) -> Self {
+        let trim = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            user_agent: trim(self.user_agent),
+            referer: trim(self.referer),
+            headers: self.headers,
+        }
+    }
+}
+
+fn validate_header_value(name: &str, value: &str) -> Result<(), String> {
+    if value.bytes().any(|b| b == b'\r' || b == b'\n' || b == 0) {
+        return Err(format!("Value of '{name}' must not contain line breaks"));
+    }
+    if value.len() > 4096 {
+        return Err(format!("Value of '{name}' is longer than 4096 bytes"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&str, &str)]) -> StreamHeaderOverridesRequest {
+        StreamHeaderOverridesRequest {
+            headers: headers
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            request(&[("X-Token", "abc"), ("Origin", "https://a.example")])
+                .validate()
+                .is_ok()
+        );
+        assert!(request(&[("Bad Name", "x")]).validate().is_err());
+        assert!(request(&[("Host", "x")]).validate().is_err());
+        assert!(request(&[("User-Agent", "x")]).validate().is_err());
+        assert!(
+            request(&[("X-Token", "a\r\nInjected: 1")])
+                .validate()
+                .is_err()
+        );
+
+        let with_ua = StreamHeaderOverridesRequest {
+            user_agent: Some("VLC\n".to_string()),
+            ..Default::default()
+        };
+        assert!(with_ua.validate().is_err());
+    }
+
+    #[test]
+    fn test_header_pairs_include_referer() {
+        let overrides = StreamHeaderOverrides {
+            referer: Some("https://a.example/".to_string()),
+            headers: BTreeMap::from([("X-Token".to_string(), "abc".to_string())]),
+            ..StreamHeaderOverrides::none(Uuid::nil())
+        };
+        assert_eq!(
+            overrides.header_pairs(),
+            vec![("Referer", "https://a.example/"), ("X-Token", "abc")]
+        );
+        assert!(StreamHeaderOverrides::none(Uuid::nil()).is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+//! Stream source mirror models
+//!
+//! Providers often host the same playlist or Xtream API behind several hosts. A source's
+//! mirrors are tried in order when its own URL fails during a refresh, and the URL that
+//! served the last successful refresh is recorded.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Most mirrors a source may have
+pub const MAX_MIRRORS: usize = 10;
+
+/// Mirror URLs of a stream source
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StreamSourceMirrors {
+    pub source_id: Uuid,
+    /// Alternative URLs serving the same content, tried in order after the source URL
+    pub mirror_urls: Vec<String>,
+    /// URL that served the last successful refresh
+    pub last_served_url: Option<String>,
+    pub last_served_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl StreamSourceMirrors {
+    /// Mirrors of a source without configured mirror URLs
+    pub fn none(source_id: Uuid) -> Self {
+        Self {
+            source_id,
+            ..Default::default()
+        }
+    }
+
+    /// URLs to fetch a source from, its own URL first, without repeats
+    pub fn candidate_urls(&self, source_url: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        std::iter::once(source_url)
+            .chain(self.mirror_urls.iter().map(String::as_str))
+            .filter(|url| seen.insert(*url))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Request to set a stream source's mirror URLs
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct StreamSourceMirrorsRequest {
+    #[serde(default)]
+    #[schema(example = json!(["http://backup.provider.example/get.php?username=user&password=pass&type=m3u_plus"]))]
+    pub mirror_urls: Vec<String>,
+}
+
+impl StreamSourceMirrorsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.mirror_urls.len() > MAX_MIRRORS {
+            return Err(format!("A source may have at most {MAX_MIRRORS} mirrors"));
+        }
+        for mirror_url in &self.mirror_urls {
+            let parsed = url::Url::parse(mirror_url)
+                .map_err(|e| format!("Invalid mirror URL '{mirror_url}': {e}"))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(format!("Mirror URL '{mirror_url}' must use http or https"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop blank and duplicate URLs, keeping the first occurrence
+    pub fn normalized(self) -> Self {
+        let mut seen = HashSet::new();
+        Self {
+            mirror_urls: self
+                .mirror_urls
+                .into_iter()
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty() && seen.insert(url.clone()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_normalize_and_validate() {
+        let request = StreamSourceMirrorsRequest {
+            mirror_urls: vec![
+                " http://a.example/list.m3u ".to_string(),
+                String::new(),
+                "http://a.example/list.m3u".to_string(),
+                "https://b.example/list.m3u".to_string(),
+            ],
+        }
+        .normalized();
+        assert_eq!(
+            request.mirror_urls,
+            ["http://a.example/list.m3u", "https://b.example/list.m3u"]
+        );
+        assert!(request.validate().is_ok());
+
+        let invalid = StreamSourceMirrorsRequest {
+            mirror_urls: vec!["ftp://a.example/list.m3u".to_string()],
+        };
+        assert!(invalid.validate().is_err());
+        let too_many = StreamSourceMirrorsRequest {
+            mirror_urls: (0..=MAX_MIRRORS)
+                .map(|i| format!("http://{i}.example/"))
+                .collect(),
+        };
+        assert!(too_many.validate().is_err());
+    }
+
+    #[test]
+    fn test_candidate_urls_start_with_source_url() {
+        let mirrors = StreamSourceMirrors {
+            mirror_urls: vec![
+                "http://b.example/".to_string(),
+                "http://a.example/".to_string(),
+            ],
+            ..StreamSourceMirrors::none(Uuid::nil())
+        };
+        assert_eq!(
+            mirrors.candidate_urls("http://a.example/"),
+            ["http://a.example/", "http://b.example/"]
+        );
+        assert_eq!(
+            StreamSourceMirrors::none(Uuid::nil()).candidate_urls("http://a.example/"),
+            ["http://a.example/"]
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Trash models
+//!
+//! Deleting a stream source, EPG source, proxy, filter or data mapping rule only marks it
+//! deleted (`deleted_at`). Trashed items are hidden everywhere else, can be restored until
+//! the retention period ends, and are then purged for good by a scheduled job.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Kind of a trashed item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrashKind {
+    StreamSource,
+    EpgSource,
+    Proxy,
+    Filter,
+    DataMappingRule,
+}
+
+impl TrashKind {
+    pub const ALL: [TrashKind; 5] = [
+        TrashKind::StreamSource,
+        TrashKind::EpgSource,
+        TrashKind::Proxy,
+        TrashKind::Filter,
+        TrashKind::DataMappingRule,
+    ];
+}
+
+/// An item in the trash
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TrashItem {
+    pub kind: TrashKind,
+    pub id: Uuid,
+    pub name: String,
+    pub deleted_at: DateTime<Utc>,
+    /// When the purge job will remove the item permanently
+    pub purge_at: DateTime<Utc>,
+}
+
+impl TrashItem {
+    pub fn new(
+        kind: TrashKind,
+        id: Uuid,
+        name: String,
+        deleted_at: DateTime<Utc>,
+        retention: Duration,
+    ) -> Self {
+        Self {
+            kind,
+            id,
+            name,
+            deleted_at,
+            purge_at: deleted_at + retention,
+        }
+    }
+}
+
+/// Trashed items removed by a purge, per kind
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct TrashPurgeSummary {
+    pub stream_sources: u64,
+    pub epg_sources: u64,
+    pub proxies: u64,
+    pub filters: u64,
+    pub data_mapping_rules: u64,
+}
+
+impl TrashPurgeSummary {
+    pub fn record(&mut self, kind: TrashKind, purged: u64) {
+        match kind {
+            TrashKind::StreamSource => self.stream_sources += purged,
+            TrashKind::EpgSource => self.epg_sources += purged,
+            TrashKind::Proxy => self.proxies += purged,
+            TrashKind::Filter => self.filters += purged,
+            TrashKind::DataMappingRule => self.data_mapping_rules += purged,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.stream_sources
+            + self.epg_sources
+            + self.proxies
+            + self.filters
+            + self.data_mapping_rules
+    }
+}
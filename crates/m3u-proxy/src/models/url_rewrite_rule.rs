@@ -0,0 +1,315 @@
+//! URL rewrite rule models
+//!
+//! Some providers need their stream URLs adjusted before they play: a different port, a token
+//! in the query string, or https instead of http. A rewrite rule replaces the first match of a
+//! regex in a channel's upstream stream URL, with `$1` / `${name}` capture group references in
+//! the replacement. Rules are applied when a proxy is generated and again when a stream is
+//! served, so redirects, proxied streams and relays all use the rewritten URL.
+
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Channel;
+
+/// Longest pattern a rule may use
+pub const MAX_PATTERN_LENGTH: usize = 1024;
+
+/// Longest replacement a rule may use
+pub const MAX_REPLACEMENT_LENGTH: usize = 2048;
+
+/// Compiled size limit of a rule's regex, keeping pathological patterns out of the stream path
+const MAX_COMPILED_REGEX_SIZE: usize = 1 << 20;
+
+/// Schemes a rewritten stream URL may use; rewrites to anything else are not applied
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "rtmp", "rtmps", "rtsp", "rtp", "udp"];
+
+/// A regex rewrite of upstream stream URLs
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UrlRewriteRule {
+    pub id: Uuid,
+    pub name: String,
+    /// Regex matched against the stream URL
+    #[schema(example = r"^http://(provider\.example\.com):8080/")]
+    pub pattern: String,
+    /// Replacement for the first match, with `$1` / `${name}` capture group references
+    #[schema(example = "https://$1/")]
+    pub replacement: String,
+    /// Only rewrite channels of this stream source (all sources when unset)
+    pub source_id: Option<Uuid>,
+    /// Only rewrite streams of this proxy (all proxies when unset)
+    pub proxy_id: Option<Uuid>,
+    /// Rules are applied in ascending priority, each to the previous rule's output
+    pub priority: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to create or replace a URL rewrite rule
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UrlRewriteRuleRequest {
+    pub name: String,
+    pub pattern: String,
+    pub replacement: String,
+    pub source_id: Option<Uuid>,
+    pub proxy_id: Option<Uuid>,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default = "default_is_active")]
+    pub is_active: bool,
+}
+
+fn default_is_active() -> bool {
+    true
+}
+
+impl UrlRewriteRuleRequest {
+    /// Validate user input, returning a message suitable for a 400 response
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("URL rewrite rule name is required".to_string());
+        }
+        if self.pattern.is_empty() {
+            return Err("URL rewrite pattern is required".to_string());
+        }
+        if self.pattern.len() > MAX_PATTERN_LENGTH {
+            return Err(format!(
+                "URL rewrite pattern is longer than {MAX_PATTERN_LENGTH} bytes"
+            ));
+        }
+        if self.replacement.len() > MAX_REPLACEMENT_LENGTH {
+            return Err(format!(
+                "URL rewrite replacement is longer than {MAX_REPLACEMENT_LENGTH} bytes"
+            ));
+        }
+        if self
+            .replacement
+            .chars()
+            .any(|c| c.is_control() || c.is_whitespace())
+        {
+            return Err("URL rewrite replacement must not contain whitespace".to_string());
+        }
+
+        let regex = compile_pattern(&self.pattern)?;
+        for group in replacement_groups(&self.replacement) {
+            let known = match group.parse::<usize>() {
+                Ok(index) => index < regex.captures_len(),
+                Err(_) => regex.capture_names().flatten().any(|name| name == group),
+            };
+            if !known {
+                return Err(format!(
+                    "Replacement refers to capture group '{group}', which the pattern does not define (write ${{1}}x rather than $1x when a group is followed by text)"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compile a rule pattern within the size limit
+fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .size_limit(MAX_COMPILED_REGEX_SIZE)
+        .build()
+        .map_err(|e| format!("Invalid URL rewrite pattern: {e}"))
+}
+
+/// Capture groups a replacement refers to, as the regex crate reads `$name` and `${name}`
+fn replacement_groups(replacement: &str) -> Vec<&str> {
+    let mut groups = Vec::new();
+    let mut rest = replacement;
+    while let Some(dollar) = rest.find('$') {
+        rest = &rest[dollar + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            rest = after;
+        } else if let Some(braced) = rest.strip_prefix('{')
+            && let Some(end) = braced.find('}')
+        {
+            groups.push(&braced[..end]);
+            rest = &braced[end + 1..];
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            if end > 0 {
+                groups.push(&rest[..end]);
+            }
+            rest = &rest[end..];
+        }
+    }
+    groups
+}
+
+/// Whether a rewritten URL is still a stream URL the proxy may connect to
+fn is_allowed_stream_url(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|parsed| {
+        ALLOWED_SCHEMES.contains(&parsed.scheme()) && parsed.host_str().is_some()
+    })
+}
+
+/// A rule compiled for application
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    name: String,
+    regex: Regex,
+    replacement: String,
+    source_id: Option<Uuid>,
+}
+
+/// Active rewrite rules of a proxy, compiled and in application order
+#[derive(Debug, Clone, Default)]
+pub struct UrlRewriteRuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl UrlRewriteRuleSet {
+    /// Compile rules already in application order; rules that no longer compile are skipped
+    pub fn new(rules: &[UrlRewriteRule]) -> Self {
+        Self {
+            rules: rules
+                .iter()
+                .filter(|rule| rule.is_active)
+                .filter_map(|rule| match compile_pattern(&rule.pattern) {
+                    Ok(regex) => Some(CompiledRule {
+                        name: rule.name.clone(),
+                        regex,
+                        replacement: rule.replacement.clone(),
+                        source_id: rule.source_id,
+                    }),
+                    Err(e) => {
+                        warn!("Skipping URL rewrite rule '{}': {}", rule.name, e);
+                        None
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Stream URL of a channel of `source_id` after all matching rules, if any rule changed it
+    ///
+    /// A rule whose output is not a network URL (e.g. `file://`) is not applied.
+    pub fn rewrite(&self, url: &str, source_id: Uuid) -> Option<String> {
+        let mut current = url.to_string();
+        for rule in &self.rules {
+            if rule.source_id.is_some_and(|id| id != source_id) {
+                continue;
+            }
+            let rewritten = rule.regex.replace(&current, rule.replacement.as_str());
+            if rewritten == current {
+                continue;
+            }
+            if !is_allowed_stream_url(&rewritten) {
+                warn!(
+                    "URL rewrite rule '{}' produced an invalid stream URL, not applied",
+                    rule.name
+                );
+                continue;
+            }
+            current = rewritten.into_owned();
+        }
+        (current != url).then_some(current)
+    }
+
+    /// Rewrite a channel's stream URL; returns whether it changed
+    pub fn apply(&self, channel: &mut Channel) -> bool {
+        match self.rewrite(&channel.stream_url, channel.source_id) {
+            Some(url) => {
+                channel.stream_url = url;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(pattern: &str, replacement: &str) -> UrlRewriteRuleRequest {
+        UrlRewriteRuleRequest {
+            name: "Rewrite".to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            source_id: None,
+            proxy_id: None,
+            priority: 0,
+            is_active: true,
+        }
+    }
+
+    fn rule(pattern: &str, replacement: &str, source_id: Option<Uuid>) -> UrlRewriteRule {
+        UrlRewriteRule {
+            id: Uuid::new_v4(),
+            name: pattern.to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            source_id,
+            proxy_id: None,
+            priority: 0,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            request(r"^http://(?P<host>[^/:]+):8080/", "https://${host}/")
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            request(r"^http://([^/]+)/", "https://$1/")
+                .validate()
+                .is_ok()
+        );
+        assert!(request("(", "x").validate().is_err());
+        assert!(request("^http://", "https://$2").validate().is_err());
+        // `$1x` names a group "1x", not group 1 followed by "x"
+        assert!(request(r"^http://(a)", "$1x").validate().is_err());
+        assert!(request(r"^http://(a)", "${1}x$$").validate().is_ok());
+        assert!(
+            request("token=old", "token=new\r\nX: 1")
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rewrite_chains_rules_in_order() {
+        let source = Uuid::new_v4();
+        let set = UrlRewriteRuleSet::new(&[
+            rule(r":8080/", ":80/", None),
+            rule(r"^http://", "https://", Some(source)),
+            rule(r"$", "?token=abc", Some(Uuid::new_v4())),
+        ]);
+
+        assert_eq!(
+            set.rewrite("http://a.example:8080/live/1.ts", source)
+                .as_deref(),
+            Some("https://a.example:80/live/1.ts")
+        );
+        assert_eq!(
+            set.rewrite("http://a.example:8080/live/1.ts", Uuid::new_v4())
+                .as_deref(),
+            Some("http://a.example:80/live/1.ts")
+        );
+        assert_eq!(set.rewrite("http://b.example/live/1.ts", Uuid::nil()), None);
+    }
+
+    #[test]
+    fn test_rewrite_rejects_non_stream_urls() {
+        let set = UrlRewriteRuleSet::new(&[rule("^https?://[^/]+/", "file:///etc/", None)]);
+        assert_eq!(set.rewrite("http://a.example/passwd", Uuid::nil()), None);
+    }
+}
@@ -0,0 +1,109 @@
+//! Virtual channel models
+//!
+//! Virtual channels are user-defined channels (weather cams, local tuners, "channel offline"
+//! loops) that do not come from a stream source. They are injected into their assigned proxies
+//! during generation and can optionally be served through a relay profile.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Channel;
+
+/// Source ID given to virtual channels when they are turned into pipeline channels
+pub const VIRTUAL_CHANNEL_SOURCE_ID: Uuid = Uuid::nil();
+
+/// A user-defined channel injected into selected proxies
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VirtualChannel {
+    pub id: Uuid,
+    pub name: String,
+    pub stream_url: String,
+    pub tvg_id: Option<String>,
+    pub tvg_logo: Option<String>,
+    /// Group the channel is placed in (appended after the group's last channel)
+    pub group_title: Option<String>,
+    /// Channel number to claim; ingested channels using it are renumbered
+    pub channel_number: Option<i32>,
+    /// Relay profile used to serve the channel regardless of the proxy's mode
+    pub relay_profile_id: Option<Uuid>,
+    pub is_active: bool,
+    /// Proxies the channel is injected into
+    pub proxy_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl VirtualChannel {
+    /// Pipeline channel representing this virtual channel
+    pub fn to_channel(&self) -> Channel {
+        Channel {
+            id: self.id,
+            source_id: VIRTUAL_CHANNEL_SOURCE_ID,
+            tvg_id: self.tvg_id.clone(),
+            tvg_name: Some(self.name.clone()),
+            tvg_chno: self.channel_number.map(|n| n.to_string()),
+            tvg_logo: self.tvg_logo.clone(),
+            tvg_shift: None,
+            epg_shift: None,
+            group_title: self.group_title.clone(),
+            channel_name: self.name.clone(),
+            stream_url: self.stream_url.clone(),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            extra_attributes: Default::default(),
+        }
+    }
+}
+
+/// Whether a pipeline channel was created from a virtual channel
+pub fn is_virtual_channel(channel: &Channel) -> bool {
+    channel.source_id == VIRTUAL_CHANNEL_SOURCE_ID
+}
+
+/// Request to create or replace a virtual channel
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct VirtualChannelRequest {
+    pub name: String,
+    pub stream_url: String,
+    pub tvg_id: Option<String>,
+    pub tvg_logo: Option<String>,
+    pub group_title: Option<String>,
+    pub channel_number: Option<i32>,
+    pub relay_profile_id: Option<Uuid>,
+    #[serde(default = "default_is_active")]
+    pub is_active: bool,
+    /// Proxies to inject the channel into
+    #[serde(default)]
+    pub proxy_ids: Vec<Uuid>,
+}
+
+fn default_is_active() -> bool {
+    true
+}
+
+impl VirtualChannelRequest {
+    /// Validate user input, returning a message suitable for a 400 response
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Virtual channel name is required".to_string());
+        }
+        let url = self.stream_url.trim();
+        if url.is_empty() {
+            return Err("Virtual channel stream URL is required".to_string());
+        }
+        if url::Url::parse(url).is_err() {
+            return Err(format!("Invalid stream URL: {url}"));
+        }
+        if self.channel_number.is_some_and(|n| n <= 0) {
+            return Err("Channel number must be positive".to_string());
+        }
+        Ok(())
+    }
+}
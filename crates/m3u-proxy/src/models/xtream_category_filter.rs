@@ -0,0 +1,248 @@
+//! Xtream category ingest filter models
+//!
+//! Large Xtream providers expose far more live streams than anyone watches. A source's
+//! category filter is applied while its channels are ingested, so streams from unwanted
+//! categories never reach the database. Categories are matched by provider category id or
+//! by a case-insensitive pattern on the category name.
+
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Most category ids or patterns a single rule list may hold
+pub const MAX_RULES: usize = 1000;
+
+/// Category ingest filter of an Xtream stream source
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct XtreamCategoryFilter {
+    pub source_id: Uuid,
+    /// Only ingest these categories (all categories when both include lists are empty)
+    pub include_category_ids: Vec<i32>,
+    /// Only ingest categories whose name matches one of these patterns
+    pub include_patterns: Vec<String>,
+    /// Never ingest these categories
+    pub exclude_category_ids: Vec<i32>,
+    /// Never ingest categories whose name matches one of these patterns
+    pub exclude_patterns: Vec<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl XtreamCategoryFilter {
+    /// Filter of a source without configured rules
+    pub fn none(source_id: Uuid) -> Self {
+        Self {
+            source_id,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include_category_ids.is_empty()
+            && self.include_patterns.is_empty()
+            && self.exclude_category_ids.is_empty()
+            && self.exclude_patterns.is_empty()
+    }
+
+    /// Compile the patterns for matching
+    pub fn compile(&self) -> Result<CompiledCategoryFilter, String> {
+        Ok(CompiledCategoryFilter {
+            include_ids: self.include_category_ids.iter().copied().collect(),
+            include_patterns: compile_patterns(&self.include_patterns)?,
+            exclude_ids: self.exclude_category_ids.iter().copied().collect(),
+            exclude_patterns: compile_patterns(&self.exclude_patterns)?,
+        })
+    }
+}
+
+/// Request to set an Xtream source's category ingest filter
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct XtreamCategoryFilterRequest {
+    #[serde(default)]
+    #[schema(example = json!([12, 15]))]
+    pub include_category_ids: Vec<i32>,
+    #[serde(default)]
+    #[schema(example = json!(["^UK\\b", "sport"]))]
+    pub include_patterns: Vec<String>,
+    #[serde(default)]
+    pub exclude_category_ids: Vec<i32>,
+    #[serde(default)]
+    #[schema(example = json!(["adult", "xxx"]))]
+    pub exclude_patterns: Vec<String>,
+}
+
+impl XtreamCategoryFilterRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        for (field, len) in [
+            ("include_category_ids", self.include_category_ids.len()),
+            ("include_patterns", self.include_patterns.len()),
+            ("exclude_category_ids", self.exclude_category_ids.len()),
+            ("exclude_patterns", self.exclude_patterns.len()),
+        ] {
+            if len > MAX_RULES {
+                return Err(format!("{field} may hold at most {MAX_RULES} entries"));
+            }
+        }
+        compile_patterns(&self.include_patterns)?;
+        compile_patterns(&self.exclude_patterns)?;
+        Ok(())
+    }
+
+    /// Drop blank patterns and duplicate entries, keeping the first occurrence
+    pub fn normalized(self) -> Self {
+        fn dedup<T: Clone + Eq + std::hash::Hash>(values: Vec<T>) -> Vec<T> {
+            let mut seen = HashSet::new();
+            values
+                .into_iter()
+                .filter(|value| seen.insert(value.clone()))
+                .collect()
+        }
+        let patterns = |patterns: Vec<String>| {
+            dedup(
+                patterns
+                    .into_iter()
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect(),
+            )
+        };
+        Self {
+            include_category_ids: dedup(self.include_category_ids),
+            include_patterns: patterns(self.include_patterns),
+            exclude_category_ids: dedup(self.exclude_category_ids),
+            exclude_patterns: patterns(self.exclude_patterns),
+        }
+    }
+}
+
+/// A category filter ready to match channels during ingestion
+#[derive(Debug, Clone, Default)]
+pub struct CompiledCategoryFilter {
+    include_ids: HashSet<i32>,
+    include_patterns: Vec<Regex>,
+    exclude_ids: HashSet<i32>,
+    exclude_patterns: Vec<Regex>,
+}
+
+impl CompiledCategoryFilter {
+    /// Whether any rule is configured
+    pub fn is_active(&self) -> bool {
+        self.has_include_rules()
+            || !self.exclude_ids.is_empty()
+            || !self.exclude_patterns.is_empty()
+    }
+
+    /// Whether any rule needs category names
+    pub fn uses_names(&self) -> bool {
+        !self.include_patterns.is_empty() || !self.exclude_patterns.is_empty()
+    }
+
+    fn has_include_rules(&self) -> bool {
+        !self.include_ids.is_empty() || !self.include_patterns.is_empty()
+    }
+
+    /// Whether channels of a category are ingested
+    ///
+    /// Exclusions win over inclusions. With include rules, channels without a category are
+    /// not ingested.
+    pub fn allows(&self, category_id: Option<i32>, category_name: Option<&str>) -> bool {
+        let matches = |ids: &HashSet<i32>, patterns: &[Regex]| {
+            category_id.is_some_and(|id| ids.contains(&id))
+                || category_name.is_some_and(|name| patterns.iter().any(|p| p.is_match(name)))
+        };
+        if matches(&self.exclude_ids, &self.exclude_patterns) {
+            return false;
+        }
+        !self.has_include_rules() || matches(&self.include_ids, &self.include_patterns)
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .size_limit(1 << 20)
+                .build()
+                .map_err(|e| format!("Invalid category pattern '{pattern}': {e}"))
+        })
+        .collect()
+}
+
+/// A provider live category, as offered for configuring the filter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct XtreamCategory {
+    pub category_id: i32,
+    pub category_name: String,
+    /// Whether the source's current filter ingests this category
+    pub ingested: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(
+        include_ids: &[i32],
+        include: &[&str],
+        exclude_ids: &[i32],
+        exclude: &[&str],
+    ) -> CompiledCategoryFilter {
+        XtreamCategoryFilter {
+            include_category_ids: include_ids.to_vec(),
+            include_patterns: include.iter().map(|p| p.to_string()).collect(),
+            exclude_category_ids: exclude_ids.to_vec(),
+            exclude_patterns: exclude.iter().map(|p| p.to_string()).collect(),
+            ..XtreamCategoryFilter::none(Uuid::nil())
+        }
+        .compile()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_empty_filter_allows_everything() {
+        let all = filter(&[], &[], &[], &[]);
+        assert!(!all.is_active());
+        assert!(all.allows(Some(1), Some("News")));
+        assert!(all.allows(None, None));
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let uk = filter(&[7], &["^uk\\b"], &[], &["adult"]);
+        assert!(uk.allows(Some(7), Some("Anything")));
+        assert!(uk.allows(Some(1), Some("UK | Sports")));
+        assert!(!uk.allows(Some(2), Some("US | Sports")));
+        assert!(!uk.allows(Some(3), Some("UK Adult")));
+        assert!(!uk.allows(None, None));
+
+        let no_adult = filter(&[], &[], &[99], &["xxx"]);
+        assert!(no_adult.allows(None, None));
+        assert!(no_adult.allows(Some(1), Some("Movies")));
+        assert!(!no_adult.allows(Some(99), None));
+        assert!(!no_adult.allows(Some(5), Some("XXX Channels")));
+        assert!(no_adult.uses_names());
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let request = XtreamCategoryFilterRequest {
+            include_patterns: vec![" sport ".to_string(), "".to_string(), "sport".to_string()],
+            include_category_ids: vec![3, 3, 4],
+            ..Default::default()
+        }
+        .normalized();
+        assert_eq!(request.include_patterns, vec!["sport".to_string()]);
+        assert_eq!(request.include_category_ids, vec![3, 4]);
+        assert!(request.validate().is_ok());
+
+        let invalid = XtreamCategoryFilterRequest {
+            exclude_patterns: vec!["(".to_string()],
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
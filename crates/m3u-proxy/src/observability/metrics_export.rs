@@ -0,0 +1,152 @@
+//! Push-based metrics export
+//!
+//! Deployments that cannot be scraped push the `AppObservability` metrics to an OTLP
+//! endpoint instead: an OpenTelemetry collector, or Prometheus itself via its OTLP
+//! receiver (`/api/v1/otlp/v1/metrics`). The periodic reader batches every instrument
+//! into one export per interval; failed exports are retried with exponential backoff.
+
+use anyhow::{Context, Result};
+use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::{
+    Resource,
+    error::OTelSdkResult,
+    metrics::{
+        PeriodicReader, SdkMeterProvider, Temporality, data::ResourceMetrics,
+        exporter::PushMetricExporter,
+    },
+};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::{MetricsExportConfig, MetricsExportProtocol};
+
+/// Longest wait between export retries
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Meter provider exporting to the configured endpoint
+pub fn build_meter_provider(
+    service_name: &str,
+    config: &MetricsExportConfig,
+) -> Result<SdkMeterProvider> {
+    let timeout = config.timeout_duration();
+    let exporter = match config.protocol {
+        MetricsExportProtocol::HttpProtobuf => {
+            let mut builder = opentelemetry_otlp::MetricExporter::builder()
+                .with_http()
+                .with_timeout(timeout)
+                .with_headers(config.headers.clone());
+            if let Some(endpoint) = &config.endpoint {
+                builder = builder.with_endpoint(endpoint);
+            }
+            builder.build()
+        }
+        MetricsExportProtocol::Grpc => {
+            let mut builder = opentelemetry_otlp::MetricExporter::builder()
+                .with_tonic()
+                .with_timeout(timeout);
+            if let Some(endpoint) = &config.endpoint {
+                builder = builder.with_endpoint(endpoint);
+            }
+            builder.build()
+        }
+    }
+    .context("Failed to build OTLP metrics exporter")?;
+
+    let reader = PeriodicReader::builder(RetryingExporter::new(exporter, config.max_retries))
+        .with_interval(config.interval_duration())
+        .build();
+
+    info!(
+        "Metrics export enabled: protocol={:?} endpoint={} interval={}",
+        config.protocol,
+        config
+            .endpoint
+            .as_deref()
+            .unwrap_or("(OTEL_EXPORTER_OTLP_ENDPOINT or default)"),
+        config.interval
+    );
+
+    Ok(SdkMeterProvider::builder()
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .with_reader(reader)
+        .build())
+}
+
+/// Wait before retry `attempt` (1-based): 1s, 2s, 4s, ... capped at 30s
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.saturating_sub(1).min(5)).min(MAX_RETRY_DELAY)
+}
+
+/// Exporter retrying failed exports of a batch before dropping it
+#[derive(Debug)]
+struct RetryingExporter<E> {
+    inner: E,
+    max_retries: u32,
+}
+
+impl<E> RetryingExporter<E> {
+    fn new(inner: E, max_retries: u32) -> Self {
+        Self { inner, max_retries }
+    }
+}
+
+impl<E: PushMetricExporter> PushMetricExporter for RetryingExporter<E> {
+    async fn export(&self, metrics: &ResourceMetrics) -> OTelSdkResult {
+        let mut attempt = 0;
+        loop {
+            match self.inner.export(metrics).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < self.max_retries => {
+                    attempt += 1;
+                    let delay = retry_delay(attempt);
+                    warn!(
+                        "Metrics export failed (attempt {}/{}), retrying in {:?}: {}",
+                        attempt,
+                        self.max_retries + 1,
+                        delay,
+                        e
+                    );
+                    // The periodic reader drives exports on its own thread
+                    std::thread::sleep(delay);
+                }
+                Err(e) => {
+                    warn!(
+                        "Metrics export failed after {} attempts; dropping batch: {}",
+                        attempt + 1,
+                        e
+                    );
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn temporality(&self) -> Temporality {
+        self.inner.temporality()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_to_cap() {
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(2), Duration::from_secs(2));
+        assert_eq!(retry_delay(4), Duration::from_secs(8));
+        assert_eq!(retry_delay(10), MAX_RETRY_DELAY);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod metrics_export;
+
 use anyhow::Result;
 use opentelemetry::{
     KeyValue, global,
@@ -11,6 +13,7 @@ use uuid::Uuid;
 #[derive(Clone)]
 pub struct AppObservability {
     pub meter: Meter,
+    meter_provider: SdkMeterProvider,
     // Note: Prometheus registry removed due to opentelemetry-prometheus incompatibility
     // Metrics are now exported via OTLP to external collectors like Prometheus
 
@@ -52,14 +55,32 @@ pub struct AppObservability {
     pub filter_evaluations: Counter<u64>,
     pub channels_included: Counter<u64>,
     pub channels_excluded: Counter<u64>,
+
+    pub access_decisions: Counter<u64>,
+
+    pub query_cache_requests: Counter<u64>,
+
+    pub stream_startup_duration: Histogram<f64>,
+    pub stream_startup_over_budget: Counter<u64>,
 }
 
 impl AppObservability {
     /// Initialize observability based on environment configuration
     pub fn new(service_name: &str) -> Result<Self> {
-        // Create a simple meter provider without Prometheus exporter
-        // Metrics will be exported via OTLP to external systems
-        let provider = SdkMeterProvider::builder().build();
+        Self::with_config(service_name, &crate::config::ObservabilityConfig::default())
+    }
+
+    /// Initialize observability, pushing metrics if `metrics_export` is enabled
+    pub fn with_config(
+        service_name: &str,
+        config: &crate::config::ObservabilityConfig,
+    ) -> Result<Self> {
+        // Without push export, metrics are only recorded locally
+        let provider = if config.metrics_export.enabled {
+            metrics_export::build_meter_provider(service_name, &config.metrics_export)?
+        } else {
+            SdkMeterProvider::builder().build()
+        };
 
         // Set as the global provider
         global::set_meter_provider(provider.clone());
@@ -78,11 +99,18 @@ impl AppObservability {
             info!("OpenTelemetry configured: Local metrics only (OTLP endpoint not configured)");
         }
 
-        let observability = Self::build_with_instruments(meter);
+        let observability = Self::build_with_instruments(meter, provider);
 
         Ok(observability)
     }
 
+    /// Push any pending metrics and stop exporting
+    pub fn shutdown(&self) {
+        if let Err(e) = self.meter_provider.shutdown() {
+            tracing::debug!("Meter provider shutdown: {}", e);
+        }
+    }
+
     /// Initialize OpenTelemetry tracing with OTLP exporter
     fn init_tracing(otlp_endpoint: &str, _service_name: String) -> Result<()> {
         // For now, just log that tracing would be initialized
@@ -95,7 +123,7 @@ impl AppObservability {
     }
 
     /// Build observability with pre-configured instruments
-    fn build_with_instruments(meter: Meter) -> Self {
+    fn build_with_instruments(meter: Meter, meter_provider: SdkMeterProvider) -> Self {
         // Client/Connection metrics
         let client_connections = meter
             .u64_counter("client_connections_total")
@@ -238,8 +266,31 @@ impl AppObservability {
             .with_description("Channels excluded by filters")
             .build();
 
+        // Access control metrics
+        let access_decisions = meter
+            .u64_counter("access_decisions_total")
+            .with_description("Proxy access rule decisions")
+            .build();
+
+        // API query cache metrics
+        let query_cache_requests = meter
+            .u64_counter("query_cache_requests_total")
+            .with_description("API query cache lookups by query and hit/miss")
+            .build();
+
+        // Stream start latency metrics
+        let stream_startup_duration = meter
+            .f64_histogram("stream_startup_duration_seconds")
+            .with_description("Time from stream request to first byte, by proxy and phase")
+            .build();
+        let stream_startup_over_budget = meter
+            .u64_counter("stream_startup_over_budget_total")
+            .with_description("Stream sessions whose first byte exceeded the latency budget")
+            .build();
+
         Self {
             meter,
+            meter_provider,
             client_connections,
             active_clients,
             bytes_sent,
@@ -273,6 +324,10 @@ impl AppObservability {
             filter_evaluations,
             channels_included,
             channels_excluded,
+            access_decisions,
+            query_cache_requests,
+            stream_startup_duration,
+            stream_startup_over_budget,
         }
     }
 
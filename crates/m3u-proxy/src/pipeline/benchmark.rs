@@ -0,0 +1,438 @@
+//! Pipeline stage benchmarks on synthetic datasets
+//!
+//! Backs both `m3u-proxy --bench-pipeline` and the criterion benchmarks in
+//! `benches/pipeline_benchmarks.rs`. Datasets come from the fixture generator, so every run
+//! of a given size sees the same channels and programmes. Stages that need the database in
+//! a real run (data mapping, filtering) are measured through the engines they are built on,
+//! with a fixed set of representative rules; numbering runs the real stage against a
+//! temporary sandbox.
+
+use crate::config::EpgMergeStrategy;
+use crate::models::proxy_settings::EpgMergePolicy;
+use crate::models::{Channel, StreamSource, StreamSourceType};
+use crate::pipeline::engines::rule_processor::RegexEvaluator;
+use crate::pipeline::engines::{
+    ChannelDataMappingEngine, DataMappingEngine, EpgProgram, FilterPlanBuilder, StreamRuleProcessor,
+};
+use crate::pipeline::models::{ArtifactType, PipelineArtifact};
+use crate::pipeline::services::{EpgProgramDeduplicator, EpgProgramMerger, SourcedProgram};
+use crate::pipeline::stages::NumberingStage;
+use crate::sources::m3u::M3uSourceHandler;
+use crate::utils::fixture_generator::{FixtureGenerator, FixtureOptions};
+use crate::utils::human_format::format_memory;
+use crate::utils::xmltv_parser::parse_xmltv_programs;
+use crate::utils::{
+    HttpClientFactory, RegexPreprocessor, STAGE_MEMORY_SAMPLE_INTERVAL, StageMemorySampler,
+};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sandboxed_file_manager::SandboxedManager;
+use serde::Serialize;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Dataset sizes (channels) used when none are given
+pub const DEFAULT_BENCHMARK_SIZES: &[usize] = &[1_000, 10_000, 100_000];
+
+/// Representative data mapping rules: a capture-group rename, a conditional default and a
+/// group rewrite
+const DATA_MAPPING_RULES: &[(&str, &str)] = &[
+    (
+        "strip-quality",
+        r#"channel_name matches "^(.+) (HD|FHD|SD|4K)$" SET channel_name = "$1""#,
+    ),
+    (
+        "default-logo",
+        r#"group_title contains "News" SET tvg_logo ?= "https://logos.fixtures.invalid/news.png""#,
+    ),
+    (
+        "uk-group",
+        r#"tvg_id matches "\.uk$" SET group_title = "United Kingdom""#,
+    ),
+];
+
+/// Representative filters: (name, inverse, expression)
+const FILTERS: &[(&str, bool, &str)] = &[
+    (
+        "include-regions",
+        false,
+        r#"group_title starts_with "UK" OR group_title starts_with "US" OR tvg_id matches "\.(de|fr)$""#,
+    ),
+    ("exclude-kids", true, r#"group_title contains "Kids""#),
+    ("exclude-4k", true, r#"channel_name matches "4K$""#),
+];
+
+/// A pipeline stage that can be benchmarked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchmarkStage {
+    /// M3U playlist parsing during ingestion
+    M3uParse,
+    /// XMLTV guide parsing during ingestion
+    XmltvParse,
+    DataMapping,
+    Filtering,
+    Numbering,
+    /// Deduplicating and merging programmes from two overlapping EPG sources
+    EpgMerge,
+}
+
+impl BenchmarkStage {
+    pub const ALL: [Self; 6] = [
+        Self::M3uParse,
+        Self::XmltvParse,
+        Self::DataMapping,
+        Self::Filtering,
+        Self::Numbering,
+        Self::EpgMerge,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::M3uParse => "m3u_parse",
+            Self::XmltvParse => "xmltv_parse",
+            Self::DataMapping => "data_mapping",
+            Self::Filtering => "filtering",
+            Self::Numbering => "numbering",
+            Self::EpgMerge => "epg_merge",
+        }
+    }
+
+    /// Run the stage once over the dataset; returns the number of records processed
+    pub async fn run(&self, dataset: &BenchmarkDataset) -> Result<usize> {
+        match self {
+            Self::M3uParse => {
+                let handler = M3uSourceHandler::new(&dataset.http_client_factory).await;
+                let channels = handler
+                    .parse_m3u_content(&dataset.m3u, &dataset.source)
+                    .await
+                    .map_err(|e| anyhow!("M3U parsing failed: {e}"))?;
+                Ok(channels.len())
+            }
+            Self::XmltvParse => {
+                let programs = parse_xmltv_programs(&dataset.xmltv)
+                    .map_err(|e| anyhow!("XMLTV parsing failed: {e}"))?;
+                Ok(programs.len())
+            }
+            Self::DataMapping => {
+                let mut engine = ChannelDataMappingEngine::new(dataset.source.id);
+                for (name, expression) in DATA_MAPPING_RULES {
+                    engine.add_rule_processor(StreamRuleProcessor::new(
+                        name.to_string(),
+                        name.to_string(),
+                        expression.to_string(),
+                        RegexEvaluator::new(RegexPreprocessor::new(Default::default())),
+                    ));
+                }
+                let result = engine
+                    .process_records(dataset.channels.clone())
+                    .map_err(|e| anyhow!("Data mapping failed: {e}"))?;
+                Ok(result.total_processed)
+            }
+            Self::Filtering => {
+                let mut builder =
+                    FilterPlanBuilder::new(RegexPreprocessor::new(Default::default()));
+                for (name, inverse, expression) in FILTERS {
+                    builder
+                        .add_filter(name.to_string(), name.to_string(), *inverse, expression)
+                        .map_err(|e| anyhow!("Invalid benchmark filter {name}: {e}"))?;
+                }
+                let result = builder.build().process_records(&dataset.channels);
+                Ok(result.total_input)
+            }
+            Self::Numbering => {
+                let stage =
+                    NumberingStage::new(dataset.file_manager.clone(), "bench".to_string(), 1, None);
+                let artifact = PipelineArtifact::new(
+                    ArtifactType::filtered_channels(),
+                    dataset.channels_file.clone(),
+                    "filtering".to_string(),
+                );
+                let outputs = stage
+                    .process(vec![artifact])
+                    .await
+                    .map_err(|e| anyhow!("Numbering failed: {e}"))?;
+                // Repeated runs would otherwise fill the sandbox with numbered copies
+                for output in outputs
+                    .iter()
+                    .filter(|o| o.file_path != dataset.channels_file)
+                {
+                    let _ = dataset.file_manager.remove_file(&output.file_path).await;
+                }
+                Ok(dataset.channels.len())
+            }
+            Self::EpgMerge => {
+                let priority = [dataset.epg_sources.0, dataset.epg_sources.1];
+                let (deduplicated, _) =
+                    EpgProgramDeduplicator::new(&priority).dedup(dataset.programs.clone());
+                let merger = EpgProgramMerger::new(
+                    EpgMergePolicy::with_strategy(EpgMergeStrategy::FillGaps),
+                    &priority,
+                );
+                merger.merge(deduplicated);
+                Ok(dataset.programs.len())
+            }
+        }
+    }
+}
+
+/// Synthetic input for every benchmarked stage
+pub struct BenchmarkDataset {
+    pub size: usize,
+    pub m3u: String,
+    pub xmltv: String,
+    /// Channels parsed from the playlist
+    pub channels: Vec<Channel>,
+    /// Guide programmes, with every channel's guide provided by two sources: the second
+    /// shifted by a few minutes and partly identical to the first
+    pub programs: Vec<SourcedProgram>,
+    epg_sources: (Uuid, Uuid),
+    source: StreamSource,
+    http_client_factory: HttpClientFactory,
+    file_manager: SandboxedManager,
+    /// Channels as a JSON lines artifact for the numbering stage
+    channels_file: String,
+    _sandbox: tempfile::TempDir,
+}
+
+impl BenchmarkDataset {
+    /// Generate a dataset of `size` channels with two days of guide data
+    pub async fn generate(size: usize) -> Result<Self> {
+        let mut generator = FixtureGenerator::new(FixtureOptions {
+            channels: size,
+            groups: 40,
+            epg_coverage_percent: 100.0,
+            epg_days: 2,
+            programmes_per_day: 24,
+            ..Default::default()
+        });
+        let fixture_channels = generator.channels();
+        let mut m3u = Vec::new();
+        generator.write_m3u(&fixture_channels, &mut m3u)?;
+        let mut xmltv = Vec::new();
+        let guide_start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        generator.write_xmltv(&fixture_channels, guide_start, &mut xmltv)?;
+        let m3u = String::from_utf8(m3u)?;
+        let xmltv = String::from_utf8(xmltv)?;
+
+        let now = Utc::now();
+        let source = StreamSource {
+            id: Uuid::new_v4(),
+            name: "benchmark".to_string(),
+            source_type: StreamSourceType::M3u,
+            url: "http://fixtures.invalid/playlist.m3u".to_string(),
+            max_concurrent_streams: 1,
+            update_cron: "0 0 0 * * * *".to_string(),
+            username: None,
+            password: None,
+            field_map: None,
+            ignore_channel_numbers: false,
+            created_at: now,
+            updated_at: now,
+            last_ingested_at: None,
+            is_active: true,
+        };
+        let http_client_factory = HttpClientFactory::new(None, std::time::Duration::from_secs(10));
+        let channels = M3uSourceHandler::new(&http_client_factory)
+            .await
+            .parse_m3u_content(&m3u, &source)
+            .await
+            .map_err(|e| anyhow!("Failed to parse benchmark playlist: {e}"))?;
+
+        let epg_sources = (Uuid::new_v4(), Uuid::new_v4());
+        let programs = sourced_programs(&xmltv, epg_sources)?;
+
+        let sandbox = tempfile::tempdir().context("Failed to create benchmark sandbox")?;
+        let file_manager = SandboxedManager::builder()
+            .base_directory(sandbox.path())
+            .cleanup_policy(sandboxed_file_manager::CleanupPolicy::disabled())
+            .build()
+            .await
+            .map_err(|e| anyhow!("Failed to create benchmark sandbox: {e}"))?;
+        let channels_file = "bench_channels.jsonl".to_string();
+        let channels_jsonl = channels
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n");
+        file_manager
+            .write(&channels_file, channels_jsonl.as_bytes())
+            .await
+            .map_err(|e| anyhow!("Failed to write benchmark channels: {e}"))?;
+
+        Ok(Self {
+            size,
+            m3u,
+            xmltv,
+            channels,
+            programs,
+            epg_sources,
+            source,
+            http_client_factory,
+            file_manager,
+            channels_file,
+            _sandbox: sandbox,
+        })
+    }
+}
+
+/// The guide as programmes of two overlapping sources
+fn sourced_programs(xmltv: &str, sources: (Uuid, Uuid)) -> Result<Vec<SourcedProgram>> {
+    let parsed =
+        parse_xmltv_programs(xmltv).map_err(|e| anyhow!("Failed to parse benchmark guide: {e}"))?;
+    let mut programs = Vec::with_capacity(parsed.len() * 2);
+    for (i, program) in parsed.into_iter().enumerate() {
+        let (Some(start), Some(end)) = (
+            parse_xmltv_time(&program.start),
+            program.stop.as_deref().and_then(parse_xmltv_time),
+        ) else {
+            continue;
+        };
+        let primary = EpgProgram {
+            id: Uuid::new_v4().to_string(),
+            channel_id: program.channel.clone(),
+            channel_name: program.channel,
+            title: program.title.unwrap_or_default(),
+            description: program.description,
+            program_icon: program.icon,
+            start_time: start,
+            end_time: end,
+            program_category: program.category,
+            subtitles: None,
+            episode_num: None,
+            season_num: None,
+            language: program.language,
+            rating: None,
+            aspect_ratio: None,
+        };
+        // Every other programme of the second source is an exact copy; the rest run late
+        let secondary = if i % 2 == 0 {
+            primary.clone()
+        } else {
+            EpgProgram {
+                id: Uuid::new_v4().to_string(),
+                start_time: start + Duration::minutes(5),
+                end_time: end + Duration::minutes(5),
+                ..primary.clone()
+            }
+        };
+        programs.push(SourcedProgram {
+            source_id: sources.0,
+            program: primary,
+        });
+        programs.push(SourcedProgram {
+            source_id: sources.1,
+            program: secondary,
+        });
+    }
+    Ok(programs)
+}
+
+fn parse_xmltv_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_str(value, "%Y%m%d%H%M%S %z")
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// One stage over one dataset size
+#[derive(Debug, Clone, Serialize)]
+pub struct StageBenchmarkResult {
+    pub stage: &'static str,
+    pub size: usize,
+    pub records: usize,
+    pub duration_ms: f64,
+    pub records_per_second: f64,
+    /// Growth of resident memory over the stage's starting point
+    pub peak_memory_growth_bytes: u64,
+}
+
+/// Results of a `--bench-pipeline` run
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineBenchmarkReport {
+    pub version: String,
+    pub results: Vec<StageBenchmarkResult>,
+}
+
+impl PipelineBenchmarkReport {
+    pub fn print_text(&self) {
+        println!("m3u-proxy pipeline benchmark v{}", self.version);
+        let mut current_size = None;
+        for result in &self.results {
+            if current_size != Some(result.size) {
+                println!();
+                println!("[{} channels]", result.size);
+                println!(
+                    "  {:<14} {:>10} {:>12} {:>14} {:>12}",
+                    "stage", "records", "duration", "records/s", "peak mem"
+                );
+                current_size = Some(result.size);
+            }
+            println!(
+                "  {:<14} {:>10} {:>10.1}ms {:>14.0} {:>12}",
+                result.stage,
+                result.records,
+                result.duration_ms,
+                result.records_per_second,
+                format_memory(result.peak_memory_growth_bytes as f64)
+            );
+        }
+    }
+}
+
+/// Run every stage against a dataset of each size
+pub async fn run_pipeline_benchmark(sizes: &[usize]) -> Result<PipelineBenchmarkReport> {
+    let mut results = Vec::new();
+    for &size in sizes {
+        let dataset = BenchmarkDataset::generate(size).await?;
+        for stage in BenchmarkStage::ALL {
+            let sampler = StageMemorySampler::start(STAGE_MEMORY_SAMPLE_INTERVAL);
+            let started = Instant::now();
+            let records = stage
+                .run(&dataset)
+                .await
+                .with_context(|| format!("Stage {} failed at {size} channels", stage.name()))?;
+            let elapsed = started.elapsed();
+            let memory = sampler.finish().await;
+
+            results.push(StageBenchmarkResult {
+                stage: stage.name(),
+                size: dataset.size,
+                records,
+                duration_ms: elapsed.as_secs_f64() * 1000.0,
+                records_per_second: records as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+                peak_memory_growth_bytes: memory.peak_growth_bytes(),
+            });
+        }
+    }
+    Ok(PipelineBenchmarkReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_every_stage_runs_on_a_small_dataset() {
+        let report = run_pipeline_benchmark(&[50]).await.unwrap();
+        assert_eq!(report.results.len(), BenchmarkStage::ALL.len());
+
+        let records = |stage: BenchmarkStage| {
+            report
+                .results
+                .iter()
+                .find(|r| r.stage == stage.name())
+                .unwrap()
+                .records
+        };
+        assert_eq!(records(BenchmarkStage::M3uParse), 50);
+        assert_eq!(records(BenchmarkStage::DataMapping), 50);
+        assert_eq!(records(BenchmarkStage::Filtering), 50);
+        assert_eq!(records(BenchmarkStage::Numbering), 50);
+        assert_eq!(
+            records(BenchmarkStage::EpgMerge),
+            records(BenchmarkStage::XmltvParse) * 2
+        );
+    }
+}
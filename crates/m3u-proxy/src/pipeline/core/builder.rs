@@ -226,6 +226,14 @@ mod tests {
             cache_channel_logos: true,
             cache_program_logos: false,
             relay_profile_id: None,
+            sign_stream_urls: false,
+            output_profile: Default::default(),
+            backup_streams: Default::default(),
+            offline_slate: Default::default(),
+            epg_languages: None,
+            regeneration_debounce_seconds: None,
+            channel_number_blocks: Vec::new(),
+            epg_timezone: None,
         }
     }
 
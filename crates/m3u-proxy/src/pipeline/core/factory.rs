@@ -171,6 +171,7 @@ impl PipelineOrchestratorFactory {
         use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
         let proxy_entity = StreamProxies::find()
+            .filter(stream_proxies::Column::DeletedAt.is_null())
             .filter(stream_proxies::Column::Id.eq(proxy_id))
             .filter(stream_proxies::Column::IsActive.eq(true))
             .one(&*self.database.connection())
@@ -196,6 +197,16 @@ impl PipelineOrchestratorFactory {
                     updated_at: entity.updated_at,
                     last_generated_at: entity.last_generated_at,
                     relay_profile_id: entity.relay_profile_id,
+                    sign_stream_urls: entity.sign_stream_urls,
+                    output_profile: entity.output_profile,
+                    backup_streams: entity.backup_streams,
+                    offline_slate: entity.offline_slate,
+                    epg_languages: entity.epg_languages,
+                    regeneration_debounce_seconds: entity.regeneration_debounce_seconds,
+                    channel_number_blocks: crate::models::ChannelNumberBlock::from_column(
+                        entity.channel_number_blocks.as_deref(),
+                    ),
+                    epg_timezone: entity.epg_timezone,
                 };
 
                 debug!(
@@ -258,6 +269,8 @@ impl PipelineOrchestratorFactory {
             pipeline_path: PathBuf::from("./pipeline"),
             pipeline_retention: "10m".to_string(),
             pipeline_cleanup_interval: "5m".to_string(),
+            object_storage: None,
+            quotas: std::collections::HashMap::new(),
         };
 
         // Create Database wrapper from connection for test
@@ -266,6 +279,7 @@ impl PipelineOrchestratorFactory {
             read_connection: std::sync::Arc::new(
                 sea_orm::MockDatabase::new(sea_orm::DatabaseBackend::Sqlite).into_connection(),
             ),
+            replica: None,
             backend: sea_orm::DatabaseBackend::Sqlite,
             ingestion_config: crate::config::IngestionConfig::default(),
             database_type: crate::database::DatabaseType::SQLite,
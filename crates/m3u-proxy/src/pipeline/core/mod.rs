@@ -4,4 +4,4 @@ pub mod orchestrator;
 
 pub use builder::{PipelineBuilder, PipelineConfig};
 pub use factory::PipelineOrchestratorFactory;
-pub use orchestrator::PipelineOrchestrator;
+pub use orchestrator::{PipelineOrchestrator, PipelineStageEvent};
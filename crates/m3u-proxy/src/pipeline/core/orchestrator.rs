@@ -15,11 +15,17 @@ pub struct OrchestratorDependencies {
     pub ingestion_state_manager: std::sync::Arc<crate::ingestor::IngestionStateManager>,
 }
 
+use crate::database::repositories::ProxySettingsSeaOrmRepository;
 use crate::ingestor::IngestionStateManager;
+use crate::models::proxy_settings::{
+    AttributePassthrough, EpgGapFilling, EpgMergePolicy, ProxySetting, StreamHints,
+};
 use crate::pipeline::error::PipelineError;
 use crate::pipeline::models::{PipelineExecution, PipelineStatus};
+use crate::pipeline::services::{ArtifactSampleStore, StageCache};
 use crate::pipeline::traits::{PipelineStage, ProgressAware, ProgressReporter};
 use crate::services::progress_service::ProgressManager;
+use crate::utils::{STAGE_MEMORY_SAMPLE_INTERVAL, StageMemorySampler, format_memory};
 use sandboxed_file_manager::SandboxedManager;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -28,6 +34,23 @@ use tracing::{debug, error, info, warn};
 /// Default suspension duration for pipeline operations (5 minutes)
 const DEFAULT_PIPELINE_SUSPENSION_DURATION: Duration = Duration::from_secs(5 * 60);
 
+/// Stage lifecycle event published to an optional observer while the pipeline runs
+#[derive(Debug, Clone)]
+pub enum PipelineStageEvent {
+    Started {
+        stage_id: &'static str,
+        stage_name: &'static str,
+        index: usize,
+        total: usize,
+    },
+    Completed {
+        stage_id: &'static str,
+        stage_name: &'static str,
+        duration: Duration,
+        artifacts: Vec<crate::pipeline::models::PipelineArtifact>,
+    },
+}
+
 /// Pipeline orchestrator using trait-based architecture with ProgressManager
 pub struct PipelineOrchestrator {
     execution: PipelineExecution,
@@ -37,6 +60,12 @@ pub struct PipelineOrchestrator {
     ingestion_state_manager: Arc<IngestionStateManager>,
     progress_manager: Option<Arc<ProgressManager>>,
     stages: Vec<Box<dyn PipelineStage>>,
+    /// Per-stage artifact sampling (enabled via `pipeline_inspection`)
+    artifact_samples: Option<ArtifactSampleStore>,
+    /// Per-stage output cache for incremental regeneration (enabled via `pipeline_stage_cache`)
+    stage_cache: Option<StageCache>,
+    /// Observer notified as stages start and complete (e.g. streaming previews)
+    stage_events: Option<tokio::sync::mpsc::UnboundedSender<PipelineStageEvent>>,
 }
 
 impl PipelineOrchestrator {
@@ -57,12 +86,17 @@ impl PipelineOrchestrator {
             ingestion_state_manager,
             progress_manager,
             stages: Vec::new(),
+            artifact_samples: None,
+            stage_cache: None,
+            stage_events: None,
         }
     }
 
     /// New preferred constructor using a dependency bundle (Clippy-friendly)
     pub fn new_from_dependencies(deps: OrchestratorDependencies) -> Self {
         let execution = PipelineExecution::new(deps.proxy_config.id);
+        let artifact_samples = artifact_sample_store(&deps.app_config, &deps.file_manager);
+        let stage_cache = stage_cache(&deps.app_config, &deps.file_manager);
         let mut orchestrator = Self {
             execution,
             file_manager: deps.file_manager,
@@ -71,6 +105,9 @@ impl PipelineOrchestrator {
             ingestion_state_manager: deps.ingestion_state_manager,
             progress_manager: None,
             stages: Vec::new(),
+            artifact_samples,
+            stage_cache,
+            stage_events: None,
         };
 
         orchestrator.create_and_add_all_stages(
@@ -100,6 +137,8 @@ impl PipelineOrchestrator {
         ingestion_state_manager: Arc<IngestionStateManager>,
     ) -> Self {
         let execution = PipelineExecution::new(proxy_config.id);
+        let artifact_samples = artifact_sample_store(&app_config, &file_manager);
+        let stage_cache = stage_cache(&app_config, &file_manager);
         let mut orchestrator = Self {
             execution,
             file_manager,
@@ -108,6 +147,9 @@ impl PipelineOrchestrator {
             ingestion_state_manager,
             progress_manager: None, // Will be set later if needed
             stages: Vec::new(),
+            artifact_samples,
+            stage_cache,
+            stage_events: None,
         };
 
         // Create and add all pipeline stages in order
@@ -116,6 +158,47 @@ impl PipelineOrchestrator {
         orchestrator
     }
 
+    /// Publish stage start/completion events to `sender` during execution
+    pub fn set_stage_event_sender(
+        &mut self,
+        sender: tokio::sync::mpsc::UnboundedSender<PipelineStageEvent>,
+    ) {
+        self.stage_events = Some(sender);
+    }
+
+    /// Drop every stage after `stage_id`, so the run ends there (e.g. previews that must
+    /// not generate or publish output). Returns false when no such stage exists.
+    pub fn stop_after_stage(&mut self, stage_id: &str) -> bool {
+        match self.stages.iter().position(|s| s.stage_id() == stage_id) {
+            Some(index) => {
+                self.stages.truncate(index + 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Publish only the XMLTV guide, for runs that re-map EPG data without replacing the
+    /// playlist. Returns false when the pipeline has no publish stage.
+    pub fn publish_guide_only(&mut self) -> bool {
+        let publish_stage = self
+            .stages
+            .iter_mut()
+            .find(|s| s.stage_id() == "publish_content")
+            .and_then(|stage| {
+                stage
+                    .as_any_mut()
+                    .downcast_mut::<crate::pipeline::stages::publish_content::PublishContentStage>()
+            });
+        match publish_stage {
+            Some(stage) => {
+                stage.set_guide_only(true);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Add a stage to the pipeline
     pub fn add_stage(&mut self, stage: Box<dyn PipelineStage>) {
         self.stages.push(stage);
@@ -202,10 +285,21 @@ impl PipelineOrchestrator {
         // Create each pipeline stage in the correct order
 
         // 1. Data Mapping Stage
+        let epg_merge_policy = proxy_setting::<EpgMergePolicy>(&database, proxy_config.id)
+            .unwrap_or_else(|| {
+                EpgMergePolicy::with_strategy(
+                    self.app_config
+                        .epg_merge
+                        .as_ref()
+                        .map(|merge| merge.default_strategy)
+                        .unwrap_or_default(),
+                )
+            });
         if let Ok(data_mapping_stage) = tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
                 crate::pipeline::stages::data_mapping::DataMappingStage::new(
-                    database.connection().clone(),
+                    // Source loading only reads, so it may use the replica
+                    database.read_connection(),
                     self.execution.execution_prefix.clone(),
                     self.file_manager.clone(),
                     self.progress_manager.clone(),
@@ -213,6 +307,15 @@ impl PipelineOrchestrator {
                 .await
             })
         }) {
+            let mut data_mapping_stage = data_mapping_stage
+                .with_epg_merge_policy(epg_merge_policy)
+                .with_proxy_id(proxy_config.id);
+            if let Some(failover) = self.app_config.epg_failover.clone() {
+                data_mapping_stage = data_mapping_stage.with_epg_failover(failover);
+            }
+            if let Some(engine_config) = &self.app_config.data_mapping_engine {
+                data_mapping_stage = data_mapping_stage.with_engine_config(engine_config);
+            }
             self.add_stage(Box::new(data_mapping_stage));
         } else {
             warn!("Failed to create DataMappingStage");
@@ -236,6 +339,17 @@ impl PipelineOrchestrator {
             warn!("Failed to create FilteringStage");
         }
 
+        // 2b. Virtual Channels Stage (inject user-defined channels after filtering)
+        self.add_stage(Box::new(
+            crate::pipeline::stages::virtual_channels::VirtualChannelsStage::new(
+                database.connection().clone(),
+                self.file_manager.clone(),
+                self.execution.execution_prefix.clone(),
+                proxy_config.id,
+                self.progress_manager.clone(),
+            ),
+        ));
+
         // 3. Logo Caching Stage
         if let Ok(logo_caching_stage) = tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
@@ -247,6 +361,11 @@ impl PipelineOrchestrator {
                     self.progress_manager.clone(),
                 )
                 .await
+                .map(|stage| {
+                    stage.with_prefetch_config(
+                        &self.app_config.logo_prefetch.clone().unwrap_or_default(),
+                    )
+                })
             })
         }) {
             self.add_stage(Box::new(logo_caching_stage));
@@ -270,11 +389,22 @@ impl PipelineOrchestrator {
             self.execution.execution_prefix.clone(),
             starting_channel_number,
             self.progress_manager.clone(),
-        );
+        )
+        .with_number_blocks(proxy_config.channel_number_blocks.clone());
         self.add_stage(Box::new(numbering_stage));
 
+        // 4b. Compliance Stage (mandatory blocklist enforcement before generation)
+        self.add_stage(Box::new(
+            crate::pipeline::stages::compliance::ComplianceStage::new(
+                self.file_manager.clone(),
+                self.execution.execution_prefix.clone(),
+                proxy_config.id,
+                self.progress_manager.clone(),
+            ),
+        ));
+
         // 5. Generation Stage
-        if let Ok(generation_stage) = tokio::task::block_in_place(|| {
+        if let Ok(mut generation_stage) = tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
                 crate::pipeline::stages::generation::GenerationStage::new(
                     database.connection().clone(),
@@ -287,6 +417,40 @@ impl PipelineOrchestrator {
                 .await
             })
         }) {
+            if let Some(gap_filler) = self.app_config.epg_gap_filler.clone()
+                && gap_filler
+                    .applies_to(proxy_setting::<EpgGapFilling>(&database, proxy_config.id).as_ref())
+            {
+                generation_stage = generation_stage.with_epg_gap_filler(gap_filler);
+            }
+            if let Some(stream_hints) = self.app_config.stream_hints.clone()
+                && stream_hints
+                    .applies_to(proxy_setting::<StreamHints>(&database, proxy_config.id).as_ref())
+            {
+                generation_stage = generation_stage.with_stream_hints(
+                    stream_hints,
+                    proxy_config.proxy_mode == crate::models::StreamProxyMode::Relay,
+                );
+            }
+            if let Some(passthrough) = self.app_config.attribute_passthrough.clone()
+                && passthrough.applies_to(
+                    proxy_setting::<AttributePassthrough>(&database, proxy_config.id).as_ref(),
+                )
+            {
+                generation_stage = generation_stage.with_attribute_passthrough(passthrough);
+            }
+            generation_stage = generation_stage.with_backup_streams(proxy_config.backup_streams);
+            generation_stage = generation_stage.with_epg_languages(
+                crate::models::epg_channel_metadata::parse_language_preference(
+                    proxy_config.epg_languages.as_deref(),
+                ),
+            );
+            if let Some(tz) = proxy_config.epg_timezone.as_deref() {
+                match crate::utils::time::parse_iana_timezone(tz) {
+                    Ok(tz) => generation_stage = generation_stage.with_output_timezone(Some(tz)),
+                    Err(e) => warn!("Writing EPG times in UTC: {}", e),
+                }
+            }
             self.add_stage(Box::new(generation_stage));
         } else {
             warn!("Failed to create GenerationStage");
@@ -300,6 +464,12 @@ impl PipelineOrchestrator {
                 proxy_config.id,                         // proxy_id
                 false,                                   // enable_versioning (disabled for now)
                 self.progress_manager.clone(),
+            )
+            .with_output_publishing(
+                self.app_config
+                    .output_publishing
+                    .clone()
+                    .unwrap_or_default(),
             );
         self.add_stage(Box::new(publish_content_stage));
 
@@ -340,6 +510,11 @@ impl PipelineOrchestrator {
                             filtering_stage.set_progress_manager(pm.clone());
                         }
                     }
+                    "virtual_channels" => {
+                        if let Some(virtual_channels_stage) = stage.as_any_mut().downcast_mut::<crate::pipeline::stages::virtual_channels::VirtualChannelsStage>() {
+                            virtual_channels_stage.set_progress_manager(pm.clone());
+                        }
+                    }
                     "logo_caching" => {
                         if let Some(logo_caching_stage) = stage.as_any_mut().downcast_mut::<crate::pipeline::stages::logo_caching::LogoCachingStage>() {
                             logo_caching_stage.set_progress_manager(pm.clone());
@@ -350,6 +525,11 @@ impl PipelineOrchestrator {
                             numbering_stage.set_progress_manager(pm.clone());
                         }
                     }
+                    "compliance" => {
+                        if let Some(compliance_stage) = stage.as_any_mut().downcast_mut::<crate::pipeline::stages::compliance::ComplianceStage>() {
+                            compliance_stage.set_progress_manager(pm.clone());
+                        }
+                    }
                     "generation" => {
                         if let Some(generation_stage) = stage.as_any_mut().downcast_mut::<crate::pipeline::stages::generation::GenerationStage>() {
                             generation_stage.set_progress_manager(pm.clone());
@@ -463,6 +643,10 @@ impl PipelineOrchestrator {
         // Execute all stages in sequence
         let mut artifacts = Vec::new();
         let total_stages = self.stages.len();
+        // Cache key of the previous stage; `None` once a stage without a fingerprint ran
+        let mut cache_chain = Some(String::new());
+        // Set once a stage actually runs: every later stage must run too
+        let mut resumed = false;
 
         for stage_index in 0..self.stages.len() {
             let stage_start = Instant::now();
@@ -488,11 +672,59 @@ impl PipelineOrchestrator {
                 progress_mgr.set_current_stage(stage_id).await;
             }
 
+            if let Some(sender) = &self.stage_events {
+                let _ = sender.send(PipelineStageEvent::Started {
+                    stage_id,
+                    stage_name,
+                    index: stage_index,
+                    total: total_stages,
+                });
+            }
+
+            // Incremental regeneration: reuse cached output while every stage so far is unchanged
+            let cache_key = match (&self.stage_cache, cache_chain.as_deref()) {
+                (Some(_), Some(previous_key)) => self.stages[stage_index]
+                    .cache_fingerprint()
+                    .await
+                    .map(|fingerprint| StageCache::chain_key(previous_key, stage_id, &fingerprint)),
+                _ => None,
+            };
+            cache_chain = cache_key.clone();
+            let cached_artifacts = match (&self.stage_cache, &cache_key) {
+                (Some(cache), Some(key)) if !resumed => {
+                    cache.lookup(self.execution.proxy_id, stage_id, key).await
+                }
+                _ => None,
+            };
+            let cache_hit = cached_artifacts.is_some();
+
             // Execute the stage (split borrow to avoid conflicts)
-            let stage_result = {
-                let stage = &mut self.stages[stage_index];
-                stage.execute(artifacts).await
+            let memory_sampler = StageMemorySampler::start(STAGE_MEMORY_SAMPLE_INTERVAL);
+            let stage_result = match cached_artifacts {
+                Some(cached) => {
+                    info!(
+                        "Stage {} inputs unchanged; reusing cached output",
+                        stage_name
+                    );
+                    Ok(cached)
+                }
+                None => {
+                    resumed = true;
+                    let stage = &mut self.stages[stage_index];
+                    stage.execute(artifacts).await
+                }
             };
+            let memory = memory_sampler.finish().await;
+            debug!(
+                "Stage {} memory: start={} peak={} end={}",
+                stage_id,
+                format_memory(memory.start_bytes as f64),
+                format_memory(memory.peak_bytes as f64),
+                format_memory(memory.end_bytes as f64)
+            );
+            self.execution
+                .stage_memory
+                .insert(stage_id.to_string(), memory);
 
             match stage_result {
                 Ok(stage_artifacts) => {
@@ -508,12 +740,128 @@ impl PipelineOrchestrator {
                         "artifacts_created".to_string(),
                         serde_json::json!(stage_artifacts.len()),
                     );
+                    metrics.insert(
+                        "memory_peak_bytes".to_string(),
+                        serde_json::json!(memory.peak_bytes),
+                    );
+                    metrics.insert(
+                        "memory_peak_growth_bytes".to_string(),
+                        serde_json::json!(memory.peak_growth_bytes()),
+                    );
+                    if let Some(key) = &cache_key {
+                        metrics.insert("cache_hit".to_string(), serde_json::json!(cache_hit));
+                        if cache_hit {
+                            self.execution.cache_hits.push(stage_id.to_string());
+                        } else {
+                            self.execution.cache_misses.push(stage_id.to_string());
+                        }
+                        if !cache_hit
+                            && let Some(cache) = &self.stage_cache
+                            && let Err(e) = cache
+                                .store(self.execution.proxy_id, stage_id, key, &stage_artifacts)
+                                .await
+                        {
+                            warn!("Failed to cache output of stage {}: {}", stage_id, e);
+                        }
+                    }
+                    if stage_id == "logo_caching" && !cache_hit {
+                        let logos_deferred: u64 = stage_artifacts
+                            .iter()
+                            .filter_map(|artifact| {
+                                artifact
+                                    .metadata
+                                    .get(crate::pipeline::stages::logo_caching::LOGOS_DEFERRED_METADATA)
+                                    .and_then(|value| value.as_u64())
+                            })
+                            .sum();
+                        metrics.insert(
+                            "logos_deferred".to_string(),
+                            serde_json::json!(logos_deferred),
+                        );
+                        self.execution.logos_deferred = logos_deferred as usize;
+                    }
+                    if stage_id == "data_mapping" && !cache_hit {
+                        self.execution.precheck_effectiveness = stage_artifacts
+                            .iter()
+                            .filter_map(|artifact| {
+                                artifact.metadata.get(
+                                    crate::pipeline::stages::data_mapping::PRECHECK_EFFECTIVENESS_METADATA,
+                                )
+                            })
+                            .filter_map(|value| {
+                                serde_json::from_value::<Vec<crate::models::PrecheckEffectiveness>>(
+                                    value.clone(),
+                                )
+                                .ok()
+                            })
+                            .flatten()
+                            .collect();
+                    }
+                    if stage_id == "data_mapping" {
+                        // Dedup counts describe the output, so cached output reports them too
+                        if let Some(dedup) = stage_artifacts
+                            .iter()
+                            .filter_map(|artifact| {
+                                artifact
+                                    .metadata
+                                    .get(crate::pipeline::stages::data_mapping::EPG_DEDUP_METADATA)
+                            })
+                            .find_map(|value| {
+                                serde_json::from_value::<crate::models::EpgDedupStats>(
+                                    value.clone(),
+                                )
+                                .ok()
+                            })
+                        {
+                            self.execution.epg_dedup = dedup;
+                        }
+                    }
+                    if stage_id == "numbering" && !cache_hit {
+                        self.execution.number_block_overflows = stage_artifacts
+                            .iter()
+                            .filter_map(|artifact| {
+                                artifact.metadata.get(
+                                    crate::pipeline::stages::numbering::NUMBER_BLOCK_OVERFLOWS_METADATA,
+                                )
+                            })
+                            .filter_map(|value| {
+                                serde_json::from_value::<Vec<crate::models::NumberBlockOverflow>>(
+                                    value.clone(),
+                                )
+                                .ok()
+                            })
+                            .flatten()
+                            .collect();
+                    }
                     self.execution.complete_stage_with_artifacts(
                         stage_id,
                         stage_artifacts.clone(),
                         metrics,
                     );
 
+                    if let Some(store) = &self.artifact_samples
+                        && let Err(e) = store
+                            .record_stage(
+                                self.execution.proxy_id,
+                                self.execution.id,
+                                stage_index,
+                                stage_id,
+                                &stage_artifacts,
+                            )
+                            .await
+                    {
+                        warn!("Failed to persist artifact samples for {}: {}", stage_id, e);
+                    }
+
+                    if let Some(sender) = &self.stage_events {
+                        let _ = sender.send(PipelineStageEvent::Completed {
+                            stage_id,
+                            stage_name,
+                            duration: stage_duration,
+                            artifacts: stage_artifacts.clone(),
+                        });
+                    }
+
                     artifacts = stage_artifacts;
                 }
                 Err(e) => {
@@ -555,9 +903,9 @@ impl PipelineOrchestrator {
     fn get_pipeline_status_for_stage(&self, stage_id: &str) -> PipelineStatus {
         match stage_id {
             "data_mapping" => PipelineStatus::DataMapping,
-            "filtering" => PipelineStatus::Filtering,
+            "filtering" | "virtual_channels" => PipelineStatus::Filtering,
             "logo_caching" => PipelineStatus::LogoCaching,
-            "numbering" => PipelineStatus::Numbering,
+            "numbering" | "compliance" => PipelineStatus::Numbering,
             "generation" => PipelineStatus::Generation,
             "publish_content" => PipelineStatus::Publishing,
             _ => PipelineStatus::DataMapping, // Default fallback
@@ -593,6 +941,50 @@ impl ProgressAware for PipelineOrchestrator {
     }
 }
 
+/// Artifact sample store when pipeline inspection is enabled
+fn artifact_sample_store(
+    app_config: &crate::config::Config,
+    file_manager: &SandboxedManager,
+) -> Option<ArtifactSampleStore> {
+    app_config
+        .pipeline_inspection
+        .as_ref()
+        .filter(|c| c.enabled)
+        .map(|c| ArtifactSampleStore::new(file_manager.clone(), c.sample_size))
+}
+
+/// Stage output cache when incremental regeneration is enabled
+fn stage_cache(
+    app_config: &crate::config::Config,
+    file_manager: &SandboxedManager,
+) -> Option<StageCache> {
+    app_config
+        .pipeline_stage_cache
+        .as_ref()
+        .filter(|c| c.enabled)
+        .map(|_| StageCache::new(file_manager.clone()))
+}
+
+/// A proxy's setting; failures to load it are logged and treated as unset
+fn proxy_setting<S: ProxySetting>(
+    database: &crate::database::Database,
+    proxy_id: uuid::Uuid,
+) -> Option<S> {
+    let repo = ProxySettingsSeaOrmRepository::new(database.connection());
+    tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(repo.get::<S>(&proxy_id))
+    })
+    .unwrap_or_else(|e| {
+        warn!(
+            "Failed to load {} setting of proxy {}, ignoring it: {}",
+            S::KEY,
+            proxy_id,
+            e
+        );
+        None
+    })
+}
+
 /// Guard that stops the suspension extension task when dropped
 struct SuspensionExtensionGuard {
     stop_flag: Arc<std::sync::atomic::AtomicBool>,
@@ -0,0 +1,813 @@
+/*!
+ * Compiled channel filter evaluation plan
+ *
+ * The filtering stage used to walk each filter's condition AST for every channel. The plan
+ * compiles a proxy's active stream filters once per run instead:
+ *
+ * - field names are resolved to accessors up front;
+ * - literal comparison values are pre-lowercased;
+ * - regex patterns are grouped per field into one `RegexSet`, evaluated at most once per
+ *   channel and field, behind the preprocessor's literal prechecks;
+ * - group children are ordered cheapest first and evaluated with short-circuiting;
+ * - filters are reordered during the run by observed selectivity (rejection rate per unit of
+ *   evaluation cost), so the filters dropping the most channels cheaply run first.
+ *
+ * A channel is kept when every filter keeps it (include filters must match, exclude filters
+ * must not), which is order independent and therefore safe to reorder. Condition semantics
+ * match `StreamFilterProcessor` exactly.
+ */
+
+use regex::{Regex, RegexSet};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::expression::{ExpressionDomain, parse_expression_extended};
+use crate::field_registry::FieldRegistry;
+use crate::models::{Channel, ConditionNode, FilterOperator, LogicalOperator};
+use crate::pipeline::engines::FilterEngineResult;
+use crate::utils::regex_preprocessor::{RegexPrecheck, RegexPreprocessor};
+
+/// Channels evaluated between selectivity reorderings
+const REORDER_INTERVAL: usize = 512;
+
+/// Relative evaluation cost of a literal comparison (regexes and groups scale from it)
+const LITERAL_COST: u32 = 1;
+const COMPARE_COST: u32 = 2;
+const REGEX_COST: u32 = 4;
+
+/// Channel field referenced by a filter condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChannelField {
+    TvgId,
+    TvgName,
+    TvgLogo,
+    TvgShift,
+    GroupTitle,
+    ChannelName,
+    StreamUrl,
+    /// Source fields are not carried on the channel and always read as empty
+    Unavailable,
+    /// Extra EXTINF attribute, by its slot in the plan's attribute names
+    Attribute(usize),
+}
+
+/// Fields other than extra attributes, which take the indices after them
+const FIELD_COUNT: usize = 8;
+
+impl ChannelField {
+    fn resolve(name: &str) -> Option<Self> {
+        Some(match name {
+            "tvg_id" => Self::TvgId,
+            "tvg_name" => Self::TvgName,
+            "tvg_logo" => Self::TvgLogo,
+            "tvg_shift" => Self::TvgShift,
+            "group_title" => Self::GroupTitle,
+            "channel_name" => Self::ChannelName,
+            "stream_url" => Self::StreamUrl,
+            "source_name" | "source_type" | "source_url" => Self::Unavailable,
+            _ => return None,
+        })
+    }
+
+    fn value<'a>(self, channel: &'a Channel, attributes: &[String]) -> &'a str {
+        match self {
+            Self::TvgId => channel.tvg_id.as_deref().unwrap_or_default(),
+            Self::TvgName => channel.tvg_name.as_deref().unwrap_or_default(),
+            Self::TvgLogo => channel.tvg_logo.as_deref().unwrap_or_default(),
+            Self::TvgShift => channel.tvg_shift.as_deref().unwrap_or_default(),
+            Self::GroupTitle => channel.group_title.as_deref().unwrap_or_default(),
+            Self::ChannelName => &channel.channel_name,
+            Self::StreamUrl => &channel.stream_url,
+            Self::Unavailable => "",
+            Self::Attribute(slot) => channel
+                .extra_attributes
+                .get(&attributes[slot])
+                .map_or("", String::as_str),
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::TvgId => 0,
+            Self::TvgName => 1,
+            Self::TvgLogo => 2,
+            Self::TvgShift => 3,
+            Self::GroupTitle => 4,
+            Self::ChannelName => 5,
+            Self::StreamUrl => 6,
+            Self::Unavailable => 7,
+            Self::Attribute(slot) => FIELD_COUNT + slot,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum LiteralOp {
+    Equals,
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+/// Compiled condition tree
+#[derive(Debug)]
+enum PlanNode {
+    /// Always true (empty group)
+    Always,
+    Literal {
+        field: ChannelField,
+        op: LiteralOp,
+        /// Lowercased unless case sensitive (equals keeps the raw value, compared ASCII-insensitively)
+        value: String,
+        case_sensitive: bool,
+        negate: bool,
+    },
+    Regex {
+        field: ChannelField,
+        /// Index of the pattern within the field's regex set
+        slot: usize,
+        precheck: RegexPrecheck,
+        negate: bool,
+    },
+    Compare {
+        field: ChannelField,
+        value: String,
+        numeric: Option<f64>,
+        ordering: Ordering,
+        or_equal: bool,
+        case_sensitive: bool,
+    },
+    All(Vec<PlanNode>),
+    Any(Vec<PlanNode>),
+}
+
+impl PlanNode {
+    fn cost(&self) -> u32 {
+        match self {
+            Self::Always => 0,
+            Self::Literal { .. } => LITERAL_COST,
+            Self::Compare { .. } => COMPARE_COST,
+            Self::Regex { .. } => REGEX_COST,
+            Self::All(children) | Self::Any(children) => children.iter().map(Self::cost).sum(),
+        }
+    }
+}
+
+/// Regex patterns of one field
+enum FieldRegexes {
+    Set(RegexSet),
+    /// Fallback when the patterns cannot be combined (e.g. set size limits)
+    Individual(Vec<Regex>),
+}
+
+impl FieldRegexes {
+    fn matches(&self, value: &str, out: &mut Vec<bool>) {
+        out.clear();
+        match self {
+            Self::Set(set) => {
+                let matches = set.matches(value);
+                out.extend((0..set.len()).map(|i| matches.matched(i)));
+            }
+            Self::Individual(regexes) => out.extend(regexes.iter().map(|r| r.is_match(value))),
+        }
+    }
+}
+
+/// Per-channel evaluation cache, reused across channels
+struct Scratch {
+    lowered: Vec<Option<String>>,
+    regex_matches: Vec<Option<Vec<bool>>>,
+}
+
+impl Scratch {
+    fn new(field_count: usize) -> Self {
+        Self {
+            lowered: vec![None; field_count],
+            regex_matches: vec![None; field_count],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.lowered.iter_mut().for_each(|v| *v = None);
+        for matches in self.regex_matches.iter_mut().flatten() {
+            matches.clear();
+        }
+    }
+}
+
+struct CompiledFilter {
+    id: String,
+    name: String,
+    is_inverse: bool,
+    /// `None` matches every channel (empty expression)
+    root: Option<PlanNode>,
+    /// Set when the expression references a field channels do not have; such a filter
+    /// rejects every channel, as evaluation errors do in the processor
+    invalid: bool,
+    cost: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct FilterStats {
+    kept: usize,
+    rejected: usize,
+    elapsed: Duration,
+}
+
+impl FilterStats {
+    /// Expected cost of rejecting a channel; lower runs first
+    fn rank(&self, static_cost: u32) -> f64 {
+        let evaluated = self.kept + self.rejected;
+        if evaluated == 0 {
+            return static_cost as f64;
+        }
+        let avg_nanos = self.elapsed.as_nanos() as f64 / evaluated as f64;
+        let reject_rate = self.rejected as f64 / evaluated as f64;
+        avg_nanos.max(1.0) / reject_rate.max(1e-6)
+    }
+}
+
+/// Collects filters and compiles them into a [`CompiledFilterPlan`]
+pub struct FilterPlanBuilder {
+    preprocessor: RegexPreprocessor,
+    filters: Vec<CompiledFilter>,
+    /// Regex patterns per field index
+    patterns: Vec<Vec<String>>,
+    /// Names of the extra attributes referenced, by slot
+    attributes: Vec<String>,
+}
+
+impl FilterPlanBuilder {
+    pub fn new(preprocessor: RegexPreprocessor) -> Self {
+        Self {
+            preprocessor,
+            filters: Vec::new(),
+            patterns: vec![Vec::new(); FIELD_COUNT],
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Field of an extra attribute, giving it a slot on first reference
+    fn attribute_field(&mut self, name: &str) -> ChannelField {
+        let slot = match self.attributes.iter().position(|a| a == name) {
+            Some(slot) => slot,
+            None => {
+                self.attributes.push(name.to_string());
+                self.patterns.push(Vec::new());
+                self.attributes.len() - 1
+            }
+        };
+        ChannelField::Attribute(slot)
+    }
+
+    /// Add a stream filter; filters are kept in the order added until statistics accumulate
+    pub fn add_filter(
+        &mut self,
+        filter_id: String,
+        filter_name: String,
+        is_inverse: bool,
+        condition_expression: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let parsed =
+            parse_expression_extended(ExpressionDomain::StreamFilter, condition_expression)
+                .map_err(|e| {
+                    format!("Failed to parse stream filter expression ({filter_id}): {e}")
+                })?;
+
+        let (root, invalid) = match parsed {
+            None => (None, false),
+            Some(parsed) => match self.compile_node(&parsed.condition_tree().root) {
+                Ok(node) => (Some(node), false),
+                Err(e) => {
+                    warn!(
+                        "STREAM filter cannot be evaluated and rejects all channels filter_id={} name={} err={}",
+                        filter_id, filter_name, e
+                    );
+                    (None, true)
+                }
+            },
+        };
+        let cost = root.as_ref().map(PlanNode::cost).unwrap_or(0);
+
+        self.filters.push(CompiledFilter {
+            id: filter_id,
+            name: filter_name,
+            is_inverse,
+            root,
+            invalid,
+            cost,
+        });
+        Ok(())
+    }
+
+    fn compile_node(&mut self, node: &ConditionNode) -> Result<PlanNode, String> {
+        match node {
+            ConditionNode::Condition {
+                field,
+                operator,
+                value,
+                case_sensitive,
+                ..
+            } => {
+                let field = match FieldRegistry::extra_attribute_name(field) {
+                    Some(name) => self.attribute_field(name),
+                    None => ChannelField::resolve(field).ok_or_else(|| {
+                        format!("Unknown stream/channel field referenced in filter: {field}")
+                    })?,
+                };
+                Ok(self.compile_condition(field, operator, value, *case_sensitive))
+            }
+            ConditionNode::Group { operator, children } => {
+                if children.is_empty() {
+                    return Ok(PlanNode::Always);
+                }
+                let mut compiled = children
+                    .iter()
+                    .map(|child| self.compile_node(child))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // Conditions are side-effect free, so cheapest-first short-circuiting is safe
+                compiled.sort_by_key(PlanNode::cost);
+                Ok(match operator {
+                    LogicalOperator::And => PlanNode::All(compiled),
+                    LogicalOperator::Or => PlanNode::Any(compiled),
+                })
+            }
+        }
+    }
+
+    fn compile_condition(
+        &mut self,
+        field: ChannelField,
+        operator: &FilterOperator,
+        value: &str,
+        case_sensitive: bool,
+    ) -> PlanNode {
+        let literal = |op: LiteralOp, negate: bool| PlanNode::Literal {
+            field,
+            op,
+            value: if case_sensitive || matches!(op, LiteralOp::Equals) {
+                value.to_string()
+            } else {
+                value.to_lowercase()
+            },
+            case_sensitive,
+            negate,
+        };
+        let compare = |ordering: Ordering, or_equal: bool| PlanNode::Compare {
+            field,
+            value: value.to_string(),
+            numeric: value.parse::<f64>().ok(),
+            ordering,
+            or_equal,
+            case_sensitive,
+        };
+
+        match operator {
+            FilterOperator::Equals => literal(LiteralOp::Equals, false),
+            FilterOperator::NotEquals => literal(LiteralOp::Equals, true),
+            FilterOperator::Contains => literal(LiteralOp::Contains, false),
+            FilterOperator::NotContains => literal(LiteralOp::Contains, true),
+            FilterOperator::StartsWith => literal(LiteralOp::StartsWith, false),
+            FilterOperator::NotStartsWith => literal(LiteralOp::StartsWith, true),
+            FilterOperator::EndsWith => literal(LiteralOp::EndsWith, false),
+            FilterOperator::NotEndsWith => literal(LiteralOp::EndsWith, true),
+            FilterOperator::Matches => self.compile_regex(field, value, false),
+            FilterOperator::NotMatches => self.compile_regex(field, value, true),
+            FilterOperator::GreaterThan => compare(Ordering::Greater, false),
+            FilterOperator::LessThan => compare(Ordering::Less, false),
+            FilterOperator::GreaterThanOrEqual => compare(Ordering::Greater, true),
+            FilterOperator::LessThanOrEqual => compare(Ordering::Less, true),
+        }
+    }
+
+    fn compile_regex(&mut self, field: ChannelField, pattern: &str, negate: bool) -> PlanNode {
+        if let Err(e) = Regex::new(pattern) {
+            warn!(
+                "Invalid regex pattern '{}': {}, falling back to substring contains",
+                pattern, e
+            );
+            return PlanNode::Literal {
+                field,
+                op: LiteralOp::Contains,
+                value: pattern.to_string(),
+                case_sensitive: true,
+                negate,
+            };
+        }
+
+        let patterns = &mut self.patterns[field.index()];
+        let slot = match patterns.iter().position(|p| p == pattern) {
+            Some(slot) => slot,
+            None => {
+                patterns.push(pattern.to_string());
+                patterns.len() - 1
+            }
+        };
+        PlanNode::Regex {
+            field,
+            slot,
+            precheck: self.preprocessor.precheck(pattern),
+            negate,
+        }
+    }
+
+    /// Compile the collected filters
+    pub fn build(self) -> CompiledFilterPlan {
+        let regexes = self
+            .patterns
+            .into_iter()
+            .map(|patterns| {
+                if patterns.is_empty() {
+                    return None;
+                }
+                Some(match RegexSet::new(&patterns) {
+                    Ok(set) => FieldRegexes::Set(set),
+                    Err(e) => {
+                        warn!(
+                            "Cannot combine {} filter regexes into a set ({}), evaluating individually",
+                            patterns.len(),
+                            e
+                        );
+                        FieldRegexes::Individual(
+                            patterns
+                                .iter()
+                                .filter_map(|p| Regex::new(p).ok())
+                                .collect(),
+                        )
+                    }
+                })
+            })
+            .collect();
+
+        let order = (0..self.filters.len()).collect();
+        let stats = vec![FilterStats::default(); self.filters.len()];
+        CompiledFilterPlan {
+            filters: self.filters,
+            regexes,
+            attributes: self.attributes,
+            order,
+            stats,
+        }
+    }
+}
+
+/// Stream filters compiled for repeated evaluation over a channel list
+pub struct CompiledFilterPlan {
+    filters: Vec<CompiledFilter>,
+    /// Regexes per field index
+    regexes: Vec<Option<FieldRegexes>>,
+    attributes: Vec<String>,
+    /// Evaluation order (indices into `filters`)
+    order: Vec<usize>,
+    stats: Vec<FilterStats>,
+}
+
+impl CompiledFilterPlan {
+    pub fn has_filters(&self) -> bool {
+        !self.filters.is_empty()
+    }
+
+    /// Filter channels; per-filter statistics are `(kept, rejected, time)` for the channels
+    /// each filter evaluated
+    pub fn process_records(&mut self, channels: &[Channel]) -> FilterEngineResult<Channel> {
+        let start = Instant::now();
+        let mut scratch = Scratch::new(self.regexes.len());
+        let mut filtered_records = Vec::with_capacity(channels.len());
+
+        for (evaluated, channel) in channels.iter().enumerate() {
+            if evaluated > 0 && evaluated.is_multiple_of(REORDER_INTERVAL) {
+                self.reorder();
+            }
+            scratch.reset();
+            if self.keeps(channel, &mut scratch) {
+                filtered_records.push(channel.clone());
+            }
+        }
+
+        debug!(
+            "Compiled filter plan evaluated channels={} kept={} order=[{}]",
+            channels.len(),
+            filtered_records.len(),
+            self.order
+                .iter()
+                .map(|&i| self.filters[i].name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let filter_stats = self
+            .filters
+            .iter()
+            .zip(&self.stats)
+            .map(|(filter, stats)| {
+                (
+                    filter.id.clone(),
+                    (stats.kept, stats.rejected, stats.elapsed),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let total_filtered = filtered_records.len();
+
+        FilterEngineResult {
+            filtered_records,
+            total_input: channels.len(),
+            total_filtered,
+            execution_time: start.elapsed(),
+            filter_stats,
+        }
+    }
+
+    fn keeps(&mut self, channel: &Channel, scratch: &mut Scratch) -> bool {
+        for position in 0..self.order.len() {
+            let idx = self.order[position];
+            let filter = &self.filters[idx];
+            let started = Instant::now();
+            let kept = if filter.invalid {
+                false
+            } else {
+                let matched = filter
+                    .root
+                    .as_ref()
+                    .is_none_or(|root| self.evaluate(root, channel, scratch));
+                matched != filter.is_inverse
+            };
+
+            let stats = &mut self.stats[idx];
+            stats.elapsed += started.elapsed();
+            if kept {
+                stats.kept += 1;
+            } else {
+                stats.rejected += 1;
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Sort filters by expected cost of rejecting a channel
+    fn reorder(&mut self) {
+        let filters = &self.filters;
+        let stats = &self.stats;
+        self.order.sort_by(|&a, &b| {
+            stats[a]
+                .rank(filters[a].cost)
+                .total_cmp(&stats[b].rank(filters[b].cost))
+                .then(a.cmp(&b))
+        });
+    }
+
+    fn evaluate(&self, node: &PlanNode, channel: &Channel, scratch: &mut Scratch) -> bool {
+        match node {
+            PlanNode::Always => true,
+            PlanNode::All(children) => children
+                .iter()
+                .all(|child| self.evaluate(child, channel, scratch)),
+            PlanNode::Any(children) => children
+                .iter()
+                .any(|child| self.evaluate(child, channel, scratch)),
+            PlanNode::Literal {
+                field,
+                op,
+                value,
+                case_sensitive,
+                negate,
+            } => {
+                let raw = field.value(channel, &self.attributes);
+                let matched = match op {
+                    LiteralOp::Equals if *case_sensitive => raw == value.as_str(),
+                    LiteralOp::Equals => raw.eq_ignore_ascii_case(value),
+                    _ => {
+                        let text = if *case_sensitive {
+                            raw
+                        } else {
+                            scratch.lowered[field.index()]
+                                .get_or_insert_with(|| raw.to_lowercase())
+                                .as_str()
+                        };
+                        match op {
+                            LiteralOp::Contains => text.contains(value.as_str()),
+                            LiteralOp::StartsWith => text.starts_with(value.as_str()),
+                            LiteralOp::EndsWith => text.ends_with(value.as_str()),
+                            LiteralOp::Equals => unreachable!(),
+                        }
+                    }
+                };
+                matched != *negate
+            }
+            PlanNode::Regex {
+                field,
+                slot,
+                precheck,
+                negate,
+            } => {
+                let raw = field.value(channel, &self.attributes);
+                let matched = precheck.may_match(raw) && {
+                    let matches = scratch.regex_matches[field.index()].get_or_insert_with(Vec::new);
+                    if matches.is_empty()
+                        && let Some(regexes) = &self.regexes[field.index()]
+                    {
+                        regexes.matches(raw, matches);
+                    }
+                    matches.get(*slot).copied().unwrap_or(false)
+                };
+                matched != *negate
+            }
+            PlanNode::Compare {
+                field,
+                value,
+                numeric,
+                ordering,
+                or_equal,
+                case_sensitive,
+            } => {
+                let raw = field.value(channel, &self.attributes);
+                let compared = match (raw.parse::<f64>(), numeric) {
+                    (Ok(a), Some(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+                    _ => raw.cmp(value.as_str()),
+                };
+                compared == *ordering
+                    || (*or_equal
+                        && if *case_sensitive {
+                            raw == value.as_str()
+                        } else {
+                            raw.eq_ignore_ascii_case(value)
+                        })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::engines::{ChannelFilteringEngine, RegexEvaluator, StreamFilterProcessor};
+    use crate::utils::regex_preprocessor::RegexPreprocessorConfig;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn preprocessor() -> RegexPreprocessor {
+        RegexPreprocessor::new(RegexPreprocessorConfig::default())
+    }
+
+    fn channel(name: &str, group: &str, tvg_id: Option<&str>) -> Channel {
+        Channel {
+            id: Uuid::new_v4(),
+            source_id: Uuid::new_v4(),
+            tvg_id: tvg_id.map(str::to_string),
+            tvg_name: None,
+            tvg_chno: None,
+            tvg_logo: None,
+            tvg_shift: None,
+            epg_shift: None,
+            group_title: Some(group.to_string()),
+            channel_name: name.to_string(),
+            stream_url: "http://example.com/stream".to_string(),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            extra_attributes: Default::default(),
+        }
+    }
+
+    fn sample_channels() -> Vec<Channel> {
+        let mut channels = Vec::new();
+        for i in 0..1200 {
+            let (group, name) = match i % 5 {
+                0 => ("UK Sports", format!("Sky Sports {i} HD")),
+                1 => ("UK News", format!("BBC News {i}")),
+                2 => ("US Entertainment", format!("HBO {i} 4K")),
+                3 => ("Adult", format!("XXX {i}")),
+                _ => ("Kids", format!("Cartoon {i}")),
+            };
+            channels.push(channel(&name, group, (i % 3 == 0).then_some("id.tv")));
+        }
+        channels
+    }
+
+    fn rules() -> Vec<(&'static str, bool, &'static str)> {
+        vec![
+            (
+                "include-uk",
+                false,
+                r#"group_title starts_with "UK" OR channel_name matches "^HBO""#,
+            ),
+            ("exclude-adult", true, r#"group_title equals "adult""#),
+            (
+                "exclude-4k",
+                true,
+                r#"channel_name matches "4K$" AND tvg_id equals "id.tv""#,
+            ),
+            (
+                "include-not-kids",
+                false,
+                r#"channel_name not contains "cartoon""#,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_plan_matches_processor_engine() {
+        let channels = sample_channels();
+
+        let mut engine = ChannelFilteringEngine::new();
+        let mut builder = FilterPlanBuilder::new(preprocessor());
+        for (id, inverse, expression) in rules() {
+            engine.add_filter_processor(Box::new(
+                StreamFilterProcessor::new(
+                    id.to_string(),
+                    id.to_string(),
+                    inverse,
+                    expression,
+                    RegexEvaluator::new(preprocessor()),
+                )
+                .unwrap(),
+            ));
+            builder
+                .add_filter(id.to_string(), id.to_string(), inverse, expression)
+                .unwrap();
+        }
+
+        let expected = engine.process_records(&channels).unwrap();
+        let actual = builder.build().process_records(&channels);
+
+        let ids = |records: &[Channel]| records.iter().map(|c| c.id).collect::<Vec<_>>();
+        assert!(actual.total_filtered > 0);
+        assert_eq!(
+            ids(&actual.filtered_records),
+            ids(&expected.filtered_records)
+        );
+    }
+
+    #[test]
+    fn test_reorders_by_selectivity() {
+        let mut builder = FilterPlanBuilder::new(preprocessor());
+        builder
+            .add_filter(
+                "keep-all".into(),
+                "keep-all".into(),
+                false,
+                r#"channel_name matches ".*""#,
+            )
+            .unwrap();
+        builder
+            .add_filter(
+                "sports".into(),
+                "sports".into(),
+                false,
+                r#"group_title contains "sports""#,
+            )
+            .unwrap();
+        let mut plan = builder.build();
+
+        let result = plan.process_records(&sample_channels());
+
+        assert_eq!(result.total_filtered, 240);
+        assert_eq!(plan.order, vec![1, 0]);
+        // After reordering the broad filter only sees channels the selective one kept
+        let (kept, rejected, _) = result.filter_stats["keep-all"];
+        assert_eq!(rejected, 0);
+        assert!(kept < 1200);
+    }
+
+    #[test]
+    fn test_empty_expression_keeps_all() {
+        let mut builder = FilterPlanBuilder::new(preprocessor());
+        builder
+            .add_filter("empty".into(), "empty".into(), false, "")
+            .unwrap();
+        let mut plan = builder.build();
+        assert_eq!(
+            plan.process_records(&sample_channels()).total_filtered,
+            1200
+        );
+    }
+
+    #[test]
+    fn test_filters_on_extra_attributes() {
+        let mut channels = vec![channel("News", "UK", None), channel("Sport", "UK", None)];
+        channels[0]
+            .extra_attributes
+            .insert("catchup-days".to_string(), "7".to_string());
+
+        let mut builder = FilterPlanBuilder::new(preprocessor());
+        builder
+            .add_filter(
+                "catchup".into(),
+                "catchup".into(),
+                false,
+                r#"attr.catchup-days matches "^[0-9]+$""#,
+            )
+            .unwrap();
+        let mut plan = builder.build();
+
+        let result = plan.process_records(&channels);
+        assert_eq!(result.total_filtered, 1);
+        assert_eq!(result.filtered_records[0].channel_name, "News");
+    }
+}
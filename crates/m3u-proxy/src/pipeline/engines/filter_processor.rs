@@ -298,6 +298,9 @@ impl StreamFilterProcessor {
         field_name: &str,
         record: &crate::models::Channel,
     ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        if let Some(name) = crate::field_registry::FieldRegistry::extra_attribute_name(field_name) {
+            return Ok(record.extra_attributes.get(name).cloned());
+        }
         let v = match field_name {
             "tvg_id" => record.tvg_id.clone(),
             "tvg_name" => record.tvg_name.clone(),
@@ -574,7 +577,21 @@ impl EpgFilterProcessor {
             "language" => record.language.clone(),
             "rating" => record.rating.clone(),
             "aspect_ratio" => record.aspect_ratio.clone(),
-            // Extend with time fields if needed
+            "start_time" | "programme_start" | "program_start" => Some(
+                record
+                    .start_time
+                    .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            ),
+            "end_time" | "programme_end" | "program_end" => Some(
+                record
+                    .end_time
+                    .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            ),
+            "programme_duration" | "program_duration" | "duration" => Some(
+                (record.end_time - record.start_time)
+                    .num_minutes()
+                    .to_string(),
+            ),
             _ => {
                 return Err(anyhow::anyhow!(
                     "Unknown EPG field referenced in filter: {}",
@@ -822,6 +839,7 @@ mod tests {
             tvg_chno: Some("1".into()),
             tvg_logo: None,
             tvg_shift: None,
+            epg_shift: None,
             group_title: Some(group.to_string()),
             channel_name: name.to_string(),
             stream_url: url.to_string(),
@@ -832,6 +850,7 @@ mod tests {
             last_probed_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            extra_attributes: Default::default(),
         }
     }
 
@@ -1242,6 +1261,86 @@ mod tests {
         // Removed invalid assertion referencing undefined variables (excluded, programs, included)
     }
 
+    #[test]
+    fn test_epg_filter_drops_programmes_by_category_and_title() {
+        // Inverse filters drop the programmes they match
+        let mut adult = EpgFilterProcessor::new(
+            "no_adult".into(),
+            "No Adult".into(),
+            true,
+            r#"programme_category equals "Adult""#,
+            regex_eval(),
+        )
+        .unwrap();
+        let mut infomercials = EpgFilterProcessor::new(
+            "no_shopping".into(),
+            "No Shopping".into(),
+            true,
+            r#"programme_title matches "(?i)^(paid programming|teleshopping)""#,
+            regex_eval(),
+        )
+        .unwrap();
+
+        let late_show = sample_epg_program("Late Show", "ch1", Some("Adult"));
+        let shopping = sample_epg_program("Teleshopping", "ch1", Some("Shopping"));
+        let film = sample_epg_program("Film", "ch1", None);
+        assert!(!adult.process_record(&late_show).unwrap().include_match);
+        assert!(adult.process_record(&film).unwrap().include_match);
+        assert!(
+            !infomercials
+                .process_record(&shopping)
+                .unwrap()
+                .include_match
+        );
+        assert!(infomercials.process_record(&film).unwrap().include_match);
+    }
+
+    #[test]
+    fn test_epg_filter_time_fields() {
+        // Sample programmes run 12:00-13:00 UTC
+        let program = sample_epg_program("Lunchtime News", "ch1", Some("News"));
+
+        let mut long = EpgFilterProcessor::new(
+            "long".into(),
+            "Long".into(),
+            false,
+            r#"programme_duration greater_than_or_equal "60""#,
+            regex_eval(),
+        )
+        .unwrap();
+        assert!(long.process_record(&program).unwrap().include_match);
+
+        let mut short = EpgFilterProcessor::new(
+            "short".into(),
+            "Short".into(),
+            false,
+            r#"program_duration less_than "30""#,
+            regex_eval(),
+        )
+        .unwrap();
+        assert!(!short.process_record(&program).unwrap().include_match);
+
+        let mut afternoon = EpgFilterProcessor::new(
+            "afternoon".into(),
+            "Afternoon".into(),
+            false,
+            r#"start_time greater_than_or_equal "2024-01-01T12:00" AND end_time less_than_or_equal "2024-01-01T18:00""#,
+            regex_eval(),
+        )
+        .unwrap();
+        assert!(afternoon.process_record(&program).unwrap().include_match);
+
+        let mut overnight = EpgFilterProcessor::new(
+            "overnight".into(),
+            "Overnight".into(),
+            false,
+            r#"start_time contains "T0""#,
+            regex_eval(),
+        )
+        .unwrap();
+        assert!(!overnight.process_record(&program).unwrap().include_match);
+    }
+
     #[test]
     fn test_epg_filter_unknown_field_suggestion() {
         // Intentionally misspelled field: program_titel (should suggest programme_title)
@@ -1,4 +1,5 @@
 pub mod data_mapping_engine;
+pub mod filter_plan;
 pub mod filter_processor;
 pub mod rule_processor;
 pub mod testing;
@@ -7,6 +8,7 @@ pub mod validation;
 pub use data_mapping_engine::{
     ChannelDataMappingEngine, DataMappingEngine, EngineResult, ProgramDataMappingEngine,
 };
+pub use filter_plan::{CompiledFilterPlan, FilterPlanBuilder};
 pub use filter_processor::{
     ChannelFilteringEngine, EpgFilterProcessor, EpgFilteringEngine, FilterEngineResult,
     FilterProcessor, FilterResult, FilteringEngine, RegexEvaluator, StreamFilterProcessor,
@@ -1,11 +1,12 @@
 use crate::expression::ExpressionDomain;
 
 use crate::models::{Action, ActionOperator, ExtendedExpression};
-use crate::utils::regex_preprocessor::RegexPreprocessor;
+use crate::utils::regex_preprocessor::{PrecheckTuning, RegexPreprocessor};
 use chrono::{DateTime, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{trace, warn};
 
 /// Type alias for regex evaluation result with captures
@@ -48,11 +49,49 @@ pub trait RuleProcessor<T> {
 /// Shared regex evaluator with preprocessing optimization for data mapping rules
 pub struct RegexEvaluator {
     preprocessor: RegexPreprocessor,
+    /// Per-rule precheck auto-tuning, when enabled
+    tuning: Option<Arc<PrecheckTuning>>,
 }
 
 impl RegexEvaluator {
     pub fn new(preprocessor: RegexPreprocessor) -> Self {
-        Self { preprocessor }
+        Self {
+            preprocessor,
+            tuning: None,
+        }
+    }
+
+    /// Measure precheck effectiveness into `tuning` and let it disable the precheck
+    pub fn with_tuning(mut self, tuning: Arc<PrecheckTuning>) -> Self {
+        self.tuning = Some(tuning);
+        self
+    }
+
+    /// Whether the regex must run, timing it for the auto-tuner while it is sampling
+    fn should_run_regex(
+        &self,
+        pattern: &str,
+        text: &str,
+        context: &str,
+    ) -> (bool, Option<Instant>) {
+        match &self.tuning {
+            Some(tuning) => {
+                let should_run = tuning
+                    .should_run(|| self.preprocessor.should_run_regex(text, pattern, context));
+                let started = (should_run && tuning.is_sampling()).then(Instant::now);
+                (should_run, started)
+            }
+            None => (
+                self.preprocessor.should_run_regex(text, pattern, context),
+                None,
+            ),
+        }
+    }
+
+    fn record_regex(&self, started: Option<Instant>) {
+        if let (Some(tuning), Some(started)) = (&self.tuning, started) {
+            tuning.record_regex(started.elapsed());
+        }
     }
 
     pub fn evaluate_with_preprocessing(
@@ -62,21 +101,24 @@ impl RegexEvaluator {
         context: &str,
     ) -> Result<bool, Box<dyn std::error::Error>> {
         // Use preprocessor to check if regex should run
-        if !self.preprocessor.should_run_regex(text, pattern, context) {
+        let (should_run, started) = self.should_run_regex(pattern, text, context);
+        if !should_run {
             return Ok(false);
         }
 
         // Run the actual regex
-        match Regex::new(pattern) {
-            Ok(regex) => Ok(regex.is_match(text)),
+        let matched = match Regex::new(pattern) {
+            Ok(regex) => regex.is_match(text),
             Err(e) => {
                 warn!(
                     "Invalid regex pattern '{}': {}, falling back to contains",
                     pattern, e
                 );
-                Ok(text.contains(pattern))
+                text.contains(pattern)
             }
-        }
+        };
+        self.record_regex(started);
+        Ok(matched)
     }
 
     pub fn evaluate_with_captures(
@@ -86,21 +128,22 @@ impl RegexEvaluator {
         context: &str,
     ) -> RegexCaptureResult {
         // Use preprocessor to check if regex should run
-        if !self.preprocessor.should_run_regex(text, pattern, context) {
+        let (should_run, started) = self.should_run_regex(pattern, text, context);
+        if !should_run {
             return Ok((false, None));
         }
 
         // Run the actual regex with captures
-        match Regex::new(pattern) {
+        let result = match Regex::new(pattern) {
             Ok(regex) => {
                 if let Some(caps) = regex.captures(text) {
                     let capture_strings: Vec<String> = caps
                         .iter()
                         .map(|m| m.map_or("".to_string(), |m| m.as_str().to_string()))
                         .collect();
-                    Ok((true, Some(capture_strings)))
+                    (true, Some(capture_strings))
                 } else {
-                    Ok((false, None))
+                    (false, None)
                 }
             }
             Err(e) => {
@@ -108,9 +151,11 @@ impl RegexEvaluator {
                     "Invalid regex pattern '{}': {}, falling back to contains",
                     pattern, e
                 );
-                Ok((text.contains(pattern), None))
+                (text.contains(pattern), None)
             }
-        }
+        };
+        self.record_regex(started);
+        Ok(result)
     }
 }
 
@@ -863,6 +908,10 @@ impl StreamRuleProcessor {
         field_name: &str,
         record: &crate::models::Channel,
     ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        if let Some(name) = crate::field_registry::FieldRegistry::extra_attribute_name(field_name) {
+            return Ok(record.extra_attributes.get(name).cloned());
+        }
+
         let registry = crate::field_registry::FieldRegistry::global();
         // Resolve alias → canonical (if unknown returns None)
         let canonical = registry
@@ -903,6 +952,7 @@ impl StreamRuleProcessor {
             "tvg_name" => Ok(record.tvg_name.clone()),
             "tvg_logo" => Ok(record.tvg_logo.clone()),
             "tvg_shift" => Ok(record.tvg_shift.clone()),
+            "epg_shift" => Ok(record.epg_shift.clone()),
             "tvg_chno" => Ok(record.tvg_chno.clone()),
             "group_title" => Ok(record.group_title.clone()),
             "channel_name" => Ok(Some(record.channel_name.clone())),
@@ -927,6 +977,13 @@ impl StreamRuleProcessor {
         value: &str,
         record: &mut crate::models::Channel,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(name) = crate::field_registry::FieldRegistry::extra_attribute_name(field_name) {
+            record
+                .extra_attributes
+                .insert(name.to_string(), value.to_string());
+            return Ok(());
+        }
+
         let registry = crate::field_registry::FieldRegistry::global();
         let canonical = registry
             .canonical_or_none(field_name)
@@ -941,6 +998,11 @@ impl StreamRuleProcessor {
             "tvg_name" => record.tvg_name = Some(value.to_string()),
             "tvg_logo" => record.tvg_logo = Some(value.to_string()),
             "tvg_shift" => record.tvg_shift = Some(value.to_string()),
+            "epg_shift" => {
+                crate::utils::time::parse_epg_shift(value)
+                    .map_err(|e| anyhow::anyhow!("Invalid epg_shift '{}': {}", value, e))?;
+                record.epg_shift = Some(value.to_string())
+            }
             "tvg_chno" => record.tvg_chno = Some(value.to_string()),
             "group_title" => record.group_title = Some(value.to_string()),
             "channel_name" => record.channel_name = value.to_string(),
@@ -956,6 +1018,11 @@ impl StreamRuleProcessor {
         field_name: &str,
         record: &mut crate::models::Channel,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(name) = crate::field_registry::FieldRegistry::extra_attribute_name(field_name) {
+            record.extra_attributes.remove(name);
+            return Ok(());
+        }
+
         let registry = crate::field_registry::FieldRegistry::global();
         let canonical = registry
             .canonical_or_none(field_name)
@@ -969,6 +1036,7 @@ impl StreamRuleProcessor {
             "tvg_name" => record.tvg_name = None,
             "tvg_logo" => record.tvg_logo = None,
             "tvg_shift" => record.tvg_shift = None,
+            "epg_shift" => record.epg_shift = None,
             "tvg_chno" => record.tvg_chno = None,
             "group_title" => record.group_title = None,
             "channel_name" | "stream_url" => {
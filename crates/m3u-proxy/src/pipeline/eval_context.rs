@@ -42,6 +42,7 @@
 //! context for read-only fields. Enforcement occurs earlier (validation)
 //! plus a defensive guard in rule application code.
 //!
+use crate::field_registry::FieldRegistry;
 use crate::models::Channel;
 use crate::pipeline::engines::rule_processor::EpgProgram;
 use std::borrow::Cow;
@@ -113,15 +114,24 @@ impl<'a> ChannelEvalContext<'a> {
                 | "tvg_chno"
                 | "tvg_logo"
                 | "tvg_shift"
+                | "epg_shift"
                 | "group_title"
                 | "channel_name"
                 | "stream_url"
-        )
+        ) || FieldRegistry::extra_attribute_name(canonical).is_some()
     }
 }
 
 impl<'a> FieldValueAccessor for ChannelEvalContext<'a> {
     fn get(&self, canonical: &str) -> Option<Cow<'_, str>> {
+        // Extra EXTINF attributes preserved from the source playlist
+        if let Some(name) = FieldRegistry::extra_attribute_name(canonical) {
+            return self
+                .channel
+                .extra_attributes
+                .get(name)
+                .map(|v| Cow::Borrowed(v.as_str()));
+        }
         // Channel fields (persisted)
         match canonical {
             // Required (always Some)
@@ -153,6 +163,11 @@ impl<'a> FieldValueAccessor for ChannelEvalContext<'a> {
                     return Some(Cow::Borrowed(v.as_str()));
                 }
             }
+            "epg_shift" => {
+                if let Some(v) = &self.channel.epg_shift {
+                    return Some(Cow::Borrowed(v.as_str()));
+                }
+            }
             "group_title" => {
                 if let Some(v) = &self.channel.group_title {
                     return Some(Cow::Borrowed(v.as_str()));
@@ -293,6 +308,7 @@ mod tests {
             tvg_chno: Some("101".into()),
             tvg_logo: Some("http://logo".into()),
             tvg_shift: None,
+            epg_shift: None,
             group_title: Some("GroupA".into()),
             channel_name: "Channel HD".into(),
             stream_url: "http://example/stream.m3u8".into(),
@@ -303,6 +319,7 @@ mod tests {
             last_probed_at: None,
             created_at: Utc.timestamp_opt(1_700_000_000, 0).single().unwrap(),
             updated_at: Utc::now(),
+            extra_attributes: Default::default(),
         }
     }
 
@@ -12,6 +12,7 @@
 //! in favor of a simpler, more maintainable approach using engines that process
 //! records one at a time through ordered rule processors.
 
+pub mod benchmark;
 pub mod core;
 pub mod engines;
 pub mod error;
@@ -24,6 +25,7 @@ pub mod traits;
 // Re-export key types for easier access
 pub use core::{
     PipelineBuilder, PipelineConfig, PipelineOrchestrator, PipelineOrchestratorFactory,
+    PipelineStageEvent,
 };
 pub use engines::{
     ChannelDataMappingEngine, DataMappingEngine, DataMappingTestResult, DataMappingTestService,
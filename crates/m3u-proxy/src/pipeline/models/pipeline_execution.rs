@@ -20,6 +20,27 @@ pub struct PipelineExecution {
     pub stages: HashMap<String, PipelineStageExecution>,
     pub artifacts: ArtifactRegistry,
     pub error_message: Option<String>,
+    /// Stages whose output was reused from the stage cache
+    #[serde(default)]
+    pub cache_hits: Vec<String>,
+    /// Cacheable stages that had to run
+    #[serde(default)]
+    pub cache_misses: Vec<String>,
+    /// New logos left to the background prefetch job because the download budget ran out
+    #[serde(default)]
+    pub logos_deferred: usize,
+    /// Data mapping precheck effectiveness per rule, when auto-tuning is enabled
+    #[serde(default)]
+    pub precheck_effectiveness: Vec<crate::models::PrecheckEffectiveness>,
+    /// Channel number blocks that could not hold all of their group's channels
+    #[serde(default)]
+    pub number_block_overflows: Vec<crate::models::NumberBlockOverflow>,
+    /// EPG programmes deduplicated across overlapping sources by data mapping
+    #[serde(default)]
+    pub epg_dedup: crate::models::EpgDedupStats,
+    /// Resident memory sampled over each stage that ran, keyed by stage id
+    #[serde(default)]
+    pub stage_memory: HashMap<String, crate::utils::StageMemoryUsage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -74,6 +95,13 @@ impl PipelineExecution {
             stages: HashMap::new(),
             artifacts: ArtifactRegistry::new(),
             error_message: None,
+            cache_hits: Vec::new(),
+            cache_misses: Vec::new(),
+            logos_deferred: 0,
+            precheck_effectiveness: Vec::new(),
+            number_block_overflows: Vec::new(),
+            epg_dedup: Default::default(),
+            stage_memory: HashMap::new(),
         }
     }
 
@@ -139,6 +167,32 @@ impl PipelineExecution {
         self.artifacts.get_by_stage(stage_name)
     }
 
+    /// Highest resident memory sampled over all stages, in bytes
+    pub fn peak_memory_bytes(&self) -> Option<u64> {
+        self.stage_memory
+            .values()
+            .map(|usage| usage.peak_bytes)
+            .max()
+    }
+
+    /// Stage durations in milliseconds, for stages that started and completed
+    pub fn stage_durations_ms(&self) -> HashMap<String, u64> {
+        self.stages
+            .iter()
+            .filter_map(|(id, stage)| {
+                let duration = stage.completed_at? - stage.started_at?;
+                Some((id.clone(), duration.num_milliseconds().max(0) as u64))
+            })
+            .collect()
+    }
+
+    /// Number of channels in the published playlist, once the publish stage has run
+    pub fn published_channel_count(&self) -> Option<usize> {
+        self.artifacts
+            .get_latest_by_type(&super::artifacts::ArtifactType::published_m3u())
+            .and_then(|artifact| artifact.record_count)
+    }
+
     /// Get artifacts of a specific type
     pub fn get_artifacts_by_type(
         &self,
@@ -0,0 +1,309 @@
+//! Pipeline artifact inspection
+//!
+//! Persists samples (first/last N records) of each stage's channel and programme artifacts
+//! under `inspection/{proxy_id}/{execution_id}/` in the pipeline file manager, and reads them
+//! back for the inspection API. Samples let a user trace where a channel was dropped or renamed.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sandboxed_file_manager::SandboxedManager;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::pipeline::models::{ContentType, PipelineArtifact};
+
+const INSPECTION_DIR: &str = "inspection";
+
+/// Sample of a single artifact produced by a stage
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArtifactSample {
+    /// Content kind ("Channels" or "EpgPrograms")
+    pub content: String,
+    pub file_path: String,
+    pub record_count: usize,
+    /// First records of the artifact
+    pub first: Vec<serde_json::Value>,
+    /// Last records of the artifact (empty when `first` already covers everything)
+    pub last: Vec<serde_json::Value>,
+}
+
+/// Samples of all artifacts produced by one stage of one pipeline execution
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StageArtifactSamples {
+    pub execution_id: Uuid,
+    pub proxy_id: Uuid,
+    pub stage_index: usize,
+    pub stage_id: String,
+    pub created_at: DateTime<Utc>,
+    pub artifacts: Vec<ArtifactSample>,
+}
+
+/// Stage entry in a generation listing
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StageSampleSummary {
+    pub stage_index: usize,
+    pub stage_id: String,
+    pub channel_count: Option<usize>,
+    pub program_count: Option<usize>,
+}
+
+/// A pipeline execution (generation) with persisted samples
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GenerationSamples {
+    pub execution_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub stages: Vec<StageSampleSummary>,
+}
+
+/// Reads and writes stage artifact samples in the pipeline file manager
+#[derive(Clone)]
+pub struct ArtifactSampleStore {
+    file_manager: SandboxedManager,
+    sample_size: usize,
+}
+
+impl ArtifactSampleStore {
+    pub fn new(file_manager: SandboxedManager, sample_size: usize) -> Self {
+        Self {
+            file_manager,
+            sample_size: sample_size.max(1),
+        }
+    }
+
+    /// Persist samples for the JSONL channel/programme artifacts of a completed stage
+    pub async fn record_stage(
+        &self,
+        proxy_id: Uuid,
+        execution_id: Uuid,
+        stage_index: usize,
+        stage_id: &str,
+        artifacts: &[PipelineArtifact],
+    ) -> Result<()> {
+        let mut samples = Vec::new();
+        for artifact in artifacts {
+            let content = match artifact.artifact_type.content {
+                ContentType::Channels => "Channels",
+                ContentType::EpgPrograms => "EpgPrograms",
+                _ => continue,
+            };
+            let bytes = match self.file_manager.read(&artifact.file_path).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    warn!(
+                        "Skipping artifact sample for {} ({}): {}",
+                        stage_id, artifact.file_path, e
+                    );
+                    continue;
+                }
+            };
+            let (record_count, first, last) =
+                sample_jsonl(&String::from_utf8_lossy(&bytes), self.sample_size);
+            samples.push(ArtifactSample {
+                content: content.to_string(),
+                file_path: artifact.file_path.clone(),
+                record_count,
+                first,
+                last,
+            });
+        }
+
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        let dir = generation_dir(proxy_id, execution_id);
+        self.file_manager.create_dir_all(&dir).await?;
+        let record = StageArtifactSamples {
+            execution_id,
+            proxy_id,
+            stage_index,
+            stage_id: stage_id.to_string(),
+            created_at: Utc::now(),
+            artifacts: samples,
+        };
+        let path = format!("{dir}/{stage_index:02}_{stage_id}.json");
+        self.file_manager
+            .write(&path, serde_json::to_vec(&record)?)
+            .await?;
+        debug!("Persisted stage artifact samples to {}", path);
+        Ok(())
+    }
+
+    /// Generations of a proxy with persisted samples, newest first
+    pub async fn list_generations(&self, proxy_id: Uuid) -> Result<Vec<GenerationSamples>> {
+        let proxy_dir = format!("{INSPECTION_DIR}/{proxy_id}");
+        if !self.file_manager.exists(&proxy_dir).await.unwrap_or(false) {
+            return Ok(Vec::new());
+        }
+
+        let mut generations = Vec::new();
+        for dir in self.file_manager.list_files(&proxy_dir).await? {
+            let Some(execution_id) = dir
+                .rsplit('/')
+                .next()
+                .and_then(|name| Uuid::parse_str(name).ok())
+            else {
+                continue;
+            };
+            let stages = self.list_stages(proxy_id, execution_id).await?;
+            let Some(created_at) = stages.iter().map(|s| s.created_at).min() else {
+                continue;
+            };
+            generations.push(GenerationSamples {
+                execution_id,
+                created_at,
+                stages: stages.iter().map(summarize).collect(),
+            });
+        }
+        generations.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(generations)
+    }
+
+    /// All stage samples of a generation, in stage order
+    pub async fn list_stages(
+        &self,
+        proxy_id: Uuid,
+        execution_id: Uuid,
+    ) -> Result<Vec<StageArtifactSamples>> {
+        let dir = generation_dir(proxy_id, execution_id);
+        if !self.file_manager.exists(&dir).await.unwrap_or(false) {
+            return Ok(Vec::new());
+        }
+
+        let mut stages = Vec::new();
+        for path in self.file_manager.list_files(&dir).await? {
+            if !path.ends_with(".json") {
+                continue;
+            }
+            let bytes = self.file_manager.read(&path).await?;
+            match serde_json::from_slice::<StageArtifactSamples>(&bytes) {
+                Ok(stage) => stages.push(stage),
+                Err(e) => warn!("Ignoring unreadable artifact sample {}: {}", path, e),
+            }
+        }
+        stages.sort_by_key(|s| s.stage_index);
+        Ok(stages)
+    }
+
+    /// Samples of a single stage of a generation
+    pub async fn get_stage(
+        &self,
+        proxy_id: Uuid,
+        execution_id: Uuid,
+        stage_id: &str,
+    ) -> Result<Option<StageArtifactSamples>> {
+        let stages = self
+            .list_stages(proxy_id, execution_id)
+            .await
+            .with_context(|| format!("Failed to read samples of generation {execution_id}"))?;
+        Ok(stages.into_iter().find(|s| s.stage_id == stage_id))
+    }
+}
+
+fn generation_dir(proxy_id: Uuid, execution_id: Uuid) -> String {
+    format!("{INSPECTION_DIR}/{proxy_id}/{execution_id}")
+}
+
+fn summarize(stage: &StageArtifactSamples) -> StageSampleSummary {
+    let count_of = |content: &str| {
+        let counts: Vec<usize> = stage
+            .artifacts
+            .iter()
+            .filter(|a| a.content == content)
+            .map(|a| a.record_count)
+            .collect();
+        (!counts.is_empty()).then(|| counts.iter().sum::<usize>())
+    };
+    StageSampleSummary {
+        stage_index: stage.stage_index,
+        stage_id: stage.stage_id.clone(),
+        channel_count: count_of("Channels"),
+        program_count: count_of("EpgPrograms"),
+    }
+}
+
+/// Count JSONL records and keep the first and last `n` of them
+///
+/// Records that appear in `first` are not repeated in `last`.
+fn sample_jsonl(
+    content: &str,
+    n: usize,
+) -> (usize, Vec<serde_json::Value>, Vec<serde_json::Value>) {
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    let parse = |line: &&str| {
+        serde_json::from_str(line).unwrap_or_else(|_| serde_json::Value::String(line.to_string()))
+    };
+    let first: Vec<serde_json::Value> = lines.iter().take(n).map(parse).collect();
+    let tail_start = lines.len().saturating_sub(n).max(first.len());
+    let last: Vec<serde_json::Value> = lines[tail_start..].iter().map(parse).collect();
+    (lines.len(), first, last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::models::{ArtifactType, ProcessingStage};
+
+    #[test]
+    fn test_sample_jsonl() {
+        let content = (1..=10)
+            .map(|i| format!("{{\"n\":{i}}}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (count, first, last) = sample_jsonl(&content, 3);
+        assert_eq!(count, 10);
+        assert_eq!(first.len(), 3);
+        assert_eq!(last[0]["n"], 8);
+        assert_eq!(last[2]["n"], 10);
+
+        let (count, first, last) = sample_jsonl(&content, 8);
+        assert_eq!(count, 10);
+        assert_eq!(first.len(), 8);
+        assert_eq!(last.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_record_and_read_stage_samples() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .build()
+            .await
+            .unwrap();
+        file_manager
+            .write(
+                "mapped.jsonl",
+                "{\"channel_name\":\"A\"}\n{\"channel_name\":\"B\"}\n",
+            )
+            .await
+            .unwrap();
+
+        let store = ArtifactSampleStore::new(file_manager, 5);
+        let proxy_id = Uuid::new_v4();
+        let execution_id = Uuid::new_v4();
+        let artifact = PipelineArtifact::new(
+            ArtifactType::new(ContentType::Channels, ProcessingStage::Mapped),
+            "mapped.jsonl".to_string(),
+            "data_mapping".to_string(),
+        );
+        store
+            .record_stage(proxy_id, execution_id, 1, "data_mapping", &[artifact])
+            .await
+            .unwrap();
+
+        let generations = store.list_generations(proxy_id).await.unwrap();
+        assert_eq!(generations.len(), 1);
+        assert_eq!(generations[0].stages[0].channel_count, Some(2));
+        assert_eq!(generations[0].stages[0].program_count, None);
+
+        let stage = store
+            .get_stage(proxy_id, execution_id, "data_mapping")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stage.artifacts[0].first[1]["channel_name"], "B");
+        assert!(stage.artifacts[0].last.is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+//! Backup stream planning for M3U generation
+//!
+//! Channels carried by several of a proxy's stream sources are matched by tvg-id (or by
+//! normalised name when there is no tvg-id). The entry from the highest-priority source stays
+//! the primary; entries from other sources become its backups. Source priority is the
+//! proxy's stream source ordering; sources not attached to the proxy rank last.
+
+use crate::models::NumberedChannel;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A playlist entry together with the entries serving as its backups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupStreamGroup {
+    /// Index of the primary channel
+    pub primary: usize,
+    /// Indices of backup channels, highest source priority first
+    pub backups: Vec<usize>,
+}
+
+/// Groups a proxy's channels into primaries and backups
+pub struct BackupStreamPlanner {
+    source_rank: HashMap<Uuid, usize>,
+}
+
+impl BackupStreamPlanner {
+    /// Create a planner; `source_priority` lists stream source ids from highest to lowest priority
+    pub fn new(source_priority: &[Uuid]) -> Self {
+        let mut source_rank = HashMap::new();
+        for (rank, source_id) in source_priority.iter().enumerate() {
+            source_rank.entry(*source_id).or_insert(rank);
+        }
+        Self { source_rank }
+    }
+
+    /// Plan the playlist; groups are ordered by the position of their primary channel
+    ///
+    /// Every channel appears exactly once, either as a primary or as a backup. Duplicates
+    /// within the primary's own source are left as separate entries.
+    pub fn plan(&self, channels: &[NumberedChannel]) -> Vec<BackupStreamGroup> {
+        let mut by_key: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut groups = Vec::new();
+        for (idx, numbered) in channels.iter().enumerate() {
+            match channel_key(numbered) {
+                Some(key) => by_key.entry(key).or_default().push(idx),
+                None => groups.push(BackupStreamGroup {
+                    primary: idx,
+                    backups: Vec::new(),
+                }),
+            }
+        }
+
+        for mut indices in by_key.into_values() {
+            indices.sort_by_key(|&idx| (self.rank(&channels[idx].channel.source_id), idx));
+            let primary = indices[0];
+            let primary_source = channels[primary].channel.source_id;
+            let mut backups = Vec::new();
+            for &idx in &indices[1..] {
+                if channels[idx].channel.source_id == primary_source {
+                    groups.push(BackupStreamGroup {
+                        primary: idx,
+                        backups: Vec::new(),
+                    });
+                } else {
+                    backups.push(idx);
+                }
+            }
+            groups.push(BackupStreamGroup { primary, backups });
+        }
+
+        groups.sort_by_key(|group| group.primary);
+        groups
+    }
+
+    fn rank(&self, source_id: &Uuid) -> usize {
+        self.source_rank
+            .get(source_id)
+            .copied()
+            .unwrap_or(usize::MAX)
+    }
+}
+
+/// Identity of a channel across sources; `None` when it has neither tvg-id nor name
+fn channel_key(numbered: &NumberedChannel) -> Option<String> {
+    let channel = &numbered.channel;
+    // Shifted channels are distinct streams, never backups of the unshifted one
+    let shift = channel.effective_epg_shift().unwrap_or("");
+    if let Some(tvg_id) = channel
+        .tvg_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+    {
+        return Some(format!("id:{}{}", tvg_id.to_lowercase(), shift));
+    }
+
+    let name = channel
+        .channel_name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    (!name.is_empty()).then(|| format!("name:{name}{shift}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Channel, ChannelNumberAssignmentType};
+    use chrono::Utc;
+
+    fn channel(source_id: Uuid, tvg_id: Option<&str>, name: &str) -> NumberedChannel {
+        NumberedChannel {
+            channel: Channel {
+                id: Uuid::new_v4(),
+                source_id,
+                tvg_id: tvg_id.map(str::to_string),
+                tvg_name: None,
+                tvg_chno: None,
+                tvg_logo: None,
+                tvg_shift: None,
+                epg_shift: None,
+                group_title: None,
+                channel_name: name.to_string(),
+                stream_url: "http://example.com/stream".to_string(),
+                video_codec: None,
+                audio_codec: None,
+                resolution: None,
+                probe_method: None,
+                last_probed_at: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                extra_attributes: Default::default(),
+            },
+            assigned_number: 0,
+            assignment_type: ChannelNumberAssignmentType::Sequential,
+        }
+    }
+
+    #[test]
+    fn test_primary_follows_source_priority() {
+        let high = Uuid::new_v4();
+        let low = Uuid::new_v4();
+        let channels = vec![
+            channel(low, Some("bbc1.uk"), "BBC One"),
+            channel(high, Some("BBC1.uk"), "BBC One HD"),
+            channel(low, Some("itv1.uk"), "ITV1"),
+        ];
+
+        let groups = BackupStreamPlanner::new(&[high, low]).plan(&channels);
+
+        assert_eq!(
+            groups,
+            vec![
+                BackupStreamGroup {
+                    primary: 1,
+                    backups: vec![0],
+                },
+                BackupStreamGroup {
+                    primary: 2,
+                    backups: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matches_by_name_without_tvg_id() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let channels = vec![
+            channel(a, None, "Sky  News"),
+            channel(b, None, "sky news"),
+            channel(b, Some(""), "Other"),
+        ];
+
+        let groups = BackupStreamPlanner::new(&[a, b]).plan(&channels);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].backups, vec![1]);
+    }
+
+    #[test]
+    fn test_same_source_duplicates_stay_separate() {
+        let a = Uuid::new_v4();
+        let channels = vec![
+            channel(a, Some("bbc1.uk"), "BBC One"),
+            channel(a, Some("bbc1.uk"), "BBC One"),
+        ];
+
+        let groups = BackupStreamPlanner::new(&[a]).plan(&channels);
+
+        assert_eq!(groups.len(), 2);
+        assert!(groups.iter().all(|g| g.backups.is_empty()));
+    }
+}
@@ -0,0 +1,365 @@
+//! EPG programme deduplication across overlapping sources
+//!
+//! Runs in the data mapping stage before [`EpgProgramMerger`](super::EpgProgramMerger).
+//! Programmes identical to one already kept are removed, and programmes of lower-priority
+//! sources that run into time covered by higher-priority sources are trimmed to the uncovered
+//! part. Programmes a higher-priority source covers completely are left alone: they are slot
+//! conflicts the merge strategy resolves (and may take metadata from).
+
+use crate::models::EpgDedupStats;
+use crate::pipeline::engines::EpgProgram;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::SourcedProgram;
+
+/// Trimmed programmes shorter than this are dropped rather than kept as slivers
+const MIN_TRIMMED_DURATION: Duration = Duration::minutes(1);
+
+type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// Removes duplicate programmes and trims overlaps between EPG sources by source priority
+pub struct EpgProgramDeduplicator {
+    source_rank: HashMap<Uuid, usize>,
+}
+
+impl EpgProgramDeduplicator {
+    /// Create a deduplicator; `source_priority` lists EPG source ids from highest to lowest
+    /// priority, and sources not listed rank last
+    pub fn new(source_priority: &[Uuid]) -> Self {
+        let mut source_rank = HashMap::new();
+        for (rank, source_id) in source_priority.iter().enumerate() {
+            source_rank.entry(*source_id).or_insert(rank);
+        }
+        Self { source_rank }
+    }
+
+    /// Deduplicate programmes channel by channel; the result is ordered by start time
+    pub fn dedup(&self, programs: Vec<SourcedProgram>) -> (Vec<SourcedProgram>, EpgDedupStats) {
+        let mut by_channel: HashMap<String, Vec<SourcedProgram>> = HashMap::new();
+        for sourced in programs {
+            by_channel
+                .entry(sourced.program.channel_id.clone())
+                .or_default()
+                .push(sourced);
+        }
+
+        let mut stats = EpgDedupStats::default();
+        let mut kept: Vec<SourcedProgram> = by_channel
+            .into_values()
+            .flat_map(|channel_programs| self.dedup_channel(channel_programs, &mut stats))
+            .collect();
+        kept.sort_by(|a, b| {
+            a.program
+                .start_time
+                .cmp(&b.program.start_time)
+                .then_with(|| a.program.channel_id.cmp(&b.program.channel_id))
+        });
+        (kept, stats)
+    }
+
+    fn dedup_channel(
+        &self,
+        mut programs: Vec<SourcedProgram>,
+        stats: &mut EpgDedupStats,
+    ) -> Vec<SourcedProgram> {
+        programs.sort_by_key(|s| {
+            (
+                self.rank(&s.source_id),
+                s.source_id,
+                s.program.start_time,
+                s.program.end_time,
+            )
+        });
+
+        // Highest-priority copies come first, so the first of a set of duplicates is kept
+        let mut seen = HashSet::new();
+        programs.retain(|sourced| {
+            let unique = seen.insert(content_key(&sourced.program));
+            if !unique {
+                stats.duplicates_removed += 1;
+            }
+            unique
+        });
+
+        let mut kept = Vec::with_capacity(programs.len());
+        let mut coverage: Vec<Interval> = Vec::new();
+        let mut source_start = 0;
+        while source_start < programs.len() {
+            let source_id = programs[source_start].source_id;
+            let source_end = programs[source_start..]
+                .iter()
+                .position(|s| s.source_id != source_id)
+                .map_or(programs.len(), |offset| source_start + offset);
+
+            // A source is only trimmed against higher-priority sources, never against itself
+            let mut added = Vec::new();
+            for sourced in &programs[source_start..source_end] {
+                let program = &sourced.program;
+                let uncovered = uncovered_segments(&coverage, program.start_time, program.end_time);
+                // Fully covered programmes are left whole for the merge strategy
+                let trimmed = match uncovered.as_slice() {
+                    [] => None,
+                    [(start, end)] if *start == program.start_time && *end == program.end_time => {
+                        None
+                    }
+                    _ => Some(longest(&uncovered)),
+                };
+
+                match trimmed {
+                    None => {
+                        added.push((program.start_time, program.end_time));
+                        kept.push(sourced.clone());
+                    }
+                    Some((start, end)) if end - start < MIN_TRIMMED_DURATION => {
+                        stats.overlaps_dropped += 1;
+                    }
+                    Some((start, end)) => {
+                        stats.overlaps_trimmed += 1;
+                        added.push((start, end));
+                        kept.push(SourcedProgram {
+                            source_id,
+                            program: EpgProgram {
+                                start_time: start,
+                                end_time: end,
+                                ..program.clone()
+                            },
+                        });
+                    }
+                }
+            }
+            coverage.extend(added);
+            coverage = merge_intervals(coverage);
+            source_start = source_end;
+        }
+        kept
+    }
+
+    fn rank(&self, source_id: &Uuid) -> usize {
+        self.source_rank
+            .get(source_id)
+            .copied()
+            .unwrap_or(usize::MAX)
+    }
+}
+
+/// Everything that identifies a programme's content, excluding its database id
+fn content_key(program: &EpgProgram) -> impl std::hash::Hash + Eq + use<> {
+    (
+        program.channel_id.clone(),
+        program.start_time,
+        program.end_time,
+        program.title.clone(),
+        program.description.clone(),
+        program.program_icon.clone(),
+        program.program_category.clone(),
+        program.subtitles.clone(),
+        (
+            program.episode_num.clone(),
+            program.season_num.clone(),
+            program.language.clone(),
+            program.rating.clone(),
+            program.aspect_ratio.clone(),
+        ),
+    )
+}
+
+/// Parts of `[start, end)` outside a sorted, disjoint coverage list
+fn uncovered_segments(
+    coverage: &[Interval],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<Interval> {
+    let mut segments = Vec::new();
+    let mut cursor = start;
+    for (covered_start, covered_end) in coverage {
+        if *covered_end <= cursor {
+            continue;
+        }
+        if *covered_start >= end {
+            break;
+        }
+        if *covered_start > cursor {
+            segments.push((cursor, *covered_start));
+        }
+        cursor = cursor.max(*covered_end);
+        if cursor >= end {
+            break;
+        }
+    }
+    if cursor < end {
+        segments.push((cursor, end));
+    }
+    segments
+}
+
+/// The longest segment; ties go to the earliest
+fn longest(segments: &[Interval]) -> Interval {
+    let mut best = segments[0];
+    for segment in &segments[1..] {
+        if segment.1 - segment.0 > best.1 - best.0 {
+            best = *segment;
+        }
+    }
+    best
+}
+
+fn merge_intervals(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.sort();
+    let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, hour, minute, 0).unwrap()
+    }
+
+    fn program(source_id: Uuid, title: &str, start: (u32, u32), end: (u32, u32)) -> SourcedProgram {
+        SourcedProgram {
+            source_id,
+            program: EpgProgram {
+                id: Uuid::new_v4().to_string(),
+                channel_id: "bbc1".to_string(),
+                channel_name: "BBC One".to_string(),
+                title: title.to_string(),
+                description: None,
+                program_icon: None,
+                start_time: at(start.0, start.1),
+                end_time: at(end.0, end.1),
+                program_category: None,
+                subtitles: None,
+                episode_num: None,
+                season_num: None,
+                language: None,
+                rating: None,
+                aspect_ratio: None,
+            },
+        }
+    }
+
+    fn slots(programs: &[SourcedProgram]) -> Vec<(&str, DateTime<Utc>, DateTime<Utc>)> {
+        programs
+            .iter()
+            .map(|s| {
+                (
+                    s.program.title.as_str(),
+                    s.program.start_time,
+                    s.program.end_time,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_duplicates_keep_highest_priority_copy() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let programs = vec![
+            program(b, "News", (1, 0), (2, 0)),
+            program(a, "News", (1, 0), (2, 0)),
+            program(a, "News", (1, 0), (2, 0)),
+        ];
+
+        let (kept, stats) = EpgProgramDeduplicator::new(&[a, b]).dedup(programs);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].source_id, a);
+        assert_eq!(stats.duplicates_removed, 2);
+    }
+
+    #[test]
+    fn test_programmes_with_different_metadata_are_not_duplicates() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut rich = program(b, "News", (1, 0), (2, 0));
+        rich.program.description = Some("Headlines".to_string());
+        let programs = vec![program(a, "News", (1, 0), (2, 0)), rich];
+
+        let (kept, stats) = EpgProgramDeduplicator::new(&[a, b]).dedup(programs);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(stats, EpgDedupStats::default());
+    }
+
+    #[test]
+    fn test_lower_priority_overlaps_are_trimmed() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let programs = vec![
+            program(a, "A news", (1, 0), (2, 0)),
+            program(b, "B film", (1, 30), (3, 0)),
+            program(b, "B early", (0, 0), (1, 15)),
+        ];
+
+        let (kept, stats) = EpgProgramDeduplicator::new(&[a, b]).dedup(programs);
+        assert_eq!(
+            slots(&kept),
+            vec![
+                ("B early", at(0, 0), at(1, 0)),
+                ("A news", at(1, 0), at(2, 0)),
+                ("B film", at(2, 0), at(3, 0)),
+            ]
+        );
+        assert_eq!(stats.overlaps_trimmed, 2);
+    }
+
+    #[test]
+    fn test_split_programme_keeps_longest_uncovered_part() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let programs = vec![
+            program(a, "A news", (2, 0), (3, 0)),
+            program(b, "B marathon", (1, 30), (5, 0)),
+        ];
+
+        let (kept, _) = EpgProgramDeduplicator::new(&[a, b]).dedup(programs);
+        assert_eq!(slots(&kept)[1], ("B marathon", at(3, 0), at(5, 0)));
+    }
+
+    #[test]
+    fn test_covered_programmes_are_left_for_the_merge_strategy() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let programs = vec![
+            program(a, "A news", (1, 0), (2, 0)),
+            program(b, "B news", (1, 0), (2, 0)),
+            program(b, "B sliver", (1, 59), (2, 0)),
+        ];
+
+        let (kept, stats) = EpgProgramDeduplicator::new(&[a, b]).dedup(programs);
+        assert_eq!(kept.len(), 3);
+        assert_eq!(stats.overlaps_trimmed, 0);
+    }
+
+    #[test]
+    fn test_slivers_are_dropped() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut programs = vec![
+            program(a, "A news", (1, 0), (2, 0)),
+            program(b, "B news", (1, 0), (2, 0)),
+        ];
+        programs[1].program.end_time = at(2, 0) + Duration::seconds(30);
+
+        let (kept, stats) = EpgProgramDeduplicator::new(&[a, b]).dedup(programs);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(stats.overlaps_dropped, 1);
+    }
+
+    #[test]
+    fn test_overlaps_within_a_source_are_kept() {
+        let a = Uuid::new_v4();
+        let programs = vec![
+            program(a, "Overlap 1", (1, 0), (3, 0)),
+            program(a, "Overlap 2", (2, 0), (4, 0)),
+        ];
+
+        let (kept, stats) = EpgProgramDeduplicator::new(&[a]).dedup(programs);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(stats, EpgDedupStats::default());
+    }
+}
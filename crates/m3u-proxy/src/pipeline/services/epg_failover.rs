@@ -0,0 +1,279 @@
+//! EPG source failover for stale guides
+//!
+//! A proxy's EPG sources are checked against the `epg_failover` staleness window. Stale
+//! sources are moved behind fresh ones in the merge priority (the proxy's preferred fallback
+//! taking their place), so channels also covered by a fresh source take its programmes.
+//! The same plan backs the degradation flags of the proxy status API.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::EpgFailoverConfig;
+use crate::database::repositories::ProxySettingsSeaOrmRepository;
+use crate::entities::{epg_sources, prelude::*, proxy_epg_sources};
+use crate::models::proxy_settings::EpgFallbacks;
+
+/// Ingestion state of an EPG source
+#[derive(Debug, Clone)]
+pub struct EpgSourceState {
+    pub epg_source_id: Uuid,
+    pub name: String,
+    pub last_ingested_at: Option<DateTime<Utc>>,
+}
+
+/// Freshness of one of a proxy's EPG sources
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EpgSourceFreshness {
+    pub epg_source_id: Uuid,
+    pub epg_source_name: String,
+    pub last_ingested_at: Option<DateTime<Utc>>,
+    /// No successful ingestion within the staleness window
+    pub stale: bool,
+    /// Fresh source whose programmes replace this one's for shared channels
+    pub fallback_source_id: Option<Uuid>,
+}
+
+/// EPG source priority for a proxy after failover
+#[derive(Debug, Clone)]
+pub struct EpgFailoverPlan {
+    /// EPG source ids from highest to lowest merge priority
+    pub source_priority: Vec<Uuid>,
+    /// The proxy's own EPG sources, in the proxy's order
+    pub sources: Vec<EpgSourceFreshness>,
+}
+
+impl EpgFailoverPlan {
+    /// Whether any of the proxy's EPG sources is stale
+    pub fn is_degraded(&self) -> bool {
+        self.sources.iter().any(|s| s.stale)
+    }
+
+    /// Build the plan for a proxy's sources (in priority order)
+    ///
+    /// `fallback_states` holds the state of preferred fallback sources that are not attached
+    /// to the proxy. With failover disabled the priority is the proxy's order unchanged.
+    pub fn build(
+        config: &EpgFailoverConfig,
+        fallbacks: &EpgFallbacks,
+        proxy_sources: &[EpgSourceState],
+        fallback_states: &HashMap<Uuid, EpgSourceState>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let window = chrono::Duration::from_std(config.staleness_window_duration())
+            .unwrap_or_else(|_| chrono::Duration::hours(72));
+        let is_stale = |state: &EpgSourceState| {
+            state
+                .last_ingested_at
+                .is_none_or(|ingested| now - ingested > window)
+        };
+        let is_fresh = |id: Uuid| {
+            proxy_sources
+                .iter()
+                .chain(fallback_states.values())
+                .find(|s| s.epg_source_id == id)
+                .is_some_and(|s| !is_stale(s))
+        };
+
+        let mut source_priority = Vec::with_capacity(proxy_sources.len());
+        let mut demoted = Vec::new();
+        let mut sources = Vec::with_capacity(proxy_sources.len());
+
+        for state in proxy_sources {
+            let stale = is_stale(state);
+            let fallback_source_id = if stale {
+                // Preferred fallback first, then the next fresh source of the proxy
+                fallbacks
+                    .fallback_for(state.epg_source_id)
+                    .filter(|id| is_fresh(*id))
+                    .or_else(|| {
+                        proxy_sources
+                            .iter()
+                            .find(|s| s.epg_source_id != state.epg_source_id && !is_stale(s))
+                            .map(|s| s.epg_source_id)
+                    })
+            } else {
+                None
+            };
+
+            if !config.enabled || !stale {
+                push_unique(&mut source_priority, state.epg_source_id);
+            } else {
+                if let Some(preferred) = fallbacks
+                    .fallback_for(state.epg_source_id)
+                    .filter(|id| is_fresh(*id))
+                {
+                    push_unique(&mut source_priority, preferred);
+                }
+                demoted.push(state.epg_source_id);
+            }
+
+            sources.push(EpgSourceFreshness {
+                epg_source_id: state.epg_source_id,
+                epg_source_name: state.name.clone(),
+                last_ingested_at: state.last_ingested_at,
+                stale,
+                fallback_source_id,
+            });
+        }
+        for id in demoted {
+            push_unique(&mut source_priority, id);
+        }
+
+        Self {
+            source_priority,
+            sources,
+        }
+    }
+
+    /// Load the plan for a proxy from the database
+    pub async fn load(
+        connection: &Arc<DatabaseConnection>,
+        config: &EpgFailoverConfig,
+        proxy_id: Uuid,
+    ) -> Result<Self> {
+        let attached = ProxyEpgSources::find()
+            .filter(proxy_epg_sources::Column::ProxyId.eq(proxy_id))
+            .order_by_asc(proxy_epg_sources::Column::PriorityOrder)
+            .all(&**connection)
+            .await?;
+        let fallbacks = ProxySettingsSeaOrmRepository::new(connection.clone())
+            .get::<EpgFallbacks>(&proxy_id)
+            .await?
+            .unwrap_or_default();
+
+        let mut wanted: Vec<Uuid> = attached.iter().map(|a| a.epg_source_id).collect();
+        for fallback in &fallbacks.fallbacks {
+            push_unique(&mut wanted, fallback.fallback_source_id);
+        }
+        let mut states: HashMap<Uuid, EpgSourceState> = EpgSources::find()
+            .filter(epg_sources::Column::DeletedAt.is_null())
+            .filter(epg_sources::Column::Id.is_in(wanted))
+            .all(&**connection)
+            .await?
+            .into_iter()
+            .map(|m| {
+                (
+                    m.id,
+                    EpgSourceState {
+                        epg_source_id: m.id,
+                        name: m.name,
+                        last_ingested_at: m.last_ingested_at,
+                    },
+                )
+            })
+            .collect();
+
+        let proxy_sources: Vec<EpgSourceState> = attached
+            .iter()
+            .filter_map(|a| states.remove(&a.epg_source_id))
+            .collect();
+        Ok(Self::build(
+            config,
+            &fallbacks,
+            &proxy_sources,
+            &states,
+            Utc::now(),
+        ))
+    }
+}
+
+fn push_unique(ids: &mut Vec<Uuid>, id: Uuid) {
+    if !ids.contains(&id) {
+        ids.push(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::proxy_settings::EpgFallback;
+
+    fn state(name: &str, hours_ago: Option<i64>, now: DateTime<Utc>) -> EpgSourceState {
+        EpgSourceState {
+            epg_source_id: Uuid::new_v4(),
+            name: name.to_string(),
+            last_ingested_at: hours_ago.map(|h| now - chrono::Duration::hours(h)),
+        }
+    }
+
+    #[test]
+    fn test_stale_sources_are_demoted() {
+        let now = Utc::now();
+        let primary = state("primary", Some(100), now);
+        let secondary = state("secondary", Some(1), now);
+        let config = EpgFailoverConfig::default();
+
+        let plan = EpgFailoverPlan::build(
+            &config,
+            &EpgFallbacks::default(),
+            &[primary.clone(), secondary.clone()],
+            &HashMap::new(),
+            now,
+        );
+        assert!(plan.is_degraded());
+        assert_eq!(
+            plan.source_priority,
+            vec![secondary.epg_source_id, primary.epg_source_id]
+        );
+        assert_eq!(
+            plan.sources[0].fallback_source_id,
+            Some(secondary.epg_source_id)
+        );
+        assert!(!plan.sources[1].stale);
+
+        let disabled = EpgFailoverConfig {
+            enabled: false,
+            ..EpgFailoverConfig::default()
+        };
+        let plan = EpgFailoverPlan::build(
+            &disabled,
+            &EpgFallbacks::default(),
+            &[primary.clone(), secondary.clone()],
+            &HashMap::new(),
+            now,
+        );
+        assert!(plan.is_degraded());
+        assert_eq!(plan.source_priority[0], primary.epg_source_id);
+    }
+
+    #[test]
+    fn test_configured_fallback_takes_stale_slot() {
+        let now = Utc::now();
+        let primary = state("primary", None, now);
+        let secondary = state("secondary", Some(1), now);
+        let alternate = state("alternate", Some(2), now);
+        let fallbacks = EpgFallbacks {
+            fallbacks: vec![EpgFallback {
+                source_id: primary.epg_source_id,
+                fallback_source_id: alternate.epg_source_id,
+            }],
+        };
+
+        let fallback_states = HashMap::from([(alternate.epg_source_id, alternate.clone())]);
+        let plan = EpgFailoverPlan::build(
+            &EpgFailoverConfig::default(),
+            &fallbacks,
+            &[primary.clone(), secondary.clone()],
+            &fallback_states,
+            now,
+        );
+        assert_eq!(
+            plan.source_priority,
+            vec![
+                alternate.epg_source_id,
+                secondary.epg_source_id,
+                primary.epg_source_id
+            ]
+        );
+        assert_eq!(
+            plan.sources[0].fallback_source_id,
+            Some(alternate.epg_source_id)
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! Synthetic programmes for channels without guide data
+//!
+//! Clients render channels without programmes as empty guide rows. The gap filler covers
+//! every gap of at least `min_gap` in a window around generation time with block
+//! programmes aligned to `block_length` boundaries (so a 1h block always runs on the
+//! hour), titled from the configured template. Gaps are computed per EPG channel id,
+//! so shifted copies of a channel receive the same filler as the original.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+
+use crate::config::EpgGapFillerConfig;
+use crate::pipeline::engines::rule_processor::EpgProgram;
+
+/// Prefix of the id of every synthetic programme
+pub const GAP_FILLER_ID_PREFIX: &str = "gap-filler";
+
+/// A guide channel that may need filling
+#[derive(Debug, Clone)]
+pub struct GapFillChannel {
+    /// EPG channel id programmes are matched on (the channel's tvg_id)
+    pub epg_channel_id: String,
+    pub channel_name: String,
+    pub group_title: Option<String>,
+}
+
+/// Computes filler programmes from the `epg_gap_filler` configuration
+#[derive(Debug, Clone)]
+pub struct EpgGapFiller {
+    config: EpgGapFillerConfig,
+    block_length: Duration,
+    lookback: Duration,
+    lookahead: Duration,
+    min_gap: Duration,
+}
+
+impl EpgGapFiller {
+    pub fn new(config: EpgGapFillerConfig) -> Self {
+        let to_chrono =
+            |d: std::time::Duration| Duration::from_std(d).unwrap_or_else(|_| Duration::seconds(0));
+        Self {
+            block_length: to_chrono(config.block_length_duration()),
+            lookback: to_chrono(config.lookback_duration()),
+            lookahead: to_chrono(config.lookahead_duration()),
+            min_gap: to_chrono(config.min_gap_duration()),
+            config,
+        }
+    }
+
+    /// Filler programmes for the gaps of each channel within the window around `now`
+    pub fn fill(
+        &self,
+        channels: &[GapFillChannel],
+        programs: &[EpgProgram],
+        now: DateTime<Utc>,
+    ) -> Vec<EpgProgram> {
+        let window_start = self.align_down(now - self.lookback);
+        let window_end = now + self.lookahead;
+
+        let mut intervals: HashMap<&str, Vec<(DateTime<Utc>, DateTime<Utc>)>> = HashMap::new();
+        for program in programs {
+            intervals
+                .entry(program.channel_id.as_str())
+                .or_default()
+                .push((program.start_time, program.end_time));
+        }
+
+        let mut seen = HashSet::new();
+        let mut filler = Vec::new();
+        for channel in channels {
+            if !seen.insert(channel.epg_channel_id.as_str()) {
+                continue;
+            }
+            let mut covered = intervals
+                .get(channel.epg_channel_id.as_str())
+                .cloned()
+                .unwrap_or_default();
+            if self.config.empty_channels_only && !covered.is_empty() {
+                continue;
+            }
+            covered.sort();
+
+            let mut cursor = window_start;
+            for (start, end) in covered {
+                if cursor >= window_end {
+                    break;
+                }
+                if start > cursor {
+                    self.fill_gap(channel, cursor, start.min(window_end), &mut filler);
+                }
+                cursor = cursor.max(end);
+            }
+            if cursor < window_end {
+                self.fill_gap(channel, cursor, window_end, &mut filler);
+            }
+        }
+        filler
+    }
+
+    /// Add blocks covering `start..end` when the gap is at least `min_gap` long
+    fn fill_gap(
+        &self,
+        channel: &GapFillChannel,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        filler: &mut Vec<EpgProgram>,
+    ) {
+        if end - start < self.min_gap {
+            return;
+        }
+        let mut block_start = start;
+        while block_start < end {
+            let block_end = (self.align_down(block_start) + self.block_length).min(end);
+            filler.push(self.program(channel, block_start, block_end));
+            block_start = block_end;
+        }
+    }
+
+    fn program(
+        &self,
+        channel: &GapFillChannel,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> EpgProgram {
+        EpgProgram {
+            id: format!(
+                "{GAP_FILLER_ID_PREFIX}:{}:{}",
+                channel.epg_channel_id,
+                start_time.timestamp()
+            ),
+            channel_id: channel.epg_channel_id.clone(),
+            channel_name: channel.channel_name.clone(),
+            title: render_template(&self.config.title_template, channel),
+            description: self
+                .config
+                .description_template
+                .as_deref()
+                .map(|template| render_template(template, channel)),
+            program_icon: None,
+            start_time,
+            end_time,
+            program_category: None,
+            subtitles: None,
+            episode_num: None,
+            season_num: None,
+            language: None,
+            rating: None,
+            aspect_ratio: None,
+        }
+    }
+
+    /// Start of the block containing `time`
+    fn align_down(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let block_seconds = self.block_length.num_seconds().max(1);
+        let aligned = time.timestamp().div_euclid(block_seconds) * block_seconds;
+        DateTime::from_timestamp(aligned, 0).unwrap_or(time)
+    }
+}
+
+/// Replace `{channel_name}`, `{channel_id}` and `{group}` in a template
+fn render_template(template: &str, channel: &GapFillChannel) -> String {
+    template
+        .replace("{channel_name}", &channel.channel_name)
+        .replace("{channel_id}", &channel.epg_channel_id)
+        .replace(
+            "{group}",
+            channel.group_title.as_deref().unwrap_or_default(),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 10, 16, hour, minute, 0).unwrap()
+    }
+
+    fn channel(id: &str) -> GapFillChannel {
+        GapFillChannel {
+            epg_channel_id: id.to_string(),
+            channel_name: format!("{id} TV"),
+            group_title: Some("News".to_string()),
+        }
+    }
+
+    fn program(channel_id: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> EpgProgram {
+        EpgProgram {
+            id: format!("{channel_id}-{}", start.timestamp()),
+            channel_id: channel_id.to_string(),
+            channel_name: channel_id.to_string(),
+            title: "Real".to_string(),
+            description: None,
+            program_icon: None,
+            start_time: start,
+            end_time: end,
+            program_category: None,
+            subtitles: None,
+            episode_num: None,
+            season_num: None,
+            language: None,
+            rating: None,
+            aspect_ratio: None,
+        }
+    }
+
+    fn filler(lookback: &str, lookahead: &str) -> EpgGapFiller {
+        EpgGapFiller::new(EpgGapFillerConfig {
+            enabled: true,
+            lookback: lookback.to_string(),
+            lookahead: lookahead.to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_fills_empty_channel_with_aligned_blocks() {
+        let programs = filler("1h", "2h").fill(&[channel("a")], &[], at(10, 30));
+
+        let spans: Vec<_> = programs
+            .iter()
+            .map(|p| (p.start_time, p.end_time))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (at(9, 0), at(10, 0)),
+                (at(10, 0), at(11, 0)),
+                (at(11, 0), at(12, 0)),
+                (at(12, 0), at(12, 30)),
+            ]
+        );
+        assert_eq!(programs[0].title, "a TV programming");
+        assert_eq!(programs[0].channel_id, "a");
+        assert!(programs[0].id.starts_with(GAP_FILLER_ID_PREFIX));
+    }
+
+    #[test]
+    fn test_fills_only_gaps_between_programmes() {
+        let programs = vec![
+            program("a", at(8, 0), at(10, 15)),
+            program("a", at(11, 0), at(13, 0)),
+            // Shorter than min_gap: left alone
+            program("a", at(13, 2), at(14, 0)),
+        ];
+        let filled = filler("1h", "4h").fill(&[channel("a")], &programs, at(10, 0));
+
+        let spans: Vec<_> = filled.iter().map(|p| (p.start_time, p.end_time)).collect();
+        assert_eq!(spans, vec![(at(10, 15), at(11, 0))]);
+    }
+
+    #[test]
+    fn test_empty_channels_only() {
+        let gap_filler = EpgGapFiller::new(EpgGapFillerConfig {
+            enabled: true,
+            lookback: "0s".to_string(),
+            lookahead: "1h".to_string(),
+            empty_channels_only: true,
+            ..Default::default()
+        });
+        let programs = vec![program("a", at(10, 0), at(10, 30))];
+        let filled = gap_filler.fill(&[channel("a"), channel("b")], &programs, at(10, 0));
+
+        assert_eq!(filled.len(), 1);
+        assert_eq!(filled[0].channel_id, "b");
+    }
+
+    #[test]
+    fn test_templates_and_duplicate_channels() {
+        let gap_filler = EpgGapFiller::new(EpgGapFillerConfig {
+            enabled: true,
+            title_template: "{group}: {channel_name}".to_string(),
+            description_template: Some("No guide data for {channel_id}".to_string()),
+            lookback: "0s".to_string(),
+            lookahead: "1h".to_string(),
+            ..Default::default()
+        });
+        // Shifted copies share an EPG channel id and are filled once
+        let filled = gap_filler.fill(&[channel("a"), channel("a")], &[], at(10, 0));
+
+        assert_eq!(filled.len(), 1);
+        assert_eq!(filled[0].title, "News: a TV");
+        assert_eq!(
+            filled[0].description.as_deref(),
+            Some("No guide data for a")
+        );
+    }
+
+    #[test]
+    fn test_applies_to_proxy() {
+        use crate::models::proxy_settings::EpgGapFilling;
+
+        let opted_in = EpgGapFilling { enabled: true };
+        let opted_out = EpgGapFilling { enabled: false };
+        let mut config = EpgGapFillerConfig::default();
+        assert!(!config.applies_to(None));
+        assert!(config.applies_to(Some(&opted_in)));
+        config.enabled = true;
+        assert!(config.applies_to(None));
+        assert!(!config.applies_to(Some(&opted_out)));
+    }
+}
@@ -0,0 +1,465 @@
+//! EPG programme merging across sources
+//!
+//! When several EPG sources provide programmes for the same channel id, the data mapping
+//! stage combines them according to the proxy's [`EpgMergeStrategy`]. Source priority is
+//! the proxy's EPG source ordering; sources not attached to the proxy rank last.
+
+use crate::config::EpgMergeStrategy;
+use crate::models::proxy_settings::EpgMergePolicy;
+use crate::pipeline::engines::EpgProgram;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A programme together with the EPG source it was ingested from
+#[derive(Debug, Clone)]
+pub struct SourcedProgram {
+    pub source_id: Uuid,
+    pub program: EpgProgram,
+}
+
+/// Programmes of one source for one channel, sorted by start time
+struct SourcePrograms {
+    source_id: Uuid,
+    programs: Vec<EpgProgram>,
+}
+
+/// Merges programmes from multiple EPG sources channel by channel
+pub struct EpgProgramMerger {
+    policy: EpgMergePolicy,
+    source_rank: HashMap<Uuid, usize>,
+}
+
+impl EpgProgramMerger {
+    /// Create a merger; `source_priority` lists EPG source ids from highest to lowest priority
+    pub fn new(policy: EpgMergePolicy, source_priority: &[Uuid]) -> Self {
+        let mut source_rank = HashMap::new();
+        for (rank, source_id) in source_priority.iter().enumerate() {
+            source_rank.entry(*source_id).or_insert(rank);
+        }
+        Self {
+            policy,
+            source_rank,
+        }
+    }
+
+    pub fn strategy(&self) -> EpgMergeStrategy {
+        self.policy.strategy
+    }
+
+    /// Merge programmes; the result is ordered by start time
+    pub fn merge(&self, programs: Vec<SourcedProgram>) -> Vec<EpgProgram> {
+        let mut by_channel: HashMap<String, Vec<SourcedProgram>> = HashMap::new();
+        for sourced in programs {
+            by_channel
+                .entry(sourced.program.channel_id.clone())
+                .or_default()
+                .push(sourced);
+        }
+
+        let mut merged: Vec<EpgProgram> = by_channel
+            .into_values()
+            .flat_map(|channel_programs| self.merge_channel(channel_programs))
+            .collect();
+        merged.sort_by(|a, b| {
+            a.start_time
+                .cmp(&b.start_time)
+                .then_with(|| a.channel_id.cmp(&b.channel_id))
+        });
+        merged
+    }
+
+    fn merge_channel(&self, programs: Vec<SourcedProgram>) -> Vec<EpgProgram> {
+        let sources = self.split_by_source(programs);
+        if sources.len() <= 1 {
+            return sources.into_iter().flat_map(|s| s.programs).collect();
+        }
+
+        match self.policy.strategy {
+            EpgMergeStrategy::Priority => sources
+                .into_iter()
+                .next()
+                .map(|s| s.programs)
+                .unwrap_or_default(),
+            EpgMergeStrategy::FillGaps => fill_gaps(&sources)
+                .into_iter()
+                .map(|(_, program)| program)
+                .collect(),
+            EpgMergeStrategy::RichestMetadata => fill_gaps(&sources)
+                .into_iter()
+                .map(|(idx, base)| richest_for_slot(&sources, idx, base))
+                .collect(),
+            EpgMergeStrategy::FieldMerge => fill_gaps(&sources)
+                .into_iter()
+                .map(|(idx, base)| self.merge_fields(&sources, idx, base))
+                .collect(),
+        }
+    }
+
+    /// Group a channel's programmes by source, ordered by source priority
+    fn split_by_source(&self, programs: Vec<SourcedProgram>) -> Vec<SourcePrograms> {
+        let mut grouped: HashMap<Uuid, Vec<EpgProgram>> = HashMap::new();
+        for sourced in programs {
+            grouped
+                .entry(sourced.source_id)
+                .or_default()
+                .push(sourced.program);
+        }
+
+        let mut sources: Vec<SourcePrograms> = grouped
+            .into_iter()
+            .map(|(source_id, mut programs)| {
+                programs.sort_by_key(|p| p.start_time);
+                SourcePrograms {
+                    source_id,
+                    programs,
+                }
+            })
+            .collect();
+        sources.sort_by_key(|s| (self.rank(&s.source_id), s.source_id));
+        sources
+    }
+
+    fn rank(&self, source_id: &Uuid) -> usize {
+        self.source_rank
+            .get(source_id)
+            .copied()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Populate each optional field from the preferred source that has a value for the slot
+    fn merge_fields(
+        &self,
+        sources: &[SourcePrograms],
+        base_idx: usize,
+        base: EpgProgram,
+    ) -> EpgProgram {
+        let mut candidates = matching_programs(sources, base_idx, &base);
+        candidates.push((base_idx, &base));
+        candidates.sort_by_key(|(idx, _)| *idx);
+
+        let prefs = &self.policy.field_sources;
+        let description = pick_field(sources, &candidates, &prefs.description, |p| &p.description);
+        let program_icon = pick_field(sources, &candidates, &prefs.icon, |p| &p.program_icon);
+        let program_category = pick_field(sources, &candidates, &prefs.category, |p| {
+            &p.program_category
+        });
+        let subtitles = pick_field(sources, &candidates, &prefs.subtitles, |p| &p.subtitles);
+        let language = pick_field(sources, &candidates, &prefs.language, |p| &p.language);
+        let rating = pick_field(sources, &candidates, &prefs.rating, |p| &p.rating);
+        let aspect_ratio = pick_field(sources, &candidates, &[], |p| &p.aspect_ratio);
+        // Episode and season numbers only make sense together, so take both from one source
+        let episode_source = candidate_order(sources, &candidates, &prefs.episode)
+            .find(|p| has_value(&p.episode_num) || has_value(&p.season_num));
+        let (episode_num, season_num) = match episode_source {
+            Some(p) => (p.episode_num.clone(), p.season_num.clone()),
+            None => (None, None),
+        };
+
+        EpgProgram {
+            description,
+            program_icon,
+            program_category,
+            subtitles,
+            episode_num,
+            season_num,
+            language,
+            rating,
+            aspect_ratio,
+            ..base
+        }
+    }
+}
+
+/// Take the highest-priority source's programmes, then add lower-priority programmes
+/// that fall entirely in time not already covered
+///
+/// Returns each accepted programme with the index of its source.
+fn fill_gaps(sources: &[SourcePrograms]) -> Vec<(usize, EpgProgram)> {
+    let mut coverage: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    let mut accepted = Vec::new();
+
+    for (idx, source) in sources.iter().enumerate() {
+        let mut added = Vec::new();
+        for program in &source.programs {
+            if !overlaps_coverage(&coverage, program.start_time, program.end_time) {
+                added.push((program.start_time, program.end_time));
+                accepted.push((idx, program.clone()));
+            }
+        }
+        coverage.extend(added);
+        coverage = merge_intervals(coverage);
+    }
+
+    accepted.sort_by_key(|(_, p)| p.start_time);
+    accepted
+}
+
+/// Choose the programme with the most metadata for a slot, keeping the base slot times
+fn richest_for_slot(sources: &[SourcePrograms], base_idx: usize, base: EpgProgram) -> EpgProgram {
+    let base_score = metadata_score(&base);
+    let richer = matching_programs(sources, base_idx, &base)
+        .into_iter()
+        .filter(|(_, p)| metadata_score(p) > base_score)
+        .max_by(|(a_idx, a), (b_idx, b)| {
+            // Higher score wins; equal scores prefer the higher-priority (lower index) source
+            metadata_score(a)
+                .cmp(&metadata_score(b))
+                .then_with(|| b_idx.cmp(a_idx))
+        });
+
+    match richer {
+        Some((_, program)) => EpgProgram {
+            channel_id: base.channel_id,
+            channel_name: base.channel_name,
+            start_time: base.start_time,
+            end_time: base.end_time,
+            ..program.clone()
+        },
+        None => base,
+    }
+}
+
+/// Programmes from other sources describing the same slot as `base`
+///
+/// A programme matches when it overlaps at least half of the shorter of the two.
+fn matching_programs<'a>(
+    sources: &'a [SourcePrograms],
+    base_idx: usize,
+    base: &EpgProgram,
+) -> Vec<(usize, &'a EpgProgram)> {
+    let mut matches = Vec::new();
+    for (idx, source) in sources.iter().enumerate() {
+        if idx == base_idx {
+            continue;
+        }
+        let end = source
+            .programs
+            .partition_point(|p| p.start_time < base.end_time);
+        for program in source.programs[..end].iter().rev() {
+            if program.end_time <= base.start_time {
+                break;
+            }
+            if is_same_slot(base, program) {
+                matches.push((idx, program));
+                break;
+            }
+        }
+    }
+    matches
+}
+
+fn is_same_slot(a: &EpgProgram, b: &EpgProgram) -> bool {
+    let overlap_start = a.start_time.max(b.start_time);
+    let overlap_end = a.end_time.min(b.end_time);
+    if overlap_end <= overlap_start {
+        return false;
+    }
+    let overlap = overlap_end - overlap_start;
+    let shorter = (a.end_time - a.start_time).min(b.end_time - b.start_time);
+    overlap * 2 >= shorter
+}
+
+/// Candidates in field preference order: preferred sources first, then source priority
+fn candidate_order<'a>(
+    sources: &'a [SourcePrograms],
+    candidates: &'a [(usize, &'a EpgProgram)],
+    preferred: &'a [Uuid],
+) -> impl Iterator<Item = &'a EpgProgram> + 'a {
+    let preferred_candidates = preferred.iter().filter_map(move |source_id| {
+        candidates
+            .iter()
+            .find(|(idx, _)| sources[*idx].source_id == *source_id)
+            .map(|(_, p)| *p)
+    });
+    preferred_candidates.chain(candidates.iter().map(|(_, p)| *p))
+}
+
+fn pick_field(
+    sources: &[SourcePrograms],
+    candidates: &[(usize, &EpgProgram)],
+    preferred: &[Uuid],
+    field: impl Fn(&EpgProgram) -> &Option<String>,
+) -> Option<String> {
+    candidate_order(sources, candidates, preferred)
+        .map(field)
+        .find(|value| has_value(value))
+        .cloned()
+        .flatten()
+}
+
+fn has_value(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|v| !v.trim().is_empty())
+}
+
+/// Number of populated optional metadata fields
+fn metadata_score(program: &EpgProgram) -> usize {
+    [
+        &program.description,
+        &program.program_icon,
+        &program.program_category,
+        &program.subtitles,
+        &program.episode_num,
+        &program.season_num,
+        &program.language,
+        &program.rating,
+        &program.aspect_ratio,
+    ]
+    .into_iter()
+    .filter(|value| has_value(value))
+    .count()
+}
+
+/// Whether `[start, end)` intersects any interval of a sorted, disjoint coverage list
+fn overlaps_coverage(
+    coverage: &[(DateTime<Utc>, DateTime<Utc>)],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> bool {
+    let idx = coverage.partition_point(|(s, _)| *s < end);
+    idx > 0 && coverage[idx - 1].1 > start
+}
+
+fn merge_intervals(
+    mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    intervals.sort();
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::with_capacity(intervals.len());
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EpgMergeFieldSources;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    fn program(source_id: Uuid, title: &str, start: u32, end: u32) -> SourcedProgram {
+        SourcedProgram {
+            source_id,
+            program: EpgProgram {
+                id: Uuid::new_v4().to_string(),
+                channel_id: "bbc1".to_string(),
+                channel_name: "BBC One".to_string(),
+                title: title.to_string(),
+                description: None,
+                program_icon: None,
+                start_time: at(start),
+                end_time: at(end),
+                program_category: None,
+                subtitles: None,
+                episode_num: None,
+                season_num: None,
+                language: None,
+                rating: None,
+                aspect_ratio: None,
+            },
+        }
+    }
+
+    fn merger(strategy: EpgMergeStrategy, priority: &[Uuid]) -> EpgProgramMerger {
+        EpgProgramMerger::new(EpgMergePolicy::with_strategy(strategy), priority)
+    }
+
+    fn titles(programs: &[EpgProgram]) -> Vec<&str> {
+        programs.iter().map(|p| p.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_priority_takes_highest_source() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let programs = vec![
+            program(b, "B news", 1, 2),
+            program(a, "A news", 1, 2),
+            program(b, "B film", 3, 5),
+        ];
+
+        let merged = merger(EpgMergeStrategy::Priority, &[a, b]).merge(programs);
+        assert_eq!(titles(&merged), vec!["A news"]);
+    }
+
+    #[test]
+    fn test_unattached_sources_rank_last() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let programs = vec![program(a, "A news", 1, 2), program(b, "B news", 1, 2)];
+
+        let merged = merger(EpgMergeStrategy::Priority, &[b]).merge(programs);
+        assert_eq!(titles(&merged), vec!["B news"]);
+    }
+
+    #[test]
+    fn test_fill_gaps_uses_lower_priority_for_uncovered_time() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let programs = vec![
+            program(a, "A news", 1, 2),
+            program(a, "A late", 4, 5),
+            program(b, "B news", 1, 2),
+            program(b, "B film", 2, 4),
+            program(b, "B overlap", 3, 6),
+        ];
+
+        let merged = merger(EpgMergeStrategy::FillGaps, &[a, b]).merge(programs);
+        assert_eq!(titles(&merged), vec!["A news", "B film", "A late"]);
+    }
+
+    #[test]
+    fn test_richest_metadata_prefers_populated_programme() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut rich = program(b, "B news", 1, 2);
+        rich.program.description = Some("Headlines".to_string());
+        rich.program.program_icon = Some("http://icons/news.png".to_string());
+        let programs = vec![program(a, "A news", 1, 2), rich, program(a, "A film", 2, 4)];
+
+        let merged = merger(EpgMergeStrategy::RichestMetadata, &[a, b]).merge(programs);
+        assert_eq!(titles(&merged), vec!["B news", "A film"]);
+        assert_eq!(merged[0].description.as_deref(), Some("Headlines"));
+    }
+
+    #[test]
+    fn test_field_merge_respects_field_preferences() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut from_a = program(a, "A news", 1, 2);
+        from_a.program.description = Some("Description from A".to_string());
+        from_a.program.program_icon = Some("http://a/icon.png".to_string());
+        let mut from_b = program(b, "B news", 1, 2);
+        from_b.program.description = Some("Description from B".to_string());
+        from_b.program.program_icon = Some("http://b/icon.png".to_string());
+        from_b.program.episode_num = Some("4".to_string());
+        from_b.program.season_num = Some("2".to_string());
+
+        let policy = EpgMergePolicy {
+            strategy: EpgMergeStrategy::FieldMerge,
+            field_sources: EpgMergeFieldSources {
+                icon: vec![b],
+                ..Default::default()
+            },
+        };
+        let merged = EpgProgramMerger::new(policy, &[a, b]).merge(vec![from_a, from_b]);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].title, "A news");
+        assert_eq!(merged[0].description.as_deref(), Some("Description from A"));
+        assert_eq!(merged[0].program_icon.as_deref(), Some("http://b/icon.png"));
+        assert_eq!(merged[0].episode_num.as_deref(), Some("4"));
+        assert_eq!(merged[0].season_num.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_single_source_channels_pass_through() {
+        let a = Uuid::new_v4();
+        let programs = vec![program(a, "Overlap 1", 1, 3), program(a, "Overlap 2", 2, 4)];
+
+        let merged = merger(EpgMergeStrategy::FillGaps, &[a]).merge(programs);
+        assert_eq!(merged.len(), 2);
+    }
+}
@@ -314,6 +314,7 @@ mod tests {
             tvg_chno: None,
             tvg_logo: Some("@logo:550e8400-e29b-41d4-a716-446655440000".to_string()),
             tvg_shift: None,
+            epg_shift: None,
             group_title: None,
             channel_name: "Test Channel".to_string(),
             stream_url: "http://example.com/stream".to_string(),
@@ -324,6 +325,7 @@ mod tests {
             last_probed_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            extra_attributes: Default::default(),
         };
 
         // Mock processors vector - would need actual processors for real test
@@ -343,6 +345,7 @@ mod tests {
             tvg_chno: None,
             tvg_logo: Some("@logo:550e8400-e29b-41d4-a716-446655440000".to_string()),
             tvg_shift: None,
+            epg_shift: None,
             group_title: None,
             channel_name: "Test Channel".to_string(),
             stream_url: "http://example.com/stream".to_string(),
@@ -353,6 +356,7 @@ mod tests {
             last_probed_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            extra_attributes: Default::default(),
         };
 
         let fields = channel.get_helper_processable_fields();
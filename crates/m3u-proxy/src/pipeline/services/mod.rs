@@ -1,11 +1,25 @@
+pub mod artifact_inspection;
+pub mod backup_streams;
+pub mod epg_dedup;
+pub mod epg_failover;
+pub mod epg_gap_filler;
+pub mod epg_merge;
 pub mod helper_processor;
 pub mod helper_traits;
 pub mod seaorm_data_mapping;
+pub mod stage_cache;
 pub mod validation;
 
+pub use artifact_inspection::ArtifactSampleStore;
+pub use backup_streams::{BackupStreamGroup, BackupStreamPlanner};
+pub use epg_dedup::EpgProgramDeduplicator;
+pub use epg_failover::{EpgFailoverPlan, EpgSourceFreshness};
+pub use epg_gap_filler::{EpgGapFiller, GapFillChannel};
+pub use epg_merge::{EpgProgramMerger, SourcedProgram};
 pub use helper_processor::{
     HelperDetectable, HelperField, HelperPostProcessor, HelperProcessable, HelperProcessor,
     HelperProcessorError, LogoHelperProcessor, TimeHelperProcessor,
 };
 pub use seaorm_data_mapping::SeaOrmDataMappingService;
+pub use stage_cache::StageCache;
 pub use validation::{ApiValidationService, PipelineValidationService};
@@ -4,18 +4,21 @@
 //! with the essential methods needed by the web API.
 
 use crate::database::repositories::DataMappingRuleSeaOrmRepository;
+use crate::entities::prelude::{StreamProxies, StreamSources};
+use crate::entities::{stream_proxies, stream_sources};
 use crate::field_registry::FieldRegistry;
 use crate::models::data_mapping::*;
 use crate::pipeline::engines::DataMappingValidator;
 use anyhow::Result;
 use regex::Regex;
-use sea_orm::DatabaseConnection;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
 use tracing::error;
 use uuid::Uuid;
 
 /// SeaORM-based data mapping service
 #[derive(Clone)]
 pub struct SeaOrmDataMappingService {
+    connection: std::sync::Arc<DatabaseConnection>,
     repository: DataMappingRuleSeaOrmRepository,
 }
 
@@ -48,8 +51,49 @@ pub(crate) fn canonicalize_expression(expr: &str) -> String {
 
 impl SeaOrmDataMappingService {
     pub fn new(connection: std::sync::Arc<DatabaseConnection>) -> Self {
-        let repository = DataMappingRuleSeaOrmRepository::new(connection);
-        Self { repository }
+        let repository = DataMappingRuleSeaOrmRepository::new(connection.clone());
+        Self {
+            connection,
+            repository,
+        }
+    }
+
+    /// Check a rule scope targets an existing source or proxy the rule type can bind to
+    async fn validate_scope(
+        &self,
+        scope: &DataMappingRuleScope,
+        source_type: &DataMappingSourceType,
+    ) -> Result<()> {
+        match scope {
+            DataMappingRuleScope::Global => {}
+            DataMappingRuleScope::Source { source_id } => {
+                // The EPG rule engine does not track which source a programme came from
+                if *source_type != DataMappingSourceType::Stream {
+                    return Err(anyhow::anyhow!(
+                        "Source scope is only supported for stream rules"
+                    ));
+                }
+                if StreamSources::find_by_id(*source_id)
+                    .filter(stream_sources::Column::DeletedAt.is_null())
+                    .one(&*self.connection)
+                    .await?
+                    .is_none()
+                {
+                    return Err(anyhow::anyhow!("Stream source {} not found", source_id));
+                }
+            }
+            DataMappingRuleScope::Proxy { proxy_id } => {
+                if StreamProxies::find_by_id(*proxy_id)
+                    .filter(stream_proxies::Column::DeletedAt.is_null())
+                    .one(&*self.connection)
+                    .await?
+                    .is_none()
+                {
+                    return Err(anyhow::anyhow!("Proxy {} not found", proxy_id));
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Create a new data mapping rule
@@ -73,6 +117,9 @@ impl SeaOrmDataMappingService {
             }
         }
 
+        self.validate_scope(&request.scope, &request.source_type)
+            .await?;
+
         // Canonicalize aliases (program_* -> programme_*, etc.) after validation
         if let Some(ref mut expression) = request.expression {
             *expression = canonicalize_expression(expression);
@@ -111,6 +158,20 @@ impl SeaOrmDataMappingService {
             }
         }
 
+        if request.scope.is_some() || request.source_type.is_some() {
+            let existing = self
+                .repository
+                .find_by_id(&rule_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Data mapping rule not found"))?;
+            let scope = request.scope.as_ref().unwrap_or(&existing.scope);
+            let source_type = request
+                .source_type
+                .as_ref()
+                .unwrap_or(&existing.source_type);
+            self.validate_scope(scope, source_type).await?;
+        }
+
         // Canonicalize aliases if an expression is provided
         if let Some(ref mut expression) = request.expression {
             *expression = canonicalize_expression(expression);
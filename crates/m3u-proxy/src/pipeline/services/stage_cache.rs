@@ -0,0 +1,189 @@
+//! Pipeline stage output cache
+//!
+//! Stores the output artifacts of cacheable stages under `stage_cache/{proxy_id}/` in the
+//! pipeline file manager, together with the cache key they were produced under. A stage's
+//! key chains the key of the previous stage with the stage's own input fingerprint, so a
+//! change anywhere upstream invalidates every later stage.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sandboxed_file_manager::SandboxedManager;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::pipeline::models::PipelineArtifact;
+
+const STAGE_CACHE_DIR: &str = "stage_cache";
+
+/// Cached output of one stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StageCacheEntry {
+    key: String,
+    created_at: DateTime<Utc>,
+    artifacts: Vec<PipelineArtifact>,
+}
+
+/// Reads and writes cached stage output in the pipeline file manager
+#[derive(Clone)]
+pub struct StageCache {
+    file_manager: SandboxedManager,
+}
+
+impl StageCache {
+    pub fn new(file_manager: SandboxedManager) -> Self {
+        Self { file_manager }
+    }
+
+    /// Cache key of a stage given the key of the stage before it and its own fingerprint
+    pub fn chain_key(previous_key: &str, stage_id: &str, fingerprint: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(previous_key.as_bytes());
+        hasher.update([0]);
+        hasher.update(stage_id.as_bytes());
+        hasher.update([0]);
+        hasher.update(fingerprint.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Cached artifacts of a stage when they were produced under `key`
+    pub async fn lookup(
+        &self,
+        proxy_id: Uuid,
+        stage_id: &str,
+        key: &str,
+    ) -> Option<Vec<PipelineArtifact>> {
+        let bytes = self
+            .file_manager
+            .read(manifest_path(proxy_id, stage_id))
+            .await
+            .ok()?;
+        let entry: StageCacheEntry = serde_json::from_slice(&bytes).ok()?;
+        if entry.key != key {
+            return None;
+        }
+        // Artifact files expire independently; every one must still be present
+        for artifact in &entry.artifacts {
+            if !self
+                .file_manager
+                .exists(&artifact.file_path)
+                .await
+                .unwrap_or(false)
+            {
+                debug!(
+                    "Stage cache for {} is missing {}; treating as a miss",
+                    stage_id, artifact.file_path
+                );
+                return None;
+            }
+        }
+        Some(entry.artifacts)
+    }
+
+    /// Copy a stage's artifacts into the cache and record them under `key`
+    pub async fn store(
+        &self,
+        proxy_id: Uuid,
+        stage_id: &str,
+        key: &str,
+        artifacts: &[PipelineArtifact],
+    ) -> Result<()> {
+        let dir = stage_dir(proxy_id, stage_id);
+        self.file_manager.create_dir_all(&dir).await?;
+
+        let mut cached = Vec::with_capacity(artifacts.len());
+        for (index, artifact) in artifacts.iter().enumerate() {
+            let mut artifact = artifact.clone();
+            let cached_path = format!("{dir}/{}_{index}.jsonl", &key[..16.min(key.len())]);
+            self.file_manager
+                .copy(&artifact.file_path, &cached_path)
+                .await?;
+            artifact.file_path = cached_path;
+            cached.push(artifact);
+        }
+
+        // Drop files of the previous entry for this stage
+        if let Ok(files) = self.file_manager.list_files(&dir).await {
+            for file in files {
+                if !cached.iter().any(|a| a.file_path == file)
+                    && let Err(e) = self.file_manager.remove_file(&file).await
+                {
+                    warn!("Failed to remove stale stage cache file {}: {}", file, e);
+                }
+            }
+        }
+
+        let entry = StageCacheEntry {
+            key: key.to_string(),
+            created_at: Utc::now(),
+            artifacts: cached,
+        };
+        self.file_manager
+            .write(
+                manifest_path(proxy_id, stage_id),
+                serde_json::to_vec(&entry)?,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+fn stage_dir(proxy_id: Uuid, stage_id: &str) -> String {
+    format!("{STAGE_CACHE_DIR}/{proxy_id}/{stage_id}")
+}
+
+fn manifest_path(proxy_id: Uuid, stage_id: &str) -> String {
+    format!("{STAGE_CACHE_DIR}/{proxy_id}/{stage_id}.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::models::{ArtifactType, ContentType, ProcessingStage};
+
+    #[test]
+    fn test_chain_key_depends_on_every_input() {
+        let base = StageCache::chain_key("", "filtering", "a");
+        assert_eq!(base, StageCache::chain_key("", "filtering", "a"));
+        assert_ne!(base, StageCache::chain_key("x", "filtering", "a"));
+        assert_ne!(base, StageCache::chain_key("", "numbering", "a"));
+        assert_ne!(base, StageCache::chain_key("", "filtering", "b"));
+    }
+
+    #[tokio::test]
+    async fn test_store_and_lookup() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .build()
+            .await
+            .unwrap();
+        file_manager
+            .write("exec_filtered.jsonl", b"{}\n")
+            .await
+            .unwrap();
+
+        let cache = StageCache::new(file_manager.clone());
+        let proxy_id = Uuid::new_v4();
+        let artifact = PipelineArtifact::new(
+            ArtifactType::new(ContentType::Channels, ProcessingStage::Filtered),
+            "exec_filtered.jsonl".to_string(),
+            "filtering".to_string(),
+        );
+        let key = StageCache::chain_key("", "filtering", "fp");
+        cache
+            .store(proxy_id, "filtering", &key, &[artifact])
+            .await
+            .unwrap();
+
+        let cached = cache.lookup(proxy_id, "filtering", &key).await.unwrap();
+        assert_eq!(cached.len(), 1);
+        assert!(cached[0].file_path.starts_with("stage_cache/"));
+        assert_eq!(
+            file_manager.read(&cached[0].file_path).await.unwrap(),
+            b"{}\n"
+        );
+        assert!(cache.lookup(proxy_id, "filtering", "other").await.is_none());
+    }
+}
@@ -0,0 +1,182 @@
+//! Compliance blocklist stage for pipeline processing
+//!
+//! Mandatory final channel stage: removes every numbered channel whose stream hostname or
+//! tvg_id is on the operator's compliance blocklist before anything is generated. Each
+//! removal is written to the `compliance_audit` log target. Programmes of removed channels
+//! drop out of the XMLTV because generation only emits guide data for published channels.
+
+use crate::models::Channel;
+use crate::pipeline::error::PipelineError;
+use crate::pipeline::models::{ArtifactType, ContentType, PipelineArtifact, ProcessingStage};
+use crate::pipeline::traits::{PipelineStage, ProgressAware};
+use crate::services::compliance_blocklist::{Blocklist, ComplianceBlocklistService};
+use crate::services::progress_service::ProgressManager;
+use sandboxed_file_manager::SandboxedManager;
+use std::sync::Arc;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+pub struct ComplianceStage {
+    file_manager: SandboxedManager,
+    pipeline_execution_prefix: String,
+    proxy_id: Uuid,
+    progress_manager: Option<Arc<ProgressManager>>,
+}
+
+impl ComplianceStage {
+    pub fn new(
+        file_manager: SandboxedManager,
+        pipeline_execution_prefix: String,
+        proxy_id: Uuid,
+        progress_manager: Option<Arc<ProgressManager>>,
+    ) -> Self {
+        Self {
+            file_manager,
+            pipeline_execution_prefix,
+            proxy_id,
+            progress_manager,
+        }
+    }
+
+    /// Helper method for reporting progress
+    async fn report_progress(&self, percentage: f64, message: &str) {
+        if let Some(pm) = &self.progress_manager
+            && let Some(updater) = pm.get_stage_updater("compliance").await
+        {
+            updater.update_progress(percentage, message).await;
+        }
+    }
+
+    /// Set the progress manager for this stage
+    pub fn set_progress_manager(&mut self, progress_manager: Arc<ProgressManager>) {
+        self.progress_manager = Some(progress_manager);
+    }
+
+    pub async fn process(
+        &self,
+        input_artifacts: Vec<PipelineArtifact>,
+    ) -> Result<Vec<PipelineArtifact>, Box<dyn std::error::Error>> {
+        let Some(service) = ComplianceBlocklistService::global() else {
+            debug!("No compliance blocklist configured");
+            return Ok(input_artifacts);
+        };
+        let Some(blocklist) = service.current() else {
+            if service.fail_closed() {
+                return Err("compliance blocklist is configured but has not been loaded".into());
+            }
+            debug!("Compliance blocklist not loaded yet; passing channels through");
+            return Ok(input_artifacts);
+        };
+
+        let mut output_artifacts = Vec::with_capacity(input_artifacts.len());
+        for artifact in input_artifacts {
+            if artifact.artifact_type.content != ContentType::Channels {
+                output_artifacts.push(artifact);
+                continue;
+            }
+            output_artifacts.push(self.filter_artifact(&artifact, &blocklist).await?);
+        }
+        Ok(output_artifacts)
+    }
+
+    async fn filter_artifact(
+        &self,
+        artifact: &PipelineArtifact,
+        blocklist: &Blocklist,
+    ) -> Result<PipelineArtifact, Box<dyn std::error::Error>> {
+        let content = String::from_utf8(self.file_manager.read(&artifact.file_path).await?)?;
+        let mut channels = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Channel>, _>>()?;
+
+        let before = channels.len();
+        channels.retain(|channel| match blocklist.blocked_reason(channel) {
+            Some(reason) => {
+                info!(
+                    target: "compliance_audit",
+                    proxy_id = %self.proxy_id,
+                    channel_id = %channel.id,
+                    source_id = %channel.source_id,
+                    channel_name = %channel.channel_name,
+                    tvg_id = channel.tvg_id.as_deref().unwrap_or(""),
+                    entry = %reason,
+                    "Removed blocklisted channel from proxy output"
+                );
+                false
+            }
+            None => true,
+        });
+        let removed = before - channels.len();
+
+        let output_filename = format!(
+            "{}_compliance_channels.jsonl",
+            self.pipeline_execution_prefix
+        );
+        let output_content = channels
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n");
+        self.file_manager
+            .write(&output_filename, output_content.as_bytes())
+            .await?;
+
+        info!(
+            "Compliance stage completed: proxy={} removed={} remaining={} blocklist_entries={}",
+            self.proxy_id,
+            removed,
+            channels.len(),
+            blocklist.len()
+        );
+
+        Ok(PipelineArtifact::new(
+            ArtifactType::new(ContentType::Channels, ProcessingStage::Numbered),
+            output_filename,
+            "compliance".to_string(),
+        )
+        .with_record_count(channels.len())
+        .with_file_size(output_content.len() as u64)
+        .with_metadata("compliance_removed".to_string(), removed.into()))
+    }
+}
+
+impl ProgressAware for ComplianceStage {
+    fn get_progress_manager(&self) -> Option<&Arc<ProgressManager>> {
+        self.progress_manager.as_ref()
+    }
+}
+
+#[async_trait::async_trait]
+impl PipelineStage for ComplianceStage {
+    async fn execute(
+        &mut self,
+        input: Vec<PipelineArtifact>,
+    ) -> Result<Vec<PipelineArtifact>, PipelineError> {
+        self.report_progress(10.0, "Applying compliance blocklist")
+            .await;
+        let result = self.process(input).await.map_err(|e| {
+            PipelineError::stage_error("compliance", format!("Compliance check failed: {e}"))
+        })?;
+        self.report_progress(100.0, "Compliance blocklist applied")
+            .await;
+        Ok(result)
+    }
+
+    fn stage_id(&self) -> &'static str {
+        "compliance"
+    }
+
+    fn stage_name(&self) -> &'static str {
+        "Compliance"
+    }
+
+    async fn cleanup(&mut self) -> Result<(), PipelineError> {
+        Ok(())
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
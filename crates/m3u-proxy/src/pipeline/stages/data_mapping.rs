@@ -1,12 +1,20 @@
-use crate::models::{Channel, data_mapping::DataMappingRule};
+use crate::models::{
+    Channel,
+    data_mapping::{DataMappingRule, scoped_rules},
+    proxy_settings::{EpgFallbacks, EpgMergePolicy, ProxySetting},
+};
 use crate::pipeline::error::PipelineError;
 use crate::pipeline::models::{ArtifactType, PipelineArtifact};
-use crate::pipeline::services::{HelperPostProcessor, HelperProcessorError};
+use crate::pipeline::services::{
+    EpgFailoverPlan, EpgProgramDeduplicator, EpgProgramMerger, HelperPostProcessor,
+    HelperProcessorError, SourcedProgram,
+};
 use crate::pipeline::traits::{PipelineStage, ProgressAware};
 use crate::services::progress_service::ProgressManager;
 // Import helper traits implementation (this ensures the trait implementations are available)
 use crate::entities::{
-    channels, data_mapping_rules, epg_programs, epg_sources, prelude::*, stream_sources,
+    channels, data_mapping_rules, epg_programs, epg_sources, prelude::*, proxy_epg_sources,
+    stream_sources,
 };
 use crate::pipeline::engines::rule_processor::{EpgRuleProcessor, RegexEvaluator};
 use crate::pipeline::engines::{
@@ -16,7 +24,7 @@ use crate::pipeline::engines::{
 #[allow(unused_imports)]
 use crate::pipeline::services::helper_traits;
 use crate::utils::human_format::format_duration_precise;
-use crate::utils::regex_preprocessor::RegexPreprocessor;
+use crate::utils::regex_preprocessor::{PrecheckTuning, PrecheckTuningConfig, RegexPreprocessor};
 use sandboxed_file_manager::SandboxedManager;
 use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
 use serde_json;
@@ -31,12 +39,25 @@ const EPG_PROGRAMS_BATCH_SIZE: usize = 1000; // Process EPG programs in batches
 
 const CHANNEL_PROGRESS_INTERVAL: usize = 1000; // Report progress every N channels
 
+/// Artifact metadata key carrying per-rule precheck effectiveness when auto-tuning
+pub const PRECHECK_EFFECTIVENESS_METADATA: &str = "precheck_effectiveness";
+
+/// Artifact metadata key carrying EPG programme deduplication counts
+pub const EPG_DEDUP_METADATA: &str = "epg_dedup";
+
 pub struct DataMappingStage {
     db_connection: std::sync::Arc<sea_orm::DatabaseConnection>,
     file_manager: SandboxedManager,
     pipeline_execution_prefix: String,
     regex_preprocessor: RegexPreprocessor,
+    precheck_tuning: Option<PrecheckTuningConfig>,
     helper_processor: Option<HelperPostProcessor>,
+    epg_merge_policy: Option<EpgMergePolicy>,
+    epg_failover: Option<crate::config::EpgFailoverConfig>,
+    /// Counts from deduplicating programmes across EPG sources during the last merge
+    epg_dedup_stats: crate::models::EpgDedupStats,
+    /// Proxy being generated; selects its proxy-scoped rules
+    proxy_id: Option<uuid::Uuid>,
     progress_manager: Option<Arc<ProgressManager>>,
     // Prevent unbounded debug spam if progress manager not present
     missing_progress_log_emitted: bool,
@@ -58,7 +79,12 @@ impl DataMappingStage {
             file_manager: shared_file_manager,
             pipeline_execution_prefix,
             regex_preprocessor,
+            precheck_tuning: None,
             helper_processor: None,
+            epg_merge_policy: None,
+            epg_failover: None,
+            epg_dedup_stats: Default::default(),
+            proxy_id: None,
             progress_manager,
             missing_progress_log_emitted: false,
         })
@@ -75,6 +101,31 @@ impl DataMappingStage {
         self
     }
 
+    /// Merge programmes from overlapping EPG sources using the proxy's merge policy
+    pub fn with_epg_merge_policy(mut self, policy: EpgMergePolicy) -> Self {
+        self.epg_merge_policy = Some(policy);
+        self
+    }
+
+    /// Apply the rules scoped to this proxy in addition to global and source rules
+    pub fn with_proxy_id(mut self, proxy_id: uuid::Uuid) -> Self {
+        self.proxy_id = Some(proxy_id);
+        self
+    }
+
+    /// Apply the configured regex precheck settings, including per-rule auto-tuning
+    pub fn with_engine_config(mut self, config: &crate::config::DataMappingEngineConfig) -> Self {
+        self.regex_preprocessor = RegexPreprocessor::new(config.preprocessor_config());
+        self.precheck_tuning = config.precheck_tuning();
+        self
+    }
+
+    /// Rank stale EPG sources after fresh ones when merging
+    pub fn with_epg_failover(mut self, config: crate::config::EpgFailoverConfig) -> Self {
+        self.epg_failover = Some(config);
+        self
+    }
+
     pub async fn process_channels(
         &mut self,
     ) -> Result<PipelineArtifact, Box<dyn std::error::Error>> {
@@ -86,6 +137,7 @@ impl DataMappingStage {
 
         // Query all stream sources using SeaORM
         let stream_sources = StreamSources::find()
+            .filter(stream_sources::Column::DeletedAt.is_null())
             .filter(stream_sources::Column::IsActive.eq(true))
             .all(&*self.db_connection)
             .await?;
@@ -112,6 +164,8 @@ impl DataMappingStage {
         let mut rule_stats: HashMap<String, (String, usize, usize, std::time::Duration)> =
             HashMap::new(); // (rule_name, applied_count, processed_count, total_time)
         let mut output_file_created = false; // Track if output file has been created across all sources
+        // Precheck tuning is per rule, shared by the rule's processors across all sources
+        let mut precheck_tunings: Vec<(String, String, Arc<PrecheckTuning>)> = Vec::new();
 
         // Pre-compute grand total channels across all sources for unified progress (10-50%)
         let mut grand_total_channels: u64 = 0;
@@ -147,6 +201,7 @@ impl DataMappingStage {
                 self.pipeline_execution_prefix
             );
             let rule_models = match DataMappingRules::find()
+                .filter(data_mapping_rules::Column::DeletedAt.is_null())
                 .filter(data_mapping_rules::Column::SourceType.eq("stream"))
                 .filter(data_mapping_rules::Column::IsActive.eq(true))
                 .order_by_asc(data_mapping_rules::Column::SortOrder)
@@ -186,6 +241,7 @@ impl DataMappingStage {
                 };
                 rules.push(rule);
             }
+            let rules = scoped_rules(rules, Some(source_id), self.proxy_id);
 
             info!(
                 "exec={} Found {} data mapping rules for stream sources",
@@ -245,7 +301,26 @@ impl DataMappingStage {
                             ),
                         );
                         let meta_map = std::sync::Arc::new(meta_map);
-                        let regex_evaluator = RegexEvaluator::new(self.regex_preprocessor.clone());
+                        let mut regex_evaluator =
+                            RegexEvaluator::new(self.regex_preprocessor.clone());
+                        if let Some(config) = &self.precheck_tuning {
+                            let tuning = match precheck_tunings
+                                .iter()
+                                .find(|(id, _, _)| *id == rule_id_str)
+                            {
+                                Some((_, _, tuning)) => tuning.clone(),
+                                None => {
+                                    let tuning = Arc::new(PrecheckTuning::new(config.clone()));
+                                    precheck_tunings.push((
+                                        rule_id_str.clone(),
+                                        rule.name.clone(),
+                                        tuning.clone(),
+                                    ));
+                                    tuning
+                                }
+                            };
+                            regex_evaluator = regex_evaluator.with_tuning(tuning);
+                        }
                         let processor = StreamRuleProcessor::new(
                             rule_id_str,
                             rule.name.clone(),
@@ -674,6 +749,23 @@ impl DataMappingStage {
             }
         }
 
+        let precheck_effectiveness: Vec<crate::models::PrecheckEffectiveness> = precheck_tunings
+            .into_iter()
+            .map(|(rule_id, rule_name, tuning)| tuning.effectiveness(rule_id, rule_name))
+            .collect();
+        for rule in &precheck_effectiveness {
+            info!(
+                "exec={}   Precheck effectiveness: rule_id={} rule_name='{}' evaluations={} regex_skipped={} sampled_skip_rate={:.3} precheck_enabled={}",
+                self.pipeline_execution_prefix,
+                rule.rule_id,
+                rule.rule_name,
+                rule.evaluations,
+                rule.regex_skipped,
+                rule.sampled_skip_rate,
+                rule.precheck_enabled
+            );
+        }
+
         // Get the relative path for the temp file
         let full_path = self.file_manager.get_full_path(&output_file_path)?;
         info!(
@@ -710,6 +802,15 @@ impl DataMappingStage {
             artifact
         };
 
+        let artifact = if precheck_effectiveness.is_empty() {
+            artifact
+        } else {
+            artifact.with_metadata(
+                PRECHECK_EFFECTIVENESS_METADATA.to_string(),
+                serde_json::to_value(&precheck_effectiveness)?,
+            )
+        };
+
         // Memory cleanup is handled automatically when variables go out of scope
 
         info!(
@@ -730,6 +831,7 @@ impl DataMappingStage {
 
         // EPG snapshot version & active sources logging
         let active_epg_sources = EpgSources::find()
+            .filter(epg_sources::Column::DeletedAt.is_null())
             .filter(epg_sources::Column::IsActive.eq(true))
             .all(&*self.db_connection)
             .await
@@ -766,12 +868,22 @@ impl DataMappingStage {
         let output_file_path = format!("{}_mapping_programs.jsonl", self.pipeline_execution_prefix);
 
         // Check if we have any EPG data mapping rules using SeaORM
-        let epg_rules = DataMappingRules::find()
+        // EPG programmes carry no source in the rule engine, so only global and proxy rules apply
+        let epg_rule_models = DataMappingRules::find()
+            .filter(data_mapping_rules::Column::DeletedAt.is_null())
             .filter(data_mapping_rules::Column::SourceType.eq("epg"))
             .filter(data_mapping_rules::Column::IsActive.eq(true))
             .order_by_asc(data_mapping_rules::Column::SortOrder)
             .all(&*self.db_connection)
             .await?;
+        let epg_rules = scoped_rules(
+            epg_rule_models
+                .iter()
+                .map(|model| self.create_data_mapping_rule_from_model(model))
+                .collect::<Result<Vec<_>, _>>()?,
+            None,
+            self.proxy_id,
+        );
 
         let epg_rules_count = epg_rules.len();
         let program_count = if epg_rules.is_empty() {
@@ -841,6 +953,7 @@ impl DataMappingStage {
             // Add rule processors to the engine
             // Build source metadata map for all active EPG sources (sanitised URLs)
             let active_epg_sources = EpgSources::find()
+                .filter(epg_sources::Column::DeletedAt.is_null())
                 .filter(epg_sources::Column::IsActive.eq(true))
                 .all(&*self.db_connection)
                 .await
@@ -988,6 +1101,10 @@ impl DataMappingStage {
         .with_metadata(
             "epg_rules_found".to_string(),
             serde_json::Value::Number(serde_json::Number::from(epg_rules_count)),
+        )
+        .with_metadata(
+            EPG_DEDUP_METADATA.to_string(),
+            serde_json::to_value(self.epg_dedup_stats)?,
         );
 
         let artifact = if let Some(size) = file_size_bytes {
@@ -1028,12 +1145,14 @@ impl DataMappingStage {
         );
 
         let mut all_programs = Vec::with_capacity(total_programs);
+        let mut source_ids = Vec::with_capacity(total_programs);
         let mut batch_programs = Vec::new();
         const BATCH_SIZE: usize = EPG_PROGRAMS_BATCH_SIZE;
         let mut processed_count = 0;
 
         for epg_model in epg_models {
             let program = self.create_epg_program_from_model(&epg_model)?;
+            source_ids.push(epg_model.source_id);
             batch_programs.push(program);
             processed_count += 1;
 
@@ -1065,9 +1184,79 @@ impl DataMappingStage {
             self.pipeline_execution_prefix,
             all_programs.len()
         );
+
+        if let (Some(policy), Some(proxy_id)) = (self.epg_merge_policy.clone(), self.proxy_id) {
+            let (merged, dedup_stats) = self
+                .merge_epg_sources(policy, proxy_id, source_ids, all_programs)
+                .await?;
+            all_programs = merged;
+            self.epg_dedup_stats = dedup_stats;
+        }
+
         Ok(all_programs)
     }
 
+    /// Deduplicate and combine programmes from EPG sources covering the same channel
+    async fn merge_epg_sources(
+        &self,
+        policy: EpgMergePolicy,
+        proxy_id: uuid::Uuid,
+        source_ids: Vec<uuid::Uuid>,
+        programs: Vec<EpgProgram>,
+    ) -> Result<(Vec<EpgProgram>, crate::models::EpgDedupStats), Box<dyn std::error::Error>> {
+        let source_priority: Vec<uuid::Uuid> = match &self.epg_failover {
+            Some(failover) => {
+                let plan = EpgFailoverPlan::load(&self.db_connection, failover, proxy_id).await?;
+                for source in plan.sources.iter().filter(|s| s.stale) {
+                    warn!(
+                        "exec={} EPG source '{}' is stale (last ingested: {:?}); fallback: {:?}",
+                        self.pipeline_execution_prefix,
+                        source.epg_source_name,
+                        source.last_ingested_at,
+                        source.fallback_source_id
+                    );
+                }
+                plan.source_priority
+            }
+            None => ProxyEpgSources::find()
+                .filter(proxy_epg_sources::Column::ProxyId.eq(proxy_id))
+                .order_by_asc(proxy_epg_sources::Column::PriorityOrder)
+                .all(&*self.db_connection)
+                .await?
+                .into_iter()
+                .map(|m| m.epg_source_id)
+                .collect(),
+        };
+
+        let before = programs.len();
+        let sourced = source_ids
+            .into_iter()
+            .zip(programs)
+            .map(|(source_id, program)| SourcedProgram { source_id, program })
+            .collect();
+        let (deduplicated, dedup_stats) =
+            EpgProgramDeduplicator::new(&source_priority).dedup(sourced);
+        info!(
+            "exec={} EPG dedup: duplicates_removed={} overlaps_trimmed={} overlaps_dropped={}",
+            self.pipeline_execution_prefix,
+            dedup_stats.duplicates_removed,
+            dedup_stats.overlaps_trimmed,
+            dedup_stats.overlaps_dropped
+        );
+
+        let merger = EpgProgramMerger::new(policy, &source_priority);
+        let merged = merger.merge(deduplicated);
+
+        info!(
+            "exec={} EPG merge strategy {:?}: {} programs -> {} programs",
+            self.pipeline_execution_prefix,
+            merger.strategy(),
+            before,
+            merged.len()
+        );
+        Ok((merged, dedup_stats))
+    }
+
     async fn write_programs_to_file(
         &self,
         programs: &[EpgProgram],
@@ -1099,6 +1288,7 @@ impl DataMappingStage {
             tvg_chno: model.tvg_chno.clone(),
             tvg_logo: model.tvg_logo.clone(),
             tvg_shift: model.tvg_shift.clone(),
+            epg_shift: None,
             group_title: model.group_title.clone(),
             channel_name: model.channel_name.clone(),
             stream_url: model.stream_url.clone(),
@@ -1109,6 +1299,7 @@ impl DataMappingStage {
             resolution: None,
             probe_method: None,
             last_probed_at: None,
+            extra_attributes: Channel::parse_extra_attributes(model.extra_attributes.as_deref()),
         };
 
         Ok(channel)
@@ -1127,6 +1318,10 @@ impl DataMappingStage {
             sort_order: model.sort_order,
             is_active: model.is_active,
             expression: model.expression.clone(),
+            scope: crate::models::data_mapping::DataMappingRuleScope::from_columns(
+                model.scope_source_id,
+                model.scope_proxy_id,
+            ),
             created_at: model.created_at,
             updated_at: model.updated_at,
         };
@@ -1231,6 +1426,88 @@ impl DataMappingStage {
     }
 }
 
+impl DataMappingStage {
+    /// Digest of the sources, rules and EPG settings this stage reads (see `cache_fingerprint`)
+    async fn input_fingerprint(&self) -> Result<String, sea_orm::DbErr> {
+        let db = &*self.db_connection;
+        let mut parts = Vec::new();
+
+        for source in StreamSources::find()
+            .filter(stream_sources::Column::DeletedAt.is_null())
+            .filter(stream_sources::Column::IsActive.eq(true))
+            .order_by_asc(stream_sources::Column::Id)
+            .all(db)
+            .await?
+        {
+            parts.push(format!(
+                "stream:{}:{}:{:?}",
+                source.id,
+                source.updated_at.timestamp_millis(),
+                source.last_ingested_at.map(|t| t.timestamp_millis())
+            ));
+        }
+        parts.push(format!("channels:{}", Channels::find().count(db).await?));
+
+        for source in EpgSources::find()
+            .filter(epg_sources::Column::DeletedAt.is_null())
+            .order_by_asc(epg_sources::Column::Id)
+            .all(db)
+            .await?
+        {
+            parts.push(format!(
+                "epg:{}:{}:{}:{:?}",
+                source.id,
+                source.is_active,
+                source.updated_at.timestamp_millis(),
+                source.last_ingested_at.map(|t| t.timestamp_millis())
+            ));
+        }
+
+        for rule in DataMappingRules::find()
+            .filter(data_mapping_rules::Column::DeletedAt.is_null())
+            .order_by_asc(data_mapping_rules::Column::Id)
+            .all(db)
+            .await?
+        {
+            parts.push(format!(
+                "rule:{}:{}:{}",
+                rule.id,
+                rule.is_active,
+                rule.updated_at.timestamp_millis()
+            ));
+        }
+
+        if let (Some(policy), Some(proxy_id)) = (&self.epg_merge_policy, self.proxy_id) {
+            parts.push(format!("merge:{policy:?}"));
+            for link in ProxyEpgSources::find()
+                .filter(proxy_epg_sources::Column::ProxyId.eq(proxy_id))
+                .order_by_asc(proxy_epg_sources::Column::PriorityOrder)
+                .all(db)
+                .await?
+            {
+                parts.push(format!(
+                    "link:{}:{}",
+                    link.epg_source_id, link.priority_order
+                ));
+            }
+        }
+        if let Some(failover) = &self.epg_failover {
+            parts.push(format!("failover:{failover:?}"));
+            if let Some(proxy_id) = self.proxy_id
+                && let Some(fallbacks) =
+                    ProxySettings::find_by_id((proxy_id, EpgFallbacks::KEY.to_string()))
+                        .one(db)
+                        .await?
+            {
+                parts.push(format!("fallbacks:{}", fallbacks.value));
+            }
+        }
+        parts.push(format!("helpers:{}", self.helper_processor.is_some()));
+
+        Ok(parts.join("\n"))
+    }
+}
+
 impl ProgressAware for DataMappingStage {
     fn get_progress_manager(&self) -> Option<&Arc<ProgressManager>> {
         self.progress_manager.as_ref()
@@ -1265,6 +1542,16 @@ impl PipelineStage for DataMappingStage {
         Ok(artifacts)
     }
 
+    async fn cache_fingerprint(&self) -> Option<String> {
+        match self.input_fingerprint().await {
+            Ok(fingerprint) => Some(fingerprint),
+            Err(e) => {
+                warn!("Failed to fingerprint data mapping inputs: {}", e);
+                None
+            }
+        }
+    }
+
     fn stage_id(&self) -> &'static str {
         "data_mapping"
     }
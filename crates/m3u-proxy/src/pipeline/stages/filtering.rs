@@ -3,12 +3,13 @@
 //! This module provides filtering capabilities for channel and EPG data using
 //! configurable filter rules with extensible design and time function support.
 
+use crate::database::repositories::channel_exclusion::ChannelExclusionSeaOrmRepository;
 use crate::database::repositories::filter::FilterSeaOrmRepository;
 use crate::database::repositories::stream_proxy::StreamProxySeaOrmRepository;
+use crate::models::channel_exclusion::ChannelExclusionSet;
 use crate::models::{Channel, FilterSourceType};
 use crate::pipeline::engines::{
-    ChannelFilteringEngine, EpgFilterProcessor, FilterEngineResult, FilteringEngine,
-    RegexEvaluator, StreamFilterProcessor,
+    EpgFilterProcessor, FilterEngineResult, FilterPlanBuilder, FilteringEngine, RegexEvaluator,
 };
 use crate::pipeline::error::PipelineError;
 use crate::pipeline::models::{ArtifactType, ContentType, PipelineArtifact};
@@ -36,6 +37,7 @@ struct FilterRule {
 pub struct FilteringStage {
     proxy_repository: StreamProxySeaOrmRepository,
     filter_repository: FilterSeaOrmRepository,
+    exclusion_repository: ChannelExclusionSeaOrmRepository,
     file_manager: SandboxedManager,
 
     regex_preprocessor: RegexPreprocessor,
@@ -55,11 +57,13 @@ impl FilteringStage {
 
         // Create repositories using the Arc<DatabaseConnection>
         let proxy_repository = StreamProxySeaOrmRepository::new(db_connection.clone());
-        let filter_repository = FilterSeaOrmRepository::new(db_connection);
+        let filter_repository = FilterSeaOrmRepository::new(db_connection.clone());
+        let exclusion_repository = ChannelExclusionSeaOrmRepository::new(db_connection);
 
         Ok(Self {
             proxy_repository,
             filter_repository,
+            exclusion_repository,
             file_manager,
             regex_preprocessor,
             proxy_id,
@@ -245,6 +249,8 @@ impl FilteringStage {
             info!("No channel filter rules found, passing through unchanged");
             let channels = self.read_channels_from_artifact(&artifact).await?;
             let input_count = channels.len();
+            let channels = self.apply_channel_exclusions(channels).await?;
+            let output_count = channels.len();
             info!(
                 "Passthrough: read {} channels, creating filtered artifact",
                 input_count
@@ -272,7 +278,7 @@ impl FilteringStage {
                 output_artifact,
                 std::collections::HashMap::new(),
                 input_count,
-                input_count,
+                output_count,
             ));
         }
 
@@ -282,23 +288,27 @@ impl FilteringStage {
         let channels = self.read_channels_from_artifact(&artifact).await?;
         debug!("Read channels from input artifact count={}", channels.len());
 
-        // Create filtering engine and add processors
-        let mut filtering_engine = ChannelFilteringEngine::new();
+        // Compile the filters into a single evaluation plan
+        let compile_start = Instant::now();
+        let mut plan_builder = FilterPlanBuilder::new(self.regex_preprocessor.clone());
         let mut filter_name_map = std::collections::HashMap::new();
         let mut filter_priority_map = std::collections::HashMap::new();
         for rule in &filter_rules {
             filter_name_map.insert(rule.id.clone(), rule.name.clone());
             filter_priority_map.insert(rule.id.clone(), rule.priority_order);
-            let regex_evaluator = RegexEvaluator::new(self.regex_preprocessor.clone());
-            let processor = StreamFilterProcessor::new(
+            plan_builder.add_filter(
                 rule.id.clone(),
                 rule.name.clone(),
                 rule.is_inverse,
                 &rule.expression,
-                regex_evaluator,
             )?;
-            filtering_engine.add_filter_processor(Box::new(processor));
         }
+        let mut filter_plan = plan_builder.build();
+        debug!(
+            "Compiled channel filter plan filters={} duration={}",
+            filter_rules.len(),
+            crate::utils::human_format::format_duration_precise(compile_start.elapsed())
+        );
 
         // Process channels through filtering engine with progress updates
         self.report_progress(
@@ -310,7 +320,7 @@ impl FilteringStage {
             ),
         )
         .await;
-        let filter_result = filtering_engine.process_records(&channels)?;
+        let filter_result = filter_plan.process_records(&channels);
 
         // Log filtering results
         self.log_filtering_results(&filter_result, "channels");
@@ -325,19 +335,24 @@ impl FilteringStage {
         )
         .await;
 
+        let output_channels = self
+            .apply_channel_exclusions(filter_result.filtered_records)
+            .await?;
+        let output_count = output_channels.len();
+
         // Write filtered channels to new artifact
         let filtered_file_path = artifact
             .file_path
             .replace("_mapping_channels.jsonl", "_filtered_channels.jsonl");
         let output_artifact = self
-            .write_channels_to_artifact(filter_result.filtered_records, &filtered_file_path)
+            .write_channels_to_artifact(output_channels, &filtered_file_path)
             .await?;
 
         info!(
             "Completed channel filtering duration={} input_channels={} output_channels={}",
             crate::utils::human_format::format_duration_precise(process_start.elapsed()),
             filter_result.total_input,
-            filter_result.total_filtered
+            output_count
         );
 
         info!(
@@ -366,10 +381,37 @@ impl FilteringStage {
             output_artifact,
             filter_stats_with_names,
             filter_result.total_input,
-            filter_result.total_filtered,
+            output_count,
         ))
     }
 
+    /// Drop the channels and groups excluded from the proxy
+    async fn apply_channel_exclusions(
+        &self,
+        channels: Vec<Channel>,
+    ) -> Result<Vec<Channel>, Box<dyn std::error::Error>> {
+        let Some(proxy_id) = self.proxy_id else {
+            return Ok(channels);
+        };
+        let exclusions =
+            ChannelExclusionSet::new(&self.exclusion_repository.list_for_proxy(&proxy_id).await?);
+        if exclusions.is_empty() {
+            return Ok(channels);
+        }
+
+        let input_count = channels.len();
+        let channels: Vec<Channel> = channels
+            .into_iter()
+            .filter(|channel| !exclusions.excludes(channel))
+            .collect();
+        info!(
+            "Applied proxy channel exclusions excluded={} remaining={}",
+            input_count - channels.len(),
+            channels.len()
+        );
+        Ok(channels)
+    }
+
     async fn process_epg_artifact(
         &mut self,
         artifact: PipelineArtifact,
@@ -793,6 +835,61 @@ impl PipelineStage for FilteringStage {
         Ok(result)
     }
 
+    async fn cache_fingerprint(&self) -> Option<String> {
+        let Some(proxy_id) = self.proxy_id else {
+            return Some(String::new());
+        };
+        let proxy_filters = match self.proxy_repository.get_proxy_filters(proxy_id).await {
+            Ok(filters) => filters,
+            Err(e) => {
+                warn!("Failed to fingerprint filters of proxy {}: {}", proxy_id, e);
+                return None;
+            }
+        };
+
+        let mut parts = Vec::with_capacity(proxy_filters.len());
+        for proxy_filter in proxy_filters {
+            let updated_at = match self
+                .filter_repository
+                .find_by_id(proxy_filter.filter_id)
+                .await
+            {
+                Ok(filter) => filter.map(|f| f.updated_at.timestamp_millis()),
+                Err(e) => {
+                    warn!(
+                        "Failed to fingerprint filter {}: {}",
+                        proxy_filter.filter_id, e
+                    );
+                    return None;
+                }
+            };
+            parts.push(format!(
+                "{}:{}:{}:{:?}",
+                proxy_filter.filter_id,
+                proxy_filter.priority_order,
+                proxy_filter.is_active,
+                updated_at
+            ));
+        }
+
+        match self.exclusion_repository.list_for_proxy(&proxy_id).await {
+            Ok(exclusions) => parts.extend(exclusions.into_iter().map(|exclusion| {
+                format!(
+                    "exclude:{}:{:?}:{:?}",
+                    exclusion.id, exclusion.channel_id, exclusion.group_title
+                )
+            })),
+            Err(e) => {
+                warn!(
+                    "Failed to fingerprint channel exclusions of proxy {}: {}",
+                    proxy_id, e
+                );
+                return None;
+            }
+        }
+        Some(parts.join("\n"))
+    }
+
     fn stage_id(&self) -> &'static str {
         "filtering"
     }
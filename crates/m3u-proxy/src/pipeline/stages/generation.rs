@@ -1,6 +1,7 @@
 use anyhow::Result;
+use chrono_tz::Tz;
 use sandboxed_file_manager::SandboxedManager;
-use sea_orm::DatabaseConnection;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
 use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -8,18 +9,46 @@ use tokio::io::AsyncWriteExt;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use crate::models::{ChannelNumberAssignmentType, NumberedChannel};
+use crate::config::{AttributePassthroughConfig, EpgGapFillerConfig, StreamHintsConfig};
+use crate::database::repositories::{
+    ChannelEpgMappingSeaOrmRepository, ChannelLogoAssignmentSeaOrmRepository,
+    EpgChannelMetadataSeaOrmRepository, LastKnownCodecSeaOrmRepository,
+    UrlRewriteRuleSeaOrmRepository,
+};
+use crate::entities::prelude::{ProxyEpgSources, ProxySources};
+use crate::entities::{proxy_epg_sources, proxy_sources};
+use crate::models::channel_epg_mapping::ChannelEpgMappingSet;
+use crate::models::channel_logo_assignment::ChannelLogoAssignmentSet;
+use crate::models::epg_channel_metadata::EpgChannelMetadata;
+use crate::models::url_rewrite_rule::UrlRewriteRuleSet;
+use crate::models::{BackupStreamMode, Channel, ChannelNumberAssignmentType, NumberedChannel};
 // (Removed EPG filtering imports – filtering now occurs in FilteringStage)
 use crate::pipeline::engines::rule_processor::EpgProgram;
 use crate::pipeline::error::PipelineError;
 use crate::pipeline::models::{ArtifactType, ContentType, PipelineArtifact, ProcessingStage};
+use crate::pipeline::services::{BackupStreamPlanner, EpgGapFiller, GapFillChannel};
 use crate::pipeline::traits::{PipelineStage, ProgressAware};
 use crate::services::progress_service::ProgressManager;
+use crate::utils::stream_hints::{self, StreamContainer};
+use crate::utils::time::apply_time_offset;
 // (Removed regex preprocessor imports – EPG filtering moved out of GenerationStage)
 
 /// Progress update interval for combined progress reporting
 const PROGRESS_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
 
+/// Group title of backup entries in [`BackupStreamMode::Group`] playlists
+const BACKUP_GROUP_TITLE: &str = "Backup Streams";
+
+/// EXTINF attributes written from channel columns, never passed through from the source
+const GENERATED_ATTRIBUTES: &[&str] = &[
+    "tvg-id",
+    "tvg-name",
+    "tvg-logo",
+    "group-title",
+    "tvg-chno",
+    "backup-url",
+];
+
 /// Helper for tracking combined progress across channels and programs
 struct ProgressTracker {
     processed_units: usize,
@@ -76,6 +105,82 @@ struct ChannelInfo {
     stream_display_names: BTreeSet<String>, // From M3U channels (by tvg_id)
     logo_url: Option<String>,               // Logo from first detected channel
     group_title: Option<String>,            // Group title from channel for category fallback
+    epg_channel_id: String,                 // EPG channel the programmes are taken from
+    shift_seconds: i32,                     // Per-channel EPG shift applied to programme times
+}
+
+/// EPG shift of a channel in seconds, from its epg_shift or else its tvg_shift (0 when
+/// unset or invalid)
+fn channel_epg_shift(channel: &crate::models::Channel) -> i32 {
+    let Some(shift) = channel.effective_epg_shift() else {
+        return 0;
+    };
+    crate::utils::time::parse_epg_shift(shift).unwrap_or_else(|e| {
+        warn!(
+            "Ignoring invalid EPG shift '{}' on channel '{}': {}",
+            shift, channel.channel_name, e
+        );
+        0
+    })
+}
+
+/// XMLTV timestamp of a programme time moved by a channel's shift, written in the
+/// proxy's output timezone (UTC when unset)
+///
+/// Shifts apply to the UTC instant before conversion, so shifts across midnight or a DST
+/// change keep programme durations and the offset always matches the local time.
+fn xmltv_programme_time(
+    time: chrono::DateTime<chrono::Utc>,
+    shift_seconds: i32,
+    timezone: Option<Tz>,
+) -> String {
+    let shifted = apply_time_offset(time, shift_seconds);
+    match timezone {
+        Some(tz) => shifted
+            .with_timezone(&tz)
+            .format("%Y%m%d%H%M%S %z")
+            .to_string(),
+        None => shifted.format("%Y%m%d%H%M%S %z").to_string(),
+    }
+}
+
+/// One entry of the generated playlist
+struct M3uEntry<'a> {
+    channel: &'a Channel,
+    /// Group title replacing the channel's own (backup entries)
+    group_title: Option<&'static str>,
+    /// Proxy URLs of the channel's backup streams (`backup-url` attribute)
+    backup_urls: Vec<String>,
+}
+
+/// XMLTV channel id for a channel: its tvg_id, or for a shifted channel a separate guide
+/// channel `<tvg_id><shift>` (e.g. "bbc1.uk+1h") so it can coexist with the unshifted one
+fn xmltv_channel_id(tvg_id: &str, shift_seconds: i32) -> String {
+    if shift_seconds == 0 {
+        tvg_id.to_string()
+    } else {
+        format!(
+            "{}{}",
+            tvg_id,
+            crate::utils::time::format_epg_shift(shift_seconds)
+        )
+    }
+}
+
+/// Allowlisted extra EXTINF attributes of a channel, as ` name="value"` pairs
+fn passthrough_attributes(channel: &Channel, allowlist: &[String]) -> String {
+    channel
+        .extra_attributes
+        .iter()
+        .filter(|(name, value)| {
+            !value.is_empty()
+                && allowlist.iter().any(|a| a.eq_ignore_ascii_case(name))
+                && !GENERATED_ATTRIBUTES
+                    .iter()
+                    .any(|g| g.eq_ignore_ascii_case(name))
+        })
+        .map(|(name, value)| format!(" {name}=\"{value}\""))
+        .collect()
 }
 
 /// Generation stage - streams to temporary files in pipeline storage
@@ -87,7 +192,16 @@ pub struct GenerationStage {
 
     base_url: String,
     progress_manager: Option<Arc<ProgressManager>>,
-    _db_connection: Arc<DatabaseConnection>, // prefixed underscore to silence unused field warning (retained for future DB use)
+    gap_filler: Option<EpgGapFiller>,
+    backup_streams: BackupStreamMode,
+    epg_languages: Vec<String>,
+    output_timezone: Option<Tz>,
+    stream_hints: Option<StreamHintsConfig>,
+    /// Extra EXTINF attributes passed through from the source playlist
+    passthrough_attributes: Vec<String>,
+    /// Streams are served through the relay, which always outputs MPEG-TS
+    relay_output: bool,
+    db_connection: Arc<DatabaseConnection>,
 }
 
 impl GenerationStage {
@@ -105,10 +219,54 @@ impl GenerationStage {
             proxy_id,
             base_url,
             progress_manager,
-            _db_connection: db_connection,
+            gap_filler: None,
+            backup_streams: BackupStreamMode::Off,
+            epg_languages: Vec::new(),
+            output_timezone: None,
+            stream_hints: None,
+            passthrough_attributes: Vec::new(),
+            relay_output: false,
+            db_connection,
         })
     }
 
+    /// Emit streams of the same channel from lower-priority sources as backups
+    pub fn with_backup_streams(mut self, mode: BackupStreamMode) -> Self {
+        self.backup_streams = mode;
+        self
+    }
+
+    /// Name guide channels in the first of these languages the EPG sources provide
+    pub fn with_epg_languages(mut self, languages: Vec<String>) -> Self {
+        self.epg_languages = languages;
+        self
+    }
+
+    /// Write XMLTV programme times in this timezone instead of UTC
+    pub fn with_output_timezone(mut self, timezone: Option<Tz>) -> Self {
+        self.output_timezone = timezone;
+        self
+    }
+
+    /// Emit container hints (and configured pipe-delimited headers) with playlist entries
+    pub fn with_stream_hints(mut self, config: StreamHintsConfig, relay_output: bool) -> Self {
+        self.stream_hints = Some(config);
+        self.relay_output = relay_output;
+        self
+    }
+
+    /// Write the allowlisted extra EXTINF attributes preserved at ingestion
+    pub fn with_attribute_passthrough(mut self, config: AttributePassthroughConfig) -> Self {
+        self.passthrough_attributes = config.attributes;
+        self
+    }
+
+    /// Fill guide gaps with synthetic programmes during XMLTV generation
+    pub fn with_epg_gap_filler(mut self, config: EpgGapFillerConfig) -> Self {
+        self.gap_filler = Some(EpgGapFiller::new(config));
+        self
+    }
+
     /// Helper method for reporting progress
     async fn report_progress(&self, percentage: f64, message: &str) {
         if let Some(pm) = &self.progress_manager
@@ -128,11 +286,84 @@ impl GenerationStage {
     /// Generate temporary M3U and XMLTV files for atomic publishing
     pub async fn process_channels_and_programs(
         &self,
-        numbered_channels: Vec<NumberedChannel>,
-        epg_programs: Vec<EpgProgram>,
+        mut numbered_channels: Vec<NumberedChannel>,
+        mut epg_programs: Vec<EpgProgram>,
     ) -> Result<Vec<PipelineArtifact>> {
         let process_start = Instant::now();
 
+        // Manual EPG mappings take precedence over matching by the channel's own tvg-id
+        let mappings = ChannelEpgMappingSeaOrmRepository::new(self.db_connection.clone())
+            .list_for_proxy(&self.proxy_id)
+            .await?;
+        let mapping_set = ChannelEpgMappingSet::new(&mappings);
+        if !mapping_set.is_empty() {
+            let applied = numbered_channels
+                .iter_mut()
+                .filter(|numbered| mapping_set.apply(&mut numbered.channel))
+                .count();
+            info!(
+                "Applied manual EPG mappings: proxy_id={} mapped_channels={}",
+                self.proxy_id, applied
+            );
+        }
+
+        // Uploaded logos assigned to channels replace the logos of their sources
+        let assignments = ChannelLogoAssignmentSeaOrmRepository::new(self.db_connection.clone())
+            .list()
+            .await?;
+        let assignment_set = ChannelLogoAssignmentSet::new(&assignments, &self.base_url);
+        if !assignment_set.is_empty() {
+            let applied = numbered_channels
+                .iter_mut()
+                .filter(|numbered| assignment_set.apply(&mut numbered.channel))
+                .count();
+            info!(
+                "Applied channel logo assignments: proxy_id={} assigned_channels={}",
+                self.proxy_id, applied
+            );
+        }
+
+        // Provider-specific stream URL rewrites (ports, tokens, schemes)
+        let rewrite_rules = UrlRewriteRuleSeaOrmRepository::new(self.db_connection.clone())
+            .list_active_for_proxy(&self.proxy_id)
+            .await?;
+        let rewrite_set = UrlRewriteRuleSet::new(&rewrite_rules);
+        if !rewrite_set.is_empty() {
+            let applied = numbered_channels
+                .iter_mut()
+                .filter(|numbered| rewrite_set.apply(&mut numbered.channel))
+                .count();
+            info!(
+                "Applied URL rewrite rules: proxy_id={} rewritten_channels={}",
+                self.proxy_id, applied
+            );
+        }
+
+        if let Some(gap_filler) = &self.gap_filler {
+            let channels: Vec<GapFillChannel> = numbered_channels
+                .iter()
+                .filter_map(|numbered| {
+                    let channel = &numbered.channel;
+                    channel
+                        .tvg_id
+                        .as_ref()
+                        .filter(|id| !id.is_empty())
+                        .map(|tvg_id| GapFillChannel {
+                            epg_channel_id: tvg_id.clone(),
+                            channel_name: channel.channel_name.clone(),
+                            group_title: channel.group_title.clone(),
+                        })
+                })
+                .collect();
+            let filler = gap_filler.fill(&channels, &epg_programs, chrono::Utc::now());
+            info!(
+                "EPG gap filler: proxy_id={} synthetic_programs={}",
+                self.proxy_id,
+                filler.len()
+            );
+            epg_programs.extend(filler);
+        }
+
         // Calculate total work units for combined progress reporting
         let total_channels = numbered_channels.len();
         let total_programs = epg_programs.len();
@@ -179,9 +410,11 @@ impl GenerationStage {
             .await;
         let xmltv_gen_start = std::time::Instant::now();
         let temp_xmltv_file = format!("{}_temp.xmltv", self.pipeline_execution_prefix);
+        let channel_metadata = self.load_channel_metadata().await?;
         let xmltv_bytes = self
             .generate_xmltv_streaming(
                 &channel_map,
+                &channel_metadata,
                 &epg_programs,
                 &temp_xmltv_file,
                 &mut progress_tracker,
@@ -259,12 +492,15 @@ impl GenerationStage {
         // Collect stream channel info from M3U (source of truth)
         for numbered_channel in numbered_channels {
             if let Some(ref tvg_id) = numbered_channel.channel.tvg_id {
+                let shift_seconds = channel_epg_shift(&numbered_channel.channel);
                 let entry = channel_map
-                    .entry(tvg_id.clone())
+                    .entry(xmltv_channel_id(tvg_id, shift_seconds))
                     .or_insert_with(|| ChannelInfo {
                         stream_display_names: BTreeSet::new(),
                         logo_url: None,
                         group_title: None,
+                        epg_channel_id: tvg_id.clone(),
+                        shift_seconds,
                     });
 
                 // Add stream display names (channel_name and tvg_name)
@@ -299,6 +535,40 @@ impl GenerationStage {
         Ok(channel_map)
     }
 
+    /// Multilingual channel metadata of the proxy's EPG sources by EPG channel id, the
+    /// highest-priority source winning (empty without a language preference)
+    async fn load_channel_metadata(&self) -> Result<HashMap<String, EpgChannelMetadata>> {
+        if self.epg_languages.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let source_priority: Vec<Uuid> = ProxyEpgSources::find()
+            .filter(proxy_epg_sources::Column::ProxyId.eq(self.proxy_id))
+            .order_by_asc(proxy_epg_sources::Column::PriorityOrder)
+            .all(&*self.db_connection)
+            .await?
+            .into_iter()
+            .map(|m| m.epg_source_id)
+            .collect();
+        let mut metadata = EpgChannelMetadataSeaOrmRepository::new(self.db_connection.clone())
+            .list_for_sources(&source_priority)
+            .await?;
+        metadata.sort_by_key(|m| source_priority.iter().position(|id| *id == m.source_id));
+
+        let mut by_channel = HashMap::new();
+        for channel in metadata {
+            by_channel
+                .entry(channel.channel_id.clone())
+                .or_insert(channel);
+        }
+        debug!(
+            "Loaded multilingual EPG channel metadata: proxy_id={} channels={} languages={:?}",
+            self.proxy_id,
+            by_channel.len(),
+            self.epg_languages
+        );
+        Ok(by_channel)
+    }
+
     /// Generate M3U content streaming to temporary file
     async fn generate_m3u_streaming(
         &self,
@@ -322,8 +592,13 @@ impl GenerationStage {
         let mut bytes_written = 7u64; // "#EXTM3U\n"
         let mut channels_written = 0;
 
-        for numbered_channel in numbered_channels {
-            let channel = &numbered_channel.channel;
+        let probed_containers = match &self.stream_hints {
+            Some(_) if !self.relay_output => self.probed_containers(numbered_channels).await,
+            _ => HashMap::new(),
+        };
+
+        for entry in self.playlist_entries(numbered_channels).await? {
+            let channel = entry.channel;
 
             // Update combined progress
             progress_tracker.update(self, false).await;
@@ -331,10 +606,11 @@ impl GenerationStage {
             // Build EXTINF line with conditional attributes
             let mut extinf_line = "#EXTINF:-1".to_string();
 
-            // Add tvg-id if present
+            // Add tvg-id if present (shifted channels point at their own guide channel)
             if let Some(ref tvg_id) = channel.tvg_id
                 && !tvg_id.is_empty()
             {
+                let tvg_id = xmltv_channel_id(tvg_id, channel_epg_shift(channel));
                 extinf_line.push_str(&format!(" tvg-id=\"{tvg_id}\""));
             }
 
@@ -352,8 +628,8 @@ impl GenerationStage {
                 extinf_line.push_str(&format!(" tvg-logo=\"{tvg_logo}\""));
             }
 
-            // Add group-title if present
-            if let Some(ref group_title) = channel.group_title
+            // Add group-title if present (backup entries go to the backup group)
+            if let Some(group_title) = entry.group_title.or(channel.group_title.as_deref())
                 && !group_title.is_empty()
             {
                 extinf_line.push_str(&format!(" group-title=\"{group_title}\""));
@@ -366,6 +642,19 @@ impl GenerationStage {
                 extinf_line.push_str(&format!(" tvg-chno=\"{tvg_chno}\""));
             }
 
+            // Add extra source attributes (catchup, timeshift, ...) on the allowlist
+            if !self.passthrough_attributes.is_empty() {
+                extinf_line.push_str(&passthrough_attributes(
+                    channel,
+                    &self.passthrough_attributes,
+                ));
+            }
+
+            // Add backup streams from lower-priority sources
+            if !entry.backup_urls.is_empty() {
+                extinf_line.push_str(&format!(" backup-url=\"{}\"", entry.backup_urls.join(",")));
+            }
+
             // Add channel name and newline
             extinf_line.push_str(&format!(",{}\n", channel.channel_name));
 
@@ -373,15 +662,30 @@ impl GenerationStage {
             writer.write_all(extinf_line.as_bytes()).await?;
             bytes_written += extinf_line.len() as u64;
 
+            // Name the container so players pick the right demuxer
+            let mut stream_url = self.proxy_stream_url(channel);
+            if let Some(hints) = &self.stream_hints {
+                let container = if self.relay_output {
+                    Some(StreamContainer::MpegTs)
+                } else {
+                    probed_containers
+                        .get(channel.stream_url.as_str())
+                        .copied()
+                        .or_else(|| StreamContainer::from_url(&channel.stream_url))
+                };
+                if let Some(container) = container {
+                    for line in stream_hints::hint_lines(container, hints) {
+                        let line = format!("{line}\n");
+                        writer.write_all(line.as_bytes()).await?;
+                        bytes_written += line.len() as u64;
+                    }
+                }
+                stream_url = stream_hints::with_pipe_headers(&stream_url, &hints.headers);
+            }
+
             // Write proxy stream URL instead of original URL
             // This allows the proxy to capture metrics and implement relays
-            let proxy_stream_url = format!(
-                "{}/stream/{}/{}",
-                self.base_url.trim_end_matches('/'),
-                crate::utils::uuid_parser::uuid_to_base64(&self.proxy_id),
-                crate::utils::uuid_parser::uuid_to_base64(&channel.id)
-            );
-            let stream_line = format!("{proxy_stream_url}\n");
+            let stream_line = format!("{stream_url}\n");
             writer.write_all(stream_line.as_bytes()).await?;
             bytes_written += stream_line.len() as u64;
 
@@ -404,10 +708,105 @@ impl GenerationStage {
         Ok(bytes_written)
     }
 
+    /// Containers of the channels' upstream streams from their last probe
+    ///
+    /// Lookup failures only cost the hints, so they leave the map empty.
+    async fn probed_containers(
+        &self,
+        numbered_channels: &[NumberedChannel],
+    ) -> HashMap<String, StreamContainer> {
+        let stream_urls: Vec<&str> = numbered_channels
+            .iter()
+            .map(|numbered| numbered.channel.stream_url.as_str())
+            .collect();
+        match LastKnownCodecSeaOrmRepository::new(self.db_connection.clone())
+            .find_container_formats(&stream_urls)
+            .await
+        {
+            Ok(formats) => formats
+                .into_iter()
+                .filter_map(|(url, format)| {
+                    Some((url, StreamContainer::from_format_name(&format)?))
+                })
+                .collect(),
+            Err(e) => {
+                warn!("Failed to load probed stream containers: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Proxy stream URL of a channel
+    fn proxy_stream_url(&self, channel: &Channel) -> String {
+        format!(
+            "{}/stream/{}/{}",
+            self.base_url.trim_end_matches('/'),
+            crate::utils::uuid_parser::uuid_to_base64(&self.proxy_id),
+            crate::utils::uuid_parser::uuid_to_base64(&channel.id)
+        )
+    }
+
+    /// Playlist entries in output order, applying the proxy's backup stream mode
+    async fn playlist_entries<'a>(
+        &self,
+        numbered_channels: &'a [NumberedChannel],
+    ) -> Result<Vec<M3uEntry<'a>>> {
+        let entry = |numbered: &'a NumberedChannel| M3uEntry {
+            channel: &numbered.channel,
+            group_title: None,
+            backup_urls: Vec::new(),
+        };
+        if self.backup_streams == BackupStreamMode::Off {
+            return Ok(numbered_channels.iter().map(entry).collect());
+        }
+
+        let source_priority: Vec<Uuid> = ProxySources::find()
+            .filter(proxy_sources::Column::ProxyId.eq(self.proxy_id))
+            .order_by_asc(proxy_sources::Column::PriorityOrder)
+            .all(&*self.db_connection)
+            .await?
+            .into_iter()
+            .map(|m| m.source_id)
+            .collect();
+        let groups = BackupStreamPlanner::new(&source_priority).plan(numbered_channels);
+
+        let mut entries = Vec::with_capacity(numbered_channels.len());
+        let mut backup_count = 0;
+        for group in groups {
+            backup_count += group.backups.len();
+            let backups = group.backups.iter().map(|&idx| &numbered_channels[idx]);
+            match self.backup_streams {
+                BackupStreamMode::Attribute => entries.push(M3uEntry {
+                    backup_urls: backups
+                        .map(|numbered| self.proxy_stream_url(&numbered.channel))
+                        .collect(),
+                    ..entry(&numbered_channels[group.primary])
+                }),
+                _ => {
+                    entries.push(entry(&numbered_channels[group.primary]));
+                    entries.extend(backups.map(|numbered| M3uEntry {
+                        group_title: Some(BACKUP_GROUP_TITLE),
+                        ..entry(numbered)
+                    }));
+                }
+            }
+        }
+
+        info!(
+            "Backup streams: proxy_id={} mode={:?} primary_channels={} backup_streams={}",
+            self.proxy_id,
+            self.backup_streams,
+            numbered_channels.len() - backup_count,
+            backup_count
+        );
+        Ok(entries)
+    }
+
     /// Generate XMLTV content using proper serialization to temporary file
     async fn generate_xmltv_streaming(
         &self,
         channel_map: &HashMap<String, ChannelInfo>,
+        channel_metadata: &HashMap<String, EpgChannelMetadata>,
         epg_programs: &[EpgProgram],
         temp_file_path: &str,
         progress_tracker: &mut ProgressTracker,
@@ -445,29 +844,45 @@ impl GenerationStage {
 
         // Write channel definitions (only M3U channels - database-first approach)
         for (channel_id, channel_info) in channel_map {
-            // Use stream display names (M3U channels are source of truth)
-            let display_name = if !channel_info.stream_display_names.is_empty() {
-                channel_info
-                    .stream_display_names
-                    .iter()
-                    .next()
-                    .unwrap()
-                    .clone()
-            } else {
-                channel_id.clone()
-            };
+            // Use stream display names (M3U channels are source of truth), unless the EPG
+            // provides one in a preferred language
+            let metadata = channel_metadata.get(&channel_info.epg_channel_id);
+            let (display_name, display_lang) =
+                match metadata.and_then(|m| m.preferred_display_name(&self.epg_languages)) {
+                    Some(name) => (name.value.clone(), name.lang.as_deref()),
+                    None => (
+                        channel_info
+                            .stream_display_names
+                            .iter()
+                            .next()
+                            .cloned()
+                            .unwrap_or_else(|| channel_id.clone()),
+                        None,
+                    ),
+                };
 
             let mut channel_line = format!(
                 "  <channel id=\"{}\">\n",
                 quick_xml::escape::escape(channel_id)
             );
-            channel_line.push_str(&format!(
-                "    <display-name>{}</display-name>\n",
-                quick_xml::escape::escape(&display_name)
-            ));
+            match display_lang {
+                Some(lang) => channel_line.push_str(&format!(
+                    "    <display-name lang=\"{}\">{}</display-name>\n",
+                    quick_xml::escape::escape(lang),
+                    quick_xml::escape::escape(&display_name)
+                )),
+                None => channel_line.push_str(&format!(
+                    "    <display-name>{}</display-name>\n",
+                    quick_xml::escape::escape(&display_name)
+                )),
+            }
 
-            // Add logo if present
-            if let Some(ref logo_url) = channel_info.logo_url
+            // Add logo if present, preferring an icon in a preferred language
+            let logo_url = metadata
+                .and_then(|m| m.preferred_icon(&self.epg_languages))
+                .map(|icon| &icon.value)
+                .or(channel_info.logo_url.as_ref());
+            if let Some(logo_url) = logo_url
                 && !logo_url.is_empty()
             {
                 channel_line.push_str(&format!(
@@ -486,114 +901,126 @@ impl GenerationStage {
         let mut programs_filtered_by_channel = 0;
         // EPG rule-level filtering already applied earlier in pipeline; no additional counters needed here
 
+        // Guide channels fed by each EPG channel (more than one when shifted copies exist)
+        let mut guide_targets: HashMap<&str, Vec<(&String, &ChannelInfo)>> = HashMap::new();
+        for (xmltv_id, info) in channel_map {
+            guide_targets
+                .entry(info.epg_channel_id.as_str())
+                .or_default()
+                .push((xmltv_id, info));
+        }
+
         for program in epg_programs {
             // Update combined progress
             progress_tracker.update(self, false).await;
 
             // CRITICAL: Only include programs for channels that exist in M3U
-            if !channel_map.contains_key(&program.channel_id) {
+            // (EPG inclusion already determined prior to this stage)
+            let Some(targets) = guide_targets.get(program.channel_id.as_str()) else {
                 programs_filtered_by_channel += 1;
                 continue;
-            }
-
-            // EPG inclusion already determined prior to this stage; only channel existence is enforced here.
-            if !channel_map.contains_key(&program.channel_id) {
-                continue;
-            }
-
-            let start_time = program.start_time.format("%Y%m%d%H%M%S %z");
-            let stop_time = program.end_time.format("%Y%m%d%H%M%S %z");
-
-            let mut program_line = format!(
-                "  <programme start=\"{}\" stop=\"{}\" channel=\"{}\">\n",
-                start_time,
-                stop_time,
-                quick_xml::escape::escape(&program.channel_id)
-            );
+            };
 
-            program_line.push_str(&format!(
-                "    <title>{}</title>\n",
-                quick_xml::escape::escape(&program.title)
-            ));
+            for (xmltv_id, info) in targets {
+                let start_time = xmltv_programme_time(
+                    program.start_time,
+                    info.shift_seconds,
+                    self.output_timezone,
+                );
+                let stop_time = xmltv_programme_time(
+                    program.end_time,
+                    info.shift_seconds,
+                    self.output_timezone,
+                );
+
+                let mut program_line = format!(
+                    "  <programme start=\"{}\" stop=\"{}\" channel=\"{}\">\n",
+                    start_time,
+                    stop_time,
+                    quick_xml::escape::escape(xmltv_id.as_str())
+                );
 
-            if let Some(description) = program.description.as_ref().filter(|d| !d.is_empty()) {
                 program_line.push_str(&format!(
-                    "    <desc>{}</desc>\n",
-                    quick_xml::escape::escape(description)
+                    "    <title>{}</title>\n",
+                    quick_xml::escape::escape(&program.title)
                 ));
-            }
 
-            // Add category with priority: program_category > channel_group_title > null
-            let category = program
-                .program_category
-                .as_ref()
-                .filter(|c| !c.is_empty())
-                .or_else(|| {
-                    // Fallback to channel group_title if no program category
-                    channel_map
-                        .get(&program.channel_id)
-                        .and_then(|info| info.group_title.as_ref())
-                        .filter(|c| !c.is_empty())
-                });
-
-            if let Some(cat) = category {
-                program_line.push_str(&format!(
-                    "    <category>{}</category>\n",
-                    quick_xml::escape::escape(cat)
-                ));
-            }
+                if let Some(description) = program.description.as_ref().filter(|d| !d.is_empty()) {
+                    program_line.push_str(&format!(
+                        "    <desc>{}</desc>\n",
+                        quick_xml::escape::escape(description)
+                    ));
+                }
 
-            // Add subtitles as sub-title
-            if let Some(subtitles) = program.subtitles.as_ref().filter(|s| !s.is_empty()) {
-                program_line.push_str(&format!(
-                    "    <sub-title>{}</sub-title>\n",
-                    quick_xml::escape::escape(subtitles)
-                ));
-            }
+                // Add category with priority: program_category > channel_group_title > null
+                let category = program
+                    .program_category
+                    .as_ref()
+                    .filter(|c| !c.is_empty())
+                    .or_else(|| {
+                        // Fallback to channel group_title if no program category
+                        info.group_title.as_ref().filter(|c| !c.is_empty())
+                    });
 
-            // Add episode numbering if available (XMLTV format: season.episode.part/total)
-            if let (Some(season), Some(episode)) =
-                (program.season_num.as_ref(), program.episode_num.as_ref())
-                && let (Ok(s), Ok(e)) = (season.parse::<i32>(), episode.parse::<i32>())
-                && s > 0
-                && e > 0
-            {
-                program_line.push_str(&format!(
-                    "    <episode-num system=\"xmltv_ns\">.{}.{}/1</episode-num>\n",
-                    s - 1,
-                    e - 1
-                ));
-            }
+                if let Some(cat) = category {
+                    program_line.push_str(&format!(
+                        "    <category>{}</category>\n",
+                        quick_xml::escape::escape(cat)
+                    ));
+                }
 
-            // Add language if specified
-            if let Some(language) = program.language.as_ref().filter(|l| !l.is_empty()) {
-                program_line.push_str(&format!(
-                    "    <language>{}</language>\n",
-                    quick_xml::escape::escape(language)
-                ));
-            }
+                // Add subtitles as sub-title
+                if let Some(subtitles) = program.subtitles.as_ref().filter(|s| !s.is_empty()) {
+                    program_line.push_str(&format!(
+                        "    <sub-title>{}</sub-title>\n",
+                        quick_xml::escape::escape(subtitles)
+                    ));
+                }
 
-            // Add rating if available
-            if let Some(rating) = program.rating.as_ref().filter(|r| !r.is_empty()) {
-                program_line.push_str(&format!(
-                    "    <rating system=\"MPAA\"><value>{}</value></rating>\n",
-                    quick_xml::escape::escape(rating)
-                ));
-            }
+                // Add episode numbering if available (XMLTV format: season.episode.part/total)
+                if let (Some(season), Some(episode)) =
+                    (program.season_num.as_ref(), program.episode_num.as_ref())
+                    && let (Ok(s), Ok(e)) = (season.parse::<i32>(), episode.parse::<i32>())
+                    && s > 0
+                    && e > 0
+                {
+                    program_line.push_str(&format!(
+                        "    <episode-num system=\"xmltv_ns\">.{}.{}/1</episode-num>\n",
+                        s - 1,
+                        e - 1
+                    ));
+                }
 
-            // Add program icon if available
-            if let Some(icon_url) = program.program_icon.as_ref().filter(|i| !i.is_empty()) {
-                program_line.push_str(&format!(
-                    "    <icon src=\"{}\"/>\n",
-                    quick_xml::escape::escape(icon_url)
-                ));
-            }
+                // Add language if specified
+                if let Some(language) = program.language.as_ref().filter(|l| !l.is_empty()) {
+                    program_line.push_str(&format!(
+                        "    <language>{}</language>\n",
+                        quick_xml::escape::escape(language)
+                    ));
+                }
+
+                // Add rating if available
+                if let Some(rating) = program.rating.as_ref().filter(|r| !r.is_empty()) {
+                    program_line.push_str(&format!(
+                        "    <rating system=\"MPAA\"><value>{}</value></rating>\n",
+                        quick_xml::escape::escape(rating)
+                    ));
+                }
+
+                // Add program icon if available
+                if let Some(icon_url) = program.program_icon.as_ref().filter(|i| !i.is_empty()) {
+                    program_line.push_str(&format!(
+                        "    <icon src=\"{}\"/>\n",
+                        quick_xml::escape::escape(icon_url)
+                    ));
+                }
 
-            program_line.push_str("  </programme>\n");
+                program_line.push_str("  </programme>\n");
 
-            writer.write_all(program_line.as_bytes()).await?;
-            bytes_written += program_line.len() as u64;
-            programs_written += 1;
+                writer.write_all(program_line.as_bytes()).await?;
+                bytes_written += program_line.len() as u64;
+                programs_written += 1;
+            }
         }
 
         // Write XMLTV footer
@@ -795,3 +1222,149 @@ impl PipelineStage for GenerationStage {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn channel(tvg_shift: Option<&str>, epg_shift: Option<&str>) -> Channel {
+        Channel {
+            id: uuid::Uuid::new_v4(),
+            source_id: uuid::Uuid::new_v4(),
+            tvg_id: Some("bbc1.uk".to_string()),
+            tvg_name: None,
+            tvg_chno: None,
+            tvg_logo: None,
+            tvg_shift: tvg_shift.map(str::to_string),
+            epg_shift: epg_shift.map(str::to_string),
+            group_title: None,
+            channel_name: "BBC One +1".to_string(),
+            stream_url: "http://example.com/bbc1".to_string(),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            extra_attributes: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_passthrough_attributes_follow_allowlist() {
+        let mut channel = channel(None, None);
+        channel.extra_attributes.extend([
+            ("catchup".to_string(), "default".to_string()),
+            ("Catchup-Days".to_string(), "7".to_string()),
+            ("x-custom".to_string(), "1".to_string()),
+            ("tvg-rec".to_string(), String::new()),
+            ("backup-url".to_string(), "http://up/b".to_string()),
+        ]);
+        let allowlist: Vec<String> = ["catchup", "catchup-days", "tvg-rec", "backup-url"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        assert_eq!(
+            passthrough_attributes(&channel, &allowlist),
+            " Catchup-Days=\"7\" catchup=\"default\""
+        );
+        assert_eq!(passthrough_attributes(&channel, &[]), "");
+    }
+
+    #[test]
+    fn test_attribute_passthrough_applies_to_proxy() {
+        use crate::models::proxy_settings::AttributePassthrough;
+
+        let mut config = AttributePassthroughConfig::default();
+        assert!(config.applies_to(None));
+        assert!(!config.applies_to(Some(&AttributePassthrough { enabled: false })));
+        config.enabled = false;
+        assert!(config.applies_to(Some(&AttributePassthrough { enabled: true })));
+        config.attributes.clear();
+        assert!(!config.applies_to(Some(&AttributePassthrough { enabled: true })));
+    }
+
+    #[test]
+    fn test_channel_epg_shift_falls_back_to_tvg_shift() {
+        assert_eq!(channel_epg_shift(&channel(None, None)), 0);
+        assert_eq!(channel_epg_shift(&channel(Some("+1"), None)), 3600);
+        assert_eq!(channel_epg_shift(&channel(Some("+24"), None)), 86400);
+        assert_eq!(channel_epg_shift(&channel(Some(" "), None)), 0);
+        assert_eq!(channel_epg_shift(&channel(Some("later"), None)), 0);
+        // A data mapping epg_shift wins over the source's tvg-shift
+        assert_eq!(channel_epg_shift(&channel(Some("+1"), Some("-30m"))), -1800);
+        assert_eq!(xmltv_channel_id("bbc1.uk", 3600), "bbc1.uk+1h");
+    }
+
+    #[test]
+    fn test_shift_across_midnight() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 23, 30, 0).unwrap();
+        assert_eq!(
+            xmltv_programme_time(start, 3600, None),
+            "20240102003000 +0000"
+        );
+        assert_eq!(
+            xmltv_programme_time(start, 86400, None),
+            "20240102233000 +0000"
+        );
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 30, 0).unwrap();
+        assert_eq!(
+            xmltv_programme_time(start, -7200, None),
+            "20231231223000 +0000"
+        );
+    }
+
+    #[test]
+    fn test_shift_across_dst_change() {
+        // UK clocks went forward at 01:00 UTC on 31 March 2024 and back on 27 October 2024;
+        // shifted programmes keep their length either side of the change
+        for (start, stop) in [
+            (
+                Utc.with_ymd_and_hms(2024, 3, 31, 0, 30, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 31, 1, 30, 0).unwrap(),
+            ),
+            (
+                Utc.with_ymd_and_hms(2024, 10, 27, 0, 30, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 27, 1, 30, 0).unwrap(),
+            ),
+        ] {
+            let shifted_start = apply_time_offset(start, 3600);
+            let shifted_stop = apply_time_offset(stop, 3600);
+            assert_eq!(shifted_stop - shifted_start, stop - start);
+        }
+        assert_eq!(
+            xmltv_programme_time(
+                Utc.with_ymd_and_hms(2024, 3, 31, 0, 30, 0).unwrap(),
+                3600,
+                None
+            ),
+            "20240331013000 +0000"
+        );
+    }
+
+    #[test]
+    fn test_programme_time_in_output_timezone() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        // EST in winter, EDT in summer
+        assert_eq!(
+            xmltv_programme_time(
+                Utc.with_ymd_and_hms(2024, 1, 15, 23, 30, 0).unwrap(),
+                0,
+                Some(tz)
+            ),
+            "20240115183000 -0500"
+        );
+        assert_eq!(
+            xmltv_programme_time(
+                Utc.with_ymd_and_hms(2024, 7, 15, 23, 30, 0).unwrap(),
+                3600,
+                Some(tz)
+            ),
+            "20240715203000 -0400"
+        );
+    }
+}
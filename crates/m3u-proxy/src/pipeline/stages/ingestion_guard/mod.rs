@@ -183,6 +183,11 @@ impl PipelineStage for IngestionGuardStage {
         Ok(input)
     }
 
+    /// The guard produces no output of its own; it only needs to run when data mapping does
+    async fn cache_fingerprint(&self) -> Option<String> {
+        Some(String::new())
+    }
+
     fn stage_id(&self) -> &'static str {
         "ingestion_guard"
     }
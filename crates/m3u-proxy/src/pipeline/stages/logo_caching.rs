@@ -4,12 +4,14 @@
 //! cache remote logo URLs. It respects per-proxy configuration settings and only
 //! caches external URLs while leaving proxy URLs unchanged.
 
+use crate::config::LogoPrefetchConfig;
 use crate::logo_assets::service::LogoAssetService;
 use crate::models::Channel;
 use crate::pipeline::engines::rule_processor::EpgProgram;
 use crate::pipeline::error::PipelineError;
 use crate::pipeline::models::{ArtifactType, ContentType, PipelineArtifact, ProcessingStage};
 use crate::pipeline::traits::{PipelineStage, ProgressAware};
+use crate::services::logo_prefetch::{LogoFetchOutcome, LogoPrefetchBudget, LogoRequest};
 use crate::services::progress_service::ProgressManager;
 use sandboxed_file_manager::SandboxedManager;
 use serde::{Deserialize, Serialize};
@@ -21,6 +23,9 @@ use tracing::{debug, info, trace, warn};
 const LOGO_CACHING_BATCH_SIZE: usize = 1000; // Process logos in batches to reduce memory pressure
 const LOGO_PROGRESS_BATCH_INTERVAL: usize = 10; // Log progress every N batches (10 batches = 10,000 channels)
 
+/// Artifact metadata key holding the number of logos deferred to the background prefetch job
+pub const LOGOS_DEFERRED_METADATA: &str = "logos_deferred";
+
 /// Configuration for logo caching behavior
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogoCachingConfig {
@@ -35,6 +40,8 @@ pub struct LogoCachingStage {
     pipeline_execution_prefix: String,
     logo_service: Arc<LogoAssetService>,
     config: LogoCachingConfig,
+    /// Allowance for downloading new logos during this run
+    budget: LogoPrefetchBudget,
     progress_manager: Option<Arc<ProgressManager>>,
 }
 
@@ -58,6 +65,8 @@ struct LogoCachingResult {
     pub local_proxy_urls: usize,
     pub remote_urls: usize,
     pub unknown_urls: usize,
+    /// New logos over the download budget, left to the background prefetch job
+    pub total_deferred: usize,
 }
 
 /// Result of EPG logo caching operations
@@ -69,78 +78,10 @@ struct EpgLogoCachingResult {
     pub cache_failures: usize,
     pub cache_hits: usize,
     pub total_downloaded_bytes: u64,
+    pub total_deferred: usize,
 }
 
 impl LogoCachingStage {
-    /// Generate a normalized cache ID from a logo URL using the same algorithm as LogoAssetService
-    fn generate_cache_id_from_url(url: &str) -> Result<String, Box<dyn std::error::Error>> {
-        use sha2::{Digest, Sha256};
-        use std::collections::BTreeMap;
-        use url::Url;
-
-        let parsed_url = Url::parse(url).map_err(|e| format!("Invalid URL '{url}': {e}"))?;
-
-        // Start building normalized URL without scheme
-        let mut normalized = String::new();
-
-        // Add host
-        if let Some(host) = parsed_url.host_str() {
-            normalized.push_str(host);
-        }
-
-        // Add port if not default
-        if let Some(port) = parsed_url.port() {
-            let is_default_port = (parsed_url.scheme() == "http" && port == 80)
-                || (parsed_url.scheme() == "https" && port == 443);
-            if !is_default_port {
-                normalized.push(':');
-                normalized.push_str(&port.to_string());
-            }
-        }
-
-        // Add path without file extension
-        let path = parsed_url.path();
-        if let Some(last_slash) = path.rfind('/') {
-            let (dir_part, file_part) = path.split_at(last_slash + 1);
-            normalized.push_str(dir_part);
-
-            // Remove extension from filename
-            if let Some(dot_pos) = file_part.rfind('.') {
-                normalized.push_str(&file_part[..dot_pos]);
-            } else {
-                normalized.push_str(file_part);
-            }
-        } else {
-            // No slash in path, treat whole path as filename
-            if let Some(dot_pos) = path.rfind('.') {
-                normalized.push_str(&path[..dot_pos]);
-            } else {
-                normalized.push_str(path);
-            }
-        }
-
-        // Sort and add query parameters
-        let mut sorted_params = BTreeMap::new();
-        for (key, value) in parsed_url.query_pairs() {
-            sorted_params.insert(key.to_string(), value.to_string());
-        }
-
-        if !sorted_params.is_empty() {
-            normalized.push('?');
-            let param_string: Vec<String> = sorted_params
-                .iter()
-                .map(|(k, v)| format!("{k}={v}"))
-                .collect();
-            normalized.push_str(&param_string.join("&"));
-        }
-
-        // Generate SHA256 hash
-        let mut hasher = Sha256::new();
-        hasher.update(normalized.as_bytes());
-        let hash = hasher.finalize();
-
-        Ok(format!("{hash:x}"))
-    }
     pub async fn new(
         file_manager: SandboxedManager,
         pipeline_execution_prefix: String,
@@ -153,10 +94,17 @@ impl LogoCachingStage {
             pipeline_execution_prefix,
             logo_service,
             config,
+            budget: LogoPrefetchBudget::new(&LogoPrefetchConfig::default()),
             progress_manager,
         })
     }
 
+    /// Download budget for new logos (defaults to `LogoPrefetchConfig::default()`)
+    pub fn with_prefetch_config(mut self, prefetch_config: &LogoPrefetchConfig) -> Self {
+        self.budget = LogoPrefetchBudget::new(prefetch_config);
+        self
+    }
+
     /// Helper method for reporting progress
     async fn report_progress(&self, percentage: f64, message: &str) {
         if let Some(pm) = &self.progress_manager
@@ -233,11 +181,12 @@ impl LogoCachingStage {
         };
 
         info!(
-            "Logo caching stage completed: processed={} cached={} failures={} hits={} total_downloaded_bytes={} average_logo_size={} duration={:?}",
+            "Logo caching stage completed: processed={} cached={} failures={} hits={} deferred={} total_downloaded_bytes={} average_logo_size={} duration={:?}",
             result.total_processed,
             result.total_cached,
             result.cache_failures,
             result.cache_hits,
+            result.total_deferred,
             result.total_downloaded_bytes,
             average_logo_size,
             stage_duration
@@ -281,6 +230,7 @@ impl LogoCachingStage {
         let mut local_proxy_urls = 0;
         let mut remote_urls = 0;
         let mut unknown_urls = 0;
+        let mut total_deferred = 0;
 
         let total_channels = channels.len();
         let start_time = std::time::Instant::now();
@@ -295,97 +245,66 @@ impl LogoCachingStage {
             let batch_start = std::time::Instant::now();
             let mut batch_processed = Vec::new();
 
-            for mut channel in batch.iter().cloned() {
-                processed_count += 1;
-
-                let original_logo = channel.tvg_logo.clone();
-
-                if let Some(ref logo_url) = original_logo {
-                    match self.classify_logo_url(logo_url) {
-                        LogoUrlType::RemoteUrl => {
-                            remote_urls += 1;
-                            trace!(
-                                "Processing remote logo URL for channel {}: {}",
-                                channel.channel_name, logo_url
-                            );
-
-                            // Check if logo already exists (cache hit detection)
-                            let cache_id = Self::generate_cache_id_from_url(logo_url)?;
-                            let logo_file_path = format!("{cache_id}.png");
-                            let logo_exists = self
-                                .file_manager
-                                .exists(&logo_file_path)
-                                .await
-                                .unwrap_or(false);
-
-                            if logo_exists {
-                                cache_hits += 1;
-                                trace!(
-                                    "Cache hit for channel {}: {} (existing cache_id={})",
-                                    channel.channel_name, logo_url, cache_id
-                                );
-                            }
-
-                            match self
-                                .logo_service
-                                .cache_logo_from_url_with_metadata_and_size_tracking(
-                                    logo_url,
-                                    Some(channel.channel_name.clone()),
-                                    channel.group_title.clone(),
-                                    None, // No extra fields
-                                )
-                                .await
-                            {
-                                Ok((returned_cache_id, bytes_transferred)) => {
-                                    let new_url = self.logo_service.get_cached_logo_url(
-                                        &returned_cache_id,
-                                        &self.config.base_url,
-                                    );
-                                    channel.tvg_logo = Some(new_url.clone());
-
-                                    if !logo_exists {
-                                        total_cached += 1;
-                                        total_downloaded_bytes += bytes_transferred;
-                                    }
-
-                                    trace!(
-                                        "Processed logo for channel {}: {} -> cache_id={} (hit={} bytes={})",
-                                        channel.channel_name,
-                                        logo_url,
-                                        returned_cache_id,
-                                        logo_exists,
-                                        bytes_transferred
-                                    );
-                                }
-                                Err(e) => {
-                                    cache_failures += 1;
-                                    debug!(
-                                        "Failed to cache logo for channel {}: {} - Error: {}",
-                                        channel.channel_name, logo_url, e
-                                    );
-                                    // Keep original URL on cache failure
-                                }
-                            }
-                        }
-                        LogoUrlType::LocalProxy => {
-                            local_proxy_urls += 1;
-                            trace!(
-                                "Skipping local proxy URL for channel {}: {}",
-                                channel.channel_name, logo_url
-                            );
-                            // Keep as-is
-                        }
-                        LogoUrlType::Unknown => {
-                            unknown_urls += 1;
-                            trace!(
-                                "Skipping unknown URL format for channel {}: {}",
-                                channel.channel_name, logo_url
-                            );
-                            // Keep as-is
+            // Cache the batch's remote logos within the generation's download budget
+            let mut requests = Vec::new();
+            for channel in batch {
+                let Some(logo_url) = &channel.tvg_logo else {
+                    continue;
+                };
+                match self.classify_logo_url(logo_url) {
+                    LogoUrlType::RemoteUrl => {
+                        remote_urls += 1;
+                        requests.push(LogoRequest {
+                            url: logo_url.clone(),
+                            name: Some(channel.channel_name.clone()),
+                            group: channel.group_title.clone(),
+                        });
+                    }
+                    LogoUrlType::LocalProxy => {
+                        local_proxy_urls += 1;
+                        trace!(
+                            "Skipping local proxy URL for channel {}: {}",
+                            channel.channel_name, logo_url
+                        );
+                    }
+                    LogoUrlType::Unknown => {
+                        unknown_urls += 1;
+                        trace!(
+                            "Skipping unknown URL format for channel {}: {}",
+                            channel.channel_name, logo_url
+                        );
+                    }
+                }
+            }
+            let outcomes = self.budget.prefetch(&self.logo_service, requests).await;
+            for outcome in outcomes.values() {
+                match outcome {
+                    LogoFetchOutcome::Cached {
+                        bytes, downloaded, ..
+                    } => {
+                        if *downloaded {
+                            total_cached += 1;
+                            total_downloaded_bytes += *bytes;
+                        } else {
+                            cache_hits += 1;
                         }
                     }
+                    LogoFetchOutcome::Deferred => total_deferred += 1,
+                    LogoFetchOutcome::Failed(_) => cache_failures += 1,
                 }
+            }
 
+            for mut channel in batch.iter().cloned() {
+                processed_count += 1;
+                // Deferred and failed logos keep their original URL for this generation
+                if let Some(LogoFetchOutcome::Cached { cache_id, .. }) =
+                    channel.tvg_logo.as_ref().and_then(|url| outcomes.get(url))
+                {
+                    channel.tvg_logo = Some(
+                        self.logo_service
+                            .get_cached_logo_url(cache_id, &self.config.base_url),
+                    );
+                }
                 batch_processed.push(channel);
             }
 
@@ -413,7 +332,7 @@ impl LogoCachingStage {
                 };
 
                 info!(
-                    "Logo caching progress: batch {}/{} channels {}/{} ({:.1}%) cached={} failures={} hits={} downloaded_bytes={} elapsed={:?} eta={:?}",
+                    "Logo caching progress: batch {}/{} channels {}/{} ({:.1}%) cached={} failures={} hits={} deferred={} downloaded_bytes={} elapsed={:?} eta={:?}",
                     batch_index + 1,
                     total_batches,
                     processed_count,
@@ -422,6 +341,7 @@ impl LogoCachingStage {
                     total_cached,
                     cache_failures,
                     cache_hits,
+                    total_deferred,
                     total_downloaded_bytes,
                     elapsed,
                     estimated_remaining
@@ -460,6 +380,7 @@ impl LogoCachingStage {
             local_proxy_urls,
             remote_urls,
             unknown_urls,
+            total_deferred,
         })
     }
 
@@ -657,6 +578,10 @@ impl LogoCachingStage {
         .with_metadata(
             "total_downloaded_bytes".to_string(),
             serde_json::Value::Number(serde_json::Number::from(logo_result.total_downloaded_bytes)),
+        )
+        .with_metadata(
+            LOGOS_DEFERRED_METADATA.to_string(),
+            serde_json::Value::Number(serde_json::Number::from(logo_result.total_deferred)),
         );
 
         Ok(output_artifact)
@@ -726,6 +651,10 @@ impl LogoCachingStage {
         .with_metadata(
             "total_downloaded_bytes".to_string(),
             serde_json::Value::Number(serde_json::Number::from(logo_result.total_downloaded_bytes)),
+        )
+        .with_metadata(
+            LOGOS_DEFERRED_METADATA.to_string(),
+            serde_json::Value::Number(serde_json::Number::from(logo_result.total_deferred)),
         );
 
         Ok(output_artifact)
@@ -777,65 +706,77 @@ impl LogoCachingStage {
         programs: Vec<EpgProgram>,
     ) -> Result<EpgLogoCachingResult, Box<dyn std::error::Error>> {
         let total_input = programs.len();
-        let mut processed_programs = Vec::with_capacity(total_input);
         let mut total_cached = 0;
         let mut cache_failures = 0;
-        let cache_hits = 0;
+        let mut cache_hits = 0;
         let mut total_downloaded_bytes = 0;
+        let mut total_deferred = 0;
 
         info!("Processing program logos for {} programs", total_input);
 
-        for mut program in programs {
-            if let Some(program_icon_url) = program.program_icon.clone() {
-                let url_type = self.classify_logo_url(&program_icon_url);
-                match url_type {
-                    LogoUrlType::RemoteUrl => {
-                        trace!("Caching remote program icon: {}", program_icon_url);
-                        match self
-                            .logo_service
-                            .cache_logo_from_url_with_metadata_and_size_tracking(
-                                &program_icon_url,
-                                Some(program.title.clone()), // Use program title as "name"
-                                None,                        // No category field in EpgProgram
-                                None,                        // No extra fields
-                            )
-                            .await
-                        {
-                            Ok((cache_id, bytes_transferred)) => {
-                                let cached_url = self
-                                    .logo_service
-                                    .get_cached_logo_url(&cache_id, &self.config.base_url);
-                                program.program_icon = Some(cached_url);
-                                total_cached += 1;
-                                total_downloaded_bytes += bytes_transferred;
-
-                                trace!(
-                                    "Program icon downloaded and cached: {} -> {}",
-                                    program_icon_url,
-                                    program.program_icon.as_ref().unwrap()
-                                );
-                            }
-                            Err(e) => {
-                                warn!("Failed to cache program icon {}: {}", program_icon_url, e);
-                                cache_failures += 1;
-                                // Keep original URL on cache failure
-                            }
-                        }
-                    }
-                    LogoUrlType::LocalProxy => {
-                        trace!("Skipping local proxy program icon: {}", program_icon_url);
-                    }
-                    LogoUrlType::Unknown => {
-                        trace!("Skipping unknown format program icon: {}", program_icon_url);
+        let mut requests = Vec::new();
+        for program in &programs {
+            let Some(program_icon_url) = &program.program_icon else {
+                continue;
+            };
+            match self.classify_logo_url(program_icon_url) {
+                LogoUrlType::RemoteUrl => requests.push(LogoRequest {
+                    url: program_icon_url.clone(),
+                    name: Some(program.title.clone()), // Use program title as "name"
+                    group: None,                       // No category field in EpgProgram
+                }),
+                LogoUrlType::LocalProxy => {
+                    trace!("Skipping local proxy program icon: {}", program_icon_url);
+                }
+                LogoUrlType::Unknown => {
+                    trace!("Skipping unknown format program icon: {}", program_icon_url);
+                }
+            }
+        }
+        let outcomes = self.budget.prefetch(&self.logo_service, requests).await;
+        for (url, outcome) in &outcomes {
+            match outcome {
+                LogoFetchOutcome::Cached {
+                    bytes, downloaded, ..
+                } => {
+                    if *downloaded {
+                        total_cached += 1;
+                        total_downloaded_bytes += *bytes;
+                    } else {
+                        cache_hits += 1;
                     }
                 }
+                LogoFetchOutcome::Deferred => total_deferred += 1,
+                LogoFetchOutcome::Failed(e) => {
+                    warn!("Failed to cache program icon {}: {}", url, e);
+                    cache_failures += 1;
+                }
+            }
+        }
+
+        let mut processed_programs = programs;
+        for program in &mut processed_programs {
+            // Deferred and failed icons keep their original URL for this generation
+            if let Some(LogoFetchOutcome::Cached { cache_id, .. }) = program
+                .program_icon
+                .as_ref()
+                .and_then(|url| outcomes.get(url))
+            {
+                program.program_icon = Some(
+                    self.logo_service
+                        .get_cached_logo_url(cache_id, &self.config.base_url),
+                );
             }
-            processed_programs.push(program);
         }
 
         info!(
-            "Program logo caching completed: processed={} cached={} failures={} cache_hits={} downloaded_bytes={}",
-            total_input, total_cached, cache_failures, cache_hits, total_downloaded_bytes
+            "Program logo caching completed: processed={} cached={} failures={} cache_hits={} deferred={} downloaded_bytes={}",
+            total_input,
+            total_cached,
+            cache_failures,
+            cache_hits,
+            total_deferred,
+            total_downloaded_bytes
         );
 
         let result = EpgLogoCachingResult {
@@ -845,6 +786,7 @@ impl LogoCachingStage {
             cache_failures,
             cache_hits,
             total_downloaded_bytes,
+            total_deferred,
         };
 
         // Memory cleanup is handled automatically when variables go out of scope
@@ -905,6 +847,7 @@ mod tests {
             tvg_chno: None,
             tvg_logo: logo_url,
             tvg_shift: None,
+            epg_shift: None,
             group_title: None,
             channel_name: name.to_string(),
             stream_url: "http://example.com/stream".to_string(),
@@ -915,6 +858,7 @@ mod tests {
             last_probed_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            extra_attributes: Default::default(),
         }
     }
 
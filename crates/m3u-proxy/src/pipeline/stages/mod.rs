@@ -1,4 +1,5 @@
 pub mod cleanup;
+pub mod compliance;
 pub mod data_mapping;
 pub mod filtering;
 pub mod generation;
@@ -8,8 +9,10 @@ pub mod ingestion_guard;
 pub mod logo_caching;
 pub mod numbering;
 pub mod publish_content;
+pub mod virtual_channels;
 
 pub use cleanup::{CleanupMode, CleanupStage};
+pub use compliance::ComplianceStage;
 pub use data_mapping::DataMappingStage;
 pub use filtering::FilteringStage;
 pub use generation::GenerationStage;
@@ -17,3 +20,4 @@ pub use ingestion_guard::IngestionGuardStage;
 pub use logo_caching::{LogoCachingConfig, LogoCachingStage};
 pub use numbering::NumberingStage;
 pub use publish_content::PublishContentStage;
+pub use virtual_channels::VirtualChannelsStage;
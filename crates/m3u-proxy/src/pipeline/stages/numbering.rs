@@ -2,8 +2,10 @@
 //!
 //! This stage assigns channel numbers to channels based on existing tvg-channo values
 //! with priority-based conflict resolution and efficient single-pass algorithm.
+//! Channels of groups with a configured number block are numbered within their block
+//! first; the rest number sequentially from the proxy's starting channel number.
 
-use crate::models::Channel;
+use crate::models::{Channel, ChannelNumberBlock, NumberBlockOverflow};
 use crate::pipeline::error::PipelineError;
 use crate::pipeline::models::{ArtifactType, ContentType, PipelineArtifact, ProcessingStage};
 use crate::pipeline::traits::{PipelineStage, ProgressAware};
@@ -11,15 +13,19 @@ use crate::services::progress_service::ProgressManager;
 use crate::utils::human_format::format_duration_precise;
 use sandboxed_file_manager::SandboxedManager;
 use serde_json;
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, info, warn};
 
+/// Artifact metadata key holding the number blocks that overflowed
+pub const NUMBER_BLOCK_OVERFLOWS_METADATA: &str = "number_block_overflows";
+
 pub struct NumberingStage {
     file_manager: SandboxedManager,
     pipeline_execution_prefix: String,
     starting_channel_number: u32,
+    number_blocks: Vec<ChannelNumberBlock>,
     progress_manager: Option<Arc<ProgressManager>>,
 }
 
@@ -34,10 +40,17 @@ impl NumberingStage {
             file_manager,
             pipeline_execution_prefix,
             starting_channel_number,
+            number_blocks: Vec::new(),
             progress_manager,
         }
     }
 
+    /// Number the channels of these groups within their own blocks
+    pub fn with_number_blocks(mut self, number_blocks: Vec<ChannelNumberBlock>) -> Self {
+        self.number_blocks = number_blocks;
+        self
+    }
+
     /// Helper method for reporting progress
     async fn report_progress(&self, percentage: f64, message: &str) {
         if let Some(pm) = &self.progress_manager
@@ -157,7 +170,7 @@ impl NumberingStage {
         }
 
         // Apply efficient numbering algorithm
-        let (assigned_count, channo_conflicts_resolved) =
+        let (assigned_count, channo_conflicts_resolved, block_overflows) =
             self.apply_numbering(&mut channels).await?;
 
         // Write numbered channels to new artifact file
@@ -193,6 +206,13 @@ impl NumberingStage {
             self.starting_channel_number.into(),
         );
 
+        if !block_overflows.is_empty() {
+            output_artifact = output_artifact.with_metadata(
+                NUMBER_BLOCK_OVERFLOWS_METADATA.to_string(),
+                serde_json::to_value(&block_overflows)?,
+            );
+        }
+
         // Add file size if possible
         output_artifact = output_artifact.with_file_size(output_content.len() as u64);
 
@@ -246,7 +266,7 @@ impl NumberingStage {
     async fn apply_numbering(
         &self,
         channels: &mut [Channel],
-    ) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+    ) -> Result<(usize, usize, Vec<NumberBlockOverflow>), Box<dyn std::error::Error>> {
         let algorithm_start = Instant::now();
         let total_channels = channels.len();
 
@@ -354,33 +374,55 @@ impl NumberingStage {
             self.report_progress(33.0, &progress_message).await;
         }
 
+        // Number channels of grouped blocks next; those that do not fit fall through to the
+        // sequential fill below
+        let unnumbered: Vec<usize> = channels_needing_numbers
+            .iter()
+            .filter(|(_, assigned_num)| assigned_num.is_none())
+            .map(|(idx, _)| *idx)
+            .collect();
+        let (block_assignments, sequential_indices, block_overflows) = assign_number_blocks(
+            channels,
+            &unnumbered,
+            &mut used_numbers,
+            &self.number_blocks,
+            self.starting_channel_number,
+        );
+        for overflow in &block_overflows {
+            warn!(
+                "Channel number block for group '{}' (from {}, {} numbers) overflowed: {} channels numbered from {} instead",
+                overflow.group,
+                overflow.start,
+                overflow.capacity,
+                overflow.overflowed,
+                self.starting_channel_number
+            );
+        }
+
         // Build available number pool efficiently - start from starting_channel_number for sequential fills
         // Only count channels that need sequential assignment (not conflict-resolved ones)
         let pool_build_start = Instant::now();
 
-        let sequential_assignment_needed = channels_needing_numbers
-            .iter()
-            .filter(|(_, assigned_num)| assigned_num.is_none())
-            .count() as u32;
-
-        let upper_bound = self.starting_channel_number + sequential_assignment_needed;
+        let sequential_assignment_needed = sequential_indices.len() as u32;
 
-        // Only add numbers from starting_channel_number upward (sequential fills)
+        // Only add numbers from starting_channel_number upward (sequential fills), skipping
+        // numbers already claimed by tvg-chno values or blocks
         let mut available_numbers = BTreeSet::new();
-        for num in self.starting_channel_number..=upper_bound {
-            if !used_numbers.contains(&num) {
-                available_numbers.insert(num);
+        let mut upper_bound = self.starting_channel_number;
+        while (available_numbers.len() as u32) < sequential_assignment_needed {
+            if !used_numbers.contains(&upper_bound) {
+                available_numbers.insert(upper_bound);
+            }
+            match upper_bound.checked_add(1) {
+                Some(next) => upper_bound = next,
+                None => break,
             }
         }
 
         let pool_build_duration = pool_build_start.elapsed();
 
         // Calculate efficiency metrics
-        let theoretical_max_pool = if sequential_assignment_needed > 0 {
-            upper_bound - self.starting_channel_number + 1
-        } else {
-            0
-        };
+        let theoretical_max_pool = upper_bound - self.starting_channel_number;
         let actual_pool_size = available_numbers.len() as u32;
         let pool_efficiency = if theoretical_max_pool > 0 {
             (actual_pool_size as f64 / theoretical_max_pool as f64) * 100.0
@@ -418,30 +460,31 @@ impl NumberingStage {
         let assignment_start = Instant::now();
         let mut assigned_count = 0;
 
-        for (idx, assigned_num) in &channels_needing_numbers {
-            match assigned_num {
-                Some(num) => {
-                    // This channel was already assigned a number due to conflict resolution
-                    channels[*idx].tvg_chno = Some(num.to_string());
-                    assigned_count += 1;
-                }
-                None => {
-                    // This channel needs sequential assignment
-                    if let Some(next_number) = available_numbers.pop_first() {
-                        channels[*idx].tvg_chno = Some(next_number.to_string());
-                        used_numbers.insert(next_number);
-                        assigned_count += 1;
-                    } else {
-                        if let Some(task) = progress_task.as_ref() {
-                            task.abort();
-                        }
-                        return Err(format!(
-                            "Ran out of available channel numbers starting from {}",
-                            self.starting_channel_number
-                        )
-                        .into());
-                    }
+        for (idx, assigned_num) in channels_needing_numbers
+            .iter()
+            .filter_map(|(idx, num)| num.map(|num| (*idx, num)))
+            .chain(block_assignments)
+        {
+            // Conflict-resolved and block numbers were claimed already
+            channels[idx].tvg_chno = Some(assigned_num.to_string());
+            assigned_count += 1;
+        }
+
+        for idx in sequential_indices {
+            // This channel needs sequential assignment
+            if let Some(next_number) = available_numbers.pop_first() {
+                channels[idx].tvg_chno = Some(next_number.to_string());
+                used_numbers.insert(next_number);
+                assigned_count += 1;
+            } else {
+                if let Some(task) = progress_task.as_ref() {
+                    task.abort();
                 }
+                return Err(format!(
+                    "Ran out of available channel numbers starting from {}",
+                    self.starting_channel_number
+                )
+                .into());
             }
         }
 
@@ -510,8 +553,93 @@ impl NumberingStage {
             );
         }
 
-        Ok((assigned_count, channo_conflicts_resolved))
+        Ok((assigned_count, channo_conflicts_resolved, block_overflows))
+    }
+}
+
+/// Number channels of grouped blocks, in channel order
+///
+/// Each block runs from its start up to the next block's start, or the starting channel
+/// number when that lies above it; the topmost block is otherwise unbounded. Numbers already
+/// claimed by tvg-chno values are skipped. Returns the block assignments, the channels left
+/// for sequential numbering (ungrouped or overflowed, in channel order) and the overflowed
+/// blocks.
+fn assign_number_blocks(
+    channels: &[Channel],
+    unnumbered: &[usize],
+    used_numbers: &mut HashSet<u32>,
+    blocks: &[ChannelNumberBlock],
+    starting_channel_number: u32,
+) -> (Vec<(usize, u32)>, Vec<usize>, Vec<NumberBlockOverflow>) {
+    if blocks.is_empty() {
+        return (Vec::new(), unnumbered.to_vec(), Vec::new());
+    }
+
+    let block_by_group: HashMap<String, &ChannelNumberBlock> = blocks
+        .iter()
+        .map(|block| (block.group.trim().to_lowercase(), block))
+        .collect();
+    let block_end = |start: u32| {
+        blocks
+            .iter()
+            .map(|block| block.start)
+            .chain(std::iter::once(starting_channel_number))
+            .filter(|&boundary| boundary > start)
+            .min()
+    };
+
+    let mut members: HashMap<u32, Vec<usize>> = HashMap::new();
+    let mut sequential = Vec::new();
+    for &idx in unnumbered {
+        let block = channels[idx]
+            .group_title
+            .as_deref()
+            .and_then(|group| block_by_group.get(&group.trim().to_lowercase()));
+        match block {
+            Some(block) => members.entry(block.start).or_default().push(idx),
+            None => sequential.push(idx),
+        }
+    }
+
+    let mut assignments = Vec::new();
+    let mut overflows = Vec::new();
+    for block in blocks {
+        let Some(indices) = members.remove(&block.start) else {
+            continue;
+        };
+        let end = block_end(block.start);
+        let mut candidate = Some(block.start);
+        let mut overflowed = 0;
+        for idx in indices {
+            while let Some(number) = candidate
+                && used_numbers.contains(&number)
+            {
+                candidate = number.checked_add(1);
+            }
+            match candidate.filter(|&number| end.is_none_or(|end| number < end)) {
+                Some(number) => {
+                    used_numbers.insert(number);
+                    assignments.push((idx, number));
+                    candidate = number.checked_add(1);
+                }
+                None => {
+                    overflowed += 1;
+                    sequential.push(idx);
+                }
+            }
+        }
+        if overflowed > 0 {
+            overflows.push(NumberBlockOverflow {
+                group: block.group.clone(),
+                start: block.start,
+                capacity: end.unwrap_or(u32::MAX) - block.start,
+                overflowed,
+            });
+        }
     }
+
+    sequential.sort_unstable();
+    (assignments, sequential, overflows)
 }
 
 impl ProgressAware for NumberingStage {
@@ -552,3 +680,93 @@ impl PipelineStage for NumberingStage {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn channel(name: &str, group: &str) -> Channel {
+        Channel {
+            id: Uuid::new_v4(),
+            source_id: Uuid::new_v4(),
+            tvg_id: None,
+            tvg_name: None,
+            tvg_chno: None,
+            tvg_logo: None,
+            tvg_shift: None,
+            epg_shift: None,
+            group_title: Some(group.to_string()),
+            channel_name: name.to_string(),
+            stream_url: format!("http://example.com/{name}"),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            extra_attributes: Default::default(),
+        }
+    }
+
+    fn block(group: &str, start: u32) -> ChannelNumberBlock {
+        ChannelNumberBlock {
+            group: group.to_string(),
+            start,
+        }
+    }
+
+    #[test]
+    fn test_blocks_number_groups_and_skip_claimed_numbers() {
+        let channels = vec![
+            channel("BBC News", "news"),
+            channel("Film4", "Movies"),
+            channel("Sky News", "News"),
+            channel("Music", "Music"),
+        ];
+        let mut used: HashSet<u32> = [100].into_iter().collect();
+        let (assignments, sequential, overflows) = assign_number_blocks(
+            &channels,
+            &[0, 1, 2, 3],
+            &mut used,
+            &[block("News", 100), block("Movies", 500)],
+            1000,
+        );
+
+        assert_eq!(assignments, vec![(0, 101), (2, 102), (1, 500)]);
+        assert_eq!(sequential, vec![3]);
+        assert!(overflows.is_empty());
+        assert!(used.contains(&101) && used.contains(&500));
+    }
+
+    #[test]
+    fn test_full_block_overflows_to_sequential_numbering() {
+        let channels = vec![
+            channel("Sport 1", "Sports"),
+            channel("Other", "Misc"),
+            channel("Sport 2", "Sports"),
+            channel("Sport 3", "Sports"),
+        ];
+        let (assignments, sequential, overflows) = assign_number_blocks(
+            &channels,
+            &[0, 1, 2, 3],
+            &mut HashSet::new(),
+            &[block("Sports", 200), block("Movies", 202)],
+            1000,
+        );
+
+        assert_eq!(assignments, vec![(0, 200), (2, 201)]);
+        assert_eq!(sequential, vec![1, 3]);
+        assert_eq!(
+            overflows,
+            vec![NumberBlockOverflow {
+                group: "Sports".to_string(),
+                start: 200,
+                capacity: 2,
+                overflowed: 1,
+            }]
+        );
+    }
+}
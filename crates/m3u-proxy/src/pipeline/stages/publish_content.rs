@@ -3,6 +3,11 @@
 //! This stage handles the atomic publishing of generated M3U and XMLTV files from
 //! temporary pipeline storage to the final proxy output location. It ensures that
 //! clients never receive incomplete files during generation by using atomic rename operations.
+//!
+//! Publishing is two-phase: every file is first staged next to its live counterpart and
+//! checked against the `output_publishing` sanity checks; only when all files pass are
+//! they renamed over the live files. The replaced generation is kept as `<file>.previous`
+//! so [`rollback_published_output`] can restore it.
 
 use anyhow::Result;
 use sandboxed_file_manager::SandboxedManager;
@@ -11,20 +16,38 @@ use std::time::Instant;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::config::OutputPublishingConfig;
 use crate::pipeline::error::PipelineError;
 use crate::pipeline::models::{ArtifactType, ContentType, PipelineArtifact, ProcessingStage};
 use crate::pipeline::traits::{PipelineStage, ProgressAware};
+use crate::services::playlist_delta::PlaylistDeltaStore;
 use crate::services::progress_service::ProgressManager;
 
+/// Suffix of a generated file awaiting validation
+pub const STAGING_SUFFIX: &str = ".staging";
+
+/// Suffix of the generation replaced by the last publish (or rollback)
+pub const PREVIOUS_SUFFIX: &str = ".previous";
+
 /// Publish content stage - atomically publishes temporary files to final locations
 pub struct PublishContentStage {
     pipeline_file_manager: SandboxedManager, // Pipeline temporary storage
     proxy_output_file_manager: SandboxedManager, // Final proxy output storage
     proxy_id: Uuid,
     enable_versioning: bool,
+    publishing: OutputPublishingConfig,
+    /// Publish only the XMLTV guide, leaving the live playlist untouched
+    guide_only: bool,
     progress_manager: Option<Arc<ProgressManager>>,
 }
 
+/// A generated file copied next to its live counterpart, not yet published
+struct StagedArtifact {
+    artifact: PipelineArtifact,
+    target_filename: String,
+    staging_filename: String,
+}
+
 impl PublishContentStage {
     pub fn new(
         pipeline_file_manager: SandboxedManager,
@@ -38,10 +61,23 @@ impl PublishContentStage {
             proxy_output_file_manager,
             proxy_id,
             enable_versioning,
+            publishing: OutputPublishingConfig::default(),
+            guide_only: false,
             progress_manager,
         }
     }
 
+    /// Sanity checks applied to staged files before they replace the live output
+    pub fn with_output_publishing(mut self, publishing: OutputPublishingConfig) -> Self {
+        self.publishing = publishing;
+        self
+    }
+
+    /// Publish only the guide (EPG re-mapping runs); the generated playlist is discarded
+    pub fn set_guide_only(&mut self, guide_only: bool) {
+        self.guide_only = guide_only;
+    }
+
     /// Helper method for reporting progress
     async fn report_progress(&self, percentage: f64, message: &str) {
         if let Some(pm) = &self.progress_manager
@@ -63,10 +99,11 @@ impl PublishContentStage {
     ) -> Result<Vec<PipelineArtifact>> {
         let stage_start = Instant::now();
         info!(
-            "Publish content stage STARTED: proxy_id={} artifacts={} versioning={}",
+            "Publish content stage STARTED: proxy_id={} artifacts={} versioning={} guide_only={}",
             self.proxy_id,
             input_artifacts.len(),
-            self.enable_versioning
+            self.enable_versioning,
+            self.guide_only
         );
 
         // Debug: Print details of all input artifacts
@@ -80,35 +117,46 @@ impl PublishContentStage {
             );
         }
 
-        let mut published_artifacts = Vec::new();
-        let mut total_bytes_published = 0u64;
-        let mut files_published = 0;
+        let mut passthrough_artifacts = Vec::new();
+        let mut staged = Vec::new();
 
+        // Phase 1: stage and validate every file; the live output is untouched until all pass
         let total_artifacts = input_artifacts.len();
         for (artifact_index, artifact) in input_artifacts.into_iter().enumerate() {
             let progress_percentage =
-                50.0 + (artifact_index as f64 / total_artifacts as f64 * 40.0); // 50% to 90%
+                50.0 + (artifact_index as f64 / total_artifacts as f64 * 30.0); // 50% to 80%
 
             match artifact.artifact_type.content {
+                ContentType::M3uPlaylist if self.guide_only => {
+                    debug!(
+                        "Guide-only publish: leaving the live playlist of proxy {} in place",
+                        self.proxy_id
+                    );
+                    passthrough_artifacts.push(artifact);
+                }
                 ContentType::M3uPlaylist | ContentType::XmltvGuide => {
                     self.report_progress(
                         progress_percentage,
                         &format!(
-                            "Publishing file {}/{}: {:?}",
+                            "Staging file {}/{}: {:?}",
                             artifact_index + 1,
                             total_artifacts,
                             artifact.artifact_type.content
                         ),
                     )
                     .await;
-                    let published_artifact = self.publish_file_artifact(artifact).await?;
-
-                    if let Some(file_size) = published_artifact.file_size {
-                        total_bytes_published += file_size;
+                    let result = match self.stage_file_artifact(artifact).await {
+                        Ok(staged_artifact) => {
+                            let validation = self.validate_staged(&staged_artifact).await;
+                            staged.push(staged_artifact);
+                            validation
+                        }
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = result {
+                        self.discard_staged(&staged).await;
+                        return Err(e);
                     }
-                    files_published += 1;
-
-                    published_artifacts.push(published_artifact);
                 }
                 _ => {
                     self.report_progress(
@@ -121,11 +169,33 @@ impl PublishContentStage {
                     )
                     .await;
                     // Pass through non-publishable artifacts unchanged
-                    published_artifacts.push(artifact);
+                    passthrough_artifacts.push(artifact);
                 }
             }
         }
 
+        // Phase 2: swap the validated files in
+        self.report_progress(85.0, "Publishing validated files")
+            .await;
+        let mut published_artifacts = Vec::new();
+        let mut total_bytes_published = 0u64;
+        let mut files_published = 0;
+        for staged_artifact in staged {
+            let is_playlist =
+                staged_artifact.artifact.artifact_type.content == ContentType::M3uPlaylist;
+            let published_artifact = self.publish_staged(staged_artifact).await?;
+            if is_playlist {
+                self.record_playlist_generation(&published_artifact.file_path)
+                    .await;
+            }
+            if let Some(file_size) = published_artifact.file_size {
+                total_bytes_published += file_size;
+            }
+            files_published += 1;
+            published_artifacts.push(published_artifact);
+        }
+        published_artifacts.extend(passthrough_artifacts);
+
         let stage_duration = stage_start.elapsed();
         info!(
             "Publish content completed: proxy_id={} files_published={} bytes_published={}KB duration={}",
@@ -138,10 +208,8 @@ impl PublishContentStage {
         Ok(published_artifacts)
     }
 
-    /// Publish a single file artifact atomically
-    async fn publish_file_artifact(&self, artifact: PipelineArtifact) -> Result<PipelineArtifact> {
-        let publish_start = Instant::now();
-
+    /// Copy a generated file next to its target as `<target>.staging`
+    async fn stage_file_artifact(&self, artifact: PipelineArtifact) -> Result<StagedArtifact> {
         // Extract target filename from artifact metadata
         let target_filename = artifact
             .metadata
@@ -149,15 +217,122 @@ impl PublishContentStage {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing target_filename in artifact metadata"))?
             .to_string();
+        let staging_filename = format!("{target_filename}{STAGING_SUFFIX}");
 
         info!(
-            "Publishing file: temp_file={} target_filename={} content_type={:?} size={}KB",
+            "Staging file: temp_file={} staging_file={} content_type={:?} size={}KB",
             artifact.file_path,
-            target_filename,
+            staging_filename,
             artifact.artifact_type.content,
             artifact.file_size.unwrap_or(0) / 1024
         );
 
+        self.atomic_move(&artifact.file_path, &staging_filename)
+            .await?;
+
+        Ok(StagedArtifact {
+            artifact,
+            target_filename,
+            staging_filename,
+        })
+    }
+
+    /// Check a staged file against the publishing sanity checks
+    async fn validate_staged(&self, staged: &StagedArtifact) -> Result<()> {
+        let is_playlist = staged.artifact.artifact_type.content == ContentType::M3uPlaylist;
+        let output = OutputSample::read(
+            &self.proxy_output_file_manager,
+            &staged.staging_filename,
+            is_playlist,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read staged file: {}", e))?;
+
+        // The previous playlist is only needed to measure the channel drop
+        let previous_channels = if is_playlist && self.publishing.max_channel_drop_percent > 0 {
+            OutputSample::read(
+                &self.proxy_output_file_manager,
+                &staged.target_filename,
+                true,
+            )
+            .await
+            .ok()
+            .map(|previous| previous.channels)
+        } else {
+            None
+        };
+
+        validate_output(
+            &staged.artifact.artifact_type.content,
+            &output,
+            previous_channels,
+            &self.publishing,
+        )
+        .map_err(|reason| {
+            warn!(
+                "Rejected regenerated {} for proxy {}: {}; keeping the current output",
+                staged.target_filename, self.proxy_id, reason
+            );
+            anyhow::anyhow!(
+                "Generated {} failed validation: {}",
+                staged.target_filename,
+                reason
+            )
+        })
+    }
+
+    /// Remove staged files after a failed validation
+    async fn discard_staged(&self, staged: &[StagedArtifact]) {
+        for staged_artifact in staged {
+            if let Err(e) = self
+                .proxy_output_file_manager
+                .remove_file(&staged_artifact.staging_filename)
+                .await
+            {
+                warn!(
+                    "Failed to remove staged file '{}': {}",
+                    staged_artifact.staging_filename, e
+                );
+            }
+        }
+    }
+
+    /// Keep the live file as the previous generation
+    ///
+    /// On disk the live file is hard linked, so no bytes are copied; the publish rename then
+    /// leaves the link pointing at the replaced generation. Object storage and filesystems
+    /// without hard links fall back to a copy.
+    async fn keep_previous(&self, target_filename: &str, previous_filename: &str) -> Result<()> {
+        let manager = &self.proxy_output_file_manager;
+        if manager.is_local() {
+            let target_path = manager.get_full_path(target_filename)?;
+            let previous_path = manager.get_full_path(previous_filename)?;
+            if let Err(e) = tokio::fs::remove_file(&previous_path).await
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                return Err(e.into());
+            }
+            match tokio::fs::hard_link(&target_path, &previous_path).await {
+                Ok(()) => return Ok(()),
+                Err(e) => debug!(
+                    "Hard link of {} failed ({}), copying it instead",
+                    target_filename, e
+                ),
+            }
+        }
+        manager.copy(target_filename, previous_filename).await?;
+        Ok(())
+    }
+
+    /// Swap a validated file in, keeping the replaced generation for rollback
+    async fn publish_staged(&self, staged: StagedArtifact) -> Result<PipelineArtifact> {
+        let publish_start = Instant::now();
+        let StagedArtifact {
+            artifact,
+            target_filename,
+            staging_filename,
+        } = staged;
+
         // Create backup of existing file if versioning is enabled
         if self.enable_versioning {
             debug!(
@@ -165,16 +340,32 @@ impl PublishContentStage {
                 target_filename
             );
             self.create_backup(&target_filename).await?;
-        } else {
-            debug!(
-                "Versioning disabled - no backup created for {}",
-                target_filename
-            );
         }
 
-        // Perform atomic move from temporary to final location
-        self.atomic_move(&artifact.file_path, &target_filename)
-            .await?;
+        let target_exists = self
+            .proxy_output_file_manager
+            .exists(&target_filename)
+            .await
+            .unwrap_or_default();
+        if self.publishing.keep_previous && target_exists {
+            let previous_filename = format!("{target_filename}{PREVIOUS_SUFFIX}");
+            self.keep_previous(&target_filename, &previous_filename)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to keep previous generation: {}", e))?;
+        }
+
+        // Rename replaces the live file atomically
+        self.proxy_output_file_manager
+            .rename(&staging_filename, &target_filename)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to publish '{}' to '{}': {}",
+                    staging_filename,
+                    target_filename,
+                    e
+                )
+            })?;
 
         let publish_duration = publish_start.elapsed();
 
@@ -207,6 +398,32 @@ impl PublishContentStage {
         Ok(published_artifact)
     }
 
+    /// Remember the published playlist so clients can fetch deltas against it
+    async fn record_playlist_generation(&self, target_filename: &str) {
+        if self.publishing.delta_history == 0 {
+            return;
+        }
+        let store = PlaylistDeltaStore::new(
+            self.proxy_output_file_manager.clone(),
+            self.publishing.delta_history,
+        );
+        let result = match self
+            .proxy_output_file_manager
+            .read_to_string(target_filename)
+            .await
+        {
+            Ok(content) => store.record(&self.proxy_id, &content).await,
+            Err(e) => Err(anyhow::anyhow!("Failed to read published playlist: {}", e)),
+        };
+        // Deltas are an optimisation; the full playlist is already published
+        if let Err(e) = result {
+            warn!(
+                "Failed to record playlist generation of proxy {}: {}",
+                self.proxy_id, e
+            );
+        }
+    }
+
     /// Create backup of existing file if it exists
     async fn create_backup(&self, target_filename: &str) -> Result<()> {
         // Check if target file exists
@@ -318,6 +535,175 @@ impl PublishContentStage {
     }
 }
 
+/// Bytes read from each end of a generated file for the publishing checks
+const SAMPLE_HEAD_BYTES: usize = 8 * 1024;
+const SAMPLE_TAIL_BYTES: usize = 1024;
+
+/// The parts of a generated file the publishing checks look at
+///
+/// Only the start and end of the file are kept; playlist channels are counted line by line,
+/// so multi-gigabyte guides are never loaded into memory.
+pub struct OutputSample {
+    head: Vec<u8>,
+    tail: Vec<u8>,
+    channels: usize,
+}
+
+impl OutputSample {
+    /// Sample content already in memory
+    pub fn from_bytes(content: &[u8]) -> Self {
+        Self {
+            head: content[..content.len().min(SAMPLE_HEAD_BYTES)].to_vec(),
+            tail: content[content.len().saturating_sub(SAMPLE_TAIL_BYTES)..].to_vec(),
+            channels: count_playlist_channels(content),
+        }
+    }
+
+    /// Sample a file, counting playlist channels only when `count_channels` is set
+    async fn read(manager: &SandboxedManager, path: &str, count_channels: bool) -> Result<Self> {
+        use std::io::SeekFrom;
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader};
+
+        if !manager.is_local() {
+            // Object storage has no seekable reads
+            return Ok(Self::from_bytes(&manager.read(path).await?));
+        }
+
+        let mut file = manager.open(path).await?;
+        let len = file.metadata().await?.len();
+        let mut head = Vec::new();
+        (&mut file)
+            .take(SAMPLE_HEAD_BYTES as u64)
+            .read_to_end(&mut head)
+            .await?;
+        let mut tail = Vec::new();
+        file.seek(SeekFrom::Start(
+            len.saturating_sub(SAMPLE_TAIL_BYTES as u64),
+        ))
+        .await?;
+        file.read_to_end(&mut tail).await?;
+
+        let mut channels = 0;
+        if count_channels {
+            file.seek(SeekFrom::Start(0)).await?;
+            let mut lines = BufReader::new(file).split(b'\n');
+            while let Some(line) = lines.next_segment().await? {
+                if is_channel_line(&line) {
+                    channels += 1;
+                }
+            }
+        }
+        Ok(Self {
+            head,
+            tail,
+            channels,
+        })
+    }
+}
+
+/// Check generated output against the publishing sanity checks
+///
+/// `previous_channels` is the channel count of the playlist being replaced, used for the
+/// channel drop limit.
+pub fn validate_output(
+    content_type: &ContentType,
+    output: &OutputSample,
+    previous_channels: Option<usize>,
+    config: &OutputPublishingConfig,
+) -> std::result::Result<(), String> {
+    let head = String::from_utf8_lossy(&output.head);
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+
+    match content_type {
+        ContentType::M3uPlaylist => {
+            if !head.starts_with("#EXTM3U") {
+                return Err("playlist does not start with #EXTM3U".to_string());
+            }
+            let channels = output.channels;
+            if channels < config.min_channels {
+                return Err(format!(
+                    "playlist has {channels} channels, fewer than the minimum of {}",
+                    config.min_channels
+                ));
+            }
+            if config.max_channel_drop_percent > 0
+                && let Some(previous_channels) = previous_channels
+                && previous_channels > channels
+            {
+                let drop_percent = (previous_channels - channels) * 100 / previous_channels;
+                if drop_percent > config.max_channel_drop_percent as usize {
+                    return Err(format!(
+                        "playlist has {channels} channels, down {drop_percent}% from {previous_channels}"
+                    ));
+                }
+            }
+        }
+        ContentType::XmltvGuide => {
+            let tail = String::from_utf8_lossy(&output.tail);
+            if !head.contains("<tv") || !tail.trim_end().ends_with("</tv>") {
+                return Err("XMLTV document is incomplete".to_string());
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn count_playlist_channels(content: &[u8]) -> usize {
+    content
+        .split(|byte| *byte == b'\n')
+        .filter(|line| is_channel_line(line))
+        .count()
+}
+
+fn is_channel_line(line: &[u8]) -> bool {
+    line.trim_ascii_start().starts_with(b"#EXTINF")
+}
+
+/// Restore the generation replaced by the last publish of a proxy's output
+///
+/// The live and previous files are swapped, so a second rollback undoes the first.
+/// Returns the restored file names, or an empty list when there is nothing to restore.
+pub async fn rollback_published_output(
+    proxy_output_file_manager: &SandboxedManager,
+    proxy_id: Uuid,
+) -> Result<Vec<String>> {
+    let mut restored = Vec::new();
+    for target_filename in [format!("{proxy_id}.m3u8"), format!("{proxy_id}.xmltv")] {
+        let previous_filename = format!("{target_filename}{PREVIOUS_SUFFIX}");
+        if !proxy_output_file_manager
+            .exists(&previous_filename)
+            .await
+            .unwrap_or_default()
+        {
+            continue;
+        }
+
+        let has_current = proxy_output_file_manager
+            .exists(&target_filename)
+            .await
+            .unwrap_or_default();
+        let swap_filename = format!("{target_filename}{STAGING_SUFFIX}");
+        if has_current {
+            proxy_output_file_manager
+                .copy(&target_filename, &swap_filename)
+                .await?;
+        }
+        proxy_output_file_manager
+            .rename(&previous_filename, &target_filename)
+            .await?;
+        if has_current {
+            proxy_output_file_manager
+                .rename(&swap_filename, &previous_filename)
+                .await?;
+        }
+
+        info!("Rolled back {} to the previous generation", target_filename);
+        restored.push(target_filename);
+    }
+    Ok(restored)
+}
+
 impl ProgressAware for PublishContentStage {
     fn get_progress_manager(&self) -> Option<&Arc<ProgressManager>> {
         self.progress_manager.as_ref()
@@ -355,3 +741,122 @@ impl PipelineStage for PublishContentStage {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist(channels: usize) -> Vec<u8> {
+        let mut text = String::from("#EXTM3U\n");
+        for i in 0..channels {
+            text.push_str(&format!("#EXTINF:-1,Channel {i}\nhttp://example.com/{i}\n"));
+        }
+        text.into_bytes()
+    }
+
+    #[test]
+    fn test_validate_playlist() {
+        let config = OutputPublishingConfig {
+            min_channels: 2,
+            max_channel_drop_percent: 50,
+            keep_previous: true,
+            delta_history: 0,
+        };
+        let m3u = &ContentType::M3uPlaylist;
+
+        let sample = |content: &[u8]| OutputSample::from_bytes(content);
+
+        assert!(validate_output(m3u, &sample(&playlist(2)), None, &config).is_ok());
+        assert!(validate_output(m3u, &sample(&playlist(1)), None, &config).is_err());
+        assert!(validate_output(m3u, &sample(b"<html>error</html>"), None, &config).is_err());
+        // Losing half the channels is allowed, losing more is not
+        assert!(validate_output(m3u, &sample(&playlist(5)), Some(10), &config).is_ok());
+        assert!(validate_output(m3u, &sample(&playlist(4)), Some(10), &config).is_err());
+    }
+
+    #[test]
+    fn test_validate_xmltv() {
+        let config = OutputPublishingConfig::default();
+        let xmltv = &ContentType::XmltvGuide;
+
+        assert!(
+            validate_output(
+                xmltv,
+                &OutputSample::from_bytes(b"<?xml version=\"1.0\"?>\n<tv>\n</tv>\n"),
+                None,
+                &config
+            )
+            .is_ok()
+        );
+        assert!(
+            validate_output(
+                xmltv,
+                &OutputSample::from_bytes(b"<?xml version=\"1.0\"?>\n<tv>\n<programme"),
+                None,
+                &config
+            )
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_swaps_generations() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .cleanup_policy(sandboxed_file_manager::CleanupPolicy::disabled())
+            .build()
+            .await
+            .unwrap();
+        let proxy_id = Uuid::new_v4();
+        let m3u = format!("{proxy_id}.m3u8");
+
+        assert!(
+            rollback_published_output(&manager, proxy_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        manager.write(&m3u, "new").await.unwrap();
+        manager
+            .write(format!("{m3u}{PREVIOUS_SUFFIX}"), "old")
+            .await
+            .unwrap();
+
+        let restored = rollback_published_output(&manager, proxy_id).await.unwrap();
+        assert_eq!(restored, vec![m3u.clone()]);
+        assert_eq!(manager.read_to_string(&m3u).await.unwrap(), "old");
+
+        // A second rollback undoes the first
+        rollback_published_output(&manager, proxy_id).await.unwrap();
+        assert_eq!(manager.read_to_string(&m3u).await.unwrap(), "new");
+    }
+
+    #[tokio::test]
+    async fn test_output_sample_reads_ends_of_large_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .cleanup_policy(sandboxed_file_manager::CleanupPolicy::disabled())
+            .build()
+            .await
+            .unwrap();
+
+        let content = playlist(2000);
+        assert!(content.len() > SAMPLE_HEAD_BYTES + SAMPLE_TAIL_BYTES);
+        manager.write("large.m3u8", &content).await.unwrap();
+
+        let sample = OutputSample::read(&manager, "large.m3u8", true)
+            .await
+            .unwrap();
+        assert_eq!(sample.channels, 2000);
+        assert_eq!(sample.head, content[..SAMPLE_HEAD_BYTES]);
+        assert_eq!(sample.tail, content[content.len() - SAMPLE_TAIL_BYTES..]);
+
+        let uncounted = OutputSample::read(&manager, "large.m3u8", false)
+            .await
+            .unwrap();
+        assert_eq!(uncounted.channels, 0);
+    }
+}
@@ -0,0 +1,292 @@
+//! Virtual channel injection stage for pipeline processing
+//!
+//! This stage inserts the user-defined virtual channels assigned to the proxy into the
+//! filtered channel set, after the last channel of their group, so they are logo-cached,
+//! numbered and generated like any ingested channel.
+
+use crate::database::repositories::VirtualChannelSeaOrmRepository;
+use crate::models::Channel;
+use crate::models::virtual_channel::{VirtualChannel, is_virtual_channel};
+use crate::pipeline::error::PipelineError;
+use crate::pipeline::models::{ArtifactType, ContentType, PipelineArtifact, ProcessingStage};
+use crate::pipeline::traits::{PipelineStage, ProgressAware};
+use crate::services::progress_service::ProgressManager;
+use sandboxed_file_manager::SandboxedManager;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+pub struct VirtualChannelsStage {
+    repository: VirtualChannelSeaOrmRepository,
+    file_manager: SandboxedManager,
+    pipeline_execution_prefix: String,
+    proxy_id: Uuid,
+    progress_manager: Option<Arc<ProgressManager>>,
+}
+
+impl VirtualChannelsStage {
+    pub fn new(
+        db_connection: Arc<DatabaseConnection>,
+        file_manager: SandboxedManager,
+        pipeline_execution_prefix: String,
+        proxy_id: Uuid,
+        progress_manager: Option<Arc<ProgressManager>>,
+    ) -> Self {
+        Self {
+            repository: VirtualChannelSeaOrmRepository::new(db_connection),
+            file_manager,
+            pipeline_execution_prefix,
+            proxy_id,
+            progress_manager,
+        }
+    }
+
+    /// Helper method for reporting progress
+    async fn report_progress(&self, percentage: f64, message: &str) {
+        if let Some(pm) = &self.progress_manager
+            && let Some(updater) = pm.get_stage_updater("virtual_channels").await
+        {
+            updater.update_progress(percentage, message).await;
+        }
+    }
+
+    /// Set the progress manager for this stage
+    pub fn set_progress_manager(&mut self, progress_manager: Arc<ProgressManager>) {
+        self.progress_manager = Some(progress_manager);
+    }
+
+    pub async fn process(
+        &self,
+        input_artifacts: Vec<PipelineArtifact>,
+    ) -> Result<Vec<PipelineArtifact>, Box<dyn std::error::Error>> {
+        let virtual_channels = self
+            .repository
+            .list_active_for_proxy(&self.proxy_id)
+            .await?;
+        if virtual_channels.is_empty() {
+            debug!("No virtual channels assigned to proxy {}", self.proxy_id);
+            return Ok(input_artifacts);
+        }
+
+        // Inject into the first channel artifact; create one when the proxy has no channels
+        let mut output_artifacts = Vec::with_capacity(input_artifacts.len() + 1);
+        let mut channels = Vec::new();
+        let mut found_channels = false;
+        for artifact in input_artifacts {
+            if found_channels || artifact.artifact_type.content != ContentType::Channels {
+                output_artifacts.push(artifact);
+                continue;
+            }
+            let content = String::from_utf8(self.file_manager.read(&artifact.file_path).await?)?;
+            channels = content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<Vec<Channel>, _>>()?;
+            found_channels = true;
+        }
+
+        let inserted = inject_virtual_channels(&mut channels, &virtual_channels);
+
+        let output_filename = format!("{}_virtual_channels.jsonl", self.pipeline_execution_prefix);
+        let output_content = channels
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n");
+        self.file_manager
+            .write(&output_filename, output_content.as_bytes())
+            .await?;
+
+        output_artifacts.insert(
+            0,
+            PipelineArtifact::new(
+                ArtifactType::new(ContentType::Channels, ProcessingStage::Filtered),
+                output_filename,
+                "virtual_channels".to_string(),
+            )
+            .with_record_count(channels.len())
+            .with_file_size(output_content.len() as u64)
+            .with_metadata("virtual_channels_injected".to_string(), inserted.into()),
+        );
+
+        info!(
+            "Virtual channels stage completed: proxy={} injected={} total_channels={}",
+            self.proxy_id,
+            inserted,
+            channels.len()
+        );
+        Ok(output_artifacts)
+    }
+}
+
+/// Insert virtual channels into a channel list, returning how many were inserted
+///
+/// Each channel goes after the last channel of its group (or at the end when the group is
+/// absent). A virtual channel with a fixed number claims it: ingested channels requesting the
+/// same number lose their `tvg_chno` so the numbering stage assigns them a free one.
+pub fn inject_virtual_channels(
+    channels: &mut Vec<Channel>,
+    virtual_channels: &[VirtualChannel],
+) -> usize {
+    for virtual_channel in virtual_channels {
+        if let Some(number) = virtual_channel.channel_number {
+            for channel in channels.iter_mut().filter(|c| !is_virtual_channel(c)) {
+                let claims_number = channel
+                    .tvg_chno
+                    .as_deref()
+                    .and_then(|chno| chno.trim_start_matches('0').parse::<i32>().ok())
+                    == Some(number);
+                if claims_number {
+                    channel.tvg_chno = None;
+                }
+            }
+        }
+
+        let insert_at = virtual_channel
+            .group_title
+            .as_deref()
+            .and_then(|group| {
+                channels
+                    .iter()
+                    .rposition(|c| c.group_title.as_deref() == Some(group))
+            })
+            .map(|index| index + 1)
+            .unwrap_or(channels.len());
+        channels.insert(insert_at, virtual_channel.to_channel());
+    }
+    virtual_channels.len()
+}
+
+impl ProgressAware for VirtualChannelsStage {
+    fn get_progress_manager(&self) -> Option<&Arc<ProgressManager>> {
+        self.progress_manager.as_ref()
+    }
+}
+
+#[async_trait::async_trait]
+impl PipelineStage for VirtualChannelsStage {
+    async fn execute(
+        &mut self,
+        input: Vec<PipelineArtifact>,
+    ) -> Result<Vec<PipelineArtifact>, PipelineError> {
+        self.report_progress(10.0, "Loading virtual channels").await;
+        let result = self.process(input).await.map_err(|e| {
+            PipelineError::stage_error(
+                "virtual_channels",
+                format!("Virtual channel injection failed: {e}"),
+            )
+        })?;
+        self.report_progress(100.0, "Virtual channels injected")
+            .await;
+        Ok(result)
+    }
+
+    async fn cache_fingerprint(&self) -> Option<String> {
+        match self.repository.list_active_for_proxy(&self.proxy_id).await {
+            Ok(virtual_channels) => Some(
+                virtual_channels
+                    .iter()
+                    .map(|vc| format!("{}:{}", vc.id, vc.updated_at.timestamp_millis()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            Err(e) => {
+                warn!("Failed to fingerprint virtual channels: {}", e);
+                None
+            }
+        }
+    }
+
+    fn stage_id(&self) -> &'static str {
+        "virtual_channels"
+    }
+
+    fn stage_name(&self) -> &'static str {
+        "Virtual Channels"
+    }
+
+    async fn cleanup(&mut self) -> Result<(), PipelineError> {
+        Ok(())
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn channel(name: &str, group: &str, chno: Option<&str>) -> Channel {
+        Channel {
+            id: Uuid::new_v4(),
+            source_id: Uuid::new_v4(),
+            tvg_id: None,
+            tvg_name: None,
+            tvg_chno: chno.map(str::to_string),
+            tvg_logo: None,
+            tvg_shift: None,
+            epg_shift: None,
+            group_title: Some(group.to_string()),
+            channel_name: name.to_string(),
+            stream_url: format!("http://example.com/{name}"),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            extra_attributes: Default::default(),
+        }
+    }
+
+    fn virtual_channel(name: &str, group: Option<&str>, number: Option<i32>) -> VirtualChannel {
+        VirtualChannel {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            stream_url: "http://192.168.1.20:5004/auto/v2.1".to_string(),
+            tvg_id: None,
+            tvg_logo: None,
+            group_title: group.map(str::to_string),
+            channel_number: number,
+            relay_profile_id: None,
+            is_active: true,
+            proxy_ids: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_inject_virtual_channels_placement() {
+        let mut channels = vec![
+            channel("News 1", "News", Some("1")),
+            channel("News 2", "News", Some("02")),
+            channel("Sport 1", "Sport", Some("3")),
+        ];
+        let inserted = inject_virtual_channels(
+            &mut channels,
+            &[
+                virtual_channel("Weather Cam", Some("News"), Some(2)),
+                virtual_channel("Offline Loop", Some("Other"), None),
+            ],
+        );
+
+        assert_eq!(inserted, 2);
+        let names: Vec<&str> = channels.iter().map(|c| c.channel_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["News 1", "News 2", "Weather Cam", "Sport 1", "Offline Loop"]
+        );
+        // The virtual channel claims number 2; the ingested channel is renumbered later
+        assert_eq!(channels[1].tvg_chno, None);
+        assert_eq!(channels[2].tvg_chno.as_deref(), Some("2"));
+        assert!(is_virtual_channel(&channels[2]));
+        assert!(!is_virtual_channel(&channels[0]));
+    }
+}
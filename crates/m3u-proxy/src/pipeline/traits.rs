@@ -88,6 +88,15 @@ pub trait PipelineStage: ProgressAware + Send + Sync {
     /// Get the human-readable name for this stage
     fn stage_name(&self) -> &'static str;
 
+    /// Fingerprint of everything this stage reads besides its input artifacts
+    ///
+    /// Stages returning `Some` may be skipped and their cached output reused when the
+    /// fingerprint, and those of all earlier stages, match a previous run. `None` (the
+    /// default) means the stage always runs, as does every stage after it.
+    async fn cache_fingerprint(&self) -> Option<String> {
+        None
+    }
+
     /// Cleanup any resources used by this stage
     async fn cleanup(&mut self) -> Result<(), PipelineError> {
         // Default implementation does nothing
@@ -188,6 +188,14 @@ impl ProxyConfigResolver {
             cache_channel_logos: true, // Default value, field was added later
             cache_program_logos: false, // Default value, field was added later
             relay_profile_id: None,    // Not used for preview proxies
+            sign_stream_urls: false,
+            output_profile: Default::default(),
+            backup_streams: Default::default(),
+            offline_slate: Default::default(),
+            epg_languages: None,
+            regeneration_debounce_seconds: None,
+            channel_number_blocks: Vec::new(),
+            epg_timezone: None,
         };
 
         // Resolve source configurations
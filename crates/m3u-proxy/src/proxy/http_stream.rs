@@ -14,6 +14,8 @@
 //!     If client supplies UA -> `m3u-proxy/<version> (<original>)`
 //!     Else -> use configured `web.user_agent` (which already holds a sensible default).
 //!   - Adds `m3u-proxy-version` header upstream (hyphenated; HTTP header names cannot contain '/').
+//!   - Per-source streaming header overrides (User-Agent, Referer, extra headers) replace the
+//!     normalized User-Agent and are sent on every upstream request.
 //!   - Optional uniform response headers added via `StreamHeaderMeta`.
 //!
 //! This keeps the proxy logic DRY and consistent across handlers.
@@ -27,6 +29,8 @@ use futures::StreamExt;
 use reqwest::Client;
 use tracing::{debug, error, info};
 
+use crate::proxy::stream_latency::StartupPhase;
+
 /// Metadata used to decorate the outgoing proxied response with normalized headers.
 /// All fields are optional; only present values are emitted.
 #[derive(Debug, Clone, Default)]
@@ -105,6 +109,80 @@ fn insert_if_absent(headers: &mut HeaderMap, name: &str, value: &str) {
     }
 }
 
+/// Apply a source's streaming header overrides to an upstream client
+///
+/// The override User-Agent replaces the one already set on the builder; the Referer and
+/// extra headers become default headers of every request.
+pub fn apply_stream_header_overrides(
+    builder: reqwest::ClientBuilder,
+    overrides: Option<&crate::models::stream_headers::StreamHeaderOverrides>,
+) -> reqwest::ClientBuilder {
+    let headers = stream_header_override_map(overrides);
+    if headers.is_empty() {
+        builder
+    } else {
+        builder.default_headers(headers)
+    }
+}
+
+/// A source's streaming header overrides, including its User-Agent, as request headers
+///
+/// Invalid values (rejected on save, but possibly stored by older versions) are skipped.
+pub fn stream_header_override_map(
+    overrides: Option<&crate::models::stream_headers::StreamHeaderOverrides>,
+) -> reqwest::header::HeaderMap {
+    let mut headers = reqwest::header::HeaderMap::new();
+    let Some(overrides) = overrides else {
+        return headers;
+    };
+    let user_agent = overrides
+        .user_agent
+        .as_deref()
+        .map(|user_agent| ("User-Agent", user_agent));
+    for (name, value) in user_agent.into_iter().chain(overrides.header_pairs()) {
+        match (
+            reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+            reqwest::header::HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => debug!("Skipping invalid upstream header override '{}'", name),
+        }
+    }
+    headers
+}
+
+/// Streaming header overrides configured for a stream source, if any
+///
+/// Lookup failures are logged and treated as "no overrides" so streaming still starts.
+pub async fn load_stream_header_overrides(
+    connection: Arc<sea_orm::DatabaseConnection>,
+    source_id: uuid::Uuid,
+) -> Option<crate::models::stream_headers::StreamHeaderOverrides> {
+    let repo = crate::database::repositories::StreamHeadersSeaOrmRepository::new(connection);
+    match repo.get(&source_id).await {
+        Ok(overrides) if !overrides.is_empty() => Some(overrides),
+        Ok(_) => None,
+        Err(e) => {
+            error!(
+                "Failed to load streaming headers for source {}: {}",
+                source_id, e
+            );
+            None
+        }
+    }
+}
+
+/// Upstream client for classification and collapsing requests carrying the overrides
+pub fn upstream_client(
+    overrides: Option<&crate::models::stream_headers::StreamHeaderOverrides>,
+) -> Client {
+    apply_stream_header_overrides(Client::builder(), overrides)
+        .build()
+        .unwrap_or_default()
+}
+
 /// Build the upstream User-Agent according to spec:
 ///  - If client supplied UA -> "m3u-proxy/<version> (<original>)"
 ///  - Else -> configured web.user_agent (already versioned)
@@ -133,45 +211,25 @@ fn build_upstream_user_agent(
     (final_ua, VERSION.to_string())
 }
 
-/// Unified stream proxy function.
-/// - Establishes upstream connection (connect timeout only).
-/// - Streams body indefinitely (no total timeout).
-/// - Tracks bytes served via `SessionTracker`.
-/// - Optionally decorates response with uniform stream headers (meta).
+/// Upstream client and forwarded headers for a client's stream request
 ///
-/// On failure, ends the session and returns an error response.
-#[allow(clippy::too_many_arguments)]
-pub async fn proxy_http_stream(
-    stream_url: &str,
+/// Returns the client (normalized User-Agent, connect timeout only, source header
+/// overrides), the headers to send upstream and the proxy version.
+pub(crate) fn upstream_stream_client(
     request_headers: &HeaderMap,
     app_config: &crate::config::Config,
-    session_tracker: Arc<crate::proxy::session_tracker::SessionTracker>,
-    session_stats: crate::proxy::session_tracker::SessionStats,
-    meta: Option<StreamHeaderMeta>,
-) -> Response<Body> {
-    info!("Proxying upstream stream: {}", stream_url);
-
+    header_overrides: Option<&crate::models::stream_headers::StreamHeaderOverrides>,
+) -> Result<(Client, reqwest::header::HeaderMap, String), reqwest::Error> {
     // Compose UA
     let (user_agent, version) = build_upstream_user_agent(request_headers, app_config);
 
     let connect_timeout: Duration = app_config.web.proxy_upstream_connect_timeout_duration();
 
-    let client = match Client::builder()
+    let builder = Client::builder()
         .user_agent(user_agent)
         .connect_timeout(connect_timeout)
-        .pool_max_idle_per_host(8)
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            error!("Failed to build reqwest client: {}", e);
-            session_tracker.end_session(&session_stats.session_id).await;
-            return error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to initialize upstream client",
-            );
-        }
-    };
+        .pool_max_idle_per_host(8);
+    let client = apply_stream_header_overrides(builder, header_overrides).build()?;
 
     // Prepare minimal header forwarding (optional extension)
     let mut forwarded = reqwest::header::HeaderMap::new();
@@ -190,6 +248,41 @@ pub async fn proxy_http_stream(
         }
     }
 
+    Ok((client, forwarded, version))
+}
+
+/// Unified stream proxy function.
+/// - Establishes upstream connection (connect timeout only).
+/// - Streams body indefinitely (no total timeout).
+/// - Tracks bytes served via `SessionTracker`.
+/// - Optionally decorates response with uniform stream headers (meta).
+///
+/// On failure, ends the session and returns an error response.
+#[allow(clippy::too_many_arguments)]
+pub async fn proxy_http_stream(
+    stream_url: &str,
+    request_headers: &HeaderMap,
+    app_config: &crate::config::Config,
+    session_tracker: Arc<crate::proxy::session_tracker::SessionTracker>,
+    session_stats: crate::proxy::session_tracker::SessionStats,
+    meta: Option<StreamHeaderMeta>,
+    header_overrides: Option<&crate::models::stream_headers::StreamHeaderOverrides>,
+) -> Response<Body> {
+    info!("Proxying upstream stream: {}", stream_url);
+
+    let (client, forwarded, version) =
+        match upstream_stream_client(request_headers, app_config, header_overrides) {
+            Ok(parts) => parts,
+            Err(e) => {
+                error!("Failed to build reqwest client: {}", e);
+                session_tracker.end_session(&session_stats.session_id).await;
+                return error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to initialize upstream client",
+                );
+            }
+        };
+
     let upstream_resp = match client.get(stream_url).headers(forwarded).send().await {
         Ok(r) => r,
         Err(e) => {
@@ -208,6 +301,9 @@ pub async fn proxy_http_stream(
         session_tracker.end_session(&session_stats.session_id).await;
         return error_response(StatusCode::BAD_GATEWAY, "Upstream error status");
     }
+    if let Some(startup) = &session_stats.startup {
+        startup.mark(StartupPhase::UpstreamConnect);
+    }
 
     let upstream_headers = upstream_resp.headers().clone();
     let content_type = upstream_headers
@@ -258,7 +354,8 @@ pub async fn proxy_http_stream(
         chunk_result
     });
 
-    let body = Body::from_stream(byte_stream);
+    // Ends with the session when it is kicked; ends the session on client disconnect
+    let body = Body::from_stream(session_tracker.track_stream(&session_stats, byte_stream));
 
     let mut builder = Response::builder()
         .status(StatusCode::OK)
@@ -0,0 +1,57 @@
+//! Line selection for balanced stream sources
+//!
+//! When the source of a requested channel belongs to a balance group, the channel can be
+//! served from any line of the group. The lines are ranked by their live session counts
+//! (see [`rank_lines`]) and resolved to the equivalent channel of each line, giving the
+//! candidates to try in order.
+
+use anyhow::Result;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::database::repositories::{ChannelSeaOrmRepository, SourceBalanceGroupSeaOrmRepository};
+use crate::models::Channel;
+use crate::models::source_balancing::rank_lines;
+use crate::proxy::session_tracker::SessionTracker;
+
+/// Channels to stream `channel` from, best line first
+///
+/// An ungrouped source yields the channel itself. An empty result means every line of the
+/// group is at its connection limit. Lines that do not carry the channel are skipped.
+pub async fn candidate_lines(
+    connection: Arc<DatabaseConnection>,
+    session_tracker: &SessionTracker,
+    channel: &Channel,
+) -> Result<Vec<Channel>> {
+    let balance_repo = SourceBalanceGroupSeaOrmRepository::new(connection.clone());
+    let mut lines = balance_repo.lines_for_source(&channel.source_id).await?;
+    if lines.is_empty() {
+        return Ok(vec![channel.clone()]);
+    }
+
+    let session_counts = session_tracker.get_source_session_counts().await;
+    for line in &mut lines {
+        line.active_streams = session_counts.get(&line.source_id).copied().unwrap_or(0);
+    }
+
+    let channel_repo = ChannelSeaOrmRepository::new(connection);
+    let mut candidates = Vec::new();
+    for source_id in rank_lines(&lines, channel.source_id) {
+        if source_id == channel.source_id {
+            candidates.push(channel.clone());
+            continue;
+        }
+        match channel_repo
+            .find_equivalent_in_source(channel, source_id)
+            .await?
+        {
+            Some(equivalent) => candidates.push(equivalent),
+            None => debug!(
+                "Line {} does not carry channel '{}', skipping",
+                source_id, channel.channel_name
+            ),
+        }
+    }
+    Ok(candidates)
+}
@@ -11,8 +11,12 @@ use crate::models::*;
 pub mod config_resolver;
 // Legacy filter engine removed - replaced by pipeline-based filtering
 pub mod http_stream;
+pub mod line_balancer;
+pub mod offline_slate;
+pub mod remux;
 pub mod robust_streaming;
 pub mod session_tracker;
+pub mod stream_latency;
 
 /// Parameters for generate_proxy_with_config method
 pub struct GenerateProxyParams<'a> {
@@ -106,6 +110,19 @@ impl ProxyService {
                 stats.total_duration_ms = start_time.elapsed().as_millis() as u64;
                 stats.started_at = execution.started_at;
                 stats.completed_at = execution.completed_at.unwrap_or_else(chrono::Utc::now);
+                stats.stage_cache_hits = execution.cache_hits.clone();
+                stats.stage_cache_misses = execution.cache_misses.clone();
+                stats.logos_deferred = execution.logos_deferred;
+                stats.precheck_effectiveness = execution.precheck_effectiveness.clone();
+                stats.number_block_overflows = execution.number_block_overflows.clone();
+                stats.epg_dedup = execution.epg_dedup;
+                stats.stage_timings = execution.stage_durations_ms();
+                for (stage, memory) in &execution.stage_memory {
+                    stats.add_stage_memory(stage, memory.peak_bytes);
+                }
+                stats.peak_memory_usage_mb = execution
+                    .peak_memory_bytes()
+                    .map(|bytes| bytes as f64 / (1024.0 * 1024.0));
                 stats
             }),
             processed_channels: None, // TODO: Load from execution output files
@@ -0,0 +1,515 @@
+//! Offline slate for proxy-mode streams
+//!
+//! When a proxy has an offline slate configured, an upstream that cannot be reached (or that
+//! drops mid-stream) does not end the client's stream. The client is fed a looping slate —
+//! a generated "channel unavailable" card or a configured media file, transcoded to MPEG-TS
+//! by ffmpeg — while the upstream is retried in the background. As soon as the upstream
+//! answers again the slate is stopped and the stream switches back to it.
+//!
+//! Only raw MPEG-TS streams can be spliced this way; playlists (HLS) keep the regular
+//! proxy path.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{HeaderMap, Response, StatusCode, header};
+use axum::response::IntoResponse;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use reqwest::Client;
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, ChildStdout, Command};
+use tracing::{debug, error, info, warn};
+
+use crate::config::OfflineSlateConfig;
+use crate::models::OfflineSlateMode;
+use crate::proxy::http_stream::{StreamHeaderMeta, apply_uniform_stream_headers};
+use crate::proxy::session_tracker::{SessionStats, SessionTracker};
+use crate::proxy::stream_latency::StartupPhase;
+use crate::services::embedded_font::EmbeddedFontManager;
+use crate::utils::url::UrlUtils;
+
+/// Font for generated slates, written out once and kept for the life of the process
+static FONT_MANAGER: LazyLock<tokio::sync::Mutex<EmbeddedFontManager>> =
+    LazyLock::new(|| tokio::sync::Mutex::new(EmbeddedFontManager::new()));
+
+/// What the slate shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlateSource {
+    /// A still card with a message
+    Generated { message: String },
+    /// A media file, looped
+    Media(PathBuf),
+}
+
+/// Everything needed to play the slate for one stream
+#[derive(Debug, Clone)]
+pub struct OfflineSlate {
+    pub source: SlateSource,
+    pub ffmpeg_command: String,
+    pub width: u32,
+    pub height: u32,
+    pub retry_interval: Duration,
+    pub max_duration: Duration,
+}
+
+impl OfflineSlate {
+    /// The slate a proxy in `mode` serves for `channel_name`, or `None` when it has none
+    pub fn for_proxy(
+        mode: OfflineSlateMode,
+        config: &OfflineSlateConfig,
+        ffmpeg_command: &str,
+        channel_name: &str,
+    ) -> Option<Self> {
+        let generated = || SlateSource::Generated {
+            message: config.message.replace("{channel}", channel_name),
+        };
+        let source = match mode {
+            OfflineSlateMode::Off => return None,
+            OfflineSlateMode::Generated => generated(),
+            OfflineSlateMode::Media => match &config.media_path {
+                Some(path) => SlateSource::Media(path.clone()),
+                None => {
+                    warn!(
+                        "Offline slate mode is 'media' but offline_slate.media_path is not set; using the generated slate"
+                    );
+                    generated()
+                }
+            },
+        };
+        Some(Self {
+            source,
+            ffmpeg_command: ffmpeg_command.to_string(),
+            width: config.width,
+            height: config.height,
+            retry_interval: config.retry_interval_duration(),
+            max_duration: config.max_duration_duration(),
+        })
+    }
+
+    /// ffmpeg arguments writing the endless slate as MPEG-TS to stdout
+    pub fn ffmpeg_args(&self, font_param: Option<&str>) -> Vec<String> {
+        let mut args: Vec<String> = ["-hide_banner", "-loglevel", "error", "-nostdin", "-re"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        match &self.source {
+            SlateSource::Media(path) => {
+                args.extend(["-stream_loop", "-1", "-i"].map(String::from));
+                args.push(path.display().to_string());
+                args.extend(["-c", "copy"].map(String::from));
+            }
+            SlateSource::Generated { message } => {
+                let text = message
+                    .replace('\\', "\\\\")
+                    .replace('\'', "\\'")
+                    .replace(':', "\\:")
+                    .replace('%', "\\%");
+                let font = font_param.map(|f| format!("{f}:")).unwrap_or_default();
+                args.extend(["-f", "lavfi", "-i"].map(String::from));
+                args.push(format!(
+                    "color=c=0x1a1a1a:s={}x{}:r=25",
+                    self.width, self.height
+                ));
+                args.extend(["-f", "lavfi", "-i", "anullsrc=r=48000:cl=stereo"].map(String::from));
+                args.push("-vf".to_string());
+                args.push(format!(
+                    "drawtext=text='{text}':{font}fontcolor=white:fontsize={}:x=(w-text_w)/2:y=(h-text_h)/2",
+                    std::cmp::max(24, self.width / 30)
+                ));
+                args.extend(
+                    [
+                        "-c:v",
+                        "libx264",
+                        "-preset",
+                        "ultrafast",
+                        "-tune",
+                        "stillimage",
+                        "-pix_fmt",
+                        "yuv420p",
+                        "-g",
+                        "50",
+                        "-b:v",
+                        "500k",
+                        "-c:a",
+                        "aac",
+                        "-b:a",
+                        "64k",
+                    ]
+                    .map(String::from),
+                );
+            }
+        }
+        args.extend(["-f", "mpegts", "pipe:1"].map(String::from));
+        args
+    }
+
+    /// Start ffmpeg playing the slate; it is killed when the returned process is dropped
+    async fn spawn(&self) -> std::io::Result<SlateProcess> {
+        let font_param = match &self.source {
+            SlateSource::Generated { .. } => FONT_MANAGER
+                .lock()
+                .await
+                .get_ffmpeg_font_param()
+                .await
+                .unwrap_or(None),
+            SlateSource::Media(_) => None,
+        };
+        let mut child = Command::new(&self.ffmpeg_command)
+            .args(self.ffmpeg_args(font_param.as_deref()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| std::io::Error::other("ffmpeg stdout not captured"))?;
+        Ok(SlateProcess {
+            _child: child,
+            stdout,
+        })
+    }
+}
+
+struct SlateProcess {
+    _child: Child,
+    stdout: ChildStdout,
+}
+
+/// Upstream request the stream keeps retrying
+#[derive(Clone)]
+struct Upstream {
+    client: Client,
+    url: String,
+    headers: reqwest::header::HeaderMap,
+}
+
+/// Outcome of one upstream connection attempt
+enum Connection {
+    Connected(reqwest::Response),
+    /// Unreachable or answering with an error status
+    Unavailable,
+    /// Up, but serving a playlist, which cannot be spliced with the slate
+    Unsupported,
+}
+
+impl Upstream {
+    async fn connect(&self) -> Connection {
+        match self
+            .client
+            .get(&self.url)
+            .headers(self.headers.clone())
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => {
+                let is_playlist = response
+                    .headers()
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .is_some_and(|ct| ct.to_ascii_lowercase().contains("mpegurl"));
+                if is_playlist {
+                    Connection::Unsupported
+                } else {
+                    Connection::Connected(response)
+                }
+            }
+            Ok(response) => {
+                debug!(
+                    "Upstream {} responded with {}",
+                    UrlUtils::obfuscate_credentials(&self.url),
+                    response.status()
+                );
+                Connection::Unavailable
+            }
+            Err(e) => {
+                debug!(
+                    "Upstream {} unreachable: {}",
+                    UrlUtils::obfuscate_credentials(&self.url),
+                    e
+                );
+                Connection::Unavailable
+            }
+        }
+    }
+}
+
+enum SlateEvent {
+    Chunk(std::io::Result<usize>),
+    Retry,
+    Reconnected(Connection),
+}
+
+/// Upstream bytes, with the slate filling in whenever the upstream is down
+fn failover_stream(
+    upstream: Upstream,
+    initial: Option<reqwest::Response>,
+    slate: OfflineSlate,
+) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send + 'static {
+    async_stream::stream! {
+        let mut current = initial.map(|response| response.bytes_stream().boxed());
+        loop {
+            if let Some(stream) = current.as_mut() {
+                match stream.next().await {
+                    Some(Ok(chunk)) => {
+                        yield Ok(chunk);
+                        continue;
+                    }
+                    Some(Err(e)) => warn!(
+                        "Upstream {} failed mid-stream, switching to offline slate: {}",
+                        UrlUtils::obfuscate_credentials(&upstream.url),
+                        e
+                    ),
+                    None => info!(
+                        "Upstream {} ended, switching to offline slate",
+                        UrlUtils::obfuscate_credentials(&upstream.url)
+                    ),
+                }
+                current = None;
+            }
+
+            let mut process = match slate.spawn().await {
+                Ok(process) => process,
+                Err(e) => {
+                    error!("Failed to start offline slate ({}): {}", slate.ffmpeg_command, e);
+                    yield Err(std::io::Error::other(format!("offline slate: {e}")));
+                    break;
+                }
+            };
+            let started = tokio::time::Instant::now();
+            let mut retry = tokio::time::interval_at(started + slate.retry_interval, slate.retry_interval);
+            retry.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut attempt: Option<tokio::task::JoinHandle<Connection>> = None;
+            let mut buf = vec![0u8; 64 * 1024];
+
+            loop {
+                let event = tokio::select! {
+                    read = process.stdout.read(&mut buf) => SlateEvent::Chunk(read),
+                    _ = retry.tick(), if attempt.is_none() => SlateEvent::Retry,
+                    joined = async { attempt.as_mut().expect("guarded").await }, if attempt.is_some() => {
+                        attempt = None;
+                        SlateEvent::Reconnected(joined.unwrap_or(Connection::Unavailable))
+                    }
+                };
+                match event {
+                    SlateEvent::Chunk(Ok(0)) => {
+                        error!("Offline slate ffmpeg exited unexpectedly");
+                        yield Err(std::io::Error::other("offline slate ended"));
+                        return;
+                    }
+                    SlateEvent::Chunk(Ok(len)) => yield Ok(Bytes::copy_from_slice(&buf[..len])),
+                    SlateEvent::Chunk(Err(e)) => {
+                        error!("Failed reading offline slate: {}", e);
+                        yield Err(e);
+                        return;
+                    }
+                    SlateEvent::Retry => {
+                        if started.elapsed() >= slate.max_duration {
+                            warn!(
+                                "Upstream {} still down after {}, ending stream",
+                                UrlUtils::obfuscate_credentials(&upstream.url),
+                                humantime::format_duration(slate.max_duration)
+                            );
+                            return;
+                        }
+                        let upstream = upstream.clone();
+                        attempt = Some(tokio::spawn(async move { upstream.connect().await }));
+                    }
+                    SlateEvent::Reconnected(Connection::Connected(response)) => {
+                        info!(
+                            "Upstream {} recovered after {}, switching back from offline slate",
+                            UrlUtils::obfuscate_credentials(&upstream.url),
+                            humantime::format_duration(Duration::from_secs(started.elapsed().as_secs()))
+                        );
+                        current = Some(response.bytes_stream().boxed());
+                        break;
+                    }
+                    SlateEvent::Reconnected(Connection::Unsupported) => {
+                        // End the stream; the client's reconnect is classified afresh
+                        info!(
+                            "Upstream {} recovered as a playlist, ending slate stream",
+                            UrlUtils::obfuscate_credentials(&upstream.url)
+                        );
+                        return;
+                    }
+                    SlateEvent::Reconnected(Connection::Unavailable) => {}
+                }
+            }
+        }
+    }
+}
+
+/// Proxy a raw TS stream, serving the offline slate whenever the upstream is down
+///
+/// Unlike [`crate::proxy::http_stream::proxy_http_stream`] an unreachable upstream is not an
+/// error: the response starts with the slate and switches over once the upstream answers.
+/// An upstream that turns out to serve a playlist is handed to the regular proxy path.
+#[allow(clippy::too_many_arguments)]
+pub async fn proxy_stream_with_slate(
+    stream_url: &str,
+    request_headers: &HeaderMap,
+    app_config: &crate::config::Config,
+    session_tracker: Arc<SessionTracker>,
+    session_stats: SessionStats,
+    meta: Option<StreamHeaderMeta>,
+    header_overrides: Option<&crate::models::stream_headers::StreamHeaderOverrides>,
+    slate: OfflineSlate,
+) -> Response<Body> {
+    info!(
+        "Proxying upstream stream with offline slate: {}",
+        UrlUtils::obfuscate_credentials(stream_url)
+    );
+
+    let (client, headers, version) = match crate::proxy::http_stream::upstream_stream_client(
+        request_headers,
+        app_config,
+        header_overrides,
+    ) {
+        Ok(parts) => parts,
+        Err(e) => {
+            error!("Failed to build reqwest client: {}", e);
+            session_tracker.end_session(&session_stats.session_id).await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to initialize upstream client",
+            )
+                .into_response();
+        }
+    };
+    let upstream = Upstream {
+        client,
+        url: stream_url.to_string(),
+        headers,
+    };
+    let initial = match upstream.connect().await {
+        Connection::Connected(response) => Some(response),
+        Connection::Unavailable => {
+            warn!(
+                "Upstream {} unavailable, starting with offline slate",
+                UrlUtils::obfuscate_credentials(stream_url)
+            );
+            None
+        }
+        Connection::Unsupported => {
+            debug!(
+                "Upstream {} serves a playlist, proxying without offline slate",
+                UrlUtils::obfuscate_credentials(stream_url)
+            );
+            return crate::proxy::http_stream::proxy_http_stream(
+                stream_url,
+                request_headers,
+                app_config,
+                session_tracker,
+                session_stats,
+                meta,
+                header_overrides,
+            )
+            .await;
+        }
+    };
+    let slate_first = initial.is_none();
+    if let Some(startup) = &session_stats.startup {
+        startup.mark(StartupPhase::UpstreamConnect);
+    }
+
+    let tracker = session_tracker.clone();
+    let session_id = session_stats.session_id.clone();
+    let byte_stream = failover_stream(upstream, initial, slate).map(move |chunk_result| {
+        if let Ok(ref chunk) = chunk_result {
+            let len = chunk.len() as u64;
+            let tracker = tracker.clone();
+            let session_id = session_id.clone();
+            tokio::spawn(async move {
+                tracker.update_session_bytes(&session_id, len).await;
+            });
+        }
+        chunk_result
+    });
+    let body = Body::from_stream(session_tracker.track_stream(&session_stats, byte_stream));
+
+    let mut response = match Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "video/mp2t")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header("m3u-proxy-version", version)
+        .body(body)
+    {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Failed building response object: {}", e);
+            session_tracker.end_session(&session_stats.session_id).await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to build response",
+            )
+                .into_response();
+        }
+    };
+
+    let mut meta = meta.unwrap_or_default();
+    if slate_first {
+        meta.fallback = Some("offline-slate".into());
+    }
+    if !meta.is_empty() {
+        apply_uniform_stream_headers(&mut response, &meta);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slate(mode: OfflineSlateMode, media_path: Option<&str>) -> Option<OfflineSlate> {
+        let config = OfflineSlateConfig {
+            media_path: media_path.map(PathBuf::from),
+            ..Default::default()
+        };
+        OfflineSlate::for_proxy(mode, &config, "ffmpeg", "BBC One")
+    }
+
+    #[test]
+    fn test_for_proxy_modes() {
+        assert!(slate(OfflineSlateMode::Off, Some("/slate.mp4")).is_none());
+        assert_eq!(
+            slate(OfflineSlateMode::Generated, None).unwrap().source,
+            SlateSource::Generated {
+                message: "BBC One is currently unavailable".to_string()
+            }
+        );
+        assert_eq!(
+            slate(OfflineSlateMode::Media, Some("/slate.mp4"))
+                .unwrap()
+                .source,
+            SlateSource::Media(PathBuf::from("/slate.mp4"))
+        );
+        // No media configured: fall back to the generated card
+        assert!(matches!(
+            slate(OfflineSlateMode::Media, None).unwrap().source,
+            SlateSource::Generated { .. }
+        ));
+    }
+
+    #[test]
+    fn test_ffmpeg_args() {
+        let media = slate(OfflineSlateMode::Media, Some("/slate.mp4")).unwrap();
+        let args = media.ffmpeg_args(None).join(" ");
+        assert!(args.contains("-stream_loop -1 -i /slate.mp4 -c copy"));
+        assert!(args.ends_with("-f mpegts pipe:1"));
+
+        let mut generated = slate(OfflineSlateMode::Generated, None).unwrap();
+        generated.source = SlateSource::Generated {
+            message: "Sky: 50% off".to_string(),
+        };
+        let args = generated.ffmpeg_args(Some("fontfile=/tmp/font.ttf"));
+        let filter = &args[args.iter().position(|a| a == "-vf").unwrap() + 1];
+        assert!(filter.starts_with("drawtext=text='Sky\\: 50\\% off':fontfile=/tmp/font.ttf:"));
+        assert!(args.contains(&"color=c=0x1a1a1a:s=1280x720:r=25".to_string()));
+    }
+}
@@ -0,0 +1,250 @@
+//! Container remux for clients that cannot play raw MPEG-TS
+//!
+//! A stream URL requested with `?container=mp4` or `?container=mkv` is served as usual
+//! (proxy or relay mode) and the resulting MPEG-TS is piped through `ffmpeg -c copy`, which
+//! only rewrites the container. MP4 output is fragmented so it can be played while live.
+//!
+//! Responses that are not MPEG-TS (HLS playlists passed through transparently, errors) are
+//! returned unchanged.
+
+use std::process::Stdio;
+
+use axum::body::Body;
+use axum::http::{HeaderValue, Response, StatusCode, header};
+use bytes::Bytes;
+use futures::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdout, Command};
+use tracing::{debug, error, info};
+
+/// Query parameter selecting the output container
+pub const CONTAINER_PARAM: &str = "container";
+
+/// Container a stream can be remuxed into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemuxContainer {
+    /// Fragmented MP4
+    Mp4,
+    /// Matroska
+    Mkv,
+}
+
+impl RemuxContainer {
+    /// Parse the `container` query value; `None` (or `ts`) keeps the stream as MPEG-TS
+    pub fn from_query(value: Option<&str>) -> Result<Option<Self>, String> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("ts") | Some("mpegts") => Ok(None),
+            Some("mp4") | Some("fmp4") => Ok(Some(Self::Mp4)),
+            Some("mkv") | Some("matroska") => Ok(Some(Self::Mkv)),
+            Some(other) => Err(format!(
+                "Unsupported container '{other}' (expected ts, mp4 or mkv)"
+            )),
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Mp4 => "video/mp4",
+            Self::Mkv => "video/x-matroska",
+        }
+    }
+
+    /// Value of the `x-stream-mode` header of remuxed responses
+    fn mode(&self) -> &'static str {
+        match self {
+            Self::Mp4 => "remux-mp4",
+            Self::Mkv => "remux-mkv",
+        }
+    }
+
+    /// ffmpeg arguments copying all streams of the MPEG-TS on stdin into this container on stdout
+    pub fn ffmpeg_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = [
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-fflags",
+            "+genpts",
+            "-f",
+            "mpegts",
+            "-i",
+            "pipe:0",
+            "-map",
+            "0",
+            "-c",
+            "copy",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        match self {
+            Self::Mp4 => {
+                // Fragmented MP4: no seekable moov atom, a fragment per keyframe
+                args.extend(
+                    [
+                        "-movflags",
+                        "frag_keyframe+empty_moov+default_base_moof",
+                        "-f",
+                        "mp4",
+                    ]
+                    .map(String::from),
+                );
+            }
+            Self::Mkv => {
+                args.extend(["-live", "1", "-f", "matroska"].map(String::from));
+            }
+        }
+        args.push("pipe:1".to_string());
+        args
+    }
+}
+
+/// Remux an MPEG-TS stream response into `container`
+///
+/// The original body keeps flowing through its session tracking and is fed to ffmpeg's
+/// stdin; ffmpeg is killed once the client goes away.
+pub fn remux_response(
+    response: Response<Body>,
+    container: RemuxContainer,
+    ffmpeg_command: &str,
+) -> Response<Body> {
+    let is_ts = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("video/mp2t"));
+    if response.status() != StatusCode::OK || !is_ts {
+        debug!(
+            "Not remuxing response (status {}, MPEG-TS: {})",
+            response.status(),
+            is_ts
+        );
+        return response;
+    }
+
+    let mut child = match Command::new(ffmpeg_command)
+        .args(container.ffmpeg_args())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            error!("Failed to start ffmpeg for {:?} remux: {}", container, e);
+            return Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from("Failed to start remux process"))
+                .unwrap();
+        }
+    };
+    let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        error!("ffmpeg remux pipes not captured");
+        return Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::from("Failed to start remux process"))
+            .unwrap();
+    };
+    info!("Remuxing stream to {:?}", container);
+
+    let (mut parts, body) = response.into_parts();
+    tokio::spawn(async move {
+        let mut input = body.into_data_stream();
+        while let Some(chunk) = input.next().await {
+            let written = match chunk {
+                Ok(chunk) => stdin.write_all(&chunk).await,
+                Err(e) => {
+                    debug!("Remux input ended with error: {}", e);
+                    break;
+                }
+            };
+            if let Err(e) = written {
+                // ffmpeg exited, usually because the client disconnected
+                debug!("Remux input closed: {}", e);
+                break;
+            }
+        }
+        // Dropping stdin signals end of input, letting ffmpeg flush its output
+    });
+
+    let output = futures::stream::unfold(
+        RemuxProcess {
+            _child: child,
+            stdout,
+        },
+        |mut process| async move {
+            let mut buf = vec![0u8; 64 * 1024];
+            match process.stdout.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok::<_, std::io::Error>(Bytes::from(buf)), process))
+                }
+                Err(e) => Some((Err(e), process)),
+            }
+        },
+    );
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(container.content_type()),
+    );
+    parts
+        .headers
+        .insert("x-stream-mode", HeaderValue::from_static(container.mode()));
+    Response::from_parts(parts, Body::from_stream(output))
+}
+
+/// ffmpeg process kept alive for as long as its output is being read
+struct RemuxProcess {
+    _child: Child,
+    stdout: ChildStdout,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_query() {
+        assert_eq!(RemuxContainer::from_query(None), Ok(None));
+        assert_eq!(RemuxContainer::from_query(Some("ts")), Ok(None));
+        assert_eq!(
+            RemuxContainer::from_query(Some("MP4")),
+            Ok(Some(RemuxContainer::Mp4))
+        );
+        assert_eq!(
+            RemuxContainer::from_query(Some("matroska")),
+            Ok(Some(RemuxContainer::Mkv))
+        );
+        assert!(RemuxContainer::from_query(Some("avi")).is_err());
+    }
+
+    #[test]
+    fn test_ffmpeg_args() {
+        let args = RemuxContainer::Mp4.ffmpeg_args().join(" ");
+        assert!(args.contains("-f mpegts -i pipe:0 -map 0 -c copy"));
+        assert!(
+            args.ends_with("-movflags frag_keyframe+empty_moov+default_base_moof -f mp4 pipe:1")
+        );
+
+        let args = RemuxContainer::Mkv.ffmpeg_args().join(" ");
+        assert!(args.ends_with("-live 1 -f matroska pipe:1"));
+    }
+
+    #[tokio::test]
+    async fn test_non_ts_response_is_unchanged() {
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/vnd.apple.mpegurl")
+            .body(Body::from("#EXTM3U"))
+            .unwrap();
+        let response = remux_response(response, RemuxContainer::Mp4, "ffmpeg");
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/vnd.apple.mpegurl"
+        );
+    }
+}
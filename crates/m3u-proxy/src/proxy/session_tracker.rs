@@ -1,14 +1,22 @@
 //! Session tracking and statistics for proxy streams
 //!
 //! This module provides comprehensive session tracking with detailed logging
-//! and periodic statistics reporting for proxy streaming sessions.
+//! and periodic statistics reporting for proxy streaming sessions. Sessions can carry
+//! the identity (share link or user) they were opened with, which bounds how many
+//! streams that identity may hold at once, and can be terminated from the API. Session
+//! starts and ends are published as automation events when MQTT is enabled.
 
+use futures::{Stream, StreamExt};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
+use super::stream_latency::StartupTimer;
+use crate::services::mqtt_publisher::{AutomationEvent, MqttPublisher};
+
 /// Client information for session tracking
 #[derive(Debug, Clone)]
 pub struct ClientInfo {
@@ -17,11 +25,54 @@ pub struct ClientInfo {
     pub referer: Option<String>,
 }
 
+/// Who a streaming session belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionIdentity {
+    /// Key sessions are grouped by for limits, e.g. "share_link:<id>" or "user:<name>"
+    pub key: String,
+    /// Human-readable label (share link name or user name)
+    pub label: Option<String>,
+    /// Maximum concurrent streams for this identity (unlimited when absent)
+    pub max_streams: Option<u32>,
+}
+
+impl SessionIdentity {
+    /// Identity of streams opened through a share link
+    pub fn share_link(link_id: uuid::Uuid, name: Option<String>, max_streams: Option<u32>) -> Self {
+        Self {
+            key: format!("share_link:{link_id}"),
+            label: name,
+            max_streams,
+        }
+    }
+
+    /// Identity of a user the stream request authenticated as
+    pub fn user(name: &str, max_streams: Option<u32>) -> Self {
+        Self {
+            key: format!("user:{name}"),
+            label: Some(name.to_string()),
+            max_streams,
+        }
+    }
+}
+
+/// Returned when starting a session would exceed its identity's stream limit
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{identity} already has {active} active stream(s) (limit {limit})")]
+pub struct SessionLimitExceeded {
+    pub identity: String,
+    pub active: usize,
+    pub limit: u32,
+}
+
 /// Session statistics for a streaming session
 #[derive(Debug, Clone)]
 pub struct SessionStats {
     pub session_id: String,
     pub client_info: ClientInfo,
+    pub identity: Option<SessionIdentity>,
+    /// Stream source (line) the session is served from
+    pub source_id: Option<uuid::Uuid>,
     pub proxy_id: String,
     pub proxy_name: String,
     pub channel_id: String,
@@ -34,6 +85,10 @@ pub struct SessionStats {
     pub upstream_url: String,
     pub connection_attempts: u32,
     pub last_error: Option<String>,
+    /// Cancelled when the session is kicked; stops the response stream
+    pub cancellation: CancellationToken,
+    /// Measures the time to first byte, when the session is timed
+    pub startup: Option<StartupTimer>,
 }
 
 impl SessionStats {
@@ -50,6 +105,8 @@ impl SessionStats {
         Self {
             session_id,
             client_info,
+            identity: None,
+            source_id: None,
             proxy_id,
             proxy_name,
             channel_id,
@@ -62,9 +119,30 @@ impl SessionStats {
             upstream_url,
             connection_attempts: 0,
             last_error: None,
+            cancellation: CancellationToken::new(),
+            startup: None,
         }
     }
 
+    /// Attribute the session to an identity
+    pub fn with_identity(mut self, identity: Option<SessionIdentity>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// Record the stream source the session is served from
+    pub fn with_source(mut self, source_id: uuid::Uuid) -> Self {
+        self.source_id = Some(source_id);
+        self
+    }
+
+    /// Time the session's start-up; the first chunk through [`SessionTracker::track_stream`]
+    /// completes the measurement
+    pub fn with_startup(mut self, timer: StartupTimer) -> Self {
+        self.startup = Some(timer);
+        self
+    }
+
     pub fn update_bytes_served(&mut self, bytes: u64) {
         self.bytes_served += bytes;
         self.chunks_served += 1;
@@ -131,6 +209,7 @@ pub struct SessionTracker {
     stats_interval: Duration,
     cleanup_interval: Duration,
     session_timeout: Duration,
+    events: MqttPublisher,
 }
 
 impl SessionTracker {
@@ -138,12 +217,37 @@ impl SessionTracker {
         stats_interval: Duration,
         cleanup_interval: Duration,
         session_timeout: Duration,
+    ) -> Self {
+        Self::with_intervals_and_events(
+            stats_interval,
+            cleanup_interval,
+            session_timeout,
+            MqttPublisher::disabled(),
+        )
+    }
+
+    /// Tracker with the default intervals publishing session starts and ends to `events`
+    pub fn with_event_publisher(events: MqttPublisher) -> Self {
+        Self::with_intervals_and_events(
+            Duration::from_secs(30),  // Stats every 30 seconds
+            Duration::from_secs(60),  // Cleanup every minute
+            Duration::from_secs(120), // 2 minute session timeout (backup for stale connections)
+            events,
+        )
+    }
+
+    fn with_intervals_and_events(
+        stats_interval: Duration,
+        cleanup_interval: Duration,
+        session_timeout: Duration,
+        events: MqttPublisher,
     ) -> Self {
         let tracker = Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             stats_interval,
             cleanup_interval,
             session_timeout,
+            events,
         };
 
         // Start background tasks
@@ -155,35 +259,136 @@ impl SessionTracker {
 
     /// Start a new streaming session
     pub async fn start_session(&self, session_stats: SessionStats) {
-        let session_id = session_stats.session_id.clone();
+        log_session_start(&session_stats);
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(session_stats.session_id.clone(), session_stats.clone());
+        self.publish_started(&sessions, &session_stats);
+    }
+
+    /// Start a session unless its identity already holds `max_streams` sessions
+    ///
+    /// The count and insert happen under one lock, so concurrent requests for the same
+    /// identity cannot overshoot the limit. Sessions without an identity or limit always start.
+    pub async fn try_start_session(
+        &self,
+        session_stats: SessionStats,
+    ) -> Result<(), SessionLimitExceeded> {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(identity) = &session_stats.identity
+            && let Some(limit) = identity.max_streams
+        {
+            let active = sessions
+                .values()
+                .filter(|s| s.identity.as_ref().is_some_and(|i| i.key == identity.key))
+                .count();
+            if active >= limit as usize {
+                warn!(
+                    "session_id={} event=session_limit identity=\"{}\" active={} limit={} client_ip={} channel_name=\"{}\"",
+                    session_stats.session_id,
+                    identity.key,
+                    active,
+                    limit,
+                    session_stats.client_info.ip,
+                    session_stats.channel_name
+                );
+                return Err(SessionLimitExceeded {
+                    identity: identity
+                        .label
+                        .clone()
+                        .unwrap_or_else(|| identity.key.clone()),
+                    active,
+                    limit,
+                });
+            }
+        }
+
+        log_session_start(&session_stats);
+        sessions.insert(session_stats.session_id.clone(), session_stats.clone());
+        self.publish_started(&sessions, &session_stats);
+        Ok(())
+    }
 
-        debug!(
-            "session_id={} event=session_start client_ip={} proxy_name=\"{}\" proxy_id={} channel_name=\"{}\" channel_id={} upstream_url=\"{}\"{}{}",
+    /// Forcibly terminate a session
+    ///
+    /// Cancels the session's response stream, which closes the client connection, and
+    /// removes it from tracking. Returns the session, or `None` when it is not active.
+    pub async fn kick_session(&self, session_id: &str) -> Option<SessionStats> {
+        let session = {
+            let mut sessions = self.sessions.write().await;
+            let session = sessions.remove(session_id)?;
+            publish_stopped(&self.events, &sessions, &session);
+            session
+        };
+        session.cancellation.cancel();
+
+        info!(
+            "session_id={} event=session_kicked duration={} data_served={} client_ip={} proxy_name=\"{}\" channel_name=\"{}\"{}",
             session_id,
-            session_stats.client_info.ip,
-            session_stats.proxy_name,
-            session_stats.proxy_id,
-            session_stats.channel_name,
-            session_stats.channel_id,
-            session_stats.upstream_url,
-            session_stats
-                .client_info
-                .user_agent
-                .as_ref()
-                .map(|ua| format!(" user_agent=\"{ua}\""))
-                .unwrap_or_default(),
-            session_stats
-                .client_info
-                .referer
+            session.format_duration(),
+            session.format_bytes(),
+            session.client_info.ip,
+            session.proxy_name,
+            session.channel_name,
+            session
+                .identity
                 .as_ref()
-                .map(|ref_| format!(" referer=\"{ref_}\""))
+                .map(|i| format!(" identity=\"{}\"", i.key))
                 .unwrap_or_default()
         );
+        Some(session)
+    }
 
-        self.sessions
-            .write()
+    /// Terminate every session of an identity; returns how many were kicked
+    pub async fn kick_identity_sessions(&self, identity_key: &str) -> usize {
+        let session_ids: Vec<String> = self
+            .sessions
+            .read()
             .await
-            .insert(session_id, session_stats);
+            .values()
+            .filter(|s| s.identity.as_ref().is_some_and(|i| i.key == identity_key))
+            .map(|s| s.session_id.clone())
+            .collect();
+
+        let mut kicked = 0;
+        for session_id in session_ids {
+            if self.kick_session(&session_id).await.is_some() {
+                kicked += 1;
+            }
+        }
+        kicked
+    }
+
+    /// Bind a response stream to a session
+    ///
+    /// The returned stream ends when the session is kicked, and the session ends as soon
+    /// as the stream is dropped (client disconnect) instead of waiting for the idle timeout.
+    /// The first item completes the session's start-up timing.
+    pub fn track_stream<S>(
+        self: &Arc<Self>,
+        session_stats: &SessionStats,
+        stream: S,
+    ) -> impl Stream<Item = S::Item> + Send + 'static
+    where
+        S: Stream + Send + 'static,
+    {
+        let guard = SessionEndGuard {
+            tracker: self.clone(),
+            session_id: session_stats.session_id.clone(),
+        };
+        let mut startup = session_stats
+            .startup
+            .clone()
+            .map(|timer| (timer, session_stats.clone()));
+        stream
+            .take_until(session_stats.cancellation.clone().cancelled_owned())
+            .map(move |item| {
+                let _ = &guard;
+                if let Some((timer, session)) = startup.take() {
+                    timer.first_byte(&session);
+                }
+                item
+            })
     }
 
     /// Update session with bytes served
@@ -229,9 +434,27 @@ impl SessionTracker {
         }
     }
 
+    /// Publish the start of `session`, already inserted into `sessions`
+    fn publish_started(&self, sessions: &HashMap<String, SessionStats>, session: &SessionStats) {
+        if !self.events.is_enabled() {
+            return;
+        }
+        self.events.publish(AutomationEvent::StreamStarted {
+            session_id: session.session_id.clone(),
+            proxy_name: session.proxy_name.clone(),
+            channel_id: session.channel_id.clone(),
+            channel_name: session.channel_name.clone(),
+            client_ip: session.client_info.ip.clone(),
+            user: session.identity.as_ref().and_then(|i| i.label.clone()),
+            channel_viewers: channel_viewers(sessions, &session.channel_id),
+        });
+    }
+
     /// End a streaming session
     pub async fn end_session(&self, session_id: &str) {
-        if let Some(session) = self.sessions.write().await.remove(session_id) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.remove(session_id) {
+            publish_stopped(&self.events, &sessions, &session);
             info!(
                 "session_id={} event=session_end duration={} data_served={} avg_bitrate_kbps={:.2} chunks_served={} errors={} client_ip={} proxy_name=\"{}\" channel_name=\"{}\"{}",
                 session_id,
@@ -287,6 +510,18 @@ impl SessionTracker {
         counts
     }
 
+    /// Get session count by stream source
+    pub async fn get_source_session_counts(&self) -> HashMap<uuid::Uuid, usize> {
+        let sessions = self.sessions.read().await;
+        let mut counts = HashMap::new();
+
+        for source_id in sessions.values().filter_map(|session| session.source_id) {
+            *counts.entry(source_id).or_insert(0) += 1;
+        }
+
+        counts
+    }
+
     /// Start periodic statistics reporting
     fn start_stats_reporter(&self) {
         let sessions = self.sessions.clone();
@@ -407,6 +642,7 @@ impl SessionTracker {
         let sessions = self.sessions.clone();
         let cleanup_interval = self.cleanup_interval;
         let session_timeout = self.session_timeout;
+        let events = self.events.clone();
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(cleanup_interval);
@@ -425,6 +661,7 @@ impl SessionTracker {
 
                 for session_id in to_remove {
                     if let Some(session) = sessions_write.remove(&session_id) {
+                        publish_stopped(&events, &sessions_write, &session);
                         warn!(
                             "session_id={} event=session_timeout duration={} data_served={} client_ip={} proxy_name=\"{}\" channel_name=\"{}\" chunks_served={} errors={}{}{}",
                             session_id,
@@ -456,11 +693,83 @@ impl SessionTracker {
 
 impl Default for SessionTracker {
     fn default() -> Self {
-        Self::new(
-            Duration::from_secs(30),  // Stats every 30 seconds
-            Duration::from_secs(60),  // Cleanup every minute
-            Duration::from_secs(120), // 2 minute session timeout (backup for stale connections)
-        )
+        Self::with_event_publisher(MqttPublisher::disabled())
+    }
+}
+
+/// Sessions currently watching a channel
+fn channel_viewers(sessions: &HashMap<String, SessionStats>, channel_id: &str) -> usize {
+    sessions
+        .values()
+        .filter(|s| s.channel_id == channel_id)
+        .count()
+}
+
+/// Publish the end of `session`, already removed from `sessions`
+fn publish_stopped(
+    events: &MqttPublisher,
+    sessions: &HashMap<String, SessionStats>,
+    session: &SessionStats,
+) {
+    if !events.is_enabled() {
+        return;
+    }
+    events.publish(AutomationEvent::StreamStopped {
+        session_id: session.session_id.clone(),
+        proxy_name: session.proxy_name.clone(),
+        channel_id: session.channel_id.clone(),
+        channel_name: session.channel_name.clone(),
+        client_ip: session.client_info.ip.clone(),
+        user: session.identity.as_ref().and_then(|i| i.label.clone()),
+        channel_viewers: channel_viewers(sessions, &session.channel_id),
+        duration_seconds: session.duration().as_secs(),
+        bytes_served: session.bytes_served,
+    });
+}
+
+fn log_session_start(session_stats: &SessionStats) {
+    debug!(
+        "session_id={} event=session_start client_ip={} proxy_name=\"{}\" proxy_id={} channel_name=\"{}\" channel_id={} upstream_url=\"{}\"{}{}{}",
+        session_stats.session_id,
+        session_stats.client_info.ip,
+        session_stats.proxy_name,
+        session_stats.proxy_id,
+        session_stats.channel_name,
+        session_stats.channel_id,
+        session_stats.upstream_url,
+        session_stats
+            .client_info
+            .user_agent
+            .as_ref()
+            .map(|ua| format!(" user_agent=\"{ua}\""))
+            .unwrap_or_default(),
+        session_stats
+            .client_info
+            .referer
+            .as_ref()
+            .map(|ref_| format!(" referer=\"{ref_}\""))
+            .unwrap_or_default(),
+        session_stats
+            .identity
+            .as_ref()
+            .map(|i| format!(" identity=\"{}\"", i.key))
+            .unwrap_or_default()
+    );
+}
+
+/// Ends a session when the stream holding it is dropped
+struct SessionEndGuard {
+    tracker: Arc<SessionTracker>,
+    session_id: String,
+}
+
+impl Drop for SessionEndGuard {
+    fn drop(&mut self) {
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let tracker = self.tracker.clone();
+            let session_id = std::mem::take(&mut self.session_id);
+            handle.spawn(async move { tracker.end_session(&session_id).await });
+        }
     }
 }
 
@@ -533,4 +842,78 @@ mod tests {
         let stats = tracker.get_session_stats("test-session").await;
         assert!(stats.is_none());
     }
+
+    fn identified_session(session_id: &str, identity: &SessionIdentity) -> SessionStats {
+        SessionStats::new(
+            session_id.to_string(),
+            ClientInfo {
+                ip: "127.0.0.1".to_string(),
+                user_agent: None,
+                referer: None,
+            },
+            "proxy-1".to_string(),
+            "Test Proxy".to_string(),
+            "channel-1".to_string(),
+            "Test Channel".to_string(),
+            "http://example.com/stream".to_string(),
+        )
+        .with_identity(Some(identity.clone()))
+    }
+
+    #[tokio::test]
+    async fn test_identity_stream_limit() {
+        let tracker = SessionTracker::default();
+        let alice = SessionIdentity::user("alice", Some(2));
+        let bob = SessionIdentity::user("bob", Some(2));
+
+        assert!(
+            tracker
+                .try_start_session(identified_session("a1", &alice))
+                .await
+                .is_ok()
+        );
+        assert!(
+            tracker
+                .try_start_session(identified_session("a2", &alice))
+                .await
+                .is_ok()
+        );
+        let err = tracker
+            .try_start_session(identified_session("a3", &alice))
+            .await
+            .unwrap_err();
+        assert_eq!(err.active, 2);
+        assert_eq!(err.limit, 2);
+
+        // Other identities are counted separately
+        assert!(
+            tracker
+                .try_start_session(identified_session("b1", &bob))
+                .await
+                .is_ok()
+        );
+
+        // Ending a session frees a slot
+        tracker.end_session("a1").await;
+        assert!(
+            tracker
+                .try_start_session(identified_session("a3", &alice))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_kick_session_stops_stream() {
+        let tracker = Arc::new(SessionTracker::default());
+        let session = identified_session("kick-me", &SessionIdentity::user("carol", None));
+        tracker.start_session(session.clone()).await;
+
+        let mut stream = Box::pin(tracker.track_stream(&session, futures::stream::pending::<u8>()));
+
+        assert!(tracker.kick_session("kick-me").await.is_some());
+        assert!(stream.next().await.is_none());
+        assert!(tracker.get_session_stats("kick-me").await.is_none());
+        assert!(tracker.kick_session("kick-me").await.is_none());
+    }
 }
@@ -0,0 +1,507 @@
+//! Stream start latency
+//!
+//! A [`StartupTimer`] follows one stream request from arrival to the first byte handed to
+//! the client, splitting the wait into phases: setup (proxy and channel lookup), upstream
+//! connect (classification and connecting to the provider), relay spawn and buffer fill
+//! (from connection to first data). The [`StreamLatencyMonitor`] keeps recent samples per
+//! proxy for percentiles, records them as metrics and logs sessions over the latency
+//! budget, naming the phase that took longest so slow providers stand out.
+
+use opentelemetry::KeyValue;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::session_tracker::SessionStats;
+use crate::config::StreamLatencyConfig;
+use crate::observability::AppObservability;
+
+/// Part of a stream's start-up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StartupPhase {
+    /// Request handling before contacting the upstream: proxy, channel and line lookup
+    Setup,
+    /// Classifying the stream and connecting to the provider, until response headers
+    UpstreamConnect,
+    /// Starting (or joining) the relay process
+    RelaySpawn,
+    /// From the connection being ready to the first byte of data
+    BufferFill,
+}
+
+impl StartupPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Setup => "setup",
+            Self::UpstreamConnect => "upstream_connect",
+            Self::RelaySpawn => "relay_spawn",
+            Self::BufferFill => "buffer_fill",
+        }
+    }
+}
+
+/// Time to first byte of one session, by phase
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartupLatency {
+    pub total: Duration,
+    /// Phases in the order they happened; they add up to `total`
+    pub phases: Vec<(StartupPhase, Duration)>,
+}
+
+impl StartupLatency {
+    pub fn phase(&self, phase: StartupPhase) -> Option<Duration> {
+        self.phases
+            .iter()
+            .find(|(p, _)| *p == phase)
+            .map(|(_, duration)| *duration)
+    }
+
+    /// The phase that took longest
+    pub fn slowest_phase(&self) -> Option<StartupPhase> {
+        self.phases
+            .iter()
+            .max_by_key(|(_, duration)| *duration)
+            .map(|(phase, _)| *phase)
+    }
+
+    /// `setup=12ms upstream_connect=2.1s buffer_fill=640ms`
+    fn breakdown(&self) -> String {
+        self.phases
+            .iter()
+            .map(|(phase, duration)| format!("{}={}", phase.as_str(), format_duration(*duration)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug)]
+struct TimerState {
+    started: Instant,
+    last_mark: Instant,
+    phases: Vec<(StartupPhase, Duration)>,
+    finished: bool,
+}
+
+/// Measures the start-up of one stream session
+///
+/// Clones share the same measurement, so the handler and the streaming code can both mark
+/// phases. Marking a phase again adds to it.
+#[derive(Clone)]
+pub struct StartupTimer {
+    proxy_id: Uuid,
+    proxy_name: String,
+    state: Arc<Mutex<TimerState>>,
+    monitor: Arc<StreamLatencyMonitor>,
+}
+
+impl std::fmt::Debug for StartupTimer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StartupTimer")
+            .field("proxy_id", &self.proxy_id)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StartupTimer {
+    /// End `phase` now; the time since the previous mark is attributed to it
+    pub fn mark(&self, phase: StartupPhase) {
+        let mut state = self.lock();
+        if state.finished {
+            return;
+        }
+        let now = Instant::now();
+        let elapsed = now - state.last_mark;
+        state.last_mark = now;
+        match state.phases.iter_mut().find(|(p, _)| *p == phase) {
+            Some((_, duration)) => *duration += elapsed,
+            None => state.phases.push((phase, elapsed)),
+        }
+    }
+
+    /// Record the first byte sent to the client; only the first call counts
+    pub fn first_byte(&self, session: &SessionStats) {
+        self.mark(StartupPhase::BufferFill);
+        let latency = {
+            let mut state = self.lock();
+            if state.finished {
+                return;
+            }
+            state.finished = true;
+            StartupLatency {
+                total: state.last_mark - state.started,
+                phases: std::mem::take(&mut state.phases),
+            }
+        };
+        self.monitor
+            .record(self.proxy_id, &self.proxy_name, &latency, Some(session));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TimerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Latency percentiles in milliseconds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct LatencyPercentiles {
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencyPercentiles {
+    fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        // Nearest-rank percentile
+        let rank = |p: usize| {
+            let index = (p * samples.len()).div_ceil(100).max(1) - 1;
+            samples[index].as_millis() as u64
+        };
+        Some(Self {
+            p50_ms: rank(50),
+            p90_ms: rank(90),
+            p95_ms: rank(95),
+            p99_ms: rank(99),
+            max_ms: samples[samples.len() - 1].as_millis() as u64,
+        })
+    }
+}
+
+/// Start-up latency of a proxy's recent stream sessions
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProxyStartupLatency {
+    pub proxy_id: Uuid,
+    pub proxy_name: String,
+    /// Sessions measured since startup
+    pub sessions: u64,
+    /// Sessions whose first byte took longer than the budget
+    pub over_budget: u64,
+    /// Recent sessions the percentiles are computed from
+    pub samples: usize,
+    /// Time to first byte
+    pub total: LatencyPercentiles,
+    /// Per phase ("setup", "upstream_connect", "relay_spawn", "buffer_fill"), over the
+    /// samples that went through it
+    pub phases: BTreeMap<String, LatencyPercentiles>,
+}
+
+/// Stream start latency across proxies
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StreamLatencyReport {
+    pub budget_ms: u64,
+    /// Slowest proxies (by median time to first byte) first
+    pub proxies: Vec<ProxyStartupLatency>,
+}
+
+struct ProxySamples {
+    proxy_name: String,
+    recent: VecDeque<StartupLatency>,
+    sessions: u64,
+    over_budget: u64,
+}
+
+/// Collects stream start latencies per proxy
+pub struct StreamLatencyMonitor {
+    budget: Duration,
+    sample_size: usize,
+    proxies: Mutex<HashMap<Uuid, ProxySamples>>,
+    observability: Option<Arc<AppObservability>>,
+}
+
+impl StreamLatencyMonitor {
+    pub fn new(config: &StreamLatencyConfig) -> Self {
+        Self {
+            budget: config.budget_duration(),
+            sample_size: config.sample_size.max(1),
+            proxies: Mutex::new(HashMap::new()),
+            observability: None,
+        }
+    }
+
+    /// Record latencies in the `stream_startup_duration_seconds` metrics
+    pub fn with_observability(mut self, observability: Arc<AppObservability>) -> Self {
+        self.observability = Some(observability);
+        self
+    }
+
+    /// Start timing a stream request that arrived at `started`
+    pub fn start_timer(
+        self: &Arc<Self>,
+        started: Instant,
+        proxy_id: Uuid,
+        proxy_name: &str,
+    ) -> StartupTimer {
+        StartupTimer {
+            proxy_id,
+            proxy_name: proxy_name.to_string(),
+            state: Arc::new(Mutex::new(TimerState {
+                started,
+                last_mark: started,
+                phases: Vec::new(),
+                finished: false,
+            })),
+            monitor: self.clone(),
+        }
+    }
+
+    fn record(
+        &self,
+        proxy_id: Uuid,
+        proxy_name: &str,
+        latency: &StartupLatency,
+        session: Option<&SessionStats>,
+    ) {
+        let over_budget = latency.total > self.budget;
+        {
+            let mut proxies = self.lock();
+            let samples = proxies.entry(proxy_id).or_insert_with(|| ProxySamples {
+                proxy_name: proxy_name.to_string(),
+                recent: VecDeque::with_capacity(self.sample_size),
+                sessions: 0,
+                over_budget: 0,
+            });
+            samples.proxy_name = proxy_name.to_string();
+            if samples.recent.len() == self.sample_size {
+                samples.recent.pop_front();
+            }
+            samples.recent.push_back(latency.clone());
+            samples.sessions += 1;
+            samples.over_budget += u64::from(over_budget);
+        }
+
+        if let Some(observability) = &self.observability {
+            let proxy = KeyValue::new("proxy_name", proxy_name.to_string());
+            observability.stream_startup_duration.record(
+                latency.total.as_secs_f64(),
+                &[proxy.clone(), KeyValue::new("phase", "total")],
+            );
+            for (phase, duration) in &latency.phases {
+                observability.stream_startup_duration.record(
+                    duration.as_secs_f64(),
+                    &[proxy.clone(), KeyValue::new("phase", phase.as_str())],
+                );
+            }
+            if over_budget {
+                observability.stream_startup_over_budget.add(1, &[proxy]);
+            }
+        }
+
+        let session_id = session.map_or("-", |s| s.session_id.as_str());
+        let channel = session.map_or("-", |s| s.channel_name.as_str());
+        if over_budget {
+            let upstream_host = session
+                .and_then(|s| url::Url::parse(&s.upstream_url).ok())
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_else(|| "-".to_string());
+            warn!(
+                "session_id={} event=slow_stream_start proxy_name=\"{}\" channel_name=\"{}\" source_id={} upstream_host={} ttfb={} budget={} slowest_phase={} {}",
+                session_id,
+                proxy_name,
+                channel,
+                session
+                    .and_then(|s| s.source_id)
+                    .map_or_else(|| "-".to_string(), |id| id.to_string()),
+                upstream_host,
+                format_duration(latency.total),
+                format_duration(self.budget),
+                latency.slowest_phase().map_or("-", StartupPhase::as_str),
+                latency.breakdown()
+            );
+        } else {
+            debug!(
+                "session_id={} event=stream_start proxy_name=\"{}\" channel_name=\"{}\" ttfb={} {}",
+                session_id,
+                proxy_name,
+                channel,
+                format_duration(latency.total),
+                latency.breakdown()
+            );
+        }
+    }
+
+    /// Percentiles of recent sessions, per proxy
+    pub fn report(&self) -> StreamLatencyReport {
+        let mut proxies: Vec<ProxyStartupLatency> = self
+            .lock()
+            .iter()
+            .filter_map(|(proxy_id, samples)| {
+                let total = LatencyPercentiles::from_samples(
+                    samples.recent.iter().map(|latency| latency.total).collect(),
+                )?;
+                let mut phases = BTreeMap::new();
+                for phase in [
+                    StartupPhase::Setup,
+                    StartupPhase::UpstreamConnect,
+                    StartupPhase::RelaySpawn,
+                    StartupPhase::BufferFill,
+                ] {
+                    let durations = samples
+                        .recent
+                        .iter()
+                        .filter_map(|latency| latency.phase(phase))
+                        .collect();
+                    if let Some(percentiles) = LatencyPercentiles::from_samples(durations) {
+                        phases.insert(phase.as_str().to_string(), percentiles);
+                    }
+                }
+                Some(ProxyStartupLatency {
+                    proxy_id: *proxy_id,
+                    proxy_name: samples.proxy_name.clone(),
+                    sessions: samples.sessions,
+                    over_budget: samples.over_budget,
+                    samples: samples.recent.len(),
+                    total,
+                    phases,
+                })
+            })
+            .collect();
+        proxies.sort_by(|a, b| {
+            b.total
+                .p50_ms
+                .cmp(&a.total.p50_ms)
+                .then_with(|| a.proxy_name.cmp(&b.proxy_name))
+        });
+
+        StreamLatencyReport {
+            budget_ms: self.budget.as_millis() as u64,
+            proxies,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, ProxySamples>> {
+        self.proxies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> Arc<StreamLatencyMonitor> {
+        Arc::new(StreamLatencyMonitor::new(&StreamLatencyConfig {
+            budget: "1s".to_string(),
+            sample_size: 3,
+        }))
+    }
+
+    fn latency(total_ms: u64, connect_ms: u64) -> StartupLatency {
+        StartupLatency {
+            total: Duration::from_millis(total_ms),
+            phases: vec![
+                (
+                    StartupPhase::Setup,
+                    Duration::from_millis(total_ms - connect_ms),
+                ),
+                (
+                    StartupPhase::UpstreamConnect,
+                    Duration::from_millis(connect_ms),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let samples = (1..=100).map(Duration::from_millis).collect();
+        let percentiles = LatencyPercentiles::from_samples(samples).unwrap();
+        assert_eq!(
+            percentiles,
+            LatencyPercentiles {
+                p50_ms: 50,
+                p90_ms: 90,
+                p95_ms: 95,
+                p99_ms: 99,
+                max_ms: 100,
+            }
+        );
+        let single = LatencyPercentiles::from_samples(vec![Duration::from_millis(7)]).unwrap();
+        assert_eq!((single.p50_ms, single.p99_ms), (7, 7));
+        assert_eq!(LatencyPercentiles::from_samples(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_report_keeps_recent_samples_per_proxy() {
+        let monitor = monitor();
+        let slow = Uuid::new_v4();
+        let fast = Uuid::new_v4();
+        for total in [4000, 200, 300, 2500] {
+            monitor.record(slow, "Slow", &latency(total, total / 2), None);
+        }
+        monitor.record(fast, "Fast", &latency(100, 20), None);
+
+        let report = monitor.report();
+        assert_eq!(report.budget_ms, 1000);
+        let names: Vec<_> = report
+            .proxies
+            .iter()
+            .map(|p| p.proxy_name.as_str())
+            .collect();
+        assert_eq!(names, ["Slow", "Fast"]);
+
+        let slow = &report.proxies[0];
+        // The oldest sample was dropped, but still counts towards the totals
+        assert_eq!((slow.sessions, slow.over_budget, slow.samples), (4, 2, 3));
+        assert_eq!(slow.total.max_ms, 2500);
+        assert_eq!(slow.phases["upstream_connect"].p50_ms, 150);
+        assert!(!slow.phases.contains_key("relay_spawn"));
+    }
+
+    #[test]
+    fn test_timer_phases_add_up() {
+        let monitor = monitor();
+        let proxy_id = Uuid::new_v4();
+        let started = Instant::now() - Duration::from_millis(50);
+        let timer = monitor.start_timer(started, proxy_id, "Living Room");
+        timer.mark(StartupPhase::Setup);
+        timer.mark(StartupPhase::UpstreamConnect);
+        timer.mark(StartupPhase::UpstreamConnect);
+
+        let session = SessionStats::new(
+            "session".to_string(),
+            crate::proxy::session_tracker::ClientInfo {
+                ip: "127.0.0.1".to_string(),
+                user_agent: None,
+                referer: None,
+            },
+            "Living Room".to_string(),
+            "Living Room".to_string(),
+            Uuid::new_v4().to_string(),
+            "BBC One".to_string(),
+            "http://provider.example/live/1.ts".to_string(),
+        );
+        timer.first_byte(&session);
+        // Later chunks do not count
+        timer.first_byte(&session);
+
+        let report = monitor.report();
+        let proxy = &report.proxies[0];
+        assert_eq!(proxy.sessions, 1);
+        assert!(proxy.total.max_ms >= 50);
+        assert_eq!(
+            proxy.phases.keys().map(String::as_str).collect::<Vec<_>>(),
+            ["buffer_fill", "setup", "upstream_connect"]
+        );
+    }
+}
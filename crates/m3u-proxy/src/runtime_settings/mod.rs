@@ -4,6 +4,8 @@
 //! changed without restarting the service. Settings changes are applied immediately
 //! and affect the actual server behavior.
 
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -48,6 +50,18 @@ pub struct RuntimeFlags {
     pub feature_flags: HashMap<String, bool>,
     /// Runtime feature configuration
     pub feature_config: HashMap<String, HashMap<String, serde_json::Value>>,
+    /// Set while the server is in maintenance mode
+    pub maintenance: Option<MaintenanceState>,
+}
+
+/// Active maintenance window
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct MaintenanceState {
+    pub since: DateTime<Utc>,
+    /// Shown to clients in the 503 response
+    pub message: Option<String>,
+    /// Sent as `Retry-After` on rejected requests
+    pub retry_after_seconds: u64,
 }
 
 impl RuntimeSettingsStore {
@@ -184,6 +198,25 @@ impl RuntimeSettingsStore {
         applied_changes
     }
 
+    /// Enter (`Some`) or leave (`None`) maintenance mode (temporary, not persisted)
+    pub async fn set_maintenance(&self, maintenance: Option<MaintenanceState>) {
+        let mut flags = self.runtime_flags.write().await;
+        match (&flags.maintenance, &maintenance) {
+            (None, Some(state)) => info!(
+                "Maintenance mode enabled (retry after {}s)",
+                state.retry_after_seconds
+            ),
+            (Some(_), None) => info!("Maintenance mode disabled"),
+            _ => {}
+        }
+        flags.maintenance = maintenance;
+    }
+
+    /// Current maintenance window, if any
+    pub async fn maintenance(&self) -> Option<MaintenanceState> {
+        self.runtime_flags.read().await.maintenance.clone()
+    }
+
     /// Update feature flags and configuration (temporary, not persisted)
     pub async fn update_feature_flags(
         &self,
@@ -0,0 +1,443 @@
+//! Authentication of the admin API
+//!
+//! Requests prove who they are with one of:
+//! - a static API token from the config, as `Authorization: Bearer <token>`
+//! - an access token issued by the OpenID Connect provider, also as a bearer token
+//! - the session cookie set after an OpenID Connect login in the browser
+//!
+//! Session cookies and the short-lived login state cookie are `<payload>.<signature>`: the
+//! payload is the URL-safe base64 of a JSON document and the signature its hex
+//! HMAC-SHA256, so no server-side session store is needed.
+
+use axum::http::{HeaderMap, header};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+
+use super::oidc::OidcClient;
+use crate::config::{AuthConfig, AuthRole};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Cookie holding the login session
+pub const SESSION_COOKIE: &str = "m3u_proxy_session";
+
+/// Cookie carrying the state of a login in progress
+pub const LOGIN_STATE_COOKIE: &str = "m3u_proxy_oidc";
+
+/// How long a user has to complete a login at the provider
+const LOGIN_STATE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// How a request was authenticated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    ApiToken,
+    Session,
+    BearerToken,
+}
+
+/// The authenticated user or token behind a request
+///
+/// Inserted into request extensions by the admin auth middleware.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Principal {
+    pub name: String,
+    pub role: AuthRole,
+    pub method: AuthMethod,
+}
+
+impl Principal {
+    /// Whether the principal may make a request with `method`
+    pub fn may(&self, method: &axum::http::Method) -> bool {
+        self.role == AuthRole::Admin
+            || matches!(*method, axum::http::Method::GET | axum::http::Method::HEAD)
+    }
+}
+
+/// Why a request was not authenticated
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AuthError {
+    #[error("authentication required")]
+    Missing,
+    #[error("invalid or expired credentials")]
+    Invalid,
+    #[error("no role granted to this user")]
+    NoRole,
+}
+
+/// Signed session cookie contents
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionClaims {
+    name: String,
+    role: AuthRole,
+    exp: i64,
+}
+
+/// Signed login state cookie contents, checked on the provider's callback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginState {
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub redirect_url: String,
+    pub return_to: String,
+    exp: i64,
+}
+
+struct ApiToken {
+    name: String,
+    digest: [u8; 32],
+    role: AuthRole,
+}
+
+/// Authenticates admin API requests
+pub struct AdminAuth {
+    enabled: bool,
+    api_tokens: Vec<ApiToken>,
+    key: Vec<u8>,
+    session_ttl: Duration,
+    secure_cookies: bool,
+    oidc: Option<Arc<OidcClient>>,
+}
+
+impl AdminAuth {
+    /// Authentication from config; `secure_cookies` marks cookies HTTPS-only
+    pub fn from_config(config: Option<&AuthConfig>, secure_cookies: bool) -> Self {
+        let default_config = AuthConfig::default();
+        let config = config.unwrap_or(&default_config);
+
+        let key = match config.session_secret.as_deref().filter(|s| !s.is_empty()) {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => {
+                static GENERATED_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+                GENERATED_KEY.get_or_init(rand::random).to_vec()
+            }
+        };
+
+        let api_tokens = config
+            .api_tokens
+            .iter()
+            .filter_map(|token| {
+                if token.token.len() < 16 {
+                    warn!(
+                        "Ignoring API token '{}': tokens must be at least 16 characters",
+                        token.name
+                    );
+                    return None;
+                }
+                Some(ApiToken {
+                    name: token.name.clone(),
+                    digest: Sha256::digest(token.token.as_bytes()).into(),
+                    role: token.role,
+                })
+            })
+            .collect();
+
+        let oidc = config
+            .oidc
+            .clone()
+            .and_then(|oidc| match OidcClient::new(oidc) {
+                Ok(client) => Some(Arc::new(client)),
+                Err(e) => {
+                    warn!("OpenID Connect login disabled: {:#}", e);
+                    None
+                }
+            });
+
+        Self {
+            enabled: config.enabled,
+            api_tokens,
+            key,
+            session_ttl: config.session_ttl_duration(),
+            secure_cookies,
+            oidc,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn oidc(&self) -> Option<&Arc<OidcClient>> {
+        self.oidc.as_ref()
+    }
+
+    /// Identify the caller from its bearer token or session cookie
+    pub async fn authenticate(&self, headers: &HeaderMap) -> Result<Principal, AuthError> {
+        if let Some(token) = bearer_token(headers) {
+            return self.authenticate_bearer(token).await;
+        }
+        let cookie = cookie_value(headers, SESSION_COOKIE).ok_or(AuthError::Missing)?;
+        let session: SessionClaims = self.verify(cookie).ok_or(AuthError::Invalid)?;
+        Ok(Principal {
+            name: session.name,
+            role: session.role,
+            method: AuthMethod::Session,
+        })
+    }
+
+    async fn authenticate_bearer(&self, token: &str) -> Result<Principal, AuthError> {
+        let digest: [u8; 32] = Sha256::digest(token.as_bytes()).into();
+        if let Some(api_token) = self.api_tokens.iter().find(|t| t.digest == digest) {
+            return Ok(Principal {
+                name: api_token.name.clone(),
+                role: api_token.role,
+                method: AuthMethod::ApiToken,
+            });
+        }
+
+        // Anything else shaped like a JWT may be an access token from the provider
+        let Some(oidc) = self
+            .oidc
+            .as_ref()
+            .filter(|_| token.matches('.').count() == 2)
+        else {
+            return Err(AuthError::Invalid);
+        };
+        let claims = oidc.validate_access_token(token).await.map_err(|e| {
+            debug!("Rejected bearer token: {:#}", e);
+            AuthError::Invalid
+        })?;
+        let (name, role) = oidc.principal_of(&claims).ok_or(AuthError::NoRole)?;
+        Ok(Principal {
+            name,
+            role,
+            method: AuthMethod::BearerToken,
+        })
+    }
+
+    /// `Set-Cookie` value starting a session for a logged-in user
+    pub fn session_cookie(&self, name: &str, role: AuthRole) -> String {
+        let token = self.sign(&SessionClaims {
+            name: name.to_string(),
+            role,
+            exp: expiry(self.session_ttl),
+        });
+        self.cookie(SESSION_COOKIE, &token, self.session_ttl)
+    }
+
+    /// `Set-Cookie` value ending the session
+    pub fn clear_session_cookie(&self) -> String {
+        self.cookie(SESSION_COOKIE, "", Duration::ZERO)
+    }
+
+    /// `Set-Cookie` value carrying a login in progress
+    pub fn login_state_cookie(
+        &self,
+        state: String,
+        nonce: String,
+        code_verifier: String,
+        redirect_url: String,
+        return_to: String,
+    ) -> String {
+        let token = self.sign(&LoginState {
+            state,
+            nonce,
+            code_verifier,
+            redirect_url,
+            return_to,
+            exp: expiry(LOGIN_STATE_TTL),
+        });
+        self.cookie(LOGIN_STATE_COOKIE, &token, LOGIN_STATE_TTL)
+    }
+
+    pub fn clear_login_state_cookie(&self) -> String {
+        self.cookie(LOGIN_STATE_COOKIE, "", Duration::ZERO)
+    }
+
+    /// The login in progress, if its cookie is intact and unexpired
+    pub fn login_state(&self, headers: &HeaderMap) -> Option<LoginState> {
+        self.verify(cookie_value(headers, LOGIN_STATE_COOKIE)?)
+    }
+
+    fn cookie(&self, name: &str, value: &str, max_age: Duration) -> String {
+        // Lax rather than Strict: the provider's redirect back to the callback is a
+        // cross-site navigation that must carry the login state cookie
+        let mut cookie = format!(
+            "{name}={value}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax",
+            max_age.as_secs()
+        );
+        if self.secure_cookies {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+
+    fn sign<T: Serialize>(&self, claims: &T) -> String {
+        let json = serde_json::to_vec(claims).expect("claims serialize to JSON");
+        let payload = URL_SAFE_NO_PAD.encode(json);
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+        format!("{payload}.{signature}")
+    }
+
+    /// Decode a signed token, rejecting bad signatures and expired tokens
+    fn verify<T: DeserializeOwned>(&self, token: &str) -> Option<T> {
+        let (payload, signature) = token.split_once('.')?;
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&hex::decode(signature).ok()?).ok()?;
+
+        let value: serde_json::Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+        let exp = value.get("exp")?.as_i64()?;
+        if Utc::now().timestamp() > exp {
+            return None;
+        }
+        serde_json::from_value(value).ok()
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length")
+    }
+}
+
+/// A local path to return to after login; anything else could redirect off-site
+pub fn safe_return_to(return_to: Option<&str>) -> String {
+    match return_to {
+        Some(path) if path.starts_with('/') && !path.starts_with("//") && !path.contains('\\') => {
+            path.to_string()
+        }
+        _ => "/".to_string(),
+    }
+}
+
+fn expiry(ttl: Duration) -> i64 {
+    Utc::now().timestamp() + ttl.as_secs() as i64
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ApiTokenConfig;
+
+    fn auth() -> AdminAuth {
+        AdminAuth::from_config(
+            Some(&AuthConfig {
+                enabled: true,
+                api_tokens: vec![
+                    ApiTokenConfig {
+                        name: "backup-script".to_string(),
+                        token: "0123456789abcdef-viewer".to_string(),
+                        role: AuthRole::Viewer,
+                    },
+                    ApiTokenConfig {
+                        name: "too-short".to_string(),
+                        token: "short".to_string(),
+                        role: AuthRole::Admin,
+                    },
+                ],
+                session_secret: Some("test-secret".to_string()),
+                session_ttl: "1h".to_string(),
+                oidc: None,
+            }),
+            true,
+        )
+    }
+
+    fn headers(name: header::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, value.parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_api_tokens() {
+        let auth = auth();
+        let principal = auth
+            .authenticate(&headers(
+                header::AUTHORIZATION,
+                "Bearer 0123456789abcdef-viewer",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(principal.name, "backup-script");
+        assert_eq!(principal.method, AuthMethod::ApiToken);
+        assert!(principal.may(&axum::http::Method::GET));
+        assert!(!principal.may(&axum::http::Method::DELETE));
+
+        let short = auth
+            .authenticate(&headers(header::AUTHORIZATION, "Bearer short"))
+            .await;
+        assert_eq!(short, Err(AuthError::Invalid));
+        assert_eq!(
+            auth.authenticate(&HeaderMap::new()).await,
+            Err(AuthError::Missing)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_cookie_round_trip() {
+        let auth = auth();
+        let set_cookie = auth.session_cookie("alex", AuthRole::Admin);
+        assert!(set_cookie.ends_with("; Secure"));
+        let cookie = set_cookie.split(';').next().unwrap();
+
+        let principal = auth
+            .authenticate(&headers(header::COOKIE, &format!("theme=dark; {cookie}")))
+            .await
+            .unwrap();
+        assert_eq!(principal.name, "alex");
+        assert_eq!(principal.role, AuthRole::Admin);
+
+        // Tampering with the payload breaks the signature
+        let (payload, signature) = cookie.split_once('.').unwrap();
+        let forged = format!("{payload}x.{signature}");
+        assert_eq!(
+            auth.authenticate(&headers(header::COOKIE, &forged)).await,
+            Err(AuthError::Invalid)
+        );
+
+        let expired = auth.sign(&SessionClaims {
+            name: "alex".to_string(),
+            role: AuthRole::Admin,
+            exp: Utc::now().timestamp() - 1,
+        });
+        assert_eq!(
+            auth.authenticate(&headers(
+                header::COOKIE,
+                &format!("{SESSION_COOKIE}={expired}")
+            ))
+            .await,
+            Err(AuthError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_safe_return_to() {
+        assert_eq!(safe_return_to(Some("/proxies?page=2")), "/proxies?page=2");
+        assert_eq!(safe_return_to(Some("//evil.example")), "/");
+        assert_eq!(safe_return_to(Some("/\\evil.example")), "/");
+        assert_eq!(safe_return_to(Some("https://evil.example")), "/");
+        assert_eq!(safe_return_to(None), "/");
+    }
+}
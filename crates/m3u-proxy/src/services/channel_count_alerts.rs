@@ -0,0 +1,204 @@
+//! Rate-of-change alerts on channel counts
+//!
+//! Each successful stream source ingestion and proxy generation records its channel count
+//! and compares it with the previous one. A change beyond the configured threshold is
+//! published as an MQTT event and stays flagged on the source or proxy until a later count
+//! is back within the threshold.
+
+use chrono::Utc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::config::ChannelCountAlertConfig;
+use crate::database::repositories::ChannelCountSnapshotSeaOrmRepository;
+use crate::models::channel_count_alert::{
+    ChannelCountAlert, ChannelCountSnapshot, ChannelCountSubject,
+};
+use crate::services::mqtt_publisher::{AutomationEvent, MqttPublisher};
+
+/// Signed percentage change from `previous` to `current` when it crosses the threshold
+///
+/// Only drops are considered when `drops_only` is set. A threshold of 0 disables the
+/// check, and previous counts below `min_channels` never alert.
+pub fn alerting_change(
+    previous: i64,
+    current: i64,
+    threshold_percent: f64,
+    drops_only: bool,
+    min_channels: u64,
+) -> Option<f64> {
+    if threshold_percent <= 0.0 || previous <= 0 || (previous as u64) < min_channels {
+        return None;
+    }
+    let change_percent = (current - previous) as f64 * 100.0 / previous as f64;
+    if drops_only && change_percent >= 0.0 {
+        return None;
+    }
+    (change_percent.abs() > threshold_percent).then_some(change_percent)
+}
+
+/// Records channel counts and raises alerts on sharp changes; cheap to clone
+#[derive(Clone)]
+pub struct ChannelCountAlertService {
+    repository: ChannelCountSnapshotSeaOrmRepository,
+    config: ChannelCountAlertConfig,
+    mqtt_publisher: MqttPublisher,
+}
+
+impl ChannelCountAlertService {
+    pub fn new(
+        repository: ChannelCountSnapshotSeaOrmRepository,
+        config: ChannelCountAlertConfig,
+        mqtt_publisher: MqttPublisher,
+    ) -> Self {
+        Self {
+            repository,
+            config,
+            mqtt_publisher,
+        }
+    }
+
+    /// Record the channel count of a successful stream source ingestion
+    pub async fn check_source(
+        &self,
+        source_id: Uuid,
+        source_name: &str,
+        channel_count: usize,
+    ) -> Option<ChannelCountAlert> {
+        let alert = self
+            .record(
+                source_id,
+                ChannelCountSubject::StreamSource,
+                channel_count,
+                self.config.source_drop_percent,
+                true,
+            )
+            .await?;
+        warn!(
+            "Stream source '{}' channel count dropped from {} to {} ({:.1}%)",
+            source_name, alert.previous_count, alert.current_count, alert.change_percent
+        );
+        self.mqtt_publisher
+            .publish(AutomationEvent::SourceChannelCountDropped {
+                source_id: source_id.to_string(),
+                source_name: source_name.to_string(),
+                previous_count: alert.previous_count,
+                current_count: alert.current_count,
+                change_percent: alert.change_percent,
+            });
+        Some(alert)
+    }
+
+    /// Record the channel count of a successful proxy generation
+    pub async fn check_proxy(
+        &self,
+        proxy_id: Uuid,
+        proxy_name: &str,
+        channel_count: usize,
+    ) -> Option<ChannelCountAlert> {
+        let alert = self
+            .record(
+                proxy_id,
+                ChannelCountSubject::StreamProxy,
+                channel_count,
+                self.config.proxy_change_percent,
+                false,
+            )
+            .await?;
+        warn!(
+            "Proxy '{}' channel count changed from {} to {} ({:+.1}%)",
+            proxy_name, alert.previous_count, alert.current_count, alert.change_percent
+        );
+        self.mqtt_publisher
+            .publish(AutomationEvent::ProxyChannelCountChanged {
+                proxy_id: proxy_id.to_string(),
+                proxy_name: proxy_name.to_string(),
+                previous_count: alert.previous_count,
+                current_count: alert.current_count,
+                change_percent: alert.change_percent,
+            });
+        Some(alert)
+    }
+
+    /// Store the new count, returning the alert it raised
+    ///
+    /// Failures are logged rather than returned: a missed comparison must not fail the
+    /// ingestion or generation that produced the count.
+    async fn record(
+        &self,
+        subject_id: Uuid,
+        subject: ChannelCountSubject,
+        channel_count: usize,
+        threshold_percent: f64,
+        drops_only: bool,
+    ) -> Option<ChannelCountAlert> {
+        if !self.config.enabled {
+            return None;
+        }
+        let current_count = i64::try_from(channel_count).unwrap_or(i64::MAX);
+        let previous = match self.repository.get(&subject_id).await {
+            Ok(previous) => previous,
+            Err(e) => {
+                warn!("Failed to load channel count of {}: {}", subject_id, e);
+                return None;
+            }
+        };
+
+        let now = Utc::now();
+        let alert = previous.and_then(|previous| {
+            alerting_change(
+                previous.channel_count,
+                current_count,
+                threshold_percent,
+                drops_only,
+                self.config.min_channels,
+            )
+            .map(|change_percent| ChannelCountAlert {
+                previous_count: previous.channel_count,
+                current_count,
+                change_percent,
+                triggered_at: now,
+            })
+        });
+
+        let snapshot = ChannelCountSnapshot {
+            subject_id,
+            subject,
+            channel_count: current_count,
+            recorded_at: now,
+            alert: alert.clone(),
+        };
+        if let Err(e) = self.repository.save(&snapshot).await {
+            warn!("Failed to record channel count of {}: {}", subject_id, e);
+        } else {
+            debug!("Recorded {} channels for {}", current_count, subject_id);
+        }
+        alert
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_beyond_threshold_alerts() {
+        assert_eq!(alerting_change(100, 70, 20.0, true, 10), Some(-30.0));
+        assert_eq!(alerting_change(100, 85, 20.0, true, 10), None);
+        assert_eq!(alerting_change(100, 0, 20.0, true, 10), Some(-100.0));
+    }
+
+    #[test]
+    fn test_growth_only_alerts_when_not_drops_only() {
+        assert_eq!(alerting_change(100, 200, 20.0, true, 10), None);
+        assert_eq!(alerting_change(100, 150, 25.0, false, 10), Some(50.0));
+        assert_eq!(alerting_change(100, 60, 25.0, false, 10), Some(-40.0));
+    }
+
+    #[test]
+    fn test_small_counts_and_disabled_threshold_never_alert() {
+        assert_eq!(alerting_change(5, 0, 20.0, true, 10), None);
+        assert_eq!(alerting_change(0, 50, 20.0, false, 0), None);
+        assert_eq!(alerting_change(100, 10, 0.0, true, 10), None);
+    }
+}
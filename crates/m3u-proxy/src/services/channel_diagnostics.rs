@@ -0,0 +1,342 @@
+//! On-demand channel diagnostics
+//!
+//! Combines an ffprobe codec probe (persisted through [`ProbePersistenceService`]) with a short
+//! upstream sample measuring response latency and bitrate. The sample goes through the shared
+//! HTTP client factory's circuit breaker and carries the source's streaming headers, like the
+//! connections made for viewers. Reports are cached per stream URL for the configured TTL so
+//! repeated diagnostics do not hammer providers.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+
+use crate::config::ChannelProbeConfig;
+use crate::models::last_known_codec::{LastKnownCodec, ProbeMethod};
+use crate::models::stream_headers::StreamHeaderOverrides;
+use crate::proxy::http_stream::stream_header_override_map;
+use crate::services::ProbePersistenceService;
+use crate::services::stream_prober::{ProbeResult, StreamInfo};
+use crate::utils::HttpClientFactory;
+
+/// Circuit breaker profile of upstream samples
+const SAMPLE_SERVICE_NAME: &str = "channel_probe";
+
+/// A single elementary stream (track) detected by ffprobe
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProbeTrack {
+    pub index: u32,
+    /// Position among tracks of the same type; the value relay profiles use to force a track
+    pub track_index: u32,
+    pub codec_name: String,
+    pub language: Option<String>,
+    pub bit_rate: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub channels: Option<u32>,
+    pub channel_layout: Option<String>,
+}
+
+impl From<&StreamInfo> for ProbeTrack {
+    fn from(stream: &StreamInfo) -> Self {
+        Self {
+            index: stream.index,
+            track_index: 0,
+            codec_name: stream.codec_name.clone(),
+            language: stream.language.clone(),
+            bit_rate: stream.bit_rate,
+            width: stream.width,
+            height: stream.height,
+            channels: stream.channels,
+            channel_layout: stream.channel_layout.clone(),
+        }
+    }
+}
+
+/// Result of reading the upstream stream for a short window
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UpstreamSample {
+    /// HTTP status returned by the provider (absent when the request failed)
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    /// Time until the provider returned response headers
+    pub latency_ms: Option<u64>,
+    /// Time until the first body byte arrived
+    pub first_byte_ms: Option<u64>,
+    pub sampled_bytes: u64,
+    pub sample_duration_ms: u64,
+    /// Average bitrate over the sample window in bits per second
+    pub sampled_bitrate: Option<u64>,
+    /// Why sampling stopped early or failed
+    pub error: Option<String>,
+}
+
+/// On-demand diagnostic report for a channel stream
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ChannelProbeReport {
+    /// Persisted codec summary (same shape as the stored last-known codec record)
+    #[serde(flatten)]
+    pub codec: LastKnownCodec,
+    pub video_tracks: Vec<ProbeTrack>,
+    pub audio_tracks: Vec<ProbeTrack>,
+    pub subtitle_tracks: Vec<ProbeTrack>,
+    pub has_multiple_audio_tracks: bool,
+    pub has_subtitles: bool,
+    pub upstream: UpstreamSample,
+    /// Whether this report was served from cache
+    pub cached: bool,
+    pub probed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ChannelProbeReport {
+    fn new(
+        codec: LastKnownCodec,
+        probe: &ProbeResult,
+        upstream: UpstreamSample,
+        ttl: Duration,
+    ) -> Self {
+        let tracks_of = |codec_type: &str| -> Vec<ProbeTrack> {
+            probe
+                .streams
+                .iter()
+                .filter(|s| s.codec_type == codec_type)
+                .enumerate()
+                .map(|(position, stream)| ProbeTrack {
+                    track_index: position as u32,
+                    ..ProbeTrack::from(stream)
+                })
+                .collect()
+        };
+        let video_tracks = tracks_of("video");
+        let audio_tracks = tracks_of("audio");
+        let subtitle_tracks = tracks_of("subtitle");
+        let probed_at = Utc::now();
+
+        Self {
+            codec,
+            has_multiple_audio_tracks: audio_tracks.len() > 1,
+            has_subtitles: !subtitle_tracks.is_empty(),
+            video_tracks,
+            audio_tracks,
+            subtitle_tracks,
+            upstream,
+            cached: false,
+            probed_at,
+            expires_at: probed_at + chrono::Duration::from_std(ttl).unwrap_or_default(),
+        }
+    }
+}
+
+struct CachedReport {
+    report: ChannelProbeReport,
+    stored_at: Instant,
+}
+
+/// Runs and caches channel diagnostics
+pub struct ChannelDiagnosticsService {
+    persistence: Arc<ProbePersistenceService>,
+    http_client_factory: HttpClientFactory,
+    config: ChannelProbeConfig,
+    cache: RwLock<HashMap<String, CachedReport>>,
+}
+
+impl ChannelDiagnosticsService {
+    pub fn new(
+        persistence: Arc<ProbePersistenceService>,
+        http_client_factory: HttpClientFactory,
+        config: ChannelProbeConfig,
+    ) -> Self {
+        Self {
+            persistence,
+            http_client_factory,
+            config,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Diagnose a stream, serving a cached report while it is younger than the TTL
+    ///
+    /// `header_overrides` are the streaming headers of the stream's source. `refresh` bypasses
+    /// (and replaces) any cached report.
+    pub async fn diagnose(
+        &self,
+        stream_url: &str,
+        header_overrides: Option<&StreamHeaderOverrides>,
+        refresh: bool,
+    ) -> Result<ChannelProbeReport> {
+        let ttl = self.config.cache_ttl_duration();
+
+        if !refresh {
+            let cache = self.cache.read().await;
+            if let Some(entry) = cache.get(stream_url)
+                && entry.stored_at.elapsed() < ttl
+            {
+                debug!(%stream_url, "Serving cached channel probe report");
+                let mut report = entry.report.clone();
+                report.cached = true;
+                return Ok(report);
+            }
+        }
+
+        // Sample before probing so providers limiting concurrent connections see one at a time
+        let upstream = self.sample_upstream(stream_url, header_overrides).await;
+        let (codec, probe) = self
+            .persistence
+            .probe_and_persist_detailed(
+                stream_url,
+                ProbeMethod::FfprobeManual,
+                Some("admin".to_string()),
+            )
+            .await?;
+        let report = ChannelProbeReport::new(codec, &probe, upstream, ttl);
+
+        let mut cache = self.cache.write().await;
+        cache.retain(|_, entry| entry.stored_at.elapsed() < ttl);
+        cache.insert(
+            stream_url.to_string(),
+            CachedReport {
+                report: report.clone(),
+                stored_at: Instant::now(),
+            },
+        );
+
+        Ok(report)
+    }
+
+    /// Measure upstream latency and bitrate by reading the stream for the sample window
+    async fn sample_upstream(
+        &self,
+        stream_url: &str,
+        header_overrides: Option<&StreamHeaderOverrides>,
+    ) -> UpstreamSample {
+        let window = self.config.sample_window_duration();
+        let max_bytes = self.config.max_sample_bytes;
+        let started = Instant::now();
+
+        let mut sample = UpstreamSample {
+            status: None,
+            content_type: None,
+            latency_ms: None,
+            first_byte_ms: None,
+            sampled_bytes: 0,
+            sample_duration_ms: 0,
+            sampled_bitrate: None,
+            error: None,
+        };
+
+        let http_client = self
+            .http_client_factory
+            .create_client_for_service(SAMPLE_SERVICE_NAME)
+            .await;
+        let mut response = match tokio::time::timeout(
+            window.max(Duration::from_secs(10)),
+            http_client.get_response(stream_url, stream_header_override_map(header_overrides)),
+        )
+        .await
+        {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) => {
+                warn!(%stream_url, error = %e, "Upstream sample request failed");
+                sample.error = Some(e.to_string());
+                return sample;
+            }
+            Err(_) => {
+                sample.error = Some("timed out waiting for upstream response".to_string());
+                return sample;
+            }
+        };
+
+        sample.latency_ms = Some(started.elapsed().as_millis() as u64);
+        sample.status = Some(response.status().as_u16());
+        sample.content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        if !response.status().is_success() {
+            sample.error = Some(format!("upstream returned {}", response.status()));
+            return sample;
+        }
+        if is_playlist(stream_url, sample.content_type.as_deref()) {
+            // Playlist bodies say nothing about media bitrate; rely on the ffprobe figures
+            sample.error = Some("HLS playlist; bitrate not sampled".to_string());
+            return sample;
+        }
+
+        let body_started = Instant::now();
+        loop {
+            let remaining = window.saturating_sub(body_started.elapsed());
+            if remaining.is_zero() || sample.sampled_bytes >= max_bytes {
+                break;
+            }
+            match tokio::time::timeout(remaining, response.chunk()).await {
+                Ok(Ok(Some(chunk))) => {
+                    if sample.first_byte_ms.is_none() {
+                        sample.first_byte_ms = Some(started.elapsed().as_millis() as u64);
+                    }
+                    sample.sampled_bytes += chunk.len() as u64;
+                }
+                Ok(Ok(None)) => break,
+                Ok(Err(e)) => {
+                    sample.error = Some(e.to_string());
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        let elapsed = body_started.elapsed();
+        sample.sample_duration_ms = elapsed.as_millis() as u64;
+        sample.sampled_bitrate = bitrate_bps(sample.sampled_bytes, elapsed);
+        sample
+    }
+}
+
+/// Whether the upstream response is an HLS playlist rather than media
+fn is_playlist(url: &str, content_type: Option<&str>) -> bool {
+    let by_type = content_type.is_some_and(|ct| ct.to_ascii_lowercase().contains("mpegurl"));
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    by_type || path.to_ascii_lowercase().ends_with(".m3u8")
+}
+
+/// Average bitrate in bits per second, when enough data was read to be meaningful
+fn bitrate_bps(bytes: u64, elapsed: Duration) -> Option<u64> {
+    let secs = elapsed.as_secs_f64();
+    if bytes == 0 || secs < 0.1 {
+        return None;
+    }
+    Some((bytes as f64 * 8.0 / secs) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_playlist() {
+        assert!(is_playlist("http://host/live/index.m3u8?token=1", None));
+        assert!(is_playlist(
+            "http://host/live/1",
+            Some("application/vnd.apple.mpegURL")
+        ));
+        assert!(!is_playlist("http://host/live/1.ts", Some("video/mp2t")));
+    }
+
+    #[test]
+    fn test_bitrate_bps() {
+        assert_eq!(
+            bitrate_bps(1_000_000, Duration::from_secs(2)),
+            Some(4_000_000)
+        );
+        assert_eq!(bitrate_bps(0, Duration::from_secs(2)), None);
+        assert_eq!(bitrate_bps(1000, Duration::from_millis(10)), None);
+    }
+}
@@ -0,0 +1,305 @@
+//! Channel list exports
+//!
+//! Renders channel lists as CSV or XLSX for offline review and audit. A proxy export lists
+//! the channels of its latest generated playlist in playlist order; a source export lists
+//! the channels ingested from the source. Both share the same columns.
+
+use anyhow::Result;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::Channel;
+use crate::services::playlist_delta::parse_entries;
+use crate::utils::uuid_parser::parse_uuid_flexible;
+
+/// Column headers of every export
+pub const EXPORT_COLUMNS: [&str; 6] = ["number", "name", "group", "tvg_id", "logo_url", "source"];
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    #[serde(alias = "excel")]
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Query parameters of the export endpoints
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct ChannelExportQuery {
+    /// `csv` (default) or `xlsx`
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Export download response, named `<file_stem>.<extension>`
+///
+/// Characters other than ASCII letters, digits, `-` and `_` in the stem are replaced so the
+/// name is safe in the `Content-Disposition` header.
+pub fn export_response(
+    format: ExportFormat,
+    file_stem: &str,
+    rows: &[ExportRow],
+) -> axum::response::Response {
+    use axum::http::{StatusCode, header};
+    use axum::response::IntoResponse;
+
+    let file_stem: String = file_stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match render(format, rows) {
+        Ok(body) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, format.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"{file_stem}.{}\"",
+                        format.extension()
+                    ),
+                ),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(e) => crate::web::responses::internal_error(&format!("Failed to render export: {e}"))
+            .into_response(),
+    }
+}
+
+/// One exported channel
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportRow {
+    pub number: Option<String>,
+    pub name: String,
+    pub group: Option<String>,
+    pub tvg_id: Option<String>,
+    pub logo_url: Option<String>,
+    pub source: Option<String>,
+}
+
+impl ExportRow {
+    fn cells(&self) -> [&str; 6] {
+        [
+            self.number.as_deref().unwrap_or_default(),
+            &self.name,
+            self.group.as_deref().unwrap_or_default(),
+            self.tvg_id.as_deref().unwrap_or_default(),
+            self.logo_url.as_deref().unwrap_or_default(),
+            self.source.as_deref().unwrap_or_default(),
+        ]
+    }
+
+    /// Row of an ingested channel
+    pub fn from_channel(channel: &Channel, source_name: Option<&str>) -> Self {
+        Self {
+            number: channel.tvg_chno.clone(),
+            name: channel.channel_name.clone(),
+            group: channel.group_title.clone(),
+            tvg_id: channel.tvg_id.clone(),
+            logo_url: channel.tvg_logo.clone(),
+            source: source_name.map(str::to_string),
+        }
+    }
+}
+
+/// A channel of a generated playlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistChannel {
+    /// Channel the proxy stream URL points at, when the URL is a proxy stream URL
+    pub channel_id: Option<Uuid>,
+    /// Row without its source, which is resolved from `channel_id`
+    pub row: ExportRow,
+}
+
+/// Channels of a generated playlist, in playlist order
+pub fn playlist_channels(content: &str) -> Vec<PlaylistChannel> {
+    parse_entries(content)
+        .into_iter()
+        .filter_map(|entry| {
+            let extinf = entry.metadata.lines().next()?;
+            Some(PlaylistChannel {
+                channel_id: stream_url_channel_id(&entry.url),
+                row: ExportRow {
+                    number: extinf_attribute(extinf, "tvg-chno"),
+                    name: extinf_title(extinf).to_string(),
+                    group: extinf_attribute(extinf, "group-title"),
+                    tvg_id: extinf_attribute(extinf, "tvg-id"),
+                    logo_url: extinf_attribute(extinf, "tvg-logo"),
+                    source: None,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Render rows in the requested format
+pub fn render(format: ExportFormat, rows: &[ExportRow]) -> Result<Vec<u8>> {
+    match format {
+        ExportFormat::Csv => render_csv(rows),
+        ExportFormat::Xlsx => render_xlsx(rows),
+    }
+}
+
+fn render_csv(rows: &[ExportRow]) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(EXPORT_COLUMNS)?;
+    for row in rows {
+        writer.write_record(row.cells())?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+fn render_xlsx(rows: &[ExportRow]) -> Result<Vec<u8>> {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let header_format = rust_xlsxwriter::Format::new().set_bold();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("Channels")?;
+    for (col, header) in EXPORT_COLUMNS.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+    }
+    for (index, row) in rows.iter().enumerate() {
+        let row_num = index as u32 + 1;
+        // Channel numbers are numeric where possible so spreadsheets sort them naturally
+        match row.number.as_deref().map(str::parse::<f64>) {
+            Some(Ok(number)) => {
+                worksheet.write_number(row_num, 0, number)?;
+            }
+            _ => {
+                worksheet.write_string(row_num, 0, row.number.as_deref().unwrap_or_default())?;
+            }
+        }
+        for (col, cell) in row.cells().iter().enumerate().skip(1) {
+            worksheet.write_string(row_num, col as u16, *cell)?;
+        }
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+    Ok(workbook.save_to_buffer()?)
+}
+
+/// Channel ID from the last path segment of a proxy stream URL
+fn stream_url_channel_id(url: &str) -> Option<Uuid> {
+    let path = url.split(['?', '#']).next()?;
+    if !path.contains("/stream/") {
+        return None;
+    }
+    let segment = path.trim_end_matches('/').rsplit('/').next()?;
+    parse_uuid_flexible(segment).ok()
+}
+
+/// Value of a quoted `name="value"` attribute of an `#EXTINF` line
+fn extinf_attribute(extinf: &str, name: &str) -> Option<String> {
+    let needle = format!("{name}=\"");
+    let mut search_from = 0;
+    while let Some(found) = extinf[search_from..].find(&needle) {
+        let start = search_from + found;
+        // Require a word boundary so `tvg-id` does not match inside `x-tvg-id`
+        let boundary = extinf[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| c.is_whitespace() || c == ':');
+        let value_start = start + needle.len();
+        if boundary {
+            let value_end = extinf[value_start..].find('"')? + value_start;
+            let value = &extinf[value_start..value_end];
+            return (!value.is_empty()).then(|| value.to_string());
+        }
+        search_from = value_start;
+    }
+    None
+}
+
+/// Display name after the attributes of an `#EXTINF` line
+fn extinf_title(extinf: &str) -> &str {
+    // Quoted attribute values may contain commas, so look past the last quote
+    let attributes_end = extinf.rfind('"').map_or(0, |quote| quote + 1);
+    extinf[attributes_end..]
+        .split_once(',')
+        .map_or("", |(_, title)| title.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playlist_channels() {
+        let channel_id = Uuid::from_u128(7);
+        let playlist = format!(
+            "#EXTM3U\n#EXTINF:-1 tvg-id=\"bbc1.uk\" tvg-chno=\"101\" tvg-logo=\"http://logo/bbc1.png\" group-title=\"News, UK\",BBC One\nhttp://host/stream/proxy/{channel_id}\n#EXTINF:-1 tvg-name=\"x\",Other, Channel\nhttp://upstream/live.ts\n"
+        );
+        let channels = playlist_channels(&playlist);
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0].channel_id, Some(channel_id));
+        assert_eq!(
+            channels[0].row,
+            ExportRow {
+                number: Some("101".to_string()),
+                name: "BBC One".to_string(),
+                group: Some("News, UK".to_string()),
+                tvg_id: Some("bbc1.uk".to_string()),
+                logo_url: Some("http://logo/bbc1.png".to_string()),
+                source: None,
+            }
+        );
+        assert_eq!(channels[1].channel_id, None);
+        assert_eq!(channels[1].row.name, "Other, Channel");
+        assert_eq!(channels[1].row.tvg_id, None);
+    }
+
+    #[test]
+    fn test_render_csv_quotes_fields() {
+        let rows = [ExportRow {
+            number: Some("1".to_string()),
+            name: "Say \"Hi\"".to_string(),
+            group: Some("A, B".to_string()),
+            ..Default::default()
+        }];
+        let csv = String::from_utf8(render(ExportFormat::Csv, &rows).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "number,name,group,tvg_id,logo_url,source\n1,\"Say \"\"Hi\"\"\",\"A, B\",,,\n"
+        );
+    }
+
+    #[test]
+    fn test_render_xlsx_is_a_zip() {
+        let bytes = render(ExportFormat::Xlsx, &[ExportRow::default()]).unwrap();
+        assert!(bytes.starts_with(b"PK"));
+    }
+
+    #[test]
+    fn test_export_format_parsing() {
+        let parse = |value: &str| serde_json::from_value::<ExportFormat>(serde_json::json!(value));
+        assert_eq!(parse("csv").unwrap(), ExportFormat::Csv);
+        assert_eq!(parse("excel").unwrap(), ExportFormat::Xlsx);
+        assert!(parse("pdf").is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! On-demand channel preview screenshots
+//!
+//! Captures a single frame from a channel's stream with a one-shot FFmpeg run (through
+//! [`RelayManager`]) and caches it as a JPEG in the logo sandbox for the configured TTL, so
+//! the channel browser can show what a stream is actually carrying.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sandboxed_file_manager::SandboxedManager;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::config::ChannelPreviewConfig;
+use crate::services::relay_manager::RelayManager;
+
+/// Sandbox directory holding captured previews
+const PREVIEW_DIR: &str = "channel_previews";
+
+/// A captured (or cached) preview image
+#[derive(Debug, Clone)]
+pub struct ChannelPreview {
+    /// JPEG image data
+    pub image: Vec<u8>,
+    pub captured_at: DateTime<Utc>,
+    /// How much longer the preview is served from cache
+    pub expires_in: Duration,
+    /// Whether the image came from cache rather than a fresh capture
+    pub cached: bool,
+}
+
+pub struct ChannelPreviewService {
+    relay_manager: Arc<RelayManager>,
+    file_manager: SandboxedManager,
+    config: ChannelPreviewConfig,
+    /// Per-channel capture locks so concurrent requests share one FFmpeg run
+    captures: Mutex<HashMap<Uuid, Arc<Mutex<()>>>>,
+}
+
+impl ChannelPreviewService {
+    pub fn new(
+        relay_manager: Arc<RelayManager>,
+        file_manager: SandboxedManager,
+        config: ChannelPreviewConfig,
+    ) -> Self {
+        Self {
+            relay_manager,
+            file_manager,
+            config,
+            captures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Preview of a channel, captured now unless a fresh cached image exists
+    ///
+    /// `refresh` ignores the cache and always captures a new frame.
+    pub async fn preview(
+        &self,
+        channel_id: Uuid,
+        stream_url: &str,
+        refresh: bool,
+    ) -> Result<ChannelPreview> {
+        let path = format!("{PREVIEW_DIR}/{channel_id}.jpg");
+        if !refresh && let Some(preview) = self.cached(&path).await {
+            return Ok(preview);
+        }
+
+        let lock = self
+            .captures
+            .lock()
+            .await
+            .entry(channel_id)
+            .or_default()
+            .clone();
+        let result = {
+            let _guard = lock.lock().await;
+            // Another request may have captured the frame while we waited
+            match self.cached(&path).await {
+                Some(preview) if !refresh || preview.captured_at > Utc::now() - ttl_slack() => {
+                    Ok(preview)
+                }
+                _ => self.capture(&path, stream_url).await,
+            }
+        };
+
+        let mut captures = self.captures.lock().await;
+        if Arc::strong_count(&lock) <= 2 {
+            captures.remove(&channel_id);
+        }
+        result
+    }
+
+    async fn capture(&self, path: &str, stream_url: &str) -> Result<ChannelPreview> {
+        let image = self
+            .relay_manager
+            .capture_frame(
+                stream_url,
+                self.config.width,
+                self.config.capture_timeout_duration(),
+            )
+            .await?;
+
+        if let Err(e) = self.file_manager.create_dir_all(PREVIEW_DIR).await {
+            warn!("Failed to create channel preview directory: {}", e);
+        }
+        if let Err(e) = self.file_manager.write(path, &image).await {
+            // A failed cache write still leaves a usable preview
+            warn!("Failed to cache channel preview {}: {}", path, e);
+        }
+
+        Ok(ChannelPreview {
+            image,
+            captured_at: Utc::now(),
+            expires_in: self.config.cache_ttl_duration(),
+            cached: false,
+        })
+    }
+
+    async fn cached(&self, path: &str) -> Option<ChannelPreview> {
+        let modified = self.file_manager.stat(path).await.ok()?.modified;
+        let age = (Utc::now() - modified).to_std().unwrap_or_default();
+        let ttl = self.config.cache_ttl_duration();
+        if age >= ttl {
+            debug!("Cached channel preview {} expired", path);
+            return None;
+        }
+
+        let image = self
+            .file_manager
+            .read(path)
+            .await
+            .map_err(|e| warn!("Failed to read cached preview {}: {}", path, e))
+            .ok()?;
+        Some(ChannelPreview {
+            image,
+            captured_at: modified,
+            expires_in: ttl - age,
+            cached: true,
+        })
+    }
+}
+
+/// A refresh that waited on a concurrent capture reuses an image this recent
+fn ttl_slack() -> chrono::Duration {
+    chrono::Duration::seconds(5)
+}
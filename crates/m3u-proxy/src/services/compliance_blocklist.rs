@@ -0,0 +1,266 @@
+//! Compliance blocklist service
+//!
+//! Loads an operator-supplied blocklist of stream hostnames and tvg_ids from a local
+//! file or URL and refreshes it on a schedule. The compliance pipeline stage consults
+//! the currently loaded list to strip matching channels from every generated output.
+//!
+//! Blocklist format, one entry per line:
+//! ```text
+//! # comment
+//! host:streams.example.com   # blocks the host and all of its subdomains
+//! tvg_id:Some.Channel.uk     # case-insensitive tvg_id match
+//! bad.example.net            # bare entries are treated as hostnames
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock, RwLock};
+use tracing::{error, info, warn};
+
+use crate::config::ComplianceBlocklistConfig;
+use crate::models::Channel;
+use crate::utils::{DecompressingHttpClient, HttpClientFactory};
+
+/// Parsed blocklist
+#[derive(Debug, Clone, Default)]
+pub struct Blocklist {
+    hostnames: HashSet<String>,
+    tvg_ids: HashSet<String>,
+    pub loaded_at: Option<DateTime<Utc>>,
+}
+
+/// Why a channel was removed by the blocklist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockReason {
+    Hostname(String),
+    TvgId(String),
+}
+
+impl std::fmt::Display for BlockReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockReason::Hostname(host) => write!(f, "host:{host}"),
+            BlockReason::TvgId(id) => write!(f, "tvg_id:{id}"),
+        }
+    }
+}
+
+impl Blocklist {
+    /// Parse blocklist content, skipping blank lines and comments
+    pub fn parse(content: &str) -> Self {
+        let mut blocklist = Self {
+            loaded_at: Some(Utc::now()),
+            ..Default::default()
+        };
+
+        for line in content.lines() {
+            let entry = line.split('#').next().unwrap_or_default().trim();
+            if entry.is_empty() {
+                continue;
+            }
+            if let Some(id) = entry.strip_prefix("tvg_id:") {
+                let id = id.trim().to_lowercase();
+                if !id.is_empty() {
+                    blocklist.tvg_ids.insert(id);
+                }
+            } else {
+                let host = entry.strip_prefix("host:").unwrap_or(entry).trim();
+                let host = host.trim_end_matches('.').to_lowercase();
+                if !host.is_empty() {
+                    blocklist.hostnames.insert(host);
+                }
+            }
+        }
+
+        blocklist
+    }
+
+    pub fn len(&self) -> usize {
+        self.hostnames.len() + self.tvg_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The blocklist entry matching this channel, if any
+    pub fn blocked_reason(&self, channel: &Channel) -> Option<BlockReason> {
+        if let Some(tvg_id) = channel.tvg_id.as_deref()
+            && self.tvg_ids.contains(&tvg_id.trim().to_lowercase())
+        {
+            return Some(BlockReason::TvgId(tvg_id.to_string()));
+        }
+
+        let host = url::Url::parse(&channel.stream_url)
+            .ok()?
+            .host_str()?
+            .trim_end_matches('.')
+            .to_lowercase();
+        // Walk up the domain labels so an entry also covers its subdomains
+        let mut candidate = host.as_str();
+        loop {
+            if self.hostnames.contains(candidate) {
+                return Some(BlockReason::Hostname(candidate.to_string()));
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => return None,
+            }
+        }
+    }
+}
+
+static GLOBAL: OnceLock<Arc<ComplianceBlocklistService>> = OnceLock::new();
+
+/// Holds the currently loaded blocklist and refreshes it from the configured source
+pub struct ComplianceBlocklistService {
+    config: ComplianceBlocklistConfig,
+    http_client_factory: HttpClientFactory,
+    current: RwLock<Option<Arc<Blocklist>>>,
+}
+
+impl ComplianceBlocklistService {
+    pub fn new(config: ComplianceBlocklistConfig, http_client_factory: HttpClientFactory) -> Self {
+        Self {
+            config,
+            http_client_factory,
+            current: RwLock::new(None),
+        }
+    }
+
+    /// Make this service the process-wide blocklist consulted by pipeline runs
+    pub fn install_global(service: Arc<Self>) {
+        if GLOBAL.set(service).is_err() {
+            warn!("Compliance blocklist service already installed; ignoring");
+        }
+    }
+
+    /// The process-wide blocklist service, if a blocklist source is configured
+    pub fn global() -> Option<Arc<Self>> {
+        GLOBAL.get().cloned()
+    }
+
+    pub fn fail_closed(&self) -> bool {
+        self.config.fail_closed
+    }
+
+    /// The most recently loaded blocklist
+    pub fn current(&self) -> Option<Arc<Blocklist>> {
+        self.current.read().ok().and_then(|guard| guard.clone())
+    }
+
+    /// Reload the blocklist from its source, keeping the previous list on failure
+    pub async fn refresh(&self) -> Result<usize> {
+        let Some(source) = self.config.source.as_deref() else {
+            return Ok(0);
+        };
+
+        let content = if source.starts_with("http://") || source.starts_with("https://") {
+            self.http_client_factory
+                .create_client_for_service("compliance_blocklist")
+                .await
+                .fetch_text(source)
+                .await
+                .with_context(|| format!("Failed to fetch compliance blocklist from {source}"))?
+        } else {
+            tokio::fs::read_to_string(source)
+                .await
+                .with_context(|| format!("Failed to read compliance blocklist {source}"))?
+        };
+
+        let blocklist = Blocklist::parse(&content);
+        let entries = blocklist.len();
+        if let Ok(mut guard) = self.current.write() {
+            *guard = Some(Arc::new(blocklist));
+        }
+        Ok(entries)
+    }
+
+    /// Run the refresh loop until cancelled
+    pub async fn run(&self, cancellation_token: tokio_util::sync::CancellationToken) {
+        let mut refresh_interval = tokio::time::interval(self.config.refresh_interval_duration());
+        // The first tick completes immediately; startup already performed a refresh
+        refresh_interval.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = refresh_interval.tick() => {
+                    match self.refresh().await {
+                        Ok(entries) => info!("Compliance blocklist refreshed ({} entries)", entries),
+                        Err(e) => error!("Compliance blocklist refresh failed, keeping previous list: {:#}", e),
+                    }
+                }
+                _ = cancellation_token.cancelled() => {
+                    info!("Compliance blocklist refresh received cancellation signal, shutting down");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(stream_url: &str, tvg_id: Option<&str>) -> Channel {
+        Channel {
+            id: uuid::Uuid::new_v4(),
+            source_id: uuid::Uuid::new_v4(),
+            tvg_id: tvg_id.map(str::to_string),
+            tvg_name: None,
+            tvg_chno: None,
+            tvg_logo: None,
+            tvg_shift: None,
+            group_title: None,
+            channel_name: "Test".to_string(),
+            stream_url: stream_url.to_string(),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            epg_shift: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            extra_attributes: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_skips_comments_and_blank_lines() {
+        let blocklist = Blocklist::parse(
+            "# header\n\nhost:Bad.Example.com\ntvg_id:Blocked.UK # trailing\nother.net.\n",
+        );
+        assert_eq!(blocklist.len(), 3);
+    }
+
+    #[test]
+    fn test_hostname_matches_subdomains() {
+        let blocklist = Blocklist::parse("host:bad.example.com");
+        assert_eq!(
+            blocklist.blocked_reason(&channel("http://cdn.bad.example.com/live/1.ts", None)),
+            Some(BlockReason::Hostname("bad.example.com".to_string()))
+        );
+        assert!(
+            blocklist
+                .blocked_reason(&channel("http://notbad.example.com/1.ts", None))
+                .is_none()
+        );
+        assert!(
+            blocklist
+                .blocked_reason(&channel("http://example.com/1.ts", None))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_tvg_id_match_is_case_insensitive() {
+        let blocklist = Blocklist::parse("tvg_id:blocked.uk");
+        assert_eq!(
+            blocklist.blocked_reason(&channel("http://ok.example.com/1.ts", Some("Blocked.UK"))),
+            Some(BlockReason::TvgId("Blocked.UK".to_string()))
+        );
+    }
+}
@@ -0,0 +1,507 @@
+//! Component-level health checks behind `/health/deep`
+//!
+//! Unlike `/health`, which summarises what the process already knows, every check here
+//! exercises its dependency: a database round trip, a write to each storage area, an
+//! `ffmpeg -version` run. Checks run concurrently, each bounded by the configured timeout,
+//! and report healthy, degraded or unhealthy with their own latency. The configured
+//! strictness turns the component outcomes into a pass or fail for load balancers.
+
+use chrono::{DateTime, Utc};
+use sandboxed_file_manager::SandboxedManager;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::config::{DeepHealthConfig, DeepHealthStrictness};
+use crate::database::Database;
+use crate::job_scheduling::{JobQueue, JobQueueRunner, JobScheduler};
+use crate::services::CircuitBreakerManager;
+use crate::utils::circuit_breaker::CircuitBreakerState;
+
+/// Outcome of a component check, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// Result of checking one component
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComponentHealth {
+    pub status: ComponentStatus,
+    /// Whether the component fails the check under `critical` strictness
+    pub critical: bool,
+    /// Time the check took
+    pub latency_ms: u64,
+    /// Why the component is not healthy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+}
+
+/// Component-level health of the instance
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeepHealthReport {
+    /// Worst status of any component
+    pub status: ComponentStatus,
+    pub strictness: DeepHealthStrictness,
+    /// Whether the instance passes at this strictness (200 rather than 503)
+    pub passing: bool,
+    pub checked_at: DateTime<Utc>,
+    /// Time all checks took together
+    pub latency_ms: u64,
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+impl DeepHealthReport {
+    fn new(
+        components: BTreeMap<String, ComponentHealth>,
+        strictness: DeepHealthStrictness,
+        latency: Duration,
+    ) -> Self {
+        let status = components
+            .values()
+            .map(|component| component.status)
+            .max()
+            .unwrap_or(ComponentStatus::Healthy);
+        let passing = match strictness {
+            DeepHealthStrictness::Critical => !components
+                .values()
+                .any(|c| c.critical && c.status == ComponentStatus::Unhealthy),
+            DeepHealthStrictness::Standard => status != ComponentStatus::Unhealthy,
+            DeepHealthStrictness::Strict => status == ComponentStatus::Healthy,
+        };
+        Self {
+            status,
+            strictness,
+            passing,
+            checked_at: Utc::now(),
+            latency_ms: latency.as_millis() as u64,
+            components,
+        }
+    }
+}
+
+/// What a single probe found, before timing is attached
+struct Probe {
+    status: ComponentStatus,
+    message: Option<String>,
+    details: serde_json::Value,
+}
+
+impl Probe {
+    fn healthy(details: serde_json::Value) -> Self {
+        Self {
+            status: ComponentStatus::Healthy,
+            message: None,
+            details,
+        }
+    }
+
+    fn with(status: ComponentStatus, message: String, details: serde_json::Value) -> Self {
+        Self {
+            status,
+            message: Some(message),
+            details,
+        }
+    }
+}
+
+/// Runs the component checks of one `/health/deep` request
+pub struct DeepHealthChecker {
+    config: DeepHealthConfig,
+    database: Database,
+    job_queue: Arc<JobQueue>,
+    job_queue_runner: Arc<JobQueueRunner>,
+    job_scheduler: Arc<JobScheduler>,
+    started_at: DateTime<Utc>,
+    storage: Vec<(&'static str, SandboxedManager)>,
+    ffmpeg_command: String,
+    circuit_breakers: Option<Arc<CircuitBreakerManager>>,
+}
+
+impl DeepHealthChecker {
+    pub fn new(
+        config: DeepHealthConfig,
+        database: Database,
+        job_queue: Arc<JobQueue>,
+        job_queue_runner: Arc<JobQueueRunner>,
+        job_scheduler: Arc<JobScheduler>,
+        started_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            config,
+            database,
+            job_queue,
+            job_queue_runner,
+            job_scheduler,
+            started_at,
+            storage: Vec::new(),
+            ffmpeg_command: "ffmpeg".to_string(),
+            circuit_breakers: None,
+        }
+    }
+
+    /// Check that a storage area accepts writes
+    pub fn with_storage(mut self, name: &'static str, manager: SandboxedManager) -> Self {
+        self.storage.push((name, manager));
+        self
+    }
+
+    /// Check the ffmpeg binary relays run
+    pub fn with_ffmpeg_command(mut self, command: impl Into<String>) -> Self {
+        self.ffmpeg_command = command.into();
+        self
+    }
+
+    /// Report the states of the managed circuit breakers
+    pub fn with_circuit_breakers(mut self, manager: Option<Arc<CircuitBreakerManager>>) -> Self {
+        self.circuit_breakers = manager;
+        self
+    }
+
+    /// Run every check and judge the outcome at `strictness`
+    pub async fn check(&self, strictness: DeepHealthStrictness) -> DeepHealthReport {
+        let started = Instant::now();
+        let (database, job_queue, scheduler, storage, ffmpeg, circuit_breakers) = tokio::join!(
+            self.timed(true, self.check_database()),
+            self.timed(true, self.check_job_queue()),
+            self.timed(false, self.check_scheduler()),
+            self.timed(true, self.check_storage()),
+            self.timed(self.config.require_ffmpeg, self.check_ffmpeg()),
+            self.timed(false, self.check_circuit_breakers()),
+        );
+
+        let components = BTreeMap::from([
+            ("database".to_string(), database),
+            ("job_queue".to_string(), job_queue),
+            ("scheduler".to_string(), scheduler),
+            ("storage".to_string(), storage),
+            ("ffmpeg".to_string(), ffmpeg),
+            ("circuit_breakers".to_string(), circuit_breakers),
+        ]);
+        DeepHealthReport::new(components, strictness, started.elapsed())
+    }
+
+    async fn timed(&self, critical: bool, probe: impl Future<Output = Probe>) -> ComponentHealth {
+        let timeout = self.config.check_timeout_duration();
+        let started = Instant::now();
+        let probe = tokio::time::timeout(timeout, probe)
+            .await
+            .unwrap_or_else(|_| {
+                Probe::with(
+                    ComponentStatus::Unhealthy,
+                    format!(
+                        "check did not finish within {}",
+                        humantime::format_duration(timeout)
+                    ),
+                    serde_json::Value::Null,
+                )
+            });
+        ComponentHealth {
+            status: probe.status,
+            critical,
+            latency_ms: started.elapsed().as_millis() as u64,
+            message: probe.message,
+            details: probe.details,
+        }
+    }
+
+    /// How long the instance has been up
+    fn uptime(&self) -> Duration {
+        (Utc::now() - self.started_at).to_std().unwrap_or_default()
+    }
+
+    /// Problem with a periodic loop whose last beat was at `last`, if any
+    fn staleness(
+        &self,
+        what: &str,
+        last: Option<DateTime<Utc>>,
+        limit: Duration,
+    ) -> Option<String> {
+        let age = match last {
+            Some(last) => (Utc::now() - last).to_std().unwrap_or_default(),
+            // Not having beaten yet is only a problem once the instance has been up a while
+            None if self.uptime() > limit => return Some(format!("{what} has not started")),
+            None => return None,
+        };
+        (age > limit).then(|| {
+            format!(
+                "{what} last ran {} ago",
+                humantime::format_duration(Duration::from_secs(age.as_secs()))
+            )
+        })
+    }
+
+    async fn check_database(&self) -> Probe {
+        use sea_orm::ConnectionTrait;
+
+        let warning = self.config.database_latency_warning_duration();
+        let replica = self.database.replica.as_ref().map(|r| r.is_healthy());
+        let statement =
+            sea_orm::Statement::from_string(self.database.backend(), "SELECT 1".to_owned());
+        let started = Instant::now();
+        let result = self.database.connection().query_one(statement).await;
+        let round_trip = started.elapsed();
+        let details = json!({
+            "backend": format!("{:?}", self.database.backend()),
+            "round_trip_ms": round_trip.as_millis() as u64,
+            "read_replica_healthy": replica,
+        });
+
+        match result {
+            Err(e) => Probe::with(
+                ComponentStatus::Unhealthy,
+                format!("database unreachable: {e}"),
+                details,
+            ),
+            Ok(_) if round_trip > warning => Probe::with(
+                ComponentStatus::Degraded,
+                format!(
+                    "database round trip took {}ms (warning above {}ms)",
+                    round_trip.as_millis(),
+                    warning.as_millis()
+                ),
+                details,
+            ),
+            Ok(_) if replica == Some(false) => Probe::with(
+                ComponentStatus::Degraded,
+                "read replica unhealthy, reads fall back to the primary".to_string(),
+                details,
+            ),
+            Ok(_) => Probe::healthy(details),
+        }
+    }
+
+    async fn check_job_queue(&self) -> Probe {
+        let stats = self.job_queue.stats().await;
+        let heartbeat = self.job_queue_runner.last_heartbeat();
+        let paused = self.job_queue_runner.is_paused();
+        let details = json!({
+            "pending_jobs": stats.pending_jobs,
+            "running_jobs": stats.running_jobs,
+            "runner_last_heartbeat": heartbeat,
+            "runner_paused": paused,
+        });
+
+        if let Some(problem) = self.staleness(
+            "job runner",
+            heartbeat,
+            self.config.job_runner_stall_timeout_duration(),
+        ) {
+            Probe::with(ComponentStatus::Unhealthy, problem, details)
+        } else if stats.pending_jobs > self.config.job_backlog_warning {
+            Probe::with(
+                ComponentStatus::Degraded,
+                format!(
+                    "{} jobs pending (warning above {})",
+                    stats.pending_jobs, self.config.job_backlog_warning
+                ),
+                details,
+            )
+        } else if paused {
+            Probe::with(
+                ComponentStatus::Degraded,
+                "job runner paused".to_string(),
+                details,
+            )
+        } else {
+            Probe::healthy(details)
+        }
+    }
+
+    async fn check_scheduler(&self) -> Probe {
+        let last_tick = self.job_scheduler.last_tick();
+        let details = json!({ "last_tick": last_tick });
+        match self.staleness(
+            "scheduler",
+            last_tick,
+            self.config.scheduler_tick_timeout_duration(),
+        ) {
+            Some(problem) => Probe::with(ComponentStatus::Unhealthy, problem, details),
+            None => Probe::healthy(details),
+        }
+    }
+
+    async fn check_storage(&self) -> Probe {
+        let probe_file = format!("health-probe-{}.tmp", uuid::Uuid::new_v4());
+        let mut details = serde_json::Map::new();
+        let mut failures = Vec::new();
+        for (name, manager) in &self.storage {
+            let result = match manager.write(&probe_file, b"ok").await {
+                Ok(()) => manager.remove_file(&probe_file).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => {
+                    details.insert(name.to_string(), json!("writable"));
+                }
+                Err(e) => {
+                    details.insert(name.to_string(), json!(format!("error: {e}")));
+                    failures.push(*name);
+                }
+            }
+        }
+
+        if failures.is_empty() {
+            Probe::healthy(details.into())
+        } else {
+            Probe::with(
+                ComponentStatus::Unhealthy,
+                format!("not writable: {}", failures.join(", ")),
+                details.into(),
+            )
+        }
+    }
+
+    async fn check_ffmpeg(&self) -> Probe {
+        let output = tokio::process::Command::new(&self.ffmpeg_command)
+            .arg("-version")
+            .kill_on_drop(true)
+            .output()
+            .await;
+        let missing = if self.config.require_ffmpeg {
+            ComponentStatus::Unhealthy
+        } else {
+            ComponentStatus::Degraded
+        };
+
+        match output {
+            Ok(output) if output.status.success() => {
+                let version = String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .next()
+                    .and_then(|line| line.split_whitespace().nth(2))
+                    .map(str::to_string);
+                Probe::healthy(json!({ "command": self.ffmpeg_command, "version": version }))
+            }
+            Ok(output) => Probe::with(
+                missing,
+                format!(
+                    "{} -version exited with {}",
+                    self.ffmpeg_command, output.status
+                ),
+                json!({ "command": self.ffmpeg_command }),
+            ),
+            Err(e) => Probe::with(
+                missing,
+                format!("{} unavailable: {e}", self.ffmpeg_command),
+                json!({ "command": self.ffmpeg_command }),
+            ),
+        }
+    }
+
+    async fn check_circuit_breakers(&self) -> Probe {
+        let Some(manager) = &self.circuit_breakers else {
+            return Probe::healthy(json!({}));
+        };
+        let stats = manager.get_all_stats().await;
+        let mut open: Vec<&str> = stats
+            .iter()
+            .filter(|(_, s)| s.state == CircuitBreakerState::Open)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        open.sort_unstable();
+        let details: serde_json::Map<String, serde_json::Value> = stats
+            .iter()
+            .map(|(name, s)| {
+                (
+                    name.clone(),
+                    serde_json::to_value(&s.state).unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        if open.is_empty() {
+            Probe::healthy(details.into())
+        } else {
+            Probe::with(
+                ComponentStatus::Degraded,
+                format!("open: {}", open.join(", ")),
+                details.into(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(status: ComponentStatus, critical: bool) -> ComponentHealth {
+        ComponentHealth {
+            status,
+            critical,
+            latency_ms: 0,
+            message: None,
+            details: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_strictness() {
+        let components = BTreeMap::from([
+            (
+                "database".to_string(),
+                component(ComponentStatus::Healthy, true),
+            ),
+            (
+                "scheduler".to_string(),
+                component(ComponentStatus::Unhealthy, false),
+            ),
+            (
+                "ffmpeg".to_string(),
+                component(ComponentStatus::Degraded, false),
+            ),
+        ]);
+        let report =
+            |strictness| DeepHealthReport::new(components.clone(), strictness, Duration::ZERO);
+
+        assert_eq!(
+            report(DeepHealthStrictness::Critical).status,
+            ComponentStatus::Unhealthy
+        );
+        assert!(report(DeepHealthStrictness::Critical).passing);
+        assert!(!report(DeepHealthStrictness::Standard).passing);
+
+        let mut degraded_only = components.clone();
+        degraded_only.insert(
+            "scheduler".to_string(),
+            component(ComponentStatus::Healthy, false),
+        );
+        assert!(
+            DeepHealthReport::new(
+                degraded_only.clone(),
+                DeepHealthStrictness::Standard,
+                Duration::ZERO
+            )
+            .passing
+        );
+        assert!(
+            !DeepHealthReport::new(degraded_only, DeepHealthStrictness::Strict, Duration::ZERO)
+                .passing
+        );
+    }
+
+    #[test]
+    fn test_critical_component_fails_every_strictness() {
+        let components = BTreeMap::from([(
+            "storage".to_string(),
+            component(ComponentStatus::Unhealthy, true),
+        )]);
+        for strictness in [
+            DeepHealthStrictness::Critical,
+            DeepHealthStrictness::Standard,
+            DeepHealthStrictness::Strict,
+        ] {
+            assert!(!DeepHealthReport::new(components.clone(), strictness, Duration::ZERO).passing);
+        }
+    }
+}
@@ -4,23 +4,52 @@
 //! including auto-linking with stream sources for Xtream providers.
 
 use anyhow::Result;
+use sandboxed_file_manager::SandboxedManager;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::sync::broadcast;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::database::Database;
 use crate::database::repositories::{
-    epg_source::EpgSourceSeaOrmRepository, stream_source::StreamSourceSeaOrmRepository,
+    EpgChannelMetadataSeaOrmRepository, epg_source::EpgSourceSeaOrmRepository,
+    ingestion_run::IngestionRunSeaOrmRepository, stream_source::StreamSourceSeaOrmRepository,
 };
-use crate::models::{EpgSource, EpgSourceCreateRequest, EpgSourceUpdateRequest};
-use crate::services::UrlLinkingService;
+use crate::models::ingest_snapshot::{IngestSnapshot, IngestSnapshotKind};
+use crate::models::ingestion_run::{IngestionRunOutcome, IngestionSourceKind, RecordChangeSummary};
+use crate::models::{EpgSource, EpgSourceCreateRequest, EpgSourceType, EpgSourceUpdateRequest};
+use crate::services::{CachedEntity, IngestArchiveService, QueryCache, UrlLinkingService};
+use crate::sources::xmltv_epg::{XmltvEpgHandler, XmltvParseStats, XmltvProgramStream};
 
 /// Service for managing EPG sources with business logic
 pub struct EpgSourceService {
     database: Database,
     epg_source_repo: EpgSourceSeaOrmRepository,
     url_linking_service: UrlLinkingService,
-    cache_invalidation_tx: broadcast::Sender<()>,
+    cache_invalidation_tx: broadcast::Sender<CachedEntity>,
     http_client_factory: crate::utils::HttpClientFactory,
+    ingest_archive: Option<Arc<IngestArchiveService>>,
+    ingestion_history: Option<IngestionRunSeaOrmRepository>,
+    query_cache: Option<Arc<QueryCache>>,
+    temp_file_manager: Option<SandboxedManager>,
+}
+
+/// Downloaded file that is removed once dropped, whether ingestion succeeded, failed or
+/// was cancelled
+struct DownloadFile(PathBuf);
+
+impl Drop for DownloadFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!(
+                "Failed to remove XMLTV download {}: {}",
+                self.0.display(),
+                e
+            );
+        }
+    }
 }
 
 impl EpgSourceService {
@@ -29,7 +58,7 @@ impl EpgSourceService {
         database: Database,
         epg_source_repo: EpgSourceSeaOrmRepository,
         url_linking_service: UrlLinkingService,
-        cache_invalidation_tx: broadcast::Sender<()>,
+        cache_invalidation_tx: broadcast::Sender<CachedEntity>,
         http_client_factory: crate::utils::HttpClientFactory,
     ) -> Self {
         Self {
@@ -38,13 +67,49 @@ impl EpgSourceService {
             url_linking_service,
             cache_invalidation_tx,
             http_client_factory,
+            ingest_archive: None,
+            ingestion_history: None,
+            query_cache: None,
+            temp_file_manager: None,
         }
     }
 
+    /// Archive downloaded XMLTV guides for replay
+    pub fn with_ingest_archive(mut self, ingest_archive: Arc<IngestArchiveService>) -> Self {
+        self.ingest_archive = Some(ingest_archive);
+        self
+    }
+
+    /// Record the outcome of every ingestion in the ingestion history
+    pub fn with_ingestion_history(mut self, repo: IngestionRunSeaOrmRepository) -> Self {
+        self.ingestion_history = Some(repo);
+        self
+    }
+
+    /// Serve source lists from the API query cache
+    pub fn with_query_cache(mut self, query_cache: Arc<QueryCache>) -> Self {
+        self.query_cache = Some(query_cache);
+        self
+    }
+
+    /// Download XMLTV guides into the temp sandbox instead of the system temp directory
+    pub fn with_temp_file_manager(mut self, temp_file_manager: SandboxedManager) -> Self {
+        self.temp_file_manager = Some(temp_file_manager);
+        self
+    }
+
+    /// Ingest snapshot archive, when enabled
+    pub fn ingest_archive(&self) -> Option<&Arc<IngestArchiveService>> {
+        self.ingest_archive.as_ref()
+    }
+
     /// Legacy constructor for backward compatibility (deprecated)
     /// TODO: Remove once all callers are updated to use dependency injection
     #[deprecated(note = "Use dependency injection constructor instead")]
-    pub fn new_legacy(database: Database, cache_invalidation_tx: broadcast::Sender<()>) -> Self {
+    pub fn new_legacy(
+        database: Database,
+        cache_invalidation_tx: broadcast::Sender<CachedEntity>,
+    ) -> Self {
         let epg_source_repo = EpgSourceSeaOrmRepository::new(database.connection().clone());
         let stream_source_repo = StreamSourceSeaOrmRepository::new(database.connection().clone());
         let url_linking_service =
@@ -104,7 +169,7 @@ impl EpgSourceService {
         };
 
         // Invalidate cache since we added a new source
-        let _ = self.cache_invalidation_tx.send(());
+        let _ = self.cache_invalidation_tx.send(CachedEntity::EpgSources);
 
         info!(
             "Successfully created EPG source: {} ({})",
@@ -159,7 +224,7 @@ impl EpgSourceService {
             .map_err(|e| anyhow::anyhow!("Failed to update EPG source: {}", e))?;
 
         // Invalidate cache
-        let _ = self.cache_invalidation_tx.send(());
+        let _ = self.cache_invalidation_tx.send(CachedEntity::EpgSources);
 
         info!(
             "Successfully updated EPG source: {} ({})",
@@ -173,14 +238,14 @@ impl EpgSourceService {
     pub async fn delete_with_cleanup(&self, id: uuid::Uuid) -> Result<()> {
         debug!("Deleting EPG source: {}", id);
 
-        // Delete the EPG source (this will cascade to linked sources)
+        // Move the EPG source to the trash; ingestion history is kept until it is purged
         self.epg_source_repo
             .delete(&id)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to delete EPG source: {}", e))?;
 
         // Invalidate cache
-        let _ = self.cache_invalidation_tx.send(());
+        let _ = self.cache_invalidation_tx.send(CachedEntity::EpgSources);
 
         info!("EPG source {} deleted successfully", id);
         Ok(())
@@ -188,6 +253,21 @@ impl EpgSourceService {
 
     /// List EPG sources with statistics
     pub async fn list_with_stats(&self) -> Result<Vec<crate::models::EpgSourceWithStats>> {
+        match &self.query_cache {
+            Some(cache) => {
+                cache
+                    .get_or_load(
+                        "epg_sources_with_stats",
+                        &[CachedEntity::EpgSources],
+                        || self.load_with_stats(),
+                    )
+                    .await
+            }
+            None => self.load_with_stats().await,
+        }
+    }
+
+    async fn load_with_stats(&self) -> Result<Vec<crate::models::EpgSourceWithStats>> {
         let sources_with_stats = self
             .epg_source_repo
             .list_with_stats()
@@ -520,6 +600,172 @@ impl EpgSourceService {
         Ok(total_saved)
     }
 
+    /// Download an XMLTV source to a temporary file and ingest it as a stream
+    async fn ingest_xmltv_streaming(
+        &self,
+        source: &EpgSource,
+        progress_updater: Option<&crate::services::progress_service::ProgressStageUpdater>,
+        outcome: &mut IngestionRunOutcome,
+    ) -> Result<usize> {
+        let handler = XmltvEpgHandler::new(&self.http_client_factory).await;
+        if let Some(updater) = progress_updater {
+            updater.update_progress(5.0, "Downloading XMLTV data").await;
+        }
+
+        let file_name = format!("m3u-proxy-xmltv-{}.download", uuid::Uuid::new_v4());
+        let download = DownloadFile(match &self.temp_file_manager {
+            Some(manager) => manager.get_full_path(&file_name)?,
+            None => std::env::temp_dir().join(file_name),
+        });
+        let download_path = &download.0;
+        async {
+            let downloaded = handler
+                .download_xmltv(source, &download_path)
+                .await
+                .map_err(|e| anyhow::anyhow!("EPG source handler failed: {}", e))?;
+            debug!(
+                "Downloaded {} bytes of XMLTV data for source '{}'",
+                downloaded, source.name
+            );
+            outcome.bytes_downloaded = Some(downloaded);
+
+            if let Some(archive) = &self.ingest_archive
+                && let Err(e) = archive
+                    .archive_file(IngestSnapshotKind::Epg, source.id, download_path)
+                    .await
+            {
+                warn!("Failed to archive XMLTV of '{}': {}", source.name, e);
+            }
+
+            let stream = XmltvProgramStream::open_file(
+                source,
+                download_path,
+                self.http_client_factory.max_decompressed_bytes(),
+            )
+            .map_err(|e| anyhow::anyhow!("EPG source handler failed: {}", e))?;
+            self.save_epg_program_stream(source.id, stream, progress_updater)
+                .await
+                .map(|(saved, _)| saved)
+        }
+        .await
+    }
+
+    /// Replace a source's programs with those of an XMLTV stream (atomic operation)
+    ///
+    /// Parsing runs on a blocking thread and hands batches over a bounded channel, so
+    /// at most a few batches are held in memory while each is inserted within the
+    /// delete + insert transaction. Progress follows the input bytes consumed. The
+    /// stream's multilingual channel metadata is replaced in the same transaction.
+    /// Returns the programmes saved and the stream's parse counts.
+    async fn save_epg_program_stream(
+        &self,
+        source_id: uuid::Uuid,
+        stream: XmltvProgramStream,
+        progress_updater: Option<&crate::services::progress_service::ProgressStageUpdater>,
+    ) -> Result<(usize, XmltvParseStats)> {
+        use crate::entities::{epg_programs, prelude::*};
+        use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, TransactionTrait};
+
+        let batch_size = crate::config::DatabaseBatchConfig::default()
+            .safe_epg_program_batch_size(self.database.connection().get_database_backend());
+
+        let (batch_tx, mut batch_rx) = tokio::sync::mpsc::channel(2);
+        let parser = tokio::task::spawn_blocking(move || {
+            let mut stream = stream;
+            let mut completed = false;
+            loop {
+                match stream.next_batch(batch_size) {
+                    Ok(batch) if batch.is_empty() => {
+                        completed = true;
+                        break;
+                    }
+                    Ok(batch) => {
+                        let message = (batch, stream.bytes_consumed(), stream.total_bytes());
+                        // A closed channel means the receiving side gave up
+                        if batch_tx.blocking_send(Ok(message)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = batch_tx.blocking_send(Err(e));
+                        break;
+                    }
+                }
+            }
+            (
+                completed.then(|| stream.take_multilingual_channels()),
+                stream.stats(),
+            )
+        });
+
+        let txn =
+            self.database.connection().begin().await.map_err(|e| {
+                anyhow::anyhow!("Failed to begin transaction for EPG programs: {}", e)
+            })?;
+
+        let delete_result = EpgPrograms::delete_many()
+            .filter(epg_programs::Column::SourceId.eq(source_id))
+            .exec(&txn)
+            .await?;
+        debug!(
+            "Deleted {} existing EPG programs for source {}",
+            delete_result.rows_affected, source_id
+        );
+
+        let mut total_saved = 0;
+        while let Some(message) = batch_rx.recv().await {
+            let (batch, consumed, total) =
+                message.map_err(|e| anyhow::anyhow!("Failed to parse XMLTV: {}", e))?;
+            total_saved +=
+                Self::insert_epg_programs_batch_in_transaction(batch, &txn, None, None).await?;
+
+            if let Some(updater) = progress_updater
+                && total > 0
+            {
+                let fraction = (consumed as f64 / total as f64).min(1.0);
+                updater
+                    .update_progress(
+                        10.0 + fraction * 90.0,
+                        &format!(
+                            "Processed {:.0}% of XMLTV data ({} programs saved)",
+                            fraction * 100.0,
+                            total_saved
+                        ),
+                    )
+                    .await;
+            }
+        }
+        let (channels, stats) = parser
+            .await
+            .map_err(|e| anyhow::anyhow!("XMLTV parser task failed: {}", e))?;
+
+        if total_saved == 0 {
+            // Keep the existing programs rather than wiping the guide; the transaction
+            // rolls back when dropped
+            debug!("No EPG programs to save for source: {}", source_id);
+            return Ok((0, stats));
+        }
+
+        if let Some(channels) = channels {
+            let saved_channels =
+                EpgChannelMetadataSeaOrmRepository::replace_for_source(&txn, source_id, &channels)
+                    .await?;
+            debug!(
+                "Saved metadata of {} multilingual EPG channels for source {}",
+                saved_channels, source_id
+            );
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to commit EPG programs transaction: {}", e))?;
+        debug!(
+            "Successfully saved {} EPG programs for source: {} (streamed)",
+            total_saved, source_id
+        );
+        Ok((total_saved, stats))
+    }
+
     /// Insert EPG programs in a transaction (helper method for atomic operations)
     async fn insert_epg_programs_batch_in_transaction(
         programs: Vec<crate::models::EpgProgram>,
@@ -653,11 +899,221 @@ impl EpgSourceService {
         Ok(total_inserted)
     }
 
+    /// Find the file-backed EPG source for a watch folder import name, creating it if needed
+    pub async fn ensure_file_backed_source(&self, import_name: &str) -> Result<EpgSource> {
+        let url = EpgSource::file_backed_url(import_name);
+
+        if let Some(existing) = self
+            .epg_source_repo
+            .find_all()
+            .await?
+            .into_iter()
+            .find(|source| source.url == url)
+        {
+            return Ok(existing);
+        }
+
+        let source = self
+            .epg_source_repo
+            .create(EpgSourceCreateRequest {
+                name: import_name.to_string(),
+                source_type: crate::models::EpgSourceType::Xmltv,
+                url,
+                // Never scheduled (file-backed sources are driven by the watch folder),
+                // but a valid expression keeps the source editable via the API
+                update_cron: "0 0 0 * * * *".to_string(),
+                username: None,
+                password: None,
+                timezone: None,
+                time_offset: None,
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create file-backed EPG source: {}", e))?;
+
+        let _ = self.cache_invalidation_tx.send(CachedEntity::EpgSources);
+        info!(
+            "Created file-backed EPG source '{}' ({}) for watch folder import",
+            source.name, source.id
+        );
+
+        Ok(source)
+    }
+
+    /// Replace a source's programmes with those parsed from an archived XMLTV guide
+    ///
+    /// Runs the current parser over the snapshot; `last_ingested_at` is left untouched as
+    /// nothing was fetched.
+    pub async fn replay_snapshot(
+        &self,
+        source: &EpgSource,
+        snapshot: &IngestSnapshot,
+    ) -> Result<usize> {
+        let archive = self
+            .ingest_archive
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Ingest archive is not enabled"))?;
+        if source.source_type != EpgSourceType::Xmltv {
+            return Err(anyhow::anyhow!(
+                "Snapshots can only be replayed into XMLTV sources"
+            ));
+        }
+
+        let stream = XmltvProgramStream::open_file(
+            source,
+            &archive.snapshot_path(snapshot)?,
+            self.http_client_factory.max_decompressed_bytes(),
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to open snapshot {}: {}", snapshot.id, e))?;
+        let (programs_saved, _) = self
+            .save_epg_program_stream(source.id, stream, None)
+            .await?;
+
+        let _ = self.cache_invalidation_tx.send(CachedEntity::EpgSources);
+
+        info!(
+            "Replayed snapshot {} into EPG source '{}': {} programs saved",
+            snapshot.id, source.name, programs_saved
+        );
+        Ok(programs_saved)
+    }
+
+    /// Ingest programs for a file-backed source from locally supplied XMLTV bytes
+    ///
+    /// Returns the programmes saved and the parse counts (duplicates, defaulted durations).
+    pub async fn ingest_local_xmltv(
+        &self,
+        source: &EpgSource,
+        bytes: bytes::Bytes,
+    ) -> Result<(usize, XmltvParseStats)> {
+        let total_bytes = bytes.len() as u64;
+        let stream = XmltvProgramStream::new(
+            source,
+            std::io::Cursor::new(bytes),
+            total_bytes,
+            self.http_client_factory.max_decompressed_bytes(),
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to parse XMLTV file: {}", e))?;
+        self.ingest_local_xmltv_stream(source, stream, None).await
+    }
+
+    /// Ingest programs for a file-backed source from a local XMLTV file, read as it is parsed
+    pub async fn ingest_local_xmltv_file(
+        &self,
+        source: &EpgSource,
+        path: &std::path::Path,
+        progress_updater: Option<&crate::services::progress_service::ProgressStageUpdater>,
+    ) -> Result<usize> {
+        let stream = XmltvProgramStream::open_file(
+            source,
+            path,
+            self.http_client_factory.max_decompressed_bytes(),
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to parse XMLTV file: {}", e))?;
+        self.ingest_local_xmltv_stream(source, stream, progress_updater)
+            .await
+            .map(|(saved, _)| saved)
+    }
+
+    async fn ingest_local_xmltv_stream(
+        &self,
+        source: &EpgSource,
+        stream: XmltvProgramStream,
+        progress_updater: Option<&crate::services::progress_service::ProgressStageUpdater>,
+    ) -> Result<(usize, XmltvParseStats)> {
+        let (programs_saved, stats) = self
+            .save_epg_program_stream(source.id, stream, progress_updater)
+            .await?;
+
+        if let Err(e) = self
+            .epg_source_repo
+            .update_last_ingested_at(&source.id)
+            .await
+        {
+            error!(
+                "Failed to update last_ingested_at for EPG source '{}': {}",
+                source.name, e
+            );
+        }
+
+        // Invalidate cache since we updated EPG programs - this triggers proxy auto-regeneration
+        let _ = self.cache_invalidation_tx.send(CachedEntity::EpgSources);
+
+        info!(
+            "Imported {} programs into file-backed EPG source '{}'",
+            programs_saved, source.name
+        );
+
+        Ok((programs_saved, stats))
+    }
+
     /// Ingest EPG programs using ProgressStageUpdater (new API)
+    ///
+    /// The outcome is recorded in the ingestion history when one is attached. Programmes are
+    /// replaced wholesale, so a run reports the saved programmes as added and the previously
+    /// stored ones as removed.
     pub async fn ingest_programs_with_progress_updater(
         &self,
         source: &EpgSource,
         progress_updater: Option<&crate::services::progress_service::ProgressStageUpdater>,
+    ) -> Result<usize> {
+        let Some(history) = &self.ingestion_history else {
+            return self
+                .ingest_programs(
+                    source,
+                    progress_updater,
+                    &mut IngestionRunOutcome::default(),
+                )
+                .await;
+        };
+
+        let started_at = chrono::Utc::now();
+        let previous = self.count_programs(source.id).await.ok();
+        let mut outcome = IngestionRunOutcome::default();
+        let result = self
+            .ingest_programs(source, progress_updater, &mut outcome)
+            .await;
+        match &result {
+            // An empty guide keeps the stored programmes
+            Ok(0) => outcome.changes = Some(RecordChangeSummary::default()),
+            Ok(saved) => {
+                outcome.changes = previous.map(|removed| RecordChangeSummary {
+                    added: *saved,
+                    updated: 0,
+                    removed,
+                })
+            }
+            Err(e) => outcome.error = Some(e.to_string()),
+        }
+
+        if let Err(e) = history
+            .record(source.id, IngestionSourceKind::Epg, started_at, outcome)
+            .await
+        {
+            warn!(
+                "Failed to record ingestion run for EPG source '{}': {}",
+                source.name, e
+            );
+        }
+        result
+    }
+
+    /// Number of programmes currently stored for a source
+    async fn count_programs(&self, source_id: uuid::Uuid) -> Result<usize> {
+        use crate::entities::{epg_programs, prelude::*};
+        use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+        let count = EpgPrograms::find()
+            .filter(epg_programs::Column::SourceId.eq(source_id))
+            .count(&*self.database.connection())
+            .await?;
+        Ok(count as usize)
+    }
+
+    async fn ingest_programs(
+        &self,
+        source: &EpgSource,
+        progress_updater: Option<&crate::services::progress_service::ProgressStageUpdater>,
+        outcome: &mut IngestionRunOutcome,
     ) -> Result<usize> {
         use crate::sources::factory::SourceHandlerFactory;
 
@@ -668,45 +1124,50 @@ impl EpgSourceService {
 
         // Wrap the entire operation in error handling to ensure progress completion
         let result = async {
-            // Create EPG source handler using the factory
-            let handler = SourceHandlerFactory::create_epg_handler(
-                &source.source_type,
-                &self.http_client_factory,
-            )
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to create EPG source handler: {}", e))?;
-
-            // Use the new ProgressStageUpdater API
-            let programs = handler
-                .ingest_epg_programs_with_progress_updater(source, progress_updater)
+            let programs_saved = if source.source_type == EpgSourceType::Xmltv {
+                // XMLTV guides can be gigabytes; parse and insert them incrementally
+                self.ingest_xmltv_streaming(source, progress_updater, outcome)
+                    .await?
+            } else {
+                // Create EPG source handler using the factory
+                let handler = SourceHandlerFactory::create_epg_handler(
+                    &source.source_type,
+                    &self.http_client_factory,
+                )
                 .await
-                .map_err(|e| anyhow::anyhow!("EPG source handler failed: {}", e))?;
+                .map_err(|e| anyhow::anyhow!("Failed to create EPG source handler: {}", e))?;
 
-            debug!(
-                "EPG handler ingested {} programs from source '{}'",
-                programs.len(),
-                source.name
-            );
+                // Use the new ProgressStageUpdater API
+                let programs = handler
+                    .ingest_epg_programs_with_progress_updater(source, progress_updater)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("EPG source handler failed: {}", e))?;
 
-            // Update progress: inserting to database (this is 80% of the total work)
-            if let Some(updater) = progress_updater {
-                updater
-                    .update_progress(
-                        20.0,
-                        &format!("Inserting {} programs to database", programs.len()),
-                    )
-                    .await;
-            }
+                debug!(
+                    "EPG handler ingested {} programs from source '{}'",
+                    programs.len(),
+                    source.name
+                );
 
-            // Save programs to database
-            debug!(
-                "Saving {} EPG programs to database for '{}'",
-                programs.len(),
-                source.name
-            );
-            let programs_saved = self
-                .save_epg_programs(source.id, programs, progress_updater)
-                .await?;
+                // Update progress: inserting to database (this is 80% of the total work)
+                if let Some(updater) = progress_updater {
+                    updater
+                        .update_progress(
+                            20.0,
+                            &format!("Inserting {} programs to database", programs.len()),
+                        )
+                        .await;
+                }
+
+                // Save programs to database
+                debug!(
+                    "Saving {} EPG programs to database for '{}'",
+                    programs.len(),
+                    source.name
+                );
+                self.save_epg_programs(source.id, programs, progress_updater)
+                    .await?
+            };
 
             // Mark stage as completed
             if let Some(updater) = progress_updater {
@@ -745,7 +1206,7 @@ impl EpgSourceService {
             }
 
             // Invalidate cache since we updated EPG programs - this triggers proxy auto-regeneration
-            let _ = self.cache_invalidation_tx.send(());
+            let _ = self.cache_invalidation_tx.send(CachedEntity::EpgSources);
 
             info!(
                 "EPG ingestion completed for source '{}': {} programs saved",
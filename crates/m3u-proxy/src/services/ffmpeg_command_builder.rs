@@ -4,7 +4,9 @@
 //! separating command generation logic from data models.
 
 use crate::{
-    models::relay::{AudioCodec, HwAccelCapabilities, ResolvedRelayConfig, VideoCodec},
+    models::relay::{
+        AudioCodec, HwAccelCapabilities, ResolvedRelayConfig, SubtitleMode, VideoCodec,
+    },
     services::{StreamMappingStrategy, StreamProber},
 };
 use serde_json;
@@ -53,11 +55,26 @@ impl FFmpegCommandBuilder {
             args.extend(hwaccel_args);
         }
 
+        // Add the source's upstream headers (input options, so they precede -i)
+        if let Some(overrides) = &config.upstream_headers {
+            args.extend(Self::upstream_header_args(overrides));
+        }
+
+        // Teletext decodes to bitmaps unless text output is requested
+        if config.profile.subtitle_mode == SubtitleMode::Convert
+            && config
+                .profile
+                .subtitle_codec
+                .is_some_and(|codec| codec.is_text())
+        {
+            args.extend(["-txt_format".to_string(), "text".to_string()]);
+        }
+
         // Add input arguments with analysis parameters
         self.add_input_args(&mut args, input_url);
 
         // Add stream mapping
-        self.add_stream_mapping(&mut args, mapping_strategy);
+        self.add_stream_mapping(&mut args, &config.profile, mapping_strategy);
 
         // Add video codec and settings
         self.add_video_codec_args(&mut args, &config.profile, hwaccel_caps, mapping_strategy);
@@ -78,6 +95,9 @@ impl FFmpegCommandBuilder {
         // Add audio codec and settings
         self.add_audio_codec_args(&mut args, &config.profile, mapping_strategy);
 
+        // Subtitles are copied unless the profile converts them
+        self.add_subtitle_codec_args(&mut args, &config.profile, mapping_strategy);
+
         // Add transport stream settings
         self.add_transport_stream_args(&mut args);
 
@@ -106,6 +126,7 @@ impl FFmpegCommandBuilder {
                     Some(StreamMappingStrategy {
                         video_mapping: Some("0:v:0".to_string()),
                         audio_mapping: Some("0:a:0".to_string()),
+                        subtitle_mappings: Vec::new(),
                         video_copy: false,
                         audio_copy: false,
                         target_video_bitrate: None,
@@ -135,10 +156,33 @@ impl FFmpegCommandBuilder {
         ]);
     }
 
+    /// HTTP input options carrying a source's streaming header overrides
+    ///
+    /// The Referer travels in `-headers` with the extra headers; each line is CRLF-terminated
+    /// as ffmpeg expects.
+    fn upstream_header_args(
+        overrides: &crate::models::stream_headers::StreamHeaderOverrides,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(user_agent) = &overrides.user_agent {
+            args.extend(["-user_agent".to_string(), user_agent.clone()]);
+        }
+        let headers: String = overrides
+            .header_pairs()
+            .into_iter()
+            .map(|(name, value)| format!("{name}: {value}\r\n"))
+            .collect();
+        if !headers.is_empty() {
+            args.extend(["-headers".to_string(), headers]);
+        }
+        args
+    }
+
     /// Add stream mapping arguments
     fn add_stream_mapping(
         &self,
         args: &mut Vec<String>,
+        profile: &crate::models::relay::RelayProfile,
         mapping_strategy: Option<&StreamMappingStrategy>,
     ) {
         if let Some(strategy) = mapping_strategy {
@@ -149,11 +193,65 @@ impl FFmpegCommandBuilder {
             if let Some(ref audio_mapping) = strategy.audio_mapping {
                 args.extend(["-map".to_string(), audio_mapping.clone()]);
             }
+            for subtitle_mapping in &strategy.subtitle_mappings {
+                args.extend(["-map".to_string(), subtitle_mapping.clone()]);
+            }
         } else {
-            // Fallback to hardcoded mapping (legacy behavior)
+            // Fallback to hardcoded mapping (legacy behavior); without a probe only a
+            // forced track index can be honoured
             args.extend(["-map".to_string(), "0:v:0".to_string()]); // First video stream
-            args.extend(["-map".to_string(), "0:a:0".to_string()]); // First audio stream
+            args.extend([
+                "-map".to_string(),
+                format!("0:a:{}", profile.audio_track_index.unwrap_or(0)),
+            ]);
+            // Trailing '?' keeps ffmpeg running when the track is absent
+            match profile.subtitle_mode {
+                SubtitleMode::Selected => {
+                    if let Some(index) = profile.subtitle_track_index {
+                        args.extend(["-map".to_string(), format!("0:s:{index}?")]);
+                    }
+                }
+                // Unprobed inputs may carry tracks MPEG-TS cannot hold; ffmpeg then fails
+                // and the relay log shows why
+                SubtitleMode::Preserve => {
+                    args.extend(["-map".to_string(), "0:s?".to_string()]);
+                }
+                SubtitleMode::Convert => {
+                    let index = profile.subtitle_track_index.unwrap_or(0);
+                    args.extend(["-map".to_string(), format!("0:s:{index}?")]);
+                }
+                SubtitleMode::Strip => {}
+            }
+        }
+    }
+
+    /// Add subtitle codec arguments for the mapped subtitle tracks
+    fn add_subtitle_codec_args(
+        &self,
+        args: &mut Vec<String>,
+        profile: &crate::models::relay::RelayProfile,
+        mapping_strategy: Option<&StreamMappingStrategy>,
+    ) {
+        let maps_subtitles = match mapping_strategy {
+            Some(strategy) => !strategy.subtitle_mappings.is_empty(),
+            None => match profile.subtitle_mode {
+                SubtitleMode::Selected => profile.subtitle_track_index.is_some(),
+                SubtitleMode::Preserve | SubtitleMode::Convert => true,
+                SubtitleMode::Strip => false,
+            },
+        };
+        if profile.subtitle_mode == SubtitleMode::Strip {
+            args.push("-sn".to_string());
+            return;
+        }
+        if !maps_subtitles {
+            return;
         }
+        let codec = match (profile.subtitle_mode, profile.subtitle_codec) {
+            (SubtitleMode::Convert, Some(codec)) => codec.encoder(),
+            _ => "copy",
+        };
+        args.extend(["-c:s".to_string(), codec.to_string()]);
     }
 
     /// Add video codec arguments
@@ -303,6 +401,32 @@ impl FFmpegCommandBuilder {
         }
     }
 
+    /// Device a relay transcodes on: the hardware accelerator encoding its video, "cpu"
+    /// for software transcodes, or `None` when it only copies streams
+    pub fn transcode_device(
+        &self,
+        config: &ResolvedRelayConfig,
+        hwaccel_caps: &HwAccelCapabilities,
+    ) -> Option<String> {
+        let profile = &config.profile;
+        // Legacy argument profiles are opaque, so they are assumed to transcode
+        if config.effective_args.is_empty()
+            && profile.video_codec == VideoCodec::Copy
+            && profile.audio_codec == AudioCodec::Copy
+        {
+            return None;
+        }
+        if profile.enable_hardware_acceleration
+            && profile.video_codec != VideoCodec::Copy
+            && self
+                .get_hwaccel_video_encoder(profile, hwaccel_caps)
+                .is_some()
+        {
+            return self.select_hwaccel(profile, hwaccel_caps);
+        }
+        Some("cpu".to_string())
+    }
+
     /// Generate hardware acceleration arguments (input setup only)
     fn generate_hwaccel_args(
         &self,
@@ -495,6 +619,31 @@ mod tests {
         assert!(args.contains(&"-probesize".to_string()));
     }
 
+    #[test]
+    fn test_upstream_header_args() {
+        let overrides = crate::models::stream_headers::StreamHeaderOverrides {
+            user_agent: Some("SmartTV/1.0".to_string()),
+            referer: Some("https://provider.example/".to_string()),
+            headers: [("X-Token".to_string(), "abc".to_string())].into(),
+            ..crate::models::stream_headers::StreamHeaderOverrides::none(uuid::Uuid::nil())
+        };
+        assert_eq!(
+            FFmpegCommandBuilder::upstream_header_args(&overrides),
+            vec![
+                "-user_agent",
+                "SmartTV/1.0",
+                "-headers",
+                "Referer: https://provider.example/\r\nX-Token: abc\r\n",
+            ]
+        );
+        assert!(
+            FFmpegCommandBuilder::upstream_header_args(
+                &crate::models::stream_headers::StreamHeaderOverrides::none(uuid::Uuid::nil())
+            )
+            .is_empty()
+        );
+    }
+
     #[test]
     fn test_transport_stream_args() {
         let builder = FFmpegCommandBuilder::new(None);
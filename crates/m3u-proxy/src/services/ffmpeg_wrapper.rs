@@ -18,13 +18,14 @@ use tokio::process::Command as TokioCommand;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::config::BufferConfig;
+use crate::config::{BufferConfig, RelayLogConfig};
 use crate::models::relay::ErrorFallbackConfig;
 use crate::models::relay::*;
 use crate::proxy::session_tracker::ClientInfo;
 use crate::services::cyclic_buffer::{BufferClient, CyclicBuffer, CyclicBufferConfig};
 use crate::services::error_fallback::{ErrorFallbackGenerator, StreamHealthMonitor};
 use crate::services::ffmpeg_command_builder::FFmpegCommandBuilder;
+use crate::services::relay_logs::RelayLogBuffer;
 use crate::services::stream_prober::StreamProber;
 use sandboxed_file_manager::SandboxedManager;
 
@@ -152,6 +153,8 @@ pub struct FFmpegProcessWrapper {
     command_builder: FFmpegCommandBuilder,
     /// Optional unified probe persistence service (injected after construction)
     probe_persistence: Option<Arc<ProbePersistenceService>>,
+    /// FFmpeg output capture settings
+    log_config: RelayLogConfig,
 }
 
 impl FFmpegProcessWrapper {
@@ -180,6 +183,7 @@ impl FFmpegProcessWrapper {
             ffmpeg_command,
             command_builder,
             probe_persistence: None,
+            log_config: RelayLogConfig::default(),
         }
     }
 
@@ -188,6 +192,11 @@ impl FFmpegProcessWrapper {
         self.probe_persistence = Some(svc);
     }
 
+    /// Override the FFmpeg output capture settings
+    pub fn set_log_config(&mut self, log_config: RelayLogConfig) {
+        self.log_config = log_config;
+    }
+
     /// Start an FFmpeg process with the given configuration
     pub async fn start_process(
         &self,
@@ -272,18 +281,13 @@ impl FFmpegProcessWrapper {
                     );
 
                     // Generate optimal mapping strategy
-                    let strategy = prober.generate_mapping_strategy(
-                        &probe_result,
-                        &config.profile.video_codec.to_string(),
-                        &config.profile.audio_codec.to_string(),
-                        config.profile.video_bitrate,
-                        config.profile.audio_bitrate,
-                    );
+                    let strategy = prober.generate_mapping_strategy(&probe_result, &config.profile);
 
                     debug!(
-                        "Generated mapping strategy: video_mapping={:?}, audio_mapping={:?}, video_copy={}, audio_copy={}",
+                        "Generated mapping strategy: video_mapping={:?}, audio_mapping={:?}, subtitle_mappings={:?}, video_copy={}, audio_copy={}",
                         strategy.video_mapping,
                         strategy.audio_mapping,
+                        strategy.subtitle_mappings,
                         strategy.video_copy,
                         strategy.audio_copy
                     );
@@ -375,6 +379,7 @@ impl FFmpegProcessWrapper {
             cyclic_buffer.clone(),
         ));
         let health_monitor = Arc::new(StreamHealthMonitor::new(config.config.id, fallback_config));
+        let log_buffer = Arc::new(RelayLogBuffer::new(self.log_config.max_lines));
 
         // Start monitoring stderr for errors with message accumulation
         if let Some(stderr) = child.stderr.take() {
@@ -382,6 +387,7 @@ impl FFmpegProcessWrapper {
             let error_count_clone = error_count.clone();
             let health_monitor_clone = health_monitor.clone();
             let error_fallback_clone = error_fallback.clone();
+            let log_buffer_clone = log_buffer.clone();
 
             tokio::spawn(async move {
                 let reader = BufReader::new(stderr);
@@ -391,6 +397,7 @@ impl FFmpegProcessWrapper {
                 let accumulation_period = tokio::time::Duration::from_millis(100);
 
                 while let Ok(Some(line)) = lines.next_line().await {
+                    log_buffer_clone.push(&line);
                     let line_lower = line.to_lowercase();
                     accumulated_lines.push(line.clone());
 
@@ -519,6 +526,7 @@ impl FFmpegProcessWrapper {
             cyclic_buffer,
             error_fallback,
             health_monitor,
+            log_buffer,
             input_url: input_url.to_string(),
             config_snapshot: config.create_config_snapshot(input_url),
         };
@@ -690,6 +698,8 @@ pub struct FFmpegProcess {
     pub cyclic_buffer: Arc<CyclicBuffer>,
     pub error_fallback: Arc<ErrorFallbackGenerator>,
     pub health_monitor: Arc<StreamHealthMonitor>,
+    /// Captured FFmpeg stderr
+    pub log_buffer: Arc<RelayLogBuffer>,
     pub input_url: String,
     pub config_snapshot: String,
 }
@@ -986,6 +996,14 @@ mod tests {
             max_segments: None,
             input_timeout: 30,
 
+            // Track selection
+            audio_languages: Vec::new(),
+            audio_track_index: None,
+            subtitle_languages: Vec::new(),
+            subtitle_track_index: None,
+            subtitle_mode: SubtitleMode::default(),
+            subtitle_codec: None,
+
             // System flags
             is_system_default: false,
             is_active: true,
@@ -0,0 +1,345 @@
+//! Pre- and post-generation hooks
+//!
+//! Runs the external commands configured in `generation_hooks` around proxy generations.
+//! Commands run without a shell, with a cleared environment and a timeout, and receive a
+//! JSON summary of the generation on stdin so they can e.g. push the published output to
+//! external systems.
+//!
+//! Hooks are external commands only; there is no WASM hook stage, as the server embeds no
+//! WASM runtime. A WASM module can be run through a runtime's CLI as the hook command.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::config::{
+    GenerationHookConfig, GenerationHookStage, GenerationHooksConfig, StorageConfig,
+};
+use crate::database::repositories::StreamProxySeaOrmRepository;
+use crate::pipeline::models::PipelineExecution;
+
+/// Hook output kept for the log, per stream
+const MAX_LOGGED_OUTPUT: usize = 2048;
+
+/// Summary of a generation handed to hooks on stdin
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationHookSummary {
+    pub stage: GenerationHookStage,
+    pub proxy_id: Uuid,
+    pub proxy_name: String,
+    /// Pipeline execution, for post-generation hooks
+    pub execution_id: Option<Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Channels in the published playlist
+    pub channel_count: Option<usize>,
+    /// Published files, when the output is stored on local disk
+    pub m3u_file: Option<PathBuf>,
+    pub xmltv_file: Option<PathBuf>,
+}
+
+impl GenerationHookSummary {
+    /// Summary for hooks run before a generation starts
+    pub fn pre(proxy_id: Uuid, proxy_name: &str) -> Self {
+        Self {
+            stage: GenerationHookStage::Pre,
+            proxy_id,
+            proxy_name: proxy_name.to_string(),
+            execution_id: None,
+            started_at: Utc::now(),
+            completed_at: None,
+            channel_count: None,
+            m3u_file: None,
+            xmltv_file: None,
+        }
+    }
+
+    /// Summary of a completed generation
+    pub fn post(proxy_name: &str, execution: &PipelineExecution, storage: &StorageConfig) -> Self {
+        let local_output = storage.object_storage_for("m3u").is_none();
+        let output_file = |extension: &str| {
+            local_output.then(|| {
+                storage
+                    .m3u_path
+                    .join(format!("{}.{extension}", execution.proxy_id))
+            })
+        };
+        Self {
+            stage: GenerationHookStage::Post,
+            proxy_id: execution.proxy_id,
+            proxy_name: proxy_name.to_string(),
+            execution_id: Some(execution.id),
+            started_at: execution.started_at,
+            completed_at: execution.completed_at,
+            channel_count: execution.published_channel_count(),
+            m3u_file: output_file("m3u8"),
+            xmltv_file: output_file("xmltv"),
+        }
+    }
+}
+
+/// Runs configured generation hooks; cheap to clone
+#[derive(Debug, Clone)]
+pub struct GenerationHookService {
+    config: GenerationHooksConfig,
+}
+
+impl GenerationHookService {
+    pub fn new(config: GenerationHooksConfig) -> Self {
+        Self { config }
+    }
+
+    /// Hook service for the app config, if any hooks are configured
+    pub fn from_config(config: &crate::config::Config) -> Option<Self> {
+        config
+            .generation_hooks
+            .clone()
+            .filter(|hooks| !hooks.hooks.is_empty())
+            .map(Self::new)
+    }
+
+    /// Warn about hook `proxies` entries that match no proxy
+    ///
+    /// Such hooks silently never run for the proxy they were meant for, typically after it
+    /// was deleted, renamed or recreated with a new id.
+    pub async fn warn_unknown_proxies(&self, proxy_repo: &StreamProxySeaOrmRepository) {
+        let proxies: Vec<(String, String)> = match proxy_repo.list_all().await {
+            Ok(proxies) => proxies
+                .into_iter()
+                .map(|proxy| (proxy.id.to_string(), proxy.name))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to check generation hook proxies: {}", e);
+                return;
+            }
+        };
+        for (hook, proxy) in unknown_proxies(&self.config, &proxies) {
+            warn!(
+                "Generation hook '{}' lists proxy '{}', which matches no proxy id or name",
+                hook, proxy
+            );
+        }
+    }
+
+    /// Run the hooks of the summary's stage that apply to its proxy, in configured order
+    ///
+    /// Fails on the first failing `required` pre-generation hook; other failures are
+    /// logged and skipped.
+    pub async fn run(&self, summary: &GenerationHookSummary) -> Result<(), String> {
+        let payload = match serde_json::to_vec(summary) {
+            Ok(payload) => payload,
+            Err(e) => return Err(format!("Failed to serialize generation summary: {e}")),
+        };
+
+        for hook in self
+            .config
+            .hooks_for(summary.stage, &summary.proxy_id, &summary.proxy_name)
+        {
+            match run_hook(hook, summary, &payload).await {
+                Ok(()) => info!(
+                    "Generation hook '{}' completed for proxy '{}'",
+                    hook.name, summary.proxy_name
+                ),
+                Err(e) if hook.required && summary.stage == GenerationHookStage::Pre => {
+                    return Err(format!(
+                        "Required generation hook '{}' failed: {e}",
+                        hook.name
+                    ));
+                }
+                Err(e) => warn!(
+                    "Generation hook '{}' failed for proxy '{}': {}",
+                    hook.name, summary.proxy_name, e
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn run_hook(
+    hook: &GenerationHookConfig,
+    summary: &GenerationHookSummary,
+    payload: &[u8],
+) -> Result<(), String> {
+    let (program, args) = hook
+        .command
+        .split_first()
+        .ok_or_else(|| "No command configured".to_string())?;
+
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(args)
+        .env_clear()
+        .envs(&hook.env)
+        .env("M3U_PROXY_HOOK_STAGE", stage_name(summary.stage))
+        .env("M3U_PROXY_HOOK_PROXY_ID", summary.proxy_id.to_string())
+        .env("M3U_PROXY_HOOK_PROXY_NAME", &summary.proxy_name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if !hook.env.contains_key("PATH")
+        && let Ok(path) = std::env::var("PATH")
+    {
+        cmd.env("PATH", path);
+    }
+    if let Some(dir) = &hook.working_dir {
+        cmd.current_dir(dir);
+    }
+
+    debug!(
+        "Running generation hook '{}': {:?}",
+        hook.name, hook.command
+    );
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {program}: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Hooks that ignore the summary may exit before reading it
+        if let Err(e) = stdin.write_all(payload).await {
+            debug!(
+                "Generation hook '{}' did not read its input: {}",
+                hook.name, e
+            );
+        }
+    }
+
+    let timeout = hook.timeout_duration();
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| format!("Timed out after {}", humantime::format_duration(timeout)))?
+        .map_err(|e| format!("Failed to wait for {program}: {e}"))?;
+
+    let stdout = truncated(&output.stdout);
+    if !stdout.is_empty() {
+        debug!("Generation hook '{}' output: {}", hook.name, stdout);
+    }
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} ({})", output.status, truncated(&output.stderr)))
+    }
+}
+
+fn stage_name(stage: GenerationHookStage) -> &'static str {
+    match stage {
+        GenerationHookStage::Pre => "pre",
+        GenerationHookStage::Post => "post",
+    }
+}
+
+/// (hook name, entry) pairs of hook `proxies` entries matching none of the (id, name) pairs
+fn unknown_proxies<'a>(
+    config: &'a GenerationHooksConfig,
+    proxies: &[(String, String)],
+) -> Vec<(&'a str, &'a str)> {
+    config
+        .hooks
+        .iter()
+        .flat_map(|hook| hook.proxies.iter().map(move |entry| (hook, entry)))
+        .filter(|(_, entry)| {
+            !proxies.iter().any(|(id, name)| {
+                entry.eq_ignore_ascii_case(id) || entry.eq_ignore_ascii_case(name)
+            })
+        })
+        .map(|(hook, entry)| (hook.name.as_str(), entry.as_str()))
+        .collect()
+}
+
+fn truncated(output: &[u8]) -> String {
+    let text = String::from_utf8_lossy(output);
+    let text = text.trim();
+    match text.char_indices().nth(MAX_LOGGED_OUTPUT) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(stage: GenerationHookStage, command: &[&str], required: bool) -> GenerationHookConfig {
+        GenerationHookConfig {
+            name: "test".to_string(),
+            stage,
+            command: command.iter().map(|s| s.to_string()).collect(),
+            proxies: Vec::new(),
+            env: Default::default(),
+            working_dir: None,
+            timeout: "5s".to_string(),
+            required,
+        }
+    }
+
+    #[test]
+    fn test_hooks_filter_by_stage_and_proxy() {
+        let proxy_id = Uuid::new_v4();
+        let mut scoped = hook(GenerationHookStage::Post, &["true"], false);
+        scoped.proxies = vec!["Living Room".to_string()];
+        let config = GenerationHooksConfig {
+            hooks: vec![
+                hook(GenerationHookStage::Pre, &["true"], false),
+                hook(GenerationHookStage::Post, &["true"], false),
+                scoped,
+            ],
+        };
+
+        assert_eq!(
+            config
+                .hooks_for(GenerationHookStage::Post, &proxy_id, "living room")
+                .count(),
+            2
+        );
+        assert_eq!(
+            config
+                .hooks_for(GenerationHookStage::Post, &proxy_id, "Kitchen")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_hook_validation_and_unknown_proxies() {
+        let mut scoped = hook(GenerationHookStage::Post, &["true"], false);
+        scoped.proxies = vec!["Living Room".to_string(), Uuid::nil().to_string()];
+        let mut config = GenerationHooksConfig {
+            hooks: vec![scoped],
+        };
+        assert!(config.validate().is_ok());
+
+        let proxies = vec![(Uuid::new_v4().to_string(), "living room".to_string())];
+        assert_eq!(
+            unknown_proxies(&config, &proxies),
+            vec![("test", Uuid::nil().to_string().as_str())]
+        );
+
+        config.hooks[0].timeout = "soon".to_string();
+        assert!(config.validate().is_err());
+        config.hooks[0].timeout = "5s".to_string();
+        config.hooks[0].command.clear();
+        assert!(config.validate().is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_required_pre_hook_failure_aborts() {
+        let summary = GenerationHookSummary::pre(Uuid::new_v4(), "Living Room");
+        let optional = GenerationHookService::new(GenerationHooksConfig {
+            hooks: vec![hook(GenerationHookStage::Pre, &["false"], false)],
+        });
+        assert!(optional.run(&summary).await.is_ok());
+
+        let required = GenerationHookService::new(GenerationHooksConfig {
+            hooks: vec![
+                hook(GenerationHookStage::Pre, &["cat"], true),
+                hook(GenerationHookStage::Pre, &["false"], true),
+            ],
+        });
+        assert!(required.run(&summary).await.is_err());
+    }
+}
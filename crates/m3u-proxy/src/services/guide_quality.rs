@@ -0,0 +1,283 @@
+//! Guide data quality analysis
+//!
+//! Cross-references the channels of a proxy's generated playlist with the programmes of
+//! its generated XMLTV to surface guide problems: channels without any guide data,
+//! channels whose guide has run out, programmes lacking descriptions or categories and
+//! how far ahead each channel's guide reaches.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::errors::AppResult;
+use crate::sources::xmltv_epg::parse_xmltv_time;
+use crate::utils::xmltv_parser::XmltvProgramReader;
+
+/// Guide coverage of a single playlist channel
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ChannelGuideCoverage {
+    pub channel_name: String,
+    pub group_title: Option<String>,
+    pub tvg_id: Option<String>,
+    /// Whether the guide contains any programmes for the channel's tvg-id
+    pub mapped: bool,
+    pub programme_count: usize,
+    /// Programmes that have not finished yet
+    pub upcoming_programmes: usize,
+    /// End of the last programme in the guide
+    pub guide_ends_at: Option<DateTime<Utc>>,
+    /// Hours of guide data left from the time of analysis (0 once the guide has run out)
+    pub horizon_hours: Option<f64>,
+}
+
+impl ChannelGuideCoverage {
+    /// Whether the channel shows no guide data to viewers right now or soon
+    pub fn has_issue(&self) -> bool {
+        !self.mapped || self.upcoming_programmes == 0
+    }
+}
+
+/// Guide data quality report of a proxy's latest generation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GuideQualityReport {
+    /// When the analyzed output was generated
+    pub generated_at: Option<DateTime<Utc>>,
+    pub analyzed_at: DateTime<Utc>,
+    pub total_channels: usize,
+    /// Channels whose tvg-id has programmes in the guide
+    pub mapped_channels: usize,
+    /// Share of channels with guide data, in percent
+    pub epg_coverage_percent: f64,
+    /// Mapped channels whose programmes have all finished
+    pub channels_without_upcoming_programmes: usize,
+    pub total_programmes: usize,
+    pub programmes_missing_description: usize,
+    pub programmes_missing_category: usize,
+    /// Guide programmes whose channel is not in the playlist
+    pub orphaned_programmes: usize,
+    /// Shortest remaining guide horizon among mapped channels, in hours
+    pub shortest_horizon_hours: Option<f64>,
+    /// Per-channel coverage, channels with problems first
+    pub channels: Vec<ChannelGuideCoverage>,
+}
+
+/// Programme statistics of one guide channel
+#[derive(Default)]
+struct GuideChannelStats {
+    programmes: usize,
+    upcoming: usize,
+    ends_at: Option<DateTime<Utc>>,
+}
+
+/// A channel entry of a generated playlist
+struct PlaylistChannel {
+    name: String,
+    group_title: Option<String>,
+    tvg_id: Option<String>,
+}
+
+/// Analyze generated playlist and XMLTV content as of `now`
+pub fn analyze_guide(m3u: &str, xmltv: &str, now: DateTime<Utc>) -> AppResult<GuideQualityReport> {
+    let playlist = playlist_channels(m3u);
+
+    let mut guide: HashMap<String, GuideChannelStats> = HashMap::new();
+    let mut total_programmes = 0;
+    let mut missing_description = 0;
+    let mut missing_category = 0;
+
+    let mut reader = XmltvProgramReader::new(xmltv.as_bytes());
+    while let Some(program) = reader.next_program()? {
+        total_programmes += 1;
+        if program
+            .description
+            .as_deref()
+            .is_none_or(|d| d.trim().is_empty())
+        {
+            missing_description += 1;
+        }
+        if program
+            .category
+            .as_deref()
+            .is_none_or(|c| c.trim().is_empty())
+        {
+            missing_category += 1;
+        }
+
+        let start = parse_xmltv_time(&program.start).ok();
+        let stop = program
+            .stop
+            .as_deref()
+            .and_then(|stop| parse_xmltv_time(stop).ok());
+        let stats = guide.entry(program.channel).or_default();
+        stats.programmes += 1;
+        if stop.or(start).is_some_and(|end| end > now) {
+            stats.upcoming += 1;
+        }
+        if let Some(end) = stop.or(start) {
+            stats.ends_at = Some(stats.ends_at.map_or(end, |current| current.max(end)));
+        }
+    }
+
+    let mut channels: Vec<ChannelGuideCoverage> = playlist
+        .into_iter()
+        .map(|channel| {
+            let stats = channel.tvg_id.as_ref().and_then(|id| guide.get(id));
+            let guide_ends_at = stats.and_then(|s| s.ends_at);
+            ChannelGuideCoverage {
+                channel_name: channel.name,
+                group_title: channel.group_title,
+                mapped: stats.is_some(),
+                programme_count: stats.map_or(0, |s| s.programmes),
+                upcoming_programmes: stats.map_or(0, |s| s.upcoming),
+                guide_ends_at,
+                horizon_hours: guide_ends_at.map(|end| {
+                    ((end - now).num_minutes().max(0) as f64 / 60.0 * 10.0).round() / 10.0
+                }),
+                tvg_id: channel.tvg_id,
+            }
+        })
+        .collect();
+
+    let total_channels = channels.len();
+    let mapped_channels = channels.iter().filter(|c| c.mapped).count();
+    let channels_without_upcoming_programmes = channels
+        .iter()
+        .filter(|c| c.mapped && c.upcoming_programmes == 0)
+        .count();
+    let shortest_horizon_hours = channels
+        .iter()
+        .filter_map(|c| c.horizon_hours)
+        .min_by(|a, b| a.total_cmp(b));
+
+    let playlist_ids: std::collections::HashSet<&str> = channels
+        .iter()
+        .filter_map(|c| c.tvg_id.as_deref())
+        .collect();
+    let orphaned_programmes = guide
+        .iter()
+        .filter(|(id, _)| !playlist_ids.contains(id.as_str()))
+        .map(|(_, stats)| stats.programmes)
+        .sum();
+
+    channels.sort_by(|a, b| {
+        b.has_issue()
+            .cmp(&a.has_issue())
+            .then_with(|| {
+                let horizon = |c: &ChannelGuideCoverage| c.horizon_hours.unwrap_or(f64::MAX);
+                horizon(a).total_cmp(&horizon(b))
+            })
+            .then_with(|| a.channel_name.cmp(&b.channel_name))
+    });
+
+    let epg_coverage_percent = if total_channels == 0 {
+        0.0
+    } else {
+        (mapped_channels as f64 / total_channels as f64 * 1000.0).round() / 10.0
+    };
+
+    Ok(GuideQualityReport {
+        generated_at: None,
+        analyzed_at: now,
+        total_channels,
+        mapped_channels,
+        epg_coverage_percent,
+        channels_without_upcoming_programmes,
+        total_programmes,
+        programmes_missing_description: missing_description,
+        programmes_missing_category: missing_category,
+        orphaned_programmes,
+        shortest_horizon_hours,
+        channels,
+    })
+}
+
+/// Channel entries of a generated playlist
+fn playlist_channels(m3u: &str) -> Vec<PlaylistChannel> {
+    m3u.lines()
+        .filter_map(|line| line.trim().strip_prefix("#EXTINF:"))
+        .map(|extinf| {
+            // The display name follows the first comma after the last quoted attribute
+            let attributes_end = extinf.rfind('"').map_or(0, |pos| pos + 1);
+            let name = extinf[attributes_end..]
+                .split_once(',')
+                .map_or("", |(_, name)| name)
+                .trim()
+                .to_string();
+            PlaylistChannel {
+                name,
+                group_title: extinf_attribute(extinf, "group-title"),
+                tvg_id: extinf_attribute(extinf, "tvg-id"),
+            }
+        })
+        .collect()
+}
+
+/// Value of a quoted `key="value"` EXTINF attribute, if present and non-empty
+fn extinf_attribute(extinf: &str, key: &str) -> Option<String> {
+    let marker = format!(" {key}=\"");
+    let start = extinf.find(&marker)? + marker.len();
+    let value = &extinf[start..start + extinf[start..].find('"')?];
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const M3U: &str = "#EXTM3U\n\
+        #EXTINF:-1 tvg-id=\"news.uk\" group-title=\"News, UK\",BBC News\nhttp://proxy/1\n\
+        #EXTINF:-1 tvg-id=\"film.uk\",Film4\nhttp://proxy/2\n\
+        #EXTINF:-1 tvg-id=\"gone.uk\",Gone\nhttp://proxy/3\n\
+        #EXTINF:-1,No Guide\nhttp://proxy/4\n";
+
+    const XMLTV: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tv>
+  <programme channel="news.uk" start="20251016100000 +0000" stop="20251016110000 +0000">
+    <title>Morning</title><desc>Headlines</desc><category>News</category>
+  </programme>
+  <programme channel="news.uk" start="20251016110000 +0000" stop="20251017230000 +0000">
+    <title>Day</title>
+  </programme>
+  <programme channel="film.uk" start="20251016120000 +0000" stop="20251016140000 +0000">
+    <title>Film</title><desc>A film</desc>
+  </programme>
+  <programme channel="gone.uk" start="20251015080000 +0000" stop="20251015090000 +0000">
+    <title>Old</title><desc>Old</desc><category>Misc</category>
+  </programme>
+  <programme channel="radio.uk" start="20251016120000 +0000" stop="20251016130000 +0000">
+    <title>Radio</title>
+  </programme>
+</tv>"#;
+
+    #[test]
+    fn test_analyze_guide() {
+        let now = Utc.with_ymd_and_hms(2025, 10, 16, 12, 0, 0).unwrap();
+        let report = analyze_guide(M3U, XMLTV, now).unwrap();
+
+        assert_eq!(report.total_channels, 4);
+        assert_eq!(report.mapped_channels, 3);
+        assert_eq!(report.epg_coverage_percent, 75.0);
+        assert_eq!(report.channels_without_upcoming_programmes, 1);
+        assert_eq!(report.total_programmes, 5);
+        assert_eq!(report.programmes_missing_description, 2);
+        assert_eq!(report.programmes_missing_category, 3);
+        assert_eq!(report.orphaned_programmes, 1);
+        assert_eq!(report.shortest_horizon_hours, Some(0.0));
+
+        // Problem channels come first
+        let names: Vec<&str> = report
+            .channels
+            .iter()
+            .map(|c| c.channel_name.as_str())
+            .collect();
+        assert_eq!(names, ["Gone", "No Guide", "Film4", "BBC News"]);
+
+        let news = &report.channels[3];
+        assert_eq!(news.group_title.as_deref(), Some("News, UK"));
+        assert_eq!((news.programme_count, news.upcoming_programmes), (2, 1));
+        assert_eq!(news.horizon_hours, Some(35.0));
+    }
+}
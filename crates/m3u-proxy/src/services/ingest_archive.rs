@@ -0,0 +1,375 @@
+//! Ingest snapshot archive
+//!
+//! Keeps the raw playlist / XMLTV document downloaded by each ingestion run in a sandboxed
+//! directory (`<kind>/<source_id>/<snapshot_id>.gz`) so the run can be replayed later. Content
+//! is gzip-compressed unless it was already served compressed. Snapshots expire with the
+//! configured retention and only the newest `max_snapshots_per_source` are kept per source.
+
+use anyhow::{Context, Result, anyhow};
+use bytes::Bytes;
+use chrono::Utc;
+use sandboxed_file_manager::SandboxedManager;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::config::IngestArchiveConfig;
+use crate::models::ingest_snapshot::{IngestSnapshot, IngestSnapshotKind};
+use crate::utils::decompression::{CompressionFormat, DecompressionService};
+
+const SNAPSHOT_EXTENSION: &str = ".gz";
+
+/// Archive of raw source downloads
+pub struct IngestArchiveService {
+    config: IngestArchiveConfig,
+    file_manager: SandboxedManager,
+}
+
+impl IngestArchiveService {
+    /// Create the archive service
+    ///
+    /// The file manager should be rooted at the configured archive path and carry the
+    /// retention cleanup policy.
+    pub fn new(config: IngestArchiveConfig, file_manager: SandboxedManager) -> Self {
+        Self {
+            config,
+            file_manager,
+        }
+    }
+
+    /// Archive downloaded content held in memory (M3U playlists)
+    pub async fn archive_bytes(
+        &self,
+        kind: IngestSnapshotKind,
+        source_id: Uuid,
+        content: &[u8],
+    ) -> Result<IngestSnapshot> {
+        let (relative_path, snapshot_id) = self.new_snapshot_path(kind, source_id).await?;
+        let stored = if DecompressionService::detect_compression_format(content)
+            == CompressionFormat::Uncompressed
+        {
+            compress(content)?
+        } else {
+            content.to_vec()
+        };
+        self.file_manager
+            .write(&relative_path, &stored)
+            .await
+            .with_context(|| format!("Failed to write snapshot {relative_path}"))?;
+
+        self.finish_archive(kind, source_id, snapshot_id, stored.len() as u64)
+            .await
+    }
+
+    /// Archive a downloaded file without loading it into memory (XMLTV guides)
+    pub async fn archive_file(
+        &self,
+        kind: IngestSnapshotKind,
+        source_id: Uuid,
+        path: &Path,
+    ) -> Result<IngestSnapshot> {
+        let (relative_path, snapshot_id) = self.new_snapshot_path(kind, source_id).await?;
+        let target = self.file_manager.get_full_path(&relative_path)?;
+        let source = path.to_path_buf();
+
+        let size = tokio::task::spawn_blocking(move || copy_compressed(&source, &target))
+            .await
+            .map_err(|e| anyhow!("Snapshot archive task failed: {e}"))??;
+
+        self.finish_archive(kind, source_id, snapshot_id, size)
+            .await
+    }
+
+    /// Snapshots of a source, newest first
+    pub async fn list(
+        &self,
+        kind: IngestSnapshotKind,
+        source_id: Uuid,
+    ) -> Result<Vec<IngestSnapshot>> {
+        let directory = source_directory(kind, source_id);
+        if !self.file_manager.exists(&directory).await? {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for file in self.file_manager.list_files(&directory).await? {
+            let Some(id) = Path::new(&file)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(SNAPSHOT_EXTENSION))
+            else {
+                continue;
+            };
+            let Some(captured_at) = IngestSnapshot::parse_id(id) else {
+                continue;
+            };
+            let size_bytes = self
+                .file_manager
+                .metadata(&file)
+                .await
+                .map(|m| m.len())
+                .unwrap_or(0);
+            snapshots.push(IngestSnapshot {
+                id: id.to_string(),
+                source_id,
+                kind,
+                captured_at,
+                size_bytes,
+            });
+        }
+
+        snapshots.sort_by(|a, b| b.captured_at.cmp(&a.captured_at));
+        Ok(snapshots)
+    }
+
+    /// Look up a snapshot; `None` when it does not exist (or has expired)
+    pub async fn get(
+        &self,
+        kind: IngestSnapshotKind,
+        source_id: Uuid,
+        snapshot_id: &str,
+    ) -> Result<Option<IngestSnapshot>> {
+        if IngestSnapshot::parse_id(snapshot_id).is_none() {
+            return Ok(None);
+        }
+        Ok(self
+            .list(kind, source_id)
+            .await?
+            .into_iter()
+            .find(|s| s.id == snapshot_id))
+    }
+
+    /// Path of a snapshot's (compressed) file on disk
+    pub fn snapshot_path(&self, snapshot: &IngestSnapshot) -> Result<PathBuf> {
+        Ok(self.file_manager.get_full_path(snapshot_file(
+            snapshot.kind,
+            snapshot.source_id,
+            &snapshot.id,
+        ))?)
+    }
+
+    /// Decompressed content of a snapshot, failing once it exceeds `max_bytes`
+    pub async fn read_text(&self, snapshot: &IngestSnapshot, max_bytes: u64) -> Result<String> {
+        let stored = self
+            .file_manager
+            .read(snapshot_file(
+                snapshot.kind,
+                snapshot.source_id,
+                &snapshot.id,
+            ))
+            .await?;
+        let format = DecompressionService::resolve_format(&stored, None, None)?;
+        let content =
+            DecompressionService::decompress_with_limit(Bytes::from(stored), format, max_bytes)?;
+        String::from_utf8(content).context("Snapshot is not valid UTF-8")
+    }
+
+    async fn new_snapshot_path(
+        &self,
+        kind: IngestSnapshotKind,
+        source_id: Uuid,
+    ) -> Result<(String, String)> {
+        let directory = source_directory(kind, source_id);
+        self.file_manager.create_dir_all(&directory).await?;
+        let snapshot_id = IngestSnapshot::id_for(Utc::now());
+        Ok((snapshot_file(kind, source_id, &snapshot_id), snapshot_id))
+    }
+
+    async fn finish_archive(
+        &self,
+        kind: IngestSnapshotKind,
+        source_id: Uuid,
+        snapshot_id: String,
+        size_bytes: u64,
+    ) -> Result<IngestSnapshot> {
+        debug!(
+            "Archived {} source {} snapshot {} ({} bytes)",
+            kind.as_str(),
+            source_id,
+            snapshot_id,
+            size_bytes
+        );
+        self.prune(kind, source_id).await;
+
+        Ok(IngestSnapshot {
+            id: snapshot_id.clone(),
+            source_id,
+            kind,
+            captured_at: IngestSnapshot::parse_id(&snapshot_id).unwrap_or_else(Utc::now),
+            size_bytes,
+        })
+    }
+
+    /// Remove snapshots beyond the per-source limit
+    async fn prune(&self, kind: IngestSnapshotKind, source_id: Uuid) {
+        let snapshots = match self.list(kind, source_id).await {
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                warn!("Failed to list snapshots of source {}: {}", source_id, e);
+                return;
+            }
+        };
+        for snapshot in snapshots
+            .iter()
+            .skip(self.config.max_snapshots_per_source.max(1))
+        {
+            if let Err(e) = self
+                .file_manager
+                .remove_file(snapshot_file(kind, source_id, &snapshot.id))
+                .await
+            {
+                warn!("Failed to remove snapshot {}: {}", snapshot.id, e);
+            }
+        }
+    }
+}
+
+fn source_directory(kind: IngestSnapshotKind, source_id: Uuid) -> String {
+    format!("{}/{}", kind.as_str(), source_id)
+}
+
+fn snapshot_file(kind: IngestSnapshotKind, source_id: Uuid, snapshot_id: &str) -> String {
+    format!(
+        "{}/{}{}",
+        source_directory(kind, source_id),
+        snapshot_id,
+        SNAPSHOT_EXTENSION
+    )
+}
+
+#[cfg(feature = "compression-gzip")]
+fn compress(content: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(content)?;
+    Ok(encoder.finish()?)
+}
+
+/// Without gzip support snapshots are stored as downloaded
+#[cfg(not(feature = "compression-gzip"))]
+fn compress(content: &[u8]) -> Result<Vec<u8>> {
+    Ok(content.to_vec())
+}
+
+/// Copy `source` to `target`, gzip-compressing it unless already compressed; returns the size written
+fn copy_compressed(source: &Path, target: &Path) -> Result<u64> {
+    let mut input = std::fs::File::open(source)
+        .with_context(|| format!("Failed to open {}", source.display()))?;
+    let mut head = [0u8; 16];
+    let read = input.read(&mut head)?;
+    let already_compressed = DecompressionService::detect_compression_format(&head[..read])
+        != CompressionFormat::Uncompressed;
+    let input = std::io::Cursor::new(head[..read].to_vec()).chain(input);
+
+    let output = std::fs::File::create(target)
+        .with_context(|| format!("Failed to create {}", target.display()))?;
+    write_compressed(input, output, !already_compressed)?;
+    Ok(std::fs::metadata(target)?.len())
+}
+
+#[cfg(feature = "compression-gzip")]
+fn write_compressed(mut input: impl Read, output: std::fs::File, compress: bool) -> Result<()> {
+    let mut output = std::io::BufWriter::new(output);
+    if compress {
+        let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+        std::io::copy(&mut input, &mut encoder)?;
+        encoder.finish()?.flush()?;
+    } else {
+        std::io::copy(&mut input, &mut output)?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "compression-gzip"))]
+fn write_compressed(mut input: impl Read, output: std::fs::File, _compress: bool) -> Result<()> {
+    let mut output = std::io::BufWriter::new(output);
+    std::io::copy(&mut input, &mut output)?;
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn archive(max_snapshots: usize) -> (tempfile::TempDir, IngestArchiveService) {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SandboxedManager::builder()
+            .base_directory(dir.path())
+            .build()
+            .await
+            .unwrap();
+        let config = IngestArchiveConfig {
+            enabled: true,
+            max_snapshots_per_source: max_snapshots,
+            ..Default::default()
+        };
+        (dir, IngestArchiveService::new(config, manager))
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_read_back() {
+        let (_dir, archive) = archive(5).await;
+        let source_id = Uuid::new_v4();
+        let playlist = "#EXTM3U\n#EXTINF:-1,BBC One\nhttp://example.com/1\n";
+
+        let snapshot = archive
+            .archive_bytes(IngestSnapshotKind::Stream, source_id, playlist.as_bytes())
+            .await
+            .unwrap();
+
+        let listed = archive
+            .list(IngestSnapshotKind::Stream, source_id)
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![snapshot.clone()]);
+        assert_eq!(
+            archive.read_text(&snapshot, 1024 * 1024).await.unwrap(),
+            playlist
+        );
+        assert!(
+            archive
+                .list(IngestSnapshotKind::Epg, source_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prunes_to_max_snapshots() {
+        let (_dir, archive) = archive(2).await;
+        let source_id = Uuid::new_v4();
+        for i in 0..3 {
+            archive
+                .archive_bytes(
+                    IngestSnapshotKind::Stream,
+                    source_id,
+                    format!("{i}").as_bytes(),
+                )
+                .await
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        let listed = archive
+            .list(IngestSnapshotKind::Stream, source_id)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(archive.read_text(&listed[0], 16).await.unwrap(), "2");
+    }
+
+    #[tokio::test]
+    async fn test_get_rejects_invalid_ids() {
+        let (_dir, archive) = archive(2).await;
+        assert!(
+            archive
+                .get(IngestSnapshotKind::Stream, Uuid::new_v4(), "../secret")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Leader election for cluster mode
+//!
+//! Several instances may share one PostgreSQL database. The instance holding a session
+//! advisory lock is the leader and runs scheduling, ingestion and regeneration; the others
+//! only serve requests. The lock lives on a connection detached from the pool, so it is
+//! released by PostgreSQL as soon as the leader stops or loses that connection.
+//!
+//! Without cluster mode the election is standalone: the instance is always the leader.
+
+use anyhow::{Result, bail};
+use sea_orm::sqlx::{self, Connection, PgConnection};
+use sea_orm::{DatabaseBackend, DatabaseConnection};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::config::ClusterConfig;
+
+/// Tracks whether this instance is the cluster leader
+pub struct LeaderElection {
+    node_id: String,
+    lock_key: i64,
+    check_interval: Duration,
+    connection: Option<Arc<DatabaseConnection>>,
+    is_leader: AtomicBool,
+    /// Connection holding the advisory lock while leader
+    lock_connection: Mutex<Option<PgConnection>>,
+}
+
+impl LeaderElection {
+    /// Election for a single instance, which always leads
+    pub fn standalone() -> Self {
+        Self {
+            node_id: String::new(),
+            lock_key: 0,
+            check_interval: Duration::from_secs(10),
+            connection: None,
+            is_leader: AtomicBool::new(true),
+            lock_connection: Mutex::new(None),
+        }
+    }
+
+    /// Election among the nodes sharing the database; starts as a follower
+    ///
+    /// Cluster mode needs PostgreSQL, as leadership is an advisory lock.
+    pub fn new(config: &ClusterConfig, connection: Arc<DatabaseConnection>) -> Result<Self> {
+        if connection.get_database_backend() != DatabaseBackend::Postgres {
+            bail!("Cluster mode requires a PostgreSQL database");
+        }
+        Ok(Self {
+            node_id: config.node_id.clone(),
+            lock_key: config.lock_key,
+            check_interval: config.check_interval_duration(),
+            connection: Some(connection),
+            is_leader: AtomicBool::new(false),
+            lock_connection: Mutex::new(None),
+        })
+    }
+
+    /// Whether this instance currently leads
+    pub fn is_leader(&self) -> bool {
+        self.is_leader.load(Ordering::Relaxed)
+    }
+
+    /// Whether the election runs among several nodes
+    pub fn is_clustered(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    /// Contend for leadership until cancelled, then release the lock
+    pub async fn run(&self, cancellation_token: CancellationToken) {
+        if !self.is_clustered() {
+            return;
+        }
+        info!(
+            "Cluster node '{}' contending for leadership (lock key {})",
+            self.node_id, self.lock_key
+        );
+
+        let mut check = tokio::time::interval(self.check_interval);
+        loop {
+            tokio::select! {
+                _ = check.tick() => self.check_leadership().await,
+                _ = cancellation_token.cancelled() => break,
+            }
+        }
+        self.release().await;
+    }
+
+    async fn check_leadership(&self) {
+        let mut lock_connection = self.lock_connection.lock().await;
+
+        // As leader, make sure the connection holding the lock is still alive
+        if let Some(connection) = lock_connection.as_mut() {
+            if let Err(e) = connection.ping().await {
+                warn!(
+                    "Cluster node '{}' lost its leadership connection: {}",
+                    self.node_id, e
+                );
+                *lock_connection = None;
+                self.set_leader(false);
+            }
+            return;
+        }
+
+        match self.try_acquire().await {
+            Ok(Some(connection)) => {
+                *lock_connection = Some(connection);
+                self.set_leader(true);
+            }
+            Ok(None) => {}
+            Err(e) => warn!(
+                "Cluster node '{}' failed to contend for leadership: {}",
+                self.node_id, e
+            ),
+        }
+    }
+
+    /// Take the advisory lock on a dedicated connection; `None` while another node holds it
+    async fn try_acquire(&self) -> Result<Option<PgConnection>> {
+        let Some(connection) = &self.connection else {
+            return Ok(None);
+        };
+        let mut lock_connection = connection
+            .get_postgres_connection_pool()
+            .acquire()
+            .await?
+            .detach();
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(self.lock_key)
+            .fetch_one(&mut lock_connection)
+            .await?;
+        if acquired {
+            Ok(Some(lock_connection))
+        } else {
+            // Closing the detached connection keeps the pool size unchanged
+            let _ = lock_connection.close().await;
+            Ok(None)
+        }
+    }
+
+    async fn release(&self) {
+        let Some(mut connection) = self.lock_connection.lock().await.take() else {
+            return;
+        };
+        self.set_leader(false);
+        if let Err(e) = sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(self.lock_key)
+            .execute(&mut connection)
+            .await
+        {
+            warn!("Failed to release cluster leadership: {}", e);
+        }
+        let _ = connection.close().await;
+        info!("Cluster node '{}' released leadership", self.node_id);
+    }
+
+    fn set_leader(&self, is_leader: bool) {
+        if self.is_leader.swap(is_leader, Ordering::Relaxed) != is_leader {
+            if is_leader {
+                info!("Cluster node '{}' became leader", self.node_id);
+            } else {
+                warn!("Cluster node '{}' is no longer leader", self.node_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_standalone_always_leads() {
+        let election = LeaderElection::standalone();
+        assert!(election.is_leader());
+        assert!(!election.is_clustered());
+        election.run(CancellationToken::new()).await;
+        assert!(election.is_leader());
+    }
+
+    #[tokio::test]
+    async fn test_cluster_mode_requires_postgres() {
+        let connection = sea_orm::Database::connect("sqlite::memory:").await.unwrap();
+        let result = LeaderElection::new(&ClusterConfig::default(), Arc::new(connection));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,458 @@
+//! Bulk logo upload
+//!
+//! Extracts the images of an uploaded ZIP archive, matches their file names to channel
+//! names, tvg-names and tvg-ids (normalised the same way as for EPG mapping) and assigns
+//! matching logos to those channels as uploaded logo assets. Logos without a match are
+//! staged in the temp sandbox under their upload batch until they are assigned by hand;
+//! the temp sandbox's retention clears batches that never are.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use std::sync::Arc;
+
+use sandboxed_file_manager::SandboxedManager;
+use sea_orm::DatabaseConnection;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::database::repositories::{
+    ChannelLogoAssignmentSeaOrmRepository, ChannelSeaOrmRepository,
+};
+use crate::errors::AppError;
+use crate::logo_assets::LogoAssetService;
+use crate::logo_assets::service::CreateAssetWithIdParams;
+use crate::models::channel_epg_mapping::normalize_channel_name;
+use crate::models::channel_logo_assignment::{
+    LogoBulkUploadReport, LogoChannel, MatchedLogo, RejectedLogo, StagedLogo, StagedLogoAssignment,
+};
+use crate::models::logo_asset::LogoAssetType;
+
+/// Largest accepted archive
+pub const MAX_ARCHIVE_BYTES: usize = 100 * 1024 * 1024;
+
+/// Temp sandbox directory holding staged logos, one subdirectory per batch
+const STAGING_DIR: &str = "logo_uploads";
+
+/// Entries read from one archive
+const MAX_ARCHIVE_ENTRIES: usize = 5000;
+
+/// Largest accepted logo (uncompressed)
+pub const MAX_LOGO_BYTES: u64 = 5 * 1024 * 1024;
+
+/// An image taken from an archive
+#[derive(Debug)]
+struct ExtractedLogo {
+    file_name: String,
+    data: Vec<u8>,
+    extension: &'static str,
+}
+
+pub struct LogoBulkUploadService {
+    connection: Arc<DatabaseConnection>,
+    logo_asset_service: LogoAssetService,
+    staging: SandboxedManager,
+}
+
+impl LogoBulkUploadService {
+    pub fn new(
+        connection: Arc<DatabaseConnection>,
+        logo_asset_service: LogoAssetService,
+        staging: SandboxedManager,
+    ) -> Self {
+        Self {
+            connection,
+            logo_asset_service,
+            staging,
+        }
+    }
+
+    /// Fresh path in the staging sandbox to receive an uploaded archive at
+    pub async fn archive_upload_path(&self) -> Result<String, AppError> {
+        self.staging
+            .create_dir_all(STAGING_DIR)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to create staging directory: {e}")))?;
+        Ok(format!("{STAGING_DIR}/{}.zip", Uuid::new_v4()))
+    }
+
+    /// Extract, match and assign the logos of a ZIP archive in the staging sandbox
+    ///
+    /// The archive is read from disk as its entries are extracted, so only the logos
+    /// themselves are held in memory.
+    pub async fn upload(&self, archive_path: &str) -> Result<LogoBulkUploadReport, AppError> {
+        let full_path = self
+            .staging
+            .get_full_path(archive_path)
+            .map_err(|e| AppError::internal(format!("Failed to locate archive: {e}")))?;
+        let (logos, rejected) = tokio::task::spawn_blocking(move || {
+            let file = std::fs::File::open(&full_path)
+                .map_err(|e| format!("Failed to open archive: {e}"))?;
+            extract_logos(BufReader::new(file))
+        })
+        .await
+        .map_err(|e| AppError::internal(format!("Archive extraction failed: {e}")))?
+        .map_err(AppError::validation)?;
+        let index = self.channel_index().await?;
+
+        let mut report = LogoBulkUploadReport {
+            batch_id: Uuid::new_v4(),
+            rejected,
+            ..Default::default()
+        };
+        for logo in logos {
+            let channels = index
+                .get(&normalize_channel_name(file_stem(&logo.file_name)))
+                .cloned()
+                .unwrap_or_default();
+            if channels.is_empty() {
+                if report.unmatched.is_empty() {
+                    let batch_dir = format!("{STAGING_DIR}/{}", report.batch_id);
+                    self.staging.create_dir_all(&batch_dir).await.map_err(|e| {
+                        AppError::internal(format!("Failed to create staging directory: {e}"))
+                    })?;
+                }
+                let path = staged_path(&report.batch_id, &logo.file_name);
+                if let Err(e) = self.staging.write(&path, &logo.data).await {
+                    warn!("Failed to stage logo {}: {}", logo.file_name, e);
+                    report.rejected.push(RejectedLogo {
+                        file_name: logo.file_name,
+                        reason: "Could not be staged".to_string(),
+                    });
+                    continue;
+                }
+                report.unmatched.push(StagedLogo {
+                    file_size: logo.data.len() as u64,
+                    file_name: logo.file_name,
+                });
+                continue;
+            }
+
+            let logo_asset_id = self.save_logo(&logo).await?;
+            self.assign(logo_asset_id, &channels).await?;
+            report.matched.push(MatchedLogo {
+                file_name: logo.file_name,
+                logo_asset_id,
+                channels,
+            });
+        }
+
+        info!(
+            "Bulk logo upload: batch_id={} matched={} unmatched={} rejected={}",
+            report.batch_id,
+            report.matched.len(),
+            report.unmatched.len(),
+            report.rejected.len()
+        );
+        Ok(report)
+    }
+
+    /// Assign staged logos of a batch to channels by hand
+    pub async fn assign_staged(
+        &self,
+        batch_id: Uuid,
+        assignments: &[StagedLogoAssignment],
+    ) -> Result<Vec<MatchedLogo>, AppError> {
+        let channel_repo = ChannelSeaOrmRepository::new(self.connection.clone());
+
+        // Check every logo and channel before saving any asset
+        let mut pending = Vec::with_capacity(assignments.len());
+        for assignment in assignments {
+            let path = staged_path(&batch_id, &assignment.file_name);
+            let data = self
+                .staging
+                .read(&path)
+                .await
+                .map_err(|_| AppError::NotFound {
+                    resource: "staged logo".to_string(),
+                    id: assignment.file_name.clone(),
+                })?;
+            let extension = image_extension(&assignment.file_name, &data).ok_or_else(|| {
+                AppError::validation(format!("'{}' is not an image", assignment.file_name))
+            })?;
+
+            let found = channel_repo
+                .find_by_ids(&assignment.channel_ids)
+                .await
+                .map_err(|e| AppError::internal(e.to_string()))?;
+            let found_ids: HashSet<Uuid> = found.iter().map(|c| c.id).collect();
+            if let Some(missing) = assignment
+                .channel_ids
+                .iter()
+                .find(|id| !found_ids.contains(id))
+            {
+                return Err(AppError::NotFound {
+                    resource: "channel".to_string(),
+                    id: missing.to_string(),
+                });
+            }
+            let channels = found
+                .into_iter()
+                .map(|channel| LogoChannel {
+                    channel_id: channel.id,
+                    channel_name: channel.channel_name,
+                })
+                .collect();
+            pending.push((
+                path,
+                ExtractedLogo {
+                    file_name: assignment.file_name.clone(),
+                    data,
+                    extension,
+                },
+                channels,
+            ));
+        }
+
+        let mut assigned = Vec::with_capacity(pending.len());
+        for (path, logo, channels) in pending {
+            let logo_asset_id = self.save_logo(&logo).await?;
+            self.assign(logo_asset_id, &channels).await?;
+            if let Err(e) = self.staging.remove_file(&path).await {
+                warn!("Failed to remove staged logo {}: {}", path, e);
+            }
+            assigned.push(MatchedLogo {
+                file_name: logo.file_name,
+                logo_asset_id,
+                channels,
+            });
+        }
+        Ok(assigned)
+    }
+
+    /// Channels by normalised name, tvg-name and tvg-id
+    async fn channel_index(&self) -> Result<HashMap<String, Vec<LogoChannel>>, AppError> {
+        let rows = ChannelSeaOrmRepository::new(self.connection.clone())
+            .list_name_keys()
+            .await
+            .map_err(|e| AppError::internal(e.to_string()))?;
+
+        let mut index: HashMap<String, Vec<LogoChannel>> = HashMap::new();
+        for (channel_id, channel_name, tvg_name, tvg_id) in rows {
+            let keys: HashSet<String> = [Some(&channel_name), tvg_name.as_ref(), tvg_id.as_ref()]
+                .into_iter()
+                .flatten()
+                .map(|key| normalize_channel_name(key))
+                .filter(|key| !key.is_empty())
+                .collect();
+            for key in keys {
+                index.entry(key).or_default().push(LogoChannel {
+                    channel_id,
+                    channel_name: channel_name.clone(),
+                });
+            }
+        }
+        Ok(index)
+    }
+
+    /// Save a logo as an uploaded asset named after its file
+    async fn save_logo(&self, logo: &ExtractedLogo) -> Result<Uuid, AppError> {
+        let asset_id = Uuid::new_v4();
+        let (file_name, file_path, file_size, mime_type, dimensions) = self
+            .logo_asset_service
+            .storage
+            .save_uploaded_file(logo.data.clone(), asset_id, logo.extension)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to save logo: {e}")))?;
+        self.logo_asset_service
+            .create_asset_with_id(CreateAssetWithIdParams {
+                asset_id,
+                name: file_stem(&logo.file_name).to_string(),
+                description: None,
+                file_name,
+                file_path,
+                file_size,
+                mime_type,
+                asset_type: LogoAssetType::Uploaded,
+                source_url: None,
+                width: dimensions.map(|(w, _)| w as i32),
+                height: dimensions.map(|(_, h)| h as i32),
+            })
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to create logo asset: {e}")))?;
+        Ok(asset_id)
+    }
+
+    async fn assign(&self, logo_asset_id: Uuid, channels: &[LogoChannel]) -> Result<(), AppError> {
+        let repo = ChannelLogoAssignmentSeaOrmRepository::new(self.connection.clone());
+        for channel in channels {
+            repo.set(
+                channel.channel_id,
+                logo_asset_id,
+                Some(channel.channel_name.clone()),
+            )
+            .await
+            .map_err(|e| AppError::internal(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Images of a ZIP archive, flattened to their file names, and the entries turned away
+///
+/// Entries with paths escaping the archive, oversized entries, non-images and repeated
+/// file names are rejected; directories, hidden files and macOS metadata are skipped.
+fn extract_logos<R: Read + Seek>(
+    archive: R,
+) -> Result<(Vec<ExtractedLogo>, Vec<RejectedLogo>), String> {
+    let mut zip =
+        zip::ZipArchive::new(archive).map_err(|e| format!("Not a valid ZIP archive: {e}"))?;
+    if zip.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(format!(
+            "Archive has {} entries; at most {MAX_ARCHIVE_ENTRIES} are accepted",
+            zip.len()
+        ));
+    }
+
+    let mut logos = Vec::new();
+    let mut rejected = Vec::new();
+    let mut seen = HashSet::new();
+    for index in 0..zip.len() {
+        let mut entry = zip
+            .by_index(index)
+            .map_err(|e| format!("Failed to read archive entry: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let reject = |rejected: &mut Vec<RejectedLogo>, file_name: &str, reason: &str| {
+            rejected.push(RejectedLogo {
+                file_name: file_name.to_string(),
+                reason: reason.to_string(),
+            })
+        };
+
+        let Some(path) = entry.enclosed_name() else {
+            reject(&mut rejected, entry.name(), "Unsafe path");
+            continue;
+        };
+        if path.components().any(|c| c.as_os_str() == "__MACOSX") {
+            continue;
+        }
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            reject(&mut rejected, entry.name(), "Unsupported file name");
+            continue;
+        };
+        if file_name.starts_with('.') {
+            continue;
+        }
+        let file_name = file_name.to_string();
+        if entry.size() > MAX_LOGO_BYTES {
+            reject(&mut rejected, &file_name, "Larger than 5 MiB");
+            continue;
+        }
+
+        // The declared size may lie, so never read past the limit
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        if let Err(e) = (&mut entry).take(MAX_LOGO_BYTES + 1).read_to_end(&mut data) {
+            reject(
+                &mut rejected,
+                &file_name,
+                &format!("Failed to extract: {e}"),
+            );
+            continue;
+        }
+        if data.len() as u64 > MAX_LOGO_BYTES {
+            reject(&mut rejected, &file_name, "Larger than 5 MiB");
+            continue;
+        }
+        let Some(extension) = image_extension(&file_name, &data) else {
+            reject(
+                &mut rejected,
+                &file_name,
+                "Not a PNG, JPEG, GIF, WebP or SVG image",
+            );
+            continue;
+        };
+        if !seen.insert(file_name.to_lowercase()) {
+            reject(&mut rejected, &file_name, "Duplicate file name");
+            continue;
+        }
+        logos.push(ExtractedLogo {
+            file_name,
+            data,
+            extension,
+        });
+    }
+    Ok((logos, rejected))
+}
+
+/// Stored file extension of an image, detected from its content
+fn image_extension(file_name: &str, data: &[u8]) -> Option<&'static str> {
+    if let Some(kind) = infer::get(data) {
+        return match kind.mime_type() {
+            "image/png" => Some("png"),
+            "image/jpeg" => Some("jpg"),
+            "image/gif" => Some("gif"),
+            "image/webp" => Some("webp"),
+            _ => None,
+        };
+    }
+    // SVG is text, so it has no magic bytes
+    let is_svg_name = Path::new(file_name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    let head = String::from_utf8_lossy(&data[..data.len().min(1024)]).to_lowercase();
+    (is_svg_name && head.contains("<svg")).then_some("svg")
+}
+
+fn file_stem(file_name: &str) -> &str {
+    Path::new(file_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name)
+}
+
+fn staged_path(batch_id: &Uuid, file_name: &str) -> String {
+    format!("{STAGING_DIR}/{batch_id}/{file_name}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    fn archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_logos_validates_entries() {
+        let archive = archive(&[
+            ("logos/BBC One.png", PNG_HEADER),
+            ("logos/itv.svg", b"<?xml version=\"1.0\"?><svg></svg>"),
+            ("../escape.png", PNG_HEADER),
+            ("notes.txt", b"not a logo"),
+            ("other/bbc one.PNG", PNG_HEADER),
+            ("__MACOSX/logos/._BBC One.png", PNG_HEADER),
+            (".DS_Store", b"\0\0\0\x01Bud1"),
+        ]);
+
+        let (logos, rejected) = extract_logos(Cursor::new(archive)).unwrap();
+        let logos: Vec<_> = logos
+            .iter()
+            .map(|logo| (logo.file_name.as_str(), logo.extension))
+            .collect();
+        assert_eq!(logos, [("BBC One.png", "png"), ("itv.svg", "svg")]);
+        let rejected: Vec<_> = rejected.iter().map(|r| r.reason.as_str()).collect();
+        assert_eq!(
+            rejected,
+            [
+                "Unsafe path",
+                "Not a PNG, JPEG, GIF, WebP or SVG image",
+                "Duplicate file name"
+            ]
+        );
+
+        assert!(extract_logos(Cursor::new(b"not a zip")).is_err());
+    }
+}
@@ -83,9 +83,9 @@ impl LogoCacheService {
                     }
 
                     // Get file size using metadata instead of reading the whole file
-                    match self.logo_file_manager.metadata(&file_name).await {
-                        Ok(metadata) => {
-                            let file_size = metadata.len();
+                    match self.logo_file_manager.stat(&file_name).await {
+                        Ok(stat) => {
+                            let file_size = stat.size_bytes;
                             if let Ok(cache_entry) = self
                                 .create_entry_from_filesystem(&file_name, file_size)
                                 .await
@@ -0,0 +1,335 @@
+//! Throttled logo prefetching
+//!
+//! Proxy generation downloads remote logos into the logo cache. A [`LogoPrefetchBudget`]
+//! caps how many new logos one generation downloads and how many downloads run at once.
+//! Logos beyond the budget go to the process-wide [`LogoPrefetchQueue`], which the
+//! `logo_prefetch` maintenance job drains in the background. Every download, in a
+//! generation or in the background, waits on the same process-wide rate limiter.
+
+use futures::StreamExt;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::config::LogoPrefetchConfig;
+use crate::logo_assets::service::LogoAssetService;
+
+/// Maintenance operation that downloads deferred logos
+pub const LOGO_PREFETCH_JOB: &str = "logo_prefetch";
+
+/// A logo to download into the logo cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogoRequest {
+    pub url: String,
+    /// Channel (or programme) name stored with the cached logo
+    pub name: Option<String>,
+    pub group: Option<String>,
+}
+
+/// What happened to a requested logo
+#[derive(Debug, Clone)]
+pub enum LogoFetchOutcome {
+    /// The logo is in the cache; `downloaded` is false when it already was
+    Cached {
+        cache_id: String,
+        bytes: u64,
+        downloaded: bool,
+    },
+    /// Over the generation's budget; queued for the background job
+    Deferred,
+    Failed(String),
+}
+
+/// Spaces logo downloads evenly to stay under a number of downloads per second
+pub struct LogoFetchRateLimiter {
+    interval: Option<Duration>,
+    next_slot: tokio::sync::Mutex<Instant>,
+}
+
+impl LogoFetchRateLimiter {
+    /// Limiter allowing `per_second` downloads per second (0 = unlimited)
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            interval: (per_second > 0).then(|| Duration::from_secs(1) / per_second),
+            next_slot: tokio::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    /// The process-wide limiter; the rate of the first caller applies, as the configuration
+    /// does not change while the process runs
+    pub fn global(per_second: u32) -> &'static Self {
+        static LIMITER: OnceLock<LogoFetchRateLimiter> = OnceLock::new();
+        LIMITER.get_or_init(|| Self::new(per_second))
+    }
+
+    /// Wait for the next free download slot
+    pub async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Logos deferred by generations, waiting for the background job
+#[derive(Default)]
+pub struct LogoPrefetchQueue {
+    pending: Mutex<PendingLogos>,
+}
+
+#[derive(Default)]
+struct PendingLogos {
+    logos: VecDeque<LogoRequest>,
+    urls: HashSet<String>,
+}
+
+impl LogoPrefetchQueue {
+    /// The process-wide queue shared by generations and the background job
+    pub fn global() -> &'static Self {
+        static QUEUE: OnceLock<LogoPrefetchQueue> = OnceLock::new();
+        QUEUE.get_or_init(Self::default)
+    }
+
+    /// Queue a logo unless it is already queued or `max_deferred` logos are waiting;
+    /// returns whether it was queued
+    pub fn defer(&self, logo: LogoRequest, max_deferred: usize) -> bool {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.logos.len() >= max_deferred || pending.urls.contains(&logo.url) {
+            return false;
+        }
+        pending.urls.insert(logo.url.clone());
+        pending.logos.push_back(logo);
+        true
+    }
+
+    /// Remove and return up to `max` logos, oldest first
+    pub fn take(&self, max: usize) -> Vec<LogoRequest> {
+        let mut pending = self.pending.lock().unwrap();
+        let count = max.min(pending.logos.len());
+        let taken: Vec<LogoRequest> = pending.logos.drain(..count).collect();
+        for logo in &taken {
+            pending.urls.remove(&logo.url);
+        }
+        taken
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap().logos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Download allowance of a single generation
+pub struct LogoPrefetchBudget {
+    remaining_new_logos: AtomicUsize,
+    max_concurrent_fetches: usize,
+    max_deferred: usize,
+    limiter: &'static LogoFetchRateLimiter,
+    queue: &'static LogoPrefetchQueue,
+}
+
+impl LogoPrefetchBudget {
+    pub fn new(config: &LogoPrefetchConfig) -> Self {
+        Self {
+            remaining_new_logos: AtomicUsize::new(config.max_new_logos_per_run),
+            max_concurrent_fetches: config.max_concurrent_fetches.max(1),
+            max_deferred: config.max_deferred,
+            limiter: LogoFetchRateLimiter::global(config.max_fetches_per_second),
+            queue: LogoPrefetchQueue::global(),
+        }
+    }
+
+    /// Take one new logo download from the budget; false once it is used up
+    pub fn try_reserve(&self) -> bool {
+        self.remaining_new_logos
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |remaining| {
+                remaining.checked_sub(1)
+            })
+            .is_ok()
+    }
+
+    /// Cache the requested logos within the budget
+    ///
+    /// Logos already in the cache are only refreshed and do not count against the budget.
+    /// New logos are downloaded while the budget lasts and deferred to the background job
+    /// after that. Returns the outcome per URL.
+    pub async fn prefetch(
+        &self,
+        logo_service: &LogoAssetService,
+        requests: Vec<LogoRequest>,
+    ) -> HashMap<String, LogoFetchOutcome> {
+        let mut outcomes = HashMap::with_capacity(requests.len());
+        let mut fetches = Vec::with_capacity(requests.len());
+        let mut seen = HashSet::with_capacity(requests.len());
+        for request in requests {
+            if !seen.insert(request.url.clone()) {
+                continue;
+            }
+            let cached = logo_service.is_logo_cached(&request.url).await;
+            if cached || self.try_reserve() {
+                fetches.push((request, !cached));
+            } else {
+                if !self.queue.defer(request.clone(), self.max_deferred) {
+                    debug!("Logo not queued for background fetch: {}", request.url);
+                }
+                outcomes.insert(request.url, LogoFetchOutcome::Deferred);
+            }
+        }
+
+        let fetched: Vec<(String, LogoFetchOutcome)> = futures::stream::iter(fetches)
+            .map(|(request, download)| async move {
+                if download {
+                    self.limiter.acquire().await;
+                }
+                let outcome = fetch_logo(logo_service, &request).await;
+                (request.url, outcome)
+            })
+            .buffer_unordered(self.max_concurrent_fetches)
+            .collect()
+            .await;
+        outcomes.extend(fetched);
+        outcomes
+    }
+}
+
+/// Result of one background prefetch run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DeferredLogoFetchStats {
+    pub downloaded: usize,
+    pub already_cached: usize,
+    pub failed: usize,
+    /// Logos still waiting for a later run
+    pub remaining: usize,
+}
+
+/// Download a batch of deferred logos (run by the `logo_prefetch` maintenance job)
+pub async fn fetch_deferred_logos(
+    logo_service: &LogoAssetService,
+    config: &LogoPrefetchConfig,
+) -> DeferredLogoFetchStats {
+    let queue = LogoPrefetchQueue::global();
+    let limiter = LogoFetchRateLimiter::global(config.max_fetches_per_second);
+    let batch = queue.take(config.background_batch_size.max(1));
+
+    let mut stats = DeferredLogoFetchStats::default();
+    let outcomes: Vec<LogoFetchOutcome> = futures::stream::iter(batch)
+        .map(|request| async move {
+            if !logo_service.is_logo_cached(&request.url).await {
+                limiter.acquire().await;
+            }
+            fetch_logo(logo_service, &request).await
+        })
+        .buffer_unordered(config.max_concurrent_fetches.max(1))
+        .collect()
+        .await;
+    for outcome in outcomes {
+        match outcome {
+            LogoFetchOutcome::Cached {
+                downloaded: true, ..
+            } => stats.downloaded += 1,
+            LogoFetchOutcome::Cached { .. } => stats.already_cached += 1,
+            LogoFetchOutcome::Deferred => {}
+            LogoFetchOutcome::Failed(_) => stats.failed += 1,
+        }
+    }
+    stats.remaining = queue.len();
+
+    info!(
+        "Deferred logo prefetch: downloaded={} already_cached={} failed={} remaining={}",
+        stats.downloaded, stats.already_cached, stats.failed, stats.remaining
+    );
+    stats
+}
+
+async fn fetch_logo(logo_service: &LogoAssetService, request: &LogoRequest) -> LogoFetchOutcome {
+    match logo_service
+        .cache_logo_from_url_with_metadata_and_size_tracking(
+            &request.url,
+            request.name.clone(),
+            request.group.clone(),
+            None,
+        )
+        .await
+    {
+        Ok((cache_id, bytes)) => LogoFetchOutcome::Cached {
+            cache_id,
+            bytes,
+            downloaded: bytes > 0,
+        },
+        Err(e) => {
+            debug!("Failed to cache logo {}: {}", request.url, e);
+            LogoFetchOutcome::Failed(e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logo(url: &str) -> LogoRequest {
+        LogoRequest {
+            url: url.to_string(),
+            name: None,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn test_queue_deduplicates_and_caps() {
+        let queue = LogoPrefetchQueue::default();
+        assert!(queue.defer(logo("https://a/1.png"), 2));
+        assert!(!queue.defer(logo("https://a/1.png"), 2));
+        assert!(queue.defer(logo("https://a/2.png"), 2));
+        assert!(!queue.defer(logo("https://a/3.png"), 2));
+        assert_eq!(queue.len(), 2);
+
+        let taken = queue.take(1);
+        assert_eq!(taken, vec![logo("https://a/1.png")]);
+        // A taken logo can be deferred again
+        assert!(queue.defer(logo("https://a/1.png"), 2));
+        assert_eq!(queue.take(10).len(), 2);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_budget_reserve() {
+        let budget = LogoPrefetchBudget::new(&LogoPrefetchConfig {
+            max_new_logos_per_run: 2,
+            ..LogoPrefetchConfig::default()
+        });
+        assert!(budget.try_reserve());
+        assert!(budget.try_reserve());
+        assert!(!budget.try_reserve());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_spaces_fetches() {
+        let limiter = LogoFetchRateLimiter::new(50);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        // First slot is immediate, the next two are 20ms apart
+        assert!(start.elapsed() >= Duration::from_millis(40));
+
+        let unlimited = LogoFetchRateLimiter::new(0);
+        let start = Instant::now();
+        for _ in 0..100 {
+            unlimited.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(20));
+    }
+}
@@ -33,23 +33,41 @@
 //! }
 //! ```
 
+pub mod auth;
+pub mod channel_count_alerts;
+pub mod channel_diagnostics;
+pub mod channel_export;
+pub mod channel_preview;
 pub mod circuit_breaker_manager;
 pub mod circuit_breaker_pool;
+pub mod compliance_blocklist;
 pub mod connection_limiter;
 pub mod cyclic_buffer;
+pub mod deep_health;
 pub mod embedded_font;
 pub mod epg_source_service;
 pub mod error_fallback;
 pub mod ffmpeg_command_builder;
 pub mod ffmpeg_wrapper;
 pub mod file_categories;
+pub mod generation_hooks;
+pub mod guide_quality;
+pub mod ingest_archive;
+pub mod leader_election;
+pub mod logo_bulk_upload;
 // logo_cache_scanner module removed - replaced by logo_cache service
 pub mod logo_cache;
 pub mod logo_cache_maintenance;
+pub mod logo_prefetch;
+pub mod mqtt_publisher;
+pub mod oidc;
+pub mod playlist_delta;
 pub mod probe_persistence;
 pub mod progress_service;
 pub mod proxy_regeneration;
+pub mod query_cache;
 pub mod relay_config_resolver;
+pub mod relay_logs;
 pub mod relay_manager;
 pub mod sandboxed_file;
 pub mod sandboxed_file_trait;
@@ -57,12 +75,21 @@ pub mod source_linking_service;
 pub mod stream_prober;
 pub mod stream_proxy;
 pub mod stream_source_service;
+pub mod systemd_notify;
 pub mod traits;
+pub mod transcode_admission;
+pub mod trash;
 pub mod url_linking_service;
+pub mod xmltv_import;
 
 // Re-export main traits and services
+pub use auth::{AdminAuth, Principal};
+pub use channel_count_alerts::ChannelCountAlertService;
+pub use channel_diagnostics::ChannelDiagnosticsService;
+pub use channel_preview::ChannelPreviewService;
 pub use circuit_breaker_manager::CircuitBreakerManager;
 pub use circuit_breaker_pool::{CircuitBreakerPool, PoolStats};
+pub use compliance_blocklist::ComplianceBlocklistService;
 pub use connection_limiter::{
     ConnectionHandle, ConnectionLimiter, ConnectionLimitsConfig, LimitExceededError,
 };
@@ -72,14 +99,22 @@ pub use epg_source_service::EpgSourceService;
 pub use error_fallback::{ErrorFallbackGenerator, StreamHealthMonitor};
 pub use ffmpeg_command_builder::FFmpegCommandBuilder;
 pub use ffmpeg_wrapper::FFmpegProcessWrapper;
+pub use generation_hooks::GenerationHookService;
+pub use ingest_archive::IngestArchiveService;
+pub use leader_election::LeaderElection;
+pub use logo_bulk_upload::LogoBulkUploadService;
+pub use mqtt_publisher::{AutomationEvent, MqttPublisher};
 pub use probe_persistence::ProbePersistenceService;
 pub use progress_service::{OperationType, ProgressService};
 pub use proxy_regeneration::ProxyRegenerationService;
+pub use query_cache::{CachedEntity, QueryCache};
 pub use relay_config_resolver::RelayConfigResolver;
 pub use relay_manager::RelayManager;
 pub use source_linking_service::SourceLinkingService;
 pub use stream_prober::{ProbeResult, StreamMappingStrategy, StreamProber};
 pub use stream_proxy::{StreamProxyService, StreamProxyServiceBuilder};
 pub use stream_source_service::StreamSourceService as StreamSourceBusinessService;
+pub use systemd_notify::SystemdNotifier;
 pub use traits::*;
 pub use url_linking_service::UrlLinkingService;
+pub use xmltv_import::XmltvImportService;
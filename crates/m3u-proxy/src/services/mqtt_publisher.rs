@@ -0,0 +1,397 @@
+//! MQTT event publishing for home automation
+//!
+//! Viewing activity, source failures and channel count alerts are published to an MQTT
+//! broker so Home Assistant (or any other subscriber) can automate on them, e.g. dim the
+//! lights when a movie channel starts. Publishing never blocks the caller: events are
+//! queued to a background task that owns the broker connection, and are dropped with a
+//! warning when the queue is full or the broker is unreachable.
+
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use serde_json::{Map, Value};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::config::MqttConfig;
+
+/// Events queued while the broker connection is busy before new ones are dropped
+const EVENT_QUEUE_CAPACITY: usize = 256;
+
+/// An event published to MQTT
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutomationEvent {
+    StreamStarted {
+        session_id: String,
+        proxy_name: String,
+        channel_id: String,
+        channel_name: String,
+        client_ip: String,
+        user: Option<String>,
+        /// Active sessions on the channel, including this one
+        channel_viewers: usize,
+    },
+    StreamStopped {
+        session_id: String,
+        proxy_name: String,
+        channel_id: String,
+        channel_name: String,
+        client_ip: String,
+        user: Option<String>,
+        /// Active sessions left on the channel
+        channel_viewers: usize,
+        duration_seconds: u64,
+        bytes_served: u64,
+    },
+    SourceRefreshFailed {
+        source_id: String,
+        source_name: String,
+        /// "stream" or "epg"
+        source_type: String,
+        error: String,
+    },
+    SourceChannelCountDropped {
+        source_id: String,
+        source_name: String,
+        previous_count: i64,
+        current_count: i64,
+        change_percent: f64,
+    },
+    ProxyChannelCountChanged {
+        proxy_id: String,
+        proxy_name: String,
+        previous_count: i64,
+        current_count: i64,
+        change_percent: f64,
+    },
+}
+
+impl AutomationEvent {
+    /// Event name used in topics, templates and the `events` filter
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StreamStarted { .. } => "stream_started",
+            Self::StreamStopped { .. } => "stream_stopped",
+            Self::SourceRefreshFailed { .. } => "source_refresh_failed",
+            Self::SourceChannelCountDropped { .. } => "source_channel_count_dropped",
+            Self::ProxyChannelCountChanged { .. } => "proxy_channel_count_changed",
+        }
+    }
+
+    /// Event fields by name, as substituted into templates
+    pub fn fields(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            Self::StreamStarted {
+                session_id,
+                proxy_name,
+                channel_id,
+                channel_name,
+                client_ip,
+                user,
+                channel_viewers,
+            } => vec![
+                ("session_id", session_id.as_str().into()),
+                ("proxy_name", proxy_name.as_str().into()),
+                ("channel_id", channel_id.as_str().into()),
+                ("channel_name", channel_name.as_str().into()),
+                ("client_ip", client_ip.as_str().into()),
+                ("user", user.as_deref().into()),
+                ("channel_viewers", (*channel_viewers).into()),
+            ],
+            Self::StreamStopped {
+                session_id,
+                proxy_name,
+                channel_id,
+                channel_name,
+                client_ip,
+                user,
+                channel_viewers,
+                duration_seconds,
+                bytes_served,
+            } => vec![
+                ("session_id", session_id.as_str().into()),
+                ("proxy_name", proxy_name.as_str().into()),
+                ("channel_id", channel_id.as_str().into()),
+                ("channel_name", channel_name.as_str().into()),
+                ("client_ip", client_ip.as_str().into()),
+                ("user", user.as_deref().into()),
+                ("channel_viewers", (*channel_viewers).into()),
+                ("duration_seconds", (*duration_seconds).into()),
+                ("bytes_served", (*bytes_served).into()),
+            ],
+            Self::SourceRefreshFailed {
+                source_id,
+                source_name,
+                source_type,
+                error,
+            } => vec![
+                ("source_id", source_id.as_str().into()),
+                ("source_name", source_name.as_str().into()),
+                ("source_type", source_type.as_str().into()),
+                ("error", error.as_str().into()),
+            ],
+            Self::SourceChannelCountDropped {
+                source_id,
+                source_name,
+                previous_count,
+                current_count,
+                change_percent,
+            } => vec![
+                ("source_id", source_id.as_str().into()),
+                ("source_name", source_name.as_str().into()),
+                ("previous_count", (*previous_count).into()),
+                ("current_count", (*current_count).into()),
+                ("change_percent", (*change_percent).into()),
+            ],
+            Self::ProxyChannelCountChanged {
+                proxy_id,
+                proxy_name,
+                previous_count,
+                current_count,
+                change_percent,
+            } => vec![
+                ("proxy_id", proxy_id.as_str().into()),
+                ("proxy_name", proxy_name.as_str().into()),
+                ("previous_count", (*previous_count).into()),
+                ("current_count", (*current_count).into()),
+                ("change_percent", (*change_percent).into()),
+            ],
+        }
+    }
+}
+
+/// Topic and payload of an event under the given configuration
+pub fn render_event(config: &MqttConfig, event: &AutomationEvent) -> (String, String) {
+    let fields = event.fields();
+    let topic_template = config
+        .topics
+        .get(event.name())
+        .unwrap_or(&config.topic_template);
+    let topic = render_template(topic_template, config, event.name(), &fields);
+
+    let payload = match config.payloads.get(event.name()) {
+        Some(template) => render_template(template, config, event.name(), &fields),
+        None => {
+            let mut object = Map::new();
+            object.insert("event".to_string(), event.name().into());
+            object.insert(
+                "timestamp".to_string(),
+                chrono::Utc::now().to_rfc3339().into(),
+            );
+            for (name, value) in fields {
+                object.insert(name.to_string(), value);
+            }
+            Value::Object(object).to_string()
+        }
+    };
+    (topic, payload)
+}
+
+fn render_template(
+    template: &str,
+    config: &MqttConfig,
+    event: &str,
+    fields: &[(&'static str, Value)],
+) -> String {
+    let mut rendered = template
+        .replace("{prefix}", &config.topic_prefix)
+        .replace("{event}", event);
+    for (name, value) in fields {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        rendered = rendered.replace(&format!("{{{name}}}"), &value);
+    }
+    rendered
+}
+
+fn qos(level: u8) -> QoS {
+    match level {
+        0 => QoS::AtMostOnce,
+        1 => QoS::AtLeastOnce,
+        _ => QoS::ExactlyOnce,
+    }
+}
+
+/// Handle for publishing automation events; cheap to clone
+///
+/// A disabled publisher (the default) discards events.
+#[derive(Debug, Clone, Default)]
+pub struct MqttPublisher {
+    sender: Option<mpsc::Sender<AutomationEvent>>,
+}
+
+impl MqttPublisher {
+    /// Publisher discarding every event
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Connect to the configured broker in the background
+    ///
+    /// Returns a disabled publisher when MQTT is not enabled.
+    pub fn start(config: Option<&MqttConfig>) -> Self {
+        let Some(config) = config.filter(|c| c.enabled) else {
+            return Self::disabled();
+        };
+        let config = config.clone();
+
+        let status_topic = format!("{}/status", config.topic_prefix);
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options.set_keep_alive(config.keep_alive_duration());
+        options.set_last_will(LastWill::new(
+            &status_topic,
+            "offline",
+            QoS::AtLeastOnce,
+            true,
+        ));
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.clone().unwrap_or_default());
+        }
+
+        let (client, mut event_loop) = AsyncClient::new(options, EVENT_QUEUE_CAPACITY);
+        let (sender, mut receiver) = mpsc::channel::<AutomationEvent>(EVENT_QUEUE_CAPACITY);
+
+        info!(
+            "MQTT event publishing enabled: broker={}:{} topic_prefix={}",
+            config.host, config.port, config.topic_prefix
+        );
+
+        // The event loop drives the connection (and reconnects); it must be polled continuously
+        {
+            let client = client.clone();
+            tokio::spawn(async move {
+                loop {
+                    match event_loop.poll().await {
+                        Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                            info!("Connected to MQTT broker");
+                            if let Err(e) = client
+                                .publish(&status_topic, QoS::AtLeastOnce, true, "online")
+                                .await
+                            {
+                                warn!("Failed to publish MQTT status: {}", e);
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            warn!("MQTT connection error: {}; retrying in 5s", e);
+                            tokio::time::sleep(Duration::from_secs(5)).await;
+                        }
+                    }
+                }
+            });
+        }
+
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                if !config.publishes(event.name()) {
+                    continue;
+                }
+                let (topic, payload) = render_event(&config, &event);
+                debug!("Publishing MQTT event {} to {}", event.name(), topic);
+                if let Err(e) = client
+                    .publish(topic, qos(config.qos), config.retain, payload)
+                    .await
+                {
+                    warn!("Failed to publish MQTT event {}: {}", event.name(), e);
+                }
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Queue an event for publishing without waiting
+    pub fn publish(&self, event: AutomationEvent) {
+        if let Some(sender) = &self.sender
+            && let Err(e) = sender.try_send(event)
+        {
+            warn!("Dropping MQTT event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn started() -> AutomationEvent {
+        AutomationEvent::StreamStarted {
+            session_id: "s1".to_string(),
+            proxy_name: "Home".to_string(),
+            channel_id: "c1".to_string(),
+            channel_name: "BBC One".to_string(),
+            client_ip: "10.0.0.2".to_string(),
+            user: None,
+            channel_viewers: 2,
+        }
+    }
+
+    #[test]
+    fn test_default_topic_and_json_payload() {
+        let (topic, payload) = render_event(&MqttConfig::default(), &started());
+        assert_eq!(topic, "m3u-proxy/events/stream_started");
+
+        let json: Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(json["event"], "stream_started");
+        assert_eq!(json["channel_name"], "BBC One");
+        assert_eq!(json["channel_viewers"], 2);
+        assert!(json["user"].is_null());
+    }
+
+    #[test]
+    fn test_templates() {
+        let mut config = MqttConfig {
+            topic_prefix: "tv".to_string(),
+            ..Default::default()
+        };
+        config.topics.insert(
+            "stream_started".to_string(),
+            "{prefix}/channels/{channel_id}/state".to_string(),
+        );
+        config.payloads.insert(
+            "stream_started".to_string(),
+            "{channel_name} ({channel_viewers}){user}".to_string(),
+        );
+
+        let (topic, payload) = render_event(&config, &started());
+        assert_eq!(topic, "tv/channels/c1/state");
+        assert_eq!(payload, "BBC One (2)");
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let config = MqttConfig {
+            events: vec!["source_refresh_failed".to_string()],
+            ..Default::default()
+        };
+        assert!(config.publishes("source_refresh_failed"));
+        assert!(!config.publishes("stream_started"));
+        assert!(MqttConfig::default().publishes("stream_started"));
+    }
+
+    #[test]
+    fn test_channel_count_event_payload() {
+        let event = AutomationEvent::SourceChannelCountDropped {
+            source_id: "src1".to_string(),
+            source_name: "Provider".to_string(),
+            previous_count: 1000,
+            current_count: 400,
+            change_percent: -60.0,
+        };
+        let (topic, payload) = render_event(&MqttConfig::default(), &event);
+        assert_eq!(topic, "m3u-proxy/events/source_channel_count_dropped");
+
+        let json: Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(json["previous_count"], 1000);
+        assert_eq!(json["current_count"], 400);
+        assert_eq!(json["change_percent"], -60.0);
+    }
+}
@@ -0,0 +1,405 @@
+//! OpenID Connect client for admin logins
+//!
+//! Reads the provider's discovery document and signing keys, builds authorization
+//! requests (code flow with PKCE), exchanges codes for tokens and validates the provider's
+//! JWTs. Both are fetched lazily and cached; keys are refetched when a token names a key
+//! id that is not in the cached set, which is how providers roll their keys.
+
+use anyhow::{Context, Result, anyhow, bail};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, jwk::JwkSet};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::config::{AuthRole, OidcConfig};
+
+/// Shortest interval between two key set downloads triggered by unknown key ids
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Clock skew tolerated on `exp`, `nbf` and `iat`
+const CLOCK_SKEW_SECONDS: u64 = 60;
+
+/// Claims of a validated token
+pub type Claims = Map<String, Value>;
+
+/// Endpoints from the provider's discovery document
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+/// Key set and when it was downloaded
+struct CachedKeys {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Client of one OpenID Connect provider
+pub struct OidcClient {
+    config: OidcConfig,
+    http: reqwest::Client,
+    metadata: RwLock<Option<ProviderMetadata>>,
+    keys: RwLock<Option<CachedKeys>>,
+}
+
+/// PKCE verifier and the challenge sent in the authorization request
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn generate() -> Self {
+        let verifier = random_token(32);
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+/// URL-safe random string of `bytes` random bytes, for states, nonces and verifiers
+pub fn random_token(bytes: usize) -> String {
+    let random: Vec<u8> = (0..bytes).map(|_| rand::random::<u8>()).collect();
+    URL_SAFE_NO_PAD.encode(random)
+}
+
+impl OidcClient {
+    pub fn new(config: OidcConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to build OIDC HTTP client")?;
+        Ok(Self {
+            config,
+            http,
+            metadata: RwLock::new(None),
+            keys: RwLock::new(None),
+        })
+    }
+
+    /// Provider URL to send the browser to
+    pub async fn authorization_url(
+        &self,
+        redirect_url: &str,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String> {
+        let metadata = self.metadata().await?;
+        let mut url = url::Url::parse(&metadata.authorization_endpoint)
+            .context("Invalid authorization endpoint")?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", redirect_url)
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", state)
+            .append_pair("nonce", nonce)
+            .append_pair("code_challenge", code_challenge)
+            .append_pair("code_challenge_method", "S256");
+        Ok(url.into())
+    }
+
+    /// Exchange an authorization code and validate the returned ID token
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        redirect_url: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<Claims> {
+        let metadata = self.metadata().await?;
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_url),
+            ("code_verifier", code_verifier),
+            ("client_id", self.config.client_id.as_str()),
+        ];
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .context("Token request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!(
+                "Token endpoint returned {}: {}",
+                status,
+                body.chars().take(200).collect::<String>()
+            );
+        }
+        let tokens: TokenResponse = response
+            .json()
+            .await
+            .context("Invalid token endpoint response")?;
+        let id_token = tokens
+            .id_token
+            .ok_or_else(|| anyhow!("Token endpoint returned no ID token"))?;
+
+        let claims = self
+            .validate(&id_token, std::slice::from_ref(&self.config.client_id))
+            .await?;
+        if claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+            bail!("ID token nonce does not match the login request");
+        }
+        Ok(claims)
+    }
+
+    /// Validate an access token sent as a bearer token by an API client
+    pub async fn validate_access_token(&self, token: &str) -> Result<Claims> {
+        let audiences = if self.config.audiences.is_empty() {
+            std::slice::from_ref(&self.config.client_id)
+        } else {
+            self.config.audiences.as_slice()
+        };
+        self.validate(token, audiences).await
+    }
+
+    /// User name and role from validated claims; `None` when the user has no role
+    pub fn principal_of(&self, claims: &Claims) -> Option<(String, AuthRole)> {
+        let role = role_from_claims(&self.config, claims)?;
+        let name = claims
+            .get(&self.config.username_claim)
+            .or_else(|| claims.get("sub"))
+            .and_then(Value::as_str)?
+            .to_string();
+        Some((name, role))
+    }
+
+    /// Check a JWT's signature against the provider's keys, and its issuer, audience and
+    /// expiry
+    async fn validate(&self, token: &str, audiences: &[String]) -> Result<Claims> {
+        let header = jsonwebtoken::decode_header(token).context("Malformed token")?;
+        // Provider tokens are signed with the provider's private keys, never a shared secret
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            bail!("Token uses unsupported algorithm {:?}", header.alg);
+        }
+
+        let metadata = self.metadata().await?;
+        let key = self.decoding_key(header.kid.as_deref()).await?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[metadata.issuer.as_str()]);
+        validation.set_audience(audiences);
+        validation.leeway = CLOCK_SKEW_SECONDS;
+
+        let data = jsonwebtoken::decode::<Claims>(token, &key, &validation)
+            .context("Token validation failed")?;
+        Ok(data.claims)
+    }
+
+    async fn decoding_key(&self, kid: Option<&str>) -> Result<DecodingKey> {
+        if let Some(key) = self.cached_key(kid).await? {
+            return Ok(key);
+        }
+
+        let stale = self
+            .keys
+            .read()
+            .await
+            .as_ref()
+            .is_none_or(|cached| cached.fetched_at.elapsed() >= JWKS_REFRESH_INTERVAL);
+        if stale {
+            self.refresh_keys().await?;
+            if let Some(key) = self.cached_key(kid).await? {
+                return Ok(key);
+            }
+        }
+        Err(anyhow!(
+            "No signing key {:?} in the provider's key set",
+            kid
+        ))
+    }
+
+    async fn cached_key(&self, kid: Option<&str>) -> Result<Option<DecodingKey>> {
+        let keys = self.keys.read().await;
+        let Some(cached) = keys.as_ref() else {
+            return Ok(None);
+        };
+        let jwk = match kid {
+            Some(kid) => cached.keys.find(kid),
+            // Without a key id, only an unambiguous key set will do
+            None if cached.keys.keys.len() == 1 => cached.keys.keys.first(),
+            None => None,
+        };
+        jwk.map(|jwk| DecodingKey::from_jwk(jwk).context("Unusable signing key"))
+            .transpose()
+    }
+
+    async fn refresh_keys(&self) -> Result<()> {
+        let metadata = self.metadata().await?;
+        let keys: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .context("Failed to download the provider's signing keys")?
+            .json()
+            .await
+            .context("Invalid provider key set")?;
+        debug!("Loaded {} OIDC signing keys", keys.keys.len());
+        *self.keys.write().await = Some(CachedKeys {
+            keys,
+            fetched_at: Instant::now(),
+        });
+        Ok(())
+    }
+
+    async fn metadata(&self) -> Result<ProviderMetadata> {
+        if let Some(metadata) = self.metadata.read().await.as_ref() {
+            return Ok(metadata.clone());
+        }
+
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            self.config.issuer_url.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = self
+            .http
+            .get(&discovery_url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("Failed to read {discovery_url}"))?
+            .json()
+            .await
+            .context("Invalid OIDC discovery document")?;
+        if metadata.issuer.trim_end_matches('/') != self.config.issuer_url.trim_end_matches('/') {
+            bail!(
+                "Discovery document is for issuer {}, not {}",
+                metadata.issuer,
+                self.config.issuer_url
+            );
+        }
+        info!("Using OIDC provider {}", metadata.issuer);
+        *self.metadata.write().await = Some(metadata.clone());
+        Ok(metadata)
+    }
+}
+
+/// Role granted by the configured claim: admin beats viewer beats the default role
+fn role_from_claims(config: &OidcConfig, claims: &Claims) -> Option<AuthRole> {
+    let mut claim = None;
+    for (i, segment) in config.role_claim.split('.').enumerate() {
+        claim = if i == 0 {
+            claims.get(segment)
+        } else {
+            claim.and_then(|value: &Value| value.get(segment))
+        };
+    }
+    let values: Vec<&str> = match claim {
+        Some(Value::String(value)) => vec![value.as_str()],
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+
+    let granted = |allowed: &[String]| values.iter().any(|v| allowed.iter().any(|a| a == v));
+    if granted(&config.admin_values) {
+        Some(AuthRole::Admin)
+    } else if granted(&config.viewer_values) {
+        Some(AuthRole::Viewer)
+    } else {
+        config.default_role
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> OidcConfig {
+        OidcConfig {
+            issuer_url: "https://idp.example.com".to_string(),
+            client_id: "m3u-proxy".to_string(),
+            client_secret: None,
+            redirect_url: None,
+            scopes: Vec::new(),
+            audiences: Vec::new(),
+            role_claim: "realm_access.roles".to_string(),
+            admin_values: vec!["tv-admins".to_string()],
+            viewer_values: vec!["tv-viewers".to_string()],
+            default_role: None,
+            username_claim: "preferred_username".to_string(),
+        }
+    }
+
+    fn claims(value: Value) -> Claims {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_role_from_nested_claim() {
+        let config = config();
+        let both = claims(json!({"realm_access": {"roles": ["tv-viewers", "tv-admins"]}}));
+        assert_eq!(role_from_claims(&config, &both), Some(AuthRole::Admin));
+
+        let viewer = claims(json!({"realm_access": {"roles": "tv-viewers"}}));
+        assert_eq!(role_from_claims(&config, &viewer), Some(AuthRole::Viewer));
+
+        let none = claims(json!({"realm_access": {"roles": ["staff"]}, "roles": ["tv-admins"]}));
+        assert_eq!(role_from_claims(&config, &none), None);
+
+        let fallback = OidcConfig {
+            default_role: Some(AuthRole::Viewer),
+            ..config
+        };
+        assert_eq!(role_from_claims(&fallback, &none), Some(AuthRole::Viewer));
+    }
+
+    #[test]
+    fn test_principal_name_falls_back_to_subject() {
+        let client = OidcClient::new(config()).unwrap();
+        let with_name = claims(json!({
+            "sub": "1234",
+            "preferred_username": "alex",
+            "realm_access": {"roles": ["tv-admins"]}
+        }));
+        assert_eq!(
+            client.principal_of(&with_name),
+            Some(("alex".to_string(), AuthRole::Admin))
+        );
+
+        let subject_only =
+            claims(json!({"sub": "1234", "realm_access": {"roles": ["tv-viewers"]}}));
+        assert_eq!(
+            client.principal_of(&subject_only),
+            Some(("1234".to_string(), AuthRole::Viewer))
+        );
+    }
+
+    #[test]
+    fn test_pkce_challenge_is_s256_of_verifier() {
+        let pkce = Pkce::generate();
+        assert_eq!(pkce.verifier.len(), 43);
+        assert_eq!(
+            pkce.challenge,
+            URL_SAFE_NO_PAD.encode(Sha256::digest(pkce.verifier.as_bytes()))
+        );
+    }
+}
@@ -0,0 +1,363 @@
+//! Playlist deltas between proxy generations
+//!
+//! Every published playlist is recorded as a manifest mapping each stream URL to a hash of
+//! its metadata lines. A client holding an earlier generation can then fetch only the
+//! entries added, changed or removed since, instead of the whole playlist. A bounded number
+//! of manifests is kept per proxy; clients on an older generation refetch the full playlist.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sandboxed_file_manager::SandboxedManager;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Response header carrying the generation version of a served playlist
+pub const VERSION_HEADER: &str = "x-playlist-version";
+
+/// A playlist entry: its stream URL and the metadata lines preceding it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    pub url: String,
+    pub metadata: String,
+}
+
+/// Entries added, changed or removed between two playlist generations
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PlaylistDelta {
+    /// Generation the delta starts from
+    pub since: u64,
+    /// Generation the delta brings the client to
+    pub version: u64,
+    pub generated_at: DateTime<Utc>,
+    pub added: Vec<PlaylistDeltaEntry>,
+    pub changed: Vec<PlaylistDeltaEntry>,
+    /// Stream URLs no longer in the playlist
+    pub removed: Vec<String>,
+}
+
+/// An added or changed playlist entry
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PlaylistDeltaEntry {
+    pub url: String,
+    /// `#EXTINF` line and any option lines, newline separated
+    pub extinf: String,
+}
+
+/// Outcome of a delta request
+#[derive(Debug)]
+pub enum DeltaOutcome {
+    Delta(PlaylistDelta),
+    /// The requested generation is no longer (or was never) recorded
+    Unavailable {
+        version: u64,
+    },
+    /// No generation of the proxy has been recorded
+    NotRecorded,
+}
+
+/// A recorded generation of a proxy's playlist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GenerationRecord {
+    version: u64,
+    generated_at: DateTime<Utc>,
+    content_hash: String,
+}
+
+/// Per-generation manifests of proxy playlists, kept next to the published output
+#[derive(Clone)]
+pub struct PlaylistDeltaStore {
+    file_manager: SandboxedManager,
+    history: usize,
+}
+
+impl PlaylistDeltaStore {
+    /// `history` is the number of generations kept per proxy (0 disables recording)
+    pub fn new(file_manager: SandboxedManager, history: usize) -> Self {
+        Self {
+            file_manager,
+            history,
+        }
+    }
+
+    fn index_path(proxy_id: &Uuid) -> String {
+        format!("{proxy_id}.m3u8.versions")
+    }
+
+    fn manifest_path(proxy_id: &Uuid, version: u64) -> String {
+        format!("{proxy_id}.m3u8.v{version}.manifest")
+    }
+
+    async fn load_index(&self, proxy_id: &Uuid) -> Vec<GenerationRecord> {
+        match self
+            .file_manager
+            .read_to_string(Self::index_path(proxy_id))
+            .await
+        {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!(
+                    "Ignoring unreadable playlist versions of proxy {}: {}",
+                    proxy_id, e
+                );
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Latest recorded generation of a proxy's playlist
+    pub async fn current_version(&self, proxy_id: &Uuid) -> Option<u64> {
+        if self.history == 0 {
+            return None;
+        }
+        self.load_index(proxy_id)
+            .await
+            .last()
+            .map(|record| record.version)
+    }
+
+    /// Record a published playlist as a new generation
+    ///
+    /// Returns the generation version, which is unchanged when the content is identical to
+    /// the latest recorded generation.
+    pub async fn record(&self, proxy_id: &Uuid, content: &str) -> Result<Option<u64>> {
+        if self.history == 0 {
+            return Ok(None);
+        }
+
+        let content_hash = hex::encode(Sha256::digest(content.as_bytes()));
+        let mut index = self.load_index(proxy_id).await;
+        if let Some(latest) = index.last()
+            && latest.content_hash == content_hash
+        {
+            debug!(
+                "Playlist of proxy {} unchanged at version {}",
+                proxy_id, latest.version
+            );
+            return Ok(Some(latest.version));
+        }
+
+        let version = index.last().map_or(1, |record| record.version + 1);
+        let manifest: HashMap<String, String> = parse_entries(content)
+            .into_iter()
+            .map(|entry| (entry.url, entry_hash(&entry.metadata)))
+            .collect();
+        self.file_manager
+            .write(
+                Self::manifest_path(proxy_id, version),
+                serde_json::to_vec(&manifest)?,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write playlist manifest: {}", e))?;
+
+        index.push(GenerationRecord {
+            version,
+            generated_at: Utc::now(),
+            content_hash,
+        });
+        let expired = index.len().saturating_sub(self.history);
+        for record in index.drain(..expired) {
+            if let Err(e) = self
+                .file_manager
+                .remove_file(Self::manifest_path(proxy_id, record.version))
+                .await
+            {
+                debug!(
+                    "Failed to remove playlist manifest {} of proxy {}: {}",
+                    record.version, proxy_id, e
+                );
+            }
+        }
+        self.file_manager
+            .write(Self::index_path(proxy_id), serde_json::to_vec(&index)?)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write playlist versions: {}", e))?;
+
+        info!(
+            "Recorded playlist version {} of proxy {} ({} entries)",
+            version,
+            proxy_id,
+            manifest.len()
+        );
+        Ok(Some(version))
+    }
+
+    /// Changes between a recorded generation and the current playlist `content`
+    pub async fn delta(&self, proxy_id: &Uuid, since: u64, content: &str) -> Result<DeltaOutcome> {
+        let index = self.load_index(proxy_id).await;
+        let Some(current) = index.last() else {
+            return Ok(DeltaOutcome::NotRecorded);
+        };
+        if !index.iter().any(|record| record.version == since) {
+            return Ok(DeltaOutcome::Unavailable {
+                version: current.version,
+            });
+        }
+
+        let previous: HashMap<String, String> = match self
+            .file_manager
+            .read(Self::manifest_path(proxy_id, since))
+            .await
+        {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(_) => {
+                return Ok(DeltaOutcome::Unavailable {
+                    version: current.version,
+                });
+            }
+        };
+
+        let mut delta = diff_entries(&previous, parse_entries(content));
+        delta.since = since;
+        delta.version = current.version;
+        delta.generated_at = current.generated_at;
+        Ok(DeltaOutcome::Delta(delta))
+    }
+}
+
+/// Split a playlist into entries
+///
+/// Entries are keyed by stream URL; a URL listed more than once keeps its first entry.
+pub fn parse_entries(content: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+    let mut metadata: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("#EXTM3U") {
+            continue;
+        }
+        if line.starts_with("#EXTINF") {
+            metadata.clear();
+            metadata.push(line);
+        } else if line.starts_with('#') {
+            if !metadata.is_empty() {
+                metadata.push(line);
+            }
+        } else if !metadata.is_empty() {
+            if seen.insert(line.to_string()) {
+                entries.push(PlaylistEntry {
+                    url: line.to_string(),
+                    metadata: metadata.join("\n"),
+                });
+            }
+            metadata.clear();
+        }
+    }
+    entries
+}
+
+fn entry_hash(metadata: &str) -> String {
+    hex::encode(&Sha256::digest(metadata.as_bytes())[..8])
+}
+
+fn diff_entries(previous: &HashMap<String, String>, current: Vec<PlaylistEntry>) -> PlaylistDelta {
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    let mut current_urls = HashSet::with_capacity(current.len());
+    for entry in current {
+        match previous.get(&entry.url) {
+            None => added.push(entry),
+            Some(hash) if *hash != entry_hash(&entry.metadata) => changed.push(entry),
+            Some(_) => {}
+        }
+        current_urls.insert(entry.url);
+    }
+    let mut removed: Vec<String> = previous
+        .keys()
+        .filter(|url| !current_urls.contains(*url))
+        .cloned()
+        .collect();
+    removed.sort();
+
+    let to_delta_entry = |entry: PlaylistEntry| PlaylistDeltaEntry {
+        url: entry.url,
+        extinf: entry.metadata,
+    };
+    PlaylistDelta {
+        since: 0,
+        version: 0,
+        generated_at: Utc::now(),
+        added: added.into_iter().map(to_delta_entry).collect(),
+        changed: changed.into_iter().map(to_delta_entry).collect(),
+        removed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYLIST_V1: &str = "#EXTM3U\n\
+#EXTINF:-1 tvg-chno=\"1\",News\n\
+http://proxy/stream/a/1\n\
+#EXTINF:-1 tvg-chno=\"2\",Sport\n\
+#EXTVLCOPT:http-user-agent=VLC\n\
+http://proxy/stream/a/2\n\
+#EXTINF:-1 tvg-chno=\"3\",Movies\n\
+http://proxy/stream/a/3\n";
+
+    const PLAYLIST_V2: &str = "#EXTM3U\n\
+#EXTINF:-1 tvg-chno=\"1\",News\n\
+http://proxy/stream/a/1\n\
+#EXTINF:-1 tvg-chno=\"2\",Sport HD\n\
+#EXTVLCOPT:http-user-agent=VLC\n\
+http://proxy/stream/a/2\n\
+#EXTINF:-1 tvg-chno=\"4\",Kids\n\
+http://proxy/stream/a/4\n";
+
+    #[test]
+    fn test_parse_entries() {
+        let entries = parse_entries(PLAYLIST_V1);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].url, "http://proxy/stream/a/2");
+        assert_eq!(
+            entries[1].metadata,
+            "#EXTINF:-1 tvg-chno=\"2\",Sport\n#EXTVLCOPT:http-user-agent=VLC"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_and_delta() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .cleanup_policy(sandboxed_file_manager::CleanupPolicy::disabled())
+            .build()
+            .await
+            .unwrap();
+        let store = PlaylistDeltaStore::new(manager, 2);
+        let proxy_id = Uuid::new_v4();
+
+        assert!(matches!(
+            store.delta(&proxy_id, 1, PLAYLIST_V1).await.unwrap(),
+            DeltaOutcome::NotRecorded
+        ));
+        assert_eq!(store.record(&proxy_id, PLAYLIST_V1).await.unwrap(), Some(1));
+        // Identical content keeps the version
+        assert_eq!(store.record(&proxy_id, PLAYLIST_V1).await.unwrap(), Some(1));
+        assert_eq!(store.record(&proxy_id, PLAYLIST_V2).await.unwrap(), Some(2));
+        assert_eq!(store.current_version(&proxy_id).await, Some(2));
+
+        let DeltaOutcome::Delta(delta) = store.delta(&proxy_id, 1, PLAYLIST_V2).await.unwrap()
+        else {
+            panic!("expected a delta");
+        };
+        assert_eq!((delta.since, delta.version), (1, 2));
+        assert_eq!(delta.added.len(), 1);
+        assert_eq!(delta.added[0].url, "http://proxy/stream/a/4");
+        assert_eq!(delta.changed.len(), 1);
+        assert_eq!(delta.changed[0].url, "http://proxy/stream/a/2");
+        assert_eq!(delta.removed, vec!["http://proxy/stream/a/3".to_string()]);
+
+        // Only two generations are kept
+        store.record(&proxy_id, PLAYLIST_V1).await.unwrap();
+        assert!(matches!(
+            store.delta(&proxy_id, 1, PLAYLIST_V1).await.unwrap(),
+            DeltaOutcome::Unavailable { version: 3 }
+        ));
+    }
+}
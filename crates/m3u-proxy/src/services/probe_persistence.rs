@@ -51,6 +51,19 @@ impl ProbePersistenceService {
         method: ProbeMethod,
         probe_source: Option<String>,
     ) -> Result<LastKnownCodec> {
+        self.probe_and_persist_detailed(stream_url, method, probe_source)
+            .await
+            .map(|(stored, _)| stored)
+    }
+
+    /// Same as [`probe_and_persist`](Self::probe_and_persist), additionally returning the raw
+    /// probe result (all tracks) for diagnostics.
+    pub async fn probe_and_persist_detailed(
+        &self,
+        stream_url: &str,
+        method: ProbeMethod,
+        probe_source: Option<String>,
+    ) -> Result<(LastKnownCodec, ProbeResult)> {
         let _lock_guard = self.acquire_in_flight_lock(stream_url).await;
 
         // Fetch existing before probing (used to decide failure handling & change detection)
@@ -121,7 +134,7 @@ impl ProbePersistenceService {
                     %stream_url,
                     "Skipping persistence: no material codec changes detected"
                 );
-                return Ok(prev.clone());
+                return Ok((prev.clone(), probe_result));
             }
         }
 
@@ -133,7 +146,7 @@ impl ProbePersistenceService {
             resolution = ?stored.resolution,
             "Persisted (or updated) codec information"
         );
-        Ok(stored)
+        Ok((stored, probe_result))
     }
 
     async fn acquire_in_flight_lock(&self, stream_url: &str) -> Arc<Mutex<()>> {
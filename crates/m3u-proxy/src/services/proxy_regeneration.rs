@@ -3,13 +3,23 @@
 //! This service manages automatic regeneration of stream proxies when their
 //! associated sources (stream or EPG) are updated. It uses pure in-memory state
 //! with Tokio timers for delayed execution and deduplication.
+//!
+//! Source updates are batched per proxy: the first trigger opens a debounce window, later
+//! triggers inside it join the batch, and the proxy regenerates once when it closes.
+//!
+//! Changes to channels' EPG identity (data mapping rules touching `tvg_id`, manual EPG
+//! mappings) queue an `epg_identity` trigger instead. A batch made only of those re-maps
+//! guide data and republishes the XMLTV, leaving the live playlist untouched.
 
 use crate::config::Config;
 use crate::database::Database;
 use crate::database::repositories::stream_proxy::StreamProxySeaOrmRepository;
 use crate::ingestor::IngestionStateManager;
+use crate::models::data_mapping::{DataMappingRule, DataMappingRuleScope};
 use crate::observability::AppObservability;
+use crate::services::generation_hooks::GenerationHookSummary;
 use crate::services::progress_service::{OperationType, ProgressManager, ProgressService};
+use crate::services::{ChannelCountAlertService, GenerationHookService, LeaderElection};
 use opentelemetry::KeyValue;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -27,6 +37,71 @@ pub struct RegenerationRequest {
     pub is_manual: bool,
     pub requested_at: chrono::DateTime<chrono::Utc>,
     pub progress_manager: Option<Arc<ProgressManager>>,
+    /// Source updates coalesced into this request (for a manual request, those of the
+    /// batch it superseded)
+    pub triggers: Vec<RegenerationTrigger>,
+    /// Only re-map and publish the guide: every trigger was an EPG identity change
+    pub guide_only: bool,
+}
+
+/// Trigger type of a change to channels' EPG identity, which only needs the guide re-mapped
+pub const EPG_IDENTITY_TRIGGER: &str = "epg_identity";
+
+/// A source update that asked for a proxy regeneration
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct RegenerationTrigger {
+    /// Source, data mapping rule or proxy that changed
+    pub source_id: Uuid,
+    /// "stream", "epg" or "epg_identity"
+    pub source_type: String,
+    pub triggered_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl RegenerationTrigger {
+    pub fn new(source_id: Uuid, source_type: &str) -> Self {
+        Self {
+            source_id,
+            source_type: source_type.to_string(),
+            triggered_at: chrono::Utc::now(),
+        }
+    }
+
+    pub fn is_epg_identity(&self) -> bool {
+        self.source_type == EPG_IDENTITY_TRIGGER
+    }
+}
+
+/// Triggers batched for a proxy while its debounce window is open
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct PendingRegeneration {
+    pub opened_at: chrono::DateTime<chrono::Utc>,
+    /// When the window closes and the proxy regenerates
+    pub regenerate_at: chrono::DateTime<chrono::Utc>,
+    pub triggers: Vec<RegenerationTrigger>,
+}
+
+impl PendingRegeneration {
+    fn new(window: Duration, triggers: Vec<RegenerationTrigger>) -> Self {
+        let opened_at = chrono::Utc::now();
+        Self {
+            opened_at,
+            regenerate_at: opened_at
+                + chrono::Duration::from_std(window).unwrap_or_else(|_| chrono::Duration::zero()),
+            triggers,
+        }
+    }
+
+    /// Add a trigger; a source that updates again within the window is only kept once
+    fn add(&mut self, trigger: RegenerationTrigger) {
+        match self
+            .triggers
+            .iter_mut()
+            .find(|existing| existing.source_id == trigger.source_id)
+        {
+            Some(existing) => existing.triggered_at = trigger.triggered_at,
+            None => self.triggers.push(trigger),
+        }
+    }
 }
 
 /// Arguments required for executing a single proxy regeneration (bundled to satisfy clippy)
@@ -39,12 +114,15 @@ struct SingleProxyRegenArgs {
     app_config: Config,
     http_client_factory: Arc<crate::utils::HttpClientFactory>,
     ingestion_state_manager: Arc<IngestionStateManager>,
+    channel_count_alerts: Option<ChannelCountAlertService>,
+    guide_only: bool,
 }
 
 /// Configuration for the regeneration service
 #[derive(Debug, Clone)]
 pub struct RegenerationConfig {
-    /// Delay in seconds after source updates before triggering regeneration
+    /// Default debounce window in seconds: source updates within it are batched into one
+    /// regeneration (proxies can override it)
     pub delay_seconds: u64,
     /// Maximum concurrent regenerations (kept for compatibility, but queue is now sequential)
     pub max_concurrent: usize,
@@ -68,6 +146,10 @@ pub struct ProxyRegenerationService {
     app_config: Config,
     /// Active delayed regeneration timers
     pending_regenerations: Arc<Mutex<HashMap<Uuid, tokio::task::JoinHandle<()>>>>,
+    /// Triggers batched per proxy while its debounce window is open
+    pending_batches: Arc<Mutex<HashMap<Uuid, PendingRegeneration>>>,
+    /// Triggers coalesced into each proxy's latest automatic regeneration
+    last_triggers: Arc<Mutex<HashMap<Uuid, Vec<RegenerationTrigger>>>>,
     /// Currently running regeneration tasks
     active_regenerations: Arc<Mutex<HashMap<Uuid, tokio::task::JoinHandle<()>>>>,
     /// Track which proxies are queued to prevent duplicates
@@ -88,6 +170,10 @@ pub struct ProxyRegenerationService {
     http_client_factory: Arc<crate::utils::HttpClientFactory>,
     /// Observability for metrics collection
     observability: Option<Arc<AppObservability>>,
+    /// In cluster mode only the leader processes queued regenerations
+    leader_election: Arc<std::sync::OnceLock<Arc<LeaderElection>>>,
+    /// Compares each generation's channel count with the previous one
+    channel_count_alerts: Arc<std::sync::OnceLock<ChannelCountAlertService>>,
 }
 
 impl ProxyRegenerationService {
@@ -114,6 +200,8 @@ impl ProxyRegenerationService {
             config: config.unwrap_or_default(),
             app_config,
             pending_regenerations: Arc::new(Mutex::new(HashMap::new())),
+            pending_batches: Arc::new(Mutex::new(HashMap::new())),
+            last_triggers: Arc::new(Mutex::new(HashMap::new())),
             active_regenerations: Arc::new(Mutex::new(HashMap::new())),
             queued_proxies: Arc::new(Mutex::new(HashSet::new())),
             recent_requests: Arc::new(Mutex::new(HashMap::new())),
@@ -125,6 +213,8 @@ impl ProxyRegenerationService {
             temp_file_manager: temp_file_manager.clone(),
             http_client_factory,
             observability: None,
+            leader_election: Arc::new(std::sync::OnceLock::new()),
+            channel_count_alerts: Arc::new(std::sync::OnceLock::new()),
         };
 
         // Start the priority queue processor in the background
@@ -154,6 +244,18 @@ impl ProxyRegenerationService {
         self
     }
 
+    /// Only process queued regenerations while this instance is the cluster leader
+    pub fn with_leader_election(self, leader_election: Arc<LeaderElection>) -> Self {
+        let _ = self.leader_election.set(leader_election);
+        self
+    }
+
+    /// Raise alerts when a generation's channel count changes sharply
+    pub fn with_channel_count_alerts(self, alerts: ChannelCountAlertService) -> Self {
+        let _ = self.channel_count_alerts.set(alerts);
+        self
+    }
+
     /// Wait for a duration while checking for cancellation
     /// Returns true if cancelled, false if duration completed
     async fn wait_with_cancellation(&self, duration: Duration) -> bool {
@@ -194,11 +296,19 @@ impl ProxyRegenerationService {
         let queued_proxies = self.queued_proxies.clone();
         let app_config = self.app_config.clone();
         let http_client_factory = self.http_client_factory.clone();
+        let leader_election = self.leader_election.clone();
+        let channel_count_alerts = self.channel_count_alerts.clone();
 
         tokio::spawn(async move {
             info!("Starting sequential proxy regeneration processor (manual priority)");
 
             loop {
+                // Followers leave requests queued until they become leader
+                if leader_election.get().is_some_and(|l| !l.is_leader()) {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+
                 // Step 1: Always check manual queue first (higher priority)
                 if let Ok(manual_request) = manual_queue_receiver.try_recv() {
                     Self::process_regeneration_request(
@@ -210,6 +320,7 @@ impl ProxyRegenerationService {
                         &queued_proxies,
                         &app_config,
                         &http_client_factory,
+                        channel_count_alerts.get(),
                     )
                     .await;
                     // Immediately iterate again to prioritise any additional manual work
@@ -226,6 +337,7 @@ impl ProxyRegenerationService {
                                 &queued_proxies,
                                 &app_config,
                                 &http_client_factory,
+                                channel_count_alerts.get(),
                             )
                             .await;
                             // Loop will naturally continue to the next iteration
@@ -257,6 +369,7 @@ impl ProxyRegenerationService {
         queued_proxies: &Arc<Mutex<HashSet<Uuid>>>,
         app_config: &Config,
         http_client_factory: &Arc<crate::utils::HttpClientFactory>,
+        channel_count_alerts: Option<&ChannelCountAlertService>,
     ) {
         let proxy_id = request.proxy_id;
 
@@ -267,8 +380,12 @@ impl ProxyRegenerationService {
         }
 
         debug!(
-            "Processing regeneration request for proxy {} (manual: {}, requested: {})",
-            proxy_id, request.is_manual, request.requested_at
+            "Processing regeneration request for proxy {} (manual: {}, guide only: {}, requested: {}, coalesced triggers: {})",
+            proxy_id,
+            request.is_manual,
+            request.guide_only,
+            request.requested_at,
+            request.triggers.len()
         );
 
         // CRITICAL: Always check ingestion status before processing (ingestion has priority)
@@ -300,6 +417,8 @@ impl ProxyRegenerationService {
             app_config: app_config.clone(),
             http_client_factory: http_client_factory.clone(),
             ingestion_state_manager: ingestion_state_manager.clone(),
+            channel_count_alerts: channel_count_alerts.cloned(),
+            guide_only: request.guide_only,
         })
         .await
         {
@@ -313,12 +432,177 @@ impl ProxyRegenerationService {
     }
 
     /// Queue a proxy for regeneration due to source update (with delay)
+    ///
+    /// Joins the proxy's open batch if its debounce window has not closed yet.
     pub async fn queue_proxy_regeneration(
         &self,
         proxy_id: Uuid,
         trigger_source_id: Uuid,
         trigger_source_type: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.queue_regeneration_batch(
+            proxy_id,
+            vec![RegenerationTrigger::new(
+                trigger_source_id,
+                trigger_source_type,
+            )],
+        )
+        .await
+    }
+
+    /// Queue an EPG re-map of a proxy whose channels' EPG identity changed
+    ///
+    /// Batched like source updates; a batch holding nothing else republishes only the guide.
+    pub async fn queue_epg_remap(
+        &self,
+        proxy_id: Uuid,
+        cause_id: Uuid,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.queue_proxy_regeneration(proxy_id, cause_id, EPG_IDENTITY_TRIGGER)
+            .await
+    }
+
+    /// Queue EPG re-maps of the proxies a data mapping rule applies to
+    pub async fn queue_epg_remap_for_rule(&self, rule: &DataMappingRule) {
+        let source_type = rule.source_type.to_string();
+        let proxy_ids = match &rule.scope {
+            DataMappingRuleScope::Proxy { proxy_id } => Ok(vec![*proxy_id]),
+            DataMappingRuleScope::Source { source_id } => {
+                self.find_affected_proxies(*source_id, &source_type).await
+            }
+            DataMappingRuleScope::Global => self.find_proxies_with_sources(&source_type).await,
+        };
+
+        match proxy_ids {
+            Ok(proxy_ids) => {
+                if !proxy_ids.is_empty() {
+                    info!(
+                        "Data mapping rule '{}' changes EPG identity, re-mapping EPG of {} proxies",
+                        rule.name,
+                        proxy_ids.len()
+                    );
+                }
+                for proxy_id in proxy_ids {
+                    if let Err(e) = self.queue_epg_remap(proxy_id, rule.id).await {
+                        warn!("Failed to queue EPG re-map of proxy {}: {}", proxy_id, e);
+                    }
+                }
+            }
+            Err(e) => warn!(
+                "Failed to find proxies affected by data mapping rule '{}': {}",
+                rule.name, e
+            ),
+        }
+    }
+
+    /// Proxies with at least one source of `source_type` ("stream" or "epg")
+    async fn find_proxies_with_sources(
+        &self,
+        source_type: &str,
+    ) -> Result<Vec<Uuid>, anyhow::Error> {
+        use crate::entities::prelude::*;
+        use sea_orm::EntityTrait;
+
+        let connection = self.database.connection();
+        let proxy_ids: HashSet<Uuid> = match source_type {
+            "stream" => ProxySources::find()
+                .all(&*connection)
+                .await?
+                .into_iter()
+                .map(|rel| rel.proxy_id)
+                .collect(),
+            "epg" => ProxyEpgSources::find()
+                .all(&*connection)
+                .await?
+                .into_iter()
+                .map(|rel| rel.proxy_id)
+                .collect(),
+            _ => return Err(anyhow::anyhow!("Invalid source_type: {}", source_type)),
+        };
+        Ok(proxy_ids.into_iter().collect())
+    }
+
+    /// Add triggers to the proxy's open batch, or open a new batch closing after `window`
+    ///
+    /// Returns true when a new batch was opened; its timer is then up to the caller.
+    async fn open_or_join_batch(
+        &self,
+        proxy_id: Uuid,
+        window: Duration,
+        triggers: Vec<RegenerationTrigger>,
+    ) -> bool {
+        let mut batches = self.pending_batches.lock().await;
+        let Some(batch) = batches.get_mut(&proxy_id) else {
+            batches.insert(proxy_id, PendingRegeneration::new(window, triggers));
+            return true;
+        };
+        for trigger in triggers {
+            debug!(
+                "Batched {} source {} into the pending regeneration of proxy {} (due {})",
+                trigger.source_type, trigger.source_id, proxy_id, batch.regenerate_at
+            );
+            batch.add(trigger);
+        }
+        false
+    }
+
+    /// Add a trigger to the proxy's open batch; false when no window is open
+    async fn join_open_batch(&self, proxy_id: Uuid, trigger: RegenerationTrigger) -> bool {
+        match self.pending_batches.lock().await.get_mut(&proxy_id) {
+            Some(batch) => {
+                batch.add(trigger);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Debounce window of a proxy: its own setting, or the service default
+    async fn debounce_window(&self, proxy_id: Uuid) -> Duration {
+        let seconds = match self.proxy_repository.find_by_id(&proxy_id).await {
+            Ok(Some(proxy)) => proxy
+                .regeneration_debounce_seconds
+                .and_then(|seconds| u64::try_from(seconds).ok())
+                .unwrap_or(self.config.delay_seconds),
+            _ => self.config.delay_seconds,
+        };
+        Duration::from_secs(seconds)
+    }
+
+    /// Cancel the proxy's delayed regeneration, returning the triggers it had batched
+    async fn cancel_pending(&self, proxy_id: Uuid) -> Vec<RegenerationTrigger> {
+        if let Some(existing_handle) = self.pending_regenerations.lock().await.remove(&proxy_id) {
+            existing_handle.abort();
+            debug!(
+                "Cancelled existing regeneration timer for proxy {}",
+                proxy_id
+            );
+        }
+        self.take_batch(proxy_id).await
+    }
+
+    /// Close the proxy's batch, returning its triggers
+    async fn take_batch(&self, proxy_id: Uuid) -> Vec<RegenerationTrigger> {
+        self.pending_batches
+            .lock()
+            .await
+            .remove(&proxy_id)
+            .map(|batch| batch.triggers)
+            .unwrap_or_default()
+    }
+
+    /// Open a debounce window for the triggers, or add them to the proxy's open one
+    async fn queue_regeneration_batch(
+        &self,
+        proxy_id: Uuid,
+        triggers: Vec<RegenerationTrigger>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(first_trigger) = triggers.first().cloned() else {
+            return Ok(());
+        };
+        let trigger_source_id = first_trigger.source_id;
+        let trigger_source_type = first_trigger.source_type.as_str();
+
         // Record queue attempt metrics
         if let Some(obs) = &self.observability {
             obs.proxy_generations.add(
@@ -356,10 +640,24 @@ impl ProxyRegenerationService {
             }
         }
 
-        let mut pending = self.pending_regenerations.lock().await;
+        // Coalesce into the open batch instead of restarting the window
+        let window = self.debounce_window(proxy_id).await;
+        let delay_seconds = window.as_secs();
+        if !self.open_or_join_batch(proxy_id, window, triggers).await {
+            if let Some(obs) = &self.observability {
+                obs.proxy_generations.add(
+                    1,
+                    &[
+                        KeyValue::new("operation", "queue_regeneration_batched"),
+                        KeyValue::new("trigger_type", trigger_source_type.to_string()),
+                    ],
+                );
+            }
+            return Ok(());
+        }
 
-        // Cancel existing timer for this proxy if any (deduplication)
-        if let Some(existing_handle) = pending.remove(&proxy_id) {
+        // A timer left over from a closed batch is superseded by the new window
+        if let Some(existing_handle) = self.pending_regenerations.lock().await.remove(&proxy_id) {
             existing_handle.abort();
             debug!(
                 "Cancelled existing regeneration timer for proxy {}",
@@ -375,7 +673,6 @@ impl ProxyRegenerationService {
             trigger_source_id,
         )
         .await;
-        let trigger_source_type_owned = trigger_source_type.to_string();
 
         // Initialize progress tracking using ProgressService
         let progress_manager = match self
@@ -423,6 +720,7 @@ impl ProxyRegenerationService {
                             "Failed to create progress manager for proxy {} even after cleanup: {} - skipping regeneration",
                             proxy_id, e2
                         );
+                        self.pending_batches.lock().await.remove(&proxy_id);
                         return Ok(());
                     }
                 }
@@ -430,11 +728,6 @@ impl ProxyRegenerationService {
         };
 
         // Create delayed regeneration task
-        let _database = self.database.clone();
-        let _temp_file_manager = self.temp_file_manager.clone();
-        let delay_seconds = self.config.delay_seconds;
-        let _pending_clone = self.pending_regenerations.clone();
-        let _active_clone = self.active_regenerations.clone();
         let service_clone = self.clone();
 
         let handle = tokio::spawn(async move {
@@ -445,7 +738,7 @@ impl ProxyRegenerationService {
                     updater
                         .update_progress(
                             10.0,
-                            &format!("Waiting {delay_seconds}s before regeneration"),
+                            &format!("Batching source updates for {delay_seconds}s"),
                         )
                         .await;
                 }
@@ -456,10 +749,7 @@ impl ProxyRegenerationService {
             );
 
             // Wait for the delay, but check for shutdown signal
-            if service_clone
-                .wait_with_cancellation(Duration::from_secs(delay_seconds))
-                .await
-            {
+            if service_clone.wait_with_cancellation(window).await {
                 debug!("Proxy {} regeneration cancelled due to shutdown", proxy_id);
                 // Remove from pending regenerations since task was cancelled
                 service_clone
@@ -467,20 +757,39 @@ impl ProxyRegenerationService {
                     .lock()
                     .await
                     .remove(&proxy_id);
+                service_clone.pending_batches.lock().await.remove(&proxy_id);
                 return;
             }
 
+            // Close the window: later triggers open a new batch
+            let triggers = service_clone.take_batch(proxy_id).await;
+            let reasons = triggers
+                .iter()
+                .map(|t| format!("{} source {}", t.source_type, t.source_id))
+                .collect::<Vec<_>>()
+                .join(", ");
             info!(
-                "Delay completed for proxy {} - queueing for sequential processing (triggered by {} source {})",
-                proxy_id, trigger_source_type_owned, trigger_source_id
+                "Debounce window closed for proxy {} - queueing one regeneration for {} coalesced trigger(s): {}",
+                proxy_id,
+                triggers.len(),
+                reasons
             );
+            service_clone
+                .last_triggers
+                .lock()
+                .await
+                .insert(proxy_id, triggers.clone());
 
             // After delay, queue the regeneration request for sequential processing
+            let guide_only =
+                !triggers.is_empty() && triggers.iter().all(RegenerationTrigger::is_epg_identity);
             let request = RegenerationRequest {
                 proxy_id,
                 is_manual: false,
                 requested_at: chrono::Utc::now(),
                 progress_manager,
+                triggers,
+                guide_only,
             };
 
             // Check if already queued to prevent duplicates
@@ -513,11 +822,14 @@ impl ProxyRegenerationService {
                 .remove(&proxy_id);
         });
 
-        pending.insert(proxy_id, handle);
+        self.pending_regenerations
+            .lock()
+            .await
+            .insert(proxy_id, handle);
 
         info!(
-            "Queued proxy {} for regeneration (trigger: {} {}, scheduled in {}s)",
-            proxy_id, trigger_source_type, trigger_source_id, self.config.delay_seconds
+            "Queued proxy {} for regeneration (trigger: {} {}, batching for {}s)",
+            proxy_id, trigger_source_type, trigger_source_id, delay_seconds
         );
 
         // Record successful queue metrics
@@ -527,7 +839,7 @@ impl ProxyRegenerationService {
                 &[
                     KeyValue::new("operation", "queue_regeneration_success"),
                     KeyValue::new("trigger_type", trigger_source_type.to_string()),
-                    KeyValue::new("delay_seconds", self.config.delay_seconds.to_string()),
+                    KeyValue::new("delay_seconds", delay_seconds.to_string()),
                 ],
             );
 
@@ -559,17 +871,9 @@ impl ProxyRegenerationService {
             }
         }
 
-        // Cancel any pending delayed regeneration since manual takes priority
-        {
-            let mut pending = self.pending_regenerations.lock().await;
-            if let Some(existing_handle) = pending.remove(&proxy_id) {
-                existing_handle.abort();
-                debug!(
-                    "Cancelled pending auto regeneration for manual trigger: {}",
-                    proxy_id
-                );
-            }
-        }
+        // Cancel any pending delayed regeneration since manual takes priority; the manual
+        // run covers the source updates batched so far
+        let superseded_triggers = self.cancel_pending(proxy_id).await;
 
         // Remove from auto queue if it exists there (manual takes priority)
         {
@@ -638,6 +942,8 @@ impl ProxyRegenerationService {
             is_manual: true,
             requested_at: chrono::Utc::now(),
             progress_manager: Some(progress_manager),
+            triggers: superseded_triggers,
+            guide_only: false,
         };
 
         if let Err(e) = self.manual_queue_sender.send(request) {
@@ -670,6 +976,8 @@ impl ProxyRegenerationService {
             app_config,
             http_client_factory,
             ingestion_state_manager,
+            channel_count_alerts,
+            guide_only,
         } = args;
         // Create and track the regeneration task
         let handle = tokio::spawn(async move {
@@ -685,6 +993,8 @@ impl ProxyRegenerationService {
                 app_config,
                 &http_client_factory,
                 ingestion_state_manager.clone(),
+                channel_count_alerts,
+                guide_only,
             )
             .await
             {
@@ -715,6 +1025,9 @@ impl ProxyRegenerationService {
     }
 
     /// Internal static method for regenerating a single proxy (used by queue processor)
+    ///
+    /// A `guide_only` run goes through the whole pipeline so channels carry their current
+    /// EPG identity, but publishes only the XMLTV guide.
     async fn regenerate_single_proxy_internal(
         database: Database,
         temp_file_manager: sandboxed_file_manager::SandboxedManager,
@@ -723,6 +1036,8 @@ impl ProxyRegenerationService {
         app_config: Config,
         http_client_factory: &crate::utils::HttpClientFactory,
         ingestion_state_manager: Arc<crate::ingestor::IngestionStateManager>,
+        channel_count_alerts: Option<ChannelCountAlertService>,
+        guide_only: bool,
     ) -> Result<(), Box<dyn std::error::Error>> {
         use crate::pipeline::PipelineOrchestratorFactory;
 
@@ -755,9 +1070,38 @@ impl ProxyRegenerationService {
             );
         }
 
+        let stream_proxy_repo = crate::database::repositories::StreamProxySeaOrmRepository::new(
+            database_connection.clone(),
+        );
+        let generation_hooks = GenerationHookService::from_config(&app_config);
+        let mut proxy_name = None;
+        if let Some(hooks) = &generation_hooks {
+            let name = match stream_proxy_repo.find_by_id(&proxy_id).await {
+                Ok(Some(proxy)) => proxy.name,
+                _ => proxy_id.to_string(),
+            };
+            if let Err(e) = hooks
+                .run(&GenerationHookSummary::pre(proxy_id, &name))
+                .await
+            {
+                error!("Regeneration of proxy {} aborted: {}", proxy_id, e);
+                if let Some(pm) = &progress_manager {
+                    pm.fail(&e).await;
+                }
+                return Err(e.into());
+            }
+            proxy_name = Some(name);
+        }
+
         // Create and execute the regeneration pipeline
         let mut orchestrator = factory.create_for_proxy(proxy_id).await?;
         orchestrator.set_progress_manager(progress_manager.clone());
+        if guide_only && orchestrator.publish_guide_only() {
+            info!(
+                "Re-mapping EPG data of proxy {}: publishing the guide only",
+                proxy_id
+            );
+        }
 
         match orchestrator.execute_pipeline().await {
             Ok(result) => {
@@ -767,10 +1111,6 @@ impl ProxyRegenerationService {
                         factory.unregister_orchestrator(proxy_id).await;
 
                         // CRITICAL FIX: Update the proxy's last_generated_at timestamp
-                        let stream_proxy_repo =
-                            crate::database::repositories::StreamProxySeaOrmRepository::new(
-                                database_connection.clone(),
-                            );
                         let update_time = chrono::Utc::now();
                         if let Err(e) = stream_proxy_repo.update_last_generated(proxy_id).await {
                             warn!(
@@ -785,6 +1125,35 @@ impl ProxyRegenerationService {
                             );
                         }
 
+                        if proxy_name.is_none()
+                            && (channel_count_alerts.is_some() || generation_hooks.is_some())
+                        {
+                            proxy_name =
+                                Some(match stream_proxy_repo.find_by_id(&proxy_id).await {
+                                    Ok(Some(proxy)) => proxy.name,
+                                    _ => proxy_id.to_string(),
+                                });
+                        }
+                        let proxy_name = proxy_name.unwrap_or_else(|| proxy_id.to_string());
+
+                        if let (Some(alerts), Some(channel_count)) =
+                            (&channel_count_alerts, result.published_channel_count())
+                        {
+                            alerts
+                                .check_proxy(proxy_id, &proxy_name, channel_count)
+                                .await;
+                        }
+
+                        if let Some(hooks) = &generation_hooks {
+                            let _ = hooks
+                                .run(&GenerationHookSummary::post(
+                                    &proxy_name,
+                                    &result,
+                                    &app_config.storage,
+                                ))
+                                .await;
+                        }
+
                         if let Some(pm) = &progress_manager {
                             pm.complete().await;
                         }
@@ -966,6 +1335,21 @@ impl ProxyRegenerationService {
     ) {
         let now = chrono::Utc::now();
 
+        // A source update inside the proxy's open debounce window joins its batch
+        if self
+            .join_open_batch(
+                proxy_id,
+                RegenerationTrigger::new(trigger_source_id, trigger_source_type),
+            )
+            .await
+        {
+            debug!(
+                "Batched regeneration for proxy {} (triggered by {} source {}) into its open debounce window",
+                proxy_id, trigger_source_type, trigger_source_id
+            );
+            return;
+        }
+
         // Check for recent requests (within 30 seconds) to prevent rapid duplicates from scheduler
         {
             let mut recent_requests = self.recent_requests.lock().await;
@@ -1068,8 +1452,10 @@ impl ProxyRegenerationService {
             if let Err(e) = self
                 .queue_proxy_regeneration_with_delay(
                     proxy_id,
-                    completed_source_id,
-                    completed_source_type,
+                    vec![RegenerationTrigger::new(
+                        completed_source_id,
+                        completed_source_type,
+                    )],
                     30,
                 )
                 .await
@@ -1098,30 +1484,36 @@ impl ProxyRegenerationService {
     }
 
     /// Queue proxy regeneration with custom delay (for coordination retries)
+    ///
+    /// The retry holds the proxy's batch open, so source updates arriving meanwhile join it.
     async fn queue_proxy_regeneration_with_delay(
         &self,
         proxy_id: Uuid,
-        trigger_source_id: Uuid,
-        trigger_source_type: &str,
+        triggers: Vec<RegenerationTrigger>,
         delay_seconds: u64,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // Similar to queue_proxy_regeneration but with custom delay and coordination retry
 
-        // Cancel existing timer for this proxy if any (deduplication)
+        // Cancel existing timer for this proxy if any, keeping the triggers it had batched
+        let mut batch = PendingRegeneration::new(Duration::from_secs(delay_seconds), Vec::new());
+        for trigger in self
+            .cancel_pending(proxy_id)
+            .await
+            .into_iter()
+            .chain(triggers)
         {
-            let mut pending = self.pending_regenerations.lock().await;
-            if let Some(existing_handle) = pending.remove(&proxy_id) {
-                existing_handle.abort();
-                debug!(
-                    "Cancelled existing regeneration timer for proxy {} (coordination retry)",
-                    proxy_id
-                );
-            }
+            batch.add(trigger);
         }
+        let reasons = batch
+            .triggers
+            .iter()
+            .map(|t| format!("{} source {}", t.source_type, t.source_id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.pending_batches.lock().await.insert(proxy_id, batch);
 
         // Create delayed regeneration task that retries coordination
         let service_clone = self.clone();
-        let trigger_source_type_owned = trigger_source_type.to_string();
 
         let handle = tokio::spawn(async move {
             sleep(Duration::from_secs(delay_seconds)).await;
@@ -1130,6 +1522,7 @@ impl ProxyRegenerationService {
                 "Retrying coordination for proxy {} after {}s delay",
                 proxy_id, delay_seconds
             );
+            let triggers = service_clone.take_batch(proxy_id).await;
 
             // Check one more time if we should proceed, then trigger regeneration directly
             let all_sources = match service_clone.get_proxy_sources(proxy_id).await {
@@ -1167,7 +1560,7 @@ impl ProxyRegenerationService {
             );
 
             if let Err(e) = service_clone
-                .queue_proxy_regeneration(proxy_id, trigger_source_id, &trigger_source_type_owned)
+                .queue_regeneration_batch(proxy_id, triggers)
                 .await
             {
                 error!(
@@ -1184,8 +1577,8 @@ impl ProxyRegenerationService {
         }
 
         debug!(
-            "Scheduled coordination retry for proxy {} in {}s (triggered by {} source {})",
-            proxy_id, delay_seconds, trigger_source_type, trigger_source_id
+            "Scheduled coordination retry for proxy {} in {}s (triggered by {})",
+            proxy_id, delay_seconds, reasons
         );
 
         Ok(())
@@ -1277,6 +1670,13 @@ impl ProxyRegenerationService {
     /// Get queue status summary for API compatibility
     pub async fn get_queue_status(&self) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
         let pending_count = self.pending_regenerations.lock().await.len();
+        let batched_triggers: usize = self
+            .pending_batches
+            .lock()
+            .await
+            .values()
+            .map(|batch| batch.triggers.len())
+            .sum();
         let active_count = self.active_regenerations.lock().await.len();
         let queued_count = self.queued_proxies.lock().await.len();
 
@@ -1289,6 +1689,7 @@ impl ProxyRegenerationService {
 
         Ok(serde_json::json!({
             "pending_delays": pending_count,
+            "batched_triggers": batched_triggers,
             "active_regenerations": active_count,
             "queued_for_processing": queued_count,
             "total_tracked": pending_count + active_count + queued_count,
@@ -1297,6 +1698,21 @@ impl ProxyRegenerationService {
         }))
     }
 
+    /// Triggers batched for a proxy whose debounce window is open
+    pub async fn pending_regeneration(&self, proxy_id: Uuid) -> Option<PendingRegeneration> {
+        self.pending_batches.lock().await.get(&proxy_id).cloned()
+    }
+
+    /// Triggers coalesced into the proxy's latest automatic regeneration
+    pub async fn last_regeneration_triggers(&self, proxy_id: Uuid) -> Vec<RegenerationTrigger> {
+        self.last_triggers
+            .lock()
+            .await
+            .get(&proxy_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// Cancel all pending regenerations (useful for shutdown)
     pub async fn cancel_all_pending(&self) {
         let mut pending = self.pending_regenerations.lock().await;
@@ -1306,6 +1722,7 @@ impl ProxyRegenerationService {
             handle.abort();
             debug!("Cancelled pending regeneration for proxy {}", proxy_id);
         }
+        self.pending_batches.lock().await.clear();
 
         for (proxy_id, handle) in active.drain() {
             handle.abort();
@@ -1375,6 +1792,7 @@ impl ProxyRegenerationService {
         let source_type_display = match trigger_source_type {
             "stream" => "Stream Source",
             "epg" => "EPG Source",
+            EPG_IDENTITY_TRIGGER => "EPG identity change",
             _ => "Source",
         };
 
@@ -1418,6 +1836,7 @@ mod tests {
         let test_database = crate::database::Database {
             connection: Arc::clone(&db_connection),
             read_connection: Arc::clone(&db_connection),
+            replica: None,
             backend: sea_orm::DatabaseBackend::Sqlite,
             ingestion_config: crate::config::IngestionConfig::default(),
             database_type: crate::database::DatabaseType::SQLite,
@@ -1474,6 +1893,62 @@ mod tests {
         // We verify that both calls succeed without errors (deduplication is working)
     }
 
+    #[tokio::test]
+    async fn test_triggers_within_window_are_batched() {
+        let ingestion_state_manager = Arc::new(IngestionStateManager::new());
+        let progress_service = Arc::new(ProgressService::new(ingestion_state_manager.clone()));
+        let db_connection = Arc::new(
+            sea_orm::MockDatabase::new(sea_orm::DatabaseBackend::Sqlite).into_connection(),
+        );
+        let test_database = crate::database::Database {
+            connection: Arc::clone(&db_connection),
+            read_connection: Arc::clone(&db_connection),
+            replica: None,
+            backend: sea_orm::DatabaseBackend::Sqlite,
+            ingestion_config: crate::config::IngestionConfig::default(),
+            database_type: crate::database::DatabaseType::SQLite,
+        };
+        let service = ProxyRegenerationService::new(
+            test_database,
+            StreamProxySeaOrmRepository::new(Arc::clone(&db_connection)),
+            Config::default(),
+            Some(RegenerationConfig {
+                delay_seconds: 60,
+                max_concurrent: 1,
+            }),
+            sandboxed_file_manager::SandboxedManager::builder()
+                .base_directory(std::env::temp_dir().join("m3u_proxy_test3"))
+                .build()
+                .await
+                .unwrap(),
+            progress_service,
+            ingestion_state_manager,
+            Arc::new(crate::utils::HttpClientFactory::new(
+                None,
+                Duration::from_secs(10),
+            )),
+        );
+
+        let proxy_id = Uuid::new_v4();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        for (source_id, source_type) in [(first, "stream"), (second, "epg"), (first, "stream")] {
+            service
+                .queue_proxy_regeneration(proxy_id, source_id, source_type)
+                .await
+                .unwrap();
+        }
+
+        // One window, one timer, and each source recorded once
+        let batch = service.pending_regeneration(proxy_id).await.unwrap();
+        let sources: Vec<Uuid> = batch.triggers.iter().map(|t| t.source_id).collect();
+        assert_eq!(sources, vec![first, second]);
+        assert_eq!(service.pending_regenerations.lock().await.len(), 1);
+
+        // A manual regeneration supersedes the batch
+        service.queue_manual_regeneration(proxy_id).await.unwrap();
+        assert!(service.pending_regeneration(proxy_id).await.is_none());
+    }
+
     #[tokio::test]
     async fn test_manual_regeneration() {
         // Create mock services for testing
@@ -1487,6 +1962,7 @@ mod tests {
         let test_database = crate::database::Database {
             connection: Arc::clone(&db_connection),
             read_connection: Arc::clone(&db_connection),
+            replica: None,
             backend: sea_orm::DatabaseBackend::Sqlite,
             ingestion_config: crate::config::IngestionConfig::default(),
             database_type: crate::database::DatabaseType::SQLite,
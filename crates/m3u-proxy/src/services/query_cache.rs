@@ -0,0 +1,298 @@
+//! In-memory cache of hot API queries
+//!
+//! Dashboards poll source lists, counts and stats far more often than the data behind them
+//! changes. Query results are cached for a short TTL, tagged with the entities they were
+//! built from. Services announce changes on the cache invalidation channel, which the cache
+//! drains before every lookup, so a list fetched right after an update never comes from
+//! before it.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use opentelemetry::KeyValue;
+use serde::Serialize;
+use tokio::sync::{RwLock, broadcast};
+use tracing::debug;
+use utoipa::ToSchema;
+
+use crate::config::QueryCacheConfig;
+use crate::observability::AppObservability;
+
+/// Kind of data that changed, sent on the cache invalidation channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachedEntity {
+    /// Stream sources, including their channels
+    StreamSources,
+    /// EPG sources, including their programmes
+    EpgSources,
+    Proxies,
+}
+
+/// Hit and miss counts of the query cache
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QueryCacheStats {
+    pub enabled: bool,
+    pub ttl_seconds: u64,
+    /// Results currently cached
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Cached results dropped because an entity they were built from changed
+    pub invalidations: u64,
+    /// Fraction of lookups served from the cache, once there has been one
+    pub hit_ratio: Option<f64>,
+}
+
+struct CacheEntry {
+    value: Arc<dyn Any + Send + Sync>,
+    entities: &'static [CachedEntity],
+    expires_at: Instant,
+}
+
+/// Cache of query results keyed by query name
+pub struct QueryCache {
+    enabled: bool,
+    ttl: Duration,
+    entries: RwLock<HashMap<&'static str, CacheEntry>>,
+    invalidation_rx: Mutex<broadcast::Receiver<CachedEntity>>,
+    /// Bumped on every invalidation; a result loaded across one is not stored
+    generation: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+    observability: Option<Arc<AppObservability>>,
+}
+
+impl QueryCache {
+    pub fn new(
+        config: &QueryCacheConfig,
+        invalidation_rx: broadcast::Receiver<CachedEntity>,
+    ) -> Self {
+        Self {
+            enabled: config.enabled,
+            ttl: config.ttl_duration(),
+            entries: RwLock::new(HashMap::new()),
+            invalidation_rx: Mutex::new(invalidation_rx),
+            generation: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+            observability: None,
+        }
+    }
+
+    /// Count lookups in the `query_cache_requests_total` metric
+    pub fn with_observability(mut self, observability: Arc<AppObservability>) -> Self {
+        self.observability = Some(observability);
+        self
+    }
+
+    /// The cached result of `key`, or the result of `load`, cached when it succeeds
+    ///
+    /// `entities` are what the query reads; a change to any of them drops the result.
+    pub async fn get_or_load<T, E, F, Fut>(
+        &self,
+        key: &'static str,
+        entities: &'static [CachedEntity],
+        load: F,
+    ) -> Result<T, E>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        if !self.enabled {
+            return load().await;
+        }
+        self.apply_pending_invalidations().await;
+
+        let cached = self
+            .entries
+            .read()
+            .await
+            .get(key)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .and_then(|entry| entry.value.downcast_ref::<T>().cloned());
+        if let Some(value) = cached {
+            self.record_lookup(key, true);
+            return Ok(value);
+        }
+        self.record_lookup(key, false);
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let value = load().await?;
+        let mut entries = self.entries.write().await;
+        if self.generation.load(Ordering::Acquire) == generation {
+            entries.insert(
+                key,
+                CacheEntry {
+                    value: Arc::new(value.clone()),
+                    entities,
+                    expires_at: Instant::now() + self.ttl,
+                },
+            );
+        }
+        Ok(value)
+    }
+
+    /// Drop every cached result built from `entity`
+    pub async fn invalidate(&self, entity: CachedEntity) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        let mut entries = self.entries.write().await;
+        let before = entries.len();
+        entries.retain(|_, entry| !entry.entities.contains(&entity));
+        let dropped = before - entries.len();
+        if dropped > 0 {
+            debug!("{:?} changed, dropped {} cached queries", entity, dropped);
+            self.invalidations
+                .fetch_add(dropped as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Drop every cached result
+    pub async fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        let mut entries = self.entries.write().await;
+        self.invalidations
+            .fetch_add(entries.len() as u64, Ordering::Relaxed);
+        entries.clear();
+    }
+
+    pub async fn stats(&self) -> QueryCacheStats {
+        self.apply_pending_invalidations().await;
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        #[allow(clippy::cast_precision_loss)]
+        let hit_ratio = (lookups > 0).then(|| hits as f64 / lookups as f64);
+        QueryCacheStats {
+            enabled: self.enabled,
+            ttl_seconds: self.ttl.as_secs(),
+            entries: self.entries.read().await.len(),
+            hits,
+            misses,
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            hit_ratio,
+        }
+    }
+
+    /// Apply changes announced on the invalidation channel since the last lookup
+    async fn apply_pending_invalidations(&self) {
+        let mut changed = Vec::new();
+        let mut lagged = false;
+        {
+            let mut rx = self
+                .invalidation_rx
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            loop {
+                match rx.try_recv() {
+                    Ok(entity) => {
+                        if !changed.contains(&entity) {
+                            changed.push(entity);
+                        }
+                    }
+                    Err(broadcast::error::TryRecvError::Lagged(_)) => lagged = true,
+                    Err(_) => break,
+                }
+            }
+        }
+
+        if lagged {
+            // Missed some changes; nothing cached can be trusted
+            self.clear().await;
+            return;
+        }
+        for entity in changed {
+            self.invalidate(entity).await;
+        }
+    }
+
+    fn record_lookup(&self, key: &'static str, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if let Some(observability) = &self.observability {
+            observability.query_cache_requests.add(
+                1,
+                &[
+                    KeyValue::new("query", key),
+                    KeyValue::new("result", if hit { "hit" } else { "miss" }),
+                ],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> (QueryCache, broadcast::Sender<CachedEntity>) {
+        let (tx, rx) = broadcast::channel(4);
+        (QueryCache::new(&QueryCacheConfig::default(), rx), tx)
+    }
+
+    async fn load(cache: &QueryCache, key: &'static str, value: u32) -> u32 {
+        cache
+            .get_or_load(key, &[CachedEntity::StreamSources], || async {
+                Ok::<_, ()>(value)
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hits_until_invalidated() {
+        let (cache, tx) = cache();
+        assert_eq!(load(&cache, "sources", 1).await, 1);
+        assert_eq!(load(&cache, "sources", 2).await, 1);
+
+        // Other entities leave the result alone
+        tx.send(CachedEntity::Proxies).unwrap();
+        assert_eq!(load(&cache, "sources", 3).await, 1);
+
+        tx.send(CachedEntity::StreamSources).unwrap();
+        assert_eq!(load(&cache, "sources", 4).await, 4);
+
+        let stats = cache.stats().await;
+        assert_eq!((stats.hits, stats.misses, stats.invalidations), (2, 2, 1));
+        assert_eq!(stats.hit_ratio, Some(0.5));
+    }
+
+    #[tokio::test]
+    async fn test_lagged_receiver_clears_cache() {
+        let (cache, tx) = cache();
+        load(&cache, "sources", 1).await;
+        for _ in 0..8 {
+            tx.send(CachedEntity::EpgSources).unwrap();
+        }
+        assert_eq!(load(&cache, "sources", 2).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors_and_disabled_cache_are_not_cached() {
+        let (cache, _tx) = cache();
+        let failed = cache
+            .get_or_load("sources", &[CachedEntity::StreamSources], || async {
+                Err::<u32, _>("database down")
+            })
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(load(&cache, "sources", 1).await, 1);
+
+        let (_tx, rx) = broadcast::channel(4);
+        let disabled = QueryCache::new(
+            &QueryCacheConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            rx,
+        );
+        load(&disabled, "sources", 1).await;
+        assert_eq!(load(&disabled, "sources", 2).await, 2);
+    }
+}
@@ -124,7 +124,9 @@ impl RelayConfigResolver {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::relay::{AudioCodec, RelayOutputFormat, RelayProfile, VideoCodec};
+    use crate::models::relay::{
+        AudioCodec, RelayOutputFormat, RelayProfile, SubtitleMode, VideoCodec,
+    };
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
@@ -191,6 +193,12 @@ mod tests {
             segment_duration: Some(10),
             max_segments: Some(3),
             input_timeout: 30,
+            audio_languages: Vec::new(),
+            audio_track_index: None,
+            subtitle_languages: Vec::new(),
+            subtitle_track_index: None,
+            subtitle_mode: SubtitleMode::default(),
+            subtitle_codec: None,
             is_system_default: false,
             is_active: true,
             created_at: chrono::Utc::now(),
@@ -0,0 +1,253 @@
+//! Relay FFmpeg log capture
+//!
+//! Every relay process writes its FFmpeg stderr into a bounded [`RelayLogBuffer`] so the
+//! output of a misbehaving relay can be inspected through the API. The buffer of the most
+//! recent process of each relay is kept after the process exits, and when FFmpeg dies the
+//! tail of its output can be written to the temp sandbox for postmortem.
+
+use chrono::{DateTime, Utc};
+use sandboxed_file_manager::SandboxedManager;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Severity of an FFmpeg output line
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum RelayLogLevel {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl RelayLogLevel {
+    /// Classify an FFmpeg stderr line by the keywords it contains
+    pub fn classify(line: &str) -> Self {
+        let line_lower = line.to_lowercase();
+        if line_lower.contains("error")
+            || line_lower.contains("failed")
+            || line_lower.contains("invalid")
+            || line_lower.contains("could not")
+            || line_lower.contains("unable to")
+            || line_lower.contains("not found")
+        {
+            RelayLogLevel::Error
+        } else if line_lower.contains("warning") || line_lower.contains("deprecated") {
+            RelayLogLevel::Warning
+        } else {
+            RelayLogLevel::Info
+        }
+    }
+}
+
+/// One captured line of FFmpeg output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+pub struct RelayLogLine {
+    pub timestamp: DateTime<Utc>,
+    pub level: RelayLogLevel,
+    pub message: String,
+}
+
+/// Captured output of a relay as returned by the API
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct RelayLogSnapshot {
+    pub relay_id: Uuid,
+    /// Whether the process that produced the output is still running
+    pub running: bool,
+    pub started_at: DateTime<Utc>,
+    pub lines: Vec<RelayLogLine>,
+    /// Temp sandbox file holding the output of the last crash, if any
+    pub crash_log: Option<String>,
+}
+
+/// Ring buffer of the most recent FFmpeg output lines of one relay process
+#[derive(Debug)]
+pub struct RelayLogBuffer {
+    max_lines: usize,
+    lines: Mutex<VecDeque<RelayLogLine>>,
+    started_at: DateTime<Utc>,
+}
+
+impl RelayLogBuffer {
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            max_lines: max_lines.max(1),
+            lines: Mutex::new(VecDeque::new()),
+            started_at: Utc::now(),
+        }
+    }
+
+    /// When the process owning this buffer was started
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// Append a line, evicting the oldest once the buffer is full
+    pub fn push(&self, message: &str) -> RelayLogLevel {
+        let level = RelayLogLevel::classify(message);
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() >= self.max_lines {
+            lines.pop_front();
+        }
+        lines.push_back(RelayLogLine {
+            timestamp: Utc::now(),
+            level,
+            message: message.to_string(),
+        });
+        level
+    }
+
+    /// The newest `limit` lines at or above `min_level`, oldest first
+    pub fn lines(&self, min_level: RelayLogLevel, limit: Option<usize>) -> Vec<RelayLogLine> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        let mut selected: Vec<RelayLogLine> = lines
+            .iter()
+            .rev()
+            .filter(|line| line.level >= min_level)
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        selected.reverse();
+        selected
+    }
+
+    /// The output as text, keeping only whole lines within the last `max_bytes`
+    pub fn render_tail(&self, max_bytes: usize) -> String {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rendered = Vec::new();
+        let mut total = 0;
+        for line in lines.iter().rev() {
+            let text = format!(
+                "{} [{:?}] {}\n",
+                line.timestamp.to_rfc3339(),
+                line.level,
+                line.message
+            );
+            if total + text.len() > max_bytes {
+                break;
+            }
+            total += text.len();
+            rendered.push(text);
+        }
+        rendered.reverse();
+        rendered.concat()
+    }
+}
+
+/// Log buffers of the most recent process of each relay, with their crash logs
+#[derive(Debug, Default)]
+pub struct RelayLogStore {
+    buffers: RwLock<HashMap<Uuid, Arc<RelayLogBuffer>>>,
+    crash_logs: RwLock<HashMap<Uuid, String>>,
+}
+
+impl RelayLogStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track the buffer of a newly started relay process, replacing the previous one
+    pub async fn register(&self, relay_id: Uuid, buffer: Arc<RelayLogBuffer>) {
+        self.buffers.write().await.insert(relay_id, buffer);
+    }
+
+    pub async fn get(&self, relay_id: Uuid) -> Option<Arc<RelayLogBuffer>> {
+        self.buffers.read().await.get(&relay_id).cloned()
+    }
+
+    /// Sandbox path of the last crash log written for a relay
+    pub async fn crash_log(&self, relay_id: Uuid) -> Option<String> {
+        self.crash_logs.read().await.get(&relay_id).cloned()
+    }
+
+    /// Write the tail of a relay's output to the temp sandbox
+    pub async fn persist_crash_log(
+        &self,
+        temp_manager: &SandboxedManager,
+        relay_id: Uuid,
+        max_bytes: usize,
+    ) -> anyhow::Result<String> {
+        let buffer = self
+            .get(relay_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("No output captured for relay {relay_id}"))?;
+        let path = format!(
+            "relay-crash-{}-{}.log",
+            relay_id,
+            Utc::now().format("%Y%m%dT%H%M%S")
+        );
+        temp_manager
+            .write(&path, buffer.render_tail(max_bytes))
+            .await?;
+        self.crash_logs.write().await.insert(relay_id, path.clone());
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            RelayLogLevel::classify("[http @ 0x1] HTTP error 404 Not Found"),
+            RelayLogLevel::Error
+        );
+        assert_eq!(
+            RelayLogLevel::classify("Warning: deprecated pixel format used"),
+            RelayLogLevel::Warning
+        );
+        assert_eq!(
+            RelayLogLevel::classify("Stream #0:0: Video: h264"),
+            RelayLogLevel::Info
+        );
+    }
+
+    #[test]
+    fn test_ring_buffer_evicts_and_filters() {
+        let buffer = RelayLogBuffer::new(3);
+        buffer.push("Input #0, mpegts");
+        buffer.push("Connection failed");
+        buffer.push("Warning: timestamps are unset");
+        buffer.push("frame= 100 fps=25");
+
+        let all = buffer.lines(RelayLogLevel::Info, None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].message, "Connection failed");
+
+        let warnings = buffer.lines(RelayLogLevel::Warning, None);
+        assert_eq!(warnings.len(), 2);
+        let newest = buffer.lines(RelayLogLevel::Info, Some(1));
+        assert_eq!(newest[0].message, "frame= 100 fps=25");
+    }
+
+    #[test]
+    fn test_render_tail_keeps_whole_lines() {
+        let buffer = RelayLogBuffer::new(10);
+        buffer.push("first line");
+        buffer.push("second line");
+        let full = buffer.render_tail(usize::MAX);
+        assert_eq!(full.lines().count(), 2);
+
+        let last_len = full.lines().last().unwrap().len() + 1;
+        let tail = buffer.render_tail(last_len);
+        assert!(tail.ends_with("second line\n"));
+        assert!(!tail.contains("first line"));
+    }
+}
@@ -6,27 +6,42 @@
 use crate::utils::SystemManager;
 use anyhow::Result;
 use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use sysinfo::Pid;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use crate::config::Config;
+use crate::config::{Config, RelayLogConfig};
 use crate::database::Database;
 use crate::database::repositories::{
-    LastKnownCodecSeaOrmRepository, channel::ChannelSeaOrmRepository,
+    LastKnownCodecSeaOrmRepository, ProxySettingsSeaOrmRepository,
+    RelayChannelViewSeaOrmRepository, RelaySeaOrmRepository, StreamProxySeaOrmRepository,
+    channel::ChannelSeaOrmRepository,
 };
+use crate::models::StreamProxyMode;
+use crate::models::proxy_settings::RelayKeepAlivePolicy;
 use crate::models::relay::*;
 use crate::observability::AppObservability;
 use crate::proxy::session_tracker::ClientInfo;
-use crate::services::ProbePersistenceService;
+use crate::services::ffmpeg_command_builder::FFmpegCommandBuilder;
 use crate::services::ffmpeg_wrapper::{FFmpegProcess, FFmpegProcessWrapper};
+use crate::services::relay_config_resolver::RelayConfigResolver;
+use crate::services::relay_logs::{RelayLogLevel, RelayLogSnapshot, RelayLogStore};
+use crate::services::transcode_admission::{
+    TranscodeAdmission, TranscodeAdmissionDecision, passthrough_config,
+};
+use crate::services::{LeaderElection, ProbePersistenceService};
 use opentelemetry::KeyValue;
 use sandboxed_file_manager::SandboxedManager;
 
+/// How often keep-alive policies are re-evaluated and view counts stored
+const KEEPALIVE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Manages FFmpeg relay processes with automatic lifecycle management
 pub struct RelayManager {
     active_processes: Arc<RwLock<HashMap<Uuid, FFmpegProcess>>>,
@@ -35,6 +50,19 @@ pub struct RelayManager {
     cleanup_interval: Duration,
     system_manager: SystemManager,
     observability: Option<Arc<AppObservability>>,
+    /// Relays pinned by keep-alive policies, with an optional warm deadline
+    pinned_relays: Arc<RwLock<HashMap<Uuid, Option<Instant>>>>,
+    /// Client connections per (proxy, channel) not yet added to the stored view counts
+    channel_views: Arc<RwLock<HashMap<(Uuid, Uuid), u64>>>,
+    /// Transcode slots per encoding device, held while a transcoding relay runs
+    transcode_admission: Arc<TranscodeAdmission>,
+    /// Captured FFmpeg output of the most recent process of each relay
+    relay_logs: Arc<RelayLogStore>,
+    log_config: RelayLogConfig,
+    /// Temp sandbox receiving crash logs
+    temp_manager: SandboxedManager,
+    /// Configured FFmpeg command, used for one-shot jobs outside relay processes
+    ffmpeg_command: String,
     pub ffmpeg_available: bool,
     pub ffmpeg_version: Option<String>,
     pub ffprobe_available: bool,
@@ -110,9 +138,23 @@ impl RelayManager {
         } else {
             None
         };
+        let transcode_admission = Arc::new(TranscodeAdmission::new(
+            &config
+                .relay
+                .as_ref()
+                .map(|r| r.transcode.clone())
+                .unwrap_or_default(),
+        ));
+
+        let log_config = config
+            .relay
+            .as_ref()
+            .map(|r| r.logs.clone())
+            .unwrap_or_default();
+
         // Build FFmpeg wrapper then inject probe persistence if available
         let mut ffmpeg_wrapper = FFmpegProcessWrapper::new(
-            temp_manager,
+            temp_manager.clone(),
             hwaccel_capabilities.clone(),
             config
                 .relay
@@ -126,6 +168,7 @@ impl RelayManager {
         if let Some(persistence) = &probe_persistence {
             ffmpeg_wrapper.set_probe_persistence(persistence.clone());
         }
+        ffmpeg_wrapper.set_log_config(log_config.clone());
 
         let manager = Self {
             active_processes: Arc::new(RwLock::new(HashMap::new())),
@@ -134,6 +177,13 @@ impl RelayManager {
             cleanup_interval: Duration::from_secs(10),
             system_manager: SystemManager::new(Duration::from_secs(5)),
             observability: None,
+            pinned_relays: Arc::new(RwLock::new(HashMap::new())),
+            channel_views: Arc::new(RwLock::new(HashMap::new())),
+            transcode_admission,
+            relay_logs: Arc::new(RelayLogStore::new()),
+            log_config,
+            temp_manager,
+            ffmpeg_command,
             ffmpeg_available,
             ffmpeg_version: ffmpeg_version.clone(),
             ffprobe_available,
@@ -188,11 +238,44 @@ impl RelayManager {
             return Ok(());
         }
 
+        // Take a transcode slot on the relay's encoding device
+        let device =
+            FFmpegCommandBuilder::new(None).transcode_device(config, &self.hwaccel_capabilities);
+        let passthrough;
+        let config = match self
+            .transcode_admission
+            .admit(config_id, device.as_deref())
+            .await
+        {
+            Ok(TranscodeAdmissionDecision::Admitted) => config,
+            Ok(TranscodeAdmissionDecision::Passthrough) => {
+                let device = device.unwrap_or_default();
+                passthrough = passthrough_config(config)
+                    .ok_or_else(|| RelayError::TranscodeCapacity(device.clone()))?;
+                warn!(
+                    "Transcode slots on {} are saturated; relaying {} without transcoding",
+                    device, config_id
+                );
+                &passthrough
+            }
+            Err(e) => {
+                warn!("Relay {} refused: {}", config_id, e);
+                return Err(e);
+            }
+        };
+
         // Start new process
         let result = self.ffmpeg_wrapper.start_process(config, input_url).await;
+        if result.is_err() {
+            self.transcode_admission.release(config_id);
+        }
 
         match result {
             Ok(process) => {
+                self.relay_logs
+                    .register(config_id, process.log_buffer.clone())
+                    .await;
+
                 // Store the process
                 self.active_processes
                     .write()
@@ -265,6 +348,16 @@ impl RelayManager {
 
             let content = process.serve_content(path, client_info).await?;
 
+            *self
+                .channel_views
+                .write()
+                .await
+                .entry((
+                    process.config.config.proxy_id,
+                    process.config.config.channel_id,
+                ))
+                .or_insert(0) += 1;
+
             // Record content serving metrics
             if let Some(obs) = &self.observability {
                 // Note: RelayContent is an enum - bytes will be tracked at stream level
@@ -312,6 +405,7 @@ impl RelayManager {
     /// Stop a relay process
     pub async fn stop_relay(&self, config_id: Uuid) -> Result<(), RelayError> {
         if let Some(mut process) = self.active_processes.write().await.remove(&config_id) {
+            self.transcode_admission.release(config_id);
             process.kill().await?;
 
             // Record relay stop metrics
@@ -347,6 +441,86 @@ impl RelayManager {
         Ok(())
     }
 
+    /// Captured FFmpeg output of a relay, newest `limit` lines at or above `min_level`
+    ///
+    /// Output of the last process is returned after it exits, until the relay restarts.
+    pub async fn get_relay_logs(
+        &self,
+        config_id: Uuid,
+        min_level: RelayLogLevel,
+        limit: Option<usize>,
+    ) -> Option<RelayLogSnapshot> {
+        let buffer = self.relay_logs.get(config_id).await?;
+        let running = self.active_processes.read().await.contains_key(&config_id);
+        Some(RelayLogSnapshot {
+            relay_id: config_id,
+            running,
+            started_at: buffer.started_at(),
+            lines: buffer.lines(min_level, limit),
+            crash_log: self.relay_logs.crash_log(config_id).await,
+        })
+    }
+
+    /// Capture a single video frame of `stream_url` as a JPEG image
+    ///
+    /// Runs a one-shot FFmpeg process (not a relay) that is killed after `timeout`.
+    pub async fn capture_frame(
+        &self,
+        stream_url: &str,
+        width: u32,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, RelayError> {
+        if !self.ffmpeg_available {
+            return Err(RelayError::ProcessFailed(
+                "FFmpeg is not available".to_string(),
+            ));
+        }
+
+        let mut cmd = tokio::process::Command::new(&self.ffmpeg_command);
+        cmd.args(["-hide_banner", "-loglevel", "error", "-nostdin"])
+            .args(["-i", stream_url])
+            .args(["-frames:v", "1", "-an", "-sn"])
+            .args(["-vf", &format!("scale={width}:-2")])
+            .args(["-f", "image2", "-c:v", "mjpeg", "-q:v", "4", "pipe:1"])
+            .kill_on_drop(true)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+
+        let child = cmd
+            .spawn()
+            .map_err(|e| RelayError::ProcessFailed(format!("Failed to spawn FFmpeg: {e}")))?;
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .map_err(|_| {
+                RelayError::ProcessFailed(format!(
+                    "Frame capture timed out after {}",
+                    crate::utils::human_format::format_duration_precise(timeout)
+                ))
+            })??;
+
+        if !output.status.success() || output.stdout.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(RelayError::ProcessFailed(format!(
+                "Frame capture failed ({}): {}",
+                output.status,
+                stderr.trim()
+            )));
+        }
+
+        debug!(
+            "Captured preview frame ({} bytes) from {}",
+            output.stdout.len(),
+            crate::utils::url::UrlUtils::obfuscate_credentials(stream_url)
+        );
+        Ok(output.stdout)
+    }
+
+    /// Transcode slot usage per encoding device
+    pub fn transcode_utilization(&self) -> Vec<crate::models::relay::TranscodeDeviceUtilization> {
+        self.transcode_admission.utilization()
+    }
+
     /// Get metrics for all active relay processes
     pub async fn get_relay_metrics(&self) -> Result<Vec<RelayProcessMetrics>, RelayError> {
         let processes = self.active_processes.read().await;
@@ -467,6 +641,7 @@ impl RelayManager {
             healthy_processes: healthy_count,
             unhealthy_processes: unhealthy_count,
             processes: process_health,
+            transcode_devices: self.transcode_admission.utilization(),
             last_check: chrono::Utc::now(),
         })
     }
@@ -574,6 +749,197 @@ impl RelayManager {
         }
     }
 
+    /// Run the proxies' keep-alive policies until cancelled
+    ///
+    /// Every node stores the client connections its relays served, ranking channels for
+    /// "most watched" policies. In a cluster only the leader keeps relays warm; other nodes
+    /// drop their pins and let the relays idle out until they take over.
+    pub async fn run_keepalive(
+        self: Arc<Self>,
+        leader_election: Arc<LeaderElection>,
+        cancellation_token: CancellationToken,
+    ) {
+        info!("Starting relay keep-alive");
+
+        let mut warm_deadlines: HashMap<Uuid, Instant> = HashMap::new();
+        let mut last_check = chrono::Utc::now();
+        let mut check_interval = tokio::time::interval(KEEPALIVE_CHECK_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = check_interval.tick() => {
+                    let now = chrono::Utc::now();
+                    self.store_channel_views().await;
+                    if leader_election.is_leader() {
+                        self.apply_keepalive_policies(&mut warm_deadlines, last_check, now)
+                            .await;
+                    } else {
+                        warm_deadlines.clear();
+                        self.pinned_relays.write().await.clear();
+                    }
+                    last_check = now;
+                }
+                _ = cancellation_token.cancelled() => {
+                    self.store_channel_views().await;
+                    info!("Relay keep-alive received cancellation signal, shutting down");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Add the client connections counted since the last call to the stored view counts
+    async fn store_channel_views(&self) {
+        let views = std::mem::take(&mut *self.channel_views.write().await);
+        if views.is_empty() {
+            return;
+        }
+
+        let repo = RelayChannelViewSeaOrmRepository::new(self.database.connection().clone());
+        if let Err(e) = repo.add_views(&views).await {
+            warn!("Failed to store relay channel views: {}", e);
+            // Keep the counts for the next attempt
+            let mut pending = self.channel_views.write().await;
+            for (key, count) in views {
+                *pending.entry(key).or_insert(0) += count;
+            }
+        }
+    }
+
+    /// Evaluate every policy once, start the selected relays and replace the pinned set
+    async fn apply_keepalive_policies(
+        &self,
+        warm_deadlines: &mut HashMap<Uuid, Instant>,
+        since: chrono::DateTime<chrono::Utc>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) {
+        let settings_repo = ProxySettingsSeaOrmRepository::new(self.database.connection().clone());
+        let policies = match settings_repo.list::<RelayKeepAlivePolicy>().await {
+            Ok(policies) => policies,
+            Err(e) => {
+                warn!("Failed to load relay keep-alive policies: {}", e);
+                return;
+            }
+        };
+        warm_deadlines.retain(|proxy_id, _| policies.iter().any(|(id, _)| id == proxy_id));
+
+        let mut pins = HashMap::new();
+
+        for (proxy_id, policy) in &policies {
+            let deadline = match &policy.schedule {
+                None => None,
+                Some(expression) => {
+                    let schedule = match cron::Schedule::from_str(expression) {
+                        Ok(schedule) => schedule,
+                        Err(e) => {
+                            warn!(
+                                "Invalid keep-alive schedule '{}' for proxy {}: {}",
+                                expression, proxy_id, e
+                            );
+                            continue;
+                        }
+                    };
+                    if schedule
+                        .after(&since)
+                        .next()
+                        .is_some_and(|next| next <= now)
+                    {
+                        info!("Pre-warming relays for proxy {}", proxy_id);
+                        warm_deadlines
+                            .insert(*proxy_id, Instant::now() + policy.warm_for_duration());
+                    }
+                    match warm_deadlines.get(proxy_id) {
+                        Some(deadline) if *deadline > Instant::now() => Some(*deadline),
+                        _ => {
+                            warm_deadlines.remove(proxy_id);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            match self.warm_proxy_relays(proxy_id, policy).await {
+                Ok(config_ids) => {
+                    for config_id in config_ids {
+                        pins.insert(config_id, deadline);
+                    }
+                }
+                Err(e) => warn!("Relay keep-alive failed for proxy {}: {}", proxy_id, e),
+            }
+        }
+
+        *self.pinned_relays.write().await = pins;
+    }
+
+    /// Ensure the relays selected by a policy are running, returning their relay config IDs
+    async fn warm_proxy_relays(
+        &self,
+        proxy_id: &Uuid,
+        policy: &RelayKeepAlivePolicy,
+    ) -> Result<Vec<Uuid>> {
+        let proxy_repo = StreamProxySeaOrmRepository::new(self.database.connection().clone());
+        let proxy = proxy_repo
+            .find_by_id(proxy_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("proxy not found"))?;
+
+        if !proxy.is_active || proxy.proxy_mode != StreamProxyMode::Relay {
+            anyhow::bail!("proxy '{}' is not an active relay-mode proxy", proxy.name);
+        }
+        let relay_profile_id = proxy
+            .relay_profile_id
+            .ok_or_else(|| anyhow::anyhow!("proxy '{}' has no relay profile", proxy.name))?;
+
+        let mut channel_ids = policy.channel_ids.clone();
+        if policy.most_watched > 0 {
+            let views_repo =
+                RelayChannelViewSeaOrmRepository::new(self.database.connection().clone());
+            for channel_id in views_repo
+                .most_watched(&proxy.id, policy.most_watched)
+                .await?
+            {
+                if !channel_ids.contains(&channel_id) {
+                    channel_ids.push(channel_id);
+                }
+            }
+        }
+
+        let resolver =
+            RelayConfigResolver::new(RelaySeaOrmRepository::new(self.database.connection()));
+        let mut config_ids = Vec::new();
+
+        for channel_id in channel_ids {
+            let Some(channel) = proxy_repo
+                .get_channel_for_proxy(proxy.id, channel_id)
+                .await?
+            else {
+                warn!(
+                    "Keep-alive channel {} is not available in proxy '{}'",
+                    channel_id, proxy.name
+                );
+                continue;
+            };
+
+            let resolved = resolver
+                .resolve_relay_config(proxy.id, channel_id, relay_profile_id)
+                .await?;
+            if let Err(e) = self
+                .ensure_relay_running(&resolved, &channel.stream_url)
+                .await
+            {
+                warn!(
+                    "Failed to keep relay running for channel '{}': {}",
+                    channel.channel_name, e
+                );
+                continue;
+            }
+
+            config_ids.push(resolved.config.id);
+        }
+
+        Ok(config_ids)
+    }
+
     /// Get channel name from database using repository
     async fn get_channel_name(&self, channel_id: Uuid) -> Option<String> {
         let channel_repo = ChannelSeaOrmRepository::new(self.database.connection().clone());
@@ -585,6 +951,11 @@ impl RelayManager {
     /// Start the cleanup task for idle processes
     fn start_cleanup_task(&self) {
         let processes = self.active_processes.clone();
+        let pinned_relays = self.pinned_relays.clone();
+        let transcode_admission = self.transcode_admission.clone();
+        let relay_logs = self.relay_logs.clone();
+        let log_config = self.log_config.clone();
+        let temp_manager = self.temp_manager.clone();
         let _database = self.database.clone();
         let interval = self.cleanup_interval;
 
@@ -594,13 +965,16 @@ impl RelayManager {
                 cleanup_interval.tick().await;
 
                 let mut to_remove = Vec::new();
+                let mut crashed = Vec::new();
                 {
+                    let pinned_guard = pinned_relays.read().await;
                     let mut processes_guard = processes.write().await;
                     for (config_id, process) in processes_guard.iter_mut() {
                         // Check if process is still running
                         if !process.is_running() {
                             warn!("FFmpeg process for relay {} has died", config_id);
                             to_remove.push(*config_id);
+                            crashed.push(*config_id);
                             continue;
                         }
 
@@ -612,6 +986,7 @@ impl RelayManager {
 
                         if buffer_client_count == 0
                             && process.last_activity.elapsed() > Duration::from_secs(60)
+                            && !is_pin_active(pinned_guard.get(config_id), Instant::now())
                         {
                             info!(
                                 "Relay {} is idle (no clients for 1 minute), scheduling for cleanup",
@@ -626,6 +1001,7 @@ impl RelayManager {
                         if let Some(mut process) = processes_guard.remove(config_id) {
                             let _ = process.kill().await;
                         }
+                        transcode_admission.release(*config_id);
                     }
                 }
 
@@ -633,6 +1009,22 @@ impl RelayManager {
                 for config_id in to_remove {
                     info!("Cleaned up relay process: {}", config_id);
                 }
+
+                if log_config.persist_on_crash {
+                    for config_id in crashed {
+                        match relay_logs
+                            .persist_crash_log(&temp_manager, config_id, log_config.crash_log_bytes)
+                            .await
+                        {
+                            Ok(path) => {
+                                info!("Saved FFmpeg output of relay {} to {}", config_id, path)
+                            }
+                            Err(e) => {
+                                warn!("Failed to save FFmpeg output of relay {}: {}", config_id, e)
+                            }
+                        }
+                    }
+                }
             }
         });
     }
@@ -978,10 +1370,31 @@ impl RelayManager {
     }
 }
 
+/// Whether a keep-alive pin still protects a relay from idle cleanup
+fn is_pin_active(pin: Option<&Option<Instant>>, now: Instant) -> bool {
+    match pin {
+        Some(None) => true,
+        Some(Some(deadline)) => *deadline > now,
+        None => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_pin_active() {
+        let now = Instant::now();
+        assert!(!is_pin_active(None, now));
+        assert!(is_pin_active(Some(&None), now));
+        assert!(is_pin_active(
+            Some(&Some(now + Duration::from_secs(60))),
+            now
+        ));
+        assert!(!is_pin_active(Some(&Some(now)), now));
+    }
+
     #[test]
     fn test_relay_event_type_serialization() {
         assert_eq!(RelayEventType::Start.to_string(), "start");
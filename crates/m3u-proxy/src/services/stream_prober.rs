@@ -10,6 +10,8 @@ use std::time::Duration;
 use tokio::process::Command;
 use tracing::{debug, warn};
 
+use crate::models::relay::{RelayProfile, SubtitleMode, TrackSelection};
+
 /// Information about a stream detected by FFprobe
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamInfo {
@@ -25,6 +27,8 @@ pub struct StreamInfo {
     pub sample_rate: Option<u32>,       // audio only
     pub channels: Option<u32>,          // audio only
     pub channel_layout: Option<String>, // audio only
+    #[serde(default)]
+    pub language: Option<String>, // from stream tags, when present
 }
 
 /// Error information from ffprobe
@@ -45,6 +49,8 @@ pub struct ProbeResult {
     pub has_audio: bool,
     pub video_streams: Vec<StreamInfo>,
     pub audio_streams: Vec<StreamInfo>,
+    #[serde(default)]
+    pub subtitle_streams: Vec<StreamInfo>,
     pub error: Option<ProbeError>,
 }
 
@@ -53,6 +59,7 @@ pub struct ProbeResult {
 pub struct StreamMappingStrategy {
     pub video_mapping: Option<String>, // e.g., "0:v:0" or None if no video
     pub audio_mapping: Option<String>, // e.g., "0:a:0" or None if no audio
+    pub subtitle_mappings: Vec<String>, // e.g., ["0:s:1"], per the profile's subtitle mode
     pub video_copy: bool,              // true if video should be copied
     pub audio_copy: bool,              // true if audio should be copied
     pub target_audio_bitrate: Option<u32>, // optimal audio bitrate (never higher than input)
@@ -82,7 +89,7 @@ impl StreamProber {
             "-v", "quiet",
             "-print_format", "json",
             "-show_error",
-            "-show_entries", "stream=index,codec_type,codec_name,codec_tag_string,duration,bit_rate,width,height,r_frame_rate,sample_rate,channels,channel_layout:format=format_name,duration,bit_rate",
+            "-show_entries", "stream=index,codec_type,codec_name,codec_tag_string,duration,bit_rate,width,height,r_frame_rate,sample_rate,channels,channel_layout:stream_tags=language:format=format_name,duration,bit_rate",
             "-analyzeduration", "5000000",  // 5 seconds
             "-probesize", "5000000",        // 5MB
             input_url
@@ -149,6 +156,7 @@ impl StreamProber {
         let mut streams = Vec::new();
         let mut video_streams = Vec::new();
         let mut audio_streams = Vec::new();
+        let mut subtitle_streams = Vec::new();
 
         // Parse error section first
         let error = data.get("error").map(|error_obj| ProbeError {
@@ -220,11 +228,18 @@ impl StreamProber {
                         .get("channel_layout")
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string()),
+                    language: stream
+                        .get("tags")
+                        .and_then(|t| t.get("language"))
+                        .and_then(|v| v.as_str())
+                        .filter(|s| !s.is_empty() && *s != "und")
+                        .map(|s| s.to_string()),
                 };
 
                 match codec_type.as_str() {
                     "video" => video_streams.push(stream_info.clone()),
                     "audio" => audio_streams.push(stream_info.clone()),
+                    "subtitle" => subtitle_streams.push(stream_info.clone()),
                     _ => {}
                 }
 
@@ -253,6 +268,7 @@ impl StreamProber {
             streams,
             video_streams,
             audio_streams,
+            subtitle_streams,
             format_name,
             duration,
             bit_rate,
@@ -261,17 +277,23 @@ impl StreamProber {
     }
 
     /// Generate optimal mapping strategy based on probe results and target profile
+    ///
+    /// The audio track follows the profile's audio selection (falling back to the first
+    /// track); a subtitle track is only mapped when the profile selects one.
     pub fn generate_mapping_strategy(
         &self,
         probe_result: &ProbeResult,
-        target_video_codec: &str,
-        target_audio_codec: &str,
-        target_video_bitrate: Option<u32>,
-        target_audio_bitrate: Option<u32>,
+        profile: &RelayProfile,
     ) -> StreamMappingStrategy {
+        let target_video_codec = profile.video_codec.to_string();
+        let target_audio_codec = profile.audio_codec.to_string();
+        let target_video_bitrate = profile.video_bitrate;
+        let target_audio_bitrate = profile.audio_bitrate;
+
         let mut strategy = StreamMappingStrategy {
             video_mapping: None,
             audio_mapping: None,
+            subtitle_mappings: Vec::new(),
             video_copy: false,
             audio_copy: false,
             target_audio_bitrate,
@@ -285,7 +307,7 @@ impl StreamProber {
             // Decide if we should copy video
             strategy.video_copy = should_copy_video_stream(
                 &video_stream.codec_name,
-                target_video_codec,
+                &target_video_codec,
                 video_stream.bit_rate,
                 target_video_bitrate,
             );
@@ -300,13 +322,19 @@ impl StreamProber {
         }
 
         // Handle audio streams
-        if let Some(audio_stream) = probe_result.audio_streams.first() {
-            strategy.audio_mapping = Some("0:a:0".to_string());
+        let audio_position = select_track(
+            &probe_result.audio_streams,
+            &profile.audio_selection(),
+            |_| true,
+        )
+        .unwrap_or(0);
+        if let Some(audio_stream) = probe_result.audio_streams.get(audio_position) {
+            strategy.audio_mapping = Some(format!("0:a:{audio_position}"));
 
             // Decide if we should copy audio
             strategy.audio_copy = should_copy_audio_stream(
                 &audio_stream.codec_name,
-                target_audio_codec,
+                &target_audio_codec,
                 audio_stream.bit_rate,
                 target_audio_bitrate,
             );
@@ -320,10 +348,17 @@ impl StreamProber {
             }
         }
 
+        // Handle subtitle streams
+        strategy.subtitle_mappings = subtitle_positions(&probe_result.subtitle_streams, profile)
+            .into_iter()
+            .map(|position| format!("0:s:{position}"))
+            .collect();
+
         debug!(
-            "Generated mapping strategy: video={:?}, audio={:?}, video_copy={}, audio_copy={}",
+            "Generated mapping strategy: video={:?}, audio={:?}, subtitles={:?}, video_copy={}, audio_copy={}",
             strategy.video_mapping,
             strategy.audio_mapping,
+            strategy.subtitle_mappings,
             strategy.video_copy,
             strategy.audio_copy
         );
@@ -332,6 +367,93 @@ impl StreamProber {
     }
 }
 
+/// Pick a track from `tracks` (all of one type, in input order) and return its position
+///
+/// A forced track index wins when it exists and is usable; otherwise the first language in
+/// priority order that a usable track is tagged with. Returns `None` when nothing matches.
+pub fn select_track(
+    tracks: &[StreamInfo],
+    selection: &TrackSelection,
+    usable: impl Fn(&StreamInfo) -> bool,
+) -> Option<usize> {
+    if let Some(index) = selection.track_index {
+        let index = index as usize;
+        match tracks.get(index) {
+            Some(track) if usable(track) => return Some(index),
+            Some(track) => warn!(
+                "Forced track {} ({}) cannot be relayed; falling back to language selection",
+                index, track.codec_name
+            ),
+            None => warn!(
+                "Forced track {} not present ({} tracks); falling back to language selection",
+                index,
+                tracks.len()
+            ),
+        }
+    }
+
+    selection.languages.iter().find_map(|language| {
+        tracks.iter().position(|track| {
+            usable(track)
+                && track
+                    .language
+                    .as_deref()
+                    .is_some_and(|tag| tag.eq_ignore_ascii_case(language.trim()))
+        })
+    })
+}
+
+/// Positions of the subtitle tracks a profile relays, per its subtitle mode
+fn subtitle_positions(subtitles: &[StreamInfo], profile: &RelayProfile) -> Vec<usize> {
+    let selection = profile.subtitle_selection();
+    match profile.subtitle_mode {
+        SubtitleMode::Strip => Vec::new(),
+        SubtitleMode::Preserve => subtitles
+            .iter()
+            .enumerate()
+            .filter(|(_, track)| is_transport_stream_subtitle(track))
+            .map(|(position, _)| position)
+            .collect(),
+        SubtitleMode::Selected => {
+            if selection.is_empty() {
+                return Vec::new();
+            }
+            let selected = select_track(subtitles, &selection, is_transport_stream_subtitle);
+            if selected.is_none() {
+                debug!(
+                    "No subtitle track matches {:?} among {} relayable subtitle tracks",
+                    selection,
+                    subtitles
+                        .iter()
+                        .filter(|s| is_transport_stream_subtitle(s))
+                        .count()
+                );
+            }
+            selected.into_iter().collect()
+        }
+        SubtitleMode::Convert => {
+            let Some(codec) = profile.subtitle_codec else {
+                return Vec::new();
+            };
+            let convertible = |track: &StreamInfo| codec.can_convert_from(&track.codec_name);
+            let selected = if selection.is_empty() {
+                subtitles.iter().position(convertible)
+            } else {
+                select_track(subtitles, &selection, convertible)
+            };
+            if selected.is_none() {
+                debug!("No subtitle track can be converted to {}", codec);
+            }
+            selected.into_iter().collect()
+        }
+    }
+}
+
+/// Whether a subtitle track can be copied into MPEG-TS output (bitmap DVB and teletext only)
+fn is_transport_stream_subtitle(stream: &StreamInfo) -> bool {
+    matches!(stream.codec_name.as_str(), "dvb_subtitle" | "dvb_teletext")
+}
+
 /// Determine if video stream should be copied instead of transcoded
 fn should_copy_video_stream(
     input_codec: &str,
@@ -407,6 +529,129 @@ fn normalize_codec_name(codec: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_probe_result_tracks() {
+        let prober = StreamProber::new(None);
+        let data = serde_json::json!({
+            "streams": [
+                {"index": 0, "codec_type": "video", "codec_name": "h264", "width": 1920, "height": 1080},
+                {"index": 1, "codec_type": "audio", "codec_name": "aac", "channels": 2, "tags": {"language": "eng"}},
+                {"index": 2, "codec_type": "audio", "codec_name": "ac3", "channels": 6, "tags": {"language": "und"}},
+                {"index": 3, "codec_type": "subtitle", "codec_name": "dvb_subtitle", "tags": {"language": "deu"}}
+            ],
+            "format": {"format_name": "mpegts", "bit_rate": "4500000"}
+        });
+
+        let result = prober.parse_probe_result(data).unwrap();
+        assert_eq!(result.streams.len(), 4);
+        assert_eq!(result.audio_streams.len(), 2);
+        assert_eq!(result.audio_streams[0].language.as_deref(), Some("eng"));
+        assert_eq!(result.audio_streams[1].language, None);
+        assert_eq!(result.streams[3].language.as_deref(), Some("deu"));
+        assert_eq!(result.subtitle_streams.len(), 1);
+        assert_eq!(result.bit_rate, Some(4_500_000));
+    }
+
+    fn track(codec_type: &str, codec_name: &str, language: Option<&str>) -> StreamInfo {
+        StreamInfo {
+            index: 0,
+            codec_type: codec_type.to_string(),
+            codec_name: codec_name.to_string(),
+            codec_tag_string: None,
+            duration: None,
+            bit_rate: None,
+            width: None,
+            height: None,
+            r_frame_rate: None,
+            sample_rate: None,
+            channels: None,
+            channel_layout: None,
+            language: language.map(|l| l.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_select_track() {
+        let audio = vec![
+            track("audio", "aac", Some("eng")),
+            track("audio", "ac3", Some("deu")),
+            track("audio", "aac", Some("fra")),
+        ];
+        let select = |languages: &[&str], track_index: Option<u32>| {
+            let selection = TrackSelection {
+                languages: languages.iter().map(|l| l.to_string()).collect(),
+                track_index,
+            };
+            select_track(&audio, &selection, |_| true)
+        };
+
+        // Languages are tried in priority order, case-insensitively
+        assert_eq!(select(&["spa", "DEU", "eng"], None), Some(1));
+        // A forced index wins over languages; an out-of-range index falls back to them
+        assert_eq!(select(&["deu"], Some(2)), Some(2));
+        assert_eq!(select(&["fra"], Some(7)), Some(2));
+        assert_eq!(select(&["spa"], None), None);
+        assert_eq!(select(&[], None), None);
+    }
+
+    #[test]
+    fn test_select_subtitle_track_skips_unrelayable() {
+        let subtitles = vec![
+            track("subtitle", "subrip", Some("eng")),
+            track("subtitle", "dvb_subtitle", Some("eng")),
+        ];
+        let selection = TrackSelection {
+            languages: vec!["eng".to_string()],
+            track_index: Some(0),
+        };
+        assert_eq!(
+            select_track(&subtitles, &selection, is_transport_stream_subtitle),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_subtitle_positions_per_mode() {
+        let subtitles = vec![
+            track("subtitle", "subrip", Some("eng")),
+            track("subtitle", "dvb_teletext", Some("deu")),
+            track("subtitle", "dvb_subtitle", Some("eng")),
+        ];
+        let profile = |mode: &str, codec: Option<&str>| -> RelayProfile {
+            serde_json::from_value(serde_json::json!({
+                "id": uuid::Uuid::nil(),
+                "name": "test",
+                "video_codec": "Copy",
+                "audio_codec": "Copy",
+                "enable_hardware_acceleration": false,
+                "output_format": "TransportStream",
+                "input_timeout": 30,
+                "subtitle_mode": mode,
+                "subtitle_codec": codec,
+                "is_system_default": false,
+                "is_active": true,
+                "created_at": "2025-01-01T00:00:00Z",
+                "updated_at": "2025-01-01T00:00:00Z"
+            }))
+            .unwrap()
+        };
+
+        assert!(subtitle_positions(&subtitles, &profile("Selected", None)).is_empty());
+        assert!(subtitle_positions(&subtitles, &profile("Strip", None)).is_empty());
+        assert_eq!(
+            subtitle_positions(&subtitles, &profile("Preserve", None)),
+            vec![1, 2]
+        );
+        assert_eq!(
+            subtitle_positions(&subtitles, &profile("Convert", Some("WebVtt"))),
+            vec![0]
+        );
+        assert_eq!(
+            subtitle_positions(&subtitles, &profile("Convert", Some("DvbSubtitle"))),
+            vec![1]
+        );
+    }
+
     #[test]
     fn test_normalize_codec_name() {
         assert_eq!(normalize_codec_name("h264"), "h264");
@@ -99,6 +99,12 @@ impl StreamProxyService {
         // Validate that all sources and filters exist
         self.validate_proxy_request(&request.stream_sources, &request.filters)
             .await?;
+        crate::models::ChannelNumberBlock::validate(&request.channel_number_blocks)
+            .map_err(|message| AppError::Validation { message })?;
+        if let Some(tz) = &request.epg_timezone {
+            crate::utils::time::parse_iana_timezone(tz)
+                .map_err(|message| AppError::Validation { message })?;
+        }
 
         // Extract relationship IDs from request
         let source_ids: Vec<Uuid> = request.stream_sources.iter().map(|s| s.source_id).collect();
@@ -139,6 +145,14 @@ impl StreamProxyService {
         // Validate that all sources and filters exist
         self.validate_proxy_request(&request.stream_sources, &request.filters)
             .await?;
+        if let Some(blocks) = &request.channel_number_blocks {
+            crate::models::ChannelNumberBlock::validate(blocks)
+                .map_err(|message| AppError::Validation { message })?;
+        }
+        if let Some(Some(tz)) = &request.epg_timezone {
+            crate::utils::time::parse_iana_timezone(tz)
+                .map_err(|message| AppError::Validation { message })?;
+        }
 
         // Extract relationship IDs from request
         let source_ids: Vec<Uuid> = request.stream_sources.iter().map(|s| s.source_id).collect();
@@ -600,6 +614,14 @@ impl StreamProxyService {
             cache_channel_logos: proxy.cache_channel_logos,
             cache_program_logos: proxy.cache_program_logos,
             relay_profile_id: proxy.relay_profile_id,
+            sign_stream_urls: proxy.sign_stream_urls,
+            output_profile: proxy.output_profile,
+            backup_streams: proxy.backup_streams,
+            offline_slate: proxy.offline_slate,
+            epg_languages: proxy.epg_languages,
+            regeneration_debounce_seconds: proxy.regeneration_debounce_seconds,
+            channel_number_blocks: proxy.channel_number_blocks,
+            epg_timezone: proxy.epg_timezone,
             stream_sources,
             epg_sources,
             filters,
@@ -613,6 +635,7 @@ impl StreamProxyService {
                 self.app_config.web.base_url.trim_end_matches('/'),
                 crate::utils::uuid_parser::uuid_to_base64(&proxy.id)
             ),
+            channel_count_alert: None,
         };
 
         Ok(response)
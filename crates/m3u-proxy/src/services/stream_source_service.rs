@@ -9,14 +9,27 @@ use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
+use crate::config::IngestionLimitsConfig;
 use crate::database::Database;
 use crate::database::repositories::{
     channel::ChannelSeaOrmRepository, epg_source::EpgSourceSeaOrmRepository,
-    stream_source::StreamSourceSeaOrmRepository,
+    ingestion_run::IngestionRunSeaOrmRepository, stream_source::StreamSourceSeaOrmRepository,
+    stream_source_mirror::StreamSourceMirrorSeaOrmRepository,
+    xtream_category_filter::XtreamCategoryFilterSeaOrmRepository,
+};
+use crate::errors::{AppError, AppResult, SourceError};
+use crate::models::ingest_snapshot::{IngestSnapshot, IngestSnapshotKind};
+use crate::models::ingestion_run::{IngestionRunOutcome, IngestionSourceKind, RecordChangeSummary};
+use crate::models::xtream_category_filter::CompiledCategoryFilter;
+use crate::models::{
+    StreamSource, StreamSourceCreateRequest, StreamSourceType, StreamSourceUpdateRequest,
 };
-use crate::models::{StreamSource, StreamSourceCreateRequest, StreamSourceUpdateRequest};
 use crate::observability::AppObservability;
-use crate::services::UrlLinkingService;
+use crate::services::{
+    CachedEntity, ChannelCountAlertService, IngestArchiveService, QueryCache, UrlLinkingService,
+};
+use crate::sources::{FullSourceHandler, SourceIngestionLimits};
+use crate::utils::url::UrlUtils;
 
 /// Service for managing stream sources with business logic
 pub struct StreamSourceService {
@@ -24,9 +37,16 @@ pub struct StreamSourceService {
     channel_repo: ChannelSeaOrmRepository,
     epg_source_repo: EpgSourceSeaOrmRepository,
     url_linking_service: UrlLinkingService,
-    cache_invalidation_tx: broadcast::Sender<()>,
+    cache_invalidation_tx: broadcast::Sender<CachedEntity>,
     http_client_factory: Option<crate::utils::HttpClientFactory>,
     observability: Option<Arc<AppObservability>>,
+    ingest_archive: Option<Arc<IngestArchiveService>>,
+    ingestion_history: Option<IngestionRunSeaOrmRepository>,
+    category_filters: Option<XtreamCategoryFilterSeaOrmRepository>,
+    mirrors: Option<StreamSourceMirrorSeaOrmRepository>,
+    ingestion_limits: IngestionLimitsConfig,
+    channel_count_alerts: Option<ChannelCountAlertService>,
+    query_cache: Option<Arc<QueryCache>>,
 }
 
 impl StreamSourceService {
@@ -36,7 +56,7 @@ impl StreamSourceService {
         channel_repo: ChannelSeaOrmRepository,
         epg_source_repo: EpgSourceSeaOrmRepository,
         url_linking_service: UrlLinkingService,
-        cache_invalidation_tx: broadcast::Sender<()>,
+        cache_invalidation_tx: broadcast::Sender<CachedEntity>,
     ) -> Self {
         Self {
             stream_source_repo,
@@ -46,6 +66,13 @@ impl StreamSourceService {
             cache_invalidation_tx,
             http_client_factory: None,
             observability: None,
+            ingest_archive: None,
+            ingestion_history: None,
+            category_filters: None,
+            mirrors: None,
+            ingestion_limits: IngestionLimitsConfig::default(),
+            channel_count_alerts: None,
+            query_cache: None,
         }
     }
 
@@ -55,13 +82,60 @@ impl StreamSourceService {
         self
     }
 
+    /// Archive downloaded M3U playlists for replay
+    pub fn with_ingest_archive(mut self, ingest_archive: Arc<IngestArchiveService>) -> Self {
+        self.ingest_archive = Some(ingest_archive);
+        self
+    }
+
+    /// Record the outcome of every refresh in the ingestion history
+    pub fn with_ingestion_history(mut self, repo: IngestionRunSeaOrmRepository) -> Self {
+        self.ingestion_history = Some(repo);
+        self
+    }
+
+    /// Apply per-source Xtream category filters while ingesting
+    pub fn with_category_filters(mut self, repo: XtreamCategoryFilterSeaOrmRepository) -> Self {
+        self.category_filters = Some(repo);
+        self
+    }
+
+    /// Fall back to per-source mirror URLs when a source's URL fails
+    pub fn with_mirrors(mut self, repo: StreamSourceMirrorSeaOrmRepository) -> Self {
+        self.mirrors = Some(repo);
+        self
+    }
+
+    /// Per-source download, channel count and parse time limits
+    pub fn with_ingestion_limits(mut self, ingestion_limits: IngestionLimitsConfig) -> Self {
+        self.ingestion_limits = ingestion_limits;
+        self
+    }
+
+    /// Compare each refresh's channel count with the previous one
+    pub fn with_channel_count_alerts(mut self, alerts: ChannelCountAlertService) -> Self {
+        self.channel_count_alerts = Some(alerts);
+        self
+    }
+
+    /// Serve source lists from the API query cache
+    pub fn with_query_cache(mut self, query_cache: Arc<QueryCache>) -> Self {
+        self.query_cache = Some(query_cache);
+        self
+    }
+
+    /// Ingest snapshot archive, when enabled
+    pub fn ingest_archive(&self) -> Option<&Arc<IngestArchiveService>> {
+        self.ingest_archive.as_ref()
+    }
+
     /// Create a new stream source service with HTTP client factory
     pub fn with_http_client_factory(
         stream_source_repo: StreamSourceSeaOrmRepository,
         channel_repo: ChannelSeaOrmRepository,
         epg_source_repo: EpgSourceSeaOrmRepository,
         url_linking_service: UrlLinkingService,
-        cache_invalidation_tx: broadcast::Sender<()>,
+        cache_invalidation_tx: broadcast::Sender<CachedEntity>,
         http_client_factory: crate::utils::HttpClientFactory,
     ) -> Self {
         Self {
@@ -72,13 +146,23 @@ impl StreamSourceService {
             cache_invalidation_tx,
             http_client_factory: Some(http_client_factory),
             observability: None,
+            ingest_archive: None,
+            ingestion_history: None,
+            category_filters: None,
+            mirrors: None,
+            ingestion_limits: IngestionLimitsConfig::default(),
+            channel_count_alerts: None,
+            query_cache: None,
         }
     }
 
     /// Legacy constructor for backward compatibility (deprecated)
     /// TODO: Remove once all callers are updated to use dependency injection
     #[deprecated(note = "Use dependency injection constructor instead")]
-    pub fn new_legacy(database: Database, cache_invalidation_tx: broadcast::Sender<()>) -> Self {
+    pub fn new_legacy(
+        database: Database,
+        cache_invalidation_tx: broadcast::Sender<CachedEntity>,
+    ) -> Self {
         let stream_source_repo = StreamSourceSeaOrmRepository::new(database.connection().clone());
         let channel_repo = ChannelSeaOrmRepository::new(database.connection().clone());
         let epg_source_repo = EpgSourceSeaOrmRepository::new(database.connection().clone());
@@ -224,6 +308,7 @@ impl StreamSourceService {
                                     "Successfully created linked EPG source: {} ({}) for stream source: {}",
                                     epg_source.name, epg_source.id, source.name
                                 );
+                                let _ = self.cache_invalidation_tx.send(CachedEntity::EpgSources);
                             }
                             Err(e) => {
                                 warn!(
@@ -252,7 +337,7 @@ impl StreamSourceService {
         }
 
         // Invalidate cache since we added a new source
-        let _ = self.cache_invalidation_tx.send(());
+        let _ = self.cache_invalidation_tx.send(CachedEntity::StreamSources);
 
         info!(
             "Successfully created stream source: {} ({})",
@@ -311,7 +396,7 @@ impl StreamSourceService {
             .map_err(|e| anyhow::anyhow!("Failed to update stream source: {}", e))?;
 
         // Invalidate cache
-        let _ = self.cache_invalidation_tx.send(());
+        let _ = self.cache_invalidation_tx.send(CachedEntity::StreamSources);
 
         info!(
             "Successfully updated stream source: {} ({})",
@@ -325,14 +410,14 @@ impl StreamSourceService {
     pub async fn delete_with_cleanup(&self, id: uuid::Uuid) -> Result<()> {
         info!("Deleting stream source: {}", id);
 
-        // Delete the stream source (this will cascade to linked sources)
+        // Move the stream source to the trash; ingestion history is kept until it is purged
         self.stream_source_repo
             .delete(&id)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to delete stream source: {}", e))?;
 
         // Invalidate cache
-        let _ = self.cache_invalidation_tx.send(());
+        let _ = self.cache_invalidation_tx.send(CachedEntity::StreamSources);
 
         info!("Stream source {} deleted successfully", id);
         Ok(())
@@ -340,6 +425,21 @@ impl StreamSourceService {
 
     /// List stream sources with statistics
     pub async fn list_with_stats(&self) -> Result<Vec<StreamSourceWithStats>> {
+        match &self.query_cache {
+            Some(cache) => {
+                cache
+                    .get_or_load(
+                        "stream_sources_with_stats",
+                        &[CachedEntity::StreamSources],
+                        || self.load_with_stats(),
+                    )
+                    .await
+            }
+            None => self.load_with_stats().await,
+        }
+    }
+
+    async fn load_with_stats(&self) -> Result<Vec<StreamSourceWithStats>> {
         let sources_with_stats = self
             .stream_source_repo
             .list_with_stats()
@@ -608,11 +708,13 @@ impl StreamSourceService {
     }
 
     /// Save channels to database using ChannelRepository
+    ///
+    /// Returns the number of channels saved and how they differ from the stored ones.
     async fn save_channels(
         &self,
         source_id: uuid::Uuid,
         channels: Vec<crate::models::Channel>,
-    ) -> Result<usize> {
+    ) -> Result<(usize, RecordChangeSummary)> {
         use tracing::debug;
 
         debug!(
@@ -622,7 +724,8 @@ impl StreamSourceService {
 
         // Use ChannelRepository to replace channels for this source
         let channels_count = channels.len();
-        self.channel_repo
+        let changes = self
+            .channel_repo
             .update_source_channels(source_id, &channels)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to update source channels: {}", e))?;
@@ -632,14 +735,50 @@ impl StreamSourceService {
             channels_count
         );
 
-        Ok(channels_count)
+        Ok((channels_count, changes))
     }
 
     /// Refresh stream source using ProgressStageUpdater (new API)
+    ///
+    /// The outcome is recorded in the ingestion history when one is attached.
     pub async fn refresh_with_progress_updater(
         &self,
         source: &crate::models::StreamSource,
         progress_updater: Option<&crate::services::progress_service::ProgressStageUpdater>,
+    ) -> Result<usize> {
+        let started_at = chrono::Utc::now();
+        let mut outcome = IngestionRunOutcome::default();
+        let result = self
+            .refresh_channels(source, progress_updater, &mut outcome)
+            .await;
+
+        if let Some(history) = &self.ingestion_history {
+            if let Err(e) = &result {
+                outcome.error = Some(e.to_string());
+            }
+            if let Err(e) = history
+                .record(source.id, IngestionSourceKind::Stream, started_at, outcome)
+                .await
+            {
+                warn!(
+                    "Failed to record ingestion run for stream source '{}': {}",
+                    source.name, e
+                );
+            }
+        }
+        if let (Ok(channel_count), Some(alerts)) = (&result, &self.channel_count_alerts) {
+            alerts
+                .check_source(source.id, &source.name, *channel_count)
+                .await;
+        }
+        result
+    }
+
+    async fn refresh_channels(
+        &self,
+        source: &crate::models::StreamSource,
+        progress_updater: Option<&crate::services::progress_service::ProgressStageUpdater>,
+        outcome: &mut IngestionRunOutcome,
     ) -> Result<usize> {
         use crate::sources::factory::SourceHandlerFactory;
 
@@ -694,11 +833,86 @@ impl StreamSourceService {
             }
         }
 
-        // Ingest channels using the handler
-        let channels = handler
-            .ingest_channels(source)
-            .await
-            .map_err(|e| anyhow::anyhow!("Stream source handler failed: {}", e))?;
+        let limits = SourceIngestionLimits::resolve(&self.ingestion_limits, source);
+
+        // Category filters are applied during ingestion so unwanted channels never reach
+        // the database
+        let filter = match (&source.source_type, &self.category_filters) {
+            (StreamSourceType::Xtream, Some(repo)) => repo
+                .get(&source.id)
+                .await?
+                .compile()
+                .map_err(|e| anyhow::anyhow!("Invalid category filter: {}", e))?,
+            _ => CompiledCategoryFilter::default(),
+        };
+        let candidate_urls = match &self.mirrors {
+            Some(repo) => repo.get(&source.id).await?.candidate_urls(&source.url),
+            None => vec![source.url.clone()],
+        };
+
+        // Try the source URL, then each mirror in order, until one is ingested
+        let mut last_error = None;
+        let mut served = None;
+        for (attempt, url) in candidate_urls.iter().enumerate() {
+            let mirror_source;
+            let attempt_source = if attempt == 0 {
+                source
+            } else {
+                if let Some(updater) = progress_updater {
+                    updater
+                        .update_progress(
+                            0.0,
+                            &format!("Trying mirror {} of {}", attempt, candidate_urls.len() - 1),
+                        )
+                        .await;
+                }
+                mirror_source = StreamSource {
+                    url: url.clone(),
+                    ..source.clone()
+                };
+                &mirror_source
+            };
+            match self
+                .fetch_channels(
+                    attempt_source,
+                    handler.as_ref(),
+                    factory,
+                    &limits,
+                    &filter,
+                    outcome,
+                )
+                .await
+            {
+                Ok(channels) => {
+                    served = Some((channels, url));
+                    break;
+                }
+                // A mirror serves the same content, so it would exceed the same limit
+                Err(e @ AppError::Source(SourceError::LimitExceeded { .. })) => {
+                    return Err(self.ingestion_failed(source, e));
+                }
+                Err(e) => {
+                    if attempt + 1 < candidate_urls.len() {
+                        warn!(
+                            "Refresh of stream source '{}' from {} failed, trying next mirror: {}",
+                            source.name,
+                            UrlUtils::obfuscate_credentials(url),
+                            e
+                        );
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        let Some((channels, served_url)) = served else {
+            return Err(match last_error {
+                Some(e) => self.ingestion_failed(source, e),
+                None => anyhow::anyhow!("Stream source '{}' has no URL", source.name),
+            });
+        };
+        limits
+            .check_channel_count(channels.len(), &source.name)
+            .map_err(|e| self.ingestion_failed(source, e))?;
 
         info!(
             "Stream handler ingested {} channels from source '{}'",
@@ -726,7 +940,10 @@ impl StreamSourceService {
             source.name
         );
         let channels_saved = match self.save_channels(source.id, channels).await {
-            Ok(count) => count,
+            Ok((count, changes)) => {
+                outcome.changes = Some(changes);
+                count
+            }
             Err(e) => {
                 warn!("Failed to save channels for '{}': {}", source.name, e);
 
@@ -782,8 +999,24 @@ impl StreamSourceService {
             }
         }
 
+        if let Some(repo) = &self.mirrors {
+            if *served_url != source.url {
+                info!(
+                    "Stream source '{}' was refreshed from mirror {}",
+                    source.name,
+                    UrlUtils::obfuscate_credentials(served_url)
+                );
+            }
+            if let Err(e) = repo.record_served(&source.id, served_url).await {
+                warn!(
+                    "Failed to record serving URL of stream source '{}': {}",
+                    source.name, e
+                );
+            }
+        }
+
         // Invalidate cache since we updated channels
-        let _ = self.cache_invalidation_tx.send(());
+        let _ = self.cache_invalidation_tx.send(CachedEntity::StreamSources);
 
         // Record comprehensive completion metrics
         if let Some(obs) = &self.observability {
@@ -817,6 +1050,156 @@ impl StreamSourceService {
         );
         Ok(channels_saved)
     }
+
+    /// Fetch and parse the channels of a source from its URL
+    ///
+    /// M3U playlists are fetched and parsed separately so the download can be measured and,
+    /// when enabled, archived.
+    async fn fetch_channels(
+        &self,
+        source: &StreamSource,
+        handler: &dyn FullSourceHandler,
+        factory: &crate::utils::HttpClientFactory,
+        limits: &SourceIngestionLimits,
+        filter: &CompiledCategoryFilter,
+        outcome: &mut IngestionRunOutcome,
+    ) -> AppResult<Vec<crate::models::Channel>> {
+        match source.source_type {
+            StreamSourceType::M3u => {
+                let m3u_handler = crate::sources::m3u::M3uSourceHandler::new(factory).await;
+                let content = m3u_handler
+                    .fetch_playlist_with_limits(source, limits)
+                    .await?;
+                outcome.bytes_downloaded = Some(content.len() as u64);
+                if let Some(archive) = &self.ingest_archive
+                    && let Err(e) = archive
+                        .archive_bytes(IngestSnapshotKind::Stream, source.id, content.as_bytes())
+                        .await
+                {
+                    warn!("Failed to archive playlist of '{}': {}", source.name, e);
+                }
+                m3u_handler
+                    .parse_m3u_content_with_limits(&content, source, limits)
+                    .await
+            }
+            StreamSourceType::Xtream => {
+                let xtream_handler =
+                    crate::sources::xtream::XtreamSourceHandler::new(factory).await;
+                with_parse_time_limit(
+                    limits,
+                    source,
+                    xtream_handler.ingest_channels_filtered(source, filter),
+                )
+                .await
+            }
+            _ => with_parse_time_limit(limits, source, handler.ingest_channels(source)).await,
+        }
+    }
+
+    /// Convert a handler error, recording refreshes stopped by an ingestion limit
+    fn ingestion_failed(&self, source: &StreamSource, error: AppError) -> anyhow::Error {
+        if let AppError::Source(SourceError::LimitExceeded { limit, .. }) = &error {
+            warn!(
+                "Refresh of stream source '{}' stopped: {}",
+                source.name, error
+            );
+            if let Some(obs) = &self.observability {
+                obs.source_failures.add(
+                    1,
+                    &[
+                        KeyValue::new("operation", "refresh_channels"),
+                        KeyValue::new("source_type", source.source_type.to_string()),
+                        KeyValue::new("error_type", format!("limit_exceeded_{limit}")),
+                    ],
+                );
+            }
+        }
+        anyhow::anyhow!("Stream source handler failed: {}", error)
+    }
+
+    /// Replace a source's channels with those parsed from an archived playlist
+    ///
+    /// Runs the current parser over the snapshot; `last_ingested_at` is left untouched as
+    /// nothing was fetched.
+    pub async fn replay_snapshot(
+        &self,
+        source: &StreamSource,
+        snapshot: &IngestSnapshot,
+    ) -> Result<usize> {
+        let archive = self
+            .ingest_archive
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Ingest archive is not enabled"))?;
+        if source.source_type != StreamSourceType::M3u {
+            return Err(anyhow::anyhow!(
+                "Snapshots can only be replayed into M3U sources"
+            ));
+        }
+
+        let factory = match &self.http_client_factory {
+            Some(factory) => factory.clone(),
+            None => crate::utils::HttpClientFactory::new(None, std::time::Duration::from_secs(10)),
+        };
+        let content = archive
+            .read_text(snapshot, factory.max_decompressed_bytes())
+            .await?;
+        let channels = crate::sources::m3u::M3uSourceHandler::new(&factory)
+            .await
+            .parse_m3u_content(&content, source)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse snapshot {}: {}", snapshot.id, e))?;
+
+        let (channels_saved, _) = self.save_channels(source.id, channels).await?;
+        let _ = self.cache_invalidation_tx.send(CachedEntity::StreamSources);
+
+        info!(
+            "Replayed snapshot {} into stream source '{}': {} channels saved",
+            snapshot.id, source.name, channels_saved
+        );
+        Ok(channels_saved)
+    }
+
+    /// Live categories offered by an Xtream source, flagged with whether its category
+    /// filter ingests them
+    pub async fn list_xtream_categories(
+        &self,
+        source: &StreamSource,
+    ) -> Result<Vec<crate::models::xtream_category_filter::XtreamCategory>> {
+        if source.source_type != StreamSourceType::Xtream {
+            return Err(anyhow::anyhow!(
+                "Categories can only be listed for Xtream sources"
+            ));
+        }
+
+        let filter = match &self.category_filters {
+            Some(repo) => repo
+                .get(&source.id)
+                .await?
+                .compile()
+                .map_err(|e| anyhow::anyhow!("Invalid category filter: {}", e))?,
+            None => CompiledCategoryFilter::default(),
+        };
+        let factory = match &self.http_client_factory {
+            Some(factory) => factory.clone(),
+            None => crate::utils::HttpClientFactory::new(None, std::time::Duration::from_secs(10)),
+        };
+        let categories = crate::sources::xtream::XtreamSourceHandler::new(&factory)
+            .await
+            .get_live_categories(source)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch categories: {}", e))?;
+
+        Ok(categories
+            .into_iter()
+            .map(|(category_id, category_name)| {
+                crate::models::xtream_category_filter::XtreamCategory {
+                    ingested: filter.allows(Some(category_id), Some(&category_name)),
+                    category_id,
+                    category_name,
+                }
+            })
+            .collect())
+    }
 }
 
 /// Stream source with statistics
@@ -849,6 +1232,33 @@ pub struct TestConnectionResult {
     pub has_epg: bool,
 }
 
+/// Run a handler's ingestion, failing once it takes longer than the parse time limit
+async fn with_parse_time_limit<F>(
+    limits: &SourceIngestionLimits,
+    source: &StreamSource,
+    ingestion: F,
+) -> AppResult<Vec<crate::models::Channel>>
+where
+    F: std::future::Future<Output = AppResult<Vec<crate::models::Channel>>>,
+{
+    let Some(max_parse_time) = limits.max_parse_time else {
+        return ingestion.await;
+    };
+    tokio::time::timeout(max_parse_time, ingestion)
+        .await
+        .unwrap_or_else(|_| {
+            Err(SourceError::limit_exceeded(
+                "max_parse_time",
+                format!(
+                    "source '{}' took longer than {} to ingest",
+                    source.name,
+                    humantime::format_duration(max_parse_time)
+                ),
+            )
+            .into())
+        })
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -0,0 +1,256 @@
+//! systemd service notification (`sd_notify`) and watchdog
+//!
+//! Speaks the notification protocol directly: datagrams of `KEY=VALUE` lines sent to the
+//! unix socket named by `NOTIFY_SOCKET` (a path, or an abstract name starting with `@`).
+//! When the variable is absent — the process is not a `Type=notify` systemd service —
+//! every notification is a no-op.
+//!
+//! The watchdog sends `WATCHDOG=1` at half the interval systemd asks for in
+//! `WATCHDOG_USEC`, but only while the job runner is making progress and the database
+//! answers; an unhealthy instance stops sending keepalives and is restarted by systemd
+//! once the watchdog timeout passes.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::config::SystemdConfig;
+use crate::database::Database;
+use crate::job_scheduling::JobQueueRunner;
+
+/// Sends service state notifications to systemd
+#[derive(Debug, Clone, Default)]
+pub struct SystemdNotifier {
+    /// Value of `NOTIFY_SOCKET`; `None` disables notifications
+    socket: Option<String>,
+    /// Keepalive interval requested by systemd for this process
+    watchdog: Option<Duration>,
+}
+
+impl SystemdNotifier {
+    /// Notifier for the socket and watchdog systemd passed in the environment
+    pub fn from_env(config: &SystemdConfig) -> Self {
+        if !config.enabled {
+            return Self::default();
+        }
+        let socket = std::env::var("NOTIFY_SOCKET")
+            .ok()
+            .filter(|socket| !socket.is_empty());
+        let watchdog = watchdog_interval(
+            std::env::var("WATCHDOG_USEC").ok().as_deref(),
+            std::env::var("WATCHDOG_PID").ok().as_deref(),
+            std::process::id(),
+        );
+        if let Some(socket) = &socket {
+            info!(
+                "systemd notifications enabled (socket: {socket}, watchdog: {})",
+                watchdog.map_or("off".to_string(), |w| humantime::format_duration(w)
+                    .to_string())
+            );
+        }
+        Self { socket, watchdog }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.socket.is_some()
+    }
+
+    /// Startup finished: the server is listening and background services are running
+    pub fn ready(&self) {
+        self.notify("READY=1\nSTATUS=Serving");
+    }
+
+    /// Shutdown has begun
+    pub fn stopping(&self) {
+        self.notify("STOPPING=1\nSTATUS=Shutting down");
+    }
+
+    /// Free-form status line shown by `systemctl status`
+    pub fn status(&self, status: &str) {
+        self.notify(&format!("STATUS={}", status.replace('\n', " ")));
+    }
+
+    /// Send raw notification lines; failures are logged, never fatal
+    pub fn notify(&self, state: &str) {
+        let Some(socket) = &self.socket else {
+            return;
+        };
+        if let Err(e) = send(socket, state) {
+            warn!("Failed to notify systemd ({socket}): {e}");
+        }
+    }
+
+    /// Send watchdog keepalives while the instance is healthy, until cancelled
+    ///
+    /// Returns immediately when systemd has not enabled the watchdog for this process.
+    pub async fn run_watchdog(
+        self: Arc<Self>,
+        config: SystemdConfig,
+        job_queue_runner: Arc<JobQueueRunner>,
+        database: Database,
+        cancellation_token: CancellationToken,
+    ) {
+        let Some(watchdog) = self.watchdog.filter(|_| self.is_enabled()) else {
+            return;
+        };
+        let stall_timeout = config.job_runner_stall_timeout_duration();
+        let database_timeout = config.database_timeout_duration();
+        let mut keepalive = tokio::time::interval(watchdog / 2);
+        keepalive.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut healthy = true;
+
+        loop {
+            tokio::select! {
+                _ = keepalive.tick() => {
+                    match health_problem(&job_queue_runner, &database, stall_timeout, database_timeout).await {
+                        None => {
+                            if !healthy {
+                                info!("Instance healthy again, resuming systemd watchdog keepalives");
+                                self.status("Serving");
+                                healthy = true;
+                            }
+                            self.notify("WATCHDOG=1");
+                        }
+                        Some(problem) => {
+                            if healthy {
+                                warn!("Withholding systemd watchdog keepalive: {problem}");
+                                healthy = false;
+                            } else {
+                                debug!("Still withholding systemd watchdog keepalive: {problem}");
+                            }
+                            self.status(&format!("Unhealthy: {problem}"));
+                        }
+                    }
+                }
+                _ = cancellation_token.cancelled() => break,
+            }
+        }
+    }
+}
+
+/// Why the instance should not be kept alive, if anything
+async fn health_problem(
+    job_queue_runner: &JobQueueRunner,
+    database: &Database,
+    stall_timeout: Duration,
+    database_timeout: Duration,
+) -> Option<String> {
+    use sea_orm::ConnectionTrait;
+
+    // The runner passes over the queue every few seconds; a missing heartbeat right after
+    // startup is not a stall
+    if let Some(heartbeat) = job_queue_runner.last_heartbeat() {
+        let age = (chrono::Utc::now() - heartbeat)
+            .to_std()
+            .unwrap_or_default();
+        if age > stall_timeout {
+            return Some(format!(
+                "job runner has not completed a pass for {}",
+                humantime::format_duration(Duration::from_secs(age.as_secs()))
+            ));
+        }
+    }
+
+    let connection = database.connection();
+    let statement = sea_orm::Statement::from_string(database.backend(), "SELECT 1".to_owned());
+    match tokio::time::timeout(database_timeout, connection.query_one(statement)).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(format!("database unreachable: {e}")),
+        Err(_) => Some(format!(
+            "database did not answer within {}",
+            humantime::format_duration(database_timeout)
+        )),
+    }
+}
+
+/// Keepalive interval from `WATCHDOG_USEC`, if the watchdog applies to this process
+fn watchdog_interval(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if let Some(pid) = pid
+        && pid.trim().parse::<u32>().ok() != Some(own_pid)
+    {
+        return None;
+    }
+    usec?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|usec| *usec > 0)
+        .map(Duration::from_micros)
+}
+
+#[cfg(unix)]
+fn send(socket: &str, state: &str) -> std::io::Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    let datagram = UnixDatagram::unbound()?;
+    if let Some(abstract_name) = socket.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::linux::net::SocketAddrExt;
+            let address = std::os::unix::net::SocketAddr::from_abstract_name(abstract_name)?;
+            datagram.send_to_addr(state.as_bytes(), &address)?;
+            return Ok(());
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = abstract_name;
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "abstract notify sockets are only supported on Linux",
+            ));
+        }
+    }
+    datagram.send_to(state.as_bytes(), socket)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send(_socket: &str, _state: &str) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "systemd notifications require unix sockets",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_interval() {
+        assert_eq!(
+            watchdog_interval(Some("30000000"), None, 42),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            watchdog_interval(Some("30000000"), Some("42"), 42),
+            Some(Duration::from_secs(30))
+        );
+        // Meant for another process (e.g. a wrapper script)
+        assert_eq!(watchdog_interval(Some("30000000"), Some("7"), 42), None);
+        assert_eq!(watchdog_interval(Some("0"), None, 42), None);
+        assert_eq!(watchdog_interval(None, None, 42), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_notify_sends_datagram() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify.sock");
+        let receiver = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+        let notifier = SystemdNotifier {
+            socket: Some(path.to_string_lossy().into_owned()),
+            watchdog: None,
+        };
+        notifier.ready();
+
+        let mut buf = [0u8; 64];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1\nSTATUS=Serving");
+
+        // Without a socket nothing is sent and nothing fails
+        SystemdNotifier::default().stopping();
+    }
+}
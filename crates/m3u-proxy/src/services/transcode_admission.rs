@@ -0,0 +1,288 @@
+//! Transcode admission control
+//!
+//! Transcoding relays compete for a limited number of encoder sessions (GPUs) or CPU
+//! cores. Each new transcoding relay takes a slot on its encoding device for as long as
+//! it runs; once a device is saturated, new relays queue for a slot, fall back to codec
+//! copy, or are refused, depending on the configured policy. Copy-only relays never
+//! take a slot.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use uuid::Uuid;
+
+use crate::config::{TranscodeAdmissionConfig, TranscodeSaturationPolicy};
+use crate::models::relay::{
+    AudioCodec, RelayError, ResolvedRelayConfig, TranscodeDeviceUtilization, VideoCodec,
+};
+
+/// Outcome of asking for a transcode slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscodeAdmissionDecision {
+    /// Start the relay as configured
+    Admitted,
+    /// The device is saturated; start the relay without transcoding
+    Passthrough,
+}
+
+/// Slot limit and counters of a device with a configured limit
+struct DeviceSlots {
+    limit: usize,
+    semaphore: Arc<Semaphore>,
+    queued: AtomicUsize,
+    passthrough_fallbacks: AtomicU64,
+    rejected: AtomicU64,
+}
+
+/// Slot held by a running relay
+struct HeldSlot {
+    device: String,
+    /// `None` on devices without a limit, where the slot is only counted
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+/// Per-device transcode slot accounting for the relay manager
+pub struct TranscodeAdmission {
+    devices: HashMap<String, DeviceSlots>,
+    policy: TranscodeSaturationPolicy,
+    queue_timeout: Duration,
+    held: Mutex<HashMap<Uuid, HeldSlot>>,
+}
+
+impl TranscodeAdmission {
+    pub fn new(config: &TranscodeAdmissionConfig) -> Self {
+        let devices = config
+            .max_concurrent
+            .iter()
+            .map(|(device, limit)| {
+                (
+                    device.to_lowercase(),
+                    DeviceSlots {
+                        limit: *limit,
+                        semaphore: Arc::new(Semaphore::new(*limit)),
+                        queued: AtomicUsize::new(0),
+                        passthrough_fallbacks: AtomicU64::new(0),
+                        rejected: AtomicU64::new(0),
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            devices,
+            policy: config.when_saturated,
+            queue_timeout: config.queue_timeout_duration(),
+            held: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a slot on `device` for relay `config_id` (`None` for copy-only relays)
+    ///
+    /// With the queue policy this waits up to the queue timeout for a slot to free up.
+    pub async fn admit(
+        &self,
+        config_id: Uuid,
+        device: Option<&str>,
+    ) -> Result<TranscodeAdmissionDecision, RelayError> {
+        let Some(device) = device.map(str::to_lowercase) else {
+            return Ok(TranscodeAdmissionDecision::Admitted);
+        };
+        let Some(slots) = self.devices.get(&device) else {
+            self.hold(config_id, device, None);
+            return Ok(TranscodeAdmissionDecision::Admitted);
+        };
+
+        let permit = match slots.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => Some(permit),
+            Err(_) if self.policy == TranscodeSaturationPolicy::Queue => {
+                slots.queued.fetch_add(1, Ordering::Relaxed);
+                let waited = tokio::time::timeout(
+                    self.queue_timeout,
+                    slots.semaphore.clone().acquire_owned(),
+                )
+                .await;
+                slots.queued.fetch_sub(1, Ordering::Relaxed);
+                waited.ok().and_then(Result::ok)
+            }
+            Err(_) => None,
+        };
+
+        match permit {
+            Some(permit) => {
+                self.hold(config_id, device, Some(permit));
+                Ok(TranscodeAdmissionDecision::Admitted)
+            }
+            None if self.policy == TranscodeSaturationPolicy::Reject => {
+                slots.rejected.fetch_add(1, Ordering::Relaxed);
+                Err(RelayError::TranscodeCapacity(device))
+            }
+            None => {
+                slots.passthrough_fallbacks.fetch_add(1, Ordering::Relaxed);
+                Ok(TranscodeAdmissionDecision::Passthrough)
+            }
+        }
+    }
+
+    /// Free the slot of a relay that stopped (no-op if it held none)
+    pub fn release(&self, config_id: Uuid) {
+        self.held_slots().remove(&config_id);
+    }
+
+    /// Slot usage of every configured device and every device with running transcodes
+    pub fn utilization(&self) -> Vec<TranscodeDeviceUtilization> {
+        let mut active: HashMap<String, usize> = HashMap::new();
+        for slot in self.held_slots().values() {
+            *active.entry(slot.device.clone()).or_default() += 1;
+        }
+
+        let mut devices: Vec<TranscodeDeviceUtilization> = self
+            .devices
+            .iter()
+            .map(|(device, slots)| TranscodeDeviceUtilization {
+                device: device.clone(),
+                max_concurrent: Some(slots.limit),
+                active: active.remove(device).unwrap_or_default(),
+                queued: slots.queued.load(Ordering::Relaxed),
+                passthrough_fallbacks: slots.passthrough_fallbacks.load(Ordering::Relaxed),
+                rejected: slots.rejected.load(Ordering::Relaxed),
+            })
+            .collect();
+        devices.extend(
+            active
+                .into_iter()
+                .map(|(device, active)| TranscodeDeviceUtilization {
+                    device,
+                    max_concurrent: None,
+                    active,
+                    queued: 0,
+                    passthrough_fallbacks: 0,
+                    rejected: 0,
+                }),
+        );
+        devices.sort_by(|a, b| a.device.cmp(&b.device));
+        devices
+    }
+
+    fn hold(&self, config_id: Uuid, device: String, permit: Option<OwnedSemaphorePermit>) {
+        self.held_slots().insert(
+            config_id,
+            HeldSlot {
+                device,
+                _permit: permit,
+            },
+        );
+    }
+
+    fn held_slots(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, HeldSlot>> {
+        self.held.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The relay with all codecs copied, for running it without a transcode slot
+///
+/// `None` for legacy argument profiles, whose codecs cannot be rewritten.
+pub fn passthrough_config(config: &ResolvedRelayConfig) -> Option<ResolvedRelayConfig> {
+    if !config.effective_args.is_empty() {
+        return None;
+    }
+    let mut passthrough = config.clone();
+    passthrough.profile.video_codec = VideoCodec::Copy;
+    passthrough.profile.audio_codec = AudioCodec::Copy;
+    passthrough.profile.enable_hardware_acceleration = false;
+    Some(passthrough)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn admission(policy: TranscodeSaturationPolicy) -> TranscodeAdmission {
+        TranscodeAdmission::new(&TranscodeAdmissionConfig {
+            max_concurrent: HashMap::from([("nvenc".to_string(), 1)]),
+            when_saturated: policy,
+            queue_timeout: "50ms".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_saturated_device_falls_back_to_passthrough() {
+        let admission = admission(TranscodeSaturationPolicy::Passthrough);
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        assert_eq!(
+            admission.admit(first, Some("nvenc")).await.unwrap(),
+            TranscodeAdmissionDecision::Admitted
+        );
+        assert_eq!(
+            admission.admit(second, Some("nvenc")).await.unwrap(),
+            TranscodeAdmissionDecision::Passthrough
+        );
+
+        admission.release(first);
+        assert_eq!(
+            admission.admit(second, Some("NVENC")).await.unwrap(),
+            TranscodeAdmissionDecision::Admitted
+        );
+
+        let usage = admission.utilization();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].active, 1);
+        assert_eq!(usage[0].passthrough_fallbacks, 1);
+    }
+
+    #[tokio::test]
+    async fn test_queue_waits_for_released_slot() {
+        let admission = Arc::new(admission(TranscodeSaturationPolicy::Queue));
+        let first = Uuid::new_v4();
+        admission.admit(first, Some("nvenc")).await.unwrap();
+
+        let releaser = admission.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            releaser.release(first);
+        });
+        assert_eq!(
+            admission
+                .admit(Uuid::new_v4(), Some("nvenc"))
+                .await
+                .unwrap(),
+            TranscodeAdmissionDecision::Admitted
+        );
+
+        // Nobody frees the slot this time, so the queued relay falls back after the timeout
+        assert_eq!(
+            admission
+                .admit(Uuid::new_v4(), Some("nvenc"))
+                .await
+                .unwrap(),
+            TranscodeAdmissionDecision::Passthrough
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reject_policy_and_unlimited_devices() {
+        let admission = admission(TranscodeSaturationPolicy::Reject);
+        admission
+            .admit(Uuid::new_v4(), Some("nvenc"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            admission.admit(Uuid::new_v4(), Some("nvenc")).await,
+            Err(RelayError::TranscodeCapacity(_))
+        ));
+
+        // Copy-only relays and devices without a limit are always admitted
+        admission.admit(Uuid::new_v4(), None).await.unwrap();
+        admission.admit(Uuid::new_v4(), Some("cpu")).await.unwrap();
+        admission.admit(Uuid::new_v4(), Some("cpu")).await.unwrap();
+
+        let usage = admission.utilization();
+        let cpu = usage.iter().find(|d| d.device == "cpu").unwrap();
+        assert_eq!((cpu.active, cpu.max_concurrent), (2, None));
+        let nvenc = usage.iter().find(|d| d.device == "nvenc").unwrap();
+        assert_eq!(nvenc.rejected, 1);
+    }
+}
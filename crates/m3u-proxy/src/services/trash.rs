@@ -0,0 +1,40 @@
+//! Trash purge
+//!
+//! Deleted sources, proxies, filters and data mapping rules stay in the trash for the
+//! configured retention period. A maintenance job, scheduled every `trash.purge_interval`,
+//! removes everything that has been in the trash for longer.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::config::TrashConfig;
+use crate::database::repositories::TrashSeaOrmRepository;
+use crate::models::trash::TrashPurgeSummary;
+
+/// Maintenance operation that purges expired trash
+pub const TRASH_PURGE_JOB: &str = "trash_purge";
+
+/// Permanently remove items trashed longer than the retention period ago
+pub async fn purge_expired(
+    connection: Arc<sea_orm::DatabaseConnection>,
+    config: &TrashConfig,
+) -> Result<TrashPurgeSummary> {
+    let retention = chrono::Duration::from_std(config.retention_duration())?;
+    let summary = TrashSeaOrmRepository::new(connection)
+        .purge_deleted_before(Utc::now() - retention)
+        .await?;
+    if summary.total() > 0 {
+        info!(
+            "Purged {} trashed item(s): {} stream source(s), {} EPG source(s), {} proxy(ies), {} filter(s), {} data mapping rule(s)",
+            summary.total(),
+            summary.stream_sources,
+            summary.epg_sources,
+            summary.proxies,
+            summary.filters,
+            summary.data_mapping_rules
+        );
+    }
+    Ok(summary)
+}
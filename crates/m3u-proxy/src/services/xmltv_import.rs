@@ -0,0 +1,280 @@
+//! XMLTV watch folder import service
+//!
+//! Periodically scans a sandboxed import directory for `.xml` / `.xml.gz` files
+//! (e.g. written by a local guide grabber). Each settled file is ingested into a
+//! file-backed EPG source named after the file, then archived or deleted
+//! according to the configured policy.
+
+use anyhow::Result;
+use chrono::Utc;
+use sandboxed_file_manager::{ListOptions, SandboxedManager};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use crate::config::{XmltvImportAction, XmltvImportConfig};
+use crate::services::{EpgSourceService, LeaderElection};
+use crate::sources::xmltv_epg::XmltvParseStats;
+
+/// Subdirectory of the import path used for archived files
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// Watch folder service that imports local XMLTV files into file-backed EPG sources
+pub struct XmltvImportService {
+    config: XmltvImportConfig,
+    import_file_manager: SandboxedManager,
+    archive_file_manager: SandboxedManager,
+    epg_source_service: Arc<EpgSourceService>,
+    leader_election: Option<Arc<LeaderElection>>,
+}
+
+/// Outcome of a single scan of the import directory
+#[derive(Debug, Default, Clone)]
+pub struct XmltvImportScanResult {
+    pub imported_files: usize,
+    pub failed_files: usize,
+    pub programs_imported: usize,
+    /// Programmes dropped as duplicates
+    pub programs_skipped: usize,
+    /// Programmes without a valid stop time, imported with the default duration
+    pub durations_defaulted: usize,
+}
+
+impl XmltvImportService {
+    /// Create a new import service
+    ///
+    /// The archive manager should be rooted at `<import_path>/archive` and carry the
+    /// archive retention cleanup policy.
+    pub fn new(
+        config: XmltvImportConfig,
+        import_file_manager: SandboxedManager,
+        archive_file_manager: SandboxedManager,
+        epg_source_service: Arc<EpgSourceService>,
+    ) -> Self {
+        Self {
+            config,
+            import_file_manager,
+            archive_file_manager,
+            epg_source_service,
+            leader_election: None,
+        }
+    }
+
+    /// Only scan while this instance is the cluster leader
+    pub fn with_leader_election(mut self, leader_election: Arc<LeaderElection>) -> Self {
+        self.leader_election = Some(leader_election);
+        self
+    }
+
+    /// Run the watch loop until cancelled
+    pub async fn run(&self, cancellation_token: tokio_util::sync::CancellationToken) -> Result<()> {
+        info!(
+            "Starting XMLTV watch folder import (path: {:?}, interval: {})",
+            self.config.import_path, self.config.poll_interval
+        );
+        let mut scan_interval = tokio::time::interval(self.config.poll_interval_duration());
+
+        loop {
+            tokio::select! {
+                _ = scan_interval.tick() => {
+                    if self.leader_election.as_ref().is_some_and(|l| !l.is_leader()) {
+                        continue;
+                    }
+                    match self.scan_once().await {
+                        Ok(result) if result.imported_files > 0 || result.failed_files > 0 => {
+                            info!(
+                                "XMLTV watch folder scan: {} imported ({} programs, {} duplicates skipped, {} durations defaulted), {} failed",
+                                result.imported_files,
+                                result.programs_imported,
+                                result.programs_skipped,
+                                result.durations_defaulted,
+                                result.failed_files
+                            );
+                        }
+                        Ok(_) => {}
+                        Err(e) => error!("XMLTV watch folder scan failed: {}", e),
+                    }
+                }
+                _ = cancellation_token.cancelled() => {
+                    info!("XMLTV watch folder received cancellation signal, shutting down");
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scan the import directory once and import every settled XMLTV file
+    pub async fn scan_once(&self) -> Result<XmltvImportScanResult> {
+        let mut result = XmltvImportScanResult::default();
+
+        for file_name in self.settled_candidates().await? {
+            let Some(import_name) = import_name_for_file(&file_name) else {
+                continue;
+            };
+
+            match self.import_file(&file_name, &import_name).await {
+                Ok((programs, stats)) => {
+                    result.imported_files += 1;
+                    result.programs_imported += programs;
+                    result.programs_skipped += stats.duplicate_programs;
+                    result.durations_defaulted += stats.defaulted_durations;
+                }
+                Err(e) => {
+                    result.failed_files += 1;
+                    error!("Failed to import XMLTV file '{}': {}", file_name, e);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// List top-level import files that look like XMLTV and have not been modified recently
+    async fn settled_candidates(&self) -> Result<Vec<String>> {
+        let settle_time = self.config.settle_time_duration();
+        let now = Utc::now();
+
+        let listing = self
+            .import_file_manager
+            .list_dir(".", &ListOptions::new())
+            .await?;
+        let mut candidates = Vec::new();
+        for entry in listing.entries {
+            if import_name_for_file(&entry.name).is_none() {
+                continue;
+            }
+
+            let age = entry
+                .modified
+                .and_then(|modified| (now - modified).to_std().ok())
+                .unwrap_or(Duration::ZERO);
+            if age < settle_time {
+                debug!(
+                    "XMLTV import file '{}' still settling ({:?} < {:?})",
+                    entry.name, age, settle_time
+                );
+                continue;
+            }
+
+            candidates.push(entry.name);
+        }
+
+        Ok(candidates)
+    }
+
+    /// Import a single file and apply the post-import action
+    ///
+    /// Returns the programmes saved and the file's parse counts.
+    async fn import_file(
+        &self,
+        file_name: &str,
+        import_name: &str,
+    ) -> Result<(usize, XmltvParseStats)> {
+        info!(
+            "Importing XMLTV file '{}' into file-backed source '{}'",
+            file_name, import_name
+        );
+
+        let bytes = bytes::Bytes::from(self.import_file_manager.read(file_name).await?);
+        let source = self
+            .epg_source_service
+            .ensure_file_backed_source(import_name)
+            .await?;
+
+        if !source.is_active {
+            warn!(
+                "File-backed EPG source '{}' is inactive; leaving '{}' in the import folder",
+                source.name, file_name
+            );
+            return Ok((0, XmltvParseStats::default()));
+        }
+
+        // Shares the buffer with the archive write below rather than copying it
+        let (programs, stats) = self
+            .epg_source_service
+            .ingest_local_xmltv(&source, bytes.clone())
+            .await?;
+
+        match self.config.after_import {
+            XmltvImportAction::Archive => {
+                let archived_name = archived_file_name(file_name, Utc::now());
+                self.archive_file_manager
+                    .write(&archived_name, &bytes)
+                    .await?;
+                self.import_file_manager.remove_file(file_name).await?;
+                debug!(
+                    "Archived XMLTV import '{}' as '{}'",
+                    file_name, archived_name
+                );
+            }
+            XmltvImportAction::Delete => {
+                self.import_file_manager.remove_file(file_name).await?;
+                debug!("Deleted XMLTV import '{}'", file_name);
+            }
+        }
+
+        Ok((programs, stats))
+    }
+}
+
+/// Derive the EPG source import name from a file name
+///
+/// Returns `None` for files that are not XMLTV (`.xml` or `.xml.gz`), so `guide.xml`
+/// and `guide.xml.gz` both map to the `guide` source.
+pub fn import_name_for_file(file_name: &str) -> Option<String> {
+    let lower = file_name.to_lowercase();
+    let stem_len = if lower.ends_with(".xml.gz") {
+        file_name.len() - ".xml.gz".len()
+    } else if lower.ends_with(".xml") {
+        file_name.len() - ".xml".len()
+    } else {
+        return None;
+    };
+
+    let stem = file_name[..stem_len].trim();
+    if stem.is_empty() || stem.starts_with('.') {
+        None
+    } else {
+        Some(stem.to_string())
+    }
+}
+
+/// Build a timestamped archive file name so repeated imports don't overwrite each other
+fn archived_file_name(file_name: &str, at: chrono::DateTime<Utc>) -> String {
+    format!("{}_{}", at.format("%Y%m%dT%H%M%SZ"), file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_name_for_file() {
+        assert_eq!(import_name_for_file("guide.xml"), Some("guide".to_string()));
+        assert_eq!(
+            import_name_for_file("Guide.XML.GZ"),
+            Some("Guide".to_string())
+        );
+        assert_eq!(
+            import_name_for_file("tv guide.xml.gz").as_deref(),
+            Some("tv guide")
+        );
+        assert_eq!(import_name_for_file("guide.gz"), None);
+        assert_eq!(import_name_for_file("guide.xml.partial"), None);
+        assert_eq!(import_name_for_file(".xml"), None);
+        assert_eq!(import_name_for_file(".hidden.xml"), None);
+    }
+
+    #[test]
+    fn test_archived_file_name() {
+        let at = chrono::DateTime::parse_from_rfc3339("2025-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            archived_file_name("guide.xml.gz", at),
+            "20250102T030405Z_guide.xml.gz"
+        );
+    }
+}
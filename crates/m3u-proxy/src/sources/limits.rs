@@ -0,0 +1,168 @@
+//! Per-source ingestion limits
+//!
+//! Limits come from `[ingestion.limits]`, with per-source overrides keyed by source name
+//! or ID for known-large trusted sources. Exceeding one fails the refresh with a
+//! [`SourceError::LimitExceeded`] and leaves the source's stored channels untouched.
+
+use std::time::{Duration, Instant};
+
+use crate::config::IngestionLimitsConfig;
+use crate::errors::{AppError, SourceError};
+use crate::models::StreamSource;
+
+/// Limits in effect for one source refresh (`None` = unlimited)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceIngestionLimits {
+    pub max_download_bytes: Option<u64>,
+    pub max_channels: Option<usize>,
+    pub max_parse_time: Option<Duration>,
+}
+
+impl SourceIngestionLimits {
+    /// No limits (used when replaying archived snapshots)
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Limits of `source`: its override (by ID, then name) on top of the defaults
+    pub fn resolve(config: &IngestionLimitsConfig, source: &StreamSource) -> Self {
+        let mut limits = Self {
+            max_download_bytes: mib_to_bytes(config.max_download_size_mb),
+            max_channels: (config.max_channels > 0).then_some(config.max_channels),
+            max_parse_time: config.max_parse_time_duration(),
+        };
+        let Some(overrides) = config
+            .overrides
+            .get(&source.id.to_string())
+            .or_else(|| config.overrides.get(&source.name))
+        else {
+            return limits;
+        };
+        if let Some(mb) = overrides.max_download_size_mb {
+            limits.max_download_bytes = mib_to_bytes(mb);
+        }
+        if let Some(max_channels) = overrides.max_channels {
+            limits.max_channels = (max_channels > 0).then_some(max_channels);
+        }
+        if let Some(max_parse_time) = overrides.max_parse_time_duration() {
+            limits.max_parse_time = max_parse_time;
+        }
+        limits
+    }
+
+    /// Fail once a source yields more than `max_channels` channels
+    pub fn check_channel_count(&self, count: usize, source_name: &str) -> Result<(), AppError> {
+        match self.max_channels {
+            Some(max) if count > max => Err(SourceError::limit_exceeded(
+                "max_channels",
+                format!("source '{source_name}' has more than {max} channels"),
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deadline for a parse starting now
+    pub fn parse_deadline(&self) -> Option<Instant> {
+        self.max_parse_time
+            .map(|max_parse_time| Instant::now() + max_parse_time)
+    }
+
+    /// Fail once a parse runs past its deadline
+    pub fn check_parse_deadline(
+        &self,
+        deadline: Option<Instant>,
+        source_name: &str,
+    ) -> Result<(), AppError> {
+        match (deadline, self.max_parse_time) {
+            (Some(deadline), Some(max_parse_time)) if Instant::now() > deadline => {
+                Err(SourceError::limit_exceeded(
+                    "max_parse_time",
+                    format!(
+                        "source '{source_name}' took longer than {} to parse",
+                        humantime::format_duration(max_parse_time)
+                    ),
+                )
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn mib_to_bytes(mb: u64) -> Option<u64> {
+    (mb > 0).then(|| mb.saturating_mul(1024 * 1024))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::IngestionLimitsOverride;
+    use crate::models::StreamSourceType;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn source(name: &str) -> StreamSource {
+        StreamSource {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            source_type: StreamSourceType::M3u,
+            url: "http://example.com/playlist.m3u".to_string(),
+            max_concurrent_streams: 10,
+            update_cron: "0 0 */6 * * * *".to_string(),
+            username: None,
+            password: None,
+            field_map: None,
+            ignore_channel_numbers: false,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            last_ingested_at: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_defaults_and_overrides() {
+        let mut config = IngestionLimitsConfig::default();
+        let limits = SourceIngestionLimits::resolve(&config, &source("Small"));
+        assert_eq!(limits.max_download_bytes, Some(512 * 1024 * 1024));
+        assert_eq!(limits.max_channels, Some(250_000));
+        assert_eq!(limits.max_parse_time, Some(Duration::from_secs(120)));
+
+        config.overrides.insert(
+            "Big Provider".to_string(),
+            IngestionLimitsOverride {
+                max_channels: Some(0),
+                max_parse_time: Some("10m".to_string()),
+                ..Default::default()
+            },
+        );
+        let limits = SourceIngestionLimits::resolve(&config, &source("Big Provider"));
+        assert_eq!(limits.max_download_bytes, Some(512 * 1024 * 1024));
+        assert_eq!(limits.max_channels, None);
+        assert_eq!(limits.max_parse_time, Some(Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn test_checks() {
+        let limits = SourceIngestionLimits {
+            max_channels: Some(2),
+            max_parse_time: Some(Duration::from_millis(1)),
+            ..Default::default()
+        };
+        assert!(limits.check_channel_count(2, "src").is_ok());
+        let err = limits.check_channel_count(3, "src").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Ingestion limit exceeded: max_channels")
+        );
+
+        let deadline = Some(Instant::now() - Duration::from_secs(1));
+        assert!(limits.check_parse_deadline(deadline, "src").is_err());
+        assert!(
+            SourceIngestionLimits::unlimited()
+                .check_parse_deadline(deadline, "src")
+                .is_ok()
+        );
+    }
+}
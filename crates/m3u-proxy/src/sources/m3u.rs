@@ -6,17 +6,27 @@
 use async_trait::async_trait;
 use chrono::Utc;
 use reqwest::Client;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use super::limits::SourceIngestionLimits;
 use super::traits::*;
-use crate::errors::{AppError, AppResult};
+use crate::errors::{AppError, AppResult, SourceError};
 use crate::models::{Channel, StreamSource, StreamSourceType};
 use crate::utils::{
     DecompressingHttpClient, HttpClientFactory, StandardHttpClient, generate_channel_uuid,
 };
 
+/// EXTINF attributes stored in channel columns; any others are kept as extra attributes
+const COLUMN_ATTRIBUTES: &[&str] = &[
+    "tvg-id",
+    "tvg-name",
+    "tvg-logo",
+    "group-title",
+    "tvg-channo",
+];
+
 /// M3U source handler
 ///
 /// This handler implements the full source handler interface for M3U playlist sources.
@@ -49,12 +59,48 @@ impl M3uSourceHandler {
         }
     }
 
+    /// Download a source's playlist
+    pub async fn fetch_playlist(&self, source: &StreamSource) -> AppResult<String> {
+        self.fetch_playlist_with_limits(source, &SourceIngestionLimits::unlimited())
+            .await
+    }
+
+    /// Download a source's playlist, failing when it exceeds the download size limit
+    pub async fn fetch_playlist_with_limits(
+        &self,
+        source: &StreamSource,
+        limits: &SourceIngestionLimits,
+    ) -> AppResult<String> {
+        self.http_client
+            .clone()
+            .with_max_download_bytes(limits.max_download_bytes)
+            .fetch_text(&source.url)
+            .await
+            .map_err(|e| match e {
+                AppError::Source(SourceError::LimitExceeded { .. }) => e,
+                e => AppError::source_error(format!("Failed to fetch M3U: {e}")),
+            })
+    }
+
     /// Parse M3U content into channels
-    async fn parse_m3u_content(
+    pub async fn parse_m3u_content(
+        &self,
+        content: &str,
+        source: &StreamSource,
+    ) -> AppResult<Vec<Channel>> {
+        self.parse_m3u_content_with_limits(content, source, &SourceIngestionLimits::unlimited())
+            .await
+    }
+
+    /// Parse M3U content into channels, stopping as soon as the channel count or parse
+    /// time limit is exceeded
+    pub async fn parse_m3u_content_with_limits(
         &self,
         content: &str,
         source: &StreamSource,
+        limits: &SourceIngestionLimits,
     ) -> AppResult<Vec<Channel>> {
+        let deadline = limits.parse_deadline();
         let mut channels = Vec::new();
         let mut current_channel: Option<PartialChannel> = None;
 
@@ -65,6 +111,10 @@ impl M3uSourceHandler {
         debug!("Starting M3U parsing for source: {}", source.name);
 
         for (line_num, line) in content.lines().enumerate() {
+            if line_num % 1024 == 0 {
+                limits.check_parse_deadline(deadline, &source.name)?;
+                limits.check_channel_count(channels.len(), &source.name)?;
+            }
             let line = line.trim();
 
             if line.is_empty() || line.starts_with('#') && !line.starts_with("#EXTINF") {
@@ -127,6 +177,7 @@ impl M3uSourceHandler {
 
         // Clean up deduplication set to free memory
         drop(seen_channels);
+        limits.check_channel_count(channels.len(), &source.name)?;
 
         if duplicate_count > 0 {
             info!(
@@ -295,8 +346,10 @@ impl M3uSourceHandler {
             tvg_id: partial.tvg_id,
             tvg_name: partial.tvg_name,
             tvg_chno: partial.attributes.get("tvg-channo").cloned(),
+            extra_attributes: extra_attributes(&partial.attributes),
             tvg_logo: partial.tvg_logo,
             tvg_shift: None,
+            epg_shift: None,
             group_title: partial.group_title,
             channel_name: partial.name,
             stream_url: partial.url,
@@ -337,6 +390,7 @@ impl M3uSourceHandler {
             tvg_chno: None,
             tvg_logo: None,
             tvg_shift: None,
+            epg_shift: None,
             group_title: None,
             channel_name: name,
             stream_url: url.to_string(),
@@ -347,6 +401,7 @@ impl M3uSourceHandler {
             resolution: None,
             probe_method: None,
             last_probed_at: None,
+            extra_attributes: Default::default(),
         })
     }
 
@@ -404,6 +459,16 @@ impl M3uSourceHandler {
     }
 }
 
+/// Attributes of an EXTINF line that have no channel column, such as catchup, timeshift,
+/// tvg-rec or custom x- attributes
+fn extra_attributes(attributes: &HashMap<String, String>) -> BTreeMap<String, String> {
+    attributes
+        .iter()
+        .filter(|(name, _)| !COLUMN_ATTRIBUTES.contains(&name.as_str()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
 /// Partial channel structure used during parsing
 struct PartialChannel {
     name: String,
@@ -510,11 +575,7 @@ impl SourceHandler for M3uSourceHandler {
 impl ChannelIngestor for M3uSourceHandler {
     async fn ingest_channels(&self, source: &StreamSource) -> AppResult<Vec<Channel>> {
         // Fetch and parse M3U content directly
-        let content = self
-            .http_client
-            .fetch_text(&source.url)
-            .await
-            .map_err(|e| AppError::source_error(format!("Failed to fetch M3U: {e}")))?;
+        let content = self.fetch_playlist(source).await?;
         self.parse_m3u_content(&content, source).await
     }
 
@@ -560,3 +621,21 @@ impl StreamUrlGenerator for M3uSourceHandler {
 }
 
 impl FullSourceHandler for M3uSourceHandler {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extra_attributes_skip_column_attributes() {
+        let attributes = HashMap::from([
+            ("tvg-id".to_string(), "bbc1.uk".to_string()),
+            ("group-title".to_string(), "UK".to_string()),
+            ("catchup".to_string(), "default".to_string()),
+            ("catchup-days".to_string(), "7".to_string()),
+            ("x-custom".to_string(), "yes".to_string()),
+        ]);
+        let extra: Vec<_> = extra_attributes(&attributes).into_keys().collect();
+        assert_eq!(extra, ["catchup", "catchup-days", "x-custom"]);
+    }
+}
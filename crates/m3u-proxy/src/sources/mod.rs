@@ -52,6 +52,7 @@
 //! ```
 
 pub mod factory;
+pub mod limits;
 pub mod m3u;
 pub mod traits;
 pub mod xmltv_epg;
@@ -59,4 +60,5 @@ pub mod xtream;
 pub mod xtream_epg;
 
 pub use factory::SourceHandlerFactory;
+pub use limits::SourceIngestionLimits;
 pub use traits::*;
@@ -5,7 +5,7 @@
 //!
 //! # Features
 //!
-//! - XMLTV format parsing using the xmltv crate
+//! - Streaming XMLTV parsing with quick-xml, in bounded batches
 //! - Robust HTTP fetching with timeout and error handling
 //! - Progress reporting during ingestion
 //! - Timezone detection and normalization
@@ -13,9 +13,15 @@
 
 use async_trait::async_trait;
 use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tracing::{debug, info};
 
 use crate::errors::{AppError, AppResult};
+use crate::models::epg_channel_metadata::{EpgChannelMetadata, LocalizedValue};
 use crate::models::{EpgProgram, EpgSource, EpgSourceType};
 use crate::sources::traits::{
     EpgProgramIngestor, EpgSourceCapabilities, EpgSourceHandler, EpgSourceHandlerSummary,
@@ -24,6 +30,7 @@ use crate::sources::traits::{
 use crate::utils::http_client::DecompressingHttpClient;
 use crate::utils::time::{detect_timezone_from_xmltv, log_timezone_detection};
 use crate::utils::url::UrlUtils;
+use crate::utils::xmltv_parser::{SimpleXmltvChannel, SimpleXmltvProgram, XmltvProgramReader};
 use crate::utils::{
     CompressionFormat, DecompressionService, HttpClientFactory, StandardHttpClient,
 };
@@ -45,6 +52,12 @@ impl XmltvEpgHandler {
 
     /// Fetch XMLTV content from URL with automatic decompression support
     async fn fetch_xmltv_content(&self, url: &str) -> AppResult<String> {
+        if url.starts_with(crate::models::epg_source::FILE_BACKED_URL_PREFIX) {
+            return Err(AppError::source_error(format!(
+                "'{url}' is a file-backed XMLTV source and is refreshed by the import watch folder"
+            )));
+        }
+
         debug!("Fetching XMLTV content from: {}", url);
 
         // Use circuit breaker-wrapped HTTP client to fetch bytes
@@ -56,19 +69,30 @@ impl XmltvEpgHandler {
 
         debug!("Fetched {} bytes of raw XMLTV content", bytes.len());
 
+        self.decode_xmltv_bytes(bytes, url)
+    }
+
+    /// Decompress (if needed) and decode raw XMLTV bytes into a UTF-8 string
+    fn decode_xmltv_bytes(&self, bytes: Vec<u8>, url: &str) -> AppResult<String> {
         // Detect compression format and decompress if needed
-        let compression_format = DecompressionService::detect_compression_format(&bytes);
+        let compression_format = DecompressionService::resolve_format(&bytes, None, Some(url))
+            .map_err(|e| AppError::source_error(e.to_string()))?;
         debug!("Detected compression format: {:?}", compression_format);
 
         let decompressed_bytes = match compression_format {
             CompressionFormat::Uncompressed => {
                 debug!("Content is uncompressed, using as-is");
-                bytes.to_vec()
+                bytes
             }
             _ => {
                 debug!("Content is compressed, decompressing...");
-                DecompressionService::decompress(bytes.into()).map_err(|e| {
-                    AppError::source_error(format!("Failed to decompress XMLTV content: {e}"))
+                DecompressionService::decompress_with_limit(
+                    bytes.into(),
+                    compression_format,
+                    self.http_client.max_decompressed_bytes(),
+                )
+                .map_err(|e| {
+                    AppError::source_error(format!("Failed to decompress XMLTV content: {e:#}"))
                 })?
             }
         };
@@ -86,126 +110,325 @@ impl XmltvEpgHandler {
         Ok(content)
     }
 
+    /// Download a source's XMLTV document to a local file, returning its size in bytes
+    ///
+    /// The body is written as served (possibly compressed) so arbitrarily large guides
+    /// never have to fit in memory; read it back with [`XmltvProgramStream`].
+    pub async fn download_xmltv(&self, source: &EpgSource, path: &Path) -> AppResult<u64> {
+        if source
+            .url
+            .starts_with(crate::models::epg_source::FILE_BACKED_URL_PREFIX)
+        {
+            return Err(AppError::source_error(format!(
+                "'{}' is a file-backed XMLTV source and is refreshed by the import watch folder",
+                source.url
+            )));
+        }
+
+        self.http_client
+            .download_to_file(&source.url, path)
+            .await
+            .map_err(|e| AppError::source_error(format!("Failed to fetch XMLTV: {e}")))
+    }
+
     /// Parse XMLTV content and extract programs only (programs-only mode)
     async fn parse_xmltv_content(
         &self,
         source: &EpgSource,
         content: &str,
     ) -> AppResult<Vec<EpgProgram>> {
-        // Parse using our custom quick-xml parser
-        let xmltv_programs = crate::utils::xmltv_parser::parse_xmltv_programs(content)?;
+        let mut reader = XmltvProgramReader::new(content.as_bytes());
+        let mut converter = ProgramConverter::new(source);
 
-        // Detect timezone
-        let detected_tz = detect_timezone_from_xmltv(content);
-        if let Some(tz) = &detected_tz {
-            log_timezone_detection(&source.name, Some(tz), tz);
-        } else {
-            log_timezone_detection(&source.name, None, "UTC");
+        // Skip channel processing - programs-only approach for database-first generation
+        let mut epg_programs = Vec::new();
+        while let Some(xmltv_program) = reader.next_program()? {
+            if let Some(program) = converter.convert(xmltv_program)? {
+                epg_programs.push(program);
+            }
         }
+        converter.log_summary();
 
-        // Skip channel processing - programs-only approach for database-first generation
+        Ok(epg_programs)
+    }
+}
 
-        // Convert from SimpleXmltvProgram to EpgProgram
-        let mut epg_programs = Vec::new();
-        let mut seen_programs = HashSet::new();
-        let mut duplicate_program_count = 0;
-
-        for xmltv_program in xmltv_programs {
-            // Create deduplication key: channel_id + start_time + program_title
-            let program_title = xmltv_program.title.as_deref().unwrap_or("Unknown Program");
-            let dedup_key = format!(
-                "{}|{}|{}",
-                xmltv_program.channel, xmltv_program.start, program_title
-            );
+/// Duration given to programmes without a usable stop time
+const DEFAULT_PROGRAM_DURATION: chrono::Duration = chrono::Duration::minutes(30);
+
+/// Programme counts of a parsed XMLTV document
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct XmltvParseStats {
+    /// Programmes kept
+    pub programs: usize,
+    /// Programmes dropped as duplicates of an earlier one
+    pub duplicate_programs: usize,
+    /// Programmes without a stop time after their start, given the default 30 minutes
+    pub defaulted_durations: usize,
+}
+
+/// Converts parsed programmes to [`EpgProgram`]s, dropping duplicates
+///
+/// Duplicates are tracked by a 64-bit hash of channel, start and title so the set stays
+/// small for guides with millions of programmes.
+struct ProgramConverter {
+    source_id: uuid::Uuid,
+    source_name: String,
+    seen_programs: HashSet<u64>,
+    stats: XmltvParseStats,
+}
+
+impl ProgramConverter {
+    fn new(source: &EpgSource) -> Self {
+        Self {
+            source_id: source.id,
+            source_name: source.name.clone(),
+            seen_programs: HashSet::new(),
+            stats: XmltvParseStats::default(),
+        }
+    }
 
-            // Skip duplicate programs
-            if seen_programs.contains(&dedup_key) {
-                duplicate_program_count += 1;
-                debug!(
-                    "Skipping duplicate program '{}' on channel '{}' at {}",
-                    program_title, xmltv_program.channel, xmltv_program.start
-                );
-                continue;
+    /// Convert one programme, or `None` when it duplicates an earlier one
+    fn convert(&mut self, xmltv_program: SimpleXmltvProgram) -> AppResult<Option<EpgProgram>> {
+        // Timezone is taken from the first programme's start offset
+        if self.seen_programs.is_empty() {
+            match detect_timezone_from_xmltv(&xmltv_program.start) {
+                Some(tz) => log_timezone_detection(&self.source_name, Some(tz.as_str()), &tz),
+                None => log_timezone_detection(&self.source_name, None, "UTC"),
             }
-            seen_programs.insert(dedup_key);
-            // Parse start and stop times
-            let start_time =
-                chrono::DateTime::parse_from_str(&xmltv_program.start, "%Y%m%d%H%M%S %z")
-                    .or_else(|_| {
-                        chrono::NaiveDateTime::parse_from_str(&xmltv_program.start, "%Y%m%d%H%M%S")
-                            .map(|dt| {
-                                dt.and_utc()
-                                    .with_timezone(&chrono::FixedOffset::east_opt(0).unwrap())
-                            })
-                    })
-                    .map_err(|e| {
-                        AppError::source_error(format!(
-                            "Failed to parse start time '{}': {}",
-                            xmltv_program.start, e
-                        ))
-                    })?;
-
-            let end_time = if let Some(ref stop) = xmltv_program.stop {
-                chrono::DateTime::parse_from_str(stop, "%Y%m%d%H%M%S %z")
-                    .or_else(|_| {
-                        chrono::NaiveDateTime::parse_from_str(stop, "%Y%m%d%H%M%S").map(|dt| {
-                            dt.and_utc()
-                                .with_timezone(&chrono::FixedOffset::east_opt(0).unwrap())
-                        })
-                    })
-                    .map_err(|e| {
-                        AppError::source_error(format!("Failed to parse stop time '{stop}': {e}"))
-                    })?
-            } else {
-                // If no stop time, estimate 30 minutes duration
-                start_time + chrono::Duration::minutes(30)
-            };
+        }
 
-            // Channel name will be resolved during generation stage from M3U channels
-            let channel_name = String::new();
-
-            let epg_program = EpgProgram {
-                id: uuid::Uuid::new_v4(),
-                source_id: source.id,
-                channel_id: xmltv_program.channel,
-                channel_name,
-                program_title: xmltv_program
-                    .title
-                    .unwrap_or_else(|| "Unknown Program".to_string()),
-                program_description: xmltv_program.description,
-                program_category: xmltv_program.category,
-                start_time: start_time.with_timezone(&chrono::Utc),
-                end_time: end_time.with_timezone(&chrono::Utc),
-                episode_num: None,
-                season_num: None,
-                rating: None,
-                language: xmltv_program.language,
-                subtitles: None,
-                aspect_ratio: None,
-                program_icon: xmltv_program.icon,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-            };
-            epg_programs.push(epg_program);
+        // Deduplicate on channel_id + start_time + program_title
+        let program_title = xmltv_program.title.as_deref().unwrap_or("Unknown Program");
+        let mut hasher = DefaultHasher::new();
+        (
+            xmltv_program.channel.as_str(),
+            xmltv_program.start.as_str(),
+            program_title,
+        )
+            .hash(&mut hasher);
+        if !self.seen_programs.insert(hasher.finish()) {
+            self.stats.duplicate_programs += 1;
+            debug!(
+                "Skipping duplicate program '{}' on channel '{}' at {}",
+                program_title, xmltv_program.channel, xmltv_program.start
+            );
+            return Ok(None);
         }
 
-        // Clean up deduplication set to free memory
-        drop(seen_programs);
+        // Parse start and stop times
+        let start_time = parse_xmltv_time(&xmltv_program.start).map_err(|e| {
+            AppError::source_error(format!(
+                "Failed to parse start time '{}': {}",
+                xmltv_program.start, e
+            ))
+        })?;
+
+        let end_time = match &xmltv_program.stop {
+            Some(stop) => Some(parse_xmltv_time(stop).map_err(|e| {
+                AppError::source_error(format!("Failed to parse stop time '{stop}': {e}"))
+            })?),
+            None => None,
+        };
+        // Without a stop time after the start, estimate the duration
+        let end_time = match end_time {
+            Some(end_time) if end_time > start_time => end_time,
+            _ => {
+                self.stats.defaulted_durations += 1;
+                start_time + DEFAULT_PROGRAM_DURATION
+            }
+        };
+
+        self.stats.programs += 1;
+        Ok(Some(EpgProgram {
+            id: uuid::Uuid::new_v4(),
+            source_id: self.source_id,
+            channel_id: xmltv_program.channel,
+            // Channel name will be resolved during generation stage from M3U channels
+            channel_name: String::new(),
+            program_title: xmltv_program
+                .title
+                .unwrap_or_else(|| "Unknown Program".to_string()),
+            program_description: xmltv_program.description,
+            program_category: xmltv_program.category,
+            start_time,
+            end_time,
+            episode_num: None,
+            season_num: None,
+            rating: None,
+            language: xmltv_program.language,
+            subtitles: None,
+            aspect_ratio: None,
+            program_icon: xmltv_program.icon,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }))
+    }
 
-        if duplicate_program_count > 0 {
+    fn log_summary(&self) {
+        if self.stats.duplicate_programs > 0 {
             info!(
                 "Removed {} duplicate program entries from XMLTV feed for source '{}'",
-                duplicate_program_count, source.name
+                self.stats.duplicate_programs, self.source_name
+            );
+        }
+        if self.stats.defaulted_durations > 0 {
+            info!(
+                "Gave {} programs without a valid stop time a {} minute duration for source '{}'",
+                self.stats.defaulted_durations,
+                DEFAULT_PROGRAM_DURATION.num_minutes(),
+                self.source_name
             );
         }
 
         info!(
             "Parsed XMLTV EPG for source '{}': {} programs",
-            source.name,
-            epg_programs.len()
+            self.source_name, self.stats.programs
         );
+    }
+}
 
-        Ok(epg_programs)
+/// Parse an XMLTV timestamp, treating timestamps without an offset as UTC
+pub(crate) fn parse_xmltv_time(value: &str) -> chrono::ParseResult<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_str(value, "%Y%m%d%H%M%S %z")
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(value, "%Y%m%d%H%M%S").map(|dt| dt.and_utc())
+        })
+}
+
+/// Counts bytes read from the underlying (possibly compressed) input
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+/// Incremental XMLTV ingestion from a plain or compressed byte stream
+///
+/// Programmes are parsed and converted in batches of a caller-chosen size, so memory
+/// use is bounded by the batch rather than the document. Reading is blocking; drive
+/// the stream from `spawn_blocking`.
+pub struct XmltvProgramStream {
+    reader: XmltvProgramReader<BufReader<Box<dyn Read + Send>>>,
+    converter: ProgramConverter,
+    bytes_read: Arc<AtomicU64>,
+    total_bytes: u64,
+    finished: bool,
+}
+
+impl XmltvProgramStream {
+    /// Open a stream over `input`, whose size (`total_bytes`) drives progress reporting
+    ///
+    /// Compressed input fails once it expands beyond `max_decompressed_bytes`.
+    pub fn new<R>(
+        source: &EpgSource,
+        input: R,
+        total_bytes: u64,
+        max_decompressed_bytes: u64,
+    ) -> AppResult<Self>
+    where
+        R: Read + Send + 'static,
+    {
+        let bytes_read = Arc::new(AtomicU64::new(0));
+        let counted = BufReader::new(CountingReader {
+            inner: input,
+            count: bytes_read.clone(),
+        });
+        let (decoder, compression_format) = DecompressionService::decompressing_reader(
+            counted,
+            Some(&source.url),
+            max_decompressed_bytes,
+        )
+        .map_err(|e| AppError::source_error(format!("Failed to read XMLTV content: {e:#}")))?;
+        debug!(
+            "Streaming XMLTV for source '{}' ({} bytes, compression: {:?})",
+            source.name, total_bytes, compression_format
+        );
+
+        Ok(Self {
+            reader: XmltvProgramReader::new(BufReader::new(decoder)),
+            converter: ProgramConverter::new(source),
+            bytes_read,
+            total_bytes,
+            finished: false,
+        })
+    }
+
+    /// Open a stream over an XMLTV file on disk
+    pub fn open_file(
+        source: &EpgSource,
+        path: &Path,
+        max_decompressed_bytes: u64,
+    ) -> AppResult<Self> {
+        let file = std::fs::File::open(path).map_err(|e| {
+            AppError::source_error(format!("Failed to open {}: {e}", path.display()))
+        })?;
+        let total_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Self::new(source, file, total_bytes, max_decompressed_bytes)
+    }
+
+    /// Parse up to `max_programs` further programmes; an empty batch marks the end
+    pub fn next_batch(&mut self, max_programs: usize) -> AppResult<Vec<EpgProgram>> {
+        let mut batch = Vec::with_capacity(max_programs.min(1024));
+        while !self.finished && batch.len() < max_programs {
+            let Some(xmltv_program) = self.reader.next_program()? else {
+                self.finished = true;
+                self.converter.log_summary();
+                break;
+            };
+            if let Some(program) = self.converter.convert(xmltv_program)? {
+                batch.push(program);
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Input bytes consumed so far (compressed size for compressed input)
+    pub fn bytes_consumed(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Programme counts so far
+    pub fn stats(&self) -> XmltvParseStats {
+        self.converter.stats
+    }
+
+    /// Channels with language-tagged display names or icons read so far
+    pub fn take_multilingual_channels(&mut self) -> Vec<EpgChannelMetadata> {
+        let source_id = self.converter.source_id;
+        self.reader
+            .take_channels()
+            .into_iter()
+            .map(|channel| channel_metadata(source_id, channel))
+            .filter(EpgChannelMetadata::is_multilingual)
+            .collect()
+    }
+}
+
+fn channel_metadata(source_id: uuid::Uuid, channel: SimpleXmltvChannel) -> EpgChannelMetadata {
+    let localized = |values: Vec<(String, Option<String>)>| {
+        values
+            .into_iter()
+            .map(|(value, lang)| LocalizedValue { value, lang })
+            .collect()
+    };
+    EpgChannelMetadata {
+        source_id,
+        channel_id: channel.id,
+        display_names: localized(channel.display_names),
+        icons: localized(channel.icons),
     }
 }
 
@@ -13,6 +13,7 @@ use tracing::{debug, info};
 
 use super::traits::*;
 use crate::errors::{AppError, AppResult, SourceError};
+use crate::models::xtream_category_filter::CompiledCategoryFilter;
 use crate::models::{Channel, StreamSource, StreamSourceType};
 use crate::utils::{
     DecompressingHttpClient, HttpClientFactory, StandardHttpClient, generate_channel_uuid,
@@ -171,6 +172,128 @@ impl XtreamSourceHandler {
         Ok(channels)
     }
 
+    /// Get live TV categories from Xtream server as (category id, category name) pairs
+    pub async fn get_live_categories(
+        &self,
+        source: &StreamSource,
+    ) -> AppResult<Vec<(i32, String)>> {
+        let base_url = self.get_api_base_url(source)?;
+        let auth_params = self.get_auth_params(source)?;
+
+        let mut url = reqwest::Url::parse(&base_url)
+            .map_err(|e| AppError::validation(format!("Invalid Xtream URL: {e}")))?;
+
+        for (key, value) in &auth_params {
+            url.query_pairs_mut().append_pair(key, value);
+        }
+        url.query_pairs_mut()
+            .append_pair("action", "get_live_categories");
+
+        debug!(
+            "Fetching live categories from Xtream source: {}",
+            source.name
+        );
+
+        let categories: Vec<XtreamCategoryEntry> =
+            self.http_client.fetch_json(url.as_str()).await?;
+
+        Ok(categories
+            .into_iter()
+            .map(|c| (c.category_id, c.category_name))
+            .collect())
+    }
+
+    /// Ingest live channels, skipping those whose category the filter rejects
+    ///
+    /// Category names are looked up from the provider when the filter matches on names,
+    /// since many providers leave `category_name` out of the stream listing.
+    pub async fn ingest_channels_filtered(
+        &self,
+        source: &StreamSource,
+        filter: &CompiledCategoryFilter,
+    ) -> AppResult<Vec<Channel>> {
+        info!(
+            "Starting Xtream channel ingestion for source: {}",
+            source.name
+        );
+
+        // Get live channels (authentication errors will be handled by this call)
+        let xtream_channels = self.get_live_channels(source).await?;
+
+        let category_names: HashMap<i32, String> = if filter.uses_names() {
+            self.get_live_categories(source)
+                .await?
+                .into_iter()
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        // Convert to internal format with deduplication
+        let mut channels = Vec::new();
+        let mut seen_channels = std::collections::HashSet::new();
+        let mut duplicate_count = 0;
+        let mut filtered_count = 0;
+
+        for xtream_channel in &xtream_channels {
+            if filter.is_active() {
+                let category_name = xtream_channel.category_name.as_deref().or_else(|| {
+                    xtream_channel
+                        .category_id
+                        .and_then(|id| category_names.get(&id))
+                        .map(String::as_str)
+                });
+                if !filter.allows(xtream_channel.category_id, category_name) {
+                    filtered_count += 1;
+                    continue;
+                }
+            }
+
+            let stream_url =
+                self.generate_xtream_stream_url(source, &xtream_channel.stream_id.to_string());
+
+            // Create deduplication key based on stream URL and channel name
+            let dedup_key = format!("{}|{}", stream_url, xtream_channel.name);
+
+            if seen_channels.contains(&dedup_key) {
+                duplicate_count += 1;
+                debug!(
+                    "Skipping duplicate Xtream channel '{}' with stream_id {}",
+                    xtream_channel.name, xtream_channel.stream_id
+                );
+                continue;
+            }
+            seen_channels.insert(dedup_key);
+
+            let channel = self.convert_xtream_channel(xtream_channel, source);
+            channels.push(channel);
+        }
+
+        // Clean up deduplication set to free memory
+        drop(seen_channels);
+
+        if filtered_count > 0 {
+            info!(
+                "Skipped {} channels outside the category filter of Xtream source '{}'",
+                filtered_count, source.name
+            );
+        }
+
+        if duplicate_count > 0 {
+            info!(
+                "Removed {} duplicate channel entries from Xtream source '{}'",
+                duplicate_count, source.name
+            );
+        }
+
+        info!(
+            "Successfully ingested {} channels from Xtream source: {}",
+            channels.len(),
+            source.name
+        );
+        Ok(channels)
+    }
+
     /// Convert Xtream channel to internal Channel model
     fn convert_xtream_channel(
         &self,
@@ -197,6 +320,7 @@ impl XtreamSourceHandler {
             tvg_chno: tvg_chno_value,
             tvg_logo: xtream_channel.stream_icon.clone(),
             tvg_shift: None,
+            epg_shift: None,
             group_title: xtream_channel.category_name.clone(),
             channel_name: xtream_channel.name.clone(),
             stream_url,
@@ -207,6 +331,7 @@ impl XtreamSourceHandler {
             resolution: None,
             probe_method: None,
             last_probed_at: None,
+            extra_attributes: Default::default(),
         }
     }
 
@@ -322,6 +447,15 @@ struct XtreamChannel {
     pub direct_source: Option<String>,
 }
 
+/// Xtream live category information
+#[derive(Debug, Clone, Deserialize)]
+struct XtreamCategoryEntry {
+    #[serde(deserialize_with = "deserialize_string_or_int")]
+    pub category_id: i32,
+    #[serde(default)]
+    pub category_name: String,
+}
+
 // Helper functions for deserialization
 fn default_stream_type() -> String {
     "live".to_string()
@@ -580,57 +714,8 @@ impl SourceHandler for XtreamSourceHandler {
 #[async_trait]
 impl ChannelIngestor for XtreamSourceHandler {
     async fn ingest_channels(&self, source: &StreamSource) -> AppResult<Vec<Channel>> {
-        // Directly ingest channels without progress callbacks
-        info!(
-            "Starting Xtream channel ingestion for source: {}",
-            source.name
-        );
-
-        // Get live channels (authentication errors will be handled by this call)
-        let xtream_channels = self.get_live_channels(source).await?;
-
-        // Convert to internal format with deduplication
-        let mut channels = Vec::new();
-        let mut seen_channels = std::collections::HashSet::new();
-        let mut duplicate_count = 0;
-
-        for xtream_channel in &xtream_channels {
-            let stream_url =
-                self.generate_xtream_stream_url(source, &xtream_channel.stream_id.to_string());
-
-            // Create deduplication key based on stream URL and channel name
-            let dedup_key = format!("{}|{}", stream_url, xtream_channel.name);
-
-            if seen_channels.contains(&dedup_key) {
-                duplicate_count += 1;
-                debug!(
-                    "Skipping duplicate Xtream channel '{}' with stream_id {}",
-                    xtream_channel.name, xtream_channel.stream_id
-                );
-                continue;
-            }
-            seen_channels.insert(dedup_key);
-
-            let channel = self.convert_xtream_channel(xtream_channel, source);
-            channels.push(channel);
-        }
-
-        // Clean up deduplication set to free memory
-        drop(seen_channels);
-
-        if duplicate_count > 0 {
-            info!(
-                "Removed {} duplicate channel entries from Xtream source '{}'",
-                duplicate_count, source.name
-            );
-        }
-
-        info!(
-            "Successfully ingested {} channels from Xtream source: {}",
-            channels.len(),
-            source.name
-        );
-        Ok(channels)
+        self.ingest_channels_filtered(source, &CompiledCategoryFilter::default())
+            .await
     }
 
     async fn estimate_channel_count(&self, _source: &StreamSource) -> AppResult<Option<u32>> {
@@ -0,0 +1,420 @@
+//! Per-proxy client IP and country access rules
+//!
+//! Rules are stored per proxy (see [`ProxyAccessRules`]) and compiled into an in-memory
+//! policy, which is reloaded whenever they change through the API. A client is denied when it matches a deny CIDR or deny country; otherwise, if the proxy has any
+//! allow rule, it must match one of them. Country lookups use a MaxMind database when the
+//! crate is built with the `geoip` feature.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::RwLock;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::AccessControlConfig;
+use crate::database::repositories::ProxySettingsSeaOrmRepository;
+use crate::models::proxy_settings::ProxyAccessRules;
+
+/// An IPv4 or IPv6 network in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CidrRange {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl CidrRange {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_matches(u32::from(network), u32::from(ip), self.prefix_len, 32)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(network), u128::from(ip), self.prefix_len, 128)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches<T>(network: T, ip: T, prefix_len: u8, bits: u8) -> bool
+where
+    T: Copy + PartialEq + std::ops::Shr<u32, Output = T>,
+{
+    if prefix_len == 0 {
+        return true;
+    }
+    let shift = u32::from(bits - prefix_len);
+    network >> shift == ip >> shift
+}
+
+impl FromStr for CidrRange {
+    type Err = String;
+
+    /// Parse `addr/len`, or a bare address as a single-host range
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let network = IpAddr::from_str(addr)
+            .map_err(|_| format!("invalid address in CIDR '{s}'"))?
+            .to_canonical();
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("invalid prefix length in CIDR '{s}'"))?,
+            None => max_len,
+        };
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+/// Outcome of checking a client against a proxy's rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessDecision {
+    Allow(AccessReason),
+    Deny(AccessReason),
+}
+
+impl AccessDecision {
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allow(_))
+    }
+
+    pub fn reason(&self) -> AccessReason {
+        match self {
+            Self::Allow(reason) | Self::Deny(reason) => *reason,
+        }
+    }
+}
+
+/// Which rule produced a decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessReason {
+    DenyCidr,
+    DenyCountry,
+    AllowCidr,
+    AllowCountry,
+    /// The proxy has allow rules and none matched
+    NotAllowed,
+    /// The proxy has only deny rules and none matched
+    NoRuleMatched,
+}
+
+impl AccessReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DenyCidr => "deny_cidr",
+            Self::DenyCountry => "deny_country",
+            Self::AllowCidr => "allow_cidr",
+            Self::AllowCountry => "allow_country",
+            Self::NotAllowed => "not_allowed",
+            Self::NoRuleMatched => "no_rule_matched",
+        }
+    }
+}
+
+/// Compiled rules for one proxy
+#[derive(Debug, Clone, Default)]
+pub struct ProxyAccessPolicy {
+    allow_cidrs: Vec<CidrRange>,
+    deny_cidrs: Vec<CidrRange>,
+    allow_countries: Vec<String>,
+    deny_countries: Vec<String>,
+}
+
+impl ProxyAccessPolicy {
+    /// Compile a proxy's rules, rejecting unparseable CIDRs
+    pub fn compile(rules: &ProxyAccessRules) -> Result<Self, String> {
+        let parse_cidrs = |cidrs: &[String]| {
+            cidrs
+                .iter()
+                .map(|c| c.parse::<CidrRange>())
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let normalize = |codes: &[String]| {
+            codes
+                .iter()
+                .map(|c| c.trim().to_ascii_uppercase())
+                .filter(|c| !c.is_empty())
+                .collect::<Vec<_>>()
+        };
+        Ok(Self {
+            allow_cidrs: parse_cidrs(&rules.allow_cidrs)?,
+            deny_cidrs: parse_cidrs(&rules.deny_cidrs)?,
+            allow_countries: normalize(&rules.allow_countries),
+            deny_countries: normalize(&rules.deny_countries),
+        })
+    }
+
+    pub fn uses_countries(&self) -> bool {
+        !self.allow_countries.is_empty() || !self.deny_countries.is_empty()
+    }
+
+    /// Decide for a client; an unknown address or country matches no rule
+    pub fn evaluate(&self, ip: Option<IpAddr>, country: Option<&str>) -> AccessDecision {
+        let in_any =
+            |ranges: &[CidrRange]| ip.is_some_and(|ip| ranges.iter().any(|r| r.contains(ip)));
+        let country_in = |codes: &[String]| {
+            country.is_some_and(|country| codes.iter().any(|c| c.eq_ignore_ascii_case(country)))
+        };
+
+        if in_any(&self.deny_cidrs) {
+            return AccessDecision::Deny(AccessReason::DenyCidr);
+        }
+        if country_in(&self.deny_countries) {
+            return AccessDecision::Deny(AccessReason::DenyCountry);
+        }
+        if in_any(&self.allow_cidrs) {
+            return AccessDecision::Allow(AccessReason::AllowCidr);
+        }
+        if country_in(&self.allow_countries) {
+            return AccessDecision::Allow(AccessReason::AllowCountry);
+        }
+        if self.allow_cidrs.is_empty() && self.allow_countries.is_empty() {
+            AccessDecision::Allow(AccessReason::NoRuleMatched)
+        } else {
+            AccessDecision::Deny(AccessReason::NotAllowed)
+        }
+    }
+}
+
+/// Country lookup backed by a MaxMind database
+#[cfg(feature = "geoip")]
+pub struct GeoIpResolver {
+    reader: maxminddb::Reader<Vec<u8>>,
+}
+
+#[cfg(feature = "geoip")]
+impl GeoIpResolver {
+    pub fn open(path: &std::path::Path) -> anyhow::Result<Self> {
+        Ok(Self {
+            reader: maxminddb::Reader::open_readfile(path)?,
+        })
+    }
+
+    /// ISO country code for an address, if the database knows it
+    pub fn country(&self, ip: IpAddr) -> Option<String> {
+        self.reader
+            .lookup::<maxminddb::geoip2::Country>(ip)
+            .ok()
+            .flatten()
+            .and_then(|record| record.country)
+            .and_then(|country| country.iso_code)
+            .map(str::to_string)
+    }
+}
+
+/// Stand-in when built without the `geoip` feature; knows no countries
+#[cfg(not(feature = "geoip"))]
+pub struct GeoIpResolver;
+
+#[cfg(not(feature = "geoip"))]
+impl GeoIpResolver {
+    pub fn country(&self, _ip: IpAddr) -> Option<String> {
+        None
+    }
+}
+
+/// Access rules of all proxies
+#[derive(Default)]
+pub struct AccessPolicy {
+    proxies: RwLock<HashMap<Uuid, ProxyAccessPolicy>>,
+    geoip: Option<GeoIpResolver>,
+}
+
+impl AccessPolicy {
+    /// Policy without proxy rules, using the configured GeoIP database; see [`Self::reload`]
+    pub fn from_config(config: Option<&AccessControlConfig>) -> Self {
+        let default_config = AccessControlConfig::default();
+        let config = config.unwrap_or(&default_config);
+
+        Self {
+            proxies: RwLock::default(),
+            geoip: Self::open_geoip(config),
+        }
+    }
+
+    /// Replace the rules of all proxies with the stored ones
+    pub async fn reload(&self, repo: &ProxySettingsSeaOrmRepository) -> anyhow::Result<()> {
+        self.set_rules(repo.list::<ProxyAccessRules>().await?);
+        Ok(())
+    }
+
+    /// Replace the rules of all proxies, skipping (and logging) proxies with invalid rules
+    pub fn set_rules(&self, rules: Vec<(Uuid, ProxyAccessRules)>) {
+        let mut proxies = HashMap::new();
+        for (proxy_id, rules) in rules {
+            match ProxyAccessPolicy::compile(&rules) {
+                Ok(policy) => {
+                    proxies.insert(proxy_id, policy);
+                }
+                Err(e) => warn!("Ignoring access rules for proxy {}: {}", proxy_id, e),
+            }
+        }
+
+        let mut current = self.proxies.write().unwrap_or_else(|e| e.into_inner());
+        // Reloads repeat unchanged rules, so only log what changed
+        if self.geoip.is_none()
+            && proxies.values().any(ProxyAccessPolicy::uses_countries)
+            && !current.values().any(ProxyAccessPolicy::uses_countries)
+        {
+            warn!(
+                "Country access rules are configured but no GeoIP database is available; they will not match"
+            );
+        }
+        if proxies.len() != current.len() {
+            info!("Access rules active for {} proxies", proxies.len());
+        }
+        *current = proxies;
+    }
+
+    #[cfg(feature = "geoip")]
+    fn open_geoip(config: &AccessControlConfig) -> Option<GeoIpResolver> {
+        let path = config.geoip_database.as_ref()?;
+        match GeoIpResolver::open(path) {
+            Ok(resolver) => {
+                info!("Loaded GeoIP database {}", path.display());
+                Some(resolver)
+            }
+            Err(e) => {
+                warn!("Failed to open GeoIP database {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "geoip"))]
+    fn open_geoip(config: &AccessControlConfig) -> Option<GeoIpResolver> {
+        if config.geoip_database.is_some() {
+            warn!("geoip_database is set but this build lacks the `geoip` feature; ignoring it");
+        }
+        None
+    }
+
+    /// Decide for a client of a proxy; `None` when the proxy has no rules
+    pub fn check(
+        &self,
+        proxy_id: &Uuid,
+        ip: Option<IpAddr>,
+    ) -> Option<(AccessDecision, Option<String>)> {
+        let proxies = self.proxies.read().unwrap_or_else(|e| e.into_inner());
+        let policy = proxies.get(proxy_id)?;
+        let country = match (&self.geoip, ip) {
+            (Some(geoip), Some(ip)) if policy.uses_countries() => geoip.country(ip),
+            _ => None,
+        };
+        Some((policy.evaluate(ip, country.as_deref()), country))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::proxy_settings::ProxySetting;
+
+    fn ip(s: &str) -> Option<IpAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let lan: CidrRange = "192.168.0.0/16".parse().unwrap();
+        assert!(lan.contains("192.168.4.20".parse().unwrap()));
+        assert!(lan.contains("::ffff:192.168.1.1".parse().unwrap()));
+        assert!(!lan.contains("10.0.0.1".parse().unwrap()));
+        assert!(!lan.contains("2001:db8::1".parse().unwrap()));
+
+        let v6: CidrRange = "2001:db8::/32".parse().unwrap();
+        assert!(v6.contains("2001:db8:ffff::1".parse().unwrap()));
+        assert!(!v6.contains("2001:db9::1".parse().unwrap()));
+
+        let host: CidrRange = "10.1.2.3".parse().unwrap();
+        assert!(host.contains("10.1.2.3".parse().unwrap()));
+        assert!(!host.contains("10.1.2.4".parse().unwrap()));
+
+        let everything: CidrRange = "0.0.0.0/0".parse().unwrap();
+        assert!(everything.contains("8.8.8.8".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<CidrRange>().is_err());
+        assert!("not-an-ip/8".parse::<CidrRange>().is_err());
+    }
+
+    #[test]
+    fn test_deny_wins_and_allow_lists_restrict() {
+        let policy = ProxyAccessPolicy::compile(&ProxyAccessRules {
+            allow_cidrs: vec!["10.0.0.0/8".to_string()],
+            deny_cidrs: vec!["10.6.0.0/16".to_string()],
+            allow_countries: vec!["gb".to_string()],
+            deny_countries: vec![],
+        })
+        .unwrap();
+
+        assert_eq!(
+            policy.evaluate(ip("10.1.1.1"), None),
+            AccessDecision::Allow(AccessReason::AllowCidr)
+        );
+        assert_eq!(
+            policy.evaluate(ip("10.6.1.1"), None),
+            AccessDecision::Deny(AccessReason::DenyCidr)
+        );
+        assert_eq!(
+            policy.evaluate(ip("81.2.69.142"), Some("GB")),
+            AccessDecision::Allow(AccessReason::AllowCountry)
+        );
+        assert_eq!(
+            policy.evaluate(ip("8.8.8.8"), Some("US")),
+            AccessDecision::Deny(AccessReason::NotAllowed)
+        );
+        assert_eq!(
+            policy.evaluate(None, None),
+            AccessDecision::Deny(AccessReason::NotAllowed)
+        );
+
+        let deny_only = ProxyAccessPolicy::compile(&ProxyAccessRules {
+            deny_countries: vec!["US".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!deny_only.evaluate(ip("8.8.8.8"), Some("US")).is_allowed());
+        assert_eq!(
+            deny_only.evaluate(None, None),
+            AccessDecision::Allow(AccessReason::NoRuleMatched)
+        );
+    }
+
+    #[test]
+    fn test_set_rules_replaces_proxy_rules() {
+        let (open, restricted) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let deny = |cidr: &str| ProxyAccessRules {
+            deny_cidrs: vec![cidr.to_string()],
+            ..Default::default()
+        };
+
+        let policy = AccessPolicy::default();
+        policy.set_rules(vec![
+            (restricted, deny("203.0.113.0/24")),
+            (open, deny("bogus")),
+        ]);
+        assert!(policy.check(&open, ip("203.0.113.5")).is_none());
+        assert!(
+            !policy
+                .check(&restricted, ip("203.0.113.5"))
+                .unwrap()
+                .0
+                .is_allowed()
+        );
+        assert!(deny("bogus").validate().is_err());
+
+        policy.set_rules(Vec::new());
+        assert!(policy.check(&restricted, ip("203.0.113.5")).is_none());
+    }
+}
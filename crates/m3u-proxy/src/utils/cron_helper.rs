@@ -43,6 +43,22 @@ pub fn calculate_next_scheduled_time_validated(
     }
 }
 
+/// The next `count` run times of a cron expression after `after`
+///
+/// # Returns
+/// * `Ok(Vec<DateTime<Utc>>)` - Up to `count` run times (fewer when the schedule ends)
+/// * `Err(String)` - Invalid cron expression with error message
+pub fn upcoming_runs(
+    cron_expression: &str,
+    after: DateTime<Utc>,
+    count: usize,
+) -> Result<Vec<DateTime<Utc>>, String> {
+    match Schedule::from_str(cron_expression) {
+        Ok(schedule) => Ok(schedule.after(&after).take(count).collect()),
+        Err(e) => Err(format!("Invalid cron expression '{cron_expression}': {e}")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,4 +93,27 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid cron expression"));
     }
+
+    #[test]
+    fn test_upcoming_runs() {
+        let after = "2025-01-01T10:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let runs = upcoming_runs("0 0 */6 * * * *", after, 3).unwrap();
+        let runs: Vec<String> = runs.iter().map(|run| run.to_rfc3339()).collect();
+        assert_eq!(
+            runs,
+            [
+                "2025-01-01T12:00:00+00:00",
+                "2025-01-01T18:00:00+00:00",
+                "2025-01-02T00:00:00+00:00"
+            ]
+        );
+
+        // A schedule that ends yields fewer runs
+        assert!(
+            upcoming_runs("0 0 0 1 1 * 2025", after, 3)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(upcoming_runs("0 0 25 * * *", after, 3).is_err());
+    }
 }
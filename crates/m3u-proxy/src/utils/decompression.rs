@@ -1,17 +1,7 @@
-#[cfg(any(
-    feature = "compression-gzip",
-    feature = "compression-bzip2",
-    feature = "compression-xz"
-))]
-use anyhow::Context;
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use bytes::Bytes;
-#[cfg(any(
-    feature = "compression-gzip",
-    feature = "compression-bzip2",
-    feature = "compression-xz"
-))]
-use std::io::Read;
+use std::io::{BufRead, Read};
+use tracing::debug;
 
 // Conditional imports based on enabled features
 #[cfg(feature = "compression-gzip")]
@@ -23,6 +13,12 @@ use bzip2::read::BzDecoder;
 #[cfg(feature = "compression-xz")]
 use xz2::read::XzDecoder;
 
+#[cfg(feature = "compression-zstd")]
+use zstd::stream::read::Decoder as ZstdDecoder;
+
+/// Default cap on decompressed content (4 GiB), guarding against decompression bombs
+pub const DEFAULT_MAX_DECOMPRESSED_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
 /// Supported compression formats for M3U/XMLTV content
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompressionFormat {
@@ -32,6 +28,8 @@ pub enum CompressionFormat {
     Bzip2,
     #[cfg(feature = "compression-xz")]
     Xz,
+    #[cfg(feature = "compression-zstd")]
+    Zstd,
     Uncompressed,
 }
 
@@ -41,67 +39,183 @@ pub struct DecompressionService;
 impl DecompressionService {
     /// Detect compression format using magic bytes
     pub fn detect_compression_format(data: &[u8]) -> CompressionFormat {
-        if let Some(kind) = infer::get(data) {
-            match kind.mime_type() {
-                #[cfg(feature = "compression-gzip")]
-                "application/gzip" => CompressionFormat::Gzip,
-                #[cfg(feature = "compression-bzip2")]
-                "application/x-bzip2" => CompressionFormat::Bzip2,
-                #[cfg(feature = "compression-xz")]
-                "application/x-xz" => CompressionFormat::Xz,
-                _ => CompressionFormat::Uncompressed,
+        Self::sniff_compression(data)
+            .and_then(|name| Self::format_for(name).ok())
+            .unwrap_or(CompressionFormat::Uncompressed)
+    }
+
+    /// Resolve the format of downloaded content from its magic bytes, Content-Encoding
+    /// header and source URL extension
+    ///
+    /// Magic bytes are authoritative; the declared encoding and extension only explain
+    /// content that was already decoded in transit. Content compressed with a format
+    /// this build does not support is an error rather than being parsed as text.
+    pub fn resolve_format(
+        data: &[u8],
+        content_encoding: Option<&str>,
+        source_url: Option<&str>,
+    ) -> Result<CompressionFormat> {
+        let declared = content_encoding
+            .and_then(Self::compression_from_content_encoding)
+            .or_else(|| source_url.and_then(Self::compression_from_extension));
+
+        match Self::sniff_compression(data) {
+            Some(name) => Self::format_for(name),
+            None => {
+                if let Some(declared) = declared {
+                    debug!(
+                        "Content declared as {} but is not compressed; using as-is",
+                        declared
+                    );
+                }
+                Ok(CompressionFormat::Uncompressed)
             }
-        } else {
-            CompressionFormat::Uncompressed
         }
     }
 
     /// Decompress data based on detected format
     pub fn decompress(data: Bytes) -> Result<Vec<u8>> {
-        let format = Self::detect_compression_format(&data);
+        let format = Self::resolve_format(&data, None, None)?;
+        Self::decompress_with_limit(data, format, DEFAULT_MAX_DECOMPRESSED_BYTES)
+    }
 
-        match format {
-            #[cfg(feature = "compression-gzip")]
-            CompressionFormat::Gzip => Self::decompress_gzip(data),
-            #[cfg(feature = "compression-bzip2")]
-            CompressionFormat::Bzip2 => Self::decompress_bzip2(data),
-            #[cfg(feature = "compression-xz")]
-            CompressionFormat::Xz => Self::decompress_xz(data),
-            CompressionFormat::Uncompressed => Ok(data.to_vec()),
+    /// Decompress data in a known format, failing once the output exceeds `max_bytes`
+    pub fn decompress_with_limit(
+        data: Bytes,
+        format: CompressionFormat,
+        max_bytes: u64,
+    ) -> Result<Vec<u8>> {
+        if format == CompressionFormat::Uncompressed {
+            return Ok(data.to_vec());
         }
-    }
 
-    /// Decompress gzip data
-    #[cfg(feature = "compression-gzip")]
-    fn decompress_gzip(data: Bytes) -> Result<Vec<u8>> {
-        let mut decoder = GzDecoder::new(data.as_ref());
+        let mut decoder = SizeLimitedReader::new(Self::decoder(data.as_ref(), format)?, max_bytes);
         let mut decompressed = Vec::new();
         decoder
             .read_to_end(&mut decompressed)
-            .context("Failed to decompress gzip data")?;
+            .with_context(|| format!("Failed to decompress {format:?} data"))?;
         Ok(decompressed)
     }
 
-    /// Decompress bzip2 data
-    #[cfg(feature = "compression-bzip2")]
-    fn decompress_bzip2(data: Bytes) -> Result<Vec<u8>> {
-        let mut decoder = BzDecoder::new(data.as_ref());
-        let mut decompressed = Vec::new();
-        decoder
-            .read_to_end(&mut decompressed)
-            .context("Failed to decompress bzip2 data")?;
-        Ok(decompressed)
+    /// Wrap a reader so it yields decompressed content, detecting the format from
+    /// the leading bytes without consuming them
+    ///
+    /// Reads fail once the decompressed output exceeds `max_bytes`.
+    pub fn decompressing_reader<R>(
+        mut reader: R,
+        source_url: Option<&str>,
+        max_bytes: u64,
+    ) -> Result<(Box<dyn Read + Send>, CompressionFormat)>
+    where
+        R: BufRead + Send + 'static,
+    {
+        let format = Self::resolve_format(reader.fill_buf()?, None, source_url)?;
+        if format == CompressionFormat::Uncompressed {
+            return Ok((Box::new(reader), format));
+        }
+
+        let decoder = SizeLimitedReader::new(Self::decoder(reader, format)?, max_bytes);
+        Ok((Box::new(decoder), format))
     }
 
-    /// Decompress xz data
-    #[cfg(feature = "compression-xz")]
-    fn decompress_xz(data: Bytes) -> Result<Vec<u8>> {
-        let mut decoder = XzDecoder::new(data.as_ref());
-        let mut decompressed = Vec::new();
-        decoder
-            .read_to_end(&mut decompressed)
-            .context("Failed to decompress xz data")?;
-        Ok(decompressed)
+    fn decoder<'a, R>(reader: R, format: CompressionFormat) -> Result<Box<dyn Read + Send + 'a>>
+    where
+        R: BufRead + Send + 'a,
+    {
+        Ok(match format {
+            #[cfg(feature = "compression-gzip")]
+            CompressionFormat::Gzip => Box::new(GzDecoder::new(reader)),
+            #[cfg(feature = "compression-bzip2")]
+            CompressionFormat::Bzip2 => Box::new(BzDecoder::new(reader)),
+            #[cfg(feature = "compression-xz")]
+            CompressionFormat::Xz => Box::new(XzDecoder::new(reader)),
+            #[cfg(feature = "compression-zstd")]
+            CompressionFormat::Zstd => Box::new(
+                ZstdDecoder::with_buffer(reader).context("Failed to initialise zstd decoder")?,
+            ),
+            CompressionFormat::Uncompressed => Box::new(reader),
+        })
+    }
+
+    /// Compression named by the content's magic bytes, whether or not it is supported
+    fn sniff_compression(data: &[u8]) -> Option<&'static str> {
+        match infer::get(data)?.mime_type() {
+            "application/gzip" => Some("gzip"),
+            "application/x-bzip2" => Some("bzip2"),
+            "application/x-xz" => Some("xz"),
+            "application/zstd" => Some("zstd"),
+            _ => None,
+        }
+    }
+
+    fn compression_from_content_encoding(value: &str) -> Option<&'static str> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some("gzip"),
+            "zstd" => Some("zstd"),
+            _ => None,
+        }
+    }
+
+    fn compression_from_extension(source_url: &str) -> Option<&'static str> {
+        // Ignore any query string or fragment (e.g. `guide.xml.gz?token=...`)
+        let path = source_url.split(['?', '#']).next().unwrap_or_default();
+        let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "gz" | "gzip" => Some("gzip"),
+            "bz2" => Some("bzip2"),
+            "xz" => Some("xz"),
+            "zst" | "zstd" => Some("zstd"),
+            _ => None,
+        }
+    }
+
+    fn format_for(name: &str) -> Result<CompressionFormat> {
+        match name {
+            #[cfg(feature = "compression-gzip")]
+            "gzip" => Ok(CompressionFormat::Gzip),
+            #[cfg(feature = "compression-bzip2")]
+            "bzip2" => Ok(CompressionFormat::Bzip2),
+            #[cfg(feature = "compression-xz")]
+            "xz" => Ok(CompressionFormat::Xz),
+            #[cfg(feature = "compression-zstd")]
+            "zstd" => Ok(CompressionFormat::Zstd),
+            _ => bail!(
+                "Content is {name}-compressed but this build lacks the compression-{name} feature"
+            ),
+        }
+    }
+}
+
+/// Reader that fails once more than `max_bytes` have been read through it
+struct SizeLimitedReader<R> {
+    inner: R,
+    max_bytes: u64,
+    remaining: u64,
+}
+
+impl<R> SizeLimitedReader<R> {
+    fn new(inner: R, max_bytes: u64) -> Self {
+        Self {
+            inner,
+            max_bytes,
+            remaining: max_bytes,
+        }
+    }
+}
+
+impl<R: Read> Read for SizeLimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.remaining = self.remaining.checked_sub(read as u64).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "decompressed content exceeds the {} byte limit",
+                    self.max_bytes
+                ),
+            )
+        })?;
+        Ok(read)
     }
 }
 
@@ -115,6 +229,13 @@ mod tests {
     #[cfg(feature = "compression-gzip")]
     use flate2::write::GzEncoder;
 
+    #[cfg(feature = "compression-gzip")]
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
     #[test]
     fn test_detect_uncompressed() {
         let data = b"Hello, world!";
@@ -128,9 +249,7 @@ mod tests {
         let original_data = b"Hello, world!";
 
         // Compress data
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(original_data).unwrap();
-        let compressed = encoder.finish().unwrap();
+        let compressed = gzip(original_data);
 
         // Detect format
         let format = DecompressionService::detect_compression_format(&compressed);
@@ -141,6 +260,75 @@ mod tests {
         assert_eq!(decompressed, original_data);
     }
 
+    #[test]
+    #[cfg(feature = "compression-gzip")]
+    fn test_decompressing_reader_gzip() {
+        let compressed = gzip(b"<tv></tv>");
+
+        let (mut reader, format) = DecompressionService::decompressing_reader(
+            std::io::Cursor::new(compressed),
+            Some("http://example.com/guide.xml.gz"),
+            DEFAULT_MAX_DECOMPRESSED_BYTES,
+        )
+        .unwrap();
+        assert_eq!(format, CompressionFormat::Gzip);
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "<tv></tv>");
+    }
+
+    #[test]
+    #[cfg(feature = "compression-gzip")]
+    fn test_decompressed_size_limit() {
+        // 1 MiB of zeros compresses to about 1 KiB
+        let compressed = gzip(&vec![0u8; 1024 * 1024]);
+
+        let result = DecompressionService::decompress_with_limit(
+            Bytes::from(compressed.clone()),
+            CompressionFormat::Gzip,
+            64 * 1024,
+        );
+        assert!(result.is_err());
+
+        let (mut reader, _) = DecompressionService::decompressing_reader(
+            std::io::Cursor::new(compressed),
+            None,
+            64 * 1024,
+        )
+        .unwrap();
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "compression-zstd")]
+    fn test_detect_and_decompress_zstd() {
+        let compressed = zstd::encode_all(&b"#EXTM3U\n"[..], 3).unwrap();
+        let format = DecompressionService::resolve_format(&compressed, Some("zstd"), None).unwrap();
+        assert_eq!(format, CompressionFormat::Zstd);
+        let decompressed = DecompressionService::decompress(Bytes::from(compressed)).unwrap();
+        assert_eq!(decompressed, b"#EXTM3U\n");
+    }
+
+    #[test]
+    fn test_declared_compression_of_plain_content() {
+        // Providers often send Content-Encoding/extension for content already decoded in transit
+        let format = DecompressionService::resolve_format(
+            b"#EXTM3U\n",
+            Some("gzip"),
+            Some("http://example.com/list.m3u.gz?token=abc"),
+        )
+        .unwrap();
+        assert_eq!(format, CompressionFormat::Uncompressed);
+        assert_eq!(
+            DecompressionService::compression_from_extension("http://x/guide.xml.zst?u=1"),
+            Some("zstd")
+        );
+        assert_eq!(
+            DecompressionService::compression_from_extension("http://x/get.php?type=m3u"),
+            None
+        );
+    }
+
     #[test]
     fn test_decompress_uncompressed() {
         let data = b"Hello, world!";
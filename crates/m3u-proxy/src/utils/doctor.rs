@@ -0,0 +1,581 @@
+//! Installation diagnostics for the `m3u-proxy doctor` command
+//!
+//! Runs a series of independent checks (configuration, database, FFmpeg, storage and
+//! outbound HTTP) and collects them into a report that can be printed or attached to a
+//! support request. A failing check never aborts the run; later checks that depend on it
+//! are reported as skipped.
+
+use crate::config::Config;
+use crate::database::Database;
+use sandboxed_file_manager::SandboxedManager;
+use sea_orm::{ConnectionTrait, Statement};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Encoders the relay profiles rely on; missing ones are reported as warnings
+const EXPECTED_ENCODERS: &[&str] = &["libx264", "libx265", "aac", "libmp3lame"];
+
+/// Decoders needed to probe and relay typical IPTV streams
+const EXPECTED_DECODERS: &[&str] = &["h264", "hevc", "mpeg2video", "aac", "ac3", "mp2"];
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Skip => "SKIP",
+        }
+    }
+}
+
+/// A single diagnostic result
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub category: String,
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// Summary counts across all checks
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DoctorSummary {
+    pub passed: usize,
+    pub warnings: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// Full diagnostics report
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub version: String,
+    pub platform: String,
+    pub config_file: String,
+    pub checks: Vec<DoctorCheck>,
+    pub summary: DoctorSummary,
+}
+
+/// Options for a doctor run
+#[derive(Debug, Clone)]
+pub struct DoctorOptions {
+    pub config_file: String,
+    pub database_url: Option<String>,
+    /// URL used to test outbound HTTP connectivity (`None` skips the check)
+    pub http_probe_url: Option<String>,
+}
+
+impl DoctorReport {
+    fn new(config_file: &str) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            platform: format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS),
+            config_file: config_file.to_string(),
+            checks: Vec::new(),
+            summary: DoctorSummary::default(),
+        }
+    }
+
+    fn add(&mut self, category: &str, name: &str, status: CheckStatus, detail: impl Into<String>) {
+        match status {
+            CheckStatus::Pass => self.summary.passed += 1,
+            CheckStatus::Warn => self.summary.warnings += 1,
+            CheckStatus::Fail => self.summary.failed += 1,
+            CheckStatus::Skip => self.summary.skipped += 1,
+        }
+        self.checks.push(DoctorCheck {
+            category: category.to_string(),
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    /// Whether any check failed
+    pub fn has_failures(&self) -> bool {
+        self.summary.failed > 0
+    }
+
+    /// Print a plain-text report grouped by category
+    pub fn print_text(&self) {
+        println!("m3u-proxy doctor v{} ({})", self.version, self.platform);
+        println!("Config file: {}", self.config_file);
+
+        let mut current_category: Option<&str> = None;
+        for check in &self.checks {
+            if current_category != Some(check.category.as_str()) {
+                println!();
+                println!("[{}]", check.category);
+                current_category = Some(check.category.as_str());
+            }
+            println!(
+                "  {:<4}  {:<28} {}",
+                check.status.as_str(),
+                check.name,
+                check.detail
+            );
+        }
+
+        println!();
+        println!(
+            "Summary: {} passed, {} warnings, {} failed, {} skipped",
+            self.summary.passed, self.summary.warnings, self.summary.failed, self.summary.skipped
+        );
+    }
+}
+
+/// Run all diagnostics
+pub async fn run_doctor(options: &DoctorOptions) -> DoctorReport {
+    let mut report = DoctorReport::new(&options.config_file);
+
+    let config = check_config(&mut report, options);
+    let config_ok = config.is_some();
+    let config = config.unwrap_or_default();
+
+    check_database(&mut report, &config).await;
+    check_ffmpeg(&mut report, &config).await;
+    check_storage(&mut report, &config, config_ok).await;
+    check_http(&mut report, &config, options.http_probe_url.as_deref()).await;
+
+    report
+}
+
+fn check_config(report: &mut DoctorReport, options: &DoctorOptions) -> Option<Config> {
+    const CATEGORY: &str = "config";
+
+    if std::path::Path::new(&options.config_file).exists() {
+        report.add(CATEGORY, "config file", CheckStatus::Pass, "found");
+    } else {
+        report.add(
+            CATEGORY,
+            "config file",
+            CheckStatus::Warn,
+            "not found, using built-in defaults and M3U_PROXY_* environment variables",
+        );
+    }
+
+    let mut config = match Config::load_from_file(&options.config_file) {
+        Ok(config) => {
+            report.add(CATEGORY, "parse", CheckStatus::Pass, "configuration loaded");
+            config
+        }
+        Err(e) => {
+            report.add(CATEGORY, "parse", CheckStatus::Fail, format!("{e:#}"));
+            return None;
+        }
+    };
+    if let Some(url) = &options.database_url {
+        config.database.url = url.clone();
+    }
+
+    let durations = [
+        ("storage.m3u_retention", &config.storage.m3u_retention),
+        (
+            "storage.m3u_cleanup_interval",
+            &config.storage.m3u_cleanup_interval,
+        ),
+        ("storage.temp_retention", &config.storage.temp_retention),
+        (
+            "storage.temp_cleanup_interval",
+            &config.storage.temp_cleanup_interval,
+        ),
+        (
+            "storage.pipeline_retention",
+            &config.storage.pipeline_retention,
+        ),
+        (
+            "storage.pipeline_cleanup_interval",
+            &config.storage.pipeline_cleanup_interval,
+        ),
+    ];
+    let invalid: Vec<String> = durations
+        .iter()
+        .filter(|(_, value)| humantime::parse_duration(value).is_err())
+        .map(|(key, value)| format!("{key}='{value}'"))
+        .collect();
+    if invalid.is_empty() {
+        report.add(
+            CATEGORY,
+            "durations",
+            CheckStatus::Pass,
+            "all storage durations valid",
+        );
+    } else {
+        report.add(
+            CATEGORY,
+            "durations",
+            CheckStatus::Fail,
+            format!("invalid duration(s): {}", invalid.join(", ")),
+        );
+    }
+
+    Some(config)
+}
+
+async fn check_database(report: &mut DoctorReport, config: &Config) {
+    const CATEGORY: &str = "database";
+
+    let started = Instant::now();
+    let database = match Database::new(&config.database, &config.ingestion).await {
+        Ok(database) => database,
+        Err(e) => {
+            report.add(CATEGORY, "connect", CheckStatus::Fail, format!("{e:#}"));
+            report.add(
+                CATEGORY,
+                "migrations",
+                CheckStatus::Skip,
+                "database unavailable",
+            );
+            return;
+        }
+    };
+
+    match database
+        .connection
+        .execute(Statement::from_string(
+            database.backend(),
+            "SELECT 1".to_string(),
+        ))
+        .await
+    {
+        Ok(_) => report.add(
+            CATEGORY,
+            "connect",
+            CheckStatus::Pass,
+            format!(
+                "{} reachable in {}ms",
+                database.database_type.as_str(),
+                started.elapsed().as_millis()
+            ),
+        ),
+        Err(e) => {
+            report.add(CATEGORY, "connect", CheckStatus::Fail, e.to_string());
+            return;
+        }
+    }
+
+    let migrations = database.migration_status().await;
+    let applied = migrations.applied.len();
+    let pending = migrations.missing();
+
+    if pending.is_empty() {
+        report.add(
+            CATEGORY,
+            "migrations",
+            CheckStatus::Pass,
+            format!("{applied} applied, none pending"),
+        );
+    } else {
+        report.add(
+            CATEGORY,
+            "migrations",
+            CheckStatus::Warn,
+            format!(
+                "{} pending (applied on next start): {}",
+                pending.len(),
+                pending.join(", ")
+            ),
+        );
+    }
+}
+
+async fn check_ffmpeg(report: &mut DoctorReport, config: &Config) {
+    const CATEGORY: &str = "ffmpeg";
+
+    let relay = config.relay.clone().unwrap_or_default();
+
+    let ffmpeg_available = match command_version(&relay.ffmpeg_command).await {
+        Ok(version) => {
+            report.add(CATEGORY, "ffmpeg", CheckStatus::Pass, version);
+            true
+        }
+        Err(e) => {
+            report.add(
+                CATEGORY,
+                "ffmpeg",
+                CheckStatus::Fail,
+                format!("'{}': {e}", relay.ffmpeg_command),
+            );
+            false
+        }
+    };
+
+    match command_version(&relay.ffprobe_command).await {
+        Ok(version) => report.add(CATEGORY, "ffprobe", CheckStatus::Pass, version),
+        Err(e) => report.add(
+            CATEGORY,
+            "ffprobe",
+            CheckStatus::Fail,
+            format!("'{}': {e}", relay.ffprobe_command),
+        ),
+    }
+
+    if !ffmpeg_available {
+        report.add(
+            CATEGORY,
+            "encoders",
+            CheckStatus::Skip,
+            "ffmpeg unavailable",
+        );
+        report.add(
+            CATEGORY,
+            "decoders",
+            CheckStatus::Skip,
+            "ffmpeg unavailable",
+        );
+        return;
+    }
+
+    for (name, flag, expected) in [
+        ("encoders", "-encoders", EXPECTED_ENCODERS),
+        ("decoders", "-decoders", EXPECTED_DECODERS),
+    ] {
+        match command_output(&relay.ffmpeg_command, &["-hide_banner", flag]).await {
+            Ok(output) => {
+                let available = parse_codec_list(&output);
+                let missing: Vec<&str> = expected
+                    .iter()
+                    .copied()
+                    .filter(|codec| !available.iter().any(|a| a == codec))
+                    .collect();
+                if missing.is_empty() {
+                    report.add(
+                        CATEGORY,
+                        name,
+                        CheckStatus::Pass,
+                        format!("{} available", expected.join(", ")),
+                    );
+                } else {
+                    report.add(
+                        CATEGORY,
+                        name,
+                        CheckStatus::Warn,
+                        format!("missing: {}", missing.join(", ")),
+                    );
+                }
+            }
+            Err(e) => report.add(CATEGORY, name, CheckStatus::Fail, e),
+        }
+    }
+}
+
+async fn check_storage(report: &mut DoctorReport, config: &Config, config_ok: bool) {
+    const CATEGORY: &str = "storage";
+
+    if !config_ok {
+        report.add(
+            CATEGORY,
+            "paths",
+            CheckStatus::Skip,
+            "configuration could not be loaded",
+        );
+        return;
+    }
+
+    let mut paths: Vec<(&str, PathBuf)> = vec![
+        ("m3u_path", config.storage.m3u_path.clone()),
+        (
+            "uploaded_logo_path",
+            config.storage.uploaded_logo_path.clone(),
+        ),
+        ("cached_logo_path", config.storage.cached_logo_path.clone()),
+        (
+            "temp_path",
+            PathBuf::from(
+                config
+                    .storage
+                    .temp_path
+                    .clone()
+                    .unwrap_or_else(|| "./data/temp".to_string()),
+            ),
+        ),
+        ("pipeline_path", config.storage.pipeline_path.clone()),
+    ];
+    if let Some(import) = config.xmltv_import.as_ref().filter(|c| c.enabled) {
+        paths.push(("xmltv_import.import_path", import.import_path.clone()));
+    }
+
+    for (name, path) in paths {
+        match probe_write_access(&path).await {
+            Ok(()) => report.add(
+                CATEGORY,
+                name,
+                CheckStatus::Pass,
+                format!("{} writable", path.display()),
+            ),
+            Err(e) => report.add(
+                CATEGORY,
+                name,
+                CheckStatus::Fail,
+                format!("{}: {e}", path.display()),
+            ),
+        }
+    }
+}
+
+async fn check_http(report: &mut DoctorReport, config: &Config, probe_url: Option<&str>) {
+    const CATEGORY: &str = "network";
+
+    let Some(url) = probe_url else {
+        report.add(CATEGORY, "outbound http", CheckStatus::Skip, "disabled");
+        return;
+    };
+
+    let client = match reqwest::Client::builder()
+        .user_agent(&config.web.user_agent)
+        .timeout(Duration::from_secs(10))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            report.add(CATEGORY, "outbound http", CheckStatus::Fail, e.to_string());
+            return;
+        }
+    };
+
+    let started = Instant::now();
+    match client.get(url).send().await {
+        Ok(response) => report.add(
+            CATEGORY,
+            "outbound http",
+            CheckStatus::Pass,
+            format!(
+                "{url} -> {} in {}ms",
+                response.status(),
+                started.elapsed().as_millis()
+            ),
+        ),
+        Err(e) => report.add(
+            CATEGORY,
+            "outbound http",
+            CheckStatus::Fail,
+            format!("{url}: {e}"),
+        ),
+    }
+}
+
+/// Write, read back and remove a probe file inside a sandboxed directory
+async fn probe_write_access(path: &std::path::Path) -> Result<(), String> {
+    let manager = SandboxedManager::builder()
+        .base_directory(path)
+        .build()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let probe_name = format!(".doctor-{}", uuid::Uuid::new_v4());
+    let contents = b"m3u-proxy doctor write probe";
+    manager
+        .write(&probe_name, contents)
+        .await
+        .map_err(|e| format!("write failed: {e}"))?;
+    let read_back = manager
+        .read(&probe_name)
+        .await
+        .map_err(|e| format!("read failed: {e}"));
+    let removed = manager
+        .remove_file(&probe_name)
+        .await
+        .map_err(|e| format!("remove failed: {e}"));
+
+    if read_back? != contents {
+        return Err("read back different contents".to_string());
+    }
+    removed
+}
+
+/// First line of `<command> -version`, e.g. "ffmpeg version 6.1.1"
+async fn command_version(command: &str) -> Result<String, String> {
+    let output = command_output(command, &["-version"]).await?;
+    Ok(output
+        .lines()
+        .next()
+        .map(|line| {
+            line.split(" Copyright")
+                .next()
+                .unwrap_or(line)
+                .trim()
+                .to_string()
+        })
+        .unwrap_or_default())
+}
+
+async fn command_output(command: &str, args: &[&str]) -> Result<String, String> {
+    let output = tokio::time::timeout(
+        Duration::from_secs(10),
+        tokio::process::Command::new(command).args(args).output(),
+    )
+    .await
+    .map_err(|_| "timed out".to_string())?
+    .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(format!("exited with {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Extract codec names from `ffmpeg -encoders` / `-decoders` output
+///
+/// Codec lines look like ` V....D libx264   libx264 H.264 ...`; the header block above the
+/// `------` separator is ignored.
+fn parse_codec_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codec_list() {
+        let output = "Encoders:\n V..... = Video\n A..... = Audio\n ------\n V....D libx264              libx264 H.264 / AVC\n A....D aac                  AAC (Advanced Audio Coding)\n";
+        assert_eq!(parse_codec_list(output), vec!["libx264", "aac"]);
+    }
+
+    #[test]
+    fn test_report_summary_counts() {
+        let mut report = DoctorReport::new("config.toml");
+        report.add("config", "parse", CheckStatus::Pass, "ok");
+        report.add("database", "migrations", CheckStatus::Warn, "1 pending");
+        report.add("ffmpeg", "ffmpeg", CheckStatus::Fail, "not found");
+        report.add(
+            "ffmpeg",
+            "encoders",
+            CheckStatus::Skip,
+            "ffmpeg unavailable",
+        );
+
+        assert_eq!(report.summary.passed, 1);
+        assert_eq!(report.summary.warnings, 1);
+        assert_eq!(report.summary.failed, 1);
+        assert_eq!(report.summary.skipped, 1);
+        assert!(report.has_failures());
+    }
+
+    #[tokio::test]
+    async fn test_probe_write_access() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        probe_write_access(temp_dir.path()).await.unwrap();
+
+        let mut entries = tokio::fs::read_dir(temp_dir.path()).await.unwrap();
+        assert!(entries.next_entry().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,594 @@
+//! Synthetic playlist and guide fixtures for the `m3u-proxy generate-fixtures` command
+//!
+//! Writes an M3U playlist and a matching XMLTV guide of configurable size and shape so
+//! scale problems can be benchmarked and reproduced without real provider data. Output is
+//! fully determined by the options and the seed, apart from the guide dates, which start at
+//! midnight UTC of the current day so the guide is always current.
+//!
+//! A configurable share of entries is malformed in the ways real providers get wrong
+//! (missing URLs, unterminated attributes, bad timestamps, ...). Malformed programmes stay
+//! well-formed XML so the rest of the guide can still be parsed.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, Utc};
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const M3U_FILE_NAME: &str = "fixtures.m3u";
+const XMLTV_FILE_NAME: &str = "fixtures.xmltv";
+
+const REGIONS: &[&str] = &["UK", "US", "DE", "FR", "ES", "IT", "NL", "PL"];
+const GENRES: &[&str] = &[
+    "News",
+    "Sports",
+    "Movies",
+    "Kids",
+    "Music",
+    "Documentary",
+    "Entertainment",
+    "Lifestyle",
+];
+const BROADCASTERS: &[&str] = &[
+    "StreamCast",
+    "ViewMedia",
+    "AeroVision",
+    "GlobalStream",
+    "NationalNet",
+    "CinemaMax",
+];
+const QUALITIES: &[&str] = &["", "", " HD", " FHD", " SD", " 4K"];
+const TITLE_WORDS: &[&str] = &[
+    "Morning", "Live", "Tonight", "World", "Weekly", "Classic", "Inside", "Grand", "Final",
+    "Report", "Stories", "Journey", "Files", "Hour", "Show", "Special",
+];
+
+/// How channels are spread over their groups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupDistribution {
+    /// Every group is equally likely
+    Uniform,
+    /// Group sizes follow a Zipf-like curve: a few large groups and a long tail
+    Skewed,
+}
+
+impl FromStr for GroupDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "uniform" => Ok(Self::Uniform),
+            "skewed" => Ok(Self::Skewed),
+            other => Err(format!(
+                "unknown group distribution '{other}' (expected 'uniform' or 'skewed')"
+            )),
+        }
+    }
+}
+
+/// Size and shape of the generated fixtures
+#[derive(Debug, Clone)]
+pub struct FixtureOptions {
+    pub output_dir: PathBuf,
+    pub channels: usize,
+    pub groups: usize,
+    pub group_distribution: GroupDistribution,
+    /// Percentage of channels with guide data
+    pub epg_coverage_percent: f64,
+    pub epg_days: u32,
+    /// Average programmes per channel and day
+    pub programmes_per_day: u32,
+    /// Percentage of M3U entries and XMLTV programmes that are malformed
+    pub malformed_percent: f64,
+    pub seed: u64,
+    /// Base of the generated stream URLs
+    pub stream_base_url: String,
+}
+
+impl Default for FixtureOptions {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("fixtures"),
+            channels: 1000,
+            groups: 20,
+            group_distribution: GroupDistribution::Skewed,
+            epg_coverage_percent: 90.0,
+            epg_days: 2,
+            programmes_per_day: 24,
+            malformed_percent: 0.0,
+            seed: 1,
+            stream_base_url: "http://fixtures.invalid/live".to_string(),
+        }
+    }
+}
+
+impl FixtureOptions {
+    fn validate(&self) -> Result<()> {
+        if self.groups == 0 {
+            bail!("groups must be at least 1");
+        }
+        for (name, value) in [
+            ("epg coverage", self.epg_coverage_percent),
+            ("malformed", self.malformed_percent),
+        ] {
+            if !(0.0..=100.0).contains(&value) {
+                bail!("{name} percentage must be between 0 and 100, got {value}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What was written, with the malformations applied by kind
+#[derive(Debug, Clone, Serialize)]
+pub struct FixtureReport {
+    pub m3u_path: String,
+    pub xmltv_path: String,
+    pub seed: u64,
+    pub channels: usize,
+    pub groups: usize,
+    pub channels_with_epg: usize,
+    pub programmes: usize,
+    pub malformed_channels: BTreeMap<&'static str, usize>,
+    pub malformed_programmes: BTreeMap<&'static str, usize>,
+    pub m3u_bytes: u64,
+    pub xmltv_bytes: u64,
+}
+
+impl FixtureReport {
+    pub fn print_text(&self) {
+        println!("Fixtures written (seed {})", self.seed);
+        println!("  {} ({} KB)", self.m3u_path, self.m3u_bytes / 1024);
+        println!("  {} ({} KB)", self.xmltv_path, self.xmltv_bytes / 1024);
+        println!();
+        println!(
+            "Channels: {} in {} groups, {} with guide data",
+            self.channels, self.groups, self.channels_with_epg
+        );
+        println!("Programmes: {}", self.programmes);
+        print_malformed("Malformed channels", &self.malformed_channels);
+        print_malformed("Malformed programmes", &self.malformed_programmes);
+    }
+}
+
+fn print_malformed(label: &str, counts: &BTreeMap<&'static str, usize>) {
+    let total: usize = counts.values().sum();
+    println!("{label}: {total}");
+    for (kind, count) in counts {
+        println!("  {kind:<24} {count}");
+    }
+}
+
+/// A generated channel, shared by the playlist and the guide
+#[derive(Debug, Clone)]
+pub struct FixtureChannel {
+    pub tvg_id: String,
+    pub name: String,
+    pub group: String,
+    pub logo: Option<String>,
+    pub number: Option<u32>,
+    pub has_epg: bool,
+}
+
+/// Ways an M3U entry is broken
+#[derive(Debug, Clone, Copy)]
+enum M3uDefect {
+    MissingUrl,
+    MissingName,
+    UnterminatedAttribute,
+    InvalidUrl,
+    EmptyName,
+}
+
+impl M3uDefect {
+    const ALL: [Self; 5] = [
+        Self::MissingUrl,
+        Self::MissingName,
+        Self::UnterminatedAttribute,
+        Self::InvalidUrl,
+        Self::EmptyName,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::MissingUrl => "missing_url",
+            Self::MissingName => "missing_name",
+            Self::UnterminatedAttribute => "unterminated_attribute",
+            Self::InvalidUrl => "invalid_url",
+            Self::EmptyName => "empty_name",
+        }
+    }
+}
+
+/// Ways an XMLTV programme is broken
+#[derive(Debug, Clone, Copy)]
+enum ProgrammeDefect {
+    BadTimestamp,
+    StopBeforeStart,
+    MissingTitle,
+    UnknownChannel,
+}
+
+impl ProgrammeDefect {
+    const ALL: [Self; 4] = [
+        Self::BadTimestamp,
+        Self::StopBeforeStart,
+        Self::MissingTitle,
+        Self::UnknownChannel,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::BadTimestamp => "bad_timestamp",
+            Self::StopBeforeStart => "stop_before_start",
+            Self::MissingTitle => "missing_title",
+            Self::UnknownChannel => "unknown_channel",
+        }
+    }
+}
+
+/// Deterministic generator of fixture channels, playlists and guides
+pub struct FixtureGenerator {
+    options: FixtureOptions,
+    rng: StdRng,
+}
+
+impl FixtureGenerator {
+    pub fn new(options: FixtureOptions) -> Self {
+        let rng = StdRng::seed_from_u64(options.seed);
+        Self { options, rng }
+    }
+
+    /// Generate the channel list
+    pub fn channels(&mut self) -> Vec<FixtureChannel> {
+        let groups = group_names(self.options.groups);
+        let weights: Vec<f64> = (0..groups.len())
+            .map(|k| match self.options.group_distribution {
+                GroupDistribution::Uniform => 1.0,
+                GroupDistribution::Skewed => 1.0 / (k + 1) as f64,
+            })
+            .collect();
+        let group_index = WeightedIndex::new(&weights).expect("group weights are positive");
+
+        (0..self.options.channels)
+            .map(|i| {
+                let group = &groups[group_index.sample(&mut self.rng)];
+                let broadcaster = BROADCASTERS.choose(&mut self.rng).copied().unwrap_or("");
+                let quality = QUALITIES.choose(&mut self.rng).copied().unwrap_or("");
+                let region = REGIONS[i % REGIONS.len()].to_ascii_lowercase();
+                FixtureChannel {
+                    tvg_id: format!("fixture{i}.{region}"),
+                    name: format!("{broadcaster} {group} {}{quality}", i + 1),
+                    group: group.clone(),
+                    logo: self
+                        .rng
+                        .random_bool(0.8)
+                        .then(|| format!("https://logos.fixtures.invalid/{i}.png")),
+                    number: self.rng.random_bool(0.5).then_some(i as u32 + 1),
+                    has_epg: self.chance(self.options.epg_coverage_percent),
+                }
+            })
+            .collect()
+    }
+
+    /// Write the playlist; returns malformed entries by kind
+    pub fn write_m3u<W: Write>(
+        &mut self,
+        channels: &[FixtureChannel],
+        mut out: W,
+    ) -> io::Result<BTreeMap<&'static str, usize>> {
+        let mut malformed = BTreeMap::new();
+        writeln!(out, "#EXTM3U")?;
+        for (i, channel) in channels.iter().enumerate() {
+            let defect = self
+                .chance(self.options.malformed_percent)
+                .then(|| *M3uDefect::ALL.choose(&mut self.rng).unwrap());
+            if let Some(defect) = defect {
+                *malformed.entry(defect.as_str()).or_insert(0) += 1;
+            }
+
+            let mut extinf = format!("#EXTINF:-1 tvg-id=\"{}\"", channel.tvg_id);
+            extinf.push_str(&format!(" tvg-name=\"{}\"", channel.name));
+            if let Some(logo) = &channel.logo {
+                extinf.push_str(&format!(" tvg-logo=\"{logo}\""));
+            }
+            if let Some(number) = channel.number {
+                extinf.push_str(&format!(" tvg-chno=\"{number}\""));
+            }
+            match defect {
+                Some(M3uDefect::UnterminatedAttribute) => {
+                    extinf.push_str(&format!(" group-title=\"{}", channel.group))
+                }
+                _ => extinf.push_str(&format!(" group-title=\"{}\"", channel.group)),
+            }
+            match defect {
+                Some(M3uDefect::MissingName) => {}
+                Some(M3uDefect::EmptyName) => extinf.push(','),
+                _ => {
+                    extinf.push(',');
+                    extinf.push_str(&channel.name);
+                }
+            }
+            writeln!(out, "{extinf}")?;
+
+            match defect {
+                Some(M3uDefect::MissingUrl) => {}
+                Some(M3uDefect::InvalidUrl) => writeln!(out, "not a url {i}")?,
+                _ => writeln!(out, "{}/{i}.ts", self.options.stream_base_url)?,
+            }
+        }
+        out.flush()?;
+        Ok(malformed)
+    }
+
+    /// Write the guide starting at `start`; returns the programme count and malformed
+    /// programmes by kind
+    pub fn write_xmltv<W: Write>(
+        &mut self,
+        channels: &[FixtureChannel],
+        start: DateTime<Utc>,
+        mut out: W,
+    ) -> io::Result<(usize, BTreeMap<&'static str, usize>)> {
+        let mut malformed = BTreeMap::new();
+        let mut programmes = 0;
+
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<tv generator-info-name="m3u-proxy generate-fixtures">"#
+        )?;
+        for channel in channels.iter().filter(|c| c.has_epg) {
+            writeln!(out, "  <channel id=\"{}\">", escape(&channel.tvg_id))?;
+            writeln!(
+                out,
+                "    <display-name>{}</display-name>",
+                escape(&channel.name)
+            )?;
+            if let Some(logo) = &channel.logo {
+                writeln!(out, "    <icon src=\"{}\"/>", escape(logo))?;
+            }
+            writeln!(out, "  </channel>")?;
+        }
+
+        let end = start + Duration::days(i64::from(self.options.epg_days));
+        let average_minutes = (1440 / self.options.programmes_per_day.max(1)).max(5) as i64;
+        for channel in channels.iter().filter(|c| c.has_epg) {
+            let mut programme_start = start;
+            while programme_start < end {
+                let minutes = self
+                    .rng
+                    .random_range(average_minutes / 2..=average_minutes * 3 / 2)
+                    .max(5);
+                let programme_end = (programme_start + Duration::minutes(minutes)).min(end);
+                let defect = self
+                    .chance(self.options.malformed_percent)
+                    .then(|| *ProgrammeDefect::ALL.choose(&mut self.rng).unwrap());
+                if let Some(defect) = defect {
+                    *malformed.entry(defect.as_str()).or_insert(0) += 1;
+                }
+                self.write_programme(&mut out, channel, programme_start, programme_end, defect)?;
+                programmes += 1;
+                programme_start = programme_end;
+            }
+        }
+
+        writeln!(out, "</tv>")?;
+        out.flush()?;
+        Ok((programmes, malformed))
+    }
+
+    fn write_programme<W: Write>(
+        &mut self,
+        out: &mut W,
+        channel: &FixtureChannel,
+        start: DateTime<Utc>,
+        stop: DateTime<Utc>,
+        defect: Option<ProgrammeDefect>,
+    ) -> io::Result<()> {
+        let (mut start_attr, mut stop_attr) = (xmltv_time(start), xmltv_time(stop));
+        let mut channel_id = escape(&channel.tvg_id);
+        match defect {
+            Some(ProgrammeDefect::BadTimestamp) => start_attr = start.to_rfc2822(),
+            Some(ProgrammeDefect::StopBeforeStart) => {
+                std::mem::swap(&mut start_attr, &mut stop_attr)
+            }
+            Some(ProgrammeDefect::UnknownChannel) => {
+                channel_id = format!("missing-{channel_id}");
+            }
+            _ => {}
+        }
+
+        writeln!(
+            out,
+            "  <programme start=\"{start_attr}\" stop=\"{stop_attr}\" channel=\"{channel_id}\">"
+        )?;
+        if !matches!(defect, Some(ProgrammeDefect::MissingTitle)) {
+            let title = format!(
+                "{} {}",
+                TITLE_WORDS.choose(&mut self.rng).unwrap(),
+                TITLE_WORDS.choose(&mut self.rng).unwrap()
+            );
+            writeln!(out, "    <title lang=\"en\">{title}</title>")?;
+        }
+        if self.rng.random_bool(0.7) {
+            writeln!(
+                out,
+                "    <desc lang=\"en\">Synthetic programme on {} &amp; friends.</desc>",
+                escape(&channel.name)
+            )?;
+        }
+        writeln!(out, "    <category>{}</category>", escape(&channel.group))?;
+        if self.rng.random_bool(0.3) {
+            writeln!(
+                out,
+                "    <episode-num system=\"xmltv_ns\">{}.{}.</episode-num>",
+                self.rng.random_range(0..10),
+                self.rng.random_range(0..24)
+            )?;
+        }
+        writeln!(out, "  </programme>")
+    }
+
+    fn chance(&mut self, percent: f64) -> bool {
+        percent > 0.0 && self.rng.random_bool((percent / 100.0).min(1.0))
+    }
+}
+
+/// Generate the playlist and guide into `options.output_dir`
+pub fn generate_fixtures(options: &FixtureOptions) -> Result<FixtureReport> {
+    options.validate()?;
+    std::fs::create_dir_all(&options.output_dir).with_context(|| {
+        format!(
+            "Failed to create output directory {}",
+            options.output_dir.display()
+        )
+    })?;
+    let m3u_path = options.output_dir.join(M3U_FILE_NAME);
+    let xmltv_path = options.output_dir.join(XMLTV_FILE_NAME);
+
+    let mut generator = FixtureGenerator::new(options.clone());
+    let channels = generator.channels();
+
+    let m3u_file = File::create(&m3u_path)
+        .with_context(|| format!("Failed to create {}", m3u_path.display()))?;
+    let malformed_channels = generator.write_m3u(&channels, BufWriter::new(m3u_file))?;
+
+    let start = Utc::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc();
+    let xmltv_file = File::create(&xmltv_path)
+        .with_context(|| format!("Failed to create {}", xmltv_path.display()))?;
+    let (programmes, malformed_programmes) =
+        generator.write_xmltv(&channels, start, BufWriter::new(xmltv_file))?;
+
+    Ok(FixtureReport {
+        m3u_bytes: std::fs::metadata(&m3u_path)?.len(),
+        xmltv_bytes: std::fs::metadata(&xmltv_path)?.len(),
+        m3u_path: m3u_path.display().to_string(),
+        xmltv_path: xmltv_path.display().to_string(),
+        seed: options.seed,
+        channels: channels.len(),
+        groups: options.groups,
+        channels_with_epg: channels.iter().filter(|c| c.has_epg).count(),
+        programmes,
+        malformed_channels,
+        malformed_programmes,
+    })
+}
+
+/// Region-prefixed genre names, numbered once the combinations run out
+fn group_names(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|k| {
+            let genre = GENRES[k % GENRES.len()];
+            let region = REGIONS[(k / GENRES.len()) % REGIONS.len()];
+            let round = k / (GENRES.len() * REGIONS.len());
+            if round == 0 {
+                format!("{region} {genre}")
+            } else {
+                format!("{region} {genre} {}", round + 1)
+            }
+        })
+        .collect()
+}
+
+fn xmltv_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%d%H%M%S +0000").to_string()
+}
+
+fn escape(value: &str) -> String {
+    quick_xml::escape::escape(value).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::xmltv_parser::parse_xmltv_programs;
+    use chrono::TimeZone;
+
+    fn options(channels: usize, malformed_percent: f64) -> FixtureOptions {
+        FixtureOptions {
+            channels,
+            groups: 5,
+            epg_coverage_percent: 100.0,
+            epg_days: 1,
+            programmes_per_day: 12,
+            malformed_percent,
+            ..Default::default()
+        }
+    }
+
+    fn render(options: FixtureOptions) -> (String, String, usize) {
+        let mut generator = FixtureGenerator::new(options);
+        let channels = generator.channels();
+        let mut m3u = Vec::new();
+        generator.write_m3u(&channels, &mut m3u).unwrap();
+        let mut xmltv = Vec::new();
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let (programmes, _) = generator.write_xmltv(&channels, start, &mut xmltv).unwrap();
+        (
+            String::from_utf8(m3u).unwrap(),
+            String::from_utf8(xmltv).unwrap(),
+            programmes,
+        )
+    }
+
+    #[test]
+    fn test_same_seed_produces_identical_output() {
+        assert_eq!(render(options(50, 10.0)), render(options(50, 10.0)));
+        let other_seed = FixtureOptions {
+            seed: 2,
+            ..options(50, 10.0)
+        };
+        assert_ne!(render(options(50, 10.0)).0, render(other_seed).0);
+    }
+
+    #[test]
+    fn test_clean_fixtures_are_complete() {
+        let (m3u, xmltv, programmes) = render(options(40, 0.0));
+        assert_eq!(m3u.lines().filter(|l| l.starts_with("#EXTINF")).count(), 40);
+        assert_eq!(m3u.lines().filter(|l| l.starts_with("http://")).count(), 40);
+
+        let parsed = parse_xmltv_programs(&xmltv).unwrap();
+        assert_eq!(parsed.len(), programmes);
+        assert!(parsed.iter().all(|p| p.title.is_some()));
+    }
+
+    #[test]
+    fn test_malformed_programmes_keep_the_guide_parseable() {
+        let (_, xmltv, programmes) = render(options(40, 100.0));
+        assert_eq!(parse_xmltv_programs(&xmltv).unwrap().len(), programmes);
+    }
+
+    #[test]
+    fn test_skewed_groups_favour_the_first_group() {
+        let mut generator = FixtureGenerator::new(FixtureOptions {
+            channels: 2000,
+            groups: 10,
+            ..Default::default()
+        });
+        let channels = generator.channels();
+        let count = |group: &str| channels.iter().filter(|c| c.group == group).count();
+        assert!(count("UK News") > count("US Sports") * 3);
+    }
+
+    #[test]
+    fn test_group_distribution_parses() {
+        assert_eq!(
+            "Uniform".parse::<GroupDistribution>(),
+            Ok(GroupDistribution::Uniform)
+        );
+        assert!("zipf".parse::<GroupDistribution>().is_err());
+    }
+}
@@ -0,0 +1,360 @@
+//! Reverse proxy forwarding headers
+//!
+//! Behind nginx or similar, the TCP peer is the proxy and the client's address and the
+//! external URL only reach us as `X-Forwarded-*` / `X-Real-IP` headers, and a user the proxy
+//! authenticated as `Remote-User` / `X-Forwarded-User`. Those headers are honoured only on
+//! connections from a trusted proxy address; anyone else could set them to spoof their
+//! address or identity, or redirect the URLs handed out in playlists.
+
+use axum::http::{HeaderMap, header};
+use std::net::IpAddr;
+use tracing::warn;
+
+use super::access_control::CidrRange;
+use crate::config::{AccessControlConfig, ReverseProxyConfig};
+
+/// Trusted proxies and which forwarding headers to honour from them
+#[derive(Debug, Clone, Default)]
+pub struct ForwardedHeaders {
+    trusted_proxies: Vec<CidrRange>,
+    forwarded_client_ip: bool,
+    forwarded_base_url: bool,
+}
+
+/// Client address and external base URL of a request, after forwarding headers
+///
+/// Inserted into request extensions by the forwarded headers middleware; requests that
+/// did not pass through it get the default, with no address and the configured base URL.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientAddress {
+    pub ip: Option<IpAddr>,
+    /// External base URL reported by a trusted proxy, without trailing slash
+    pub base_url: Option<String>,
+    /// User a trusted proxy authenticated (`Remote-User` / `X-Forwarded-User`)
+    pub remote_user: Option<String>,
+}
+
+impl ClientAddress {
+    /// Client address for logs and session tracking
+    pub fn ip_string(&self) -> String {
+        self.ip
+            .map_or_else(|| "unknown".to_string(), |ip| ip.to_string())
+    }
+
+    /// The forwarded base URL, or `configured` (`web.base_url`) when there is none
+    pub fn base_url<'a>(&'a self, configured: &'a str) -> &'a str {
+        self.base_url
+            .as_deref()
+            .unwrap_or_else(|| configured.trim_end_matches('/'))
+    }
+
+    /// Move URLs in generated content from the configured base URL to the forwarded one
+    pub fn rebase(&self, content: String, configured: &str) -> String {
+        let configured = configured.trim_end_matches('/');
+        match &self.base_url {
+            Some(base_url) if base_url != configured && !configured.is_empty() => {
+                content.replace(&format!("{configured}/"), &format!("{base_url}/"))
+            }
+            _ => content,
+        }
+    }
+}
+
+impl ForwardedHeaders {
+    /// Compile the trusted proxy list, skipping (and logging) invalid entries
+    pub fn from_config(
+        config: Option<&ReverseProxyConfig>,
+        access_control: Option<&AccessControlConfig>,
+    ) -> Self {
+        let default_config = ReverseProxyConfig::default();
+        let config = config.unwrap_or(&default_config);
+
+        let trusted_proxies = config
+            .trusted_proxies
+            .iter()
+            .filter_map(|cidr| match cidr.parse::<CidrRange>() {
+                Ok(range) => Some(range),
+                Err(e) => {
+                    warn!("Ignoring trusted proxy entry: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            trusted_proxies,
+            forwarded_client_ip: access_control.is_none_or(|c| c.trust_forwarded_headers),
+            forwarded_base_url: config.forwarded_base_url,
+        }
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|range| range.contains(ip))
+    }
+
+    /// Resolve a request from TCP peer `peer`
+    pub fn resolve(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> ClientAddress {
+        let Some(peer) = peer.map(|ip| ip.to_canonical()) else {
+            return ClientAddress::default();
+        };
+        if !self.is_trusted(peer) {
+            return ClientAddress {
+                ip: Some(peer),
+                ..Default::default()
+            };
+        }
+        ClientAddress {
+            ip: Some(if self.forwarded_client_ip {
+                self.forwarded_client_ip(headers, peer)
+            } else {
+                peer
+            }),
+            base_url: self
+                .forwarded_base_url
+                .then(|| forwarded_base_url(headers))
+                .flatten(),
+            remote_user: first_value(headers, "remote-user")
+                .or_else(|| first_value(headers, "x-forwarded-user"))
+                .map(str::to_string),
+        }
+    }
+
+    /// Walk `X-Forwarded-For` from the nearest hop back to the first untrusted address
+    ///
+    /// Entries left of the first untrusted hop were written by the client and prove nothing.
+    fn forwarded_client_ip(&self, headers: &HeaderMap, peer: IpAddr) -> IpAddr {
+        let hops: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+        if hops.is_empty() {
+            return first_value(headers, "x-real-ip")
+                .and_then(parse_hop)
+                .unwrap_or(peer);
+        }
+
+        let mut client = peer;
+        for hop in hops.iter().rev() {
+            let Some(ip) = parse_hop(hop) else {
+                break;
+            };
+            client = ip;
+            if !self.is_trusted(ip) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+/// Parse one forwarded address, as `1.2.3.4`, `1.2.3.4:5678`, `2001:db8::1` or `[2001:db8::1]:5678`
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    let hop = hop.trim();
+    if let Ok(ip) = hop.parse::<IpAddr>() {
+        return Some(ip.to_canonical());
+    }
+    if let Some(rest) = hop.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse::<IpAddr>().ok();
+    }
+    let (addr, _port) = hop.rsplit_once(':')?;
+    addr.parse::<std::net::Ipv4Addr>().ok().map(IpAddr::V4)
+}
+
+fn first_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// External base URL from `X-Forwarded-Proto` / `-Host` / `-Port` / `-Prefix`
+///
+/// `None` unless the proxy sent a protocol or host, so plain requests keep `web.base_url`.
+fn forwarded_base_url(headers: &HeaderMap) -> Option<String> {
+    let proto = first_value(headers, "x-forwarded-proto");
+    let forwarded_host = first_value(headers, "x-forwarded-host");
+    if proto.is_none() && forwarded_host.is_none() {
+        return None;
+    }
+
+    let proto = proto.unwrap_or("http").to_ascii_lowercase();
+    if proto != "http" && proto != "https" {
+        return None;
+    }
+    let mut host = forwarded_host
+        .or_else(|| {
+            headers
+                .get(header::HOST)
+                .and_then(|value| value.to_str().ok())
+        })
+        .filter(|host| is_valid_host(host))?
+        .to_string();
+
+    let has_port = host
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| !port.contains(']'));
+    if !has_port
+        && let Some(port) = first_value(headers, "x-forwarded-port")
+        && let Ok(port) = port.parse::<u16>()
+        && !matches!((proto.as_str(), port), ("http", 80) | ("https", 443))
+    {
+        host = format!("{host}:{port}");
+    }
+
+    let prefix = first_value(headers, "x-forwarded-prefix")
+        .filter(|prefix| is_valid_prefix(prefix))
+        .map(|prefix| prefix.trim_end_matches('/'))
+        .unwrap_or_default();
+
+    Some(format!("{proto}://{host}{prefix}"))
+}
+
+/// Host, optionally with port; rejects anything that could change the URL's meaning
+fn is_valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && host.len() <= 255
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'))
+}
+
+fn is_valid_prefix(prefix: &str) -> bool {
+    prefix.starts_with('/')
+        && !prefix.starts_with("//")
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '-' | '_' | '.' | '~'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn forwarded() -> ForwardedHeaders {
+        ForwardedHeaders::from_config(Some(&ReverseProxyConfig::default()), None)
+    }
+
+    #[test]
+    fn test_client_ip_only_from_trusted_proxies() {
+        let forwarded = forwarded();
+        let spoofed = headers(&[("x-forwarded-for", "203.0.113.9")]);
+
+        let direct = forwarded.resolve(&spoofed, Some("198.51.100.7".parse().unwrap()));
+        assert_eq!(direct.ip, Some("198.51.100.7".parse().unwrap()));
+
+        let proxied = forwarded.resolve(&spoofed, Some("127.0.0.1".parse().unwrap()));
+        assert_eq!(proxied.ip, Some("203.0.113.9".parse().unwrap()));
+
+        let real_ip = headers(&[("x-real-ip", "203.0.113.10")]);
+        let proxied = forwarded.resolve(&real_ip, Some("10.0.0.2".parse().unwrap()));
+        assert_eq!(proxied.ip, Some("203.0.113.10".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_client_ip_skips_client_supplied_hops() {
+        let forwarded = forwarded();
+        // The client sent a fake first entry; nginx appended the real address, then an
+        // internal load balancer appended nginx's
+        let chain = headers(&[("x-forwarded-for", "1.1.1.1, 203.0.113.9:51234, 10.0.0.5")]);
+        let resolved = forwarded.resolve(&chain, Some("10.0.0.6".parse().unwrap()));
+        assert_eq!(resolved.ip, Some("203.0.113.9".parse().unwrap()));
+
+        let disabled = ForwardedHeaders::from_config(
+            None,
+            Some(&AccessControlConfig {
+                trust_forwarded_headers: false,
+                ..Default::default()
+            }),
+        );
+        let resolved = disabled.resolve(&chain, Some("10.0.0.6".parse().unwrap()));
+        assert_eq!(resolved.ip, Some("10.0.0.6".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_forwarded_base_url() {
+        let forwarded = forwarded();
+        let peer = Some("127.0.0.1".parse().unwrap());
+
+        let resolved = forwarded.resolve(
+            &headers(&[
+                ("x-forwarded-proto", "https"),
+                ("x-forwarded-host", "tv.example.com"),
+                ("x-forwarded-port", "8443"),
+                ("x-forwarded-prefix", "/m3u/"),
+            ]),
+            peer,
+        );
+        assert_eq!(
+            resolved.base_url.as_deref(),
+            Some("https://tv.example.com:8443/m3u")
+        );
+
+        let resolved = forwarded.resolve(
+            &headers(&[("x-forwarded-proto", "https"), ("host", "tv.example.com")]),
+            peer,
+        );
+        assert_eq!(resolved.base_url.as_deref(), Some("https://tv.example.com"));
+
+        let resolved = forwarded.resolve(&headers(&[("host", "tv.example.com")]), peer);
+        assert_eq!(resolved.base_url, None);
+
+        let resolved =
+            forwarded.resolve(&headers(&[("x-forwarded-host", "evil.example/@x")]), peer);
+        assert_eq!(resolved.base_url, None);
+
+        let untrusted = forwarded.resolve(
+            &headers(&[("x-forwarded-host", "evil.example")]),
+            Some("198.51.100.7".parse().unwrap()),
+        );
+        assert_eq!(untrusted.base_url, None);
+    }
+
+    #[test]
+    fn test_remote_user_only_from_trusted_proxies() {
+        let forwarded = forwarded();
+        let user = headers(&[("remote-user", "alice")]);
+
+        let proxied = forwarded.resolve(&user, Some("127.0.0.1".parse().unwrap()));
+        assert_eq!(proxied.remote_user.as_deref(), Some("alice"));
+
+        let forwarded_user = headers(&[("x-forwarded-user", "bob")]);
+        let proxied = forwarded.resolve(&forwarded_user, Some("10.0.0.2".parse().unwrap()));
+        assert_eq!(proxied.remote_user.as_deref(), Some("bob"));
+
+        let direct = forwarded.resolve(&user, Some("198.51.100.7".parse().unwrap()));
+        assert_eq!(direct.remote_user, None);
+    }
+
+    #[test]
+    fn test_rebase() {
+        let client = ClientAddress {
+            base_url: Some("https://tv.example.com".to_string()),
+            ..Default::default()
+        };
+        let playlist = "#EXTM3U\n#EXTINF:-1,One\nhttp://localhost:8080/stream/abc/def\n";
+        assert_eq!(
+            client.rebase(playlist.to_string(), "http://localhost:8080/"),
+            "#EXTM3U\n#EXTINF:-1,One\nhttps://tv.example.com/stream/abc/def\n"
+        );
+        assert_eq!(
+            client.base_url("http://localhost:8080/"),
+            "https://tv.example.com"
+        );
+        assert_eq!(
+            ClientAddress::default().base_url("http://localhost:8080/"),
+            "http://localhost:8080"
+        );
+    }
+}
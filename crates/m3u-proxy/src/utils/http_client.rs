@@ -7,6 +7,7 @@ use tracing::debug;
 
 use crate::errors::{AppError, AppResult};
 use crate::services::CircuitBreakerManager;
+use crate::utils::decompression::DEFAULT_MAX_DECOMPRESSED_BYTES;
 use crate::utils::url::UrlUtils;
 use crate::utils::{CircuitBreaker, CompressionFormat, DecompressionService};
 
@@ -42,6 +43,9 @@ pub struct StandardHttpClient {
     client: Client,
     circuit_breaker: Option<Arc<crate::utils::ConcreteCircuitBreaker>>,
     acceptable_status_codes: Vec<String>,
+    max_decompressed_bytes: u64,
+    /// Largest response body accepted before decompression
+    max_download_bytes: Option<u64>,
 }
 
 impl StandardHttpClient {
@@ -51,6 +55,7 @@ impl StandardHttpClient {
         circuit_breaker: Option<Arc<crate::utils::ConcreteCircuitBreaker>>,
         user_agent: &str,
         acceptable_status_codes: Vec<String>,
+        max_decompressed_bytes: u64,
     ) -> Self {
         let client = Client::builder()
             .connect_timeout(connect_timeout)
@@ -62,9 +67,17 @@ impl StandardHttpClient {
             client,
             circuit_breaker,
             acceptable_status_codes,
+            max_decompressed_bytes,
+            max_download_bytes: None,
         }
     }
 
+    /// Reject response bodies larger than `max_download_bytes` (`None` = unlimited)
+    pub fn with_max_download_bytes(mut self, max_download_bytes: Option<u64>) -> Self {
+        self.max_download_bytes = max_download_bytes;
+        self
+    }
+
     /// Create new HTTP client with circuit breaker protection
     pub async fn with_circuit_breaker_manager(
         connect_timeout: Duration,
@@ -85,6 +98,8 @@ impl StandardHttpClient {
             client,
             circuit_breaker: Some(circuit_breaker),
             acceptable_status_codes: vec!["2xx".to_string(), "3xx".to_string()], // Default
+            max_decompressed_bytes: DEFAULT_MAX_DECOMPRESSED_BYTES,
+            max_download_bytes: None,
         })
     }
 
@@ -205,8 +220,146 @@ impl StandardHttpClient {
         }
     }
 
+    /// Send a GET request through the circuit breaker and return the response unread
+    ///
+    /// `headers` are added to the request and replace any default of the same name, such
+    /// as the User-Agent.
+    pub async fn get_response(
+        &self,
+        url: &str,
+        headers: reqwest::header::HeaderMap,
+    ) -> AppResult<Response> {
+        let request_fn = || async {
+            self.client
+                .get(url)
+                .headers(headers.clone())
+                .send()
+                .await
+                .map_err(|e| {
+                    let error_msg = e.to_string();
+                    let obfuscated_msg = UrlUtils::obfuscate_credentials(&error_msg);
+                    format!("HTTP request failed: {}", obfuscated_msg)
+                })
+        };
+
+        if let Some(circuit_breaker) = &self.circuit_breaker {
+            let cb_result = circuit_breaker.as_ref().execute(request_fn).await;
+            match cb_result.result {
+                Ok(response) => Ok(response),
+                Err(crate::utils::circuit_breaker::CircuitBreakerError::CircuitOpen) => {
+                    Err(AppError::ExternalService {
+                        service: "http_client".to_string(),
+                        message: "Circuit breaker is open - too many failures".to_string(),
+                    })
+                }
+                Err(crate::utils::circuit_breaker::CircuitBreakerError::Timeout) => {
+                    Err(AppError::ExternalService {
+                        service: "http_client".to_string(),
+                        message: "Request timed out".to_string(),
+                    })
+                }
+                Err(crate::utils::circuit_breaker::CircuitBreakerError::ServiceError(msg)) => {
+                    Err(AppError::ExternalService {
+                        service: "http_client".to_string(),
+                        message: msg,
+                    })
+                }
+            }
+        } else {
+            request_fn().await.map_err(|e| AppError::ExternalService {
+                service: "http_client".to_string(),
+                message: e,
+            })
+        }
+    }
+
+    /// Stream a response body to a file without buffering it in memory
+    ///
+    /// The body is written as received (no decompression); returns the bytes written.
+    pub async fn download_to_file(&self, url: &str, path: &std::path::Path) -> AppResult<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        debug!(
+            "Downloading content from {} to {}",
+            UrlUtils::obfuscate_credentials(url),
+            path.display()
+        );
+
+        let mut response = self
+            .get_response(url, reqwest::header::HeaderMap::new())
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::source_error(format!(
+                "HTTP error: {} {} - URL: {}",
+                response.status(),
+                response.status().canonical_reason().unwrap_or("Unknown"),
+                UrlUtils::obfuscate_credentials(url)
+            )));
+        }
+
+        let mut file = tokio::fs::File::create(path).await.map_err(|e| {
+            AppError::source_error(format!("Failed to create {}: {e}", path.display()))
+        })?;
+        let mut written = 0u64;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| AppError::source_error(format!("Failed to read response: {e}")))?
+        {
+            file.write_all(&chunk).await.map_err(|e| {
+                AppError::source_error(format!("Failed to write {}: {e}", path.display()))
+            })?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await.map_err(|e| {
+            AppError::source_error(format!("Failed to write {}: {e}", path.display()))
+        })?;
+
+        debug!("Downloaded {} bytes to {}", written, path.display());
+        Ok(written)
+    }
+
+    /// Largest size a compressed response may expand to
+    pub fn max_decompressed_bytes(&self) -> u64 {
+        self.max_decompressed_bytes
+    }
+
+    /// Read a response body, enforcing `max_download_bytes` while it arrives
+    async fn read_body(&self, mut response: Response, url: &str) -> AppResult<bytes::Bytes> {
+        let read_error =
+            |e: reqwest::Error| AppError::source_error(format!("Failed to read response: {e}"));
+        let Some(max_bytes) = self.max_download_bytes else {
+            return response.bytes().await.map_err(read_error);
+        };
+        let too_large = || {
+            AppError::from(crate::errors::SourceError::limit_exceeded(
+                "max_download_size",
+                format!(
+                    "{} is larger than {} bytes",
+                    UrlUtils::obfuscate_credentials(url),
+                    max_bytes
+                ),
+            ))
+        };
+        if response.content_length().is_some_and(|len| len > max_bytes) {
+            return Err(too_large());
+        }
+        let mut body = bytes::BytesMut::new();
+        while let Some(chunk) = response.chunk().await.map_err(read_error)? {
+            if body.len() as u64 + chunk.len() as u64 > max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body.freeze())
+    }
+
     /// Process response with automatic decompression
-    async fn process_response_to_bytes(response: Response, url: &str) -> AppResult<Vec<u8>> {
+    ///
+    /// The format is resolved from the body's magic bytes, the Content-Encoding header
+    /// and the URL extension; decompressed output is capped at `max_decompressed_bytes`.
+    async fn process_response_to_bytes(&self, response: Response, url: &str) -> AppResult<Vec<u8>> {
         if !response.status().is_success() {
             return Err(AppError::source_error(format!(
                 "HTTP error: {} {} - URL: {}",
@@ -216,16 +369,21 @@ impl StandardHttpClient {
             )));
         }
 
+        let content_encoding = response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
         // Get raw bytes to detect compression
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| AppError::source_error(format!("Failed to read response: {e}")))?;
+        let bytes = self.read_body(response, url).await?;
 
         debug!("Fetched {} bytes of raw content", bytes.len());
 
         // Detect compression format and decompress if needed
-        let compression_format = DecompressionService::detect_compression_format(&bytes);
+        let compression_format =
+            DecompressionService::resolve_format(&bytes, content_encoding.as_deref(), Some(url))
+                .map_err(|e| AppError::source_error(e.to_string()))?;
         debug!("Detected compression format: {:?}", compression_format);
 
         let decompressed_bytes = match compression_format {
@@ -235,8 +393,13 @@ impl StandardHttpClient {
             }
             _ => {
                 debug!("Content is compressed, decompressing...");
-                DecompressionService::decompress(bytes).map_err(|e| {
-                    AppError::source_error(format!("Failed to decompress content: {e}"))
+                DecompressionService::decompress_with_limit(
+                    bytes,
+                    compression_format,
+                    self.max_decompressed_bytes,
+                )
+                .map_err(|e| {
+                    AppError::source_error(format!("Failed to decompress content: {e:#}"))
                 })?
             }
         };
@@ -300,7 +463,7 @@ impl DecompressingHttpClient for StandardHttpClient {
             })?
         };
 
-        let decompressed_bytes = Self::process_response_to_bytes(response, url).await?;
+        let decompressed_bytes = self.process_response_to_bytes(response, url).await?;
 
         // Convert decompressed bytes to UTF-8 string
         let content = String::from_utf8(decompressed_bytes).map_err(|e| {
@@ -366,7 +529,7 @@ impl DecompressingHttpClient for StandardHttpClient {
             })?
         };
 
-        let decompressed_bytes = Self::process_response_to_bytes(response, url).await?;
+        let decompressed_bytes = self.process_response_to_bytes(response, url).await?;
 
         // Parse JSON from decompressed bytes
         let json_value = serde_json::from_slice(&decompressed_bytes)
@@ -428,7 +591,7 @@ impl DecompressingHttpClient for StandardHttpClient {
             })?
         };
 
-        let decompressed_bytes = Self::process_response_to_bytes(response, url).await?;
+        let decompressed_bytes = self.process_response_to_bytes(response, url).await?;
 
         debug!(
             "Successfully fetched {} bytes of binary content",
@@ -507,7 +670,7 @@ impl DecompressingHttpClient for StandardHttpClient {
             })?
         };
 
-        let decompressed_bytes = Self::process_response_to_bytes(response, url).await?;
+        let decompressed_bytes = self.process_response_to_bytes(response, url).await?;
 
         // Convert decompressed bytes to UTF-8 string
         let content = String::from_utf8(decompressed_bytes).map_err(|e| {
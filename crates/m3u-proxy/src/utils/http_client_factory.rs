@@ -6,6 +6,7 @@
 
 use crate::services::CircuitBreakerManager;
 use crate::utils::StandardHttpClient;
+use crate::utils::decompression::DEFAULT_MAX_DECOMPRESSED_BYTES;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -15,6 +16,7 @@ pub struct HttpClientFactory {
     circuit_breaker_manager: Option<Arc<CircuitBreakerManager>>,
     default_connect_timeout: Duration,
     user_agent: String,
+    max_decompressed_bytes: u64,
 }
 
 impl HttpClientFactory {
@@ -28,9 +30,21 @@ impl HttpClientFactory {
             circuit_breaker_manager,
             default_connect_timeout,
             user_agent: format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            max_decompressed_bytes: DEFAULT_MAX_DECOMPRESSED_BYTES,
         }
     }
 
+    /// Cap the decompressed size of compressed downloads made by created clients
+    pub fn with_max_decompressed_bytes(mut self, max_decompressed_bytes: u64) -> Self {
+        self.max_decompressed_bytes = max_decompressed_bytes;
+        self
+    }
+
+    /// Largest size a compressed download may expand to
+    pub fn max_decompressed_bytes(&self) -> u64 {
+        self.max_decompressed_bytes
+    }
+
     /// Create an HTTP client for a specific service
     ///
     /// The service name determines which circuit breaker profile to use:
@@ -58,6 +72,7 @@ impl HttpClientFactory {
                             Some(circuit_breaker),
                             &self.user_agent,
                             acceptable_status_codes,
+                            self.max_decompressed_bytes,
                         )
                     }
                     Err(e) => {
@@ -71,6 +86,7 @@ impl HttpClientFactory {
                             None,
                             &self.user_agent,
                             acceptable_status_codes,
+                            self.max_decompressed_bytes,
                         )
                     }
                 }
@@ -87,6 +103,7 @@ impl HttpClientFactory {
                     None,
                     &self.user_agent,
                     default_acceptable_codes,
+                    self.max_decompressed_bytes,
                 )
             }
         }
@@ -101,6 +118,7 @@ impl HttpClientFactory {
             None,
             &self.user_agent,
             default_acceptable_codes,
+            self.max_decompressed_bytes,
         )
     }
 
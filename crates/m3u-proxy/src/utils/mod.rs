@@ -3,6 +3,7 @@
 //! This module contains reusable utilities that can be used
 //! across different parts of the system.
 
+pub mod access_control;
 pub mod circuit_breaker;
 pub mod circuit_breaker_noop;
 pub mod circuit_breaker_simple;
@@ -12,6 +13,9 @@ pub mod database_retry;
 pub mod datetime;
 pub mod decompression;
 pub mod deterministic_uuid;
+pub mod doctor;
+pub mod fixture_generator;
+pub mod forwarded;
 pub mod http_client;
 pub mod http_client_factory;
 pub mod human_format;
@@ -20,10 +24,16 @@ pub mod log_capture;
 pub mod logo;
 pub mod memory_cleanup;
 pub mod memory_stats;
+pub mod output_profile;
+pub mod partial_update;
+pub mod password_hash;
 pub mod regex_preprocessor;
 pub mod sample_data;
 pub mod sandbox_health;
+pub mod stage_memory;
 pub mod status_code_matcher;
+pub mod stream_hints;
+pub mod stream_signing;
 pub mod system_manager;
 pub mod time;
 pub mod url;
@@ -37,7 +47,9 @@ pub use circuit_breaker::{
     create_circuit_breaker, create_circuit_breaker_for_service,
     create_circuit_breaker_from_profile,
 };
-pub use cron_helper::{calculate_next_scheduled_time, calculate_next_scheduled_time_validated};
+pub use cron_helper::{
+    calculate_next_scheduled_time, calculate_next_scheduled_time_validated, upcoming_runs,
+};
 pub use database_operations::DatabaseOperations;
 pub use database_retry::{RetryConfig, with_retry};
 pub use decompression::{CompressionFormat, DecompressionService};
@@ -53,9 +65,12 @@ pub use memory_cleanup::{
 };
 // Memory monitoring modules available for future pipeline integration
 // but not exposed to prevent accidental usage
-pub use regex_preprocessor::{RegexPreprocessor, RegexPreprocessorConfig};
+pub use regex_preprocessor::{RegexPrecheck, RegexPreprocessor, RegexPreprocessorConfig};
 pub use sample_data::{SampleChannel, SampleDataGenerator};
+pub use partial_update::deserialize_nullable_update;
+pub use stage_memory::{STAGE_MEMORY_SAMPLE_INTERVAL, StageMemorySampler, StageMemoryUsage};
 pub use status_code_matcher::is_status_acceptable;
+pub use stream_signing::{StreamTokenError, StreamUrlSigner};
 pub use system_manager::SystemManager;
 pub use url::UrlUtils;
 pub use uuid_parser::{deserialize_optional_uuid, resolve_proxy_id, uuid_to_base64, uuid_to_hex32};
@@ -0,0 +1,212 @@
+//! Client output profiles for served playlists
+//!
+//! Playlists are generated once in the standard form; a profile is applied when the
+//! playlist is served, so one proxy can feed different clients. The Kodi profile targets
+//! the PVR IPTV Simple Client: the header points Kodi at the proxy's XMLTV guide, every
+//! channel advertises `append` catchup, and nested group titles become Kodi multi-groups.
+
+use std::collections::HashMap;
+
+use crate::config::KodiPresetConfig;
+
+/// Catchup query parameters Kodi fills in (`{utc}` / `{lutc}` start and current time)
+const CATCHUP_PARAMS: [&str; 2] = ["utc", "lutc"];
+
+/// Rewrite a standard playlist for Kodi PVR IPTV Simple Client
+pub fn apply_kodi_profile(content: &str, xmltv_url: &str, config: &KodiPresetConfig) -> String {
+    let mut output = String::with_capacity(content.len() + content.len() / 4);
+    let mut pending_extinf: Option<String> = None;
+
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix("#EXTM3U") {
+            output.push_str(&format!(
+                "#EXTM3U x-tvg-url=\"{xmltv_url}\" url-tvg=\"{xmltv_url}\"{rest}\n"
+            ));
+        } else if line.starts_with("#EXTINF") {
+            pending_extinf = Some(kodi_groups(line, config));
+        } else if !line.starts_with('#')
+            && !line.trim().is_empty()
+            && let Some(extinf) = pending_extinf.take()
+        {
+            // Catchup attributes depend on the URL line, which follows its EXTINF
+            output.push_str(&with_catchup(&extinf, line, config.catchup_days));
+            output.push('\n');
+            output.push_str(line);
+            output.push('\n');
+        } else {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+    if let Some(extinf) = pending_extinf {
+        output.push_str(&extinf);
+        output.push('\n');
+    }
+    output
+}
+
+/// Upstream URL for a catchup request, carrying Kodi's `utc`/`lutc` parameters
+///
+/// Returns `None` for live requests (no numeric `utc` parameter).
+pub fn catchup_stream_url(stream_url: &str, query: &HashMap<String, String>) -> Option<String> {
+    let is_timestamp = |value: &String| value.parse::<i64>().is_ok();
+    query.get("utc").filter(|v| is_timestamp(v))?;
+
+    let mut url = url::Url::parse(stream_url).ok()?;
+    {
+        let mut pairs = url.query_pairs_mut();
+        for param in CATCHUP_PARAMS {
+            if let Some(value) = query.get(param).filter(|v| is_timestamp(v)) {
+                pairs.append_pair(param, value);
+            }
+        }
+    }
+    Some(url.into())
+}
+
+/// Byte offset of the comma separating EXTINF attributes from the channel name
+fn attributes_end(extinf: &str) -> Option<usize> {
+    let mut in_quotes = false;
+    for (index, c) in extinf.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn with_catchup(extinf: &str, stream_url: &str, catchup_days: u32) -> String {
+    if catchup_days == 0 {
+        return extinf.to_string();
+    }
+    let Some(end) = attributes_end(extinf) else {
+        return extinf.to_string();
+    };
+    // Catchup passed through from the source playlist takes precedence
+    if extinf[..end].contains(" catchup=\"") {
+        return extinf.to_string();
+    }
+    // Pipe-delimited player headers are not part of the URL
+    let url = stream_url.split('|').next().unwrap_or(stream_url);
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!(
+        "{} catchup=\"append\" catchup-days=\"{catchup_days}\" catchup-source=\"{separator}utc={{utc}}&lutc={{lutc}}\"{}",
+        &extinf[..end],
+        &extinf[end..]
+    )
+}
+
+fn kodi_groups(extinf: &str, config: &KodiPresetConfig) -> String {
+    const GROUP_ATTR: &str = " group-title=\"";
+
+    if let Some(start) = extinf.find(GROUP_ATTR) {
+        let value_start = start + GROUP_ATTR.len();
+        let Some(value_len) = extinf[value_start..].find('"') else {
+            return extinf.to_string();
+        };
+        let group = &extinf[value_start..value_start + value_len];
+        let Some(separator) = config.group_separator.as_deref().filter(|s| !s.is_empty()) else {
+            return extinf.to_string();
+        };
+        let groups = group
+            .split(separator)
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .collect::<Vec<_>>()
+            .join(";");
+        return format!(
+            "{}{groups}{}",
+            &extinf[..value_start],
+            &extinf[value_start + value_len..]
+        );
+    }
+
+    match (config.default_group.as_deref(), attributes_end(extinf)) {
+        (Some(default_group), Some(end)) if !default_group.is_empty() => format!(
+            "{}{GROUP_ATTR}{default_group}\"{}",
+            &extinf[..end],
+            &extinf[end..]
+        ),
+        _ => extinf.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XMLTV_URL: &str = "http://localhost:8080/proxy/abc/xmltv";
+
+    fn config(
+        catchup_days: u32,
+        separator: Option<&str>,
+        default_group: Option<&str>,
+    ) -> KodiPresetConfig {
+        KodiPresetConfig {
+            catchup_days,
+            group_separator: separator.map(str::to_string),
+            default_group: default_group.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_header_and_catchup_attributes() {
+        let playlist =
+            "#EXTM3U\n#EXTINF:-1 tvg-id=\"a.uk\" tvg-name=\"A, B\",A, B\nhttp://x/stream/p/c\n";
+        let output = apply_kodi_profile(playlist, XMLTV_URL, &config(7, None, None));
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            format!("#EXTM3U x-tvg-url=\"{XMLTV_URL}\" url-tvg=\"{XMLTV_URL}\"")
+        );
+        assert_eq!(
+            lines[1],
+            "#EXTINF:-1 tvg-id=\"a.uk\" tvg-name=\"A, B\" catchup=\"append\" catchup-days=\"7\" catchup-source=\"?utc={utc}&lutc={lutc}\",A, B"
+        );
+        assert_eq!(lines[2], "http://x/stream/p/c");
+    }
+
+    #[test]
+    fn test_catchup_source_follows_signed_urls() {
+        let playlist = "#EXTM3U\n#EXTINF:-1,A\nhttp://x/stream/p/c?token=t\n";
+        let output = apply_kodi_profile(playlist, XMLTV_URL, &config(3, None, None));
+        assert!(output.contains("catchup-source=\"&utc={utc}&lutc={lutc}\",A"));
+
+        let output = apply_kodi_profile(playlist, XMLTV_URL, &config(0, None, None));
+        assert!(!output.contains("catchup"));
+
+        let playlist = "#EXTM3U\n#EXTINF:-1 catchup=\"shift\",A\nhttp://x/stream/p/c\n";
+        let output = apply_kodi_profile(playlist, XMLTV_URL, &config(3, None, None));
+        assert!(output.contains("#EXTINF:-1 catchup=\"shift\",A\n"));
+    }
+
+    #[test]
+    fn test_group_conventions() {
+        let playlist = "#EXTM3U\n#EXTINF:-1 group-title=\"Sports | UK\",A\nhttp://x/1\n#EXTINF:-1,B\nhttp://x/2\n";
+        let output = apply_kodi_profile(
+            playlist,
+            XMLTV_URL,
+            &config(0, Some("|"), Some("Uncategorised")),
+        );
+        assert!(output.contains("group-title=\"Sports;UK\",A"));
+        assert!(output.contains("#EXTINF:-1 group-title=\"Uncategorised\",B"));
+    }
+
+    #[test]
+    fn test_catchup_stream_url() {
+        let mut query = HashMap::new();
+        assert!(catchup_stream_url("http://up/live/1.ts", &query).is_none());
+
+        query.insert("utc".to_string(), "1760000000".to_string());
+        query.insert("lutc".to_string(), "1760003600".to_string());
+        assert_eq!(
+            catchup_stream_url("http://up/live/1.ts?u=x", &query).as_deref(),
+            Some("http://up/live/1.ts?u=x&utc=1760000000&lutc=1760003600")
+        );
+
+        query.insert("utc".to_string(), "now".to_string());
+        assert!(catchup_stream_url("http://up/live/1.ts", &query).is_none());
+    }
+}
@@ -0,0 +1,40 @@
+//! Serde helpers for partial update requests
+//!
+//! Update requests leave fields the client did not send unchanged. Nullable fields need a
+//! third state to tell "not sent" apart from "cleared", which these helpers provide.
+
+use serde::{Deserialize, Deserializer};
+
+/// Serde helper for nullable fields of update requests
+///
+/// Use with `#[serde(default, deserialize_with = "deserialize_nullable_update")]` on an
+/// `Option<Option<T>>`: a missing field stays `None` (leave unchanged), `null` becomes
+/// `Some(None)` (clear) and a value becomes `Some(Some(value))`.
+pub fn deserialize_nullable_update<'de, D, T>(
+    deserializer: D,
+) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Update {
+        #[serde(default, deserialize_with = "deserialize_nullable_update")]
+        value: Option<Option<i32>>,
+    }
+
+    #[test]
+    fn test_deserialize_nullable_update() {
+        let parse = |json| serde_json::from_str::<Update>(json).unwrap().value;
+        assert_eq!(parse("{}"), None);
+        assert_eq!(parse(r#"{"value":null}"#), Some(None));
+        assert_eq!(parse(r#"{"value":5}"#), Some(Some(5)));
+    }
+}
@@ -0,0 +1,123 @@
+//! Salted password hashes for client credentials
+//!
+//! Passwords are stored as `pbkdf2-sha256$<iterations>$<salt>$<hash>` (salt and hash in
+//! base64), using PBKDF2 with HMAC-SHA256. Verification compares in constant time.
+
+use base64::{Engine as _, engine::general_purpose::STANDARD_NO_PAD};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SCHEME: &str = "pbkdf2-sha256";
+const ITERATIONS: u32 = 100_000;
+const HASH_LEN: usize = 32;
+
+/// Hash a password with a fresh random salt
+pub fn hash_password(password: &str) -> String {
+    let salt: [u8; 16] = rand::random();
+    encode(password, &salt, ITERATIONS)
+}
+
+/// Whether a password matches an encoded hash
+///
+/// Malformed hashes never match.
+pub fn verify_password(password: &str, encoded: &str) -> bool {
+    let mut parts = encoded.split('$');
+    let (Some(SCHEME), Some(iterations), Some(salt), Some(hash), None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return false;
+    };
+    let (Ok(iterations), Ok(salt), Ok(expected)) = (
+        iterations.parse::<u32>(),
+        STANDARD_NO_PAD.decode(salt),
+        STANDARD_NO_PAD.decode(hash),
+    ) else {
+        return false;
+    };
+    if iterations == 0 || expected.len() != HASH_LEN {
+        return false;
+    }
+
+    let actual = pbkdf2(password.as_bytes(), &salt, iterations);
+    actual
+        .iter()
+        .zip(&expected)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Random password suitable for typing into a set-top box
+pub fn generate_password() -> String {
+    const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+    let bytes: [u8; 20] = rand::random();
+    bytes
+        .iter()
+        .map(|b| ALPHABET[*b as usize % ALPHABET.len()] as char)
+        .collect()
+}
+
+fn encode(password: &str, salt: &[u8], iterations: u32) -> String {
+    let hash = pbkdf2(password.as_bytes(), salt, iterations);
+    format!(
+        "{SCHEME}${iterations}${}${}",
+        STANDARD_NO_PAD.encode(salt),
+        STANDARD_NO_PAD.encode(hash)
+    )
+}
+
+/// PBKDF2-HMAC-SHA256 producing a single 32-byte block
+fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32) -> [u8; HASH_LEN] {
+    let prf = HmacSha256::new_from_slice(password).expect("HMAC accepts keys of any length");
+
+    let mut block = [0u8; HASH_LEN];
+    let mut mac = prf.clone();
+    mac.update(salt);
+    mac.update(&1u32.to_be_bytes());
+    block.copy_from_slice(&mac.finalize().into_bytes());
+    let mut result = block;
+    for _ in 1..iterations {
+        let mut mac = prf.clone();
+        mac.update(&block);
+        block.copy_from_slice(&mac.finalize().into_bytes());
+        for (r, b) in result.iter_mut().zip(&block) {
+            *r ^= b;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pbkdf2_known_vector() {
+        // RFC 7914 section 11 test vector for PBKDF2-HMAC-SHA256
+        let hash = pbkdf2(b"passwd", b"salt", 1);
+        assert_eq!(
+            hex::encode(hash),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+    }
+
+    #[test]
+    fn test_hash_and_verify() {
+        let encoded = encode("secret", b"0123456789abcdef", 10);
+        assert!(verify_password("secret", &encoded));
+        assert!(!verify_password("Secret", &encoded));
+        assert!(!verify_password("secret", "plain-text"));
+        assert!(!verify_password("secret", "pbkdf2-sha256$0$AAAA$AAAA"));
+
+        // Fresh salts give distinct hashes of the same password
+        let hashed = hash_password("secret");
+        assert!(verify_password("secret", &hashed));
+        assert_ne!(hashed, encode("secret", b"0123456789abcdef", ITERATIONS));
+        assert_eq!(generate_password().len(), 20);
+    }
+}
@@ -1,4 +1,6 @@
-use tracing::trace;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{debug, trace};
 
 /// Information about a regex quantifier
 #[derive(Debug, Clone)]
@@ -30,6 +32,221 @@ impl Default for RegexPreprocessorConfig {
     }
 }
 
+/// First-pass check of one regex pattern, see [`RegexPreprocessor::should_run_regex`]
+#[derive(Debug, Clone)]
+pub struct RegexPrecheck {
+    enabled: bool,
+    literal_strings: Vec<String>,
+    special_chars: Vec<char>,
+}
+
+impl RegexPrecheck {
+    /// A precheck that never skips the regex
+    pub fn always() -> Self {
+        Self {
+            enabled: false,
+            literal_strings: Vec::new(),
+            special_chars: Vec::new(),
+        }
+    }
+
+    /// Whether the regex could match `field_value` and must be executed
+    pub fn may_match(&self, field_value: &str) -> bool {
+        if !self.enabled {
+            return true;
+        }
+
+        // The regex can only match if the field contains the literal special characters it's looking for
+        let special_chars_present = self.special_chars.iter().any(|&c| field_value.contains(c));
+
+        // Check if any significant literal strings are present in field value; without any
+        // (regex without literals is valid) the regex is executed
+        let literal_strings_present = self.literal_strings.is_empty()
+            || self.literal_strings.iter().any(|s| field_value.contains(s));
+
+        special_chars_present || literal_strings_present
+    }
+}
+
+/// Settings for [`PrecheckTuning`]
+#[derive(Debug, Clone)]
+pub struct PrecheckTuningConfig {
+    /// Evaluations sampled before deciding whether to keep the precheck
+    pub sample_size: u64,
+    /// Prechecks skipping fewer than this fraction of sampled evaluations are disabled
+    pub min_skip_rate: f64,
+}
+
+impl Default for PrecheckTuningConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: 1000,
+            min_skip_rate: 0.05,
+        }
+    }
+}
+
+const TUNING_SAMPLING: u8 = 0;
+const TUNING_ENABLED: u8 = 1;
+const TUNING_DISABLED: u8 = 2;
+
+/// Measures how well prechecks pay off for one rule and switches them off when they don't
+///
+/// The first `sample_size` evaluations time both the precheck and any regex it lets
+/// through. The precheck is then kept only if it skips at least `min_skip_rate` of
+/// evaluations and the regex time it saves outweighs its own cost; otherwise every
+/// later evaluation goes straight to the regex.
+#[derive(Debug)]
+pub struct PrecheckTuning {
+    config: PrecheckTuningConfig,
+    state: AtomicU8,
+    evaluations: AtomicU64,
+    skipped: AtomicU64,
+    sampled_precheck_nanos: AtomicU64,
+    sampled_regex_nanos: AtomicU64,
+    sampled_regex_runs: AtomicU64,
+}
+
+impl PrecheckTuning {
+    pub fn new(config: PrecheckTuningConfig) -> Self {
+        Self {
+            config,
+            state: AtomicU8::new(TUNING_SAMPLING),
+            evaluations: AtomicU64::new(0),
+            skipped: AtomicU64::new(0),
+            sampled_precheck_nanos: AtomicU64::new(0),
+            sampled_regex_nanos: AtomicU64::new(0),
+            sampled_regex_runs: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether the regex must run, consulting `precheck` unless tuning disabled it
+    pub fn should_run(&self, precheck: impl FnOnce() -> bool) -> bool {
+        let evaluations = self.evaluations.fetch_add(1, Ordering::Relaxed);
+        let should_run = match self.state.load(Ordering::Relaxed) {
+            TUNING_DISABLED => return true,
+            TUNING_ENABLED => precheck(),
+            _ if evaluations >= self.config.sample_size => {
+                self.decide();
+                return self.should_run_after_decision(precheck);
+            }
+            _ => {
+                let started = Instant::now();
+                let should_run = precheck();
+                self.sampled_precheck_nanos
+                    .fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+                should_run
+            }
+        };
+        if !should_run {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+        }
+        should_run
+    }
+
+    fn should_run_after_decision(&self, precheck: impl FnOnce() -> bool) -> bool {
+        if self.state.load(Ordering::Relaxed) == TUNING_DISABLED {
+            return true;
+        }
+        let should_run = precheck();
+        if !should_run {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+        }
+        should_run
+    }
+
+    /// Whether regex timings are still being sampled
+    pub fn is_sampling(&self) -> bool {
+        self.state.load(Ordering::Relaxed) == TUNING_SAMPLING
+    }
+
+    /// Record the time a regex took to run after passing the precheck
+    pub fn record_regex(&self, elapsed: Duration) {
+        if self.is_sampling() {
+            self.sampled_regex_nanos
+                .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+            self.sampled_regex_runs.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn decide(&self) {
+        let skip_rate = self.sampled_skip_rate();
+        let saved_nanos = skip_rate * self.average_regex_nanos();
+        let keep = self.sampled_regex_runs.load(Ordering::Relaxed) == 0
+            || (skip_rate >= self.config.min_skip_rate
+                && saved_nanos > self.average_precheck_nanos());
+        let state = if keep {
+            TUNING_ENABLED
+        } else {
+            TUNING_DISABLED
+        };
+        if self
+            .state
+            .compare_exchange(TUNING_SAMPLING, state, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            debug!(
+                "Precheck auto-tuning: skip_rate={:.3} avg_precheck_ns={:.0} avg_regex_ns={:.0} precheck_enabled={}",
+                skip_rate,
+                self.average_precheck_nanos(),
+                self.average_regex_nanos(),
+                keep
+            );
+        }
+    }
+
+    fn sampled_evaluations(&self) -> u64 {
+        self.evaluations
+            .load(Ordering::Relaxed)
+            .min(self.config.sample_size)
+    }
+
+    fn sampled_skip_rate(&self) -> f64 {
+        let sampled = self.sampled_evaluations();
+        if sampled == 0 {
+            return 0.0;
+        }
+        let sampled_runs = self.sampled_regex_runs.load(Ordering::Relaxed);
+        sampled.saturating_sub(sampled_runs) as f64 / sampled as f64
+    }
+
+    fn average_precheck_nanos(&self) -> f64 {
+        let sampled = self.sampled_evaluations();
+        if sampled == 0 {
+            return 0.0;
+        }
+        self.sampled_precheck_nanos.load(Ordering::Relaxed) as f64 / sampled as f64
+    }
+
+    fn average_regex_nanos(&self) -> f64 {
+        let runs = self.sampled_regex_runs.load(Ordering::Relaxed);
+        if runs == 0 {
+            return 0.0;
+        }
+        self.sampled_regex_nanos.load(Ordering::Relaxed) as f64 / runs as f64
+    }
+
+    /// Effectiveness report for a rule, as shown in generation statistics
+    pub fn effectiveness(
+        &self,
+        rule_id: String,
+        rule_name: String,
+    ) -> crate::models::PrecheckEffectiveness {
+        let state = self.state.load(Ordering::Relaxed);
+        crate::models::PrecheckEffectiveness {
+            rule_id,
+            rule_name,
+            evaluations: self.evaluations.load(Ordering::Relaxed),
+            regex_skipped: self.skipped.load(Ordering::Relaxed),
+            sampled_skip_rate: self.sampled_skip_rate(),
+            average_precheck_ns: self.average_precheck_nanos(),
+            average_regex_ns: self.average_regex_nanos(),
+            tuned: state != TUNING_SAMPLING,
+            precheck_enabled: state != TUNING_DISABLED,
+        }
+    }
+}
+
 /// Shared regex preprocessing utility for performance optimization
 #[derive(Clone)]
 pub struct RegexPreprocessor {
@@ -258,41 +475,7 @@ impl RegexPreprocessor {
         regex_pattern: &str,
         debug_context: &str,
     ) -> bool {
-        // If first-pass filtering is disabled, always run the regex
-        if !self.config.enable_first_pass_filtering {
-            return true;
-        }
-
-        // Extract literal strings from the regex pattern for string-based precheck
-        let literal_strings = self.extract_literal_strings_from_regex(regex_pattern);
-
-        // Extract special characters that are explicitly matched in the regex
-        let regex_special_chars = self.extract_special_chars_from_regex(regex_pattern);
-
-        // Check if any of the regex's literal special characters are present in the field value
-        let regex_special_chars_present =
-            regex_special_chars.iter().any(|&c| field_value.contains(c));
-
-        // Only use regex-specific special chars, not general precheck chars
-        // The regex can only match if the field contains the literal special characters it's looking for
-        let special_chars_present = regex_special_chars_present;
-
-        // Check if any significant literal strings (length >= minimum) are present in field value
-        let literal_strings_present = if literal_strings
-            .iter()
-            .any(|s| s.len() >= self.config.minimum_literal_length)
-        {
-            // At least one literal string meets minimum length, check if any are present in field
-            literal_strings
-                .iter()
-                .filter(|s| s.len() >= self.config.minimum_literal_length)
-                .any(|s| field_value.contains(s))
-        } else {
-            // No literal strings meet minimum length - default to executing regex (regex without literals is valid)
-            true
-        };
-
-        let should_run = special_chars_present || literal_strings_present;
+        let should_run = self.precheck(regex_pattern).may_match(field_value);
 
         if !should_run {
             trace!(
@@ -304,6 +487,28 @@ impl RegexPreprocessor {
         should_run
     }
 
+    /// Build the first-pass check for a pattern once, for evaluating it against many values
+    pub fn precheck(&self, regex_pattern: &str) -> RegexPrecheck {
+        // If first-pass filtering is disabled, always run the regex
+        if !self.config.enable_first_pass_filtering {
+            return RegexPrecheck::always();
+        }
+
+        // Only literal strings meeting the minimum length are significant
+        let literal_strings = self
+            .extract_literal_strings_from_regex(regex_pattern)
+            .into_iter()
+            .filter(|s| s.len() >= self.config.minimum_literal_length)
+            .collect();
+
+        RegexPrecheck {
+            enabled: true,
+            literal_strings,
+            // Only use regex-specific special chars, not general precheck chars
+            special_chars: self.extract_special_chars_from_regex(regex_pattern),
+        }
+    }
+
     /// Extract literal strings from a regex pattern for preprocessing
     /// This extracts contiguous sequences of non-regex-special characters,
     /// but excludes any literals that are followed by optional quantifiers
@@ -610,4 +815,44 @@ mod tests {
         // Should always run regex when preprocessing is disabled
         assert!(preprocessor.should_run_regex("BBC One HD", ".*complex.*regex.*", "test"));
     }
+
+    #[test]
+    fn test_precheck_tuning_disables_ineffective_precheck() {
+        let tuning = PrecheckTuning::new(PrecheckTuningConfig {
+            sample_size: 10,
+            min_skip_rate: 0.05,
+        });
+
+        // Precheck never skips the regex during sampling
+        for _ in 0..10 {
+            assert!(tuning.should_run(|| true));
+            tuning.record_regex(Duration::from_micros(1));
+        }
+
+        // Once sampling ends the precheck is no longer consulted
+        assert!(tuning.should_run(|| false));
+        let report = tuning.effectiveness("rule".to_string(), "Rule".to_string());
+        assert!(report.tuned);
+        assert!(!report.precheck_enabled);
+        assert_eq!(report.regex_skipped, 0);
+    }
+
+    #[test]
+    fn test_precheck_tuning_keeps_effective_precheck() {
+        let tuning = PrecheckTuning::new(PrecheckTuningConfig {
+            sample_size: 10,
+            min_skip_rate: 0.05,
+        });
+
+        for _ in 0..10 {
+            assert!(!tuning.should_run(|| false));
+        }
+
+        assert!(!tuning.should_run(|| false));
+        let report = tuning.effectiveness("rule".to_string(), "Rule".to_string());
+        assert!(report.tuned);
+        assert!(report.precheck_enabled);
+        assert_eq!(report.regex_skipped, 11);
+        assert_eq!(report.sampled_skip_rate, 1.0);
+    }
 }
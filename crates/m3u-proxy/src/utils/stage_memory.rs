@@ -0,0 +1,152 @@
+//! Pipeline stage memory sampling
+//!
+//! Samples the resident set size of this process while a pipeline stage runs, so the
+//! reported peak reflects the stage's high-water mark rather than a single reading taken
+//! after it finished. Only this process is refreshed on each sample, which keeps sampling
+//! cheap enough to run for every stage.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::task::JoinHandle;
+
+/// Interval between resident memory samples while a stage runs
+pub const STAGE_MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Resident memory of this process over one pipeline stage, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageMemoryUsage {
+    /// Resident memory when the stage started
+    pub start_bytes: u64,
+    /// Highest resident memory sampled while the stage ran
+    pub peak_bytes: u64,
+    /// Resident memory when the stage finished
+    pub end_bytes: u64,
+}
+
+impl StageMemoryUsage {
+    /// Growth of the peak over the memory held when the stage started
+    pub fn peak_growth_bytes(&self) -> u64 {
+        self.peak_bytes.saturating_sub(self.start_bytes)
+    }
+}
+
+/// Resident memory reader for this process
+struct ProcessMemoryReader {
+    system: System,
+    pid: Pid,
+}
+
+impl ProcessMemoryReader {
+    fn new() -> Self {
+        Self {
+            system: System::new(),
+            pid: Pid::from_u32(std::process::id()),
+        }
+    }
+
+    /// Current resident set size, or 0 when the platform does not report it
+    fn read(&mut self) -> u64 {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[self.pid]),
+            false,
+            ProcessRefreshKind::nothing().with_memory(),
+        );
+        self.system
+            .process(self.pid)
+            .map(|process| process.memory())
+            .unwrap_or(0)
+    }
+}
+
+/// Samples resident memory in the background from `start` until `finish`
+pub struct StageMemorySampler {
+    start_bytes: u64,
+    peak_bytes: Arc<AtomicU64>,
+    task: JoinHandle<ProcessMemoryReader>,
+    stop: Arc<tokio::sync::Notify>,
+}
+
+impl StageMemorySampler {
+    /// Take the starting sample and begin sampling every `interval`
+    pub fn start(interval: Duration) -> Self {
+        let mut reader = ProcessMemoryReader::new();
+        let start_bytes = reader.read();
+        let peak_bytes = Arc::new(AtomicU64::new(start_bytes));
+        let stop = Arc::new(tokio::sync::Notify::new());
+
+        let task = {
+            let peak_bytes = peak_bytes.clone();
+            let stop = stop.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                loop {
+                    tokio::select! {
+                        _ = stop.notified() => break,
+                        _ = ticker.tick() => {
+                            peak_bytes.fetch_max(reader.read(), Ordering::Relaxed);
+                        }
+                    }
+                }
+                reader
+            })
+        };
+
+        Self {
+            start_bytes,
+            peak_bytes,
+            task,
+            stop,
+        }
+    }
+
+    /// Stop sampling and take the final sample
+    pub async fn finish(mut self) -> StageMemoryUsage {
+        self.stop.notify_one();
+        let end_bytes = match (&mut self.task).await {
+            Ok(mut reader) => reader.read(),
+            Err(_) => ProcessMemoryReader::new().read(),
+        };
+        let peak_bytes = self
+            .peak_bytes
+            .fetch_max(end_bytes, Ordering::Relaxed)
+            .max(end_bytes);
+
+        StageMemoryUsage {
+            start_bytes: self.start_bytes,
+            peak_bytes,
+            end_bytes,
+        }
+    }
+}
+
+impl Drop for StageMemorySampler {
+    fn drop(&mut self) {
+        // Stops sampling when a stage fails before `finish`
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sampler_peak_covers_start_and_end() {
+        let sampler = StageMemorySampler::start(Duration::from_millis(5));
+        let buffer = vec![1u8; 8 * 1024 * 1024];
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let usage = sampler.finish().await;
+        drop(buffer);
+
+        assert!(usage.peak_bytes >= usage.start_bytes);
+        assert!(usage.peak_bytes >= usage.end_bytes);
+        assert_eq!(
+            usage.peak_growth_bytes(),
+            usage.peak_bytes - usage.start_bytes
+        );
+    }
+}
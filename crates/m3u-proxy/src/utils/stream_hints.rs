@@ -0,0 +1,169 @@
+//! Playback hints for generated playlist entries
+//!
+//! Players choose a demuxer from the stream URL, and the proxy's stream URLs carry no
+//! extension. With `stream_hints` enabled, generation names each entry's container in
+//! `#EXTVLCOPT` / `#KODIPROP` lines and can append request headers in the pipe-delimited
+//! URL form (`url|User-Agent=...`) understood by Kodi and most IPTV players.
+
+use std::collections::BTreeMap;
+
+use crate::config::StreamHintsConfig;
+
+/// Container of a stream as players see it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamContainer {
+    Hls,
+    MpegTs,
+    Mp4,
+    Matroska,
+    Flv,
+}
+
+impl StreamContainer {
+    /// Container of an ffprobe `format_name` (e.g. "mpegts", "mov,mp4,m4a,3gp,3g2,mj2")
+    pub fn from_format_name(format_name: &str) -> Option<Self> {
+        format_name
+            .split(',')
+            .map(|name| name.trim().to_ascii_lowercase())
+            .find_map(|name| match name.as_str() {
+                "hls" | "applehttp" => Some(Self::Hls),
+                "mpegts" => Some(Self::MpegTs),
+                "mp4" | "mov" => Some(Self::Mp4),
+                "matroska" | "webm" => Some(Self::Matroska),
+                "flv" => Some(Self::Flv),
+                _ => None,
+            })
+    }
+
+    /// Container implied by the file extension of a URL's path
+    pub fn from_url(stream_url: &str) -> Option<Self> {
+        let url = url::Url::parse(stream_url).ok()?;
+        let file = url.path_segments()?.next_back()?;
+        let (_, extension) = file.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "m3u8" | "m3u" => Some(Self::Hls),
+            "ts" | "mts" | "m2ts" => Some(Self::MpegTs),
+            "mp4" | "m4v" | "mov" => Some(Self::Mp4),
+            "mkv" | "webm" => Some(Self::Matroska),
+            "flv" => Some(Self::Flv),
+            _ => None,
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Hls => "application/vnd.apple.mpegurl",
+            Self::MpegTs => "video/mp2t",
+            Self::Mp4 => "video/mp4",
+            Self::Matroska => "video/x-matroska",
+            Self::Flv => "video/x-flv",
+        }
+    }
+
+    /// VLC demux module for the container
+    fn vlc_demux(self) -> &'static str {
+        match self {
+            Self::Hls => "adaptive",
+            Self::MpegTs => "ts",
+            Self::Mp4 => "mp4",
+            Self::Matroska => "mkv",
+            Self::Flv => "avformat",
+        }
+    }
+}
+
+/// Option lines written between an entry's `#EXTINF` line and its URL
+pub fn hint_lines(container: StreamContainer, config: &StreamHintsConfig) -> Vec<String> {
+    let mut lines = Vec::with_capacity(2);
+    if config.vlc_options {
+        lines.push(format!("#EXTVLCOPT:demux={}", container.vlc_demux()));
+    }
+    if config.kodi_properties {
+        lines.push(format!("#KODIPROP:mimetype={}", container.mime_type()));
+    }
+    lines
+}
+
+/// Stream URL with request headers appended in pipe-delimited form
+pub fn with_pipe_headers(stream_url: &str, headers: &BTreeMap<String, String>) -> String {
+    if headers.is_empty() {
+        return stream_url.to_string();
+    }
+    let headers = headers
+        .iter()
+        .map(|(name, value)| format!("{name}={}", urlencoding::encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{stream_url}|{headers}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_from_probe_and_url() {
+        assert_eq!(
+            StreamContainer::from_format_name("mov,mp4,m4a,3gp,3g2,mj2"),
+            Some(StreamContainer::Mp4)
+        );
+        assert_eq!(
+            StreamContainer::from_format_name("hls"),
+            Some(StreamContainer::Hls)
+        );
+        assert_eq!(StreamContainer::from_format_name("rtsp"), None);
+
+        assert_eq!(
+            StreamContainer::from_url("http://host/live/user/pass/123.ts?token=a.m3u8"),
+            Some(StreamContainer::MpegTs)
+        );
+        assert_eq!(
+            StreamContainer::from_url("https://cdn.example.com/news/index.M3U8"),
+            Some(StreamContainer::Hls)
+        );
+        assert_eq!(
+            StreamContainer::from_url("http://host.example.com/live/123"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hint_lines_and_pipe_headers() {
+        let mut config = StreamHintsConfig::default();
+        assert_eq!(
+            hint_lines(StreamContainer::Hls, &config),
+            vec![
+                "#EXTVLCOPT:demux=adaptive",
+                "#KODIPROP:mimetype=application/vnd.apple.mpegurl"
+            ]
+        );
+        config.vlc_options = false;
+        assert_eq!(
+            hint_lines(StreamContainer::MpegTs, &config),
+            vec!["#KODIPROP:mimetype=video/mp2t"]
+        );
+
+        let url = "http://proxy/stream/a/b";
+        assert_eq!(with_pipe_headers(url, &BTreeMap::new()), url);
+        let headers = BTreeMap::from([
+            ("User-Agent".to_string(), "VLC/3.0 LibVLC".to_string()),
+            ("Referer".to_string(), "http://example.com/".to_string()),
+        ]);
+        assert_eq!(
+            with_pipe_headers(url, &headers),
+            "http://proxy/stream/a/b|Referer=http%3A%2F%2Fexample.com%2F&User-Agent=VLC%2F3.0%20LibVLC"
+        );
+    }
+
+    #[test]
+    fn test_applies_to_proxy() {
+        use crate::models::proxy_settings::StreamHints;
+
+        let mut config = StreamHintsConfig::default();
+        assert!(!config.applies_to(None));
+        assert!(config.applies_to(Some(&StreamHints { enabled: true })));
+        config.enabled = true;
+        assert!(config.applies_to(None));
+        assert!(!config.applies_to(Some(&StreamHints { enabled: false })));
+    }
+}
@@ -0,0 +1,270 @@
+//! Signed, time-limited stream URLs
+//!
+//! A stream token is `<payload>.<signature>`: the payload is the URL-safe base64 of
+//! `proxy_id:channel_id:expires_at[:subject]` (UUIDs in base64, expiry as a unix timestamp)
+//! and the signature is the hex HMAC-SHA256 of the payload. Tokens are minted when a
+//! playlist is served, so the generated M3U on disk keeps plain stream URLs. The optional
+//! subject is the user the playlist was authenticated for, whose stream limit the streams
+//! count against.
+
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::OnceLock;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::StreamSigningConfig;
+use crate::utils::uuid_parser::{parse_uuid_flexible, uuid_to_base64};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Query parameter carrying the stream token
+pub const STREAM_TOKEN_PARAM: &str = "token";
+
+/// Why a stream token was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StreamTokenError {
+    #[error("stream token missing")]
+    Missing,
+    #[error("stream token malformed")]
+    Malformed,
+    #[error("stream token signature invalid")]
+    InvalidSignature,
+    #[error("stream token issued for another stream")]
+    WrongStream,
+    #[error("stream token expired")]
+    Expired,
+}
+
+/// Mints and verifies stream tokens
+#[derive(Clone)]
+pub struct StreamUrlSigner {
+    key: Vec<u8>,
+    lifetime: Duration,
+}
+
+impl StreamUrlSigner {
+    pub fn new(key: impl Into<Vec<u8>>, lifetime: Duration) -> Self {
+        Self {
+            key: key.into(),
+            lifetime,
+        }
+    }
+
+    /// Signer for the configured secret, or a per-process random key when none is set
+    pub fn from_config(config: Option<&StreamSigningConfig>) -> Self {
+        let default_config = StreamSigningConfig::default();
+        let config = config.unwrap_or(&default_config);
+        let key = match config.secret.as_deref().filter(|s| !s.is_empty()) {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => {
+                static GENERATED_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+                GENERATED_KEY.get_or_init(rand::random).to_vec()
+            }
+        };
+        Self::new(key, config.url_lifetime_duration())
+    }
+
+    /// Token for a proxy's channel, valid for the configured lifetime from `now`
+    pub fn sign(
+        &self,
+        proxy_id: &Uuid,
+        channel_id: &Uuid,
+        subject: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> String {
+        let expires_at = now.timestamp() + self.lifetime.as_secs() as i64;
+        let mut payload = format!(
+            "{}:{}:{}",
+            uuid_to_base64(proxy_id),
+            uuid_to_base64(channel_id),
+            expires_at
+        );
+        if let Some(subject) = subject {
+            payload.push(':');
+            payload.push_str(subject);
+        }
+        let payload = URL_SAFE_NO_PAD.encode(payload);
+        let signature = self.signature(&payload);
+        format!("{payload}.{signature}")
+    }
+
+    /// Check a token was issued by this signer for the stream and has not expired,
+    /// returning its subject
+    pub fn verify(
+        &self,
+        token: Option<&str>,
+        proxy_id: &Uuid,
+        channel_id: &Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Option<String>, StreamTokenError> {
+        let token = token.ok_or(StreamTokenError::Missing)?;
+        let (payload, signature) = token.split_once('.').ok_or(StreamTokenError::Malformed)?;
+        let signature = hex::decode(signature).map_err(|_| StreamTokenError::Malformed)?;
+
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| StreamTokenError::InvalidSignature)?;
+
+        let decoded = URL_SAFE_NO_PAD
+            .decode(payload)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or(StreamTokenError::Malformed)?;
+        let mut parts = decoded.splitn(4, ':');
+        let (Some(token_proxy), Some(token_channel), Some(expires_at)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(StreamTokenError::Malformed);
+        };
+        let expires_at: i64 = expires_at
+            .parse()
+            .map_err(|_| StreamTokenError::Malformed)?;
+
+        let same_stream = parse_uuid_flexible(token_proxy).ok() == Some(*proxy_id)
+            && parse_uuid_flexible(token_channel).ok() == Some(*channel_id);
+        if !same_stream {
+            return Err(StreamTokenError::WrongStream);
+        }
+        if now.timestamp() > expires_at {
+            return Err(StreamTokenError::Expired);
+        }
+        Ok(parts.next().map(str::to_string))
+    }
+
+    /// Append a fresh token to every stream URL of a proxy's generated playlist
+    pub fn sign_playlist(
+        &self,
+        content: &str,
+        proxy_id: &Uuid,
+        subject: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> String {
+        let stream_path = format!("/stream/{}/", uuid_to_base64(proxy_id));
+        let mut signed = String::with_capacity(content.len() + content.len() / 4);
+        for line in content.lines() {
+            // Pipe-delimited player headers (`url|User-Agent=...`) stay after the token
+            let (url, headers) = match line.split_once('|') {
+                Some((url, headers)) if !line.starts_with('#') => (url, Some(headers)),
+                _ => (line, None),
+            };
+            signed.push_str(url);
+            let channel_id = url
+                .split_once(&stream_path)
+                .filter(|_| !url.starts_with('#'))
+                .and_then(|(_, rest)| parse_uuid_flexible(rest.split(['?', '#']).next()?).ok());
+            if let Some(channel_id) = channel_id {
+                signed.push(if url.contains('?') { '&' } else { '?' });
+                signed.push_str(STREAM_TOKEN_PARAM);
+                signed.push('=');
+                signed.push_str(&self.sign(proxy_id, &channel_id, subject, now));
+            }
+            if let Some(headers) = headers {
+                signed.push('|');
+                signed.push_str(headers);
+            }
+            signed.push('\n');
+        }
+        signed
+    }
+
+    fn signature(&self, payload: &str) -> String {
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer() -> StreamUrlSigner {
+        StreamUrlSigner::new("test-secret", Duration::from_secs(3600))
+    }
+
+    #[test]
+    fn test_token_round_trip_and_rejections() {
+        let signer = signer();
+        let proxy_id = Uuid::new_v4();
+        let channel_id = Uuid::new_v4();
+        let now = Utc::now();
+        let token = signer.sign(&proxy_id, &channel_id, None, now);
+
+        assert_eq!(
+            signer.verify(Some(&token), &proxy_id, &channel_id, now),
+            Ok(None)
+        );
+        let token_for_user = signer.sign(&proxy_id, &channel_id, Some("alice:tv"), now);
+        assert_eq!(
+            signer.verify(Some(&token_for_user), &proxy_id, &channel_id, now),
+            Ok(Some("alice:tv".to_string()))
+        );
+        assert_eq!(
+            signer.verify(None, &proxy_id, &channel_id, now),
+            Err(StreamTokenError::Missing)
+        );
+        assert_eq!(
+            signer.verify(Some(&token), &proxy_id, &Uuid::new_v4(), now),
+            Err(StreamTokenError::WrongStream)
+        );
+        assert_eq!(
+            signer.verify(
+                Some(&token),
+                &proxy_id,
+                &channel_id,
+                now + chrono::Duration::hours(2)
+            ),
+            Err(StreamTokenError::Expired)
+        );
+
+        let other = StreamUrlSigner::new("other-secret", Duration::from_secs(3600));
+        assert_eq!(
+            other.verify(Some(&token), &proxy_id, &channel_id, now),
+            Err(StreamTokenError::InvalidSignature)
+        );
+        assert_eq!(
+            signer.verify(Some("garbage"), &proxy_id, &channel_id, now),
+            Err(StreamTokenError::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_sign_playlist_appends_tokens_to_stream_urls() {
+        let signer = signer();
+        let proxy_id = Uuid::new_v4();
+        let channel_id = Uuid::new_v4();
+        let content = format!(
+            "#EXTM3U\n#EXTINF:-1 tvg-id=\"bbc1\",BBC One\nhttp://host:8080/stream/{}/{}\n",
+            uuid_to_base64(&proxy_id),
+            uuid_to_base64(&channel_id)
+        );
+
+        let now = Utc::now();
+        let signed = signer.sign_playlist(&content, &proxy_id, Some("alice"), now);
+        let url = signed.lines().nth(2).unwrap();
+        let (_, token) = url.split_once("?token=").unwrap();
+        assert_eq!(
+            signer.verify(Some(token), &proxy_id, &channel_id, now),
+            Ok(Some("alice".to_string()))
+        );
+        assert_eq!(signed.lines().nth(1), content.lines().nth(1));
+
+        let with_headers = content.replace(
+            &uuid_to_base64(&channel_id),
+            &format!("{}|User-Agent=VLC", uuid_to_base64(&channel_id)),
+        );
+        let signed = signer.sign_playlist(&with_headers, &proxy_id, None, now);
+        let url = signed.lines().nth(2).unwrap();
+        let (url, headers) = url.split_once('|').unwrap();
+        assert!(url.contains("?token="));
+        assert_eq!(headers, "User-Agent=VLC");
+    }
+}
@@ -93,6 +93,41 @@ pub fn apply_time_offset(dt: DateTime<Utc>, offset_seconds: i32) -> DateTime<Utc
     }
 }
 
+/// Parse a per-channel EPG shift: a time offset ("+1h", "-30m") or hours as written in
+/// tvg-shift ("+1", "-2", "+5.5")
+pub fn parse_epg_shift(shift: &str) -> Result<i32, String> {
+    let shift = shift.trim();
+    if let Ok(hours) = shift.trim_start_matches('+').parse::<f64>()
+        && hours.is_finite()
+    {
+        if hours.abs() > 24.0 {
+            return Err(format!(
+                "EPG shift too large: {hours}h. Maximum allowed is ±24 hours"
+            ));
+        }
+        return Ok((hours * 3600.0).round() as i32);
+    }
+    parse_time_offset(shift)
+}
+
+/// Signed label for a shift, e.g. "+1h", "-30m" or "+1h30m"
+pub fn format_epg_shift(offset_seconds: i32) -> String {
+    let sign = if offset_seconds < 0 { '-' } else { '+' };
+    let total = offset_seconds.unsigned_abs();
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+    let mut label = sign.to_string();
+    if hours > 0 {
+        label.push_str(&format!("{hours}h"));
+    }
+    if minutes > 0 {
+        label.push_str(&format!("{minutes}m"));
+    }
+    if seconds > 0 || total == 0 {
+        label.push_str(&format!("{seconds}s"));
+    }
+    label
+}
+
 /// Detect timezone from XMLTV content
 pub fn detect_timezone_from_xmltv(content: &str) -> Option<String> {
     // Look for timezone information in XMLTV format
@@ -187,6 +222,23 @@ pub fn validate_timezone(tz_str: &str) -> Result<String, String> {
     ))
 }
 
+/// Parse a named timezone from the IANA tz database (e.g. "America/New_York")
+pub fn parse_iana_timezone(tz_str: &str) -> Result<Tz, String> {
+    tz_str.trim().parse::<Tz>().map_err(|_| {
+        format!(
+            "Invalid timezone: '{tz_str}'. Use a timezone from the IANA tz database (e.g., 'Europe/London')"
+        )
+    })
+}
+
+/// Timezone of this instance: the IANA timezone named by `TZ`, or UTC
+pub fn instance_timezone() -> Tz {
+    std::env::var("TZ")
+        .ok()
+        .and_then(|tz| parse_iana_timezone(tz.trim_start_matches(':')).ok())
+        .unwrap_or(Tz::UTC)
+}
+
 /// Parse fixed offset timezone formats like "+01:00", "+0100", etc.
 fn parse_fixed_offset(offset_str: &str) -> Result<FixedOffset, String> {
     let offset_str = offset_str.trim();
@@ -403,6 +455,22 @@ mod tests {
         assert!(parse_time_offset("90s").is_err()); // Seconds too large
     }
 
+    #[test]
+    fn test_parse_and_format_epg_shift() {
+        assert_eq!(parse_epg_shift("+1").unwrap(), 3600);
+        assert_eq!(parse_epg_shift("-2").unwrap(), -7200);
+        assert_eq!(parse_epg_shift("+1h30m").unwrap(), 5400);
+        assert_eq!(parse_epg_shift("+24").unwrap(), 86400);
+        assert_eq!(parse_epg_shift("+5.5").unwrap(), 19800);
+        assert_eq!(parse_epg_shift(" -0.5 ").unwrap(), -1800);
+        assert!(parse_epg_shift("+48").is_err());
+        assert!(parse_epg_shift("soon").is_err());
+
+        assert_eq!(format_epg_shift(3600), "+1h");
+        assert_eq!(format_epg_shift(-1800), "-30m");
+        assert_eq!(format_epg_shift(5400), "+1h30m");
+    }
+
     #[test]
     fn test_validate_timezone() {
         assert!(validate_timezone("UTC").is_ok());
@@ -8,6 +8,7 @@ use crate::errors::{AppError, AppResult};
 use quick_xml::Reader;
 use quick_xml::events::{BytesStart, Event};
 use std::collections::HashMap;
+use std::io::BufRead;
 
 /// Simple program structure containing only the fields we actually use
 #[derive(Debug, Clone)]
@@ -22,129 +23,182 @@ pub struct SimpleXmltvProgram {
     pub icon: Option<String>,
 }
 
+/// `<channel>` metadata: every display name and icon, with its `lang` attribute if any
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimpleXmltvChannel {
+    pub id: String,
+    /// (name, lang)
+    pub display_names: Vec<(String, Option<String>)>,
+    /// (src, lang)
+    pub icons: Vec<(String, Option<String>)>,
+}
+
 /// Parse XMLTV content using streaming quick-xml parser
 pub fn parse_xmltv_programs(content: &str) -> AppResult<Vec<SimpleXmltvProgram>> {
-    let mut reader = Reader::from_str(content);
-    reader.config_mut().trim_text(true);
-
+    let mut reader = XmltvProgramReader::new(content.as_bytes());
     let mut programs = Vec::new();
+    while let Some(program) = reader.next_program()? {
+        programs.push(program);
+    }
+    Ok(programs)
+}
 
-    let mut current_program: Option<SimpleXmltvProgram> = None;
-    let mut current_element_stack = Vec::new();
-    let mut current_text = String::new();
-
-    loop {
-        match reader.read_event() {
-            Ok(Event::Start(ref e)) => {
-                let name = std::str::from_utf8(e.name().as_ref())
-                    .map_err(|e| {
-                        AppError::source_error(format!("Invalid UTF-8 in XML element name: {e}"))
-                    })?
-                    .to_string();
-
-                if name.as_str() == "programme" {
-                    let attrs = parse_attributes(e);
-                    current_program = Some(SimpleXmltvProgram {
-                        channel: attrs.get("channel").cloned().unwrap_or_default(),
-                        start: attrs.get("start").cloned().unwrap_or_default(),
-                        stop: attrs.get("stop").cloned(),
-                        title: None,
-                        description: None,
-                        category: None,
-                        language: None,
-                        icon: None,
-                    });
-                }
+/// Pull parser yielding one `<programme>` at a time
+///
+/// Only the current event and the programme being built are held in memory, so
+/// documents of any size can be processed from a file or decompressing reader.
+/// `<channel>` elements passed on the way are collected (see [`Self::take_channels`]).
+pub struct XmltvProgramReader<R: BufRead> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    current_program: Option<SimpleXmltvProgram>,
+    current_channel: Option<SimpleXmltvChannel>,
+    current_lang: Option<String>,
+    current_text: String,
+    channels: Vec<SimpleXmltvChannel>,
+}
 
-                current_element_stack.push(name);
-                current_text.clear();
-            }
+impl<R: BufRead> XmltvProgramReader<R> {
+    pub fn new(input: R) -> Self {
+        let mut reader = Reader::from_reader(input);
+        reader.config_mut().trim_text(true);
+        Self {
+            reader,
+            buf: Vec::with_capacity(4096),
+            current_program: None,
+            current_channel: None,
+            current_lang: None,
+            current_text: String::new(),
+            channels: Vec::new(),
+        }
+    }
 
-            Ok(Event::End(ref e)) => {
-                let name = std::str::from_utf8(e.name().as_ref())
-                    .map_err(|e| {
-                        AppError::source_error(format!("Invalid UTF-8 in XML element name: {e}"))
-                    })?
-                    .to_string();
-
-                // Process the element we're closing
-                if let Some(ref mut program) = current_program {
-                    match name.as_str() {
-                        "title" => {
-                            if !current_text.trim().is_empty() {
-                                program.title = Some(current_text.trim().to_string());
-                            }
+    /// Channels read so far (all of them once `next_program` returned `None`)
+    pub fn take_channels(&mut self) -> Vec<SimpleXmltvChannel> {
+        std::mem::take(&mut self.channels)
+    }
+
+    fn collecting_text(&self) -> bool {
+        self.current_program.is_some() || self.current_channel.is_some()
+    }
+
+    /// Read the next complete programme, or `None` at the end of the document
+    pub fn next_program(&mut self) -> AppResult<Option<SimpleXmltvProgram>> {
+        loop {
+            self.buf.clear();
+            let event = self
+                .reader
+                .read_event_into(&mut self.buf)
+                .map_err(|e| AppError::source_error(format!("XML parsing error: {e}")))?;
+
+            match event {
+                Event::Start(ref e) => {
+                    match e.name().as_ref() {
+                        b"programme" => {
+                            let attrs = parse_attributes(e);
+                            self.current_program = Some(SimpleXmltvProgram {
+                                channel: attrs.get("channel").cloned().unwrap_or_default(),
+                                start: attrs.get("start").cloned().unwrap_or_default(),
+                                stop: attrs.get("stop").cloned(),
+                                title: None,
+                                description: None,
+                                category: None,
+                                language: None,
+                                icon: None,
+                            });
                         }
-                        "desc" => {
-                            if !current_text.trim().is_empty() {
-                                program.description = Some(current_text.trim().to_string());
-                            }
+                        b"channel" if self.current_program.is_none() => {
+                            let attrs = parse_attributes(e);
+                            self.current_channel = Some(SimpleXmltvChannel {
+                                id: attrs.get("id").cloned().unwrap_or_default(),
+                                ..Default::default()
+                            });
                         }
-                        "category" => {
-                            if !current_text.trim().is_empty() {
-                                program.category = Some(current_text.trim().to_string());
-                            }
+                        b"display-name" => {
+                            self.current_lang = parse_attributes(e).remove("lang");
                         }
-                        "language" => {
-                            if !current_text.trim().is_empty() {
-                                program.language = Some(current_text.trim().to_string());
+                        _ => {}
+                    }
+                    self.current_text.clear();
+                }
+
+                Event::End(ref e) => {
+                    // Process the element we're closing
+                    if let Some(ref mut channel) = self.current_channel {
+                        match e.name().as_ref() {
+                            b"display-name" => {
+                                let text = self.current_text.trim();
+                                if !text.is_empty() {
+                                    channel
+                                        .display_names
+                                        .push((text.to_string(), self.current_lang.take()));
+                                }
                             }
+                            b"channel" => {
+                                if let Some(channel) = self.current_channel.take() {
+                                    self.channels.push(channel);
+                                }
+                            }
+                            _ => {}
                         }
-                        "programme" => {
-                            // End of programme - add to results
-                            if let Some(program) = current_program.take() {
-                                programs.push(program);
+                    } else if let Some(ref mut program) = self.current_program {
+                        let text = self.current_text.trim();
+                        let value = (!text.is_empty()).then(|| text.to_string());
+                        match e.name().as_ref() {
+                            b"title" if value.is_some() => program.title = value,
+                            b"desc" if value.is_some() => program.description = value,
+                            b"category" if value.is_some() => program.category = value,
+                            b"language" if value.is_some() => program.language = value,
+                            b"programme" => {
+                                self.current_text.clear();
+                                return Ok(self.current_program.take());
                             }
+                            _ => {}
                         }
-                        _ => {}
                     }
+                    self.current_text.clear();
                 }
 
-                current_element_stack.pop();
-                current_text.clear();
-            }
-
-            Ok(Event::Empty(ref e)) => {
-                let name = std::str::from_utf8(e.name().as_ref())
-                    .map_err(|e| {
-                        AppError::source_error(format!("Invalid UTF-8 in XML element name: {e}"))
-                    })?
-                    .to_string();
-
-                // Handle self-closing elements
-                if let Some(ref mut program) = current_program
-                    && name.as_str() == "icon"
-                {
-                    let attrs = parse_attributes(e);
-                    if let Some(src) = attrs.get("src") {
-                        program.icon = Some(src.clone());
+                Event::Empty(ref e) => {
+                    // Handle self-closing elements
+                    if e.name().as_ref() == b"icon" {
+                        let mut attrs = parse_attributes(e);
+                        if let Some(ref mut channel) = self.current_channel {
+                            if let Some(src) = attrs.remove("src") {
+                                channel.icons.push((src, attrs.remove("lang")));
+                            }
+                        } else if let Some(ref mut program) = self.current_program
+                            && let Some(src) = attrs.remove("src")
+                        {
+                            program.icon = Some(src);
+                        }
                     }
                 }
-            }
 
-            Ok(Event::Text(e)) => {
-                let text = std::str::from_utf8(&e)
-                    .map_err(|e| AppError::source_error(format!("Invalid UTF-8 in text: {e}")))?;
-                current_text.push_str(text);
-            }
+                Event::Text(ref e) => {
+                    if self.collecting_text() {
+                        let text = std::str::from_utf8(e).map_err(|e| {
+                            AppError::source_error(format!("Invalid UTF-8 in text: {e}"))
+                        })?;
+                        self.current_text.push_str(text);
+                    }
+                }
 
-            Ok(Event::CData(e)) => {
-                let text = std::str::from_utf8(&e)
-                    .map_err(|e| AppError::source_error(format!("Invalid UTF-8 in CDATA: {e}")))?;
-                current_text.push_str(text);
-            }
+                Event::CData(ref e) => {
+                    if self.collecting_text() {
+                        let text = std::str::from_utf8(e).map_err(|e| {
+                            AppError::source_error(format!("Invalid UTF-8 in CDATA: {e}"))
+                        })?;
+                        self.current_text.push_str(text);
+                    }
+                }
 
-            Ok(Event::Eof) => break,
+                Event::Eof => return Ok(None),
 
-            Err(e) => {
-                return Err(AppError::source_error(format!("XML parsing error: {e}")));
+                _ => {} // Ignore other events (comments, processing instructions, etc.)
             }
-
-            _ => {} // Ignore other events (comments, processing instructions, etc.)
         }
     }
-
-    Ok(programs)
 }
 
 /// Parse XML attributes into a HashMap
@@ -161,3 +215,96 @@ fn parse_attributes(element: &BytesStart) -> HashMap<String, String> {
     }
     attrs
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tv>
+  <channel id="one.uk"><display-name>One</display-name></channel>
+  <programme channel="one.uk" start="20251016120000 +0100" stop="20251016130000 +0100">
+    <title>News</title>
+    <desc><![CDATA[Headlines]]></desc>
+    <category>Current Affairs</category>
+    <icon src="http://img/news.png"/>
+  </programme>
+  <programme channel="two.uk" start="20251016130000 +0100">
+    <title></title>
+  </programme>
+</tv>"#;
+
+    #[test]
+    fn test_parse_programs() {
+        let programs = parse_xmltv_programs(SAMPLE).unwrap();
+        assert_eq!(programs.len(), 2);
+        assert_eq!(programs[0].channel, "one.uk");
+        assert_eq!(programs[0].stop.as_deref(), Some("20251016130000 +0100"));
+        assert_eq!(programs[0].title.as_deref(), Some("News"));
+        assert_eq!(programs[0].description.as_deref(), Some("Headlines"));
+        assert_eq!(programs[0].category.as_deref(), Some("Current Affairs"));
+        assert_eq!(programs[0].icon.as_deref(), Some("http://img/news.png"));
+        assert!(programs[1].title.is_none());
+        assert!(programs[1].stop.is_none());
+    }
+
+    #[test]
+    fn test_reader_pulls_from_small_buffers() {
+        // A tiny BufReader capacity forces elements to span many refills
+        let input = std::io::BufReader::with_capacity(8, SAMPLE.as_bytes());
+        let mut reader = XmltvProgramReader::new(input);
+        assert_eq!(reader.next_program().unwrap().unwrap().channel, "one.uk");
+        assert_eq!(reader.next_program().unwrap().unwrap().channel, "two.uk");
+        assert!(reader.next_program().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_channels_are_collected() {
+        let mut reader = XmltvProgramReader::new(
+            r#"<tv>
+  <channel id="arte.fr">
+    <display-name lang="fr">Arte</display-name>
+    <display-name lang="de">Arte Deutsch</display-name>
+    <display-name>ARTE</display-name>
+    <icon src="http://img/arte.png"/>
+    <icon src="http://img/arte-de.png" lang="de"/>
+  </channel>
+  <programme channel="arte.fr" start="20251016120000 +0100"><title>Doku</title></programme>
+</tv>"#
+                .as_bytes(),
+        );
+        assert_eq!(
+            reader.next_program().unwrap().unwrap().title.as_deref(),
+            Some("Doku")
+        );
+        assert!(reader.next_program().unwrap().is_none());
+
+        let channels = reader.take_channels();
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].id, "arte.fr");
+        assert_eq!(
+            channels[0].display_names,
+            vec![
+                ("Arte".to_string(), Some("fr".to_string())),
+                ("Arte Deutsch".to_string(), Some("de".to_string())),
+                ("ARTE".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            channels[0].icons,
+            vec![
+                ("http://img/arte.png".to_string(), None),
+                ("http://img/arte-de.png".to_string(), Some("de".to_string())),
+            ]
+        );
+        assert!(reader.take_channels().is_empty());
+    }
+
+    #[test]
+    fn test_malformed_document_errors() {
+        let mut reader = XmltvProgramReader::new(
+            "<tv><programme channel=\"a\" start=\"1\"><title>x</desc></programme></tv>".as_bytes(),
+        );
+        assert!(reader.next_program().is_err());
+    }
+}
@@ -12,16 +12,20 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use super::AppState;
+use super::extractors::RequestContext;
+use super::handlers::rule_versions::record_rule_version;
 
 pub mod log_streaming;
 pub mod progress_events;
 pub mod relay;
 pub mod settings;
+pub mod websocket;
 
 use crate::data_mapping::DataMappingService;
 use crate::models::data_mapping::{
     DataMappingExpressionPreviewRequest, DataMappingPreviewResponse, DataMappingSourceType,
 };
+use crate::models::rule_version::{RuleChange, RuleKind, RuleSnapshot};
 use crate::models::*;
 use crate::services::progress_service::{OperationType, UniversalState};
 use crate::web::api::progress_events::{ProgressEvent, ProgressStageEvent};
@@ -52,6 +56,14 @@ pub struct FilterQueryParams {
     pub order: Option<String>,
 }
 
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct DataMappingQueryParams {
+    /// Filter by source type (stream or epg)
+    pub source_type: Option<DataMappingSourceType>,
+    /// Filter by active state
+    pub is_active: Option<bool>,
+}
+
 /// Convert operation type enum to lowercase string
 fn operation_type_to_string(op_type: &OperationType) -> String {
     match op_type {
@@ -345,16 +357,27 @@ pub async fn get_proxy_regeneration_progress(
     tag = "filters",
     summary = "List filters",
     description = "Retrieve all filters with usage statistics and expression trees",
-    params(FilterQueryParams),
+    params(
+        FilterQueryParams,
+        ("page" = Option<u32>, Query, description = "Page number (1-based)"),
+        ("limit" = Option<u32>, Query, description = "Number of items per page"),
+        ("offset" = Option<u32>, Query, description = "0-based offset; overrides page"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous next_cursor; overrides offset and page"),
+        ("search" = Option<String>, Query, description = "Search term for name or expression"),
+        ("sort_by" = Option<String>, Query, description = "Sort field: name, created_at, usage_count (alternative to sort)"),
+        ("sort_ascending" = Option<bool>, Query, description = "Sort direction when using sort_by (default true)"),
+    ),
     responses(
-        (status = 200, description = "List of filters with statistics"),
+        (status = 200, description = "Paginated list of filters with statistics"),
+        (status = 400, description = "Invalid query parameters"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn list_filters(
     Query(params): Query<FilterQueryParams>,
+    list_params: crate::web::ListParams,
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<crate::web::PaginatedResponse<serde_json::Value>>, axum::response::Response> {
     // Parse source_type parameter
     let source_type = params
         .source_type
@@ -365,14 +388,42 @@ pub async fn list_filters(
             _ => None,
         });
 
+    // `sort`/`order` take precedence; `sort_by`/`sort_ascending` are the shared list parameters
+    let (sort, order) = match params.sort {
+        Some(sort) => (Some(sort), params.order),
+        None => {
+            let sort_by = list_params
+                .sort_field(&["name", "created_at", "usage_count"])
+                .map_err(|errors| {
+                    crate::web::responses::validation_error(errors).into_response()
+                })?;
+            let order = if list_params.search.sort_ascending {
+                "asc"
+            } else {
+                "desc"
+            };
+            (sort_by.map(str::to_string), Some(order.to_string()))
+        }
+    };
+
     let filter_repo = crate::database::repositories::FilterSeaOrmRepository::new(
         state.database.connection().clone(),
     );
     match filter_repo
-        .get_filters_with_usage_filtered(source_type, params.sort, params.order)
+        .get_filters_with_usage_filtered(source_type, sort, order)
         .await
     {
-        Ok(filters) => {
+        Ok(mut filters) => {
+            list_params.apply_search(&mut filters, |filter_with_usage, term| {
+                filter_with_usage.filter.name.to_lowercase().contains(term)
+                    || filter_with_usage
+                        .filter
+                        .expression
+                        .to_lowercase()
+                        .contains(term)
+            });
+            let filters = list_params.paginate(filters);
+
             // Get available fields for expression parsing
             let available_fields = match filter_repo.get_available_filter_fields().await {
                 Ok(fields) => fields.into_iter().map(|f| f.name).collect::<Vec<String>>(),
@@ -387,43 +438,40 @@ pub async fn list_filters(
                 .with_fields(available_fields)
                 .with_aliases(alias_map);
 
-            let enhanced_filters: Vec<serde_json::Value> = filters
-                .into_iter()
-                .map(|filter_with_usage| {
-                    // Parse expression to condition_tree for UI compatibility
-                    let condition_tree = if !filter_with_usage.filter.expression.trim().is_empty() {
-                        parser.parse(&filter_with_usage.filter.expression).ok()
-                    } else {
-                        None
-                    };
+            let enhanced_filters = filters.map_items(|filter_with_usage| {
+                // Parse expression to condition_tree for UI compatibility
+                let condition_tree = if !filter_with_usage.filter.expression.trim().is_empty() {
+                    parser.parse(&filter_with_usage.filter.expression).ok()
+                } else {
+                    None
+                };
 
-                    // Create filter object with both expression and condition_tree
-                    let mut filter_json =
-                        serde_json::to_value(&filter_with_usage.filter).unwrap_or_default();
-                    if let Some(filter_obj) = filter_json.as_object_mut() {
+                // Create filter object with both expression and condition_tree
+                let mut filter_json =
+                    serde_json::to_value(&filter_with_usage.filter).unwrap_or_default();
+                if let Some(filter_obj) = filter_json.as_object_mut() {
+                    filter_obj.insert(
+                        "usage_count".to_string(),
+                        serde_json::json!(filter_with_usage.usage_count),
+                    );
+                    if let Some(tree) = condition_tree {
                         filter_obj.insert(
-                            "usage_count".to_string(),
-                            serde_json::json!(filter_with_usage.usage_count),
+                            "condition_tree".to_string(),
+                            serde_json::to_value(tree).unwrap_or_default(),
                         );
-                        if let Some(tree) = condition_tree {
-                            filter_obj.insert(
-                                "condition_tree".to_string(),
-                                serde_json::to_value(tree).unwrap_or_default(),
-                            );
-                        }
                     }
+                }
 
-                    serde_json::json!({
-                        "filter": filter_json
-                    })
+                serde_json::json!({
+                    "filter": filter_json
                 })
-                .collect();
+            });
 
-            Ok(Json(serde_json::Value::Array(enhanced_filters)))
+            Ok(Json(enhanced_filters))
         }
         Err(e) => {
             error!("Failed to list filters: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
         }
     }
 }
@@ -840,13 +888,26 @@ pub async fn get_date_completion_options(
 )]
 pub async fn create_filter(
     State(state): State<AppState>,
+    context: RequestContext,
     Json(payload): Json<FilterCreateRequest>,
 ) -> Result<Json<Filter>, StatusCode> {
     let filter_repo = crate::database::repositories::FilterSeaOrmRepository::new(
         state.database.connection().clone(),
     );
     match filter_repo.create(payload).await {
-        Ok(filter) => Ok(Json(filter)),
+        Ok(filter) => {
+            record_rule_version(
+                &state,
+                RuleKind::Filter,
+                filter.id,
+                None,
+                &RuleSnapshot::from(&filter),
+                RuleChange::Created,
+                &context,
+            )
+            .await;
+            Ok(Json(filter))
+        }
         Err(e) => {
             error!("Failed to create filter: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -946,13 +1007,33 @@ pub async fn get_filter(
 pub async fn update_filter(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    context: RequestContext,
     Json(payload): Json<FilterUpdateRequest>,
 ) -> Result<Json<Filter>, StatusCode> {
     let filter_repo = crate::database::repositories::FilterSeaOrmRepository::new(
         state.database.connection().clone(),
     );
+    let previous = match filter_repo.find_by_id(id).await {
+        Ok(filter) => filter.as_ref().map(RuleSnapshot::from),
+        Err(e) => {
+            warn!("Failed to load filter {} before update: {}", id, e);
+            None
+        }
+    };
     match filter_repo.update(&id, payload).await {
-        Ok(filter) => Ok(Json(filter)),
+        Ok(filter) => {
+            record_rule_version(
+                &state,
+                RuleKind::Filter,
+                id,
+                previous.as_ref(),
+                &RuleSnapshot::from(&filter),
+                RuleChange::Updated,
+                &context,
+            )
+            .await;
+            Ok(Json(filter))
+        }
         Err(e) => {
             error!("Failed to update filter {}: {}", id, e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -965,7 +1046,7 @@ pub async fn update_filter(
     path = "/filters/{id}",
     tag = "filters",
     summary = "Delete filter",
-    description = "Delete a filter",
+    description = "Move a filter to the trash; it is no longer applied until restored via `/trash`",
     params(
         ("id" = String, Path, description = "Filter ID (UUID)"),
     ),
@@ -1056,6 +1137,12 @@ Validate expression syntax and semantic correctness including context-specific f
 ## Field Validation
 The endpoint validates field names against the appropriate schema for the specified context,
 providing intelligent suggestions for typos and unknown fields.
+
+## Lint Mode
+Pass `lint=true` to also receive `warnings` for valid but questionable expressions: regexes prone
+to catastrophic backtracking, duplicate or redundant OR/AND branches and case-sensitivity pitfalls.
+With `source_ids` (comma-separated), conditions on fields that are empty for every record of those
+sources are flagged too.
 ",
     request_body = ExpressionValidateRequest,
     responses(
@@ -1100,7 +1187,105 @@ pub async fn validate_expression(
         domains.push(crate::expression::ExpressionDomain::EpgFilter);
     }
 
-    validate_expression_engine(&state, &payload.expression, &domains).await
+    //   lint=true enables lint warnings; source_ids=uuid,uuid adds the always-empty field check
+    let lint = params
+        .get("lint")
+        .is_some_and(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes"));
+    let lint_source_ids = if lint {
+        let mut ids = Vec::new();
+        for part in params
+            .get("source_ids")
+            .map(|s| s.as_str())
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            match crate::utils::uuid_parser::parse_uuid_flexible(part) {
+                Ok(id) => ids.push(id),
+                Err(_) => return Err(StatusCode::BAD_REQUEST),
+            }
+        }
+        Some(ids)
+    } else {
+        None
+    };
+
+    validate_expression_engine(
+        &state,
+        &payload.expression,
+        &domains,
+        lint_source_ids.as_deref(),
+    )
+    .await
+}
+
+/// Explain how an expression evaluates against a sample record
+#[utoipa::path(
+    post,
+    path = "/api/v1/expressions/explain",
+    tag = "expressions",
+    summary = "Explain expression evaluation against a sample record",
+    description = "
+Evaluate an expression against a sample channel or programme and return a step-by-step trace for
+rule debugging: every condition with the value it saw and whether it matched, regex capture groups,
+and each action with whether it would fire and the value it would write.
+
+`domain` selects the field set (`stream_filter`, `epg_filter`, `stream_mapping` (default) or
+`epg_mapping`). `sample` maps field names (aliases accepted) to values. Nothing is persisted.
+
+Invalid expressions return 200 with `is_valid = false` and the parse error.
+",
+    request_body = ExpressionExplainRequest,
+    responses(
+        (status = 200, description = "Evaluation trace", body = ExpressionExplainResult),
+        (status = 400, description = "Unknown domain")
+    )
+)]
+pub async fn explain_expression(
+    Json(payload): Json<ExpressionExplainRequest>,
+) -> Result<Json<ExpressionExplainResult>, StatusCode> {
+    use crate::expression::ExpressionDomain;
+
+    let domain = match payload
+        .domain
+        .as_deref()
+        .map(|d| d.trim().to_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("stream_mapping" | "stream_data_mapping") => {
+            ExpressionDomain::StreamDataMapping
+        }
+        Some("epg_mapping" | "epg_data_mapping") => ExpressionDomain::EpgDataMapping,
+        Some("stream_filter" | "stream") => ExpressionDomain::StreamFilter,
+        Some("epg_filter" | "epg") => ExpressionDomain::EpgFilter,
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let sample = crate::expression::explain::sample_record(&payload.sample);
+    let result = match crate::expression::parse_expression_extended(domain, &payload.expression) {
+        Ok(Some(parsed)) => {
+            crate::expression::explain::explain_expression(&parsed.extended, &sample)
+        }
+        Ok(None) => ExpressionExplainResult {
+            is_valid: false,
+            error: Some("Expression is empty".to_string()),
+            matched: false,
+            groups: Vec::new(),
+            record: sample,
+            modified_fields: Vec::new(),
+        },
+        Err(e) => ExpressionExplainResult {
+            is_valid: false,
+            error: Some(e.to_string()),
+            matched: false,
+            groups: Vec::new(),
+            record: sample,
+            modified_fields: Vec::new(),
+        },
+    };
+
+    Ok(Json(result))
 }
 
 /// Validate stream source filter expressions
@@ -1126,10 +1311,13 @@ pub async fn validate_expression(
 // Removed ValidationContext enum (unified validation no longer requires context discriminator)
 
 /// Unified engine-based validation logic: builds union field/alias set for requested domains and returns structured results (includes canonical_expression).
+///
+/// When `lint_source_ids` is set, lint warnings are added for expressions that parsed.
 async fn validate_expression_engine(
-    _state: &AppState,
+    state: &AppState,
     expression: &str,
     domains: &[crate::expression::ExpressionDomain],
+    lint_source_ids: Option<&[Uuid]>,
 ) -> Result<Json<ExpressionValidateResult>, StatusCode> {
     use crate::field_registry::{FieldRegistry, SourceKind, StageKind};
     use std::collections::{HashMap, HashSet};
@@ -1178,12 +1366,127 @@ async fn validate_expression_engine(
             Some(parser.canonicalize_expression_lossy(expression));
     }
 
+    if let Some(source_ids) = lint_source_ids
+        && validation_result.expression_tree.is_some()
+        && let Ok(parsed) = parser.parse_extended(expression)
+    {
+        validation_result.warnings = crate::expression::lint::lint_expression(&parsed);
+
+        if !source_ids.is_empty() {
+            let aliases = registry.alias_map();
+            let fields: std::collections::BTreeSet<String> =
+                crate::expression::lint::referenced_fields(&parsed)
+                    .into_iter()
+                    .map(|f| aliases.get(&f).cloned().unwrap_or(f))
+                    .collect();
+            match always_empty_fields(state, &fields, source_ids).await {
+                Ok(empty) => validation_result.warnings.extend(empty.iter().map(|field| {
+                    crate::expression::lint::empty_field_warning(field, source_ids.len())
+                })),
+                Err(e) => warn!("Skipping empty-field lint: {}", e),
+            }
+        }
+    }
+
     // Record metrics (using global expression module validation metrics)
     crate::expression::record_validation_metrics(domains, start.elapsed());
 
     Ok(Json(validation_result))
 }
 
+/// Fields (from `fields`) without a single non-empty value among the records of `source_ids`
+///
+/// Stream fields are checked against channels and EPG fields against programmes; a table is
+/// only consulted when the selected sources have records in it.
+async fn always_empty_fields(
+    state: &AppState,
+    fields: &std::collections::BTreeSet<String>,
+    source_ids: &[Uuid],
+) -> anyhow::Result<Vec<String>> {
+    use crate::entities::{channels, epg_programs, prelude::*};
+    use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+    let connection = state.database.read_connection();
+    let mut has_value: HashMap<String, bool> = HashMap::new();
+
+    let channel_column = |field: &str| match field {
+        "channel_name" => Some(channels::Column::ChannelName),
+        "group_title" => Some(channels::Column::GroupTitle),
+        "tvg_id" => Some(channels::Column::TvgId),
+        "tvg_name" => Some(channels::Column::TvgName),
+        "tvg_logo" => Some(channels::Column::TvgLogo),
+        "tvg_shift" => Some(channels::Column::TvgShift),
+        "tvg_chno" => Some(channels::Column::TvgChno),
+        "stream_url" => Some(channels::Column::StreamUrl),
+        _ => None,
+    };
+    let channel_fields: Vec<(&String, channels::Column)> = fields
+        .iter()
+        .filter_map(|f| channel_column(f).map(|c| (f, c)))
+        .collect();
+    if !channel_fields.is_empty() {
+        let in_sources = channels::Column::SourceId.is_in(source_ids.iter().copied());
+        let total = Channels::find()
+            .filter(in_sources.clone())
+            .count(&*connection)
+            .await?;
+        if total > 0 {
+            for (field, column) in channel_fields {
+                let non_empty = Channels::find()
+                    .filter(in_sources.clone())
+                    .filter(column.is_not_null())
+                    .filter(column.ne(""))
+                    .count(&*connection)
+                    .await?;
+                *has_value.entry(field.clone()).or_default() |= non_empty > 0;
+            }
+        }
+    }
+
+    let program_column = |field: &str| match field {
+        "channel_id" => Some(epg_programs::Column::ChannelId),
+        "programme_title" => Some(epg_programs::Column::ProgramTitle),
+        "programme_description" => Some(epg_programs::Column::ProgramDescription),
+        "programme_category" => Some(epg_programs::Column::ProgramCategory),
+        "programme_icon" => Some(epg_programs::Column::ProgramIcon),
+        "programme_subtitle" => Some(epg_programs::Column::Subtitles),
+        "episode_num" => Some(epg_programs::Column::EpisodeNum),
+        "season_num" => Some(epg_programs::Column::SeasonNum),
+        "language" => Some(epg_programs::Column::Language),
+        "rating" => Some(epg_programs::Column::Rating),
+        "aspect_ratio" => Some(epg_programs::Column::AspectRatio),
+        _ => None,
+    };
+    let program_fields: Vec<(&String, epg_programs::Column)> = fields
+        .iter()
+        .filter_map(|f| program_column(f).map(|c| (f, c)))
+        .collect();
+    if !program_fields.is_empty() {
+        let in_sources = epg_programs::Column::SourceId.is_in(source_ids.iter().copied());
+        let total = EpgPrograms::find()
+            .filter(in_sources.clone())
+            .count(&*connection)
+            .await?;
+        if total > 0 {
+            for (field, column) in program_fields {
+                let non_empty = EpgPrograms::find()
+                    .filter(in_sources.clone())
+                    .filter(column.is_not_null())
+                    .filter(column.ne(""))
+                    .count(&*connection)
+                    .await?;
+                *has_value.entry(field.clone()).or_default() |= non_empty > 0;
+            }
+        }
+    }
+
+    Ok(fields
+        .iter()
+        .filter(|f| has_value.get(*f) == Some(&false))
+        .cloned()
+        .collect())
+}
+
 // Create parser with field validation enabled
 // Create parser with field validation + alias support (e.g. program_category -> programme_category)
 // (Deleted parser construction from legacy context-based validation)
@@ -1200,117 +1503,162 @@ async fn validate_expression_engine(
     path = "/data-mapping",
     tag = "data-mapping",
     summary = "List data mapping rules",
-    description = "Retrieve all data mapping rules with enhanced metadata",
+    description = "Retrieve data mapping rules with enhanced metadata. Rules are returned in execution order unless `sort_by` is given.",
+    params(
+        DataMappingQueryParams,
+        ("page" = Option<u32>, Query, description = "Page number (1-based)"),
+        ("limit" = Option<u32>, Query, description = "Number of items per page"),
+        ("offset" = Option<u32>, Query, description = "0-based offset; overrides page"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous next_cursor; overrides offset and page"),
+        ("search" = Option<String>, Query, description = "Search term for name, description or expression"),
+        ("sort_by" = Option<String>, Query, description = "Sort field: name, sort_order, created_at, updated_at"),
+        ("sort_ascending" = Option<bool>, Query, description = "Sort direction (default true)"),
+    ),
     responses(
-        (status = 200, description = "List of data mapping rules"),
+        (status = 200, description = "Paginated list of data mapping rules"),
+        (status = 400, description = "Invalid query parameters"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn list_data_mapping_rules(
+    Query(params): Query<DataMappingQueryParams>,
+    list_params: crate::web::ListParams,
     State(state): State<AppState>,
-) -> Result<Json<Vec<serde_json::Value>>, StatusCode> {
+) -> Result<Json<crate::web::PaginatedResponse<serde_json::Value>>, axum::response::Response> {
+    let sort_field = list_params
+        .sort_field(&["name", "sort_order", "created_at", "updated_at"])
+        .map_err(|errors| crate::web::responses::validation_error(errors).into_response())?;
+
     match state.data_mapping_service.get_all_rules().await {
-        Ok(rules) => {
-            let enhanced_rules = rules
-                .into_iter()
-                .map(|rule| {
-                    // Parse expression to get counts
-                    let (condition_count, action_count) = if let Some(expression) = &rule.expression
-                    {
-                        let available_fields = vec![
-                            "tvg_id".to_string(),
-                            "tvg_name".to_string(),
-                            "tvg_logo".to_string(),
-                            "tvg_shift".to_string(),
-                            "group_title".to_string(),
-                            "channel_name".to_string(),
-                        ];
-                        let parser = crate::expression_parser::ExpressionParser::new()
-                            .with_fields(available_fields);
-                        if let Ok(parsed) = parser.parse_extended(expression) {
-                            match parsed {
-                                crate::models::ExtendedExpression::ConditionOnly(
-                                    condition_tree,
-                                ) => (count_conditions_in_tree(&condition_tree), 0),
-                                crate::models::ExtendedExpression::ConditionWithActions {
-                                    condition,
-                                    actions,
-                                } => (count_conditions_in_tree(&condition), actions.len()),
-                                crate::models::ExtendedExpression::ConditionalActionGroups(
-                                    groups,
-                                ) => {
-                                    let condition_count: usize = groups
-                                        .iter()
-                                        .map(|g| count_conditions_in_tree(&g.conditions))
-                                        .sum();
-                                    let action_count: usize =
-                                        groups.iter().map(|g| g.actions.len()).sum();
-                                    (condition_count, action_count)
-                                }
+        Ok(mut rules) => {
+            if let Some(source_type) = &params.source_type {
+                rules.retain(|rule| &rule.source_type == source_type);
+            }
+            if let Some(is_active) = params.is_active {
+                rules.retain(|rule| rule.is_active == is_active);
+            }
+            list_params.apply_search(&mut rules, |rule, term| {
+                rule.name.to_lowercase().contains(term)
+                    || rule
+                        .description
+                        .as_deref()
+                        .is_some_and(|d| d.to_lowercase().contains(term))
+                    || rule
+                        .expression
+                        .as_deref()
+                        .is_some_and(|e| e.to_lowercase().contains(term))
+            });
+            match sort_field {
+                Some("name") => rules.sort_by(|a, b| {
+                    list_params.direction(a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                }),
+                Some("sort_order") => {
+                    rules.sort_by(|a, b| list_params.direction(a.sort_order.cmp(&b.sort_order)))
+                }
+                Some("created_at") => {
+                    rules.sort_by(|a, b| list_params.direction(a.created_at.cmp(&b.created_at)))
+                }
+                Some("updated_at") => {
+                    rules.sort_by(|a, b| list_params.direction(a.updated_at.cmp(&b.updated_at)))
+                }
+                _ => {}
+            }
+
+            let enhanced_rules = list_params.paginate(rules).map_items(|rule| {
+                // Parse expression to get counts
+                let (condition_count, action_count) = if let Some(expression) = &rule.expression {
+                    let available_fields = vec![
+                        "tvg_id".to_string(),
+                        "tvg_name".to_string(),
+                        "tvg_logo".to_string(),
+                        "tvg_shift".to_string(),
+                        "group_title".to_string(),
+                        "channel_name".to_string(),
+                    ];
+                    let parser = crate::expression_parser::ExpressionParser::new()
+                        .with_fields(available_fields);
+                    if let Ok(parsed) = parser.parse_extended(expression) {
+                        match parsed {
+                            crate::models::ExtendedExpression::ConditionOnly(condition_tree) => {
+                                (count_conditions_in_tree(&condition_tree), 0)
+                            }
+                            crate::models::ExtendedExpression::ConditionWithActions {
+                                condition,
+                                actions,
+                            } => (count_conditions_in_tree(&condition), actions.len()),
+                            crate::models::ExtendedExpression::ConditionalActionGroups(groups) => {
+                                let condition_count: usize = groups
+                                    .iter()
+                                    .map(|g| count_conditions_in_tree(&g.conditions))
+                                    .sum();
+                                let action_count: usize =
+                                    groups.iter().map(|g| g.actions.len()).sum();
+                                (condition_count, action_count)
                             }
-                        } else {
-                            (0, 0)
                         }
                     } else {
                         (0, 0)
-                    };
+                    }
+                } else {
+                    (0, 0)
+                };
 
-                    // Generate JSON expression tree for frontend display
-                    let expression_tree = if let Some(expression) = &rule.expression {
-                        let available_fields = vec![
-                            "tvg_id".to_string(),
-                            "tvg_name".to_string(),
-                            "tvg_logo".to_string(),
-                            "tvg_shift".to_string(),
-                            "group_title".to_string(),
-                            "channel_name".to_string(),
-                        ];
-                        let parser = crate::expression_parser::ExpressionParser::new()
-                            .with_fields(available_fields);
-                        if let Ok(parsed) = parser.parse_extended(expression) {
-                            match parsed {
-                                crate::models::ExtendedExpression::ConditionOnly(
-                                    condition_tree,
-                                ) => Some(generate_expression_tree_json(&condition_tree)),
-                                crate::models::ExtendedExpression::ConditionWithActions {
-                                    condition,
-                                    ..
-                                } => Some(generate_expression_tree_json(&condition)),
-                                crate::models::ExtendedExpression::ConditionalActionGroups(
-                                    groups,
-                                ) => groups.first().map(|first_group| {
+                // Generate JSON expression tree for frontend display
+                let expression_tree = if let Some(expression) = &rule.expression {
+                    let available_fields = vec![
+                        "tvg_id".to_string(),
+                        "tvg_name".to_string(),
+                        "tvg_logo".to_string(),
+                        "tvg_shift".to_string(),
+                        "group_title".to_string(),
+                        "channel_name".to_string(),
+                    ];
+                    let parser = crate::expression_parser::ExpressionParser::new()
+                        .with_fields(available_fields);
+                    if let Ok(parsed) = parser.parse_extended(expression) {
+                        match parsed {
+                            crate::models::ExtendedExpression::ConditionOnly(condition_tree) => {
+                                Some(generate_expression_tree_json(&condition_tree))
+                            }
+                            crate::models::ExtendedExpression::ConditionWithActions {
+                                condition,
+                                ..
+                            } => Some(generate_expression_tree_json(&condition)),
+                            crate::models::ExtendedExpression::ConditionalActionGroups(groups) => {
+                                groups.first().map(|first_group| {
                                     generate_expression_tree_json(&first_group.conditions)
-                                }),
+                                })
                             }
-                        } else {
-                            None
                         }
                     } else {
                         None
-                    };
+                    }
+                } else {
+                    None
+                };
 
-                    serde_json::json!({
-                        "id": rule.id,
-                        "name": rule.name,
-                        "description": rule.description,
-                        "source_type": rule.source_type,
-                        "expression": rule.expression,
-                        "sort_order": rule.sort_order,
-                        "is_active": rule.is_active,
-                        "created_at": rule.created_at,
-                        "updated_at": rule.updated_at,
-                        "condition_count": condition_count,
-                        "action_count": action_count,
-                        "expression_tree": expression_tree,
-                    })
+                serde_json::json!({
+                    "id": rule.id,
+                    "name": rule.name,
+                    "description": rule.description,
+                    "source_type": rule.source_type,
+                    "expression": rule.expression,
+                    "sort_order": rule.sort_order,
+                    "is_active": rule.is_active,
+                    "scope": rule.scope,
+                    "created_at": rule.created_at,
+                    "updated_at": rule.updated_at,
+                    "condition_count": condition_count,
+                    "action_count": action_count,
+                    "expression_tree": expression_tree,
                 })
-                .collect();
+            });
 
             Ok(Json(enhanced_rules))
         }
         Err(e) => {
             error!("Failed to list data mapping rules: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
         }
     }
 }
@@ -1320,7 +1668,7 @@ pub async fn list_data_mapping_rules(
     path = "/data-mapping",
     tag = "data-mapping",
     summary = "Create data mapping rule",
-    description = "Create a new data mapping rule for transforming channel metadata",
+    description = "Create a new data mapping rule for transforming channel metadata. `scope` binds the rule to one stream source (`{\"type\": \"source\", \"source_id\": ...}`) or proxy (`{\"type\": \"proxy\", \"proxy_id\": ...}`); rules default to global. Rules run global, then source, then proxy scoped, each group by sort order.",
     responses(
         (status = 200, description = "Data mapping rule created successfully"),
         (status = 500, description = "Internal server error")
@@ -1328,10 +1676,24 @@ pub async fn list_data_mapping_rules(
 )]
 pub async fn create_data_mapping_rule(
     State(state): State<AppState>,
+    context: RequestContext,
     Json(payload): Json<crate::models::data_mapping::DataMappingRuleCreateRequest>,
 ) -> Result<Json<crate::models::data_mapping::DataMappingRule>, StatusCode> {
     match state.data_mapping_service.create_rule(payload).await {
-        Ok(rule) => Ok(Json(rule)),
+        Ok(rule) => {
+            record_rule_version(
+                &state,
+                RuleKind::DataMappingRule,
+                rule.id,
+                None,
+                &RuleSnapshot::from(&rule),
+                RuleChange::Created,
+                &context,
+            )
+            .await;
+            queue_epg_remap_if_needed(&state, &rule).await;
+            Ok(Json(rule))
+        }
         Err(e) => {
             error!("Failed to create data mapping rule: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -1389,10 +1751,39 @@ pub async fn get_data_mapping_rule(
 pub async fn update_data_mapping_rule(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    context: RequestContext,
     Json(payload): Json<crate::models::data_mapping::DataMappingRuleUpdateRequest>,
 ) -> Result<Json<crate::models::data_mapping::DataMappingRule>, StatusCode> {
+    let previous_rule = match state.data_mapping_service.get_rule_with_details(id).await {
+        Ok(rule) => rule,
+        Err(e) => {
+            warn!(
+                "Failed to load data mapping rule {} before update: {}",
+                id, e
+            );
+            None
+        }
+    };
+    let previous = previous_rule.as_ref().map(RuleSnapshot::from);
     match state.data_mapping_service.update_rule(id, payload).await {
-        Ok(rule) => Ok(Json(rule)),
+        Ok(rule) => {
+            record_rule_version(
+                &state,
+                RuleKind::DataMappingRule,
+                id,
+                previous.as_ref(),
+                &RuleSnapshot::from(&rule),
+                RuleChange::Updated,
+                &context,
+            )
+            .await;
+            // The old version may have mapped tvg_id for proxies the new one no longer covers
+            if let Some(previous_rule) = &previous_rule {
+                queue_epg_remap_if_needed(&state, previous_rule).await;
+            }
+            queue_epg_remap_if_needed(&state, &rule).await;
+            Ok(Json(rule))
+        }
         Err(e) => {
             error!("Failed to update data mapping rule {}: {}", id, e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -1405,7 +1796,7 @@ pub async fn update_data_mapping_rule(
     path = "/data-mapping/{id}",
     tag = "data-mapping",
     summary = "Delete data mapping rule",
-    description = "Delete a data mapping rule",
+    description = "Move a data mapping rule to the trash; it is no longer applied until restored via `/trash`",
     params(
         ("id" = String, Path, description = "Data mapping rule ID (UUID)"),
     ),
@@ -1419,8 +1810,19 @@ pub async fn delete_data_mapping_rule(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
 ) -> Result<StatusCode, StatusCode> {
+    let rule = state
+        .data_mapping_service
+        .get_rule_with_details(id)
+        .await
+        .ok()
+        .flatten();
     match state.data_mapping_service.delete_rule(id).await {
-        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Ok(_) => {
+            if let Some(rule) = &rule {
+                queue_epg_remap_if_needed(&state, rule).await;
+            }
+            Ok(StatusCode::NO_CONTENT)
+        }
         Err(e) => {
             error!("Failed to delete data mapping rule {}: {}", id, e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -1450,8 +1852,17 @@ pub async fn reorder_data_mapping_rules(
         return Err(StatusCode::BAD_REQUEST);
     }
 
+    let rule_ids: Vec<Uuid> = payload.iter().map(|(id, _)| *id).collect();
     match state.data_mapping_service.reorder_rules(payload).await {
-        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Ok(_) => {
+            // Reordering changes which rule has the last word on tvg_id
+            for id in rule_ids {
+                if let Ok(Some(rule)) = state.data_mapping_service.get_rule_with_details(id).await {
+                    queue_epg_remap_if_needed(&state, &rule).await;
+                }
+            }
+            Ok(StatusCode::NO_CONTENT)
+        }
         Err(e) => {
             error!("Failed to reorder data mapping rules: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -1459,6 +1870,19 @@ pub async fn reorder_data_mapping_rules(
     }
 }
 
+/// Re-map the EPG of proxies whose channels' EPG identity a rule change may have moved
+async fn queue_epg_remap_if_needed(
+    state: &AppState,
+    rule: &crate::models::data_mapping::DataMappingRule,
+) {
+    if rule.touches_epg_identity() {
+        state
+            .proxy_regeneration_service
+            .queue_epg_remap_for_rule(rule)
+            .await;
+    }
+}
+
 /// Generalized validation endpoint for any pipeline stage
 #[utoipa::path(
     post,
@@ -3793,6 +4217,20 @@ async fn list_logo_assets_with_cached(
     })
 }
 
+/// Read an uploaded logo, rejecting it as soon as it grows beyond the logo size limit
+async fn read_logo_field(
+    field: &mut axum::extract::multipart::Field<'_>,
+) -> Result<Vec<u8>, StatusCode> {
+    use crate::web::uploads::{UploadError, read_field};
+
+    read_field(field, crate::services::logo_bulk_upload::MAX_LOGO_BYTES)
+        .await
+        .map_err(|e| match e {
+            UploadError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::BAD_REQUEST,
+        })
+}
+
 /// Upload a new logo asset
 #[utoipa::path(
     post,
@@ -3804,6 +4242,7 @@ async fn list_logo_assets_with_cached(
     responses(
         (status = 200, description = "Logo asset uploaded successfully"),
         (status = 400, description = "Invalid upload data"),
+        (status = 413, description = "Logo larger than 5 MiB"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -3813,12 +4252,12 @@ pub async fn upload_logo_asset(
 ) -> Result<Json<crate::models::logo_asset::LogoAssetUploadResponse>, StatusCode> {
     use crate::models::logo_asset::{LogoAssetCreateRequest, LogoAssetUploadResponse};
 
-    let mut file_data: Option<(String, String, axum::body::Bytes)> = None;
+    let mut file_data: Option<(String, String, Vec<u8>)> = None;
     let mut logo_name: Option<String> = None;
     let mut logo_description: Option<String> = None;
 
     // Process all multipart fields
-    while let Some(field) = multipart
+    while let Some(mut field) = multipart
         .next_field()
         .await
         .map_err(|_| StatusCode::BAD_REQUEST)?
@@ -3835,13 +4274,13 @@ pub async fn upload_logo_asset(
                     .unwrap_or("application/octet-stream")
                     .to_string();
 
-                let data = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?;
-
                 // Validate file type
                 if !content_type.starts_with("image/") {
                     return Err(StatusCode::BAD_REQUEST);
                 }
 
+                let data = read_logo_field(&mut field).await?;
+
                 file_data = Some((file_name, content_type, data));
             }
             Some("name") => {
@@ -3890,7 +4329,7 @@ pub async fn upload_logo_asset(
     match state
         .logo_asset_service
         .storage
-        .save_uploaded_file(data.to_vec(), asset_id, file_extension)
+        .save_uploaded_file(data, asset_id, file_extension)
         .await
     {
         Ok((file_name, file_path, file_size, mime_type, dimensions)) => {
@@ -4232,6 +4671,7 @@ pub async fn update_logo_asset(
     responses(
         (status = 200, description = "Logo image replaced successfully"),
         (status = 400, description = "Invalid file or missing data"),
+        (status = 413, description = "Logo larger than 5 MiB"),
         (status = 404, description = "Logo asset not found"),
         (status = 500, description = "Internal server error")
     )
@@ -4241,12 +4681,12 @@ pub async fn replace_logo_asset_image(
     State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<Json<crate::models::logo_asset::LogoAsset>, StatusCode> {
-    let mut file_data: Option<(String, String, axum::body::Bytes)> = None;
+    let mut file_data: Option<(String, String, Vec<u8>)> = None;
     let mut logo_name: Option<String> = None;
     let mut logo_description: Option<String> = None;
 
     // Process all multipart fields
-    while let Some(field) = multipart
+    while let Some(mut field) = multipart
         .next_field()
         .await
         .map_err(|_| StatusCode::BAD_REQUEST)?
@@ -4261,7 +4701,7 @@ pub async fn replace_logo_asset_image(
                     .content_type()
                     .ok_or(StatusCode::BAD_REQUEST)?
                     .to_string();
-                let data = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?;
+                let data = read_logo_field(&mut field).await?;
                 file_data = Some((file_name, content_type, data));
             }
             Some("name") => {
@@ -4297,7 +4737,7 @@ pub async fn replace_logo_asset_image(
     match state
         .logo_asset_service
         .storage
-        .save_uploaded_file(data.to_vec(), id, file_extension)
+        .save_uploaded_file(data, id, file_extension)
         .await
     {
         Ok((_, new_file_path, new_file_size, new_mime_type, dimensions)) => {
@@ -4486,72 +4926,37 @@ pub async fn delete_logo_asset(
     ),
     responses(
         (status = 200, description = "Cached logo image data", content_type = "image/*"),
+        (status = 206, description = "Partial cached logo image data (Range request)", content_type = "image/*"),
+        (status = 304, description = "Not modified (ETag matched)"),
         (status = 404, description = "Cached logo not found"),
+        (status = 416, description = "Requested range not satisfiable"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn get_cached_logo_asset(
     Path(cache_id): Path<String>,
     State(state): State<AppState>,
-) -> Result<(axum::http::HeaderMap, Vec<u8>), StatusCode> {
-    // The file manager's base is already the cached logo directory, so use direct paths
-    let mut file_extension = "png".to_string();
-    let file_data = match state
-        .logo_file_manager
-        .read(&format!("{cache_id}.png"))
-        .await
-    {
-        Ok(data) => {
-            debug!("Serving normalized cached logo: {}.png", cache_id);
-            data
-        }
-        Err(_) => {
-            // Fall back to legacy format with other extensions
-            let mut found_data = None;
-            for ext in &["jpg", "jpeg", "gif", "webp", "svg"] {
-                match state
-                    .logo_file_manager
-                    .read(&format!("{cache_id}.{ext}"))
-                    .await
-                {
-                    Ok(data) => {
-                        file_extension = ext.to_string();
-                        found_data = Some(data);
-                        debug!("Serving legacy cached logo: {}.{}", cache_id, ext);
-                        break;
-                    }
-                    Err(_) => { /* ignore missing legacy extension and try next */ }
-                }
-            }
-            found_data.ok_or_else(|| {
-                debug!("Cached logo not found: {}", cache_id);
-                StatusCode::NOT_FOUND
-            })?
+    request_headers: axum::http::HeaderMap,
+) -> axum::response::Response {
+    // The file manager's base is already the cached logo directory, so use direct paths.
+    // Prefer the normalized PNG, falling back to legacy formats with other extensions.
+    for ext in &["png", "jpg", "jpeg", "gif", "webp", "svg"] {
+        let file_name = format!("{cache_id}.{ext}");
+        if matches!(state.logo_file_manager.exists(&file_name).await, Ok(true)) {
+            debug!("Serving cached logo: {}", file_name);
+            return crate::web::file_serving::serve_sandboxed_file(
+                &state.logo_file_manager,
+                &file_name,
+                None,
+                "public, max-age=2592000", // 30 days cache
+                &request_headers,
+            )
+            .await;
         }
-    };
-
-    // Determine content type from extension
-    let content_type = match file_extension.as_str() {
-        "png" => "image/png",
-        "jpg" | "jpeg" => "image/jpeg",
-        "gif" => "image/gif",
-        "webp" => "image/webp",
-        "svg" => "image/svg+xml",
-        _ => "application/octet-stream",
-    };
-
-    // Set response headers
-    let mut headers = axum::http::HeaderMap::new();
-    headers.insert(
-        axum::http::header::CONTENT_TYPE,
-        content_type.parse().unwrap(),
-    );
-    headers.insert(
-        axum::http::header::CACHE_CONTROL,
-        "public, max-age=2592000".parse().unwrap(), // 30 days cache
-    );
+    }
 
-    Ok((headers, file_data))
+    debug!("Cached logo not found: {}", cache_id);
+    StatusCode::NOT_FOUND.into_response()
 }
 
 /// Legacy health check endpoint
@@ -4978,6 +5383,77 @@ pub async fn list_all_sources(
     Ok(Json(unified_sources))
 }
 
+/// Get ingestion history of a source
+#[utoipa::path(
+    get,
+    path = "/sources/{id}/history",
+    tag = "sources",
+    summary = "Get source ingestion history",
+    description = "Retrieve the recorded ingestion runs of a stream or EPG source, newest first, with duration, bytes downloaded, records added/updated/removed and errors",
+    params(
+        ("id" = String, Path, description = "Stream or EPG source ID (UUID)"),
+        ("page" = Option<u32>, Query, description = "Page number (1-based)"),
+        ("limit" = Option<u32>, Query, description = "Runs per page (1-1000)"),
+    ),
+    responses(
+        (status = 200, description = "Page of ingestion runs", body = crate::web::responses::PaginatedResponse<crate::models::ingestion_run::IngestionRun>),
+        (status = 400, description = "Invalid source ID or pagination parameters"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_source_history(
+    Path(id): Path<Uuid>,
+    pagination: crate::web::PaginationParams,
+    State(state): State<AppState>,
+) -> Result<
+    Json<crate::web::responses::PaginatedResponse<crate::models::ingestion_run::IngestionRun>>,
+    StatusCode,
+> {
+    let connection = state.database.connection().clone();
+    let stream_source =
+        crate::database::repositories::StreamSourceSeaOrmRepository::new(connection.clone())
+            .find_by_id(&id)
+            .await;
+    let exists = match stream_source {
+        Ok(Some(_)) => true,
+        Ok(None) => {
+            crate::database::repositories::EpgSourceSeaOrmRepository::new(connection.clone())
+                .find_by_id(&id)
+                .await
+                .map_err(|e| {
+                    error!("Failed to look up EPG source {}: {}", id, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+                .is_some()
+        }
+        Err(e) => {
+            error!("Failed to look up stream source {}: {}", id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if !exists {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let history = crate::database::repositories::IngestionRunSeaOrmRepository::new(connection);
+    match history
+        .list_for_source(&id, pagination.offset() as u64, pagination.limit as u64)
+        .await
+    {
+        Ok((runs, total)) => Ok(Json(crate::web::responses::PaginatedResponse::new(
+            runs,
+            total,
+            pagination.page,
+            pagination.limit,
+        ))),
+        Err(e) => {
+            error!("Failed to load ingestion history of source {}: {}", id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 /// Search logo assets
 #[utoipa::path(
     get,
@@ -5605,9 +6081,20 @@ pub async fn get_dashboard_metrics(
 ) -> Result<Json<DashboardMetrics>, StatusCode> {
     // Get total channels across all proxies - rationalized to SeaORM
     use crate::entities::prelude::{Channels, StreamProxies};
-    use sea_orm::{EntityTrait, PaginatorTrait};
-
-    let total_channels = match Channels::find().count(&*state.database.connection()).await {
+    use crate::entities::stream_proxies;
+    use crate::services::CachedEntity;
+    use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+    let connection = state.database.connection();
+    let total_channels = match state
+        .query_cache
+        .get_or_load(
+            "dashboard_total_channels",
+            &[CachedEntity::StreamSources],
+            || Channels::find().count(&*connection),
+        )
+        .await
+    {
         Ok(count) => count,
         Err(e) => {
             error!("Failed to get total channels: {}", e);
@@ -5616,8 +6103,13 @@ pub async fn get_dashboard_metrics(
     };
 
     // Get total proxies - rationalized to SeaORM
-    let total_proxies = match StreamProxies::find()
-        .count(&*state.database.connection())
+    let total_proxies = match state
+        .query_cache
+        .get_or_load("dashboard_total_proxies", &[CachedEntity::Proxies], || {
+            StreamProxies::find()
+                .filter(stream_proxies::Column::DeletedAt.is_null())
+                .count(&*connection)
+        })
         .await
     {
         Ok(count) => count,
@@ -5638,6 +6130,38 @@ pub async fn get_dashboard_metrics(
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics/query-cache",
+    tag = "metrics",
+    summary = "Get query cache statistics",
+    description = "Hits, misses and invalidations of the in-memory cache of source lists and dashboard totals",
+    responses(
+        (status = 200, description = "Query cache statistics", body = crate::services::query_cache::QueryCacheStats)
+    )
+)]
+pub async fn get_query_cache_stats(
+    State(state): State<AppState>,
+) -> Json<crate::services::query_cache::QueryCacheStats> {
+    Json(state.query_cache.stats().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics/stream-latency",
+    tag = "metrics",
+    summary = "Get stream start latency",
+    description = "Time to first byte of recent stream sessions per proxy, as percentiles overall and per phase (setup, upstream connect, relay spawn, buffer fill), with the number of sessions over the configured latency budget. Slowest proxies first.",
+    responses(
+        (status = 200, description = "Stream start latency per proxy", body = crate::proxy::stream_latency::StreamLatencyReport)
+    )
+)]
+pub async fn get_stream_latency(
+    State(state): State<AppState>,
+) -> Json<crate::proxy::stream_latency::StreamLatencyReport> {
+    Json(state.stream_latency.report())
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/metrics/realtime",
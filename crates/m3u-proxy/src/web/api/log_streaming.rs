@@ -51,7 +51,7 @@ pub struct SpanInfo {
 }
 
 /// Query parameters for log streaming
-#[derive(Debug, Deserialize, IntoParams)]
+#[derive(Debug, Clone, Deserialize, IntoParams)]
 pub struct LogStreamParams {
     /// Minimum log level to stream (default: INFO)
     #[serde(default = "default_log_level")]
@@ -78,6 +78,35 @@ fn default_include_spans() -> bool {
     true
 }
 
+impl LogStreamParams {
+    /// Whether `level` names a known log level
+    pub fn has_valid_level(&self) -> bool {
+        LogLevel::from_str(&self.level).is_some()
+    }
+
+    /// Apply the level and target filters and strip the parts not asked for
+    pub fn apply(&self, mut event: LogEvent) -> Option<LogEvent> {
+        let min_level = LogLevel::from_str(&self.level).unwrap_or(LogLevel::Info);
+        if let Some(event_level) = LogLevel::from_str(&event.level)
+            && event_level < min_level
+        {
+            return None;
+        }
+        if let Some(ref target_filter) = self.target
+            && !event.target.contains(target_filter)
+        {
+            return None;
+        }
+        if !self.include_fields {
+            event.fields.clear();
+        }
+        if !self.include_spans {
+            event.span = None;
+        }
+        Some(event)
+    }
+}
+
 /// Log level enum for filtering
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum LogLevel {
@@ -125,12 +154,9 @@ pub async fn stream_logs(
     debug!("Starting log stream with params: {:?}", params);
 
     // Validate log level parameter
-    let min_level = match LogLevel::from_str(&params.level) {
-        Some(level) => level,
-        None => {
-            return Err(axum::http::StatusCode::BAD_REQUEST);
-        }
-    };
+    if !params.has_valid_level() {
+        return Err(axum::http::StatusCode::BAD_REQUEST);
+    }
 
     // Get or create the log broadcast receiver
     let log_receiver = match state.log_broadcaster.as_ref() {
@@ -148,28 +174,7 @@ pub async fn stream_logs(
             Err(_) => return None, // Skip lagged events
         };
 
-        // Apply log level filtering
-        if let Some(event_level) = LogLevel::from_str(&event.level)
-            && event_level < min_level
-        {
-            return None;
-        }
-
-        // Apply target filtering
-        if let Some(ref target_filter) = params.target
-            && !event.target.contains(target_filter)
-        {
-            return None;
-        }
-
-        // Optionally strip fields/spans based on parameters
-        let mut filtered_event = event;
-        if !params.include_fields {
-            filtered_event.fields.clear();
-        }
-        if !params.include_spans {
-            filtered_event.span = None;
-        }
+        let filtered_event = params.apply(event)?;
 
         // Create SSE event
         let sse_event = match Event::default()
@@ -15,7 +15,7 @@ use std::time::Duration;
 use tracing::{debug, error};
 use utoipa::{IntoParams, ToSchema};
 
-use crate::services::progress_service::{OperationType, UniversalState};
+use crate::services::progress_service::{OperationType, UniversalProgress, UniversalState};
 use crate::web::AppState;
 
 /// Convert operation type enum to lowercase string
@@ -51,7 +51,7 @@ fn universal_state_to_string(state: &UniversalState) -> String {
 }
 
 /// Query parameters for progress event filtering
-#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
 pub struct ProgressEventQuery {
     /// Filter by operation type (e.g., "stream_ingestion", "epg_ingestion", "proxy_regeneration")
     pub operation_type: Option<String>,
@@ -65,6 +65,39 @@ pub struct ProgressEventQuery {
     pub active_only: Option<bool>,
 }
 
+impl ProgressEventQuery {
+    /// Whether a progress update passes these filters
+    pub fn matches(&self, progress: &UniversalProgress) -> bool {
+        if let Some(ref op_type) = self.operation_type
+            && operation_type_to_string(&progress.operation_type) != op_type.to_lowercase()
+        {
+            return false;
+        }
+        if let Some(ref state_filter) = self.state
+            && universal_state_to_string(&progress.state) != state_filter.to_lowercase()
+        {
+            return false;
+        }
+        // Support both resource_id and owner_id (for compatibility)
+        if let Some(ref resource_id) = self.resource_id
+            && progress.owner_id.to_string() != *resource_id
+        {
+            return false;
+        }
+        if let Some(ref owner_id) = self.owner_id
+            && progress.owner_id.to_string() != *owner_id
+        {
+            return false;
+        }
+        // Filter by completion status
+        !(self.active_only.unwrap_or(false)
+            && matches!(
+                progress.state,
+                UniversalState::Completed | UniversalState::Error | UniversalState::Cancelled
+            ))
+    }
+}
+
 /// Stage information for progress events
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProgressStageEvent {
@@ -112,6 +145,39 @@ pub struct ProgressEvent {
     pub error: Option<String>,
 }
 
+impl From<UniversalProgress> for ProgressEvent {
+    fn from(progress: UniversalProgress) -> Self {
+        let stages = progress
+            .stages
+            .iter()
+            .map(|stage| ProgressStageEvent {
+                id: stage.id.clone(),
+                name: stage.name.clone(),
+                percentage: stage.percentage,
+                state: universal_state_to_string(&stage.state),
+                stage_step: stage.stage_step.clone(),
+            })
+            .collect();
+
+        // Include id in JSON data for consistency with UI expectations
+        Self {
+            id: Some(progress.id.to_string()),
+            owner_id: progress.owner_id.to_string(),
+            owner_type: progress.owner_type,
+            operation_type: operation_type_to_string(&progress.operation_type),
+            operation_name: progress.operation_name,
+            state: universal_state_to_string(&progress.state),
+            current_stage: progress.current_stage,
+            overall_percentage: progress.overall_percentage,
+            stages,
+            started_at: progress.started_at.to_rfc3339(),
+            last_update: progress.last_update.to_rfc3339(),
+            completed_at: progress.completed_at.map(|dt| dt.to_rfc3339()),
+            error: progress.error_message,
+        }
+    }
+}
+
 /// Stream real-time progress events via SSE  
 ///
 /// This endpoint provides real-time progress updates only. Use GET /progress/operations
@@ -161,81 +227,18 @@ pub async fn progress_events_stream(
                               universal_state_to_string(&progress.state));
                     }
 
-                    // Apply filters (matching original logic)
-                    if let Some(ref op_type) = query.operation_type {
-                        let progress_op_type = operation_type_to_string(&progress.operation_type);
-                        if progress_op_type != op_type.to_lowercase() {
-                            debug!("SSE filtering out progress update: type {} doesn't match filter {}",
-                                  progress_op_type, op_type);
-                            continue;
-                        }
+                    if !query.matches(&progress) {
+                        continue;
                     }
-
-                    if let Some(ref state_filter) = query.state {
-                        let state_str = universal_state_to_string(&progress.state);
-                        if state_str != state_filter.to_lowercase() {
-                            continue;
-                        }
-                    }
-
-                    // Support both resource_id and owner_id (for compatibility)
-                    if let Some(ref resource_id) = query.resource_id
-                        && progress.owner_id.to_string() != *resource_id {
-                            continue;
-                        }
-
-                    if let Some(ref owner_id) = query.owner_id
-                        && progress.owner_id.to_string() != *owner_id {
-                            continue;
-                        }
-
-                    // Filter by completion status
-                    if query.active_only.unwrap_or(false) {
-                        use crate::services::progress_service::UniversalState;
-                        match progress.state {
-                            UniversalState::Completed | UniversalState::Error | UniversalState::Cancelled => {
-                                continue;
-                            }
-                            _ => {}
-                        }
-                    }
-
-
-                    // Convert stages from UniversalProgress to ProgressStageEvent
-                    let stages: Vec<ProgressStageEvent> = progress.stages.iter().map(|stage| {
-                        ProgressStageEvent {
-                            id: stage.id.clone(),
-                            name: stage.name.clone(),
-                            percentage: stage.percentage,
-                            state: universal_state_to_string(&stage.state),
-                            stage_step: stage.stage_step.clone(),
-                        }
-                    }).collect();
-
-                    // Convert UniversalProgress to ProgressEvent for SSE (matching original format)
-                    // Include id in JSON data for consistency with UI expectations
-                    let event = ProgressEvent {
-                        id: Some(progress.id.to_string()),
-                        owner_id: progress.owner_id.to_string(),
-                        owner_type: progress.owner_type,
-                        operation_type: operation_type_to_string(&progress.operation_type),
-                        operation_name: progress.operation_name,
-                        state: universal_state_to_string(&progress.state),
-                        current_stage: progress.current_stage,
-                        overall_percentage: progress.overall_percentage,
-                        stages,
-                        started_at: progress.started_at.to_rfc3339(),
-                        last_update: progress.last_update.to_rfc3339(),
-                        completed_at: progress.completed_at.map(|dt| dt.to_rfc3339()),
-                        error: progress.error_message,
-                    };
+                    let progress_id = progress.id;
+                    let event = ProgressEvent::from(progress);
 
                     // Serialize to JSON for SSE
                     match serde_json::to_string(&event) {
                         Ok(json) => {
                             yield Ok::<Event, axum::Error>(Event::default()
                                 .event("progress")  // Use "progress" event type to match original
-                                .id(progress.id.to_string())
+                                .id(progress_id.to_string())
                                 .data(json));
                         }
                         Err(e) => {
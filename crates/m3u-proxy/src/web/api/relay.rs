@@ -5,19 +5,22 @@
 
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     routing::get,
 };
+use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::models::relay::*;
+use crate::services::relay_logs::{RelayLogLevel, RelayLogSnapshot};
 use crate::web::AppState;
+use crate::web::extractors::ListParams;
 use crate::web::handlers::health::{
     check_ffmpeg_availability, check_ffprobe_availability, check_hardware_acceleration,
 };
-use crate::web::responses::RelayHealthApiResponse;
+use crate::web::responses::{RelayHealthApiResponse, validation_error};
 
 /// Create relay API routes
 pub fn relay_routes() -> Router<AppState> {
@@ -30,6 +33,26 @@ pub fn relay_routes() -> Router<AppState> {
         )
         // System monitoring
         .route("/relay/health", get(get_relay_health))
+        .route("/relays/{id}/logs", get(get_relay_logs))
+}
+
+/// Query parameters for relay log retrieval
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct RelayLogsQuery {
+    /// Minimum level: `info` (default), `warning` or `error`
+    #[serde(default)]
+    pub level: RelayLogLevel,
+    /// Return only the newest N matching lines
+    pub limit: Option<usize>,
+}
+
+/// Query parameters for relay profile listing
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct RelayProfilesQuery {
+    /// Only profiles with (or without) hardware acceleration enabled
+    pub hardware_acceleration: Option<bool>,
+    /// Only system default (or user-defined) profiles
+    pub is_system_default: Option<bool>,
 }
 
 /// List all relay profiles
@@ -38,18 +61,65 @@ pub fn relay_routes() -> Router<AppState> {
     path = "/relay/profiles",
     tag = "relay",
     summary = "List relay profiles",
-    description = "Retrieve all relay profiles for stream transcoding",
+    description = "Retrieve active relay profiles for stream transcoding",
+    params(
+        RelayProfilesQuery,
+        ("page" = Option<u32>, Query, description = "Page number (1-based)"),
+        ("limit" = Option<u32>, Query, description = "Number of items per page"),
+        ("offset" = Option<u32>, Query, description = "0-based offset; overrides page"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous next_cursor; overrides offset and page"),
+        ("search" = Option<String>, Query, description = "Search term for name or description"),
+        ("sort_by" = Option<String>, Query, description = "Sort field: name, created_at, updated_at"),
+        ("sort_ascending" = Option<bool>, Query, description = "Sort direction (default true)"),
+    ),
     responses(
-        (status = 200, description = "List of relay profiles"),
+        (status = 200, description = "Paginated list of relay profiles"),
+        (status = 400, description = "Invalid query parameters"),
         (status = 500, description = "Internal server error")
     )
 )]
-pub async fn list_profiles(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn list_profiles(
+    State(state): State<AppState>,
+    Query(query): Query<RelayProfilesQuery>,
+    list_params: ListParams,
+) -> impl IntoResponse {
+    let sort_field = match list_params.sort_field(&["name", "created_at", "updated_at"]) {
+        Ok(field) => field,
+        Err(errors) => return validation_error(errors).into_response(),
+    };
+
     let relay_repo = crate::database::repositories::RelaySeaOrmRepository::new(
         state.database.connection().clone(),
     );
     match relay_repo.get_active_profiles().await {
-        Ok(profiles) => Json(profiles).into_response(),
+        Ok(mut profiles) => {
+            if let Some(hwaccel) = query.hardware_acceleration {
+                profiles.retain(|profile| profile.enable_hardware_acceleration == hwaccel);
+            }
+            if let Some(is_system_default) = query.is_system_default {
+                profiles.retain(|profile| profile.is_system_default == is_system_default);
+            }
+            list_params.apply_search(&mut profiles, |profile, term| {
+                profile.name.to_lowercase().contains(term)
+                    || profile
+                        .description
+                        .as_deref()
+                        .is_some_and(|d| d.to_lowercase().contains(term))
+            });
+            match sort_field {
+                Some("name") => profiles.sort_by(|a, b| {
+                    list_params.direction(a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                }),
+                Some("created_at") => {
+                    profiles.sort_by(|a, b| list_params.direction(a.created_at.cmp(&b.created_at)))
+                }
+                Some("updated_at") => {
+                    profiles.sort_by(|a, b| list_params.direction(a.updated_at.cmp(&b.updated_at)))
+                }
+                _ => {}
+            }
+            Json(list_params.paginate(profiles)).into_response()
+        }
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Repository error: {e}"),
@@ -106,6 +176,14 @@ pub async fn create_profile(
     State(state): State<AppState>,
     Json(request): Json<CreateRelayProfileRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = validate_subtitle_settings(
+        &request.output_format,
+        request.subtitle_mode.unwrap_or_default(),
+        request.subtitle_codec,
+    ) {
+        return (StatusCode::BAD_REQUEST, e).into_response();
+    }
+
     // Create the profile using repository
     let relay_repo = crate::database::repositories::RelaySeaOrmRepository::new(
         state.database.connection().clone(),
@@ -145,6 +223,36 @@ pub async fn update_profile(
     let relay_repo = crate::database::repositories::RelaySeaOrmRepository::new(
         state.database.connection().clone(),
     );
+
+    // Subtitle settings are validated against the profile as it will be after the update
+    if request.subtitle_mode.is_some()
+        || request.subtitle_codec.is_some()
+        || request.output_format.is_some()
+    {
+        match relay_repo.find_by_id(id).await {
+            Ok(Some(existing)) => {
+                if let Err(e) = validate_subtitle_settings(
+                    request
+                        .output_format
+                        .as_ref()
+                        .unwrap_or(&existing.output_format),
+                    request.subtitle_mode.unwrap_or(existing.subtitle_mode),
+                    request.subtitle_codec.or(existing.subtitle_codec),
+                ) {
+                    return (StatusCode::BAD_REQUEST, e).into_response();
+                }
+            }
+            Ok(None) => return (StatusCode::NOT_FOUND, "Profile not found").into_response(),
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Repository error: {e}"),
+                )
+                    .into_response();
+            }
+        }
+    }
+
     match relay_repo.update(id, request).await {
         Ok(profile) => Json(profile).into_response(),
         Err(e) => {
@@ -271,6 +379,7 @@ pub async fn get_relay_health(State(state): State<AppState>) -> impl IntoRespons
                 ffprobe_version,
                 hwaccel_available,
                 hwaccel_capabilities,
+                transcode_devices: relay_health.transcode_devices,
             };
 
             Json(dashboard_response).into_response()
@@ -305,8 +414,40 @@ pub async fn get_relay_health(State(state): State<AppState>) -> impl IntoRespons
                 ffprobe_version,
                 hwaccel_available,
                 hwaccel_capabilities,
+                transcode_devices: state.relay_manager.transcode_utilization(),
             };
             Json(dashboard_response).into_response()
         }
     }
 }
+
+/// Get captured FFmpeg output of a relay
+#[utoipa::path(
+    get,
+    path = "/relays/{id}/logs",
+    tag = "relay",
+    summary = "Get relay FFmpeg logs",
+    description = "Retrieve the FFmpeg output captured for a relay process, filtered by minimum level. Output of the last process is kept after it exits until the relay restarts.",
+    params(
+        ("id" = String, Path, description = "Relay configuration ID (UUID)"),
+        RelayLogsQuery
+    ),
+    responses(
+        (status = 200, description = "Captured FFmpeg output", body = RelayLogSnapshot),
+        (status = 404, description = "No output captured for this relay")
+    )
+)]
+pub async fn get_relay_logs(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RelayLogsQuery>,
+) -> impl IntoResponse {
+    match state
+        .relay_manager
+        .get_relay_logs(id, query.level, query.limit)
+        .await
+    {
+        Some(snapshot) => Json(snapshot).into_response(),
+        None => (StatusCode::NOT_FOUND, "No output captured for this relay").into_response(),
+    }
+}
@@ -187,6 +187,12 @@ pub struct UpdateJobSchedulingRequest {
     pub proxy_regeneration_limit: Option<usize>,
     /// Maintenance job concurrency limit (optional)
     pub maintenance_limit: Option<usize>,
+    /// Ingestion class (stream + EPG) concurrency limit (optional)
+    pub ingestion_class_limit: Option<usize>,
+    /// Regeneration class concurrency limit (optional)
+    pub regeneration_class_limit: Option<usize>,
+    /// Maintenance class concurrency limit (optional)
+    pub maintenance_class_limit: Option<usize>,
 }
 
 /// Response for job scheduling configuration operations
@@ -303,6 +309,39 @@ pub async fn update_job_scheduling_config(
         }
     }
 
+    let class_limits = [
+        (
+            "Ingestion",
+            request.ingestion_class_limit,
+            &mut new_config.ingestion_class_limit,
+        ),
+        (
+            "Regeneration",
+            request.regeneration_class_limit,
+            &mut new_config.regeneration_class_limit,
+        ),
+        (
+            "Maintenance",
+            request.maintenance_class_limit,
+            &mut new_config.maintenance_class_limit,
+        ),
+    ];
+    for (class_name, requested, current) in class_limits {
+        if let Some(limit) = requested {
+            if limit == 0 || limit > 50 {
+                validation_errors
+                    .push(format!("{class_name} class limit must be between 1 and 50"));
+            } else {
+                *current = limit;
+                applied_changes.push(format!(
+                    "Updated {} class limit to {}",
+                    class_name.to_lowercase(),
+                    limit
+                ));
+            }
+        }
+    }
+
     // Additional validation: ensure type limits don't exceed global limit
     let total_max_possible = new_config.stream_ingestion_limit
         + new_config.epg_ingestion_limit
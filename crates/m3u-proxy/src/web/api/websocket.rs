@@ -0,0 +1,565 @@
+//! WebSocket API for interactive clients
+//!
+//! A single connection multiplexes progress events, log streaming and lightweight RPC.
+//! Messages are JSON objects tagged by `type`. Clients subscribe to the events they want,
+//! each subscription getting its own filters, and receive every matching event tagged
+//! with the subscription's ID:
+//!
+//! ```json
+//! {"type": "subscribe", "id": "p1", "topic": "progress", "filter": {"owner_id": "<proxy uuid>"}}
+//! {"type": "subscribe", "id": "l1", "topic": "logs", "filter": {"level": "WARN"}}
+//! {"type": "unsubscribe", "id": "p1"}
+//! {"type": "rpc", "id": "r1", "method": "regenerate_proxy", "params": {"id": "<proxy uuid>"}}
+//! {"type": "ping"}
+//! ```
+//!
+//! Requests carrying an `id` are answered with `{"type": "ack", ...}` or
+//! `{"type": "error", ...}` echoing it.
+//!
+//! The upgrade is a GET, so viewers may connect; RPC calls queue work and, with
+//! authentication enabled, are only accepted from admins.
+
+use axum::{
+    Extension,
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::AuthRole;
+use crate::job_scheduling::types::{JobPriority, JobType, ScheduledJob};
+use crate::services::Principal;
+use crate::web::AppState;
+use crate::web::api::log_streaming::{LogEvent, LogStreamParams};
+use crate::web::api::progress_events::{ProgressEvent, ProgressEventQuery};
+
+/// Most subscriptions one connection may hold
+const MAX_SUBSCRIPTIONS: usize = 32;
+
+/// Message sent by the client
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Start receiving events of a topic; `id` names the subscription
+    Subscribe {
+        id: String,
+        topic: SubscriptionTopic,
+        /// Filters of the topic's SSE endpoint (`GET /progress/events`, `GET /logs/stream`)
+        #[serde(default)]
+        filter: serde_json::Value,
+    },
+    /// Stop a subscription
+    Unsubscribe {
+        id: String,
+    },
+    /// Call a method; answered with an `ack` carrying its result
+    Rpc {
+        id: String,
+        method: RpcMethod,
+        #[serde(default)]
+        params: RpcParams,
+    },
+    Ping,
+}
+
+/// Event topics a connection can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionTopic {
+    Progress,
+    Logs,
+}
+
+/// A subscription with its parsed filters
+#[derive(Debug, Clone)]
+enum Subscription {
+    Progress(ProgressEventQuery),
+    Logs(LogStreamParams),
+}
+
+impl Subscription {
+    fn parse(topic: SubscriptionTopic, filter: serde_json::Value) -> Result<Self, String> {
+        let filter = if filter.is_null() {
+            serde_json::json!({})
+        } else {
+            filter
+        };
+        let subscription = match topic {
+            SubscriptionTopic::Progress => serde_json::from_value(filter).map(Self::Progress),
+            SubscriptionTopic::Logs => serde_json::from_value(filter).map(Self::Logs),
+        }
+        .map_err(|e| format!("Invalid filter: {e}"))?;
+        if let Self::Logs(filter) = &subscription
+            && !filter.has_valid_level()
+        {
+            return Err(format!("Invalid log level: {}", filter.level));
+        }
+        Ok(subscription)
+    }
+}
+
+/// Methods callable over the WebSocket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcMethod {
+    RefreshStreamSource,
+    RefreshEpgSource,
+    RegenerateProxy,
+}
+
+/// Parameters of an RPC call
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct RpcParams {
+    /// ID of the source or proxy the method acts on
+    pub id: Option<Uuid>,
+}
+
+/// Message sent by the server
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Connection established
+    Welcome {
+        max_subscriptions: usize,
+    },
+    /// A request succeeded
+    Ack {
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        result: Option<serde_json::Value>,
+    },
+    /// A request failed, or a message could not be understood (`id` unknown)
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        message: String,
+    },
+    /// Progress event for a subscription
+    Progress {
+        subscription: String,
+        event: ProgressEvent,
+    },
+    /// Log event for a subscription
+    Log {
+        subscription: String,
+        event: LogEvent,
+    },
+    /// Events were dropped because the client did not keep up
+    Lagged {
+        topic: String,
+        skipped: u64,
+    },
+    Pong,
+}
+
+impl ServerMessage {
+    fn error(id: Option<String>, message: impl Into<String>) -> Self {
+        Self::Error {
+            id,
+            message: message.into(),
+        }
+    }
+}
+
+/// Subscriptions of one connection
+#[derive(Default)]
+struct Subscriptions {
+    by_id: HashMap<String, Subscription>,
+}
+
+impl Subscriptions {
+    fn add(&mut self, id: String, subscription: Subscription) -> Result<(), String> {
+        if !self.by_id.contains_key(&id) && self.by_id.len() >= MAX_SUBSCRIPTIONS {
+            return Err(format!(
+                "At most {MAX_SUBSCRIPTIONS} subscriptions per connection"
+            ));
+        }
+        self.by_id.insert(id, subscription);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> bool {
+        self.by_id.remove(id).is_some()
+    }
+
+    /// Messages delivering a progress update to every matching subscription
+    fn progress_messages(
+        &self,
+        progress: &crate::services::progress_service::UniversalProgress,
+    ) -> Vec<ServerMessage> {
+        let matching: Vec<&String> = self
+            .by_id
+            .iter()
+            .filter_map(|(id, subscription)| match subscription {
+                Subscription::Progress(filter) if filter.matches(progress) => Some(id),
+                _ => None,
+            })
+            .collect();
+        if matching.is_empty() {
+            return Vec::new();
+        }
+        let event = ProgressEvent::from(progress.clone());
+        matching
+            .into_iter()
+            .map(|id| ServerMessage::Progress {
+                subscription: id.clone(),
+                event: event.clone(),
+            })
+            .collect()
+    }
+
+    /// Messages delivering a log event to every matching subscription
+    fn log_messages(&self, event: &LogEvent) -> Vec<ServerMessage> {
+        self.by_id
+            .iter()
+            .filter_map(|(id, subscription)| match subscription {
+                Subscription::Logs(filter) => {
+                    filter.apply(event.clone()).map(|event| ServerMessage::Log {
+                        subscription: id.clone(),
+                        event,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// WebSocket endpoint for progress events, log streaming and RPC
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "progress",
+    summary = "Interactive WebSocket API",
+    description = "Upgrade to a WebSocket multiplexing progress events, log streaming and RPC.
+
+Client messages (`ClientMessage`): `subscribe` (topic `progress` or `logs` with the same filters
+as the SSE endpoints), `unsubscribe`, `rpc` (`refresh_stream_source`, `refresh_epg_source`,
+`regenerate_proxy` with `params.id`) and `ping`. Server messages are `ServerMessage`s.",
+    responses(
+        (status = 101, description = "Switching to the WebSocket protocol"),
+        (status = 400, description = "Not a WebSocket upgrade request")
+    )
+)]
+pub async fn websocket_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+) -> impl IntoResponse {
+    let principal = principal.map(|Extension(principal)| principal);
+    ws.on_upgrade(move |socket| handle_socket(socket, state, principal))
+}
+
+async fn handle_socket(mut socket: WebSocket, state: AppState, principal: Option<Principal>) {
+    let connection_id = Uuid::new_v4();
+    debug!("WebSocket connection {} opened", connection_id);
+
+    let mut progress_rx = state.progress_service.subscribe();
+    let mut log_rx = state
+        .log_broadcaster
+        .as_ref()
+        .map(|broadcaster| broadcaster.subscribe());
+    let mut subscriptions = Subscriptions::default();
+
+    if !send(
+        &mut socket,
+        &ServerMessage::Welcome {
+            max_subscriptions: MAX_SUBSCRIPTIONS,
+        },
+    )
+    .await
+    {
+        return;
+    }
+
+    loop {
+        let outgoing = tokio::select! {
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    let principal = principal.as_ref();
+                    vec![handle_client_message(&state, principal, &mut subscriptions, text.as_str()).await]
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => {
+                    debug!("WebSocket connection {} failed: {}", connection_id, e);
+                    break;
+                }
+            },
+            progress = progress_rx.recv() => match progress {
+                Ok(progress) => subscriptions.progress_messages(&progress),
+                Err(RecvError::Lagged(skipped)) => vec![ServerMessage::Lagged {
+                    topic: "progress".to_string(),
+                    skipped,
+                }],
+                Err(RecvError::Closed) => break,
+            },
+            log = recv_log(&mut log_rx) => match log {
+                Ok(event) => subscriptions.log_messages(&event),
+                Err(RecvError::Lagged(skipped)) => vec![ServerMessage::Lagged {
+                    topic: "logs".to_string(),
+                    skipped,
+                }],
+                Err(RecvError::Closed) => {
+                    log_rx = None;
+                    Vec::new()
+                }
+            },
+        };
+        for message in &outgoing {
+            if !send(&mut socket, message).await {
+                debug!("WebSocket connection {} closed by client", connection_id);
+                return;
+            }
+        }
+    }
+    debug!("WebSocket connection {} closed", connection_id);
+}
+
+/// Next log event, or never when log streaming is unavailable
+async fn recv_log(rx: &mut Option<broadcast::Receiver<LogEvent>>) -> Result<LogEvent, RecvError> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(json) => socket.send(Message::Text(json.into())).await.is_ok(),
+        Err(e) => {
+            warn!("Failed to serialize WebSocket message: {}", e);
+            true
+        }
+    }
+}
+
+async fn handle_client_message(
+    state: &AppState,
+    principal: Option<&Principal>,
+    subscriptions: &mut Subscriptions,
+    text: &str,
+) -> ServerMessage {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(e) => return ServerMessage::error(None, format!("Invalid message: {e}")),
+    };
+    match message {
+        ClientMessage::Subscribe { id, topic, filter } => {
+            match Subscription::parse(topic, filter)
+                .and_then(|subscription| subscriptions.add(id.clone(), subscription))
+            {
+                Ok(()) => ServerMessage::Ack { id, result: None },
+                Err(e) => ServerMessage::error(Some(id), e),
+            }
+        }
+        ClientMessage::Unsubscribe { id } => {
+            if subscriptions.remove(&id) {
+                ServerMessage::Ack { id, result: None }
+            } else {
+                ServerMessage::error(Some(id), "No such subscription")
+            }
+        }
+        ClientMessage::Rpc { id, method, params } => match authorize_rpc(principal, method) {
+            Ok(()) => match call(state, method, &params).await {
+                Ok(result) => ServerMessage::Ack {
+                    id,
+                    result: Some(result),
+                },
+                Err(e) => ServerMessage::error(Some(id), e),
+            },
+            Err(e) => ServerMessage::error(Some(id), e),
+        },
+        ClientMessage::Ping => ServerMessage::Pong,
+    }
+}
+
+/// RPC calls queue work, so only admins may make them; without a principal authentication
+/// is disabled and every call is allowed
+fn authorize_rpc(principal: Option<&Principal>, method: RpcMethod) -> Result<(), String> {
+    match principal {
+        Some(principal) if principal.role != AuthRole::Admin => Err(format!(
+            "{method:?} requires the admin role; {} is a viewer",
+            principal.name
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Run an RPC call; refreshes and regenerations are queued like their REST counterparts
+async fn call(
+    state: &AppState,
+    method: RpcMethod,
+    params: &RpcParams,
+) -> Result<serde_json::Value, String> {
+    let id = params.id.ok_or("Missing params.id")?;
+    let job_type = match method {
+        RpcMethod::RefreshStreamSource => {
+            crate::database::repositories::StreamSourceSeaOrmRepository::new(
+                state.database.connection().clone(),
+            )
+            .find_by_id(&id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Stream source {id} not found"))?;
+            JobType::StreamIngestion(id)
+        }
+        RpcMethod::RefreshEpgSource => {
+            crate::database::repositories::EpgSourceSeaOrmRepository::new(
+                state.database.connection().clone(),
+            )
+            .find_by_id(&id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("EPG source {id} not found"))?;
+            JobType::EpgIngestion(id)
+        }
+        RpcMethod::RegenerateProxy => {
+            state
+                .database
+                .get_stream_proxy(id)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Proxy {id} not found"))?;
+            // Convert immediately to avoid Send issues with Box<dyn StdError>
+            let queued = state
+                .proxy_regeneration_service
+                .queue_manual_regeneration(id)
+                .await
+                .map_err(|e| e.to_string());
+            queued?;
+            JobType::ProxyRegeneration(id)
+        }
+    };
+
+    let newly_queued = state
+        .job_queue
+        .enqueue(ScheduledJob::new(job_type, JobPriority::High))
+        .await
+        .map_err(|e| e.to_string())?;
+    info!("WebSocket RPC {:?} queued for {}", method, id);
+    Ok(serde_json::json!({
+        "id": id,
+        "status": if newly_queued { "queued" } else { "already_queued" },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> ClientMessage {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_client_messages() {
+        let message = parse(
+            r#"{"type":"subscribe","id":"p1","topic":"progress","filter":{"owner_id":"abc"}}"#,
+        );
+        let ClientMessage::Subscribe { id, topic, filter } = message else {
+            panic!("expected subscribe");
+        };
+        assert_eq!(id, "p1");
+        assert!(matches!(
+            Subscription::parse(topic, filter),
+            Ok(Subscription::Progress(ref filter)) if filter.owner_id.as_deref() == Some("abc")
+        ));
+
+        let ClientMessage::Subscribe { topic, filter, .. } =
+            parse(r#"{"type":"subscribe","id":"l1","topic":"logs"}"#)
+        else {
+            panic!("expected subscribe");
+        };
+        assert!(matches!(
+            Subscription::parse(topic, filter),
+            Ok(Subscription::Logs(ref filter)) if filter.level == "INFO"
+        ));
+        assert!(
+            Subscription::parse(
+                SubscriptionTopic::Logs,
+                serde_json::json!({"level": "LOUD"})
+            )
+            .is_err()
+        );
+
+        let message = parse(
+            r#"{"type":"rpc","id":"r1","method":"regenerate_proxy","params":{"id":"6f1c1f5e-0d4c-4f43-9d2b-3b6a0b4f5a10"}}"#,
+        );
+        assert!(matches!(
+            message,
+            ClientMessage::Rpc { method: RpcMethod::RegenerateProxy, ref params, .. } if params.id.is_some()
+        ));
+
+        assert!(matches!(parse(r#"{"type":"ping"}"#), ClientMessage::Ping));
+        assert!(
+            serde_json::from_str::<ClientMessage>(
+                r#"{"type":"rpc","id":"x","method":"drop_tables"}"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_subscriptions() {
+        let mut subscriptions = Subscriptions::default();
+        let logs = |level: &str| {
+            Subscription::parse(
+                SubscriptionTopic::Logs,
+                serde_json::json!({ "level": level }),
+            )
+            .unwrap()
+        };
+        assert!(subscriptions.add("l1".to_string(), logs("WARN")).is_ok());
+
+        let event = |level: &str| LogEvent {
+            id: "1".to_string(),
+            timestamp: String::new(),
+            level: level.to_string(),
+            target: "m3u_proxy".to_string(),
+            message: "hello".to_string(),
+            fields: HashMap::new(),
+            span: None,
+        };
+        assert!(subscriptions.log_messages(&event("INFO")).is_empty());
+        assert_eq!(subscriptions.log_messages(&event("ERROR")).len(), 1);
+
+        assert!(subscriptions.remove("l1"));
+        assert!(!subscriptions.remove("l1"));
+        for i in 0..MAX_SUBSCRIPTIONS {
+            subscriptions.add(i.to_string(), logs("INFO")).unwrap();
+        }
+        assert!(
+            subscriptions
+                .add("one_too_many".to_string(), logs("INFO"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_viewers_may_not_call_rpcs() {
+        let principal = |role| Principal {
+            name: "sam".to_string(),
+            role,
+            method: crate::services::auth::AuthMethod::ApiToken,
+        };
+        let ClientMessage::Rpc { method, .. } = parse(
+            r#"{"type":"rpc","id":"r1","method":"regenerate_proxy","params":{"id":"6f1c1f5e-0d4c-4f43-9d2b-3b6a0b4f5a10"}}"#,
+        ) else {
+            panic!("expected rpc");
+        };
+
+        let rejected = authorize_rpc(Some(&principal(AuthRole::Viewer)), method).unwrap_err();
+        assert!(rejected.contains("admin role"));
+        assert!(authorize_rpc(Some(&principal(AuthRole::Admin)), method).is_ok());
+        assert!(authorize_rpc(None, method).is_ok());
+    }
+}
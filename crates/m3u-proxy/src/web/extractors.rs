@@ -9,19 +9,45 @@ use axum::{
     http::{StatusCode, request::Parts},
     response::{IntoResponse, Response},
 };
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use serde::Deserialize;
+use std::cmp::Ordering;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::responses::{ApiResponse, ValidationErrorResponse, validation_error};
+use super::responses::{ApiResponse, PaginatedResponse, ValidationErrorResponse, validation_error};
+use crate::utils::forwarded::ClientAddress;
 
 /// Pagination parameters from query string
+///
+/// Pages can be addressed by `page`, by an explicit 0-based `offset`, or by the
+/// opaque `cursor` returned as `next_cursor` in a previous response. When more
+/// than one is given, `cursor` wins over `offset`, which wins over `page`.
 #[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct PaginationParams {
     #[serde(default = "default_page")]
     pub page: u32,
     #[serde(default = "default_limit")]
     pub limit: u32,
+    #[serde(default)]
+    pub offset: Option<u32>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// Encode a 0-based offset as an opaque pagination cursor
+pub fn encode_cursor(offset: u32) -> String {
+    URL_SAFE_NO_PAD.encode(format!("o:{offset}"))
+}
+
+/// Decode a pagination cursor produced by [`encode_cursor`]
+pub fn decode_cursor(cursor: &str) -> Option<u32> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    std::str::from_utf8(&bytes)
+        .ok()?
+        .strip_prefix("o:")?
+        .parse()
+        .ok()
 }
 
 fn default_page() -> u32 {
@@ -37,6 +63,8 @@ impl Default for PaginationParams {
         Self {
             page: default_page(),
             limit: default_limit(),
+            offset: None,
+            cursor: None,
         }
     }
 }
@@ -60,6 +88,15 @@ impl PaginationParams {
             });
         }
 
+        if let Some(ref cursor) = self.cursor
+            && decode_cursor(cursor).is_none()
+        {
+            errors.push(ValidationErrorResponse {
+                field: "cursor".to_string(),
+                message: "Cursor is not valid".to_string(),
+            });
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -69,7 +106,11 @@ impl PaginationParams {
 
     /// Calculate offset for database queries (0-based)
     pub fn offset(&self) -> u32 {
-        (self.page.saturating_sub(1)) * self.limit
+        if let Some(offset) = self.cursor.as_deref().and_then(decode_cursor) {
+            return offset;
+        }
+        self.offset
+            .unwrap_or_else(|| (self.page.saturating_sub(1)).saturating_mul(self.limit))
     }
 }
 
@@ -167,6 +208,69 @@ where
     }
 }
 
+impl ListParams {
+    /// Keep only items matching the `search` term
+    ///
+    /// The matcher receives each item together with the trimmed, lowercased
+    /// search term. A missing or blank term keeps every item.
+    pub fn apply_search<T>(&self, items: &mut Vec<T>, matches: impl Fn(&T, &str) -> bool) {
+        if let Some(term) = self
+            .search
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+        {
+            let term = term.to_lowercase();
+            items.retain(|item| matches(item, &term));
+        }
+    }
+
+    /// Resolve the requested `sort_by` field against the fields an endpoint supports
+    ///
+    /// Returns `None` when no sort field was requested. Unknown fields are
+    /// rejected rather than silently falling back to the default order.
+    pub fn sort_field(
+        &self,
+        allowed: &[&str],
+    ) -> Result<Option<&str>, Vec<ValidationErrorResponse>> {
+        match self.search.sort_by.as_deref() {
+            None => Ok(None),
+            Some(field) if allowed.contains(&field) => Ok(Some(field)),
+            Some(field) => Err(vec![ValidationErrorResponse {
+                field: "sort_by".to_string(),
+                message: format!(
+                    "Cannot sort by '{field}'; supported fields: {}",
+                    allowed.join(", ")
+                ),
+            }]),
+        }
+    }
+
+    /// Apply the requested sort direction to an ascending comparison
+    pub fn direction(&self, ordering: Ordering) -> Ordering {
+        if self.search.sort_ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    }
+
+    /// Slice an already filtered and sorted list into the requested page
+    pub fn paginate<T>(&self, items: Vec<T>) -> PaginatedResponse<T> {
+        let total = items.len() as u64;
+        let offset = self.pagination.offset();
+        let limit = self.pagination.limit;
+        let page_items = items
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+
+        PaginatedResponse::from_offset(page_items, total, offset, limit)
+    }
+}
+
 /// Validated JSON extractor that provides better error messages
 pub struct ValidatedJson<T>(pub T);
 
@@ -332,15 +436,27 @@ where
 pub struct RequestContext {
     pub user_agent: Option<String>,
     pub real_ip: Option<String>,
+    /// User authenticated by the admin API, or else by a trusted reverse proxy
+    /// (`Remote-User` / `X-Forwarded-User`)
+    pub remote_user: Option<String>,
     pub request_id: String,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+impl RequestContext {
+    /// Who made the request, for change history: the authenticated user when there is
+    /// one, otherwise the client address
+    pub fn author(&self) -> Option<String> {
+        self.remote_user.clone().or_else(|| self.real_ip.clone())
+    }
+}
+
 impl Default for RequestContext {
     fn default() -> Self {
         Self {
             user_agent: None,
             real_ip: None,
+            remote_user: None,
             request_id: Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now(),
         }
@@ -360,18 +476,89 @@ where
             .and_then(|h| h.to_str().ok())
             .map(|s| s.to_string());
 
-        let real_ip = parts
-            .headers
-            .get("x-real-ip")
-            .or_else(|| parts.headers.get("x-forwarded-for"))
-            .and_then(|h| h.to_str().ok())
-            .map(|s| s.split(',').next().unwrap_or(s).trim().to_string());
+        let client = parts.extensions.get::<ClientAddress>();
+        let real_ip = client.and_then(|client| client.ip).map(|ip| ip.to_string());
+
+        let remote_user = parts
+            .extensions
+            .get::<crate::services::Principal>()
+            .map(|principal| principal.name.clone())
+            .or_else(|| client.and_then(|client| client.remote_user.clone()));
 
         Ok(Self {
             user_agent,
             real_ip,
+            remote_user,
             request_id: Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now(),
         })
     }
 }
+
+/// The request's [`ClientAddress`] as resolved by the forwarded headers middleware
+impl<S> FromRequestParts<S> for ClientAddress
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<ClientAddress>()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_params(offset: Option<u32>, cursor: Option<String>, limit: u32) -> ListParams {
+        ListParams {
+            pagination: PaginationParams {
+                limit,
+                offset,
+                cursor,
+                ..Default::default()
+            },
+            search: SearchParams::default(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        assert_eq!(decode_cursor(&encode_cursor(150)), Some(150));
+        assert_eq!(decode_cursor("not-a-cursor"), None);
+    }
+
+    #[test]
+    fn test_paginate_follows_next_cursor() {
+        let items: Vec<u32> = (0..5).collect();
+
+        let first = list_params(None, None, 2).paginate(items.clone());
+        assert_eq!(first.items, vec![0, 1]);
+        assert_eq!(first.total, 5);
+        assert!(first.has_next);
+
+        let second = list_params(None, first.next_cursor, 2).paginate(items.clone());
+        assert_eq!(second.items, vec![2, 3]);
+        assert_eq!(second.page, 2);
+
+        let last = list_params(Some(4), None, 2).paginate(items);
+        assert_eq!(last.items, vec![4]);
+        assert!(!last.has_next);
+        assert!(last.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_sort_field_rejects_unknown_fields() {
+        let mut params = list_params(None, None, 50);
+        params.search.sort_by = Some("name".to_string());
+        assert_eq!(params.sort_field(&["name"]).unwrap(), Some("name"));
+
+        params.search.sort_by = Some("password".to_string());
+        assert!(params.sort_field(&["name"]).is_err());
+    }
+}
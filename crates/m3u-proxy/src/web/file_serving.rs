@@ -0,0 +1,453 @@
+//! Sandboxed file serving with HTTP range support
+//!
+//! Serves files from a [`SandboxedManager`] with `Accept-Ranges`, single byte-range
+//! `206 Partial Content` responses, `ETag` / `Last-Modified` validators and conditional
+//! requests (`If-None-Match`, `If-Range`), so web players and Kodi can seek in media
+//! files without downloading them in full.
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use sandboxed_file_manager::SandboxedManager;
+use std::io::SeekFrom;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tracing::{debug, warn};
+
+/// Read buffer size used when streaming file bodies
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// An inclusive byte range within a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// Number of bytes covered by the range
+    pub fn content_length(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Result of interpreting a `Range` header against a file size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// No (usable) range - serve the full file
+    Full,
+    /// A single satisfiable range
+    Partial(ByteRange),
+    /// The range cannot be satisfied for this file size
+    Unsatisfiable,
+}
+
+/// Parse a `Range` header value for a file of `file_size` bytes
+///
+/// Only single `bytes=` ranges are honoured; multi-range and malformed headers fall back
+/// to a full response as permitted by RFC 9110.
+pub fn parse_range_header(value: &str, file_size: u64) -> RangeRequest {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RangeRequest::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // Suffix range: last N bytes
+        let Ok(suffix) = end.parse::<u64>() else {
+            return RangeRequest::Full;
+        };
+        if suffix == 0 || file_size == 0 {
+            return RangeRequest::Unsatisfiable;
+        }
+        ByteRange {
+            start: file_size.saturating_sub(suffix),
+            end: file_size - 1,
+        }
+    } else {
+        let Ok(start) = start.parse::<u64>() else {
+            return RangeRequest::Full;
+        };
+        let end = if end.is_empty() {
+            file_size.saturating_sub(1)
+        } else {
+            match end.parse::<u64>() {
+                Ok(end) if end >= start => end.min(file_size.saturating_sub(1)),
+                _ => return RangeRequest::Full,
+            }
+        };
+        if start >= file_size {
+            return RangeRequest::Unsatisfiable;
+        }
+        ByteRange { start, end }
+    };
+
+    RangeRequest::Partial(range)
+}
+
+/// Guess a Content-Type from a file name, covering media and playlist formats
+pub fn content_type_for_path(path: &str) -> &'static str {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "ts" | "m2ts" => "video/mp2t",
+        "mp4" | "m4v" => "video/mp4",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "aac" => "audio/aac",
+        "m3u8" => "application/vnd.apple.mpegurl",
+        "m3u" => "audio/x-mpegurl",
+        "xml" => "application/xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Build a strong ETag from file size and modification time
+fn file_etag(size: u64, modified_nanos: u128) -> String {
+    format!("\"{size:x}-{modified_nanos:x}\"")
+}
+
+/// Whether an `If-None-Match` header value matches the given ETag
+fn etag_matches(header_value: &str, etag: &str) -> bool {
+    header_value
+        .split(',')
+        .map(|candidate| candidate.trim().trim_start_matches("W/"))
+        .any(|candidate| candidate == "*" || candidate == etag)
+}
+
+/// Format a timestamp as an HTTP date (`Last-Modified`, `If-Modified-Since`)
+pub fn http_date(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Strong ETag for generated content, identifying the generation and the content variant
+pub fn generation_etag(
+    resource_id: &uuid::Uuid,
+    generated_at: chrono::DateTime<chrono::Utc>,
+    variant: &str,
+) -> String {
+    format!(
+        "\"{}-{:x}-{variant}\"",
+        resource_id.simple(),
+        generated_at.timestamp_millis()
+    )
+}
+
+/// Whether a conditional GET for content with these validators can be answered with 304
+///
+/// `If-None-Match` takes precedence; `If-Modified-Since` is only evaluated without it
+/// (RFC 9110 section 13.2.2) and compared at second precision.
+pub fn is_not_modified(
+    request_headers: &HeaderMap,
+    etag: &str,
+    last_modified: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    if let Some(value) = request_headers.get(header::IF_NONE_MATCH) {
+        return value.to_str().is_ok_and(|v| etag_matches(v, etag));
+    }
+    request_headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| chrono::DateTime::parse_from_rfc2822(v.trim()).ok())
+        .is_some_and(|since| last_modified.timestamp() <= since.timestamp())
+}
+
+/// Serve a file from a sandboxed manager, honouring range and conditional request headers
+///
+/// `path` is relative to the manager's base directory. When `content_type` is `None`
+/// it is derived from the file extension.
+pub async fn serve_sandboxed_file(
+    manager: &SandboxedManager,
+    path: &str,
+    content_type: Option<&str>,
+    cache_control: &str,
+    request_headers: &HeaderMap,
+) -> Response {
+    let stat = match manager.stat(path).await {
+        Ok(stat) => stat,
+        Err(e) => {
+            debug!("Sandboxed file '{}' not available: {}", path, e);
+            return StatusCode::NOT_FOUND.into_response();
+        }
+    };
+
+    let file_size = stat.size_bytes;
+    let modified_nanos = stat
+        .modified
+        .timestamp_nanos_opt()
+        .and_then(|nanos| u128::try_from(nanos).ok())
+        .unwrap_or_default();
+    let etag = file_etag(file_size, modified_nanos);
+    let last_modified = http_date(stat.modified);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(content_type.unwrap_or_else(|| content_type_for_path(path)))
+            .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
+    );
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Ok(value) = HeaderValue::from_str(cache_control) {
+        headers.insert(header::CACHE_CONTROL, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&last_modified) {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+
+    if request_headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| etag_matches(v, &etag))
+    {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    // If-Range: only honour the range when the client's validator still matches
+    let range_allowed = match request_headers
+        .get(header::IF_RANGE)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(validator) => validator.trim() == etag || validator.trim() == last_modified,
+        None => true,
+    };
+
+    let range = match request_headers
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(value) if range_allowed => parse_range_header(value, file_size),
+        _ => RangeRequest::Full,
+    };
+
+    let (status, start, length) = match range {
+        RangeRequest::Full => (StatusCode::OK, 0, file_size),
+        RangeRequest::Partial(range) => {
+            if let Ok(value) = HeaderValue::from_str(&format!(
+                "bytes {}-{}/{}",
+                range.start, range.end, file_size
+            )) {
+                headers.insert(header::CONTENT_RANGE, value);
+            }
+            (
+                StatusCode::PARTIAL_CONTENT,
+                range.start,
+                range.content_length(),
+            )
+        }
+        RangeRequest::Unsatisfiable => {
+            if let Ok(value) = HeaderValue::from_str(&format!("bytes */{file_size}")) {
+                headers.insert(header::CONTENT_RANGE, value);
+            }
+            return (StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response();
+        }
+    };
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+
+    // Object storage hands out no file handles; its files (logos, playlists) are small
+    // enough to fetch whole and slice
+    if !manager.is_local() {
+        let content = match manager.read(path).await {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read sandboxed file '{}': {}", path, e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+        let start = usize::try_from(start)
+            .unwrap_or(usize::MAX)
+            .min(content.len());
+        let end = usize::try_from(length)
+            .map_or(content.len(), |length| start.saturating_add(length))
+            .min(content.len());
+        return (
+            status,
+            headers,
+            Body::from(bytes::Bytes::from(content).slice(start..end)),
+        )
+            .into_response();
+    }
+
+    let mut file = match manager.open(path).await {
+        Ok(file) => file,
+        Err(e) => {
+            warn!("Failed to open sandboxed file '{}': {}", path, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if start > 0
+        && let Err(e) = file.seek(SeekFrom::Start(start)).await
+    {
+        warn!("Failed to seek sandboxed file '{}': {}", path, e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let body_stream = futures::stream::unfold((file, length), |(mut file, remaining)| async move {
+        if remaining == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u8; remaining.min(STREAM_CHUNK_SIZE as u64) as usize];
+        match file.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(read) => {
+                buffer.truncate(read);
+                Some((
+                    Ok::<_, std::io::Error>(bytes::Bytes::from(buffer)),
+                    (file, remaining - read as u64),
+                ))
+            }
+            Err(e) => Some((Err(e), (file, 0))),
+        }
+    });
+
+    (status, headers, Body::from_stream(body_stream)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range_header() {
+        assert_eq!(
+            parse_range_header("bytes=0-99", 1000),
+            RangeRequest::Partial(ByteRange { start: 0, end: 99 })
+        );
+        assert_eq!(
+            parse_range_header("bytes=500-", 1000),
+            RangeRequest::Partial(ByteRange {
+                start: 500,
+                end: 999
+            })
+        );
+        assert_eq!(
+            parse_range_header("bytes=-100", 1000),
+            RangeRequest::Partial(ByteRange {
+                start: 900,
+                end: 999
+            })
+        );
+        // End beyond the file is clamped
+        assert_eq!(
+            parse_range_header("bytes=900-5000", 1000),
+            RangeRequest::Partial(ByteRange {
+                start: 900,
+                end: 999
+            })
+        );
+        assert_eq!(
+            parse_range_header("bytes=1000-", 1000),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range_header("bytes=-0", 1000),
+            RangeRequest::Unsatisfiable
+        );
+        // Multi-range, malformed and non-byte units fall back to a full response
+        assert_eq!(
+            parse_range_header("bytes=0-1,5-6", 1000),
+            RangeRequest::Full
+        );
+        assert_eq!(parse_range_header("bytes=9-3", 1000), RangeRequest::Full);
+        assert_eq!(parse_range_header("items=0-1", 1000), RangeRequest::Full);
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let etag = file_etag(1000, 42);
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches(&format!("\"other\", W/{etag}"), &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("\"other\"", &etag));
+    }
+
+    #[tokio::test]
+    async fn test_serve_sandboxed_file_range_request() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .build()
+            .await
+            .unwrap();
+        manager.write("clip.ts", b"0123456789").await.unwrap();
+
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::RANGE, HeaderValue::from_static("bytes=2-5"));
+        let response =
+            serve_sandboxed_file(&manager, "clip.ts", None, "no-cache", &request_headers).await;
+
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 2-5/10");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "video/mp2t");
+        let etag = response.headers()[header::ETAG].clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"2345");
+
+        // A matching ETag short-circuits to 304
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::IF_NONE_MATCH, etag);
+        let response =
+            serve_sandboxed_file(&manager, "clip.ts", None, "no-cache", &request_headers).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response =
+            serve_sandboxed_file(&manager, "missing.ts", None, "no-cache", &HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_is_not_modified() {
+        let generated_at = chrono::Utc::now();
+        let etag = generation_etag(&uuid::Uuid::new_v4(), generated_at, "m3u");
+
+        let mut headers = HeaderMap::new();
+        assert!(!is_not_modified(&headers, &etag, generated_at));
+
+        headers.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_str(&http_date(generated_at)).unwrap(),
+        );
+        assert!(is_not_modified(&headers, &etag, generated_at));
+        assert!(!is_not_modified(
+            &headers,
+            &etag,
+            generated_at + chrono::Duration::seconds(5)
+        ));
+
+        // A non-matching If-None-Match wins over a matching If-Modified-Since
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"stale\""));
+        assert!(!is_not_modified(&headers, &etag, generated_at));
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(&etag).unwrap());
+        assert!(is_not_modified(&headers, &etag, generated_at));
+    }
+
+    #[test]
+    fn test_content_type_for_path() {
+        assert_eq!(content_type_for_path("recording.TS"), "video/mp2t");
+        assert_eq!(content_type_for_path("show.mkv"), "video/x-matroska");
+        assert_eq!(
+            content_type_for_path("noextension"),
+            "application/octet-stream"
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! Login and session handlers
+//!
+//! OpenID Connect login uses the authorization code flow with PKCE: `/oidc/login` sends the
+//! browser to the identity provider with the login state kept in a signed cookie, and
+//! `/oidc/callback` exchanges the returned code, maps the user's claims to a role and sets
+//! the session cookie. These endpoints sit outside the authenticated API.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{AppendHeaders, IntoResponse, Redirect},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::services::Principal;
+use crate::services::oidc::{Pkce, random_token};
+use crate::utils::forwarded::ClientAddress;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{ApiResponse, bad_request, internal_error, no_content, not_found, ok},
+    utils::log_request,
+};
+
+/// Authentication settings and the current user
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthStatus {
+    /// Whether the admin API requires authentication
+    pub enabled: bool,
+    /// Whether OpenID Connect login is available
+    pub oidc: bool,
+    /// The authenticated user, if any
+    pub user: Option<Principal>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LoginQuery {
+    /// Local path to return to after login (default `/`)
+    pub return_to: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set by the provider when the login failed or was cancelled
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// Authentication status
+#[utoipa::path(
+    get,
+    path = "/auth/status",
+    tag = "auth",
+    summary = "Get authentication status",
+    description = "Whether the admin API requires authentication, whether OpenID Connect login is available, and who the caller is authenticated as",
+    responses(
+        (status = 200, description = "Authentication status", body = AuthStatus)
+    )
+)]
+pub async fn auth_status(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    let auth = &state.admin_auth;
+    let user = if auth.is_enabled() {
+        auth.authenticate(&headers).await.ok()
+    } else {
+        None
+    };
+    ok(AuthStatus {
+        enabled: auth.is_enabled(),
+        oidc: auth.oidc().is_some(),
+        user,
+    })
+}
+
+/// Start an OpenID Connect login
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/login",
+    tag = "auth",
+    summary = "Start OpenID Connect login",
+    description = "Redirect the browser to the identity provider. After login the provider redirects back to the callback, which sets the session cookie and returns to `return_to`.",
+    params(LoginQuery),
+    responses(
+        (status = 302, description = "Redirect to the identity provider"),
+        (status = 404, description = "OpenID Connect login is not configured"),
+        (status = 500, description = "Identity provider unavailable")
+    )
+)]
+pub async fn oidc_login(
+    State(state): State<AppState>,
+    Query(query): Query<LoginQuery>,
+    client: ClientAddress,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/auth/oidc/login".parse().unwrap(),
+        &context,
+    );
+
+    let auth = &state.admin_auth;
+    let Some(oidc) = auth.oidc() else {
+        return not_found("OpenID Connect provider", "oidc").into_response();
+    };
+
+    let redirect_url = state
+        .config
+        .auth
+        .as_ref()
+        .and_then(|auth| auth.oidc.as_ref())
+        .and_then(|oidc| oidc.redirect_url.clone())
+        .unwrap_or_else(|| {
+            format!(
+                "{}/api/v1/auth/oidc/callback",
+                client.base_url(&state.config.web.base_url)
+            )
+        });
+    let login_state = random_token(24);
+    let nonce = random_token(24);
+    let pkce = Pkce::generate();
+
+    let location = match oidc
+        .authorization_url(&redirect_url, &login_state, &nonce, &pkce.challenge)
+        .await
+    {
+        Ok(location) => location,
+        Err(e) => {
+            warn!("Cannot start OpenID Connect login: {:#}", e);
+            return internal_error("Identity provider unavailable").into_response();
+        }
+    };
+
+    let cookie = auth.login_state_cookie(
+        login_state,
+        nonce,
+        pkce.verifier,
+        redirect_url,
+        crate::services::auth::safe_return_to(query.return_to.as_deref()),
+    );
+    ([(header::SET_COOKIE, cookie)], Redirect::to(&location)).into_response()
+}
+
+/// Finish an OpenID Connect login
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/callback",
+    tag = "auth",
+    summary = "OpenID Connect callback",
+    description = "Redirect target of the identity provider. Validates the login, sets the session cookie and redirects to the page the login started from.",
+    params(CallbackQuery),
+    responses(
+        (status = 302, description = "Logged in; redirect to the original page"),
+        (status = 400, description = "Invalid, expired or failed login"),
+        (status = 403, description = "The user has no role in m3u-proxy"),
+        (status = 404, description = "OpenID Connect login is not configured")
+    )
+)]
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    Query(query): Query<CallbackQuery>,
+    headers: HeaderMap,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/auth/oidc/callback".parse().unwrap(),
+        &context,
+    );
+
+    let auth = &state.admin_auth;
+    let Some(oidc) = auth.oidc() else {
+        return not_found("OpenID Connect provider", "oidc").into_response();
+    };
+    if let Some(error) = &query.error {
+        warn!(
+            "OpenID Connect login failed at the provider: {} {}",
+            error,
+            query.error_description.as_deref().unwrap_or_default()
+        );
+        return bad_request(&format!("Login failed: {error}")).into_response();
+    }
+
+    let Some(login) = auth.login_state(&headers) else {
+        return bad_request("Login expired or was started in another browser").into_response();
+    };
+    let (Some(code), Some(returned_state)) = (&query.code, &query.state) else {
+        return bad_request("Missing code or state").into_response();
+    };
+    if *returned_state != login.state {
+        return bad_request("Login state does not match").into_response();
+    }
+
+    let claims = match oidc
+        .exchange_code(
+            code,
+            &login.redirect_url,
+            &login.code_verifier,
+            &login.nonce,
+        )
+        .await
+    {
+        Ok(claims) => claims,
+        Err(e) => {
+            warn!("OpenID Connect login rejected: {:#}", e);
+            return bad_request("Login could not be verified").into_response();
+        }
+    };
+    let Some((name, role)) = oidc.principal_of(&claims) else {
+        warn!(
+            "OpenID Connect user {:?} has no role",
+            claims.get("sub").and_then(|sub| sub.as_str())
+        );
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "Your account has no access to m3u-proxy".to_string(),
+            )),
+        )
+            .into_response();
+    };
+
+    info!("User '{}' logged in as {:?}", name, role);
+    (
+        AppendHeaders([
+            (header::SET_COOKIE, auth.session_cookie(&name, role)),
+            (header::SET_COOKIE, auth.clear_login_state_cookie()),
+        ]),
+        Redirect::to(&login.return_to),
+    )
+        .into_response()
+}
+
+/// Log out
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    tag = "auth",
+    summary = "Log out",
+    description = "End the browser's login session by clearing its session cookie",
+    responses(
+        (status = 204, description = "Logged out")
+    )
+)]
+pub async fn logout(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::SET_COOKIE, state.admin_auth.clear_session_cookie())],
+        no_content(),
+    )
+}
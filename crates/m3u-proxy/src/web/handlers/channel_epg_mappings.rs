@@ -0,0 +1,431 @@
+//! Manual channel-to-EPG mapping handlers
+//!
+//! Find a proxy's channels without guide data, search the EPG channels of the proxy's
+//! EPG sources and map channels to them by hand. Mappings take precedence over tvg-id
+//! matching; changing one queues a re-map of the proxy's guide.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use tracing::{info, warn};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::database::repositories::{
+    ChannelEpgMappingSeaOrmRepository, ChannelSeaOrmRepository, EpgProgramSeaOrmRepository,
+    StreamProxySeaOrmRepository,
+};
+use crate::models::channel_epg_mapping::{
+    ChannelEpgMapping, EpgChannelCandidate, SetChannelEpgMappingsRequest, UnmappedChannel,
+    normalize_channel_name, rank_candidates,
+};
+use crate::utils::resolve_proxy_id;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+const DEFAULT_CANDIDATE_LIMIT: usize = 50;
+const MAX_CANDIDATE_LIMIT: usize = 500;
+
+/// Query parameters for searching EPG channels
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct EpgChannelCandidatesQuery {
+    /// Case-insensitive text matched against EPG channel ids and names
+    pub q: Option<String>,
+    /// Maximum number of channels to return (default 50, max 500)
+    pub limit: Option<usize>,
+}
+
+/// List manual EPG mappings of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/epg-mappings",
+    tag = "proxies",
+    summary = "List proxy EPG mappings",
+    description = "List the channels of a proxy that are mapped to an EPG channel by hand",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "EPG mappings", body = Vec<ChannelEpgMapping>),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_epg_mappings(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/epg-mappings")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let repo = ChannelEpgMappingSeaOrmRepository::new(state.database.read_connection());
+    match repo.list_for_proxy(&proxy_id).await {
+        Ok(mappings) => ok(mappings).into_response(),
+        Err(e) => internal_error(&format!("Failed to list EPG mappings: {e}")).into_response(),
+    }
+}
+
+/// Save manual EPG mappings of a proxy
+#[utoipa::path(
+    put,
+    path = "/proxies/{id}/epg-mappings",
+    tag = "proxies",
+    summary = "Save proxy EPG mappings",
+    description = "Map channels of a proxy to EPG channel ids, replacing their existing mappings. A mapped channel takes its programmes from the mapped EPG channel instead of the one matching its tvg-id. The proxy's guide is re-mapped shortly after, without regenerating its playlist.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = SetChannelEpgMappingsRequest,
+    responses(
+        (status = 200, description = "Saved mappings", body = Vec<ChannelEpgMapping>),
+        (status = 400, description = "Invalid request or channel not part of the proxy"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_epg_mappings(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<SetChannelEpgMappingsRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/proxies/{id}/epg-mappings")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+    if let Err(e) = request.validate() {
+        return bad_request(&e).into_response();
+    }
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    match proxy_repo.find_by_id(&proxy_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found("proxy", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    }
+
+    // Check every channel before saving any mapping
+    let mut channel_names = Vec::with_capacity(request.mappings.len());
+    for mapping in &request.mappings {
+        match proxy_repo
+            .get_channel_for_proxy(proxy_id, mapping.channel_id)
+            .await
+        {
+            Ok(Some(channel)) => channel_names.push(channel.channel_name),
+            Ok(None) => {
+                return bad_request(&format!(
+                    "Channel {} is not part of this proxy",
+                    mapping.channel_id
+                ))
+                .into_response();
+            }
+            Err(e) => return internal_error(&e.to_string()).into_response(),
+        }
+    }
+
+    let repo = ChannelEpgMappingSeaOrmRepository::new(state.database.connection().clone());
+    let mut mappings = Vec::with_capacity(request.mappings.len());
+    for (mapping, channel_name) in request.mappings.iter().zip(channel_names) {
+        match repo
+            .set(
+                proxy_id,
+                mapping.channel_id,
+                &mapping.epg_channel_id,
+                Some(channel_name),
+            )
+            .await
+        {
+            Ok(mapping) => mappings.push(mapping),
+            Err(e) => {
+                return internal_error(&format!("Failed to save EPG mapping: {e}")).into_response();
+            }
+        }
+    }
+
+    info!(
+        "Saved {} manual EPG mapping(s) for proxy {}",
+        mappings.len(),
+        proxy_id
+    );
+    queue_epg_remap(&state, proxy_id).await;
+    ok(mappings).into_response()
+}
+
+/// Queue a guide-only regeneration so a mapping change shows up without a full rebuild
+async fn queue_epg_remap(state: &AppState, proxy_id: Uuid) {
+    if let Err(e) = state
+        .proxy_regeneration_service
+        .queue_epg_remap(proxy_id, proxy_id)
+        .await
+    {
+        warn!("Failed to queue EPG re-map of proxy {}: {}", proxy_id, e);
+    }
+}
+
+/// Remove the manual EPG mapping of a channel
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/epg-mappings/{channel_id}",
+    tag = "proxies",
+    summary = "Remove proxy EPG mapping",
+    description = "Remove a channel's manual mapping so it is matched by tvg-id again. The proxy's guide is re-mapped shortly after.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("channel_id" = String, Path, description = "Channel ID"),
+    ),
+    responses(
+        (status = 200, description = "Mapping removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Mapping not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_epg_mapping(
+    State(state): State<AppState>,
+    Path((id, channel_id)): Path<(String, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/proxies/{id}/epg-mappings/{channel_id}")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+    let channel_uuid = match Uuid::parse_str(&channel_id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid channel ID").into_response(),
+    };
+
+    let repo = ChannelEpgMappingSeaOrmRepository::new(state.database.connection().clone());
+    match repo.delete(&proxy_id, &channel_uuid).await {
+        Ok(true) => {
+            info!(
+                "Removed EPG mapping of channel {} from proxy {}",
+                channel_uuid, proxy_id
+            );
+            queue_epg_remap(&state, proxy_id).await;
+            ok(serde_json::json!({"message": "EPG mapping removed"})).into_response()
+        }
+        Ok(false) => not_found("EPG mapping", &channel_id).into_response(),
+        Err(e) => internal_error(&format!("Failed to remove EPG mapping: {e}")).into_response(),
+    }
+}
+
+/// List channels of a proxy without guide data
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/epg-mappings/unmapped",
+    tag = "proxies",
+    summary = "List unmapped proxy channels",
+    description = "List the channels of the proxy's stream sources that have no manual mapping and whose tvg-id is empty or matches no channel of the proxy's EPG sources. Each carries the EPG channel with the same normalised name as a suggestion, when there is one.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Unmapped channels", body = Vec<UnmappedChannel>),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_unmapped_channels(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/epg-mappings/unmapped")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let connection = state.database.read_connection();
+    let proxy_repo = StreamProxySeaOrmRepository::new(connection.clone());
+    match proxy_repo.find_by_id(&proxy_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found("proxy", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    }
+    let (stream_source_ids, epg_source_ids) = match tokio::try_join!(
+        proxy_repo.get_stream_source_ids(proxy_id),
+        proxy_repo.get_epg_source_ids(proxy_id)
+    ) {
+        Ok(ids) => ids,
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    };
+
+    let epg_channels = match EpgProgramSeaOrmRepository::new(connection.clone())
+        .list_channels(&epg_source_ids, None)
+        .await
+    {
+        Ok(channels) => channels,
+        Err(e) => {
+            return internal_error(&format!("Failed to list EPG channels: {e}")).into_response();
+        }
+    };
+    let mapped: HashSet<Uuid> = match ChannelEpgMappingSeaOrmRepository::new(connection.clone())
+        .list_for_proxy(&proxy_id)
+        .await
+    {
+        Ok(mappings) => mappings.into_iter().map(|m| m.channel_id).collect(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    };
+
+    let epg_channel_ids: HashSet<&str> = epg_channels
+        .iter()
+        .map(|c| c.epg_channel_id.as_str())
+        .collect();
+    // Suggest the EPG channel with the most programmes for each normalised name
+    let mut by_name: HashMap<String, &EpgChannelCandidate> = HashMap::new();
+    for candidate in &epg_channels {
+        let entry = by_name
+            .entry(normalize_channel_name(&candidate.channel_name))
+            .or_insert(candidate);
+        if candidate.programme_count > entry.programme_count {
+            *entry = candidate;
+        }
+    }
+
+    let channel_repo = ChannelSeaOrmRepository::new(connection);
+    let mut unmapped = Vec::new();
+    for source_id in stream_source_ids {
+        let channels = match channel_repo.find_by_source_id(&source_id).await {
+            Ok(channels) => channels,
+            Err(e) => return internal_error(&e.to_string()).into_response(),
+        };
+        for channel in channels {
+            if mapped.contains(&channel.id) {
+                continue;
+            }
+            let tvg_id = channel.tvg_id.as_deref().map(str::trim).unwrap_or("");
+            if !tvg_id.is_empty() && epg_channel_ids.contains(tvg_id) {
+                continue;
+            }
+            let suggestion = [Some(&channel.channel_name), channel.tvg_name.as_ref()]
+                .into_iter()
+                .flatten()
+                .find_map(|name| by_name.get(&normalize_channel_name(name)))
+                .map(|candidate| (*candidate).clone());
+            unmapped.push(UnmappedChannel {
+                channel_id: channel.id,
+                channel_name: channel.channel_name,
+                tvg_id: channel.tvg_id,
+                tvg_name: channel.tvg_name,
+                group_title: channel.group_title,
+                source_id: channel.source_id,
+                suggestion,
+            });
+        }
+    }
+
+    ok(unmapped).into_response()
+}
+
+/// Search EPG channels a proxy's channels can be mapped to
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/epg-mappings/candidates",
+    tag = "proxies",
+    summary = "Search EPG channels for mapping",
+    description = "Search the channels of the proxy's EPG sources by id or name. Results are ordered by match quality (exact normalised name or id, then prefix) and then by programme count.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        EpgChannelCandidatesQuery,
+    ),
+    responses(
+        (status = 200, description = "EPG channels", body = Vec<EpgChannelCandidate>),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn search_epg_channel_candidates(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<EpgChannelCandidatesQuery>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/epg-mappings/candidates")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let connection = state.database.read_connection();
+    let proxy_repo = StreamProxySeaOrmRepository::new(connection.clone());
+    match proxy_repo.find_by_id(&proxy_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found("proxy", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    }
+    let epg_source_ids = match proxy_repo.get_epg_source_ids(proxy_id).await {
+        Ok(ids) => ids,
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    };
+
+    let search = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let mut candidates = match EpgProgramSeaOrmRepository::new(connection)
+        .list_channels(&epg_source_ids, search)
+        .await
+    {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            return internal_error(&format!("Failed to search EPG channels: {e}")).into_response();
+        }
+    };
+    rank_candidates(search.unwrap_or(""), &mut candidates);
+    candidates.truncate(
+        query
+            .limit
+            .unwrap_or(DEFAULT_CANDIDATE_LIMIT)
+            .clamp(1, MAX_CANDIDATE_LIMIT),
+    );
+
+    ok(candidates).into_response()
+}
@@ -0,0 +1,205 @@
+//! Proxy channel exclusion handlers
+//!
+//! Quick "exclude channel" actions for a proxy: exclude channels (or a whole group) from
+//! the proxy's next generations without writing a filter, list what is excluded and
+//! re-enable it again.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::database::repositories::{
+    ChannelExclusionSeaOrmRepository, ChannelSeaOrmRepository, StreamProxySeaOrmRepository,
+};
+use crate::models::channel_exclusion::{ExcludeChannelsRequest, ProxyChannelExclusion};
+use crate::utils::resolve_proxy_id;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+/// List channels and groups excluded from a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/exclusions",
+    tag = "proxies",
+    summary = "List proxy channel exclusions",
+    description = "List the channels and groups currently excluded from a proxy's output, newest first",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Channel exclusions", body = Vec<ProxyChannelExclusion>),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_channel_exclusions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/exclusions").parse().unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let repo = ChannelExclusionSeaOrmRepository::new(state.database.read_connection());
+    match repo.list_for_proxy(&proxy_id).await {
+        Ok(exclusions) => ok(exclusions).into_response(),
+        Err(e) => {
+            internal_error(&format!("Failed to list channel exclusions: {e}")).into_response()
+        }
+    }
+}
+
+/// Exclude channels or a group from a proxy
+#[utoipa::path(
+    post,
+    path = "/proxies/{id}/exclusions",
+    tag = "proxies",
+    summary = "Exclude channels from proxy",
+    description = "Exclude the listed channels, and/or every channel of `group_title`, from the proxy's subsequent generations without authoring a filter. Exclusions apply after the proxy's filters, to the mapped channel data. Excluding something already excluded returns the existing exclusion.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = ExcludeChannelsRequest,
+    responses(
+        (status = 200, description = "Resulting exclusions", body = Vec<ProxyChannelExclusion>),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_channel_exclusions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<ExcludeChannelsRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/proxies/{id}/exclusions").parse().unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+    let group_title = request
+        .group_title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty());
+    if request.channel_ids.is_empty() && group_title.is_none() {
+        return bad_request("Provide channel_ids and/or group_title").into_response();
+    }
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    match proxy_repo.find_by_id(&proxy_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found("proxy", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    }
+
+    let repo = ChannelExclusionSeaOrmRepository::new(state.database.connection().clone());
+    let channel_repo = ChannelSeaOrmRepository::new(state.database.read_connection());
+    let mut exclusions = Vec::new();
+    for channel_id in request.channel_ids {
+        // Channels that are gone from the database (e.g. virtual channels) keep no name
+        let channel_name = match channel_repo.find_by_id(&channel_id).await {
+            Ok(channel) => channel.map(|c| c.channel_name),
+            Err(e) => return internal_error(&e.to_string()).into_response(),
+        };
+        match repo
+            .exclude_channel(proxy_id, channel_id, channel_name)
+            .await
+        {
+            Ok(exclusion) => exclusions.push(exclusion),
+            Err(e) => {
+                return internal_error(&format!("Failed to exclude channel: {e}")).into_response();
+            }
+        }
+    }
+    if let Some(group_title) = group_title {
+        match repo.exclude_group(proxy_id, group_title).await {
+            Ok(exclusion) => exclusions.push(exclusion),
+            Err(e) => {
+                return internal_error(&format!("Failed to exclude group: {e}")).into_response();
+            }
+        }
+    }
+
+    info!(
+        "Excluded {} channel(s)/group(s) from proxy {}",
+        exclusions.len(),
+        proxy_id
+    );
+    ok(exclusions).into_response()
+}
+
+/// Re-enable an excluded channel or group
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/exclusions/{exclusion_id}",
+    tag = "proxies",
+    summary = "Remove proxy channel exclusion",
+    description = "Remove an exclusion so the channel or group is included again from the proxy's next generation",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("exclusion_id" = String, Path, description = "Exclusion ID"),
+    ),
+    responses(
+        (status = 200, description = "Exclusion removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Exclusion not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_channel_exclusion(
+    State(state): State<AppState>,
+    Path((id, exclusion_id)): Path<(String, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/proxies/{id}/exclusions/{exclusion_id}")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+    let exclusion_uuid = match Uuid::parse_str(&exclusion_id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid exclusion ID").into_response(),
+    };
+
+    let repo = ChannelExclusionSeaOrmRepository::new(state.database.connection().clone());
+    match repo.delete(&proxy_id, &exclusion_uuid).await {
+        Ok(true) => {
+            info!(
+                "Removed channel exclusion {} from proxy {}",
+                exclusion_uuid, proxy_id
+            );
+            ok(serde_json::json!({"message": "Exclusion removed"})).into_response()
+        }
+        Ok(false) => not_found("exclusion", &exclusion_id).into_response(),
+        Err(e) => internal_error(&format!("Failed to remove exclusion: {e}")).into_response(),
+    }
+}
@@ -62,6 +62,10 @@ pub struct ChannelResponse {
     pub resolution: Option<String>,
     pub last_probed_at: Option<String>, // ISO 8601 datetime string
     pub probe_method: Option<String>,
+    /// Missing from the source's latest ingestions but retained for its grace period
+    pub stale: bool,
+    /// Consecutive ingestions the channel has been missing from
+    pub missed_ingestions: i32,
 }
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
@@ -94,7 +98,7 @@ pub async fn list_channels(
         let limit = params.limit.unwrap_or(50).min(500); // Cap at 500 items per page
 
         // Use SeaORM connection for read operations
-        let channel_repo = ChannelSeaOrmRepository::new(state.database.connection().clone());
+        let channel_repo = ChannelSeaOrmRepository::new(state.database.read_connection());
 
         // Simple implementation using SeaORM repository
         let all_channels = channel_repo
@@ -169,7 +173,7 @@ pub async fn list_channels(
             // Use SeaORM repository to get source names
             let stream_source_repo =
                 crate::database::repositories::StreamSourceSeaOrmRepository::new(
-                    state.database.connection().clone(),
+                    state.database.read_connection(),
                 );
             let mut names = std::collections::HashMap::new();
 
@@ -186,7 +190,7 @@ pub async fn list_channels(
         };
 
         // Get codec information for all channels by stream URL
-        let codec_repo = LastKnownCodecSeaOrmRepository::new(state.database.connection().clone());
+        let codec_repo = LastKnownCodecSeaOrmRepository::new(state.database.read_connection());
         let mut codec_info = std::collections::HashMap::new();
         for channel in paginated_channels.iter() {
             if let Ok(Some(codec)) = codec_repo.get_latest_codec_info(&channel.stream_url).await {
@@ -194,6 +198,12 @@ pub async fn list_channels(
             }
         }
 
+        let page_ids: Vec<uuid::Uuid> = paginated_channels.iter().map(|c| c.id).collect();
+        let stale_channels = channel_repo
+            .find_stale_channels(&page_ids)
+            .await
+            .unwrap_or_default();
+
         let mut channel_responses: Vec<ChannelResponse> = Vec::new();
         for channel in paginated_channels {
             let (stream_url, proxy_id, source_type, source_name) =
@@ -240,6 +250,8 @@ pub async fn list_channels(
                 resolution: codec.as_ref().and_then(|c| c.resolution.clone()),
                 last_probed_at: codec.as_ref().map(|c| c.detected_at.to_rfc3339()),
                 probe_method: codec.as_ref().map(|c| format!("{:?}", c.probe_method)),
+                stale: stale_channels.contains_key(&channel.id),
+                missed_ingestions: stale_channels.get(&channel.id).copied().unwrap_or(0),
             });
         }
 
@@ -294,7 +306,7 @@ pub async fn get_proxy_channels(
         let limit = params.limit.unwrap_or(50).min(500);
 
         // Use SeaORM connection for read operations
-        let channel_repo = ChannelSeaOrmRepository::new(state.database.connection().clone());
+        let channel_repo = ChannelSeaOrmRepository::new(state.database.read_connection());
 
         // Get channels for the specific source using SeaORM repository
         let source_channels = channel_repo
@@ -337,10 +349,18 @@ pub async fn get_proxy_channels(
             Vec::new()
         };
 
+        let page_ids: Vec<uuid::Uuid> = paginated_channels.iter().map(|c| c.id).collect();
+        let stale_channels = channel_repo
+            .find_stale_channels(&page_ids)
+            .await
+            .unwrap_or_default();
+
         // Convert Channel models to ChannelResponse
         let channel_responses: Vec<ChannelResponse> = paginated_channels
             .into_iter()
             .map(|channel| ChannelResponse {
+                stale: stale_channels.contains_key(&channel.id),
+                missed_ingestions: stale_channels.get(&channel.id).copied().unwrap_or(0),
                 id: channel.id.to_string(),
                 name: channel.channel_name,
                 logo_url: channel.tvg_logo,
@@ -453,18 +473,27 @@ pub async fn get_channel_stream(
     handle_result(inner(state, channel_id).await)
 }
 
-/// Probe codec information for a specific channel using ffprobe
+/// Query parameters for channel probing
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct ProbeChannelQuery {
+    /// Ignore any cached report and probe the upstream again
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Probe a channel and report stream diagnostics
 #[utoipa::path(
     post,
     path = "/api/v1/channels/{channel_id}/probe",
     tag = "channels",
-    summary = "Probe channel codec information",
-    description = "Run ffprobe on a channel to detect and store codec information",
+    summary = "Probe channel stream diagnostics",
+    description = "Run ffprobe on a channel to detect and store codec information, and sample the upstream briefly to report latency, bitrate, and audio/subtitle tracks. Reports are cached per stream for `channel_probe.cache_ttl`; pass `refresh=true` to bypass the cache.",
     params(
-        ("channel_id" = String, Path, description = "Channel ID")
+        ("channel_id" = String, Path, description = "Channel ID"),
+        ProbeChannelQuery
     ),
     responses(
-        (status = 200, description = "Channel probed successfully", body = HashMap<String, String>),
+        (status = 200, description = "Channel probed successfully", body = crate::services::channel_diagnostics::ChannelProbeReport),
         (status = 404, description = "Channel not found"),
         (status = 500, description = "Internal server error")
     )
@@ -472,11 +501,13 @@ pub async fn get_channel_stream(
 pub async fn probe_channel_codecs(
     State(state): State<AppState>,
     Path(channel_id): Path<String>,
+    Query(query): Query<ProbeChannelQuery>,
 ) -> impl IntoResponse {
     async fn inner(
         state: AppState,
         channel_id: String,
-    ) -> AppResult<crate::models::last_known_codec::LastKnownCodec> {
+        refresh: bool,
+    ) -> AppResult<crate::services::channel_diagnostics::ChannelProbeReport> {
         let channel_uuid = parse_uuid_flexible(&channel_id).map_err(|e| AppError::Validation {
             message: format!("Invalid channel ID format: {}", e),
         })?;
@@ -491,28 +522,133 @@ pub async fn probe_channel_codecs(
                 resource: "Channel".to_string(),
                 id: channel_id.clone(),
             })?;
-        let stream_url = channel.stream_url;
-        let persistence =
+        let diagnostics =
             state
-                .probe_persistence_service
+                .channel_diagnostics_service
                 .clone()
                 .ok_or_else(|| AppError::Validation {
                     message: "Probe persistence unavailable (ffprobe not configured)".to_string(),
                 })?;
-        let stored = persistence
-            .probe_and_persist(
-                &stream_url,
-                crate::models::last_known_codec::ProbeMethod::FfprobeManual,
-                Some("admin".to_string()),
-            )
+        // Sample the line a viewer would be served from, with the headers its source requires
+        let lines = crate::proxy::line_balancer::candidate_lines(
+            state.database.connection().clone(),
+            &state.session_tracker,
+            &channel,
+        )
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to resolve balanced lines for channel {}, using its own source: {}",
+                channel.id,
+                e
+            );
+            vec![channel.clone()]
+        });
+        let line = lines
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::ExternalService {
+                service: "upstream".to_string(),
+                message: "All lines for this channel are at their connection limit".to_string(),
+            })?;
+        let header_overrides = crate::proxy::http_stream::load_stream_header_overrides(
+            state.database.connection().clone(),
+            line.source_id,
+        )
+        .await;
+        let report = diagnostics
+            .diagnose(&line.stream_url, header_overrides.as_ref(), refresh)
             .await
             .map_err(|e| AppError::Validation {
                 message: format!("Probe failed: {}", e),
             })?;
-        Ok(stored)
+        Ok(report)
     }
 
-    handle_result(inner(state, channel_id).await)
+    handle_result(inner(state, channel_id, query.refresh).await)
+}
+
+/// Query parameters for channel previews
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct ChannelPreviewQuery {
+    /// Ignore any cached preview and capture a new frame
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Capture a preview frame from a channel's stream
+#[utoipa::path(
+    get,
+    path = "/api/v1/channels/{channel_id}/preview",
+    tag = "channels",
+    summary = "Get channel preview screenshot",
+    description = "Capture a single frame from the channel's stream with ffmpeg and return it as a JPEG. Previews are cached for `channel_preview.cache_ttl`; pass `refresh=true` to capture a new frame.",
+    params(
+        ("channel_id" = String, Path, description = "Channel ID"),
+        ChannelPreviewQuery
+    ),
+    responses(
+        (status = 200, description = "Preview image", content_type = "image/jpeg"),
+        (status = 404, description = "Channel not found"),
+        (status = 502, description = "Frame capture failed"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_channel_preview(
+    State(state): State<AppState>,
+    Path(channel_id): Path<String>,
+    Query(query): Query<ChannelPreviewQuery>,
+) -> axum::response::Response {
+    use axum::http::{StatusCode, header};
+
+    async fn inner(
+        state: AppState,
+        channel_id: String,
+        refresh: bool,
+    ) -> AppResult<crate::services::channel_preview::ChannelPreview> {
+        let channel_uuid = parse_uuid_flexible(&channel_id).map_err(|e| AppError::Validation {
+            message: format!("Invalid channel ID format: {}", e),
+        })?;
+        let channel_repo = ChannelSeaOrmRepository::new(state.database.connection().clone());
+        let channel = channel_repo
+            .find_by_id(&channel_uuid)
+            .await
+            .map_err(|e| AppError::Internal {
+                message: e.to_string(),
+            })?
+            .ok_or_else(|| AppError::NotFound {
+                resource: "Channel".to_string(),
+                id: channel_id.clone(),
+            })?;
+        state
+            .channel_preview_service
+            .preview(channel_uuid, &channel.stream_url, refresh)
+            .await
+            .map_err(|e| AppError::ExternalService {
+                service: "ffmpeg".to_string(),
+                message: format!("Preview capture failed: {}", e),
+            })
+    }
+
+    match inner(state, channel_id, query.refresh).await {
+        Ok(preview) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "image/jpeg".to_string()),
+                (
+                    header::CACHE_CONTROL,
+                    format!("private, max-age={}", preview.expires_in.as_secs()),
+                ),
+                (
+                    header::LAST_MODIFIED,
+                    crate::web::file_serving::http_date(preview.captured_at),
+                ),
+            ],
+            preview.image,
+        )
+            .into_response(),
+        Err(e) => crate::web::responses::handle_error(e).into_response(),
+    }
 }
 
 /// Proxy a channel stream directly (solves CORS issues)
@@ -532,17 +668,13 @@ pub async fn probe_channel_codecs(
 pub async fn proxy_channel_stream(
     State(state): State<AppState>,
     Path(channel_id): Path<String>,
+    client: crate::utils::forwarded::ClientAddress,
     headers: axum::http::HeaderMap,
 ) -> impl IntoResponse {
     use axum::http::StatusCode;
     use tracing::{debug, error, info, warn};
 
-    let client_ip = headers
-        .get("x-forwarded-for")
-        .or_else(|| headers.get("x-real-ip"))
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("unknown")
-        .to_string();
+    let client_ip = client.ip_string();
 
     let user_agent = headers
         .get("user-agent")
@@ -623,6 +755,38 @@ pub async fn proxy_channel_stream(
         }
     };
 
+    // The direct route names no proxy, so every proxy carrying the channel's source must let
+    // the client in; otherwise its access rules could be sidestepped
+    let proxy_ids = match state
+        .proxy_regeneration_service
+        .find_affected_proxies(channel.source_id, "stream")
+        .await
+    {
+        Ok(proxy_ids) => proxy_ids,
+        Err(e) => {
+            error!(
+                "Failed to look up proxies of source {}: {}",
+                channel.source_id, e
+            );
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error".to_string(),
+            )
+                .into_response();
+        }
+    };
+    for proxy_id in &proxy_ids {
+        if let Some(response) = crate::web::middleware::access_denied(
+            &state.access_policy,
+            &state.observability.access_decisions,
+            proxy_id,
+            client.ip,
+            "/channel/{channel_id}/stream",
+        ) {
+            return response;
+        }
+    }
+
     info!(
         "Proxying direct channel stream: '{}' from URL: {}",
         channel.channel_name, channel.stream_url
@@ -635,9 +799,15 @@ pub async fn proxy_channel_stream(
         ClassificationParams, StreamModeDecision, classify_stream,
     };
 
+    let header_overrides = crate::proxy::http_stream::load_stream_header_overrides(
+        state.database.connection().clone(),
+        channel.source_id,
+    )
+    .await;
+
     let classification_result = classify_stream(
         &channel.stream_url,
-        &reqwest::Client::new(),
+        &crate::proxy::http_stream::upstream_client(header_overrides.as_ref()),
         ClassificationParams {
             format: "auto",
             ..Default::default()
@@ -685,7 +855,9 @@ pub async fn proxy_channel_stream(
                 .unwrap_or_else(|| channel.stream_url.clone());
 
             let handle = crate::streaming::collapsing::spawn_collapsing_session(
-                Arc::new(reqwest::Client::new()),
+                Arc::new(crate::proxy::http_stream::upstream_client(
+                    header_overrides.as_ref(),
+                )),
                 collapsing_playlist_url,
                 class_res.target_duration,
                 crate::streaming::collapsing::CollapsingConfig::default(),
@@ -778,6 +950,7 @@ pub async fn proxy_channel_stream(
             state.session_tracker.clone(),
             session_stats,
             Some(meta),
+            header_overrides.as_ref(),
         )
         .await;
     }
@@ -796,6 +969,7 @@ pub async fn proxy_channel_stream(
         state.session_tracker.clone(),
         session_stats,
         Some(meta),
+        header_overrides.as_ref(),
     )
     .await
 }
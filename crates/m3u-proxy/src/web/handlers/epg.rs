@@ -7,8 +7,10 @@ use axum::{
     response::IntoResponse,
 };
 use chrono::{DateTime, Utc};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
 use crate::{
     errors::{AppError, AppResult},
@@ -100,7 +102,7 @@ pub async fn list_epg_programs(
 
         // Use clean SeaORM repository (rationalized approach)
         let epg_program_repo = crate::database::repositories::EpgProgramSeaOrmRepository::new(
-            state.database.connection().clone(),
+            state.database.read_connection(),
         );
 
         // Determine source filter
@@ -156,7 +158,7 @@ pub async fn list_epg_programs(
 
         // Check streamability for all programs (database-only, no external HTTP requests)
         let channel_repo = crate::database::repositories::ChannelSeaOrmRepository::new(
-            state.database.connection().clone(),
+            state.database.read_connection(),
         );
         let mut streamable_channels = std::collections::HashSet::new();
 
@@ -253,7 +255,7 @@ pub async fn get_source_epg_programs(
 
         // Use clean SeaORM repository (rationalized approach)
         let epg_source_repo = crate::database::repositories::EpgSourceSeaOrmRepository::new(
-            state.database.connection().clone(),
+            state.database.read_connection(),
         );
 
         // Verify source exists
@@ -278,7 +280,7 @@ pub async fn get_source_epg_programs(
             .unwrap_or_else(|| Utc::now() + chrono::Duration::hours(24));
 
         let epg_program_repo = crate::database::repositories::EpgProgramSeaOrmRepository::new(
-            state.database.connection().clone(),
+            state.database.read_connection(),
         );
 
         // Get programs by time range for specific source
@@ -326,7 +328,7 @@ pub async fn get_source_epg_programs(
 
         // Check streamability for all programs (database-only, no external HTTP requests)
         let channel_repo = crate::database::repositories::ChannelSeaOrmRepository::new(
-            state.database.connection().clone(),
+            state.database.read_connection(),
         );
         let mut streamable_channels = std::collections::HashSet::new();
 
@@ -407,7 +409,7 @@ pub async fn list_epg_sources(State(state): State<AppState>) -> impl IntoRespons
     async fn inner(state: AppState) -> AppResult<Vec<EpgSourceResponse>> {
         // Use clean SeaORM repository (rationalized approach)
         let epg_source_repo = crate::database::repositories::EpgSourceSeaOrmRepository::new(
-            state.database.connection().clone(),
+            state.database.read_connection(),
         );
 
         let sources = epg_source_repo
@@ -435,27 +437,82 @@ pub async fn list_epg_sources(State(state): State<AppState>) -> impl IntoRespons
     handle_result(inner(state).await)
 }
 
+/// Query of the EPG guide
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct EpgGuideQuery {
+    /// Filter by EPG source IDs (comma-separated)
+    pub source_id: Option<String>,
+    /// Only include these XMLTV channel ids (comma-separated)
+    pub channel_id: Option<String>,
+    /// Window start (ISO 8601, default now)
+    pub start_time: Option<DateTime<Utc>>,
+    /// Window end (ISO 8601, default six hours after now)
+    pub end_time: Option<DateTime<Utc>>,
+    /// Channels to skip, in channel name order
+    pub channel_offset: Option<u32>,
+    /// Most channels to return (default all)
+    pub channel_limit: Option<u32>,
+}
+
+/// A channel row of the EPG guide
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct EpgGuideChannel {
+    pub id: String,
+    pub name: String,
+    /// Channel logo, served from the logo cache when it has been cached
+    pub logo: Option<String>,
+}
+
+/// A programme cell of the EPG guide
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct EpgGuideProgram {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub category: Option<String>,
+    /// Programme icon, served from the logo cache when it has been cached
+    pub icon: Option<String>,
+}
+
+/// EPG guide grid for a time window and a page of channels
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct EpgGuideResponse {
+    /// Channels of this page by channel id
+    pub channels: HashMap<String, EpgGuideChannel>,
+    /// Channel ids of this page in display order
+    pub channel_order: Vec<String>,
+    /// Programmes by channel id, in start time order
+    pub programs: HashMap<String, Vec<EpgGuideProgram>>,
+    pub time_slots: Vec<DateTime<Utc>>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// Channels with programmes in the window, across all pages
+    pub total_channels: u32,
+    pub channel_offset: u32,
+    pub has_more_channels: bool,
+}
+
+/// Concurrent logo cache lookups while resolving guide images
+const IMAGE_LOOKUP_CONCURRENCY: usize = 32;
+
 /// Get EPG guide data (time-based grid format)
 #[utoipa::path(
     get,
     path = "/api/v1/epg/guide",
     tag = "epg",
-    params(EpgQuery),
+    params(EpgGuideQuery),
     responses(
-        (status = 200, description = "EPG guide data retrieved successfully"),
+        (status = 200, description = "EPG guide data retrieved successfully", body = EpgGuideResponse),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn get_epg_guide(
     State(state): State<AppState>,
-    Query(params): Query<EpgQuery>,
+    Query(params): Query<EpgGuideQuery>,
 ) -> impl IntoResponse {
-    async fn inner(
-        state: AppState,
-        params: EpgQuery,
-    ) -> AppResult<HashMap<String, serde_json::Value>> {
-        // This would return EPG data in a time-grid format suitable for TV guide display
-
+    async fn inner(state: AppState, params: EpgGuideQuery) -> AppResult<EpgGuideResponse> {
         let start_time = params.start_time.unwrap_or_else(Utc::now);
         let end_time = params
             .end_time
@@ -463,96 +520,194 @@ pub async fn get_epg_guide(
 
         // Use clean SeaORM repository (rationalized approach)
         let epg_program_repo = crate::database::repositories::EpgProgramSeaOrmRepository::new(
-            state.database.connection().clone(),
+            state.database.read_connection(),
         );
 
-        // Determine source filter
-        let source_filter = if let Some(source_id_str) = params.source_id {
-            parse_uuid_flexible(&source_id_str).ok()
-        } else {
-            None
-        };
+        // Determine source filter; unparseable ids are ignored
+        let source_filter: Vec<Uuid> = params
+            .source_id
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|id| parse_uuid_flexible(id.trim()).ok())
+            .collect();
 
         // Get programs by time range
-        let mut programs = epg_program_repo
-            .find_by_time_range(source_filter.as_ref(), &start_time, &end_time)
-            .await
-            .map_err(|e| AppError::Validation {
-                message: e.to_string(),
-            })?;
+        let mut programs = Vec::new();
+        if source_filter.is_empty() {
+            programs = epg_program_repo
+                .find_by_time_range(None, &start_time, &end_time)
+                .await
+                .map_err(|e| AppError::Validation {
+                    message: e.to_string(),
+                })?;
+        }
+        for source_id in &source_filter {
+            programs.extend(
+                epg_program_repo
+                    .find_by_time_range(Some(source_id), &start_time, &end_time)
+                    .await
+                    .map_err(|e| AppError::Validation {
+                        message: e.to_string(),
+                    })?,
+            );
+        }
+        if source_filter.len() > 1 {
+            programs.sort_by_key(|p| p.start_time);
+        }
 
         // Apply channel filter if specified
-        if let Some(channel_id_str) = params.channel_id
-            && let Ok(channel_id) = parse_uuid_flexible(&channel_id_str)
-        {
-            programs.retain(|p| p.channel_id == channel_id.to_string());
+        if let Some(channel_ids) = params.channel_id.as_deref() {
+            let channel_ids: HashSet<&str> = channel_ids
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .collect();
+            programs.retain(|p| channel_ids.contains(p.channel_id.as_str()));
         }
 
-        // No truncation - return all matching programs for comprehensive EPG display
-
-        // Group programs by channel for grid display
-        let mut grid_data = HashMap::new();
-        let mut channels = HashMap::new();
-        let mut time_slots = Vec::new();
+        // Window the channels in name order
+        let mut channel_names: HashMap<String, (String, Uuid)> = HashMap::new();
+        for program in &programs {
+            channel_names
+                .entry(program.channel_id.clone())
+                .or_insert_with(|| (program.channel_name.clone(), program.source_id));
+        }
+        let mut channel_order: Vec<String> = channel_names.keys().cloned().collect();
+        channel_order.sort_by(|a, b| {
+            let name = |id: &String| channel_names[id].0.to_lowercase();
+            name(a).cmp(&name(b)).then_with(|| a.cmp(b))
+        });
+        let total_channels = channel_order.len() as u32;
+        let channel_offset = params.channel_offset.unwrap_or(0);
+        let channel_order: Vec<String> = channel_order
+            .into_iter()
+            .skip(channel_offset as usize)
+            .take(
+                params
+                    .channel_limit
+                    .map_or(usize::MAX, |limit| limit as usize),
+            )
+            .collect();
+        let has_more_channels = channel_offset + (channel_order.len() as u32) < total_channels;
+        let page_channels: HashSet<&str> = channel_order.iter().map(String::as_str).collect();
+        programs.retain(|p| page_channels.contains(p.channel_id.as_str()));
+
+        // Channel logos come from the XMLTV channel icons, then from the logo of a stream
+        // channel with the same tvg-id
+        let source_ids: Vec<Uuid> = channel_order
+            .iter()
+            .map(|id| channel_names[id].1)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut channel_logos: HashMap<String, String> =
+            crate::database::repositories::EpgChannelMetadataSeaOrmRepository::new(
+                state.database.read_connection(),
+            )
+            .list_for_sources(&source_ids)
+            .await
+            .map_err(|e| AppError::Validation {
+                message: e.to_string(),
+            })?
+            .into_iter()
+            .filter(|metadata| page_channels.contains(metadata.channel_id.as_str()))
+            .filter_map(|metadata| {
+                let icon = metadata.icons.into_iter().next()?;
+                Some((metadata.channel_id, icon.value))
+            })
+            .collect();
+        let missing_logos: Vec<String> = channel_order
+            .iter()
+            .filter(|id| !channel_logos.contains_key(*id))
+            .cloned()
+            .collect();
+        channel_logos.extend(
+            crate::database::repositories::ChannelSeaOrmRepository::new(
+                state.database.read_connection(),
+            )
+            .find_logos_by_tvg_ids(&missing_logos)
+            .await
+            .map_err(|e| AppError::Validation {
+                message: e.to_string(),
+            })?,
+        );
 
-        for program in programs {
-            let channel_id = program.channel_id.to_string();
+        let image_urls: HashSet<String> = channel_logos
+            .values()
+            .cloned()
+            .chain(programs.iter().filter_map(|p| p.program_icon.clone()))
+            .collect();
+        let images = resolve_cached_images(&state, image_urls).await;
+        let image = |url: Option<&String>| url.and_then(|url| images.get(url).cloned());
 
-            // Track channels
+        let mut channels = HashMap::new();
+        for channel_id in &channel_order {
             channels.insert(
                 channel_id.clone(),
-                serde_json::json!({
-                    "id": channel_id,
-                    "name": program.channel_name,
-                    "logo": null // Not available in current model
-                }),
+                EpgGuideChannel {
+                    id: channel_id.clone(),
+                    name: channel_names[channel_id].0.clone(),
+                    logo: image(channel_logos.get(channel_id)),
+                },
             );
+        }
 
-            // Add program to channel's schedule
-            let programs = grid_data.entry(channel_id).or_insert_with(Vec::new);
-
-            programs.push(serde_json::json!({
-                "id": program.id.to_string(),
-                "title": program.program_title,
-                "description": program.program_description,
-                "start_time": program.start_time,
-                "end_time": program.end_time,
-                "category": program.program_category
-            }));
+        // Group programs by channel for grid display
+        let mut grid_data: HashMap<String, Vec<EpgGuideProgram>> = HashMap::new();
+        for program in programs {
+            let icon = image(program.program_icon.as_ref());
+            grid_data
+                .entry(program.channel_id)
+                .or_default()
+                .push(EpgGuideProgram {
+                    id: program.id.to_string(),
+                    title: program.program_title,
+                    description: program.program_description,
+                    start_time: program.start_time,
+                    end_time: program.end_time,
+                    category: program.program_category,
+                    icon,
+                });
         }
 
         // Generate time slots (hourly intervals)
+        let mut time_slots = Vec::new();
         let mut current_time = start_time;
         while current_time < end_time {
             time_slots.push(current_time);
             current_time += chrono::Duration::hours(1);
         }
 
-        let response = HashMap::from([
-            (
-                "channels".to_string(),
-                serde_json::to_value(channels).unwrap(),
-            ),
-            (
-                "programs".to_string(),
-                serde_json::to_value(grid_data).unwrap(),
-            ),
-            (
-                "time_slots".to_string(),
-                serde_json::to_value(time_slots).unwrap(),
-            ),
-            (
-                "start_time".to_string(),
-                serde_json::to_value(start_time).unwrap(),
-            ),
-            (
-                "end_time".to_string(),
-                serde_json::to_value(end_time).unwrap(),
-            ),
-        ]);
-
-        Ok(response)
+        Ok(EpgGuideResponse {
+            channels,
+            channel_order,
+            programs: grid_data,
+            time_slots,
+            start_time,
+            end_time,
+            total_channels,
+            channel_offset,
+            has_more_channels,
+        })
     }
 
     handle_result(inner(state, params).await)
 }
+
+/// Image URLs to show, by their source URL: the logo cache URL of cached images, otherwise
+/// the source URL itself
+async fn resolve_cached_images(state: &AppState, urls: HashSet<String>) -> HashMap<String, String> {
+    futures::stream::iter(urls)
+        .filter(|url| std::future::ready(!url.trim().is_empty()))
+        .map(|url| async move {
+            let resolved = match state.logo_asset_service.cached_logo_id(&url).await {
+                Some(cache_id) => state.logo_asset_service.get_cached_logo_url(&cache_id, ""),
+                None => url.clone(),
+            };
+            (url, resolved)
+        })
+        .buffer_unordered(IMAGE_LOOKUP_CONCURRENCY)
+        .collect()
+        .await
+}
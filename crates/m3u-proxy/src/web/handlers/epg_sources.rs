@@ -18,7 +18,7 @@ use crate::models::{EpgSource, EpgSourceType};
 use crate::web::{
     AppState,
     extractors::{EpgSourceFilterParams, ListParams, RequestContext},
-    responses::{ApiResponse, PaginatedResponse, ok},
+    responses::{ApiResponse, PaginatedResponse, ok, validation_error},
     utils::{extract_uuid_param, log_request},
 };
 
@@ -159,6 +159,10 @@ impl From<EpgSource> for EpgSourceResponse {
         ("search" = Option<String>, Query, description = "Search term"),
         ("source_type" = Option<String>, Query, description = "Filter by source type"),
         ("enabled" = Option<bool>, Query, description = "Filter by enabled status"),
+        ("offset" = Option<u32>, Query, description = "0-based offset; overrides page"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous next_cursor; overrides offset and page"),
+        ("sort_by" = Option<String>, Query, description = "Sort field: name, created_at, updated_at"),
+        ("sort_ascending" = Option<bool>, Query, description = "Sort direction (default true)"),
         ("healthy" = Option<bool>, Query, description = "Filter by health status"),
     ),
     responses(
@@ -191,13 +195,9 @@ pub async fn list_epg_sources(
             }
 
             // Apply filtering if needed
-            if let Some(search) = list_params.search.search {
-                let search_lower = search.to_lowercase();
-                response_items.retain(|item| {
-                    item.name.to_lowercase().contains(&search_lower)
-                        || item.url.to_lowercase().contains(&search_lower)
-                });
-            }
+            list_params.apply_search(&mut response_items, |item, term| {
+                item.name.to_lowercase().contains(term) || item.url.to_lowercase().contains(term)
+            });
 
             if let Some(source_type_str) = filter_params.source_type {
                 response_items.retain(|item| {
@@ -205,29 +205,26 @@ pub async fn list_epg_sources(
                 });
             }
 
-            let total = response_items.len();
-
-            // Apply pagination
-            let page = list_params.pagination.page;
-            let limit = list_params.pagination.limit as usize;
-            let offset = ((page - 1) * limit as u32) as usize;
-
-            let paginated_items = if offset < response_items.len() {
-                response_items
-                    .into_iter()
-                    .skip(offset)
-                    .take(limit)
-                    .collect()
-            } else {
-                Vec::new()
+            if let Some(enabled) = filter_params.enabled {
+                response_items.retain(|item| item.is_active == enabled);
+            }
+
+            let sort_field = match list_params.sort_field(&["name", "created_at", "updated_at"]) {
+                Ok(field) => field,
+                Err(errors) => return validation_error(errors).into_response(),
             };
+            match sort_field {
+                Some("created_at") => response_items
+                    .sort_by(|a, b| list_params.direction(a.created_at.cmp(&b.created_at))),
+                Some("updated_at") => response_items
+                    .sort_by(|a, b| list_params.direction(a.updated_at.cmp(&b.updated_at))),
+                Some("name") => response_items.sort_by(|a, b| {
+                    list_params.direction(a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                }),
+                _ => {}
+            }
 
-            let paginated_response = crate::web::responses::PaginatedResponse::new(
-                paginated_items,
-                total as u64,
-                page,
-                limit as u32,
-            );
+            let paginated_response = list_params.paginate(response_items);
             ok(paginated_response).into_response()
         }
         Err(e) => {
@@ -394,7 +391,7 @@ pub async fn update_epg_source(
     path = "/sources/epg/{id}",
     tag = "sources-epg",
     summary = "Delete EPG source",
-    description = "Delete an EPG source and clean up related data",
+    description = "Move an EPG source to the trash. It disappears from listings and proxies until restored via `/trash`, and is purged permanently after the trash retention period",
     params(
         ("id" = String, Path, description = "EPG source ID (UUID)", example = "550e8400-e29b-41d4-a716-446655440000"),
     ),
@@ -489,3 +486,396 @@ pub async fn validate_epg_source(
         }
     }
 }
+
+/// List archived XMLTV snapshots of an EPG source
+#[utoipa::path(
+    get,
+    path = "/sources/epg/{id}/snapshots",
+    tag = "sources-epg",
+    summary = "List ingest snapshots",
+    description = "Raw XMLTV guides archived by previous ingestions of an XMLTV source, newest first. Requires `[ingest_archive]` to be enabled.",
+    params(
+        ("id" = String, Path, description = "EPG source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Archived snapshots", body = Vec<crate::models::ingest_snapshot::IngestSnapshot>),
+        (status = 400, description = "Invalid ID or ingest archive disabled"),
+        (status = 404, description = "EPG source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_epg_source_snapshots(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/epg/{id}/snapshots")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    let Some(archive) = state.epg_source_service.ingest_archive() else {
+        return crate::web::responses::bad_request("Ingest archive is not enabled").into_response();
+    };
+    let epg_source_repo = crate::database::repositories::EpgSourceSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match epg_source_repo.find_by_id(&uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return crate::web::responses::not_found("epg_source", &id).into_response(),
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+
+    match archive
+        .list(
+            crate::models::ingest_snapshot::IngestSnapshotKind::Epg,
+            uuid,
+        )
+        .await
+    {
+        Ok(snapshots) => ok(snapshots).into_response(),
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Re-run ingestion of an EPG source from an archived XMLTV guide
+#[utoipa::path(
+    post,
+    path = "/sources/epg/{id}/snapshots/{snapshot_id}/replay",
+    tag = "sources-epg",
+    summary = "Replay ingest snapshot",
+    description = "Parse an archived XMLTV guide with the current parser and replace the source's programmes with the result, as a refresh would. Useful for reproducing parsing issues against the exact content that was served.",
+    params(
+        ("id" = String, Path, description = "EPG source ID (UUID)"),
+        ("snapshot_id" = String, Path, description = "Snapshot ID"),
+    ),
+    responses(
+        (status = 200, description = "Snapshot replayed", body = crate::models::ingest_snapshot::IngestSnapshotReplayResult),
+        (status = 400, description = "Invalid ID, ingest archive disabled or not an XMLTV source"),
+        (status = 404, description = "EPG source or snapshot not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn replay_epg_source_snapshot(
+    State(state): State<AppState>,
+    Path((id, snapshot_id)): Path<(String, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    use crate::models::ingest_snapshot::{IngestSnapshotKind, IngestSnapshotReplayResult};
+
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/sources/epg/{id}/snapshots/{snapshot_id}/replay")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    let Some(archive) = state.epg_source_service.ingest_archive() else {
+        return crate::web::responses::bad_request("Ingest archive is not enabled").into_response();
+    };
+
+    let epg_source_repo = crate::database::repositories::EpgSourceSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    let source = match epg_source_repo.find_by_id(&uuid).await {
+        Ok(Some(source)) => source,
+        Ok(None) => return crate::web::responses::not_found("epg_source", &id).into_response(),
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+    if source.source_type != EpgSourceType::Xmltv {
+        return crate::web::responses::bad_request("Only XMLTV sources have snapshots")
+            .into_response();
+    }
+
+    let snapshot = match archive
+        .get(IngestSnapshotKind::Epg, uuid, &snapshot_id)
+        .await
+    {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => {
+            return crate::web::responses::not_found("snapshot", &snapshot_id).into_response();
+        }
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+
+    match state
+        .epg_source_service
+        .replay_snapshot(&source, &snapshot)
+        .await
+    {
+        Ok(records_saved) => {
+            state
+                .proxy_regeneration_service
+                .queue_affected_proxies_coordinated(uuid, "epg")
+                .await;
+            ok(IngestSnapshotReplayResult {
+                snapshot,
+                records_saved,
+            })
+            .into_response()
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to replay snapshot {} of EPG source {}: {}",
+                snapshot_id,
+                uuid,
+                e
+            );
+            crate::web::responses::internal_error(&e.to_string()).into_response()
+        }
+    }
+}
+
+/// Largest accepted XMLTV upload, before decompression
+pub const MAX_XMLTV_UPLOAD_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Body limit of the XMLTV upload route, leaving room for multipart framing
+pub const XMLTV_UPLOAD_BODY_LIMIT: usize = MAX_XMLTV_UPLOAD_BYTES as usize + 1024 * 1024;
+
+/// Temp sandbox directory holding XMLTV uploads until they are ingested
+const XMLTV_UPLOAD_DIR: &str = "xmltv_uploads";
+
+/// An XMLTV upload that was received and is being ingested
+#[derive(Debug, Serialize, ToSchema)]
+pub struct XmltvUploadResponse {
+    /// File-backed EPG source the guide is ingested into
+    pub source_id: Uuid,
+    pub source_name: String,
+    pub bytes_received: u64,
+    /// Progress operation of the upload and ingestion
+    pub operation_id: Uuid,
+    pub progress_url: String,
+}
+
+/// A received XMLTV upload awaiting ingestion
+struct ReceivedXmltv {
+    source: EpgSource,
+    path: String,
+    bytes: u64,
+    progress: std::sync::Arc<crate::services::progress_service::ProgressManager>,
+}
+
+/// Upload an XMLTV guide into a file-backed EPG source
+#[utoipa::path(
+    post,
+    path = "/sources/epg/upload",
+    tag = "sources-epg",
+    summary = "Upload XMLTV guide",
+    description = "Upload an `.xml` or `.xml.gz` guide as multipart field `file`. The file is streamed to disk as it arrives and ingested in the background into the file-backed EPG source named after it, as the XMLTV watch folder would. Upload and ingestion progress is reported as an EPG ingestion of that source.",
+    request_body(content = String, description = "Multipart form data with the XMLTV guide in field `file`"),
+    responses(
+        (status = 200, description = "Guide received, ingestion started", body = XmltvUploadResponse),
+        (status = 400, description = "Missing or invalid file"),
+        (status = 409, description = "Source is already being ingested"),
+        (status = 413, description = "Guide larger than 1 GiB"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn upload_epg_source_xmltv(
+    State(state): State<AppState>,
+    context: RequestContext,
+    headers: axum::http::HeaderMap,
+    mut multipart: axum::extract::Multipart,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &"/api/v1/sources/epg/upload".parse().unwrap(),
+        &context,
+    );
+
+    let expected_bytes = headers
+        .get(axum::http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    let mut received = None;
+    loop {
+        match multipart.next_field().await {
+            Ok(Some(mut field)) if field.name() == Some("file") => {
+                match receive_xmltv(&state, &mut field, expected_bytes).await {
+                    Ok(upload) => received = Some(upload),
+                    Err(response) => return response,
+                }
+                break;
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => break,
+            Err(e) => {
+                return crate::web::responses::bad_request(&format!("Invalid upload: {e}"))
+                    .into_response();
+            }
+        }
+    }
+    let Some(ReceivedXmltv {
+        source,
+        path,
+        bytes,
+        progress,
+    }) = received
+    else {
+        return crate::web::responses::bad_request("Missing file field").into_response();
+    };
+
+    let operation_id = progress.get_progress().await.id;
+    let response = XmltvUploadResponse {
+        source_id: source.id,
+        source_name: source.name.clone(),
+        bytes_received: bytes,
+        operation_id,
+        progress_url: format!(
+            "/api/v1/progress/events?operation_type=epg_ingestion&owner_id={}",
+            source.id
+        ),
+    };
+
+    let epg_source_service = state.epg_source_service.clone();
+    let proxy_regeneration_service = state.proxy_regeneration_service.clone();
+    let temp_file_manager = state.temp_file_manager.clone();
+    tokio::spawn(async move {
+        let updater = progress.get_stage_updater("ingestion").await;
+        let result = match temp_file_manager.get_full_path(&path) {
+            Ok(full_path) => {
+                epg_source_service
+                    .ingest_local_xmltv_file(&source, &full_path, updater.as_ref())
+                    .await
+            }
+            Err(e) => Err(anyhow::anyhow!("Failed to locate upload: {e}")),
+        };
+        match result {
+            Ok(programs) => {
+                tracing::info!(
+                    "Ingested uploaded XMLTV into EPG source '{}': {} programs",
+                    source.name,
+                    programs
+                );
+                proxy_regeneration_service
+                    .queue_affected_proxies_coordinated(source.id, "epg")
+                    .await;
+                progress.complete().await;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to ingest uploaded XMLTV into EPG source '{}': {}",
+                    source.name,
+                    e
+                );
+                progress.fail(&format!("Ingestion failed: {e}")).await;
+            }
+        }
+        if let Err(e) = temp_file_manager.remove_file(&path).await {
+            tracing::warn!("Failed to remove XMLTV upload {}: {}", path, e);
+        }
+    });
+
+    ok(response).into_response()
+}
+
+/// Stream an XMLTV upload to the temp sandbox under a progress operation of its source
+async fn receive_xmltv(
+    state: &AppState,
+    field: &mut axum::extract::multipart::Field<'_>,
+    expected_bytes: Option<u64>,
+) -> Result<ReceivedXmltv, axum::response::Response> {
+    use crate::web::responses::{bad_request, conflict, internal_error};
+
+    // Browsers may send a path; only the file name names the source
+    let file_name = field
+        .file_name()
+        .and_then(|name| name.rsplit(['/', '\\']).next())
+        .unwrap_or_default()
+        .to_string();
+    let Some(import_name) = crate::services::xmltv_import::import_name_for_file(&file_name) else {
+        return Err(bad_request("Expected an .xml or .xml.gz file").into_response());
+    };
+
+    let source = state
+        .epg_source_service
+        .ensure_file_backed_source(&import_name)
+        .await
+        .map_err(|e| internal_error(&e.to_string()).into_response())?;
+    if !source.is_active {
+        return Err(bad_request(&format!(
+            "File-backed EPG source '{}' is inactive",
+            source.name
+        ))
+        .into_response());
+    }
+
+    let progress = state
+        .progress_service
+        .create_staged_progress_manager(
+            source.id,
+            "epg_source".to_string(),
+            crate::services::progress_service::OperationType::EpgIngestion,
+            format!("XMLTV Upload: {}", source.name),
+        )
+        .await
+        .map_err(|_| {
+            conflict(&format!(
+                "EPG source '{}' is already being ingested",
+                source.name
+            ))
+            .into_response()
+        })?;
+    let progress = progress
+        .add_stage("upload", "Upload")
+        .await
+        .add_stage("ingestion", "Ingestion")
+        .await;
+    let updater = progress.get_stage_updater("upload").await;
+
+    let extension = if file_name.to_lowercase().ends_with(".gz") {
+        "xml.gz"
+    } else {
+        "xml"
+    };
+    let path = format!("{XMLTV_UPLOAD_DIR}/{}.{extension}", Uuid::new_v4());
+    let stored = match state
+        .temp_file_manager
+        .create_dir_all(XMLTV_UPLOAD_DIR)
+        .await
+    {
+        Ok(()) => {
+            crate::web::uploads::stream_field_to_file(
+                field,
+                &state.temp_file_manager,
+                &path,
+                MAX_XMLTV_UPLOAD_BYTES,
+                updater.as_ref(),
+                expected_bytes,
+            )
+            .await
+        }
+        Err(e) => Err(crate::web::uploads::UploadError::Write(e.to_string())),
+    };
+
+    match stored {
+        Ok(bytes) => {
+            if let Some(updater) = &updater {
+                updater.complete_stage().await;
+            }
+            Ok(ReceivedXmltv {
+                source,
+                path,
+                bytes,
+                progress,
+            })
+        }
+        Err(e) => {
+            progress.fail(&e.to_string()).await;
+            Err(e.into_response())
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Filter group handlers
+//!
+//! Named, ordered sets of filters and attaching/detaching them to a proxy as a unit.
+//! Attaching appends the group's filters to the end of the proxy's filter list; the
+//! proxy's own filter list stays the source of truth for generation.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::database::repositories::{
+    FilterGroupSeaOrmRepository, FilterSeaOrmRepository, StreamProxySeaOrmRepository,
+};
+use crate::models::proxy_template::{FilterGroup, FilterGroupRequest};
+use crate::utils::resolve_proxy_id;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, conflict, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+/// List filter groups
+#[utoipa::path(
+    get,
+    path = "/filter-groups",
+    tag = "filters",
+    summary = "List filter groups",
+    description = "List all filter groups with their member filters in attach order",
+    responses(
+        (status = 200, description = "Filter groups", body = Vec<FilterGroup>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_filter_groups(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/filter-groups".parse().unwrap(),
+        &context,
+    );
+
+    let repo = FilterGroupSeaOrmRepository::new(state.database.read_connection());
+    match repo.list_all().await {
+        Ok(groups) => ok(groups).into_response(),
+        Err(e) => internal_error(&format!("Failed to list filter groups: {e}")).into_response(),
+    }
+}
+
+/// Get a filter group
+#[utoipa::path(
+    get,
+    path = "/filter-groups/{id}",
+    tag = "filters",
+    summary = "Get filter group",
+    params(
+        ("id" = Uuid, Path, description = "Filter group ID"),
+    ),
+    responses(
+        (status = 200, description = "Filter group", body = FilterGroup),
+        (status = 404, description = "Filter group not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_filter_group(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/filter-groups/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let repo = FilterGroupSeaOrmRepository::new(state.database.read_connection());
+    match repo.find_by_id(&id).await {
+        Ok(Some(group)) => ok(group).into_response(),
+        Ok(None) => not_found("Filter group", &id.to_string()).into_response(),
+        Err(e) => internal_error(&format!("Failed to load filter group: {e}")).into_response(),
+    }
+}
+
+/// Create a filter group
+#[utoipa::path(
+    post,
+    path = "/filter-groups",
+    tag = "filters",
+    summary = "Create filter group",
+    description = "Create a named group of filters. Filters are attached to proxies in the listed order.",
+    request_body = FilterGroupRequest,
+    responses(
+        (status = 200, description = "Created filter group", body = FilterGroup),
+        (status = 400, description = "Invalid request or unknown filter"),
+        (status = 409, description = "A filter group with this name exists"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_filter_group(
+    State(state): State<AppState>,
+    context: RequestContext,
+    Json(request): Json<FilterGroupRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &"/api/v1/filter-groups".parse().unwrap(),
+        &context,
+    );
+
+    if let Err(response) = validate_request(&state, None, &request).await {
+        return response;
+    }
+
+    let repo = FilterGroupSeaOrmRepository::new(state.database.connection().clone());
+    match repo.create(request).await {
+        Ok(group) => {
+            info!("Created filter group '{}' ({})", group.name, group.id);
+            ok(group).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to create filter group: {e}")).into_response(),
+    }
+}
+
+/// Replace a filter group
+#[utoipa::path(
+    put,
+    path = "/filter-groups/{id}",
+    tag = "filters",
+    summary = "Update filter group",
+    description = "Replace a filter group's name, description and members. Proxies the group was attached to keep the filters they already have.",
+    params(
+        ("id" = Uuid, Path, description = "Filter group ID"),
+    ),
+    request_body = FilterGroupRequest,
+    responses(
+        (status = 200, description = "Updated filter group", body = FilterGroup),
+        (status = 400, description = "Invalid request or unknown filter"),
+        (status = 404, description = "Filter group not found"),
+        (status = 409, description = "A filter group with this name exists"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_filter_group(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    context: RequestContext,
+    Json(request): Json<FilterGroupRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/filter-groups/{id}").parse().unwrap(),
+        &context,
+    );
+
+    if let Err(response) = validate_request(&state, Some(id), &request).await {
+        return response;
+    }
+
+    let repo = FilterGroupSeaOrmRepository::new(state.database.connection().clone());
+    match repo.update(&id, request).await {
+        Ok(Some(group)) => ok(group).into_response(),
+        Ok(None) => not_found("Filter group", &id.to_string()).into_response(),
+        Err(e) => internal_error(&format!("Failed to update filter group: {e}")).into_response(),
+    }
+}
+
+/// Delete a filter group
+#[utoipa::path(
+    delete,
+    path = "/filter-groups/{id}",
+    tag = "filters",
+    summary = "Delete filter group",
+    description = "Delete a filter group. Its filters, and proxies they were attached to, are unaffected.",
+    params(
+        ("id" = Uuid, Path, description = "Filter group ID"),
+    ),
+    responses(
+        (status = 200, description = "Filter group deleted"),
+        (status = 404, description = "Filter group not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_filter_group(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/filter-groups/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let repo = FilterGroupSeaOrmRepository::new(state.database.connection().clone());
+    match repo.delete(&id).await {
+        Ok(true) => ok(serde_json::json!({"message": "Filter group deleted"})).into_response(),
+        Ok(false) => not_found("Filter group", &id.to_string()).into_response(),
+        Err(e) => internal_error(&format!("Failed to delete filter group: {e}")).into_response(),
+    }
+}
+
+/// Attach a filter group to a proxy
+#[utoipa::path(
+    post,
+    path = "/proxies/{id}/filter-groups/{group_id}",
+    tag = "proxies",
+    summary = "Attach filter group to proxy",
+    description = "Append the group's filters to the end of the proxy's filter list, in group order. Filters the proxy already has keep their position. Takes effect on the next generation.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("group_id" = Uuid, Path, description = "Filter group ID"),
+    ),
+    responses(
+        (status = 200, description = "IDs of the filters that were added", body = Vec<Uuid>),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 404, description = "Proxy or filter group not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn attach_filter_group(
+    State(state): State<AppState>,
+    Path((id, group_id)): Path<(String, Uuid)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/proxies/{id}/filter-groups/{group_id}")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let (proxy_id, group) = match load_proxy_and_group(&state, &id, group_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    match proxy_repo.attach_filters(proxy_id, &group.filter_ids).await {
+        Ok(attached) => {
+            info!(
+                "Attached filter group '{}' to proxy {} ({} filters added)",
+                group.name,
+                proxy_id,
+                attached.len()
+            );
+            ok(attached).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to attach filter group: {e}")).into_response(),
+    }
+}
+
+/// Detach a filter group from a proxy
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/filter-groups/{group_id}",
+    tag = "proxies",
+    summary = "Detach filter group from proxy",
+    description = "Remove the group's current member filters from the proxy's filter list. Takes effect on the next generation.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("group_id" = Uuid, Path, description = "Filter group ID"),
+    ),
+    responses(
+        (status = 200, description = "Filter group detached"),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 404, description = "Proxy or filter group not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn detach_filter_group(
+    State(state): State<AppState>,
+    Path((id, group_id)): Path<(String, Uuid)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/proxies/{id}/filter-groups/{group_id}")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let (proxy_id, group) = match load_proxy_and_group(&state, &id, group_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    match proxy_repo.detach_filters(proxy_id, &group.filter_ids).await {
+        Ok(removed) => {
+            info!(
+                "Detached filter group '{}' from proxy {} ({} filters removed)",
+                group.name, proxy_id, removed
+            );
+            ok(serde_json::json!({ "removed_filters": removed })).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to detach filter group: {e}")).into_response(),
+    }
+}
+
+/// Check the group name is unique and every member filter exists
+async fn validate_request(
+    state: &AppState,
+    group_id: Option<Uuid>,
+    request: &FilterGroupRequest,
+) -> Result<(), axum::response::Response> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(bad_request("Filter group name must not be empty").into_response());
+    }
+
+    let repo = FilterGroupSeaOrmRepository::new(state.database.read_connection());
+    match repo.list_all().await {
+        Ok(groups) => {
+            if groups
+                .iter()
+                .any(|group| Some(group.id) != group_id && group.name.eq_ignore_ascii_case(name))
+            {
+                return Err(
+                    conflict(&format!("A filter group named '{name}' already exists"))
+                        .into_response(),
+                );
+            }
+        }
+        Err(e) => {
+            return Err(
+                internal_error(&format!("Failed to list filter groups: {e}")).into_response(),
+            );
+        }
+    }
+
+    let filter_repo = FilterSeaOrmRepository::new(state.database.read_connection());
+    for filter_id in &request.filter_ids {
+        match filter_repo.find_by_id(*filter_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(bad_request(&format!("Unknown filter: {filter_id}")).into_response());
+            }
+            Err(e) => {
+                return Err(internal_error(&format!("Failed to load filter: {e}")).into_response());
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn load_proxy_and_group(
+    state: &AppState,
+    id: &str,
+    group_id: Uuid,
+) -> Result<(Uuid, FilterGroup), axum::response::Response> {
+    let proxy_id = resolve_proxy_id(id).map_err(|e| bad_request(&e.to_string()).into_response())?;
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.read_connection());
+    match proxy_repo.find_by_id(&proxy_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(not_found("Proxy", id).into_response()),
+        Err(e) => {
+            return Err(internal_error(&format!("Failed to load proxy: {e}")).into_response());
+        }
+    }
+
+    let group_repo = FilterGroupSeaOrmRepository::new(state.database.read_connection());
+    match group_repo.find_by_id(&group_id).await {
+        Ok(Some(group)) => Ok((proxy_id, group)),
+        Ok(None) => Err(not_found("Filter group", &group_id.to_string()).into_response()),
+        Err(e) => Err(internal_error(&format!("Failed to load filter group: {e}")).into_response()),
+    }
+}
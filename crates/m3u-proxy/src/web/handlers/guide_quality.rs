@@ -0,0 +1,98 @@
+//! Guide data quality report handler
+//!
+//! Analyzes a proxy's latest generated playlist and XMLTV so missing or stale guide
+//! data can be found before viewers notice it.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::database::repositories::StreamProxySeaOrmRepository;
+use crate::services::guide_quality::{GuideQualityReport, analyze_guide};
+use crate::utils::resolve_proxy_id;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+/// Query parameters for the guide quality report
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct GuideQualityQuery {
+    /// Only list channels without guide data or without upcoming programmes
+    #[serde(default)]
+    pub issues_only: bool,
+}
+
+/// Report EPG coverage of a proxy's latest generation
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/guide-quality",
+    tag = "proxies",
+    summary = "Guide data quality report",
+    description = "Analyze the proxy's latest generated playlist and XMLTV: share of channels with guide data, channels without upcoming programmes, programmes missing descriptions or categories, and how far ahead each channel's guide reaches",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        GuideQualityQuery,
+    ),
+    responses(
+        (status = 200, description = "Guide quality report", body = GuideQualityReport),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 404, description = "Proxy not found or not generated yet"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_guide_quality(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<GuideQualityQuery>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/guide-quality")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.read_connection());
+    let proxy = match proxy_repo.find_by_id(&proxy_id).await {
+        Ok(Some(proxy)) => proxy,
+        Ok(None) => return not_found("Proxy", &id).into_response(),
+        Err(e) => return internal_error(&format!("Failed to load proxy: {e}")).into_response(),
+    };
+
+    let file_manager = &state.proxy_output_file_manager;
+    let (m3u, xmltv) = match (
+        file_manager
+            .read_to_string(&format!("{proxy_id}.m3u8"))
+            .await,
+        file_manager
+            .read_to_string(&format!("{proxy_id}.xmltv"))
+            .await,
+    ) {
+        (Ok(m3u), Ok(xmltv)) => (m3u, xmltv),
+        _ => return not_found("Generated output for proxy", &id).into_response(),
+    };
+
+    match analyze_guide(&m3u, &xmltv, chrono::Utc::now()) {
+        Ok(mut report) => {
+            report.generated_at = proxy.last_generated_at;
+            if query.issues_only {
+                report.channels.retain(|c| c.has_issue());
+            }
+            ok(report).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to analyze guide: {e}")).into_response(),
+    }
+}
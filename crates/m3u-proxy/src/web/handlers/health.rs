@@ -191,6 +191,73 @@ pub async fn readiness_check(
     }
 }
 
+/// Query parameters of the deep health check
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct DeepHealthQuery {
+    /// Override the configured strictness: critical, standard or strict
+    pub strictness: Option<crate::config::DeepHealthStrictness>,
+}
+
+/// Deep health check exercising every dependency
+#[utoipa::path(
+    get,
+    path = "/health/deep",
+    tag = "health",
+    summary = "Deep health check",
+    description = "Checks the database (round trip latency), job queue (backlog and runner heartbeat), scheduler (last tick), storage (a write to each area), ffmpeg and circuit breakers, reporting each as healthy, degraded or unhealthy with its latency. Answers 503 when the outcome fails the strictness: `critical` fails only on an unhealthy database, job queue or storage, `standard` on any unhealthy component, `strict` on anything not healthy.",
+    params(DeepHealthQuery),
+    responses(
+        (status = 200, description = "Instance passes at the requested strictness", body = crate::services::deep_health::DeepHealthReport),
+        (status = 503, description = "Instance fails at the requested strictness", body = crate::services::deep_health::DeepHealthReport)
+    )
+)]
+pub async fn deep_health_check(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<DeepHealthQuery>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/health/deep".parse().unwrap(),
+        &context,
+    );
+
+    let config = state.config.deep_health.clone().unwrap_or_default();
+    let strictness = query.strictness.unwrap_or(config.strictness);
+    let ffmpeg_command = state
+        .config
+        .relay
+        .as_ref()
+        .map(|relay| relay.ffmpeg_command.clone())
+        .unwrap_or_else(|| "ffmpeg".to_string());
+    let checker = crate::services::deep_health::DeepHealthChecker::new(
+        config,
+        state.database.clone(),
+        state.job_queue.clone(),
+        state.job_queue_runner.clone(),
+        state.job_scheduler.clone(),
+        state.start_time,
+    )
+    .with_storage("proxy_output", state.proxy_output_file_manager.clone())
+    .with_storage("logos", state.logo_file_manager.clone())
+    .with_storage("pipeline", state.pipeline_file_manager.clone())
+    .with_storage("temp", state.temp_file_manager.clone())
+    .with_ffmpeg_command(ffmpeg_command)
+    .with_circuit_breakers(state.circuit_breaker_manager.clone());
+
+    let report = checker.check(strictness).await;
+    let status = if report.passing {
+        axum::http::StatusCode::OK
+    } else {
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        [(axum::http::header::CACHE_CONTROL, "no-store")],
+        axum::Json(report),
+    )
+}
+
 /// Liveness check (for Kubernetes probes)
 #[utoipa::path(
     get,
@@ -439,6 +506,13 @@ async fn check_database_health(
         idle_connections,
         pool_utilization_percent: (active_connections as f32 / max_connections as f32 * 100.0)
             as u32,
+        read_replica: database.replica.as_ref().map(|replica| {
+            if replica.is_healthy() {
+                "healthy".to_string()
+            } else {
+                "fallback_to_primary".to_string()
+            }
+        }),
     }
 }
 
@@ -573,6 +647,7 @@ pub async fn get_relay_system_health_from_manager(
             healthy_processes: 0,
             unhealthy_processes: 0,
             processes: Vec::new(),
+            transcode_devices: Vec::new(),
             last_check: chrono::Utc::now(),
         }
     });
@@ -0,0 +1,162 @@
+//! Job queue handlers
+//!
+//! Inspect pending jobs and reprioritize or cancel them before they start. Running jobs
+//! are not listed and cannot be changed.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::job_scheduling::{JobClass, JobPriority, ScheduledJob};
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, no_content, not_found, ok},
+    utils::log_request,
+};
+
+/// A job waiting in the queue
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QueuedJobResponse {
+    pub id: Uuid,
+    /// Deduplication key, e.g. `epg:<source id>`
+    pub job_key: String,
+    pub class: JobClass,
+    /// Source or proxy the job operates on (none for maintenance jobs)
+    pub resource_id: Option<Uuid>,
+    pub priority: JobPriority,
+    pub scheduled_time: DateTime<Utc>,
+}
+
+impl From<ScheduledJob> for QueuedJobResponse {
+    fn from(job: ScheduledJob) -> Self {
+        Self {
+            id: job.id,
+            job_key: job.job_key(),
+            class: job.class(),
+            resource_id: job.job_type.resource_id(),
+            priority: job.priority,
+            scheduled_time: job.scheduled_time,
+        }
+    }
+}
+
+/// Request to change a queued job's priority
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateQueuedJobRequest {
+    pub priority: JobPriority,
+}
+
+/// List queued jobs
+#[utoipa::path(
+    get,
+    path = "/jobs/queue",
+    tag = "jobs",
+    summary = "List queued jobs",
+    description = "List pending jobs in execution order (priority, then scheduled time)",
+    responses(
+        (status = 200, description = "Queued jobs", body = Vec<QueuedJobResponse>)
+    )
+)]
+pub async fn list_queued_jobs(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/jobs/queue".parse().unwrap(),
+        &context,
+    );
+
+    let jobs: Vec<QueuedJobResponse> = state
+        .job_queue
+        .list_pending()
+        .await
+        .into_iter()
+        .map(QueuedJobResponse::from)
+        .collect();
+    ok(jobs).into_response()
+}
+
+/// Reprioritize a queued job
+#[utoipa::path(
+    patch,
+    path = "/jobs/queue/{id}",
+    tag = "jobs",
+    summary = "Reprioritize queued job",
+    description = "Change the priority of a pending job. Higher priority jobs are started first once a slot in their concurrency class is free.",
+    params(
+        ("id" = String, Path, description = "Job ID"),
+    ),
+    request_body = UpdateQueuedJobRequest,
+    responses(
+        (status = 200, description = "Job reprioritized", body = QueuedJobResponse),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "No pending job with this ID")
+    )
+)]
+pub async fn update_queued_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<UpdateQueuedJobRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PATCH,
+        &format!("/api/v1/jobs/queue/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid job ID").into_response(),
+    };
+
+    match state.job_queue.reprioritize(uuid, request.priority).await {
+        Some(job) => ok(QueuedJobResponse::from(job)).into_response(),
+        None => not_found("queued job", &id).into_response(),
+    }
+}
+
+/// Cancel a queued job
+#[utoipa::path(
+    delete,
+    path = "/jobs/queue/{id}",
+    tag = "jobs",
+    summary = "Cancel queued job",
+    description = "Remove a pending job from the queue. Jobs that have already started run to completion.",
+    params(
+        ("id" = String, Path, description = "Job ID"),
+    ),
+    responses(
+        (status = 204, description = "Job cancelled"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "No pending job with this ID")
+    )
+)]
+pub async fn cancel_queued_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/jobs/queue/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid job ID").into_response(),
+    };
+
+    match state.job_queue.cancel(uuid).await {
+        Some(_) => no_content().into_response(),
+        None => not_found("queued job", &id).into_response(),
+    }
+}
@@ -0,0 +1,241 @@
+//! Bulk logo upload and channel logo assignment handlers
+//!
+//! Upload a ZIP of logos named after channels, assign the logos that matched no channel by
+//! hand, and list or remove channel logo assignments. Assigned logos replace the source
+//! logo of their channels from each proxy's next generation.
+
+use axum::{
+    extract::{Multipart, Path, State},
+    response::IntoResponse,
+};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::database::repositories::ChannelLogoAssignmentSeaOrmRepository;
+use crate::models::channel_logo_assignment::{
+    AssignStagedLogosRequest, ChannelLogoAssignment, LogoBulkUploadReport, MatchedLogo,
+};
+use crate::services::LogoBulkUploadService;
+use crate::services::logo_bulk_upload::{MAX_ARCHIVE_BYTES, MAX_LOGO_BYTES};
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, handle_error, internal_error, not_found, ok},
+    uploads::stream_field_to_file,
+    utils::log_request,
+};
+
+/// Body limit of the bulk upload route, leaving room for multipart framing
+pub const BULK_UPLOAD_BODY_LIMIT: usize = MAX_ARCHIVE_BYTES + 1024 * 1024;
+
+/// Body limit of the single logo upload routes, leaving room for multipart framing
+pub const LOGO_UPLOAD_BODY_LIMIT: usize = MAX_LOGO_BYTES as usize + 1024 * 1024;
+
+fn bulk_upload_service(state: &AppState) -> LogoBulkUploadService {
+    LogoBulkUploadService::new(
+        state.database.connection(),
+        state.logo_asset_service.clone(),
+        state.temp_file_manager.clone(),
+    )
+}
+
+/// Upload a ZIP archive of logos
+#[utoipa::path(
+    post,
+    path = "/logos/bulk-upload",
+    tag = "logos",
+    summary = "Bulk upload logos",
+    description = "Upload a ZIP archive of PNG, JPEG, GIF, WebP or SVG logos as multipart field `file`. Logos whose file name matches a channel name, tvg-name or tvg-id (ignoring case, punctuation and quality suffixes such as HD) are saved as logo assets and assigned to those channels. Other logos are staged under the returned batch id for manual assignment.",
+    request_body(content = String, description = "Multipart form data with the ZIP archive in field `file`"),
+    responses(
+        (status = 200, description = "Matching results", body = LogoBulkUploadReport),
+        (status = 400, description = "Missing or invalid archive"),
+        (status = 413, description = "Archive larger than 100 MiB"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn bulk_upload_logos(
+    State(state): State<AppState>,
+    context: RequestContext,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &"/api/v1/logos/bulk-upload".parse().unwrap(),
+        &context,
+    );
+
+    // The archive is streamed to the staging sandbox rather than buffered
+    let service = bulk_upload_service(&state);
+    let mut archive_path = None;
+    loop {
+        match multipart.next_field().await {
+            Ok(Some(mut field)) if field.name() == Some("file") => {
+                let path = match service.archive_upload_path().await {
+                    Ok(path) => path,
+                    Err(e) => return handle_error(e).into_response(),
+                };
+                if let Err(e) = stream_field_to_file(
+                    &mut field,
+                    &state.temp_file_manager,
+                    &path,
+                    MAX_ARCHIVE_BYTES as u64,
+                    None,
+                    None,
+                )
+                .await
+                {
+                    return e.into_response();
+                }
+                archive_path = Some(path);
+                break;
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => break,
+            Err(e) => return bad_request(&format!("Invalid upload: {e}")).into_response(),
+        }
+    }
+    let Some(archive_path) = archive_path else {
+        return bad_request("Missing file field").into_response();
+    };
+
+    let result = service.upload(&archive_path).await;
+    if let Err(e) = state.temp_file_manager.remove_file(&archive_path).await {
+        warn!("Failed to remove uploaded archive {}: {}", archive_path, e);
+    }
+    match result {
+        Ok(report) => ok(report).into_response(),
+        Err(e) => handle_error(e).into_response(),
+    }
+}
+
+/// Assign staged logos of a bulk upload to channels
+#[utoipa::path(
+    post,
+    path = "/logos/bulk-upload/{batch_id}/assign",
+    tag = "logos",
+    summary = "Assign staged logos",
+    description = "Save staged logos of a bulk upload as logo assets and assign them to the given channels, replacing their existing assignments",
+    params(
+        ("batch_id" = String, Path, description = "Bulk upload batch ID"),
+    ),
+    request_body = AssignStagedLogosRequest,
+    responses(
+        (status = 200, description = "Assigned logos", body = Vec<MatchedLogo>),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Staged logo or channel not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn assign_staged_logos(
+    State(state): State<AppState>,
+    Path(batch_id): Path<String>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<AssignStagedLogosRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/logos/bulk-upload/{batch_id}/assign")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let batch_uuid = match Uuid::parse_str(&batch_id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid batch ID").into_response(),
+    };
+    if let Err(e) = request.validate() {
+        return bad_request(&e).into_response();
+    }
+
+    match bulk_upload_service(&state)
+        .assign_staged(batch_uuid, &request.assignments)
+        .await
+    {
+        Ok(assigned) => {
+            info!(
+                "Assigned {} staged logo(s) of batch {}",
+                assigned.len(),
+                batch_uuid
+            );
+            ok(assigned).into_response()
+        }
+        Err(e) => handle_error(e).into_response(),
+    }
+}
+
+/// List channel logo assignments
+#[utoipa::path(
+    get,
+    path = "/logos/assignments",
+    tag = "logos",
+    summary = "List channel logo assignments",
+    description = "List the channels showing an uploaded logo instead of their source logo",
+    responses(
+        (status = 200, description = "Logo assignments", body = Vec<ChannelLogoAssignment>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_logo_assignments(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/logos/assignments".parse().unwrap(),
+        &context,
+    );
+
+    let repo = ChannelLogoAssignmentSeaOrmRepository::new(state.database.read_connection());
+    match repo.list().await {
+        Ok(assignments) => ok(assignments).into_response(),
+        Err(e) => internal_error(&format!("Failed to list logo assignments: {e}")).into_response(),
+    }
+}
+
+/// Remove the logo assignment of a channel
+#[utoipa::path(
+    delete,
+    path = "/logos/assignments/{channel_id}",
+    tag = "logos",
+    summary = "Remove channel logo assignment",
+    description = "Remove a channel's logo assignment so it shows its source logo again from each proxy's next generation. The logo asset is kept.",
+    params(
+        ("channel_id" = String, Path, description = "Channel ID"),
+    ),
+    responses(
+        (status = 200, description = "Assignment removed"),
+        (status = 400, description = "Invalid channel ID"),
+        (status = 404, description = "Assignment not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_logo_assignment(
+    State(state): State<AppState>,
+    Path(channel_id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/logos/assignments/{channel_id}")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let channel_uuid = match Uuid::parse_str(&channel_id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid channel ID").into_response(),
+    };
+
+    let repo = ChannelLogoAssignmentSeaOrmRepository::new(state.database.connection());
+    match repo.delete(&channel_uuid).await {
+        Ok(true) => {
+            info!("Removed logo assignment of channel {}", channel_uuid);
+            ok(serde_json::json!({"message": "Logo assignment removed"})).into_response()
+        }
+        Ok(false) => not_found("logo assignment", &channel_id).into_response(),
+        Err(e) => internal_error(&format!("Failed to remove logo assignment: {e}")).into_response(),
+    }
+}
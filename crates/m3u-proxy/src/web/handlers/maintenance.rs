@@ -0,0 +1,133 @@
+//! Maintenance mode handlers
+//!
+//! While maintenance mode is on, streaming and regeneration endpoints answer 503 with
+//! `Retry-After`, the job queue runner starts no new jobs (scheduled and requested work
+//! stays queued) and read-only admin APIs keep working. Turning it off resumes the queue.
+
+use axum::{extract::State, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::runtime_settings::MaintenanceState;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, ok},
+    utils::log_request,
+};
+
+/// `Retry-After` used when a request does not set one
+const DEFAULT_RETRY_AFTER_SECONDS: u64 = 300;
+
+/// Maintenance mode state and the work held back by it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+    pub since: Option<DateTime<Utc>>,
+    pub message: Option<String>,
+    pub retry_after_seconds: Option<u64>,
+    /// Jobs waiting in the queue
+    pub queued_jobs: usize,
+    /// Jobs started before maintenance began that are still running
+    pub running_jobs: usize,
+}
+
+/// Request to turn maintenance mode on or off
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMaintenanceRequest {
+    pub enabled: bool,
+    /// Message returned to rejected clients
+    pub message: Option<String>,
+    /// `Retry-After` for rejected requests (1-86400 seconds, default 300)
+    pub retry_after_seconds: Option<u64>,
+}
+
+async fn maintenance_status(state: &AppState) -> MaintenanceStatus {
+    let maintenance = state.runtime_settings_store.maintenance().await;
+    MaintenanceStatus {
+        enabled: maintenance.is_some(),
+        since: maintenance.as_ref().map(|m| m.since),
+        message: maintenance.as_ref().and_then(|m| m.message.clone()),
+        retry_after_seconds: maintenance.as_ref().map(|m| m.retry_after_seconds),
+        queued_jobs: state.job_queue.pending_count().await,
+        running_jobs: state.job_queue.running_count().await,
+    }
+}
+
+/// Get maintenance mode status
+#[utoipa::path(
+    get,
+    path = "/maintenance",
+    tag = "settings",
+    summary = "Get maintenance mode",
+    responses(
+        (status = 200, description = "Maintenance mode status", body = MaintenanceStatus)
+    )
+)]
+pub async fn get_maintenance(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/maintenance".parse().unwrap(),
+        &context,
+    );
+
+    ok(maintenance_status(&state).await).into_response()
+}
+
+/// Turn maintenance mode on or off
+#[utoipa::path(
+    put,
+    path = "/maintenance",
+    tag = "settings",
+    summary = "Set maintenance mode",
+    description = "Enable maintenance mode to reject streaming and regeneration requests with 503 and hold queued jobs; running jobs finish. Disabling it resumes the job queue immediately. Not persisted across restarts.",
+    request_body = UpdateMaintenanceRequest,
+    responses(
+        (status = 200, description = "Maintenance mode updated", body = MaintenanceStatus),
+        (status = 400, description = "Invalid request")
+    )
+)]
+pub async fn update_maintenance(
+    State(state): State<AppState>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<UpdateMaintenanceRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &"/api/v1/maintenance".parse().unwrap(),
+        &context,
+    );
+
+    if request.enabled {
+        let retry_after_seconds = request
+            .retry_after_seconds
+            .unwrap_or(DEFAULT_RETRY_AFTER_SECONDS);
+        if !(1..=86400).contains(&retry_after_seconds) {
+            return bad_request("retry_after_seconds must be between 1 and 86400").into_response();
+        }
+        // Keep the original start time when only the message or retry hint changes
+        let since = state
+            .runtime_settings_store
+            .maintenance()
+            .await
+            .map_or_else(Utc::now, |m| m.since);
+        state
+            .runtime_settings_store
+            .set_maintenance(Some(MaintenanceState {
+                since,
+                message: request.message.filter(|m| !m.trim().is_empty()),
+                retry_after_seconds,
+            }))
+            .await;
+        state.job_queue_runner.pause();
+    } else {
+        state.runtime_settings_store.set_maintenance(None).await;
+        state.job_queue_runner.resume();
+    }
+
+    ok(maintenance_status(&state).await).into_response()
+}
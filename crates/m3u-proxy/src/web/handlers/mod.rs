@@ -4,16 +4,39 @@
 //! Each handler module focuses on a specific domain area and uses
 //! the service layer for business logic.
 
+pub mod auth;
+pub mod channel_epg_mappings;
+pub mod channel_exclusions;
 pub mod channels;
 pub mod circuit_breaker;
 pub mod epg;
 pub mod epg_sources;
 pub mod features;
+pub mod filter_groups;
+pub mod guide_quality;
 pub mod health;
 pub mod index;
+pub mod jobs;
+pub mod logo_uploads;
+pub mod maintenance;
+pub mod pipeline_artifacts;
 pub mod proxies;
+pub mod proxy_basic_auth;
+pub mod proxy_order;
+pub mod proxy_preview;
+pub mod proxy_settings;
+pub mod proxy_templates;
+pub mod rule_versions;
+pub mod schedules;
+pub mod search;
+pub mod sessions;
+pub mod share_links;
 pub mod static_assets;
+pub mod storage;
 pub mod stream_sources;
+pub mod trash;
+pub mod url_rewrite_rules;
+pub mod virtual_channels;
 
 // Re-export common handler utilities
 pub use crate::web::extractors::*;
@@ -0,0 +1,172 @@
+//! Pipeline artifact inspection handlers
+//!
+//! Lists and fetches the per-stage artifact samples persisted during regenerations when
+//! `pipeline_inspection.enabled` is set.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+use crate::pipeline::services::ArtifactSampleStore;
+use crate::utils::resolve_proxy_id;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+fn sample_store(state: &AppState) -> ArtifactSampleStore {
+    let sample_size = state
+        .config
+        .pipeline_inspection
+        .as_ref()
+        .map(|c| c.sample_size)
+        .unwrap_or_default();
+    ArtifactSampleStore::new(state.pipeline_file_manager.clone(), sample_size)
+}
+
+/// List regenerations of a proxy with persisted artifact samples
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/pipeline-artifacts",
+    tag = "proxies",
+    summary = "List pipeline artifact generations",
+    description = "List recent pipeline executions of a proxy that have per-stage artifact samples, newest first. Samples are only recorded when `pipeline_inspection.enabled` is set and are kept for the pipeline storage retention.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Generations with samples", body = Vec<crate::pipeline::services::artifact_inspection::GenerationSamples>),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_artifact_generations(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/pipeline-artifacts")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    match sample_store(&state).list_generations(proxy_id).await {
+        Ok(generations) => ok(generations).into_response(),
+        Err(e) => internal_error(&format!("Failed to list artifact samples: {e}")).into_response(),
+    }
+}
+
+/// List the stage samples of one regeneration
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/pipeline-artifacts/{execution_id}",
+    tag = "proxies",
+    summary = "List stage artifact samples",
+    description = "Return the artifact samples of every stage of a pipeline execution, in stage order",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("execution_id" = String, Path, description = "Pipeline execution ID"),
+    ),
+    responses(
+        (status = 200, description = "Stage samples", body = Vec<crate::pipeline::services::artifact_inspection::StageArtifactSamples>),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "No samples for this execution"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_generation_stages(
+    State(state): State<AppState>,
+    Path((id, execution_id)): Path<(String, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/pipeline-artifacts/{execution_id}")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+    let execution_uuid = match Uuid::parse_str(&execution_id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid execution ID").into_response(),
+    };
+
+    match sample_store(&state)
+        .list_stages(proxy_id, execution_uuid)
+        .await
+    {
+        Ok(stages) if stages.is_empty() => {
+            not_found("pipeline execution", &execution_id).into_response()
+        }
+        Ok(stages) => ok(stages).into_response(),
+        Err(e) => internal_error(&format!("Failed to read artifact samples: {e}")).into_response(),
+    }
+}
+
+/// Fetch the artifact samples of a single stage
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/pipeline-artifacts/{execution_id}/{stage_id}",
+    tag = "proxies",
+    summary = "Get stage artifact samples",
+    description = "Return the first/last records of each channel and programme artifact produced by a stage (e.g. data_mapping, filtering, numbering)",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("execution_id" = String, Path, description = "Pipeline execution ID"),
+        ("stage_id" = String, Path, description = "Stage ID"),
+    ),
+    responses(
+        (status = 200, description = "Stage samples", body = crate::pipeline::services::artifact_inspection::StageArtifactSamples),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "No samples for this stage"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_stage_samples(
+    State(state): State<AppState>,
+    Path((id, execution_id, stage_id)): Path<(String, String, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/pipeline-artifacts/{execution_id}/{stage_id}")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+    let execution_uuid = match Uuid::parse_str(&execution_id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid execution ID").into_response(),
+    };
+
+    match sample_store(&state)
+        .get_stage(proxy_id, execution_uuid, &stage_id)
+        .await
+    {
+        Ok(Some(stage)) => ok(stage).into_response(),
+        Ok(None) => not_found("pipeline stage samples", &stage_id).into_response(),
+        Err(e) => internal_error(&format!("Failed to read artifact samples: {e}")).into_response(),
+    }
+}
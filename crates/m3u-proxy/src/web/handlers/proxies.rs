@@ -13,17 +13,29 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    config::PlaylistCacheConfig,
     database::repositories::{
         ChannelSeaOrmRepository, FilterSeaOrmRepository, StreamProxySeaOrmRepository,
         StreamSourceSeaOrmRepository,
     },
-    models::{StreamProxy, StreamProxyMode},
-    proxy::session_tracker::{ClientInfo, SessionStats},
+    models::{
+        BackupStreamMode, ChannelNumberBlock, OfflineSlateMode, OutputProfile, StreamProxy,
+        StreamProxyMode,
+    },
+    proxy::{
+        session_tracker::{ClientInfo, SessionIdentity, SessionStats},
+        stream_latency::StartupPhase,
+    },
     streaming::classification::{ClassificationParams, StreamModeDecision, classify_stream},
-    utils::{resolve_proxy_id, uuid_parser::parse_uuid_flexible},
+    utils::{
+        StreamUrlSigner, forwarded::ClientAddress, resolve_proxy_id,
+        stream_signing::STREAM_TOKEN_PARAM, uuid_parser::parse_uuid_flexible,
+    },
     web::{
         AppState,
         extractors::{ListParams, RequestContext},
+        file_serving::{generation_etag, http_date, is_not_modified},
+        middleware::ProxyBasicAuthUser,
         responses::ok,
         utils::log_request,
     },
@@ -74,6 +86,37 @@ pub struct CreateStreamProxyRequest {
     #[serde(default)]
     pub cache_program_logos: bool,
     pub relay_profile_id: Option<Uuid>,
+    /// Serve the playlist with HMAC-signed stream URLs that expire after
+    /// `stream_signing.url_lifetime`
+    #[serde(default)]
+    pub sign_stream_urls: bool,
+    /// Client preset for the playlist ("standard" or "kodi")
+    #[serde(default)]
+    pub output_profile: OutputProfile,
+    /// Emit streams of the same channel from lower-priority sources as backups
+    /// ("off", "attribute" or "group")
+    #[serde(default)]
+    pub backup_streams: BackupStreamMode,
+    /// Serve a "channel unavailable" slate while the upstream is down, in proxy mode
+    /// ("off", "generated" or "media")
+    #[serde(default)]
+    pub offline_slate: OfflineSlateMode,
+    /// Comma-separated preferred languages of EPG channel names and icons, in order
+    /// (e.g. "de,en"); unset keeps the playlist's channel names and logos
+    #[serde(default)]
+    pub epg_languages: Option<String>,
+    /// Seconds source updates are batched for before the proxy regenerates once
+    /// (unset uses the global `regeneration.debounce`)
+    #[serde(default)]
+    pub regeneration_debounce_seconds: Option<i32>,
+    /// Channel number blocks per group (e.g. News from 100, Sports from 200); channels of
+    /// other groups, and those overflowing their block, number from `starting_channel_number`
+    #[serde(default)]
+    pub channel_number_blocks: Vec<ChannelNumberBlock>,
+    /// IANA timezone the XMLTV programme times are written in, e.g. "America/New_York"
+    /// (unset writes UTC)
+    #[serde(default)]
+    pub epg_timezone: Option<String>,
 }
 
 fn default_cache_channel_logos() -> bool {
@@ -103,6 +146,8 @@ pub struct ProxyFilterRequest {
 }
 
 /// Request DTO for updating a stream proxy
+///
+/// Proxy settings the request omits keep their current value.
 #[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct UpdateStreamProxyRequest {
     pub name: String,
@@ -124,6 +169,49 @@ pub struct UpdateStreamProxyRequest {
     pub cache_program_logos: bool,
     #[serde(deserialize_with = "crate::utils::deserialize_optional_uuid")]
     pub relay_profile_id: Option<Uuid>,
+    /// Sign the playlist's stream URLs with expiring tokens
+    #[serde(default)]
+    pub sign_stream_urls: Option<bool>,
+    /// Client preset for the playlist ("standard" or "kodi")
+    #[serde(default)]
+    pub output_profile: Option<OutputProfile>,
+    /// Emit streams of the same channel from lower-priority sources as backups
+    /// ("off", "attribute" or "group")
+    #[serde(default)]
+    pub backup_streams: Option<BackupStreamMode>,
+    /// Serve a "channel unavailable" slate while the upstream is down, in proxy mode
+    /// ("off", "generated" or "media")
+    #[serde(default)]
+    pub offline_slate: Option<OfflineSlateMode>,
+    /// Comma-separated preferred languages of EPG channel names and icons, in order
+    /// (e.g. "de,en"); null keeps the playlist's channel names and logos
+    #[serde(
+        default,
+        deserialize_with = "crate::utils::deserialize_nullable_update"
+    )]
+    #[schema(value_type = Option<String>)]
+    pub epg_languages: Option<Option<String>>,
+    /// Seconds source updates are batched for before the proxy regenerates once
+    /// (null uses the global `regeneration.debounce`; omitted keeps the current value)
+    #[serde(
+        default,
+        deserialize_with = "crate::utils::deserialize_nullable_update"
+    )]
+    #[schema(value_type = Option<i32>)]
+    pub regeneration_debounce_seconds: Option<Option<i32>>,
+    /// Channel number blocks per group (e.g. News from 100, Sports from 200); channels of
+    /// other groups, and those overflowing their block, number from `starting_channel_number`
+    /// (omitted keeps the current blocks)
+    #[serde(default)]
+    pub channel_number_blocks: Option<Vec<ChannelNumberBlock>>,
+    /// IANA timezone the XMLTV programme times are written in, e.g. "America/New_York"
+    /// (null writes UTC; omitted keeps the current timezone)
+    #[serde(
+        default,
+        deserialize_with = "crate::utils::deserialize_nullable_update"
+    )]
+    #[schema(value_type = Option<String>)]
+    pub epg_timezone: Option<Option<String>>,
 }
 
 /// Response DTO for stream proxy
@@ -144,11 +232,23 @@ pub struct StreamProxyResponse {
     pub cache_channel_logos: bool,
     pub cache_program_logos: bool,
     pub relay_profile_id: Option<Uuid>,
+    pub sign_stream_urls: bool,
+    pub output_profile: OutputProfile,
+    pub backup_streams: BackupStreamMode,
+    pub offline_slate: OfflineSlateMode,
+    pub epg_languages: Option<String>,
+    pub regeneration_debounce_seconds: Option<i32>,
+    pub channel_number_blocks: Vec<ChannelNumberBlock>,
+    pub epg_timezone: Option<String>,
     pub stream_sources: Vec<ProxySourceResponse>,
     pub epg_sources: Vec<ProxyEpgSourceResponse>,
     pub filters: Vec<ProxyFilterResponse>,
     pub m3u8_url: String,
     pub xmltv_url: String,
+    /// Set while the latest generation changed the channel count beyond the
+    /// `channel_count_alerts` threshold; only populated on the detail response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_count_alert: Option<crate::models::channel_count_alert::ChannelCountAlert>,
 }
 
 /// Stream source in proxy response
@@ -226,6 +326,14 @@ impl CreateStreamProxyRequest {
             cache_channel_logos: self.cache_channel_logos,
             cache_program_logos: self.cache_program_logos,
             relay_profile_id: self.relay_profile_id,
+            sign_stream_urls: self.sign_stream_urls,
+            output_profile: self.output_profile,
+            backup_streams: self.backup_streams,
+            offline_slate: self.offline_slate,
+            epg_languages: self.epg_languages,
+            regeneration_debounce_seconds: self.regeneration_debounce_seconds,
+            channel_number_blocks: self.channel_number_blocks,
+            epg_timezone: self.epg_timezone,
         })
     }
 }
@@ -258,11 +366,20 @@ impl StreamProxyResponse {
             cache_channel_logos: proxy.cache_channel_logos,
             cache_program_logos: proxy.cache_program_logos,
             relay_profile_id: proxy.relay_profile_id,
+            sign_stream_urls: proxy.sign_stream_urls,
+            output_profile: proxy.output_profile,
+            backup_streams: proxy.backup_streams,
+            offline_slate: proxy.offline_slate,
+            epg_languages: proxy.epg_languages,
+            regeneration_debounce_seconds: proxy.regeneration_debounce_seconds,
+            channel_number_blocks: proxy.channel_number_blocks,
+            epg_timezone: proxy.epg_timezone,
             stream_sources: vec![], // Will be populated by service layer
             epg_sources: vec![],    // Will be populated by service layer
             filters: vec![],        // Will be populated by service layer
             m3u8_url: format!("{trimmed_base_url}/proxy/{proxy_id_b64}/m3u8"),
             xmltv_url: format!("{trimmed_base_url}/proxy/{proxy_id_b64}/xmltv"),
+            channel_count_alert: None,
         }
     }
 }
@@ -290,11 +407,20 @@ impl From<StreamProxy> for StreamProxyResponse {
             cache_channel_logos: proxy.cache_channel_logos,
             cache_program_logos: proxy.cache_program_logos,
             relay_profile_id: proxy.relay_profile_id,
+            sign_stream_urls: proxy.sign_stream_urls,
+            output_profile: proxy.output_profile,
+            backup_streams: proxy.backup_streams,
+            offline_slate: proxy.offline_slate,
+            epg_languages: proxy.epg_languages,
+            regeneration_debounce_seconds: proxy.regeneration_debounce_seconds,
+            channel_number_blocks: proxy.channel_number_blocks,
+            epg_timezone: proxy.epg_timezone,
             stream_sources: vec![], // Will be populated by service layer
             epg_sources: vec![],    // Will be populated by service layer
             filters: vec![],        // Will be populated by service layer
             m3u8_url: String::new(),
             xmltv_url: String::new(),
+            channel_count_alert: None,
         }
     }
 }
@@ -405,7 +531,11 @@ pub struct ProcessingEvent {
     params(
         ("page" = Option<u32>, Query, description = "Page number (1-based)"),
         ("limit" = Option<u32>, Query, description = "Number of items per page"),
-        ("search" = Option<String>, Query, description = "Search term"),
+        ("search" = Option<String>, Query, description = "Search term for name or description"),
+        ("offset" = Option<u32>, Query, description = "0-based offset; overrides page"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous next_cursor; overrides offset and page"),
+        ("sort_by" = Option<String>, Query, description = "Sort field: name, created_at, updated_at"),
+        ("sort_ascending" = Option<bool>, Query, description = "Sort direction (default true)"),
     ),
     responses(
         (status = 200, description = "List of stream proxies"),
@@ -446,20 +576,34 @@ pub async fn list_proxies(
             system: state.system.clone(),
         });
 
-    // Get proxies with pagination
-    let limit = Some(list_params.pagination.limit as usize);
-    let offset = Some(((list_params.pagination.page - 1) * list_params.pagination.limit) as usize);
-
-    match service.list(limit, offset).await {
-        Ok(proxies) => {
-            let total_count = proxies.len() as u64;
-            let response = crate::web::responses::PaginatedResponse::new(
-                proxies,
-                total_count, // In a real implementation, this would be the total count
-                list_params.pagination.page,
-                list_params.pagination.limit,
-            );
-            ok(response).into_response()
+    let sort_field = match list_params.sort_field(&["name", "created_at", "updated_at"]) {
+        Ok(field) => field,
+        Err(errors) => return crate::web::responses::validation_error(errors).into_response(),
+    };
+
+    // Search and sort before paginating so `total` covers every matching proxy
+    match service.list(None, None).await {
+        Ok(mut proxies) => {
+            list_params.apply_search(&mut proxies, |proxy, term| {
+                proxy.name.to_lowercase().contains(term)
+                    || proxy
+                        .description
+                        .as_deref()
+                        .is_some_and(|d| d.to_lowercase().contains(term))
+            });
+            match sort_field {
+                Some("name") => proxies.sort_by(|a, b| {
+                    list_params.direction(a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                }),
+                Some("created_at") => {
+                    proxies.sort_by(|a, b| list_params.direction(a.created_at.cmp(&b.created_at)))
+                }
+                Some("updated_at") => {
+                    proxies.sort_by(|a, b| list_params.direction(a.updated_at.cmp(&b.updated_at)))
+                }
+                _ => {}
+            }
+            ok(list_params.paginate(proxies)).into_response()
         }
         Err(err) => crate::web::responses::handle_error(err).into_response(),
     }
@@ -471,7 +615,7 @@ pub async fn list_proxies(
     path = "/proxies/{id}",
     tag = "proxies",
     summary = "Get stream proxy",
-    description = "Retrieve a specific stream proxy configuration by ID",
+    description = "Retrieve a specific stream proxy configuration by ID. `channel_count_alert` is set while the latest generation changed the channel count beyond the `channel_count_alerts.proxy_change_percent` threshold.",
     params(
         ("id" = String, Path, description = "Proxy ID (UUID or friendly name)"),
     ),
@@ -523,12 +667,338 @@ pub async fn get_proxy(
         });
 
     match service.get_by_id(uuid).await {
-        Ok(Some(proxy)) => ok(proxy).into_response(),
+        Ok(Some(mut proxy)) => {
+            proxy.channel_count_alert = channel_count_alert(&state, uuid).await;
+            ok(proxy).into_response()
+        }
         Ok(None) => crate::web::responses::not_found("stream_proxy", &id).into_response(),
         Err(err) => crate::web::responses::handle_error(err).into_response(),
     }
 }
 
+/// Operational status of a proxy
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProxyStatusResponse {
+    pub proxy_id: Uuid,
+    pub is_active: bool,
+    pub last_generated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub regenerating: bool,
+    /// Source updates waiting in the proxy's debounce window, if one is open
+    pub pending_regeneration: Option<crate::services::proxy_regeneration::PendingRegeneration>,
+    /// Source updates coalesced into the latest automatic regeneration
+    pub last_regeneration_triggers: Vec<crate::services::proxy_regeneration::RegenerationTrigger>,
+    /// At least one EPG source is stale; its channels use a fallback guide where available
+    pub epg_degraded: bool,
+    pub epg_sources: Vec<crate::pipeline::services::EpgSourceFreshness>,
+    /// Set while the latest generation changed the channel count beyond the
+    /// `channel_count_alerts` threshold
+    pub channel_count_alert: Option<crate::models::channel_count_alert::ChannelCountAlert>,
+}
+
+/// Active channel count alert of a proxy; lookup failures are logged and reported as none
+async fn channel_count_alert(
+    state: &AppState,
+    proxy_id: Uuid,
+) -> Option<crate::models::channel_count_alert::ChannelCountAlert> {
+    crate::database::repositories::ChannelCountSnapshotSeaOrmRepository::new(
+        state.database.connection().clone(),
+    )
+    .active_alert(&proxy_id)
+    .await
+    .unwrap_or_else(|e| {
+        warn!(
+            "Failed to load channel count alert of proxy {}: {}",
+            proxy_id, e
+        );
+        None
+    })
+}
+
+/// Get proxy status
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/status",
+    tag = "proxies",
+    summary = "Get stream proxy status",
+    description = "Report generation state and EPG freshness of a proxy, including source updates batched in its open regeneration debounce window and those coalesced into its latest automatic regeneration. EPG sources that have not ingested successfully within `epg_failover.staleness_window` are flagged stale, along with the fallback source used for their channels during XMLTV generation. `channel_count_alert` is set while the latest generation changed the channel count beyond the `channel_count_alerts.proxy_change_percent` threshold.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Proxy status", body = ProxyStatusResponse),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 404, description = "Stream proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_proxy_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/status").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => {
+            return crate::web::responses::bad_request(&error.to_string()).into_response();
+        }
+    };
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    let proxy = match proxy_repo.find_by_id(&uuid).await {
+        Ok(Some(proxy)) => proxy,
+        Ok(None) => return crate::web::responses::not_found("stream_proxy", &id).into_response(),
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+
+    let failover = state.config.epg_failover.clone().unwrap_or_default();
+    let plan = match crate::pipeline::services::EpgFailoverPlan::load(
+        &state.database.connection(),
+        &failover,
+        uuid,
+    )
+    .await
+    {
+        Ok(plan) => plan,
+        Err(e) => {
+            return crate::web::responses::internal_error(&format!(
+                "Failed to check EPG sources: {e}"
+            ))
+            .into_response();
+        }
+    };
+
+    ok(ProxyStatusResponse {
+        proxy_id: proxy.id,
+        is_active: proxy.is_active,
+        last_generated_at: proxy.last_generated_at,
+        regenerating: state
+            .proxy_regeneration_service
+            .has_active_regeneration(uuid)
+            .await,
+        pending_regeneration: state
+            .proxy_regeneration_service
+            .pending_regeneration(uuid)
+            .await,
+        last_regeneration_triggers: state
+            .proxy_regeneration_service
+            .last_regeneration_triggers(uuid)
+            .await,
+        epg_degraded: plan.is_degraded(),
+        epg_sources: plan.sources,
+        channel_count_alert: channel_count_alert(&state, uuid).await,
+    })
+    .into_response()
+}
+
+/// Result of rolling back a proxy's output
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProxyRollbackResponse {
+    pub proxy_id: Uuid,
+    /// Output files restored to the previous generation
+    pub restored_files: Vec<String>,
+}
+
+/// Roll back a proxy's output to the previous generation
+#[utoipa::path(
+    post,
+    path = "/proxies/{id}/rollback",
+    tag = "proxies",
+    summary = "Roll back proxy output",
+    description = "Restore the M3U and XMLTV output replaced by the last successful regeneration. The current output becomes the previous generation, so rolling back again undoes the rollback. The next regeneration publishes fresh output as usual.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Output rolled back", body = ProxyRollbackResponse),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 404, description = "Stream proxy not found or no previous generation"),
+        (status = 409, description = "A regeneration is in progress"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn rollback_proxy_output(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/proxies/{id}/rollback").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => {
+            return crate::web::responses::bad_request(&error.to_string()).into_response();
+        }
+    };
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    match proxy_repo.find_by_id(&uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return crate::web::responses::not_found("stream_proxy", &id).into_response(),
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+
+    if state
+        .proxy_regeneration_service
+        .has_active_regeneration(uuid)
+        .await
+    {
+        return crate::web::responses::conflict(
+            "A regeneration is in progress; roll back once it has finished",
+        )
+        .into_response();
+    }
+
+    let restored = match crate::pipeline::stages::publish_content::rollback_published_output(
+        &state.proxy_output_file_manager,
+        uuid,
+    )
+    .await
+    {
+        Ok(restored) if restored.is_empty() => {
+            return crate::web::responses::not_found("previous proxy output", &id).into_response();
+        }
+        Ok(restored) => restored,
+        Err(e) => {
+            error!("Failed to roll back output of proxy {}: {}", uuid, e);
+            return crate::web::responses::internal_error(&format!(
+                "Failed to roll back proxy output: {e}"
+            ))
+            .into_response();
+        }
+    };
+
+    // Clients fetching deltas must see the restored playlist as a new version
+    if let Ok(content) = state
+        .proxy_output_file_manager
+        .read_to_string(format!("{uuid}.m3u8"))
+        .await
+        && let Err(e) = playlist_delta_store(&state).record(&uuid, &content).await
+    {
+        warn!(
+            "Failed to record restored playlist of proxy {}: {}",
+            uuid, e
+        );
+    }
+
+    // The served content changed, so cache validators derived from the generation time must too
+    if let Err(e) = proxy_repo.update_last_generated(uuid).await {
+        warn!("Failed to update generation time of proxy {}: {}", uuid, e);
+    }
+
+    info!("Rolled back output of proxy {}: {:?}", uuid, restored);
+    ok(ProxyRollbackResponse {
+        proxy_id: uuid,
+        restored_files: restored,
+    })
+    .into_response()
+}
+
+/// Export the channel list of a proxy's latest generation
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/channels/export",
+    tag = "proxies",
+    summary = "Export proxy channel list",
+    description = "Download the channels of the proxy's latest generated playlist, in playlist order, as CSV or XLSX. Columns: number, name, group, tvg_id, logo_url and the stream source the channel comes from.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        crate::services::channel_export::ChannelExportQuery
+    ),
+    responses(
+        (status = 200, description = "Channel list file", content_type = ["text/csv", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"]),
+        (status = 400, description = "Invalid proxy ID or format"),
+        (status = 404, description = "Stream proxy not found or not generated yet"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn export_proxy_channels(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<
+        crate::services::channel_export::ChannelExportQuery,
+    >,
+    context: RequestContext,
+) -> impl IntoResponse {
+    use crate::services::channel_export::{export_response, playlist_channels};
+
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/channels/export")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => {
+            return crate::web::responses::bad_request(&error.to_string()).into_response();
+        }
+    };
+
+    let (proxy_repo, channel_repo, _, source_repo) = create_repositories(&state.database);
+    let proxy = match proxy_repo.find_by_id(&uuid).await {
+        Ok(Some(proxy)) => proxy,
+        Ok(None) => return crate::web::responses::not_found("stream_proxy", &id).into_response(),
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+
+    let content = match state
+        .proxy_output_file_manager
+        .read_to_string(format!("{uuid}.m3u8"))
+        .await
+    {
+        Ok(content) => content,
+        Err(_) => {
+            return crate::web::responses::not_found("generated playlist", &id).into_response();
+        }
+    };
+    let playlist = playlist_channels(&content);
+
+    // Resolve each channel's source from the channel IDs in the proxy stream URLs
+    let channel_ids: Vec<Uuid> = playlist.iter().filter_map(|c| c.channel_id).collect();
+    let channel_sources: std::collections::HashMap<Uuid, Uuid> =
+        match channel_repo.find_by_ids(&channel_ids).await {
+            Ok(channels) => channels.into_iter().map(|c| (c.id, c.source_id)).collect(),
+            Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+        };
+    let source_names: std::collections::HashMap<Uuid, String> = match source_repo.find_all().await {
+        Ok(sources) => sources.into_iter().map(|s| (s.id, s.name)).collect(),
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+
+    let rows: Vec<_> = playlist
+        .into_iter()
+        .map(|channel| {
+            let mut row = channel.row;
+            row.source = channel
+                .channel_id
+                .and_then(|channel_id| channel_sources.get(&channel_id))
+                .and_then(|source_id| source_names.get(source_id))
+                .cloned();
+            row
+        })
+        .collect();
+
+    info!(
+        "Exporting {} channels of proxy {} as {:?}",
+        rows.len(),
+        uuid,
+        query.format
+    );
+    export_response(query.format, &format!("{}-channels", proxy.name), &rows)
+}
+
 /// Create a new proxy
 #[utoipa::path(
     post,
@@ -559,6 +1029,14 @@ pub async fn create_proxy(
         Err(error) => return crate::web::responses::bad_request(&error).into_response(),
     };
 
+    create_proxy_from_request(&state, service_request).await
+}
+
+/// Create a proxy through the proxy service, responding with the created proxy
+pub(crate) async fn create_proxy_from_request(
+    state: &AppState,
+    service_request: crate::models::StreamProxyCreateRequest,
+) -> axum::response::Response {
     // Create service instances using write repositories for mutations
     let (proxy_repo, channel_repo, filter_repo, stream_source_repo) =
         create_repositories(&state.database);
@@ -583,7 +1061,12 @@ pub async fn create_proxy(
         });
 
     match service.create(service_request).await {
-        Ok(proxy) => ok(proxy).into_response(),
+        Ok(proxy) => {
+            let _ = state
+                .cache_invalidation_tx
+                .send(crate::services::CachedEntity::Proxies);
+            ok(proxy).into_response()
+        }
         Err(err) => crate::web::responses::handle_error(err).into_response(),
     }
 }
@@ -674,6 +1157,14 @@ pub async fn update_proxy(
         cache_channel_logos: request.cache_channel_logos,
         cache_program_logos: request.cache_program_logos,
         relay_profile_id: request.relay_profile_id,
+        sign_stream_urls: request.sign_stream_urls,
+        output_profile: request.output_profile,
+        backup_streams: request.backup_streams,
+        offline_slate: request.offline_slate,
+        epg_languages: request.epg_languages,
+        regeneration_debounce_seconds: request.regeneration_debounce_seconds,
+        channel_number_blocks: request.channel_number_blocks,
+        epg_timezone: request.epg_timezone,
     };
 
     // Create service instances using write repositories for mutations
@@ -711,7 +1202,7 @@ pub async fn update_proxy(
     path = "/proxies/{id}",
     tag = "proxies",
     summary = "Delete stream proxy",
-    description = "Delete a stream proxy configuration",
+    description = "Move a stream proxy to the trash. It stops being served until restored via `/trash`, and is purged permanently after the trash retention period",
     params(
         ("id" = String, Path, description = "Proxy ID (UUID or friendly name)"),
     ),
@@ -764,6 +1255,9 @@ pub async fn delete_proxy(
 
     match service.delete(uuid).await {
         Ok(()) => {
+            let _ = state
+                .cache_invalidation_tx
+                .send(crate::services::CachedEntity::Proxies);
             crate::web::responses::ok(serde_json::json!({"message": "Proxy deleted successfully"}))
                 .into_response()
         }
@@ -963,29 +1457,42 @@ pub async fn preview_existing_proxy(
 
 // Proxy Content Serving Handlers (Non-API endpoints)
 
+/// Query parameters for the playlist endpoint
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct PlaylistQuery {
+    /// Output profile overriding the proxy's own ("standard" or "kodi")
+    pub profile: Option<String>,
+}
+
 /// Serve M3U8 content for a proxy (from static file)
 #[utoipa::path(
     get,
     path = "/proxies/{id}/playlist.m3u",
     tag = "proxies",
     summary = "Get proxy M3U playlist",
-    description = "Retrieve the M3U playlist for a specific proxy",
+    description = "Retrieve the M3U playlist for a specific proxy, rendered with the proxy's output profile or the one named by `profile`",
     params(
         ("id" = String, Path, description = "Proxy ID (UUID or friendly name)"),
+        PlaylistQuery
     ),
     responses(
         (status = 200, description = "M3U playlist content", content_type = "application/vnd.apple.mpegurl"),
+        (status = 304, description = "Playlist unchanged since the client's cached copy (If-None-Match / If-Modified-Since)"),
+        (status = 401, description = "The proxy requires Basic Auth and the credentials are missing or wrong"),
         (status = 404, description = "Proxy not found or no playlist available"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn serve_proxy_m3u(
     axum::extract::Path(id): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<PlaylistQuery>,
     State(state): State<AppState>,
+    client: ClientAddress,
+    basic_auth_user: Option<axum::Extension<ProxyBasicAuthUser>>,
+    request_headers: axum::http::HeaderMap,
 ) -> impl IntoResponse {
     use crate::utils::resolve_proxy_id;
     use axum::http::{HeaderMap, StatusCode};
-    use tokio::fs;
     use tracing::{error, info, trace, warn};
 
     trace!("Serving static M3U8 for proxy: {}", id);
@@ -1008,7 +1515,7 @@ pub async fn serve_proxy_m3u(
     let proxy_repo = crate::database::repositories::StreamProxySeaOrmRepository::new(
         state.database.connection().clone(),
     );
-    let _proxy = match proxy_repo.find_by_id(&resolved_uuid).await {
+    let proxy = match proxy_repo.find_by_id(&resolved_uuid).await {
         Ok(Some(proxy)) => {
             if !proxy.is_active {
                 warn!("Proxy {} is not active", id);
@@ -1053,36 +1560,100 @@ pub async fn serve_proxy_m3u(
         }
     };
 
-    // 2. Try to serve static M3U8 file from disk using resolved UUID
-    let m3u_file_path = state
-        .config
-        .storage
-        .m3u_path
-        .join(format!("{resolved_uuid}.m3u8"));
+    let output_profile = match query.profile.as_deref().map(str::parse::<OutputProfile>) {
+        None => proxy.output_profile,
+        Some(Ok(profile)) => profile,
+        Some(Err(e)) => {
+            let mut headers = HeaderMap::new();
+            headers.insert("content-type", "application/x-mpegurl".parse().unwrap());
+            return (StatusCode::BAD_REQUEST, headers, e);
+        }
+    };
+
+    // 2. Try to serve static M3U8 file from proxy output storage using resolved UUID
+    let m3u_file_path = format!("{resolved_uuid}.m3u8");
 
-    match fs::read_to_string(&m3u_file_path).await {
+    // Signed playlists differ per fetch, so they carry no validators
+    let cache_control = playlist_cache_config(&state).m3u_cache_control;
+    let validators = if proxy.sign_stream_urls {
+        None
+    } else {
+        let variant = match output_profile {
+            OutputProfile::Standard => "m3u",
+            OutputProfile::Kodi => "m3u-kodi",
+        };
+        generation_validators(&state, &proxy, &m3u_file_path, variant).await
+    };
+    if let Some((etag, last_modified)) = &validators
+        && is_not_modified(&request_headers, etag, *last_modified)
+    {
+        trace!("M3U8 for proxy {} not modified", id);
+        let mut headers = HeaderMap::new();
+        insert_cache_headers(&mut headers, &cache_control, validators.as_ref());
+        return (StatusCode::NOT_MODIFIED, headers, String::new());
+    }
+
+    match state
+        .proxy_output_file_manager
+        .read_to_string(&m3u_file_path)
+        .await
+    {
         Ok(content) => {
-            info!(
-                "Served static M3U8 for proxy {} from {}",
-                id,
-                m3u_file_path.display()
-            );
+            info!("Served static M3U8 for proxy {} from {}", id, m3u_file_path);
 
             let mut headers = HeaderMap::new();
             headers.insert(
                 "content-type",
                 "application/vnd.apple.mpegurl".parse().unwrap(),
             );
-            headers.insert("cache-control", "max-age=3600".parse().unwrap()); // 1 hour cache for static files
+            // Deltas describe the standard rendering, so only it carries a version
+            if output_profile == OutputProfile::Standard
+                && let Some(version) = playlist_delta_store(&state)
+                    .current_version(&resolved_uuid)
+                    .await
+                && let Ok(value) = version.to_string().parse()
+            {
+                headers.insert(crate::services::playlist_delta::VERSION_HEADER, value);
+            }
+            let content = client.rebase(content, &state.config.web.base_url);
+            let content = if proxy.sign_stream_urls {
+                let signer = StreamUrlSigner::from_config(state.config.stream_signing.as_ref());
+                // Streams count against the user the playlist was authenticated for
+                let subject = basic_auth_user.as_ref().map(|user| user.0.0.as_str());
+                signer.sign_playlist(&content, &resolved_uuid, subject, chrono::Utc::now())
+            } else {
+                content
+            };
+            // Profiles run after signing so catchup sources extend the final stream URLs
+            let content = match output_profile {
+                OutputProfile::Standard => content,
+                OutputProfile::Kodi => {
+                    let xmltv_url = format!(
+                        "{}/proxy/{}/xmltv",
+                        client.base_url(&state.config.web.base_url),
+                        crate::utils::uuid_to_base64(&resolved_uuid)
+                    );
+                    let kodi_config = state.config.kodi_preset.clone().unwrap_or_default();
+                    crate::utils::output_profile::apply_kodi_profile(
+                        &content,
+                        &xmltv_url,
+                        &kodi_config,
+                    )
+                }
+            };
+            if proxy.sign_stream_urls {
+                // Tokens are minted per fetch, so the signed playlist must not be cached
+                headers.insert("cache-control", "no-store".parse().unwrap());
+                return (StatusCode::OK, headers, content);
+            }
+            insert_cache_headers(&mut headers, &cache_control, validators.as_ref());
 
             (StatusCode::OK, headers, content)
         }
         Err(e) => {
             error!(
                 "Failed to read M3U8 file for proxy {} at {}: {}",
-                id,
-                m3u_file_path.display(),
-                e
+                id, m3u_file_path, e
             );
 
             // If file doesn't exist, suggest regeneration
@@ -1098,6 +1669,149 @@ pub async fn serve_proxy_m3u(
     }
 }
 
+/// Query parameters for the playlist delta endpoint
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct PlaylistDeltaQuery {
+    /// Playlist version the client holds (from the `X-Playlist-Version` header)
+    pub since: u64,
+}
+
+/// Serve the changes to a proxy's playlist since an earlier version
+#[utoipa::path(
+    get,
+    path = "/proxy/{id}/m3u8/delta",
+    tag = "streaming",
+    summary = "Get proxy M3U delta",
+    description = "Entries added, changed or removed since the playlist version a client holds, as compact JSON. The full playlist carries its version in the `X-Playlist-Version` header. A version that is no longer remembered returns 410 with the current version; the client should then refetch the full playlist. Entries are keyed by stream URL and describe the standard output profile.",
+    params(
+        ("id" = String, Path, description = "Proxy identifier (UUID, base64, or other supported format)"),
+        PlaylistDeltaQuery
+    ),
+    responses(
+        (status = 200, description = "Playlist delta", body = crate::services::playlist_delta::PlaylistDelta),
+        (status = 400, description = "Invalid proxy ID or version"),
+        (status = 401, description = "The proxy requires Basic Auth and the credentials are missing or wrong"),
+        (status = 404, description = "Proxy not found or no versions recorded"),
+        (status = 410, description = "Version no longer available; refetch the full playlist"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn serve_proxy_m3u_delta(
+    axum::extract::Path(id): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<PlaylistDeltaQuery>,
+    State(state): State<AppState>,
+    client: ClientAddress,
+    basic_auth_user: Option<axum::Extension<ProxyBasicAuthUser>>,
+) -> impl IntoResponse {
+    use crate::services::playlist_delta::{DeltaOutcome, PlaylistDelta, PlaylistDeltaEntry};
+    use crate::utils::resolve_proxy_id;
+    use axum::http::StatusCode;
+
+    let error_response = |status: StatusCode, message: &str| {
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    };
+
+    let resolved_uuid = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid proxy ID format: {e}"),
+            );
+        }
+    };
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    let proxy = match proxy_repo.find_by_id(&resolved_uuid).await {
+        Ok(Some(proxy)) if proxy.is_active => proxy,
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "Proxy not found"),
+        Err(e) => {
+            error!("Failed to find proxy {}: {}", id, e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load proxy");
+        }
+    };
+
+    let content = match state
+        .proxy_output_file_manager
+        .read_to_string(format!("{resolved_uuid}.m3u8"))
+        .await
+    {
+        Ok(content) => content,
+        Err(_) => return error_response(StatusCode::NOT_FOUND, "Playlist not generated yet"),
+    };
+
+    let delta = match playlist_delta_store(&state)
+        .delta(&resolved_uuid, query.since, &content)
+        .await
+    {
+        Ok(DeltaOutcome::Delta(delta)) => delta,
+        Ok(DeltaOutcome::Unavailable { version }) => {
+            return (
+                StatusCode::GONE,
+                Json(serde_json::json!({
+                    "error": "Playlist version is no longer available; fetch the full playlist",
+                    "version": version,
+                })),
+            )
+                .into_response();
+        }
+        Ok(DeltaOutcome::NotRecorded) => {
+            return error_response(StatusCode::NOT_FOUND, "No playlist versions recorded");
+        }
+        Err(e) => {
+            error!("Failed to compute playlist delta of proxy {}: {}", id, e);
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to compute playlist delta",
+            );
+        }
+    };
+
+    // Versions are recorded under `web.base_url`, so URLs move to the client's base URL only
+    // now; added and changed entries also carry fresh tokens, as the full playlist would
+    let base_url = &state.config.web.base_url;
+    let signer = proxy
+        .sign_stream_urls
+        .then(|| StreamUrlSigner::from_config(state.config.stream_signing.as_ref()));
+    let now = chrono::Utc::now();
+    let subject = basic_auth_user.as_ref().map(|user| user.0.0.as_str());
+    let serve = |entry: PlaylistDeltaEntry| {
+        let url = client.rebase(entry.url, base_url);
+        PlaylistDeltaEntry {
+            url: match &signer {
+                Some(signer) => signer
+                    .sign_playlist(&url, &resolved_uuid, subject, now)
+                    .trim_end()
+                    .to_string(),
+                None => url,
+            },
+            extinf: client.rebase(entry.extinf, base_url),
+        }
+    };
+    let delta = PlaylistDelta {
+        added: delta.added.into_iter().map(serve).collect(),
+        changed: delta.changed.into_iter().map(serve).collect(),
+        removed: delta
+            .removed
+            .into_iter()
+            .map(|url| client.rebase(url, base_url))
+            .collect(),
+        ..delta
+    };
+
+    debug!(
+        "Served playlist delta {}..{} for proxy {}: {} added, {} changed, {} removed",
+        delta.since,
+        delta.version,
+        id,
+        delta.added.len(),
+        delta.changed.len(),
+        delta.removed.len()
+    );
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert("cache-control", "no-store".parse().unwrap());
+    (StatusCode::OK, headers, Json(delta)).into_response()
+}
+
 /// Serve XMLTV Electronic Program Guide for a proxy
 #[utoipa::path(
     get,
@@ -1118,6 +1832,8 @@ The ID can be provided in any supported format (UUID, base64-encoded UUID, etc.)
     ),
     responses(
         (status = 200, description = "XMLTV EPG content", content_type = "application/xml"),
+        (status = 401, description = "The proxy requires Basic Auth and the credentials are missing or wrong"),
+        (status = 304, description = "Guide unchanged since the client's cached copy (If-None-Match / If-Modified-Since)"),
         (status = 404, description = "Proxy not found or XMLTV not generated yet"),
         (status = 500, description = "Internal server error")
     )
@@ -1125,10 +1841,11 @@ The ID can be provided in any supported format (UUID, base64-encoded UUID, etc.)
 pub async fn serve_proxy_xmltv(
     axum::extract::Path(id): axum::extract::Path<String>,
     State(state): State<AppState>,
+    client: ClientAddress,
+    request_headers: axum::http::HeaderMap,
 ) -> impl IntoResponse {
     use crate::utils::resolve_proxy_id;
     use axum::http::{HeaderMap, StatusCode};
-    use tokio::fs;
     use tracing::{error, info, warn};
 
     info!("Serving static XMLTV for proxy: {}", id);
@@ -1185,33 +1902,42 @@ pub async fn serve_proxy_xmltv(
         }
     };
 
-    // 2. Try to serve static XMLTV file from disk
-    let xmltv_file_path = state
-        .config
-        .storage
-        .m3u_path
-        .join(format!("{resolved_uuid}.xmltv"));
+    // 2. Try to serve static XMLTV file from proxy output storage
+    let xmltv_file_path = format!("{resolved_uuid}.xmltv");
 
-    match fs::read_to_string(&xmltv_file_path).await {
+    let cache_control = playlist_cache_config(&state).xmltv_cache_control;
+    let validators = generation_validators(&state, &proxy, &xmltv_file_path, "xmltv").await;
+    if let Some((etag, last_modified)) = &validators
+        && is_not_modified(&request_headers, etag, *last_modified)
+    {
+        info!("XMLTV for proxy {} not modified", id);
+        let mut headers = HeaderMap::new();
+        insert_cache_headers(&mut headers, &cache_control, validators.as_ref());
+        return (StatusCode::NOT_MODIFIED, headers, String::new());
+    }
+
+    match state
+        .proxy_output_file_manager
+        .read_to_string(&xmltv_file_path)
+        .await
+    {
         Ok(content) => {
             info!(
                 "Served static XMLTV for proxy {} from {}",
-                id,
-                xmltv_file_path.display()
+                id, xmltv_file_path
             );
 
             let mut headers = HeaderMap::new();
             headers.insert("content-type", "application/xml".parse().unwrap());
-            headers.insert("cache-control", "max-age=3600".parse().unwrap()); // 1 hour cache for static files
+            insert_cache_headers(&mut headers, &cache_control, validators.as_ref());
 
+            let content = client.rebase(content, &state.config.web.base_url);
             (StatusCode::OK, headers, content)
         }
         Err(e) => {
             error!(
                 "Failed to read XMLTV file for proxy {} at {}: {}",
-                id,
-                xmltv_file_path.display(),
-                e
+                id, xmltv_file_path, e
             );
 
             // If file doesn't exist, return placeholder XMLTV
@@ -1230,6 +1956,67 @@ pub async fn serve_proxy_xmltv(
     }
 }
 
+fn playlist_cache_config(state: &AppState) -> PlaylistCacheConfig {
+    state.config.playlist_cache.clone().unwrap_or_default()
+}
+
+fn playlist_delta_store(state: &AppState) -> crate::services::playlist_delta::PlaylistDeltaStore {
+    crate::services::playlist_delta::PlaylistDeltaStore::new(
+        state.proxy_output_file_manager.clone(),
+        state
+            .config
+            .output_publishing
+            .as_ref()
+            .map_or(0, |publishing| publishing.delta_history),
+    )
+}
+
+/// ETag and Last-Modified of a generated proxy file
+///
+/// Derived from the proxy's last generation, falling back to the file's modification time;
+/// None when the file does not exist.
+/// Saturated transcode slots are a temporary condition the client may retry
+fn relay_start_error_status(error: &crate::models::relay::RelayError) -> axum::http::StatusCode {
+    match error {
+        crate::models::relay::RelayError::TranscodeCapacity(_) => {
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        }
+        _ => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn generation_validators(
+    state: &AppState,
+    proxy: &StreamProxy,
+    path: &str,
+    variant: &str,
+) -> Option<(String, chrono::DateTime<chrono::Utc>)> {
+    let stat = state.proxy_output_file_manager.stat(path).await.ok()?;
+    let generated_at = proxy.last_generated_at.unwrap_or(stat.modified);
+    Some((
+        generation_etag(&proxy.id, generated_at, variant),
+        generated_at,
+    ))
+}
+
+fn insert_cache_headers(
+    headers: &mut axum::http::HeaderMap,
+    cache_control: &str,
+    validators: Option<&(String, chrono::DateTime<chrono::Utc>)>,
+) {
+    if let Ok(value) = cache_control.parse() {
+        headers.insert("cache-control", value);
+    }
+    if let Some((etag, last_modified)) = validators {
+        if let Ok(value) = etag.parse() {
+            headers.insert("etag", value);
+        }
+        if let Ok(value) = http_date(*last_modified).parse() {
+            headers.insert("last-modified", value);
+        }
+    }
+}
+
 /// Stream content through the proxy
 #[utoipa::path(
     get,
@@ -1245,14 +2032,22 @@ This is the main streaming endpoint that:
 - Handles client connection management
 - Provides health monitoring for upstream sources
 
-The proxy_id and channel_id are base64-encoded UUIDs from the M3U playlist.",
+The proxy_id and channel_id are base64-encoded UUIDs from the M3U playlist.
+For proxies with `sign_stream_urls` enabled the URL must carry the playlist's signed `token`.
+Streams count against `stream_sessions.max_streams_per_user` for the user the proxy's Basic Auth
+authenticated, taken from the signed token or from the request's own credentials.",
     params(
         ("proxy_id" = String, Path, description = "Base64-encoded proxy UUID (from M3U playlist)"),
-        ("channel_id" = String, Path, description = "Base64-encoded channel UUID (from M3U playlist)")
+        ("channel_id" = String, Path, description = "Base64-encoded channel UUID (from M3U playlist)"),
+        ("token" = Option<String>, Query, description = "Signed stream token (required when the proxy signs stream URLs)"),
+        ("container" = Option<String>, Query, description = "Output container: ts (default), mp4 (fragmented) or mkv; codecs are copied, not transcoded")
     ),
     responses(
         (status = 200, description = "Streaming content (video/audio stream)", content_type = "video/mp2t"),
+        (status = 400, description = "Unsupported container"),
+        (status = 403, description = "Missing, invalid or expired stream token"),
         (status = 404, description = "Proxy or channel not found"),
+        (status = 429, description = "The authenticated user already has the maximum number of concurrent streams"),
         (status = 502, description = "Upstream source unavailable"),
         (status = 503, description = "Service temporarily unavailable")
     ),
@@ -1261,20 +2056,90 @@ The proxy_id and channel_id are base64-encoded UUIDs from the M3U playlist.",
     )
 )]
 pub async fn proxy_stream(
-    axum::extract::Path((proxy_id, channel_id_str)): axum::extract::Path<(String, String)>,
-    axum::extract::Query(q): axum::extract::Query<std::collections::HashMap<String, String>>,
+    path: axum::extract::Path<(String, String)>,
+    query: axum::extract::Query<std::collections::HashMap<String, String>>,
+    client: ClientAddress,
     headers: axum::http::HeaderMap,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
+    serve_proxy_stream(path, query, client, headers, state, None).await
+}
+
+/// Serve a proxy stream on behalf of `identity`, whose stream limit is enforced at session start
+///
+/// Without an `identity` the stream belongs to the user the request authenticated as, if any.
+///
+/// `?container=mp4|mkv` remuxes the MPEG-TS output into another container without transcoding.
+pub(crate) async fn serve_proxy_stream(
+    path: axum::extract::Path<(String, String)>,
+    query: axum::extract::Query<std::collections::HashMap<String, String>>,
+    client: ClientAddress,
+    headers: axum::http::HeaderMap,
+    state: AppState,
+    identity: Option<SessionIdentity>,
+) -> axum::response::Response {
+    use crate::proxy::remux::{CONTAINER_PARAM, RemuxContainer, remux_response};
+
+    let container = match RemuxContainer::from_query(query.get(CONTAINER_PARAM).map(String::as_str))
+    {
+        Ok(container) => container,
+        Err(e) => return (axum::http::StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let ffmpeg_command = state
+        .config
+        .relay
+        .as_ref()
+        .map_or("ffmpeg", |relay| relay.ffmpeg_command.as_str())
+        .to_string();
+    let response = serve_channel_stream(path, query, client, headers, state, identity).await;
+    match container {
+        Some(container) => remux_response(response, container, &ffmpeg_command),
+        None => response,
+    }
+}
+
+/// User a stream request authenticated as: the subject of its signed token, or the proxy's
+/// Basic Auth user when the request carries matching credentials
+async fn authenticated_stream_user(
+    state: &AppState,
+    proxy: &StreamProxy,
+    token_subject: Option<String>,
+    headers: &axum::http::HeaderMap,
+) -> Option<String> {
+    if token_subject.is_some() {
+        return token_subject;
+    }
+    if !headers.contains_key(axum::http::header::AUTHORIZATION) {
+        return None;
+    }
+    let repo = crate::database::repositories::ProxyBasicAuthSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.get(&proxy.id).await {
+        Ok(Some(credentials)) => {
+            crate::web::middleware::verify_proxy_basic_auth(&credentials, headers).await
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Failed to load Basic Auth of proxy {}: {}", proxy.id, e);
+            None
+        }
+    }
+}
+
+async fn serve_channel_stream(
+    axum::extract::Path((proxy_id, channel_id_str)): axum::extract::Path<(String, String)>,
+    axum::extract::Query(q): axum::extract::Query<std::collections::HashMap<String, String>>,
+    client: ClientAddress,
+    headers: axum::http::HeaderMap,
+    state: AppState,
+    identity: Option<SessionIdentity>,
+) -> axum::response::Response {
     use axum::http::StatusCode;
     use tracing::{debug, error, info, warn};
 
-    let client_ip = headers
-        .get("x-forwarded-for")
-        .or_else(|| headers.get("x-real-ip"))
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("unknown")
-        .to_string();
+    let request_started = std::time::Instant::now();
+    let client_ip = client.ip_string();
 
     let user_agent = headers
         .get("user-agent")
@@ -1342,9 +2207,44 @@ pub async fn proxy_stream(
         }
     };
 
+    let mut token_subject = None;
+    if proxy.sign_stream_urls {
+        let signer = StreamUrlSigner::from_config(state.config.stream_signing.as_ref());
+        match signer.verify(
+            q.get(STREAM_TOKEN_PARAM).map(String::as_str),
+            &resolved_proxy_uuid,
+            &channel_id,
+            chrono::Utc::now(),
+        ) {
+            Ok(subject) => token_subject = subject,
+            Err(e) => {
+                warn!(
+                    "Rejected stream request for proxy {} channel {} from {}: {}",
+                    resolved_proxy_uuid, channel_id, client_ip, e
+                );
+                return (StatusCode::FORBIDDEN, format!("Invalid stream URL: {e}")).into_response();
+            }
+        }
+    }
+    let identity = match identity {
+        Some(identity) => Some(identity),
+        None => authenticated_stream_user(&state, &proxy, token_subject, &headers)
+            .await
+            .map(|user| {
+                SessionIdentity::user(
+                    &user,
+                    state
+                        .config
+                        .stream_sessions
+                        .as_ref()
+                        .and_then(|c| c.user_limit()),
+                )
+            }),
+    };
+
     // 2. Look up channel within proxy context using repository
     let stream_proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
-    let channel = match stream_proxy_repo
+    let mut channel = match stream_proxy_repo
         .get_channel_for_proxy(resolved_proxy_uuid, channel_id)
         .await
     {
@@ -1381,6 +2281,74 @@ pub async fn proxy_stream(
         }
     };
 
+    // Channels of a balanced source can be served from any line of its group; the least
+    // loaded line comes first and the others are kept for failover
+    let mut lines = match crate::proxy::line_balancer::candidate_lines(
+        state.database.connection().clone(),
+        &state.session_tracker,
+        &channel,
+    )
+    .await
+    {
+        Ok(lines) => lines,
+        Err(e) => {
+            warn!(
+                "Failed to resolve balanced lines for channel {}, using its own source: {}",
+                channel_id, e
+            );
+            vec![channel.clone()]
+        }
+    };
+    if lines.is_empty() {
+        warn!(
+            "All lines for channel '{}' are at their connection limit",
+            channel.channel_name
+        );
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "All lines for this channel are at their connection limit".to_string(),
+        )
+            .into_response();
+    }
+
+    // Provider-specific stream URL rewrites (ports, tokens, schemes)
+    match crate::database::repositories::UrlRewriteRuleSeaOrmRepository::new(
+        state.database.connection().clone(),
+    )
+    .list_active_for_proxy(&resolved_proxy_uuid)
+    .await
+    {
+        Ok(rules) => {
+            let rewrites = crate::models::url_rewrite_rule::UrlRewriteRuleSet::new(&rules);
+            for line in &mut lines {
+                if rewrites.apply(line) {
+                    debug!(
+                        "Rewrote stream URL of channel {} (source {})",
+                        channel_id, line.source_id
+                    );
+                }
+            }
+        }
+        Err(e) => warn!(
+            "Failed to load URL rewrite rules for proxy {}: {}",
+            resolved_proxy_uuid, e
+        ),
+    }
+
+    // Kodi catchup requests carry the programme start time; forward it upstream
+    for line in &mut lines {
+        if let Some(catchup_url) =
+            crate::utils::output_profile::catchup_stream_url(&line.stream_url, &q)
+        {
+            debug!(
+                "Catchup request for channel {} in proxy {}",
+                channel_id, resolved_proxy_uuid
+            );
+            line.stream_url = catchup_url;
+        }
+    }
+    channel = lines[0].clone();
+
     // Note: Relay configuration is now handled per-proxy basis in the match statement below
 
     // 4. Log access metrics and create active session
@@ -1398,9 +2366,39 @@ pub async fn proxy_stream(
         )
         .await;
 
+    // Virtual channels backed by a relay profile are always relayed, whatever the proxy mode
+    let mut relay_profile_id = proxy.relay_profile_id;
+    let mut proxy_mode = proxy.proxy_mode.clone();
+    if crate::models::virtual_channel::is_virtual_channel(&channel) {
+        let virtual_repo = crate::database::repositories::VirtualChannelSeaOrmRepository::new(
+            state.database.connection().clone(),
+        );
+        if let Ok(Some(virtual_channel)) = virtual_repo
+            .find_active_for_proxy(&resolved_proxy_uuid, &channel_id)
+            .await
+            && let Some(profile_id) = virtual_channel.relay_profile_id
+        {
+            relay_profile_id = Some(profile_id);
+            proxy_mode = StreamProxyMode::Relay;
+        }
+    }
+
+    // Headers the channel's source requires on upstream stream connections
+    let mut header_overrides = crate::proxy::http_stream::load_stream_header_overrides(
+        state.database.connection().clone(),
+        channel.source_id,
+    )
+    .await;
+
+    // Time to first byte by phase; redirected clients fetch the stream themselves
+    let startup = state
+        .stream_latency
+        .start_timer(request_started, proxy.id, &proxy.name);
+    startup.mark(StartupPhase::Setup);
+
     // Note: Relay logic is now handled in the match statement below based on proxy_mode
 
-    match proxy.proxy_mode {
+    match proxy_mode {
         StreamProxyMode::Redirect => {
             info!(
                 "Redirecting stream request for channel '{}' to original URL: {}",
@@ -1443,9 +2441,13 @@ pub async fn proxy_stream(
                 channel_id.to_string(),
                 channel.channel_name.clone(),
                 channel.stream_url.clone(),
-            );
+            )
+            .with_identity(identity.clone())
+            .with_source(channel.source_id);
 
-            state.session_tracker.start_session(session_stats).await;
+            if let Err(e) = state.session_tracker.try_start_session(session_stats).await {
+                return (StatusCode::TOO_MANY_REQUESTS, e.to_string()).into_response();
+            }
 
             // For redirects, immediately finish the session since we're not tracking the stream
             let state_clone = state.clone();
@@ -1471,6 +2473,52 @@ pub async fn proxy_stream(
             Redirect::temporary(&channel.stream_url).into_response()
         }
         StreamProxyMode::Proxy => {
+            // Hybrid streaming classification (only for proxy mode and only affects headers here).
+            // Parse ?format=raw|auto (defaults to auto; unknown values -> auto).
+            let format_param = match q.get("format").map(|s| s.as_str()) {
+                Some("raw") => "raw",
+                _ => "auto",
+            };
+            // Classifying probes the upstream, so an unreachable line fails over to the next
+            // one of its balance group. When none answers the first line is kept.
+            let mut classification_result = None;
+            for line in &lines {
+                let line_header_overrides = if line.source_id == channel.source_id {
+                    header_overrides.clone()
+                } else {
+                    crate::proxy::http_stream::load_stream_header_overrides(
+                        state.database.connection().clone(),
+                        line.source_id,
+                    )
+                    .await
+                };
+                match classify_stream(
+                    &line.stream_url,
+                    &crate::proxy::http_stream::upstream_client(line_header_overrides.as_ref()),
+                    ClassificationParams {
+                        format: format_param,
+                        ..Default::default()
+                    },
+                )
+                .await
+                {
+                    Ok(result) => {
+                        classification_result = Some(result);
+                        channel = line.clone();
+                        header_overrides = line_header_overrides;
+                        break;
+                    }
+                    Err(e) if lines.len() > 1 => {
+                        warn!(
+                            "Line {} failed for channel '{}', trying the next line: {}",
+                            line.source_id, line.channel_name, e
+                        );
+                    }
+                    Err(_) => {}
+                }
+            }
+            startup.mark(StartupPhase::UpstreamConnect);
+
             info!(
                 "Proxying stream request for channel '{}' from URL: {}",
                 channel.channel_name, channel.stream_url
@@ -1515,29 +2563,62 @@ pub async fn proxy_stream(
                 channel_id.to_string(),
                 channel.channel_name.clone(),
                 channel.stream_url.clone(),
-            );
+            )
+            .with_identity(identity.clone())
+            .with_source(channel.source_id)
+            .with_startup(startup.clone());
 
-            state
+            if let Err(e) = state
                 .session_tracker
-                .start_session(session_stats.clone())
-                .await;
+                .try_start_session(session_stats.clone())
+                .await
+            {
+                return (StatusCode::TOO_MANY_REQUESTS, e.to_string()).into_response();
+            }
 
-            // Hybrid streaming classification (only for proxy mode and only affects headers here).
-            // Parse ?format=raw|auto (defaults to auto; unknown values -> auto).
-            let format_param = match q.get("format").map(|s| s.as_str()) {
-                Some("raw") => "raw",
-                _ => "auto",
-            };
-            let classification_result = classify_stream(
-                &channel.stream_url,
-                &reqwest::Client::new(),
-                ClassificationParams {
-                    format: format_param,
+            // Raw TS, or an upstream that could not be classified because it is down, can be
+            // spliced with the proxy's offline slate
+            let offline_slate = crate::proxy::offline_slate::OfflineSlate::for_proxy(
+                proxy.offline_slate,
+                &state.config.offline_slate.clone().unwrap_or_default(),
+                state
+                    .config
+                    .relay
+                    .as_ref()
+                    .map_or("ffmpeg", |relay| relay.ffmpeg_command.as_str()),
+                &channel.channel_name,
+            );
+            if let Some(slate) = offline_slate
+                && classification_result.as_ref().is_none_or(|class_res| {
+                    matches!(class_res.decision, StreamModeDecision::PassthroughRawTs)
+                })
+            {
+                let classified = classification_result.is_some();
+                let meta = crate::proxy::http_stream::StreamHeaderMeta {
+                    origin_kind: Some(if classified { "RAW_TS" } else { "UNKNOWN" }.into()),
+                    decision: Some(
+                        if classified {
+                            "passthrough-raw-ts"
+                        } else {
+                            "transparent-unknown"
+                        }
+                        .into(),
+                    ),
+                    mode: Some("passthrough".into()),
                     ..Default::default()
-                },
-            )
-            .await
-            .ok();
+                };
+                return crate::proxy::offline_slate::proxy_stream_with_slate(
+                    &channel.stream_url,
+                    &headers,
+                    &state.config,
+                    state.session_tracker.clone(),
+                    session_stats,
+                    Some(meta),
+                    header_overrides.as_ref(),
+                    slate,
+                )
+                .await;
+            }
 
             // Perform streaming (collapsed or passthrough) using unified proxy implementation.
             // 1. If classification decided on collapsing a single-variant TS playlist, run collapsing pipeline.
@@ -1557,7 +2638,9 @@ pub async fn proxy_stream(
                         .clone()
                         .unwrap_or_else(|| channel.stream_url.clone());
                     let handle = crate::streaming::collapsing::spawn_collapsing_session(
-                        Arc::new(reqwest::Client::new()),
+                        Arc::new(crate::proxy::http_stream::upstream_client(
+                            header_overrides.as_ref(),
+                        )),
                         collapsing_playlist_url,
                         class_res.target_duration,
                         crate::streaming::collapsing::CollapsingConfig::default(),
@@ -1569,7 +2652,11 @@ pub async fn proxy_stream(
                             Err(std::io::Error::other(format!("collapsing: {e}")))
                         }
                     });
-                    let body = Body::from_stream(collapsing_stream);
+                    let body = Body::from_stream(
+                        state
+                            .session_tracker
+                            .track_stream(&session_stats, collapsing_stream),
+                    );
                     Response::builder()
                         .status(StatusCode::OK)
                         .header(header::CONTENT_TYPE, "video/mp2t")
@@ -1630,6 +2717,7 @@ pub async fn proxy_stream(
                         state.session_tracker.clone(),
                         session_stats,
                         Some(meta),
+                        header_overrides.as_ref(),
                     )
                     .await
                 }
@@ -1647,6 +2735,7 @@ pub async fn proxy_stream(
                     state.session_tracker.clone(),
                     session_stats,
                     Some(meta),
+                    header_overrides.as_ref(),
                 )
                 .await
             }
@@ -1660,8 +2749,8 @@ pub async fn proxy_stream(
                 channel.channel_name, channel.stream_url
             );
 
-            // Check if proxy has a relay profile configured
-            let _relay_profile_id = match proxy.relay_profile_id {
+            // Check if proxy (or virtual channel) has a relay profile configured
+            let _relay_profile_id = match relay_profile_id {
                 Some(id) => id,
                 None => {
                     error!(
@@ -1715,15 +2804,21 @@ pub async fn proxy_stream(
                 channel_id.to_string(),
                 channel.channel_name.clone(),
                 channel.stream_url.clone(),
-            );
+            )
+            .with_identity(identity.clone())
+            .with_source(channel.source_id)
+            .with_startup(startup.clone());
 
-            state
+            if let Err(e) = state
                 .session_tracker
-                .start_session(session_stats.clone())
-                .await;
+                .try_start_session(session_stats.clone())
+                .await
+            {
+                return (StatusCode::TOO_MANY_REQUESTS, e.to_string()).into_response();
+            }
 
             // Resolve relay configuration
-            let relay_config = match state
+            let mut relay_config = match state
                 .relay_config_resolver
                 .resolve_relay_config(proxy.id, channel_id, _relay_profile_id)
                 .await
@@ -1739,6 +2834,8 @@ pub async fn proxy_stream(
                 }
             };
 
+            relay_config.upstream_headers = header_overrides;
+
             // Ensure relay is running
             if let Err(e) = state
                 .relay_manager
@@ -1747,11 +2844,12 @@ pub async fn proxy_stream(
             {
                 error!("Failed to start relay: {}", e);
                 return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
+                    relay_start_error_status(&e),
                     "Failed to start relay process",
                 )
                     .into_response();
             }
+            startup.mark(StartupPhase::RelaySpawn);
 
             // Create client info for relay
             let client_info = ClientInfo {
@@ -1799,7 +2897,11 @@ pub async fn proxy_stream(
                         .status(StatusCode::OK)
                         .header(header::CONTENT_TYPE, "video/mp2t")
                         .header(header::CACHE_CONTROL, "no-cache, no-store")
-                        .body(Body::from_stream(tracked_stream))
+                        .body(Body::from_stream(
+                            state
+                                .session_tracker
+                                .track_stream(&session_stats, tracked_stream),
+                        ))
                         .unwrap();
                     crate::proxy::http_stream::apply_uniform_stream_headers(
                         &mut resp,
@@ -2018,10 +3120,7 @@ async fn diagnose_channel_access_issue_seaorm(
                         error!("Failed to complete active session: {}", e);
                     }
                     state.session_tracker.end_session(&session_id).await;
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        "Failed to start relay process",
-                    )
+                    return (relay_start_error_status(&e), "Failed to start relay process")
                         .into_response();
                 }
             }
@@ -2570,6 +3669,7 @@ async fn diagnose_channel_proxy_issue(
         Ok(None) => {
             // Channel doesn't exist - this suggests stale M3U
             let proxy_info = StreamProxies::find()
+                .filter(stream_proxies::Column::DeletedAt.is_null())
                 .filter(stream_proxies::Column::Id.eq(proxy_id))
                 .select_only()
                 .column(stream_proxies::Column::LastGeneratedAt)
@@ -2594,6 +3694,7 @@ async fn diagnose_channel_proxy_issue(
     use crate::entities::{prelude::StreamProxies, stream_proxies};
 
     let proxy_status = StreamProxies::find()
+        .filter(stream_proxies::Column::DeletedAt.is_null())
         .filter(stream_proxies::Column::Id.eq(proxy_id))
         .one(&*database.connection())
         .await;
@@ -2677,6 +3778,14 @@ mod tests {
             cache_channel_logos: true,
             cache_program_logos: false,
             relay_profile_id: None,
+            sign_stream_urls: false,
+            output_profile: Default::default(),
+            backup_streams: Default::default(),
+            offline_slate: Default::default(),
+            epg_languages: None,
+            regeneration_debounce_seconds: None,
+            channel_number_blocks: Vec::new(),
+            epg_timezone: None,
         };
 
         let response = StreamProxyResponse::from_proxy_with_base_url(proxy, base_url);
@@ -2712,6 +3821,14 @@ mod tests {
             cache_channel_logos: true,
             cache_program_logos: false,
             relay_profile_id: None,
+            sign_stream_urls: false,
+            output_profile: Default::default(),
+            backup_streams: Default::default(),
+            offline_slate: Default::default(),
+            epg_languages: None,
+            regeneration_debounce_seconds: None,
+            channel_number_blocks: Vec::new(),
+            epg_timezone: None,
         };
 
         let response = StreamProxyResponse::from_proxy_with_base_url(proxy, base_url);
@@ -0,0 +1,145 @@
+//! Proxy ordering handlers
+//!
+//! Read and move entries of a proxy's stream source, EPG source and filter order one at
+//! a time, for drag-and-drop editors. Every move carries the version of the order the
+//! client last saw and is rejected with 409 if the order has changed since.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tracing::info;
+
+use crate::database::repositories::StreamProxySeaOrmRepository;
+use crate::models::proxy_order::{
+    ProxyOrderKind, ProxyOrdering, ProxyReorderOutcome, ProxyReorderRequest,
+};
+use crate::utils::resolve_proxy_id;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, conflict, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+/// Get the order of a proxy's stream sources, EPG sources or filters
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/order/{kind}",
+    tag = "proxies",
+    summary = "Get proxy ordering",
+    description = "Current priority order of a proxy's stream sources, EPG sources or filters, with the version token required to move entries",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("kind" = ProxyOrderKind, Path, description = "stream-sources, epg-sources or filters"),
+    ),
+    responses(
+        (status = 200, description = "Current order", body = ProxyOrdering),
+        (status = 400, description = "Invalid proxy ID or list kind"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_proxy_ordering(
+    State(state): State<AppState>,
+    Path((id, kind)): Path<(String, ProxyOrderKind)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/order").parse().unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    match repo.find_by_id(&proxy_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found("proxy", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    }
+
+    match repo.get_ordering(proxy_id, kind).await {
+        Ok(ids) => ok(ProxyOrdering::new(kind, &ids)).into_response(),
+        Err(e) => internal_error(&format!("Failed to load proxy ordering: {e}")).into_response(),
+    }
+}
+
+/// Move one entry of a proxy's stream sources, EPG sources or filters
+#[utoipa::path(
+    post,
+    path = "/proxies/{id}/order/{kind}/move",
+    tag = "proxies",
+    summary = "Move proxy ordering entry",
+    description = "Move one entry up, down or to a 1-based position. `expected_version` must match the current order's version; otherwise nothing changes and 409 is returned so the client can reload and retry. Priorities are renumbered 1..n after the move.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("kind" = ProxyOrderKind, Path, description = "stream-sources, epg-sources or filters"),
+    ),
+    request_body = ProxyReorderRequest,
+    responses(
+        (status = 200, description = "New order", body = ProxyOrdering),
+        (status = 400, description = "Invalid proxy ID, list kind or position"),
+        (status = 404, description = "Proxy or entry not found"),
+        (status = 409, description = "Order changed since expected_version"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn move_proxy_ordering_item(
+    State(state): State<AppState>,
+    Path((id, kind)): Path<(String, ProxyOrderKind)>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<ProxyReorderRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/proxies/{id}/order/move").parse().unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    match repo.find_by_id(&proxy_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found("proxy", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    }
+
+    match repo
+        .reorder(
+            proxy_id,
+            kind,
+            request.item_id,
+            request.movement,
+            &request.expected_version,
+        )
+        .await
+    {
+        Ok(ProxyReorderOutcome::Applied(ids)) => {
+            info!(
+                "Moved {} {:?} ({:?}) for proxy {}",
+                request.item_id, kind, request.movement, proxy_id
+            );
+            ok(ProxyOrdering::new(kind, &ids)).into_response()
+        }
+        Ok(ProxyReorderOutcome::Conflict(ids)) => conflict(&format!(
+            "Order has changed since version {}; current version is {}",
+            request.expected_version,
+            ProxyOrdering::new(kind, &ids).version
+        ))
+        .into_response(),
+        Ok(ProxyReorderOutcome::UnknownItem) => {
+            not_found("proxy ordering entry", &request.item_id.to_string()).into_response()
+        }
+        Ok(ProxyReorderOutcome::Invalid(message)) => bad_request(&message).into_response(),
+        Err(e) => internal_error(&format!("Failed to reorder proxy: {e}")).into_response(),
+    }
+}
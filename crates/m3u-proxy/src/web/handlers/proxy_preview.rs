@@ -0,0 +1,242 @@
+//! Streaming proxy preview handler
+//!
+//! Runs the proxy pipeline up to its final channel stage (nothing is generated or
+//! published) and reports it over Server-Sent Events: a `stage` event as each stage
+//! starts and completes, `channels` events carrying the resulting channels in batches,
+//! then `complete` or `error`. Closing the connection aborts the pipeline run.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::Channel;
+use crate::pipeline::models::ContentType;
+use crate::pipeline::{PipelineOrchestratorFactory, PipelineStageEvent};
+use crate::utils::resolve_proxy_id;
+use crate::web::{
+    AppState, extractors::RequestContext, responses::bad_request, utils::log_request,
+};
+
+/// Last stage run by a preview; later stages generate and publish output
+const PREVIEW_FINAL_STAGE: &str = "compliance";
+const DEFAULT_BATCH_SIZE: usize = 200;
+const MAX_BATCH_SIZE: usize = 5000;
+
+/// Query parameters for the streaming preview
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PreviewStreamQuery {
+    /// Channels per `channels` event (1-5000, default 200)
+    pub batch_size: Option<usize>,
+}
+
+/// `stage` event payload
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewStageEvent {
+    pub stage_id: String,
+    pub stage_name: String,
+    /// "started" or "completed"
+    pub state: String,
+    /// Zero-based stage position and stage count (started events)
+    pub index: Option<usize>,
+    pub total: Option<usize>,
+    /// Stage duration (completed events)
+    pub duration_ms: Option<u64>,
+    /// Channels in the stage output (completed events)
+    pub channel_count: Option<usize>,
+}
+
+/// `channels` event payload
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewChannelBatch {
+    /// Position of the first channel of this batch in the preview
+    pub offset: usize,
+    pub channels: Vec<Channel>,
+}
+
+/// `complete` event payload
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewCompleteEvent {
+    pub total_channels: usize,
+    pub duration_ms: u64,
+}
+
+/// Aborts the pipeline task when the SSE stream is dropped (client disconnected)
+struct AbortOnDrop(tokio::task::JoinHandle<Result<(), String>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn json_event(name: &str, payload: &impl Serialize) -> Event {
+    Event::default()
+        .event(name)
+        .data(serde_json::to_string(payload).unwrap_or_default())
+}
+
+/// Stream a live preview of an existing proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/preview/stream",
+    tag = "proxies",
+    summary = "Stream proxy preview",
+    description = "Run the proxy pipeline up to its final channel stage and stream progress over SSE.
+
+Events:
+- `stage`: a stage started or completed (`PreviewStageEvent`)
+- `channels`: a batch of resulting channels (`PreviewChannelBatch`)
+- `complete`: the preview finished (`PreviewCompleteEvent`)
+- `error`: the pipeline failed (`{\"message\": ...}`)
+
+Nothing is generated or published. Closing the connection cancels the run.",
+    params(
+        ("id" = String, Path, description = "Proxy identifier (UUID, base64, or other supported format)"),
+        PreviewStreamQuery
+    ),
+    responses(
+        (status = 200, description = "Preview event stream (SSE)", content_type = "text/event-stream"),
+        (status = 400, description = "Invalid proxy ID or batch size")
+    )
+)]
+pub async fn stream_proxy_preview(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<PreviewStreamQuery>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/preview/stream")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return bad_request(&error.to_string()).into_response(),
+    };
+    let batch_size = query.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+    if !(1..=MAX_BATCH_SIZE).contains(&batch_size) {
+        return bad_request("batch_size must be between 1 and 5000").into_response();
+    }
+
+    let factory = PipelineOrchestratorFactory::new(
+        state.database.clone(),
+        Arc::new(state.logo_asset_service.clone()),
+        state.config.clone(),
+        state.temp_file_manager.clone(),
+        state.proxy_output_file_manager.clone(),
+        state.progress_service.get_ingestion_state_manager(),
+    );
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+    let pipeline_task = AbortOnDrop(tokio::spawn(async move {
+        let mut orchestrator = factory
+            .create_for_proxy(proxy_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        orchestrator.stop_after_stage(PREVIEW_FINAL_STAGE);
+        orchestrator.set_stage_event_sender(event_tx);
+        orchestrator
+            .execute_pipeline()
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }));
+
+    let file_manager = state.temp_file_manager.clone();
+    let stream = async_stream::stream! {
+        let mut pipeline_task = pipeline_task;
+        let started = Instant::now();
+        let mut final_channels = None;
+
+        while let Some(event) = event_rx.recv().await {
+            match event {
+                PipelineStageEvent::Started { stage_id, stage_name, index, total } => {
+                    yield Ok::<Event, axum::Error>(json_event("stage", &PreviewStageEvent {
+                        stage_id: stage_id.to_string(),
+                        stage_name: stage_name.to_string(),
+                        state: "started".to_string(),
+                        index: Some(index),
+                        total: Some(total),
+                        duration_ms: None,
+                        channel_count: None,
+                    }));
+                }
+                PipelineStageEvent::Completed { stage_id, stage_name, duration, artifacts } => {
+                    let channels_artifact = artifacts
+                        .into_iter()
+                        .find(|a| a.artifact_type.content == ContentType::Channels);
+                    yield Ok(json_event("stage", &PreviewStageEvent {
+                        stage_id: stage_id.to_string(),
+                        stage_name: stage_name.to_string(),
+                        state: "completed".to_string(),
+                        index: None,
+                        total: None,
+                        duration_ms: Some(duration.as_millis() as u64),
+                        channel_count: channels_artifact.as_ref().and_then(|a| a.record_count),
+                    }));
+                    final_channels = channels_artifact;
+                }
+            }
+        }
+
+        // The event channel closes when the pipeline task finishes
+        let outcome = match (&mut pipeline_task.0).await {
+            Ok(result) => result,
+            Err(e) => Err(format!("Preview task failed: {e}")),
+        };
+        if let Err(message) = outcome {
+            warn!("Streaming preview failed for proxy {}: {}", proxy_id, message);
+            yield Ok(json_event("error", &serde_json::json!({ "message": message })));
+            return;
+        }
+
+        let mut total_channels = 0;
+        if let Some(artifact) = final_channels {
+            let channels = match file_manager.read(&artifact.file_path).await {
+                Ok(bytes) => String::from_utf8_lossy(&bytes)
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .filter_map(|line| serde_json::from_str::<Channel>(line).ok())
+                    .collect::<Vec<_>>(),
+                Err(e) => {
+                    yield Ok(json_event("error", &serde_json::json!({
+                        "message": format!("Failed to read preview channels: {e}")
+                    })));
+                    return;
+                }
+            };
+            for (batch_index, batch) in channels.chunks(batch_size).enumerate() {
+                yield Ok(json_event("channels", &PreviewChannelBatch {
+                    offset: batch_index * batch_size,
+                    channels: batch.to_vec(),
+                }));
+            }
+            total_channels = channels.len();
+        }
+
+        info!(
+            "Streaming preview completed for proxy {} ({} channels)",
+            proxy_id, total_channels
+        );
+        yield Ok(json_event("complete", &PreviewCompleteEvent {
+            total_channels,
+            duration_ms: started.elapsed().as_millis() as u64,
+        }));
+    };
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
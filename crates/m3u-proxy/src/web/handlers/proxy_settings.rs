@@ -0,0 +1,771 @@
+//! Per-proxy feature setting handlers
+//!
+//! Each optional proxy feature is read, replaced and removed under its own
+//! `/api/v1/proxies/{id}/...` path. A proxy without the setting does not use the feature.
+
+use axum::{
+    extract::{Path, State},
+    http::Method,
+    response::{IntoResponse, Response},
+};
+use tracing::info;
+
+use super::proxy_basic_auth::resolve_existing_proxy;
+use crate::database::repositories::ProxySettingsSeaOrmRepository;
+use crate::models::proxy_settings::{
+    AttributePassthrough, EpgFallbacks, EpgGapFilling, EpgMergePolicy, ProxyAccessRules,
+    ProxySetting, RelayKeepAlivePolicy, StreamHints,
+};
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, internal_error, ok},
+    utils::log_request,
+};
+
+/// A proxy's setting, or null when it has none
+async fn get_setting<S: ProxySetting>(state: &AppState, id: &str) -> Response {
+    let proxy_id = match resolve_existing_proxy(state, id).await {
+        Ok(proxy_id) => proxy_id,
+        Err(response) => return response,
+    };
+
+    let repo = ProxySettingsSeaOrmRepository::new(state.database.connection().clone());
+    match repo.get::<S>(&proxy_id).await {
+        Ok(setting) => ok(setting).into_response(),
+        Err(e) => internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Validate and store a proxy's setting
+async fn set_setting<S: ProxySetting>(state: &AppState, id: &str, setting: S) -> Response {
+    if let Err(error) = setting.validate() {
+        return bad_request(&error).into_response();
+    }
+    let proxy_id = match resolve_existing_proxy(state, id).await {
+        Ok(proxy_id) => proxy_id,
+        Err(response) => return response,
+    };
+
+    let repo = ProxySettingsSeaOrmRepository::new(state.database.connection().clone());
+    match repo.set(&proxy_id, &setting).await {
+        Ok(()) => {
+            info!("Set {} of proxy {}", S::KEY, proxy_id);
+            ok(setting).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to set {}: {e}", S::KEY)).into_response(),
+    }
+}
+
+/// Remove a proxy's setting
+async fn delete_setting<S: ProxySetting>(state: &AppState, id: &str) -> Response {
+    let proxy_id = match resolve_existing_proxy(state, id).await {
+        Ok(proxy_id) => proxy_id,
+        Err(response) => return response,
+    };
+
+    let repo = ProxySettingsSeaOrmRepository::new(state.database.connection().clone());
+    match repo.delete::<S>(&proxy_id).await {
+        Ok(removed) => {
+            if removed {
+                info!("Removed {} of proxy {}", S::KEY, proxy_id);
+            }
+            ok(None::<S>).into_response()
+        }
+        Err(e) => internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Apply changed access rules to the cached policy, passing failed responses through
+async fn reload_access_policy(state: &AppState, response: Response) -> Response {
+    if !response.status().is_success() {
+        return response;
+    }
+    let repo = ProxySettingsSeaOrmRepository::new(state.database.connection().clone());
+    match state.access_policy.reload(&repo).await {
+        Ok(()) => response,
+        Err(e) => internal_error(&format!("Failed to reload access rules: {e}")).into_response(),
+    }
+}
+
+/// Get the relay keep-alive policy of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/relay-keepalive",
+    tag = "proxies",
+    summary = "Get relay keep-alive policy",
+    description = "The channels whose relays are kept running or pre-warmed for the proxy, or null when it has no keep-alive policy",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Keep-alive policy", body = Option<RelayKeepAlivePolicy>),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_relay_keepalive(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::GET,
+        &format!("/api/v1/proxies/{id}/relay-keepalive")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    get_setting::<RelayKeepAlivePolicy>(&state, &id).await
+}
+
+/// Set the relay keep-alive policy of a proxy
+#[utoipa::path(
+    put,
+    path = "/proxies/{id}/relay-keepalive",
+    tag = "proxies",
+    summary = "Set relay keep-alive policy",
+    description = "Keep the relays of the listed channels, plus the proxy's most-watched channels, running to avoid FFmpeg startup latency. With a schedule the relays are started at each scheduled time and kept warm for `warm_for`. The proxy must be an active relay-mode proxy with a relay profile; in a cluster only the leader keeps relays warm.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = RelayKeepAlivePolicy,
+    responses(
+        (status = 200, description = "Keep-alive policy set", body = RelayKeepAlivePolicy),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_relay_keepalive(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(policy): axum::Json<RelayKeepAlivePolicy>,
+) -> impl IntoResponse {
+    log_request(
+        &Method::PUT,
+        &format!("/api/v1/proxies/{id}/relay-keepalive")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    set_setting(&state, &id, policy).await
+}
+
+/// Remove the relay keep-alive policy of a proxy
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/relay-keepalive",
+    tag = "proxies",
+    summary = "Remove relay keep-alive policy",
+    description = "Stop keeping the proxy's relays warm; running relays are then cleaned up when idle",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Keep-alive policy removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_relay_keepalive(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::DELETE,
+        &format!("/api/v1/proxies/{id}/relay-keepalive")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    delete_setting::<RelayKeepAlivePolicy>(&state, &id).await
+}
+
+/// Get the EPG merge setting of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/epg-merge",
+    tag = "proxies",
+    summary = "Get proxy EPG merge setting",
+    description = "How the proxy combines programmes of EPG sources covering the same channel, or null when it uses the configured default strategy",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "EPG merge setting", body = Option<EpgMergePolicy>),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_epg_merge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::GET,
+        &format!("/api/v1/proxies/{id}/epg-merge").parse().unwrap(),
+        &context,
+    );
+    get_setting::<EpgMergePolicy>(&state, &id).await
+}
+
+/// Set the EPG merge setting of a proxy
+#[utoipa::path(
+    put,
+    path = "/proxies/{id}/epg-merge",
+    tag = "proxies",
+    summary = "Set proxy EPG merge setting",
+    description = "Choose how programmes of EPG sources covering the same channel are combined: `priority`, `richest_metadata`, `fill_gaps` or `field_merge`. Source priority follows the proxy's EPG source ordering; `field_sources` picks preferred sources per field for `field_merge`. Applies from the next generation.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = EpgMergePolicy,
+    responses(
+        (status = 200, description = "EPG merge setting set", body = EpgMergePolicy),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_epg_merge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(policy): axum::Json<EpgMergePolicy>,
+) -> impl IntoResponse {
+    log_request(
+        &Method::PUT,
+        &format!("/api/v1/proxies/{id}/epg-merge").parse().unwrap(),
+        &context,
+    );
+    set_setting(&state, &id, policy).await
+}
+
+/// Remove the EPG merge setting of a proxy
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/epg-merge",
+    tag = "proxies",
+    summary = "Remove proxy EPG merge setting",
+    description = "Merge the proxy's EPG sources with the configured default strategy again",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "EPG merge setting removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_epg_merge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::DELETE,
+        &format!("/api/v1/proxies/{id}/epg-merge").parse().unwrap(),
+        &context,
+    );
+    delete_setting::<EpgMergePolicy>(&state, &id).await
+}
+
+/// Get the EPG fallbacks of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/epg-fallbacks",
+    tag = "proxies",
+    summary = "Get proxy EPG fallbacks",
+    description = "Preferred alternate sources for the proxy's EPG sources when they go stale, or null when stale sources fall back to the proxy's next fresh source",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "EPG fallbacks", body = Option<EpgFallbacks>),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_epg_fallbacks(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::GET,
+        &format!("/api/v1/proxies/{id}/epg-fallbacks")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    get_setting::<EpgFallbacks>(&state, &id).await
+}
+
+/// Set the EPG fallbacks of a proxy
+#[utoipa::path(
+    put,
+    path = "/proxies/{id}/epg-fallbacks",
+    tag = "proxies",
+    summary = "Set proxy EPG fallbacks",
+    description = "Name the EPG source whose programmes replace each of the proxy's sources once it has not ingested within `epg_failover.staleness_window`. The fallback need not be attached to the proxy. Stale sources without an entry fall back to the proxy's next fresh source. Applies from the next generation.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = EpgFallbacks,
+    responses(
+        (status = 200, description = "EPG fallbacks set", body = EpgFallbacks),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_epg_fallbacks(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(fallbacks): axum::Json<EpgFallbacks>,
+) -> impl IntoResponse {
+    log_request(
+        &Method::PUT,
+        &format!("/api/v1/proxies/{id}/epg-fallbacks")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    set_setting(&state, &id, fallbacks).await
+}
+
+/// Remove the EPG fallbacks of a proxy
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/epg-fallbacks",
+    tag = "proxies",
+    summary = "Remove proxy EPG fallbacks",
+    description = "Let stale EPG sources fall back to the proxy's next fresh source again",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "EPG fallbacks removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_epg_fallbacks(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::DELETE,
+        &format!("/api/v1/proxies/{id}/epg-fallbacks")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    delete_setting::<EpgFallbacks>(&state, &id).await
+}
+
+/// Get the EPG gap filling setting of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/epg-gap-filling",
+    tag = "proxies",
+    summary = "Get proxy EPG gap filling",
+    description = "Whether the proxy's guide gets synthetic programmes where it has no guide data, or null when it follows `epg_gap_filler.enabled`",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "EPG gap filling setting", body = Option<EpgGapFilling>),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_epg_gap_filling(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::GET,
+        &format!("/api/v1/proxies/{id}/epg-gap-filling")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    get_setting::<EpgGapFilling>(&state, &id).await
+}
+
+/// Set the EPG gap filling setting of a proxy
+#[utoipa::path(
+    put,
+    path = "/proxies/{id}/epg-gap-filling",
+    tag = "proxies",
+    summary = "Set proxy EPG gap filling",
+    description = "Turn synthetic filler programmes on or off for the proxy's guide, overriding `epg_gap_filler.enabled`. Block length, templates and the filled window follow the `epg_gap_filler` configuration. Applies from the next generation.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = EpgGapFilling,
+    responses(
+        (status = 200, description = "EPG gap filling setting set", body = EpgGapFilling),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_epg_gap_filling(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(gap_filling): axum::Json<EpgGapFilling>,
+) -> impl IntoResponse {
+    log_request(
+        &Method::PUT,
+        &format!("/api/v1/proxies/{id}/epg-gap-filling")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    set_setting(&state, &id, gap_filling).await
+}
+
+/// Remove the EPG gap filling setting of a proxy
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/epg-gap-filling",
+    tag = "proxies",
+    summary = "Remove proxy EPG gap filling",
+    description = "Let the proxy follow `epg_gap_filler.enabled` again",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "EPG gap filling setting removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_epg_gap_filling(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::DELETE,
+        &format!("/api/v1/proxies/{id}/epg-gap-filling")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    delete_setting::<EpgGapFilling>(&state, &id).await
+}
+
+/// Get the stream hints setting of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/stream-hints",
+    tag = "proxies",
+    summary = "Get proxy stream hints",
+    description = "Whether the proxy's playlist names each stream's container and request headers for players, or null when it follows `stream_hints.enabled`",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Stream hints setting", body = Option<StreamHints>),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_stream_hints(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::GET,
+        &format!("/api/v1/proxies/{id}/stream-hints")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    get_setting::<StreamHints>(&state, &id).await
+}
+
+/// Set the stream hints setting of a proxy
+#[utoipa::path(
+    put,
+    path = "/proxies/{id}/stream-hints",
+    tag = "proxies",
+    summary = "Set proxy stream hints",
+    description = "Turn playback hints on or off for the proxy's playlist, overriding `stream_hints.enabled`. The emitted lines and headers follow the `stream_hints` configuration. Applies from the next generation.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = StreamHints,
+    responses(
+        (status = 200, description = "Stream hints setting set", body = StreamHints),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_stream_hints(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(stream_hints): axum::Json<StreamHints>,
+) -> impl IntoResponse {
+    log_request(
+        &Method::PUT,
+        &format!("/api/v1/proxies/{id}/stream-hints")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    set_setting(&state, &id, stream_hints).await
+}
+
+/// Remove the stream hints setting of a proxy
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/stream-hints",
+    tag = "proxies",
+    summary = "Remove proxy stream hints",
+    description = "Let the proxy follow `stream_hints.enabled` again",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Stream hints setting removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_stream_hints(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::DELETE,
+        &format!("/api/v1/proxies/{id}/stream-hints")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    delete_setting::<StreamHints>(&state, &id).await
+}
+
+/// Get the attribute passthrough setting of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/attribute-passthrough",
+    tag = "proxies",
+    summary = "Get proxy attribute passthrough",
+    description = "Whether the proxy's playlist carries the allowlisted extra EXTINF attributes, or null when it follows `attribute_passthrough.enabled`",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Attribute passthrough setting", body = Option<AttributePassthrough>),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_attribute_passthrough(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::GET,
+        &format!("/api/v1/proxies/{id}/attribute-passthrough")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    get_setting::<AttributePassthrough>(&state, &id).await
+}
+
+/// Set the attribute passthrough setting of a proxy
+#[utoipa::path(
+    put,
+    path = "/proxies/{id}/attribute-passthrough",
+    tag = "proxies",
+    summary = "Set proxy attribute passthrough",
+    description = "Turn attribute passthrough on or off for the proxy's playlist, overriding `attribute_passthrough.enabled`. The attributes written follow the `attribute_passthrough` allowlist. Applies from the next generation.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = AttributePassthrough,
+    responses(
+        (status = 200, description = "Attribute passthrough setting set", body = AttributePassthrough),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_attribute_passthrough(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(passthrough): axum::Json<AttributePassthrough>,
+) -> impl IntoResponse {
+    log_request(
+        &Method::PUT,
+        &format!("/api/v1/proxies/{id}/attribute-passthrough")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    set_setting(&state, &id, passthrough).await
+}
+
+/// Remove the attribute passthrough setting of a proxy
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/attribute-passthrough",
+    tag = "proxies",
+    summary = "Remove proxy attribute passthrough",
+    description = "Let the proxy follow `attribute_passthrough.enabled` again",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Attribute passthrough setting removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_attribute_passthrough(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::DELETE,
+        &format!("/api/v1/proxies/{id}/attribute-passthrough")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    delete_setting::<AttributePassthrough>(&state, &id).await
+}
+
+/// Get the access rules of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/access-rules",
+    tag = "proxies",
+    summary = "Get proxy access rules",
+    description = "Client IP and country allow/deny rules of the proxy's playlist, guide, stream and share link endpoints, or null when it is open to everyone",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Access rules", body = Option<ProxyAccessRules>),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_access_rules(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::GET,
+        &format!("/api/v1/proxies/{id}/access-rules")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    get_setting::<ProxyAccessRules>(&state, &id).await
+}
+
+/// Set the access rules of a proxy
+#[utoipa::path(
+    put,
+    path = "/proxies/{id}/access-rules",
+    tag = "proxies",
+    summary = "Set proxy access rules",
+    description = "Restrict the proxy's playlist, guide, stream and share link endpoints by client IP and country. Deny rules win; once any allow rule is set, only matching clients get in. Country rules need `access_control.geoip_database`. Applies immediately.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = ProxyAccessRules,
+    responses(
+        (status = 200, description = "Access rules set", body = ProxyAccessRules),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_access_rules(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(rules): axum::Json<ProxyAccessRules>,
+) -> impl IntoResponse {
+    log_request(
+        &Method::PUT,
+        &format!("/api/v1/proxies/{id}/access-rules")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    let response = set_setting(&state, &id, rules).await;
+    reload_access_policy(&state, response).await
+}
+
+/// Remove the access rules of a proxy
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/access-rules",
+    tag = "proxies",
+    summary = "Remove proxy access rules",
+    description = "Open the proxy to every client again",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Access rules removed"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_access_rules(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &Method::DELETE,
+        &format!("/api/v1/proxies/{id}/access-rules")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    let response = delete_setting::<ProxyAccessRules>(&state, &id).await;
+    reload_access_policy(&state, response).await
+}
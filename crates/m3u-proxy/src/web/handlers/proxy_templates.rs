@@ -0,0 +1,320 @@
+//! Proxy template handlers
+//!
+//! Saved bundles of proxy settings and creating new proxies from them.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::database::repositories::{FilterGroupSeaOrmRepository, ProxyTemplateSeaOrmRepository};
+use crate::models::proxy_template::{
+    CreateProxyFromTemplateRequest, ProxyTemplate, ProxyTemplateRequest, expand_filters,
+};
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    handlers::proxies::{StreamProxyResponse, create_proxy_from_request},
+    responses::{bad_request, conflict, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+/// List proxy templates
+#[utoipa::path(
+    get,
+    path = "/proxy-templates",
+    tag = "proxies",
+    summary = "List proxy templates",
+    responses(
+        (status = 200, description = "Proxy templates", body = Vec<ProxyTemplate>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_proxy_templates(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/proxy-templates".parse().unwrap(),
+        &context,
+    );
+
+    let repo = ProxyTemplateSeaOrmRepository::new(state.database.read_connection());
+    match repo.list_all().await {
+        Ok(templates) => ok(templates).into_response(),
+        Err(e) => internal_error(&format!("Failed to list proxy templates: {e}")).into_response(),
+    }
+}
+
+/// Get a proxy template
+#[utoipa::path(
+    get,
+    path = "/proxy-templates/{id}",
+    tag = "proxies",
+    summary = "Get proxy template",
+    params(
+        ("id" = Uuid, Path, description = "Proxy template ID"),
+    ),
+    responses(
+        (status = 200, description = "Proxy template", body = ProxyTemplate),
+        (status = 404, description = "Proxy template not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_proxy_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxy-templates/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let repo = ProxyTemplateSeaOrmRepository::new(state.database.read_connection());
+    match repo.find_by_id(&id).await {
+        Ok(Some(template)) => ok(template).into_response(),
+        Ok(None) => not_found("Proxy template", &id.to_string()).into_response(),
+        Err(e) => internal_error(&format!("Failed to load proxy template: {e}")).into_response(),
+    }
+}
+
+/// Create a proxy template
+#[utoipa::path(
+    post,
+    path = "/proxy-templates",
+    tag = "proxies",
+    summary = "Create proxy template",
+    description = "Save a bundle of proxy settings: mode, stream and EPG sources, filters and filter groups in priority order, and output options",
+    request_body = ProxyTemplateRequest,
+    responses(
+        (status = 200, description = "Created proxy template", body = ProxyTemplate),
+        (status = 400, description = "Invalid request"),
+        (status = 409, description = "A proxy template with this name exists"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_proxy_template(
+    State(state): State<AppState>,
+    context: RequestContext,
+    Json(request): Json<ProxyTemplateRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &"/api/v1/proxy-templates".parse().unwrap(),
+        &context,
+    );
+
+    if let Err(response) = validate_request(&state, None, &request).await {
+        return response;
+    }
+
+    let repo = ProxyTemplateSeaOrmRepository::new(state.database.connection().clone());
+    match repo.create(request).await {
+        Ok(template) => {
+            info!(
+                "Created proxy template '{}' ({})",
+                template.name, template.id
+            );
+            ok(template).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to create proxy template: {e}")).into_response(),
+    }
+}
+
+/// Replace a proxy template
+#[utoipa::path(
+    put,
+    path = "/proxy-templates/{id}",
+    tag = "proxies",
+    summary = "Update proxy template",
+    description = "Replace a proxy template. Proxies already created from it are unaffected.",
+    params(
+        ("id" = Uuid, Path, description = "Proxy template ID"),
+    ),
+    request_body = ProxyTemplateRequest,
+    responses(
+        (status = 200, description = "Updated proxy template", body = ProxyTemplate),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy template not found"),
+        (status = 409, description = "A proxy template with this name exists"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_proxy_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    context: RequestContext,
+    Json(request): Json<ProxyTemplateRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/proxy-templates/{id}").parse().unwrap(),
+        &context,
+    );
+
+    if let Err(response) = validate_request(&state, Some(id), &request).await {
+        return response;
+    }
+
+    let repo = ProxyTemplateSeaOrmRepository::new(state.database.connection().clone());
+    match repo.update(&id, request).await {
+        Ok(Some(template)) => ok(template).into_response(),
+        Ok(None) => not_found("Proxy template", &id.to_string()).into_response(),
+        Err(e) => internal_error(&format!("Failed to update proxy template: {e}")).into_response(),
+    }
+}
+
+/// Delete a proxy template
+#[utoipa::path(
+    delete,
+    path = "/proxy-templates/{id}",
+    tag = "proxies",
+    summary = "Delete proxy template",
+    description = "Delete a proxy template. Proxies created from it are unaffected.",
+    params(
+        ("id" = Uuid, Path, description = "Proxy template ID"),
+    ),
+    responses(
+        (status = 200, description = "Proxy template deleted"),
+        (status = 404, description = "Proxy template not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_proxy_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/proxy-templates/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let repo = ProxyTemplateSeaOrmRepository::new(state.database.connection().clone());
+    match repo.delete(&id).await {
+        Ok(true) => ok(serde_json::json!({"message": "Proxy template deleted"})).into_response(),
+        Ok(false) => not_found("Proxy template", &id.to_string()).into_response(),
+        Err(e) => internal_error(&format!("Failed to delete proxy template: {e}")).into_response(),
+    }
+}
+
+/// Create a proxy from a template
+#[utoipa::path(
+    post,
+    path = "/proxy-templates/{id}/proxies",
+    tag = "proxies",
+    summary = "Create proxy from template",
+    description = "Create a new proxy with the template's settings. The template's filter groups are expanded after its individual filters, in order; a filter listed more than once is attached once, at its first position.",
+    params(
+        ("id" = Uuid, Path, description = "Proxy template ID"),
+    ),
+    request_body = CreateProxyFromTemplateRequest,
+    responses(
+        (status = 200, description = "Created stream proxy", body = StreamProxyResponse),
+        (status = 400, description = "Invalid request or template references a missing filter group"),
+        (status = 404, description = "Proxy template not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_proxy_from_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    context: RequestContext,
+    Json(request): Json<CreateProxyFromTemplateRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/proxy-templates/{id}/proxies")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let repo = ProxyTemplateSeaOrmRepository::new(state.database.read_connection());
+    let template = match repo.find_by_id(&id).await {
+        Ok(Some(template)) => template,
+        Ok(None) => return not_found("Proxy template", &id.to_string()).into_response(),
+        Err(e) => {
+            return internal_error(&format!("Failed to load proxy template: {e}")).into_response();
+        }
+    };
+
+    let group_repo = FilterGroupSeaOrmRepository::new(state.database.read_connection());
+    let groups = match group_repo
+        .find_many(&template.settings.filter_group_ids)
+        .await
+    {
+        Ok(groups) => groups,
+        Err(e) => {
+            return internal_error(&format!("Failed to load filter groups: {e}")).into_response();
+        }
+    };
+    if let Some(missing) = template
+        .settings
+        .filter_group_ids
+        .iter()
+        .find(|group_id| !groups.iter().any(|group| group.id == **group_id))
+    {
+        return bad_request(&format!(
+            "Template references filter group {missing}, which no longer exists"
+        ))
+        .into_response();
+    }
+
+    let filter_ids = expand_filters(&template.settings.filter_ids, &groups);
+    let description = request.description.or(template.description.clone());
+    let service_request =
+        match template
+            .settings
+            .to_create_request(request.name, description, &filter_ids)
+        {
+            Ok(service_request) => service_request,
+            Err(error) => return bad_request(&error).into_response(),
+        };
+
+    info!(
+        "Creating proxy '{}' from template '{}'",
+        service_request.name, template.name
+    );
+    create_proxy_from_request(&state, service_request).await
+}
+
+/// Check the template name is unique and its settings are usable
+async fn validate_request(
+    state: &AppState,
+    template_id: Option<Uuid>,
+    request: &ProxyTemplateRequest,
+) -> Result<(), axum::response::Response> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(bad_request("Proxy template name must not be empty").into_response());
+    }
+    if let Err(error) = request.settings.proxy_mode() {
+        return Err(bad_request(&error).into_response());
+    }
+
+    let repo = ProxyTemplateSeaOrmRepository::new(state.database.read_connection());
+    match repo.list_all().await {
+        Ok(templates)
+            if templates.iter().any(|template| {
+                Some(template.id) != template_id && template.name.eq_ignore_ascii_case(name)
+            }) =>
+        {
+            Err(
+                conflict(&format!("A proxy template named '{name}' already exists"))
+                    .into_response(),
+            )
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            Err(internal_error(&format!("Failed to list proxy templates: {e}")).into_response())
+        }
+    }
+}
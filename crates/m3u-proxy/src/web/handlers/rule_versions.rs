@@ -0,0 +1,425 @@
+//! Filter and data mapping rule version handlers
+//!
+//! List the version history of a filter or data mapping rule, compare two versions and
+//! revert a rule to an earlier version. Versions are recorded whenever a rule is created,
+//! updated or reverted through the API.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use tracing::{info, warn};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::database::repositories::{FilterSeaOrmRepository, RuleVersionSeaOrmRepository};
+use crate::models::data_mapping::DataMappingRuleUpdateRequest;
+use crate::models::rule_version::{
+    RuleChange, RuleKind, RuleSnapshot, RuleVersion, RuleVersionDiff,
+};
+use crate::models::{Filter, FilterUpdateRequest};
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+/// Versions to compare
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RuleVersionDiffQuery {
+    /// Older version
+    pub from: i32,
+    /// Newer version (default: latest)
+    pub to: Option<i32>,
+}
+
+/// Record a saved state of a rule in its version history
+///
+/// History is secondary to the save itself, so failures are logged rather than returned.
+pub async fn record_rule_version(
+    state: &AppState,
+    kind: RuleKind,
+    rule_id: Uuid,
+    previous: Option<&RuleSnapshot>,
+    current: &RuleSnapshot,
+    change: RuleChange,
+    context: &RequestContext,
+) {
+    let repo = RuleVersionSeaOrmRepository::new(state.database.connection().clone());
+    if let Err(e) = repo
+        .record(
+            kind,
+            rule_id,
+            previous,
+            current,
+            change,
+            None,
+            context.author(),
+        )
+        .await
+    {
+        warn!(
+            "Failed to record version of {} {}: {}",
+            kind.as_str(),
+            rule_id,
+            e
+        );
+    }
+}
+
+/// List the versions of a filter
+#[utoipa::path(
+    get,
+    path = "/filters/{id}/versions",
+    tag = "filters",
+    summary = "List filter versions",
+    description = "List the saved versions of a filter, newest first, with their author, time and the expression each replaced",
+    params(("id" = String, Path, description = "Filter ID (UUID)")),
+    responses(
+        (status = 200, description = "Filter versions", body = Vec<RuleVersion>),
+        (status = 400, description = "Invalid filter ID"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_filter_versions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/filters/{id}/versions").parse().unwrap(),
+        &context,
+    );
+    list_versions(&state, RuleKind::Filter, &id).await
+}
+
+/// Compare two versions of a filter
+#[utoipa::path(
+    get,
+    path = "/filters/{id}/versions/diff",
+    tag = "filters",
+    summary = "Diff filter versions",
+    description = "List the fields that differ between two versions of a filter, with a word-level diff of their expressions",
+    params(("id" = String, Path, description = "Filter ID (UUID)"), RuleVersionDiffQuery),
+    responses(
+        (status = 200, description = "Version differences", body = RuleVersionDiff),
+        (status = 400, description = "Invalid filter ID"),
+        (status = 404, description = "Version not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn diff_filter_versions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<RuleVersionDiffQuery>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/filters/{id}/versions/diff")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    diff_versions(&state, RuleKind::Filter, &id, &query).await
+}
+
+/// Revert a filter to an earlier version
+#[utoipa::path(
+    post,
+    path = "/filters/{id}/versions/{version}/revert",
+    tag = "filters",
+    summary = "Revert filter",
+    description = "Restore a filter's name, source type, inversion and expression from an earlier version. The revert is recorded as a new version.",
+    params(
+        ("id" = String, Path, description = "Filter ID (UUID)"),
+        ("version" = i32, Path, description = "Version to restore"),
+    ),
+    responses(
+        (status = 200, description = "Reverted filter", body = Filter),
+        (status = 400, description = "Invalid filter ID"),
+        (status = 404, description = "Filter or version not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn revert_filter(
+    State(state): State<AppState>,
+    Path((id, version)): Path<(String, i32)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/filters/{id}/versions/{version}/revert")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let (rule_id, target) = match find_version(&state, RuleKind::Filter, &id, version).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let request: FilterUpdateRequest = match serde_json::from_value(target.definition.clone()) {
+        Ok(request) => request,
+        Err(e) => {
+            return internal_error(&format!("Version {version} cannot be restored: {e}"))
+                .into_response();
+        }
+    };
+
+    let filter_repo = FilterSeaOrmRepository::new(state.database.connection().clone());
+    let previous = match filter_repo.find_by_id(rule_id).await {
+        Ok(Some(filter)) => RuleSnapshot::from(&filter),
+        Ok(None) => return not_found("filter", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    };
+    match filter_repo.update(&rule_id, request).await {
+        Ok(filter) => {
+            record_revert(
+                &state,
+                RuleKind::Filter,
+                rule_id,
+                &previous,
+                &RuleSnapshot::from(&filter),
+                version,
+                &context,
+            )
+            .await;
+            ok(filter).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to revert filter: {e}")).into_response(),
+    }
+}
+
+/// List the versions of a data mapping rule
+#[utoipa::path(
+    get,
+    path = "/data-mapping/{id}/versions",
+    tag = "data-mapping",
+    summary = "List data mapping rule versions",
+    description = "List the saved versions of a data mapping rule, newest first, with their author, time and the expression each replaced",
+    params(("id" = String, Path, description = "Data mapping rule ID (UUID)")),
+    responses(
+        (status = 200, description = "Data mapping rule versions", body = Vec<RuleVersion>),
+        (status = 400, description = "Invalid rule ID"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_data_mapping_rule_versions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/data-mapping/{id}/versions")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    list_versions(&state, RuleKind::DataMappingRule, &id).await
+}
+
+/// Compare two versions of a data mapping rule
+#[utoipa::path(
+    get,
+    path = "/data-mapping/{id}/versions/diff",
+    tag = "data-mapping",
+    summary = "Diff data mapping rule versions",
+    description = "List the fields that differ between two versions of a data mapping rule, with a word-level diff of their expressions",
+    params(("id" = String, Path, description = "Data mapping rule ID (UUID)"), RuleVersionDiffQuery),
+    responses(
+        (status = 200, description = "Version differences", body = RuleVersionDiff),
+        (status = 400, description = "Invalid rule ID"),
+        (status = 404, description = "Version not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn diff_data_mapping_rule_versions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<RuleVersionDiffQuery>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/data-mapping/{id}/versions/diff")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+    diff_versions(&state, RuleKind::DataMappingRule, &id, &query).await
+}
+
+/// Revert a data mapping rule to an earlier version
+#[utoipa::path(
+    post,
+    path = "/data-mapping/{id}/versions/{version}/revert",
+    tag = "data-mapping",
+    summary = "Revert data mapping rule",
+    description = "Restore a data mapping rule's name, description, source type, expression, active state and scope from an earlier version. The revert is recorded as a new version.",
+    params(
+        ("id" = String, Path, description = "Data mapping rule ID (UUID)"),
+        ("version" = i32, Path, description = "Version to restore"),
+    ),
+    responses(
+        (status = 200, description = "Reverted data mapping rule", body = crate::models::data_mapping::DataMappingRule),
+        (status = 400, description = "Invalid rule ID or the version no longer validates"),
+        (status = 404, description = "Data mapping rule or version not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn revert_data_mapping_rule(
+    State(state): State<AppState>,
+    Path((id, version)): Path<(String, i32)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/data-mapping/{id}/versions/{version}/revert")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let (rule_id, target) =
+        match find_version(&state, RuleKind::DataMappingRule, &id, version).await {
+            Ok(found) => found,
+            Err(response) => return response,
+        };
+    let request: DataMappingRuleUpdateRequest =
+        match serde_json::from_value(target.definition.clone()) {
+            Ok(request) => request,
+            Err(e) => {
+                return internal_error(&format!("Version {version} cannot be restored: {e}"))
+                    .into_response();
+            }
+        };
+
+    let previous = match state
+        .data_mapping_service
+        .get_rule_with_details(rule_id)
+        .await
+    {
+        Ok(Some(rule)) => RuleSnapshot::from(&rule),
+        Ok(None) => return not_found("data mapping rule", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    };
+    // The version is validated again, as fields or sources it refers to may have changed
+    match state
+        .data_mapping_service
+        .update_rule(rule_id, request)
+        .await
+    {
+        Ok(rule) => {
+            record_revert(
+                &state,
+                RuleKind::DataMappingRule,
+                rule_id,
+                &previous,
+                &RuleSnapshot::from(&rule),
+                version,
+                &context,
+            )
+            .await;
+            ok(rule).into_response()
+        }
+        Err(e) => bad_request(&format!("Failed to revert data mapping rule: {e}")).into_response(),
+    }
+}
+
+async fn list_versions(state: &AppState, kind: RuleKind, id: &str) -> axum::response::Response {
+    let Ok(rule_id) = Uuid::parse_str(id) else {
+        return bad_request("Invalid rule ID").into_response();
+    };
+    let repo = RuleVersionSeaOrmRepository::new(state.database.read_connection());
+    match repo.list(kind, &rule_id).await {
+        Ok(versions) => ok(versions).into_response(),
+        Err(e) => internal_error(&format!("Failed to list versions: {e}")).into_response(),
+    }
+}
+
+async fn diff_versions(
+    state: &AppState,
+    kind: RuleKind,
+    id: &str,
+    query: &RuleVersionDiffQuery,
+) -> axum::response::Response {
+    let (rule_id, from) = match find_version(state, kind, id, query.from).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let to = match query.to {
+        Some(version) => match find_version(state, kind, id, version).await {
+            Ok((_, to)) => to,
+            Err(response) => return response,
+        },
+        None => {
+            let repo = RuleVersionSeaOrmRepository::new(state.database.read_connection());
+            match repo.latest(kind, &rule_id).await {
+                Ok(Some(latest)) => latest,
+                Ok(None) => return not_found("version", "latest").into_response(),
+                Err(e) => return internal_error(&e.to_string()).into_response(),
+            }
+        }
+    };
+    ok(RuleVersionDiff::between(&from, &to)).into_response()
+}
+
+async fn find_version(
+    state: &AppState,
+    kind: RuleKind,
+    id: &str,
+    version: i32,
+) -> Result<(Uuid, RuleVersion), axum::response::Response> {
+    let Ok(rule_id) = Uuid::parse_str(id) else {
+        return Err(bad_request("Invalid rule ID").into_response());
+    };
+    let repo = RuleVersionSeaOrmRepository::new(state.database.read_connection());
+    match repo.find(kind, &rule_id, version).await {
+        Ok(Some(found)) => Ok((rule_id, found)),
+        Ok(None) => Err(not_found("version", &version.to_string()).into_response()),
+        Err(e) => Err(internal_error(&e.to_string()).into_response()),
+    }
+}
+
+async fn record_revert(
+    state: &AppState,
+    kind: RuleKind,
+    rule_id: Uuid,
+    previous: &RuleSnapshot,
+    current: &RuleSnapshot,
+    version: i32,
+    context: &RequestContext,
+) {
+    let repo = RuleVersionSeaOrmRepository::new(state.database.connection().clone());
+    match repo
+        .record(
+            kind,
+            rule_id,
+            Some(previous),
+            current,
+            RuleChange::Reverted,
+            Some(version),
+            context.author(),
+        )
+        .await
+    {
+        Ok(recorded) => info!(
+            "Reverted {} {} to version {} (now version {})",
+            kind.as_str(),
+            rule_id,
+            version,
+            recorded.version
+        ),
+        Err(e) => warn!(
+            "Failed to record revert of {} {}: {}",
+            kind.as_str(),
+            rule_id,
+            e
+        ),
+    }
+}
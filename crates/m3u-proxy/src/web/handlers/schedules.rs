@@ -0,0 +1,197 @@
+//! Cron schedule preview handlers
+//!
+//! Validate a source update schedule and list its next runs before it is saved, so an
+//! invalid expression is rejected early and the UI can show when the next refresh happens.
+
+use axum::{extract::State, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::repositories::{EpgSourceSeaOrmRepository, StreamSourceSeaOrmRepository};
+use crate::utils::time::{instance_timezone, parse_iana_timezone};
+use crate::utils::upcoming_runs;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+const DEFAULT_RUN_COUNT: usize = 5;
+const MAX_RUN_COUNT: usize = 50;
+
+/// Request to preview a cron schedule
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CronPreviewRequest {
+    /// Cron expression (seconds minutes hours day-of-month month day-of-week [year])
+    pub expression: String,
+    /// Number of runs to list (default 5, max 50)
+    pub count: Option<usize>,
+    /// Stream or EPG source whose schedule the expression is for
+    pub source_id: Option<Uuid>,
+}
+
+/// A scheduled run
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScheduledRun {
+    pub at: DateTime<Utc>,
+    /// Run time in the timezone of the preview, RFC 3339 with offset
+    pub local: String,
+}
+
+/// Next runs of a cron expression
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CronPreviewResponse {
+    pub expression: String,
+    /// Timezone of this instance (`TZ`, or UTC) the local run times are given in
+    pub timezone: String,
+    pub next_runs: Vec<ScheduledRun>,
+    /// Runs of the source when the expression replaces its schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceSchedulePreview>,
+}
+
+/// Next runs of a source on a previewed schedule
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SourceSchedulePreview {
+    pub source_id: Uuid,
+    /// "stream" or "epg"
+    pub source_type: String,
+    pub name: String,
+    /// The source's current cron expression
+    pub current_expression: String,
+    pub last_ingested_at: Option<DateTime<Utc>>,
+    /// Timezone the local run times are given in: the EPG source's timezone when it is a
+    /// named timezone, otherwise the instance timezone
+    pub timezone: String,
+    /// Runs counted from the last ingestion, as the scheduler does, or from now once
+    /// that run has passed
+    pub next_runs: Vec<ScheduledRun>,
+}
+
+/// A source's schedule state
+struct SourceSchedule {
+    source_type: &'static str,
+    name: String,
+    update_cron: String,
+    last_ingested_at: Option<DateTime<Utc>>,
+    timezone: Option<String>,
+}
+
+fn scheduled_runs(runs: Vec<DateTime<Utc>>, timezone: Tz) -> Vec<ScheduledRun> {
+    runs.into_iter()
+        .map(|at| ScheduledRun {
+            at,
+            local: at.with_timezone(&timezone).to_rfc3339(),
+        })
+        .collect()
+}
+
+/// Preview a cron schedule
+#[utoipa::path(
+    post,
+    path = "/schedules/preview",
+    tag = "jobs",
+    summary = "Preview cron schedule",
+    description = "Validate a cron expression and list its next run times in the instance timezone. With a source id, also list the source's next runs on the expression, counted from its last ingestion like the scheduler does.",
+    request_body = CronPreviewRequest,
+    responses(
+        (status = 200, description = "Next runs", body = CronPreviewResponse),
+        (status = 400, description = "Invalid cron expression"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn preview_cron_schedule(
+    State(state): State<AppState>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<CronPreviewRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &"/api/v1/schedules/preview".parse().unwrap(),
+        &context,
+    );
+
+    let expression = request.expression.trim();
+    let count = request
+        .count
+        .unwrap_or(DEFAULT_RUN_COUNT)
+        .clamp(1, MAX_RUN_COUNT);
+    let now = Utc::now();
+    let runs = match upcoming_runs(expression, now, count) {
+        Ok(runs) => runs,
+        Err(e) => return bad_request(&e).into_response(),
+    };
+    let timezone = instance_timezone();
+
+    let source = match request.source_id {
+        Some(source_id) => match find_source(&state, source_id).await {
+            Ok(Some(source)) => {
+                let source_timezone = source
+                    .timezone
+                    .as_deref()
+                    .and_then(|tz| parse_iana_timezone(tz).ok())
+                    .unwrap_or(timezone);
+                // The scheduler runs a source at its first scheduled time after the last
+                // ingestion, unless that time has already passed
+                let from = source.last_ingested_at.unwrap_or(now);
+                let source_runs = match upcoming_runs(expression, from, count) {
+                    Ok(source_runs) if source_runs.first().is_some_and(|run| *run > now) => {
+                        source_runs
+                    }
+                    _ => runs.clone(),
+                };
+                Some(SourceSchedulePreview {
+                    source_id,
+                    source_type: source.source_type.to_string(),
+                    name: source.name,
+                    current_expression: source.update_cron,
+                    last_ingested_at: source.last_ingested_at,
+                    timezone: source_timezone.name().to_string(),
+                    next_runs: scheduled_runs(source_runs, source_timezone),
+                })
+            }
+            Ok(None) => return not_found("source", &source_id.to_string()).into_response(),
+            Err(e) => return internal_error(&e.to_string()).into_response(),
+        },
+        None => None,
+    };
+
+    ok(CronPreviewResponse {
+        expression: expression.to_string(),
+        timezone: timezone.name().to_string(),
+        next_runs: scheduled_runs(runs, timezone),
+        source,
+    })
+    .into_response()
+}
+
+async fn find_source(state: &AppState, source_id: Uuid) -> anyhow::Result<Option<SourceSchedule>> {
+    let connection = state.database.read_connection();
+    if let Some(source) = StreamSourceSeaOrmRepository::new(connection.clone())
+        .find_by_id(&source_id)
+        .await?
+    {
+        return Ok(Some(SourceSchedule {
+            source_type: "stream",
+            name: source.name,
+            update_cron: source.update_cron,
+            last_ingested_at: source.last_ingested_at,
+            timezone: None,
+        }));
+    }
+    Ok(EpgSourceSeaOrmRepository::new(connection)
+        .find_by_id(&source_id)
+        .await?
+        .map(|source| SourceSchedule {
+            source_type: "epg",
+            name: source.name,
+            update_cron: source.update_cron,
+            last_ingested_at: source.last_ingested_at,
+            timezone: source.original_timezone,
+        }))
+}
@@ -0,0 +1,425 @@
+//! Unified search API handlers
+//!
+//! Provides a single endpoint that searches sources, proxies, filters, channels and
+//! EPG programs so the frontend's global search doesn't need one request per entity.
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::repositories::traits::contains_any;
+use crate::database::repositories::{ChannelSeaOrmRepository, EpgProgramSeaOrmRepository};
+use crate::entities::{epg_sources, filters, stream_proxies, stream_sources};
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{PaginatedResponse, bad_request, internal_error, ok},
+    utils::log_request,
+};
+
+/// Minimum number of characters required for a search query
+const MIN_QUERY_LENGTH: usize = 2;
+/// Default number of results per page
+const DEFAULT_LIMIT: u32 = 20;
+/// Maximum number of results per page
+const MAX_LIMIT: u32 = 100;
+
+/// Query parameters for unified search
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct SearchQuery {
+    /// Search term (case-insensitive substring match)
+    pub q: String,
+    /// Comma-separated result types to include (defaults to all)
+    pub types: Option<String>,
+    /// Pagination: page number (1-based)
+    pub page: Option<u32>,
+    /// Pagination: items per page (max 100)
+    pub limit: Option<u32>,
+}
+
+/// Entity type of a search result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchResultType {
+    StreamSource,
+    EpgSource,
+    Proxy,
+    Filter,
+    Channel,
+    EpgProgram,
+}
+
+impl SearchResultType {
+    /// All result types, in the order results are returned
+    pub const ALL: [SearchResultType; 6] = [
+        SearchResultType::StreamSource,
+        SearchResultType::EpgSource,
+        SearchResultType::Proxy,
+        SearchResultType::Filter,
+        SearchResultType::Channel,
+        SearchResultType::EpgProgram,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchResultType::StreamSource => "stream_source",
+            SearchResultType::EpgSource => "epg_source",
+            SearchResultType::Proxy => "proxy",
+            SearchResultType::Filter => "filter",
+            SearchResultType::Channel => "channel",
+            SearchResultType::EpgProgram => "epg_program",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// A single type-tagged search hit
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchResult {
+    pub result_type: SearchResultType,
+    pub id: Uuid,
+    /// Primary display text (entity name, channel name or programme title)
+    pub title: String,
+    /// Secondary display text (source type, group, channel name etc.)
+    pub subtitle: Option<String>,
+    /// Owning source for channels and EPG programs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+/// Unified search response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchResponse {
+    pub query: String,
+    /// Total matches per result type (across all pages)
+    pub counts: BTreeMap<String, u64>,
+    pub results: PaginatedResponse<SearchResult>,
+}
+
+/// Tracks the page window while walking result types in order
+#[derive(Debug)]
+struct SearchWindow {
+    offset: u64,
+    remaining: u64,
+}
+
+impl SearchWindow {
+    fn new(offset: u64, limit: u64) -> Self {
+        Self {
+            offset,
+            remaining: limit,
+        }
+    }
+
+    /// Record that a result type had `total` matches of which `taken` were returned
+    fn advance(&mut self, total: u64, taken: usize) {
+        if self.offset >= total {
+            self.offset -= total;
+        } else {
+            self.offset = 0;
+            self.remaining = self.remaining.saturating_sub(taken as u64);
+        }
+    }
+}
+
+/// Not-deleted rows of an entity with `query` in any of `columns`: the total count and the
+/// rows falling in the window, ordered by `order_by`
+async fn search_entity<E>(
+    connection: &DatabaseConnection,
+    query: &str,
+    columns: impl IntoIterator<Item = E::Column>,
+    deleted_at: E::Column,
+    order_by: E::Column,
+    window: &mut SearchWindow,
+) -> anyhow::Result<(Vec<E::Model>, u64)>
+where
+    E: EntityTrait,
+    E::Model: Sync,
+{
+    let condition = Condition::all()
+        .add(deleted_at.is_null())
+        .add(contains_any(query, columns));
+    let total = E::find()
+        .filter(condition.clone())
+        .count(connection)
+        .await?;
+    let models = if window.remaining == 0 || window.offset >= total {
+        Vec::new()
+    } else {
+        E::find()
+            .filter(condition)
+            .order_by_asc(order_by)
+            .offset(window.offset)
+            .limit(window.remaining)
+            .all(connection)
+            .await?
+    };
+    window.advance(total, models.len());
+    Ok((models, total))
+}
+
+/// Search across sources, proxies, filters, channels and EPG programs
+#[utoipa::path(
+    get,
+    path = "/search",
+    tag = "search",
+    summary = "Unified search",
+    description = "Search stream sources, EPG sources, proxies, filters, channels and current/upcoming EPG programs in one call. Results are grouped by type in a fixed order and paginated as a single list.",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Search results", body = SearchResponse),
+        (status = 400, description = "Invalid query or result type"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn search(
+    State(state): State<AppState>,
+    context: RequestContext,
+    Query(params): Query<SearchQuery>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/search".parse().unwrap(),
+        &context,
+    );
+
+    let query = params.q.trim().to_string();
+    if query.chars().count() < MIN_QUERY_LENGTH {
+        return bad_request(&format!(
+            "Search query must be at least {MIN_QUERY_LENGTH} characters"
+        ))
+        .into_response();
+    }
+
+    let types = match &params.types {
+        Some(types) if !types.trim().is_empty() => {
+            let mut parsed = Vec::new();
+            for value in types.split(',').filter(|v| !v.trim().is_empty()) {
+                match SearchResultType::parse(value) {
+                    Some(t) => parsed.push(t),
+                    None => {
+                        return bad_request(&format!("Invalid result type: {}", value.trim()))
+                            .into_response();
+                    }
+                }
+            }
+            parsed.sort();
+            parsed.dedup();
+            parsed
+        }
+        _ => SearchResultType::ALL.to_vec(),
+    };
+
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    match run_search(&state, &query, &types, page, limit).await {
+        Ok(response) => ok(response).into_response(),
+        Err(e) => {
+            tracing::error!("Unified search failed: {}", e);
+            internal_error(&format!("Search failed: {e}")).into_response()
+        }
+    }
+}
+
+async fn run_search(
+    state: &AppState,
+    query: &str,
+    types: &[SearchResultType],
+    page: u32,
+    limit: u32,
+) -> anyhow::Result<SearchResponse> {
+    let connection = state.database.read_connection();
+    let mut window = SearchWindow::new((page as u64 - 1) * limit as u64, limit as u64);
+    let mut counts = BTreeMap::new();
+    let mut items = Vec::new();
+
+    for result_type in types {
+        let total = match result_type {
+            SearchResultType::StreamSource => {
+                let (sources, total) = search_entity::<stream_sources::Entity>(
+                    &connection,
+                    query,
+                    [stream_sources::Column::Name, stream_sources::Column::Url],
+                    stream_sources::Column::DeletedAt,
+                    stream_sources::Column::Name,
+                    &mut window,
+                )
+                .await?;
+                items.extend(sources.into_iter().map(|s| SearchResult {
+                    result_type: *result_type,
+                    id: s.id,
+                    title: s.name,
+                    subtitle: Some(s.source_type.to_string()),
+                    source_id: None,
+                    start_time: None,
+                    end_time: None,
+                }));
+                total
+            }
+            SearchResultType::EpgSource => {
+                let (sources, total) = search_entity::<epg_sources::Entity>(
+                    &connection,
+                    query,
+                    [epg_sources::Column::Name, epg_sources::Column::Url],
+                    epg_sources::Column::DeletedAt,
+                    epg_sources::Column::Name,
+                    &mut window,
+                )
+                .await?;
+                items.extend(sources.into_iter().map(|s| SearchResult {
+                    result_type: *result_type,
+                    id: s.id,
+                    title: s.name,
+                    subtitle: Some(s.source_type),
+                    source_id: None,
+                    start_time: None,
+                    end_time: None,
+                }));
+                total
+            }
+            SearchResultType::Proxy => {
+                let (proxies, total) = search_entity::<stream_proxies::Entity>(
+                    &connection,
+                    query,
+                    [
+                        stream_proxies::Column::Name,
+                        stream_proxies::Column::Description,
+                    ],
+                    stream_proxies::Column::DeletedAt,
+                    stream_proxies::Column::Name,
+                    &mut window,
+                )
+                .await?;
+                items.extend(proxies.into_iter().map(|p| SearchResult {
+                    result_type: *result_type,
+                    id: p.id,
+                    title: p.name,
+                    subtitle: p.description,
+                    source_id: None,
+                    start_time: None,
+                    end_time: None,
+                }));
+                total
+            }
+            SearchResultType::Filter => {
+                let (filters, total) = search_entity::<filters::Entity>(
+                    &connection,
+                    query,
+                    [filters::Column::Name, filters::Column::Expression],
+                    filters::Column::DeletedAt,
+                    filters::Column::Name,
+                    &mut window,
+                )
+                .await?;
+                items.extend(filters.into_iter().map(|f| SearchResult {
+                    result_type: *result_type,
+                    id: f.id,
+                    title: f.name,
+                    subtitle: Some(f.expression),
+                    source_id: None,
+                    start_time: None,
+                    end_time: None,
+                }));
+                total
+            }
+            SearchResultType::Channel => {
+                let (channels, total) = ChannelSeaOrmRepository::new(connection.clone())
+                    .search(query, window.offset, window.remaining)
+                    .await?;
+                window.advance(total, channels.len());
+                items.extend(channels.into_iter().map(|c| SearchResult {
+                    result_type: *result_type,
+                    id: c.id,
+                    title: c.channel_name,
+                    subtitle: c.group_title,
+                    source_id: Some(c.source_id),
+                    start_time: None,
+                    end_time: None,
+                }));
+                total
+            }
+            SearchResultType::EpgProgram => {
+                let (programs, total) = EpgProgramSeaOrmRepository::new(connection.clone())
+                    .search(query, &Utc::now(), window.offset, window.remaining)
+                    .await?;
+                window.advance(total, programs.len());
+                items.extend(programs.into_iter().map(|p| SearchResult {
+                    result_type: *result_type,
+                    id: p.id,
+                    title: p.program_title,
+                    subtitle: Some(p.channel_name),
+                    source_id: Some(p.source_id),
+                    start_time: Some(p.start_time),
+                    end_time: Some(p.end_time),
+                }));
+                total
+            }
+        };
+        counts.insert(result_type.as_str().to_string(), total);
+    }
+
+    let total = counts.values().sum();
+    Ok(SearchResponse {
+        query: query.to_string(),
+        counts,
+        results: PaginatedResponse::new(items, total, page, limit),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_window_spans_result_types() {
+        // Page 2 with a limit of 3: skip 3 results, then take 3
+        let mut window = SearchWindow::new(3, 3);
+
+        // First type has 2 matches, both skipped
+        window.advance(2, 0);
+        assert_eq!(window.offset, 1);
+        assert_eq!(window.remaining, 3);
+
+        // Second type has 3 matches: skip 1, take 2
+        window.advance(3, 2);
+        assert_eq!(window.offset, 0);
+        assert_eq!(window.remaining, 1);
+
+        // Third type fills the page
+        window.advance(10, 1);
+        assert_eq!(window.remaining, 0);
+    }
+
+    #[test]
+    fn test_search_result_type_parse() {
+        assert_eq!(
+            SearchResultType::parse(" Channel "),
+            Some(SearchResultType::Channel)
+        );
+        assert_eq!(
+            SearchResultType::parse("epg_program"),
+            Some(SearchResultType::EpgProgram)
+        );
+        assert_eq!(SearchResultType::parse("channels"), None);
+    }
+}
@@ -0,0 +1,151 @@
+//! Streaming session handlers
+//!
+//! List the streams currently being served, with the identity (share link or user) each
+//! was opened with, and forcibly terminate a session, e.g. to stop account sharing.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::proxy::session_tracker::SessionStats;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{not_found, ok},
+    utils::log_request,
+};
+
+/// An active streaming session
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActiveSessionResponse {
+    pub session_id: String,
+    pub proxy_name: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub client_ip: String,
+    pub user_agent: Option<String>,
+    /// Identity key, e.g. "share_link:<id>" or "user:<name>" (absent for anonymous streams)
+    pub identity: Option<String>,
+    pub identity_label: Option<String>,
+    /// Concurrent stream limit of the identity
+    pub max_streams: Option<u32>,
+    pub started_at: DateTime<Utc>,
+    pub duration_seconds: u64,
+    pub bytes_served: u64,
+    pub average_bitrate_kbps: f64,
+}
+
+impl From<&SessionStats> for ActiveSessionResponse {
+    fn from(session: &SessionStats) -> Self {
+        let duration = session.duration();
+        Self {
+            session_id: session.session_id.clone(),
+            proxy_name: session.proxy_name.clone(),
+            channel_id: session.channel_id.clone(),
+            channel_name: session.channel_name.clone(),
+            client_ip: session.client_info.ip.clone(),
+            user_agent: session.client_info.user_agent.clone(),
+            identity: session.identity.as_ref().map(|i| i.key.clone()),
+            identity_label: session.identity.as_ref().and_then(|i| i.label.clone()),
+            max_streams: session.identity.as_ref().and_then(|i| i.max_streams),
+            started_at: Utc::now() - chrono::Duration::from_std(duration).unwrap_or_default(),
+            duration_seconds: duration.as_secs(),
+            bytes_served: session.bytes_served,
+            average_bitrate_kbps: session.average_bitrate_kbps(),
+        }
+    }
+}
+
+/// Filters for listing sessions
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListSessionsQuery {
+    /// Only sessions of this identity key (e.g. "user:alice")
+    pub identity: Option<String>,
+}
+
+/// List active streaming sessions
+#[utoipa::path(
+    get,
+    path = "/sessions",
+    tag = "streaming",
+    summary = "List active streaming sessions",
+    description = "Streams currently being served, longest-running first, with the client and the identity (share link or user) each stream counts against",
+    params(ListSessionsQuery),
+    responses(
+        (status = 200, description = "Active sessions", body = Vec<ActiveSessionResponse>)
+    )
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Query(query): Query<ListSessionsQuery>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/sessions".parse().unwrap(),
+        &context,
+    );
+
+    let mut sessions: Vec<ActiveSessionResponse> = state
+        .session_tracker
+        .get_active_sessions()
+        .await
+        .iter()
+        .filter(|session| {
+            query.identity.as_deref().is_none_or(|key| {
+                session
+                    .identity
+                    .as_ref()
+                    .is_some_and(|identity| identity.key == key)
+            })
+        })
+        .map(ActiveSessionResponse::from)
+        .collect();
+    sessions.sort_by(|a, b| b.duration_seconds.cmp(&a.duration_seconds));
+
+    ok(sessions)
+}
+
+/// Terminate a streaming session
+#[utoipa::path(
+    delete,
+    path = "/sessions/{session_id}",
+    tag = "streaming",
+    summary = "Terminate streaming session",
+    description = "Close the session's stream immediately. The client may reconnect unless its share link is revoked or its stream limit is reached.",
+    params(
+        ("session_id" = String, Path, description = "Session ID"),
+    ),
+    responses(
+        (status = 204, description = "Session terminated"),
+        (status = 404, description = "Session not found")
+    )
+)]
+pub async fn kick_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/sessions/{session_id}").parse().unwrap(),
+        &context,
+    );
+
+    match state.session_tracker.kick_session(&session_id).await {
+        Some(session) => {
+            info!(
+                "Terminated session {} ({} on {})",
+                session_id, session.client_info.ip, session.channel_name
+            );
+            StatusCode::NO_CONTENT.into_response()
+        }
+        None => not_found("session", &session_id).into_response(),
+    }
+}
@@ -0,0 +1,670 @@
+//! Proxy share link handlers
+//!
+//! Management endpoints mint, list and revoke share links for a proxy. The public
+//! `/share/{token}/...` endpoints serve the proxy's playlist, guide and streams while the
+//! link is valid, to clients the proxy's access rules allow; playlist fetches count towards
+//! the link's `max_uses`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use opentelemetry::metrics::Counter;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::repositories::{ShareLinkSeaOrmRepository, StreamProxySeaOrmRepository};
+use crate::models::share_link::{CreateShareLinkRequest, ProxyShareLink, ShareLinkStatus};
+use crate::proxy::session_tracker::SessionIdentity;
+use crate::utils::access_control::AccessPolicy;
+use crate::utils::forwarded::ClientAddress;
+use crate::utils::stream_signing::STREAM_TOKEN_PARAM;
+use crate::utils::uuid_parser::parse_uuid_flexible;
+use crate::utils::{StreamUrlSigner, resolve_proxy_id, uuid_to_base64};
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    middleware::access_denied,
+    responses::{bad_request, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+/// Default share link lifetime when `valid_for` is omitted
+const DEFAULT_VALID_FOR: Duration = Duration::from_secs(72 * 60 * 60);
+
+/// Share link with its current status and public URLs
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareLinkResponse {
+    #[serde(flatten)]
+    pub link: ProxyShareLink,
+    pub status: ShareLinkStatus,
+    pub m3u8_url: String,
+    pub xmltv_url: String,
+}
+
+impl ShareLinkResponse {
+    fn new(link: ProxyShareLink, base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        Self {
+            status: link.status_at(Utc::now()),
+            m3u8_url: format!("{base_url}/share/{}/m3u8", link.token),
+            xmltv_url: format!("{base_url}/share/{}/xmltv", link.token),
+            link,
+        }
+    }
+}
+
+/// Create a share link for a proxy
+#[utoipa::path(
+    post,
+    path = "/proxies/{id}/share-links",
+    tag = "proxies",
+    summary = "Create proxy share link",
+    description = "Mint an expiring, revocable share URL for a proxy's playlist. Playlist fetches count towards `max_uses`; the guide and streams stay available until the link expires or is revoked.",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    request_body = CreateShareLinkRequest,
+    responses(
+        (status = 200, description = "Share link created", body = ShareLinkResponse),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Proxy not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_share_link(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    client: ClientAddress,
+    context: RequestContext,
+    axum::Json(request): axum::Json<CreateShareLinkRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/proxies/{id}/share-links").parse().unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let valid_for = match request.valid_for.as_deref() {
+        Some(raw) => match humantime::parse_duration(raw) {
+            Ok(duration) if !duration.is_zero() => duration,
+            _ => {
+                return bad_request(&format!("Invalid valid_for duration: {raw}")).into_response();
+            }
+        },
+        None => DEFAULT_VALID_FOR,
+    };
+    let expires_at = match chrono::Duration::from_std(valid_for) {
+        Ok(duration) => Utc::now() + duration,
+        Err(_) => return bad_request("valid_for is too large").into_response(),
+    };
+    if request.max_uses.is_some_and(|max| max < 1) {
+        return bad_request("max_uses must be at least 1").into_response();
+    }
+    if request.max_streams.is_some_and(|max| max < 1) {
+        return bad_request("max_streams must be at least 1").into_response();
+    }
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    match proxy_repo.find_by_id(&proxy_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found("proxy", &id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    }
+
+    let repo = ShareLinkSeaOrmRepository::new(state.database.connection().clone());
+    match repo
+        .create(
+            proxy_id,
+            request.name,
+            expires_at,
+            request.max_uses,
+            request.max_streams,
+        )
+        .await
+    {
+        Ok(link) => {
+            info!(
+                "Created share link {} for proxy {} (expires {})",
+                link.id, proxy_id, link.expires_at
+            );
+            let base_url = client.base_url(&state.config.web.base_url);
+            ok(ShareLinkResponse::new(link, base_url)).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to create share link: {e}")).into_response(),
+    }
+}
+
+/// List share links of a proxy
+#[utoipa::path(
+    get,
+    path = "/proxies/{id}/share-links",
+    tag = "proxies",
+    summary = "List proxy share links",
+    description = "List all share links of a proxy, including expired, exhausted and revoked ones",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+    ),
+    responses(
+        (status = 200, description = "Share links", body = Vec<ShareLinkResponse>),
+        (status = 400, description = "Invalid proxy ID"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_share_links(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    client: ClientAddress,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/proxies/{id}/share-links").parse().unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let repo = ShareLinkSeaOrmRepository::new(state.database.read_connection());
+    match repo.list_for_proxy(&proxy_id).await {
+        Ok(links) => {
+            let base_url = client.base_url(&state.config.web.base_url);
+            let links: Vec<ShareLinkResponse> = links
+                .into_iter()
+                .map(|link| ShareLinkResponse::new(link, base_url))
+                .collect();
+            ok(links).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to list share links: {e}")).into_response(),
+    }
+}
+
+/// Revoke a share link
+#[utoipa::path(
+    delete,
+    path = "/proxies/{id}/share-links/{link_id}",
+    tag = "proxies",
+    summary = "Revoke proxy share link",
+    description = "Revoke a share link immediately; its playlist, guide and stream URLs stop working and streams open through it are terminated",
+    params(
+        ("id" = String, Path, description = "Proxy ID (UUID or base64)"),
+        ("link_id" = String, Path, description = "Share link ID"),
+    ),
+    responses(
+        (status = 200, description = "Share link revoked"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Share link not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn revoke_share_link(
+    State(state): State<AppState>,
+    Path((id, link_id)): Path<(String, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/proxies/{id}/share-links/{link_id}")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let proxy_id = match resolve_proxy_id(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+    let link_uuid = match Uuid::parse_str(&link_id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid share link ID").into_response(),
+    };
+
+    let repo = ShareLinkSeaOrmRepository::new(state.database.connection().clone());
+    match repo.find_by_id(&link_uuid).await {
+        Ok(Some(link)) if link.proxy_id == proxy_id => {}
+        Ok(_) => return not_found("share link", &link_id).into_response(),
+        Err(e) => return internal_error(&e.to_string()).into_response(),
+    }
+
+    match repo.revoke(&link_uuid).await {
+        Ok(_) => {
+            let kicked = state
+                .session_tracker
+                .kick_identity_sessions(&SessionIdentity::share_link(link_uuid, None, None).key)
+                .await;
+            info!(
+                "Revoked share link {} for proxy {} ({} active stream(s) terminated)",
+                link_uuid, proxy_id, kicked
+            );
+            ok(serde_json::json!({"message": "Share link revoked"})).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to revoke share link: {e}")).into_response(),
+    }
+}
+
+/// Serve a proxy's M3U playlist through a share link
+#[utoipa::path(
+    get,
+    path = "/share/{token}/m3u8",
+    tag = "streaming",
+    summary = "Get shared M3U playlist",
+    description = "Serve the proxy playlist for a valid share link. Each fetch counts towards the link's `max_uses`; stream URLs in the playlist are rewritten to go through the share link.",
+    params(
+        ("token" = String, Path, description = "Share link token"),
+    ),
+    responses(
+        (status = 200, description = "M3U playlist content", content_type = "application/vnd.apple.mpegurl"),
+        (status = 404, description = "Unknown share link, inactive proxy or playlist not generated"),
+        (status = 410, description = "Share link expired, revoked or out of uses")
+    )
+)]
+pub async fn serve_shared_m3u(
+    Path(token): Path<String>,
+    State(state): State<AppState>,
+    client: ClientAddress,
+) -> Response {
+    let link = match resolve_share_link(&state, &token, false, &client).await {
+        Ok(link) => link,
+        Err(response) => return response,
+    };
+
+    let repo = ShareLinkSeaOrmRepository::new(state.database.connection().clone());
+    match repo.record_use(&link.id).await {
+        Ok(true) => {}
+        Ok(false) => return share_unavailable(ShareLinkStatus::Exhausted),
+        Err(e) => {
+            error!("Failed to record use of share link {}: {}", link.id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    }
+
+    let path = format!("{}.m3u8", link.proxy_id);
+    match state.proxy_output_file_manager.read_to_string(&path).await {
+        Ok(content) => {
+            info!(
+                "Served shared M3U8 for proxy {} via share link {}",
+                link.proxy_id, link.id
+            );
+            let content = client.rebase(content, &state.config.web.base_url);
+            let content = rewrite_stream_urls(&content, &link.proxy_id, &link.token);
+            (
+                StatusCode::OK,
+                [
+                    ("content-type", "application/vnd.apple.mpegurl"),
+                    ("cache-control", "no-store"),
+                ],
+                content,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!(
+                "Failed to read M3U8 file for shared proxy {} at {}: {}",
+                link.proxy_id, path, e
+            );
+            (
+                StatusCode::NOT_FOUND,
+                [("content-type", "application/vnd.apple.mpegurl")],
+                "#EXTM3U\n# Playlist not generated yet\n",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Serve a proxy's XMLTV guide through a share link
+#[utoipa::path(
+    get,
+    path = "/share/{token}/xmltv",
+    tag = "streaming",
+    summary = "Get shared XMLTV EPG",
+    description = "Serve the proxy XMLTV guide for a share link that has not expired or been revoked",
+    params(
+        ("token" = String, Path, description = "Share link token"),
+    ),
+    responses(
+        (status = 200, description = "XMLTV EPG content", content_type = "application/xml"),
+        (status = 404, description = "Unknown share link, inactive proxy or guide not generated"),
+        (status = 410, description = "Share link expired or revoked")
+    )
+)]
+pub async fn serve_shared_xmltv(
+    Path(token): Path<String>,
+    State(state): State<AppState>,
+    client: ClientAddress,
+) -> Response {
+    let link = match resolve_share_link(&state, &token, true, &client).await {
+        Ok(link) => link,
+        Err(response) => return response,
+    };
+
+    let path = format!("{}.xmltv", link.proxy_id);
+    match state.proxy_output_file_manager.read_to_string(&path).await {
+        Ok(content) => (
+            StatusCode::OK,
+            [
+                ("content-type", "application/xml"),
+                ("cache-control", "no-store"),
+            ],
+            client.rebase(content, &state.config.web.base_url),
+        )
+            .into_response(),
+        Err(e) => {
+            error!(
+                "Failed to read XMLTV file for shared proxy {} at {}: {}",
+                link.proxy_id, path, e
+            );
+            (
+                StatusCode::NOT_FOUND,
+                [("content-type", "application/xml")],
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tv><!-- Guide not generated yet --></tv>",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Stream a channel through a share link
+#[utoipa::path(
+    get,
+    path = "/share/{token}/stream/{channel_id}",
+    tag = "streaming",
+    summary = "Stream shared channel",
+    description = "Stream a channel of the shared proxy. Works until the share link expires or is revoked, even after its playlist uses are exhausted.",
+    params(
+        ("token" = String, Path, description = "Share link token"),
+        ("channel_id" = String, Path, description = "Base64-encoded channel UUID (from the shared playlist)")
+    ),
+    responses(
+        (status = 200, description = "Streaming content", content_type = "video/mp2t"),
+        (status = 404, description = "Unknown share link, inactive proxy or channel"),
+        (status = 410, description = "Share link expired or revoked"),
+        (status = 429, description = "Share link already has its maximum concurrent streams")
+    )
+)]
+pub async fn shared_stream(
+    Path((token, channel_id)): Path<(String, String)>,
+    Query(mut query): Query<HashMap<String, String>>,
+    client: ClientAddress,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Response {
+    let link = match resolve_share_link(&state, &token, true, &client).await {
+        Ok(link) => link,
+        Err(response) => return response,
+    };
+
+    // The share link authorises the stream; satisfy signed-URL proxies with a fresh token
+    if let Ok(channel_uuid) = parse_uuid_flexible(&channel_id) {
+        let signer = StreamUrlSigner::from_config(state.config.stream_signing.as_ref());
+        query.insert(
+            STREAM_TOKEN_PARAM.to_string(),
+            signer.sign(&link.proxy_id, &channel_uuid, None, Utc::now()),
+        );
+    }
+
+    // Streams through the link count towards its concurrent stream limit
+    let max_streams = link.max_streams.map(|max| max.max(1) as u32).or_else(|| {
+        state
+            .config
+            .stream_sessions
+            .as_ref()
+            .and_then(|c| c.share_link_limit())
+    });
+    let identity = SessionIdentity::share_link(link.id, link.name.clone(), max_streams);
+
+    crate::web::handlers::proxies::serve_proxy_stream(
+        Path((uuid_to_base64(&link.proxy_id), channel_id)),
+        Query(query),
+        client,
+        headers,
+        state,
+        Some(identity),
+    )
+    .await
+}
+
+/// Look up a share link and check it may be used
+///
+/// Playlist fetches require an active link; the guide and streams (`allow_exhausted`) stay
+/// available once the use limit is reached so already-loaded playlists keep working. Links
+/// to a deleted or deactivated proxy are not found.
+async fn resolve_share_link(
+    state: &AppState,
+    token: &str,
+    allow_exhausted: bool,
+    client: &ClientAddress,
+) -> Result<ProxyShareLink, Response> {
+    let repo = ShareLinkSeaOrmRepository::new(state.database.read_connection());
+    let link = match repo.find_by_token(token).await {
+        Ok(Some(link)) => link,
+        Ok(None) => {
+            warn!("Unknown share link token requested");
+            return Err((StatusCode::NOT_FOUND, "Share link not found").into_response());
+        }
+        Err(e) => {
+            error!("Failed to look up share link: {}", e);
+            return Err(
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
+            );
+        }
+    };
+
+    let link = authorize_share_link(
+        link,
+        allow_exhausted,
+        &state.access_policy,
+        &state.observability.access_decisions,
+        client,
+    )?;
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.read_connection());
+    require_active_proxy(&proxy_repo, link).await
+}
+
+/// Check the shared proxy exists and is active, as the `/proxy/{id}` routes do
+async fn require_active_proxy(
+    proxy_repo: &StreamProxySeaOrmRepository,
+    link: ProxyShareLink,
+) -> Result<ProxyShareLink, Response> {
+    match proxy_repo.find_by_id(&link.proxy_id).await {
+        Ok(Some(proxy)) if proxy.is_active => Ok(link),
+        Ok(_) => {
+            info!(
+                "Rejected share link {}: proxy {} not found or inactive",
+                link.id, link.proxy_id
+            );
+            Err((StatusCode::NOT_FOUND, "Proxy not found").into_response())
+        }
+        Err(e) => {
+            error!("Failed to look up proxy {}: {}", link.proxy_id, e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response())
+        }
+    }
+}
+
+/// Check a share link's status and the shared proxy's access rules for the client
+///
+/// Share links bypass the `/proxy/{id}` routes, so the proxy's rules are applied here.
+fn authorize_share_link(
+    link: ProxyShareLink,
+    allow_exhausted: bool,
+    policy: &AccessPolicy,
+    decisions: &Counter<u64>,
+    client: &ClientAddress,
+) -> Result<ProxyShareLink, Response> {
+    match link.status_at(Utc::now()) {
+        ShareLinkStatus::Active => {}
+        ShareLinkStatus::Exhausted if allow_exhausted => {}
+        status => {
+            info!("Rejected share link {} ({:?})", link.id, status);
+            return Err(share_unavailable(status));
+        }
+    }
+
+    match access_denied(
+        policy,
+        decisions,
+        &link.proxy_id,
+        client.ip,
+        &format!("/share/{}", link.id),
+    ) {
+        Some(response) => Err(response),
+        None => Ok(link),
+    }
+}
+
+fn share_unavailable(status: ShareLinkStatus) -> Response {
+    let message = match status {
+        ShareLinkStatus::Expired => "Share link has expired",
+        ShareLinkStatus::Exhausted => "Share link has no uses left",
+        ShareLinkStatus::Revoked => "Share link has been revoked",
+        ShareLinkStatus::Active => "Share link unavailable",
+    };
+    (StatusCode::GONE, message).into_response()
+}
+
+/// Point the playlist's stream URLs at the share link instead of the proxy
+fn rewrite_stream_urls(content: &str, proxy_id: &Uuid, token: &str) -> String {
+    content.replace(
+        &format!("/stream/{}/", uuid_to_base64(proxy_id)),
+        &format!("/share/{token}/stream/"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_stream_urls() {
+        let proxy_id = Uuid::new_v4();
+        let proxy_b64 = uuid_to_base64(&proxy_id);
+        let content = format!(
+            "#EXTM3U\n#EXTINF:-1 tvg-id=\"bbc1\",BBC One\nhttp://host:8080/stream/{proxy_b64}/abc123\n"
+        );
+
+        let rewritten = rewrite_stream_urls(&content, &proxy_id, "tok");
+        assert!(rewritten.contains("http://host:8080/share/tok/stream/abc123"));
+        assert!(!rewritten.contains(&proxy_b64));
+    }
+
+    fn share_link(proxy_id: Uuid) -> ProxyShareLink {
+        let now = Utc::now();
+        ProxyShareLink {
+            id: Uuid::new_v4(),
+            proxy_id,
+            token: "tok".to_string(),
+            name: None,
+            expires_at: now + chrono::Duration::hours(1),
+            max_uses: None,
+            use_count: 0,
+            max_streams: None,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_share_links_to_inactive_proxies_are_not_found() {
+        use crate::database::migrations::Migrator;
+        use crate::models::{StreamProxyCreateRequest, StreamProxyMode};
+        use sea_orm_migration::MigratorTrait;
+
+        let db_url = format!("sqlite::memory:{}?cache=shared&mode=memory", Uuid::new_v4());
+        let db = sea_orm::Database::connect(&db_url)
+            .await
+            .expect("memory db");
+        Migrator::up(&db, None).await.expect("migrations");
+        let proxy_repo = StreamProxySeaOrmRepository::new(std::sync::Arc::new(db));
+
+        let create = |name: &str, is_active| StreamProxyCreateRequest {
+            name: name.to_string(),
+            description: None,
+            proxy_mode: StreamProxyMode::Proxy,
+            upstream_timeout: None,
+            buffer_size: None,
+            max_concurrent_streams: None,
+            starting_channel_number: 1,
+            stream_sources: Vec::new(),
+            epg_sources: Vec::new(),
+            filters: Vec::new(),
+            is_active,
+            auto_regenerate: false,
+            cache_channel_logos: false,
+            cache_program_logos: false,
+            relay_profile_id: None,
+            sign_stream_urls: false,
+            output_profile: Default::default(),
+            backup_streams: Default::default(),
+            offline_slate: Default::default(),
+            epg_languages: None,
+            regeneration_debounce_seconds: None,
+            channel_number_blocks: Vec::new(),
+            epg_timezone: None,
+        };
+        let active = proxy_repo.create(create("Active", true)).await.unwrap();
+        let inactive = proxy_repo.create(create("Inactive", false)).await.unwrap();
+
+        assert!(
+            require_active_proxy(&proxy_repo, share_link(active.id))
+                .await
+                .is_ok()
+        );
+        for proxy_id in [inactive.id, Uuid::new_v4()] {
+            let rejected = require_active_proxy(&proxy_repo, share_link(proxy_id))
+                .await
+                .unwrap_err();
+            assert_eq!(rejected.status(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[test]
+    fn test_share_links_follow_the_proxy_access_rules() {
+        let proxy_id = Uuid::new_v4();
+        let policy = AccessPolicy::default();
+        policy.set_rules(vec![(
+            proxy_id,
+            crate::models::proxy_settings::ProxyAccessRules {
+                deny_cidrs: vec!["203.0.113.0/24".to_string()],
+                ..Default::default()
+            },
+        )]);
+        let decisions = opentelemetry::global::meter("test")
+            .u64_counter("access_decisions_total")
+            .build();
+        let link = share_link(proxy_id);
+        let client = |ip: &str| ClientAddress {
+            ip: Some(ip.parse().unwrap()),
+            ..Default::default()
+        };
+
+        let blocked = authorize_share_link(
+            link.clone(),
+            true,
+            &policy,
+            &decisions,
+            &client("203.0.113.7"),
+        )
+        .unwrap_err();
+        assert_eq!(blocked.status(), StatusCode::FORBIDDEN);
+        assert!(
+            authorize_share_link(link, true, &policy, &decisions, &client("198.51.100.7")).is_ok()
+        );
+    }
+}
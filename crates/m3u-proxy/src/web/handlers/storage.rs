@@ -0,0 +1,175 @@
+//! Storage usage handlers
+//!
+//! Report how much disk each sandboxed storage area uses, alongside its retention and
+//! subdirectory quotas, so operators can see what is filling the disk before it runs out.
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use sandboxed_file_manager::{QuotaUsage, SandboxedManager};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{internal_error, ok},
+    utils::log_request,
+};
+
+/// How long a storage scan is reused before the tree is walked again
+const USAGE_SCAN_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// Disk usage of one storage area
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StorageAreaUsage {
+    /// Storage area: m3u, pipeline, temp or cached_logos
+    pub name: String,
+    pub path: String,
+    pub total_files: usize,
+    pub total_size_bytes: u64,
+    /// Modification time of the oldest file
+    pub oldest_file_at: Option<DateTime<Utc>>,
+    /// Modification time of the newest file
+    pub newest_file_at: Option<DateTime<Utc>>,
+    /// Configured retention (e.g. "1day"); absent when files are kept until removed by maintenance
+    pub retention: Option<String>,
+    /// Interval of the automatic cleanup (absent when automatic cleanup is disabled)
+    pub cleanup_interval: Option<String>,
+    pub scanned_at: DateTime<Utc>,
+    /// Usage of the area's quota-limited subdirectories against their quotas
+    pub quotas: Vec<StorageQuotaUsage>,
+}
+
+/// Usage of one quota-limited subdirectory of a storage area
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StorageQuotaUsage {
+    pub directory: String,
+    pub used_bytes: u64,
+    pub used_files: u64,
+    pub max_bytes: Option<u64>,
+    pub max_files: Option<u64>,
+    /// Fraction of the tighter limit in use
+    pub utilization: Option<f64>,
+    /// Writes rejected for exceeding the quota since startup
+    pub rejected_writes: u64,
+    pub scanned_at: Option<DateTime<Utc>>,
+}
+
+impl From<QuotaUsage> for StorageQuotaUsage {
+    fn from(usage: QuotaUsage) -> Self {
+        Self {
+            utilization: usage.utilization(),
+            directory: usage.directory,
+            used_bytes: usage.used_bytes,
+            used_files: usage.used_files,
+            max_bytes: usage.max_bytes,
+            max_files: usage.max_files,
+            rejected_writes: usage.rejected_writes,
+            scanned_at: usage.scanned_at,
+        }
+    }
+}
+
+/// Disk usage of all storage areas
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StorageUsageResponse {
+    pub areas: Vec<StorageAreaUsage>,
+    pub total_files: usize,
+    pub total_size_bytes: u64,
+}
+
+/// Storage usage options
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StorageUsageQuery {
+    /// Rescan now instead of using a scan from the last minute
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Get disk usage per storage area
+#[utoipa::path(
+    get,
+    path = "/storage/usage",
+    tag = "health",
+    summary = "Get storage usage",
+    description = "Total size, file count, oldest/newest file, configured retention and subdirectory quota usage of each storage area (m3u output, pipeline, temp and cached logos). Scans are cached for a minute unless refresh=true.",
+    params(StorageUsageQuery),
+    responses(
+        (status = 200, description = "Storage usage", body = StorageUsageResponse),
+        (status = 500, description = "A storage area could not be scanned")
+    )
+)]
+pub async fn get_storage_usage(
+    State(state): State<AppState>,
+    Query(query): Query<StorageUsageQuery>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/storage/usage".parse().unwrap(),
+        &context,
+    );
+
+    let max_age = if query.refresh {
+        Duration::ZERO
+    } else {
+        USAGE_SCAN_MAX_AGE
+    };
+    let areas = [
+        ("m3u", &state.proxy_output_file_manager),
+        ("pipeline", &state.pipeline_file_manager),
+        ("temp", &state.temp_file_manager),
+        ("cached_logos", &state.logo_file_manager),
+    ];
+
+    let mut response = StorageUsageResponse {
+        areas: Vec::with_capacity(areas.len()),
+        total_files: 0,
+        total_size_bytes: 0,
+    };
+    for (name, manager) in areas {
+        match area_usage(name, manager, max_age).await {
+            Ok(usage) => {
+                response.total_files += usage.total_files;
+                response.total_size_bytes += usage.total_size_bytes;
+                response.areas.push(usage);
+            }
+            Err(e) => {
+                return internal_error(&format!("Failed to scan {name} storage: {e}"))
+                    .into_response();
+            }
+        }
+    }
+
+    ok(response).into_response()
+}
+
+async fn area_usage(
+    name: &str,
+    manager: &SandboxedManager,
+    max_age: Duration,
+) -> Result<StorageAreaUsage, sandboxed_file_manager::SandboxedFileError> {
+    let usage = manager.disk_usage(max_age).await?;
+    let quotas = manager.quota_usage().await?;
+    let policy = manager.cleanup_policy();
+    let cleanup_interval = manager.cleanup_interval();
+
+    Ok(StorageAreaUsage {
+        name: name.to_string(),
+        path: manager.base_directory().display().to_string(),
+        total_files: usage.total_files,
+        total_size_bytes: usage.total_size_bytes,
+        oldest_file_at: usage.oldest_modified,
+        newest_file_at: usage.newest_modified,
+        retention: (!policy.infinite_retention)
+            .then(|| humantime::format_duration(policy.retention_duration).to_string()),
+        cleanup_interval: (!cleanup_interval.is_zero())
+            .then(|| humantime::format_duration(cleanup_interval).to_string()),
+        scanned_at: usage.scanned_at,
+        quotas: quotas.into_iter().map(StorageQuotaUsage::from).collect(),
+    })
+}
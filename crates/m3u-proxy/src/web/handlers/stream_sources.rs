@@ -14,6 +14,7 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    database::repositories::ChannelCountSnapshotSeaOrmRepository,
     models::{StreamSource, StreamSourceType},
     sources::SourceHandlerFactory,
 };
@@ -21,7 +22,7 @@ use crate::{
 use crate::web::{
     AppState,
     extractors::{ListParams, RequestContext, StreamSourceFilterParams},
-    responses::ok,
+    responses::{ok, validation_error},
     utils::{extract_uuid_param, log_request},
 };
 
@@ -144,6 +145,10 @@ pub struct StreamSourceResponse {
     pub is_active: bool,
     pub channel_count: u64,
     pub next_scheduled_update: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set while the latest ingestion dropped more channels than `channel_count_alerts`
+    /// allows; only populated on the detail response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_count_alert: Option<crate::models::channel_count_alert::ChannelCountAlert>,
 }
 
 impl From<StreamSource> for StreamSourceResponse {
@@ -166,6 +171,7 @@ impl From<StreamSource> for StreamSourceResponse {
             is_active: source.is_active,
             channel_count: 0, // Default value, should be set when creating from stats
             next_scheduled_update: None, // Default value, should be set when creating from stats
+            channel_count_alert: None,
         }
     }
 }
@@ -180,6 +186,11 @@ impl From<StreamSource> for StreamSourceResponse {
         ("limit" = Option<u32>, Query, description = "Items per page (1-100)", example = 20),
         ("search" = Option<String>, Query, description = "Search term for name or URL"),
         ("source_type" = Option<String>, Query, description = "Filter by source type: m3u, xtream"),
+        ("enabled" = Option<bool>, Query, description = "Filter by enabled status"),
+        ("offset" = Option<u32>, Query, description = "0-based offset; overrides page"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous next_cursor; overrides offset and page"),
+        ("sort_by" = Option<String>, Query, description = "Sort field: name, created_at, updated_at"),
+        ("sort_ascending" = Option<bool>, Query, description = "Sort direction (default true)"),
     ),
     responses(
         (status = 200, description = "List of stream sources retrieved successfully"),
@@ -211,13 +222,9 @@ pub async fn list_stream_sources(
             }
 
             // Apply filtering if needed
-            if let Some(search) = list_params.search.search {
-                let search_lower = search.to_lowercase();
-                response_items.retain(|item| {
-                    item.name.to_lowercase().contains(&search_lower)
-                        || item.url.to_lowercase().contains(&search_lower)
-                });
-            }
+            list_params.apply_search(&mut response_items, |item, term| {
+                item.name.to_lowercase().contains(term) || item.url.to_lowercase().contains(term)
+            });
 
             if let Some(source_type_str) = filter_params.source_type {
                 response_items.retain(|item| {
@@ -225,29 +232,26 @@ pub async fn list_stream_sources(
                 });
             }
 
-            let total = response_items.len();
-
-            // Apply pagination
-            let page = list_params.pagination.page;
-            let limit = list_params.pagination.limit as usize;
-            let offset = ((page - 1) * limit as u32) as usize;
-
-            let paginated_items = if offset < response_items.len() {
-                response_items
-                    .into_iter()
-                    .skip(offset)
-                    .take(limit)
-                    .collect()
-            } else {
-                Vec::new()
+            if let Some(enabled) = filter_params.enabled {
+                response_items.retain(|item| item.is_active == enabled);
+            }
+
+            let sort_field = match list_params.sort_field(&["name", "created_at", "updated_at"]) {
+                Ok(field) => field,
+                Err(errors) => return validation_error(errors).into_response(),
             };
+            match sort_field {
+                Some("created_at") => response_items
+                    .sort_by(|a, b| list_params.direction(a.created_at.cmp(&b.created_at))),
+                Some("updated_at") => response_items
+                    .sort_by(|a, b| list_params.direction(a.updated_at.cmp(&b.updated_at))),
+                Some("name") => response_items.sort_by(|a, b| {
+                    list_params.direction(a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                }),
+                _ => {}
+            }
 
-            let paginated_response = crate::web::responses::PaginatedResponse::new(
-                paginated_items,
-                total as u64,
-                page,
-                limit as u32,
-            );
+            let paginated_response = list_params.paginate(response_items);
             ok(paginated_response).into_response()
         }
         Err(e) => {
@@ -267,7 +271,7 @@ pub async fn list_stream_sources(
         ("id" = String, Path, description = "Stream source ID (UUID)", example = "550e8400-e29b-41d4-a716-446655440000"),
     ),
     responses(
-        (status = 200, description = "Stream source details retrieved successfully"),
+        (status = 200, description = "Stream source details retrieved successfully, with `channel_count_alert` set while the latest ingestion dropped channels beyond the configured threshold"),
         (status = 400, description = "Invalid UUID format"),
         (status = 404, description = "Stream source not found"),
         (status = 500, description = "Internal server error"),
@@ -291,7 +295,19 @@ pub async fn get_stream_source(
 
     match state.stream_source_service.get_with_details(uuid).await {
         Ok(source_with_details) => {
-            let response = StreamSourceResponse::from(source_with_details.source);
+            let mut response = StreamSourceResponse::from(source_with_details.source);
+            response.channel_count_alert =
+                ChannelCountSnapshotSeaOrmRepository::new(state.database.connection().clone())
+                    .active_alert(&uuid)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::warn!(
+                            "Failed to load channel count alert of stream source {}: {}",
+                            uuid,
+                            e
+                        );
+                        None
+                    });
             ok(response).into_response()
         }
         Err(e) => {
@@ -426,6 +442,7 @@ pub async fn update_stream_source(
     delete,
     path = "/sources/stream/{id}",
     tag = "sources-streams",
+    description = "Move a stream source to the trash. It disappears from listings and proxies until restored via `/trash`, and is purged permanently after the trash retention period",
     params(
         ("id" = String, Path, description = "Stream source ID (UUID)", example = "550e8400-e29b-41d4-a716-446655440000"),
     ),
@@ -718,3 +735,1003 @@ pub async fn refresh_stream_source(
         }
     }
 }
+
+/// Get channel retention of a stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/channel-retention",
+    tag = "sources-streams",
+    summary = "Get channel retention",
+    description = "How many consecutive ingestions channels the source stops listing are retained (and reported as stale) before they are removed",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Channel retention", body = crate::models::channel_retention::ChannelRetention),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_channel_retention(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/channel-retention")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::ChannelRetentionSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.get(&uuid).await {
+        Ok(retention) => ok(retention).into_response(),
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Set channel retention of a stream source
+#[utoipa::path(
+    put,
+    path = "/sources/stream/{id}/channel-retention",
+    tag = "sources-streams",
+    summary = "Set channel retention",
+    description = "Keep channels the source stops listing for up to `max_missed_ingestions` consecutive ingestions, so a provider temporarily dropping channels does not remove them from proxies. Retained channels are marked stale in the channel browser. 0 removes missing channels immediately.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    request_body = crate::models::channel_retention::ChannelRetentionRequest,
+    responses(
+        (status = 200, description = "Channel retention updated", body = crate::models::channel_retention::ChannelRetention),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_channel_retention(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    Json(request): Json<crate::models::channel_retention::ChannelRetentionRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/sources/stream/{id}/channel-retention")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(error) = request.validate() {
+        return crate::web::responses::bad_request(&error).into_response();
+    }
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::ChannelRetentionSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.set(&uuid, request.max_missed_ingestions).await {
+        Ok(retention) => {
+            tracing::info!(
+                "Set channel retention for stream source {} to {} missed ingestions",
+                uuid,
+                retention.max_missed_ingestions
+            );
+            ok(retention).into_response()
+        }
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Get balance group of a stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/balance-group",
+    tag = "sources-streams",
+    summary = "Get balance group",
+    description = "Balance group the source is a line of, if any",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Balance group", body = crate::models::source_balancing::SourceBalanceGroup),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_balance_group(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/balance-group")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::SourceBalanceGroupSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.get(&uuid).await {
+        Ok(group) => ok(group).into_response(),
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Set balance group of a stream source
+#[utoipa::path(
+    put,
+    path = "/sources/stream/{id}/balance-group",
+    tag = "sources-streams",
+    summary = "Set balance group",
+    description = "Declare the source a line of a provider configured several times with different credentials. Channels of any line in the group are streamed from the line with the fewest active connections that is under its max_concurrent_streams, failing over to the next line when the upstream cannot be reached. An empty group name removes the source from its group.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    request_body = crate::models::source_balancing::SourceBalanceGroupRequest,
+    responses(
+        (status = 200, description = "Balance group updated", body = crate::models::source_balancing::SourceBalanceGroup),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_balance_group(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    Json(request): Json<crate::models::source_balancing::SourceBalanceGroupRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/sources/stream/{id}/balance-group")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(error) = request.validate() {
+        return crate::web::responses::bad_request(&error).into_response();
+    }
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::SourceBalanceGroupSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.set(&uuid, request.group_name()).await {
+        Ok(group) => {
+            tracing::info!(
+                "Set balance group for stream source {} to {:?}",
+                uuid,
+                group.group_name
+            );
+            ok(group).into_response()
+        }
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// List balance groups
+#[utoipa::path(
+    get,
+    path = "/sources/stream/balance-groups",
+    tag = "sources-streams",
+    summary = "List balance groups",
+    description = "All balance groups with their lines and the streams currently served from each line",
+    responses(
+        (status = 200, description = "Balance groups", body = Vec<crate::models::source_balancing::BalanceGroupStatus>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_balance_groups(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/sources/stream/balance-groups".parse().unwrap(),
+        &context,
+    );
+
+    let repo = crate::database::repositories::SourceBalanceGroupSeaOrmRepository::new(
+        state.database.read_connection(),
+    );
+    let mut groups = match repo.list_groups().await {
+        Ok(groups) => groups,
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+    let session_counts = state.session_tracker.get_source_session_counts().await;
+    for line in groups.iter_mut().flat_map(|group| group.lines.iter_mut()) {
+        line.active_streams = session_counts.get(&line.source_id).copied().unwrap_or(0);
+    }
+    ok(groups).into_response()
+}
+
+/// Export the channels of a stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/channels/export",
+    tag = "sources-streams",
+    summary = "Export source channel list",
+    description = "Download the raw channels ingested from the source, ordered by channel number then name, as CSV or XLSX. Columns match the proxy channel export: number, name, group, tvg_id, logo_url and source.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+        crate::services::channel_export::ChannelExportQuery
+    ),
+    responses(
+        (status = 200, description = "Channel list file", content_type = ["text/csv", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"]),
+        (status = 400, description = "Invalid ID or format"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn export_stream_source_channels(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<
+        crate::services::channel_export::ChannelExportQuery,
+    >,
+    context: RequestContext,
+) -> impl IntoResponse {
+    use crate::services::channel_export::{ExportRow, export_response};
+
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/channels/export")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    let source_repo = crate::database::repositories::StreamSourceSeaOrmRepository::new(
+        state.database.read_connection(),
+    );
+    let source = match source_repo.find_by_id(&uuid).await {
+        Ok(Some(source)) => source,
+        Ok(None) => return crate::web::responses::not_found("stream_source", &id).into_response(),
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+
+    let channel_repo = crate::database::repositories::ChannelSeaOrmRepository::new(
+        state.database.read_connection(),
+    );
+    let mut channels = match channel_repo.find_by_source_id(&uuid).await {
+        Ok(channels) => channels,
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+    // Numbered channels first, in number order; the rest by name
+    channels.sort_by(|a, b| {
+        let number = |channel: &crate::models::Channel| {
+            channel
+                .tvg_chno
+                .as_deref()
+                .and_then(|chno| chno.trim().parse::<f64>().ok())
+        };
+        match (number(a), number(b)) {
+            (Some(x), Some(y)) => x.total_cmp(&y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        }
+        .then_with(|| a.channel_name.cmp(&b.channel_name))
+    });
+
+    let rows: Vec<ExportRow> = channels
+        .iter()
+        .map(|channel| ExportRow::from_channel(channel, Some(&source.name)))
+        .collect();
+    tracing::info!(
+        "Exporting {} channels of stream source {} as {:?}",
+        rows.len(),
+        uuid,
+        query.format
+    );
+    export_response(query.format, &format!("{}-channels", source.name), &rows)
+}
+
+/// Get channel identity key of a stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/channel-identity",
+    tag = "sources-streams",
+    summary = "Get channel identity key",
+    description = "Which channel fields the source's channel UUIDs are derived from on ingestion",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Channel identity key", body = crate::models::channel_identity::ChannelIdentity),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_channel_identity(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/channel-identity")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::ChannelIdentitySeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.get(&uuid).await {
+        Ok(identity) => ok(identity).into_response(),
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Set channel identity key of a stream source
+#[utoipa::path(
+    put,
+    path = "/sources/stream/{id}/channel-identity",
+    tag = "sources-streams",
+    summary = "Set channel identity key",
+    description = "Derive the source's channel UUIDs from `url_and_name` (default), `tvg_id`, `stream_url_path` (ignoring host and query) or `name_and_group`, so channels keep their UUID — and with it numbering, overrides and EPG mappings — across refreshes of providers that rotate stream URLs. Channels lacking the keyed field or sharing its value keep the default id. Takes effect on the next ingestion; changing the key changes the UUIDs of the source's channels once.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    request_body = crate::models::channel_identity::ChannelIdentityRequest,
+    responses(
+        (status = 200, description = "Channel identity key updated", body = crate::models::channel_identity::ChannelIdentity),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_channel_identity(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    Json(request): Json<crate::models::channel_identity::ChannelIdentityRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/sources/stream/{id}/channel-identity")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::ChannelIdentitySeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.set(&uuid, request.identity_key).await {
+        Ok(identity) => {
+            tracing::info!(
+                "Set channel identity key for stream source {} to {}",
+                uuid,
+                identity.identity_key.as_str()
+            );
+            ok(identity).into_response()
+        }
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Get streaming header overrides of a stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/stream-headers",
+    tag = "sources-streams",
+    summary = "Get streaming headers",
+    description = "User-Agent, Referer and extra headers sent upstream when streaming the source's channels",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Streaming header overrides", body = crate::models::stream_headers::StreamHeaderOverrides),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_stream_headers(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/stream-headers")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::StreamHeadersSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.get(&uuid).await {
+        Ok(overrides) => ok(overrides).into_response(),
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Set streaming header overrides of a stream source
+#[utoipa::path(
+    put,
+    path = "/sources/stream/{id}/stream-headers",
+    tag = "sources-streams",
+    summary = "Set streaming headers",
+    description = "Replace the headers sent upstream when the proxy or relay connects to the source's streams. `user_agent` replaces the proxy's User-Agent; `referer` and `headers` are added to every request. Headers managed by the proxy (Host, Range, Connection, ...) cannot be set. An empty request removes the overrides. Ingestion requests are not affected.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    request_body = crate::models::stream_headers::StreamHeaderOverridesRequest,
+    responses(
+        (status = 200, description = "Streaming headers updated", body = crate::models::stream_headers::StreamHeaderOverrides),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_stream_headers(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    Json(request): Json<crate::models::stream_headers::StreamHeaderOverridesRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/sources/stream/{id}/stream-headers")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    let request = request.normalized();
+    if let Err(error) = request.validate() {
+        return crate::web::responses::bad_request(&error).into_response();
+    }
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::StreamHeadersSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.set(&uuid, request).await {
+        Ok(overrides) => {
+            tracing::info!(
+                "Set streaming headers for stream source {} ({} extra headers, custom user agent: {})",
+                uuid,
+                overrides.headers.len(),
+                overrides.user_agent.is_some()
+            );
+            ok(overrides).into_response()
+        }
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Get the Xtream category ingest filter of a stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/category-filter",
+    tag = "sources-streams",
+    summary = "Get category filter",
+    description = "Xtream categories included or excluded when the source's channels are ingested",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Category ingest filter", body = crate::models::xtream_category_filter::XtreamCategoryFilter),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_category_filter(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/category-filter")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::XtreamCategoryFilterSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.get(&uuid).await {
+        Ok(filter) => ok(filter).into_response(),
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Set the Xtream category ingest filter of a stream source
+#[utoipa::path(
+    put,
+    path = "/sources/stream/{id}/category-filter",
+    tag = "sources-streams",
+    summary = "Set category filter",
+    description = "Replace the categories ingested from an Xtream source. With include rules only matching categories are ingested; exclude rules always win. Patterns are case-insensitive regular expressions on the category name. An empty request ingests every category. Takes effect on the next refresh.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    request_body = crate::models::xtream_category_filter::XtreamCategoryFilterRequest,
+    responses(
+        (status = 200, description = "Category filter updated", body = crate::models::xtream_category_filter::XtreamCategoryFilter),
+        (status = 400, description = "Invalid request or not an Xtream source"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_category_filter(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    Json(request): Json<crate::models::xtream_category_filter::XtreamCategoryFilterRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/sources/stream/{id}/category-filter")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    let request = request.normalized();
+    if let Err(error) = request.validate() {
+        return crate::web::responses::bad_request(&error).into_response();
+    }
+
+    let stream_source_repo = crate::database::repositories::StreamSourceSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match stream_source_repo.find_by_id(&uuid).await {
+        Ok(Some(source)) if source.source_type != crate::models::StreamSourceType::Xtream => {
+            return crate::web::responses::bad_request(
+                "Category filters only apply to Xtream sources",
+            )
+            .into_response();
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return crate::web::responses::not_found("stream_source", &id).into_response();
+        }
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+
+    let repo = crate::database::repositories::XtreamCategoryFilterSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.set(&uuid, request).await {
+        Ok(filter) => {
+            tracing::info!(
+                "Set category filter for stream source {} ({} include, {} exclude rules)",
+                uuid,
+                filter.include_category_ids.len() + filter.include_patterns.len(),
+                filter.exclude_category_ids.len() + filter.exclude_patterns.len()
+            );
+            ok(filter).into_response()
+        }
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Get the mirror URLs of a stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/mirrors",
+    tag = "sources-streams",
+    summary = "Get mirrors",
+    description = "Mirror URLs tried in order when the source's URL fails during a refresh, and the URL that served the last successful refresh",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Source mirrors", body = crate::models::stream_source_mirror::StreamSourceMirrors),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_stream_source_mirrors(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/mirrors")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::StreamSourceMirrorSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.get(&uuid).await {
+        Ok(mirrors) => ok(mirrors).into_response(),
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Set the mirror URLs of a stream source
+#[utoipa::path(
+    put,
+    path = "/sources/stream/{id}/mirrors",
+    tag = "sources-streams",
+    summary = "Set mirrors",
+    description = "Replace the mirror URLs of a source. Mirrors must serve the same playlist or Xtream API as the source URL; when a refresh from the source URL fails, each mirror is tried in order. An empty list removes the mirrors. Takes effect on the next refresh.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    request_body = crate::models::stream_source_mirror::StreamSourceMirrorsRequest,
+    responses(
+        (status = 200, description = "Mirrors updated", body = crate::models::stream_source_mirror::StreamSourceMirrors),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_stream_source_mirrors(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    Json(request): Json<crate::models::stream_source_mirror::StreamSourceMirrorsRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/sources/stream/{id}/mirrors")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    let request = request.normalized();
+    if let Err(error) = request.validate() {
+        return crate::web::responses::bad_request(&error).into_response();
+    }
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    let repo = crate::database::repositories::StreamSourceMirrorSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match repo.set(&uuid, request).await {
+        Ok(mirrors) => {
+            tracing::info!(
+                "Set {} mirror(s) for stream source {}",
+                mirrors.mirror_urls.len(),
+                uuid
+            );
+            ok(mirrors).into_response()
+        }
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// List the live categories offered by an Xtream stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/categories",
+    tag = "sources-streams",
+    summary = "List provider categories",
+    description = "Live categories fetched from the Xtream provider, each flagged with whether the source's category filter ingests it",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Provider categories", body = Vec<crate::models::xtream_category_filter::XtreamCategory>),
+        (status = 400, description = "Invalid ID or not an Xtream source"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Failed to fetch categories from the provider")
+    )
+)]
+pub async fn list_stream_source_categories(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/categories")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+
+    let stream_source_repo = crate::database::repositories::StreamSourceSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    let source = match stream_source_repo.find_by_id(&uuid).await {
+        Ok(Some(source)) => source,
+        Ok(None) => {
+            return crate::web::responses::not_found("stream_source", &id).into_response();
+        }
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+    if source.source_type != crate::models::StreamSourceType::Xtream {
+        return crate::web::responses::bad_request("Only Xtream sources have categories")
+            .into_response();
+    }
+
+    match state
+        .stream_source_service
+        .list_xtream_categories(&source)
+        .await
+    {
+        Ok(categories) => ok(categories).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list categories of stream source {}: {}", uuid, e);
+            crate::web::responses::internal_error(&e.to_string()).into_response()
+        }
+    }
+}
+
+/// List archived playlist snapshots of a stream source
+#[utoipa::path(
+    get,
+    path = "/sources/stream/{id}/snapshots",
+    tag = "sources-streams",
+    summary = "List ingest snapshots",
+    description = "Raw playlists archived by previous ingestions of an M3U source, newest first. Requires `[ingest_archive]` to be enabled.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+    ),
+    responses(
+        (status = 200, description = "Archived snapshots", body = Vec<crate::models::ingest_snapshot::IngestSnapshot>),
+        (status = 400, description = "Invalid ID or ingest archive disabled"),
+        (status = 404, description = "Stream source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_stream_source_snapshots(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/sources/stream/{id}/snapshots")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    let Some(archive) = state.stream_source_service.ingest_archive() else {
+        return crate::web::responses::bad_request("Ingest archive is not enabled").into_response();
+    };
+    if let Err(response) = ensure_stream_source_exists(&state, &uuid, &id).await {
+        return response;
+    }
+
+    match archive
+        .list(
+            crate::models::ingest_snapshot::IngestSnapshotKind::Stream,
+            uuid,
+        )
+        .await
+    {
+        Ok(snapshots) => ok(snapshots).into_response(),
+        Err(e) => crate::web::responses::internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Re-run ingestion of a stream source from an archived playlist
+#[utoipa::path(
+    post,
+    path = "/sources/stream/{id}/snapshots/{snapshot_id}/replay",
+    tag = "sources-streams",
+    summary = "Replay ingest snapshot",
+    description = "Parse an archived playlist with the current parser and replace the source's channels with the result, as a refresh would. Useful for reproducing parsing issues against the exact content that was served.",
+    params(
+        ("id" = String, Path, description = "Stream source ID (UUID)"),
+        ("snapshot_id" = String, Path, description = "Snapshot ID"),
+    ),
+    responses(
+        (status = 200, description = "Snapshot replayed", body = crate::models::ingest_snapshot::IngestSnapshotReplayResult),
+        (status = 400, description = "Invalid ID, ingest archive disabled or not an M3U source"),
+        (status = 404, description = "Stream source or snapshot not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn replay_stream_source_snapshot(
+    State(state): State<AppState>,
+    Path((id, snapshot_id)): Path<(String, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    use crate::models::ingest_snapshot::{IngestSnapshotKind, IngestSnapshotReplayResult};
+
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/sources/stream/{id}/snapshots/{snapshot_id}/replay")
+            .parse()
+            .unwrap(),
+        &context,
+    );
+
+    let uuid = match extract_uuid_param(&id) {
+        Ok(uuid) => uuid,
+        Err(error) => return crate::web::responses::bad_request(&error).into_response(),
+    };
+    let Some(archive) = state.stream_source_service.ingest_archive() else {
+        return crate::web::responses::bad_request("Ingest archive is not enabled").into_response();
+    };
+
+    let stream_source_repo = crate::database::repositories::StreamSourceSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    let source = match stream_source_repo.find_by_id(&uuid).await {
+        Ok(Some(source)) => source,
+        Ok(None) => {
+            return crate::web::responses::not_found("stream_source", &id).into_response();
+        }
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+    if source.source_type != crate::models::StreamSourceType::M3u {
+        return crate::web::responses::bad_request("Only M3U sources have snapshots")
+            .into_response();
+    }
+
+    let snapshot = match archive
+        .get(IngestSnapshotKind::Stream, uuid, &snapshot_id)
+        .await
+    {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => {
+            return crate::web::responses::not_found("snapshot", &snapshot_id).into_response();
+        }
+        Err(e) => return crate::web::responses::internal_error(&e.to_string()).into_response(),
+    };
+
+    match state
+        .stream_source_service
+        .replay_snapshot(&source, &snapshot)
+        .await
+    {
+        Ok(records_saved) => {
+            state
+                .proxy_regeneration_service
+                .queue_affected_proxies_coordinated(uuid, "stream")
+                .await;
+            ok(IngestSnapshotReplayResult {
+                snapshot,
+                records_saved,
+            })
+            .into_response()
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to replay snapshot {} of stream source {}: {}",
+                snapshot_id,
+                uuid,
+                e
+            );
+            crate::web::responses::internal_error(&e.to_string()).into_response()
+        }
+    }
+}
+
+async fn ensure_stream_source_exists(
+    state: &AppState,
+    uuid: &Uuid,
+    id: &str,
+) -> Result<(), axum::response::Response> {
+    let stream_source_repo = crate::database::repositories::StreamSourceSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    match stream_source_repo.find_by_id(uuid).await {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(crate::web::responses::not_found("stream_source", id).into_response()),
+        Err(e) => Err(crate::web::responses::internal_error(&e.to_string()).into_response()),
+    }
+}
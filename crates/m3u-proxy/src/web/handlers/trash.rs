@@ -0,0 +1,155 @@
+//! Trash handlers
+//!
+//! Deleted stream sources, EPG sources, proxies, filters and data mapping rules are kept in
+//! the trash until the retention period (`trash.retention`) has passed. These endpoints list
+//! the trash, restore an item, or purge it permanently ahead of the scheduled purge.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tracing::info;
+
+use crate::database::repositories::TrashSeaOrmRepository;
+use crate::models::trash::{TrashItem, TrashKind};
+use crate::services::CachedEntity;
+use crate::utils::uuid_parser::parse_uuid_flexible;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, internal_error, not_found, ok},
+    utils::log_request,
+};
+
+/// List trashed items
+#[utoipa::path(
+    get,
+    path = "/trash",
+    tag = "trash",
+    summary = "List trash",
+    description = "List deleted stream sources, EPG sources, proxies, filters and data mapping rules that can still be restored, most recently deleted first, with the time each will be purged",
+    responses(
+        (status = 200, description = "Trashed items", body = Vec<TrashItem>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_trash(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/trash".parse().unwrap(),
+        &context,
+    );
+
+    let config = state.config.trash.clone().unwrap_or_default();
+    let retention = match chrono::Duration::from_std(config.retention_duration()) {
+        Ok(retention) => retention,
+        Err(e) => return internal_error(&format!("Invalid trash retention: {e}")).into_response(),
+    };
+    let repo = TrashSeaOrmRepository::new(state.database.read_connection());
+    match repo.list(retention).await {
+        Ok(items) => ok(items).into_response(),
+        Err(e) => internal_error(&format!("Failed to list trash: {e}")).into_response(),
+    }
+}
+
+/// Restore a trashed item
+#[utoipa::path(
+    post,
+    path = "/trash/{kind}/{id}/restore",
+    tag = "trash",
+    summary = "Restore from trash",
+    description = "Take an item out of the trash. It reappears in listings, with its proxy links, and is used by the next generations again.",
+    params(
+        ("kind" = TrashKind, Path, description = "stream-source, epg-source, proxy, filter or data-mapping-rule"),
+        ("id" = String, Path, description = "Item ID"),
+    ),
+    responses(
+        (status = 200, description = "Item restored"),
+        (status = 400, description = "Invalid kind or ID"),
+        (status = 404, description = "Item not in the trash"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn restore_trash_item(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(TrashKind, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &format!("/api/v1/trash/{id}/restore").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match parse_uuid_flexible(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let repo = TrashSeaOrmRepository::new(state.database.connection().clone());
+    match repo.restore(kind, uuid).await {
+        Ok(true) => {
+            info!("Restored {:?} {} from the trash", kind, uuid);
+            let entity = match kind {
+                TrashKind::StreamSource => Some(CachedEntity::StreamSources),
+                TrashKind::EpgSource => Some(CachedEntity::EpgSources),
+                TrashKind::Proxy => Some(CachedEntity::Proxies),
+                TrashKind::Filter | TrashKind::DataMappingRule => None,
+            };
+            if let Some(entity) = entity {
+                let _ = state.cache_invalidation_tx.send(entity);
+            }
+            ok(serde_json::json!({"message": "Item restored"})).into_response()
+        }
+        Ok(false) => not_found("trash item", &id).into_response(),
+        Err(e) => internal_error(&format!("Failed to restore item: {e}")).into_response(),
+    }
+}
+
+/// Permanently delete a trashed item
+#[utoipa::path(
+    delete,
+    path = "/trash/{kind}/{id}",
+    tag = "trash",
+    summary = "Purge from trash",
+    description = "Permanently delete a trashed item now instead of waiting for the retention period. This cascades like a hard delete (e.g. a source's channels and ingestion history) and cannot be undone.",
+    params(
+        ("kind" = TrashKind, Path, description = "stream-source, epg-source, proxy, filter or data-mapping-rule"),
+        ("id" = String, Path, description = "Item ID"),
+    ),
+    responses(
+        (status = 200, description = "Item purged"),
+        (status = 400, description = "Invalid kind or ID"),
+        (status = 404, description = "Item not in the trash"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn purge_trash_item(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(TrashKind, String)>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/trash/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match parse_uuid_flexible(&id) {
+        Ok(uuid) => uuid,
+        Err(e) => return bad_request(&e.to_string()).into_response(),
+    };
+
+    let repo = TrashSeaOrmRepository::new(state.database.connection().clone());
+    match repo.purge(kind, uuid).await {
+        Ok(true) => {
+            info!("Purged {:?} {} from the trash", kind, uuid);
+            ok(serde_json::json!({"message": "Item permanently deleted"})).into_response()
+        }
+        Ok(false) => not_found("trash item", &id).into_response(),
+        Err(e) => internal_error(&format!("Failed to purge item: {e}")).into_response(),
+    }
+}
@@ -0,0 +1,258 @@
+//! URL rewrite rule handlers
+//!
+//! CRUD endpoints for regex rewrites of upstream stream URLs. Changes apply to new stream
+//! requests straight away and to generated playlists from the next regeneration.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::database::repositories::{
+    StreamProxySeaOrmRepository, StreamSourceSeaOrmRepository, UrlRewriteRuleSeaOrmRepository,
+};
+use crate::models::url_rewrite_rule::UrlRewriteRuleRequest;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, created, internal_error, no_content, not_found, ok},
+    utils::log_request,
+};
+
+fn repository(state: &AppState) -> UrlRewriteRuleSeaOrmRepository {
+    UrlRewriteRuleSeaOrmRepository::new(state.database.connection().clone())
+}
+
+/// Check the request and that the source and proxy it is scoped to exist
+async fn validate_request(
+    state: &AppState,
+    request: &UrlRewriteRuleRequest,
+) -> Result<(), axum::response::Response> {
+    request
+        .validate()
+        .map_err(|e| bad_request(&e).into_response())?;
+
+    if let Some(source_id) = request.source_id {
+        let source_repo = StreamSourceSeaOrmRepository::new(state.database.connection().clone());
+        match source_repo.find_by_id(&source_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(
+                    bad_request(&format!("Stream source {source_id} not found")).into_response()
+                );
+            }
+            Err(e) => return Err(internal_error(&e.to_string()).into_response()),
+        }
+    }
+
+    if let Some(proxy_id) = request.proxy_id {
+        let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+        match proxy_repo.find_by_id(&proxy_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(bad_request(&format!("Proxy {proxy_id} not found")).into_response());
+            }
+            Err(e) => return Err(internal_error(&e.to_string()).into_response()),
+        }
+    }
+    Ok(())
+}
+
+/// List URL rewrite rules
+#[utoipa::path(
+    get,
+    path = "/url-rewrite-rules",
+    tag = "url-rewrite-rules",
+    summary = "List URL rewrite rules",
+    description = "List all stream URL rewrite rules in the order they are applied",
+    responses(
+        (status = 200, description = "URL rewrite rules", body = Vec<crate::models::url_rewrite_rule::UrlRewriteRule>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_url_rewrite_rules(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/url-rewrite-rules".parse().unwrap(),
+        &context,
+    );
+
+    match repository(&state).list().await {
+        Ok(rules) => ok(rules).into_response(),
+        Err(e) => internal_error(&format!("Failed to list URL rewrite rules: {e}")).into_response(),
+    }
+}
+
+/// Get a URL rewrite rule
+#[utoipa::path(
+    get,
+    path = "/url-rewrite-rules/{id}",
+    tag = "url-rewrite-rules",
+    summary = "Get URL rewrite rule",
+    params(
+        ("id" = String, Path, description = "URL rewrite rule ID"),
+    ),
+    responses(
+        (status = 200, description = "URL rewrite rule", body = crate::models::url_rewrite_rule::UrlRewriteRule),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "URL rewrite rule not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_url_rewrite_rule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/url-rewrite-rules/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid URL rewrite rule ID").into_response(),
+    };
+
+    match repository(&state).find_by_id(&uuid).await {
+        Ok(Some(rule)) => ok(rule).into_response(),
+        Ok(None) => not_found("URL rewrite rule", &id).into_response(),
+        Err(e) => internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Create a URL rewrite rule
+#[utoipa::path(
+    post,
+    path = "/url-rewrite-rules",
+    tag = "url-rewrite-rules",
+    summary = "Create URL rewrite rule",
+    description = "Create a rule replacing the first match of `pattern` in upstream stream URLs with `replacement`, which may refer to capture groups as `$1` or `${name}` (use `${1}` when a group is followed by letters or digits). Scope it to one stream source and/or one proxy, or leave both unset to rewrite every stream. Rules run in ascending `priority`, each on the previous rule's output, and a rewrite that does not produce an http(s), rtmp(s), rtsp, rtp or udp URL is skipped.",
+    request_body = UrlRewriteRuleRequest,
+    responses(
+        (status = 201, description = "URL rewrite rule created", body = crate::models::url_rewrite_rule::UrlRewriteRule),
+        (status = 400, description = "Invalid request"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_url_rewrite_rule(
+    State(state): State<AppState>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<UrlRewriteRuleRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &"/api/v1/url-rewrite-rules".parse().unwrap(),
+        &context,
+    );
+
+    if let Err(response) = validate_request(&state, &request).await {
+        return response;
+    }
+
+    match repository(&state).create(request).await {
+        Ok(rule) => {
+            info!("Created URL rewrite rule '{}' ({})", rule.name, rule.id);
+            created(rule).into_response()
+        }
+        Err(e) => {
+            internal_error(&format!("Failed to create URL rewrite rule: {e}")).into_response()
+        }
+    }
+}
+
+/// Replace a URL rewrite rule
+#[utoipa::path(
+    put,
+    path = "/url-rewrite-rules/{id}",
+    tag = "url-rewrite-rules",
+    summary = "Update URL rewrite rule",
+    params(
+        ("id" = String, Path, description = "URL rewrite rule ID"),
+    ),
+    request_body = UrlRewriteRuleRequest,
+    responses(
+        (status = 200, description = "URL rewrite rule updated", body = crate::models::url_rewrite_rule::UrlRewriteRule),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "URL rewrite rule not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_url_rewrite_rule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<UrlRewriteRuleRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/url-rewrite-rules/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid URL rewrite rule ID").into_response(),
+    };
+    if let Err(response) = validate_request(&state, &request).await {
+        return response;
+    }
+
+    match repository(&state).update(&uuid, request).await {
+        Ok(Some(rule)) => ok(rule).into_response(),
+        Ok(None) => not_found("URL rewrite rule", &id).into_response(),
+        Err(e) => {
+            internal_error(&format!("Failed to update URL rewrite rule: {e}")).into_response()
+        }
+    }
+}
+
+/// Delete a URL rewrite rule
+#[utoipa::path(
+    delete,
+    path = "/url-rewrite-rules/{id}",
+    tag = "url-rewrite-rules",
+    summary = "Delete URL rewrite rule",
+    params(
+        ("id" = String, Path, description = "URL rewrite rule ID"),
+    ),
+    responses(
+        (status = 204, description = "URL rewrite rule deleted"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "URL rewrite rule not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_url_rewrite_rule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/url-rewrite-rules/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid URL rewrite rule ID").into_response(),
+    };
+
+    match repository(&state).delete(&uuid).await {
+        Ok(true) => {
+            info!("Deleted URL rewrite rule {}", uuid);
+            no_content().into_response()
+        }
+        Ok(false) => not_found("URL rewrite rule", &id).into_response(),
+        Err(e) => {
+            internal_error(&format!("Failed to delete URL rewrite rule: {e}")).into_response()
+        }
+    }
+}
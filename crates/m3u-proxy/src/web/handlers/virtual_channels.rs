@@ -0,0 +1,258 @@
+//! Virtual channel handlers
+//!
+//! CRUD endpoints for user-defined virtual channels. Changes take effect on the next
+//! regeneration of the assigned proxies.
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::database::repositories::{
+    RelaySeaOrmRepository, StreamProxySeaOrmRepository, VirtualChannelSeaOrmRepository,
+};
+use crate::models::virtual_channel::VirtualChannelRequest;
+use crate::web::{
+    AppState,
+    extractors::RequestContext,
+    responses::{bad_request, created, internal_error, no_content, not_found, ok},
+    utils::log_request,
+};
+
+fn repository(state: &AppState) -> VirtualChannelSeaOrmRepository {
+    VirtualChannelSeaOrmRepository::new(state.database.connection().clone())
+}
+
+/// Check the request and that its relay profile and proxies exist
+async fn validate_request(
+    state: &AppState,
+    request: &VirtualChannelRequest,
+) -> Result<(), axum::response::Response> {
+    request
+        .validate()
+        .map_err(|e| bad_request(&e).into_response())?;
+
+    if let Some(profile_id) = request.relay_profile_id {
+        let relay_repo = RelaySeaOrmRepository::new(state.database.connection().clone());
+        match relay_repo.find_by_id(profile_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(
+                    bad_request(&format!("Relay profile {profile_id} not found")).into_response(),
+                );
+            }
+            Err(e) => return Err(internal_error(&e.to_string()).into_response()),
+        }
+    }
+
+    let proxy_repo = StreamProxySeaOrmRepository::new(state.database.connection().clone());
+    for proxy_id in &request.proxy_ids {
+        match proxy_repo.find_by_id(proxy_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(bad_request(&format!("Proxy {proxy_id} not found")).into_response());
+            }
+            Err(e) => return Err(internal_error(&e.to_string()).into_response()),
+        }
+    }
+    Ok(())
+}
+
+/// List virtual channels
+#[utoipa::path(
+    get,
+    path = "/virtual-channels",
+    tag = "virtual-channels",
+    summary = "List virtual channels",
+    description = "List all user-defined virtual channels with the proxies they are injected into",
+    responses(
+        (status = 200, description = "Virtual channels", body = Vec<crate::models::virtual_channel::VirtualChannel>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_virtual_channels(
+    State(state): State<AppState>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &"/api/v1/virtual-channels".parse().unwrap(),
+        &context,
+    );
+
+    match repository(&state).list().await {
+        Ok(channels) => ok(channels).into_response(),
+        Err(e) => internal_error(&format!("Failed to list virtual channels: {e}")).into_response(),
+    }
+}
+
+/// Get a virtual channel
+#[utoipa::path(
+    get,
+    path = "/virtual-channels/{id}",
+    tag = "virtual-channels",
+    summary = "Get virtual channel",
+    params(
+        ("id" = String, Path, description = "Virtual channel ID"),
+    ),
+    responses(
+        (status = 200, description = "Virtual channel", body = crate::models::virtual_channel::VirtualChannel),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Virtual channel not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_virtual_channel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::GET,
+        &format!("/api/v1/virtual-channels/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid virtual channel ID").into_response(),
+    };
+
+    match repository(&state).find_by_id(&uuid).await {
+        Ok(Some(channel)) => ok(channel).into_response(),
+        Ok(None) => not_found("virtual channel", &id).into_response(),
+        Err(e) => internal_error(&e.to_string()).into_response(),
+    }
+}
+
+/// Create a virtual channel
+#[utoipa::path(
+    post,
+    path = "/virtual-channels",
+    tag = "virtual-channels",
+    summary = "Create virtual channel",
+    description = "Create a channel with a fixed name, logo and stream URL (e.g. a weather cam, a local HDHomeRun tuner or a \"channel offline\" loop). It is injected into the listed proxies at their next regeneration, after the last channel of `group_title`, claiming `channel_number` when set. With a `relay_profile_id` the channel is always served through that relay profile.",
+    request_body = VirtualChannelRequest,
+    responses(
+        (status = 201, description = "Virtual channel created", body = crate::models::virtual_channel::VirtualChannel),
+        (status = 400, description = "Invalid request"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_virtual_channel(
+    State(state): State<AppState>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<VirtualChannelRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::POST,
+        &"/api/v1/virtual-channels".parse().unwrap(),
+        &context,
+    );
+
+    if let Err(response) = validate_request(&state, &request).await {
+        return response;
+    }
+
+    match repository(&state).create(request).await {
+        Ok(channel) => {
+            info!(
+                "Created virtual channel '{}' ({}) for {} proxies",
+                channel.name,
+                channel.id,
+                channel.proxy_ids.len()
+            );
+            created(channel).into_response()
+        }
+        Err(e) => internal_error(&format!("Failed to create virtual channel: {e}")).into_response(),
+    }
+}
+
+/// Replace a virtual channel
+#[utoipa::path(
+    put,
+    path = "/virtual-channels/{id}",
+    tag = "virtual-channels",
+    summary = "Update virtual channel",
+    description = "Replace a virtual channel, including its proxy assignments",
+    params(
+        ("id" = String, Path, description = "Virtual channel ID"),
+    ),
+    request_body = VirtualChannelRequest,
+    responses(
+        (status = 200, description = "Virtual channel updated", body = crate::models::virtual_channel::VirtualChannel),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Virtual channel not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_virtual_channel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+    axum::Json(request): axum::Json<VirtualChannelRequest>,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::PUT,
+        &format!("/api/v1/virtual-channels/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid virtual channel ID").into_response(),
+    };
+    if let Err(response) = validate_request(&state, &request).await {
+        return response;
+    }
+
+    match repository(&state).update(&uuid, request).await {
+        Ok(Some(channel)) => ok(channel).into_response(),
+        Ok(None) => not_found("virtual channel", &id).into_response(),
+        Err(e) => internal_error(&format!("Failed to update virtual channel: {e}")).into_response(),
+    }
+}
+
+/// Delete a virtual channel
+#[utoipa::path(
+    delete,
+    path = "/virtual-channels/{id}",
+    tag = "virtual-channels",
+    summary = "Delete virtual channel",
+    params(
+        ("id" = String, Path, description = "Virtual channel ID"),
+    ),
+    responses(
+        (status = 204, description = "Virtual channel deleted"),
+        (status = 400, description = "Invalid ID"),
+        (status = 404, description = "Virtual channel not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_virtual_channel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    context: RequestContext,
+) -> impl IntoResponse {
+    log_request(
+        &axum::http::Method::DELETE,
+        &format!("/api/v1/virtual-channels/{id}").parse().unwrap(),
+        &context,
+    );
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(uuid) => uuid,
+        Err(_) => return bad_request("Invalid virtual channel ID").into_response(),
+    };
+
+    match repository(&state).delete(&uuid).await {
+        Ok(true) => {
+            info!("Deleted virtual channel {}", uuid);
+            no_content().into_response()
+        }
+        Ok(false) => not_found("virtual channel", &id).into_response(),
+        Err(e) => internal_error(&format!("Failed to delete virtual channel: {e}")).into_response(),
+    }
+}
@@ -0,0 +1,200 @@
+//! Localisation of API error messages
+//!
+//! Error responses carry a stable `code` and the `params` of their message next to the
+//! English `error` text, so clients can localise errors without matching strings. The
+//! server localises them too: message catalogs in `locales/errors/*.json` map each code to
+//! a template, and the language is negotiated from the request's `Accept-Language` header
+//! (see [`crate::web::middleware::localize_errors_middleware`]).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+use utoipa::ToSchema;
+
+/// Language of the messages built by the handlers
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Stable identifier of an API error, independent of its message text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    ValidationFailed,
+    NotFound,
+    PermissionDenied,
+    Conflict,
+    PayloadTooLarge,
+    OperationInProgress,
+    ConfigurationError,
+    ExternalServiceError,
+    ExternalServiceUnavailable,
+    DatabaseError,
+    DataAccessError,
+    SourceError,
+    RequestFailed,
+    InternalError,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BadRequest => "bad_request",
+            Self::ValidationFailed => "validation_failed",
+            Self::NotFound => "not_found",
+            Self::PermissionDenied => "permission_denied",
+            Self::Conflict => "conflict",
+            Self::PayloadTooLarge => "payload_too_large",
+            Self::OperationInProgress => "operation_in_progress",
+            Self::ConfigurationError => "configuration_error",
+            Self::ExternalServiceError => "external_service_error",
+            Self::ExternalServiceUnavailable => "external_service_unavailable",
+            Self::DatabaseError => "database_error",
+            Self::DataAccessError => "data_access_error",
+            Self::SourceError => "source_error",
+            Self::RequestFailed => "request_failed",
+            Self::InternalError => "internal_error",
+        }
+    }
+}
+
+/// Message catalogs by language, each mapping error codes to message templates
+static CATALOGS: LazyLock<HashMap<&'static str, HashMap<String, String>>> = LazyLock::new(|| {
+    [
+        ("en", include_str!("../../locales/errors/en.json")),
+        ("de", include_str!("../../locales/errors/de.json")),
+        ("es", include_str!("../../locales/errors/es.json")),
+        ("fr", include_str!("../../locales/errors/fr.json")),
+    ]
+    .into_iter()
+    .map(|(language, json)| {
+        let catalog = serde_json::from_str(json)
+            .unwrap_or_else(|e| panic!("invalid {language} error catalog: {e}"));
+        (language, catalog)
+    })
+    .collect()
+});
+
+/// Catalog language best matching an `Accept-Language` header
+///
+/// Ranges are taken by quality, and a regional range such as `de-AT` matches its primary
+/// language. Returns `None` when no catalog language is acceptable.
+pub fn negotiate_language(accept_language: &str) -> Option<&'static str> {
+    let mut ranges: Vec<(f32, String)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim().to_ascii_lowercase();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!tag.is_empty() && quality > 0.0).then_some((quality, tag))
+        })
+        .collect();
+    // Stable, so equally weighted ranges keep the client's order
+    ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    ranges.into_iter().find_map(|(_, tag)| {
+        if tag == "*" {
+            return Some(DEFAULT_LANGUAGE);
+        }
+        let primary = tag.split('-').next().unwrap_or(&tag);
+        CATALOGS
+            .get_key_value(primary)
+            .map(|(language, _)| *language)
+    })
+}
+
+/// Message of an error code in a catalog language, with its params filled in
+pub fn translate(language: &str, code: &str, params: &BTreeMap<String, String>) -> Option<String> {
+    let mut rest = CATALOGS.get(language)?.get(code)?.as_str();
+
+    // One pass, so placeholders appearing in param values are left alone
+    let mut message = String::with_capacity(rest.len());
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest.find('}') {
+            Some(end) => {
+                match params.get(&rest[1..end]) {
+                    Some(value) => message.push_str(value),
+                    None => message.push_str(&rest[..=end]),
+                }
+                rest = &rest[end + 1..];
+            }
+            None => break,
+        }
+    }
+    message.push_str(rest);
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_CODES: [ErrorCode; 15] = [
+        ErrorCode::BadRequest,
+        ErrorCode::ValidationFailed,
+        ErrorCode::NotFound,
+        ErrorCode::PermissionDenied,
+        ErrorCode::Conflict,
+        ErrorCode::PayloadTooLarge,
+        ErrorCode::OperationInProgress,
+        ErrorCode::ConfigurationError,
+        ErrorCode::ExternalServiceError,
+        ErrorCode::ExternalServiceUnavailable,
+        ErrorCode::DatabaseError,
+        ErrorCode::DataAccessError,
+        ErrorCode::SourceError,
+        ErrorCode::RequestFailed,
+        ErrorCode::InternalError,
+    ];
+
+    fn placeholders(template: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = template
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_catalogs_cover_every_code() {
+        let english = &CATALOGS[DEFAULT_LANGUAGE];
+        assert_eq!(english.len(), ALL_CODES.len());
+        for (language, catalog) in CATALOGS.iter() {
+            for code in ALL_CODES {
+                let template = catalog
+                    .get(code.as_str())
+                    .unwrap_or_else(|| panic!("{language} catalog lacks {}", code.as_str()));
+                assert_eq!(
+                    placeholders(template),
+                    placeholders(&english[code.as_str()]),
+                    "{language} {} placeholders",
+                    code.as_str()
+                );
+            }
+            assert_eq!(catalog.len(), english.len(), "{language} has unknown codes");
+        }
+    }
+
+    #[test]
+    fn test_negotiate_and_translate() {
+        assert_eq!(negotiate_language("de-AT,de;q=0.9,en;q=0.8"), Some("de"));
+        assert_eq!(negotiate_language("ja, fr;q=0.5, en;q=0.7"), Some("en"));
+        assert_eq!(negotiate_language("es;q=0, *;q=0.1"), Some("en"));
+        assert_eq!(negotiate_language("ja"), None);
+
+        let params = BTreeMap::from([
+            ("resource".to_string(), "Proxy".to_string()),
+            ("id".to_string(), "{resource}".to_string()),
+        ]);
+        assert_eq!(
+            translate("de", "not_found", &params).as_deref(),
+            Some("Proxy mit der ID '{resource}' wurde nicht gefunden")
+        );
+        assert_eq!(translate("de", "unknown_code", &params), None);
+    }
+}
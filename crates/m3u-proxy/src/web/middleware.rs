@@ -7,14 +7,16 @@ use axum::{
     Json,
     body::Body,
     extract::Request,
-    http::{HeaderMap, Method, StatusCode, Uri},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, Uri, header},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use std::time::Instant;
 use tracing::{info, warn};
 
-use super::responses::ApiResponse;
+use super::i18n;
+use super::responses::{ApiResponse, generate_etag_bytes};
+use crate::utils::forwarded::ClientAddress;
 
 /// Request logging middleware
 ///
@@ -165,6 +167,73 @@ pub async fn security_headers_middleware(request: Request, next: Next) -> Respon
     response
 }
 
+/// Error localisation middleware
+///
+/// Rewrites the message of coded JSON error responses in the language negotiated from the
+/// request's `Accept-Language` header. Responses stay English when no catalog language is
+/// acceptable or English is preferred.
+pub async fn localize_errors_middleware(request: Request, next: Next) -> Response {
+    /// Error bodies are small; anything larger is passed through untouched
+    const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+    let language = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(i18n::negotiate_language);
+    let mut response = next.run(request).await;
+
+    let is_json_error = (response.status().is_client_error()
+        || response.status().is_server_error())
+        && response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if !is_json_error {
+        return response;
+    }
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept-language"));
+    let Some(language) = language.filter(|language| *language != i18n::DEFAULT_LANGUAGE) else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Failed to buffer error response for localisation: {}", e);
+            return (parts.status, "Failed to read error response").into_response();
+        }
+    };
+    let Ok(mut payload) = serde_json::from_slice::<ApiResponse<serde_json::Value>>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    let Some(message) = payload.code.and_then(|code| {
+        i18n::translate(
+            language,
+            code.as_str(),
+            payload.params.as_ref().unwrap_or(&Default::default()),
+        )
+    }) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    payload.error = Some(message);
+    let Ok(localized) = serde_json::to_vec(&payload) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    if let Ok(etag) = HeaderValue::from_str(&generate_etag_bytes(&localized)) {
+        parts.headers.insert(header::ETAG, etag);
+    }
+    parts
+        .headers
+        .insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(language));
+    Response::from_parts(parts, Body::from(localized))
+}
+
 /// Request timeout middleware
 ///
 /// Ensures requests don't run indefinitely
@@ -270,3 +339,351 @@ impl MiddlewareStack {
         }
     }
 }
+
+/// Forwarded headers middleware
+///
+/// Resolves the client address and external base URL once per request, honouring
+/// `X-Forwarded-*` only from trusted proxies, and stores them as a [`ClientAddress`]
+/// request extension for the access rules, session tracking and URL generation.
+pub async fn forwarded_headers_middleware(
+    axum::extract::State(state): axum::extract::State<crate::web::AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        .map(|info| info.0.ip());
+    let client = state.forwarded_headers.resolve(request.headers(), peer);
+    request.extensions_mut().insert(client);
+    next.run(request).await
+}
+
+/// Per-proxy access control middleware
+///
+/// Applies the `access_control` rules of the proxy named in the path to playlist, XMLTV
+/// and stream requests. Every decision is logged and counted; denied clients get a 403.
+/// Share link and direct channel routes name no proxy and check through [`access_denied`].
+pub async fn access_control_middleware(
+    axum::extract::State(state): axum::extract::State<crate::web::AppState>,
+    uri: Uri,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(proxy_id) = proxy_id_from_path(uri.path()) else {
+        return next.run(request).await;
+    };
+
+    let client_ip = request
+        .extensions()
+        .get::<ClientAddress>()
+        .and_then(|client| client.ip);
+    match access_denied(
+        &state.access_policy,
+        &state.observability.access_decisions,
+        &proxy_id,
+        client_ip,
+        uri.path(),
+    ) {
+        Some(response) => response,
+        None => next.run(request).await,
+    }
+}
+
+/// Check a client against a proxy's access rules, logging and counting the decision
+///
+/// Returns the 403 response when the client is denied; proxies without rules allow everyone.
+/// `route` is logged with denials and must not carry secrets such as share tokens.
+pub(crate) fn access_denied(
+    policy: &crate::utils::access_control::AccessPolicy,
+    decisions: &opentelemetry::metrics::Counter<u64>,
+    proxy_id: &uuid::Uuid,
+    client_ip: Option<std::net::IpAddr>,
+    route: &str,
+) -> Option<Response> {
+    let (decision, country) = policy.check(proxy_id, client_ip)?;
+
+    let outcome = if decision.is_allowed() {
+        "allow"
+    } else {
+        "deny"
+    };
+    decisions.add(
+        1,
+        &[
+            opentelemetry::KeyValue::new("proxy_id", proxy_id.to_string()),
+            opentelemetry::KeyValue::new("decision", outcome),
+            opentelemetry::KeyValue::new("reason", decision.reason().as_str()),
+        ],
+    );
+
+    if decision.is_allowed() {
+        tracing::debug!(
+            proxy_id = %proxy_id,
+            client_ip = ?client_ip,
+            country = ?country,
+            reason = decision.reason().as_str(),
+            "Access allowed"
+        );
+        return None;
+    }
+
+    warn!(
+        proxy_id = %proxy_id,
+        client_ip = ?client_ip,
+        country = ?country,
+        reason = decision.reason().as_str(),
+        route = %route,
+        "Access denied"
+    );
+    Some(
+        (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error("Access denied".to_string())),
+        )
+            .into_response(),
+    )
+}
+
+/// User a proxy's Basic Auth authenticated the request as
+///
+/// Added to the request extensions by [`proxy_basic_auth_middleware`].
+#[derive(Debug, Clone)]
+pub struct ProxyBasicAuthUser(pub String);
+
+/// HTTP Basic Auth on proxy playlist and guide endpoints
+///
+/// Proxies without credentials are served as before. Failed attempts get a 401 challenge,
+/// so clients holding `user:pass@` URLs resend with credentials.
+pub async fn proxy_basic_auth_middleware(
+    axum::extract::State(state): axum::extract::State<crate::web::AppState>,
+    uri: Uri,
+    headers: HeaderMap,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(proxy_id) = proxy_id_from_path(uri.path()) else {
+        return next.run(request).await;
+    };
+
+    let repo = crate::database::repositories::ProxyBasicAuthSeaOrmRepository::new(
+        state.database.connection().clone(),
+    );
+    let credentials = match repo.get(&proxy_id).await {
+        Ok(Some(credentials)) => credentials,
+        Ok(None) => return next.run(request).await,
+        Err(e) => {
+            warn!("Failed to load Basic Auth of proxy {}: {}", proxy_id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    if let Some(username) = verify_proxy_basic_auth(&credentials, &headers).await {
+        request
+            .extensions_mut()
+            .insert(ProxyBasicAuthUser(username));
+        return next.run(request).await;
+    }
+
+    if headers.contains_key(axum::http::header::AUTHORIZATION) {
+        warn!(proxy_id = %proxy_id, uri = %uri, "Basic Auth rejected");
+    }
+    (
+        StatusCode::UNAUTHORIZED,
+        [(
+            axum::http::header::WWW_AUTHENTICATE,
+            "Basic realm=\"m3u-proxy\", charset=\"UTF-8\"",
+        )],
+    )
+        .into_response()
+}
+
+/// Username of a `Basic` Authorization header matching a proxy's credentials
+pub(crate) async fn verify_proxy_basic_auth(
+    credentials: &crate::models::proxy_basic_auth::ProxyBasicAuth,
+    headers: &HeaderMap,
+) -> Option<String> {
+    let (username, password) = basic_credentials(headers)?;
+    if username != credentials.username {
+        return None;
+    }
+    let password_hash = credentials.password_hash.clone();
+    // Hashing is deliberately slow, so keep it off the async workers
+    let verified = tokio::task::spawn_blocking(move || {
+        crate::utils::password_hash::verify_password(&password, &password_hash)
+    })
+    .await
+    .unwrap_or(false);
+    verified.then_some(username)
+}
+
+/// Username and password of a `Basic` Authorization header
+fn basic_credentials(headers: &HeaderMap) -> Option<(String, String)> {
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+    let value = headers
+        .get(axum::http::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?;
+    let (scheme, encoded) = value.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("basic") {
+        return None;
+    }
+    let decoded = String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    Some((username.to_string(), password.to_string()))
+}
+
+/// Proxy id of `/proxy/{id}/...` and `/stream/{id}/...` paths
+fn proxy_id_from_path(path: &str) -> Option<uuid::Uuid> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    match segments.next()? {
+        "proxy" | "stream" => crate::utils::resolve_proxy_id(segments.next()?).ok(),
+        _ => None,
+    }
+}
+
+/// Admin API authentication middleware
+///
+/// With `auth.enabled`, every request needs an API token, an OpenID Connect access token
+/// or a login session; the caller's [`Principal`](crate::services::Principal) is added to
+/// the request extensions. Viewers may only read. Disabled, requests pass through untouched.
+pub async fn admin_auth_middleware(
+    axum::extract::State(state): axum::extract::State<crate::web::AppState>,
+    method: Method,
+    uri: Uri,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if !state.admin_auth.is_enabled() {
+        return next.run(request).await;
+    }
+
+    let principal = match state.admin_auth.authenticate(request.headers()).await {
+        Ok(principal) => principal,
+        Err(e) => {
+            if e != crate::services::auth::AuthError::Missing {
+                warn!(uri = %uri, "Admin API authentication failed: {}", e);
+            }
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer realm=\"m3u-proxy\"")],
+                Json(ApiResponse::<()>::error(format!("Unauthorized: {e}"))),
+            )
+                .into_response();
+        }
+    };
+
+    if !principal.may(&method) {
+        warn!(user = %principal.name, method = %method, uri = %uri, "Viewer attempted a change");
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "This account has read-only access".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
+
+/// Maintenance mode middleware
+///
+/// While maintenance mode is on, streaming and regeneration requests get a 503 with
+/// `Retry-After`; everything else, including the admin API, passes through.
+pub async fn maintenance_middleware(
+    axum::extract::State(state): axum::extract::State<crate::web::AppState>,
+    method: Method,
+    uri: Uri,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(maintenance) = state.runtime_settings_store.maintenance().await else {
+        return next.run(request).await;
+    };
+    if !is_blocked_during_maintenance(&method, uri.path()) {
+        return next.run(request).await;
+    }
+
+    info!(method = %method, uri = %uri, "Request rejected during maintenance");
+    let message = maintenance
+        .message
+        .unwrap_or_else(|| "Server is under maintenance".to_string());
+    let mut response = (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ApiResponse::<()>::error(message)),
+    )
+        .into_response();
+    response.headers_mut().insert(
+        axum::http::header::RETRY_AFTER,
+        axum::http::HeaderValue::from(maintenance.retry_after_seconds),
+    );
+    response
+}
+
+/// Streaming and regeneration endpoints
+fn is_blocked_during_maintenance(method: &Method, path: &str) -> bool {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["stream", ..] | ["channel", _, "stream"] | ["share", _, "stream", ..] => true,
+        ["api", "v1", "proxies", _, "regenerate"] => method == Method::POST,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maintenance_blocks_streaming_and_regeneration_only() {
+        assert!(is_blocked_during_maintenance(
+            &Method::GET,
+            "/stream/abc/def"
+        ));
+        assert!(is_blocked_during_maintenance(
+            &Method::GET,
+            "/channel/abc/stream"
+        ));
+        assert!(is_blocked_during_maintenance(
+            &Method::GET,
+            "/share/token/stream/abc"
+        ));
+        assert!(is_blocked_during_maintenance(
+            &Method::POST,
+            "/api/v1/proxies/abc/regenerate"
+        ));
+        assert!(!is_blocked_during_maintenance(
+            &Method::GET,
+            "/api/v1/proxies/abc"
+        ));
+        assert!(!is_blocked_during_maintenance(
+            &Method::GET,
+            "/proxy/abc/m3u8"
+        ));
+        assert!(!is_blocked_during_maintenance(
+            &Method::PUT,
+            "/api/v1/maintenance"
+        ));
+    }
+
+    #[test]
+    fn test_basic_credentials() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(basic_credentials(&headers), None);
+
+        // "tv:pa:ss" - only the first colon separates the username
+        headers.insert("authorization", "Basic dHY6cGE6c3M=".parse().unwrap());
+        assert_eq!(
+            basic_credentials(&headers),
+            Some(("tv".to_string(), "pa:ss".to_string()))
+        );
+
+        headers.insert("authorization", "Bearer dHY6cGE6c3M=".parse().unwrap());
+        assert_eq!(basic_credentials(&headers), None);
+        headers.insert("authorization", "Basic not-base64!".parse().unwrap());
+        assert_eq!(basic_credentials(&headers), None);
+    }
+}
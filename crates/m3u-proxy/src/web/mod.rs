@@ -25,7 +25,8 @@
 use anyhow::Result;
 use axum::{
     Router,
-    routing::{delete, get, post, put},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, patch, post, put},
 };
 use axum_tracing_opentelemetry::middleware::OtelAxumLayer;
 use std::collections::HashSet;
@@ -57,10 +58,14 @@ use tokio::sync::mpsc;
 
 pub mod api;
 pub mod extractors;
+pub mod file_serving;
 pub mod handlers;
+pub mod i18n;
 pub mod middleware;
 pub mod openapi;
 pub mod responses;
+pub mod tls;
+pub mod uploads;
 pub mod utils;
 
 // Re-export commonly used types
@@ -74,6 +79,7 @@ pub use responses::{
 pub struct WebServer {
     app: Router,
     addr: SocketAddr,
+    tls: Option<crate::config::WebTlsConfig>,
 }
 
 /// Builder for WebServer with many dependencies
@@ -100,6 +106,7 @@ pub struct WebServerBuilder {
     pub log_broadcaster: broadcast::Sender<crate::web::api::log_streaming::LogEvent>,
     pub runtime_settings_store: Arc<RuntimeSettingsStore>,
     pub circuit_breaker_manager: Option<std::sync::Arc<crate::services::CircuitBreakerManager>>,
+    pub http_client_factory: crate::utils::HttpClientFactory,
     pub observability: Arc<AppObservability>,
     pub job_scheduler: Arc<JobScheduler>,
     pub job_queue: Arc<JobQueue>,
@@ -107,6 +114,8 @@ pub struct WebServerBuilder {
     pub logo_cache_service: Arc<crate::services::logo_cache::LogoCacheService>,
     pub logo_cache_maintenance_service:
         Arc<crate::services::logo_cache_maintenance::LogoCacheMaintenanceService>,
+    pub mqtt_publisher: crate::services::MqttPublisher,
+    pub query_cache: Arc<crate::services::QueryCache>,
 }
 
 impl WebServerBuilder {
@@ -154,6 +163,39 @@ impl WebServer {
 
         let log_broadcaster = Some(builder.log_broadcaster.clone());
 
+        let channel_preview_service = Arc::new(crate::services::ChannelPreviewService::new(
+            builder.relay_manager.clone(),
+            builder.logos_cached_file_manager.clone(),
+            builder.config.channel_preview.clone().unwrap_or_default(),
+        ));
+
+        // Browsers only send Secure cookies over HTTPS, so set the flag only when it is in use
+        let secure_cookies =
+            builder.config.web.tls.is_some() || builder.config.web.base_url.starts_with("https://");
+
+        // Proxy access rules are stored per proxy and cached; a follower also picks up rules
+        // changed through another node's API
+        let access_policy = Arc::new(crate::utils::access_control::AccessPolicy::from_config(
+            builder.config.access_control.as_ref(),
+        ));
+        let proxy_settings_repo = crate::database::repositories::ProxySettingsSeaOrmRepository::new(
+            builder.database.connection().clone(),
+        );
+        access_policy.reload(&proxy_settings_repo).await?;
+        if let Some(cluster) = builder.config.cluster.as_ref().filter(|c| c.enabled) {
+            let access_policy = access_policy.clone();
+            let mut ticker = tokio::time::interval(cluster.check_interval_duration());
+            tokio::spawn(async move {
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = access_policy.reload(&proxy_settings_repo).await {
+                        tracing::warn!("Failed to reload proxy access rules: {e}");
+                    }
+                }
+            });
+        }
+
         let app = Self::create_router(AppState {
             database: builder.database.clone(),
             config: builder.config.clone(),
@@ -167,6 +209,7 @@ impl WebServer {
             logo_file_manager: builder.logos_cached_file_manager,
             proxy_output_file_manager: builder.proxy_output_file_manager,
             temp_file_manager: builder.temp_file_manager,
+            pipeline_file_manager: builder.pipeline_file_manager.clone(),
             observability: builder.observability.clone(),
             scheduler_event_tx: None,
             job_scheduler: builder.job_scheduler,
@@ -174,7 +217,9 @@ impl WebServer {
             job_queue_runner: builder.job_queue_runner,
             // logo_cache_scanner removed - replaced by logo_cache_service
             session_tracker: std::sync::Arc::new(
-                crate::proxy::session_tracker::SessionTracker::default(),
+                crate::proxy::session_tracker::SessionTracker::with_event_publisher(
+                    builder.mqtt_publisher,
+                ),
             ),
             relay_manager: builder.relay_manager.clone(),
             relay_config_resolver: builder.relay_config_resolver,
@@ -192,13 +237,43 @@ impl WebServer {
             logo_cache_service: builder.logo_cache_service,
             logo_cache_maintenance_service: builder.logo_cache_maintenance_service,
             probe_persistence_service: builder.relay_manager.probe_persistence.clone(),
+            channel_diagnostics_service: builder.relay_manager.probe_persistence.clone().map(
+                |persistence| {
+                    Arc::new(crate::services::ChannelDiagnosticsService::new(
+                        persistence,
+                        builder.http_client_factory.clone(),
+                        builder.config.channel_probe.clone().unwrap_or_default(),
+                    ))
+                },
+            ),
+            channel_preview_service,
+            access_policy,
+            forwarded_headers: Arc::new(crate::utils::forwarded::ForwardedHeaders::from_config(
+                builder.config.reverse_proxy.as_ref(),
+                builder.config.access_control.as_ref(),
+            )),
+            query_cache: builder.query_cache,
+            stream_latency: Arc::new(
+                crate::proxy::stream_latency::StreamLatencyMonitor::new(
+                    &builder.config.stream_latency.clone().unwrap_or_default(),
+                )
+                .with_observability(builder.observability.clone()),
+            ),
+            admin_auth: Arc::new(crate::services::AdminAuth::from_config(
+                builder.config.auth.as_ref(),
+                secure_cookies,
+            )),
         })
         .await;
 
         let addr: SocketAddr =
             format!("{}:{}", builder.config.web.host, builder.config.web.port).parse()?;
 
-        Ok(Self { app, addr })
+        Ok(Self {
+            app,
+            addr,
+            tls: builder.config.web.tls.clone(),
+        })
     }
 
     /// Create the router with all routes and middleware
@@ -208,27 +283,46 @@ impl WebServer {
             .route("/health", get(handlers::health::health_check))
             .route("/ready", get(handlers::health::readiness_check))
             .route("/live", get(handlers::health::liveness_check))
-            .route("/debug/logo-cache", get(handlers::health::logo_cache_debug))
+            // Component health and debug endpoints expose internals, so they need auth
+            .merge(
+                Router::new()
+                    .route("/health/deep", get(handlers::health::deep_health_check))
+                    .route("/debug/logo-cache", get(handlers::health::logo_cache_debug))
+                    .route_layer(axum::middleware::from_fn_with_state(
+                        state.clone(),
+                        middleware::admin_auth_middleware,
+                    )),
+            )
             // OpenAPI documentation
             .merge(Self::openapi_routes())
-            // API v1 routes
-            .nest("/api/v1", Self::api_v1_routes())
+            // Login endpoints, reachable without a session
+            .nest("/api/v1/auth", Self::auth_routes())
+            // API v1 routes, behind authentication when enabled
+            .nest(
+                "/api/v1",
+                Self::api_v1_routes().route_layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    middleware::admin_auth_middleware,
+                )),
+            )
             // Proxy/Streaming endpoints (non-API content serving)
+            .merge(Self::proxy_content_routes(state.clone()))
             .route(
-                "/proxy/{ulid}/m3u8",
-                get(handlers::proxies::serve_proxy_m3u),
+                "/channel/{channel_id}/stream",
+                get(handlers::channels::proxy_channel_stream),
             )
+            // Temporary share links
             .route(
-                "/proxy/{ulid}/xmltv",
-                get(handlers::proxies::serve_proxy_xmltv),
+                "/share/{token}/m3u8",
+                get(handlers::share_links::serve_shared_m3u),
             )
             .route(
-                "/stream/{proxy_ulid}/{channel_id}",
-                get(handlers::proxies::proxy_stream),
+                "/share/{token}/xmltv",
+                get(handlers::share_links::serve_shared_xmltv),
             )
             .route(
-                "/channel/{channel_id}/stream",
-                get(handlers::channels::proxy_channel_stream),
+                "/share/{token}/stream/{channel_id}",
+                get(handlers::share_links::shared_stream),
             )
             // .route("/logos/{logo_id}", get(handlers::static_assets::serve_logo))
             // Root route for basic index page
@@ -246,15 +340,61 @@ impl WebServer {
             .layer(axum::middleware::from_fn(
                 middleware::security_headers_middleware,
             ))
+            // Localise error messages per Accept-Language
+            .layer(axum::middleware::from_fn(
+                middleware::localize_errors_middleware,
+            ))
+            // Maintenance mode: reject streaming and regeneration with 503
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                middleware::maintenance_middleware,
+            ))
             // Conditional request logging middleware (respects runtime settings)
             .layer(axum::middleware::from_fn_with_state(
                 state.clone(),
                 middleware::conditional_request_logging_middleware,
             ))
+            // Client address and external URL from trusted reverse proxies
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                middleware::forwarded_headers_middleware,
+            ))
             // Shared state
             .with_state(state)
     }
 
+    /// Per-proxy playlist, XMLTV and stream routes, guarded by the proxy's access rules
+    fn proxy_content_routes(state: AppState) -> Router<AppState> {
+        // Playlist and guide may additionally require the proxy's Basic Auth credentials
+        let guarded = Router::new()
+            .route(
+                "/proxy/{ulid}/m3u8",
+                get(handlers::proxies::serve_proxy_m3u),
+            )
+            .route(
+                "/proxy/{ulid}/m3u8/delta",
+                get(handlers::proxies::serve_proxy_m3u_delta),
+            )
+            .route(
+                "/proxy/{ulid}/xmltv",
+                get(handlers::proxies::serve_proxy_xmltv),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                middleware::proxy_basic_auth_middleware,
+            ));
+        Router::new()
+            .merge(guarded)
+            .route(
+                "/stream/{proxy_ulid}/{channel_id}",
+                get(handlers::proxies::proxy_stream),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                state,
+                middleware::access_control_middleware,
+            ))
+    }
+
     /// OpenAPI documentation routes
     fn openapi_routes() -> Router<AppState> {
         use utoipa_swagger_ui::SwaggerUi;
@@ -267,6 +407,15 @@ impl WebServer {
             ))
     }
 
+    /// Login, logout and session status
+    fn auth_routes() -> Router<AppState> {
+        Router::new()
+            .route("/status", get(handlers::auth::auth_status))
+            .route("/oidc/login", get(handlers::auth::oidc_login))
+            .route("/oidc/callback", get(handlers::auth::oidc_callback))
+            .route("/logout", post(handlers::auth::logout))
+    }
+
     /// API v1 routes with standard Axum routing
     fn api_v1_routes() -> Router<AppState> {
         Router::new()
@@ -315,6 +464,62 @@ impl WebServer {
                 "/sources/stream/{id}/channels",
                 get(api::get_stream_source_channels),
             )
+            .route(
+                "/sources/stream/{id}/channels/export",
+                get(handlers::stream_sources::export_stream_source_channels),
+            )
+            .route(
+                "/sources/stream/{id}/channel-retention",
+                get(handlers::stream_sources::get_channel_retention)
+                    .put(handlers::stream_sources::update_channel_retention),
+            )
+            .route(
+                "/sources/stream/{id}/balance-group",
+                get(handlers::stream_sources::get_balance_group)
+                    .put(handlers::stream_sources::update_balance_group),
+            )
+            .route(
+                "/sources/stream/balance-groups",
+                get(handlers::stream_sources::list_balance_groups),
+            )
+            .route(
+                "/sources/stream/{id}/channel-identity",
+                get(handlers::stream_sources::get_channel_identity)
+                    .put(handlers::stream_sources::update_channel_identity),
+            )
+            .route(
+                "/sources/stream/{id}/stream-headers",
+                get(handlers::stream_sources::get_stream_headers)
+                    .put(handlers::stream_sources::update_stream_headers),
+            )
+            .route(
+                "/sources/stream/{id}/category-filter",
+                get(handlers::stream_sources::get_category_filter)
+                    .put(handlers::stream_sources::update_category_filter),
+            )
+            .route(
+                "/sources/stream/{id}/mirrors",
+                get(handlers::stream_sources::get_stream_source_mirrors)
+                    .put(handlers::stream_sources::update_stream_source_mirrors),
+            )
+            .route(
+                "/sources/stream/{id}/categories",
+                get(handlers::stream_sources::list_stream_source_categories),
+            )
+            .route(
+                "/sources/stream/{id}/snapshots",
+                get(handlers::stream_sources::list_stream_source_snapshots),
+            )
+            .route(
+                "/sources/stream/{id}/snapshots/{snapshot_id}/replay",
+                post(handlers::stream_sources::replay_stream_source_snapshot),
+            )
+            .route(
+                "/sources/epg/upload",
+                post(handlers::epg_sources::upload_epg_source_xmltv).layer(DefaultBodyLimit::max(
+                    handlers::epg_sources::XMLTV_UPLOAD_BODY_LIMIT,
+                )),
+            )
             .route(
                 "/sources/epg/{id}/refresh",
                 post(api::refresh_epg_source_unified),
@@ -323,13 +528,24 @@ impl WebServer {
                 "/sources/epg/{id}/channels",
                 get(api::get_epg_source_channels_unified),
             )
+            .route(
+                "/sources/epg/{id}/snapshots",
+                get(handlers::epg_sources::list_epg_source_snapshots),
+            )
+            .route(
+                "/sources/epg/{id}/snapshots/{snapshot_id}/replay",
+                post(handlers::epg_sources::replay_epg_source_snapshot),
+            )
             // Unified sources
             .route("/sources", get(api::list_all_sources))
+            .route("/sources/{id}/history", get(api::get_source_history))
             // Progress events SSE endpoint
             .route(
                 "/progress/events",
                 get(api::progress_events::progress_events_stream),
             )
+            // WebSocket API multiplexing progress events, logs and RPC
+            .route("/ws", get(api::websocket::websocket_handler))
             // Progress operations REST endpoint
             .route("/progress/operations", get(api::get_operation_progress))
             // Logo assets
@@ -343,12 +559,40 @@ impl WebServer {
                     .delete(api::delete_logo_asset),
             )
             .route("/logos/{id}/info", get(api::get_logo_asset_with_formats))
-            .route("/logos/{id}/image", put(api::replace_logo_asset_image))
+            .route(
+                "/logos/{id}/image",
+                put(api::replace_logo_asset_image).layer(DefaultBodyLimit::max(
+                    handlers::logo_uploads::LOGO_UPLOAD_BODY_LIMIT,
+                )),
+            )
             .route(
                 "/logos/{id}/formats/{format}",
                 get(api::get_logo_asset_format),
             )
-            .route("/logos/upload", post(api::upload_logo_asset))
+            .route(
+                "/logos/upload",
+                post(api::upload_logo_asset).layer(DefaultBodyLimit::max(
+                    handlers::logo_uploads::LOGO_UPLOAD_BODY_LIMIT,
+                )),
+            )
+            .route(
+                "/logos/bulk-upload",
+                post(handlers::logo_uploads::bulk_upload_logos).layer(DefaultBodyLimit::max(
+                    handlers::logo_uploads::BULK_UPLOAD_BODY_LIMIT,
+                )),
+            )
+            .route(
+                "/logos/bulk-upload/{batch_id}/assign",
+                post(handlers::logo_uploads::assign_staged_logos),
+            )
+            .route(
+                "/logos/assignments",
+                get(handlers::logo_uploads::list_logo_assignments),
+            )
+            .route(
+                "/logos/assignments/{channel_id}",
+                delete(handlers::logo_uploads::delete_logo_assignment),
+            )
             .route(
                 "/logos/generate-metadata",
                 post(api::generate_cached_logo_metadata),
@@ -359,6 +603,7 @@ impl WebServer {
             .route("/logos/cached/{cache_id}", get(api::get_cached_logo_asset))
             // Expression validation (unified endpoint; legacy per-domain endpoints removed)
             .route("/expressions/validate", post(api::validate_expression))
+            .route("/expressions/explain", post(api::explain_expression))
             // Filters
             .route("/filters", get(api::list_filters).post(api::create_filter))
             .route(
@@ -367,9 +612,33 @@ impl WebServer {
                     .put(api::update_filter)
                     .delete(api::delete_filter),
             )
+            .route(
+                "/filters/{id}/versions",
+                get(handlers::rule_versions::list_filter_versions),
+            )
+            .route(
+                "/filters/{id}/versions/diff",
+                get(handlers::rule_versions::diff_filter_versions),
+            )
+            .route(
+                "/filters/{id}/versions/{version}/revert",
+                post(handlers::rule_versions::revert_filter),
+            )
             .route("/filters/test", post(api::test_filter))
             .route("/filters/fields/stream", get(api::get_stream_filter_fields))
             .route("/filters/fields/epg", get(api::get_epg_filter_fields))
+            // Filter groups
+            .route(
+                "/filter-groups",
+                get(handlers::filter_groups::list_filter_groups)
+                    .post(handlers::filter_groups::create_filter_group),
+            )
+            .route(
+                "/filter-groups/{id}",
+                get(handlers::filter_groups::get_filter_group)
+                    .put(handlers::filter_groups::update_filter_group)
+                    .delete(handlers::filter_groups::delete_filter_group),
+            )
             // Data mapping
             .route(
                 "/data-mapping",
@@ -381,6 +650,18 @@ impl WebServer {
                     .put(api::update_data_mapping_rule)
                     .delete(api::delete_data_mapping_rule),
             )
+            .route(
+                "/data-mapping/{id}/versions",
+                get(handlers::rule_versions::list_data_mapping_rule_versions),
+            )
+            .route(
+                "/data-mapping/{id}/versions/diff",
+                get(handlers::rule_versions::diff_data_mapping_rule_versions),
+            )
+            .route(
+                "/data-mapping/{id}/versions/{version}/revert",
+                post(handlers::rule_versions::revert_data_mapping_rule),
+            )
             .route("/data-mapping/test", post(api::test_data_mapping_rule))
             .route("/data-mapping/helpers", get(api::get_data_mapping_helpers))
             .route(
@@ -448,15 +729,185 @@ impl WebServer {
                 "/proxies/{id}/preview",
                 get(handlers::proxies::preview_existing_proxy),
             )
+            .route(
+                "/proxies/{id}/preview/stream",
+                get(handlers::proxy_preview::stream_proxy_preview),
+            )
             .route("/proxies/{id}/regenerate", post(api::regenerate_proxy))
+            .route(
+                "/proxies/{id}/rollback",
+                post(handlers::proxies::rollback_proxy_output),
+            )
+            .route(
+                "/proxies/{id}/channels/export",
+                get(handlers::proxies::export_proxy_channels),
+            )
+            .route(
+                "/proxies/{id}/status",
+                get(handlers::proxies::get_proxy_status),
+            )
+            .route(
+                "/proxies/{id}/order/{kind}",
+                get(handlers::proxy_order::get_proxy_ordering),
+            )
+            .route(
+                "/proxies/{id}/order/{kind}/move",
+                post(handlers::proxy_order::move_proxy_ordering_item),
+            )
+            .route(
+                "/proxies/{id}/share-links",
+                get(handlers::share_links::list_share_links)
+                    .post(handlers::share_links::create_share_link),
+            )
+            .route(
+                "/proxies/{id}/share-links/{link_id}",
+                delete(handlers::share_links::revoke_share_link),
+            )
+            .route(
+                "/proxies/{id}/basic-auth",
+                get(handlers::proxy_basic_auth::get_proxy_basic_auth)
+                    .put(handlers::proxy_basic_auth::set_proxy_basic_auth)
+                    .delete(handlers::proxy_basic_auth::delete_proxy_basic_auth),
+            )
+            .route(
+                "/proxies/{id}/relay-keepalive",
+                get(handlers::proxy_settings::get_relay_keepalive)
+                    .put(handlers::proxy_settings::set_relay_keepalive)
+                    .delete(handlers::proxy_settings::delete_relay_keepalive),
+            )
+            .route(
+                "/proxies/{id}/epg-merge",
+                get(handlers::proxy_settings::get_epg_merge)
+                    .put(handlers::proxy_settings::set_epg_merge)
+                    .delete(handlers::proxy_settings::delete_epg_merge),
+            )
+            .route(
+                "/proxies/{id}/epg-fallbacks",
+                get(handlers::proxy_settings::get_epg_fallbacks)
+                    .put(handlers::proxy_settings::set_epg_fallbacks)
+                    .delete(handlers::proxy_settings::delete_epg_fallbacks),
+            )
+            .route(
+                "/proxies/{id}/epg-gap-filling",
+                get(handlers::proxy_settings::get_epg_gap_filling)
+                    .put(handlers::proxy_settings::set_epg_gap_filling)
+                    .delete(handlers::proxy_settings::delete_epg_gap_filling),
+            )
+            .route(
+                "/proxies/{id}/stream-hints",
+                get(handlers::proxy_settings::get_stream_hints)
+                    .put(handlers::proxy_settings::set_stream_hints)
+                    .delete(handlers::proxy_settings::delete_stream_hints),
+            )
+            .route(
+                "/proxies/{id}/attribute-passthrough",
+                get(handlers::proxy_settings::get_attribute_passthrough)
+                    .put(handlers::proxy_settings::set_attribute_passthrough)
+                    .delete(handlers::proxy_settings::delete_attribute_passthrough),
+            )
+            .route(
+                "/proxies/{id}/access-rules",
+                get(handlers::proxy_settings::get_access_rules)
+                    .put(handlers::proxy_settings::set_access_rules)
+                    .delete(handlers::proxy_settings::delete_access_rules),
+            )
+            .route(
+                "/proxies/{id}/exclusions",
+                get(handlers::channel_exclusions::list_channel_exclusions)
+                    .post(handlers::channel_exclusions::create_channel_exclusions),
+            )
+            .route(
+                "/proxies/{id}/exclusions/{exclusion_id}",
+                delete(handlers::channel_exclusions::delete_channel_exclusion),
+            )
+            .route(
+                "/proxies/{id}/epg-mappings",
+                get(handlers::channel_epg_mappings::list_epg_mappings)
+                    .put(handlers::channel_epg_mappings::set_epg_mappings),
+            )
+            .route(
+                "/proxies/{id}/epg-mappings/unmapped",
+                get(handlers::channel_epg_mappings::list_unmapped_channels),
+            )
+            .route(
+                "/proxies/{id}/epg-mappings/candidates",
+                get(handlers::channel_epg_mappings::search_epg_channel_candidates),
+            )
+            .route(
+                "/proxies/{id}/epg-mappings/{channel_id}",
+                delete(handlers::channel_epg_mappings::delete_epg_mapping),
+            )
+            .route(
+                "/proxies/{id}/filter-groups/{group_id}",
+                post(handlers::filter_groups::attach_filter_group)
+                    .delete(handlers::filter_groups::detach_filter_group),
+            )
+            // Proxy templates
+            .route(
+                "/proxy-templates",
+                get(handlers::proxy_templates::list_proxy_templates)
+                    .post(handlers::proxy_templates::create_proxy_template),
+            )
+            .route(
+                "/proxy-templates/{id}",
+                get(handlers::proxy_templates::get_proxy_template)
+                    .put(handlers::proxy_templates::update_proxy_template)
+                    .delete(handlers::proxy_templates::delete_proxy_template),
+            )
+            .route(
+                "/proxy-templates/{id}/proxies",
+                post(handlers::proxy_templates::create_proxy_from_template),
+            )
+            .route(
+                "/proxies/{id}/guide-quality",
+                get(handlers::guide_quality::get_guide_quality),
+            )
+            .route(
+                "/proxies/{id}/pipeline-artifacts",
+                get(handlers::pipeline_artifacts::list_artifact_generations),
+            )
+            .route(
+                "/proxies/{id}/pipeline-artifacts/{execution_id}",
+                get(handlers::pipeline_artifacts::list_generation_stages),
+            )
+            .route(
+                "/proxies/{id}/pipeline-artifacts/{execution_id}/{stage_id}",
+                get(handlers::pipeline_artifacts::get_stage_samples),
+            )
             .route(
                 "/proxies/regeneration/status",
                 get(api::get_regeneration_queue_status),
             )
+            // Virtual channels
+            .route(
+                "/virtual-channels",
+                get(handlers::virtual_channels::list_virtual_channels)
+                    .post(handlers::virtual_channels::create_virtual_channel),
+            )
+            .route(
+                "/virtual-channels/{id}",
+                get(handlers::virtual_channels::get_virtual_channel)
+                    .put(handlers::virtual_channels::update_virtual_channel)
+                    .delete(handlers::virtual_channels::delete_virtual_channel),
+            )
+            // Stream URL rewrite rules
+            .route(
+                "/url-rewrite-rules",
+                get(handlers::url_rewrite_rules::list_url_rewrite_rules)
+                    .post(handlers::url_rewrite_rules::create_url_rewrite_rule),
+            )
+            .route(
+                "/url-rewrite-rules/{id}",
+                get(handlers::url_rewrite_rules::get_url_rewrite_rule)
+                    .put(handlers::url_rewrite_rules::update_url_rewrite_rule)
+                    .delete(handlers::url_rewrite_rules::delete_url_rewrite_rule),
+            )
             // Relay system endpoints
             .merge(api::relay::relay_routes())
             // Metrics and analytics
             .route("/metrics/dashboard", get(api::get_dashboard_metrics))
+            .route("/metrics/query-cache", get(api::get_query_cache_stats))
+            .route("/metrics/stream-latency", get(api::get_stream_latency))
             // Log streaming endpoints
             .route("/logs/stream", get(api::log_streaming::stream_logs))
             .route("/logs/stats", get(api::log_streaming::get_log_stats))
@@ -474,6 +925,32 @@ impl WebServer {
                 "/settings/job-scheduling",
                 put(api::settings::update_job_scheduling_config),
             )
+            .route(
+                "/schedules/preview",
+                post(handlers::schedules::preview_cron_schedule),
+            )
+            // Maintenance mode
+            .route(
+                "/maintenance",
+                get(handlers::maintenance::get_maintenance)
+                    .put(handlers::maintenance::update_maintenance),
+            )
+            // Job queue endpoints
+            .route("/jobs/queue", get(handlers::jobs::list_queued_jobs))
+            .route(
+                "/jobs/queue/{id}",
+                patch(handlers::jobs::update_queued_job).delete(handlers::jobs::cancel_queued_job),
+            )
+            // Trash endpoints
+            .route("/trash", get(handlers::trash::list_trash))
+            .route(
+                "/trash/{kind}/{id}",
+                delete(handlers::trash::purge_trash_item),
+            )
+            .route(
+                "/trash/{kind}/{id}/restore",
+                post(handlers::trash::restore_trash_item),
+            )
             // Feature flags endpoints
             .route(
                 "/features",
@@ -493,6 +970,10 @@ impl WebServer {
                 "/channels/{channel_id}/probe",
                 post(handlers::channels::probe_channel_codecs),
             )
+            .route(
+                "/channels/{channel_id}/preview",
+                get(handlers::channels::get_channel_preview),
+            )
             // EPG viewer endpoints
             .route("/epg/programs", get(handlers::epg::list_epg_programs))
             .route(
@@ -501,6 +982,16 @@ impl WebServer {
             )
             .route("/epg/sources", get(handlers::epg::list_epg_sources))
             .route("/epg/guide", get(handlers::epg::get_epg_guide))
+            // Unified search
+            .route("/search", get(handlers::search::search))
+            // Storage usage
+            .route("/storage/usage", get(handlers::storage::get_storage_usage))
+            // Active streaming sessions
+            .route("/sessions", get(handlers::sessions::list_sessions))
+            .route(
+                "/sessions/{session_id}",
+                delete(handlers::sessions::kick_session),
+            )
             // Circuit breaker management endpoints
             .route(
                 "/circuit-breakers",
@@ -527,9 +1018,8 @@ impl WebServer {
 
     /// Start the web server
     pub async fn serve(self) -> Result<()> {
-        let listener = tokio::net::TcpListener::bind(&self.addr).await?;
-        axum::serve(listener, self.app).await?;
-        Ok(())
+        let (ready_signal, _ready) = tokio::sync::oneshot::channel();
+        self.serve_with_cancellation(ready_signal, None).await
     }
 
     /// Serve with a notification when the server is actually listening or fails to bind
@@ -541,59 +1031,65 @@ impl WebServer {
     }
 
     /// Serve with cancellation support and ready notification
+    ///
+    /// With `web.tls` configured the listener terminates TLS (negotiating HTTP/2 via ALPN)
+    /// and, when `redirect_http_port` is set, a second plaintext listener redirects to HTTPS.
     pub async fn serve_with_cancellation(
         self,
         ready_signal: tokio::sync::oneshot::Sender<Result<()>>,
         cancellation_token: Option<tokio_util::sync::CancellationToken>,
     ) -> Result<()> {
-        match tokio::net::TcpListener::bind(&self.addr).await {
+        match std::net::TcpListener::bind(self.addr) {
             Ok(listener) => {
                 // Signal that we're now actually listening on the port
                 let _ = ready_signal.send(Ok(()));
 
-                // Create graceful shutdown signal
-                let shutdown_signal = async move {
-                    if let Some(token) = &cancellation_token {
-                        token.cancelled().await;
-                        tracing::info!(
-                            "Web server received cancellation signal, shutting down gracefully"
-                        );
-                    } else {
-                        // Fallback to signal handling if no cancellation token provided
-                        #[cfg(unix)]
-                        {
-                            use tokio::signal::unix::{SignalKind, signal};
-                            let mut sigterm = signal(SignalKind::terminate())
-                                .expect("failed to install SIGTERM handler");
-                            let mut sigint = signal(SignalKind::interrupt())
-                                .expect("failed to install SIGINT handler");
-
-                            tokio::select! {
-                                _ = sigterm.recv() => {
-                                    tracing::info!("Received SIGTERM, shutting down gracefully");
-                                }
-                                _ = sigint.recv() => {
-                                    tracing::info!("Received SIGINT (Ctrl+C), shutting down gracefully");
-                                }
-                            }
-                        }
+                let Some(tls) = self.tls else {
+                    listener.set_nonblocking(true)?;
+                    let listener = tokio::net::TcpListener::from_std(listener)?;
+                    axum::serve(
+                        listener,
+                        self.app.into_make_service_with_connect_info::<SocketAddr>(),
+                    )
+                    .with_graceful_shutdown(shutdown_signal(cancellation_token))
+                    .await?;
+                    return Ok(());
+                };
 
-                        #[cfg(not(unix))]
+                let redirect_handle = tls.redirect_http_port.map(|redirect_port| {
+                    let host = self.addr.ip().to_string();
+                    let https_port = self.addr.port();
+                    let token = cancellation_token.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = tls::serve_https_redirect(
+                            host,
+                            redirect_port,
+                            https_port,
+                            shutdown_signal(token),
+                        )
+                        .await
                         {
-                            use tokio::signal;
-                            signal::ctrl_c()
-                                .await
-                                .expect("failed to install Ctrl+C handler");
-                            tracing::info!("Received Ctrl+C, shutting down gracefully");
+                            tracing::error!("HTTP redirect listener failed: {e}");
                         }
-                    }
-                };
+                    })
+                });
 
-                // Now serve until shutdown signal
-                axum::serve(listener, self.app)
-                    .with_graceful_shutdown(shutdown_signal)
-                    .await?;
-                Ok(())
+                tracing::info!(
+                    "Serving HTTPS on {} (HTTP/2 {})",
+                    self.addr,
+                    if tls.http2 { "enabled" } else { "disabled" }
+                );
+                let result = tls::serve_tls(
+                    listener,
+                    self.app,
+                    &tls,
+                    shutdown_signal(cancellation_token),
+                )
+                .await;
+                if let Some(handle) = redirect_handle {
+                    handle.abort();
+                }
+                result
             }
             Err(bind_error) => {
                 // Signal the bind failure immediately
@@ -638,6 +1134,7 @@ pub struct AppState {
     pub logo_file_manager: SandboxedManager,
     pub proxy_output_file_manager: SandboxedManager,
     pub temp_file_manager: SandboxedManager,
+    pub pipeline_file_manager: SandboxedManager,
     pub observability: Arc<AppObservability>,
     pub scheduler_event_tx: Option<mpsc::UnboundedSender<SchedulerEvent>>,
     pub job_scheduler: Arc<JobScheduler>,
@@ -671,6 +1168,56 @@ pub struct AppState {
         Arc<crate::services::logo_cache_maintenance::LogoCacheMaintenanceService>,
     /// Probe persistence service (codec info)
     pub probe_persistence_service: Option<std::sync::Arc<crate::services::ProbePersistenceService>>,
+    /// On-demand channel diagnostics (cached probe reports)
+    pub channel_diagnostics_service: Option<Arc<crate::services::ChannelDiagnosticsService>>,
+    /// On-demand channel preview screenshots
+    pub channel_preview_service: Arc<crate::services::ChannelPreviewService>,
+    /// Per-proxy client IP/country rules
+    pub access_policy: Arc<crate::utils::access_control::AccessPolicy>,
+    /// Trusted reverse proxies and the forwarding headers honoured from them
+    pub forwarded_headers: Arc<crate::utils::forwarded::ForwardedHeaders>,
+    /// Cached results of frequently polled queries (source lists, dashboard totals)
+    pub query_cache: Arc<crate::services::QueryCache>,
+    /// Stream start latency per proxy and phase
+    pub stream_latency: Arc<crate::proxy::stream_latency::StreamLatencyMonitor>,
+    /// Admin API authentication (API tokens, OpenID Connect)
+    pub admin_auth: Arc<crate::services::AdminAuth>,
 }
 
 impl AppState {}
+
+/// Resolves when the server should shut down gracefully
+async fn shutdown_signal(cancellation_token: Option<tokio_util::sync::CancellationToken>) {
+    if let Some(token) = &cancellation_token {
+        token.cancelled().await;
+        tracing::info!("Web server received cancellation signal, shutting down gracefully");
+    } else {
+        // Fallback to signal handling if no cancellation token provided
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{SignalKind, signal};
+            let mut sigterm =
+                signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
+            let mut sigint =
+                signal(SignalKind::interrupt()).expect("failed to install SIGINT handler");
+
+            tokio::select! {
+                _ = sigterm.recv() => {
+                    tracing::info!("Received SIGTERM, shutting down gracefully");
+                }
+                _ = sigint.recv() => {
+                    tracing::info!("Received SIGINT (Ctrl+C), shutting down gracefully");
+                }
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            use tokio::signal;
+            signal::ctrl_c()
+                .await
+                .expect("failed to install Ctrl+C handler");
+            tracing::info!("Received Ctrl+C, shutting down gracefully");
+        }
+    }
+}
@@ -90,6 +90,12 @@ Check the endpoints below to see the complete API surface.
         (name = "epg", description = "Electronic Program Guide operations"),
         (name = "logs", description = "Real-time log streaming and monitoring"),
         (name = "settings", description = "Runtime server settings management"),
+        (name = "search", description = "Unified search across sources, proxies, filters, channels and programs"),
+        (name = "virtual-channels", description = "User-defined channels injected into proxies"),
+        (name = "url-rewrite-rules", description = "Regex rewrites of upstream stream URLs"),
+        (name = "jobs", description = "Background job queue inspection and control"),
+        (name = "trash", description = "Restore or purge deleted sources, proxies, filters and rules"),
+        (name = "auth", description = "OpenID Connect login and session status"),
     ),
     components(
         schemas(
@@ -98,18 +104,39 @@ Check the endpoints below to see the complete API surface.
             crate::models::StreamSourceType,
             crate::models::EpgSource,
             crate::models::EpgSourceType,
+            crate::models::OutputProfile,
+            crate::models::ChannelNumberBlock,
 
             // Stream Sources DTOs
             crate::web::handlers::stream_sources::CreateStreamSourceRequest,
             crate::web::handlers::stream_sources::UpdateStreamSourceRequest,
             crate::web::handlers::stream_sources::StreamSourceResponse,
+            crate::models::channel_retention::ChannelRetention,
+            crate::models::channel_retention::ChannelRetentionRequest,
+            crate::models::source_balancing::SourceBalanceGroup,
+            crate::models::source_balancing::SourceBalanceGroupRequest,
+            crate::models::source_balancing::BalanceGroupStatus,
+            crate::models::source_balancing::BalanceLineStatus,
+            crate::services::channel_export::ExportFormat,
+            crate::models::channel_identity::ChannelIdentity,
+            crate::models::channel_identity::ChannelIdentityKey,
+            crate::models::channel_identity::ChannelIdentityRequest,
+            crate::models::stream_headers::StreamHeaderOverrides,
+            crate::models::stream_headers::StreamHeaderOverridesRequest,
+            crate::models::xtream_category_filter::XtreamCategoryFilter,
+            crate::models::xtream_category_filter::XtreamCategoryFilterRequest,
+            crate::models::xtream_category_filter::XtreamCategory,
+            crate::models::stream_source_mirror::StreamSourceMirrors,
+            crate::models::stream_source_mirror::StreamSourceMirrorsRequest,
 
             // EPG Sources DTOs
             crate::web::handlers::epg_sources::CreateEpgSourceRequest,
             crate::web::handlers::epg_sources::UpdateEpgSourceRequest,
             crate::web::handlers::epg_sources::EpgSourceResponse,
+            crate::web::handlers::epg_sources::XmltvUploadResponse,
 
             // Response wrappers
+            crate::web::i18n::ErrorCode,
             crate::web::responses::ApiResponse<crate::web::handlers::stream_sources::StreamSourceResponse>,
             crate::web::responses::PaginatedResponse<crate::web::handlers::stream_sources::StreamSourceResponse>,
             crate::web::responses::ApiResponse<crate::web::handlers::epg_sources::EpgSourceResponse>,
@@ -124,6 +151,15 @@ Check the endpoints below to see the complete API surface.
             crate::web::api::log_streaming::LogEvent,
             crate::web::api::log_streaming::SpanInfo,
 
+            // WebSocket API schemas
+            crate::web::api::progress_events::ProgressEvent,
+            crate::web::api::progress_events::ProgressStageEvent,
+            crate::web::api::websocket::ClientMessage,
+            crate::web::api::websocket::ServerMessage,
+            crate::web::api::websocket::SubscriptionTopic,
+            crate::web::api::websocket::RpcMethod,
+            crate::web::api::websocket::RpcParams,
+
             // Settings schemas
             crate::web::api::settings::RuntimeSettings,
             crate::web::api::settings::UpdateSettingsRequest,
@@ -139,6 +175,7 @@ Check the endpoints below to see the complete API surface.
             crate::models::FilterTestChannel,
             crate::models::FilterWithUsage,
             crate::models::FilterFieldInfo,
+            crate::models::data_mapping::DataMappingRuleScope,
             crate::models::data_mapping::DataMappingPreviewRequest,
             crate::models::data_mapping::DataMappingExpressionPreviewRequest,
             crate::models::data_mapping::DataMappingPreviewResponse,
@@ -148,6 +185,136 @@ Check the endpoints below to see the complete API surface.
 
             // Filter query parameters
             crate::web::api::FilterQueryParams,
+            crate::web::api::DataMappingQueryParams,
+
+            // Unified search schemas
+            crate::web::handlers::search::SearchResultType,
+            crate::web::handlers::search::SearchResult,
+            crate::web::handlers::search::SearchResponse,
+
+            // Proxy share link schemas
+            crate::models::share_link::ProxyShareLink,
+            crate::models::share_link::ShareLinkStatus,
+            crate::models::share_link::CreateShareLinkRequest,
+            crate::models::channel_exclusion::ProxyChannelExclusion,
+            crate::models::channel_epg_mapping::ChannelEpgMapping,
+            crate::models::channel_epg_mapping::ChannelEpgMappingInput,
+            crate::models::channel_epg_mapping::SetChannelEpgMappingsRequest,
+            crate::models::channel_epg_mapping::EpgChannelCandidate,
+            crate::models::channel_epg_mapping::UnmappedChannel,
+            crate::models::channel_logo_assignment::ChannelLogoAssignment,
+            crate::models::channel_logo_assignment::LogoBulkUploadReport,
+            crate::models::channel_logo_assignment::MatchedLogo,
+            crate::models::channel_logo_assignment::LogoChannel,
+            crate::models::channel_logo_assignment::StagedLogo,
+            crate::models::channel_logo_assignment::RejectedLogo,
+            crate::models::channel_logo_assignment::StagedLogoAssignment,
+            crate::models::channel_logo_assignment::AssignStagedLogosRequest,
+            crate::models::channel_exclusion::ExcludeChannelsRequest,
+            crate::models::ingestion_run::IngestionRun,
+            crate::models::ingestion_run::IngestionSourceKind,
+            crate::models::ingestion_run::IngestionRunStatus,
+            crate::web::handlers::share_links::ShareLinkResponse,
+            crate::models::proxy_basic_auth::ProxyBasicAuthStatus,
+            crate::models::proxy_basic_auth::SetProxyBasicAuthRequest,
+            crate::models::proxy_basic_auth::ProxyBasicAuthCredentials,
+            crate::models::proxy_settings::RelayKeepAlivePolicy,
+            crate::models::proxy_settings::EpgMergePolicy,
+            crate::models::proxy_settings::EpgFallbacks,
+            crate::models::proxy_settings::EpgFallback,
+            crate::models::proxy_settings::EpgGapFilling,
+            crate::models::proxy_settings::StreamHints,
+            crate::models::proxy_settings::AttributePassthrough,
+            crate::models::proxy_settings::ProxyAccessRules,
+            crate::config::EpgMergeStrategy,
+            crate::config::EpgMergeFieldSources,
+            crate::web::handlers::sessions::ActiveSessionResponse,
+
+            // Storage usage schemas
+            crate::web::handlers::storage::StorageAreaUsage,
+            crate::web::handlers::storage::StorageQuotaUsage,
+            crate::web::handlers::storage::StorageUsageResponse,
+
+            // Proxy ordering schemas
+            crate::models::proxy_order::ProxyOrderKind,
+            crate::models::proxy_order::ProxyOrderMove,
+            crate::models::proxy_order::ProxyReorderRequest,
+            crate::models::proxy_order::ProxyOrderItem,
+            crate::models::proxy_order::ProxyOrdering,
+
+            // Proxy status schemas
+            crate::web::handlers::proxies::ProxyStatusResponse,
+            crate::services::proxy_regeneration::PendingRegeneration,
+            crate::models::channel_count_alert::ChannelCountAlert,
+            crate::services::relay_logs::RelayLogSnapshot,
+            crate::services::relay_logs::RelayLogLine,
+            crate::services::relay_logs::RelayLogLevel,
+            crate::services::proxy_regeneration::RegenerationTrigger,
+            crate::web::handlers::proxies::ProxyRollbackResponse,
+            crate::pipeline::services::EpgSourceFreshness,
+            crate::services::query_cache::QueryCacheStats,
+            crate::proxy::stream_latency::StreamLatencyReport,
+            crate::proxy::stream_latency::ProxyStartupLatency,
+            crate::proxy::stream_latency::LatencyPercentiles,
+            crate::web::handlers::auth::AuthStatus,
+            crate::services::auth::Principal,
+            crate::services::auth::AuthMethod,
+            crate::config::AuthRole,
+
+            // Virtual channel schemas
+            crate::models::virtual_channel::VirtualChannel,
+            crate::models::virtual_channel::VirtualChannelRequest,
+
+            // URL rewrite rule schemas
+            crate::models::url_rewrite_rule::UrlRewriteRule,
+            crate::models::url_rewrite_rule::UrlRewriteRuleRequest,
+
+            // Trash schemas
+            crate::models::trash::TrashKind,
+            crate::models::trash::TrashItem,
+
+            // Rule version schemas
+            crate::models::rule_version::RuleKind,
+            crate::models::rule_version::RuleChange,
+            crate::models::rule_version::RuleVersion,
+            crate::models::rule_version::RuleFieldChange,
+            crate::models::rule_version::DiffOp,
+            crate::models::rule_version::ExpressionDiffSegment,
+            crate::models::rule_version::RuleVersionDiff,
+
+            // Job queue schemas
+            crate::job_scheduling::JobPriority,
+            crate::job_scheduling::JobClass,
+            crate::web::handlers::jobs::QueuedJobResponse,
+            crate::web::handlers::jobs::UpdateQueuedJobRequest,
+            crate::web::handlers::schedules::CronPreviewRequest,
+            crate::web::handlers::schedules::CronPreviewResponse,
+            crate::web::handlers::schedules::ScheduledRun,
+            crate::web::handlers::schedules::SourceSchedulePreview,
+            crate::web::handlers::maintenance::MaintenanceStatus,
+            crate::web::handlers::proxy_preview::PreviewStageEvent,
+            crate::web::handlers::proxy_preview::PreviewChannelBatch,
+            crate::web::handlers::proxy_preview::PreviewCompleteEvent,
+            crate::web::handlers::maintenance::UpdateMaintenanceRequest,
+
+            // Pipeline artifact inspection schemas
+            crate::pipeline::services::artifact_inspection::ArtifactSample,
+            crate::pipeline::services::artifact_inspection::StageArtifactSamples,
+            crate::pipeline::services::artifact_inspection::StageSampleSummary,
+            crate::pipeline::services::artifact_inspection::GenerationSamples,
+
+            // Channel probe diagnostics schemas
+            crate::services::channel_diagnostics::ChannelProbeReport,
+            crate::services::channel_diagnostics::ProbeTrack,
+            crate::services::channel_diagnostics::UpstreamSample,
+            crate::services::guide_quality::GuideQualityReport,
+            crate::services::guide_quality::ChannelGuideCoverage,
+            crate::services::playlist_delta::PlaylistDelta,
+            crate::services::playlist_delta::PlaylistDeltaEntry,
+            crate::services::deep_health::DeepHealthReport,
+            crate::services::deep_health::ComponentHealth,
+            crate::services::deep_health::ComponentStatus,
+            crate::config::DeepHealthStrictness,
 
         )
     ),
@@ -163,13 +330,33 @@ Check the endpoints below to see the complete API surface.
 
         // Source operations (refresh, channels, etc.)
         crate::web::handlers::stream_sources::refresh_stream_source,
+        crate::web::handlers::stream_sources::get_channel_retention,
+        crate::web::handlers::stream_sources::update_channel_retention,
+        crate::web::handlers::stream_sources::get_balance_group,
+        crate::web::handlers::stream_sources::update_balance_group,
+        crate::web::handlers::stream_sources::export_stream_source_channels,
+        crate::web::handlers::stream_sources::list_balance_groups,
+        crate::web::handlers::stream_sources::get_channel_identity,
+        crate::web::handlers::stream_sources::update_channel_identity,
+        crate::web::handlers::stream_sources::get_stream_headers,
+        crate::web::handlers::stream_sources::update_stream_headers,
+        crate::web::handlers::stream_sources::get_category_filter,
+        crate::web::handlers::stream_sources::update_category_filter,
+        crate::web::handlers::stream_sources::get_stream_source_mirrors,
+        crate::web::handlers::stream_sources::update_stream_source_mirrors,
+        crate::web::handlers::stream_sources::list_stream_source_categories,
+        crate::web::handlers::stream_sources::list_stream_source_snapshots,
+        crate::web::handlers::stream_sources::replay_stream_source_snapshot,
         crate::web::api::refresh_epg_source_unified,
         crate::web::api::get_stream_source_channels,
         crate::web::api::get_epg_source_channels_unified,
         crate::web::api::list_all_sources,
+        crate::web::api::get_source_history,
 
         // Progress events SSE endpoint
         crate::web::api::progress_events::progress_events_stream,
+        // WebSocket API (progress, logs and RPC)
+        crate::web::api::websocket::websocket_handler,
 
         // EPG Sources endpoints
         crate::web::handlers::epg_sources::list_epg_sources,
@@ -178,10 +365,17 @@ Check the endpoints below to see the complete API surface.
         crate::web::handlers::epg_sources::update_epg_source,
         crate::web::handlers::epg_sources::delete_epg_source,
         crate::web::handlers::epg_sources::validate_epg_source,
+        crate::web::handlers::epg_sources::list_epg_source_snapshots,
+        crate::web::handlers::epg_sources::replay_epg_source_snapshot,
+        crate::web::handlers::epg_sources::upload_epg_source_xmltv,
 
         // Logo endpoints
         crate::web::api::list_logo_assets,
         crate::web::api::upload_logo_asset,
+        crate::web::handlers::logo_uploads::bulk_upload_logos,
+        crate::web::handlers::logo_uploads::assign_staged_logos,
+        crate::web::handlers::logo_uploads::list_logo_assignments,
+        crate::web::handlers::logo_uploads::delete_logo_assignment,
         crate::web::api::get_logo_asset_image,
         crate::web::api::update_logo_asset,
         crate::web::api::replace_logo_asset_image,
@@ -204,6 +398,15 @@ Check the endpoints below to see the complete API surface.
         crate::web::api::get_stream_filter_fields,
         crate::web::api::get_epg_filter_fields,
 
+        // Filter groups
+        crate::web::handlers::filter_groups::list_filter_groups,
+        crate::web::handlers::filter_groups::get_filter_group,
+        crate::web::handlers::filter_groups::create_filter_group,
+        crate::web::handlers::filter_groups::update_filter_group,
+        crate::web::handlers::filter_groups::delete_filter_group,
+        crate::web::handlers::filter_groups::attach_filter_group,
+        crate::web::handlers::filter_groups::detach_filter_group,
+
         // Data mapping endpoints
         crate::web::api::list_data_mapping_rules,
         crate::web::api::create_data_mapping_rule,
@@ -219,6 +422,7 @@ Check the endpoints below to see the complete API surface.
 
         // Expression validation endpoint (unified)
         crate::web::api::validate_expression,
+        crate::web::api::explain_expression,
         crate::web::api::test_data_mapping_rule,
         crate::web::api::apply_data_mapping_rules,
         crate::web::api::apply_data_mapping_rules_post,
@@ -234,28 +438,147 @@ Check the endpoints below to see the complete API surface.
         crate::web::handlers::channels::get_proxy_channels,
         crate::web::handlers::channels::get_channel_stream,
         crate::web::handlers::channels::probe_channel_codecs,
+        crate::web::handlers::channels::get_channel_preview,
 
         // EPG viewer
         crate::web::handlers::epg::list_epg_programs,
         crate::web::handlers::epg::get_epg_guide,
+
+        // Unified search
+        crate::web::handlers::search::search,
         crate::web::api::get_epg_category_stats,
 
         // Proxy endpoints
         crate::web::handlers::proxies::list_proxies,
         crate::web::handlers::proxies::get_proxy,
+        crate::web::handlers::proxies::get_proxy_status,
+        crate::web::handlers::proxies::rollback_proxy_output,
+        crate::web::handlers::proxies::export_proxy_channels,
         crate::web::handlers::proxies::create_proxy,
         crate::web::handlers::proxies::update_proxy,
         crate::web::handlers::proxies::delete_proxy,
         crate::web::handlers::proxies::serve_proxy_m3u,
+        crate::web::handlers::proxies::serve_proxy_m3u_delta,
 
         // Proxy preview endpoints
         crate::web::handlers::proxies::preview_proxy_config,
         crate::web::handlers::proxies::preview_existing_proxy,
+        crate::web::handlers::proxy_preview::stream_proxy_preview,
 
         // Streaming endpoints
         crate::web::handlers::proxies::proxy_stream,
         crate::web::handlers::proxies::serve_proxy_xmltv,
 
+        // Proxy ordering
+        crate::web::handlers::proxy_order::get_proxy_ordering,
+        crate::web::handlers::proxy_order::move_proxy_ordering_item,
+
+        // Proxy share links
+        crate::web::handlers::share_links::create_share_link,
+        crate::web::handlers::share_links::list_share_links,
+        crate::web::handlers::share_links::revoke_share_link,
+        crate::web::handlers::share_links::serve_shared_m3u,
+        crate::web::handlers::share_links::serve_shared_xmltv,
+        crate::web::handlers::share_links::shared_stream,
+        crate::web::handlers::proxy_basic_auth::get_proxy_basic_auth,
+        crate::web::handlers::proxy_basic_auth::set_proxy_basic_auth,
+        crate::web::handlers::proxy_basic_auth::delete_proxy_basic_auth,
+
+        // Per-proxy feature settings
+        crate::web::handlers::proxy_settings::get_relay_keepalive,
+        crate::web::handlers::proxy_settings::set_relay_keepalive,
+        crate::web::handlers::proxy_settings::delete_relay_keepalive,
+        crate::web::handlers::proxy_settings::get_epg_merge,
+        crate::web::handlers::proxy_settings::set_epg_merge,
+        crate::web::handlers::proxy_settings::delete_epg_merge,
+        crate::web::handlers::proxy_settings::get_epg_fallbacks,
+        crate::web::handlers::proxy_settings::set_epg_fallbacks,
+        crate::web::handlers::proxy_settings::delete_epg_fallbacks,
+        crate::web::handlers::proxy_settings::get_epg_gap_filling,
+        crate::web::handlers::proxy_settings::set_epg_gap_filling,
+        crate::web::handlers::proxy_settings::delete_epg_gap_filling,
+        crate::web::handlers::proxy_settings::get_stream_hints,
+        crate::web::handlers::proxy_settings::set_stream_hints,
+        crate::web::handlers::proxy_settings::delete_stream_hints,
+        crate::web::handlers::proxy_settings::get_attribute_passthrough,
+        crate::web::handlers::proxy_settings::set_attribute_passthrough,
+        crate::web::handlers::proxy_settings::delete_attribute_passthrough,
+        crate::web::handlers::proxy_settings::get_access_rules,
+        crate::web::handlers::proxy_settings::set_access_rules,
+        crate::web::handlers::proxy_settings::delete_access_rules,
+
+        // Proxy channel exclusions
+        crate::web::handlers::channel_exclusions::list_channel_exclusions,
+        crate::web::handlers::channel_exclusions::create_channel_exclusions,
+        crate::web::handlers::channel_exclusions::delete_channel_exclusion,
+        crate::web::handlers::guide_quality::get_guide_quality,
+
+        // Manual channel-to-EPG mappings
+        crate::web::handlers::channel_epg_mappings::list_epg_mappings,
+        crate::web::handlers::channel_epg_mappings::set_epg_mappings,
+        crate::web::handlers::channel_epg_mappings::delete_epg_mapping,
+        crate::web::handlers::channel_epg_mappings::list_unmapped_channels,
+        crate::web::handlers::channel_epg_mappings::search_epg_channel_candidates,
+
+        // Proxy templates
+        crate::web::handlers::proxy_templates::list_proxy_templates,
+        crate::web::handlers::proxy_templates::get_proxy_template,
+        crate::web::handlers::proxy_templates::create_proxy_template,
+        crate::web::handlers::proxy_templates::update_proxy_template,
+        crate::web::handlers::proxy_templates::delete_proxy_template,
+        crate::web::handlers::proxy_templates::create_proxy_from_template,
+        crate::web::handlers::sessions::list_sessions,
+        crate::web::handlers::sessions::kick_session,
+
+        // Storage usage
+        crate::web::handlers::storage::get_storage_usage,
+
+        // Virtual channels
+        crate::web::handlers::virtual_channels::list_virtual_channels,
+        crate::web::handlers::virtual_channels::get_virtual_channel,
+        crate::web::handlers::virtual_channels::create_virtual_channel,
+        crate::web::handlers::virtual_channels::update_virtual_channel,
+        crate::web::handlers::virtual_channels::delete_virtual_channel,
+
+        // URL rewrite rules
+        crate::web::handlers::url_rewrite_rules::list_url_rewrite_rules,
+        crate::web::handlers::url_rewrite_rules::get_url_rewrite_rule,
+        crate::web::handlers::url_rewrite_rules::create_url_rewrite_rule,
+        crate::web::handlers::url_rewrite_rules::update_url_rewrite_rule,
+        crate::web::handlers::url_rewrite_rules::delete_url_rewrite_rule,
+
+        // Authentication
+        crate::web::handlers::auth::auth_status,
+        crate::web::handlers::auth::oidc_login,
+        crate::web::handlers::auth::oidc_callback,
+        crate::web::handlers::auth::logout,
+
+        // Trash
+        crate::web::handlers::trash::list_trash,
+        crate::web::handlers::trash::restore_trash_item,
+        crate::web::handlers::trash::purge_trash_item,
+
+        // Rule versions
+        crate::web::handlers::rule_versions::list_filter_versions,
+        crate::web::handlers::rule_versions::diff_filter_versions,
+        crate::web::handlers::rule_versions::revert_filter,
+        crate::web::handlers::rule_versions::list_data_mapping_rule_versions,
+        crate::web::handlers::rule_versions::diff_data_mapping_rule_versions,
+        crate::web::handlers::rule_versions::revert_data_mapping_rule,
+
+        // Job queue
+        crate::web::handlers::jobs::list_queued_jobs,
+        crate::web::handlers::jobs::update_queued_job,
+        crate::web::handlers::jobs::cancel_queued_job,
+        crate::web::handlers::schedules::preview_cron_schedule,
+        crate::web::handlers::maintenance::get_maintenance,
+        crate::web::handlers::maintenance::update_maintenance,
+
+        // Pipeline artifact inspection
+        crate::web::handlers::pipeline_artifacts::list_artifact_generations,
+        crate::web::handlers::pipeline_artifacts::list_generation_stages,
+        crate::web::handlers::pipeline_artifacts::get_stage_samples,
+
         // Proxy regeneration endpoints
         crate::web::api::regenerate_proxy,
         crate::web::api::get_regeneration_queue_status,
@@ -269,9 +592,11 @@ Check the endpoints below to see the complete API surface.
 
         // Relay health and metrics
         crate::web::api::relay::get_relay_health,
+        crate::web::api::relay::get_relay_logs,
 
         // Health endpoints
         crate::web::handlers::health::health_check,
+        crate::web::handlers::health::deep_health_check,
         crate::web::handlers::health::readiness_check,
         crate::web::handlers::health::liveness_check,
 
@@ -291,6 +616,8 @@ Check the endpoints below to see the complete API surface.
 
         // Metrics endpoints
         crate::web::api::get_dashboard_metrics,
+        crate::web::api::get_query_cache_stats,
+        crate::web::api::get_stream_latency,
 
         // Log streaming endpoints
         crate::web::api::log_streaming::stream_logs,
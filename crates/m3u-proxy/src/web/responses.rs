@@ -10,9 +10,10 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use utoipa::ToSchema;
 
+use super::i18n::ErrorCode;
 use crate::errors::{AppError, AppResult};
 
 /// Standard API response wrapper
@@ -26,6 +27,12 @@ pub struct ApiResponse<T> {
     /// Error message (present on failure)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Stable error code for clients localising the message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
+    /// Values filled into the message template of the error code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<BTreeMap<String, String>>,
     /// Additional error details
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<HashMap<String, String>>,
@@ -43,6 +50,8 @@ where
             success: true,
             data: Some(data),
             error: None,
+            code: None,
+            params: None,
             details: None,
             timestamp: chrono::Utc::now(),
         }
@@ -54,6 +63,25 @@ where
             success: false,
             data: None,
             error: Some(message),
+            code: None,
+            params: None,
+            details: None,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Create an error response with an error code and the params of its message
+    pub fn error_with_code(
+        code: ErrorCode,
+        message: String,
+        params: BTreeMap<String, String>,
+    ) -> ApiResponse<()> {
+        ApiResponse {
+            success: false,
+            data: None,
+            error: Some(message),
+            code: Some(code),
+            params: (!params.is_empty()).then_some(params),
             details: None,
             timestamp: chrono::Utc::now(),
         }
@@ -68,6 +96,8 @@ where
             success: false,
             data: None,
             error: Some(message),
+            code: None,
+            params: None,
             details: Some(details),
             timestamp: chrono::Utc::now(),
         }
@@ -107,16 +137,52 @@ pub struct PaginatedResponse<T> {
     pub has_next: bool,
     /// Whether there is a previous page
     pub has_previous: bool,
+    /// 0-based offset of the first item in this page
+    pub offset: u32,
+    /// Cursor to pass as `cursor` to fetch the next page, if any
+    pub next_cursor: Option<String>,
 }
 
 impl<T> PaginatedResponse<T> {
     /// Create a new paginated response
     pub fn new(items: Vec<T>, total: u64, page: u32, per_page: u32) -> Self {
+        let offset = page.saturating_sub(1).saturating_mul(per_page);
+        Self::build(items, total, page, per_page, offset)
+    }
+
+    /// Create a paginated response for a page addressed by offset or cursor
+    pub fn from_offset(items: Vec<T>, total: u64, offset: u32, per_page: u32) -> Self {
+        let page = if per_page > 0 {
+            offset / per_page + 1
+        } else {
+            1
+        };
+        Self::build(items, total, page, per_page, offset)
+    }
+
+    /// Transform the items of this page, keeping the pagination metadata
+    pub fn map_items<U>(self, f: impl FnMut(T) -> U) -> PaginatedResponse<U> {
+        PaginatedResponse {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            per_page: self.per_page,
+            total_pages: self.total_pages,
+            has_next: self.has_next,
+            has_previous: self.has_previous,
+            offset: self.offset,
+            next_cursor: self.next_cursor,
+        }
+    }
+
+    fn build(items: Vec<T>, total: u64, page: u32, per_page: u32, offset: u32) -> Self {
         let total_pages = if per_page > 0 {
             (total as f64 / per_page as f64).ceil() as u32
         } else {
             1
         };
+        let next_offset = offset as u64 + items.len() as u64;
+        let has_next = !items.is_empty() && next_offset < total;
 
         Self {
             items,
@@ -124,8 +190,10 @@ impl<T> PaginatedResponse<T> {
             page,
             per_page,
             total_pages,
-            has_next: page < total_pages,
-            has_previous: page > 1,
+            has_next,
+            has_previous: offset > 0,
+            offset,
+            next_cursor: has_next.then(|| super::extractors::encode_cursor(next_offset as u32)),
         }
     }
 }
@@ -147,75 +215,108 @@ where
 
 /// Convert AppError to appropriate HTTP response
 pub fn handle_error(error: AppError) -> impl IntoResponse {
-    let (status, message, details) = match &error {
-        AppError::Validation { message } => (StatusCode::BAD_REQUEST, message.clone(), None),
+    let (status, code, message, params) = match &error {
+        AppError::Validation { message } => (
+            StatusCode::BAD_REQUEST,
+            ErrorCode::ValidationFailed,
+            message.clone(),
+            error_params([("message", message)]),
+        ),
         AppError::NotFound { resource, id } => (
             StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
             format!("{resource} with id '{id}' not found"),
-            None,
+            error_params([("resource", resource), ("id", id)]),
         ),
         AppError::PermissionDenied { action, resource } => (
             StatusCode::FORBIDDEN,
+            ErrorCode::PermissionDenied,
             format!("Permission denied: {action} on {resource}"),
-            None,
+            error_params([("action", action), ("resource", resource)]),
         ),
         AppError::Configuration { message } => (
             StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::ConfigurationError,
             format!("Configuration error: {message}"),
-            None,
+            error_params([("message", message)]),
         ),
         AppError::ExternalService { service, message } => (
             StatusCode::BAD_GATEWAY,
+            ErrorCode::ExternalServiceError,
             format!("External service error ({service}): {message}"),
-            None,
+            error_params([("service", service), ("message", message)]),
         ),
         AppError::Http(_) => (
             StatusCode::BAD_GATEWAY,
+            ErrorCode::ExternalServiceUnavailable,
             "External service communication failed".to_string(),
-            None,
+            BTreeMap::new(),
         ),
         AppError::Database(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::DatabaseError,
             "Database operation failed".to_string(),
-            None,
+            BTreeMap::new(),
         ),
         AppError::Repository(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::DataAccessError,
             "Data access failed".to_string(),
-            None,
+            BTreeMap::new(),
         ),
         AppError::Source(_) => (
             StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::SourceError,
             "Source operation failed".to_string(),
-            None,
+            BTreeMap::new(),
         ),
         AppError::Web(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::RequestFailed,
             "Web request processing failed".to_string(),
-            None,
+            BTreeMap::new(),
         ),
         AppError::OperationInProgress {
             operation_type,
             resource,
         } => (
             StatusCode::CONFLICT,
+            ErrorCode::OperationInProgress,
             format!("Operation already in progress: {operation_type} on {resource}"),
-            None,
+            error_params([("operation_type", operation_type), ("resource", resource)]),
         ),
         AppError::Internal { message } => (
             StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::InternalError,
             format!("Internal error: {message}"),
-            None,
+            error_params([("message", message)]),
         ),
     };
 
-    let response = if let Some(details) = details {
-        ApiResponse::<()>::error_with_details(message, details)
-    } else {
-        ApiResponse::<()>::error(message)
-    };
+    coded_error(status, code, message, params).into_response()
+}
 
-    (status, with_default_headers(Json(response))).into_response()
+/// Message params of an error response
+fn error_params<S: AsRef<str>, const N: usize>(params: [(&str, S); N]) -> BTreeMap<String, String> {
+    params
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.as_ref().to_string()))
+        .collect()
+}
+
+/// Error response with an error code and the params of its message
+fn coded_error(
+    status: StatusCode,
+    code: ErrorCode,
+    message: String,
+    params: BTreeMap<String, String>,
+) -> impl IntoResponse {
+    (
+        status,
+        with_default_headers(Json(ApiResponse::<()>::error_with_code(
+            code, message, params,
+        ))),
+    )
 }
 
 /// Success response helpers
@@ -254,32 +355,49 @@ pub fn no_content() -> impl IntoResponse {
 
 /// Error response helpers
 pub fn bad_request(message: &str) -> impl IntoResponse {
-    (
+    coded_error(
         StatusCode::BAD_REQUEST,
-        with_default_headers(Json(ApiResponse::<()>::error(message.to_string()))),
+        ErrorCode::BadRequest,
+        message.to_string(),
+        error_params([("message", message)]),
     )
 }
 
 pub fn not_found(resource: &str, id: &str) -> impl IntoResponse {
-    (
+    coded_error(
         StatusCode::NOT_FOUND,
-        with_default_headers(Json(ApiResponse::<()>::error(format!(
-            "{resource} with id '{id}' not found"
-        )))),
+        ErrorCode::NotFound,
+        format!("{resource} with id '{id}' not found"),
+        error_params([("resource", resource), ("id", id)]),
     )
 }
 
 pub fn internal_error(message: &str) -> impl IntoResponse {
-    (
+    coded_error(
         StatusCode::INTERNAL_SERVER_ERROR,
-        with_default_headers(Json(ApiResponse::<()>::error(message.to_string()))),
+        ErrorCode::InternalError,
+        message.to_string(),
+        error_params([("message", message)]),
     )
 }
 
 pub fn conflict(message: &str) -> impl IntoResponse {
-    (
+    coded_error(
         StatusCode::CONFLICT,
-        with_default_headers(Json(ApiResponse::<()>::error(message.to_string()))),
+        ErrorCode::Conflict,
+        message.to_string(),
+        error_params([("message", message)]),
+    )
+}
+
+/// Upload exceeding its size limit, given in bytes
+pub fn payload_too_large(limit: u64) -> impl IntoResponse {
+    let limit = format!("{} MiB", limit.div_ceil(1024 * 1024));
+    coded_error(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        ErrorCode::PayloadTooLarge,
+        format!("Upload exceeds the limit of {limit}"),
+        error_params([("limit", limit)]),
     )
 }
 
@@ -295,13 +413,19 @@ pub fn validation_error(errors: Vec<ValidationErrorResponse>) -> impl IntoRespon
     for error in &errors {
         details.insert(error.field.clone(), error.message.clone());
     }
-
+    let summary = errors
+        .iter()
+        .map(|error| format!("{}: {}", error.field, error.message))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let mut response =
+        ApiResponse::<()>::error_with_details("Validation failed".to_string(), details);
+    response.code = Some(ErrorCode::ValidationFailed);
+    response.params = Some(error_params([("message", summary)]));
     (
         StatusCode::BAD_REQUEST,
-        with_default_headers(Json(ApiResponse::<()>::error_with_details(
-            "Validation failed".to_string(),
-            details,
-        ))),
+        with_default_headers(Json(response)),
     )
 }
 
@@ -318,6 +442,9 @@ pub struct DatabaseHealth {
     pub no_blocking_locks: bool,
     pub idle_connections: u32,
     pub pool_utilization_percent: u32,
+    /// Read replica state ("healthy" or "fallback_to_primary"); absent without a replica
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_replica: Option<String>,
 }
 
 /// Scheduler health information
@@ -413,6 +540,8 @@ pub struct RelayHealthApiResponse {
     pub ffprobe_version: Option<String>,
     pub hwaccel_available: bool,
     pub hwaccel_capabilities: DetailedHwAccelCapabilities,
+    /// Transcode slot usage per encoding device
+    pub transcode_devices: Vec<crate::models::relay::TranscodeDeviceUtilization>,
 }
 
 /// Individual relay process information for dashboard
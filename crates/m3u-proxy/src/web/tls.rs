@@ -0,0 +1,223 @@
+//! Native TLS termination
+//!
+//! Serves the router over rustls with HTTP/2 negotiated via ALPN. Certificates are loaded
+//! from PEM files or obtained from an ACME provider (TLS-ALPN-01), and an optional plaintext
+//! listener redirects HTTP requests to HTTPS.
+
+use anyhow::{Context, Result};
+use axum::{
+    Router,
+    extract::Request,
+    http::{StatusCode, Uri, header},
+    response::{IntoResponse, Redirect, Response},
+};
+use futures::StreamExt;
+use rustls::ServerConfig;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::config::{AcmeConfig, WebTlsConfig};
+
+/// Time given to open connections to finish once shutdown begins
+const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn crypto_provider() -> Arc<rustls::crypto::CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// Serve `app` over TLS on an already bound listener until `shutdown` resolves
+pub async fn serve_tls(
+    listener: std::net::TcpListener,
+    app: Router,
+    tls: &WebTlsConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    listener.set_nonblocking(true)?;
+    let handle = axum_server::Handle::new();
+    {
+        let handle = handle.clone();
+        tokio::spawn(async move {
+            shutdown.await;
+            handle.graceful_shutdown(Some(GRACEFUL_SHUTDOWN_TIMEOUT));
+        });
+    }
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
+
+    if let Some(acme) = &tls.acme {
+        let acceptor = acme_acceptor(acme, tls.alpn_protocols())?;
+        axum_server::from_tcp(listener)
+            .acceptor(acceptor)
+            .handle(handle)
+            .serve(service)
+            .await?;
+    } else {
+        let server_config = file_server_config(tls)?;
+        let rustls_config = axum_server::tls_rustls::RustlsConfig::from_config(server_config);
+        axum_server::from_tcp_rustls(listener, rustls_config)
+            .handle(handle)
+            .serve(service)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Rustls configuration from the PEM certificate chain and key of `tls`
+pub fn file_server_config(tls: &WebTlsConfig) -> Result<Arc<ServerConfig>> {
+    let (Some(cert_path), Some(key_path)) = (&tls.cert_path, &tls.key_path) else {
+        anyhow::bail!("web.tls requires both cert_path and key_path");
+    };
+    let cert_pem = std::fs::read(cert_path)
+        .with_context(|| format!("Failed to read TLS certificate {}", cert_path.display()))?;
+    let key_pem = std::fs::read(key_path)
+        .with_context(|| format!("Failed to read TLS private key {}", key_path.display()))?;
+
+    let certs = rustls_pemfile::certs(&mut cert_pem.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid TLS certificate {}", cert_path.display()))?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in {}", cert_path.display());
+    }
+    let key = rustls_pemfile::private_key(&mut key_pem.as_slice())
+        .with_context(|| format!("Invalid TLS private key {}", key_path.display()))?
+        .with_context(|| format!("No private key found in {}", key_path.display()))?;
+
+    let mut config = ServerConfig::builder_with_provider(crypto_provider())
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    config.alpn_protocols = tls.alpn_protocols();
+    Ok(Arc::new(config))
+}
+
+/// Acceptor answering TLS-ALPN-01 challenges and serving the managed certificate
+///
+/// Spawns the task that orders and renews the certificate; it runs for the lifetime of
+/// the process.
+fn acme_acceptor(
+    acme: &AcmeConfig,
+    alpn_protocols: Vec<Vec<u8>>,
+) -> Result<rustls_acme::axum::AxumAcceptor> {
+    let mut acme_config = rustls_acme::AcmeConfig::new(acme.domains.clone())
+        .contact(acme.contacts.iter().map(|contact| {
+            if contact.starts_with("mailto:") {
+                contact.clone()
+            } else {
+                format!("mailto:{contact}")
+            }
+        }))
+        .cache(rustls_acme::caches::DirCache::new(acme.cache_path.clone()));
+    acme_config = match &acme.directory_url {
+        Some(url) => acme_config.directory(url),
+        None => acme_config.directory_lets_encrypt(acme.production),
+    };
+
+    let mut state = acme_config.state();
+    let mut server_config = ServerConfig::builder_with_provider(crypto_provider())
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_cert_resolver(state.resolver());
+    server_config.alpn_protocols = alpn_protocols;
+    let acceptor = state.axum_acceptor(Arc::new(server_config));
+
+    tokio::spawn(async move {
+        while let Some(event) = state.next().await {
+            match event {
+                Ok(ok) => info!("ACME: {:?}", ok),
+                Err(err) => error!("ACME: {}", err),
+            }
+        }
+    });
+
+    info!(
+        "ACME certificate management enabled for {}",
+        acme.domains.join(", ")
+    );
+    Ok(acceptor)
+}
+
+/// Serve permanent redirects to HTTPS on `port` until `shutdown` resolves
+///
+/// `https_port` is appended to the redirect host unless it is the default 443.
+pub async fn serve_https_redirect(
+    host: String,
+    port: u16,
+    https_port: u16,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    let addr: SocketAddr = format!("{host}:{port}").parse()?;
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind HTTP redirect listener to {addr}"))?;
+    info!("Redirecting HTTP on {} to HTTPS", addr);
+
+    let app = Router::new()
+        .fallback(move |request: Request| async move { redirect_to_https(&request, https_port) });
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+fn redirect_to_https(request: &Request, https_port: u16) -> Response {
+    let Some(host) = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| request.uri().host())
+    else {
+        warn!("Cannot redirect HTTP request without a Host header");
+        return (StatusCode::BAD_REQUEST, "Missing Host header").into_response();
+    };
+    match https_url(host, https_port, request.uri()) {
+        Some(location) => Redirect::permanent(&location).into_response(),
+        None => (StatusCode::BAD_REQUEST, "Invalid Host header").into_response(),
+    }
+}
+
+/// HTTPS URL for a request to `host` (optionally carrying a port) and `uri`
+fn https_url(host: &str, https_port: u16, uri: &Uri) -> Option<String> {
+    let authority: axum::http::uri::Authority = host.parse().ok()?;
+    let path_and_query = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+    let host = authority.host();
+    Some(if https_port == 443 {
+        format!("https://{host}{path_and_query}")
+    } else {
+        format!("https://{host}:{https_port}{path_and_query}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_https_url() {
+        let uri: Uri = "/live/channel.m3u8?token=abc".parse().unwrap();
+        assert_eq!(
+            https_url("tv.example.com:8080", 443, &uri).as_deref(),
+            Some("https://tv.example.com/live/channel.m3u8?token=abc")
+        );
+        assert_eq!(
+            https_url("tv.example.com", 8443, &"/".parse().unwrap()).as_deref(),
+            Some("https://tv.example.com:8443/")
+        );
+        assert_eq!(
+            https_url("[::1]:80", 443, &uri).as_deref(),
+            Some("https://[::1]/live/channel.m3u8?token=abc")
+        );
+        assert!(https_url("bad host", 443, &uri).is_none());
+    }
+
+    #[test]
+    fn test_file_server_config_requires_readable_files() {
+        let tls = WebTlsConfig {
+            cert_path: Some("/nonexistent/cert.pem".into()),
+            key_path: Some("/nonexistent/key.pem".into()),
+            ..Default::default()
+        };
+        assert!(file_server_config(&tls).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! Streaming multipart uploads
+//!
+//! Upload fields are read chunk by chunk, so size limits are enforced while the body
+//! arrives instead of after it has been buffered, and large files are written straight
+//! into a sandboxed manager rather than held in memory.
+
+use axum::extract::multipart::Field;
+use axum::response::{IntoResponse, Response};
+use sandboxed_file_manager::SandboxedManager;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::services::progress_service::ProgressStageUpdater;
+use crate::web::responses::{bad_request, internal_error, payload_too_large};
+
+/// Bytes between progress updates of an upload of unknown size
+const UNSIZED_PROGRESS_INTERVAL: u64 = 16 * 1024 * 1024;
+
+/// Reason an upload field could not be received
+#[derive(Debug)]
+pub enum UploadError {
+    /// The field grew beyond its limit, in bytes
+    TooLarge { limit: u64 },
+    /// The request body could not be read
+    Read(String),
+    /// The upload could not be stored
+    Write(String),
+}
+
+impl std::fmt::Display for UploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLarge { limit } => write!(f, "Upload exceeds the limit of {limit} bytes"),
+            Self::Read(e) => write!(f, "Failed to read upload: {e}"),
+            Self::Write(e) => write!(f, "Failed to store upload: {e}"),
+        }
+    }
+}
+
+impl IntoResponse for UploadError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::TooLarge { limit } => payload_too_large(limit).into_response(),
+            Self::Read(_) => bad_request(&self.to_string()).into_response(),
+            Self::Write(_) => internal_error(&self.to_string()).into_response(),
+        }
+    }
+}
+
+/// Read a field into memory, failing as soon as it grows beyond `limit` bytes
+pub async fn read_field(field: &mut Field<'_>, limit: u64) -> Result<Vec<u8>, UploadError> {
+    let mut data = Vec::new();
+    while let Some(chunk) = next_chunk(field).await? {
+        if (data.len() + chunk.len()) as u64 > limit {
+            return Err(UploadError::TooLarge { limit });
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+/// Write a field to `path` in a sandboxed manager, failing as soon as it grows beyond
+/// `limit` bytes, and return its size
+///
+/// Progress is reported to `progress` against `expected_bytes` (usually the request's
+/// Content-Length) when known. The partial file is removed when the upload fails.
+pub async fn stream_field_to_file(
+    field: &mut Field<'_>,
+    manager: &SandboxedManager,
+    path: &str,
+    limit: u64,
+    progress: Option<&ProgressStageUpdater>,
+    expected_bytes: Option<u64>,
+) -> Result<u64, UploadError> {
+    let mut file = manager
+        .create(path)
+        .await
+        .map_err(|e| UploadError::Write(e.to_string()))?;
+
+    let result = async {
+        let mut written = 0u64;
+        let mut reported = 0u64;
+        while let Some(chunk) = next_chunk(field).await? {
+            written += chunk.len() as u64;
+            if written > limit {
+                return Err(UploadError::TooLarge { limit });
+            }
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| UploadError::Write(e.to_string()))?;
+
+            if let Some(progress) = progress {
+                let step = match expected_bytes {
+                    Some(expected) if expected > 0 => (expected / 100).max(1),
+                    _ => UNSIZED_PROGRESS_INTERVAL,
+                };
+                if written / step > reported / step {
+                    reported = written;
+                    report_progress(progress, written, expected_bytes).await;
+                }
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| UploadError::Write(e.to_string()))?;
+        Ok(written)
+    }
+    .await;
+
+    if result.is_err() {
+        drop(file);
+        if let Err(e) = manager.remove_file(path).await {
+            warn!("Failed to remove partial upload {}: {}", path, e);
+        }
+    }
+    result
+}
+
+async fn next_chunk(field: &mut Field<'_>) -> Result<Option<axum::body::Bytes>, UploadError> {
+    field
+        .chunk()
+        .await
+        .map_err(|e| UploadError::Read(e.body_text()))
+}
+
+async fn report_progress(progress: &ProgressStageUpdater, written: u64, expected: Option<u64>) {
+    const MIB: f64 = 1024.0 * 1024.0;
+    let received = written as f64 / MIB;
+    match expected {
+        Some(expected) if expected > 0 => {
+            let percentage = (written as f64 / expected as f64 * 100.0).min(100.0);
+            let description = format!("Received {received:.1} of {:.1} MiB", expected as f64 / MIB);
+            progress.update_progress(percentage, &description).await;
+        }
+        _ => {
+            let description = format!("Received {received:.1} MiB");
+            progress.update_progress(0.0, &description).await;
+        }
+    }
+}
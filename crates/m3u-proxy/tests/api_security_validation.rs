@@ -50,7 +50,8 @@ async fn create_seaorm_test_database() -> Result<std::sync::Arc<DatabaseConnecti
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL,
             last_ingested_at TEXT,
-            is_active INTEGER NOT NULL DEFAULT 1
+            is_active INTEGER NOT NULL DEFAULT 1,
+            deleted_at TEXT
         );
         "#
             .to_string(),
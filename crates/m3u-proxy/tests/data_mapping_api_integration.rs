@@ -55,7 +55,8 @@ async fn create_test_database() -> (Database, std::sync::Arc<DatabaseConnection>
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL,
             last_ingested_at TEXT,
-            is_active INTEGER NOT NULL DEFAULT 1
+            is_active INTEGER NOT NULL DEFAULT 1,
+            deleted_at TEXT
         );
         CREATE TABLE channels (
             id TEXT PRIMARY KEY,
@@ -69,7 +70,9 @@ async fn create_test_database() -> (Database, std::sync::Arc<DatabaseConnection>
             group_title TEXT,
             stream_url TEXT NOT NULL,
             created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
+            updated_at TEXT NOT NULL,
+            missed_ingestions INTEGER NOT NULL DEFAULT 0,
+            extra_attributes TEXT
         );
         "#
             .to_string(),
@@ -372,6 +375,8 @@ async fn create_in_memory_database() -> anyhow::Result<Database> {
         progress_update_interval: 1000,
         run_missed_immediately: true,
         use_new_source_handlers: true,
+        max_decompressed_size_mb: 4096,
+        limits: Default::default(),
     };
 
     Database::new(&db_config, &ingestion_config).await
@@ -566,6 +571,8 @@ async fn test_transaction_rollback_pattern(connection: &DatabaseConnection) -> a
         stream_url: Set(test_channel.stream_url),
         created_at: Set(chrono::Utc::now()),
         updated_at: Set(chrono::Utc::now()),
+        missed_ingestions: Set(0),
+        extra_attributes: Set(None),
     };
 
     let _created_channel = channel_active.insert(&txn).await?;
@@ -12,11 +12,13 @@ use m3u_proxy::{
     database::Database,
     database::repositories::{
         channel::{ChannelCreateRequest, ChannelSeaOrmRepository},
+        channel_retention::ChannelRetentionSeaOrmRepository,
         epg_source::EpgSourceSeaOrmRepository,
         filter::FilterSeaOrmRepository,
         stream_source::StreamSourceSeaOrmRepository,
+        trash::TrashSeaOrmRepository,
     },
-    models::*,
+    models::{trash::TrashKind, *},
 };
 
 /// Helper to create test database using SeaORM infrastructure
@@ -45,7 +47,8 @@ async fn create_test_database() -> (Database, std::sync::Arc<DatabaseConnection>
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL,
             last_ingested_at TEXT,
-            is_active INTEGER NOT NULL DEFAULT 1
+            is_active INTEGER NOT NULL DEFAULT 1,
+            deleted_at TEXT
         );
         CREATE TABLE channels (
             id TEXT PRIMARY KEY,
@@ -60,6 +63,14 @@ async fn create_test_database() -> (Database, std::sync::Arc<DatabaseConnection>
             stream_url TEXT NOT NULL,
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL,
+            missed_ingestions INTEGER NOT NULL DEFAULT 0,
+            extra_attributes TEXT,
+            FOREIGN KEY (source_id) REFERENCES stream_sources (id) ON DELETE CASCADE
+        );
+        CREATE TABLE stream_source_channel_retention (
+            source_id TEXT PRIMARY KEY,
+            max_missed_ingestions INTEGER NOT NULL,
+            updated_at TEXT NOT NULL,
             FOREIGN KEY (source_id) REFERENCES stream_sources (id) ON DELETE CASCADE
         );
         CREATE TABLE filters (
@@ -70,7 +81,8 @@ async fn create_test_database() -> (Database, std::sync::Arc<DatabaseConnection>
             is_system_default INTEGER NOT NULL DEFAULT 0,
             is_inverse INTEGER NOT NULL DEFAULT 0,
             created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
+            updated_at TEXT NOT NULL,
+            deleted_at TEXT
         );
         CREATE TABLE epg_sources (
             id TEXT PRIMARY KEY,
@@ -85,7 +97,12 @@ async fn create_test_database() -> (Database, std::sync::Arc<DatabaseConnection>
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL,
             last_ingested_at TEXT,
-            is_active INTEGER NOT NULL DEFAULT 1
+            is_active INTEGER NOT NULL DEFAULT 1,
+            deleted_at TEXT
+        );
+        CREATE TABLE ingestion_runs (
+            id TEXT PRIMARY KEY,
+            source_id TEXT NOT NULL
         );
         "#
             .to_string(),
@@ -291,7 +308,7 @@ async fn test_stream_source_validation_and_constraints() {
 async fn test_channel_repository_with_source_relationship() {
     let (_db, connection) = create_test_database().await;
     let source_repo = StreamSourceSeaOrmRepository::new(connection.clone());
-    let channel_repo = ChannelSeaOrmRepository::new(connection);
+    let channel_repo = ChannelSeaOrmRepository::new(connection.clone());
 
     // Create source first (foreign key relationship)
     let source = create_test_stream_source(&source_repo).await;
@@ -345,10 +362,25 @@ async fn test_channel_repository_with_source_relationship() {
         .unwrap();
     assert!(channel_name.is_some());
 
-    // Delete source to test cascade deletion
+    // Deleting moves the source to the trash and keeps its channels for a restore
     source_repo.delete(&source.id).await.unwrap();
+    assert!(source_repo.find_by_id(&source.id).await.unwrap().is_none());
+    assert!(
+        channel_repo
+            .find_by_id(&created_channel.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
 
-    // Verify channel was deleted due to cascade
+    // Purging the trashed source cascades to its channels
+    let trash_repo = TrashSeaOrmRepository::new(connection.clone());
+    assert!(
+        trash_repo
+            .purge(TrashKind::StreamSource, source.id)
+            .await
+            .unwrap()
+    );
     assert!(
         channel_repo
             .find_by_id(&created_channel.id)
@@ -388,6 +420,105 @@ async fn test_channel_repository_foreign_key_constraints() {
 // EPG SOURCE REPOSITORY TESTS
 // =============================================================================
 
+#[tokio::test]
+async fn test_channel_retention_keeps_missing_channels_as_stale() {
+    let (_db, connection) = create_test_database().await;
+    let source_repo = StreamSourceSeaOrmRepository::new(connection.clone());
+    let channel_repo = ChannelSeaOrmRepository::new(connection.clone());
+    let retention_repo = ChannelRetentionSeaOrmRepository::new(connection);
+
+    let source = create_test_stream_source(&source_repo).await;
+    let ingested_channel = |name: &str| {
+        let now = chrono::Utc::now();
+        Channel {
+            id: Uuid::new_v4(),
+            source_id: source.id,
+            tvg_id: None,
+            tvg_name: None,
+            tvg_chno: None,
+            tvg_logo: None,
+            tvg_shift: None,
+            epg_shift: None,
+            group_title: None,
+            channel_name: name.to_string(),
+            stream_url: format!("http://example.com/{name}"),
+            video_codec: None,
+            audio_codec: None,
+            resolution: None,
+            probe_method: None,
+            last_probed_at: None,
+            created_at: now,
+            updated_at: now,
+            extra_attributes: Default::default(),
+        }
+    };
+    let news = ingested_channel("news");
+    let sport = ingested_channel("sport");
+
+    assert_eq!(
+        retention_repo
+            .get(&source.id)
+            .await
+            .unwrap()
+            .max_missed_ingestions,
+        0
+    );
+    retention_repo.set(&source.id, 2).await.unwrap();
+
+    let changes = channel_repo
+        .update_source_channels(source.id, &[news.clone(), sport.clone()])
+        .await
+        .unwrap();
+    assert_eq!(changes.added, 2);
+
+    // The provider drops "sport" for two ingestions: it is retained and reported stale
+    for missed in 1..=2 {
+        let changes = channel_repo
+            .update_source_channels(source.id, std::slice::from_ref(&news))
+            .await
+            .unwrap();
+        assert_eq!((changes.updated, changes.removed), (1, 0));
+        assert_eq!(
+            channel_repo
+                .find_by_source_id(&source.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        let stale = channel_repo
+            .find_stale_channels(&[news.id, sport.id])
+            .await
+            .unwrap();
+        assert_eq!(stale.get(&sport.id), Some(&missed));
+        assert!(!stale.contains_key(&news.id));
+    }
+
+    // A third absence exceeds the grace period and purges it
+    let changes = channel_repo
+        .update_source_channels(source.id, std::slice::from_ref(&news))
+        .await
+        .unwrap();
+    assert_eq!(changes.removed, 1);
+    let remaining = channel_repo.find_by_source_id(&source.id).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, news.id);
+
+    // Without retention missing channels are removed at once
+    retention_repo.set(&source.id, 0).await.unwrap();
+    channel_repo
+        .update_source_channels(source.id, &[news.clone(), sport.clone()])
+        .await
+        .unwrap();
+    channel_repo
+        .update_source_channels(source.id, std::slice::from_ref(&sport))
+        .await
+        .unwrap();
+    let remaining = channel_repo.find_by_source_id(&source.id).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, sport.id);
+}
+
 #[tokio::test]
 async fn test_epg_source_repository_basic_operations() {
     let (_db, connection) = create_test_database().await;
@@ -89,7 +89,8 @@ async fn test_database_comprehensive(database_url: &str) -> Result<()> {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
                 last_ingested_at TEXT,
-                is_active INTEGER NOT NULL DEFAULT 1
+                is_active INTEGER NOT NULL DEFAULT 1,
+                deleted_at TEXT
             );
             CREATE TABLE channels (
                 id TEXT PRIMARY KEY,
@@ -103,7 +104,9 @@ async fn test_database_comprehensive(database_url: &str) -> Result<()> {
                 group_title TEXT,
                 stream_url TEXT NOT NULL,
                 created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                missed_ingestions INTEGER NOT NULL DEFAULT 0,
+                extra_attributes TEXT
             );
             CREATE TABLE epg_sources (
                 id TEXT PRIMARY KEY,
@@ -118,7 +121,8 @@ async fn test_database_comprehensive(database_url: &str) -> Result<()> {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
                 last_ingested_at TEXT,
-                is_active INTEGER NOT NULL DEFAULT 1
+                is_active INTEGER NOT NULL DEFAULT 1,
+                deleted_at TEXT
             );
             "#
                 .to_string(),
@@ -265,7 +269,8 @@ async fn test_seaorm_migration_system() -> Result<()> {
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL,
             last_ingested_at TEXT,
-            is_active INTEGER NOT NULL DEFAULT 1
+            is_active INTEGER NOT NULL DEFAULT 1,
+            deleted_at TEXT
         );
         "#
             .to_string(),
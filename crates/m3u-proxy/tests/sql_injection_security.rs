@@ -87,7 +87,8 @@ impl TestDatabaseConfig {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
                 last_ingested_at TEXT,
-                is_active INTEGER NOT NULL DEFAULT 1
+                is_active INTEGER NOT NULL DEFAULT 1,
+                deleted_at TEXT
             );
             "#
                 .to_string(),
@@ -309,6 +310,7 @@ async fn test_seaorm_entity_queries_prevent_sql_injection() -> Result<()> {
             updated_at: Set(chrono::Utc::now()),
             last_ingested_at: Set(None),
             is_active: Set(true),
+            deleted_at: Set(None),
         };
 
         let insert_result = active_model.insert(connection.as_ref()).await;
@@ -367,6 +369,7 @@ async fn test_seaorm_like_queries_prevent_sql_injection() -> Result<()> {
             updated_at: Set(chrono::Utc::now()),
             last_ingested_at: Set(None),
             is_active: Set(true),
+            deleted_at: Set(None),
         };
         active_model.insert(connection.as_ref()).await?;
     }
@@ -469,6 +472,7 @@ async fn test_seaorm_order_by_injection_prevention() -> Result<()> {
             updated_at: Set(chrono::Utc::now()),
             last_ingested_at: Set(None),
             is_active: Set(true),
+            deleted_at: Set(None),
         };
         active_model.insert(connection.as_ref()).await?;
     }
@@ -765,6 +769,7 @@ async fn test_seaorm_transaction_safety_with_malicious_input() -> Result<()> {
                     updated_at: Set(chrono::Utc::now()),
                     last_ingested_at: Set(None),
                     is_active: Set(true),
+                    deleted_at: Set(None),
                 };
                 normal_active_model.insert(tx).await?;
 
@@ -785,6 +790,7 @@ async fn test_seaorm_transaction_safety_with_malicious_input() -> Result<()> {
                     updated_at: Set(chrono::Utc::now()),
                     last_ingested_at: Set(None),
                     is_active: Set(true),
+                    deleted_at: Set(None),
                 };
 
                 // This should succeed (malicious data stored safely) or fail (validation/constraints)
@@ -887,6 +893,7 @@ async fn test_seaorm_transaction_safety_with_malicious_input() -> Result<()> {
                         updated_at: Set(chrono::Utc::now()),
                         last_ingested_at: Set(None),
                         is_active: Set(true),
+                        deleted_at: Set(None),
                     };
 
                     let insert_result = active_model.insert(tx).await;
@@ -1157,6 +1164,7 @@ async fn test_seaorm_mock_database_security_patterns() -> Result<()> {
                 updated_at: chrono::Utc::now(),
                 last_ingested_at: None,
                 is_active: true,
+                deleted_at: None,
             }],
         ])
         .append_exec_results([
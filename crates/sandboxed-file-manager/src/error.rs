@@ -39,6 +39,21 @@ pub enum SandboxedFileError {
     #[error("Permission denied: {operation} on {path:?}")]
     Permission { operation: String, path: PathBuf },
 
+    /// Object storage request failed
+    #[error("Object storage error for '{key}': {message}")]
+    ObjectStorage { key: String, message: String },
+
+    /// A write would take a top-level directory over its quota
+    #[error(
+        "Quota exceeded for '{directory}/': {would_use} {limit} would exceed the limit of {max}"
+    )]
+    QuotaExceeded {
+        directory: String,
+        limit: crate::quota::QuotaLimit,
+        max: u64,
+        would_use: u64,
+    },
+
     /// Configuration error
     #[error("Configuration error: {message}")]
     Configuration { message: String },
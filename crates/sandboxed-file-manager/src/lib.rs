@@ -23,6 +23,10 @@
 //! - **Configurable Retention**: File cleanup based on atime, mtime, or ctime
 //! - **Automatic Cleanup**: Background cleanup with configurable intervals
 //! - **Security First**: Symlink validation and path sanitization
+//! - **Object Storage**: Optional S3-compatible backend (`s3` feature) for stateless deployments
+//! - **Directory Quotas**: Byte and file-count limits per top-level subdirectory
+//! - **Directory Listings**: `list_dir`/`walk` with sizes, timestamps, detected types, glob
+//!   filters and pagination
 //!
 //! ## Basic Usage
 //!
@@ -197,16 +201,22 @@
 
 pub mod error;
 pub mod file_types;
+pub mod listing;
 pub mod manager;
+pub mod object_storage;
 pub mod policy;
+pub mod quota;
 pub mod security;
 
 pub use error::{Result, SandboxedFileError};
 pub use file_types::{
     DetectionMethod, FileTypeConfig, FileTypeConfigBuilder, FileTypeInfo, FileTypeValidator,
 };
-pub use manager::{FileInfo, SandboxedManager, SandboxedManagerBuilder};
+pub use listing::{DirEntryInfo, DirListing, EntryKind, ListOptions};
+pub use manager::{DiskUsage, FileInfo, FileStat, SandboxedManager, SandboxedManagerBuilder};
+pub use object_storage::ObjectStorageConfig;
 pub use policy::{CleanupPolicy, TimeMatch};
+pub use quota::{DirectoryQuota, QuotaLimit, QuotaUsage};
 
 // Re-export commonly used types
 pub use std::time::Duration;
@@ -0,0 +1,326 @@
+//! Directory listings with metadata, filters and pagination.
+//!
+//! Listings are produced by [`SandboxedManager::list_dir`](crate::SandboxedManager::list_dir)
+//! and [`SandboxedManager::walk`](crate::SandboxedManager::walk). Entries are sorted by path
+//! so that `offset`/`limit` pages stay stable between calls while the directory is unchanged.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Whether a listed entry is a file or a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    File,
+    Directory,
+}
+
+/// A file or directory within the sandbox.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirEntryInfo {
+    /// Path relative to the sandbox root, `/`-separated
+    pub path: String,
+    /// Final path component
+    pub name: String,
+    pub kind: EntryKind,
+    /// Size in bytes (0 for directories)
+    pub size_bytes: u64,
+    pub modified: Option<DateTime<Utc>>,
+    /// Creation time, where the filesystem records one
+    pub created: Option<DateTime<Utc>>,
+    /// Last access time, where the filesystem records one
+    pub accessed: Option<DateTime<Utc>>,
+    /// MIME type detected from the file's magic number, when requested and recognised
+    pub content_type: Option<String>,
+}
+
+/// A page of directory entries.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirListing {
+    pub entries: Vec<DirEntryInfo>,
+    /// Entries matching the filters, across all pages
+    pub total: usize,
+    pub offset: usize,
+    /// Offset of the next page, if there are more entries
+    pub next_offset: Option<usize>,
+}
+
+/// Filters and pagination for a directory listing.
+///
+/// ```rust
+/// use sandboxed_file_manager::ListOptions;
+///
+/// let options = ListOptions::new()
+///     .glob("**/*.png")
+///     .min_size(1024)
+///     .detect_types(true)
+///     .limit(50);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    glob: Option<Glob>,
+    include_dirs: bool,
+    detect_types: bool,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    modified_after: Option<DateTime<Utc>>,
+    modified_before: Option<DateTime<Utc>>,
+    offset: usize,
+    limit: Option<usize>,
+}
+
+impl ListOptions {
+    /// Files only, unfiltered and unpaginated.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list entries whose path relative to the listed directory matches `pattern`.
+    ///
+    /// `*` matches within one path component, `**` across components and `?` one
+    /// character; a pattern without `/` is matched against the entry name alone.
+    #[must_use]
+    pub fn glob(mut self, pattern: impl Into<String>) -> Self {
+        self.glob = Some(Glob::new(pattern.into()));
+        self
+    }
+
+    /// Also list directories. Object storage has no directories, so none are listed there.
+    #[must_use]
+    pub fn include_dirs(mut self, include: bool) -> Self {
+        self.include_dirs = include;
+        self
+    }
+
+    /// Detect the content type of listed files from their magic numbers.
+    ///
+    /// This reads the start of every listed file (the whole object on object storage), so
+    /// it is applied after pagination and only to the returned page.
+    #[must_use]
+    pub fn detect_types(mut self, detect: bool) -> Self {
+        self.detect_types = detect;
+        self
+    }
+
+    /// Only list files of at least `bytes`.
+    #[must_use]
+    pub fn min_size(mut self, bytes: u64) -> Self {
+        self.min_size = Some(bytes);
+        self
+    }
+
+    /// Only list files of at most `bytes`.
+    #[must_use]
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Only list entries modified after `time`.
+    #[must_use]
+    pub fn modified_after(mut self, time: DateTime<Utc>) -> Self {
+        self.modified_after = Some(time);
+        self
+    }
+
+    /// Only list entries modified before `time`.
+    #[must_use]
+    pub fn modified_before(mut self, time: DateTime<Utc>) -> Self {
+        self.modified_before = Some(time);
+        self
+    }
+
+    /// Skip the first `offset` matching entries.
+    #[must_use]
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Return at most `limit` entries.
+    #[must_use]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub(crate) fn detects_types(&self) -> bool {
+        self.detect_types
+    }
+
+    /// Whether an entry passes the filters; `relative` is its path below the listed directory
+    pub(crate) fn matches(&self, entry: &DirEntryInfo, relative: &str) -> bool {
+        if entry.kind == EntryKind::Directory && !self.include_dirs {
+            return false;
+        }
+        if let Some(glob) = &self.glob {
+            let subject = if glob.pattern.contains('/') {
+                relative
+            } else {
+                &entry.name
+            };
+            if !glob.matches(subject) {
+                return false;
+            }
+        }
+        if entry.kind == EntryKind::File
+            && (self.min_size.is_some_and(|min| entry.size_bytes < min)
+                || self.max_size.is_some_and(|max| entry.size_bytes > max))
+        {
+            return false;
+        }
+        if self.modified_after.is_some() || self.modified_before.is_some() {
+            let Some(modified) = entry.modified else {
+                return false;
+            };
+            if self.modified_after.is_some_and(|after| modified <= after)
+                || self
+                    .modified_before
+                    .is_some_and(|before| modified >= before)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Sort matching entries by path and cut out the requested page
+    pub(crate) fn paginate(&self, mut entries: Vec<DirEntryInfo>) -> DirListing {
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let total = entries.len();
+        let end = self
+            .limit
+            .map_or(total, |limit| self.offset.saturating_add(limit).min(total));
+        let page = if self.offset < end {
+            entries.drain(self.offset..end).collect()
+        } else {
+            Vec::new()
+        };
+
+        DirListing {
+            entries: page,
+            total,
+            offset: self.offset,
+            next_offset: (end < total).then_some(end),
+        }
+    }
+}
+
+/// Glob pattern over `/`-separated paths.
+#[derive(Debug, Clone)]
+struct Glob {
+    pattern: String,
+}
+
+impl Glob {
+    fn new(pattern: String) -> Self {
+        Self { pattern }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        let pattern: Vec<&str> = self.pattern.split('/').collect();
+        let path: Vec<&str> = path.split('/').collect();
+        match_components(&pattern, &path)
+    }
+}
+
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        // `**` matches any number of components, including none
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_components(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(component, path_rest)| {
+            match_component(first.as_bytes(), component.as_bytes())
+                && match_components(rest, path_rest)
+        }),
+    }
+}
+
+/// Match one path component against a pattern component with `*` and `?` wildcards
+fn match_component(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it has consumed up to
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(b'?') => {
+                p += 1;
+                t += 1;
+            }
+            Some(&c) if c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size_bytes: u64) -> DirEntryInfo {
+        DirEntryInfo {
+            path: path.to_string(),
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            kind: EntryKind::File,
+            size_bytes,
+            modified: None,
+            created: None,
+            accessed: None,
+            content_type: None,
+        }
+    }
+
+    #[test]
+    fn test_glob_matching() {
+        let glob = |pattern: &str, path: &str| Glob::new(pattern.to_string()).matches(path);
+        assert!(glob("*.png", "logo.png"));
+        assert!(!glob("*.png", "logo.svg"));
+        assert!(glob("logo-??.png", "logo-uk.png"));
+        assert!(glob("cached/*/*.png", "cached/ab/logo.png"));
+        assert!(!glob("cached/*.png", "cached/ab/logo.png"));
+        assert!(glob("**/*.png", "logo.png"));
+        assert!(glob("**/*.png", "cached/ab/logo.png"));
+        assert!(glob("cached/**", "cached/ab/logo.png"));
+        assert!(glob("a*b*c", "aXbYbZc"));
+    }
+
+    #[test]
+    fn test_filters_and_pagination() {
+        let options = ListOptions::new().glob("*.png").min_size(10).limit(2);
+        let entries: Vec<_> = [
+            file("d.png", 40),
+            file("a.png", 10),
+            file("small.png", 1),
+            file("b.svg", 20),
+            file("c.png", 30),
+        ]
+        .into_iter()
+        .filter(|entry| options.matches(entry, &entry.path.clone()))
+        .collect();
+
+        let page = options.paginate(entries.clone());
+        let paths: Vec<_> = page.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["a.png", "c.png"]);
+        assert_eq!((page.total, page.next_offset), (3, Some(2)));
+
+        let last = options.clone().offset(2).paginate(entries);
+        assert_eq!(last.entries.len(), 1);
+        assert_eq!(last.next_offset, None);
+    }
+}
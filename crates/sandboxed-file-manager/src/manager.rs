@@ -3,7 +3,10 @@
 use crate::{
     error::{Result, SandboxedFileError},
     file_types::{FileTypeInfo, FileTypeValidator},
+    listing::{DirEntryInfo, DirListing, EntryKind, ListOptions},
+    object_storage::{ObjectStorage, ObjectStorageConfig, object_key},
     policy::CleanupPolicy,
+    quota::{DEFAULT_QUOTA_RESCAN_INTERVAL, DirectoryQuota, QuotaTracker, QuotaUsage, UsageDelta},
     security::set_secure_permissions,
 };
 
@@ -18,6 +21,9 @@ use std::{
 };
 use tokio::{fs, sync::RwLock, time::interval};
 
+/// Bytes read from the start of a file to detect its content type in listings.
+const CONTENT_TYPE_DETECTION_BYTES: usize = 8192;
+
 /// Information about a managed file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileInfo {
@@ -38,6 +44,26 @@ pub struct ManagerStats {
     pub base_directory: PathBuf,
 }
 
+/// Disk usage of everything under the sandbox root, from a filesystem scan.
+///
+/// Unlike [`ManagerStats`], which reflects the in-memory registry, this includes files in
+/// subdirectories and files written by other processes.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsage {
+    pub total_files: usize,
+    pub total_size_bytes: u64,
+    pub oldest_modified: Option<DateTime<Utc>>,
+    pub newest_modified: Option<DateTime<Utc>>,
+    pub scanned_at: DateTime<Utc>,
+}
+
+/// Size and modification time of a file, available from every storage backend.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStat {
+    pub size_bytes: u64,
+    pub modified: DateTime<Utc>,
+}
+
 /// Internal snapshot entry used for two‑phase cleanup evaluation (Phase 1 snapshot).
 #[derive(Debug, Clone)]
 struct SnapshotEntry {
@@ -66,6 +92,13 @@ pub struct SandboxedManager {
     cleanup_policy: CleanupPolicy,
     cleanup_interval: Duration,
     cleanup_suspension: Arc<RwLock<Option<std::time::Instant>>>,
+    usage_cache: Arc<RwLock<Option<(std::time::Instant, DiskUsage)>>>,
+    /// Object storage backend; files live in a bucket instead of under `base_dir`
+    object_storage: Option<ObjectStorage>,
+    /// Expiry of objects is handled by bucket lifecycle rules, not the cleanup task
+    bucket_lifecycle: bool,
+    /// Quotas of top-level subdirectories and their tracked usage
+    quotas: Arc<QuotaTracker>,
 }
 
 impl SandboxedManager {
@@ -80,12 +113,28 @@ impl SandboxedManager {
     /// # Errors
     /// Returns an error if:
     /// - The path is invalid or escapes the sandbox
+    /// - The write would exceed the quota of its top-level directory
     /// - The underlying write operation fails
     pub async fn write<P: AsRef<str>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> Result<()> {
         let path_str = path.as_ref();
+        let contents = contents.as_ref();
+        let reservation = self
+            .reserve_write(path_str, contents.len() as u64, false)
+            .await?;
+        let result = self.write_unchecked(path_str, contents).await;
+        if result.is_err() {
+            self.release_quota(reservation).await;
+        }
+        result
+    }
+
+    async fn write_unchecked(&self, path_str: &str, contents: &[u8]) -> Result<()> {
+        if let Some(storage) = &self.object_storage {
+            return storage.put(&object_key(path_str)?, contents.to_vec()).await;
+        }
         let file_path = self.validate_and_get_path(path_str)?;
 
-        fs::write(&file_path, contents.as_ref()).await?;
+        fs::write(&file_path, contents).await?;
 
         // Update registry for tracking
         let file_info = FileInfo {
@@ -93,7 +142,7 @@ impl SandboxedManager {
             file_path: file_path.clone(),
             created_at: Utc::now(),
             last_accessed: Utc::now(),
-            size_bytes: contents.as_ref().len() as u64,
+            size_bytes: contents.len() as u64,
             content_type: "application/octet-stream".to_string(),
             original_name: Some(path_str.to_string()),
         };
@@ -114,6 +163,9 @@ impl SandboxedManager {
     /// - The file cannot be opened or read
     pub async fn read<P: AsRef<str>>(&self, path: P) -> Result<Vec<u8>> {
         let path_str = path.as_ref();
+        if let Some(storage) = &self.object_storage {
+            return storage.get(&object_key(path_str)?).await;
+        }
         let file_path = self.validate_and_get_path(path_str)?;
 
         // Update access time in registry
@@ -133,6 +185,12 @@ impl SandboxedManager {
     /// - The file cannot be opened or read as UTF-8 text
     pub async fn read_to_string<P: AsRef<str>>(&self, path: P) -> Result<String> {
         let path_str = path.as_ref();
+        if let Some(storage) = &self.object_storage {
+            let content = storage.get(&object_key(path_str)?).await?;
+            return String::from_utf8(content).map_err(|e| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()).into()
+            });
+        }
         let file_path = self.validate_and_get_path(path_str)?;
 
         // Update access time in registry
@@ -150,12 +208,24 @@ impl SandboxedManager {
     /// # Errors
     /// Returns an error if:
     /// - The path is invalid / escapes the sandbox
+    /// - Its top-level directory is at its file-count quota or already full
     /// - Parent directories cannot be created or the file cannot be created
+    ///
+    /// Bytes later written through the returned handle count towards a directory quota
+    /// once the directory is rescanned.
     pub async fn create<P: AsRef<str>>(&self, path: P) -> Result<fs::File> {
         let path_str = path.as_ref();
+        self.require_local("create")?;
         let file_path = self.validate_and_get_path(path_str)?;
 
-        let file = fs::File::create(&file_path).await?;
+        let reservation = self.reserve_write(path_str, 0, true).await?;
+        let file = match fs::File::create(&file_path).await {
+            Ok(file) => file,
+            Err(e) => {
+                self.release_quota(reservation).await;
+                return Err(e.into());
+            }
+        };
 
         // Update registry
         let file_info = FileInfo {
@@ -184,6 +254,7 @@ impl SandboxedManager {
     /// - The file cannot be opened
     pub async fn open<P: AsRef<str>>(&self, path: P) -> Result<fs::File> {
         let path_str = path.as_ref();
+        self.require_local("open")?;
         let file_path = self.validate_and_get_path(path_str)?;
 
         // Update access time in registry
@@ -203,6 +274,23 @@ impl SandboxedManager {
     /// - The underlying file removal fails
     pub async fn remove_file<P: AsRef<str>>(&self, path: P) -> Result<()> {
         let path_str = path.as_ref();
+        let removed = self.quota_removal(path_str).await?;
+        self.remove_file_unchecked(path_str).await?;
+        if let Some((directory, delta)) = removed {
+            self.quotas.record(&directory, delta).await;
+        }
+        Ok(())
+    }
+
+    async fn remove_file_unchecked(&self, path_str: &str) -> Result<()> {
+        if let Some(storage) = &self.object_storage {
+            let key = object_key(path_str)?;
+            // Deleting a missing object succeeds on S3; keep `std::fs` semantics
+            if storage.head(&key).await?.is_none() {
+                return Err(not_found(path_str));
+            }
+            return storage.delete(&key).await;
+        }
         let file_path = self.validate_and_get_path(path_str)?;
 
         fs::remove_file(&file_path).await?;
@@ -221,12 +309,44 @@ impl SandboxedManager {
     /// - Metadata cannot be retrieved
     pub async fn metadata<P: AsRef<str>>(&self, path: P) -> Result<std::fs::Metadata> {
         let path_str = path.as_ref();
+        self.require_local("metadata")?;
         let file_path = self.validate_and_get_path(path_str)?;
 
         let metadata = fs::metadata(&file_path).await?;
         Ok(metadata)
     }
 
+    /// Size and modification time of a file, on any storage backend.
+    ///
+    /// Prefer this over [`Self::metadata`] in code that may run against object storage.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - Path validation fails
+    /// - The path does not exist or is not a file
+    pub async fn stat<P: AsRef<str>>(&self, path: P) -> Result<FileStat> {
+        let path_str = path.as_ref();
+        if let Some(storage) = &self.object_storage {
+            let info = storage
+                .head(&object_key(path_str)?)
+                .await?
+                .ok_or_else(|| not_found(path_str))?;
+            return Ok(FileStat {
+                size_bytes: info.size_bytes,
+                modified: info.last_modified,
+            });
+        }
+
+        let metadata = fs::metadata(self.validate_and_get_path(path_str)?).await?;
+        if !metadata.is_file() {
+            return Err(not_found(path_str));
+        }
+        Ok(FileStat {
+            size_bytes: metadata.len(),
+            modified: DateTime::from(metadata.modified().unwrap_or(std::time::UNIX_EPOCH)),
+        })
+    }
+
     /// Sandboxed version of `std::fs::create_dir` - creates a directory within the sandbox.
     ///
     /// # Errors
@@ -235,6 +355,11 @@ impl SandboxedManager {
     /// - Directory creation fails
     pub async fn create_dir<P: AsRef<str>>(&self, path: P) -> Result<()> {
         let path_str = path.as_ref();
+        if self.object_storage.is_some() {
+            // Object stores have no directories, only key prefixes
+            object_key(path_str)?;
+            return Ok(());
+        }
         let dir_path = self.validate_and_get_path(path_str)?;
 
         fs::create_dir(&dir_path).await?;
@@ -249,6 +374,11 @@ impl SandboxedManager {
     /// - Recursive directory creation fails
     pub async fn create_dir_all<P: AsRef<str>>(&self, path: P) -> Result<()> {
         let path_str = path.as_ref();
+        if self.object_storage.is_some() {
+            // Object stores have no directories, only key prefixes
+            object_key(path_str)?;
+            return Ok(());
+        }
         let dir_path = self.validate_and_get_path(path_str)?;
 
         fs::create_dir_all(&dir_path).await?;
@@ -263,6 +393,11 @@ impl SandboxedManager {
     /// - Directory removal fails
     pub async fn remove_dir<P: AsRef<str>>(&self, path: P) -> Result<()> {
         let path_str = path.as_ref();
+        if self.object_storage.is_some() {
+            // Object stores have no directories, only key prefixes
+            object_key(path_str)?;
+            return Ok(());
+        }
         let dir_path = self.validate_and_get_path(path_str)?;
 
         fs::remove_dir(&dir_path).await?;
@@ -277,9 +412,15 @@ impl SandboxedManager {
     /// - Recursive removal fails
     pub async fn remove_dir_all<P: AsRef<str>>(&self, path: P) -> Result<()> {
         let path_str = path.as_ref();
+        if let Some(storage) = &self.object_storage {
+            storage.delete_all(&object_key(path_str)?).await?;
+            self.quotas.invalidate_all().await;
+            return Ok(());
+        }
         let dir_path = self.validate_and_get_path(path_str)?;
 
         fs::remove_dir_all(&dir_path).await?;
+        self.quotas.invalidate_all().await;
 
         // Remove all files in this directory from registry
         let prefix = format!("{path_str}/");
@@ -296,10 +437,35 @@ impl SandboxedManager {
     /// # Errors
     /// Returns an error if:
     /// - Either source or destination path is invalid
+    /// - The copy would exceed the quota of the destination's top-level directory
     /// - The underlying copy operation fails
     pub async fn copy<P: AsRef<str>, Q: AsRef<str>>(&self, from: P, to: Q) -> Result<u64> {
         let from_str = from.as_ref();
         let to_str = to.as_ref();
+        let reservation = if self.quotas.is_empty() {
+            None
+        } else {
+            let size = self.stat(from_str).await?.size_bytes;
+            self.reserve_write(to_str, size, false).await?
+        };
+        let result = self.copy_unchecked(from_str, to_str).await;
+        if result.is_err() {
+            self.release_quota(reservation).await;
+        }
+        result
+    }
+
+    async fn copy_unchecked(&self, from_str: &str, to_str: &str) -> Result<u64> {
+        if let Some(storage) = &self.object_storage {
+            let from_key = object_key(from_str)?;
+            let size = storage
+                .head(&from_key)
+                .await?
+                .ok_or_else(|| not_found(from_str))?
+                .size_bytes;
+            storage.copy(&from_key, &object_key(to_str)?).await?;
+            return Ok(size);
+        }
         let from_path = self.validate_and_get_path(from_str)?;
         let to_path = self.validate_and_get_path(to_str)?;
 
@@ -329,6 +495,157 @@ impl SandboxedManager {
         Ok(bytes_copied)
     }
 
+    /// Rename a file within the sandbox - equivalent to `std::fs::rename`.
+    ///
+    /// Replaces `to` atomically if it exists, so readers see either the old or the new file.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - Either source or destination path is invalid
+    /// - The move would exceed the quota of the destination's top-level directory
+    /// - The underlying rename operation fails
+    pub async fn rename<P: AsRef<str>, Q: AsRef<str>>(&self, from: P, to: Q) -> Result<()> {
+        let from_str = from.as_ref();
+        let to_str = to.as_ref();
+        if self.quotas.is_empty() {
+            return self.rename_unchecked(from_str, to_str).await;
+        }
+
+        let from_dir = self.quota_directory(from_str).await?;
+        let to_dir = self.quota_directory(to_str).await?;
+        if from_dir.is_some() && from_dir == to_dir {
+            // Moving within a directory only frees the space of a replaced file
+            let replaced = self.quota_removal(to_str).await?;
+            self.rename_unchecked(from_str, to_str).await?;
+            if let Some((directory, delta)) = replaced {
+                self.quotas.record(&directory, delta).await;
+            }
+            return Ok(());
+        }
+
+        let size = self.stat(from_str).await?.size_bytes;
+        let reservation = self.reserve_write(to_str, size, false).await?;
+        if let Err(e) = self.rename_unchecked(from_str, to_str).await {
+            self.release_quota(reservation).await;
+            return Err(e);
+        }
+        if let Some(directory) = from_dir {
+            self.quotas.record(&directory, removal_delta(size)).await;
+        }
+        Ok(())
+    }
+
+    async fn rename_unchecked(&self, from_str: &str, to_str: &str) -> Result<()> {
+        if let Some(storage) = &self.object_storage {
+            // A server-side copy then delete: readers may briefly see both objects
+            return storage
+                .rename(&object_key(from_str)?, &object_key(to_str)?)
+                .await;
+        }
+        let from_path = self.validate_and_get_path(from_str)?;
+        let to_path = self.validate_and_get_path(to_str)?;
+
+        fs::rename(&from_path, &to_path).await?;
+
+        let mut registry = self.file_registry.write().await;
+        if let Some(mut info) = registry.remove(from_str) {
+            info.id = to_str.to_string();
+            info.file_path = to_path;
+            info.original_name = Some(to_str.to_string());
+            registry.insert(to_str.to_string(), info);
+        } else {
+            registry.remove(to_str);
+        }
+
+        Ok(())
+    }
+
+    /// Usage of each quota-limited directory against its quota, sorted by directory.
+    ///
+    /// Directories whose usage is older than the rescan interval are rescanned first.
+    ///
+    /// # Errors
+    /// Returns an error if a directory cannot be scanned.
+    pub async fn quota_usage(&self) -> Result<Vec<QuotaUsage>> {
+        let mut directories: Vec<String> = self.quotas.directories().map(String::from).collect();
+        directories.sort();
+
+        let mut usage = Vec::with_capacity(directories.len());
+        for directory in directories {
+            self.refresh_quota_usage(&directory).await?;
+            usage.extend(self.quotas.usage(&directory).await);
+        }
+        Ok(usage)
+    }
+
+    /// Quota-limited top-level directory of `path`, its usage rescanned if stale
+    async fn quota_directory(&self, path: &str) -> Result<Option<String>> {
+        if self.quotas.is_empty() {
+            return Ok(None);
+        }
+        let key = object_key(path)?;
+        let Some(directory) = self.quotas.directory_of(&key).map(String::from) else {
+            return Ok(None);
+        };
+        self.refresh_quota_usage(&directory).await?;
+        Ok(Some(directory))
+    }
+
+    async fn refresh_quota_usage(&self, directory: &str) -> Result<()> {
+        if !self.quotas.needs_scan(directory).await {
+            return Ok(());
+        }
+        let listing = self.walk(directory, &ListOptions::new()).await?;
+        let bytes = listing.entries.iter().map(|entry| entry.size_bytes).sum();
+        self.quotas
+            .set_scanned(directory, bytes, listing.total as u64)
+            .await;
+        Ok(())
+    }
+
+    /// Reserve quota for writing `bytes` to `path`, replacing any file already there.
+    ///
+    /// An `open_ended` write (a created file that is written to later) is also refused
+    /// when the directory's byte quota is already used up.
+    async fn reserve_write(
+        &self,
+        path: &str,
+        bytes: u64,
+        open_ended: bool,
+    ) -> Result<Option<(String, UsageDelta)>> {
+        let Some(directory) = self.quota_directory(path).await? else {
+            return Ok(None);
+        };
+        let bytes = i64::try_from(bytes).unwrap_or(i64::MAX);
+        let delta = match self.stat(path).await {
+            Ok(existing) => UsageDelta {
+                bytes: bytes - i64::try_from(existing.size_bytes).unwrap_or(i64::MAX),
+                files: 0,
+            },
+            Err(_) => UsageDelta { bytes, files: 1 },
+        };
+        self.quotas.reserve(&directory, delta, open_ended).await?;
+        Ok(Some((directory, delta)))
+    }
+
+    async fn release_quota(&self, reservation: Option<(String, UsageDelta)>) {
+        if let Some((directory, delta)) = reservation {
+            self.quotas.release(&directory, delta).await;
+        }
+    }
+
+    /// Usage freed by removing the file at `path`, if it is in a quota-limited directory
+    async fn quota_removal(&self, path: &str) -> Result<Option<(String, UsageDelta)>> {
+        let Some(directory) = self.quota_directory(path).await? else {
+            return Ok(None);
+        };
+        Ok(self
+            .stat(path)
+            .await
+            .ok()
+            .map(|existing| (directory, removal_delta(existing.size_bytes))))
+    }
+
     /// Get file information from the manager's registry.
     ///
     /// # Errors
@@ -355,6 +672,109 @@ impl SandboxedManager {
         }
     }
 
+    /// Root directory of the sandbox; an `s3://bucket/prefix` location for object storage.
+    #[must_use]
+    pub fn base_directory(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// Whether files are kept on the local filesystem rather than in object storage.
+    ///
+    /// Only local sandboxes support [`Self::open`], [`Self::create`], [`Self::metadata`]
+    /// and [`Self::get_full_path`].
+    #[must_use]
+    pub const fn is_local(&self) -> bool {
+        self.object_storage.is_none()
+    }
+
+    /// The cleanup policy files in this sandbox are subject to.
+    #[must_use]
+    pub const fn cleanup_policy(&self) -> &CleanupPolicy {
+        &self.cleanup_policy
+    }
+
+    /// How often the background cleanup task runs (zero when it does not run).
+    #[must_use]
+    pub fn cleanup_interval(&self) -> Duration {
+        if self.cleanup_policy.infinite_retention {
+            Duration::ZERO
+        } else {
+            self.cleanup_interval
+        }
+    }
+
+    /// Disk usage of the sandbox, rescanned at most once per `max_age`.
+    ///
+    /// Scans walk the whole tree under the base directory, so callers polling usage
+    /// should pass a `max_age` long enough to amortise the scan.
+    ///
+    /// # Errors
+    /// Returns an error if the base directory cannot be read.
+    pub async fn disk_usage(&self, max_age: Duration) -> Result<DiskUsage> {
+        if let Some((scanned, usage)) = self.usage_cache.read().await.as_ref()
+            && scanned.elapsed() < max_age
+        {
+            return Ok(usage.clone());
+        }
+
+        let usage = self.scan_disk_usage().await?;
+        *self.usage_cache.write().await = Some((std::time::Instant::now(), usage.clone()));
+        Ok(usage)
+    }
+
+    async fn scan_disk_usage(&self) -> Result<DiskUsage> {
+        let mut usage = DiskUsage {
+            total_files: 0,
+            total_size_bytes: 0,
+            oldest_modified: None,
+            newest_modified: None,
+            scanned_at: Utc::now(),
+        };
+
+        if let Some(storage) = &self.object_storage {
+            for object in storage.list("", true).await? {
+                usage.total_files += 1;
+                usage.total_size_bytes += object.size_bytes;
+                let modified = object.last_modified;
+                usage.oldest_modified =
+                    Some(usage.oldest_modified.map_or(modified, |t| t.min(modified)));
+                usage.newest_modified =
+                    Some(usage.newest_modified.map_or(modified, |t| t.max(modified)));
+            }
+            return Ok(usage);
+        }
+
+        let mut pending = vec![self.base_dir.clone()];
+        while let Some(dir) = pending.pop() {
+            let mut entries = match fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                // The base directory must be readable; subdirectories may vanish mid-scan
+                Err(e) if dir == self.base_dir => return Err(e.into()),
+                Err(_) => continue,
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let Ok(metadata) = entry.metadata().await else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    pending.push(entry.path());
+                } else if metadata.is_file() {
+                    usage.total_files += 1;
+                    usage.total_size_bytes += metadata.len();
+                    if let Ok(modified) = metadata.modified() {
+                        let modified = DateTime::<Utc>::from(modified);
+                        usage.oldest_modified =
+                            Some(usage.oldest_modified.map_or(modified, |t| t.min(modified)));
+                        usage.newest_modified =
+                            Some(usage.newest_modified.map_or(modified, |t| t.max(modified)));
+                    }
+                }
+            }
+        }
+
+        Ok(usage)
+    }
+
     /// Validate file type using magic number detection with sandbox security checks.
     ///
     /// # Errors
@@ -368,6 +788,10 @@ impl SandboxedManager {
         validator: &FileTypeValidator,
     ) -> Result<FileTypeInfo> {
         let path_str = path.as_ref();
+        if let Some(storage) = &self.object_storage {
+            let content = storage.get(&object_key(path_str)?).await?;
+            return validator.validate_from_bytes(&content, Path::new(path_str));
+        }
         let file_path = self.validate_and_get_path(path_str)?;
 
         validator
@@ -375,6 +799,16 @@ impl SandboxedManager {
             .await
     }
 
+    /// Fail operations that hand out local files or paths when backed by object storage.
+    fn require_local(&self, operation: &str) -> Result<()> {
+        if self.object_storage.is_some() {
+            return Err(SandboxedFileError::Configuration {
+                message: format!("`{operation}` is not supported by object storage sandboxes"),
+            });
+        }
+        Ok(())
+    }
+
     /// Validate a filepath and construct the full path within the sandbox.
     ///
     /// Uses OS syscalls to properly resolve paths including symlinks, .., ., etc.
@@ -622,6 +1056,10 @@ impl SandboxedManager {
             return Ok(0);
         }
 
+        if let Some(storage) = &self.object_storage {
+            return self.cleanup_expired_objects(storage).await;
+        }
+
         // Phase 1
         let snapshot = self.collect_cleanup_snapshot().await;
 
@@ -638,6 +1076,35 @@ impl SandboxedManager {
 
         if removed > 0 {
             tracing::info!("Cleaned up {} expired files", removed);
+            self.quotas.invalidate_all().await;
+        }
+        Ok(removed)
+    }
+
+    /// Scheduled cleanup for object storage, judged by each object's last modification.
+    async fn cleanup_expired_objects(&self, storage: &ObjectStorage) -> Result<usize> {
+        let never_accessed = DateTime::<Utc>::from(std::time::UNIX_EPOCH);
+        let mut removed = 0;
+        for object in storage.list("", true).await? {
+            let modified = object.last_modified;
+            if !self
+                .cleanup_policy
+                .should_cleanup(None, never_accessed, modified, modified)
+            {
+                continue;
+            }
+            match storage.delete(&object.key).await {
+                Ok(()) => {
+                    tracing::debug!("Removed expired object: {}", object.key);
+                    removed += 1;
+                }
+                Err(e) => tracing::warn!("Failed to remove expired object {}: {}", object.key, e),
+            }
+        }
+
+        if removed > 0 {
+            tracing::info!("Cleaned up {} expired objects", removed);
+            self.quotas.invalidate_all().await;
         }
         Ok(removed)
     }
@@ -647,6 +1114,13 @@ impl SandboxedManager {
         if self.cleanup_policy.infinite_retention || self.cleanup_interval.is_zero() {
             return;
         }
+        if self.object_storage.is_some() && self.bucket_lifecycle {
+            tracing::debug!(
+                "Retention of {:?} left to bucket lifecycle rules",
+                self.base_dir
+            );
+            return;
+        }
 
         let manager = self.clone();
 
@@ -709,9 +1183,11 @@ impl SandboxedManager {
     /// Returns an error if:
     /// - The path is empty, absolute, or escapes the sandbox root
     /// - Intermediate parent directories cannot be created during validation
-    #[allow(clippy::unused_async)]
     pub async fn exists<P: AsRef<str>>(&self, path: P) -> Result<bool> {
         let path_str = path.as_ref();
+        if let Some(storage) = &self.object_storage {
+            return Ok(storage.head(&object_key(path_str)?).await?.is_some());
+        }
         let file_path = self.validate_and_get_path(path_str)?;
 
         Ok(file_path.exists())
@@ -726,6 +1202,7 @@ impl SandboxedManager {
     /// - The resolved path would escape the sandbox root
     pub fn get_full_path<P: AsRef<str>>(&self, path: P) -> Result<PathBuf> {
         let path_str = path.as_ref();
+        self.require_local("get_full_path")?;
         self.validate_and_get_path(path_str)
     }
 
@@ -738,6 +1215,10 @@ impl SandboxedManager {
     /// - The underlying `read_dir` operation fails
     pub async fn list_files<P: AsRef<str>>(&self, dir_path: P) -> Result<Vec<String>> {
         let dir_str = dir_path.as_ref();
+        if let Some(storage) = &self.object_storage {
+            let objects = storage.list(&object_key(dir_str)?, false).await?;
+            return Ok(objects.into_iter().map(|object| object.key).collect());
+        }
         let full_dir_path = self.validate_and_get_path(dir_str)?;
 
         let mut files = Vec::new();
@@ -769,6 +1250,157 @@ impl SandboxedManager {
         Ok(files)
     }
 
+    /// List the files (and optionally directories) directly within a directory, with their
+    /// size and timestamps, filtered and paginated by `options`.
+    ///
+    /// A directory that does not exist lists as empty. Symlinks are not listed.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The directory path is invalid or escapes the sandbox
+    /// - The directory cannot be read
+    pub async fn list_dir<P: AsRef<str>>(
+        &self,
+        dir_path: P,
+        options: &ListOptions,
+    ) -> Result<DirListing> {
+        self.list_entries(dir_path.as_ref(), false, options).await
+    }
+
+    /// Like [`Self::list_dir`], but lists everything below the directory, recursively.
+    ///
+    /// Globs are matched against paths relative to `dir_path`, so `**/*.png` finds PNG files
+    /// at any depth.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The directory path is invalid or escapes the sandbox
+    /// - The directory cannot be read
+    pub async fn walk<P: AsRef<str>>(
+        &self,
+        dir_path: P,
+        options: &ListOptions,
+    ) -> Result<DirListing> {
+        self.list_entries(dir_path.as_ref(), true, options).await
+    }
+
+    async fn list_entries(
+        &self,
+        dir_str: &str,
+        recursive: bool,
+        options: &ListOptions,
+    ) -> Result<DirListing> {
+        let mut entries = Vec::new();
+
+        if let Some(storage) = &self.object_storage {
+            let dir_key = object_key(dir_str)?;
+            for object in storage.list(&dir_key, recursive).await? {
+                let relative = if dir_key.is_empty() {
+                    object.key.as_str()
+                } else {
+                    object
+                        .key
+                        .strip_prefix(&dir_key)
+                        .map_or(object.key.as_str(), |rest| rest.trim_start_matches('/'))
+                };
+                let relative = relative.to_string();
+                let entry = DirEntryInfo {
+                    name: relative.rsplit('/').next().unwrap_or_default().to_string(),
+                    path: object.key,
+                    kind: EntryKind::File,
+                    size_bytes: object.size_bytes,
+                    modified: Some(object.last_modified),
+                    created: None,
+                    accessed: None,
+                    content_type: None,
+                };
+                if options.matches(&entry, &relative) {
+                    entries.push(entry);
+                }
+            }
+        } else {
+            let root = self.validate_and_get_path(dir_str)?;
+            let mut pending = vec![root.clone()];
+            while let Some(dir) = pending.pop() {
+                let mut dir_entries = match fs::read_dir(&dir).await {
+                    Ok(dir_entries) => dir_entries,
+                    Err(e) if dir == root && e.kind() == std::io::ErrorKind::NotFound => break,
+                    Err(e) if dir == root => return Err(e.into()),
+                    // Subdirectories may vanish mid-walk
+                    Err(_) => continue,
+                };
+                while let Some(dir_entry) = dir_entries.next_entry().await? {
+                    // Does not follow symlinks, which are neither files nor directories here
+                    let Ok(metadata) = dir_entry.metadata().await else {
+                        continue;
+                    };
+                    let kind = if metadata.is_dir() {
+                        EntryKind::Directory
+                    } else if metadata.is_file() {
+                        EntryKind::File
+                    } else {
+                        continue;
+                    };
+                    let entry_path = dir_entry.path();
+                    if kind == EntryKind::Directory && recursive {
+                        pending.push(entry_path.clone());
+                    }
+
+                    let (Some(path), Some(relative)) = (
+                        slash_path(&entry_path, &self.base_dir),
+                        slash_path(&entry_path, &root),
+                    ) else {
+                        continue;
+                    };
+                    let entry = DirEntryInfo {
+                        name: dir_entry.file_name().to_string_lossy().into_owned(),
+                        path,
+                        kind,
+                        size_bytes: if kind == EntryKind::File {
+                            metadata.len()
+                        } else {
+                            0
+                        },
+                        modified: metadata.modified().ok().map(DateTime::from),
+                        created: metadata.created().ok().map(DateTime::from),
+                        accessed: metadata.accessed().ok().map(DateTime::from),
+                        content_type: None,
+                    };
+                    if options.matches(&entry, &relative) {
+                        entries.push(entry);
+                    }
+                }
+            }
+        }
+
+        let mut listing = options.paginate(entries);
+        if options.detects_types() {
+            for entry in &mut listing.entries {
+                if entry.kind == EntryKind::File {
+                    entry.content_type = self.detect_content_type(&entry.path).await;
+                }
+            }
+        }
+        Ok(listing)
+    }
+
+    /// MIME type of a file from its magic number, if recognised
+    async fn detect_content_type(&self, path: &str) -> Option<String> {
+        let content = if let Some(storage) = &self.object_storage {
+            storage.get(&object_key(path).ok()?).await.ok()?
+        } else {
+            use tokio::io::AsyncReadExt;
+            let mut file = fs::File::open(self.validate_and_get_path(path).ok()?)
+                .await
+                .ok()?;
+            let mut buffer = vec![0u8; CONTENT_TYPE_DETECTION_BYTES];
+            let read = file.read(&mut buffer).await.ok()?;
+            buffer.truncate(read);
+            buffer
+        };
+        infer::get(&content).map(|kind| kind.mime_type().to_string())
+    }
+
     /// Try to get filesystem access time (atime) from metadata.
     /// Returns None if atime is not available or unreliable on this filesystem.
     #[allow(clippy::unused_async)]
@@ -816,11 +1448,35 @@ impl SandboxedManager {
     }
 }
 
+/// `/`-separated path of `path` relative to `base`, or `None` if it is not below `base`
+/// or is not valid UTF-8.
+fn slash_path(path: &Path, base: &Path) -> Option<String> {
+    let relative = path.strip_prefix(base).ok()?;
+    let parts: Option<Vec<&str>> = relative.iter().map(|part| part.to_str()).collect();
+    Some(parts?.join("/"))
+}
+
+/// Usage change from removing a file of `bytes`
+fn removal_delta(bytes: u64) -> UsageDelta {
+    UsageDelta {
+        bytes: -i64::try_from(bytes).unwrap_or(i64::MAX),
+        files: -1,
+    }
+}
+
+/// `NotFound` error for a missing sandbox path, matching what `std::fs` reports.
+fn not_found(path: &str) -> SandboxedFileError {
+    std::io::Error::new(std::io::ErrorKind::NotFound, format!("{path}: not found")).into()
+}
+
 /// Builder for configuring a `SandboxedManager`.
 pub struct SandboxedManagerBuilder {
     base_directory: Option<PathBuf>,
     cleanup_policy: CleanupPolicy,
     cleanup_interval: Duration,
+    object_storage: Option<ObjectStorageConfig>,
+    quotas: HashMap<String, DirectoryQuota>,
+    quota_rescan_interval: Duration,
 }
 
 impl SandboxedManagerBuilder {
@@ -829,6 +1485,9 @@ impl SandboxedManagerBuilder {
             base_directory: None,
             cleanup_policy: CleanupPolicy::default(),
             cleanup_interval: Duration::from_secs(60 * 60), // 1 hour default
+            object_storage: None,
+            quotas: HashMap::new(),
+            quota_rescan_interval: DEFAULT_QUOTA_RESCAN_INTERVAL,
         }
     }
 
@@ -855,15 +1514,83 @@ impl SandboxedManagerBuilder {
         self
     }
 
-    /// Build the `SandboxedManager`.
+    /// Store files in S3-compatible object storage instead of the base directory.
+    ///
+    /// The base directory is then not needed. Requires the `s3` feature.
+    #[must_use]
+    pub fn object_storage(mut self, config: ObjectStorageConfig) -> Self {
+        self.object_storage = Some(config);
+        self
+    }
+
+    /// Limit the size and file count of a top-level subdirectory (e.g. `recordings`).
+    #[must_use]
+    pub fn directory_quota(mut self, directory: impl Into<String>, quota: DirectoryQuota) -> Self {
+        self.quotas.insert(directory.into(), quota);
+        self
+    }
+
+    /// How long scanned quota usage is trusted before the directory is scanned again
+    /// (default 5 minutes). Rescans pick up files written outside the manager's `write`.
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn quota_rescan_interval(mut self, interval: Duration) -> Self {
+        self.quota_rescan_interval = interval;
+        self
+    }
+
+    fn quota_tracker(&self) -> Result<Arc<QuotaTracker>> {
+        let mut quotas = HashMap::with_capacity(self.quotas.len());
+        for (directory, quota) in &self.quotas {
+            let name = directory.trim_matches('/');
+            if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+                return Err(SandboxedFileError::Configuration {
+                    message: format!(
+                        "Quota directory '{directory}' must be a single top-level directory name"
+                    ),
+                });
+            }
+            quotas.insert(name.to_string(), *quota);
+        }
+        Ok(Arc::new(QuotaTracker::new(
+            quotas,
+            self.quota_rescan_interval,
+        )))
+    }
+
     /// Build the `SandboxedManager`.
     ///
     /// # Errors
     /// Returns an error if:
     /// - Base directory is not set
+    /// - A quota directory is not a single top-level directory name
     /// - Base directory cannot be created or secured
     /// - Existing file loading fails
+    /// - Object storage settings are invalid or the `s3` feature is missing
     pub async fn build(self) -> Result<SandboxedManager> {
+        let quotas = self.quota_tracker()?;
+        if let Some(config) = self.object_storage {
+            let manager = SandboxedManager {
+                base_dir: config.location(),
+                file_registry: Arc::new(RwLock::new(HashMap::new())),
+                cleanup_policy: self.cleanup_policy,
+                cleanup_interval: self.cleanup_interval,
+                cleanup_suspension: Arc::new(RwLock::new(None)),
+                usage_cache: Arc::new(RwLock::new(None)),
+                object_storage: Some(ObjectStorage::connect(&config)?),
+                bucket_lifecycle: config.bucket_lifecycle,
+                quotas,
+            };
+            manager.start_cleanup_task();
+            tracing::info!(
+                "SandboxedManager initialized - object storage: {:?}, cleanup_interval: {:?}, cleanup_enabled: {}",
+                manager.base_dir,
+                manager.cleanup_interval,
+                !manager.cleanup_policy.infinite_retention && !manager.bucket_lifecycle
+            );
+            return Ok(manager);
+        }
+
         let base_dir = self
             .base_directory
             .ok_or_else(|| SandboxedFileError::Configuration {
@@ -886,6 +1613,10 @@ impl SandboxedManagerBuilder {
             cleanup_policy: self.cleanup_policy,
             cleanup_interval: self.cleanup_interval,
             cleanup_suspension: Arc::new(RwLock::new(None)),
+            usage_cache: Arc::new(RwLock::new(None)),
+            object_storage: None,
+            bucket_lifecycle: false,
+            quotas,
         };
 
         // Load existing files from disk
@@ -1104,6 +1835,57 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rename_replaces_target() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir()?;
+
+        let manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .cleanup_policy(CleanupPolicy::disabled())
+            .build()
+            .await?;
+
+        manager.write("current.txt", "old").await?;
+        manager.write("staging.txt", "new").await?;
+        manager.rename("staging.txt", "current.txt").await?;
+
+        assert_eq!(manager.read_to_string("current.txt").await?, "new");
+        assert!(!manager.exists("staging.txt").await?);
+        assert!(manager.file_info("staging.txt").await?.is_none());
+        assert!(manager.file_info("current.txt").await?.is_some());
+        assert!(manager.rename("missing.txt", "other.txt").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disk_usage() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir()?;
+
+        let manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .cleanup_policy(CleanupPolicy::disabled())
+            .build()
+            .await?;
+
+        let usage = manager.disk_usage(StdDuration::ZERO).await?;
+        assert_eq!(usage.total_files, 0);
+        assert!(usage.oldest_modified.is_none());
+
+        manager.write("a.txt", "12345").await?;
+        manager.write("nested/b.txt", "123").await?;
+
+        // Cached result is returned until it is older than max_age
+        let cached = manager.disk_usage(StdDuration::from_secs(60)).await?;
+        assert_eq!(cached.total_files, 0);
+
+        let usage = manager.disk_usage(StdDuration::ZERO).await?;
+        assert_eq!(usage.total_files, 2);
+        assert_eq!(usage.total_size_bytes, 8);
+        assert!(usage.oldest_modified <= usage.newest_modified);
+        assert_eq!(manager.cleanup_interval(), StdDuration::ZERO);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_invalid_paths() -> std::result::Result<(), Box<dyn std::error::Error>> {
         let temp_dir = tempfile::tempdir()?;
@@ -1174,4 +1956,201 @@ mod tests {
         }
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_list_dir_and_walk() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .cleanup_policy(CleanupPolicy::disabled())
+            .build()
+            .await?;
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        manager.write("logos/a.png", png).await?;
+        manager.write("logos/notes.txt", "notes").await?;
+        manager.write("logos/cached/b.png", png).await?;
+
+        let listing = manager.list_dir("logos", &ListOptions::new()).await?;
+        let paths: Vec<_> = listing.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["logos/a.png", "logos/notes.txt"]);
+        assert_eq!(listing.entries[1].size_bytes, 5);
+        assert!(listing.entries[0].modified.is_some());
+
+        let with_dirs = manager
+            .list_dir("logos", &ListOptions::new().include_dirs(true))
+            .await?;
+        assert!(
+            with_dirs
+                .entries
+                .iter()
+                .any(|e| e.path == "logos/cached" && e.kind == EntryKind::Directory)
+        );
+
+        let pngs = manager
+            .walk(
+                "logos",
+                &ListOptions::new()
+                    .glob("**/*.png")
+                    .detect_types(true)
+                    .limit(1),
+            )
+            .await?;
+        assert_eq!(pngs.total, 2);
+        assert_eq!(pngs.next_offset, Some(1));
+        assert_eq!(pngs.entries[0].path, "logos/a.png");
+        assert_eq!(pngs.entries[0].content_type.as_deref(), Some("image/png"));
+
+        let root = manager
+            .walk(".", &ListOptions::new().glob("cached/*"))
+            .await?;
+        assert!(root.entries.is_empty());
+        assert_eq!(
+            manager
+                .list_dir("missing", &ListOptions::new())
+                .await?
+                .total,
+            0
+        );
+        assert!(manager.list_dir("..", &ListOptions::new()).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_directory_quotas() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir()?;
+        std::fs::create_dir(temp_dir.path().join("recordings"))?;
+        std::fs::write(temp_dir.path().join("recordings/existing.ts"), [0u8; 40])?;
+
+        let manager = SandboxedManager::builder()
+            .base_directory(temp_dir.path())
+            .cleanup_policy(CleanupPolicy::disabled())
+            .directory_quota(
+                "recordings",
+                DirectoryQuota::new().max_bytes(100).max_files(3),
+            )
+            .build()
+            .await?;
+
+        // Files already on disk count towards the quota
+        manager.write("recordings/a.ts", [0u8; 50]).await?;
+        let err = manager
+            .write("recordings/b.ts", [0u8; 20])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SandboxedFileError::QuotaExceeded {
+                limit: crate::quota::QuotaLimit::Bytes,
+                would_use: 110,
+                ..
+            }
+        ));
+        assert!(!manager.exists("recordings/b.ts").await?);
+
+        // Overwriting only counts the difference, and other directories are unlimited
+        manager.write("recordings/a.ts", [0u8; 60]).await?;
+        manager.write("temp/big.bin", [0u8; 500]).await?;
+        assert!(
+            manager
+                .copy("temp/big.bin", "recordings/c.ts")
+                .await
+                .is_err()
+        );
+
+        manager.remove_file("recordings/existing.ts").await?;
+        manager
+            .rename("temp/big.bin", "recordings/c.ts")
+            .await
+            .unwrap_err();
+        manager.write("temp/small.bin", [0u8; 30]).await?;
+        manager.rename("temp/small.bin", "recordings/c.ts").await?;
+
+        let usage = manager.quota_usage().await?;
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].directory, "recordings");
+        assert_eq!((usage[0].used_bytes, usage[0].used_files), (90, 2));
+        assert_eq!(usage[0].rejected_writes, 3);
+
+        assert!(
+            SandboxedManager::builder()
+                .base_directory(temp_dir.path())
+                .directory_quota("a/b", DirectoryQuota::new())
+                .build()
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "s3")]
+    fn in_memory_object_manager(policy: CleanupPolicy) -> SandboxedManager {
+        let store = Arc::new(object_store::memory::InMemory::new());
+        SandboxedManager {
+            base_dir: PathBuf::from("s3://test/m3u"),
+            file_registry: Arc::new(RwLock::new(HashMap::new())),
+            cleanup_policy: policy,
+            cleanup_interval: Duration::from_secs(60),
+            cleanup_suspension: Arc::new(RwLock::new(None)),
+            usage_cache: Arc::new(RwLock::new(None)),
+            object_storage: Some(ObjectStorage::with_store(store, "m3u")),
+            bucket_lifecycle: false,
+            quotas: Arc::new(QuotaTracker::new(
+                HashMap::new(),
+                DEFAULT_QUOTA_RESCAN_INTERVAL,
+            )),
+        }
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_object_storage_operations() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let manager = in_memory_object_manager(CleanupPolicy::disabled());
+        assert!(!manager.is_local());
+
+        manager.write("lists/a.m3u8", "#EXTM3U").await?;
+        assert!(manager.exists("lists/a.m3u8").await?);
+        assert_eq!(
+            manager.read_to_string("lists/x/../a.m3u8").await?,
+            "#EXTM3U"
+        );
+        assert_eq!(manager.stat("lists/a.m3u8").await?.size_bytes, 7);
+
+        assert_eq!(manager.copy("lists/a.m3u8", "lists/b.m3u8").await?, 7);
+        manager.rename("lists/b.m3u8", "lists/c.m3u8").await?;
+        let mut files = manager.list_files("lists").await?;
+        files.sort();
+        assert_eq!(files, vec!["lists/a.m3u8", "lists/c.m3u8"]);
+
+        manager.remove_file("lists/a.m3u8").await?;
+        assert!(!manager.exists("lists/a.m3u8").await?);
+        assert!(manager.remove_file("lists/a.m3u8").await.is_err());
+        assert!(manager.read("lists/a.m3u8").await.is_err());
+
+        assert!(manager.write("../escape.txt", "nope").await.is_err());
+        assert!(manager.get_full_path("lists/c.m3u8").is_err());
+
+        let usage = manager.disk_usage(Duration::ZERO).await?;
+        assert_eq!(usage.total_files, 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_object_storage_cleanup() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let policy = CleanupPolicy::new()
+            .remove_after(StdDuration::from_millis(50))
+            .enabled(true);
+        let manager = in_memory_object_manager(policy);
+
+        manager.write("old.txt", "stale").await?;
+        tokio::time::sleep(StdDuration::from_millis(100)).await;
+        manager.write("new.txt", "fresh").await?;
+
+        assert_eq!(manager.cleanup_expired_files().await?, 1);
+        assert!(!manager.exists("old.txt").await?);
+        assert!(manager.exists("new.txt").await?);
+        Ok(())
+    }
 }
@@ -0,0 +1,428 @@
+//! S3-compatible object storage backend.
+//!
+//! A manager built with [`ObjectStorageConfig`] keeps its files as objects under a key
+//! prefix instead of in a local directory, so containers using it can be stateless.
+//! Paths are normalised lexically into object keys: there are no symlinks to resolve,
+//! but `..` may still not climb above the prefix.
+//!
+//! Object stores track no access time, so `TimeMatch::LastAccess` and `TimeMatch::Created`
+//! fall back to the object's last modification. Retention is either enforced by the
+//! manager's scheduled cleanup or, with `bucket_lifecycle`, left to the bucket's own
+//! lifecycle rules.
+//!
+//! The backend itself needs the `s3` feature; without it, building a manager with object
+//! storage fails with a configuration error.
+
+use crate::error::{Result, SandboxedFileError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// Connection settings for an S3-compatible bucket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectStorageConfig {
+    /// Bucket holding the objects
+    pub bucket: String,
+
+    /// Key prefix all paths live under (empty for the bucket root)
+    #[serde(default)]
+    pub prefix: String,
+
+    /// Custom endpoint for S3-compatible services (MinIO, R2, ...); AWS when unset
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    #[serde(default = "default_region")]
+    pub region: String,
+
+    /// Credentials; when unset they are taken from the standard `AWS_*` environment
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    #[serde(default)]
+    pub secret_access_key: Option<String>,
+
+    /// Permit plain HTTP endpoints (local MinIO and the like)
+    #[serde(default)]
+    pub allow_http: bool,
+
+    /// Address the bucket as `bucket.endpoint` rather than `endpoint/bucket`
+    #[serde(default)]
+    pub virtual_hosted_style: bool,
+
+    /// Leave expiry to the bucket's lifecycle rules instead of scheduled cleanup
+    #[serde(default)]
+    pub bucket_lifecycle: bool,
+}
+
+impl ObjectStorageConfig {
+    /// Settings for `bucket` with defaults for everything else.
+    #[must_use]
+    pub fn new(bucket: impl Into<String>) -> Self {
+        Self {
+            bucket: bucket.into(),
+            prefix: String::new(),
+            endpoint: None,
+            region: default_region(),
+            access_key_id: None,
+            secret_access_key: None,
+            allow_http: false,
+            virtual_hosted_style: false,
+            bucket_lifecycle: false,
+        }
+    }
+
+    /// The same bucket with `child` appended to the key prefix.
+    #[must_use]
+    pub fn with_child_prefix(&self, child: &str) -> Self {
+        let parent = self.prefix.trim_matches('/');
+        let child = child.trim_matches('/');
+        let prefix = match (parent.is_empty(), child.is_empty()) {
+            (true, _) => child.to_string(),
+            (false, true) => parent.to_string(),
+            (false, false) => format!("{parent}/{child}"),
+        };
+        Self {
+            prefix,
+            ..self.clone()
+        }
+    }
+
+    /// Display location, e.g. `s3://bucket/prefix`, used in place of a base directory.
+    #[must_use]
+    pub fn location(&self) -> PathBuf {
+        let prefix = self.prefix.trim_matches('/');
+        if prefix.is_empty() {
+            PathBuf::from(format!("s3://{}", self.bucket))
+        } else {
+            PathBuf::from(format!("s3://{}/{prefix}", self.bucket))
+        }
+    }
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// Size and modification time of a stored object.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "s3"), allow(dead_code))]
+pub(crate) struct ObjectInfo {
+    pub key: String,
+    pub size_bytes: u64,
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Normalise a sandbox-relative path into an object key.
+///
+/// Applies the same rules as local paths: no empty, absolute or null-byte paths, and
+/// `..` must not climb above the sandbox root.
+pub(crate) fn object_key(filepath: &str) -> Result<String> {
+    let invalid = |reason: &str| SandboxedFileError::PathValidation {
+        path: PathBuf::from(filepath),
+        reason: reason.to_string(),
+    };
+
+    if filepath.is_empty() {
+        return Err(invalid("Filepath cannot be empty"));
+    }
+    if filepath.contains('\0') {
+        return Err(invalid("Filepath contains null bytes"));
+    }
+
+    let mut parts: Vec<&str> = Vec::new();
+    for component in Path::new(filepath).components() {
+        match component {
+            Component::Normal(part) => {
+                parts.push(
+                    part.to_str()
+                        .ok_or_else(|| invalid("Filepath is not UTF-8"))?,
+                );
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if parts.pop().is_none() {
+                    return Err(invalid("Path escapes sandbox"));
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(invalid(
+                    "Absolute paths not allowed - use relative paths within sandbox",
+                ));
+            }
+        }
+    }
+
+    Ok(parts.join("/"))
+}
+
+#[cfg(feature = "s3")]
+pub(crate) use backend::ObjectStorage;
+
+#[cfg(not(feature = "s3"))]
+pub(crate) use unavailable::ObjectStorage;
+
+#[cfg(feature = "s3")]
+mod backend {
+    use super::{ObjectInfo, ObjectStorageConfig};
+    use crate::error::{Result, SandboxedFileError};
+    use futures::TryStreamExt;
+    use object_store::{ObjectMeta, ObjectStore, aws::AmazonS3Builder, path::Path as ObjectPath};
+    use std::sync::Arc;
+
+    /// Handle on a bucket prefix.
+    #[derive(Debug, Clone)]
+    pub(crate) struct ObjectStorage {
+        store: Arc<dyn ObjectStore>,
+        prefix: String,
+    }
+
+    impl ObjectStorage {
+        pub(crate) fn connect(config: &ObjectStorageConfig) -> Result<Self> {
+            let mut builder = AmazonS3Builder::from_env()
+                .with_bucket_name(&config.bucket)
+                .with_region(&config.region)
+                .with_allow_http(config.allow_http)
+                .with_virtual_hosted_style_request(config.virtual_hosted_style);
+            if let Some(endpoint) = &config.endpoint {
+                builder = builder.with_endpoint(endpoint);
+            }
+            if let Some(access_key_id) = &config.access_key_id {
+                builder = builder.with_access_key_id(access_key_id);
+            }
+            if let Some(secret_access_key) = &config.secret_access_key {
+                builder = builder.with_secret_access_key(secret_access_key);
+            }
+
+            let store = builder
+                .build()
+                .map_err(|e| SandboxedFileError::Configuration {
+                    message: format!("Invalid object storage settings: {e}"),
+                })?;
+            Ok(Self::with_store(Arc::new(store), &config.prefix))
+        }
+
+        pub(crate) fn with_store(store: Arc<dyn ObjectStore>, prefix: &str) -> Self {
+            Self {
+                store,
+                prefix: prefix.trim_matches('/').to_string(),
+            }
+        }
+
+        fn location(&self, key: &str) -> ObjectPath {
+            match (self.prefix.is_empty(), key.is_empty()) {
+                (true, _) => ObjectPath::from(key),
+                (false, true) => ObjectPath::from(self.prefix.as_str()),
+                (false, false) => ObjectPath::from(format!("{}/{key}", self.prefix)),
+            }
+        }
+
+        /// Key relative to the prefix for an object listed by the store
+        fn relative_key(&self, location: &ObjectPath) -> String {
+            let key = location.as_ref();
+            if self.prefix.is_empty() {
+                return key.to_string();
+            }
+            key.strip_prefix(&self.prefix)
+                .map_or(key, |rest| rest.trim_start_matches('/'))
+                .to_string()
+        }
+
+        fn info(&self, meta: &ObjectMeta) -> ObjectInfo {
+            ObjectInfo {
+                key: self.relative_key(&meta.location),
+                size_bytes: meta.size,
+                last_modified: meta.last_modified,
+            }
+        }
+
+        pub(crate) async fn put(&self, key: &str, contents: Vec<u8>) -> Result<()> {
+            self.store
+                .put(&self.location(key), contents.into())
+                .await
+                .map_err(|e| storage_error(key, e))?;
+            Ok(())
+        }
+
+        pub(crate) async fn get(&self, key: &str) -> Result<Vec<u8>> {
+            let object = self
+                .store
+                .get(&self.location(key))
+                .await
+                .map_err(|e| storage_error(key, e))?;
+            let bytes = object.bytes().await.map_err(|e| storage_error(key, e))?;
+            Ok(bytes.to_vec())
+        }
+
+        pub(crate) async fn head(&self, key: &str) -> Result<Option<ObjectInfo>> {
+            match self.store.head(&self.location(key)).await {
+                Ok(meta) => Ok(Some(self.info(&meta))),
+                Err(object_store::Error::NotFound { .. }) => Ok(None),
+                Err(e) => Err(storage_error(key, e)),
+            }
+        }
+
+        pub(crate) async fn copy(&self, from: &str, to: &str) -> Result<()> {
+            self.store
+                .copy(&self.location(from), &self.location(to))
+                .await
+                .map_err(|e| storage_error(from, e))
+        }
+
+        pub(crate) async fn rename(&self, from: &str, to: &str) -> Result<()> {
+            self.store
+                .rename(&self.location(from), &self.location(to))
+                .await
+                .map_err(|e| storage_error(from, e))
+        }
+
+        pub(crate) async fn delete(&self, key: &str) -> Result<()> {
+            self.store
+                .delete(&self.location(key))
+                .await
+                .map_err(|e| storage_error(key, e))
+        }
+
+        /// Objects under `dir`; only its direct children unless `recursive`
+        pub(crate) async fn list(&self, dir: &str, recursive: bool) -> Result<Vec<ObjectInfo>> {
+            let location = self.location(dir);
+            let prefix = (!location.as_ref().is_empty()).then_some(&location);
+            let objects = if recursive {
+                self.store
+                    .list(prefix)
+                    .try_collect::<Vec<_>>()
+                    .await
+                    .map_err(|e| storage_error(dir, e))?
+            } else {
+                self.store
+                    .list_with_delimiter(prefix)
+                    .await
+                    .map_err(|e| storage_error(dir, e))?
+                    .objects
+            };
+            Ok(objects.iter().map(|meta| self.info(meta)).collect())
+        }
+
+        pub(crate) async fn delete_all(&self, dir: &str) -> Result<usize> {
+            let objects = self.list(dir, true).await?;
+            for object in &objects {
+                self.delete(&object.key).await?;
+            }
+            Ok(objects.len())
+        }
+    }
+
+    /// Missing objects surface as `NotFound` I/O errors, like missing local files
+    fn storage_error(key: &str, error: object_store::Error) -> SandboxedFileError {
+        match error {
+            object_store::Error::NotFound { .. } => SandboxedFileError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{key}: {error}"),
+            )),
+            other => SandboxedFileError::ObjectStorage {
+                key: key.to_string(),
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(not(feature = "s3"))]
+mod unavailable {
+    use super::{ObjectInfo, ObjectStorageConfig};
+    use crate::error::{Result, SandboxedFileError};
+
+    /// Stand-in when built without the `s3` feature; can never be connected
+    #[derive(Debug, Clone)]
+    #[allow(dead_code)]
+    pub(crate) struct ObjectStorage;
+
+    fn unavailable<T>() -> Result<T> {
+        Err(SandboxedFileError::Configuration {
+            message: "Object storage requires the `s3` feature".to_string(),
+        })
+    }
+
+    impl ObjectStorage {
+        pub(crate) fn connect(_config: &ObjectStorageConfig) -> Result<Self> {
+            unavailable()
+        }
+
+        #[allow(clippy::unused_async)]
+        pub(crate) async fn put(&self, _key: &str, _contents: Vec<u8>) -> Result<()> {
+            unavailable()
+        }
+
+        #[allow(clippy::unused_async)]
+        pub(crate) async fn get(&self, _key: &str) -> Result<Vec<u8>> {
+            unavailable()
+        }
+
+        #[allow(clippy::unused_async)]
+        pub(crate) async fn head(&self, _key: &str) -> Result<Option<ObjectInfo>> {
+            unavailable()
+        }
+
+        #[allow(clippy::unused_async)]
+        pub(crate) async fn copy(&self, _from: &str, _to: &str) -> Result<()> {
+            unavailable()
+        }
+
+        #[allow(clippy::unused_async)]
+        pub(crate) async fn rename(&self, _from: &str, _to: &str) -> Result<()> {
+            unavailable()
+        }
+
+        #[allow(clippy::unused_async)]
+        pub(crate) async fn delete(&self, _key: &str) -> Result<()> {
+            unavailable()
+        }
+
+        #[allow(clippy::unused_async)]
+        pub(crate) async fn list(&self, _dir: &str, _recursive: bool) -> Result<Vec<ObjectInfo>> {
+            unavailable()
+        }
+
+        #[allow(clippy::unused_async)]
+        pub(crate) async fn delete_all(&self, _dir: &str) -> Result<usize> {
+            unavailable()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_key_normalisation() {
+        assert_eq!(object_key("file.txt").unwrap(), "file.txt");
+        assert_eq!(object_key("./a/b/../c.txt").unwrap(), "a/c.txt");
+        assert_eq!(object_key("a//b.txt").unwrap(), "a/b.txt");
+        assert_eq!(object_key(".").unwrap(), "");
+    }
+
+    #[test]
+    fn test_object_key_rejects_escapes() {
+        assert!(object_key("").is_err());
+        assert!(object_key("../secret").is_err());
+        assert!(object_key("a/../../secret").is_err());
+        assert!(object_key("/etc/passwd").is_err());
+        assert!(object_key("file\0.txt").is_err());
+    }
+
+    #[test]
+    fn test_child_prefix_and_location() {
+        let config = ObjectStorageConfig::new("media");
+        assert_eq!(config.location(), PathBuf::from("s3://media"));
+
+        let m3u = config.with_child_prefix("m3u");
+        assert_eq!(m3u.prefix, "m3u");
+        let nested = ObjectStorageConfig {
+            prefix: "/cluster-a/".to_string(),
+            ..config
+        }
+        .with_child_prefix("m3u");
+        assert_eq!(nested.prefix, "cluster-a/m3u");
+        assert_eq!(nested.location(), PathBuf::from("s3://media/cluster-a/m3u"));
+    }
+}
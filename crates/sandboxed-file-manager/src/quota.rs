@@ -0,0 +1,382 @@
+//! Byte and file-count quotas per top-level subdirectory.
+//!
+//! A quota applies to everything below one top-level directory of the sandbox (for example
+//! `recordings/`). Writes through the manager are checked against the directory's usage and
+//! fail with [`SandboxedFileError::QuotaExceeded`] when they would exceed it.
+//!
+//! Usage is counted by scanning the directory, then kept current by the manager's own
+//! writes and removals. Files written through a handle from
+//! [`SandboxedManager::create`](crate::SandboxedManager::create), or by other processes,
+//! are only counted once the directory is rescanned, at most one rescan interval later.
+
+use crate::error::{Result, SandboxedFileError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Default interval after which a directory's usage is rescanned.
+pub const DEFAULT_QUOTA_RESCAN_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Limits on one top-level subdirectory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryQuota {
+    pub max_bytes: Option<u64>,
+    pub max_files: Option<u64>,
+}
+
+impl DirectoryQuota {
+    /// An unlimited quota; add limits with [`Self::max_bytes`] and [`Self::max_files`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the total size of the directory.
+    #[must_use]
+    pub const fn max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Limit the number of files in the directory.
+    #[must_use]
+    pub const fn max_files(mut self, files: u64) -> Self {
+        self.max_files = Some(files);
+        self
+    }
+}
+
+/// Which limit of a quota a write would exceed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaLimit {
+    Bytes,
+    Files,
+}
+
+impl std::fmt::Display for QuotaLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bytes => write!(f, "bytes"),
+            Self::Files => write!(f, "files"),
+        }
+    }
+}
+
+/// Usage of a quota-limited directory against its quota.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    /// Top-level directory the quota applies to
+    pub directory: String,
+    pub used_bytes: u64,
+    pub used_files: u64,
+    pub max_bytes: Option<u64>,
+    pub max_files: Option<u64>,
+    /// Writes rejected for exceeding the quota since the manager started
+    pub rejected_writes: u64,
+    /// When the directory was last scanned
+    pub scanned_at: Option<DateTime<Utc>>,
+}
+
+impl QuotaUsage {
+    /// Fraction of the tighter limit in use, if the directory has any limit
+    #[must_use]
+    pub fn utilization(&self) -> Option<f64> {
+        let ratio = |used: u64, max: Option<u64>| {
+            #[allow(clippy::cast_precision_loss)]
+            max.map(|max| {
+                if max == 0 {
+                    1.0
+                } else {
+                    used as f64 / max as f64
+                }
+            })
+        };
+        match (
+            ratio(self.used_bytes, self.max_bytes),
+            ratio(self.used_files, self.max_files),
+        ) {
+            (Some(bytes), Some(files)) => Some(bytes.max(files)),
+            (bytes, files) => bytes.or(files),
+        }
+    }
+}
+
+/// Tracked usage of one directory
+#[derive(Debug, Default)]
+struct TrackedUsage {
+    bytes: u64,
+    files: u64,
+    rejected_writes: u64,
+    /// Set once scanned; `None` or older than the rescan interval forces a rescan
+    scanned: Option<(Instant, DateTime<Utc>)>,
+}
+
+/// Change to a directory's usage from one operation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct UsageDelta {
+    pub bytes: i64,
+    pub files: i64,
+}
+
+/// Quotas of a manager and the usage they are checked against.
+#[derive(Debug)]
+pub(crate) struct QuotaTracker {
+    quotas: HashMap<String, DirectoryQuota>,
+    usage: Mutex<HashMap<String, TrackedUsage>>,
+    rescan_interval: Duration,
+}
+
+impl QuotaTracker {
+    pub(crate) fn new(quotas: HashMap<String, DirectoryQuota>, rescan_interval: Duration) -> Self {
+        Self {
+            quotas,
+            usage: Mutex::new(HashMap::new()),
+            rescan_interval,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.quotas.is_empty()
+    }
+
+    /// Quota-limited top-level directory containing `key` (a normalised relative path)
+    pub(crate) fn directory_of<'a>(&self, key: &'a str) -> Option<&'a str> {
+        let (top, rest) = key.split_once('/')?;
+        (!rest.is_empty() && self.quotas.contains_key(top)).then_some(top)
+    }
+
+    pub(crate) fn directories(&self) -> impl Iterator<Item = &str> {
+        self.quotas.keys().map(String::as_str)
+    }
+
+    /// Whether the directory's usage must be scanned before it can be checked
+    pub(crate) async fn needs_scan(&self, directory: &str) -> bool {
+        self.usage
+            .lock()
+            .await
+            .get(directory)
+            .and_then(|usage| usage.scanned)
+            .map_or(true, |(scanned, _)| {
+                scanned.elapsed() >= self.rescan_interval
+            })
+    }
+
+    /// Replace the directory's usage with a fresh scan
+    pub(crate) async fn set_scanned(&self, directory: &str, bytes: u64, files: u64) {
+        let mut usage = self.usage.lock().await;
+        let tracked = usage.entry(directory.to_string()).or_default();
+        tracked.bytes = bytes;
+        tracked.files = files;
+        tracked.scanned = Some((Instant::now(), Utc::now()));
+    }
+
+    /// Force a rescan of every directory
+    pub(crate) async fn invalidate_all(&self) {
+        for tracked in self.usage.lock().await.values_mut() {
+            tracked.scanned = None;
+        }
+    }
+
+    /// Check that `delta` fits the directory's quota and count it as used
+    ///
+    /// The reservation is made under the lock, so concurrent writes cannot both pass a check
+    /// that only one of them fits. Undo it with [`Self::release`] if the write then fails.
+    /// An `open_ended` write, whose final size is not known yet, is also refused once the
+    /// byte limit is reached.
+    pub(crate) async fn reserve(
+        &self,
+        directory: &str,
+        delta: UsageDelta,
+        open_ended: bool,
+    ) -> Result<()> {
+        let Some(quota) = self.quotas.get(directory) else {
+            return Ok(());
+        };
+        let mut usage = self.usage.lock().await;
+        let tracked = usage.entry(directory.to_string()).or_default();
+        let bytes = apply(tracked.bytes, delta.bytes);
+        let files = apply(tracked.files, delta.files);
+
+        let exceeded = [
+            (QuotaLimit::Bytes, delta.bytes, bytes, quota.max_bytes),
+            (QuotaLimit::Files, delta.files, files, quota.max_files),
+        ]
+        .into_iter()
+        .find(|&(limit, change, would_use, max)| {
+            max.is_some_and(|max| {
+                (change > 0 && would_use > max)
+                    || (open_ended && limit == QuotaLimit::Bytes && would_use >= max)
+            })
+        });
+        if let Some((limit, _, would_use, max)) = exceeded {
+            tracked.rejected_writes += 1;
+            let max = max.unwrap_or_default();
+            tracing::warn!(
+                "Quota exceeded for '{}/': {} {} would exceed the limit of {}",
+                directory,
+                would_use,
+                limit,
+                max
+            );
+            return Err(SandboxedFileError::QuotaExceeded {
+                directory: directory.to_string(),
+                limit,
+                max,
+                would_use,
+            });
+        }
+
+        tracked.bytes = bytes;
+        tracked.files = files;
+        Ok(())
+    }
+
+    /// Count a change that is not subject to the quota, such as a removal
+    pub(crate) async fn record(&self, directory: &str, delta: UsageDelta) {
+        let mut usage = self.usage.lock().await;
+        let tracked = usage.entry(directory.to_string()).or_default();
+        tracked.bytes = apply(tracked.bytes, delta.bytes);
+        tracked.files = apply(tracked.files, delta.files);
+    }
+
+    /// Undo a reservation whose write failed
+    pub(crate) async fn release(&self, directory: &str, delta: UsageDelta) {
+        self.record(
+            directory,
+            UsageDelta {
+                bytes: -delta.bytes,
+                files: -delta.files,
+            },
+        )
+        .await;
+    }
+
+    pub(crate) async fn usage(&self, directory: &str) -> Option<QuotaUsage> {
+        let quota = self.quotas.get(directory)?;
+        let usage = self.usage.lock().await;
+        let tracked = usage.get(directory);
+        Some(QuotaUsage {
+            directory: directory.to_string(),
+            used_bytes: tracked.map_or(0, |t| t.bytes),
+            used_files: tracked.map_or(0, |t| t.files),
+            max_bytes: quota.max_bytes,
+            max_files: quota.max_files,
+            rejected_writes: tracked.map_or(0, |t| t.rejected_writes),
+            scanned_at: tracked.and_then(|t| t.scanned).map(|(_, at)| at),
+        })
+    }
+}
+
+fn apply(value: u64, delta: i64) -> u64 {
+    if delta >= 0 {
+        value.saturating_add(delta.unsigned_abs())
+    } else {
+        value.saturating_sub(delta.unsigned_abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> QuotaTracker {
+        QuotaTracker::new(
+            HashMap::from([(
+                "recordings".to_string(),
+                DirectoryQuota::new().max_bytes(100).max_files(2),
+            )]),
+            DEFAULT_QUOTA_RESCAN_INTERVAL,
+        )
+    }
+
+    #[test]
+    fn test_directory_of() {
+        let tracker = tracker();
+        assert_eq!(tracker.directory_of("recordings/a.ts"), Some("recordings"));
+        assert_eq!(
+            tracker.directory_of("recordings/x/a.ts"),
+            Some("recordings")
+        );
+        assert_eq!(tracker.directory_of("recordings"), None);
+        assert_eq!(tracker.directory_of("temp/a.ts"), None);
+    }
+
+    #[tokio::test]
+    async fn test_reserve_enforces_limits() {
+        let tracker = tracker();
+        let file = |bytes| UsageDelta { bytes, files: 1 };
+
+        tracker
+            .reserve("recordings", file(60), false)
+            .await
+            .unwrap();
+        let err = tracker
+            .reserve("recordings", file(50), false)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SandboxedFileError::QuotaExceeded {
+                limit: QuotaLimit::Bytes,
+                max: 100,
+                would_use: 110,
+                ..
+            }
+        ));
+        tracker
+            .reserve("recordings", file(40), false)
+            .await
+            .unwrap();
+        assert!(matches!(
+            tracker.reserve("recordings", file(0), false).await,
+            Err(SandboxedFileError::QuotaExceeded {
+                limit: QuotaLimit::Files,
+                ..
+            })
+        ));
+
+        // Shrinking a file is always allowed, even over quota
+        tracker
+            .reserve(
+                "recordings",
+                UsageDelta {
+                    bytes: -10,
+                    files: 0,
+                },
+                false,
+            )
+            .await
+            .unwrap();
+        let usage = tracker.usage("recordings").await.unwrap();
+        assert_eq!((usage.used_bytes, usage.used_files), (90, 2));
+        assert_eq!(usage.rejected_writes, 2);
+        assert_eq!(usage.utilization(), Some(1.0));
+
+        // Opening a file for streaming needs room left, not just a free file slot
+        tracker
+            .release("recordings", UsageDelta { bytes: 0, files: 1 })
+            .await;
+        tracker
+            .reserve("recordings", UsageDelta { bytes: 0, files: 1 }, true)
+            .await
+            .unwrap();
+        tracker
+            .record(
+                "recordings",
+                UsageDelta {
+                    bytes: 10,
+                    files: -1,
+                },
+            )
+            .await;
+        assert!(
+            tracker
+                .reserve("recordings", UsageDelta { bytes: 0, files: 1 }, true)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -1086,6 +1086,7 @@ export default function ChannelsPage() {
         )}
         <ChannelDetailsSheet
           channel={detailsChannel}
+          previewable
           open={isDetailsOpen}
           onOpenChange={(open) => {
             setIsDetailsOpen(open);
@@ -76,6 +76,7 @@ interface EpgProgram {
   end_time: string;
   category?: string;
   rating?: string;
+  icon?: string;
   source_id?: string;
   metadata?: Record<string, string>;
   is_streamable: boolean;
@@ -107,10 +108,14 @@ interface EpgProgramsResponse {
 
 interface EpgGuideResponse {
   channels: Record<string, { id: string; name: string; logo?: string }>;
+  channel_order?: string[];
   programs: Record<string, EpgProgram[]>;
   time_slots: string[];
   start_time: string;
   end_time: string;
+  total_channels?: number;
+  channel_offset?: number;
+  has_more_channels?: boolean;
 }
 
 export default function EpgPage() {
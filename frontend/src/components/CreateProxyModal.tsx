@@ -28,7 +28,13 @@ import { Label } from '@/components/ui/label';
 import { Plus, GripVertical, Trash2, AlertCircle, Loader2, ArrowUp, ArrowDown } from 'lucide-react';
 import { getBackendUrl } from '@/lib/config';
 import { apiClient } from '@/lib/api-client';
-import { StreamProxy } from '@/types/api';
+import {
+  BackupStreamMode,
+  ChannelNumberBlock,
+  OfflineSlateMode,
+  OutputProfile,
+  StreamProxy,
+} from '@/types/api';
 
 // Types based on your API specification
 interface StreamSourceResponse {
@@ -92,6 +98,14 @@ interface ProxyFormData {
   cache_channel_logos: boolean;
   cache_program_logos: boolean;
   relay_profile_id?: string;
+  sign_stream_urls?: boolean;
+  output_profile?: OutputProfile;
+  backup_streams?: BackupStreamMode;
+  offline_slate?: OfflineSlateMode;
+  epg_languages?: string;
+  regeneration_debounce_seconds?: number;
+  channel_number_blocks?: ChannelNumberBlock[];
+  epg_timezone?: string;
 }
 
 // Multi-select modal component
@@ -552,6 +566,14 @@ export function ProxySheet({
               cache_channel_logos: sourceProxyData.cache_channel_logos,
               cache_program_logos: sourceProxyData.cache_program_logos,
               relay_profile_id: sourceProxyData.relay_profile_id || '',
+              sign_stream_urls: sourceProxyData.sign_stream_urls,
+              output_profile: sourceProxyData.output_profile,
+              backup_streams: sourceProxyData.backup_streams,
+              offline_slate: sourceProxyData.offline_slate,
+              epg_languages: sourceProxyData.epg_languages,
+              regeneration_debounce_seconds: sourceProxyData.regeneration_debounce_seconds,
+              channel_number_blocks: sourceProxyData.channel_number_blocks || [],
+              epg_timezone: sourceProxyData.epg_timezone || '',
             });
           } else {
             // Reset form for create mode
@@ -946,6 +968,204 @@ export function ProxySheet({
                 />
               </div>
             </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="output_profile">Output Profile</Label>
+              <Select
+                value={formData.output_profile || 'standard'}
+                onValueChange={(value) =>
+                  setFormData((prev) => ({ ...prev, output_profile: value as OutputProfile }))
+                }
+              >
+                <SelectTrigger id="output_profile">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="standard">Standard</SelectItem>
+                  <SelectItem value="kodi">Kodi</SelectItem>
+                </SelectContent>
+              </Select>
+              <p className="text-sm text-muted-foreground">
+                Client preset for the playlist and guide. Kodi adds the hints the PVR IPTV Simple
+                Client reads.
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="backup_streams">Backup Streams</Label>
+              <Select
+                value={formData.backup_streams || 'off'}
+                onValueChange={(value) =>
+                  setFormData((prev) => ({ ...prev, backup_streams: value as BackupStreamMode }))
+                }
+              >
+                <SelectTrigger id="backup_streams">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="off">Off</SelectItem>
+                  <SelectItem value="attribute">Backup URL attribute</SelectItem>
+                  <SelectItem value="group">Backup Streams group</SelectItem>
+                </SelectContent>
+              </Select>
+              <p className="text-sm text-muted-foreground">
+                How streams of the same channel from lower-priority sources are listed as backups.
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="offline_slate">Offline Slate</Label>
+              <Select
+                value={formData.offline_slate || 'off'}
+                onValueChange={(value) =>
+                  setFormData((prev) => ({ ...prev, offline_slate: value as OfflineSlateMode }))
+                }
+              >
+                <SelectTrigger id="offline_slate">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="off">Off</SelectItem>
+                  <SelectItem value="generated">Generated card</SelectItem>
+                  <SelectItem value="media">Configured media</SelectItem>
+                </SelectContent>
+              </Select>
+              <p className="text-sm text-muted-foreground">
+                What proxy-mode streams show while their upstream is down.
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="epg_languages">EPG Languages</Label>
+              <Input
+                id="epg_languages"
+                value={formData.epg_languages || ''}
+                onChange={(e) =>
+                  setFormData((prev) => ({ ...prev, epg_languages: e.target.value }))
+                }
+                placeholder="de,en"
+              />
+              <p className="text-sm text-muted-foreground">
+                Preferred languages of EPG channel names and icons, in order. Leave empty to keep
+                the playlist&apos;s channel names and logos.
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="regeneration_debounce_seconds">Regeneration Debounce (seconds)</Label>
+              <Input
+                id="regeneration_debounce_seconds"
+                type="text"
+                inputMode="numeric"
+                pattern="[0-9]*"
+                value={formData.regeneration_debounce_seconds?.toString() ?? ''}
+                onChange={(e) => {
+                  const value = e.target.value.replace(/[^0-9]/g, '');
+                  setFormData((prev) => ({
+                    ...prev,
+                    regeneration_debounce_seconds: value === '' ? undefined : parseInt(value),
+                  }));
+                }}
+                placeholder="Global default"
+              />
+              <p className="text-sm text-muted-foreground">
+                How long source updates are batched before the proxy regenerates once. Leave empty
+                for the global regeneration debounce.
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="epg_timezone">EPG Timezone</Label>
+              <Input
+                id="epg_timezone"
+                value={formData.epg_timezone || ''}
+                onChange={(e) => setFormData((prev) => ({ ...prev, epg_timezone: e.target.value }))}
+                placeholder="UTC"
+              />
+              <p className="text-sm text-muted-foreground">
+                IANA timezone for programme times in the generated XMLTV (e.g. America/New_York).
+                Leave empty for UTC.
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <div className="flex items-center justify-between">
+                <div>
+                  <Label>Group Number Blocks</Label>
+                  <p className="text-sm text-muted-foreground">
+                    Number a group&apos;s channels from its own start (e.g. News from 100). Each
+                    block ends where the next begins; channels that do not fit, and other groups,
+                    continue from the starting channel number.
+                  </p>
+                </div>
+                <Button
+                  type="button"
+                  variant="outline"
+                  size="sm"
+                  onClick={() =>
+                    setFormData((prev) => ({
+                      ...prev,
+                      channel_number_blocks: [
+                        ...(prev.channel_number_blocks || []),
+                        { group: '', start: 100 },
+                      ],
+                    }))
+                  }
+                >
+                  <Plus className="h-4 w-4 mr-1" />
+                  Add Block
+                </Button>
+              </div>
+              {(formData.channel_number_blocks || []).map((block, index) => (
+                <div key={index} className="flex items-center gap-2">
+                  <Input
+                    value={block.group}
+                    onChange={(e) =>
+                      setFormData((prev) => ({
+                        ...prev,
+                        channel_number_blocks: (prev.channel_number_blocks || []).map((b, i) =>
+                          i === index ? { ...b, group: e.target.value } : b
+                        ),
+                      }))
+                    }
+                    placeholder="Group"
+                  />
+                  <Input
+                    className="w-32"
+                    type="text"
+                    inputMode="numeric"
+                    pattern="[0-9]*"
+                    value={block.start.toString()}
+                    onChange={(e) => {
+                      const value = e.target.value.replace(/[^0-9]/g, '');
+                      setFormData((prev) => ({
+                        ...prev,
+                        channel_number_blocks: (prev.channel_number_blocks || []).map((b, i) =>
+                          i === index ? { ...b, start: value === '' ? 1 : parseInt(value) } : b
+                        ),
+                      }));
+                    }}
+                    onFocus={(e) => e.target.select()}
+                    placeholder="100"
+                  />
+                  <Button
+                    type="button"
+                    variant="ghost"
+                    size="sm"
+                    onClick={() =>
+                      setFormData((prev) => ({
+                        ...prev,
+                        channel_number_blocks: (prev.channel_number_blocks || []).filter(
+                          (_, i) => i !== index
+                        ),
+                      }))
+                    }
+                  >
+                    <Trash2 className="h-4 w-4" />
+                  </Button>
+                </div>
+              ))}
+            </div>
           </div>
 
           {/* Boolean Settings */}
@@ -1002,6 +1222,21 @@ export function ProxySheet({
                   }
                 />
               </div>
+
+              <div className="flex items-center justify-between rounded-lg border p-3">
+                <div>
+                  <Label>Sign Stream URLs</Label>
+                  <p className="text-sm text-muted-foreground">
+                    Add expiring tokens to the playlist&apos;s stream URLs
+                  </p>
+                </div>
+                <Switch
+                  checked={formData.sign_stream_urls ?? false}
+                  onCheckedChange={(checked) =>
+                    setFormData((prev) => ({ ...prev, sign_stream_urls: checked }))
+                  }
+                />
+              </div>
             </div>
           </div>
         </form>
@@ -11,8 +11,10 @@ import {
 import { ScrollArea } from '@/components/ui/scroll-area';
 import { Tooltip, TooltipContent, TooltipProvider, TooltipTrigger } from '@/components/ui/tooltip';
 import { Badge } from '@/components/ui/badge';
-import { Copy, Check, Info } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Copy, Check, Info, Camera, RefreshCw } from 'lucide-react';
 import { cn } from '@/lib/utils';
+import { getBackendUrl } from '@/lib/config';
 
 type Primitive = string | number | boolean | null | undefined;
 
@@ -54,6 +56,8 @@ interface ChannelDetailsSheetProps {
   onOpenChange: (open: boolean) => void;
   additionalFields?: Record<string, Primitive>;
   className?: string;
+  /** Offer a captured frame from the stream (channels stored in the database only) */
+  previewable?: boolean;
 }
 
 /* ---------------- Formatting Helpers ---------------- */
@@ -204,6 +208,7 @@ export const ChannelDetailsSheet: React.FC<ChannelDetailsSheetProps> = ({
   onOpenChange,
   additionalFields,
   className,
+  previewable = false,
 }) => {
   const rows = useMemo(() => {
     if (!channel) return [];
@@ -308,6 +313,24 @@ export const ChannelDetailsSheet: React.FC<ChannelDetailsSheetProps> = ({
       });
   };
 
+  // Preview capture is opt-in: each request may spawn an ffmpeg run against the upstream
+  const [previewSrc, setPreviewSrc] = React.useState<string | null>(null);
+  const [previewLoading, setPreviewLoading] = React.useState(false);
+  const [previewError, setPreviewError] = React.useState(false);
+  React.useEffect(() => {
+    setPreviewSrc(null);
+    setPreviewLoading(false);
+    setPreviewError(false);
+  }, [channel?.id, open]);
+
+  const capturePreview = (refresh: boolean) => {
+    if (!channel?.id) return;
+    const params = refresh ? `?refresh=true&t=${Date.now()}` : '';
+    setPreviewLoading(true);
+    setPreviewError(false);
+    setPreviewSrc(`${getBackendUrl()}/api/v1/channels/${channel.id}/preview${params}`);
+  };
+
   const title = channel?.name && channel.name.trim() !== '' ? channel.name : 'Channel Details';
 
   return (
@@ -356,6 +379,42 @@ export const ChannelDetailsSheet: React.FC<ChannelDetailsSheetProps> = ({
           </div>
         )}
 
+        {previewable && channel?.id && (
+          <div className="border-b px-5 py-3 flex flex-col items-center gap-2 bg-background">
+            {previewSrc && !previewError && (
+              /* eslint-disable-next-line @next/next/no-img-element */
+              <img
+                src={previewSrc}
+                alt={`${title} preview`}
+                className={cn('max-h-60 rounded object-contain', previewLoading && 'opacity-50')}
+                onLoad={() => setPreviewLoading(false)}
+                onError={() => {
+                  setPreviewLoading(false);
+                  setPreviewError(true);
+                }}
+              />
+            )}
+            {previewError && (
+              <span className="text-xs text-destructive">
+                Could not capture a frame from this stream
+              </span>
+            )}
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={previewLoading}
+              onClick={() => capturePreview(previewSrc !== null)}
+            >
+              {previewSrc ? (
+                <RefreshCw className={cn('h-3.5 w-3.5 mr-1.5', previewLoading && 'animate-spin')} />
+              ) : (
+                <Camera className="h-3.5 w-3.5 mr-1.5" />
+              )}
+              {previewLoading ? 'Capturing…' : previewSrc ? 'Refresh preview' : 'Capture preview'}
+            </Button>
+          </div>
+        )}
+
         <TooltipProvider delayDuration={150}>
           <ScrollArea className="flex-1 min-h-0">
             {/* Header row for clarity */}
@@ -0,0 +1,84 @@
+'use client';
+
+import { useEffect, useState } from 'react';
+import { CalendarClock } from 'lucide-react';
+import { apiClient, ApiError } from '@/lib/api-client';
+import { CronPreviewResponse } from '@/types/api';
+
+const PREVIEW_DEBOUNCE_MS = 400;
+
+/**
+ * Next refresh times of a source update schedule, validated by the server as it is typed.
+ * With a source id, the runs are counted from the source's last ingestion like the scheduler.
+ */
+export function CronNextRuns({
+  expression,
+  sourceId,
+  enabled = true,
+}: {
+  expression: string;
+  sourceId?: string;
+  enabled?: boolean;
+}) {
+  const [preview, setPreview] = useState<CronPreviewResponse | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!enabled || !expression.trim()) {
+      setPreview(null);
+      setError(null);
+      return;
+    }
+
+    let cancelled = false;
+    const timer = setTimeout(async () => {
+      try {
+        const result = await apiClient.previewCronSchedule(expression, { sourceId, count: 3 });
+        if (!cancelled) {
+          setPreview(result);
+          setError(null);
+        }
+      } catch (err) {
+        if (!cancelled) {
+          setPreview(null);
+          setError((err as ApiError).message);
+        }
+      }
+    }, PREVIEW_DEBOUNCE_MS);
+
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [expression, sourceId, enabled]);
+
+  if (error) {
+    return <p className="text-sm text-destructive">{error}</p>;
+  }
+  const runs = preview?.source?.next_runs ?? preview?.next_runs;
+  if (!preview || !runs?.length) {
+    return null;
+  }
+  const timezone = preview.source?.timezone ?? preview.timezone;
+
+  return (
+    <div className="flex items-start gap-2 text-xs text-muted-foreground">
+      <CalendarClock className="h-3.5 w-3.5 mt-0.5 shrink-0" />
+      <div>
+        <p>
+          Next refresh at{' '}
+          {new Date(runs[0].at).toLocaleString(undefined, { timeZone: timezone })} ({timezone})
+        </p>
+        {runs.length > 1 && (
+          <p>
+            Then{' '}
+            {runs
+              .slice(1)
+              .map((run) => new Date(run.at).toLocaleString(undefined, { timeZone: timezone }))
+              .join(', ')}
+          </p>
+        )}
+      </div>
+    </div>
+  );
+}
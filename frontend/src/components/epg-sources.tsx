@@ -1,6 +1,6 @@
 'use client';
 
-import { useState, useEffect, useCallback, useMemo } from 'react';
+import { useState, useEffect, useCallback, useMemo, useRef } from 'react';
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
 import { Button } from '@/components/ui/button';
 import { Badge } from '@/components/ui/badge';
@@ -48,6 +48,7 @@ import {
   AlertCircle,
   CheckCircle,
   Loader2,
+  Upload,
   WifiOff,
 } from 'lucide-react';
 import {
@@ -55,6 +56,7 @@ import {
   CreateEpgSourceRequest,
   EpgSourceType,
   PaginatedResponse,
+  XmltvUploadResponse,
 } from '@/types/api';
 import { apiClient, ApiError } from '@/lib/api-client';
 import { DEFAULT_PAGE_SIZE, API_CONFIG } from '@/lib/config';
@@ -68,6 +70,7 @@ import {
   describeCronExpression,
   COMMON_CRON_TEMPLATES,
 } from '@/lib/cron-validation';
+import { CronNextRuns } from '@/components/cron-next-runs';
 
 interface LoadingState {
   sources: boolean;
@@ -323,6 +326,7 @@ function CreateEpgSourceSheet({
                 )}
               </div>
             )}
+            <CronNextRuns expression={formData.update_cron} enabled={cronValidation.isValid} />
             <div className="flex flex-wrap gap-1 text-xs">
               {COMMON_CRON_TEMPLATES.slice(0, 3).map((template) => (
                 <Button
@@ -591,6 +595,11 @@ function EditEpgSourceSheet({
                 )}
               </div>
             )}
+            <CronNextRuns
+              expression={formData.update_cron || ''}
+              sourceId={source?.id}
+              enabled={cronValidation.isValid}
+            />
             <div className="flex flex-wrap gap-1 text-xs">
               {COMMON_CRON_TEMPLATES.slice(0, 3).map((template) => (
                 <Button
@@ -631,6 +640,68 @@ function EditEpgSourceSheet({
   );
 }
 
+function UploadXmltvButton({
+  onUploaded,
+  onError,
+}: {
+  onUploaded: (response: XmltvUploadResponse) => void;
+  onError: (message: string) => void;
+}) {
+  const inputRef = useRef<HTMLInputElement>(null);
+  const [progress, setProgress] = useState<number | null>(null);
+
+  const handleFile = async (file: File) => {
+    setProgress(0);
+    try {
+      onUploaded(await apiClient.uploadEpgXmltv(file, setProgress));
+    } catch (error) {
+      onError(error instanceof Error ? error.message : 'Upload failed');
+    } finally {
+      setProgress(null);
+      if (inputRef.current) {
+        inputRef.current.value = '';
+      }
+    }
+  };
+
+  return (
+    <>
+      <input
+        ref={inputRef}
+        type="file"
+        accept=".xml,.gz"
+        className="hidden"
+        onChange={(e) => {
+          const file = e.target.files?.[0];
+          if (file) {
+            handleFile(file);
+          }
+        }}
+      />
+      <Tooltip>
+        <TooltipTrigger asChild>
+          <Button
+            variant="outline"
+            className="gap-2"
+            disabled={progress !== null}
+            onClick={() => inputRef.current?.click()}
+          >
+            {progress !== null ? (
+              <Loader2 className="h-4 w-4 animate-spin" />
+            ) : (
+              <Upload className="h-4 w-4" />
+            )}
+            {progress !== null ? `Uploading ${progress}%` : 'Upload XMLTV'}
+          </Button>
+        </TooltipTrigger>
+        <TooltipContent>
+          Import an .xml or .xml.gz guide into a file-backed source named after the file
+        </TooltipContent>
+      </Tooltip>
+    </>
+  );
+}
+
 export function EpgSources() {
   const progressContext = useProgressContext();
   const [allSources, setAllSources] = useState<EpgSourceResponse[]>([]);
@@ -941,6 +1012,10 @@ export function EpgSources() {
           </div>
           <div className="flex items-center gap-2">
             {!isOnline && <WifiOff className="h-5 w-5 text-destructive" />}
+            <UploadXmltvButton
+              onUploaded={() => loadSources()}
+              onError={(message) => setErrors((prev) => ({ ...prev, action: message }))}
+            />
             <CreateEpgSourceSheet
               onCreateSource={handleCreateSource}
               loading={loading.create}
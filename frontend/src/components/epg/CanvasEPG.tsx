@@ -21,6 +21,7 @@ interface EpgProgram {
   end_time: string;
   category?: string;
   rating?: string;
+  icon?: string;
   source_id?: string;
   metadata?: Record<string, string>;
   is_streamable: boolean;
@@ -28,10 +29,14 @@ interface EpgProgram {
 
 interface EpgGuideResponse {
   channels: Record<string, { id: string; name: string; logo?: string }>;
+  channel_order?: string[];
   programs: Record<string, EpgProgram[]>;
   time_slots: string[];
   start_time: string;
   end_time: string;
+  total_channels?: number;
+  channel_offset?: number;
+  has_more_channels?: boolean;
 }
 
 interface CanvasEPGProps {
@@ -50,6 +55,9 @@ const CHANNEL_HEIGHT = 60;
 const CHANNEL_SIDEBAR_WIDTH = 200;
 const PIXELS_PER_HOUR = 200;
 const TIME_HEADER_HEIGHT = 50;
+const LOGO_SIZE = 32;
+const PROGRAM_ICON_SIZE = 28;
+const MIN_WIDTH_FOR_ICON = 120;
 
 export const CanvasEPG: React.FC<CanvasEPGProps> = ({
   guideData,
@@ -72,6 +80,48 @@ export const CanvasEPG: React.FC<CanvasEPGProps> = ({
   const [themeKey, setThemeKey] = useState(0); // Force theme updates
   const isRenderingRef = useRef(false);
 
+  // Channel logos and programme icons by URL; null while loading or when loading failed
+  const imageCacheRef = useRef(new Map<string, HTMLImageElement | null>());
+  const [imageVersion, setImageVersion] = useState(0); // Redraw as images load
+
+  const getImage = useCallback((url?: string): HTMLImageElement | undefined => {
+    if (!url) return undefined;
+    const cache = imageCacheRef.current;
+    if (cache.has(url)) return cache.get(url) ?? undefined;
+
+    cache.set(url, null);
+    const image = new Image();
+    image.onload = () => {
+      cache.set(url, image);
+      setImageVersion((prev) => prev + 1);
+    };
+    image.src = url;
+    return undefined;
+  }, []);
+
+  // Draw an image scaled to fit a square, keeping its aspect ratio
+  const drawImageContained = useCallback(
+    (
+      ctx: CanvasRenderingContext2D,
+      image: HTMLImageElement,
+      x: number,
+      y: number,
+      size: number
+    ) => {
+      const scale = Math.min(size / image.naturalWidth, size / image.naturalHeight);
+      const drawWidth = image.naturalWidth * scale;
+      const drawHeight = image.naturalHeight * scale;
+      ctx.drawImage(
+        image,
+        x + (size - drawWidth) / 2,
+        y + (size - drawHeight) / 2,
+        drawWidth,
+        drawHeight
+      );
+    },
+    []
+  );
+
   // Calculate guide parameters
   const GUIDE_HOURS = parseInt(guideTimeRange.replace('h', ''));
   const TOTAL_GUIDE_WIDTH = GUIDE_HOURS * PIXELS_PER_HOUR;
@@ -444,14 +494,28 @@ export const CanvasEPG: React.FC<CanvasEPGProps> = ({
         }
         ctx.font = 'bold 11px ui-sans-serif, system-ui, -apple-system, sans-serif';
 
+        // Programme icon (if there's space)
+        let textX = programX + 6;
+        const icon = programWidth >= MIN_WIDTH_FOR_ICON ? getImage(program.icon) : undefined;
+        if (icon) {
+          drawImageContained(
+            ctx,
+            icon,
+            programX + 6,
+            programY + (programHeight - PROGRAM_ICON_SIZE) / 2,
+            PROGRAM_ICON_SIZE
+          );
+          textX += PROGRAM_ICON_SIZE + 6;
+        }
+
         // Program title
-        const maxTitleWidth = programWidth - 12;
+        const maxTitleWidth = programX + programWidth - textX - 6;
         if (maxTitleWidth > 20) {
           const titleText =
             program.title.length > maxTitleWidth / 7
               ? program.title.substring(0, Math.floor(maxTitleWidth / 7)) + '...'
               : program.title;
-          ctx.fillText(titleText, programX + 6, programY + 14);
+          ctx.fillText(titleText, textX, programY + 14);
         }
 
         // Program time (if there's space)
@@ -465,7 +529,7 @@ export const CanvasEPG: React.FC<CanvasEPGProps> = ({
             ctx.fillStyle = theme.mutedForeground;
           }
           const timeText = `${formatTimeInTimezone(program.start_time)}-${formatTimeInTimezone(program.end_time)}`;
-          ctx.fillText(timeText, programX + 6, programY + 30);
+          ctx.fillText(timeText, textX, programY + 30);
         }
 
         // Store program bounds for hit testing (adjusted for scroll)
@@ -537,17 +601,28 @@ export const CanvasEPG: React.FC<CanvasEPGProps> = ({
       ctx.fillStyle = i % 2 === 0 ? theme.secondary : theme.muted;
       ctx.fillRect(0, channelY, CHANNEL_SIDEBAR_WIDTH, CHANNEL_HEIGHT);
 
+      // Channel logo
+      let channelTextX = 50;
+      const logo = getImage(channel.logo);
+      if (logo) {
+        drawImageContained(ctx, logo, 48, channelY + (CHANNEL_HEIGHT - LOGO_SIZE) / 2, LOGO_SIZE);
+        channelTextX += LOGO_SIZE + 6;
+      }
+
       // Channel name and ID
+      const maxChars = logo ? 14 : 20;
       ctx.fillStyle = theme.foreground;
       ctx.font = 'bold 13px ui-sans-serif, system-ui, -apple-system, sans-serif';
       const channelName = channel.name || channelId;
       const truncatedName =
-        channelName.length > 20 ? channelName.substring(0, 17) + '...' : channelName;
-      ctx.fillText(truncatedName, 50, channelY + CHANNEL_HEIGHT / 2 - 8);
+        channelName.length > maxChars
+          ? channelName.substring(0, maxChars - 3) + '...'
+          : channelName;
+      ctx.fillText(truncatedName, channelTextX, channelY + CHANNEL_HEIGHT / 2 - 8);
 
       ctx.font = '11px ui-sans-serif, system-ui, -apple-system, sans-serif';
       ctx.fillStyle = theme.mutedForeground;
-      ctx.fillText(channelId, 50, channelY + CHANNEL_HEIGHT / 2 + 8);
+      ctx.fillText(channelId, channelTextX, channelY + CHANNEL_HEIGHT / 2 + 8);
 
       // Play button area
       ctx.fillStyle = theme.accent;
@@ -624,6 +699,9 @@ export const CanvasEPG: React.FC<CanvasEPGProps> = ({
     formatTimeInTimezone,
     GUIDE_HOURS,
     themeKey, // Use themeKey instead of theme object
+    imageVersion,
+    getImage,
+    drawImageContained,
   ]);
 
   // Render when key dependencies change (debounced to prevent infinite loops)
@@ -654,6 +732,7 @@ export const CanvasEPG: React.FC<CanvasEPGProps> = ({
     currentTime.getTime(), // Use primitive value instead of Date object
     hoveredProgram?.id,
     themeKey,
+    imageVersion,
     // Removed renderCanvas to prevent circular dependency
   ]);
 
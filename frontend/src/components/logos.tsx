@@ -52,6 +52,7 @@ import {
   LogoStats,
   LogoUploadRequest,
   LogoAssetUpdateRequest,
+  LogoBulkUploadReport,
 } from '@/types/api';
 import { apiClient, ApiError } from '@/lib/api-client';
 import { API_CONFIG } from '@/lib/config';
@@ -368,6 +369,143 @@ function UploadLogoSheet({
   );
 }
 
+function BulkUploadLogosSheet({
+  onUploaded,
+  open,
+  onOpenChange,
+}: {
+  onUploaded: () => Promise<void>;
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}) {
+  const [file, setFile] = useState<File | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [report, setReport] = useState<LogoBulkUploadReport | null>(null);
+
+  useEffect(() => {
+    if (!open) {
+      setFile(null);
+      setError(null);
+      setReport(null);
+    }
+  }, [open]);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!file) return;
+
+    setLoading(true);
+    setError(null);
+    try {
+      setReport(await apiClient.bulkUploadLogos(file));
+      await onUploaded();
+    } catch (err) {
+      setError(`Failed to upload logos: ${(err as ApiError).message}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <Sheet open={open} onOpenChange={onOpenChange}>
+      <SheetContent side="right" className="w-full sm:max-w-lg overflow-y-auto">
+        <SheetHeader>
+          <SheetTitle>Bulk Upload Logos</SheetTitle>
+          <SheetDescription>
+            Upload a ZIP archive of logos named after channels. Logos whose file name matches a
+            channel name, tvg-name or tvg-id are assigned to those channels; the rest are staged
+            for manual assignment.
+          </SheetDescription>
+        </SheetHeader>
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertCircle className="h-4 w-4" />
+            <AlertTitle>Error</AlertTitle>
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        <form id="bulk-upload-logos-form" onSubmit={handleSubmit} className="space-y-4 px-4">
+          <div className="space-y-2">
+            <Label htmlFor="bulk-file">ZIP Archive</Label>
+            <Input
+              id="bulk-file"
+              type="file"
+              accept=".zip,application/zip"
+              onChange={(e) => setFile(e.target.files?.[0] ?? null)}
+              disabled={loading}
+            />
+            <p className="text-xs text-muted-foreground">
+              PNG, JPEG, GIF, WebP and SVG logos up to 5 MB each
+            </p>
+          </div>
+        </form>
+
+        {report && (
+          <div className="space-y-4 px-4 text-sm">
+            <div className="flex gap-2">
+              <Badge variant="secondary">{report.matched.length} matched</Badge>
+              <Badge variant="outline">{report.unmatched.length} unmatched</Badge>
+              {report.rejected.length > 0 && (
+                <Badge variant="destructive">{report.rejected.length} rejected</Badge>
+              )}
+            </div>
+            {report.matched.length > 0 && (
+              <div className="space-y-1">
+                <p className="font-medium">Matched</p>
+                {report.matched.map((logo) => (
+                  <p key={logo.file_name} className="text-muted-foreground">
+                    {logo.file_name} &rarr;{' '}
+                    {logo.channels.map((channel) => channel.channel_name).join(', ')}
+                  </p>
+                ))}
+              </div>
+            )}
+            {report.unmatched.length > 0 && (
+              <div className="space-y-1">
+                <p className="font-medium">Staged for manual assignment</p>
+                <p className="text-xs text-muted-foreground">Batch {report.batch_id}</p>
+                {report.unmatched.map((logo) => (
+                  <p key={logo.file_name} className="text-muted-foreground">
+                    {logo.file_name} ({formatFileSize(logo.file_size)})
+                  </p>
+                ))}
+              </div>
+            )}
+            {report.rejected.length > 0 && (
+              <div className="space-y-1">
+                <p className="font-medium">Rejected</p>
+                {report.rejected.map((logo, index) => (
+                  <p key={`${logo.file_name}-${index}`} className="text-destructive">
+                    {logo.file_name}: {logo.reason}
+                  </p>
+                ))}
+              </div>
+            )}
+          </div>
+        )}
+
+        <SheetFooter className="gap-2">
+          <Button
+            type="button"
+            variant="outline"
+            onClick={() => onOpenChange(false)}
+            disabled={loading}
+          >
+            Close
+          </Button>
+          <Button form="bulk-upload-logos-form" type="submit" disabled={loading || !file}>
+            {loading && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            Upload Archive
+          </Button>
+        </SheetFooter>
+      </SheetContent>
+    </Sheet>
+  );
+}
+
 function EditLogoSheet({
   logo,
   onUpdateLogo,
@@ -589,6 +727,7 @@ export function Logos() {
   const [hasMore, setHasMore] = useState(false);
   const [isOnline, setIsOnline] = useState(true);
   const [isUploadSheetOpen, setIsUploadSheetOpen] = useState(false);
+  const [isBulkUploadSheetOpen, setIsBulkUploadSheetOpen] = useState(false);
   const [isEditSheetOpen, setIsEditSheetOpen] = useState(false);
   const [editingLogo, setEditingLogo] = useState<LogoAsset | null>(null);
   const [viewMode, setViewMode] = useState<'grid' | 'list' | 'table'>('grid');
@@ -978,6 +1117,14 @@ export function Logos() {
               <Plus className="h-4 w-4" />
               Upload Logo
             </Button>
+            <Button
+              onClick={() => setIsBulkUploadSheetOpen(true)}
+              variant="outline"
+              className="gap-2"
+            >
+              <Upload className="h-4 w-4" />
+              Bulk Upload
+            </Button>
             <Button
               onClick={handleRescanCache}
               disabled={loading.rescan}
@@ -1733,6 +1880,15 @@ export function Logos() {
         onOpenChange={setIsUploadSheetOpen}
       />
 
+      <BulkUploadLogosSheet
+        onUploaded={async () => {
+          await loadLogos(1, false);
+          await loadStats();
+        }}
+        open={isBulkUploadSheetOpen}
+        onOpenChange={setIsBulkUploadSheetOpen}
+      />
+
       <EditLogoSheet
         logo={editingLogo}
         onUpdateLogo={handleUpdateLogo}
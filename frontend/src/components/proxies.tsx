@@ -337,6 +337,14 @@ export function Proxies() {
         cache_channel_logos: formData.cache_channel_logos,
         cache_program_logos: formData.cache_program_logos,
         relay_profile_id: formData.relay_profile_id,
+        sign_stream_urls: formData.sign_stream_urls,
+        output_profile: formData.output_profile,
+        backup_streams: formData.backup_streams,
+        offline_slate: formData.offline_slate,
+        epg_languages: formData.epg_languages || undefined,
+        regeneration_debounce_seconds: formData.regeneration_debounce_seconds,
+        channel_number_blocks: formData.channel_number_blocks,
+        epg_timezone: formData.epg_timezone || undefined,
       };
 
       await apiClient.createProxy(createRequest);
@@ -376,6 +384,14 @@ export function Proxies() {
         cache_channel_logos: formData.cache_channel_logos,
         cache_program_logos: formData.cache_program_logos,
         relay_profile_id: formData.relay_profile_id,
+        sign_stream_urls: formData.sign_stream_urls,
+        output_profile: formData.output_profile,
+        backup_streams: formData.backup_streams,
+        offline_slate: formData.offline_slate,
+        epg_languages: formData.epg_languages || null,
+        regeneration_debounce_seconds: formData.regeneration_debounce_seconds ?? null,
+        channel_number_blocks: formData.channel_number_blocks,
+        epg_timezone: formData.epg_timezone || null,
       };
 
       await apiClient.updateProxy(proxyId, updateRequest);
@@ -40,6 +40,7 @@ import {
   ApiResponse,
 } from '@/types/api';
 import { getBackendUrl } from '@/lib/config';
+import { apiClient } from '@/lib/api-client';
 import { RelayProfileForm } from '@/components/relay-profile-form';
 
 function formatBitrate(bitrate?: number): string {
@@ -95,11 +96,7 @@ export function RelayProfiles() {
 
   const fetchProfiles = async () => {
     try {
-      const backendUrl = getBackendUrl();
-      const response = await fetch(`${backendUrl}/api/v1/relay/profiles`);
-      if (!response.ok) throw new Error('Failed to fetch relay profiles');
-      const data: RelayProfile[] = await response.json();
-      setProfiles(data || []);
+      setProfiles(await apiClient.getRelayProfiles());
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Unknown error occurred');
     }
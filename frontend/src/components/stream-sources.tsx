@@ -70,6 +70,7 @@ import {
   describeCronExpression,
   COMMON_CRON_TEMPLATES,
 } from '@/lib/cron-validation';
+import { CronNextRuns } from '@/components/cron-next-runs';
 
 interface LoadingState {
   sources: boolean;
@@ -315,6 +316,7 @@ function CreateSourceSheet({
                   )}
                 </div>
               )}
+              <CronNextRuns expression={formData.update_cron} enabled={cronValidation.isValid} />
               <div className="flex flex-wrap gap-1 text-xs">
                 {COMMON_CRON_TEMPLATES.slice(0, 3).map((template) => (
                   <Button
@@ -573,6 +575,11 @@ function EditSourceSheet({
                   )}
                 </div>
               )}
+              <CronNextRuns
+                expression={formData.update_cron || ''}
+                sourceId={source?.id}
+                enabled={cronValidation.isValid}
+              />
               <div className="flex flex-wrap gap-1 text-xs">
                 {COMMON_CRON_TEMPLATES.slice(0, 3).map((template) => (
                   <Button
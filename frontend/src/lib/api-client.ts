@@ -25,6 +25,15 @@ import {
   LogoStats,
   LogoAssetUpdateRequest,
   LogoUploadRequest,
+  LogoBulkUploadReport,
+  XmltvUploadResponse,
+  MatchedLogo,
+  StagedLogoAssignment,
+  ApiErrorCode,
+  CronPreviewResponse,
+  RuleKind,
+  RuleVersion,
+  RuleVersionDiff,
 } from '@/types/api';
 
 class ApiError extends Error {
@@ -36,6 +45,11 @@ class ApiError extends Error {
     super(message);
     this.name = 'ApiError';
   }
+
+  /** Stable error code of the response, for localising without matching messages */
+  get code(): ApiErrorCode | undefined {
+    return this.response?.code;
+  }
 }
 
 class ApiClient {
@@ -46,6 +60,39 @@ class ApiClient {
     this.baseUrl = baseUrl;
   }
 
+  // Send the browser to the identity provider when the session is missing or expired
+  private async redirectToLogin(): Promise<void> {
+    if (typeof window === 'undefined') {
+      return;
+    }
+    try {
+      const response = await fetch(`${this.baseUrl}/api/v1/auth/status`);
+      const status = await response.json();
+      if (status.data?.oidc) {
+        const returnTo = encodeURIComponent(window.location.pathname + window.location.search);
+        window.location.href = `${this.baseUrl}/api/v1/auth/oidc/login?return_to=${returnTo}`;
+      }
+    } catch {
+      // Leave the error to the caller
+    }
+  }
+
+  // Fetch every page of a list endpoint by following next_cursor
+  private async requestAllPages<T>(endpoint: string): Promise<T[]> {
+    const items: T[] = [];
+    const separator = endpoint.includes('?') ? '&' : '?';
+    let cursor: string | null | undefined;
+    do {
+      const pageEndpoint = cursor
+        ? `${endpoint}${separator}cursor=${encodeURIComponent(cursor)}`
+        : endpoint;
+      const response = await this.request<PaginatedResponse<T>>(pageEndpoint);
+      items.push(...(response.items ?? []));
+      cursor = response.next_cursor;
+    } while (cursor);
+    return items;
+  }
+
   private async request<T>(endpoint: string, options: RequestInit = {}): Promise<T> {
     const url = `${this.baseUrl}${endpoint}`;
 
@@ -55,6 +102,11 @@ class ApiClient {
       Accept: 'application/json',
     };
 
+    // Error messages are localised server-side from Accept-Language
+    if (typeof navigator !== 'undefined' && navigator.languages?.length) {
+      defaultHeaders['Accept-Language'] = navigator.languages.join(',');
+    }
+
     if (!isFormData) {
       defaultHeaders['Content-Type'] = 'application/json';
     }
@@ -89,6 +141,10 @@ class ApiClient {
           // Response is not JSON, use status text
         }
 
+        if (response.status === 401) {
+          await this.redirectToLogin();
+        }
+
         throw new ApiError(errorMessage, response.status, errorData);
       }
 
@@ -243,6 +299,44 @@ class ApiClient {
     });
   }
 
+  // Upload an XMLTV guide into the file-backed source named after it. Uses XHR rather than
+  // fetch for upload progress, and has no timeout as guides can be hundreds of MB.
+  uploadEpgXmltv(file: File, onProgress?: (percent: number) => void): Promise<XmltvUploadResponse> {
+    return new Promise((resolve, reject) => {
+      const xhr = new XMLHttpRequest();
+      xhr.open('POST', `${this.baseUrl}${API_CONFIG.endpoints.epgSources}/upload`);
+      xhr.setRequestHeader('Accept', 'application/json');
+      if (typeof navigator !== 'undefined' && navigator.languages?.length) {
+        xhr.setRequestHeader('Accept-Language', navigator.languages.join(','));
+      }
+
+      xhr.upload.onprogress = (event) => {
+        if (event.lengthComputable) {
+          onProgress?.(Math.round((event.loaded / event.total) * 100));
+        }
+      };
+      xhr.onload = () => {
+        let data;
+        try {
+          data = JSON.parse(xhr.responseText);
+        } catch {
+          // Response is not JSON, use status text
+        }
+        if (xhr.status >= 200 && xhr.status < 300 && data?.success) {
+          resolve(data.data);
+        } else {
+          const message = data?.error ?? `HTTP ${xhr.status}: ${xhr.statusText}`;
+          reject(new ApiError(message, xhr.status, data));
+        }
+      };
+      xhr.onerror = () => reject(new ApiError('Upload failed: network error', 0));
+
+      const formData = new FormData();
+      formData.append('file', file);
+      xhr.send(formData);
+    });
+  }
+
   // Proxy API
   async getProxies(params?: {
     page?: number;
@@ -339,7 +433,12 @@ class ApiClient {
     const queryString = searchParams.toString();
     const endpoint = `${API_CONFIG.endpoints.filters}${queryString ? `?${queryString}` : ''}`;
 
-    return this.request<FilterWithMeta[]>(endpoint);
+    // A requested page is returned as is; otherwise callers expect the full list
+    if (params?.page) {
+      const response = await this.request<PaginatedResponse<FilterWithMeta>>(endpoint);
+      return response.items ?? [];
+    }
+    return this.requestAllPages<FilterWithMeta>(endpoint);
   }
 
   async getFilter(id: string): Promise<ApiResponse<Filter>> {
@@ -411,7 +510,12 @@ class ApiClient {
     const queryString = searchParams.toString();
     const endpoint = `${API_CONFIG.endpoints.dataMapping}${queryString ? `?${queryString}` : ''}`;
 
-    return this.request<DataMappingRule[]>(endpoint);
+    // A requested page is returned as is; otherwise callers expect the full list
+    if (params?.page) {
+      const response = await this.request<PaginatedResponse<DataMappingRule>>(endpoint);
+      return response.items ?? [];
+    }
+    return this.requestAllPages<DataMappingRule>(endpoint);
   }
 
   async getDataMappingRule(id: string): Promise<ApiResponse<DataMappingRule>> {
@@ -437,6 +541,38 @@ class ApiClient {
     });
   }
 
+  // Rule version history (filters and data mapping rules)
+  private ruleVersionsUrl(kind: RuleKind, id: string): string {
+    const base =
+      kind === 'filter' ? API_CONFIG.endpoints.filters : API_CONFIG.endpoints.dataMapping;
+    return `${base}/${id}/versions`;
+  }
+
+  async getRuleVersions(kind: RuleKind, id: string): Promise<RuleVersion[]> {
+    return this.request(this.ruleVersionsUrl(kind, id));
+  }
+
+  async diffRuleVersions(
+    kind: RuleKind,
+    id: string,
+    from: number,
+    to?: number
+  ): Promise<RuleVersionDiff> {
+    const params = new URLSearchParams({ from: String(from) });
+    if (to !== undefined) params.set('to', String(to));
+    return this.request(`${this.ruleVersionsUrl(kind, id)}/diff?${params.toString()}`);
+  }
+
+  async revertRule<T extends Filter | DataMappingRule>(
+    kind: RuleKind,
+    id: string,
+    version: number
+  ): Promise<T> {
+    return this.request(`${this.ruleVersionsUrl(kind, id)}/${version}/revert`, {
+      method: 'POST',
+    });
+  }
+
   async deleteDataMappingRule(id: string): Promise<void> {
     await this.request<void>(`${API_CONFIG.endpoints.dataMapping}/${id}`, {
       method: 'DELETE',
@@ -525,7 +661,7 @@ class ApiClient {
 
   // Relay Profiles API
   async getRelayProfiles(): Promise<RelayProfile[]> {
-    return this.request<RelayProfile[]>(`${API_CONFIG.endpoints.relays}/profiles`);
+    return this.requestAllPages<RelayProfile>(`${API_CONFIG.endpoints.relays}/profiles`);
   }
 
   // Settings API
@@ -612,6 +748,29 @@ class ApiClient {
     });
   }
 
+  // Upload a ZIP of logos, assigning those whose file name matches a channel
+  async bulkUploadLogos(file: File): Promise<LogoBulkUploadReport> {
+    const formData = new FormData();
+    formData.append('file', file);
+
+    return this.request(`${API_CONFIG.endpoints.logos}/bulk-upload`, {
+      method: 'POST',
+      body: formData,
+      headers: {},
+    });
+  }
+
+  // Assign logos staged by a bulk upload to channels
+  async assignStagedLogos(
+    batchId: string,
+    assignments: StagedLogoAssignment[]
+  ): Promise<MatchedLogo[]> {
+    return this.request(`${API_CONFIG.endpoints.logos}/bulk-upload/${batchId}/assign`, {
+      method: 'POST',
+      body: JSON.stringify({ assignments }),
+    });
+  }
+
   // Rescan logo cache
   async rescanLogoCache(): Promise<any> {
     return this.request(`${API_CONFIG.endpoints.logos}/rescan`, {
@@ -626,6 +785,21 @@ class ApiClient {
     });
   }
 
+  // Validate a cron expression and list its next runs
+  async previewCronSchedule(
+    expression: string,
+    options: { sourceId?: string; count?: number } = {}
+  ): Promise<CronPreviewResponse> {
+    return this.request(`${API_CONFIG.endpoints.schedules}/preview`, {
+      method: 'POST',
+      body: JSON.stringify({
+        expression,
+        count: options.count,
+        source_id: options.sourceId,
+      }),
+    });
+  }
+
   // Health check
   async healthCheck(): Promise<any> {
     return this.request<any>(API_CONFIG.endpoints.health);
@@ -27,6 +27,7 @@ export const API_CONFIG = {
     dataMapping: '/api/v1/data-mapping',
     logos: '/api/v1/logos',
     relays: '/api/v1/relay',
+    schedules: '/api/v1/schedules',
     dashboard: '/api/v1/metrics/dashboard',
     health: '/health',
   },
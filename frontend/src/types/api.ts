@@ -1,11 +1,30 @@
 // API Types generated from OpenAPI specification
 
 // Core API Response Types
+export type ApiErrorCode =
+  | 'bad_request'
+  | 'validation_failed'
+  | 'not_found'
+  | 'permission_denied'
+  | 'conflict'
+  | 'payload_too_large'
+  | 'operation_in_progress'
+  | 'configuration_error'
+  | 'external_service_error'
+  | 'external_service_unavailable'
+  | 'database_error'
+  | 'data_access_error'
+  | 'source_error'
+  | 'request_failed'
+  | 'internal_error';
+
 export interface ApiResponse<T> {
   success: boolean;
   timestamp: string;
   data?: T;
   error?: string;
+  code?: ApiErrorCode;
+  params?: Record<string, string>;
   details?: Record<string, string>;
 }
 
@@ -17,6 +36,8 @@ export interface PaginatedResponse<T> {
   total_pages: number;
   has_next: boolean;
   has_previous: boolean;
+  offset: number;
+  next_cursor?: string | null;
 }
 
 // Stream Source Types
@@ -71,7 +92,19 @@ export interface EpgSourceResponse extends EpgSource {
   next_scheduled_update?: string;
 }
 
+export interface XmltvUploadResponse {
+  source_id: string;
+  source_name: string;
+  bytes_received: number;
+  operation_id: string;
+  progress_url: string;
+}
+
 // Proxy Types
+export type OutputProfile = 'standard' | 'kodi';
+export type BackupStreamMode = 'off' | 'attribute' | 'group';
+export type OfflineSlateMode = 'off' | 'generated' | 'media';
+
 export interface StreamProxy {
   id: string;
   name: string;
@@ -85,6 +118,14 @@ export interface StreamProxy {
   cache_channel_logos: boolean;
   cache_program_logos: boolean;
   relay_profile_id?: string;
+  sign_stream_urls?: boolean;
+  output_profile?: OutputProfile;
+  backup_streams?: BackupStreamMode;
+  offline_slate?: OfflineSlateMode;
+  epg_languages?: string;
+  regeneration_debounce_seconds?: number;
+  channel_number_blocks?: ChannelNumberBlock[];
+  epg_timezone?: string;
   m3u8_url?: string;
   xmltv_url?: string;
   created_at: string;
@@ -92,6 +133,12 @@ export interface StreamProxy {
   last_generated_at?: string;
 }
 
+/** Channel numbers reserved for one channel group, from `start` up to the next block */
+export interface ChannelNumberBlock {
+  group: string;
+  start: number;
+}
+
 export interface ProxySourceRequest {
   source_id: string;
   priority_order: number;
@@ -329,6 +376,62 @@ export interface LogoUploadRequest {
   file: File;
 }
 
+export interface LogoChannel {
+  channel_id: string;
+  channel_name: string;
+}
+
+export interface MatchedLogo {
+  file_name: string;
+  logo_asset_id: string;
+  channels: LogoChannel[];
+}
+
+export interface StagedLogo {
+  file_name: string;
+  file_size: number;
+}
+
+export interface RejectedLogo {
+  file_name: string;
+  reason: string;
+}
+
+export interface LogoBulkUploadReport {
+  batch_id: string;
+  matched: MatchedLogo[];
+  unmatched: StagedLogo[];
+  rejected: RejectedLogo[];
+}
+
+export interface StagedLogoAssignment {
+  file_name: string;
+  channel_ids: string[];
+}
+
+// Cron schedule preview
+export interface ScheduledRun {
+  at: string;
+  local: string;
+}
+
+export interface SourceSchedulePreview {
+  source_id: string;
+  source_type: 'stream' | 'epg';
+  name: string;
+  current_expression: string;
+  last_ingested_at?: string;
+  timezone: string;
+  next_runs: ScheduledRun[];
+}
+
+export interface CronPreviewResponse {
+  expression: string;
+  timezone: string;
+  next_runs: ScheduledRun[];
+  source?: SourceSchedulePreview;
+}
+
 // Data Mapping Types
 export type DataMappingSourceType = 'stream' | 'epg';
 
@@ -344,6 +447,45 @@ export interface DataMappingRule {
   updated_at: string;
 }
 
+// Rule Version Types
+export type RuleKind = 'filter' | 'data_mapping_rule';
+export type RuleChange = 'created' | 'updated' | 'reverted';
+
+export interface RuleVersion {
+  id: string;
+  rule_kind: RuleKind;
+  rule_id: string;
+  version: number;
+  name: string;
+  expression?: string;
+  previous_expression?: string;
+  definition: Record<string, unknown>;
+  change: RuleChange;
+  restored_version?: number;
+  author?: string;
+  created_at: string;
+}
+
+export interface RuleFieldChange {
+  field: string;
+  from: unknown;
+  to: unknown;
+}
+
+export interface ExpressionDiffSegment {
+  op: 'equal' | 'removed' | 'added';
+  text: string;
+}
+
+export interface RuleVersionDiff {
+  rule_kind: RuleKind;
+  rule_id: string;
+  from_version: number;
+  to_version: number;
+  changes: RuleFieldChange[];
+  expression_diff: ExpressionDiffSegment[];
+}
+
 // Dashboard Metrics Types
 export interface DashboardMetrics {
   active_clients: number;
@@ -540,6 +682,14 @@ export interface CreateStreamProxyRequest {
   cache_channel_logos: boolean;
   cache_program_logos: boolean;
   relay_profile_id?: string;
+  sign_stream_urls?: boolean;
+  output_profile?: OutputProfile;
+  backup_streams?: BackupStreamMode;
+  offline_slate?: OfflineSlateMode;
+  epg_languages?: string;
+  regeneration_debounce_seconds?: number;
+  channel_number_blocks?: ChannelNumberBlock[];
+  epg_timezone?: string;
 }
 
 export interface UpdateStreamProxyRequest {
@@ -557,6 +707,15 @@ export interface UpdateStreamProxyRequest {
   cache_channel_logos?: boolean;
   cache_program_logos?: boolean;
   relay_profile_id?: string;
+  // Omitted settings keep their current value; null clears a nullable one
+  sign_stream_urls?: boolean;
+  output_profile?: OutputProfile;
+  backup_streams?: BackupStreamMode;
+  offline_slate?: OfflineSlateMode;
+  epg_languages?: string | null;
+  regeneration_debounce_seconds?: number | null;
+  channel_number_blocks?: ChannelNumberBlock[];
+  epg_timezone?: string | null;
 }
 
 export interface FilterTestRequest {